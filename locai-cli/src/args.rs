@@ -21,6 +21,21 @@ pub struct AddMemoryArgs {
     /// Tags to associate with the memory
     #[arg(long = "tag", short = 't')]
     pub tags: Vec<String>,
+
+    /// Where the memory happened, as LAT,LON in degrees
+    #[arg(long, allow_hyphen_values = true)]
+    pub location: Option<String>,
+}
+
+#[derive(Args)]
+pub struct NewMemoryArgs {
+    /// Template to fill (see `memory templates`)
+    #[arg(long, short)]
+    pub template: String,
+
+    /// Template variable as name=value (repeatable)
+    #[arg(long = "var")]
+    pub vars: Vec<String>,
 }
 
 #[derive(Args)]
@@ -32,7 +47,8 @@ pub struct GetMemoryArgs {
 #[derive(Args)]
 pub struct SearchArgs {
     /// Search query
-    pub query: String,
+    #[arg(required_unless_present = "interactive")]
+    pub query: Option<String>,
 
     /// Maximum number of results
     #[arg(short, long, default_value_t = 10)]
@@ -62,6 +78,102 @@ pub struct SearchArgs {
     /// Filter by creation time (ISO 8601)
     #[arg(long)]
     pub created_before: Option<String>,
+
+    /// Only search memories in this collection (ID or name)
+    #[arg(long)]
+    pub collection: Option<String>,
+
+    /// Put the memories pinned for this scope first (`global` for global pins only)
+    #[arg(long)]
+    pub pin_scope: Option<String>,
+
+    /// Also search archived memories
+    #[arg(long)]
+    pub include_archived: bool,
+
+    /// Filter by memory property as KEY=VALUE (repeatable; VALUE is parsed as JSON when it can be)
+    #[arg(long = "property", value_name = "KEY=VALUE")]
+    pub properties: Vec<String>,
+
+    /// Typed property comparisons, such as "amount > 100 and due within next 7d"
+    #[arg(long, value_name = "EXPRESSION")]
+    pub filter: Option<String>,
+
+    /// Show which storage indexes the filter uses
+    #[arg(long)]
+    pub explain_plan: bool,
+
+    /// Only memories within --radius of this point, as LAT,LON in degrees
+    #[arg(long, allow_hyphen_values = true)]
+    pub near: Option<String>,
+
+    /// Radius for --near, in meters
+    #[arg(long, default_value_t = 1000.0)]
+    pub radius: f64,
+
+    /// Build the search step by step with prompts
+    #[arg(long, short)]
+    pub interactive: bool,
+}
+
+#[derive(Args)]
+pub struct NearArgs {
+    /// Center of the search, as LAT,LON in degrees
+    #[arg(allow_hyphen_values = true)]
+    pub location: String,
+
+    /// Search radius in meters
+    #[arg(long, short, default_value_t = 1000.0)]
+    pub radius: f64,
+
+    /// Maximum number of results
+    #[arg(short, long, default_value_t = 10)]
+    pub limit: usize,
+}
+
+#[derive(Args)]
+pub struct PinMemoryArgs {
+    /// Memory ID
+    pub id: String,
+
+    /// Pin scope, such as an agent ID (default: global)
+    #[arg(long)]
+    pub scope: Option<String>,
+}
+
+#[derive(Args)]
+pub struct PinnedArgs {
+    /// Pin scope; global pins are always included
+    #[arg(long)]
+    pub scope: Option<String>,
+}
+
+#[derive(Args)]
+pub struct RemindArgs {
+    /// Memory ID
+    pub id: String,
+
+    /// When to remind: an RFC 3339 time, a date (midnight UTC), or a delay such as `2h` or `3days`
+    #[arg(long, required_unless_present = "cancel")]
+    pub at: Option<String>,
+
+    /// Repeat daily, weekly, monthly, or at an interval such as `90m`
+    #[arg(long)]
+    pub every: Option<String>,
+
+    /// Cancel the memory's reminder
+    #[arg(long, conflicts_with_all = ["at", "every"])]
+    pub cancel: bool,
+}
+
+#[derive(Args)]
+pub struct SnoozeArgs {
+    /// Memory ID
+    pub id: String,
+
+    /// How long to put the reminder off
+    #[arg(long = "for", default_value = "10m")]
+    pub delay: String,
 }
 
 #[derive(Args)]
@@ -87,6 +199,10 @@ pub struct ListMemoriesArgs {
     /// Filter by priority
     #[arg(long)]
     pub priority: Option<String>,
+
+    /// Typed property comparisons, such as "amount > 100 and due within next 7d"
+    #[arg(long, value_name = "EXPRESSION")]
+    pub filter: Option<String>,
 }
 
 #[derive(Args)]
@@ -203,6 +319,41 @@ pub struct UpdateEntityArgs {
     pub properties: Option<String>,
 }
 
+#[derive(Args)]
+pub struct MergeEntityArgs {
+    /// Entity to keep
+    pub target: String,
+
+    /// Duplicate entities to merge into it and delete
+    #[arg(required = true)]
+    pub sources: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct SplitEntityArgs {
+    /// Entity to split
+    pub id: String,
+
+    /// ID of the new entity
+    pub new_id: String,
+
+    /// Type of the new entity (defaults to the original's type)
+    #[arg(long)]
+    pub entity_type: Option<String>,
+
+    /// Properties of the new entity (JSON format)
+    #[arg(long)]
+    pub properties: Option<String>,
+
+    /// Relationship to move to the new entity (repeatable)
+    #[arg(long = "relationship")]
+    pub relationships: Vec<String>,
+
+    /// Memory whose link moves to the new entity (repeatable)
+    #[arg(long = "memory")]
+    pub memories: Vec<String>,
+}
+
 // Relationship command arguments
 #[derive(Args)]
 pub struct CreateRelationshipArgs {
@@ -355,6 +506,34 @@ pub struct GraphEntityArgs {
     pub include_temporal_span: bool,
 }
 
+#[derive(Args)]
+pub struct GraphShowArgs {
+    /// Memory or entity ID (memory IDs may be abbreviated)
+    pub id: String,
+
+    /// Depth of traversal
+    #[arg(long, default_value_t = 2)]
+    pub depth: u8,
+
+    /// Only follow these relationship types, comma separated
+    #[arg(long, value_delimiter = ',')]
+    pub types: Vec<String>,
+
+    /// Exclude temporal relationships (temporal_sequence)
+    #[arg(long)]
+    pub no_temporal: bool,
+
+    /// Render as an ASCII tree or as a Mermaid flowchart
+    #[arg(long, value_enum, default_value_t = GraphShowFormat::Tree)]
+    pub format: GraphShowFormat,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum GraphShowFormat {
+    Tree,
+    Mermaid,
+}
+
 #[derive(Args)]
 pub struct MemoryRelationshipsArgs {
     /// Memory ID
@@ -384,6 +563,31 @@ pub struct CreateMemoryRelationshipArgs {
     pub properties: Option<String>,
 }
 
+#[derive(Args)]
+pub struct ReviewArgs {
+    /// Approve, reject or show a memory (default: list the queue)
+    #[command(subcommand)]
+    pub command: Option<ReviewSubcommand>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum ReviewSubcommand {
+    /// Show a memory waiting for review
+    Show(ReviewIdArgs),
+
+    /// Approve a memory, storing it
+    Approve(ReviewIdArgs),
+
+    /// Reject a memory, dropping it
+    Reject(ReviewIdArgs),
+}
+
+#[derive(Args)]
+pub struct ReviewIdArgs {
+    /// Memory ID
+    pub id: String,
+}
+
 #[derive(Args)]
 pub struct EntityRelationshipsArgs {
     /// Entity ID
@@ -445,6 +649,49 @@ pub struct ExecuteBatchArgs {
     pub continue_on_error: bool,
 }
 
+// Import command arguments
+#[derive(Args)]
+pub struct ImportConversationsArgs {
+    /// Export zip, unpacked export directory, or conversations.json
+    pub path: String,
+
+    /// Import at most this many conversations
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Parse the export and report what would be imported without storing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct ImportFileArgs {
+    /// Export JSON file
+    pub path: String,
+
+    /// Parse the export and report what would be imported without storing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct ImportVaultArgs {
+    /// Vault directory
+    pub vault_dir: String,
+
+    /// Vault name used to recognize notes on re-import (default: directory name)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Delete memories of notes that were removed from the vault
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Scan the vault and report what it contains without storing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
 // Relationship type command arguments
 #[derive(Args)]
 pub struct GetRelationshipTypeArgs {
@@ -514,6 +761,38 @@ pub struct TutorialArgs {
     pub examples_only: bool,
 }
 
+#[derive(Args)]
+pub struct DiagnoseArgs {
+    /// Also check index consistency: vectors without memories, relationships
+    /// with missing endpoints and delta chains with missing parents
+    #[arg(long)]
+    pub deep: bool,
+
+    /// Repair the issues found by the deep checks (implies --deep)
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Repair without asking for confirmation
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+#[derive(Args)]
+pub struct RepairStorageArgs {
+    /// Snapshot bundle (from `snapshot export`) to restore memories missing
+    /// an intact version from
+    #[arg(long)]
+    pub backup: Option<String>,
+
+    /// Also store checksums for records written before checksums existed
+    #[arg(long)]
+    pub backfill: bool,
+
+    /// Repair without asking for confirmation
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
 #[derive(Args)]
 pub struct QuickstartArgs {
     /// Remove sample data created by quickstart
@@ -523,6 +802,26 @@ pub struct QuickstartArgs {
     /// Show step-by-step guide (1-3)
     #[arg(long)]
     pub step: Option<u8>,
+
+    /// Generate this many synthetic memories instead of the sample data
+    #[arg(long, value_name = "N", conflicts_with = "step")]
+    pub synthetic: Option<usize>,
+
+    /// Synthetic entities: people, places and organizations
+    #[arg(long, value_name = "M", default_value = "100", requires = "synthetic")]
+    pub entities: usize,
+
+    /// Synthetic relationships per memory on average
+    #[arg(long, value_name = "D", default_value = "2.0", requires = "synthetic")]
+    pub graph_density: f64,
+
+    /// Seed for the synthetic data; the same seed gives the same data
+    #[arg(long, default_value = "42", requires = "synthetic")]
+    pub seed: u64,
+
+    /// Give synthetic memories random embeddings, for vector search
+    #[arg(long, requires = "synthetic")]
+    pub embeddings: bool,
 }
 
 #[derive(Args)]
@@ -541,3 +840,560 @@ pub enum Shell {
     Power,
     Elvish,
 }
+
+// Benchmark command arguments
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Corpus sizes to measure, comma separated
+    #[arg(long, value_delimiter = ',', default_value = "1000,10000")]
+    pub sizes: Vec<usize>,
+
+    /// Queries timed per search and traversal measurement
+    #[arg(long, default_value = "50")]
+    pub queries: usize,
+
+    /// Maximum results per search
+    #[arg(long, default_value = "10")]
+    pub limit: usize,
+
+    /// Graph traversal and path depth
+    #[arg(long, default_value = "3")]
+    pub depth: u8,
+
+    /// Seed for the synthetic corpus
+    #[arg(long, default_value = "42")]
+    pub seed: u64,
+
+    /// Also write the JSON report to this file
+    #[arg(long)]
+    pub save: Option<String>,
+
+    /// Compare against a report saved by an earlier run
+    #[arg(long)]
+    pub compare: Option<String>,
+}
+
+// Serve command arguments
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Port to listen on (default: LOCAI_PORT or 3000)
+    #[arg(long, short)]
+    pub port: Option<u16>,
+
+    /// Require JWT authentication (the default unless LOCAI_ENABLE_AUTH=false)
+    #[arg(long, conflicts_with = "no_auth")]
+    pub enable_auth: bool,
+
+    /// Disable authentication; only for development or trusted networks
+    #[arg(long)]
+    pub no_auth: bool,
+
+    /// Allow new users to sign up
+    #[arg(long, value_name = "BOOL")]
+    pub allow_signup: Option<bool>,
+
+    /// Root user password (generated on first run if not set)
+    #[arg(long)]
+    pub root_password: Option<String>,
+
+    /// JWT signing secret (generated if not set)
+    #[arg(long)]
+    pub jwt_secret: Option<String>,
+
+    /// Disable the inter-agent messaging endpoints
+    #[arg(long)]
+    pub messaging_disabled: bool,
+}
+
+// Daemon command arguments
+#[derive(Args)]
+pub struct DaemonArgs {
+    /// Socket to listen on (default: LOCAI_DAEMON_SOCKET, or daemon.sock in
+    /// the data directory). Clients only look at the default, so set
+    /// LOCAI_DAEMON_SOCKET for both when moving it.
+    #[arg(long)]
+    pub socket: Option<String>,
+}
+
+// TUI command arguments
+#[derive(Args)]
+pub struct TuiArgs {
+    /// Maximum items to load into each pane
+    #[arg(long, short, default_value = "200")]
+    pub limit: usize,
+}
+
+// Quick capture command arguments
+#[derive(Args)]
+pub struct CaptureArgs {
+    /// Tags to associate with the memory (repeat or comma-separate)
+    #[arg(
+        long = "tag",
+        visible_alias = "tags",
+        short = 't',
+        value_delimiter = ','
+    )]
+    pub tags: Vec<String>,
+
+    /// Memory type (fact, conversation, procedural, episodic, identity, world, action, event)
+    #[arg(long, short, default_value = "fact")]
+    pub memory_type: String,
+
+    /// Priority (low, normal, high, critical)
+    #[arg(long, short, default_value = "normal")]
+    pub priority: String,
+}
+
+#[derive(Args)]
+pub struct NoteArgs {
+    /// Text of the note; reads stdin when omitted or '-'
+    pub content: Option<String>,
+
+    #[command(flatten)]
+    pub capture: CaptureArgs,
+}
+
+#[derive(Args)]
+pub struct ClipArgs {
+    #[command(flatten)]
+    pub capture: CaptureArgs,
+}
+
+// Export command arguments
+#[derive(Args)]
+pub struct VectorExportArgs {
+    /// Memories read and written per batch
+    #[arg(long, default_value = "256")]
+    pub batch_size: usize,
+
+    /// Only export memories of this type
+    #[arg(long)]
+    pub memory_type: Option<String>,
+
+    /// Only export memories with any of these tags
+    #[arg(long)]
+    pub tag: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct QdrantExportArgs {
+    /// Qdrant REST endpoint
+    #[arg(long, default_value = "http://localhost:6333")]
+    pub url: String,
+
+    /// Collection to write to (created if missing)
+    #[arg(long, default_value = "locai")]
+    pub collection: String,
+
+    /// API key (default: QDRANT_API_KEY environment variable)
+    #[arg(long)]
+    pub api_key: Option<String>,
+
+    /// Distance for a newly created collection (Cosine, Dot, Euclid, Manhattan)
+    #[arg(long, default_value = "Cosine")]
+    pub distance: String,
+
+    #[command(flatten)]
+    pub export: VectorExportArgs,
+}
+
+#[derive(Args)]
+pub struct PgvectorExportArgs {
+    /// Table to write to, optionally schema-qualified
+    #[arg(long, default_value = "locai_memories")]
+    pub table: String,
+
+    /// Do not create an HNSW index on the embedding column
+    #[arg(long)]
+    pub no_index: bool,
+
+    /// Write the SQL to this file instead of stdout
+    #[arg(long)]
+    pub sql_file: Option<String>,
+
+    #[command(flatten)]
+    pub export: VectorExportArgs,
+}
+
+// Messaging command arguments
+#[derive(Args)]
+pub struct DeadLettersArgs {
+    /// Only show dead letters from this application's namespace
+    #[arg(long)]
+    pub app: Option<String>,
+
+    /// Maximum number of dead letters to show
+    #[arg(long, short, default_value = "20")]
+    pub limit: usize,
+}
+
+// Snapshot command arguments
+#[derive(Args)]
+pub struct CreateSnapshotArgs {
+    /// Memory IDs to include (default: all memories)
+    pub memory_ids: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct RestoreSnapshotArgs {
+    /// Snapshot ID
+    pub snapshot_id: String,
+
+    /// How to handle memories that still exist
+    #[arg(long, value_enum, default_value = "overwrite")]
+    pub mode: RestoreModeArg,
+
+    /// Only show what the restore would change
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Restore without asking for confirmation
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+#[derive(Args)]
+pub struct ExportSnapshotArgs {
+    /// Snapshot ID
+    pub snapshot_id: String,
+
+    /// File to write the snapshot bundle to
+    pub path: String,
+}
+
+#[derive(Args)]
+pub struct ImportSnapshotArgs {
+    /// Snapshot bundle file
+    pub path: String,
+
+    /// How to handle memories that already exist
+    #[arg(long, value_enum, default_value = "skip-existing")]
+    pub mode: RestoreModeArg,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreModeArg {
+    /// Overwrite memories with their snapshot content
+    Overwrite,
+    /// Leave memories that still exist untouched
+    SkipExisting,
+    /// Add the snapshot content as a new version
+    CreateVersions,
+}
+
+// Collection command arguments
+#[derive(Args)]
+pub struct CollectionNameArgs {
+    /// Collection ID or name
+    pub collection: String,
+}
+
+#[derive(Args)]
+pub struct CreateCollectionArgs {
+    /// Collection name
+    pub name: String,
+
+    /// Description of the collection
+    #[arg(long, short)]
+    pub description: Option<String>,
+
+    /// Memory ID to add (repeatable)
+    #[arg(long = "memory")]
+    pub memory_ids: Vec<String>,
+
+    /// Also include memories of this type
+    #[arg(long)]
+    pub memory_type: Option<String>,
+
+    /// Also include memories with this tag (repeatable, any tag matches)
+    #[arg(long = "tag", short = 't')]
+    pub tags: Vec<String>,
+
+    /// Also include memories from this source
+    #[arg(long)]
+    pub source: Option<String>,
+
+    /// Also include memories whose content contains this text
+    #[arg(long)]
+    pub content: Option<String>,
+}
+
+#[derive(Args)]
+pub struct UpdateCollectionArgs {
+    /// Collection ID or name
+    pub collection: String,
+
+    /// New name
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// New description (empty to clear it)
+    #[arg(long, short)]
+    pub description: Option<String>,
+
+    /// Stop including memories that match the collection's query
+    #[arg(long)]
+    pub remove_query: bool,
+}
+
+#[derive(Args)]
+pub struct CollectionMemoryArgs {
+    /// Collection ID or name
+    pub collection: String,
+
+    /// Memory IDs
+    #[arg(required = true)]
+    pub memory_ids: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct CollectionMemoriesArgs {
+    /// Collection ID or name
+    pub collection: String,
+
+    /// Maximum number of results
+    #[arg(short, long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+// Task command arguments
+#[derive(Args)]
+pub struct TaskIdArgs {
+    /// Task ID
+    pub id: String,
+}
+
+#[derive(Args)]
+pub struct AddTaskArgs {
+    /// What needs doing
+    pub title: String,
+
+    /// Who the task is for
+    #[arg(long, short)]
+    pub assignee: Option<String>,
+
+    /// When it is due: an RFC 3339 time, a date (YYYY-MM-DD), or a delay such as 3days
+    #[arg(long)]
+    pub due: Option<String>,
+
+    /// Tag (repeatable)
+    #[arg(long = "tag", short = 't')]
+    pub tags: Vec<String>,
+
+    /// ID of a task this one waits on (repeatable)
+    #[arg(long = "depends-on")]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ListTasksArgs {
+    /// Only tasks assigned to this assignee
+    #[arg(long, short)]
+    pub assignee: Option<String>,
+
+    /// Only tasks with this status (repeatable); open and in-progress tasks by default
+    #[arg(long, conflicts_with = "all")]
+    pub status: Vec<String>,
+
+    /// Include done and cancelled tasks
+    #[arg(long)]
+    pub all: bool,
+
+    /// Only open tasks past their due date
+    #[arg(long)]
+    pub overdue: bool,
+}
+
+#[derive(Args)]
+pub struct AssignTaskArgs {
+    /// Task ID
+    pub id: String,
+
+    /// Assignee; omit to unassign the task
+    pub assignee: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TaskDueArgs {
+    /// Task ID
+    pub id: String,
+
+    /// When it is due, as for `task add --due`; omit to clear the due date
+    pub due: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TaskDependencyArgs {
+    /// Task ID
+    pub id: String,
+
+    /// ID of the task it waits on
+    pub depends_on: String,
+
+    /// Remove the dependency instead
+    #[arg(long)]
+    pub remove: bool,
+}
+
+// Procedure command arguments
+#[derive(Args)]
+pub struct ProcedureIdArgs {
+    /// Procedure ID
+    pub id: String,
+}
+
+#[derive(Args)]
+pub struct AddProcedureArgs {
+    /// Procedure name
+    pub name: String,
+
+    /// What following the procedure achieves
+    #[arg(long, short)]
+    pub goal: String,
+
+    /// Step, in order (repeatable); add the expected outcome after " => "
+    #[arg(long = "step", short = 's', required = true)]
+    pub steps: Vec<String>,
+
+    /// What must hold before starting (repeatable)
+    #[arg(long = "precondition", short = 'p')]
+    pub preconditions: Vec<String>,
+
+    /// How to tell the procedure worked (repeatable)
+    #[arg(long = "metric", short = 'm')]
+    pub metrics: Vec<String>,
+
+    /// Tag (repeatable)
+    #[arg(long = "tag", short = 't')]
+    pub tags: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct FindProceduresArgs {
+    /// What you are trying to achieve
+    pub goal: String,
+
+    /// Maximum number of procedures to show
+    #[arg(long, short, default_value = "5")]
+    pub limit: usize,
+}
+
+// Preference command arguments
+#[derive(Args)]
+pub struct PreferenceKeyArgs {
+    /// Preference key
+    pub key: String,
+
+    /// Preference scope, such as a user or agent ID (default: global)
+    #[arg(long, short)]
+    pub scope: Option<String>,
+}
+
+#[derive(Args)]
+pub struct SetPreferenceArgs {
+    /// Preference key
+    pub key: String,
+
+    /// New value
+    pub value: String,
+
+    /// Preference scope, such as a user or agent ID (default: global)
+    #[arg(long, short)]
+    pub scope: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ListPreferencesArgs {
+    /// Scope whose preferences to show, with the global ones it doesn't override
+    #[arg(long, short, conflicts_with = "all")]
+    pub scope: Option<String>,
+
+    /// List every scope's own preferences
+    #[arg(long)]
+    pub all: bool,
+}
+
+// Rule command arguments
+#[derive(Args)]
+pub struct RuleNameArgs {
+    /// Rule name
+    pub name: String,
+}
+
+#[derive(Args)]
+pub struct RuleScriptArgs {
+    /// Rhai source of the rule
+    #[arg(
+        long,
+        short = 's',
+        conflicts_with = "file",
+        required_unless_present = "file"
+    )]
+    pub script: Option<String>,
+
+    /// File holding the Rhai source of the rule
+    #[arg(long, short = 'f')]
+    pub file: Option<String>,
+}
+
+#[derive(Args)]
+pub struct SetRuleArgs {
+    /// Rule name
+    pub name: String,
+
+    /// Event the rule runs on (created, updated, accessed, deleted, reminder)
+    #[arg(long, short = 'e')]
+    pub event: String,
+
+    #[command(flatten)]
+    pub source: RuleScriptArgs,
+
+    /// What the rule is for
+    #[arg(long, short = 'd')]
+    pub description: Option<String>,
+
+    /// Keep the rule without running it
+    #[arg(long)]
+    pub disabled: bool,
+}
+
+#[derive(Args)]
+pub struct TestRuleArgs {
+    /// ID of the memory to run the script on
+    pub memory_id: String,
+
+    /// Event to run the script as if it happened
+    #[arg(long, short = 'e', default_value = "created")]
+    pub event: String,
+
+    #[command(flatten)]
+    pub source: RuleScriptArgs,
+}
+
+// Persona command arguments
+#[derive(Args)]
+pub struct PersonaAgentArgs {
+    /// Agent ID
+    pub agent: String,
+}
+
+#[derive(Args)]
+pub struct SetPersonaArgs {
+    /// Agent ID
+    pub agent: String,
+
+    /// Who the agent is
+    pub identity: String,
+
+    /// Trait (repeatable)
+    #[arg(long = "trait", short = 't')]
+    pub traits: Vec<String>,
+
+    /// How the agent writes
+    #[arg(long, short = 'w')]
+    pub writing_style: Option<String>,
+
+    /// What the agent must or must not do (repeatable)
+    #[arg(long = "constraint", short = 'c')]
+    pub constraints: Vec<String>,
+}
@@ -11,12 +11,18 @@ pub enum Commands {
     Version,
 
     /// Run diagnostic checks
-    Diagnose,
+    Diagnose(DiagnoseArgs),
 
     /// Memory management commands
     #[command(subcommand)]
     Memory(MemoryCommands),
 
+    /// Capture a note as a memory
+    Note(NoteArgs),
+
+    /// Capture the clipboard contents as a memory
+    Clip(ClipArgs),
+
     /// Entity management commands
     #[command(subcommand)]
     Entity(EntityCommands),
@@ -33,6 +39,54 @@ pub enum Commands {
     #[command(subcommand)]
     Batch(BatchCommands),
 
+    /// Import data exported from other tools
+    #[command(subcommand)]
+    Import(ImportCommands),
+
+    /// Export data to other tools
+    #[command(subcommand)]
+    Export(ExportCommands),
+
+    /// Inspect inter-agent messaging
+    #[command(subcommand)]
+    Messaging(MessagingCommands),
+
+    /// Memory snapshot commands
+    #[command(subcommand)]
+    Snapshot(SnapshotCommands),
+
+    /// Memory collection commands
+    #[command(subcommand)]
+    Collection(CollectionCommands),
+
+    /// Task commands
+    #[command(subcommand)]
+    Task(TaskCommands),
+
+    /// Procedure commands
+    #[command(subcommand)]
+    Procedure(ProcedureCommands),
+
+    /// Preference commands
+    #[command(subcommand)]
+    Preference(PreferenceCommands),
+
+    /// Persona commands
+    #[command(subcommand)]
+    Persona(PersonaCommands),
+
+    /// Automation rule commands
+    #[command(subcommand)]
+    Rule(RuleCommands),
+
+    /// Configuration checks
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Storage maintenance commands
+    #[command(subcommand)]
+    Maintenance(MaintenanceCommands),
+
     /// Relationship type management
     #[command(subcommand)]
     RelationshipType(RelationshipTypeCommands),
@@ -44,6 +98,18 @@ pub enum Commands {
     /// Quick start guide - create sample data
     Quickstart(QuickstartArgs),
 
+    /// Benchmark store, search and graph performance on a synthetic corpus
+    Bench(BenchArgs),
+
+    /// Serve the HTTP and WebSocket API from this binary
+    Serve(ServeArgs),
+
+    /// Browse, search and edit memories in a terminal dashboard
+    Tui(TuiArgs),
+
+    /// Keep storage open and run commands from other invocations
+    Daemon(DaemonArgs),
+
     /// Generate shell completion scripts
     Completions(CompletionsArgs),
 
@@ -104,6 +170,28 @@ RELATED COMMANDS:
     )]
     Add(AddMemoryArgs),
 
+    /// Create a memory from a template
+    #[command(long_about = r#"
+Create a memory from a template. The template fills the memory's content, type,
+tags and properties from the variables given, checking each against its type.
+
+EXAMPLES:
+  # A meeting note
+  locai-cli memory new --template meeting --var title=Standup --var date=2025-11-03 \
+      --var "attendees=Ana, Raj"
+
+  # A bug report
+  locai-cli memory new --template bug-report --var component=parser \
+      --var "summary=Crash on empty input"
+
+RELATED COMMANDS:
+  • locai-cli memory templates - List templates and their variables
+"#)]
+    New(NewMemoryArgs),
+
+    /// List memory templates and their variables
+    Templates,
+
     /// Get a memory by ID
     #[command(
         alias = "show",
@@ -171,6 +259,9 @@ EXAMPLES:
   # Filter by tag
   locai-cli memory search "important" --tag urgent
   
+  # Build the search step by step, then print the equivalent command
+  locai-cli memory search --interactive
+  
   # Using friendly alias
   locai-cli recall "query"
 
@@ -182,6 +273,23 @@ RELATED COMMANDS:
     )]
     Search(SearchArgs),
 
+    /// Find memories located near a point, nearest first
+    #[command(long_about = r#"
+Find the memories whose location is within a radius of a point, nearest first.
+Give memories a location with `memory add --location LAT,LON`.
+
+EXAMPLES:
+  # Memories within a kilometer (the default radius)
+  locai-cli memory near 51.5007,-0.1246
+
+  # Within 250 meters
+  locai-cli memory near 51.5007,-0.1246 --radius 250
+
+  # Combine with a text query instead
+  locai-cli memory search "coffee" --near 51.5007,-0.1246 --radius 500
+"#)]
+    Near(NearArgs),
+
     /// Delete a memory by ID
     #[command(alias = "forget")]
     Delete(DeleteMemoryArgs),
@@ -192,6 +300,43 @@ RELATED COMMANDS:
     /// Add a tag to a memory
     Tag(TagMemoryArgs),
 
+    /// Pin a memory so it leads search results
+    Pin(PinMemoryArgs),
+
+    /// Unpin a memory
+    Unpin(PinMemoryArgs),
+
+    /// List pinned memories
+    Pinned(PinnedArgs),
+
+    /// Set or cancel a memory's reminder
+    #[command(long_about = r#"
+Set a reminder to surface a memory at a time, once or on a schedule. A running
+locai-server announces reminders as they come due on the `system.reminders`
+messaging topic and to `memory.reminder` webhooks.
+
+EXAMPLES:
+  # Remind in two hours
+  locai-cli memory remind memory:abc123 --at 2h
+
+  # Remind every Tuesday, starting next Tuesday
+  locai-cli memory remind memory:abc123 --at 2025-11-04T09:00:00Z --every weekly
+
+  # Cancel the reminder
+  locai-cli memory remind memory:abc123 --cancel
+
+RELATED COMMANDS:
+  • locai-cli memory snooze <id> - Put off a reminder
+  • locai-cli memory reminders - List reminders
+"#)]
+    Remind(RemindArgs),
+
+    /// Put off a memory's reminder
+    Snooze(SnoozeArgs),
+
+    /// List reminders, soonest first
+    Reminders,
+
     /// Count memories
     Count(CountMemoriesArgs),
 
@@ -206,6 +351,27 @@ RELATED COMMANDS:
 
     /// Manage memory relationships
     Relationships(MemoryRelationshipsArgs),
+
+    /// List, approve or reject memories waiting for review
+    #[command(long_about = r#"
+With review enabled in the configuration, memories stored from the listed
+sources wait in a review queue instead of being stored. Approving a memory
+stores it under the ID it was given; rejecting it drops it.
+
+EXAMPLES:
+  # List memories waiting for review, oldest first
+  locai-cli memory review
+
+  # Look at one before deciding
+  locai-cli memory review show memory:abc123
+
+  # Store it
+  locai-cli memory review approve memory:abc123
+
+  # Drop it
+  locai-cli memory review reject memory:abc123
+"#)]
+    Review(ReviewArgs),
 }
 
 #[derive(Subcommand)]
@@ -228,6 +394,12 @@ pub enum EntityCommands {
     /// Update an entity
     Update(UpdateEntityArgs),
 
+    /// Merge duplicate entities into one
+    Merge(MergeEntityArgs),
+
+    /// Split part of an entity off into a new one
+    Split(SplitEntityArgs),
+
     /// Manage entity relationships
     Relationships(EntityRelationshipsArgs),
 
@@ -318,6 +490,9 @@ pub enum GraphCommands {
 
     /// Get entity graph
     Entity(GraphEntityArgs),
+
+    /// Show the graph around a memory or entity as a tree or Mermaid diagram
+    Show(GraphShowArgs),
 }
 
 #[derive(Subcommand)]
@@ -326,6 +501,29 @@ pub enum BatchCommands {
     Execute(ExecuteBatchArgs),
 }
 
+#[derive(Subcommand)]
+pub enum ImportCommands {
+    /// Import a ChatGPT data export (Settings > Data controls > Export data)
+    #[command(name = "chatgpt")]
+    ChatGpt(ImportConversationsArgs),
+
+    /// Import a Claude data export (Settings > Privacy > Export data)
+    Claude(ImportConversationsArgs),
+
+    /// Import memories and graph relations exported from mem0
+    #[command(name = "mem0")]
+    Mem0(ImportFileArgs),
+
+    /// Import sessions, facts, document collections and graph from Zep
+    Zep(ImportFileArgs),
+
+    /// Import an Obsidian vault or folder of markdown notes
+    ///
+    /// Wikilinks become relationships between notes and front-matter tags
+    /// become memory tags. Re-running the import only updates changed notes.
+    Obsidian(ImportVaultArgs),
+}
+
 #[derive(Subcommand)]
 pub enum RelationshipTypeCommands {
     /// List all relationship types
@@ -349,3 +547,185 @@ pub enum RelationshipTypeCommands {
     /// Seed common relationship types
     Seed,
 }
+
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// Push memory embeddings and metadata to a Qdrant collection
+    ///
+    /// Requires the `qdrant` feature.
+    Qdrant(QdrantExportArgs),
+
+    /// Write memory embeddings and metadata as pgvector SQL (pipe into psql)
+    ///
+    /// Requires the `pgvector` feature.
+    Pgvector(PgvectorExportArgs),
+}
+
+#[derive(Subcommand)]
+pub enum MessagingCommands {
+    /// List messages that could not be delivered, most recent first
+    #[command(alias = "dlq")]
+    DeadLetters(DeadLettersArgs),
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Check the resolved configuration against this environment
+    Doctor,
+}
+
+#[derive(Subcommand)]
+pub enum MaintenanceCommands {
+    /// Check memory and vector content against their stored checksums
+    Verify,
+
+    /// Restore corrupted memories and vectors from versions or a backup
+    Repair(RepairStorageArgs),
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotCommands {
+    /// Snapshot the current version of memories
+    Create(CreateSnapshotArgs),
+
+    /// Restore memories from a snapshot, after previewing the changes
+    Restore(RestoreSnapshotArgs),
+
+    /// Export a snapshot with its memory content to a file
+    Export(ExportSnapshotArgs),
+
+    /// Import a snapshot file exported from another instance
+    Import(ImportSnapshotArgs),
+}
+
+#[derive(Subcommand)]
+pub enum CollectionCommands {
+    /// List all collections
+    List,
+
+    /// Show a collection's details
+    Get(CollectionNameArgs),
+
+    /// Create a collection from memory IDs, a query, or both
+    ///
+    /// Query options (--tag, --memory-type, --source, --content) keep the
+    /// collection up to date with memories added later.
+    Create(CreateCollectionArgs),
+
+    /// Rename or describe a collection
+    Update(UpdateCollectionArgs),
+
+    /// Delete a collection, keeping its memories
+    Delete(CollectionNameArgs),
+
+    /// Add memories to a collection
+    Add(CollectionMemoryArgs),
+
+    /// Remove memories from a collection
+    Remove(CollectionMemoryArgs),
+
+    /// List the memories in a collection
+    Memories(CollectionMemoriesArgs),
+}
+
+#[derive(Subcommand)]
+pub enum TaskCommands {
+    /// Create a task
+    Add(AddTaskArgs),
+
+    /// List tasks, soonest due first
+    List(ListTasksArgs),
+
+    /// Show a task and what it waits on
+    Show(TaskIdArgs),
+
+    /// Mark a task in progress
+    Start(TaskIdArgs),
+
+    /// Mark a task done
+    Done(TaskIdArgs),
+
+    /// Cancel a task
+    Cancel(TaskIdArgs),
+
+    /// Reopen a done or cancelled task, or put an in-progress one back
+    Reopen(TaskIdArgs),
+
+    /// Assign a task, or unassign it
+    Assign(AssignTaskArgs),
+
+    /// Set or clear a task's due date
+    Due(TaskDueArgs),
+
+    /// Make a task wait on another
+    Depend(TaskDependencyArgs),
+}
+
+#[derive(Subcommand)]
+pub enum ProcedureCommands {
+    /// Create a procedure
+    Add(AddProcedureArgs),
+
+    /// List procedures by name
+    List,
+
+    /// Show a procedure's steps
+    Show(ProcedureIdArgs),
+
+    /// Find the procedures that reach a goal
+    Find(FindProceduresArgs),
+}
+
+#[derive(Subcommand)]
+pub enum PreferenceCommands {
+    /// Set a preference, replacing its value
+    Set(SetPreferenceArgs),
+
+    /// Show the value in effect for a key
+    Get(PreferenceKeyArgs),
+
+    /// List the preferences in effect for a scope
+    List(ListPreferencesArgs),
+
+    /// Remove a scope's value for a key
+    Unset(PreferenceKeyArgs),
+
+    /// Show the values a key has had
+    History(PreferenceKeyArgs),
+}
+
+#[derive(Subcommand)]
+pub enum PersonaCommands {
+    /// Set an agent's persona, replacing any it had
+    Set(SetPersonaArgs),
+
+    /// Show an agent's persona
+    Show(PersonaAgentArgs),
+
+    /// List every agent's persona
+    List,
+
+    /// Remove an agent's persona
+    Remove(PersonaAgentArgs),
+
+    /// Show the revisions of an agent's persona
+    History(PersonaAgentArgs),
+}
+
+#[derive(Subcommand)]
+pub enum RuleCommands {
+    /// Set a rule, replacing any with the same name
+    Set(SetRuleArgs),
+
+    /// Show a rule
+    Show(RuleNameArgs),
+
+    /// List every rule
+    List,
+
+    /// Remove a rule
+    Remove(RuleNameArgs),
+
+    /// Run a script on a memory without saving changes or sending messages
+    Test(TestRuleArgs),
+}
@@ -1,4 +1,4 @@
-use locai::config::ConfigBuilder;
+use locai::config::{ConfigBuilder, LocaiConfig};
 use locai::prelude::*;
 use locai::relationships::RelationshipTypeRegistry;
 
@@ -9,17 +9,7 @@ pub struct LocaiCliContext {
 
 impl LocaiCliContext {
     pub async fn new(data_dir: Option<String>) -> locai::Result<Self> {
-        let mm = if let Some(dir) = data_dir {
-            let config = ConfigBuilder::new()
-                .with_data_dir(dir)
-                .with_default_storage()
-                .with_default_ml()
-                .with_default_logging()
-                .build()?;
-            locai::init(config).await?
-        } else {
-            locai::init_with_defaults().await?
-        };
+        let mm = locai::init(resolve_config(data_dir.as_deref())?).await?;
 
         let registry = RelationshipTypeRegistry::new();
 
@@ -29,3 +19,17 @@ impl LocaiCliContext {
         })
     }
 }
+
+/// The configuration the CLI runs with for `--data-dir`
+pub fn resolve_config(data_dir: Option<&str>) -> locai::Result<LocaiConfig> {
+    let builder = if let Some(dir) = data_dir {
+        ConfigBuilder::new()
+            .with_data_dir(dir)
+            .with_default_storage()
+            .with_default_ml()
+            .with_default_logging()
+    } else {
+        ConfigBuilder::defaults()
+    };
+    Ok(builder.build()?)
+}
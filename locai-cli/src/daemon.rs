@@ -0,0 +1,303 @@
+//! Daemon mode: keep storage open and run commands for later invocations
+//!
+//! `locai-cli daemon` listens on a Unix domain socket. Later invocations for
+//! the same data directory connect, pass their stdin, stdout and stderr
+//! descriptors along with the command line, and wait for the exit code. The
+//! command runs against the already-open storage, but reads and writes the
+//! caller's terminal, pipes or files as if it had run locally, through an
+//! [`Invocation`] scoped to it. The daemon's own stdin, stdout and stderr,
+//! where its logs go, are never touched. Requests run one at a time.
+
+use std::fs::DirBuilder;
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use locai::LocaiError;
+use serde::{Deserialize, Serialize};
+use tokio::net::UnixListener;
+use tokio::signal::unix::{SignalKind, signal};
+
+use crate::context::resolve_config;
+use crate::invocation::Invocation;
+
+/// Overrides the socket path for both the daemon and its clients
+pub const SOCKET_ENV: &str = "LOCAI_DAEMON_SOCKET";
+
+const SOCKET_FILE: &str = "daemon.sock";
+
+/// How long the daemon waits for a connected client to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A command forwarded from another invocation
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DaemonRequest {
+    /// Command line without the program name
+    pub args: Vec<String>,
+
+    /// Output format resolved by the client, which can see its own TTY
+    pub output_format: String,
+
+    /// Whether the client's output should be colored
+    pub color: bool,
+
+    /// The client's working directory, for relative paths
+    pub cwd: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DaemonResponse {
+    exit_code: i32,
+}
+
+/// The socket for `--data-dir`: `LOCAI_DAEMON_SOCKET`, or `daemon.sock` in
+/// the data directory
+pub fn socket_path(data_dir: Option<&str>) -> locai::Result<PathBuf> {
+    if let Some(path) = std::env::var_os(SOCKET_ENV) {
+        return Ok(PathBuf::from(path));
+    }
+    Ok(resolve_config(data_dir)?.storage.data_dir.join(SOCKET_FILE))
+}
+
+/// Run `request` on the daemon listening at `socket`
+///
+/// Returns the command's exit code, or `None` when no daemon is listening.
+pub fn forward(socket: &Path, request: &DaemonRequest) -> locai::Result<Option<i32>> {
+    let stream = match UnixStream::connect(socket) {
+        Ok(stream) => stream,
+        // No daemon, or a stale socket left by one that was killed
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            return Ok(None);
+        }
+        Err(e) => return Err(socket_error("connect to", socket, e)),
+    };
+
+    let fds = [
+        io::stdin().as_raw_fd(),
+        io::stdout().as_raw_fd(),
+        io::stderr().as_raw_fd(),
+    ];
+    send_fds(&stream, &fds).map_err(|e| socket_error("send to", socket, e))?;
+    write_message(&stream, request).map_err(|e| socket_error("send to", socket, e))?;
+
+    let response: DaemonResponse =
+        read_message(&stream).map_err(|e| socket_error("read the exit code from", socket, e))?;
+    Ok(Some(response.exit_code))
+}
+
+/// Accept requests on `socket` until interrupted, running each through `handle`
+///
+/// `handle` runs in an [`Invocation`] of the client's stdin, stdout, stderr
+/// and working directory, and returns the exit code to send back.
+pub async fn serve<F, Fut>(socket: &Path, mut handle: F) -> locai::Result<()>
+where
+    F: FnMut(DaemonRequest) -> Fut,
+    Fut: Future<Output = i32>,
+{
+    if UnixStream::connect(socket).is_ok() {
+        return Err(LocaiError::Other(format!(
+            "A daemon is already listening on {}",
+            socket.display()
+        )));
+    }
+    // Nobody listens on a leftover socket file, so it's safe to replace
+    let _ = std::fs::remove_file(socket);
+    if let Some(parent) = socket.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| socket_error("create", parent, e))?;
+    }
+
+    let listener = bind_private(socket).map_err(|e| socket_error("bind", socket, e))?;
+    let mut terminate =
+        signal(SignalKind::terminate()).map_err(|e| socket_error("listen on", socket, e))?;
+
+    errln!("Daemon listening on {}", socket.display());
+    let result = loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => break Err(socket_error("accept on", socket, e)),
+            },
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+            _ = terminate.recv() => break Ok(()),
+        };
+        if let Err(e) = serve_connection(stream, &mut handle).await {
+            tracing::warn!("Daemon request failed: {}", e);
+        }
+    };
+
+    let _ = std::fs::remove_file(socket);
+    result
+}
+
+async fn serve_connection<F, Fut>(stream: tokio::net::UnixStream, handle: &mut F) -> io::Result<()>
+where
+    F: FnMut(DaemonRequest) -> Fut,
+    Fut: Future<Output = i32>,
+{
+    let stream = stream.into_std()?;
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let fds: [OwnedFd; 3] = recv_fds(&stream)?.try_into().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "expected the client's stdin, stdout and stderr",
+        )
+    })?;
+    let request: DaemonRequest = read_message(&stream)?;
+
+    let [stdin, stdout, stderr] = fds;
+    let invocation = Invocation::new(
+        stdin.into(),
+        stdout.into(),
+        stderr.into(),
+        request.cwd.clone(),
+    );
+    let exit_code = invocation.scope(handle(request)).await;
+
+    write_message(&stream, &DaemonResponse { exit_code })
+}
+
+fn write_message<T: Serialize>(mut stream: &UnixStream, message: &T) -> io::Result<()> {
+    let mut line = serde_json::to_string(message).map_err(io::Error::other)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+fn read_message<T: for<'de> Deserialize<'de>>(stream: &UnixStream) -> io::Result<T> {
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    if line.is_empty() {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Bind a listener at `socket` that only this user can connect to
+///
+/// Commands run with the daemon's access to storage, so only its owner may
+/// connect. The socket is bound in a private directory and restricted before
+/// it's moved into place, so it's never reachable with wider permissions.
+fn bind_private(socket: &Path) -> io::Result<UnixListener> {
+    let staging = socket.with_file_name(format!(".locai-daemon-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&staging);
+    DirBuilder::new().mode(0o700).create(&staging)?;
+    let staged = staging.join(SOCKET_FILE);
+    let bound = UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, socket)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_dir_all(&staging);
+    bound
+}
+
+fn socket_error(action: &str, path: &Path, e: io::Error) -> LocaiError {
+    LocaiError::Other(format!("Failed to {} {}: {}", action, path.display(), e))
+}
+
+/// A zeroed control message buffer for `fds_len` bytes of descriptors, made of
+/// u64s so that it is aligned for `cmsghdr`
+fn control_buffer(fds_len: u32) -> Vec<u64> {
+    // SAFETY: CMSG_SPACE is a pure size computation
+    let space = unsafe { libc::CMSG_SPACE(fds_len) } as usize;
+    vec![0; space.div_ceil(std::mem::size_of::<u64>())]
+}
+
+/// Send `fds` over `stream` as SCM_RIGHTS ancillary data on a single byte
+fn send_fds(stream: &UnixStream, fds: &[RawFd]) -> io::Result<()> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let fds_len = std::mem::size_of_val(fds) as u32;
+    let mut control = control_buffer(fds_len);
+
+    // SAFETY: msghdr is plain data, and every pointer in it refers to
+    // buffers that outlive the sendmsg call
+    unsafe {
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = std::mem::size_of_val(control.as_slice()) as _;
+
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len) as _;
+        std::ptr::copy_nonoverlapping(
+            fds.as_ptr(),
+            libc::CMSG_DATA(cmsg).cast::<RawFd>(),
+            fds.len(),
+        );
+
+        if libc::sendmsg(stream.as_raw_fd(), &msg, 0) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Receive the descriptors sent by [`send_fds`]
+fn recv_fds(stream: &UnixStream) -> io::Result<Vec<OwnedFd>> {
+    const MAX_FDS: u32 = 3;
+
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let fds_len = MAX_FDS * std::mem::size_of::<RawFd>() as u32;
+    let mut control = control_buffer(fds_len);
+
+    let mut fds = Vec::new();
+    // SAFETY: as in send_fds; each descriptor the kernel installed for us
+    // is taken into an OwnedFd exactly once
+    unsafe {
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = std::mem::size_of_val(control.as_slice()) as _;
+
+        let received = libc::recvmsg(stream.as_raw_fd(), &mut msg, 0);
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if received == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+                let count = ((*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize)
+                    / std::mem::size_of::<RawFd>();
+                for i in 0..count {
+                    fds.push(OwnedFd::from_raw_fd(data.add(i).read_unaligned()));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+
+        if msg.msg_flags & libc::MSG_CTRUNC != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "too many descriptors in the request",
+            ));
+        }
+    }
+    Ok(fds)
+}
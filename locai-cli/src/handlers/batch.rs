@@ -5,7 +5,6 @@ use crate::context::LocaiCliContext;
 use crate::output::*;
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
-use is_terminal::IsTerminal;
 use locai::LocaiError;
 use locai::batch::{BatchExecutor, BatchExecutorConfig, BatchOperation, BatchResult};
 use std::fs;
@@ -17,7 +16,7 @@ pub async fn handle_batch_command(
 ) -> locai::Result<()> {
     match cmd {
         BatchCommands::Execute(args) => {
-            let file_contents = fs::read_to_string(&args.file)
+            let file_contents = fs::read_to_string(crate::invocation::resolve(&args.file))
                 .map_err(|e| LocaiError::Other(format!("Failed to read batch file: {}", e)))?;
 
             let (operations, file_transaction): (Vec<BatchOperation>, Option<bool>) =
@@ -68,7 +67,7 @@ pub async fn handle_batch_command(
             let transaction = args.transaction || file_transaction.unwrap_or(false);
 
             // Create progress bar if stdout is a TTY and not JSON output
-            let pb = if std::io::stdout().is_terminal()
+            let pb = if crate::invocation::shows_progress()
                 && output_format != "json"
                 && operations.len() > 5
             {
@@ -108,12 +107,9 @@ pub async fn handle_batch_command(
             };
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&response).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&response);
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_info(&format!(
                         "Batch execution completed: {} succeeded, {} failed",
@@ -122,7 +118,7 @@ pub async fn handle_batch_command(
                 );
 
                 if response.has_errors() {
-                    println!("\nErrors:");
+                    outln!("\nErrors:");
                     for result in &response.results {
                         if let BatchResult::Error {
                             operation_index,
@@ -130,7 +126,7 @@ pub async fn handle_batch_command(
                             ..
                         } = result
                         {
-                            println!(
+                            outln!(
                                 "  Operation {}: {}",
                                 operation_index.to_string().color(CliColors::error()),
                                 error.color(CliColors::error())
@@ -140,7 +136,7 @@ pub async fn handle_batch_command(
                 }
 
                 if response.completed > 0 {
-                    println!("\nSuccessful operations:");
+                    outln!("\nSuccessful operations:");
                     for result in &response.results {
                         if let BatchResult::Success {
                             operation_index,
@@ -148,7 +144,7 @@ pub async fn handle_batch_command(
                             ..
                         } = result
                         {
-                            println!(
+                            outln!(
                                 "  Operation {}: {}",
                                 operation_index.to_string().color(CliColors::success()),
                                 resource_id.color(CliColors::accent())
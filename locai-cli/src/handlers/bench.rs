@@ -0,0 +1,150 @@
+//! Benchmark command handler
+
+use crate::args::BenchArgs;
+use crate::output::*;
+use colored::*;
+use locai::LocaiError;
+use locai::bench::{BenchConfig, BenchReport, CorpusReport};
+
+pub async fn handle_bench_command(args: BenchArgs, output_format: &str) -> locai::Result<()> {
+    let baseline = match &args.compare {
+        Some(path) => Some(read_report(path)?),
+        None => None,
+    };
+
+    let config = BenchConfig {
+        corpus_sizes: args.sizes,
+        queries: args.queries,
+        search_limit: args.limit,
+        traversal_depth: args.depth,
+        seed: args.seed,
+    };
+
+    if output_format != "json" {
+        outln!(
+            "{}",
+            format_info(&format!(
+                "Benchmarking corpus sizes {:?} with {} queries each...",
+                config.corpus_sizes, config.queries
+            ))
+        );
+    }
+
+    let report = locai::bench::run(&config).await?;
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| LocaiError::Other(format!("Failed to serialize report: {}", e)))?;
+
+    if let Some(path) = &args.save {
+        std::fs::write(path, &json)
+            .map_err(|e| LocaiError::Other(format!("Failed to write report to {}: {}", path, e)))?;
+    }
+
+    if output_format == "json" {
+        print_json(&report);
+    } else {
+        print_report(&report, baseline.as_ref());
+        if let Some(path) = &args.save {
+            outln!();
+            outln!("{}", format_success(&format!("Report saved to {}", path)));
+        }
+    }
+    Ok(())
+}
+
+fn read_report(path: &str) -> locai::Result<BenchReport> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| LocaiError::Other(format!("Failed to read report {}: {}", path, e)))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| LocaiError::Other(format!("Invalid benchmark report {}: {}", path, e)))
+}
+
+fn print_report(report: &BenchReport, baseline: Option<&BenchReport>) {
+    outln!();
+    outln!(
+        "{} {} ({})",
+        "Locai".color(CliColors::accent()).bold(),
+        report.locai_version.color(CliColors::success()),
+        report.storage.color(CliColors::muted())
+    );
+
+    for corpus in &report.corpora {
+        let previous = baseline.and_then(|b| {
+            b.corpora
+                .iter()
+                .find(|c| c.corpus_size == corpus.corpus_size)
+        });
+        print_corpus(corpus, previous);
+    }
+}
+
+fn print_corpus(corpus: &CorpusReport, baseline: Option<&CorpusReport>) {
+    outln!();
+    outln!(
+        "{}",
+        format!("{} memories", corpus.corpus_size)
+            .color(CliColors::primary())
+            .bold()
+    );
+    outln!(
+        "  Store: {:.1} memories/s   Link: {:.1} relationships/s",
+        corpus.store.per_second,
+        corpus.link.per_second
+    );
+    outln!();
+    outln!(
+        "  {:<18} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "Operation".color(CliColors::muted()).bold(),
+        "Mean ms".color(CliColors::muted()).bold(),
+        "p50 ms".color(CliColors::muted()).bold(),
+        "p95 ms".color(CliColors::muted()).bold(),
+        "p99 ms".color(CliColors::muted()).bold(),
+        "vs base".color(CliColors::muted()).bold()
+    );
+    outln!("  {}", "─".repeat(73).color(CliColors::muted()));
+
+    let rows = [
+        (
+            "BM25 search",
+            &corpus.bm25_search,
+            baseline.map(|b| &b.bm25_search),
+        ),
+        (
+            "Vector search",
+            &corpus.vector_search,
+            baseline.map(|b| &b.vector_search),
+        ),
+        (
+            "Graph traversal",
+            &corpus.graph_traversal,
+            baseline.map(|b| &b.graph_traversal),
+        ),
+        (
+            "Shortest path",
+            &corpus.shortest_path,
+            baseline.map(|b| &b.shortest_path),
+        ),
+    ];
+    for (name, stats, previous) in rows {
+        let change = match previous {
+            Some(previous) if previous.p50_ms > 0.0 => {
+                let percent = (stats.p50_ms - previous.p50_ms) / previous.p50_ms * 100.0;
+                let text = format!("{:>+9.1}%", percent);
+                if percent > 0.0 {
+                    text.color(CliColors::warning())
+                } else {
+                    text.color(CliColors::success())
+                }
+            }
+            _ => format!("{:>10}", "-").color(CliColors::muted()),
+        };
+        outln!(
+            "  {:<18} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {}",
+            name,
+            stats.mean_ms,
+            stats.p50_ms,
+            stats.p95_ms,
+            stats.p99_ms,
+            change
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Quick capture handlers: `note` and `clip`
+//!
+//! Captures store the memory and report its ID before embedding and entity
+//! extraction, which run in the background. A one-off invocation finishes
+//! that indexing before it exits (see [`finish_indexing`]); a daemon carries
+//! on with it after the client already has its answer.
+
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+
+use crate::args::{CaptureArgs, ClipArgs, NoteArgs};
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use crate::utils::*;
+use colored::Colorize;
+use locai::LocaiError;
+use locai::prelude::MemoryBuilder;
+use serde_json::json;
+use tokio::task::JoinHandle;
+
+/// Indexing tasks started by captures in this process
+static PENDING_INDEXING: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+pub async fn handle_note_command(
+    args: NoteArgs,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let content = match args.content {
+        Some(content) if content != "-" => content,
+        _ => std::io::read_to_string(crate::invocation::stdin())
+            .map_err(|e| LocaiError::Other(format!("Failed to read the note from stdin: {}", e)))?,
+    };
+    capture(content, args.capture, "note", ctx, output_format).await
+}
+
+pub async fn handle_clip_command(
+    args: ClipArgs,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let content = read_clipboard()?;
+    capture(content, args.capture, "clipboard", ctx, output_format).await
+}
+
+/// Wait for the indexing started by captures in this process
+pub async fn finish_indexing() {
+    let pending = std::mem::take(
+        &mut *PENDING_INDEXING
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner),
+    );
+    for task in pending {
+        let _ = task.await;
+    }
+}
+
+async fn capture(
+    content: String,
+    args: CaptureArgs,
+    source: &str,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(LocaiError::Other(format!(
+            "Nothing to capture: the {} is empty",
+            source
+        )));
+    }
+
+    let mut builder = MemoryBuilder::new_with_content(content)
+        .memory_type(parse_memory_type(&args.memory_type)?)
+        .priority(parse_priority(&args.priority)?)
+        .source(source);
+    for tag in args.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        builder = builder.tag(tag);
+    }
+
+    let (memory_id, indexing) = ctx
+        .memory_manager
+        .store_memory_deferred(builder.build())
+        .await?;
+    {
+        let mut pending = PENDING_INDEXING
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // A daemon captures indefinitely, so drop the tasks that are done
+        pending.retain(|task| !task.is_finished());
+        pending.push(indexing);
+    }
+
+    if output_format == "json" {
+        print_json(&json!({ "memory_id": memory_id }));
+    } else {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Captured {}",
+                memory_id.color(CliColors::accent()).bold()
+            ))
+        );
+    }
+    Ok(())
+}
+
+/// Clipboard readers to try, in order, for this platform
+fn clipboard_commands() -> &'static [&'static [&'static str]] {
+    if cfg!(target_os = "macos") {
+        &[&["pbpaste"]]
+    } else if cfg!(windows) {
+        &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]]
+    } else {
+        &[
+            &["wl-paste", "--no-newline"],
+            &["xclip", "-selection", "clipboard", "-o"],
+            &["xsel", "--clipboard", "--output"],
+        ]
+    }
+}
+
+/// Read the clipboard through the first platform tool that succeeds
+fn read_clipboard() -> locai::Result<String> {
+    let commands = clipboard_commands();
+    for command in commands {
+        let output = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        // Not installed, or no clipboard it can reach (e.g. wl-paste under X11)
+        if let Ok(output) = output
+            && output.status.success()
+        {
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    let tools: Vec<&str> = commands.iter().map(|command| command[0]).collect();
+    Err(LocaiError::Other(format!(
+        "Couldn't read the clipboard; tried {}",
+        tools.join(", ")
+    )))
+}
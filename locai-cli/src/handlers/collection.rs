@@ -0,0 +1,280 @@
+//! Collection command handlers
+
+use crate::args::{CreateCollectionArgs, UpdateCollectionArgs};
+use crate::commands::CollectionCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use crate::tabular::{TableFormat, memory_table};
+use colored::Colorize;
+use locai::LocaiError;
+use locai::memory::{Collection, CollectionQuery};
+use serde_json::json;
+
+pub async fn handle_collection_command(
+    cmd: CollectionCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        CollectionCommands::List => {
+            let collections = ctx.memory_manager.list_collections().await?;
+
+            if output_format == "json" {
+                print_json(&collections);
+            } else if collections.is_empty() {
+                outln!("{}", format_info("No collections found."));
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!("Found {} collections:", collections.len()))
+                );
+                outln!();
+                outln!(
+                    "{:<30} {:<10} {:<8} {}",
+                    "Name".color(CliColors::muted()).bold(),
+                    "Memories".color(CliColors::muted()).bold(),
+                    "Query".color(CliColors::muted()).bold(),
+                    "Description".color(CliColors::muted()).bold()
+                );
+                outln!("{}", "─".repeat(80).color(CliColors::muted()));
+
+                for collection in collections {
+                    outln!(
+                        "{:<30} {:<10} {:<8} {}",
+                        collection.name.color(CliColors::accent()),
+                        collection.memory_ids.len(),
+                        if collection.query.is_some() {
+                            "Yes".color(CliColors::success())
+                        } else {
+                            "No".color(CliColors::muted())
+                        },
+                        collection
+                            .description
+                            .as_deref()
+                            .unwrap_or("-")
+                            .color(CliColors::muted())
+                    );
+                }
+            }
+        }
+
+        CollectionCommands::Get(args) => {
+            let collection = find(ctx, &args.collection).await?;
+            if output_format == "json" {
+                print_json(&collection);
+            } else {
+                print_collection(&collection);
+            }
+        }
+
+        CollectionCommands::Create(args) => {
+            let collection = ctx.memory_manager.create_collection(build(args)).await?;
+            if output_format == "json" {
+                print_json(&collection);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Collection '{}' created.",
+                        collection.name.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+
+        CollectionCommands::Update(args) => {
+            let collection = find(ctx, &args.collection).await?;
+            let collection = ctx
+                .memory_manager
+                .update_collection(apply(collection, args))
+                .await?;
+            if output_format == "json" {
+                print_json(&collection);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Collection '{}' updated.",
+                        collection.name.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+
+        CollectionCommands::Delete(args) => {
+            let deleted = ctx
+                .memory_manager
+                .delete_collection(&args.collection)
+                .await?;
+            if !deleted {
+                return Err(not_found(&args.collection));
+            }
+            if output_format == "json" {
+                print_json(&json!({ "deleted": args.collection }));
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Collection '{}' deleted. Its memories were kept.",
+                        args.collection.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+
+        CollectionCommands::Add(args) => {
+            let collection = ctx
+                .memory_manager
+                .add_to_collection(&args.collection, &args.memory_ids)
+                .await?
+                .ok_or_else(|| not_found(&args.collection))?;
+            if output_format == "json" {
+                print_json(&collection);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Collection '{}' now lists {} memories.",
+                        collection.name.color(CliColors::accent()),
+                        collection.memory_ids.len()
+                    ))
+                );
+            }
+        }
+
+        CollectionCommands::Remove(args) => {
+            let collection = ctx
+                .memory_manager
+                .remove_from_collection(&args.collection, &args.memory_ids)
+                .await?
+                .ok_or_else(|| not_found(&args.collection))?;
+            if output_format == "json" {
+                print_json(&collection);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Collection '{}' now lists {} memories.",
+                        collection.name.color(CliColors::accent()),
+                        collection.memory_ids.len()
+                    ))
+                );
+                if collection.query.is_some() {
+                    outln!(
+                        "{}",
+                        format_info("Memories matching the collection's query stay members.")
+                    );
+                }
+            }
+        }
+
+        CollectionCommands::Memories(args) => {
+            let collection = find(ctx, &args.collection).await?;
+            let memories = ctx
+                .memory_manager
+                .collection_memories(&collection, Some(args.limit))
+                .await?;
+
+            if output_format == "json" {
+                print_json(&memories);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                memory_table(&memories).print(format);
+            } else {
+                print_memory_list(&memories);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn find(ctx: &LocaiCliContext, id_or_name: &str) -> locai::Result<Collection> {
+    ctx.memory_manager
+        .get_collection(id_or_name)
+        .await?
+        .ok_or_else(|| not_found(id_or_name))
+}
+
+fn not_found(id_or_name: &str) -> LocaiError {
+    LocaiError::Collection(format!("Collection '{}' not found", id_or_name))
+}
+
+/// The collection described by `create` arguments
+fn build(args: CreateCollectionArgs) -> Collection {
+    let query = CollectionQuery {
+        memory_type: args.memory_type,
+        tags: (!args.tags.is_empty()).then_some(args.tags),
+        source: args.source,
+        content: args.content,
+        ..Default::default()
+    };
+
+    let mut collection = Collection::new(args.name).with_memories(args.memory_ids);
+    if let Some(description) = args.description {
+        collection = collection.with_description(description);
+    }
+    if query != CollectionQuery::default() {
+        collection = collection.with_query(query);
+    }
+    collection
+}
+
+fn apply(mut collection: Collection, args: UpdateCollectionArgs) -> Collection {
+    if let Some(name) = args.name {
+        collection.name = name;
+    }
+    if let Some(description) = args.description {
+        collection.description = (!description.is_empty()).then_some(description);
+    }
+    if args.remove_query {
+        collection.query = None;
+    }
+    collection
+}
+
+fn print_collection(collection: &Collection) {
+    outln!(
+        "{}",
+        "━━━ Collection Details ━━━"
+            .color(CliColors::accent())
+            .bold()
+    );
+    outln!(
+        "{}: {}",
+        "Name".color(CliColors::muted()),
+        collection.name.color(CliColors::accent()).bold()
+    );
+    outln!(
+        "{}: {}",
+        "ID".color(CliColors::muted()),
+        collection.id.color(CliColors::primary())
+    );
+    if let Some(description) = &collection.description {
+        outln!(
+            "{}: {}",
+            "Description".color(CliColors::muted()),
+            description
+        );
+    }
+    outln!(
+        "{}: {}",
+        "Listed memories".color(CliColors::muted()),
+        collection.memory_ids.len()
+    );
+    if let Some(query) = &collection.query {
+        outln!(
+            "{}: {}",
+            "Query".color(CliColors::muted()),
+            serde_json::to_string(query).unwrap_or_default()
+        );
+    }
+    outln!(
+        "{}: {}",
+        "Created".color(CliColors::muted()),
+        collection
+            .created_at
+            .format("%Y-%m-%d %H:%M:%S UTC")
+            .to_string()
+            .color(CliColors::primary())
+    );
+}
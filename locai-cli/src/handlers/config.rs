@@ -0,0 +1,359 @@
+//! Config command handlers
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::commands::ConfigCommands;
+use crate::context::resolve_config;
+use crate::output::*;
+use colored::*;
+use locai::LocaiError;
+use locai::config::{EmbeddingServiceType, LocaiConfig};
+use locai::core::is_feature_enabled;
+use locai::storage::config::SurrealDBEngine;
+use locai::storage::shared_storage::schema::EMBEDDING_DIMENSIONS;
+use locai::storage::traits::GraphStore;
+use serde::Serialize;
+
+/// How long to wait for storage to open before reporting it unreachable
+const STORAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Stored embeddings sampled for the dimension check
+const EMBEDDING_SAMPLE: usize = 500;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize)]
+struct DoctorCheck {
+    name: &'static str,
+    status: CheckStatus,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Ok,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warning(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warning,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn error(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Error,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub async fn handle_config_command(
+    cmd: ConfigCommands,
+    data_dir: Option<&str>,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        ConfigCommands::Doctor => doctor(data_dir, output_format).await,
+    }
+}
+
+async fn doctor(data_dir: Option<&str>, output_format: &str) -> locai::Result<()> {
+    let checks = run_checks(data_dir).await;
+
+    if output_format == "json" {
+        print_json(&checks);
+    } else {
+        for check in &checks {
+            let line = format!("{}: {}", check.name, check.message);
+            let line = match check.status {
+                CheckStatus::Ok => format_success(&line),
+                CheckStatus::Warning => format_warning(&line),
+                CheckStatus::Error => format_error(&line),
+            };
+            outln!("{}", line);
+            if let Some(hint) = &check.hint {
+                outln!("  {}", hint.color(CliColors::muted()));
+            }
+        }
+    }
+
+    let errors = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Error)
+        .count();
+    if errors > 0 {
+        return Err(LocaiError::Configuration(format!(
+            "{} config doctor checks failed",
+            errors
+        )));
+    }
+    Ok(())
+}
+
+async fn run_checks(data_dir: Option<&str>) -> Vec<DoctorCheck> {
+    let config = match resolve_config(data_dir) {
+        Ok(config) => config,
+        Err(e) => {
+            // Nothing else can be checked without a config
+            return vec![DoctorCheck::error(
+                "Configuration",
+                e.to_string(),
+                "Fix the setting named above; SURREALDB_URL and --data-dir also feed into the config",
+            )];
+        }
+    };
+
+    let mut checks = vec![DoctorCheck::ok("Configuration", "Valid")];
+    checks.extend(check_features(&config));
+    checks.extend(check_directories(&config));
+
+    let engine_compiled =
+        engine_feature(&config.storage.graph.surrealdb.engine).is_none_or(is_feature_enabled);
+    if engine_compiled {
+        checks.extend(check_storage(&config).await);
+    }
+    checks
+}
+
+/// The cargo feature an engine needs, if any
+fn engine_feature(engine: &SurrealDBEngine) -> Option<&'static str> {
+    match engine {
+        SurrealDBEngine::Memory | SurrealDBEngine::RocksDB => Some("surrealdb-embedded"),
+        SurrealDBEngine::WebSocket | SurrealDBEngine::Http => Some("surrealdb-remote"),
+    }
+}
+
+fn check_features(config: &LocaiConfig) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+
+    let engine = &config.storage.graph.surrealdb.engine;
+    if let Some(feature) = engine_feature(engine)
+        && !is_feature_enabled(feature)
+    {
+        checks.push(DoctorCheck::error(
+            "Feature flags",
+            format!(
+                "The {:?} storage engine is configured, but this build lacks the '{}' feature",
+                engine, feature
+            ),
+            format!(
+                "Rebuild with `--features {}`, or configure an engine this build supports",
+                feature
+            ),
+        ));
+    }
+
+    if config.ml.embedding.service_type == EmbeddingServiceType::Local
+        && !is_feature_enabled("fastembed")
+    {
+        checks.push(DoctorCheck::warning(
+            "Feature flags",
+            "Local embeddings are configured, but this build lacks the 'fastembed' feature",
+            "Rebuild with `--features fastembed`, or use a remote embedding service",
+        ));
+    }
+
+    if checks.is_empty() {
+        checks.push(DoctorCheck::ok(
+            "Feature flags",
+            format!(
+                "Match the config ({})",
+                locai::core::enabled_features().join(", ")
+            ),
+        ));
+    }
+    checks
+}
+
+fn check_directories(config: &LocaiConfig) -> Vec<DoctorCheck> {
+    let mut dirs: Vec<(&'static str, PathBuf)> =
+        vec![("Data directory", config.storage.data_dir.clone())];
+    let surrealdb = &config.storage.graph.surrealdb;
+    if matches!(surrealdb.engine, SurrealDBEngine::RocksDB) {
+        let path = PathBuf::from(&surrealdb.connection);
+        if !path.starts_with(&config.storage.data_dir) {
+            dirs.push(("Database directory", path));
+        }
+    }
+    if config.ml.embedding.service_type == EmbeddingServiceType::Local {
+        dirs.push(("Model cache directory", config.ml.model_cache_dir.clone()));
+    }
+    if let Some(parent) = config
+        .logging
+        .file
+        .as_deref()
+        .and_then(Path::parent)
+        .filter(|p| !p.as_os_str().is_empty())
+    {
+        dirs.push(("Log directory", parent.to_path_buf()));
+    }
+
+    dirs.into_iter()
+        .map(|(name, dir)| match check_writable(&dir) {
+            Ok(()) => DoctorCheck::ok(name, format!("{} is writable", dir.display())),
+            Err(e) => DoctorCheck::error(
+                name,
+                format!("{} is not writable: {}", dir.display(), e),
+                "Fix the directory's permissions, or pass --data-dir with a writable directory",
+            ),
+        })
+        .collect()
+}
+
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(".locai-doctor-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+async fn check_storage(config: &LocaiConfig) -> Vec<DoctorCheck> {
+    let surrealdb = &config.storage.graph.surrealdb;
+    let unreachable_hint = match surrealdb.engine {
+        SurrealDBEngine::WebSocket | SurrealDBEngine::Http => format!(
+            "Check that SurrealDB is running at {} and that the SURREALDB_* credentials are right",
+            surrealdb.connection
+        ),
+        SurrealDBEngine::RocksDB => {
+            "Another Locai process may hold the database lock; stop it and retry".to_string()
+        }
+        SurrealDBEngine::Memory => "Re-run with --verbose for details".to_string(),
+    };
+
+    let storage = match tokio::time::timeout(
+        STORAGE_TIMEOUT,
+        locai::storage::create_storage_service(config),
+    )
+    .await
+    {
+        Ok(Ok(storage)) => storage,
+        Ok(Err(e)) => {
+            return vec![DoctorCheck::error(
+                "Storage",
+                format!("Failed to open {:?} storage: {}", surrealdb.engine, e),
+                unreachable_hint,
+            )];
+        }
+        Err(_) => {
+            return vec![DoctorCheck::error(
+                "Storage",
+                format!(
+                    "Timed out after {}s opening {:?} storage",
+                    STORAGE_TIMEOUT.as_secs(),
+                    surrealdb.engine
+                ),
+                unreachable_hint,
+            )];
+        }
+    };
+
+    let mut checks = vec![match storage.health_check().await {
+        Ok(true) => DoctorCheck::ok(
+            "Storage",
+            format!("{:?} storage is reachable", surrealdb.engine),
+        ),
+        Ok(false) => DoctorCheck::error(
+            "Storage",
+            format!("{:?} storage reports unhealthy", surrealdb.engine),
+            unreachable_hint,
+        ),
+        Err(e) => DoctorCheck::error(
+            "Storage",
+            format!("Health check failed: {}", e),
+            unreachable_hint,
+        ),
+    }];
+    checks.push(check_embedding_dimensions(storage.as_ref()).await);
+    checks
+}
+
+async fn check_embedding_dimensions(storage: &dyn GraphStore) -> DoctorCheck {
+    let mut dimensions = BTreeSet::new();
+
+    match storage
+        .list_memories(None, Some(EMBEDDING_SAMPLE), None)
+        .await
+    {
+        Ok(memories) => dimensions.extend(
+            memories
+                .iter()
+                .filter_map(|m| m.embedding.as_ref().map(Vec::len)),
+        ),
+        Err(e) => {
+            return DoctorCheck::warning(
+                "Embedding dimensions",
+                format!("Could not read stored memories: {}", e),
+                "Re-run with --verbose for details",
+            );
+        }
+    }
+    match storage
+        .list_vectors(None, Some(EMBEDDING_SAMPLE), None)
+        .await
+    {
+        Ok(vectors) => dimensions.extend(vectors.iter().map(|v| v.dimension)),
+        Err(e) => {
+            return DoctorCheck::warning(
+                "Embedding dimensions",
+                format!("Could not read stored vectors: {}", e),
+                "Re-run with --verbose for details",
+            );
+        }
+    }
+
+    let found = dimensions
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    match dimensions.len() {
+        0 => DoctorCheck::ok("Embedding dimensions", "No stored embeddings yet"),
+        1 if dimensions.contains(&EMBEDDING_DIMENSIONS) => DoctorCheck::ok(
+            "Embedding dimensions",
+            format!(
+                "Stored embeddings match the {}-dimension index",
+                EMBEDDING_DIMENSIONS
+            ),
+        ),
+        1 => DoctorCheck::error(
+            "Embedding dimensions",
+            format!(
+                "Stored embeddings have {} dimensions, but the vector index expects {}",
+                found, EMBEDDING_DIMENSIONS
+            ),
+            format!(
+                "Use a {}-dimension embedding model (such as BAAI/bge-m3) and re-embed existing memories",
+                EMBEDDING_DIMENSIONS
+            ),
+        ),
+        _ => DoctorCheck::error(
+            "Embedding dimensions",
+            format!("Stored embeddings mix dimensions {}", found),
+            format!(
+                "Embeddings from different models can't be compared; re-embed all memories with one {}-dimension model",
+                EMBEDDING_DIMENSIONS
+            ),
+        ),
+    }
+}
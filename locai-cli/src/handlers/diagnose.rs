@@ -0,0 +1,152 @@
+//! Deep diagnose handler: index consistency checks and repairs
+
+use crate::args::DiagnoseArgs;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::*;
+use locai::LocaiError;
+use locai::core::{ConsistencyReport, check_consistency, repair_consistency};
+use serde_json::json;
+use std::io::BufRead;
+
+pub async fn handle_deep_diagnose(
+    args: &DiagnoseArgs,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let report = check_consistency(&ctx.memory_manager).await?;
+
+    if output_format == "json" {
+        // Prompting would corrupt the JSON on stdout, so require --yes instead
+        if args.fix && !args.yes && !report.is_consistent() {
+            return Err(LocaiError::Other(
+                "Pass --yes together with --fix to repair".to_string(),
+            ));
+        }
+    } else {
+        print_consistency_report(&report);
+    }
+
+    if !args.fix || report.is_consistent() {
+        if output_format == "json" {
+            print_json(&json!({ "consistency": report, "repair": null }));
+        } else if !report.is_consistent() {
+            outln!("{}", format_info("Run with --fix to repair these issues."));
+        }
+        return Ok(());
+    }
+
+    if !args.yes {
+        outln!();
+        outln!("Type 'yes' to repair {} issues:", report.issue_count());
+        let mut input = String::new();
+        crate::invocation::stdin()
+            .read_line(&mut input)
+            .map_err(|e| LocaiError::Other(format!("Failed to read input: {}", e)))?;
+        if input.trim() != "yes" {
+            outln!("{}", format_info("Repair cancelled."));
+            return Ok(());
+        }
+    }
+
+    let repair = repair_consistency(&ctx.memory_manager, &report).await?;
+
+    if output_format == "json" {
+        print_json(&json!({ "consistency": report, "repair": repair }));
+    } else {
+        for detail in &repair.details {
+            outln!("  {}", detail.color(CliColors::muted()));
+        }
+        let summary = format!(
+            "Deleted {} vectors and {} relationships, repaired {} versions.",
+            repair.vectors_deleted, repair.relationships_deleted, repair.versions_repaired
+        );
+        if repair.failed > 0 {
+            outln!(
+                "{}",
+                format_warning(&format!(
+                    "{} {} issues could not be repaired.",
+                    summary, repair.failed
+                ))
+            );
+        } else {
+            outln!("{}", format_success(&summary));
+        }
+    }
+    Ok(())
+}
+
+fn print_consistency_report(report: &ConsistencyReport) {
+    if report.is_consistent() {
+        outln!("{}", format_success("Index consistency: No issues found"));
+        return;
+    }
+
+    outln!(
+        "{}",
+        format_warning(&format!(
+            "Index consistency: {} issues found",
+            report.issue_count()
+        ))
+    );
+
+    if !report.orphaned_vectors.is_empty() {
+        outln!();
+        outln!(
+            "{}",
+            format!(
+                "Vectors without memories ({})",
+                report.orphaned_vectors.len()
+            )
+            .bold()
+        );
+        for vector_id in &report.orphaned_vectors {
+            outln!("  {}", vector_id.color(CliColors::accent()));
+        }
+    }
+
+    if !report.dangling_relationships.is_empty() {
+        outln!();
+        outln!(
+            "{}",
+            format!(
+                "Relationships with missing endpoints ({})",
+                report.dangling_relationships.len()
+            )
+            .bold()
+        );
+        for dangling in &report.dangling_relationships {
+            outln!(
+                "  {} {} {}",
+                dangling.relationship_id.color(CliColors::accent()),
+                format!("[{}]", dangling.relationship_type).color(CliColors::muted()),
+                format!("missing: {}", dangling.missing_endpoints.join(", "))
+                    .color(CliColors::error())
+            );
+        }
+    }
+
+    if !report.broken_delta_chains.is_empty() {
+        outln!();
+        outln!(
+            "{}",
+            format!(
+                "Delta chains with missing parents ({})",
+                report.broken_delta_chains.len()
+            )
+            .bold()
+        );
+        for issue in &report.broken_delta_chains {
+            outln!(
+                "  {} {} {}",
+                issue.memory_id.color(CliColors::accent()),
+                issue
+                    .version_id
+                    .as_deref()
+                    .unwrap_or_default()
+                    .color(CliColors::muted()),
+                issue.description.color(CliColors::error())
+            );
+        }
+    }
+}
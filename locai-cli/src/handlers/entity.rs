@@ -4,10 +4,11 @@ use crate::args::*;
 use crate::commands::EntityCommands;
 use crate::context::LocaiCliContext;
 use crate::output::*;
+use crate::tabular::{TableFormat, entity_table, memory_table};
 use colored::*;
 use locai::LocaiError;
 use locai::storage::filters::{EntityFilter, RelationshipFilter};
-use locai::storage::models::{Entity, Relationship};
+use locai::storage::models::{Entity, EntitySplit, Relationship};
 use serde_json::{Value, json};
 
 pub async fn handle_entity_command(
@@ -40,28 +41,22 @@ pub async fn handle_entity_command(
             let created = ctx.memory_manager.create_entity(entity).await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&created).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&created);
             } else {
-                println!("Entity created with ID: {}", created.id);
+                outln!("Entity created with ID: {}", created.id);
             }
         }
 
         EntityCommands::Get(args) => match ctx.memory_manager.get_entity(&args.id).await? {
             Some(entity) => {
                 if output_format == "json" {
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&entity).unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&entity);
                 } else {
                     print_entity(&entity);
                 }
             }
             None => {
-                println!("Entity with ID '{}' not found.", args.id);
+                outln!("Entity with ID '{}' not found.", args.id);
             }
         },
 
@@ -77,18 +72,17 @@ pub async fn handle_entity_command(
                 .await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&entities).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&entities);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                entity_table(&entities).print(format);
             } else {
                 print_entity_list(&entities);
             }
         }
 
         EntityCommands::Delete(args) => match ctx.memory_manager.delete_entity(&args.id).await? {
-            true => println!("Entity '{}' deleted successfully.", args.id),
-            false => println!("Entity '{}' not found or could not be deleted.", args.id),
+            true => outln!("Entity '{}' deleted successfully.", args.id),
+            false => outln!("Entity '{}' not found or could not be deleted.", args.id),
         },
 
         EntityCommands::Count => {
@@ -96,12 +90,9 @@ pub async fn handle_entity_command(
 
             if output_format == "json" {
                 let result = json!({ "count": count });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else {
-                println!("Total entities: {}", count);
+                outln!("Total entities: {}", count);
             }
         }
 
@@ -125,12 +116,9 @@ pub async fn handle_entity_command(
             let updated = ctx.memory_manager.update_entity(entity).await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&updated).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&updated);
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_success(&format!(
                         "Entity '{}' updated successfully.",
@@ -140,6 +128,68 @@ pub async fn handle_entity_command(
             }
         }
 
+        EntityCommands::Merge(args) => {
+            let merged = ctx
+                .memory_manager
+                .merge_entities(&args.target, &args.sources)
+                .await?;
+
+            if output_format == "json" {
+                print_json(&merged);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Merged {} into '{}'.",
+                        args.sources.join(", "),
+                        merged.id.color(CliColors::accent())
+                    ))
+                );
+                print_entity(&merged);
+            }
+        }
+
+        EntityCommands::Split(args) => {
+            let original = ctx
+                .memory_manager
+                .get_entity(&args.id)
+                .await?
+                .ok_or_else(|| LocaiError::Other(format!("Entity '{}' not found", args.id)))?;
+
+            let properties = match args.properties {
+                Some(props) => serde_json::from_str(&props)
+                    .map_err(|e| LocaiError::Other(format!("Invalid JSON properties: {}", e)))?,
+                None => json!({}),
+            };
+
+            let split = EntitySplit {
+                entity: Entity {
+                    id: args.new_id,
+                    entity_type: args.entity_type.unwrap_or(original.entity_type),
+                    properties,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                },
+                relationship_ids: args.relationships,
+                memory_ids: args.memories,
+            };
+            let created = ctx.memory_manager.split_entity(&args.id, split).await?;
+
+            if output_format == "json" {
+                print_json(&created);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Split '{}' off from '{}'.",
+                        created.id.color(CliColors::accent()),
+                        args.id
+                    ))
+                );
+                print_entity(&created);
+            }
+        }
+
         EntityCommands::Relationships(args) => {
             if let Some(command) = args.command {
                 match command {
@@ -189,13 +239,9 @@ pub async fn handle_entity_command(
                             .await?;
 
                         if output_format == "json" {
-                            println!(
-                                "{}",
-                                serde_json::to_string_pretty(&created)
-                                    .unwrap_or_else(|_| "{}".to_string())
-                            );
+                            print_json(&created);
                         } else {
-                            println!(
+                            outln!(
                                 "{}",
                                 format_success(&format!(
                                     "Relationship '{}' created from entity '{}' to '{}'",
@@ -239,36 +285,33 @@ pub async fn handle_entity_command(
                         "incoming": incoming,
                         "total": outgoing.len() + incoming.len()
                     });
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&result);
                 } else {
-                    println!(
+                    outln!(
                         "{}",
                         format_info(&format!(
                             "Entity Relationships: {}",
                             args.id.color(CliColors::accent())
                         ))
                     );
-                    println!();
+                    outln!();
                     if !outgoing.is_empty() {
-                        println!(
+                        outln!(
                             "{}",
                             format_info(&format!("Outgoing Relationships ({}):", outgoing.len()))
                         );
                         print_relationship_list(&outgoing);
-                        println!();
+                        outln!();
                     }
                     if !incoming.is_empty() {
-                        println!(
+                        outln!(
                             "{}",
                             format_info(&format!("Incoming Relationships ({}):", incoming.len()))
                         );
                         print_relationship_list(&incoming);
                     }
                     if outgoing.is_empty() && incoming.is_empty() {
-                        println!("{}", format_info("No relationships found."));
+                        outln!("{}", format_info("No relationships found."));
                     }
                 }
             }
@@ -296,12 +339,11 @@ pub async fn handle_entity_command(
             }
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&memories).unwrap_or_else(|_| "[]".to_string())
-                );
+                print_json(&memories);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                memory_table(&memories).print(format);
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_info(&format!(
                         "Memories for Entity: {}",
@@ -309,9 +351,9 @@ pub async fn handle_entity_command(
                     ))
                 );
                 if memories.is_empty() {
-                    println!("{}", format_info("No memories found."));
+                    outln!("{}", format_info("No memories found."));
                 } else {
-                    println!();
+                    outln!();
                     print_memory_list(&memories);
                 }
             }
@@ -325,17 +367,13 @@ pub async fn handle_entity_command(
 
             if entities.is_empty() {
                 if output_format == "json" {
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&json!({
-                            "central_entities": [],
-                            "total_results": 0,
-                            "message": "No entities found in storage"
-                        }))
-                        .unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&json!({
+                        "central_entities": [],
+                        "total_results": 0,
+                        "message": "No entities found in storage"
+                    }));
                 } else {
-                    println!("{}", format_info("No entities found in storage."));
+                    outln!("{}", format_info("No entities found in storage."));
                 }
                 return Ok(());
             }
@@ -401,20 +439,17 @@ pub async fn handle_entity_command(
                         "preview": preview
                     })).collect::<Vec<_>>()
                 });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else {
-                println!(
+                outln!(
                     "{}",
                     "━━━ Central Entities ━━━".color(CliColors::accent()).bold()
                 );
                 if entity_centrality.is_empty() {
-                    println!("{}", format_info("No entities found."));
+                    outln!("{}", format_info("No entities found."));
                 } else {
-                    println!();
-                    println!(
+                    outln!();
+                    outln!(
                         "{:<8} {:<36} {:<15} {:<10} {}",
                         "Rank".color(CliColors::muted()).bold(),
                         "Entity ID".color(CliColors::muted()).bold(),
@@ -422,11 +457,11 @@ pub async fn handle_entity_command(
                         "Score".color(CliColors::muted()).bold(),
                         "Preview".color(CliColors::muted()).bold()
                     );
-                    println!("{}", "─".repeat(100).color(CliColors::muted()));
+                    outln!("{}", "─".repeat(100).color(CliColors::muted()));
                     for (i, (entity_id, score, entity_type, preview)) in
                         entity_centrality.iter().enumerate()
                     {
-                        println!(
+                        outln!(
                             "{:<8} {:<36} {:<15} {:<10} {}",
                             (i + 1).to_string().color(CliColors::muted()),
                             entity_id.color(CliColors::accent()),
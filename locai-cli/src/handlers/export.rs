@@ -0,0 +1,146 @@
+//! Export command handlers
+
+use crate::args::VectorExportArgs;
+use crate::commands::ExportCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use locai::LocaiError;
+use locai::export::{VectorExportOptions, VectorExportReport};
+use locai::storage::filters::MemoryFilter;
+
+pub async fn handle_export_command(
+    cmd: ExportCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        ExportCommands::Qdrant(args) => {
+            #[cfg(feature = "qdrant")]
+            {
+                use locai::export::qdrant::{QdrantConfig, QdrantSink};
+
+                let sink = QdrantSink::new(QdrantConfig {
+                    url: args.url,
+                    collection: args.collection,
+                    api_key: args
+                        .api_key
+                        .or_else(|| std::env::var("QDRANT_API_KEY").ok()),
+                    distance: args.distance,
+                    ..QdrantConfig::default()
+                })?;
+                let report = locai::export::export_vectors(
+                    &ctx.memory_manager,
+                    &sink,
+                    export_options(args.export),
+                )
+                .await?;
+                print_report(&report, &sink.config().collection, output_format, false);
+                Ok(())
+            }
+            #[cfg(not(feature = "qdrant"))]
+            {
+                let _ = (args, ctx, output_format);
+                Err(LocaiError::FeatureNotEnabled {
+                    feature: "qdrant".to_string(),
+                })
+            }
+        }
+        ExportCommands::Pgvector(args) => {
+            #[cfg(feature = "pgvector")]
+            {
+                use locai::export::pgvector::{PgvectorConfig, PgvectorSink};
+
+                let to_stdout = args.sql_file.is_none();
+                let writer: Box<dyn std::io::Write + Send> = match &args.sql_file {
+                    Some(path) => Box::new(
+                        std::fs::File::create(crate::invocation::resolve(path)).map_err(|e| {
+                            LocaiError::Other(format!("Failed to create {}: {}", path, e))
+                        })?,
+                    ),
+                    None => Box::new(crate::invocation::stdout()),
+                };
+                let sink = PgvectorSink::new(
+                    PgvectorConfig {
+                        table: args.table.clone(),
+                        create_index: !args.no_index,
+                    },
+                    writer,
+                )?;
+                let report = locai::export::export_vectors(
+                    &ctx.memory_manager,
+                    &sink,
+                    export_options(args.export),
+                )
+                .await?;
+                print_report(&report, &args.table, output_format, to_stdout);
+                Ok(())
+            }
+            #[cfg(not(feature = "pgvector"))]
+            {
+                let _ = (args, ctx, output_format);
+                Err(LocaiError::FeatureNotEnabled {
+                    feature: "pgvector".to_string(),
+                })
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(any(feature = "qdrant", feature = "pgvector")), allow(dead_code))]
+fn export_options(args: VectorExportArgs) -> VectorExportOptions {
+    let filter = if args.memory_type.is_some() || !args.tag.is_empty() {
+        Some(MemoryFilter {
+            memory_type: args.memory_type,
+            tags: (!args.tag.is_empty()).then_some(args.tag),
+            ..Default::default()
+        })
+    } else {
+        None
+    };
+
+    VectorExportOptions {
+        batch_size: args.batch_size,
+        filter,
+    }
+}
+
+/// Print the export report; when the export itself went to stdout, the report
+/// goes to stderr so it does not end up in the piped output
+#[cfg_attr(not(any(feature = "qdrant", feature = "pgvector")), allow(dead_code))]
+fn print_report(
+    report: &VectorExportReport,
+    target: &str,
+    output_format: &str,
+    export_on_stdout: bool,
+) {
+    let lines = if output_format == "json" {
+        vec![serde_json::to_string_pretty(report).unwrap_or_else(|_| "{}".to_string())]
+    } else {
+        let mut lines = vec![format_success(&format!(
+            "Exported {} vectors to {}",
+            report.exported, target
+        ))];
+        if report.skipped_without_embedding > 0 {
+            lines.push(format_info(&format!(
+                "Skipped {} memories without embeddings",
+                report.skipped_without_embedding
+            )));
+        }
+        if report.skipped_dimension_mismatch > 0 {
+            lines.push(format_warning(&format!(
+                "Skipped {} memories whose embedding dimension differs from {}",
+                report.skipped_dimension_mismatch,
+                report.dimensions.unwrap_or_default()
+            )));
+        }
+        lines
+    };
+
+    for line in lines {
+        if export_on_stdout {
+            errln!("{}", line);
+        } else {
+            outln!("{}", line);
+        }
+    }
+}
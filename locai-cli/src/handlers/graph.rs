@@ -1,5 +1,6 @@
 //! Graph command handlers
 
+use crate::args::{GraphShowArgs, GraphShowFormat};
 use crate::commands::GraphCommands;
 use crate::context::LocaiCliContext;
 use crate::output::*;
@@ -41,19 +42,15 @@ pub async fn handle_graph_command(
                             "duration_seconds": duration_seconds,
                             "memory_count": memories.len()
                         });
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&graph_json)
-                                .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&graph_json);
                     } else {
                         print_memory_graph(&graph);
-                        println!();
-                        println!(
+                        outln!();
+                        outln!(
                             "{}",
                             "━━━ Temporal Span ━━━".color(CliColors::accent()).bold()
                         );
-                        println!(
+                        outln!(
                             "{}: {}",
                             "Start".color(CliColors::muted()),
                             start
@@ -61,20 +58,20 @@ pub async fn handle_graph_command(
                                 .to_string()
                                 .color(CliColors::primary())
                         );
-                        println!(
+                        outln!(
                             "{}: {}",
                             "End".color(CliColors::muted()),
                             end.format("%Y-%m-%d %H:%M:%S UTC")
                                 .to_string()
                                 .color(CliColors::primary())
                         );
-                        println!(
+                        outln!(
                             "{}: {} days ({} seconds)",
                             "Duration".color(CliColors::muted()),
                             duration_days.to_string().color(CliColors::accent()),
                             duration_seconds.to_string().color(CliColors::muted())
                         );
-                        println!(
+                        outln!(
                             "{}: {}",
                             "Memory Count".color(CliColors::muted()),
                             memories.len().to_string().color(CliColors::accent())
@@ -85,10 +82,7 @@ pub async fn handle_graph_command(
             }
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&graph).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&graph);
             } else {
                 print_memory_graph(&graph);
             }
@@ -103,10 +97,7 @@ pub async fn handle_graph_command(
                 .await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&paths).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&paths);
             } else {
                 print_paths(&paths);
             }
@@ -213,10 +204,7 @@ pub async fn handle_graph_command(
             }
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&graph).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&graph);
             } else {
                 print_connected_memories_tree(&memory_id, &graph, args.no_temporal).await?;
             }
@@ -354,22 +342,19 @@ pub async fn handle_graph_command(
                         "content_preview": content
                     })).collect::<Vec<_>>()
                 });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else {
-                println!(
+                outln!(
                     "{}",
                     "━━━ Graph Metrics ━━━".color(CliColors::accent()).bold()
                 );
-                println!();
-                println!(
+                outln!();
+                outln!(
                     "{}: {}",
                     "Memories".color(CliColors::muted()),
                     memory_count.to_string().color(CliColors::accent()).bold()
                 );
-                println!(
+                outln!(
                     "{}: {}",
                     "Relationships".color(CliColors::muted()),
                     relationship_count
@@ -377,35 +362,35 @@ pub async fn handle_graph_command(
                         .color(CliColors::accent())
                         .bold()
                 );
-                println!(
+                outln!(
                     "{}: {}",
                     "Entities".color(CliColors::muted()),
                     entity_count.to_string().color(CliColors::accent()).bold()
                 );
-                println!(
+                outln!(
                     "{}: {:.2}",
                     "Average Degree".color(CliColors::muted()),
                     average_degree.to_string().color(CliColors::accent())
                 );
-                println!(
+                outln!(
                     "{}: {:.4}",
                     "Graph Density".color(CliColors::muted()),
                     density.to_string().color(CliColors::accent())
                 );
-                println!(
+                outln!(
                     "{}: {}",
                     "Connected Components".color(CliColors::muted()),
                     connected_components.to_string().color(CliColors::accent())
                 );
 
                 if !central_memories.is_empty() {
-                    println!();
-                    println!(
+                    outln!();
+                    outln!(
                         "{}",
                         "Central Memories (Top 5):".color(CliColors::muted()).bold()
                     );
                     for (i, (memory_id, score, content)) in central_memories.iter().enumerate() {
-                        println!(
+                        outln!(
                             "  {}. {} (score: {:.1}) - {}",
                             (i + 1).to_string().color(CliColors::muted()),
                             memory_id[..8].color(CliColors::accent()),
@@ -435,18 +420,14 @@ pub async fn handle_graph_command(
 
                 if all_memories.is_empty() {
                     if output_format == "json" {
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&json!({
-                                "query": args.pattern,
-                                "results": [],
-                                "total_results": 0,
-                                "message": "No memories found in storage"
-                            }))
-                            .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&json!({
+                            "query": args.pattern,
+                            "results": [],
+                            "total_results": 0,
+                            "message": "No memories found in storage"
+                        }));
                     } else {
-                        println!("{}", format_info("No memories found in storage."));
+                        outln!("{}", format_info("No memories found in storage."));
                     }
                     return Ok(());
                 }
@@ -471,18 +452,14 @@ pub async fn handle_graph_command(
 
                 if all_memories.is_empty() {
                     if output_format == "json" {
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&json!({
-                                "query": args.pattern,
-                                "results": [],
-                                "total_results": 0,
-                                "message": "No memories found in storage"
-                            }))
-                            .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&json!({
+                            "query": args.pattern,
+                            "results": [],
+                            "total_results": 0,
+                            "message": "No memories found in storage"
+                        }));
                     } else {
-                        println!("{}", format_info("No memories found in storage."));
+                        outln!("{}", format_info("No memories found in storage."));
                     }
                     return Ok(());
                 }
@@ -544,12 +521,9 @@ pub async fn handle_graph_command(
                     })).collect::<Vec<_>>(),
                     "total_results": results.len()
                 });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_info(&format!(
                         "Graph Query: \"{}\"",
@@ -557,22 +531,22 @@ pub async fn handle_graph_command(
                     ))
                 );
                 if results.is_empty() {
-                    println!("{}", format_info("No matching graph structures found."));
+                    outln!("{}", format_info("No matching graph structures found."));
                 } else {
-                    println!();
-                    println!("Found {} matching graph structures:", results.len());
-                    println!();
+                    outln!();
+                    outln!("Found {} matching graph structures:", results.len());
+                    outln!();
                     for (i, (center_id, graph)) in results.iter().enumerate() {
-                        println!(
+                        outln!(
                             "Graph {} (Center: {}):",
                             (i + 1).to_string().color(CliColors::muted()),
                             center_id[..8].color(CliColors::accent())
                         );
-                        println!(
+                        outln!(
                             "  Nodes: {} memories",
                             graph.memories.len().to_string().color(CliColors::accent())
                         );
-                        println!(
+                        outln!(
                             "  Edges: {} relationships",
                             graph
                                 .relationships
@@ -581,14 +555,14 @@ pub async fn handle_graph_command(
                                 .color(CliColors::accent())
                         );
                         if !graph.memories.is_empty() {
-                            println!("  Memories:");
+                            outln!("  Memories:");
                             for memory in graph.memories.values().take(3) {
                                 let content = if memory.content.len() > 60 {
                                     format!("{}...", &memory.content[..57])
                                 } else {
                                     memory.content.clone()
                                 };
-                                println!(
+                                outln!(
                                     "    {} [{}] {}",
                                     "●".color(CliColors::accent()),
                                     format_memory_type(&memory.memory_type),
@@ -596,7 +570,7 @@ pub async fn handle_graph_command(
                                 );
                             }
                             if graph.memories.len() > 3 {
-                                println!(
+                                outln!(
                                     "    ... and {} more",
                                     (graph.memories.len() - 3)
                                         .to_string()
@@ -605,7 +579,7 @@ pub async fn handle_graph_command(
                             }
                         }
                         if i < results.len() - 1 {
-                            println!();
+                            outln!();
                         }
                     }
                 }
@@ -659,18 +633,14 @@ pub async fn handle_graph_command(
 
             if candidate_memories.is_empty() {
                 if output_format == "json" {
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&json!({
-                            "pattern_id": args.pattern_id,
-                            "results": [],
-                            "total_results": 0,
-                            "message": "No candidate memories found for comparison"
-                        }))
-                        .unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&json!({
+                        "pattern_id": args.pattern_id,
+                        "results": [],
+                        "total_results": 0,
+                        "message": "No candidate memories found for comparison"
+                    }));
                 } else {
-                    println!(
+                    outln!(
                         "{}",
                         format_info("No candidate memories found for comparison.")
                     );
@@ -756,12 +726,9 @@ pub async fn handle_graph_command(
                         "common_types": types
                     })).collect::<Vec<_>>()
                 });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_info(&format!(
                         "Similar Structures to: {}",
@@ -769,28 +736,46 @@ pub async fn handle_graph_command(
                     ))
                 );
                 if similar_structures.is_empty() {
-                    println!("{}", format_info("No similar structures found."));
+                    outln!("{}", format_info("No similar structures found."));
                 } else {
-                    println!();
+                    outln!();
                     for (i, (memory_id, similarity, nodes, edges, types)) in
                         similar_structures.iter().enumerate()
                     {
-                        println!(
+                        outln!(
                             "{}. {} (Similarity: {:.2})",
                             (i + 1).to_string().color(CliColors::muted()),
                             memory_id[..8].color(CliColors::accent()),
                             similarity
                         );
-                        println!("   Structure: {} memories, {} relationships", nodes, edges);
+                        outln!("   Structure: {} memories, {} relationships", nodes, edges);
                         if !types.is_empty() {
-                            println!("   Types: {}", types.join(", ").color(CliColors::info()));
+                            outln!("   Types: {}", types.join(", ").color(CliColors::info()));
                         }
-                        println!();
+                        outln!();
                     }
                 }
             }
         }
 
+        GraphCommands::Show(args) => {
+            let root_id = if ctx.memory_manager.get_entity(&args.id).await?.is_some() {
+                args.id.clone()
+            } else {
+                resolve_memory_id(ctx, &args.id).await?
+            };
+            let view = collect_graph_view(ctx, &root_id, &args).await?;
+
+            if output_format == "json" {
+                print_json(&view);
+            } else {
+                match args.format {
+                    GraphShowFormat::Tree => print_graph_tree(&view),
+                    GraphShowFormat::Mermaid => outln!("{}", render_graph_mermaid(&view)),
+                }
+            }
+        }
+
         GraphCommands::Entity(args) => {
             // Check if ID exists as either memory or entity
             let is_memory = ctx.memory_manager.get_memory(&args.id).await?.is_some();
@@ -829,19 +814,15 @@ pub async fn handle_graph_command(
                                 "duration_seconds": duration_seconds,
                                 "memory_count": memories.len()
                             });
-                            println!(
-                                "{}",
-                                serde_json::to_string_pretty(&graph_json)
-                                    .unwrap_or_else(|_| "{}".to_string())
-                            );
+                            print_json(&graph_json);
                         } else {
                             print_memory_graph(&graph);
-                            println!();
-                            println!(
+                            outln!();
+                            outln!(
                                 "{}",
                                 "━━━ Temporal Span ━━━".color(CliColors::accent()).bold()
                             );
-                            println!(
+                            outln!(
                                 "{}: {}",
                                 "Start".color(CliColors::muted()),
                                 start
@@ -849,20 +830,20 @@ pub async fn handle_graph_command(
                                     .to_string()
                                     .color(CliColors::primary())
                             );
-                            println!(
+                            outln!(
                                 "{}: {}",
                                 "End".color(CliColors::muted()),
                                 end.format("%Y-%m-%d %H:%M:%S UTC")
                                     .to_string()
                                     .color(CliColors::primary())
                             );
-                            println!(
+                            outln!(
                                 "{}: {} days ({} seconds)",
                                 "Duration".color(CliColors::muted()),
                                 duration_days.to_string().color(CliColors::accent()),
                                 duration_seconds.to_string().color(CliColors::muted())
                             );
-                            println!(
+                            outln!(
                                 "{}: {}",
                                 "Memory Count".color(CliColors::muted()),
                                 memories.len().to_string().color(CliColors::accent())
@@ -873,10 +854,7 @@ pub async fn handle_graph_command(
                 }
 
                 if output_format == "json" {
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&graph).unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&graph);
                 } else {
                     print_memory_graph(&graph);
                 }
@@ -936,25 +914,22 @@ pub async fn handle_graph_command(
                         "relationships": relationships,
                         "memories": memories
                     });
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&result);
                 } else {
-                    println!(
+                    outln!(
                         "{}",
                         format_info(&format!(
                             "Entity Graph: {}",
                             args.id.color(CliColors::accent())
                         ))
                     );
-                    println!();
-                    println!(
+                    outln!();
+                    outln!(
                         "{}: {}",
                         "Memories".color(CliColors::muted()),
                         memories.len().to_string().color(CliColors::accent())
                     );
-                    println!(
+                    outln!(
                         "{}: {}",
                         "Entities".color(CliColors::muted()),
                         related_entities
@@ -962,22 +937,22 @@ pub async fn handle_graph_command(
                             .to_string()
                             .color(CliColors::accent())
                     );
-                    println!(
+                    outln!(
                         "{}: {}",
                         "Relationships".color(CliColors::muted()),
                         relationships.len().to_string().color(CliColors::accent())
                     );
 
                     if !memories.is_empty() {
-                        println!();
-                        println!("{}", "Memories:".color(CliColors::primary()).bold());
+                        outln!();
+                        outln!("{}", "Memories:".color(CliColors::primary()).bold());
                         for memory in memories.iter().take(10) {
                             let content = if memory.content.len() > 60 {
                                 format!("{}...", &memory.content[..57])
                             } else {
                                 memory.content.clone()
                             };
-                            println!(
+                            outln!(
                                 "  {} [{}] {}",
                                 "●".color(CliColors::accent()),
                                 format_memory_type(&memory.memory_type),
@@ -987,10 +962,10 @@ pub async fn handle_graph_command(
                     }
 
                     if !related_entities.is_empty() {
-                        println!();
-                        println!("{}", "Related Entities:".color(CliColors::primary()).bold());
+                        outln!();
+                        outln!("{}", "Related Entities:".color(CliColors::primary()).bold());
                         for entity in related_entities.iter().take(10) {
-                            println!(
+                            outln!(
                                 "  {} {} ({})",
                                 "◇".color(CliColors::entity()),
                                 entity.entity_type.color(CliColors::entity()),
@@ -1005,3 +980,86 @@ pub async fn handle_graph_command(
 
     Ok(())
 }
+
+/// Walk out from `root_id` through memories and entities, following the
+/// relationship types selected by `args` in both directions
+async fn collect_graph_view(
+    ctx: &LocaiCliContext,
+    root_id: &str,
+    args: &GraphShowArgs,
+) -> locai::Result<GraphView> {
+    let mut view = GraphView {
+        root_id: root_id.to_string(),
+        ..Default::default()
+    };
+    let Some(root) = resolve_view_node(ctx, root_id).await? else {
+        return Ok(view);
+    };
+    view.nodes.insert(root_id.to_string(), root);
+
+    let mut seen_relationships = HashSet::new();
+    let mut queue = VecDeque::from([(root_id.to_string(), 0u8)]);
+    while let Some((current_id, current_depth)) = queue.pop_front() {
+        if current_depth >= args.depth {
+            continue;
+        }
+
+        for filter in [
+            RelationshipFilter {
+                source_id: Some(current_id.clone()),
+                ..Default::default()
+            },
+            RelationshipFilter {
+                target_id: Some(current_id.clone()),
+                ..Default::default()
+            },
+        ] {
+            let relationships = ctx
+                .memory_manager
+                .list_relationships(Some(filter), Some(100), None)
+                .await?;
+            for rel in relationships {
+                if !args.types.is_empty() && !args.types.contains(&rel.relationship_type) {
+                    continue;
+                }
+                if args.no_temporal && rel.relationship_type == "temporal_sequence" {
+                    continue;
+                }
+                if !seen_relationships.insert(rel.id.clone()) {
+                    continue;
+                }
+
+                let other_id = if rel.source_id == current_id {
+                    rel.target_id.clone()
+                } else {
+                    rel.source_id.clone()
+                };
+                if !view.nodes.contains_key(&other_id) {
+                    let Some(node) = resolve_view_node(ctx, &other_id).await? else {
+                        continue;
+                    };
+                    view.nodes.insert(other_id.clone(), node);
+                    queue.push_back((other_id, current_depth + 1));
+                }
+                view.relationships.push(rel);
+            }
+        }
+    }
+
+    Ok(view)
+}
+
+/// The memory or entity with `id` as a graph node, if either exists
+pub(crate) async fn resolve_view_node(
+    ctx: &LocaiCliContext,
+    id: &str,
+) -> locai::Result<Option<GraphViewNode>> {
+    if let Some(memory) = ctx.memory_manager.get_memory(id).await? {
+        return Ok(Some(GraphViewNode::from_memory(&memory)));
+    }
+    Ok(ctx
+        .memory_manager
+        .get_entity(id)
+        .await?
+        .map(|entity| GraphViewNode::from_entity(&entity)))
+}
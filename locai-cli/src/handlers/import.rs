@@ -0,0 +1,212 @@
+//! Import command handlers
+
+use crate::args::{ImportFileArgs, ImportVaultArgs};
+use crate::commands::ImportCommands;
+use crate::context::LocaiCliContext;
+use crate::import::obsidian::{self, VaultImportSummary};
+use crate::import::{self, ImportSummary, ImportedData, chatgpt, claude, mem0, zep};
+use crate::output::*;
+use colored::Colorize;
+
+pub async fn handle_import_command(
+    cmd: ImportCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let (source, args, mut conversations) = match cmd {
+        ImportCommands::ChatGpt(args) => {
+            let json = import::read_export_file(
+                &crate::invocation::resolve(&args.path),
+                chatgpt::CONVERSATIONS_FILE,
+            )?;
+            ("chatgpt", args, chatgpt::parse_conversations(&json)?)
+        }
+        ImportCommands::Claude(args) => {
+            let json = import::read_export_file(
+                &crate::invocation::resolve(&args.path),
+                claude::CONVERSATIONS_FILE,
+            )?;
+            ("claude", args, claude::parse_conversations(&json)?)
+        }
+        ImportCommands::Mem0(args) => {
+            return handle_data_import("mem0", args, mem0::parse_export, ctx, output_format).await;
+        }
+        ImportCommands::Zep(args) => {
+            return handle_data_import("zep", args, zep::parse_export, ctx, output_format).await;
+        }
+        ImportCommands::Obsidian(args) => {
+            return handle_vault_import(args, ctx, output_format).await;
+        }
+    };
+
+    if let Some(limit) = args.limit {
+        conversations.truncate(limit);
+    }
+
+    let summary = if args.dry_run {
+        ImportSummary {
+            source: source.to_string(),
+            conversations_imported: conversations.len(),
+            messages_imported: conversations.iter().map(|c| c.messages.len()).sum(),
+            ..Default::default()
+        }
+    } else {
+        import::import_conversations(&ctx.memory_manager, source, conversations).await?
+    };
+
+    print_summary(&summary, args.dry_run, output_format);
+    Ok(())
+}
+
+async fn handle_data_import(
+    source: &str,
+    args: ImportFileArgs,
+    parse: fn(&str) -> locai::Result<ImportedData>,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let json = std::fs::read_to_string(crate::invocation::resolve(&args.path))
+        .map_err(|e| locai::LocaiError::Other(format!("Failed to read {}: {}", args.path, e)))?;
+    let data = parse(&json)?;
+
+    let summary = if args.dry_run {
+        ImportSummary {
+            source: source.to_string(),
+            conversations_imported: data.conversations.len(),
+            messages_imported: data.conversations.iter().map(|c| c.messages.len()).sum(),
+            memories_imported: data.memories.len(),
+            relationships_imported: data.relations.len(),
+            ..Default::default()
+        }
+    } else {
+        import::import_data(&ctx.memory_manager, source, data).await?
+    };
+
+    print_summary(&summary, args.dry_run, output_format);
+    Ok(())
+}
+
+fn print_summary(summary: &ImportSummary, dry_run: bool, output_format: &str) {
+    if output_format == "json" {
+        print_json(summary);
+        return;
+    }
+
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    let has_conversations = summary.conversations_imported + summary.conversations_skipped > 0;
+    let has_memories =
+        summary.memories_imported + summary.memories_skipped + summary.relationships_imported > 0;
+
+    if has_conversations || !has_memories {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "{} {} messages from {} conversations",
+                verb, summary.messages_imported, summary.conversations_imported
+            ))
+        );
+    }
+    if has_memories {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "{} {} memories and {} relationships",
+                verb, summary.memories_imported, summary.relationships_imported
+            ))
+        );
+    }
+    if summary.conversations_skipped > 0 {
+        outln!(
+            "{}",
+            format_info(&format!(
+                "Skipped {} conversations that were already imported",
+                summary
+                    .conversations_skipped
+                    .to_string()
+                    .color(CliColors::accent())
+            ))
+        );
+    }
+    if summary.memories_skipped > 0 {
+        outln!(
+            "{}",
+            format_info(&format!(
+                "Skipped {} memories that were already imported",
+                summary
+                    .memories_skipped
+                    .to_string()
+                    .color(CliColors::accent())
+            ))
+        );
+    }
+}
+
+async fn handle_vault_import(
+    args: ImportVaultArgs,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let vault_dir = crate::invocation::resolve(&args.vault_dir);
+    let vault = match args.name {
+        Some(name) => name,
+        None => vault_dir
+            .canonicalize()
+            .ok()
+            .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "vault".to_string()),
+    };
+
+    let notes = obsidian::scan_vault(&vault_dir)?;
+    let summary = if args.dry_run {
+        VaultImportSummary {
+            vault,
+            notes_created: notes.len(),
+            ..Default::default()
+        }
+    } else {
+        obsidian::import_vault(&ctx.memory_manager, &vault, notes, args.prune).await?
+    };
+
+    if output_format == "json" {
+        print_json(&summary);
+        return Ok(());
+    }
+
+    if args.dry_run {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Found {} notes in vault '{}'",
+                summary.notes_created, summary.vault
+            ))
+        );
+        return Ok(());
+    }
+
+    outln!(
+        "{}",
+        format_success(&format!(
+            "Imported vault '{}': {} new, {} updated, {} unchanged",
+            summary.vault, summary.notes_created, summary.notes_updated, summary.notes_unchanged
+        ))
+    );
+    if summary.notes_removed > 0 {
+        outln!(
+            "{}",
+            format_info(&format!(
+                "Removed {} notes no longer in the vault",
+                summary.notes_removed.to_string().color(CliColors::accent())
+            ))
+        );
+    }
+    outln!(
+        "{}",
+        format_info(&format!(
+            "Created {} links ({} unresolved)",
+            summary.links_created.to_string().color(CliColors::accent()),
+            summary.unresolved_links
+        ))
+    );
+
+    Ok(())
+}
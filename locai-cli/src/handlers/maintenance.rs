@@ -0,0 +1,174 @@
+//! Storage maintenance handlers: checksum verification and repair
+
+use crate::args::RepairStorageArgs;
+use crate::commands::MaintenanceCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::*;
+use locai::LocaiError;
+use locai::core::{repair_storage, verify_storage};
+use locai::storage::models::{ChecksumReport, ChecksummedRecord, SnapshotBundle};
+use serde_json::json;
+use std::io::BufRead;
+
+pub async fn handle_maintenance_command(
+    cmd: MaintenanceCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        MaintenanceCommands::Verify => verify(ctx, output_format).await,
+        MaintenanceCommands::Repair(args) => repair(args, ctx, output_format).await,
+    }
+}
+
+async fn verify(ctx: &LocaiCliContext, output_format: &str) -> locai::Result<()> {
+    let report = verify_storage(&ctx.memory_manager).await?;
+
+    if output_format == "json" {
+        print_json(&report);
+    } else {
+        print_checksum_report(&report);
+        if !report.is_intact() {
+            outln!(
+                "{}",
+                format_info("Run `maintenance repair` to restore these records.")
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn repair(
+    args: RepairStorageArgs,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let backup = match &args.backup {
+        Some(path) => {
+            let json = std::fs::read(crate::invocation::resolve(path))
+                .map_err(|e| LocaiError::Other(format!("Failed to read {}: {}", path, e)))?;
+            let bundle: SnapshotBundle = serde_json::from_slice(&json).map_err(|e| {
+                LocaiError::Other(format!("Failed to parse snapshot bundle: {}", e))
+            })?;
+            Some(bundle)
+        }
+        None => None,
+    };
+
+    let report = verify_storage(&ctx.memory_manager).await?;
+    let backfill = args.backfill && report.unchecksummed > 0;
+
+    if output_format == "json" {
+        // Prompting would corrupt the JSON on stdout, so require --yes instead
+        if !args.yes && (!report.is_intact() || backfill) {
+            return Err(LocaiError::Other(
+                "Pass --yes to repair when using JSON output".to_string(),
+            ));
+        }
+    } else {
+        print_checksum_report(&report);
+    }
+
+    if report.is_intact() && !backfill {
+        if output_format == "json" {
+            print_json(&json!({ "verification": report, "repair": null }));
+        }
+        return Ok(());
+    }
+
+    if !args.yes {
+        outln!();
+        if backfill {
+            outln!(
+                "Type 'yes' to repair {} records and checksum {} others:",
+                report.issues.len(),
+                report.unchecksummed
+            );
+        } else {
+            outln!("Type 'yes' to repair {} records:", report.issues.len());
+        }
+        let mut input = String::new();
+        crate::invocation::stdin()
+            .read_line(&mut input)
+            .map_err(|e| LocaiError::Other(format!("Failed to read input: {}", e)))?;
+        if input.trim() != "yes" {
+            outln!("{}", format_info("Repair cancelled."));
+            return Ok(());
+        }
+    }
+
+    let repair = repair_storage(&ctx.memory_manager, &report, backup.as_ref(), backfill).await?;
+
+    if output_format == "json" {
+        print_json(&json!({ "verification": report, "repair": repair }));
+    } else {
+        for detail in &repair.details {
+            outln!("  {}", detail.color(CliColors::muted()));
+        }
+        let summary = format!(
+            "Restored {} memories from versions and {} records from backup, rebuilt {} vectors.",
+            repair.restored_from_versions, repair.restored_from_backup, repair.vectors_rebuilt
+        );
+        if repair.failed > 0 {
+            outln!(
+                "{}",
+                format_warning(&format!(
+                    "{} {} records could not be repaired.",
+                    summary, repair.failed
+                ))
+            );
+        } else {
+            outln!("{}", format_success(&summary));
+        }
+    }
+    Ok(())
+}
+
+fn print_checksum_report(report: &ChecksumReport) {
+    let checked = format!(
+        "{} memories and {} vectors checked",
+        report.memories_checked, report.vectors_checked
+    );
+    if report.is_intact() {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Storage integrity: {}, no corruption found",
+                checked
+            ))
+        );
+    } else {
+        outln!(
+            "{}",
+            format_warning(&format!(
+                "Storage integrity: {}, {} corrupted",
+                checked,
+                report.issues.len()
+            ))
+        );
+        outln!();
+        for issue in &report.issues {
+            let record = match issue.record {
+                ChecksummedRecord::Memory => "memory",
+                ChecksummedRecord::Vector => "vector",
+            };
+            outln!(
+                "  {} {} {}",
+                issue.id.color(CliColors::accent()),
+                format!("[{}]", record).color(CliColors::muted()),
+                issue.description.color(CliColors::error())
+            );
+        }
+    }
+
+    if report.unchecksummed > 0 {
+        outln!(
+            "{}",
+            format_info(&format!(
+                "{} records predate checksums and were not checked. Run `maintenance repair --backfill` to add them.",
+                report.unchecksummed
+            ))
+        );
+    }
+}
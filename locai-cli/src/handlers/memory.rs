@@ -4,12 +4,17 @@ use crate::args::*;
 use crate::commands::MemoryCommands;
 use crate::context::LocaiCliContext;
 use crate::output::*;
+use crate::tabular::{Table, TableFormat, memory_cells, memory_table};
 use crate::utils::*;
+use chrono::Utc;
 use colored::Colorize;
 use locai::LocaiError;
 use locai::memory::search_extensions::SearchMode;
+use locai::memory::{GLOBAL_PIN_SCOPE, Recurrence, Reminder, parse_delay, scope_to_members};
+use locai::models::{GeoPoint, GeoRadius};
+use locai::storage::filter_expr::parse_filter_expression;
 use locai::storage::filters::{MemoryFilter, RelationshipFilter, SemanticSearchFilter};
-use locai::storage::models::Relationship;
+use locai::storage::models::{FilterExplanation, Relationship};
 use reqwest;
 use serde_json::{Value, json};
 
@@ -104,6 +109,44 @@ fn generate_mock_query_embedding(query: &str, dimensions: usize) -> Vec<f32> {
     embedding
 }
 
+/// Parse a `LAT,LON` location
+fn parse_location(value: &str) -> locai::Result<GeoPoint> {
+    GeoPoint::parse(value).ok_or_else(|| {
+        LocaiError::Other(format!(
+            "Invalid location '{}': expected LAT,LON in degrees, such as 51.5007,-0.1246",
+            value
+        ))
+    })
+}
+
+/// A distance in meters, switching to kilometers from 1 km
+fn format_distance(meters: f64) -> String {
+    if meters < 1000.0 {
+        format!("{:.0} m", meters)
+    } else {
+        format!("{:.1} km", meters / 1000.0)
+    }
+}
+
+/// Parse a `KEY=VALUE` property filter; VALUE is read as JSON, falling back to a string
+fn parse_property_filter(property: &str) -> locai::Result<(String, Value)> {
+    let (key, value) = property.split_once('=').ok_or_else(|| {
+        LocaiError::Other(format!(
+            "Invalid property filter '{}': expected KEY=VALUE",
+            property
+        ))
+    })?;
+    let key = key.trim().trim_start_matches("properties.");
+    if key.is_empty() {
+        return Err(LocaiError::Other(format!(
+            "Invalid property filter '{}': missing key",
+            property
+        )));
+    }
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+    Ok((key.to_string(), value))
+}
+
 pub async fn handle_memory_command(
     cmd: MemoryCommands,
     ctx: &LocaiCliContext,
@@ -113,6 +156,7 @@ pub async fn handle_memory_command(
         MemoryCommands::Add(args) => {
             let memory_type = parse_memory_type(&args.memory_type)?;
             let priority = parse_priority(&args.priority)?;
+            let location = args.location.as_deref().map(parse_location).transpose()?;
 
             let memory_id = ctx
                 .memory_manager
@@ -121,40 +165,96 @@ pub async fn handle_memory_command(
                     for tag in args.tags {
                         b = b.tag(tag);
                     }
+                    if let Some(location) = location {
+                        b = b.location(location);
+                    }
                     b
                 })
                 .await?;
 
             if output_format == "json" {
                 let result = json!({ "memory_id": memory_id });
-                println!(
+                print_json(&result);
+            } else {
+                outln!(
                     "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
+                    format_success(&format!(
+                        "Memory created with ID: {}",
+                        memory_id.color(CliColors::accent()).bold()
+                    ))
                 );
+            }
+        }
+
+        MemoryCommands::New(args) => {
+            let vars = args
+                .vars
+                .iter()
+                .map(|var| parse_template_var(var))
+                .collect::<locai::Result<Vec<_>>>()?;
+            let memory_id = ctx
+                .memory_manager
+                .remember_from_template(&args.template, vars)
+                .await?;
+
+            if output_format == "json" {
+                let result = json!({ "memory_id": memory_id, "template": args.template });
+                print_json(&result);
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_success(&format!(
-                        "Memory created with ID: {}",
+                        "Memory created from template '{}' with ID: {}",
+                        args.template,
                         memory_id.color(CliColors::accent()).bold()
                     ))
                 );
             }
         }
 
+        MemoryCommands::Templates => {
+            let templates = ctx.memory_manager.list_templates().await?;
+
+            if output_format == "json" {
+                print_json(&templates);
+            } else {
+                for template in templates {
+                    outln!(
+                        "{} {}",
+                        template.name.color(CliColors::accent()).bold(),
+                        template
+                            .description
+                            .as_deref()
+                            .unwrap_or_default()
+                            .color(CliColors::muted())
+                    );
+                    for placeholder in &template.placeholders {
+                        outln!(
+                            "  --var {}=<{}>{}",
+                            placeholder.name,
+                            placeholder.kind,
+                            if placeholder.required {
+                                ""
+                            } else {
+                                " (optional)"
+                            }
+                        );
+                    }
+                    outln!();
+                }
+            }
+        }
+
         MemoryCommands::Get(args) => match ctx.memory_manager.get_memory(&args.id).await? {
             Some(memory) => {
                 if output_format == "json" {
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&memory).unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&memory);
                 } else {
                     print_memory(&memory);
                 }
             }
             None => {
-                println!(
+                outln!(
                     "{}",
                     format_warning(&format!(
                         "Memory with ID '{}' not found.",
@@ -165,6 +265,21 @@ pub async fn handle_memory_command(
         },
 
         MemoryCommands::Search(args) => {
+            let args = if args.interactive {
+                // Prompt on stderr so stdout only carries the results
+                crate::search_builder::prompt_search_args(
+                    &mut crate::invocation::stdin(),
+                    &mut crate::invocation::stderr(),
+                    args,
+                )?
+            } else {
+                args
+            };
+            let equivalent_command = args
+                .interactive
+                .then(|| crate::search_builder::equivalent_command(&args));
+            let query = args.query.clone().unwrap_or_default();
+
             // Parse requested mode
             let requested_mode = match args.mode.as_str() {
                 "vector" => SearchMode::Vector,
@@ -200,22 +315,74 @@ pub async fn handle_memory_command(
                     .with_timezone(&chrono::Utc);
                 mem_filter.created_before = Some(created_before);
             }
+            if !args.properties.is_empty() {
+                mem_filter.properties = Some(
+                    args.properties
+                        .iter()
+                        .map(|property| parse_property_filter(property))
+                        .collect::<locai::Result<_>>()?,
+                );
+            }
+            if let Some(expression) = &args.filter {
+                mem_filter.conditions = Some(parse_filter_expression(expression, Utc::now())?);
+            }
+            if let Some(near) = &args.near {
+                let near = GeoRadius::new(parse_location(near)?, args.radius);
+                near.validate()?;
+                mem_filter.near = Some(near);
+            }
 
             // Check if filter has any non-default values
             let has_filters = mem_filter.memory_type.is_some()
                 || mem_filter.tags.is_some()
                 || mem_filter.created_after.is_some()
-                || mem_filter.created_before.is_some();
+                || mem_filter.created_before.is_some()
+                || mem_filter.properties.is_some()
+                || mem_filter.conditions.is_some()
+                || mem_filter.near.is_some();
+
+            let explanation = if args.explain_plan {
+                Some(
+                    ctx.memory_manager
+                        .explain_memory_filter(&mem_filter)
+                        .await?,
+                )
+            } else {
+                None
+            };
 
-            let filter = if args.threshold.is_some() || has_filters {
+            let filter = if args.threshold.is_some() || has_filters || args.include_archived {
                 Some(SemanticSearchFilter {
                     similarity_threshold: args.threshold,
                     memory_filter: if has_filters { Some(mem_filter) } else { None },
+                    include_archived: args.include_archived,
                 })
             } else {
                 None
             };
 
+            // Scope to a collection's members. Members can rank below other
+            // matches, so fetch at least as many results as there are members.
+            let mut member_count = 0;
+            let filter = match &args.collection {
+                Some(name) => {
+                    let collection =
+                        ctx.memory_manager
+                            .get_collection(name)
+                            .await?
+                            .ok_or_else(|| {
+                                LocaiError::Collection(format!("Collection '{}' not found", name))
+                            })?;
+                    let members = ctx
+                        .memory_manager
+                        .collection_member_ids(&collection)
+                        .await?;
+                    member_count = members.len();
+                    Some(scope_to_members(filter, members))
+                }
+                None => filter,
+            };
+
             // Check if embeddings are available (Ollama configured)
             let has_ollama =
                 std::env::var("OLLAMA_URL").is_ok() && std::env::var("OLLAMA_MODEL").is_ok();
@@ -248,8 +415,8 @@ pub async fn handle_memory_command(
                 let text_results = match ctx
                     .memory_manager
                     .search(
-                        &args.query,
-                        Some(args.limit * 2),
+                        &query,
+                        Some((args.limit * 2).max(member_count)),
                         filter.clone(),
                         SearchMode::Text,
                     )
@@ -262,13 +429,13 @@ pub async fn handle_memory_command(
                     }
                 };
 
-                let query_embedding = generate_query_embedding(&args.query, 1024).await;
+                let query_embedding = generate_query_embedding(&query, 1024).await;
                 let semantic_results = match ctx
                     .memory_manager
                     .search_with_embedding(
-                        &args.query,
+                        &query,
                         Some(&query_embedding),
-                        Some(args.limit * 2),
+                        Some((args.limit * 2).max(member_count)),
                         filter,
                         SearchMode::Vector,
                     )
@@ -336,24 +503,30 @@ pub async fn handle_memory_command(
             } else {
                 // Single mode search (text or semantic)
                 let results = if matches!(search_mode, SearchMode::Vector) {
-                    let query_embedding = generate_query_embedding(&args.query, 1024).await;
+                    let query_embedding = generate_query_embedding(&query, 1024).await;
                     ctx.memory_manager
                         .search_with_embedding(
-                            &args.query,
+                            &query,
                             Some(&query_embedding),
-                            Some(args.limit),
+                            Some(args.limit.max(member_count)),
                             filter,
                             search_mode,
                         )
                         .await?
                 } else {
                     ctx.memory_manager
-                        .search(&args.query, Some(args.limit), filter, search_mode)
+                        .search(
+                            &query,
+                            Some(args.limit.max(member_count)),
+                            filter,
+                            search_mode,
+                        )
                         .await?
                 };
 
                 results
                     .into_iter()
+                    .take(args.limit)
                     .map(|r| TaggedResult {
                         memory: r.memory,
                         score: r.score,
@@ -369,6 +542,29 @@ pub async fn handle_memory_command(
                     .collect()
             };
 
+            // Pinned memories lead, whatever their score
+            let tagged_results: Vec<TaggedResult> = match args.pin_scope.as_deref() {
+                Some(scope) => {
+                    let pinned = ctx.memory_manager.pinned_memories(Some(scope)).await?;
+                    let pinned_ids: std::collections::HashSet<String> =
+                        pinned.iter().map(|memory| memory.id.clone()).collect();
+                    pinned
+                        .into_iter()
+                        .map(|memory| TaggedResult {
+                            memory,
+                            score: None,
+                            tags: vec!["pinned".to_string()],
+                        })
+                        .chain(
+                            tagged_results
+                                .into_iter()
+                                .filter(|tr| !pinned_ids.contains(&tr.memory.id)),
+                        )
+                        .collect()
+                }
+                None => tagged_results,
+            };
+
             // Convert tagged results to regular results for JSON output
             let results: Vec<locai::storage::models::SearchResult> = tagged_results
                 .iter()
@@ -396,55 +592,70 @@ pub async fn handle_memory_command(
                         })
                     })
                     .collect();
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&json_results)
-                        .unwrap_or_else(|_| "[]".to_string())
-                );
+                match &explanation {
+                    Some(explanation) => {
+                        print_json(&json!({ "results": json_results, "explain": explanation }))
+                    }
+                    None => print_json(&json_results),
+                }
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                // Search columns first, then the same columns as memory lists
+                let mut table = Table::new(&[
+                    "Score", "Match", "ID", "Type", "Priority", "Tags", "Created", "Content",
+                ]);
+                for tr in &tagged_results {
+                    let mut row = vec![
+                        tr.score.map(|s| format!("{:.3}", s)).unwrap_or_default(),
+                        tr.tags.join("+"),
+                    ];
+                    row.extend(memory_cells(&tr.memory));
+                    table.push_row(row);
+                }
+                table.print(format);
             } else if results.is_empty() {
-                println!(
+                outln!(
                     "{}",
                     format_info(&format!(
                         "No memories found matching '{}'",
-                        args.query.color(CliColors::accent())
+                        query.color(CliColors::accent())
                     ))
                 );
 
                 // Provide helpful suggestions
                 if use_hybrid_tagging {
-                    println!();
-                    println!("{}", "💡 Tips:".bold());
-                    println!("  • Hybrid search combines text and semantic search automatically");
-                    println!("  • Text search finds exact keyword matches");
-                    println!("  • Semantic search finds related concepts (requires embeddings)");
+                    outln!();
+                    outln!("{}", "💡 Tips:".bold());
+                    outln!("  • Hybrid search combines text and semantic search automatically");
+                    outln!("  • Text search finds exact keyword matches");
+                    outln!("  • Semantic search finds related concepts (requires embeddings)");
                     if !has_ollama {
-                        println!(
+                        outln!(
                             "  • {} Semantic search unavailable - set OLLAMA_URL and OLLAMA_MODEL to enable",
                             "⚠️".color(CliColors::warning())
                         );
                     }
-                    println!("  • Try searching for different keywords or related concepts");
+                    outln!("  • Try searching for different keywords or related concepts");
                 } else if search_mode == SearchMode::Vector {
-                    println!();
-                    println!("{}", "💡 Tips for semantic search:".bold());
-                    println!("  • Semantic search only finds memories with embeddings");
+                    outln!();
+                    outln!("{}", "💡 Tips for semantic search:".bold());
+                    outln!("  • Semantic search only finds memories with embeddings");
                     if has_ollama {
-                        println!("  • Using Ollama for query embeddings");
+                        outln!("  • Using Ollama for query embeddings");
                     } else {
-                        println!(
+                        outln!(
                             "  • {} Using mock query embeddings - these don't capture semantic meaning!",
                             "⚠️".color(CliColors::warning())
                         );
-                        println!("  • Set OLLAMA_URL and OLLAMA_MODEL for real semantic search");
+                        outln!("  • Set OLLAMA_URL and OLLAMA_MODEL for real semantic search");
                     }
                 } else {
-                    println!();
-                    println!("{}", "💡 Tips:".bold());
-                    println!(
+                    outln!();
+                    outln!("{}", "💡 Tips:".bold());
+                    outln!(
                         "  • BM25 search looks for exact words - try searching for words that appear in your memories"
                     );
                     if has_ollama {
-                        println!(
+                        outln!(
                             "  • Use {} for semantic search or {} for hybrid (default)",
                             "--mode semantic".color(CliColors::accent()),
                             "--mode hybrid".color(CliColors::accent())
@@ -461,11 +672,11 @@ pub async fn handle_memory_command(
                     "[text]"
                 };
 
-                println!(
+                outln!(
                     "{} {} (query: {})",
                     format_info(&format!("Found {} memories:", results.len())),
                     mode_info.color(CliColors::muted()),
-                    args.query.color(CliColors::accent()).italic()
+                    query.color(CliColors::accent()).italic()
                 );
 
                 // Warn if using mock embeddings with semantic search
@@ -475,8 +686,8 @@ pub async fn handle_memory_command(
                             / tagged_results.len().max(1) as f32;
 
                     if avg_score < 0.1 && !tagged_results.is_empty() {
-                        println!();
-                        println!(
+                        outln!();
+                        outln!(
                             "{}",
                             format!(
                                 "⚠️  Warning: Very low similarity scores detected ({:.2} average)",
@@ -484,25 +695,25 @@ pub async fn handle_memory_command(
                             )
                             .color(CliColors::warning())
                         );
-                        println!(
+                        outln!(
                             "  This likely means you're using mock query embeddings with real stored embeddings."
                         );
-                        println!("  Set OLLAMA_URL and OLLAMA_MODEL for real semantic search.");
-                        println!();
+                        outln!("  Set OLLAMA_URL and OLLAMA_MODEL for real semantic search.");
+                        outln!();
                     }
                 }
 
                 // Show note if hybrid fell back to text-only
                 if requested_mode == SearchMode::Hybrid && !has_ollama {
-                    println!();
-                    println!(
+                    outln!();
+                    outln!(
                         "{}",
                         format_info(
                             "ℹ️  Hybrid search requested but semantic search unavailable (no embeddings). Using text search only."
                         )
                     );
-                    println!("  Set OLLAMA_URL and OLLAMA_MODEL to enable semantic search.");
-                    println!();
+                    outln!("  Set OLLAMA_URL and OLLAMA_MODEL to enable semantic search.");
+                    outln!();
                 }
 
                 // Display results with tags
@@ -525,7 +736,7 @@ pub async fn handle_memory_command(
                         "[text]".to_string()
                     };
 
-                    println!(
+                    outln!(
                         "{}. {} {} {}",
                         format!("{}", i + 1).color(CliColors::muted()),
                         tag_str.color(if tagged_result.tags.contains(&"semantic".to_string()) {
@@ -538,17 +749,82 @@ pub async fn handle_memory_command(
                     );
                 }
             }
+
+            if output_format != "json"
+                && let Some(explanation) = &explanation
+            {
+                print_filter_explanation(explanation);
+            }
+
+            if let Some(command) = equivalent_command {
+                if output_format == "json" {
+                    errln!("Equivalent command: {}", command);
+                } else {
+                    outln!();
+                    outln!("{}", format_info("Equivalent command:"));
+                    outln!("  {}", command.color(CliColors::accent()));
+                }
+            }
+        }
+
+        MemoryCommands::Near(args) => {
+            let center = parse_location(&args.location)?;
+            let nearby = ctx
+                .memory_manager
+                .search_near(center, args.radius, Some(args.limit))
+                .await?;
+
+            if output_format == "json" {
+                print_json(&nearby);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                let mut table = Table::new(&[
+                    "Distance", "ID", "Type", "Priority", "Tags", "Created", "Content",
+                ]);
+                for found in &nearby {
+                    let mut row = vec![format_distance(found.distance_meters)];
+                    row.extend(memory_cells(&found.memory));
+                    table.push_row(row);
+                }
+                table.print(format);
+            } else if nearby.is_empty() {
+                outln!(
+                    "{}",
+                    format_info(&format!(
+                        "No memories within {} of {}",
+                        format_distance(args.radius),
+                        args.location.color(CliColors::accent())
+                    ))
+                );
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!(
+                        "Found {} memories within {}:",
+                        nearby.len(),
+                        format_distance(args.radius)
+                    ))
+                );
+                for (i, found) in nearby.iter().enumerate() {
+                    outln!(
+                        "{}. {} {}",
+                        format!("{}", i + 1).color(CliColors::muted()),
+                        format!("[{}]", format_distance(found.distance_meters))
+                            .color(CliColors::info()),
+                        found.memory.content
+                    );
+                }
+            }
         }
 
         MemoryCommands::Delete(args) => match ctx.memory_manager.delete_memory(&args.id).await? {
-            true => println!(
+            true => outln!(
                 "{}",
                 format_success(&format!(
                     "Memory '{}' deleted successfully.",
                     args.id.color(CliColors::accent())
                 ))
             ),
-            false => println!(
+            false => outln!(
                 "{}",
                 format_warning(&format!(
                     "Memory '{}' not found or could not be deleted.",
@@ -568,16 +844,19 @@ pub async fn handle_memory_command(
                 filter.tags = Some(vec![tag]);
             }
 
+            if let Some(expression) = &args.filter {
+                filter.conditions = Some(parse_filter_expression(expression, Utc::now())?);
+            }
+
             let memories = ctx
                 .memory_manager
                 .filter_memories(filter, None, None, Some(args.limit))
                 .await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&memories).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&memories);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                memory_table(&memories).print(format);
             } else {
                 print_memory_list(&memories);
             }
@@ -585,8 +864,174 @@ pub async fn handle_memory_command(
 
         MemoryCommands::Tag(args) => {
             match ctx.memory_manager.tag_memory(&args.id, &args.tag).await? {
-                true => println!("Tag '{}' added to memory '{}'.", args.tag, args.id),
-                false => println!("Failed to add tag or memory not found."),
+                true => outln!("Tag '{}' added to memory '{}'.", args.tag, args.id),
+                false => outln!("Failed to add tag or memory not found."),
+            }
+        }
+
+        MemoryCommands::Pin(args) => {
+            let scope = args.scope.as_deref().unwrap_or(GLOBAL_PIN_SCOPE);
+            let pinned = ctx.memory_manager.pin_in_scope(scope, &args.id).await?;
+            if output_format == "json" {
+                print_json(&json!({ "id": args.id, "scope": scope, "pinned": pinned }));
+            } else if pinned {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Memory '{}' pinned in scope '{}'.",
+                        args.id.color(CliColors::accent()),
+                        scope
+                    ))
+                );
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!(
+                        "Memory '{}' was already pinned in scope '{}'.",
+                        args.id, scope
+                    ))
+                );
+            }
+        }
+
+        MemoryCommands::Unpin(args) => {
+            let scope = args.scope.as_deref().unwrap_or(GLOBAL_PIN_SCOPE);
+            let unpinned = ctx.memory_manager.unpin_in_scope(scope, &args.id).await?;
+            if output_format == "json" {
+                print_json(&json!({ "id": args.id, "scope": scope, "unpinned": unpinned }));
+            } else if unpinned {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Memory '{}' unpinned from scope '{}'.",
+                        args.id.color(CliColors::accent()),
+                        scope
+                    ))
+                );
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!(
+                        "Memory '{}' isn't pinned in scope '{}'.",
+                        args.id, scope
+                    ))
+                );
+            }
+        }
+
+        MemoryCommands::Pinned(args) => {
+            let memories = ctx
+                .memory_manager
+                .pinned_memories(args.scope.as_deref())
+                .await?;
+
+            if output_format == "json" {
+                print_json(&memories);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                memory_table(&memories).print(format);
+            } else {
+                print_memory_list(&memories);
+            }
+        }
+
+        MemoryCommands::Remind(args) if args.cancel => {
+            let cancelled = ctx.memory_manager.cancel_reminder(&args.id).await?;
+            if output_format == "json" {
+                print_json(&json!({ "id": args.id, "cancelled": cancelled }));
+            } else if cancelled {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Reminder on memory '{}' cancelled.",
+                        args.id.color(CliColors::accent())
+                    ))
+                );
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!("Memory '{}' has no reminder.", args.id))
+                );
+            }
+        }
+
+        MemoryCommands::Remind(args) => {
+            let at = parse_when(args.at.as_deref().unwrap_or_default(), Utc::now())?;
+            let mut reminder = Reminder::new(&args.id, at);
+            if let Some(every) = &args.every {
+                reminder = reminder.with_recurrence(every.parse::<Recurrence>()?);
+            }
+            let reminder = ctx.memory_manager.set_reminder(reminder).await?;
+
+            if output_format == "json" {
+                print_json(&reminder);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Reminder on memory '{}' set for {}{}.",
+                        args.id.color(CliColors::accent()),
+                        reminder.remind_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                        reminder
+                            .recurrence
+                            .map(|recurrence| format!(", repeating {}", recurrence))
+                            .unwrap_or_default()
+                    ))
+                );
+            }
+        }
+
+        MemoryCommands::Snooze(args) => {
+            let delay = chrono::Duration::from_std(parse_delay(&args.delay)?)
+                .map_err(|_| LocaiError::Reminder("Snooze delay is too long".to_string()))?;
+            let reminder = ctx
+                .memory_manager
+                .snooze_reminder(&args.id, Utc::now() + delay)
+                .await?
+                .ok_or_else(|| {
+                    LocaiError::Reminder(format!("Memory '{}' has no reminder", args.id))
+                })?;
+
+            if output_format == "json" {
+                print_json(&reminder);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Reminder on memory '{}' snoozed until {}.",
+                        args.id.color(CliColors::accent()),
+                        reminder.due_at().format("%Y-%m-%d %H:%M:%S UTC")
+                    ))
+                );
+            }
+        }
+
+        MemoryCommands::Reminders => {
+            let reminders = ctx.memory_manager.list_reminders().await?;
+
+            if output_format == "json" {
+                print_json(&reminders);
+            } else if reminders.is_empty() {
+                outln!("{}", format_info("No reminders set."));
+            } else {
+                outln!(
+                    "{:<24} {:<16} {}",
+                    "Due".color(CliColors::muted()).bold(),
+                    "Repeats".color(CliColors::muted()).bold(),
+                    "Memory".color(CliColors::muted()).bold()
+                );
+                outln!("{}", "─".repeat(80).color(CliColors::muted()));
+
+                for reminder in reminders {
+                    outln!(
+                        "{:<24} {:<16} {}",
+                        reminder.due_at().format("%Y-%m-%d %H:%M:%S UTC"),
+                        reminder
+                            .recurrence
+                            .map(|recurrence| recurrence.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        reminder.memory_id.color(CliColors::accent())
+                    );
+                }
             }
         }
 
@@ -605,12 +1050,9 @@ pub async fn handle_memory_command(
 
             if output_format == "json" {
                 let result = json!({ "count": count });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else {
-                println!("Total memories: {}", count);
+                outln!("Total memories: {}", count);
             }
         }
 
@@ -622,10 +1064,9 @@ pub async fn handle_memory_command(
                 .await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&memories).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&memories);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                memory_table(&memories).print(format);
             } else {
                 print_memory_list(&memories);
             }
@@ -635,10 +1076,9 @@ pub async fn handle_memory_command(
             let memories = ctx.memory_manager.get_recent_memories(args.limit).await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&memories).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&memories);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                memory_table(&memories).print(format);
             } else {
                 print_memory_list(&memories);
             }
@@ -680,12 +1120,9 @@ pub async fn handle_memory_command(
                     "success": updated,
                     "memory_id": args.id
                 });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else if updated {
-                println!(
+                outln!(
                     "{}",
                     format_success(&format!(
                         "Memory '{}' updated successfully.",
@@ -693,7 +1130,7 @@ pub async fn handle_memory_command(
                     ))
                 );
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_warning(&format!(
                         "Memory '{}' not found or could not be updated.",
@@ -703,6 +1140,10 @@ pub async fn handle_memory_command(
             }
         }
 
+        MemoryCommands::Review(args) => {
+            super::review::handle_review(args, ctx, output_format).await?;
+        }
+
         MemoryCommands::Relationships(args) => {
             if let Some(command) = args.command {
                 match command {
@@ -761,13 +1202,9 @@ pub async fn handle_memory_command(
                             .await?;
 
                         if output_format == "json" {
-                            println!(
-                                "{}",
-                                serde_json::to_string_pretty(&created)
-                                    .unwrap_or_else(|_| "{}".to_string())
-                            );
+                            print_json(&created);
                         } else {
-                            println!(
+                            outln!(
                                 "{}",
                                 format_success(&format!(
                                     "Relationship '{}' created from memory '{}' to '{}'",
@@ -811,21 +1248,18 @@ pub async fn handle_memory_command(
                         "incoming": incoming,
                         "total": relationships.len() + incoming.len()
                     });
-                    println!(
-                        "{}",
-                        serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                    );
+                    print_json(&result);
                 } else {
-                    println!(
+                    outln!(
                         "{}",
                         format_info(&format!(
                             "Memory Relationships: {}",
                             args.id.color(CliColors::accent())
                         ))
                     );
-                    println!();
+                    outln!();
                     if !relationships.is_empty() {
-                        println!(
+                        outln!(
                             "{}",
                             format_info(&format!(
                                 "Outgoing Relationships ({}):",
@@ -833,17 +1267,17 @@ pub async fn handle_memory_command(
                             ))
                         );
                         print_relationship_list(&relationships);
-                        println!();
+                        outln!();
                     }
                     if !incoming.is_empty() {
-                        println!(
+                        outln!(
                             "{}",
                             format_info(&format!("Incoming Relationships ({}):", incoming.len()))
                         );
                         print_relationship_list(&incoming);
                     }
                     if relationships.is_empty() && incoming.is_empty() {
-                        println!("{}", format_info("No relationships found."));
+                        outln!("{}", format_info("No relationships found."));
                     }
                 }
             }
@@ -852,3 +1286,38 @@ pub async fn handle_memory_command(
 
     Ok(())
 }
+
+fn print_filter_explanation(explanation: &FilterExplanation) {
+    outln!();
+    if explanation.indexes_used.is_empty() {
+        outln!("{}", format_info("Filter plan: no indexes used"));
+    } else {
+        outln!(
+            "{}",
+            format_info(&format!(
+                "Filter plan: uses {}",
+                explanation
+                    .indexes_used
+                    .join(", ")
+                    .color(CliColors::accent())
+            ))
+        );
+    }
+    if explanation.full_scan {
+        outln!(
+            "{}",
+            format_warning("The filter scans every memory in the table.")
+        );
+    }
+    if !explanation.unindexed_properties.is_empty() {
+        outln!(
+            "  {} {}",
+            "Unindexed properties:".color(CliColors::muted()),
+            explanation.unindexed_properties.join(", ")
+        );
+        outln!(
+            "  Add them to {} to index them.",
+            "storage.indexed_properties".color(CliColors::accent())
+        );
+    }
+}
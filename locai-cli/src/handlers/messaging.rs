@@ -0,0 +1,78 @@
+//! Messaging command handlers
+
+use crate::args::DeadLettersArgs;
+use crate::commands::MessagingCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::*;
+use locai::messaging::DeadLetter;
+use locai::messaging::dead_letter::list_dead_letters;
+
+pub async fn handle_messaging_command(
+    cmd: MessagingCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        MessagingCommands::DeadLetters(args) => dead_letters(args, ctx, output_format).await,
+    }
+}
+
+async fn dead_letters(
+    args: DeadLettersArgs,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let letters =
+        list_dead_letters(&ctx.memory_manager, args.app.as_deref(), Some(args.limit)).await?;
+
+    if output_format == "json" {
+        print_json(&letters);
+    } else {
+        print_dead_letters(&letters);
+    }
+    Ok(())
+}
+
+fn print_dead_letters(letters: &[DeadLetter]) {
+    if letters.is_empty() {
+        outln!("{}", format_info("No dead letters found."));
+        return;
+    }
+
+    outln!(
+        "{}",
+        format_info(&format!("Found {} dead letters:", letters.len()))
+    );
+    outln!();
+
+    outln!(
+        "{:<20} {:<30} {:<15} {:<8} {}",
+        "Failed At".color(CliColors::muted()).bold(),
+        "Original Topic".color(CliColors::muted()).bold(),
+        "Reason".color(CliColors::muted()).bold(),
+        "Tries".color(CliColors::muted()).bold(),
+        "Error".color(CliColors::muted()).bold()
+    );
+    outln!("{}", "─".repeat(100).color(CliColors::muted()));
+
+    for letter in letters {
+        outln!(
+            "{:<20} {:<30} {:<15} {:<8} {}",
+            letter.failed_at.format("%Y-%m-%d %H:%M:%S"),
+            shorten(&letter.original_topic, 30).color(CliColors::accent()),
+            letter.reason.to_string().color(CliColors::warning()),
+            letter.attempts,
+            shorten(&letter.error, 60).color(CliColors::primary())
+        );
+    }
+}
+
+fn shorten(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        let head: String = text.chars().take(max - 3).collect();
+        format!("{}...", head)
+    } else {
+        text.to_string()
+    }
+}
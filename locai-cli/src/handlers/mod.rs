@@ -1,19 +1,54 @@
 //! Command handlers for the Locai CLI
 
 pub mod batch;
+pub mod bench;
+pub mod capture;
+pub mod collection;
+pub mod config;
+pub mod diagnose;
 pub mod entity;
+pub mod export;
 pub mod graph;
+pub mod import;
+pub mod maintenance;
 pub mod memory;
+pub mod messaging;
+pub mod persona;
+pub mod preference;
+pub mod procedure;
 pub mod quickstart;
 pub mod relationship;
 pub mod relationship_type;
+pub mod review;
+pub mod rule;
+pub mod serve;
+pub mod snapshot;
+pub mod task;
+pub mod tui;
 pub mod tutorial;
 
 pub use batch::handle_batch_command;
+pub use bench::handle_bench_command;
+pub use capture::{handle_clip_command, handle_note_command};
+pub use collection::handle_collection_command;
+pub use config::handle_config_command;
+pub use diagnose::handle_deep_diagnose;
 pub use entity::handle_entity_command;
+pub use export::handle_export_command;
 pub use graph::handle_graph_command;
+pub use import::handle_import_command;
+pub use maintenance::handle_maintenance_command;
 pub use memory::handle_memory_command;
+pub use messaging::handle_messaging_command;
+pub use persona::handle_persona_command;
+pub use preference::handle_preference_command;
+pub use procedure::handle_procedure_command;
 pub use quickstart::handle_quickstart_command;
 pub use relationship::handle_relationship_command;
 pub use relationship_type::handle_relationship_type_command;
+pub use rule::handle_rule_command;
+pub use serve::handle_serve_command;
+pub use snapshot::handle_snapshot_command;
+pub use task::handle_task_command;
+pub use tui::handle_tui_command;
 pub use tutorial::handle_tutorial_command;
@@ -0,0 +1,154 @@
+//! Persona command handlers
+
+use crate::commands::PersonaCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::Colorize;
+use locai::LocaiError;
+use locai::memory::Persona;
+use serde_json::json;
+
+pub async fn handle_persona_command(
+    cmd: PersonaCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        PersonaCommands::Set(args) => {
+            let persona = ctx
+                .memory_manager
+                .set_persona(Persona {
+                    traits: args.traits,
+                    writing_style: args.writing_style,
+                    constraints: args.constraints,
+                    ..Persona::new(args.agent, args.identity)
+                })
+                .await?;
+
+            if output_format == "json" {
+                print_json(&persona);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Set the persona of {}.",
+                        persona.agent.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+
+        PersonaCommands::Show(args) => {
+            let persona = ctx
+                .memory_manager
+                .get_persona(&args.agent)
+                .await?
+                .ok_or_else(|| not_found(&args.agent))?;
+
+            if output_format == "json" {
+                print_json(&persona);
+            } else {
+                print_persona(&persona);
+            }
+        }
+
+        PersonaCommands::List => {
+            let personas = ctx.memory_manager.list_personas().await?;
+
+            if output_format == "json" {
+                print_json(&personas);
+            } else if personas.is_empty() {
+                outln!("{}", format_info("No personas set."));
+            } else {
+                for persona in personas {
+                    let identity = persona.identity.lines().next().unwrap_or_default();
+                    outln!(
+                        "{:<24} {}",
+                        persona.agent.color(CliColors::accent()),
+                        identity
+                    );
+                }
+            }
+        }
+
+        PersonaCommands::Remove(args) => {
+            let removed = ctx.memory_manager.remove_persona(&args.agent).await?;
+
+            if output_format == "json" {
+                print_json(&json!({ "agent": args.agent, "removed": removed }));
+            } else if removed {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Removed the persona of {}.",
+                        args.agent.color(CliColors::accent())
+                    ))
+                );
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!("{} has no persona.", args.agent))
+                );
+            }
+        }
+
+        PersonaCommands::History(args) => {
+            let history = ctx.memory_manager.persona_history(&args.agent).await?;
+
+            if output_format == "json" {
+                print_json(&history);
+            } else if history.is_empty() {
+                return Err(not_found(&args.agent));
+            } else {
+                for (index, revision) in history.iter().enumerate() {
+                    if index > 0 {
+                        outln!();
+                    }
+                    outln!(
+                        "{}",
+                        revision
+                            .updated_at
+                            .format("%Y-%m-%d %H:%M:%S UTC")
+                            .to_string()
+                            .color(CliColors::muted())
+                    );
+                    outln!("{}", revision.render());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn not_found(agent: &str) -> LocaiError {
+    LocaiError::Persona(format!("{} has no persona", agent))
+}
+
+fn print_persona(persona: &Persona) {
+    outln!(
+        "{} {}",
+        "Agent:".color(CliColors::muted()).bold(),
+        persona.agent.color(CliColors::accent())
+    );
+    outln!("{}", persona.identity);
+    if !persona.traits.is_empty() {
+        outln!("{}", "Traits:".color(CliColors::muted()).bold());
+        for value in &persona.traits {
+            outln!("  - {}", value);
+        }
+    }
+    if let Some(style) = &persona.writing_style {
+        outln!(
+            "{} {}",
+            "Writing style:".color(CliColors::muted()).bold(),
+            style
+        );
+    }
+    if !persona.constraints.is_empty() {
+        outln!("{}", "Constraints:".color(CliColors::muted()).bold());
+        for constraint in &persona.constraints {
+            outln!("  - {}", constraint);
+        }
+    }
+}
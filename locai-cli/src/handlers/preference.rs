@@ -0,0 +1,147 @@
+//! Preference command handlers
+
+use crate::commands::PreferenceCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::Colorize;
+use locai::LocaiError;
+use locai::memory::{GLOBAL_PREFERENCE_SCOPE, Preference};
+use serde_json::json;
+
+pub async fn handle_preference_command(
+    cmd: PreferenceCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        PreferenceCommands::Set(args) => {
+            let scope = args.scope.as_deref().unwrap_or(GLOBAL_PREFERENCE_SCOPE);
+            let preference = ctx
+                .memory_manager
+                .set_scoped_preference(scope, &args.key, &args.value)
+                .await?;
+
+            if output_format == "json" {
+                print_json(&preference);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Set {} = {} in {}.",
+                        preference.key.color(CliColors::accent()),
+                        preference.value,
+                        preference.scope
+                    ))
+                );
+            }
+        }
+
+        PreferenceCommands::Get(args) => {
+            let preference = ctx
+                .memory_manager
+                .get_preference(&args.key, args.scope.as_deref())
+                .await?
+                .ok_or_else(|| not_found(&args.key))?;
+
+            if output_format == "json" {
+                print_json(&preference);
+            } else {
+                outln!("{}", preference.value);
+            }
+        }
+
+        PreferenceCommands::List(args) => {
+            let preferences = if args.all {
+                ctx.memory_manager.list_preferences().await?
+            } else {
+                ctx.memory_manager
+                    .get_preferences(args.scope.as_deref())
+                    .await?
+            };
+
+            if output_format == "json" {
+                print_json(&preferences);
+            } else if preferences.is_empty() {
+                outln!("{}", format_info("No preferences set."));
+            } else {
+                print_preferences(&preferences);
+            }
+        }
+
+        PreferenceCommands::Unset(args) => {
+            let scope = args.scope.as_deref().unwrap_or(GLOBAL_PREFERENCE_SCOPE);
+            let removed = ctx
+                .memory_manager
+                .remove_preference(scope, &args.key)
+                .await?;
+
+            if output_format == "json" {
+                print_json(&json!({ "scope": scope, "key": args.key, "removed": removed }));
+            } else if removed {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Removed {} from {}.",
+                        args.key.color(CliColors::accent()),
+                        scope
+                    ))
+                );
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!("{} doesn't set {}.", scope, args.key))
+                );
+            }
+        }
+
+        PreferenceCommands::History(args) => {
+            let scope = args.scope.as_deref().unwrap_or(GLOBAL_PREFERENCE_SCOPE);
+            let history = ctx
+                .memory_manager
+                .preference_history(scope, &args.key)
+                .await?;
+
+            if output_format == "json" {
+                print_json(&history);
+            } else if history.is_empty() {
+                return Err(not_found(&args.key));
+            } else {
+                for change in history {
+                    outln!(
+                        "{}  {}",
+                        change
+                            .changed_at
+                            .format("%Y-%m-%d %H:%M:%S UTC")
+                            .to_string()
+                            .color(CliColors::muted()),
+                        change.value
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn not_found(key: &str) -> LocaiError {
+    LocaiError::Preference(format!("Preference '{}' is not set", key))
+}
+
+fn print_preferences(preferences: &[Preference]) {
+    outln!(
+        "{:<16} {:<24} {}",
+        "Scope".color(CliColors::muted()).bold(),
+        "Key".color(CliColors::muted()).bold(),
+        "Value".color(CliColors::muted()).bold()
+    );
+    outln!("{}", "─".repeat(80).color(CliColors::muted()));
+    for preference in preferences {
+        outln!(
+            "{:<16} {:<24} {}",
+            preference.scope,
+            preference.key.color(CliColors::accent()),
+            preference.value
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Procedure command handlers
+
+use crate::args::AddProcedureArgs;
+use crate::commands::ProcedureCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::Colorize;
+use locai::LocaiError;
+use locai::memory::{Procedure, ProcedureStep};
+
+pub async fn handle_procedure_command(
+    cmd: ProcedureCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        ProcedureCommands::Add(args) => {
+            let procedure = ctx.memory_manager.create_procedure(build(args)).await?;
+
+            if output_format == "json" {
+                print_json(&procedure);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Procedure '{}' created with ID {}.",
+                        procedure.name,
+                        procedure.id.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+
+        ProcedureCommands::List => {
+            let procedures = ctx.memory_manager.list_procedures().await?;
+
+            if output_format == "json" {
+                print_json(&procedures);
+            } else if procedures.is_empty() {
+                outln!("{}", format_info("No procedures found."));
+            } else {
+                outln!(
+                    "{:<38} {:<6} {:<30} {}",
+                    "ID".color(CliColors::muted()).bold(),
+                    "Steps".color(CliColors::muted()).bold(),
+                    "Name".color(CliColors::muted()).bold(),
+                    "Goal".color(CliColors::muted()).bold()
+                );
+                outln!("{}", "─".repeat(100).color(CliColors::muted()));
+
+                for procedure in procedures {
+                    outln!(
+                        "{:<38} {:<6} {:<30} {}",
+                        procedure.id.color(CliColors::accent()),
+                        procedure.steps.len(),
+                        procedure.name,
+                        procedure.goal
+                    );
+                }
+            }
+        }
+
+        ProcedureCommands::Show(args) => {
+            let procedure = ctx
+                .memory_manager
+                .get_procedure(&args.id)
+                .await?
+                .ok_or_else(|| {
+                    LocaiError::Procedure(format!("Procedure '{}' not found", args.id))
+                })?;
+
+            if output_format == "json" {
+                print_json(&procedure);
+            } else {
+                print_procedure(&procedure);
+            }
+        }
+
+        ProcedureCommands::Find(args) => {
+            let matches = ctx
+                .memory_manager
+                .find_procedures_for(&args.goal, Some(args.limit))
+                .await?;
+
+            if output_format == "json" {
+                print_json(&matches);
+            } else if matches.is_empty() {
+                outln!("{}", format_info("No procedures match that goal."));
+            } else {
+                for found in matches {
+                    outln!(
+                        "{} {} {}",
+                        format!("{:.2}", found.score).color(CliColors::success()),
+                        found.procedure.id.color(CliColors::accent()),
+                        found.procedure.name.bold()
+                    );
+                    if !found.procedure.goal.is_empty() {
+                        outln!("     {}", found.procedure.goal.color(CliColors::muted()));
+                    }
+                    if !found.matched_entities.is_empty() {
+                        outln!(
+                            "     {}: {}",
+                            "Entities".color(CliColors::muted()),
+                            found.matched_entities.join(", ")
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The procedure described by `add` arguments
+fn build(args: AddProcedureArgs) -> Procedure {
+    let mut procedure = Procedure::new(args.name, args.goal);
+    procedure.steps = args.steps.iter().map(|step| parse_step(step)).collect();
+    procedure.preconditions = args.preconditions;
+    procedure.success_metrics = args.metrics;
+    procedure.tags = args.tags;
+    procedure
+}
+
+/// A step written as `instruction` or `instruction => expected outcome`
+fn parse_step(step: &str) -> ProcedureStep {
+    match step.split_once("=>") {
+        Some((instruction, expected)) if !expected.trim().is_empty() => {
+            ProcedureStep::new(instruction.trim()).expecting(expected.trim())
+        }
+        _ => ProcedureStep::new(step.trim()),
+    }
+}
+
+fn print_procedure(procedure: &Procedure) {
+    outln!(
+        "{}",
+        "━━━ Procedure Details ━━━"
+            .color(CliColors::accent())
+            .bold()
+    );
+    outln!(
+        "{}: {}",
+        "Name".color(CliColors::muted()),
+        procedure.name.bold()
+    );
+    outln!(
+        "{}: {}",
+        "ID".color(CliColors::muted()),
+        procedure.id.color(CliColors::primary())
+    );
+    if !procedure.goal.is_empty() {
+        outln!("{}: {}", "Goal".color(CliColors::muted()), procedure.goal);
+    }
+    if !procedure.preconditions.is_empty() {
+        outln!("{}:", "Preconditions".color(CliColors::muted()));
+        for precondition in &procedure.preconditions {
+            outln!("  - {}", precondition);
+        }
+    }
+    outln!("{}:", "Steps".color(CliColors::muted()));
+    for (index, step) in procedure.steps.iter().enumerate() {
+        outln!("  {}. {}", index + 1, step.instruction);
+        if let Some(expected) = &step.expected {
+            outln!("     {} {}", "expect:".color(CliColors::muted()), expected);
+        }
+    }
+    if !procedure.success_metrics.is_empty() {
+        outln!("{}:", "Success".color(CliColors::muted()));
+        for metric in &procedure.success_metrics {
+            outln!("  - {}", metric);
+        }
+    }
+    if !procedure.tags.is_empty() {
+        outln!(
+            "{}: {}",
+            "Tags".color(CliColors::muted()),
+            procedure.tags.join(", ")
+        );
+    }
+}
@@ -4,7 +4,9 @@ use crate::args::QuickstartArgs;
 use crate::context::LocaiCliContext;
 use crate::output::*;
 use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
 use locai::storage::filters::MemoryFilter;
+use locai::synthetic::{SyntheticConfig, SyntheticProgress};
 
 /// Load pre-generated embeddings from JSON file
 /// Returns a map of text -> embedding vector
@@ -80,23 +82,34 @@ fn generate_mock_embedding(text: &str, dimensions: usize) -> Vec<f32> {
 pub async fn handle_quickstart_command(
     args: QuickstartArgs,
     ctx: &LocaiCliContext,
-    _output_format: &str,
+    output_format: &str,
 ) -> locai::Result<()> {
     if args.cleanup {
         return cleanup_quickstart_data(ctx).await;
     }
 
+    if let Some(memories) = args.synthetic {
+        let config = SyntheticConfig {
+            memories,
+            entities: args.entities,
+            graph_density: args.graph_density,
+            seed: args.seed,
+            embeddings: args.embeddings,
+        };
+        return load_synthetic_data(ctx, &config, output_format).await;
+    }
+
     // Only show full intro if not using --step flag
     if args.step.is_none() {
-        println!(
+        outln!(
             "{}",
             "━━━ Locai Quick Start ━━━"
                 .color(CliColors::accent())
                 .bold()
         );
-        println!();
-        println!("Welcome to Locai! Creating sample data to help you explore.");
-        println!();
+        outln!();
+        outln!("Welcome to Locai! Creating sample data to help you explore.");
+        outln!();
     }
 
     let sample_memories = vec![
@@ -128,7 +141,7 @@ pub async fn handle_quickstart_command(
     ];
 
     if args.step.is_none() {
-        println!("{}", format_info("Creating sample memories..."));
+        outln!("{}", format_info("Creating sample memories..."));
     }
     let mut created_ids = Vec::new();
 
@@ -212,7 +225,7 @@ pub async fn handle_quickstart_command(
         };
 
         if embedding_count > 0 {
-            println!(
+            outln!(
                 "{}",
                 format_success(&format!(
                     "✓ Created/verified {} sample memories ({} with {} for semantic search demo)",
@@ -222,7 +235,7 @@ pub async fn handle_quickstart_command(
                 ))
             );
         } else {
-            println!(
+            outln!(
                 "{}",
                 format_success(&format!(
                     "✓ Created/verified {} sample memories",
@@ -230,7 +243,7 @@ pub async fn handle_quickstart_command(
                 ))
             );
         }
-        println!("{}", format_info("Creating sample entities..."));
+        outln!("{}", format_info("Creating sample entities..."));
     }
     let mut entity_count = 0;
 
@@ -276,14 +289,14 @@ pub async fn handle_quickstart_command(
     }
 
     if args.step.is_none() {
-        println!(
+        outln!(
             "{}",
             format_success(&format!(
                 "✓ Created/verified {} sample entities",
                 entity_count
             ))
         );
-        println!("{}", format_info("Creating sample relationships..."));
+        outln!("{}", format_info("Creating sample relationships..."));
     }
 
     // Small delay to ensure entities are fully persisted
@@ -422,9 +435,9 @@ pub async fn handle_quickstart_command(
                     relationship_count
                 )
             };
-            println!("{}", format_success(&msg));
+            outln!("{}", format_success(&msg));
         } else {
-            println!(
+            outln!(
                 "{}",
                 format_warning("⚠ No relationships created (entities may not be ready yet)")
             );
@@ -434,117 +447,117 @@ pub async fn handle_quickstart_command(
     // Show different output based on --step flag
     match args.step {
         Some(1) => {
-            println!();
-            println!(
+            outln!();
+            outln!(
                 "{}",
                 "━━━ Step 1: Search ━━━".color(CliColors::accent()).bold()
             );
-            println!();
-            println!("Try searching for memories:");
-            println!("  locai-cli memory search \"warrior\"");
-            println!("  locai-cli memory search \"John\"");
-            println!("  locai-cli memory search \"Alice\"");
-            println!();
-            println!("{}", "Note:".bold());
-            println!("  • Default search uses BM25 (keyword matching)");
-            println!("  • Search for words that appear in the memory content");
-            println!(
+            outln!();
+            outln!("Try searching for memories:");
+            outln!("  locai-cli memory search \"warrior\"");
+            outln!("  locai-cli memory search \"John\"");
+            outln!("  locai-cli memory search \"Alice\"");
+            outln!();
+            outln!("{}", "Note:".bold());
+            outln!("  • Default search uses BM25 (keyword matching)");
+            outln!("  • Search for words that appear in the memory content");
+            outln!(
                 "  • First 3 memories have {} embeddings for demo",
                 "mock".color(CliColors::accent())
             );
-            println!(
+            outln!(
                 "  • Try semantic search: {}",
                 "locai-cli memory search \"character\" --mode semantic".color(CliColors::accent())
             );
-            println!();
-            println!(
+            outln!();
+            outln!(
                 "Next: {}",
                 "locai-cli quickstart --step 2".color(CliColors::accent())
             );
         }
         Some(2) => {
-            println!();
-            println!(
+            outln!();
+            outln!(
                 "{}",
                 "━━━ Step 2: Explore ━━━".color(CliColors::accent()).bold()
             );
-            println!();
+            outln!();
             if let Some(first_id) = created_ids.first() {
-                println!("View a memory:");
-                println!("  locai-cli memory get {}", first_id);
-                println!();
-                println!("See how memories connect:");
-                println!("  locai-cli graph subgraph {}", first_id);
-                println!();
+                outln!("View a memory:");
+                outln!("  locai-cli memory get {}", first_id);
+                outln!();
+                outln!("See how memories connect:");
+                outln!("  locai-cli graph subgraph {}", first_id);
+                outln!();
             }
-            println!(
+            outln!(
                 "Next: {}",
                 "locai-cli quickstart --step 3".color(CliColors::accent())
             );
         }
         Some(3) => {
-            println!();
-            println!(
+            outln!();
+            outln!(
                 "{}",
                 "━━━ Step 3: Learn More ━━━"
                     .color(CliColors::accent())
                     .bold()
             );
-            println!();
-            println!("Interactive tutorial:");
-            println!("  locai-cli tutorial");
-            println!();
-            println!("Get explanations:");
-            println!("  locai-cli --explain memory");
-            println!("  locai-cli --explain graph");
-            println!();
-            println!("{}", format_success("You're all set! Happy exploring! 🚀"));
+            outln!();
+            outln!("Interactive tutorial:");
+            outln!("  locai-cli tutorial");
+            outln!();
+            outln!("Get explanations:");
+            outln!("  locai-cli --explain memory");
+            outln!("  locai-cli --explain graph");
+            outln!();
+            outln!("{}", format_success("You're all set! Happy exploring! 🚀"));
         }
         Some(n) if n > 3 => {
-            println!();
-            println!(
+            outln!();
+            outln!(
                 "{}",
                 format_error("Invalid step number. Use --step 1, 2, or 3.")
             );
         }
         _ => {
             // Default: Show summary and 3 key commands
-            println!();
-            println!("{}", format_success("✓ Sample data created!"));
-            println!();
-            println!("{}", "Try these 3 commands:".bold());
-            println!();
-            println!("  1. {}", "Search memories".color(CliColors::accent()));
-            println!("     locai-cli memory search \"warrior\"");
-            println!("     locai-cli memory search \"John\"");
-            println!();
-            println!("  2. {}", "List all memories".color(CliColors::accent()));
-            println!("     locai-cli memory list");
-            println!();
+            outln!();
+            outln!("{}", format_success("✓ Sample data created!"));
+            outln!();
+            outln!("{}", "Try these 3 commands:".bold());
+            outln!();
+            outln!("  1. {}", "Search memories".color(CliColors::accent()));
+            outln!("     locai-cli memory search \"warrior\"");
+            outln!("     locai-cli memory search \"John\"");
+            outln!();
+            outln!("  2. {}", "List all memories".color(CliColors::accent()));
+            outln!("     locai-cli memory list");
+            outln!();
             if let Some(first_id) = created_ids.first() {
-                println!("  3. {}", "See the graph".color(CliColors::accent()));
-                println!("     locai-cli graph subgraph {}", first_id);
+                outln!("  3. {}", "See the graph".color(CliColors::accent()));
+                outln!("     locai-cli graph subgraph {}", first_id);
             }
-            println!();
-            println!("{}", "Next steps:".bold());
-            println!(
+            outln!();
+            outln!("{}", "Next steps:".bold());
+            outln!(
                 "  • Run interactive tutorial: {}",
                 "locai-cli tutorial".color(CliColors::accent())
             );
-            println!(
+            outln!(
                 "  • Learn concepts: {}",
                 "locai-cli --explain memory".color(CliColors::accent())
             );
-            println!(
+            outln!(
                 "  • Step-by-step guide: {}",
                 "locai-cli quickstart --step 1".color(CliColors::accent())
             );
-            println!(
+            outln!(
                 "  • Remove sample data: {}",
                 "locai-cli quickstart --cleanup".color(CliColors::accent())
             );
-            println!();
-            println!("{}", "💡 About Semantic Search:".bold());
+            outln!();
+            outln!("{}", "💡 About Semantic Search:".bold());
             let embeddings = load_quickstart_embeddings();
             let has_real_embeddings = !embeddings.is_empty()
                 && embeddings
@@ -553,27 +566,27 @@ pub async fn handle_quickstart_command(
                     .map(|v| v.len() == 1024)
                     .unwrap_or(false);
             if has_real_embeddings {
-                println!(
+                outln!(
                     "  • First 3 memories have {} embeddings for demonstration",
                     "pre-generated".color(CliColors::accent())
                 );
             } else {
-                println!(
+                outln!(
                     "  • First 3 memories have {} embeddings for demonstration",
                     "mock".color(CliColors::accent())
                 );
-                println!(
+                outln!(
                     "  • To use real embeddings, run: {}",
                     "./scripts/generate_quickstart_embeddings.sh".color(CliColors::accent())
                 );
             }
-            println!(
+            outln!(
                 "  • Try: {}",
                 "locai-cli memory search \"character\" --mode semantic".color(CliColors::accent())
             );
-            println!("  • Semantic search understands meaning, not just keywords");
-            println!("  • For production, use real embeddings from OpenAI, Cohere, etc.");
-            println!(
+            outln!("  • Semantic search understands meaning, not just keywords");
+            outln!("  • For production, use real embeddings from OpenAI, Cohere, etc.");
+            outln!(
                 "  • See: {}",
                 "locai-cli --explain search".color(CliColors::accent())
             );
@@ -583,15 +596,84 @@ pub async fn handle_quickstart_command(
     Ok(())
 }
 
+/// Generate synthetic memories, entities and relationships for benchmarks and demos
+async fn load_synthetic_data(
+    ctx: &LocaiCliContext,
+    config: &SyntheticConfig,
+    output_format: &str,
+) -> locai::Result<()> {
+    config.validate()?;
+    let pb = if crate::invocation::shows_progress() && output_format != "json" {
+        outln!(
+            "{}",
+            format_info(&format!(
+                "Generating {} memories and {} entities (seed {}, {} relationships per memory)...",
+                config.memories, config.entities, config.seed, config.graph_density
+            ))
+        );
+        let pb = ProgressBar::new(0);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}",
+                )
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        Some(pb)
+    } else {
+        None
+    };
+
+    let report = locai::synthetic::load(
+        &ctx.memory_manager,
+        config,
+        |SyntheticProgress { done, total }| {
+            if let Some(pb) = &pb {
+                pb.set_length(total as u64);
+                pb.set_position(done as u64);
+            }
+        },
+    )
+    .await?;
+    if let Some(pb) = pb {
+        pb.finish_and_clear();
+    }
+
+    if output_format == "json" {
+        print_json(&report);
+        return Ok(());
+    }
+
+    outln!(
+        "{}",
+        format_success(&format!(
+            "✓ Created {} memories, {} entities and {} relationships",
+            report.memories, report.entities, report.relationships
+        ))
+    );
+    if report.failed > 0 {
+        outln!(
+            "{}",
+            format_warning(&format!("{} records failed to store", report.failed))
+        );
+    }
+    outln!(
+        "{}",
+        format_info("Remove the synthetic data with 'locai-cli quickstart --cleanup'.")
+    );
+    Ok(())
+}
+
 async fn cleanup_quickstart_data(ctx: &LocaiCliContext) -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Cleaning Up Quickstart Data ━━━"
             .color(CliColors::accent())
             .bold()
     );
-    println!();
-    println!(
+    outln!();
+    outln!(
         "{}",
         format_info("Removing sample memories and entities...")
     );
@@ -625,16 +707,28 @@ async fn cleanup_quickstart_data(ctx: &LocaiCliContext) -> locai::Result<()> {
         }
     }
 
-    println!();
-    println!(
+    let (synthetic_memories, synthetic_entities) =
+        locai::synthetic::remove(&ctx.memory_manager).await?;
+
+    outln!();
+    outln!(
         "{}",
         format_success(&format!(
             "✓ Cleaned up {} memories and quickstart entities",
             deleted_count
         ))
     );
-    println!();
-    println!(
+    if synthetic_memories + synthetic_entities > 0 {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "✓ Removed {} synthetic memories and {} synthetic entities",
+                synthetic_memories, synthetic_entities
+            ))
+        );
+    }
+    outln!();
+    outln!(
         "{}",
         format_info("Sample data removed. Run 'locai-cli quickstart' again to recreate it.")
     );
@@ -3,6 +3,7 @@
 use crate::commands::RelationshipCommands;
 use crate::context::LocaiCliContext;
 use crate::output::*;
+use crate::tabular::{TableFormat, memory_table, relationship_table};
 use colored::Colorize;
 use locai::LocaiError;
 use locai::storage::filters::RelationshipFilter;
@@ -23,15 +24,16 @@ pub async fn handle_relationship_command(
                         &args.relationship_type,
                     )
                     .await?;
-                println!(
+                outln!(
                     "Bidirectional relationship created between '{}' and '{}'",
-                    args.from, args.to
+                    args.from,
+                    args.to
                 );
             } else {
                 ctx.memory_manager
                     .create_relationship(&args.from, &args.to, &args.relationship_type)
                     .await?;
-                println!("Relationship created from '{}' to '{}'", args.from, args.to);
+                outln!("Relationship created from '{}' to '{}'", args.from, args.to);
             }
         }
 
@@ -39,17 +41,13 @@ pub async fn handle_relationship_command(
             match ctx.memory_manager.get_relationship(&args.id).await? {
                 Some(relationship) => {
                     if output_format == "json" {
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&relationship)
-                                .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&relationship);
                     } else {
                         print_relationship(&relationship);
                     }
                 }
                 None => {
-                    println!("Relationship with ID '{}' not found.", args.id);
+                    outln!("Relationship with ID '{}' not found.", args.id);
                 }
             }
         }
@@ -66,11 +64,9 @@ pub async fn handle_relationship_command(
                 .await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&relationships)
-                        .unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&relationships);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                relationship_table(&relationships).print(format);
             } else {
                 print_relationship_list(&relationships);
             }
@@ -78,8 +74,8 @@ pub async fn handle_relationship_command(
 
         RelationshipCommands::Delete(args) => {
             match ctx.memory_manager.delete_relationship(&args.id).await? {
-                true => println!("Relationship '{}' deleted successfully.", args.id),
-                false => println!(
+                true => outln!("Relationship '{}' deleted successfully.", args.id),
+                false => outln!(
                     "Relationship '{}' not found or could not be deleted.",
                     args.id
                 ),
@@ -93,10 +89,9 @@ pub async fn handle_relationship_command(
                 .await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&memories).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&memories);
+            } else if let Some(format) = TableFormat::from_output(output_format) {
+                memory_table(&memories).print(format);
             } else {
                 print_memory_list(&memories);
             }
@@ -124,12 +119,9 @@ pub async fn handle_relationship_command(
             let updated = ctx.memory_manager.update_relationship(relationship).await?;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&updated).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&updated);
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_success(&format!(
                         "Relationship '{}' updated successfully.",
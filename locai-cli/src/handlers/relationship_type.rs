@@ -19,19 +19,16 @@ pub async fn handle_relationship_type_command(
             let types = ctx.relationship_type_registry.list().await;
 
             if output_format == "json" {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&types).unwrap_or_else(|_| "[]".to_string())
-                );
+                print_json(&types);
             } else if types.is_empty() {
-                println!("{}", format_info("No relationship types registered."));
+                outln!("{}", format_info("No relationship types registered."));
             } else {
-                println!(
+                outln!(
                     "{}",
                     format_info(&format!("Found {} relationship types:", types.len()))
                 );
-                println!();
-                println!(
+                outln!();
+                outln!(
                     "{:<30} {:<15} {:<12} {:<12} {}",
                     "Name".color(CliColors::muted()).bold(),
                     "Inverse".color(CliColors::muted()).bold(),
@@ -39,10 +36,10 @@ pub async fn handle_relationship_type_command(
                     "Transitive".color(CliColors::muted()).bold(),
                     "Created".color(CliColors::muted()).bold()
                 );
-                println!("{}", "─".repeat(100).color(CliColors::muted()));
+                outln!("{}", "─".repeat(100).color(CliColors::muted()));
 
                 for type_def in types {
-                    println!(
+                    outln!(
                         "{:<30} {:<15} {:<12} {:<12} {}",
                         type_def.name.color(CliColors::accent()),
                         type_def
@@ -74,31 +71,27 @@ pub async fn handle_relationship_type_command(
             match ctx.relationship_type_registry.get(&args.name).await {
                 Some(type_def) => {
                     if output_format == "json" {
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&type_def)
-                                .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&type_def);
                     } else {
-                        println!(
+                        outln!(
                             "{}",
                             "━━━ Relationship Type Details ━━━"
                                 .color(CliColors::accent())
                                 .bold()
                         );
-                        println!(
+                        outln!(
                             "{}: {}",
                             "Name".color(CliColors::muted()),
                             type_def.name.color(CliColors::accent()).bold()
                         );
                         if let Some(inverse) = &type_def.inverse {
-                            println!(
+                            outln!(
                                 "{}: {}",
                                 "Inverse".color(CliColors::muted()),
                                 inverse.color(CliColors::accent())
                             );
                         }
-                        println!(
+                        outln!(
                             "{}: {}",
                             "Symmetric".color(CliColors::muted()),
                             if type_def.symmetric {
@@ -107,7 +100,7 @@ pub async fn handle_relationship_type_command(
                                 "No".color(CliColors::muted())
                             }
                         );
-                        println!(
+                        outln!(
                             "{}: {}",
                             "Transitive".color(CliColors::muted()),
                             if type_def.transitive {
@@ -116,7 +109,7 @@ pub async fn handle_relationship_type_command(
                                 "No".color(CliColors::muted())
                             }
                         );
-                        println!(
+                        outln!(
                             "{}: {}",
                             "Created".color(CliColors::muted()),
                             type_def
@@ -128,7 +121,7 @@ pub async fn handle_relationship_type_command(
                     }
                 }
                 None => {
-                    println!(
+                    outln!(
                         "{}",
                         format_warning(&format!(
                             "Relationship type '{}' not found.",
@@ -156,8 +149,10 @@ pub async fn handle_relationship_type_command(
             }
 
             if let Some(schema_path) = args.schema {
-                let schema_content = fs::read_to_string(&schema_path)
-                    .map_err(|e| LocaiError::Other(format!("Failed to read schema file: {}", e)))?;
+                let schema_content = fs::read_to_string(crate::invocation::resolve(&schema_path))
+                    .map_err(|e| {
+                    LocaiError::Other(format!("Failed to read schema file: {}", e))
+                })?;
                 let schema: Value = serde_json::from_str(&schema_content)
                     .map_err(|e| LocaiError::Other(format!("Invalid JSON schema: {}", e)))?;
                 type_def = type_def.with_metadata_schema(schema);
@@ -170,13 +165,9 @@ pub async fn handle_relationship_type_command(
             {
                 Ok(()) => {
                     if output_format == "json" {
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&type_def)
-                                .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&type_def);
                     } else {
-                        println!(
+                        outln!(
                             "{}",
                             format_success(&format!(
                                 "Relationship type '{}' registered successfully.",
@@ -246,8 +237,10 @@ pub async fn handle_relationship_type_command(
             }
 
             if let Some(schema_path) = args.schema {
-                let schema_content = fs::read_to_string(&schema_path)
-                    .map_err(|e| LocaiError::Other(format!("Failed to read schema file: {}", e)))?;
+                let schema_content = fs::read_to_string(crate::invocation::resolve(&schema_path))
+                    .map_err(|e| {
+                    LocaiError::Other(format!("Failed to read schema file: {}", e))
+                })?;
                 let schema: Value = serde_json::from_str(&schema_content)
                     .map_err(|e| LocaiError::Other(format!("Invalid JSON schema: {}", e)))?;
                 type_def = type_def.with_metadata_schema(schema);
@@ -260,13 +253,9 @@ pub async fn handle_relationship_type_command(
             {
                 Ok(()) => {
                     if output_format == "json" {
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&type_def)
-                                .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&type_def);
                     } else {
-                        println!(
+                        outln!(
                             "{}",
                             format_success(&format!(
                                 "Relationship type '{}' updated successfully.",
@@ -292,13 +281,9 @@ pub async fn handle_relationship_type_command(
                             "success": true,
                             "name": args.name
                         });
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&result)
-                                .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&result);
                     } else {
-                        println!(
+                        outln!(
                             "{}",
                             format_success(&format!(
                                 "Relationship type '{}' deleted successfully.",
@@ -330,23 +315,20 @@ pub async fn handle_relationship_type_command(
                     "transitive_types": transitive_count,
                     "types_with_inverse": with_inverse_count
                 });
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string())
-                );
+                print_json(&result);
             } else {
-                println!(
+                outln!(
                     "{}",
                     "━━━ Relationship Type Metrics ━━━"
                         .color(CliColors::accent())
                         .bold()
                 );
-                println!(
+                outln!(
                     "{}: {}",
                     "Total Types".color(CliColors::muted()),
                     count.to_string().color(CliColors::accent()).bold()
                 );
-                println!(
+                outln!(
                     "{}: {} ({:.1}%)",
                     "Symmetric Types".color(CliColors::muted()),
                     symmetric_count.to_string().color(CliColors::success()),
@@ -356,7 +338,7 @@ pub async fn handle_relationship_type_command(
                         0.0
                     }
                 );
-                println!(
+                outln!(
                     "{}: {} ({:.1}%)",
                     "Transitive Types".color(CliColors::muted()),
                     transitive_count.to_string().color(CliColors::success()),
@@ -366,7 +348,7 @@ pub async fn handle_relationship_type_command(
                         0.0
                     }
                 );
-                println!(
+                outln!(
                     "{}: {} ({:.1}%)",
                     "Types with Inverse".color(CliColors::muted()),
                     with_inverse_count.to_string().color(CliColors::info()),
@@ -388,13 +370,9 @@ pub async fn handle_relationship_type_command(
                             "success": true,
                             "types_seeded": types.len()
                         });
-                        println!(
-                            "{}",
-                            serde_json::to_string_pretty(&result)
-                                .unwrap_or_else(|_| "{}".to_string())
-                        );
+                        print_json(&result);
                     } else {
-                        println!(
+                        outln!(
                             "{}",
                             format_success(&format!(
                                 "Seeded {} common relationship types.",
@@ -0,0 +1,118 @@
+//! Review queue command handlers
+
+use crate::args::{ReviewArgs, ReviewSubcommand};
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::Colorize;
+use locai::LocaiError;
+use serde_json::json;
+
+/// Longest content shown per memory when listing the queue
+const PREVIEW_CHARS: usize = 60;
+
+pub async fn handle_review(
+    args: ReviewArgs,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match args.command {
+        None => {
+            let items = ctx.memory_manager.list_pending_reviews().await?;
+
+            if output_format == "json" {
+                print_json(&items);
+            } else if items.is_empty() {
+                outln!("{}", format_info("No memories are waiting for review."));
+            } else {
+                outln!(
+                    "{:<24} {:<20} {:<38} {}",
+                    "Submitted".color(CliColors::muted()).bold(),
+                    "Source".color(CliColors::muted()).bold(),
+                    "Memory".color(CliColors::muted()).bold(),
+                    "Content".color(CliColors::muted()).bold()
+                );
+                outln!("{}", "─".repeat(100).color(CliColors::muted()));
+
+                for item in items {
+                    outln!(
+                        "{:<24} {:<20} {:<38} {}",
+                        item.submitted_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                        item.memory.source,
+                        item.memory.id.color(CliColors::accent()),
+                        preview(&item.memory.content)
+                    );
+                }
+            }
+        }
+
+        Some(ReviewSubcommand::Show(args)) => {
+            let item = ctx
+                .memory_manager
+                .get_pending_review(&args.id)
+                .await?
+                .ok_or_else(|| not_waiting(&args.id))?;
+
+            if output_format == "json" {
+                print_json(&item);
+            } else {
+                print_memory(&item.memory);
+                outln!(
+                    "{}: {}",
+                    "Submitted".color(CliColors::muted()),
+                    item.submitted_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+            }
+        }
+
+        Some(ReviewSubcommand::Approve(args)) => {
+            let memory_id = ctx.memory_manager.approve_memory(&args.id).await?;
+
+            if output_format == "json" {
+                print_json(&json!({ "memory_id": memory_id, "approved": true }));
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Approved memory {}.",
+                        memory_id.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+
+        Some(ReviewSubcommand::Reject(args)) => {
+            if !ctx.memory_manager.reject_memory(&args.id).await? {
+                return Err(not_waiting(&args.id));
+            }
+
+            if output_format == "json" {
+                print_json(&json!({ "memory_id": args.id, "rejected": true }));
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Rejected memory {}.",
+                        args.id.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn not_waiting(id: &str) -> LocaiError {
+    LocaiError::Review(format!("Memory {} is not waiting for review", id))
+}
+
+/// The start of `content` on one line
+fn preview(content: &str) -> String {
+    let line = content.lines().next().unwrap_or_default();
+    if line.chars().count() > PREVIEW_CHARS || content.contains('\n') {
+        let start: String = line.chars().take(PREVIEW_CHARS).collect();
+        format!("{}…", start)
+    } else {
+        line.to_string()
+    }
+}
@@ -0,0 +1,193 @@
+//! Automation rule command handlers
+
+use crate::args::RuleScriptArgs;
+use crate::commands::RuleCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::Colorize;
+use locai::LocaiError;
+use locai::memory::{Rule, RuleEvent, RuleOutcome};
+use serde_json::json;
+
+pub async fn handle_rule_command(
+    cmd: RuleCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        RuleCommands::Set(args) => {
+            let event: RuleEvent = args.event.parse()?;
+            let rule = Rule {
+                description: args.description,
+                ..Rule::new(args.name, event, read_script(args.source)?)
+                    .with_enabled(!args.disabled)
+            };
+            let rule = ctx.memory_manager.set_rule(rule).await?;
+
+            if output_format == "json" {
+                print_json(&rule);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Set rule {} on {}.",
+                        rule.name.color(CliColors::accent()),
+                        rule.event
+                    ))
+                );
+            }
+        }
+
+        RuleCommands::Show(args) => {
+            let rule = ctx
+                .memory_manager
+                .get_rule(&args.name)
+                .await?
+                .ok_or_else(|| not_found(&args.name))?;
+
+            if output_format == "json" {
+                print_json(&rule);
+            } else {
+                print_rule(&rule);
+            }
+        }
+
+        RuleCommands::List => {
+            let rules = ctx.memory_manager.list_rules().await?;
+
+            if output_format == "json" {
+                print_json(&rules);
+            } else if rules.is_empty() {
+                outln!("{}", format_info("No rules set."));
+            } else {
+                for rule in rules {
+                    let status = if rule.enabled { "" } else { " (disabled)" };
+                    outln!(
+                        "{:<24} {:<10} {}{}",
+                        rule.name.color(CliColors::accent()),
+                        rule.event,
+                        rule.description.unwrap_or_default(),
+                        status.color(CliColors::muted())
+                    );
+                }
+            }
+        }
+
+        RuleCommands::Remove(args) => {
+            let removed = ctx.memory_manager.remove_rule(&args.name).await?;
+
+            if output_format == "json" {
+                print_json(&json!({ "name": args.name, "removed": removed }));
+            } else if removed {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Removed rule {}.",
+                        args.name.color(CliColors::accent())
+                    ))
+                );
+            } else {
+                outln!("{}", format_info(&format!("No rule named {}.", args.name)));
+            }
+        }
+
+        RuleCommands::Test(args) => {
+            let event: RuleEvent = args.event.parse()?;
+            let memory = ctx
+                .memory_manager
+                .get_memory(&args.memory_id)
+                .await?
+                .ok_or_else(|| LocaiError::Rule(format!("Memory {} not found", args.memory_id)))?;
+            let rule = Rule::new("test", event, read_script(args.source)?);
+            let outcome = ctx.memory_manager.test_rule(&rule, event, &memory)?;
+
+            if output_format == "json" {
+                print_json(&outcome);
+            } else {
+                print_outcome(&outcome);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_script(source: RuleScriptArgs) -> locai::Result<String> {
+    match (source.script, source.file) {
+        (Some(script), _) => Ok(script),
+        (None, Some(path)) => std::fs::read_to_string(crate::invocation::resolve(&path))
+            .map_err(|e| LocaiError::Other(format!("Failed to read {}: {}", path, e))),
+        (None, None) => Err(LocaiError::Rule(
+            "Give the script with --script or --file".to_string(),
+        )),
+    }
+}
+
+fn not_found(name: &str) -> LocaiError {
+    LocaiError::Rule(format!("No rule named {}", name))
+}
+
+fn print_rule(rule: &Rule) {
+    outln!(
+        "{} {}",
+        "Rule:".color(CliColors::muted()).bold(),
+        rule.name.color(CliColors::accent())
+    );
+    outln!(
+        "{} {}",
+        "Event:".color(CliColors::muted()).bold(),
+        rule.event
+    );
+    if let Some(description) = &rule.description {
+        outln!(
+            "{} {}",
+            "Description:".color(CliColors::muted()).bold(),
+            description
+        );
+    }
+    if !rule.enabled {
+        outln!("{}", "Disabled".color(CliColors::muted()));
+    }
+    outln!("{}", rule.script.trim());
+}
+
+fn print_outcome(outcome: &RuleOutcome) {
+    for failure in &outcome.failures {
+        outln!("{}", format_error(&failure.error));
+    }
+    if outcome.fired.is_empty() {
+        return;
+    }
+    match &outcome.memory {
+        Some(memory) => {
+            outln!("{}", format_success("The rule would change the memory:"));
+            outln!(
+                "  {} {:?}",
+                "Priority:".color(CliColors::muted()),
+                memory.priority
+            );
+            outln!(
+                "  {} {}",
+                "Tags:".color(CliColors::muted()),
+                memory.tags.join(", ")
+            );
+            outln!(
+                "  {} {}",
+                "Properties:".color(CliColors::muted()),
+                memory.properties
+            );
+        }
+        None => outln!(
+            "{}",
+            format_info("The rule would leave the memory as it is.")
+        ),
+    }
+    for message in &outcome.messages {
+        outln!(
+            "{} {} {}",
+            "Would send to".color(CliColors::muted()),
+            message.topic.color(CliColors::accent()),
+            message.content
+        );
+    }
+}
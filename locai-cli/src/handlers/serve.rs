@@ -0,0 +1,53 @@
+//! Serve command handler: runs the locai-server API in this process
+
+use crate::args::ServeArgs;
+use crate::context::LocaiCliContext;
+use locai::LocaiError;
+
+/// Serve the HTTP and WebSocket API over the CLI's storage until the server
+/// stops. Settings without a flag come from the same `LOCAI_*` environment
+/// variables as `locai-server`.
+pub async fn handle_serve_command(args: ServeArgs, ctx: LocaiCliContext) -> locai::Result<()> {
+    #[cfg(feature = "server")]
+    {
+        use locai_server::cli::CliArgs;
+        use locai_server::config::ServerConfig;
+
+        let enable_auth = if args.enable_auth {
+            Some(true)
+        } else if args.no_auth {
+            Some(false)
+        } else {
+            None
+        };
+        let server_config = ServerConfig::from_cli_and_env(CliArgs {
+            port: args.port,
+            enable_auth,
+            allow_signup: args.allow_signup,
+            root_password: args.root_password,
+            jwt_secret: args.jwt_secret,
+            jwt_expiration_hours: None,
+            config_file: None,
+            rate_limit_rpm: None,
+            websocket_timeout: None,
+            enable_live_queries: None,
+            enable_vectorstore_compat: None,
+            messaging_enabled: args.messaging_disabled.then_some(false),
+            messaging_auth_required: None,
+            max_request_size: None,
+            log_level: None,
+        })
+        .map_err(|e| LocaiError::Configuration(format!("Invalid server configuration: {}", e)))?;
+
+        locai_server::serve(ctx.memory_manager, server_config)
+            .await
+            .map_err(|e| LocaiError::Other(format!("Server failed: {}", e)))
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        let _ = (args, ctx);
+        Err(LocaiError::FeatureNotEnabled {
+            feature: "server".to_string(),
+        })
+    }
+}
@@ -0,0 +1,276 @@
+//! Snapshot command handlers
+
+use crate::args::{
+    CreateSnapshotArgs, ExportSnapshotArgs, ImportSnapshotArgs, RestoreModeArg, RestoreSnapshotArgs,
+};
+use crate::commands::SnapshotCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use colored::*;
+use locai::LocaiError;
+use locai::storage::models::{
+    Change, DiffLine, MemorySnapshot, RestoreAction, RestoreMode, RestorePreview, SnapshotBundle,
+};
+use locai::storage::traits::MemoryVersionStore;
+use serde_json::json;
+use std::io::BufRead;
+
+pub async fn handle_snapshot_command(
+    cmd: SnapshotCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    let store = ctx.memory_manager.memory_version_store().ok_or_else(|| {
+        LocaiError::Storage("Memory versioning is only supported with SharedStorage".to_string())
+    })?;
+
+    match cmd {
+        SnapshotCommands::Create(args) => create(args, store, output_format).await,
+        SnapshotCommands::Restore(args) => restore(args, store, output_format).await,
+        SnapshotCommands::Export(args) => export(args, store, output_format).await,
+        SnapshotCommands::Import(args) => import(args, store, output_format).await,
+    }
+}
+
+async fn create(
+    args: CreateSnapshotArgs,
+    store: &dyn MemoryVersionStore,
+    output_format: &str,
+) -> locai::Result<()> {
+    let memory_ids = (!args.memory_ids.is_empty()).then_some(args.memory_ids.as_slice());
+    let snapshot = store
+        .create_snapshot(memory_ids, None)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to create snapshot: {}", e)))?;
+
+    if output_format == "json" {
+        print_json(&snapshot);
+    } else {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Snapshot '{}' created with {} memories.",
+                snapshot.snapshot_id.color(CliColors::accent()),
+                snapshot.memory_count
+            ))
+        );
+    }
+    Ok(())
+}
+
+async fn restore(
+    args: RestoreSnapshotArgs,
+    store: &dyn MemoryVersionStore,
+    output_format: &str,
+) -> locai::Result<()> {
+    let snapshot = get_snapshot(store, &args.snapshot_id).await?;
+    let mode = restore_mode(args.mode);
+
+    let preview = store
+        .restore_snapshot_preview(&snapshot, mode.clone())
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to preview restore: {}", e)))?;
+    let changing = preview.entries.len() - preview.count(RestoreAction::Skip);
+
+    if output_format == "json" {
+        // Prompting would corrupt the JSON on stdout, so require --yes instead
+        if !args.dry_run && !args.yes && changing > 0 {
+            return Err(LocaiError::Other(
+                "Pass --yes to restore, or --dry-run to only preview".to_string(),
+            ));
+        }
+    } else {
+        print_restore_preview(&preview);
+    }
+
+    if args.dry_run || changing == 0 {
+        if output_format == "json" {
+            print_json(&json!({ "preview": preview, "restored": false }));
+        } else if changing == 0 {
+            outln!("{}", format_info("Nothing to restore."));
+        }
+        return Ok(());
+    }
+
+    if !args.yes {
+        outln!();
+        outln!("Type 'yes' to restore {} memories:", changing);
+        let mut input = String::new();
+        crate::invocation::stdin()
+            .read_line(&mut input)
+            .map_err(|e| LocaiError::Other(format!("Failed to read input: {}", e)))?;
+        if input.trim() != "yes" {
+            outln!("{}", format_info("Restore cancelled."));
+            return Ok(());
+        }
+    }
+
+    store
+        .restore_snapshot(&snapshot, mode)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to restore snapshot: {}", e)))?;
+
+    if output_format == "json" {
+        print_json(&json!({ "preview": preview, "restored": true }));
+    } else {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Restored {} memories from snapshot '{}'.",
+                changing,
+                snapshot.snapshot_id.color(CliColors::accent())
+            ))
+        );
+    }
+    Ok(())
+}
+
+async fn export(
+    args: ExportSnapshotArgs,
+    store: &dyn MemoryVersionStore,
+    output_format: &str,
+) -> locai::Result<()> {
+    let snapshot = get_snapshot(store, &args.snapshot_id).await?;
+    let bundle = store
+        .export_snapshot(&snapshot)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to export snapshot: {}", e)))?;
+
+    let json = serde_json::to_vec(&bundle)
+        .map_err(|e| LocaiError::Other(format!("Failed to serialize snapshot bundle: {}", e)))?;
+    std::fs::write(crate::invocation::resolve(&args.path), json)
+        .map_err(|e| LocaiError::Other(format!("Failed to write {}: {}", args.path, e)))?;
+
+    if output_format == "json" {
+        print_json(&json!({
+            "snapshot_id": snapshot.snapshot_id,
+            "path": args.path,
+            "memory_count": bundle.memories.len(),
+        }));
+    } else {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Exported {} memories from snapshot '{}' to {}.",
+                bundle.memories.len(),
+                snapshot.snapshot_id.color(CliColors::accent()),
+                args.path
+            ))
+        );
+        let missing = snapshot.memory_count.saturating_sub(bundle.memories.len());
+        if missing > 0 {
+            outln!(
+                "{}",
+                format_warning(&format!(
+                    "{} memories were deleted since the snapshot and were left out.",
+                    missing
+                ))
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn import(
+    args: ImportSnapshotArgs,
+    store: &dyn MemoryVersionStore,
+    output_format: &str,
+) -> locai::Result<()> {
+    let json = std::fs::read(crate::invocation::resolve(&args.path))
+        .map_err(|e| LocaiError::Other(format!("Failed to read {}: {}", args.path, e)))?;
+    let bundle: SnapshotBundle = serde_json::from_slice(&json)
+        .map_err(|e| LocaiError::Other(format!("Failed to parse snapshot bundle: {}", e)))?;
+
+    let snapshot = store
+        .import_snapshot(&bundle, restore_mode(args.mode))
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to import snapshot: {}", e)))?;
+
+    if output_format == "json" {
+        print_json(&snapshot);
+    } else {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Imported {} of {} memories as snapshot '{}'.",
+                snapshot.memory_count,
+                bundle.memories.len(),
+                snapshot.snapshot_id.color(CliColors::accent())
+            ))
+        );
+    }
+    Ok(())
+}
+
+async fn get_snapshot(
+    store: &dyn MemoryVersionStore,
+    snapshot_id: &str,
+) -> locai::Result<MemorySnapshot> {
+    store
+        .get_snapshot(snapshot_id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get snapshot: {}", e)))?
+        .ok_or_else(|| LocaiError::Storage(format!("Snapshot '{}' not found", snapshot_id)))
+}
+
+fn restore_mode(mode: RestoreModeArg) -> RestoreMode {
+    match mode {
+        RestoreModeArg::Overwrite => RestoreMode::Overwrite,
+        RestoreModeArg::SkipExisting => RestoreMode::SkipExisting,
+        RestoreModeArg::CreateVersions => RestoreMode::CreateVersions,
+    }
+}
+
+fn print_restore_preview(preview: &RestorePreview) {
+    outln!(
+        "{}",
+        format_info(&format!(
+            "Restoring snapshot '{}' would overwrite {}, add versions to {} and skip {} memories:",
+            preview.snapshot_id,
+            preview.count(RestoreAction::Overwrite),
+            preview.count(RestoreAction::CreateVersion),
+            preview.count(RestoreAction::Skip)
+        ))
+    );
+
+    for entry in &preview.entries {
+        outln!();
+        let action = match entry.action {
+            RestoreAction::Overwrite => "overwrite".color(CliColors::warning()),
+            RestoreAction::CreateVersion => "new version".color(CliColors::info()),
+            RestoreAction::Skip => "skip".color(CliColors::muted()),
+        };
+        outln!(
+            "{} {}",
+            format!("[{}]", action).bold(),
+            entry.memory_id.color(CliColors::accent())
+        );
+
+        if let Some(reason) = &entry.skip_reason {
+            outln!("  {}", reason.color(CliColors::muted()));
+        } else if entry.changes.is_empty() {
+            outln!("  {}", "Content unchanged".color(CliColors::muted()));
+        }
+
+        for change in &entry.changes {
+            let Change::ContentChanged { diff_hunks, .. } = change else {
+                continue;
+            };
+            for hunk in diff_hunks {
+                for line in &hunk.lines {
+                    match line {
+                        DiffLine::Removed(text) => {
+                            outln!("  {}", format!("- {}", text).color(CliColors::error()))
+                        }
+                        DiffLine::Added(text) => {
+                            outln!("  {}", format!("+ {}", text).color(CliColors::success()))
+                        }
+                        DiffLine::Context(text) => {
+                            outln!("  {}", format!("  {}", text).color(CliColors::muted()))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Task command handlers
+
+use crate::args::{AddTaskArgs, ListTasksArgs};
+use crate::commands::TaskCommands;
+use crate::context::LocaiCliContext;
+use crate::output::*;
+use crate::utils::parse_when;
+use chrono::Utc;
+use colored::{ColoredString, Colorize};
+use locai::LocaiError;
+use locai::memory::{Task, TaskQuery, TaskStatus};
+use serde_json::json;
+
+pub async fn handle_task_command(
+    cmd: TaskCommands,
+    ctx: &LocaiCliContext,
+    output_format: &str,
+) -> locai::Result<()> {
+    match cmd {
+        TaskCommands::Add(args) => {
+            let depends_on = args.depends_on.clone();
+            let task = ctx.memory_manager.create_task(build(args)?).await?;
+            for dependency in &depends_on {
+                ctx.memory_manager
+                    .add_task_dependency(&task.id, dependency)
+                    .await?;
+            }
+
+            if output_format == "json" {
+                print_json(&task);
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Task '{}' created with ID {}.",
+                        task.title,
+                        task.id.color(CliColors::accent())
+                    ))
+                );
+            }
+        }
+
+        TaskCommands::List(args) => {
+            let tasks = ctx.memory_manager.list_tasks(&query(args)?).await?;
+
+            if output_format == "json" {
+                print_json(&tasks);
+            } else if tasks.is_empty() {
+                outln!("{}", format_info("No tasks found."));
+            } else {
+                outln!(
+                    "{:<38} {:<12} {:<16} {:<12} {}",
+                    "ID".color(CliColors::muted()).bold(),
+                    "Status".color(CliColors::muted()).bold(),
+                    "Assignee".color(CliColors::muted()).bold(),
+                    "Due".color(CliColors::muted()).bold(),
+                    "Title".color(CliColors::muted()).bold()
+                );
+                outln!("{}", "─".repeat(100).color(CliColors::muted()));
+
+                let now = Utc::now();
+                for task in tasks {
+                    let due = task
+                        .due_at
+                        .map(|due_at| due_at.format("%Y-%m-%d").to_string())
+                        .unwrap_or_else(|| "-".to_string());
+                    outln!(
+                        "{:<38} {:<12} {:<16} {:<12} {}",
+                        task.id.color(CliColors::accent()),
+                        format_status(task.status),
+                        task.assignee.as_deref().unwrap_or("-"),
+                        if task.is_overdue(now) {
+                            due.color(CliColors::error())
+                        } else {
+                            due.normal()
+                        },
+                        task.title
+                    );
+                }
+            }
+        }
+
+        TaskCommands::Show(args) => {
+            let task = find(ctx, &args.id).await?;
+            let dependencies = ctx.memory_manager.task_dependencies(&task.id).await?;
+
+            if output_format == "json" {
+                print_json(&json!({ "task": task, "depends_on": dependencies }));
+            } else {
+                print_task(&task, &dependencies);
+            }
+        }
+
+        TaskCommands::Start(args) => {
+            set_status(ctx, &args.id, TaskStatus::InProgress, output_format).await?;
+        }
+
+        TaskCommands::Done(args) => {
+            set_status(ctx, &args.id, TaskStatus::Done, output_format).await?;
+        }
+
+        TaskCommands::Cancel(args) => {
+            set_status(ctx, &args.id, TaskStatus::Cancelled, output_format).await?;
+        }
+
+        TaskCommands::Reopen(args) => {
+            set_status(ctx, &args.id, TaskStatus::Open, output_format).await?;
+        }
+
+        TaskCommands::Assign(args) => {
+            let task = ctx
+                .memory_manager
+                .assign_task(&args.id, args.assignee.as_deref())
+                .await?
+                .ok_or_else(|| not_found(&args.id))?;
+
+            if output_format == "json" {
+                print_json(&task);
+            } else {
+                let message = match &task.assignee {
+                    Some(assignee) => format!("Task '{}' assigned to {}.", task.title, assignee),
+                    None => format!("Task '{}' unassigned.", task.title),
+                };
+                outln!("{}", format_success(&message));
+            }
+        }
+
+        TaskCommands::Due(args) => {
+            let due_at = args
+                .due
+                .as_deref()
+                .map(|due| parse_when(due, Utc::now()))
+                .transpose()?;
+            let task = ctx
+                .memory_manager
+                .set_task_due(&args.id, due_at)
+                .await?
+                .ok_or_else(|| not_found(&args.id))?;
+
+            if output_format == "json" {
+                print_json(&task);
+            } else {
+                let message = match task.due_at {
+                    Some(due_at) => format!(
+                        "Task '{}' due {}.",
+                        task.title,
+                        due_at.format("%Y-%m-%d %H:%M:%S UTC")
+                    ),
+                    None => format!("Task '{}' no longer has a due date.", task.title),
+                };
+                outln!("{}", format_success(&message));
+            }
+        }
+
+        TaskCommands::Depend(args) if args.remove => {
+            let removed = ctx
+                .memory_manager
+                .remove_task_dependency(&args.id, &args.depends_on)
+                .await?;
+            if output_format == "json" {
+                print_json(&json!({
+                    "id": args.id,
+                    "depends_on": args.depends_on,
+                    "removed": removed
+                }));
+            } else if removed {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Task '{}' no longer waits on '{}'.",
+                        args.id.color(CliColors::accent()),
+                        args.depends_on
+                    ))
+                );
+            } else {
+                outln!(
+                    "{}",
+                    format_info(&format!(
+                        "Task '{}' didn't wait on '{}'.",
+                        args.id, args.depends_on
+                    ))
+                );
+            }
+        }
+
+        TaskCommands::Depend(args) => {
+            let added = ctx
+                .memory_manager
+                .add_task_dependency(&args.id, &args.depends_on)
+                .await?;
+            if output_format == "json" {
+                print_json(&json!({
+                    "id": args.id,
+                    "depends_on": args.depends_on,
+                    "added": added
+                }));
+            } else {
+                outln!(
+                    "{}",
+                    format_success(&format!(
+                        "Task '{}' waits on '{}'.",
+                        args.id.color(CliColors::accent()),
+                        args.depends_on
+                    ))
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn find(ctx: &LocaiCliContext, id: &str) -> locai::Result<Task> {
+    ctx.memory_manager
+        .get_task(id)
+        .await?
+        .ok_or_else(|| not_found(id))
+}
+
+fn not_found(id: &str) -> LocaiError {
+    LocaiError::Task(format!("Task '{}' not found", id))
+}
+
+async fn set_status(
+    ctx: &LocaiCliContext,
+    id: &str,
+    status: TaskStatus,
+    output_format: &str,
+) -> locai::Result<()> {
+    let task = ctx
+        .memory_manager
+        .set_task_status(id, status)
+        .await?
+        .ok_or_else(|| not_found(id))?;
+
+    if output_format == "json" {
+        print_json(&task);
+    } else {
+        outln!(
+            "{}",
+            format_success(&format!(
+                "Task '{}' is now {}.",
+                task.title,
+                format_status(task.status)
+            ))
+        );
+    }
+    Ok(())
+}
+
+/// The task described by `add` arguments
+fn build(args: AddTaskArgs) -> locai::Result<Task> {
+    let mut task = Task::new(args.title);
+    task.assignee = args.assignee;
+    task.due_at = args
+        .due
+        .as_deref()
+        .map(|due| parse_when(due, Utc::now()))
+        .transpose()?;
+    task.tags = args.tags;
+    Ok(task)
+}
+
+/// The query described by `list` arguments
+fn query(args: ListTasksArgs) -> locai::Result<TaskQuery> {
+    let mut query = if args.all {
+        TaskQuery::default()
+    } else {
+        TaskQuery::open()
+    };
+    if !args.status.is_empty() {
+        query.statuses = args
+            .status
+            .iter()
+            .map(|status| status.parse())
+            .collect::<locai::Result<_>>()?;
+    }
+    query.assignee = args.assignee;
+    if args.overdue {
+        query = query.due_before(Utc::now());
+    }
+    Ok(query)
+}
+
+fn format_status(status: TaskStatus) -> ColoredString {
+    let label = status.to_string();
+    match status {
+        TaskStatus::Open => label.color(CliColors::info()),
+        TaskStatus::InProgress => label.color(CliColors::warning()),
+        TaskStatus::Done => label.color(CliColors::success()),
+        TaskStatus::Cancelled => label.color(CliColors::muted()),
+    }
+}
+
+fn print_task(task: &Task, dependencies: &[Task]) {
+    outln!(
+        "{}",
+        "━━━ Task Details ━━━".color(CliColors::accent()).bold()
+    );
+    outln!(
+        "{}: {}",
+        "Title".color(CliColors::muted()),
+        task.title.bold()
+    );
+    outln!(
+        "{}: {}",
+        "ID".color(CliColors::muted()),
+        task.id.color(CliColors::primary())
+    );
+    outln!(
+        "{}: {}",
+        "Status".color(CliColors::muted()),
+        format_status(task.status)
+    );
+    if let Some(assignee) = &task.assignee {
+        outln!("{}: {}", "Assignee".color(CliColors::muted()), assignee);
+    }
+    if let Some(due_at) = task.due_at {
+        let due = due_at.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+        outln!(
+            "{}: {}",
+            "Due".color(CliColors::muted()),
+            if task.is_overdue(Utc::now()) {
+                format!("{} (overdue)", due).color(CliColors::error())
+            } else {
+                due.normal()
+            }
+        );
+    }
+    if let Some(closed_at) = task.closed_at {
+        outln!(
+            "{}: {}",
+            "Closed".color(CliColors::muted()),
+            closed_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+    }
+    if !task.tags.is_empty() {
+        outln!(
+            "{}: {}",
+            "Tags".color(CliColors::muted()),
+            task.tags.join(", ")
+        );
+    }
+    if !dependencies.is_empty() {
+        outln!("{}:", "Waits on".color(CliColors::muted()));
+        for dependency in dependencies {
+            outln!(
+                "  {} {} {}",
+                format_status(dependency.status),
+                dependency.id.color(CliColors::accent()),
+                dependency.title
+            );
+        }
+    }
+}
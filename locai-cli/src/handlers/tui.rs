@@ -0,0 +1,27 @@
+//! TUI command handler
+
+use crate::args::TuiArgs;
+use crate::context::LocaiCliContext;
+use locai::LocaiError;
+
+/// Run the terminal dashboard until the user quits
+pub async fn handle_tui_command(args: TuiArgs, ctx: &LocaiCliContext) -> locai::Result<()> {
+    #[cfg(feature = "tui")]
+    {
+        use is_terminal::IsTerminal;
+
+        if !std::io::stdout().is_terminal() {
+            return Err(LocaiError::Other(
+                "The dashboard needs an interactive terminal".to_string(),
+            ));
+        }
+        crate::tui::run(ctx, args.limit).await
+    }
+    #[cfg(not(feature = "tui"))]
+    {
+        let _ = (args, ctx);
+        Err(LocaiError::FeatureNotEnabled {
+            feature: "tui".to_string(),
+        })
+    }
+}
@@ -5,7 +5,7 @@ use crate::context::LocaiCliContext;
 use crate::output::*;
 use colored::Colorize;
 use locai::LocaiError;
-use std::io::{self, Write};
+use std::io::{BufRead, Write};
 
 pub async fn handle_tutorial_command(
     args: TutorialArgs,
@@ -13,25 +13,25 @@ pub async fn handle_tutorial_command(
     _output_format: &str,
 ) -> locai::Result<()> {
     if !args.examples_only {
-        println!(
+        outln!(
             "{}",
             "━━━ Welcome to Locai Tutorial ━━━"
                 .color(CliColors::accent())
                 .bold()
         );
-        println!();
-        println!("This interactive tutorial will help you learn Locai step by step.");
-        println!("You'll create sample data and see how concepts work together.");
-        println!();
-        print!("Press Enter to continue, or Ctrl+C to exit...");
-        io::stdout()
+        outln!();
+        outln!("This interactive tutorial will help you learn Locai step by step.");
+        outln!("You'll create sample data and see how concepts work together.");
+        outln!();
+        out!("Press Enter to continue, or Ctrl+C to exit...");
+        crate::invocation::stdout()
             .flush()
             .map_err(|e| LocaiError::Other(format!("Failed to flush stdout: {}", e)))?;
         let mut input = String::new();
-        io::stdin()
+        crate::invocation::stdin()
             .read_line(&mut input)
             .map_err(|e| LocaiError::Other(format!("Failed to read input: {}", e)))?;
-        println!();
+        outln!();
     }
 
     let topic = args.topic.to_lowercase();
@@ -58,7 +58,7 @@ pub async fn handle_tutorial_command(
             run_graph_tutorial(ctx, args.examples_only).await?;
         }
         _ => {
-            println!(
+            outln!(
                 "{}",
                 format_error(&format!(
                     "Unknown topic: {}. Use: memory, entity, relationship, graph, or all",
@@ -70,47 +70,47 @@ pub async fn handle_tutorial_command(
     }
 
     if !args.examples_only {
-        println!();
-        println!(
+        outln!();
+        outln!(
             "{}",
             "━━━ Tutorial Complete! ━━━"
                 .color(CliColors::accent())
                 .bold()
         );
-        println!();
-        println!("Next steps:");
-        println!("  • Try: locai-cli memory add \"your content\"");
-        println!("  • Try: locai-cli memory search \"your query\"");
-        println!("  • Explore: locai-cli --help");
+        outln!();
+        outln!("Next steps:");
+        outln!("  • Try: locai-cli memory add \"your content\"");
+        outln!("  • Try: locai-cli memory search \"your query\"");
+        outln!("  • Explore: locai-cli --help");
     }
 
     Ok(())
 }
 
 fn wait_for_continue() -> locai::Result<()> {
-    println!();
-    print!("Press Enter to continue...");
-    io::stdout()
+    outln!();
+    out!("Press Enter to continue...");
+    crate::invocation::stdout()
         .flush()
         .map_err(|e| LocaiError::Other(format!("Failed to flush stdout: {}", e)))?;
     let mut input = String::new();
-    io::stdin()
+    crate::invocation::stdin()
         .read_line(&mut input)
         .map_err(|e| LocaiError::Other(format!("Failed to read input: {}", e)))?;
-    println!();
+    outln!();
     Ok(())
 }
 
 async fn run_memory_tutorial(ctx: &LocaiCliContext, examples_only: bool) -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Lesson 1: What is a Memory? ━━━"
             .color(CliColors::accent())
             .bold()
     );
-    println!();
-    println!("A memory is a piece of information stored in Locai. Let's create one:");
-    println!();
+    outln!();
+    outln!("A memory is a piece of information stored in Locai. Let's create one:");
+    outln!();
 
     let memory_id = ctx
         .memory_manager
@@ -124,22 +124,22 @@ async fn run_memory_tutorial(ctx: &LocaiCliContext, examples_only: bool) -> loca
         )
         .await?;
 
-    println!(
+    outln!(
         "{}",
         format_success(&format!(
             "✓ Created: {}",
             memory_id.color(CliColors::accent())
         ))
     );
-    println!("  Content: \"Locai is a memory management system for AI agents\"");
-    println!();
+    outln!("  Content: \"Locai is a memory management system for AI agents\"");
+    outln!();
 
     if !examples_only {
         wait_for_continue()?;
     }
 
-    println!("Now let's search for it:");
-    println!();
+    outln!("Now let's search for it:");
+    outln!();
 
     let results = ctx
         .memory_manager
@@ -152,25 +152,25 @@ async fn run_memory_tutorial(ctx: &LocaiCliContext, examples_only: bool) -> loca
         .await?;
 
     if !results.is_empty() {
-        println!(
+        outln!(
             "{}",
             format_success(&format!("✓ Found {} memory:", results.len()))
         );
         for (i, result) in results.iter().take(3).enumerate() {
-            println!("  {}. {}", i + 1, result.memory.content);
+            outln!("  {}. {}", i + 1, result.memory.content);
         }
     }
 
-    println!();
-    println!(
+    outln!();
+    outln!(
         "{}",
         "━━━ Lesson 2: Memory Types ━━━"
             .color(CliColors::accent())
             .bold()
     );
-    println!();
-    println!("Memories can have different types. Let's create a few examples:");
-    println!();
+    outln!();
+    outln!("Memories can have different types. Let's create a few examples:");
+    outln!();
 
     let fact_id = ctx
         .memory_manager
@@ -178,7 +178,7 @@ async fn run_memory_tutorial(ctx: &LocaiCliContext, examples_only: bool) -> loca
             builder.memory_type(locai::models::MemoryType::Fact)
         })
         .await?;
-    println!(
+    outln!(
         "{}",
         format_success(&format!(
             "✓ Created fact memory: {}",
@@ -192,7 +192,7 @@ async fn run_memory_tutorial(ctx: &LocaiCliContext, examples_only: bool) -> loca
             builder.memory_type(locai::models::MemoryType::Episodic)
         })
         .await?;
-    println!(
+    outln!(
         "{}",
         format_success(&format!(
             "✓ Created episodic memory: {}",
@@ -204,15 +204,15 @@ async fn run_memory_tutorial(ctx: &LocaiCliContext, examples_only: bool) -> loca
 }
 
 async fn run_entity_tutorial(ctx: &LocaiCliContext, _examples_only: bool) -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Lesson 3: Entities ━━━"
             .color(CliColors::accent())
             .bold()
     );
-    println!();
-    println!("Locai automatically extracts entities from memories. Let's create an entity:");
-    println!();
+    outln!();
+    outln!("Locai automatically extracts entities from memories. Let's create an entity:");
+    outln!();
 
     let entity = locai::storage::models::Entity {
         id: "entity:tutorial:locai".to_string(),
@@ -223,15 +223,15 @@ async fn run_entity_tutorial(ctx: &LocaiCliContext, _examples_only: bool) -> loc
     };
 
     let created = ctx.memory_manager.create_entity(entity).await?;
-    println!(
+    outln!(
         "{}",
         format_success(&format!(
             "✓ Created entity: {}",
             created.id.color(CliColors::accent())
         ))
     );
-    println!("  Type: Organization");
-    println!("  Name: Locai");
+    outln!("  Type: Organization");
+    outln!("  Name: Locai");
 
     Ok(())
 }
@@ -240,15 +240,15 @@ async fn run_relationship_tutorial(
     ctx: &LocaiCliContext,
     _examples_only: bool,
 ) -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Lesson 4: Relationships ━━━"
             .color(CliColors::accent())
             .bold()
     );
-    println!();
-    println!("Relationships connect memories and entities. Let's create one:");
-    println!();
+    outln!();
+    outln!("Relationships connect memories and entities. Let's create one:");
+    outln!();
 
     let memories = ctx
         .memory_manager
@@ -268,7 +268,7 @@ async fn run_relationship_tutorial(
             .create_relationship(source_id, target_id, "related_to")
             .await?;
 
-        println!(
+        outln!(
             "{}",
             format_success(&format!(
                 "✓ Created relationship: {} → related_to → {}",
@@ -282,15 +282,15 @@ async fn run_relationship_tutorial(
 }
 
 async fn run_graph_tutorial(ctx: &LocaiCliContext, _examples_only: bool) -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Lesson 5: Graph Visualization ━━━"
             .color(CliColors::accent())
             .bold()
     );
-    println!();
-    println!("Let's see how memories connect:");
-    println!();
+    outln!();
+    outln!("Let's see how memories connect:");
+    outln!();
 
     let memories = ctx
         .memory_manager
@@ -304,15 +304,15 @@ async fn run_graph_tutorial(ctx: &LocaiCliContext, _examples_only: bool) -> loca
 
     if let Some(memory) = memories.first() {
         let graph = ctx.memory_manager.get_memory_graph(&memory.id, 1).await?;
-        println!(
+        outln!(
             "{}",
             format_success(&format!(
                 "✓ Graph created for memory: {}",
                 memory.id[..8].color(CliColors::accent())
             ))
         );
-        println!("  - {} memories", graph.memories.len());
-        println!("  - {} relationships", graph.relationships.len());
+        outln!("  - {} memories", graph.memories.len());
+        outln!("  - {} relationships", graph.relationships.len());
     }
 
     Ok(())
@@ -19,201 +19,203 @@ pub fn show_explanation(concept: &str) -> locai::Result<()> {
 }
 
 fn show_memory_explanation() -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Memory Concept ━━━".color(CliColors::accent()).bold()
     );
-    println!();
-    println!("{}", "What is a Memory?".white().bold());
-    println!();
-    println!("A memory is a piece of information stored in Locai. It can represent:");
-    println!("  • Facts - Objective information (e.g., 'Paris is the capital of France')");
-    println!("  • Episodes - Specific events or experiences");
-    println!("  • Conversations - Dialogues or exchanges");
-    println!("  • Procedural knowledge - How to do something");
-    println!();
-    println!("{}", "Key Features:".bold());
-    println!("  • Each memory has a unique ID");
-    println!("  • Can be tagged for organization");
-    println!("  • Has a priority level (Critical, High, Normal, Low)");
-    println!("  • Can be linked to other memories via relationships");
-    println!("  • Supports semantic search");
-    println!();
-    println!("{}", "Common Commands:".bold());
-    println!("  locai-cli memory add \"Content\"     # Create a new memory");
-    println!("  locai-cli memory search \"query\"    # Search memories semantically");
-    println!("  locai-cli memory list               # List all memories");
-    println!("  locai-cli memory get <id>           # Get a specific memory");
-    println!();
+    outln!();
+    outln!("{}", "What is a Memory?".white().bold());
+    outln!();
+    outln!("A memory is a piece of information stored in Locai. It can represent:");
+    outln!("  • Facts - Objective information (e.g., 'Paris is the capital of France')");
+    outln!("  • Episodes - Specific events or experiences");
+    outln!("  • Conversations - Dialogues or exchanges");
+    outln!("  • Procedural knowledge - How to do something");
+    outln!();
+    outln!("{}", "Key Features:".bold());
+    outln!("  • Each memory has a unique ID");
+    outln!("  • Can be tagged for organization");
+    outln!("  • Has a priority level (Critical, High, Normal, Low)");
+    outln!("  • Can be linked to other memories via relationships");
+    outln!("  • Supports semantic search");
+    outln!();
+    outln!("{}", "Common Commands:".bold());
+    outln!("  locai-cli memory add \"Content\"     # Create a new memory");
+    outln!("  locai-cli memory search \"query\"    # Search memories semantically");
+    outln!("  locai-cli memory list               # List all memories");
+    outln!("  locai-cli memory get <id>           # Get a specific memory");
+    outln!();
     Ok(())
 }
 
 fn show_entity_explanation() -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Entity Concept ━━━".color(CliColors::entity()).bold()
     );
-    println!();
-    println!("{}", "What is an Entity?".bold());
-    println!();
-    println!("An entity represents a real-world object, person, place, or concept that");
-    println!("can be extracted from memories. Examples:");
-    println!("  • People: 'Alice', 'Bob'");
-    println!("  • Places: 'Paris', 'New York'");
-    println!("  • Organizations: 'Acme Corp', 'MIT'");
-    println!("  • Concepts: 'Machine Learning', 'Quantum Physics'");
-    println!();
-    println!("{}", "Key Features:".bold());
-    println!("  • Automatically extracted from memory content");
-    println!("  • Can have custom properties");
-    println!("  • Can be linked to other entities via relationships");
-    println!("  • Helps connect related memories");
-    println!();
-    println!("{}", "Common Commands:".bold());
-    println!("  locai-cli entity list               # List all entities");
-    println!("  locai-cli entity get <id>           # Get a specific entity");
-    println!("  locai-cli entity search \"query\"     # Search entities");
-    println!();
+    outln!();
+    outln!("{}", "What is an Entity?".bold());
+    outln!();
+    outln!("An entity represents a real-world object, person, place, or concept that");
+    outln!("can be extracted from memories. Examples:");
+    outln!("  • People: 'Alice', 'Bob'");
+    outln!("  • Places: 'Paris', 'New York'");
+    outln!("  • Organizations: 'Acme Corp', 'MIT'");
+    outln!("  • Concepts: 'Machine Learning', 'Quantum Physics'");
+    outln!();
+    outln!("{}", "Key Features:".bold());
+    outln!("  • Automatically extracted from memory content");
+    outln!("  • Can have custom properties");
+    outln!("  • Can be linked to other entities via relationships");
+    outln!("  • Helps connect related memories");
+    outln!();
+    outln!("{}", "Common Commands:".bold());
+    outln!("  locai-cli entity list               # List all entities");
+    outln!("  locai-cli entity get <id>           # Get a specific entity");
+    outln!("  locai-cli entity search \"query\"     # Search entities");
+    outln!();
     Ok(())
 }
 
 fn show_relationship_explanation() -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Relationship Concept ━━━"
             .color(CliColors::info())
             .bold()
     );
-    println!();
-    println!("{}", "What is a Relationship?".bold());
-    println!();
-    println!("A relationship connects two memories or entities, creating a graph structure.");
-    println!("Relationships can be:");
-    println!("  • Memory-to-Memory: Direct connections between memories");
-    println!("  • Entity-to-Entity: Connections between entities");
-    println!("  • Memory-to-Entity: Memories containing entities");
-    println!();
-    println!("{}", "Common Relationship Types:".bold());
-    println!("  • related_to - General connection");
-    println!("  • temporal_sequence - Time-based ordering");
-    println!("  • causes - Causal relationship");
-    println!("  • contains - Containment relationship");
-    println!("  • Custom types - You can define your own");
-    println!();
-    println!("{}", "Key Features:".bold());
-    println!("  • Relationships enable graph traversal");
-    println!("  • Can have metadata/properties");
-    println!("  • Support bidirectional and symmetric relationships");
-    println!("  • Enable finding paths between memories");
-    println!();
-    println!("{}", "Common Commands:".bold());
-    println!("  locai-cli relationship create <source> <target> <type>");
-    println!("  locai-cli relationship list         # List all relationships");
-    println!("  locai-cli relationship delete <id>  # Delete a relationship");
-    println!();
+    outln!();
+    outln!("{}", "What is a Relationship?".bold());
+    outln!();
+    outln!("A relationship connects two memories or entities, creating a graph structure.");
+    outln!("Relationships can be:");
+    outln!("  • Memory-to-Memory: Direct connections between memories");
+    outln!("  • Entity-to-Entity: Connections between entities");
+    outln!("  • Memory-to-Entity: Memories containing entities");
+    outln!();
+    outln!("{}", "Common Relationship Types:".bold());
+    outln!("  • related_to - General connection");
+    outln!("  • temporal_sequence - Time-based ordering");
+    outln!("  • causes - Causal relationship");
+    outln!("  • contains - Containment relationship");
+    outln!("  • Custom types - You can define your own");
+    outln!();
+    outln!("{}", "Key Features:".bold());
+    outln!("  • Relationships enable graph traversal");
+    outln!("  • Can have metadata/properties");
+    outln!("  • Support bidirectional and symmetric relationships");
+    outln!("  • Enable finding paths between memories");
+    outln!();
+    outln!("{}", "Common Commands:".bold());
+    outln!("  locai-cli relationship create <source> <target> <type>");
+    outln!("  locai-cli relationship list         # List all relationships");
+    outln!("  locai-cli relationship delete <id>  # Delete a relationship");
+    outln!();
     Ok(())
 }
 
 fn show_graph_explanation() -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Graph Concept ━━━".color(CliColors::accent()).bold()
     );
-    println!();
-    println!("{}", "What is the Graph?".bold());
-    println!();
-    println!("The graph is the network of memories and entities connected by relationships.");
-    println!("It enables powerful operations like:");
-    println!("  • Finding connected memories");
-    println!("  • Discovering paths between memories");
-    println!("  • Identifying central/important memories");
-    println!("  • Analyzing graph structure and metrics");
-    println!();
-    println!("{}", "Graph Operations:".bold());
-    println!("  • Subgraph - Get memories connected to a specific memory");
-    println!("  • Paths - Find paths between two memories");
-    println!("  • Metrics - Analyze graph statistics");
-    println!("  • Query - Search for patterns (connected, isolated, etc.)");
-    println!("  • Central - Find most important/central memories");
-    println!();
-    println!("{}", "Common Commands:".bold());
-    println!("  locai-cli graph subgraph <id>       # Get connected memories");
-    println!("  locai-cli graph paths <id1> <id2>   # Find paths between memories");
-    println!("  locai-cli graph metrics              # View graph statistics");
-    println!("  locai-cli graph query \"connected\"   # Query graph patterns");
-    println!();
+    outln!();
+    outln!("{}", "What is the Graph?".bold());
+    outln!();
+    outln!("The graph is the network of memories and entities connected by relationships.");
+    outln!("It enables powerful operations like:");
+    outln!("  • Finding connected memories");
+    outln!("  • Discovering paths between memories");
+    outln!("  • Identifying central/important memories");
+    outln!("  • Analyzing graph structure and metrics");
+    outln!();
+    outln!("{}", "Graph Operations:".bold());
+    outln!("  • Subgraph - Get memories connected to a specific memory");
+    outln!("  • Show - Draw the graph around a memory or entity as a tree or Mermaid diagram");
+    outln!("  • Paths - Find paths between two memories");
+    outln!("  • Metrics - Analyze graph statistics");
+    outln!("  • Query - Search for patterns (connected, isolated, etc.)");
+    outln!("  • Central - Find most important/central memories");
+    outln!();
+    outln!("{}", "Common Commands:".bold());
+    outln!("  locai-cli graph subgraph <id>       # Get connected memories");
+    outln!("  locai-cli graph paths <id1> <id2>   # Find paths between memories");
+    outln!("  locai-cli graph show <id> --format mermaid  # Mermaid diagram for docs");
+    outln!("  locai-cli graph metrics              # View graph statistics");
+    outln!("  locai-cli graph query \"connected\"   # Query graph patterns");
+    outln!();
     Ok(())
 }
 
 fn show_search_explanation() -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Search Concept ━━━".color(CliColors::info()).bold()
     );
-    println!();
-    println!("{}", "How Does Search Work?".bold());
-    println!();
-    println!("Locai supports semantic search, which understands the meaning of your");
-    println!("query, not just exact keyword matches.");
-    println!();
-    println!("{}", "Search Types:".bold());
-    println!("  • Hybrid (default) - Automatically combines text and semantic search");
-    println!("  • Text - BM25 keyword search - always available, fast");
-    println!("  • Semantic - Vector similarity search - finds related concepts");
-    println!();
-    println!("{}", "Search Features:".bold());
-    println!("  • Understands synonyms and related concepts");
-    println!("  • Ranks results by relevance");
-    println!("  • Can filter by type, priority, tags");
-    println!("  • Supports time-based filtering");
-    println!();
-    println!("{}", "Common Commands:".bold());
-    println!("  locai-cli memory search \"query\"     # Hybrid search (default)");
-    println!("  locai-cli memory search \"query\" --mode text     # Text search only");
-    println!("  locai-cli memory search \"query\" --mode semantic  # Semantic search only");
-    println!("  locai-cli memory search \"query\" --type fact     # Filter by type");
-    println!("  locai-cli memory search \"query\" --tags tag1,tag2 # Filter by tags");
-    println!();
+    outln!();
+    outln!("{}", "How Does Search Work?".bold());
+    outln!();
+    outln!("Locai supports semantic search, which understands the meaning of your");
+    outln!("query, not just exact keyword matches.");
+    outln!();
+    outln!("{}", "Search Types:".bold());
+    outln!("  • Hybrid (default) - Automatically combines text and semantic search");
+    outln!("  • Text - BM25 keyword search - always available, fast");
+    outln!("  • Semantic - Vector similarity search - finds related concepts");
+    outln!();
+    outln!("{}", "Search Features:".bold());
+    outln!("  • Understands synonyms and related concepts");
+    outln!("  • Ranks results by relevance");
+    outln!("  • Can filter by type, priority, tags");
+    outln!("  • Supports time-based filtering");
+    outln!();
+    outln!("{}", "Common Commands:".bold());
+    outln!("  locai-cli memory search \"query\"     # Hybrid search (default)");
+    outln!("  locai-cli memory search \"query\" --mode text     # Text search only");
+    outln!("  locai-cli memory search \"query\" --mode semantic  # Semantic search only");
+    outln!("  locai-cli memory search \"query\" --type fact     # Filter by type");
+    outln!("  locai-cli memory search \"query\" --tags tag1,tag2 # Filter by tags");
+    outln!();
     Ok(())
 }
 
 fn show_batch_explanation() -> locai::Result<()> {
-    println!(
+    outln!(
         "{}",
         "━━━ Batch Operations Concept ━━━"
             .color(CliColors::accent())
             .bold()
     );
-    println!();
-    println!("{}", "What are Batch Operations?".bold());
-    println!();
-    println!("Batch operations allow you to perform multiple Locai operations in a");
-    println!("single request, optionally as a transaction.");
-    println!();
-    println!("{}", "Key Features:".bold());
-    println!("  • Execute multiple operations atomically");
-    println!("  • All-or-nothing transaction support");
-    println!("  • Efficient for bulk imports/exports");
-    println!("  • Progress tracking for large batches");
-    println!();
-    println!("{}", "Operation Types:".bold());
-    println!("  • create_memory - Add new memories");
-    println!("  • update_memory - Update existing memories");
-    println!("  • delete_memory - Remove memories");
-    println!("  • create_relationship - Add relationships");
-    println!("  • create_entity - Add entities");
-    println!();
-    println!("{}", "Common Commands:".bold());
-    println!("  locai-cli batch execute batch.json   # Execute batch file");
-    println!("  locai-cli batch execute batch.json --transaction");
-    println!();
-    println!("{}", "Example Batch File:".bold());
-    println!("  {{");
-    println!("    \"operations\": [");
-    println!("      {{ \"operation\": \"create_memory\", \"content\": \"Memory 1\" }},");
-    println!("      {{ \"operation\": \"create_memory\", \"content\": \"Memory 2\" }}");
-    println!("    ]");
-    println!("  }}");
-    println!();
+    outln!();
+    outln!("{}", "What are Batch Operations?".bold());
+    outln!();
+    outln!("Batch operations allow you to perform multiple Locai operations in a");
+    outln!("single request, optionally as a transaction.");
+    outln!();
+    outln!("{}", "Key Features:".bold());
+    outln!("  • Execute multiple operations atomically");
+    outln!("  • All-or-nothing transaction support");
+    outln!("  • Efficient for bulk imports/exports");
+    outln!("  • Progress tracking for large batches");
+    outln!();
+    outln!("{}", "Operation Types:".bold());
+    outln!("  • create_memory - Add new memories");
+    outln!("  • update_memory - Update existing memories");
+    outln!("  • delete_memory - Remove memories");
+    outln!("  • create_relationship - Add relationships");
+    outln!("  • create_entity - Add entities");
+    outln!();
+    outln!("{}", "Common Commands:".bold());
+    outln!("  locai-cli batch execute batch.json   # Execute batch file");
+    outln!("  locai-cli batch execute batch.json --transaction");
+    outln!();
+    outln!("{}", "Example Batch File:".bold());
+    outln!("  {{");
+    outln!("    \"operations\": [");
+    outln!("      {{ \"operation\": \"create_memory\", \"content\": \"Memory 1\" }},");
+    outln!("      {{ \"operation\": \"create_memory\", \"content\": \"Memory 2\" }}");
+    outln!("    ]");
+    outln!("  }}");
+    outln!();
     Ok(())
 }
@@ -0,0 +1,133 @@
+//! ChatGPT data export parser
+//!
+//! The export zip contains `conversations.json`, an array of conversations.
+//! Messages are stored as a tree (`mapping`) because edited prompts and
+//! regenerated answers create branches; the branch ending at `current_node`
+//! is the one the user last saw, and is the one imported.
+
+use std::collections::HashSet;
+
+use locai::LocaiError;
+use serde_json::Value;
+
+use super::{ImportedConversation, ImportedMessage, from_unix_seconds};
+
+/// Name of the conversations file inside the export
+pub const CONVERSATIONS_FILE: &str = "conversations.json";
+
+/// Parse the contents of `conversations.json`
+pub fn parse_conversations(json: &str) -> locai::Result<Vec<ImportedConversation>> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| LocaiError::Other(format!("Invalid ChatGPT export: {}", e)))?;
+    let conversations = value.as_array().ok_or_else(|| {
+        LocaiError::Other("Invalid ChatGPT export: expected an array of conversations".to_string())
+    })?;
+
+    Ok(conversations
+        .iter()
+        .filter_map(parse_conversation)
+        .collect())
+}
+
+fn parse_conversation(conversation: &Value) -> Option<ImportedConversation> {
+    let id = conversation
+        .get("conversation_id")
+        .or_else(|| conversation.get("id"))
+        .and_then(Value::as_str)?
+        .to_string();
+    let mapping = conversation.get("mapping")?.as_object()?;
+
+    // Walk from the current node up to the root, then reverse
+    let mut node_ids = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = conversation
+        .get("current_node")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    while let Some(node_id) = current {
+        if !seen.insert(node_id.clone()) {
+            break;
+        }
+        let Some(node) = mapping.get(&node_id) else {
+            break;
+        };
+        node_ids.push(node_id);
+        current = node
+            .get("parent")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+    }
+    node_ids.reverse();
+
+    let messages = node_ids
+        .iter()
+        .filter_map(|node_id| mapping.get(node_id)?.get("message"))
+        .filter_map(parse_message)
+        .collect();
+
+    Some(ImportedConversation {
+        id,
+        title: conversation
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or("Untitled conversation")
+            .to_string(),
+        created_at: conversation
+            .get("create_time")
+            .and_then(Value::as_f64)
+            .and_then(from_unix_seconds),
+        messages,
+    })
+}
+
+fn parse_message(message: &Value) -> Option<ImportedMessage> {
+    let role = message.get("author")?.get("role")?.as_str()?;
+    // System prompts and tool calls are plumbing, not conversation
+    if role != "user" && role != "assistant" {
+        return None;
+    }
+
+    let metadata = message.get("metadata");
+    let hidden = metadata
+        .and_then(|m| m.get("is_visually_hidden_from_conversation"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if hidden {
+        return None;
+    }
+
+    // Non-text parts (images, files) are objects and are skipped
+    let content = message
+        .get("content")?
+        .get("parts")?
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    if content.is_empty() {
+        return None;
+    }
+
+    let participant = if role == "assistant" {
+        metadata
+            .and_then(|m| m.get("model_slug"))
+            .and_then(Value::as_str)
+            .unwrap_or("ChatGPT")
+            .to_string()
+    } else {
+        "user".to_string()
+    };
+
+    Some(ImportedMessage {
+        role: role.to_string(),
+        participant,
+        content,
+        created_at: message
+            .get("create_time")
+            .and_then(Value::as_f64)
+            .and_then(from_unix_seconds),
+    })
+}
@@ -0,0 +1,90 @@
+//! Claude data export parser
+//!
+//! The export contains `conversations.json`, an array of conversations each
+//! holding a flat `chat_messages` list. Newer exports split message text into
+//! typed `content` blocks; older ones only have a `text` field.
+
+use locai::LocaiError;
+use serde_json::Value;
+
+use super::{ImportedConversation, ImportedMessage, parse_timestamp};
+
+/// Name of the conversations file inside the export
+pub const CONVERSATIONS_FILE: &str = "conversations.json";
+
+/// Parse the contents of `conversations.json`
+pub fn parse_conversations(json: &str) -> locai::Result<Vec<ImportedConversation>> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| LocaiError::Other(format!("Invalid Claude export: {}", e)))?;
+    let conversations = value.as_array().ok_or_else(|| {
+        LocaiError::Other("Invalid Claude export: expected an array of conversations".to_string())
+    })?;
+
+    Ok(conversations
+        .iter()
+        .filter_map(parse_conversation)
+        .collect())
+}
+
+fn parse_conversation(conversation: &Value) -> Option<ImportedConversation> {
+    let id = conversation.get("uuid")?.as_str()?.to_string();
+    let title = conversation
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or("Untitled conversation")
+        .to_string();
+
+    let messages = conversation
+        .get("chat_messages")
+        .and_then(Value::as_array)
+        .map(|messages| messages.iter().filter_map(parse_message).collect())
+        .unwrap_or_default();
+
+    Some(ImportedConversation {
+        id,
+        title,
+        created_at: parse_timestamp(conversation.get("created_at")),
+        messages,
+    })
+}
+
+fn parse_message(message: &Value) -> Option<ImportedMessage> {
+    let (role, participant) = match message.get("sender")?.as_str()? {
+        "human" => ("user", "user"),
+        "assistant" => ("assistant", "Claude"),
+        _ => return None,
+    };
+
+    let blocks: Vec<&str> = message
+        .get("content")
+        .and_then(Value::as_array)
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+                .filter_map(|block| block.get("text").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default();
+    let content = if blocks.is_empty() {
+        message
+            .get("text")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string()
+    } else {
+        blocks.join("\n")
+    };
+    let content = content.trim().to_string();
+    if content.is_empty() {
+        return None;
+    }
+
+    Some(ImportedMessage {
+        role: role.to_string(),
+        participant: participant.to_string(),
+        content,
+        created_at: parse_timestamp(message.get("created_at")),
+    })
+}
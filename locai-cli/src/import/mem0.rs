@@ -0,0 +1,104 @@
+//! mem0 export parser
+//!
+//! mem0's `get_all()` returns `{"results": [...], "relations": [...]}`; older
+//! versions and the platform export return a bare array of memories. Each
+//! memory is an extracted fact scoped to a `user_id`, `agent_id` and/or
+//! `run_id`. With graph memory enabled, `relations` holds
+//! `{source, relationship, destination}` triples between named nodes.
+
+use locai::LocaiError;
+use locai::models::MemoryType;
+use serde_json::{Map, Value, json};
+
+use super::{ImportedData, ImportedMemory, ImportedRelation, parse_timestamp};
+
+/// Parse a mem0 export
+pub fn parse_export(json: &str) -> locai::Result<ImportedData> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| LocaiError::Other(format!("Invalid mem0 export: {}", e)))?;
+
+    let (memories, relations) = match &value {
+        Value::Array(memories) => (Some(memories), None),
+        Value::Object(object) => (
+            object
+                .get("results")
+                .or_else(|| object.get("memories"))
+                .and_then(Value::as_array),
+            object.get("relations").and_then(Value::as_array),
+        ),
+        _ => (None, None),
+    };
+    let memories = memories.ok_or_else(|| {
+        LocaiError::Other("Invalid mem0 export: expected a list of memories".to_string())
+    })?;
+
+    Ok(ImportedData {
+        conversations: Vec::new(),
+        memories: memories.iter().filter_map(parse_memory).collect(),
+        relations: relations
+            .map(|relations| relations.iter().filter_map(parse_relation).collect())
+            .unwrap_or_default(),
+    })
+}
+
+fn parse_memory(memory: &Value) -> Option<ImportedMemory> {
+    let id = memory.get("id")?.as_str()?.to_string();
+    let content = memory.get("memory")?.as_str()?.trim().to_string();
+    if content.is_empty() {
+        return None;
+    }
+
+    let mut tags: Vec<String> = memory
+        .get("categories")
+        .and_then(Value::as_array)
+        .map(|categories| {
+            categories
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut properties = Map::new();
+    for (scope, tag_prefix) in [("user_id", "user"), ("agent_id", "agent")] {
+        if let Some(scope_id) = memory.get(scope).and_then(Value::as_str) {
+            tags.push(format!("{}:{}", tag_prefix, scope_id));
+            properties.insert(scope.to_string(), json!(scope_id));
+        }
+    }
+    for key in ["hash", "metadata", "updated_at"] {
+        if let Some(value) = memory.get(key).filter(|v| !v.is_null()) {
+            properties.insert(key.to_string(), value.clone());
+        }
+    }
+
+    Some(ImportedMemory {
+        id,
+        content,
+        memory_type: MemoryType::Fact,
+        session_id: memory
+            .get("run_id")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        created_at: parse_timestamp(memory.get("created_at")),
+        tags,
+        properties,
+    })
+}
+
+fn parse_relation(relation: &Value) -> Option<ImportedRelation> {
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| relation.get(*key).and_then(Value::as_str))
+            .map(str::to_string)
+    };
+
+    Some(ImportedRelation {
+        source: text(&["source"])?,
+        source_type: text(&["source_type"]),
+        relationship: text(&["relationship", "relation"])?,
+        target: text(&["destination", "target"])?,
+        target_type: text(&["destination_type", "target_type"]),
+    })
+}
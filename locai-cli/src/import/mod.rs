@@ -0,0 +1,500 @@
+//! Importers for data exported from other tools
+//!
+//! Chat exports are parsed into [`ImportedConversation`]s, which
+//! [`import_conversations`] stores as conversation memories. Every conversation
+//! becomes a session entity, and each of its messages a memory linked to that
+//! session, so a whole chat can be pulled back with a graph query.
+//!
+//! Exports from other agent-memory systems (mem0, Zep) also carry standalone
+//! memories and knowledge-graph relations; they are parsed into
+//! [`ImportedData`] and stored by [`import_data`]. Note vaults are handled by
+//! [`obsidian`].
+
+pub mod chatgpt;
+pub mod claude;
+pub mod mem0;
+pub mod obsidian;
+pub mod zep;
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use locai::LocaiError;
+use locai::models::{MemoryBuilder, MemoryType};
+use locai::prelude::MemoryManager;
+use locai::storage::filters::{MemoryFilter, RelationshipFilter};
+use locai::storage::models::{Entity, Relationship};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+/// Entity type of the session entity created for each imported conversation
+pub const SESSION_ENTITY_TYPE: &str = "conversation_session";
+
+/// Relationship type linking a message memory to its session
+pub const SESSION_RELATIONSHIP_TYPE: &str = "part_of_session";
+
+/// A single message from an imported conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedMessage {
+    /// Author role as given by the export (user, assistant, ...)
+    pub role: String,
+
+    /// Display name of the author (a model name for assistant messages, if known)
+    pub participant: String,
+
+    /// Message text
+    pub content: String,
+
+    /// When the message was sent
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A conversation parsed from an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedConversation {
+    /// Conversation ID from the export
+    pub id: String,
+
+    /// Conversation title
+    pub title: String,
+
+    /// When the conversation started
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// Messages in the order they were sent
+    pub messages: Vec<ImportedMessage>,
+}
+
+impl ImportedConversation {
+    /// Distinct participants in order of first appearance
+    pub fn participants(&self) -> Vec<String> {
+        let mut participants: Vec<String> = Vec::new();
+        for message in &self.messages {
+            if !participants.contains(&message.participant) {
+                participants.push(message.participant.clone());
+            }
+        }
+        participants
+    }
+}
+
+/// A standalone memory (an extracted fact, a document, ...) from an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedMemory {
+    /// ID in the exporting system, used to skip memories imported before
+    pub id: String,
+
+    /// Memory text
+    pub content: String,
+
+    /// Locai memory type to store it as
+    pub memory_type: MemoryType,
+
+    /// Conversation the memory belongs to, if any
+    pub session_id: Option<String>,
+
+    /// When the memory was created in the exporting system
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// Tags to add besides the source tag
+    pub tags: Vec<String>,
+
+    /// Properties copied from the export
+    pub properties: Map<String, Value>,
+}
+
+/// A relation between two named nodes of an exported knowledge graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedRelation {
+    /// Name of the source node
+    pub source: String,
+
+    /// Type of the source node, if the export has one
+    pub source_type: Option<String>,
+
+    /// Relation name as exported (`works_at`, `LIKES`, ...)
+    pub relationship: String,
+
+    /// Name of the target node
+    pub target: String,
+
+    /// Type of the target node, if the export has one
+    pub target_type: Option<String>,
+}
+
+/// Everything parsed from an agent-memory export
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedData {
+    /// Conversations with their messages
+    pub conversations: Vec<ImportedConversation>,
+
+    /// Standalone memories
+    pub memories: Vec<ImportedMemory>,
+
+    /// Knowledge-graph relations
+    pub relations: Vec<ImportedRelation>,
+}
+
+/// Outcome of an import run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    /// Export format imported from
+    pub source: String,
+
+    /// Conversations stored
+    pub conversations_imported: usize,
+
+    /// Conversations skipped because they were imported before
+    pub conversations_skipped: usize,
+
+    /// Messages stored as memories
+    pub messages_imported: usize,
+
+    /// Standalone memories stored
+    pub memories_imported: usize,
+
+    /// Standalone memories skipped because they were imported before
+    pub memories_skipped: usize,
+
+    /// Relationships created between graph entities
+    pub relationships_imported: usize,
+}
+
+/// Read a JSON file from an export
+///
+/// `path` may be the zip archive as downloaded, the unpacked export directory,
+/// or the JSON file itself.
+pub fn read_export_file(path: &Path, file_name: &str) -> locai::Result<String> {
+    if path.is_dir() {
+        let file_path = path.join(file_name);
+        return std::fs::read_to_string(&file_path).map_err(|e| {
+            LocaiError::Other(format!("Failed to read {}: {}", file_path.display(), e))
+        });
+    }
+
+    let is_zip = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if !is_zip {
+        return std::fs::read_to_string(path)
+            .map_err(|e| LocaiError::Other(format!("Failed to read {}: {}", path.display(), e)));
+    }
+
+    let file = File::open(path)
+        .map_err(|e| LocaiError::Other(format!("Failed to open {}: {}", path.display(), e)))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| LocaiError::Other(format!("Failed to read zip archive: {}", e)))?;
+
+    // Exports sometimes wrap everything in a top-level folder
+    let entry_name = archive
+        .file_names()
+        .find(|name| *name == file_name || name.ends_with(&format!("/{}", file_name)))
+        .map(str::to_string)
+        .ok_or_else(|| LocaiError::Other(format!("{} not found in export archive", file_name)))?;
+
+    let mut contents = String::new();
+    archive
+        .by_name(&entry_name)
+        .map_err(|e| LocaiError::Other(format!("Failed to read {}: {}", entry_name, e)))?
+        .read_to_string(&mut contents)
+        .map_err(|e| LocaiError::Other(format!("Failed to read {}: {}", entry_name, e)))?;
+    Ok(contents)
+}
+
+/// Store conversations as session-grouped conversation memories
+///
+/// Conversations whose session entity already exists are skipped, so running
+/// the same import twice does not duplicate memories. Entity extraction runs
+/// on each message as for any other stored memory.
+pub async fn import_conversations(
+    manager: &MemoryManager,
+    source: &str,
+    conversations: Vec<ImportedConversation>,
+) -> locai::Result<ImportSummary> {
+    let mut summary = ImportSummary {
+        source: source.to_string(),
+        ..Default::default()
+    };
+
+    for conversation in conversations {
+        if conversation.messages.is_empty() {
+            continue;
+        }
+
+        let session_id = session_entity_id(source, &conversation.id);
+        if manager.get_entity(&session_id).await?.is_some() {
+            summary.conversations_skipped += 1;
+            continue;
+        }
+
+        let now = Utc::now();
+        let started_at = conversation
+            .created_at
+            .or_else(|| conversation.messages.iter().find_map(|m| m.created_at))
+            .unwrap_or(now);
+
+        manager
+            .create_entity(Entity {
+                id: session_id.clone(),
+                entity_type: SESSION_ENTITY_TYPE.to_string(),
+                properties: json!({
+                    "name": conversation.title,
+                    "source": source,
+                    "external_id": conversation.id,
+                    "participants": conversation.participants(),
+                    "message_count": conversation.messages.len(),
+                    "started_at": started_at.to_rfc3339(),
+                }),
+                created_at: started_at,
+                updated_at: now,
+            })
+            .await?;
+
+        for (index, message) in conversation.messages.iter().enumerate() {
+            let mut memory = MemoryBuilder::conversation(message.content.clone())
+                .source(format!("import:{}", source))
+                .tag(source)
+                .tag(format!("session:{}", conversation.id))
+                .property("session_id", json!(session_id))
+                .property("session_title", json!(conversation.title))
+                .property("role", json!(message.role))
+                .property("participant", json!(message.participant))
+                .property("message_index", json!(index))
+                .build();
+            memory.created_at = message.created_at.unwrap_or(started_at);
+
+            let memory_id = manager.store_memory(memory).await?;
+            manager
+                .create_relationship_entity(Relationship {
+                    id: format!("{}_{}", session_id, index),
+                    relationship_type: SESSION_RELATIONSHIP_TYPE.to_string(),
+                    source_id: memory_id,
+                    target_id: session_id.clone(),
+                    properties: json!({ "message_index": index }),
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await?;
+            summary.messages_imported += 1;
+        }
+
+        summary.conversations_imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// Store conversations, standalone memories and graph relations
+///
+/// Conversations go through [`import_conversations`]. Memories are stored with
+/// their export ID in the `external_id` property and skipped if a memory with
+/// that ID was imported from the same source before; those that belong to an
+/// imported conversation are linked to its session. Relations become
+/// relationships between entities named after the graph nodes, created on
+/// first use.
+pub async fn import_data(
+    manager: &MemoryManager,
+    source: &str,
+    data: ImportedData,
+) -> locai::Result<ImportSummary> {
+    let mut summary = import_conversations(manager, source, data.conversations).await?;
+    let memory_source = format!("import:{}", source);
+    let now = Utc::now();
+
+    let mut imported_ids: HashSet<String> = manager
+        .filter_memories(
+            MemoryFilter {
+                source: Some(memory_source.clone()),
+                ..Default::default()
+            },
+            None,
+            None,
+            None,
+        )
+        .await?
+        .into_iter()
+        .filter_map(|memory| Some(memory.properties.get("external_id")?.as_str()?.to_string()))
+        .collect();
+
+    for imported in data.memories {
+        if imported.content.trim().is_empty() {
+            continue;
+        }
+        if !imported_ids.insert(imported.id.clone()) {
+            summary.memories_skipped += 1;
+            continue;
+        }
+
+        let session_id = imported
+            .session_id
+            .as_deref()
+            .map(|id| session_entity_id(source, id));
+
+        let mut builder = MemoryBuilder::new_with_content(imported.content)
+            .memory_type(imported.memory_type)
+            .source(memory_source.as_str())
+            .tag(source)
+            .property("external_id", json!(imported.id));
+        for tag in imported.tags {
+            builder = builder.tag(tag);
+        }
+        if let (Some(external), Some(session_id)) = (&imported.session_id, &session_id) {
+            builder = builder
+                .tag(format!("session:{}", external))
+                .property("session_id", json!(session_id));
+        }
+        for (key, value) in imported.properties {
+            builder = builder.property(&key, value);
+        }
+        let mut memory = builder.build();
+        if let Some(created_at) = imported.created_at {
+            memory.created_at = created_at;
+        }
+
+        let memory_id = manager.store_memory(memory).await?;
+        if let Some(session_id) = session_id
+            && manager.get_entity(&session_id).await?.is_some()
+        {
+            manager
+                .create_relationship_entity(Relationship {
+                    id: String::new(),
+                    relationship_type: SESSION_RELATIONSHIP_TYPE.to_string(),
+                    source_id: memory_id,
+                    target_id: session_id,
+                    properties: json!({}),
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await?;
+        }
+        summary.memories_imported += 1;
+    }
+
+    for relation in data.relations {
+        let source_id = ensure_graph_entity(
+            manager,
+            source,
+            &relation.source,
+            relation.source_type.as_deref(),
+        )
+        .await?;
+        let target_id = ensure_graph_entity(
+            manager,
+            source,
+            &relation.target,
+            relation.target_type.as_deref(),
+        )
+        .await?;
+        let relationship_type = sanitize(&relation.relationship.to_lowercase());
+
+        let existing = manager
+            .list_relationships(
+                Some(RelationshipFilter {
+                    source_id: Some(source_id.clone()),
+                    relationship_type: Some(relationship_type.clone()),
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .await?;
+        if existing.iter().any(|r| r.target_id == target_id) {
+            continue;
+        }
+
+        manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type,
+                source_id,
+                target_id,
+                properties: json!({
+                    "source": source,
+                    "name": relation.relationship,
+                }),
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+        summary.relationships_imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// Look up the entity for a graph node, creating it if needed
+async fn ensure_graph_entity(
+    manager: &MemoryManager,
+    source: &str,
+    name: &str,
+    entity_type: Option<&str>,
+) -> locai::Result<String> {
+    let id = graph_entity_id(source, name);
+    if manager.get_entity(&id).await?.is_none() {
+        let now = Utc::now();
+        manager
+            .create_entity(Entity {
+                id: id.clone(),
+                entity_type: entity_type
+                    .map(|t| sanitize(&t.to_lowercase()))
+                    .unwrap_or_else(|| "concept".to_string()),
+                properties: json!({
+                    "name": name,
+                    "source": source,
+                }),
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+    }
+    Ok(id)
+}
+
+/// ID of the session entity for a conversation
+pub fn session_entity_id(source: &str, conversation_id: &str) -> String {
+    format!("{}_session_{}", source, sanitize(conversation_id))
+}
+
+/// ID of the entity for a knowledge-graph node
+///
+/// Node names are matched case-insensitively, as graph exports are not
+/// consistent about capitalization.
+pub fn graph_entity_id(source: &str, name: &str) -> String {
+    format!(
+        "{}_entity_{}",
+        source,
+        sanitize(&name.trim().to_lowercase())
+    )
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Parse a Unix timestamp in (possibly fractional) seconds
+pub(crate) fn from_unix_seconds(seconds: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis((seconds * 1000.0) as i64)
+}
+
+/// Parse an RFC 3339 timestamp, a timestamp without offset (taken as UTC) or
+/// Unix seconds
+pub(crate) fn parse_timestamp(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    match value? {
+        Value::String(raw) => DateTime::parse_from_rfc3339(raw)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f")
+                    .ok()
+                    .map(|dt| dt.and_utc())
+            }),
+        Value::Number(seconds) => from_unix_seconds(seconds.as_f64()?),
+        _ => None,
+    }
+}
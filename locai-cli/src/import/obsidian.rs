@@ -0,0 +1,412 @@
+//! Obsidian (or any markdown) vault importer
+//!
+//! Each note becomes a memory holding the note body. Front-matter tags become
+//! memory tags, and `[[wikilinks]]` become `links_to` relationships between
+//! the notes' memories. Re-importing a vault compares content hashes and only
+//! rewrites notes that changed since the last import.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use locai::LocaiError;
+use locai::models::{Memory, MemoryBuilder, MemoryType};
+use locai::prelude::MemoryManager;
+use locai::storage::filters::{MemoryFilter, RelationshipFilter};
+use locai::storage::models::Relationship;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+/// Memory source for imported notes
+pub const SOURCE: &str = "import:obsidian";
+
+/// Relationship type created for each wikilink
+pub const LINK_RELATIONSHIP_TYPE: &str = "links_to";
+
+/// `[[target]]`, `[[target#heading]]`, `[[target|label]]` and `![[embeds]]`
+static WIKILINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[([^\]|#]*)(?:#[^\]|]*)?(?:\|[^\]]*)?\]\]").unwrap());
+
+/// A note parsed from a vault
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObsidianNote {
+    /// Path relative to the vault root, with `/` separators
+    pub path: String,
+
+    /// Note title (the file name without `.md`)
+    pub title: String,
+
+    /// Note body without front matter
+    pub body: String,
+
+    /// Tags from the front matter, without a leading `#`
+    pub tags: Vec<String>,
+
+    /// Alternative names from the front matter `aliases`
+    pub aliases: Vec<String>,
+
+    /// Link targets as written, in order of first appearance
+    pub links: Vec<String>,
+
+    /// Parsed front matter (null if there is none)
+    pub front_matter: Value,
+
+    /// SHA-256 of the raw file contents
+    pub content_hash: String,
+
+    /// File modification time
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a vault import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VaultImportSummary {
+    /// Vault name used to tag the imported memories
+    pub vault: String,
+
+    /// Notes imported for the first time
+    pub notes_created: usize,
+
+    /// Notes whose content changed since the last import
+    pub notes_updated: usize,
+
+    /// Notes left untouched
+    pub notes_unchanged: usize,
+
+    /// Memories deleted because their note no longer exists
+    pub notes_removed: usize,
+
+    /// Link relationships created
+    pub links_created: usize,
+
+    /// Links to notes that do not exist in the vault
+    pub unresolved_links: usize,
+}
+
+/// Parse a note from its raw contents
+pub fn parse_note(path: &str, raw: &str) -> ObsidianNote {
+    let (front_matter, body) = split_front_matter(raw);
+    let front_matter = front_matter
+        .and_then(|yaml| serde_yaml::from_str::<Value>(yaml).ok())
+        .unwrap_or(Value::Null);
+
+    let mut links: Vec<String> = Vec::new();
+    for capture in WIKILINK.captures_iter(body) {
+        let target = capture[1].trim().to_string();
+        if !target.is_empty() && !links.contains(&target) {
+            links.push(target);
+        }
+    }
+
+    let title = Path::new(path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(path)
+        .to_string();
+
+    ObsidianNote {
+        path: path.to_string(),
+        title,
+        body: body.trim().to_string(),
+        tags: string_list(front_matter.get("tags"))
+            .into_iter()
+            .map(|tag| tag.trim_start_matches('#').to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        aliases: string_list(front_matter.get("aliases")),
+        links,
+        front_matter,
+        content_hash: format!("{:x}", Sha256::digest(raw.as_bytes())),
+        modified_at: None,
+    }
+}
+
+/// Read every markdown note in a vault, skipping hidden folders like `.obsidian`
+pub fn scan_vault(vault_dir: &Path) -> locai::Result<Vec<ObsidianNote>> {
+    if !vault_dir.is_dir() {
+        return Err(LocaiError::Other(format!(
+            "{} is not a directory",
+            vault_dir.display()
+        )));
+    }
+
+    let mut notes = Vec::new();
+    let walker = WalkDir::new(vault_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        });
+
+    for entry in walker {
+        let entry = entry.map_err(|e| LocaiError::Other(format!("Failed to read vault: {}", e)))?;
+        let is_markdown = entry
+            .path()
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+        if !entry.file_type().is_file() || !is_markdown {
+            continue;
+        }
+
+        let raw = std::fs::read_to_string(entry.path()).map_err(|e| {
+            LocaiError::Other(format!("Failed to read {}: {}", entry.path().display(), e))
+        })?;
+        let relative = entry
+            .path()
+            .strip_prefix(vault_dir)
+            .unwrap_or(entry.path())
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut note = parse_note(&relative, &raw);
+        note.modified_at = entry
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .map(DateTime::<Utc>::from);
+        notes.push(note);
+    }
+
+    Ok(notes)
+}
+
+/// Import a vault, updating only notes that changed since the last import
+///
+/// Imported memories are tagged `vault:<vault>`; that tag and each note's path
+/// identify what was imported before. With `prune`, memories of notes that
+/// were deleted from the vault are deleted too.
+pub async fn import_vault(
+    manager: &MemoryManager,
+    vault: &str,
+    notes: Vec<ObsidianNote>,
+    prune: bool,
+) -> locai::Result<VaultImportSummary> {
+    let mut summary = VaultImportSummary {
+        vault: vault.to_string(),
+        ..Default::default()
+    };
+    // The tag ends up in a query string; keep it to safe characters
+    let vault_tag = format!(
+        "vault:{}",
+        vault
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            })
+            .collect::<String>()
+    );
+
+    let mut existing: HashMap<String, Memory> = manager
+        .filter_memories(
+            MemoryFilter {
+                tags: Some(vec![vault_tag.clone()]),
+                source: Some(SOURCE.to_string()),
+                ..Default::default()
+            },
+            None,
+            None,
+            None,
+        )
+        .await?
+        .into_iter()
+        .filter_map(|memory| {
+            let path = memory.properties.get("path")?.as_str()?.to_string();
+            Some((path, memory))
+        })
+        .collect();
+
+    // First pass: store notes so every link target has a memory ID
+    let mut memory_ids: HashMap<String, String> = HashMap::new();
+    let mut changed: Vec<&ObsidianNote> = Vec::new();
+    for note in &notes {
+        match existing.remove(&note.path) {
+            Some(memory)
+                if memory.properties.get("content_hash") == Some(&json!(note.content_hash)) =>
+            {
+                memory_ids.insert(note.path.clone(), memory.id);
+                summary.notes_unchanged += 1;
+            }
+            Some(mut memory) => {
+                let updated = note_memory(note, &vault_tag);
+                memory.content = updated.content;
+                memory.tags = updated.tags;
+                memory.properties = updated.properties;
+                memory.embedding = None;
+                manager.update_memory(memory.clone()).await?;
+                memory_ids.insert(note.path.clone(), memory.id);
+                changed.push(note);
+                summary.notes_updated += 1;
+            }
+            None => {
+                let id = manager.store_memory(note_memory(note, &vault_tag)).await?;
+                memory_ids.insert(note.path.clone(), id);
+                changed.push(note);
+                summary.notes_created += 1;
+            }
+        }
+    }
+
+    if prune {
+        for memory in existing.into_values() {
+            manager.delete_memory(&memory.id).await?;
+            summary.notes_removed += 1;
+        }
+    }
+
+    // Second pass: rebuild outgoing links of changed notes
+    let resolver = LinkResolver::new(&notes);
+    let now = Utc::now();
+    for note in changed {
+        let source_id = &memory_ids[&note.path];
+
+        let stale = manager
+            .list_relationships(
+                Some(RelationshipFilter {
+                    source_id: Some(source_id.clone()),
+                    relationship_type: Some(LINK_RELATIONSHIP_TYPE.to_string()),
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .await?;
+        for relationship in stale {
+            manager.delete_relationship(&relationship.id).await?;
+        }
+
+        for link in &note.links {
+            let Some(target_id) = resolver.resolve(link).and_then(|path| memory_ids.get(path))
+            else {
+                summary.unresolved_links += 1;
+                continue;
+            };
+            if target_id == source_id {
+                continue;
+            }
+            manager
+                .create_relationship_entity(Relationship {
+                    id: String::new(),
+                    relationship_type: LINK_RELATIONSHIP_TYPE.to_string(),
+                    source_id: source_id.clone(),
+                    target_id: target_id.clone(),
+                    properties: json!({ "link": link }),
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await?;
+            summary.links_created += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+fn note_memory(note: &ObsidianNote, vault_tag: &str) -> Memory {
+    let content = if note.body.is_empty() {
+        note.title.clone()
+    } else {
+        note.body.clone()
+    };
+
+    let mut builder = MemoryBuilder::new_with_content(content)
+        .memory_type(MemoryType::Custom("note".to_string()))
+        .source(SOURCE)
+        .tag(vault_tag)
+        .property("path", json!(note.path))
+        .property("title", json!(note.title))
+        .property("aliases", json!(note.aliases))
+        .property("front_matter", note.front_matter.clone())
+        .property("content_hash", json!(note.content_hash));
+    for tag in &note.tags {
+        builder = builder.tag(tag.as_str());
+    }
+
+    let mut memory = builder.build();
+    if let Some(modified_at) = note.modified_at {
+        memory.created_at = modified_at;
+    }
+    memory
+}
+
+/// Resolves link text to note paths the way Obsidian does: by path without
+/// extension, then by file name, then by alias (all case-insensitive)
+struct LinkResolver<'a> {
+    by_path: HashMap<String, &'a str>,
+    by_name: HashMap<String, &'a str>,
+}
+
+impl<'a> LinkResolver<'a> {
+    fn new(notes: &'a [ObsidianNote]) -> Self {
+        let mut by_path = HashMap::new();
+        let mut by_name = HashMap::new();
+        for note in notes {
+            let without_ext = note.path.strip_suffix(".md").unwrap_or(&note.path);
+            by_path.insert(without_ext.to_lowercase(), note.path.as_str());
+            by_name
+                .entry(note.title.to_lowercase())
+                .or_insert(note.path.as_str());
+        }
+        // Aliases never shadow real note names
+        for note in notes {
+            for alias in &note.aliases {
+                by_name
+                    .entry(alias.to_lowercase())
+                    .or_insert(note.path.as_str());
+            }
+        }
+        Self { by_path, by_name }
+    }
+
+    fn resolve(&self, link: &str) -> Option<&'a str> {
+        let key = link.strip_suffix(".md").unwrap_or(link).to_lowercase();
+        self.by_path
+            .get(&key)
+            .or_else(|| self.by_name.get(&key))
+            .copied()
+    }
+}
+
+/// Split `---` delimited YAML front matter from the body
+fn split_front_matter(raw: &str) -> (Option<&str>, &str) {
+    let Some(rest) = raw
+        .strip_prefix("---\n")
+        .or_else(|| raw.strip_prefix("---\r\n"))
+    else {
+        return (None, raw);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, raw)
+}
+
+/// A YAML value that may be a list or a comma/space separated string
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(s)) => s
+            .split([',', ' '])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
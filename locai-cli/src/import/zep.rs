@@ -0,0 +1,226 @@
+//! Zep export parser
+//!
+//! Zep has no single export format, so this accepts the shapes its SDKs
+//! return, combined into one JSON file:
+//!
+//! - `sessions`: sessions with their `messages` and extracted `facts`
+//! - `collections`: document collections with their `documents`
+//! - `nodes` and `edges`: the knowledge graph, where each edge carries a fact
+//!
+//! A bare session object or an array of sessions is accepted as well.
+
+use std::collections::HashMap;
+
+use locai::LocaiError;
+use locai::models::MemoryType;
+use serde_json::{Map, Value, json};
+
+use super::{
+    ImportedConversation, ImportedData, ImportedMemory, ImportedMessage, ImportedRelation,
+    parse_timestamp,
+};
+
+/// Parse a Zep export
+pub fn parse_export(json: &str) -> locai::Result<ImportedData> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| LocaiError::Other(format!("Invalid Zep export: {}", e)))?;
+
+    let mut data = ImportedData::default();
+    match &value {
+        Value::Array(sessions) => sessions.iter().for_each(|s| parse_session(s, &mut data)),
+        Value::Object(object) if object.contains_key("messages") => {
+            parse_session(&value, &mut data)
+        }
+        Value::Object(object) => {
+            for session in array(object.get("sessions")) {
+                parse_session(session, &mut data);
+            }
+            for collection in array(object.get("collections")) {
+                parse_collection(collection, &mut data);
+            }
+            parse_graph(
+                array(object.get("nodes")),
+                array(object.get("edges")),
+                &mut data,
+            );
+        }
+        _ => {
+            return Err(LocaiError::Other(
+                "Invalid Zep export: expected an object or an array of sessions".to_string(),
+            ));
+        }
+    }
+
+    Ok(data)
+}
+
+fn parse_session(session: &Value, data: &mut ImportedData) {
+    let Some(session_id) = text(session, &["session_id", "uuid"]) else {
+        return;
+    };
+    let metadata = session.get("metadata");
+    let title = metadata
+        .and_then(|m| m.get("title"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Zep session {}", session_id));
+
+    let messages: Vec<ImportedMessage> = array(session.get("messages"))
+        .iter()
+        .filter_map(parse_message)
+        .collect();
+    data.conversations.push(ImportedConversation {
+        id: session_id.clone(),
+        title,
+        created_at: parse_timestamp(session.get("created_at")),
+        messages,
+    });
+
+    let user_tag = text(session, &["user_id"]).map(|user| format!("user:{}", user));
+    for (index, fact) in array(session.get("facts")).iter().enumerate() {
+        // Older servers return facts as plain strings
+        let (content, id) = match fact {
+            Value::String(content) => (content.clone(), None),
+            _ => match text(fact, &["fact", "content"]) {
+                Some(content) => (content, text(fact, &["uuid"])),
+                None => continue,
+            },
+        };
+
+        let mut properties = Map::new();
+        if let Some(rating) = fact.get("rating").filter(|r| !r.is_null()) {
+            properties.insert("rating".to_string(), rating.clone());
+        }
+        data.memories.push(ImportedMemory {
+            id: id.unwrap_or_else(|| format!("{}_fact_{}", session_id, index)),
+            content,
+            memory_type: MemoryType::Fact,
+            session_id: Some(session_id.clone()),
+            created_at: parse_timestamp(fact.get("created_at")),
+            tags: user_tag.iter().cloned().collect(),
+            properties,
+        });
+    }
+}
+
+fn parse_message(message: &Value) -> Option<ImportedMessage> {
+    let role = text(message, &["role_type"]).unwrap_or_else(|| "user".to_string());
+    if role != "user" && role != "assistant" {
+        return None;
+    }
+    let content = text(message, &["content"])?.trim().to_string();
+    if content.is_empty() {
+        return None;
+    }
+
+    // `role` holds the speaker's name in Zep, `role_type` the actual role
+    let participant = text(message, &["role"])
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| role.clone());
+
+    Some(ImportedMessage {
+        role,
+        participant,
+        content,
+        created_at: parse_timestamp(message.get("created_at")),
+    })
+}
+
+fn parse_collection(collection: &Value, data: &mut ImportedData) {
+    let Some(name) = text(collection, &["name"]) else {
+        return;
+    };
+
+    for (index, document) in array(collection.get("documents")).iter().enumerate() {
+        let Some(content) = text(document, &["content"]) else {
+            continue;
+        };
+
+        let mut properties = Map::new();
+        properties.insert("collection".to_string(), json!(name));
+        for key in ["document_id", "metadata"] {
+            if let Some(value) = document.get(key).filter(|v| !v.is_null()) {
+                properties.insert(key.to_string(), value.clone());
+            }
+        }
+        data.memories.push(ImportedMemory {
+            id: text(document, &["uuid", "document_id"])
+                .unwrap_or_else(|| format!("{}_document_{}", name, index)),
+            content,
+            memory_type: MemoryType::Custom("document".to_string()),
+            session_id: None,
+            created_at: parse_timestamp(document.get("created_at")),
+            tags: vec![format!("collection:{}", name)],
+            properties,
+        });
+    }
+}
+
+fn parse_graph(nodes: &[Value], edges: &[Value], data: &mut ImportedData) {
+    // uuid -> (name, type)
+    let nodes: HashMap<String, (String, Option<String>)> = nodes
+        .iter()
+        .filter_map(|node| {
+            let uuid = text(node, &["uuid"])?;
+            let name = text(node, &["name"])?;
+            // Every node carries the generic "Entity" label; prefer a specific one
+            let node_type = array(node.get("labels"))
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|label| *label != "Entity")
+                .map(str::to_string);
+            Some((uuid, (name, node_type)))
+        })
+        .collect();
+
+    for edge in edges {
+        let endpoints = text(edge, &["source_node_uuid"])
+            .and_then(|uuid| nodes.get(&uuid))
+            .zip(text(edge, &["target_node_uuid"]).and_then(|uuid| nodes.get(&uuid)));
+        let Some(((source, source_type), (target, target_type))) = endpoints else {
+            continue;
+        };
+
+        if let Some(relationship) = text(edge, &["name"]) {
+            data.relations.push(ImportedRelation {
+                source: source.clone(),
+                source_type: source_type.clone(),
+                relationship,
+                target: target.clone(),
+                target_type: target_type.clone(),
+            });
+        }
+
+        if let (Some(uuid), Some(fact)) = (text(edge, &["uuid"]), text(edge, &["fact"])) {
+            let mut properties = Map::new();
+            for key in ["valid_at", "invalid_at"] {
+                if let Some(value) = edge.get(key).filter(|v| !v.is_null()) {
+                    properties.insert(key.to_string(), value.clone());
+                }
+            }
+            data.memories.push(ImportedMemory {
+                id: uuid,
+                content: fact,
+                memory_type: MemoryType::Fact,
+                session_id: None,
+                created_at: parse_timestamp(edge.get("created_at")),
+                tags: Vec::new(),
+                properties,
+            });
+        }
+    }
+}
+
+/// First of `keys` holding a string
+fn text(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+fn array(value: Option<&Value>) -> &[Value] {
+    value
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
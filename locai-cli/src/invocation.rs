@@ -0,0 +1,197 @@
+//! The terminal and working directory a command runs for
+//!
+//! Run locally, a command reads this process's stdin, writes its stdout and
+//! stderr, and resolves paths against its working directory. Run by the
+//! daemon, it uses the client's instead: [`Invocation::scope`] sets them for
+//! the command's task alone, so the daemon's own descriptors, and the logs
+//! and background tasks writing to them, are never pointed at a client.
+//!
+//! Commands write through `out!`, `outln!` and `errln!` rather than `print!`,
+//! `println!` and `eprintln!`, read through [`stdin`], and open files at
+//! [`resolve`]d paths.
+
+use std::fs::File;
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+tokio::task_local! {
+    static INVOCATION: Invocation;
+}
+
+/// Like `print!`, to the invoking terminal's stdout
+macro_rules! out {
+    ($($arg:tt)*) => {{
+        use std::io::Write as _;
+        let _ = write!($crate::invocation::stdout(), $($arg)*);
+    }};
+}
+
+/// Like `println!`, to the invoking terminal's stdout
+macro_rules! outln {
+    ($($arg:tt)*) => {{
+        use std::io::Write as _;
+        let _ = writeln!($crate::invocation::stdout(), $($arg)*);
+    }};
+}
+
+/// Like `eprintln!`, to the invoking terminal's stderr
+macro_rules! errln {
+    ($($arg:tt)*) => {{
+        use std::io::Write as _;
+        let _ = writeln!($crate::invocation::stderr(), $($arg)*);
+    }};
+}
+
+/// A client's descriptors and working directory, for a command the daemon runs
+#[derive(Debug)]
+pub struct Invocation {
+    stdin: Arc<File>,
+    stdout: Arc<File>,
+    stderr: Arc<File>,
+    cwd: PathBuf,
+}
+
+impl Invocation {
+    pub fn new(stdin: File, stdout: File, stderr: File, cwd: PathBuf) -> Self {
+        Self {
+            stdin: Arc::new(stdin),
+            stdout: Arc::new(stdout),
+            stderr: Arc::new(stderr),
+            cwd,
+        }
+    }
+
+    /// Run `future` reading and writing this invocation's descriptors
+    ///
+    /// Tasks it spawns don't inherit them.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        INVOCATION.scope(self, future).await
+    }
+}
+
+/// Whether the current command was forwarded to the daemon
+pub fn is_forwarded() -> bool {
+    INVOCATION.try_with(|_| ()).is_ok()
+}
+
+/// `path` as the invoking shell would find it
+pub fn resolve(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    INVOCATION
+        .try_with(|invocation| invocation.cwd.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Whether progress bars can be drawn
+///
+/// They draw on this process's stderr, which a forwarded command doesn't own.
+pub fn shows_progress() -> bool {
+    !is_forwarded() && io::stdout().is_terminal()
+}
+
+pub fn stdout() -> Output {
+    INVOCATION
+        .try_with(|invocation| Output::Forwarded(Arc::clone(&invocation.stdout)))
+        .unwrap_or_else(|_| Output::Stdout(io::stdout()))
+}
+
+pub fn stderr() -> Output {
+    INVOCATION
+        .try_with(|invocation| Output::Forwarded(Arc::clone(&invocation.stderr)))
+        .unwrap_or_else(|_| Output::Stderr(io::stderr()))
+}
+
+pub fn stdin() -> Input {
+    INVOCATION
+        .try_with(|invocation| {
+            // Reads a byte at a time, so a line read here doesn't swallow the
+            // start of the next; large reads bypass the buffer
+            Input::Forwarded(BufReader::with_capacity(
+                1,
+                SharedFile(Arc::clone(&invocation.stdin)),
+            ))
+        })
+        .unwrap_or_else(|_| Input::Stdin(io::stdin().lock()))
+}
+
+/// The invoking terminal's stdout or stderr
+#[derive(Debug)]
+pub enum Output {
+    Stdout(io::Stdout),
+    Stderr(io::Stderr),
+    Forwarded(Arc<File>),
+}
+
+impl Output {
+    pub fn is_terminal(&self) -> bool {
+        match self {
+            Self::Stdout(stdout) => stdout.is_terminal(),
+            Self::Stderr(stderr) => stderr.is_terminal(),
+            Self::Forwarded(file) => file.is_terminal(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::Stderr(stderr) => stderr.write(buf),
+            Self::Forwarded(file) => file.as_ref().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Stderr(stderr) => stderr.flush(),
+            Self::Forwarded(file) => file.as_ref().flush(),
+        }
+    }
+}
+
+/// The invoking terminal's stdin
+#[derive(Debug)]
+pub enum Input {
+    Stdin(io::StdinLock<'static>),
+    Forwarded(BufReader<SharedFile>),
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdin(stdin) => stdin.read(buf),
+            Self::Forwarded(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl BufRead for Input {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self {
+            Self::Stdin(stdin) => stdin.fill_buf(),
+            Self::Forwarded(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        match self {
+            Self::Stdin(stdin) => stdin.consume(amount),
+            Self::Forwarded(reader) => reader.consume(amount),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SharedFile(Arc<File>);
+
+impl Read for SharedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.as_ref().read(buf)
+    }
+}
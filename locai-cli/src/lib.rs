@@ -1,15 +1,28 @@
+// First, so every module can use its output macros
+#[macro_use]
+pub mod invocation;
+
 pub mod args;
 pub mod commands;
 pub mod context;
+#[cfg(unix)]
+pub mod daemon;
 pub mod handlers;
+pub mod import;
 pub mod output;
+pub mod search_builder;
+pub mod tabular;
+pub mod template;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod utils;
 
 pub use context::LocaiCliContext;
 pub use output::{
-    CliColors, format_error, format_info, format_memory_type, format_priority, format_success,
-    format_warning, output_error, print_connected_memories_tree, print_entity, print_entity_list,
-    print_memory, print_memory_graph, print_memory_list, print_paths, print_relationship,
-    print_relationship_list,
+    CliColors, GraphView, GraphViewNode, format_error, format_info, format_memory_type,
+    format_priority, format_success, format_warning, output_error, print_connected_memories_tree,
+    print_entity, print_entity_list, print_graph_tree, print_memory, print_memory_graph,
+    print_memory_list, print_paths, print_relationship, print_relationship_list,
+    render_graph_mermaid,
 };
 pub use utils::{parse_memory_type, parse_priority, resolve_memory_id};
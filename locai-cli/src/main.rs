@@ -1,19 +1,33 @@
 use clap::{CommandFactory, Parser, Subcommand};
 use colored::*;
 use is_terminal::IsTerminal;
+use locai::LocaiError;
+use std::io::BufRead;
 use tracing::{Level, error, info};
 
+// First, so every module can use its output macros
+#[macro_use]
+mod invocation;
+
 mod args;
 mod commands;
 mod context;
+#[cfg(unix)]
+mod daemon;
 mod handlers;
 mod help;
+mod import;
 mod output;
+mod search_builder;
+mod tabular;
+mod template;
+#[cfg(feature = "tui")]
+mod tui;
 mod utils;
 
 use context::LocaiCliContext;
 use handlers::*;
-use output::{CliColors, format_error, format_info, format_success};
+use output::{CliColors, format_error, format_info, format_success, print_json};
 
 #[derive(Parser)]
 #[command(name = "locai-cli")]
@@ -24,14 +38,29 @@ struct Cli {
     #[arg(long, short, global = true)]
     data_dir: Option<String>,
 
-    /// Output format (table, json) - use json for tool integration
-    #[arg(long, short, default_value = "table", global = true)]
+    /// Output format (table, json, csv, markdown) - use json for tool integration
+    #[arg(
+        long,
+        short,
+        default_value = "table",
+        global = true,
+        value_parser = ["table", "json", "csv", "markdown", "md"]
+    )]
     output: String,
 
     /// Use machine-readable output (alias for --output json)
     #[arg(long, global = true)]
     machine: bool,
 
+    /// Comma-separated fields to keep in JSON output (e.g. id,content,metadata.source)
+    #[arg(long, global = true)]
+    fields: Option<String>,
+
+    /// Render each result through a template instead of printing JSON
+    /// (e.g. '{{id}}\t{{content | truncate 80}}')
+    #[arg(long, global = true)]
+    template: Option<String>,
+
     /// Verbose output (debug level logging)
     #[arg(long, short, global = true)]
     verbose: bool,
@@ -52,6 +81,10 @@ struct Cli {
     #[arg(long, global = true)]
     explain: Option<String>,
 
+    /// Run locally even when a daemon is serving this data directory
+    #[arg(long, global = true)]
+    no_daemon: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -62,12 +95,18 @@ enum Commands {
     Version,
 
     /// Run diagnostic checks
-    Diagnose,
+    Diagnose(args::DiagnoseArgs),
 
     /// Memory operations
     #[command(subcommand)]
     Memory(commands::MemoryCommands),
 
+    /// Capture a note as a memory
+    Note(args::NoteArgs),
+
+    /// Capture the clipboard contents as a memory
+    Clip(args::ClipArgs),
+
     /// Entity operations
     #[command(subcommand)]
     Entity(commands::EntityCommands),
@@ -84,6 +123,54 @@ enum Commands {
     #[command(subcommand)]
     Batch(commands::BatchCommands),
 
+    /// Import data exported from other tools
+    #[command(subcommand)]
+    Import(commands::ImportCommands),
+
+    /// Export data to other tools
+    #[command(subcommand)]
+    Export(commands::ExportCommands),
+
+    /// Messaging operations
+    #[command(subcommand)]
+    Messaging(commands::MessagingCommands),
+
+    /// Snapshot operations
+    #[command(subcommand)]
+    Snapshot(commands::SnapshotCommands),
+
+    /// Collection operations
+    #[command(subcommand)]
+    Collection(commands::CollectionCommands),
+
+    /// Task operations
+    #[command(subcommand)]
+    Task(commands::TaskCommands),
+
+    /// Procedure operations
+    #[command(subcommand)]
+    Procedure(commands::ProcedureCommands),
+
+    /// Preference operations
+    #[command(subcommand)]
+    Preference(commands::PreferenceCommands),
+
+    /// Persona operations
+    #[command(subcommand)]
+    Persona(commands::PersonaCommands),
+
+    /// Automation rule operations
+    #[command(subcommand)]
+    Rule(commands::RuleCommands),
+
+    /// Configuration checks
+    #[command(subcommand)]
+    Config(commands::ConfigCommands),
+
+    /// Storage maintenance
+    #[command(subcommand)]
+    Maintenance(commands::MaintenanceCommands),
+
     /// Relationship type operations
     #[command(subcommand)]
     RelationshipType(commands::RelationshipTypeCommands),
@@ -95,6 +182,18 @@ enum Commands {
     /// Quick start guide - create sample data
     Quickstart(args::QuickstartArgs),
 
+    /// Benchmark store, search and graph performance on a synthetic corpus
+    Bench(args::BenchArgs),
+
+    /// Serve the HTTP and WebSocket API from this binary
+    Serve(args::ServeArgs),
+
+    /// Browse, search and edit memories in a terminal dashboard
+    Tui(args::TuiArgs),
+
+    /// Keep storage open and run commands from other invocations
+    Daemon(args::DaemonArgs),
+
     /// Generate shell completion scripts
     Completions(args::CompletionsArgs),
 
@@ -106,15 +205,42 @@ enum Commands {
 async fn main() {
     let cli_args = Cli::parse();
 
-    let output_format_str = if cli_args.machine {
+    let output_shape =
+        template::OutputShape::parse(cli_args.fields.as_deref(), cli_args.template.as_deref());
+    let shaped = match output_shape {
+        Ok(shape) => {
+            let shaped = shape.is_set();
+            template::set_output_shape(shape);
+            shaped
+        }
+        Err(e) => {
+            crate::output::output_error_json(&e, &cli_args.output);
+            std::process::exit(1);
+        }
+    };
+
+    let output_format_str = if cli_args.machine || shaped {
+        // --fields and --template shape the JSON output
         "json".to_string()
-    } else if !std::io::stdout().is_terminal() {
-        // Auto-detect: if stdout is not a TTY (piped/redirected), default to JSON
+    } else if !std::io::stdout().is_terminal()
+        && tabular::TableFormat::from_output(&cli_args.output).is_none()
+    {
+        // Auto-detect: if stdout is not a TTY (piped/redirected), default to JSON.
+        // CSV and Markdown are usually redirected into files, so keep them.
         "json".to_string()
     } else {
         cli_args.output.clone()
     };
 
+    #[cfg(unix)]
+    if !cli_args.no_daemon
+        && cli_args.explain.is_none()
+        && forwards_to_daemon(&cli_args.command)
+        && let Some(exit_code) = forward_to_daemon(&cli_args, &output_format_str)
+    {
+        std::process::exit(exit_code);
+    }
+
     let result = run(cli_args, &output_format_str).await;
 
     if let Err(e) = result {
@@ -149,7 +275,7 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
                 "debug" => Level::DEBUG,
                 "trace" => Level::TRACE,
                 _ => {
-                    eprintln!(
+                    errln!(
                         "Warning: Invalid log level '{}'. Valid levels: off, error, warn, info, debug, trace",
                         level_str
                     );
@@ -160,6 +286,9 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
             Level::ERROR
         } else if cli_args.verbose || cli_args.debug {
             Level::DEBUG
+        } else if matches!(cli_args.command, Commands::Serve(_)) {
+            // A server should report that it's listening and log requests
+            Level::INFO
         } else {
             // Default: suppress all logging by using ERROR level
             // This prevents the library from initializing logging with its defaults
@@ -176,14 +305,47 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
     }
 
     let mut context: Option<LocaiCliContext> = None;
-    // Skip context initialization for commands that don't need storage
-    if !skip_init {
-        context = Some(LocaiCliContext::new(cli_args.data_dir).await?);
+    // Skip context initialization for commands that don't need storage;
+    // benchmarks run against their own in-memory stores, and config checks
+    // must still report when storage can't be opened
+    if !skip_init && !matches!(cli_args.command, Commands::Bench(_) | Commands::Config(_)) {
+        context = Some(LocaiCliContext::new(cli_args.data_dir.clone()).await?);
     }
 
     match cli_args.command {
+        Commands::Serve(serve_args) => match context {
+            Some(ctx) => handle_serve_command(serve_args, ctx).await,
+            None => Ok(()),
+        },
+        Commands::Daemon(daemon_args) => match &context {
+            Some(ctx) => run_daemon(daemon_args, ctx, cli_args.data_dir.as_deref()).await,
+            None => Ok(()),
+        },
+        command => {
+            let result = execute(
+                command,
+                context.as_ref(),
+                cli_args.data_dir.as_deref(),
+                output_format,
+            )
+            .await;
+            // Captures answer before their indexing is done; finish it before exiting
+            handlers::capture::finish_indexing().await;
+            result
+        }
+    }
+}
+
+/// Run a command that needs at most a borrowed context
+async fn execute(
+    command: Commands,
+    context: Option<&LocaiCliContext>,
+    data_dir: Option<&str>,
+    output_format: &str,
+) -> locai::Result<()> {
+    match command {
         Commands::Version => {
-            println!(
+            outln!(
                 "{} {} {}",
                 "Locai CLI".color(CliColors::accent()).bold(),
                 "v".color(CliColors::muted()),
@@ -191,89 +353,187 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
             );
         }
 
-        Commands::Diagnose => {
-            if let Some(ctx) = &context {
+        Commands::Diagnose(diagnose_args) => {
+            if let Some(ctx) = context {
                 info!("Running diagnostic checks...");
 
                 match ctx.memory_manager.storage().health_check().await {
-                    Ok(true) => println!("{}", format_success("Storage: Healthy")),
-                    Ok(false) => println!("{}", format_error("Storage: Unhealthy")),
-                    Err(e) => println!("{}", format_error(&format!("Storage: Error - {}", e))),
+                    Ok(true) => outln!("{}", format_success("Storage: Healthy")),
+                    Ok(false) => outln!("{}", format_error("Storage: Unhealthy")),
+                    Err(e) => outln!("{}", format_error(&format!("Storage: Error - {}", e))),
                 }
 
                 if ctx.memory_manager.config().ml.embedding.service_type
                     == locai::config::EmbeddingServiceType::Local
                 {
-                    println!("{}", format_success("ML Service: Enabled (Local)"));
+                    outln!("{}", format_success("ML Service: Enabled (Local)"));
                 } else {
-                    println!("{}", format_success("ML Service: Enabled (Remote)"));
+                    outln!("{}", format_success("ML Service: Enabled (Remote)"));
                 }
 
                 match ctx.memory_manager.storage().get_metadata().await {
                     Ok(metadata) => {
                         if output_format == "json" {
-                            println!(
-                                "{}",
-                                serde_json::to_string_pretty(&metadata)
-                                    .unwrap_or_else(|_| "{}".to_string())
-                            );
+                            print_json(&metadata);
                         } else {
-                            println!("Storage metadata: {}", metadata);
+                            outln!("Storage metadata: {}", metadata);
                         }
                     }
                     Err(e) => error!("Failed to get storage metadata: {}", e),
                 }
+
+                if diagnose_args.deep || diagnose_args.fix {
+                    handle_deep_diagnose(&diagnose_args, ctx, output_format).await?;
+                }
             }
         }
 
         Commands::Memory(memory_cmd) => {
             if let Some(ctx) = context {
-                handle_memory_command(memory_cmd, &ctx, output_format).await?;
+                handle_memory_command(memory_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Note(note_args) => {
+            if let Some(ctx) = context {
+                handle_note_command(note_args, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Clip(clip_args) => {
+            if let Some(ctx) = context {
+                handle_clip_command(clip_args, ctx, output_format).await?;
             }
         }
 
         Commands::Entity(entity_cmd) => {
             if let Some(ctx) = context {
-                handle_entity_command(entity_cmd, &ctx, output_format).await?;
+                handle_entity_command(entity_cmd, ctx, output_format).await?;
             }
         }
 
         Commands::Relationship(rel_cmd) => {
             if let Some(ctx) = context {
-                handle_relationship_command(rel_cmd, &ctx, output_format).await?;
+                handle_relationship_command(rel_cmd, ctx, output_format).await?;
             }
         }
 
         Commands::Graph(graph_cmd) => {
             if let Some(ctx) = context {
-                handle_graph_command(graph_cmd, &ctx, output_format).await?;
+                handle_graph_command(graph_cmd, ctx, output_format).await?;
             }
         }
 
         Commands::Batch(batch_cmd) => {
             if let Some(ctx) = context {
-                handle_batch_command(batch_cmd, &ctx, output_format).await?;
+                handle_batch_command(batch_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Import(import_cmd) => {
+            if let Some(ctx) = context {
+                handle_import_command(import_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Export(export_cmd) => {
+            if let Some(ctx) = context {
+                handle_export_command(export_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Messaging(messaging_cmd) => {
+            if let Some(ctx) = context {
+                handle_messaging_command(messaging_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Snapshot(snapshot_cmd) => {
+            if let Some(ctx) = context {
+                handle_snapshot_command(snapshot_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Collection(collection_cmd) => {
+            if let Some(ctx) = context {
+                handle_collection_command(collection_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Task(task_cmd) => {
+            if let Some(ctx) = context {
+                handle_task_command(task_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Procedure(procedure_cmd) => {
+            if let Some(ctx) = context {
+                handle_procedure_command(procedure_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Preference(preference_cmd) => {
+            if let Some(ctx) = context {
+                handle_preference_command(preference_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Persona(persona_cmd) => {
+            if let Some(ctx) = context {
+                handle_persona_command(persona_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Rule(rule_cmd) => {
+            if let Some(ctx) = context {
+                handle_rule_command(rule_cmd, ctx, output_format).await?;
+            }
+        }
+
+        Commands::Config(config_cmd) => {
+            handle_config_command(config_cmd, data_dir, output_format).await?;
+        }
+
+        Commands::Maintenance(maintenance_cmd) => {
+            if let Some(ctx) = context {
+                handle_maintenance_command(maintenance_cmd, ctx, output_format).await?;
             }
         }
 
         Commands::RelationshipType(rel_type_cmd) => {
             if let Some(ctx) = context {
-                handle_relationship_type_command(rel_type_cmd, &ctx, output_format).await?;
+                handle_relationship_type_command(rel_type_cmd, ctx, output_format).await?;
             }
         }
 
         Commands::Tutorial(tutorial_args) => {
             if let Some(ctx) = context {
-                handle_tutorial_command(tutorial_args, &ctx, output_format).await?;
+                handle_tutorial_command(tutorial_args, ctx, output_format).await?;
             }
         }
 
         Commands::Quickstart(quickstart_args) => {
             if let Some(ctx) = context {
-                handle_quickstart_command(quickstart_args, &ctx, output_format).await?;
+                handle_quickstart_command(quickstart_args, ctx, output_format).await?;
             }
         }
 
+        Commands::Bench(bench_args) => {
+            handle_bench_command(bench_args, output_format).await?;
+        }
+
+        Commands::Tui(tui_args) => {
+            if let Some(ctx) = context {
+                handle_tui_command(tui_args, ctx).await?;
+            }
+        }
+
+        Commands::Serve(_) | Commands::Daemon(_) => {
+            return Err(LocaiError::Other(
+                "serve and daemon can't run inside the daemon".to_string(),
+            ));
+        }
+
         Commands::Completions(completions_args) => {
             use clap_complete::generate;
             let mut cmd = Cli::command();
@@ -289,21 +549,21 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
                     );
                     // Show installation instructions on stderr (only if stderr is a TTY)
                     if std::io::stderr().is_terminal() {
-                        eprintln!(
+                        errln!(
                             "\n{}",
                             format_info("Bash completion script generated. Installation options:")
                         );
-                        eprintln!("  1. Direct sourcing: source <(locai-cli completions bash)");
-                        eprintln!(
+                        errln!("  1. Direct sourcing: source <(locai-cli completions bash)");
+                        errln!(
                             "  2. Save and source: locai-cli completions bash > ~/.locai-cli.bash && echo 'source ~/.locai-cli.bash' >> ~/.bashrc"
                         );
-                        eprintln!(
+                        errln!(
                             "  3. System-wide: sudo sh -c 'locai-cli completions bash > /etc/bash_completion.d/locai-cli'"
                         );
-                        eprintln!(
+                        errln!(
                             "\nNote: ~/.bash_completion.d/ is NOT automatically loaded by bash."
                         );
-                        eprintln!("See docs/SHELL_COMPLETION_INSTALLATION.md for details.");
+                        errln!("See docs/SHELL_COMPLETION_INSTALLATION.md for details.");
                     }
                 }
                 args::Shell::Zsh => {
@@ -314,14 +574,14 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
                         &mut std::io::stdout(),
                     );
                     if std::io::stderr().is_terminal() {
-                        eprintln!(
+                        errln!(
                             "\n{}",
                             format_info("Zsh completion script generated. To install:")
                         );
-                        eprintln!("  mkdir -p ~/.zsh/completions");
-                        eprintln!("  locai-cli completions zsh > ~/.zsh/completions/_locai-cli");
-                        eprintln!("  echo 'fpath=(~/.zsh/completions $fpath)' >> ~/.zshrc");
-                        eprintln!("  echo 'autoload -U compinit && compinit' >> ~/.zshrc");
+                        errln!("  mkdir -p ~/.zsh/completions");
+                        errln!("  locai-cli completions zsh > ~/.zsh/completions/_locai-cli");
+                        errln!("  echo 'fpath=(~/.zsh/completions $fpath)' >> ~/.zshrc");
+                        errln!("  echo 'autoload -U compinit && compinit' >> ~/.zshrc");
                     }
                 }
                 args::Shell::Fish => {
@@ -332,15 +592,15 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
                         &mut std::io::stdout(),
                     );
                     if std::io::stderr().is_terminal() {
-                        eprintln!(
+                        errln!(
                             "\n{}",
                             format_info("Fish completion script generated. To install:")
                         );
-                        eprintln!("  mkdir -p ~/.config/fish/completions");
-                        eprintln!(
+                        errln!("  mkdir -p ~/.config/fish/completions");
+                        errln!(
                             "  locai-cli completions fish > ~/.config/fish/completions/locai-cli.fish"
                         );
-                        eprintln!("  (Fish automatically loads completions from this directory)");
+                        errln!("  (Fish automatically loads completions from this directory)");
                     }
                 }
                 args::Shell::Power => {
@@ -351,14 +611,12 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
                         &mut std::io::stdout(),
                     );
                     if std::io::stderr().is_terminal() {
-                        eprintln!(
+                        errln!(
                             "\n{}",
                             format_info("PowerShell completion script generated. To install:")
                         );
-                        eprintln!("  locai-cli completions powershell > $PROFILE\\locai-cli.ps1");
-                        eprintln!(
-                            "  Add 'source $PROFILE\\locai-cli.ps1' to your PowerShell profile"
-                        );
+                        errln!("  locai-cli completions powershell > $PROFILE\\locai-cli.ps1");
+                        errln!("  Add 'source $PROFILE\\locai-cli.ps1' to your PowerShell profile");
                     }
                 }
                 args::Shell::Elvish => {
@@ -369,15 +627,15 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
                         &mut std::io::stdout(),
                     );
                     if std::io::stderr().is_terminal() {
-                        eprintln!(
+                        errln!(
                             "\n{}",
                             format_info("Elvish completion script generated. To install:")
                         );
-                        eprintln!("  mkdir -p ~/.config/elvish/lib");
-                        eprintln!(
+                        errln!("  mkdir -p ~/.config/elvish/lib");
+                        errln!(
                             "  locai-cli completions elvish > ~/.config/elvish/lib/locai-cli.elv"
                         );
-                        eprintln!("  (Elvish automatically loads completions from this directory)");
+                        errln!("  (Elvish automatically loads completions from this directory)");
                     }
                 }
             }
@@ -385,18 +643,18 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
 
         Commands::Clear => {
             if let Some(ctx) = context {
-                println!("Are you sure you want to clear all data? This cannot be undone.");
-                println!("Type 'yes' to confirm:");
+                outln!("Are you sure you want to clear all data? This cannot be undone.");
+                outln!("Type 'yes' to confirm:");
                 let mut input = String::new();
-                if let Err(e) = std::io::stdin().read_line(&mut input) {
+                if let Err(e) = invocation::stdin().read_line(&mut input) {
                     error!("Failed to read input: {}", e);
                     return Ok(());
                 }
                 if input.trim() == "yes" {
                     ctx.memory_manager.clear_storage().await?;
-                    println!("{}", format_success("Storage cleared successfully."));
+                    outln!("{}", format_success("Storage cleared successfully."));
                 } else {
-                    println!("{}", format_info("Operation cancelled."));
+                    outln!("{}", format_info("Operation cancelled."));
                 }
             }
         }
@@ -404,3 +662,107 @@ async fn run(cli_args: Cli, output_format: &str) -> locai::Result<()> {
 
     Ok(())
 }
+
+/// Commands worth sending to a daemon: those that open storage and can run
+/// against a shared context
+#[cfg(unix)]
+fn forwards_to_daemon(command: &Commands) -> bool {
+    !matches!(
+        command,
+        Commands::Version
+            | Commands::Completions(_)
+            | Commands::Bench(_)
+            | Commands::Config(_)
+            // The dashboard drives its terminal directly
+            | Commands::Tui(_)
+            | Commands::Serve(_)
+            | Commands::Daemon(_)
+    )
+}
+
+/// Run the command on a daemon serving the same data directory, returning
+/// its exit code, or `None` when no daemon is listening
+#[cfg(unix)]
+fn forward_to_daemon(cli_args: &Cli, output_format: &str) -> Option<i32> {
+    let socket = daemon::socket_path(cli_args.data_dir.as_deref()).ok()?;
+    let request = daemon::DaemonRequest {
+        args: std::env::args().skip(1).collect(),
+        output_format: output_format.to_string(),
+        color: colored::control::SHOULD_COLORIZE.should_colorize(),
+        cwd: std::env::current_dir().ok()?,
+    };
+    match daemon::forward(&socket, &request) {
+        Ok(exit_code) => exit_code,
+        Err(e) => {
+            crate::output::output_error_json(&e, output_format);
+            Some(1)
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn run_daemon(
+    args: args::DaemonArgs,
+    ctx: &LocaiCliContext,
+    data_dir: Option<&str>,
+) -> locai::Result<()> {
+    let socket = match args.socket {
+        Some(socket) => socket.into(),
+        None => daemon::socket_path(data_dir)?,
+    };
+    daemon::serve(&socket, |request| execute_daemon_request(request, ctx)).await
+}
+
+#[cfg(not(unix))]
+async fn run_daemon(
+    _args: args::DaemonArgs,
+    _ctx: &LocaiCliContext,
+    _data_dir: Option<&str>,
+) -> locai::Result<()> {
+    Err(LocaiError::Other(
+        "Daemon mode needs Unix domain sockets".to_string(),
+    ))
+}
+
+/// Run a forwarded command the way `main` would have in the client
+#[cfg(unix)]
+async fn execute_daemon_request(request: daemon::DaemonRequest, ctx: &LocaiCliContext) -> i32 {
+    let args = std::iter::once("locai-cli".to_string()).chain(request.args);
+    let cli_args = match Cli::try_parse_from(args) {
+        Ok(cli_args) => cli_args,
+        Err(e) => {
+            let rendered = e.render();
+            if e.use_stderr() {
+                errln!("{}", rendered.ansi());
+            } else {
+                outln!("{}", rendered.ansi());
+            }
+            return e.exit_code();
+        }
+    };
+
+    colored::control::set_override(request.color);
+    let shape =
+        template::OutputShape::parse(cli_args.fields.as_deref(), cli_args.template.as_deref());
+    let result = match shape {
+        Ok(shape) => {
+            template::set_output_shape(shape);
+            execute(
+                cli_args.command,
+                Some(ctx),
+                cli_args.data_dir.as_deref(),
+                &request.output_format,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            crate::output::output_error_json(&e, &request.output_format);
+            1
+        }
+    }
+}
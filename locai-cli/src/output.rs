@@ -1,8 +1,12 @@
 use colored::*;
+use locai::memory::{Diagram, DiagramEdge, DiagramNode};
 use locai::models::{MemoryPriority, MemoryType};
 use locai::prelude::Memory;
+use locai::replication::RecordKind;
 use locai::storage::models::{Entity, MemoryGraph, MemoryPath, Relationship};
+use serde::Serialize;
 use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 pub struct CliColors;
 
@@ -99,7 +103,7 @@ pub fn output_error(error_msg: &str, output_format: &str) {
             "message": error_msg,
             "timestamp": chrono::Utc::now().to_rfc3339()
         });
-        println!(
+        outln!(
             "{}",
             serde_json::to_string_pretty(&error_response).unwrap_or_else(|_| "{}".to_string())
         );
@@ -108,6 +112,20 @@ pub fn output_error(error_msg: &str, output_format: &str) {
     }
 }
 
+/// Print a JSON result, shaped by `--fields` and `--template` when given
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match crate::template::output_shape().filter(|shape| shape.is_set()) {
+        Some(shape) => {
+            let value = serde_json::to_value(value).unwrap_or_else(|_| json!({}));
+            outln!("{}", shape.render(value));
+        }
+        None => outln!(
+            "{}",
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| "{}".to_string())
+        ),
+    }
+}
+
 /// Output a LocaiError in structured JSON format
 pub fn output_error_json(error: &locai::LocaiError, output_format: &str) {
     if output_format == "json" {
@@ -120,6 +138,23 @@ pub fn output_error_json(error: &locai::LocaiError, output_format: &str) {
             locai::LocaiError::Entity(msg) => ("ENTITY_ERROR", msg.clone(), None),
             locai::LocaiError::Relationship(msg) => ("RELATIONSHIP_ERROR", msg.clone(), None),
             locai::LocaiError::Version(msg) => ("VERSION_ERROR", msg.clone(), None),
+            locai::LocaiError::Collection(msg) => ("COLLECTION_ERROR", msg.clone(), None),
+            locai::LocaiError::Template(msg) => ("TEMPLATE_ERROR", msg.clone(), None),
+            locai::LocaiError::Reminder(msg) => ("REMINDER_ERROR", msg.clone(), None),
+            locai::LocaiError::Task(msg) => ("TASK_ERROR", msg.clone(), None),
+            locai::LocaiError::Procedure(msg) => ("PROCEDURE_ERROR", msg.clone(), None),
+            locai::LocaiError::Preference(msg) => ("PREFERENCE_ERROR", msg.clone(), None),
+            locai::LocaiError::Persona(msg) => ("PERSONA_ERROR", msg.clone(), None),
+            locai::LocaiError::Session(msg) => ("SESSION_ERROR", msg.clone(), None),
+            locai::LocaiError::Reflection(msg) => ("REFLECTION_ERROR", msg.clone(), None),
+            locai::LocaiError::Filter(msg) => ("FILTER_ERROR", msg.clone(), None),
+            locai::LocaiError::Scoring(msg) => ("SCORING_ERROR", msg.clone(), None),
+            locai::LocaiError::InvalidEmbedding(msg) => ("INVALID_EMBEDDING", msg.clone(), None),
+            locai::LocaiError::Idempotency(msg) => ("IDEMPOTENCY_ERROR", msg.clone(), None),
+            locai::LocaiError::Rule(msg) => ("RULE_ERROR", msg.clone(), None),
+            locai::LocaiError::Review(msg) => ("REVIEW_ERROR", msg.clone(), None),
+            locai::LocaiError::Notification(msg) => ("NOTIFICATION_ERROR", msg.clone(), None),
+            locai::LocaiError::Usage(msg) => ("USAGE_ERROR", msg.clone(), None),
             locai::LocaiError::MLNotConfigured => (
                 "ML_NOT_CONFIGURED",
                 error.to_string(),
@@ -127,11 +162,12 @@ pub fn output_error_json(error: &locai::LocaiError, output_format: &str) {
                     "hint": "Initialize with Locai::builder().with_defaults().build().await or use ConfigBuilder::new().with_default_ml()"
                 })),
             ),
-            locai::LocaiError::StorageNotAccessible { path } => (
+            locai::LocaiError::StorageNotAccessible { path, hint } => (
                 "STORAGE_NOT_ACCESSIBLE",
                 error.to_string(),
                 Some(json!({
-                    "path": path
+                    "path": path,
+                    "hint": hint
                 })),
             ),
             locai::LocaiError::InvalidEmbeddingModel { model } => (
@@ -155,6 +191,21 @@ pub fn output_error_json(error: &locai::LocaiError, output_format: &str) {
                     "feature": feature
                 })),
             ),
+            locai::LocaiError::QuotaExceeded {
+                owner,
+                resource,
+                used,
+                limit,
+            } => (
+                "QUOTA_EXCEEDED",
+                error.to_string(),
+                Some(json!({
+                    "owner": owner,
+                    "resource": resource,
+                    "used": used,
+                    "limit": limit
+                })),
+            ),
             locai::LocaiError::Other(msg) => ("OTHER_ERROR", msg.clone(), None),
         };
 
@@ -169,12 +220,12 @@ pub fn output_error_json(error: &locai::LocaiError, output_format: &str) {
             error_response["details"] = details;
         }
 
-        eprintln!(
+        errln!(
             "{}",
             serde_json::to_string_pretty(&error_response).unwrap_or_else(|_| "{}".to_string())
         );
     } else {
-        eprintln!("{}", format_error(&error.to_string()));
+        errln!("{}", format_error(&error.to_string()));
     }
 }
 
@@ -218,7 +269,11 @@ pub fn format_memory_type(memory_type: &MemoryType) -> ColoredString {
         | MemoryType::World
         | MemoryType::Action
         | MemoryType::Event
-        | MemoryType::Wisdom => format!("{:?}", memory_type).color(CliColors::memory_semantic()),
+        | MemoryType::Wisdom
+        | MemoryType::Task
+        | MemoryType::Preference => {
+            format!("{:?}", memory_type).color(CliColors::memory_semantic())
+        }
         MemoryType::Conversation | MemoryType::Identity => {
             format!("{:?}", memory_type).color(CliColors::memory_episodic())
         }
@@ -236,31 +291,31 @@ pub fn format_priority(priority: &MemoryPriority) -> ColoredString {
 }
 
 pub fn print_memory(memory: &Memory) {
-    println!(
+    outln!(
         "{}",
         "━━━ Memory Details ━━━".color(CliColors::accent()).bold()
     );
-    println!(
+    outln!(
         "{}: {}",
         "ID".color(CliColors::muted()),
         memory.id.color(CliColors::accent()).bold()
     );
-    println!(
+    outln!(
         "{}: {}",
         "Type".color(CliColors::muted()),
         format_memory_type(&memory.memory_type)
     );
-    println!(
+    outln!(
         "{}: {}",
         "Priority".color(CliColors::muted()),
         format_priority(&memory.priority)
     );
-    println!(
+    outln!(
         "{}: {}",
         "Content".color(CliColors::muted()),
         memory.content
     );
-    println!(
+    outln!(
         "{}: {}",
         "Created".color(CliColors::muted()),
         memory
@@ -270,7 +325,7 @@ pub fn print_memory(memory: &Memory) {
             .color(CliColors::primary())
     );
     if let Some(last_accessed) = memory.last_accessed {
-        println!(
+        outln!(
             "{}: {}",
             "Last Accessed".color(CliColors::muted()),
             last_accessed
@@ -280,7 +335,7 @@ pub fn print_memory(memory: &Memory) {
         );
     }
     if !memory.tags.is_empty() {
-        println!(
+        outln!(
             "{}: {}",
             "Tags".color(CliColors::muted()),
             memory
@@ -293,7 +348,7 @@ pub fn print_memory(memory: &Memory) {
         );
     }
     if memory.embedding.is_some() {
-        println!(
+        outln!(
             "{}: {}",
             "Has Embedding".color(CliColors::muted()),
             "Yes".color(CliColors::success())
@@ -303,24 +358,24 @@ pub fn print_memory(memory: &Memory) {
 
 pub fn print_memory_list(memories: &[Memory]) {
     if memories.is_empty() {
-        println!("{}", format_info("No memories found."));
+        outln!("{}", format_info("No memories found."));
         return;
     }
 
-    println!(
+    outln!(
         "{}",
         format_info(&format!("Found {} memories:", memories.len()))
     );
-    println!();
+    outln!();
 
-    println!(
+    outln!(
         "{:<36} {:<15} {:<10} {}",
         "ID".color(CliColors::muted()).bold(),
         "Type".color(CliColors::muted()).bold(),
         "Priority".color(CliColors::muted()).bold(),
         "Content".color(CliColors::muted()).bold()
     );
-    println!("{}", "─".repeat(80).color(CliColors::muted()));
+    outln!("{}", "─".repeat(80).color(CliColors::muted()));
 
     for memory in memories {
         let content = if memory.content.len() > 50 {
@@ -329,7 +384,7 @@ pub fn print_memory_list(memories: &[Memory]) {
             memory.content.clone()
         };
 
-        println!(
+        outln!(
             "{:<36} {:<24} {:<18} {}",
             memory.id.color(CliColors::accent()),
             format_memory_type(&memory.memory_type),
@@ -347,7 +402,7 @@ pub async fn print_connected_memories_tree(
     use locai::LocaiError;
 
     if graph.memories.is_empty() {
-        println!("{}", format_info("No memories found."));
+        outln!("{}", format_info("No memories found."));
         return Ok(());
     }
 
@@ -357,14 +412,14 @@ pub async fn print_connected_memories_tree(
 
     let connected_count = graph.memories.len().saturating_sub(1);
     if connected_count > 0 {
-        println!(
+        outln!(
             "{}",
             format_info(&format!("Found {} connected memories:", connected_count))
         );
-        println!();
+        outln!();
     } else {
-        println!("{}", format_info("No connected memories found."));
-        println!();
+        outln!("{}", format_info("No connected memories found."));
+        outln!();
     }
 
     let mut adjacency: std::collections::HashMap<String, Vec<(String, String, bool)>> =
@@ -418,7 +473,7 @@ fn print_tree_node(
     };
 
     if is_root {
-        println!(
+        outln!(
             "{} {} [{}] {}",
             "●".color(CliColors::accent()).bold(),
             memory_id[..8].color(CliColors::accent()).bold(),
@@ -427,7 +482,7 @@ fn print_tree_node(
         );
     } else {
         let connector = if is_last { "└──" } else { "├──" };
-        println!(
+        outln!(
             "{}{} {} [{}] {}",
             prefix.color(CliColors::muted()),
             connector.color(CliColors::muted()),
@@ -484,7 +539,7 @@ fn print_tree_node(
                     .to_string()
             };
 
-            println!(
+            outln!(
                 "{}{} {}",
                 new_prefix.color(CliColors::muted()),
                 if is_last_child { "└─" } else { "├─" },
@@ -506,7 +561,7 @@ fn print_tree_node(
                 visited.remove(child_id);
             } else {
                 let connector = if is_last_child { "└─" } else { "├─" };
-                println!(
+                outln!(
                     "{}{} {} {}",
                     new_prefix.color(CliColors::muted()),
                     connector.color(CliColors::muted()),
@@ -521,21 +576,21 @@ fn print_tree_node(
 }
 
 pub fn print_entity(entity: &Entity) {
-    println!(
+    outln!(
         "{}",
         "━━━ Entity Details ━━━".color(CliColors::entity()).bold()
     );
-    println!(
+    outln!(
         "{}: {}",
         "ID".color(CliColors::muted()),
         entity.id.color(CliColors::entity()).bold()
     );
-    println!(
+    outln!(
         "{}: {}",
         "Type".color(CliColors::muted()),
         entity.entity_type.color(CliColors::entity())
     );
-    println!(
+    outln!(
         "{}: {}",
         "Created".color(CliColors::muted()),
         entity
@@ -544,7 +599,7 @@ pub fn print_entity(entity: &Entity) {
             .to_string()
             .color(CliColors::primary())
     );
-    println!(
+    outln!(
         "{}: {}",
         "Updated".color(CliColors::muted()),
         entity
@@ -554,7 +609,7 @@ pub fn print_entity(entity: &Entity) {
             .color(CliColors::primary())
     );
     if entity.properties != serde_json::Value::Null {
-        println!(
+        outln!(
             "{}: {}",
             "Properties".color(CliColors::muted()),
             serde_json::to_string_pretty(&entity.properties)
@@ -566,25 +621,25 @@ pub fn print_entity(entity: &Entity) {
 
 pub fn print_entity_list(entities: &[Entity]) {
     if entities.is_empty() {
-        println!("{}", format_info("No entities found."));
+        outln!("{}", format_info("No entities found."));
         return;
     }
 
-    println!(
+    outln!(
         "{}",
         format_info(&format!("Found {} entities:", entities.len()))
     );
-    println!();
+    outln!();
 
-    println!(
+    outln!(
         "{:<36} {}",
         "ID".color(CliColors::muted()).bold(),
         "Type".color(CliColors::muted()).bold()
     );
-    println!("{}", "─".repeat(60).color(CliColors::muted()));
+    outln!("{}", "─".repeat(60).color(CliColors::muted()));
 
     for entity in entities {
-        println!(
+        outln!(
             "{:<36} {}",
             entity.id.color(CliColors::accent()),
             entity.entity_type.color(CliColors::entity())
@@ -593,33 +648,33 @@ pub fn print_entity_list(entities: &[Entity]) {
 }
 
 pub fn print_relationship(relationship: &Relationship) {
-    println!(
+    outln!(
         "{}",
         "━━━ Relationship Details ━━━"
             .color(CliColors::info())
             .bold()
     );
-    println!(
+    outln!(
         "{}: {}",
         "ID".color(CliColors::muted()),
         relationship.id.color(CliColors::accent()).bold()
     );
-    println!(
+    outln!(
         "{}: {}",
         "Source".color(CliColors::muted()),
         relationship.source_id.color(CliColors::accent())
     );
-    println!(
+    outln!(
         "{}: {}",
         "Target".color(CliColors::muted()),
         relationship.target_id.color(CliColors::accent())
     );
-    println!(
+    outln!(
         "{}: {}",
         "Type".color(CliColors::muted()),
         relationship.relationship_type.color(CliColors::info())
     );
-    println!(
+    outln!(
         "{}: {}",
         "Created".color(CliColors::muted()),
         relationship
@@ -629,7 +684,7 @@ pub fn print_relationship(relationship: &Relationship) {
             .color(CliColors::primary())
     );
     if relationship.properties != serde_json::Value::Null {
-        println!(
+        outln!(
             "{}: {}",
             "Properties".color(CliColors::muted()),
             serde_json::to_string_pretty(&relationship.properties)
@@ -641,27 +696,27 @@ pub fn print_relationship(relationship: &Relationship) {
 
 pub fn print_relationship_list(relationships: &[Relationship]) {
     if relationships.is_empty() {
-        println!("{}", format_info("No relationships found."));
+        outln!("{}", format_info("No relationships found."));
         return;
     }
 
-    println!(
+    outln!(
         "{}",
         format_info(&format!("Found {} relationships:", relationships.len()))
     );
-    println!();
+    outln!();
 
-    println!(
+    outln!(
         "{:<20} {:<36} {:<36} {}",
         "Type".color(CliColors::muted()).bold(),
         "Source".color(CliColors::muted()).bold(),
         "Target".color(CliColors::muted()).bold(),
         "ID".color(CliColors::muted()).bold()
     );
-    println!("{}", "─".repeat(120).color(CliColors::muted()));
+    outln!("{}", "─".repeat(120).color(CliColors::muted()));
 
     for rel in relationships {
-        println!(
+        outln!(
             "{:<20} {:<36} {:<36} {}",
             rel.relationship_type.color(CliColors::info()),
             rel.source_id.color(CliColors::accent()),
@@ -672,16 +727,16 @@ pub fn print_relationship_list(relationships: &[Relationship]) {
 }
 
 pub fn print_memory_graph(graph: &MemoryGraph) {
-    println!(
+    outln!(
         "{}",
         "━━━ Memory Graph ━━━".color(CliColors::accent()).bold()
     );
-    println!(
+    outln!(
         "{}: {}",
         "Memories".color(CliColors::muted()),
         graph.memories.len().to_string().color(CliColors::success())
     );
-    println!(
+    outln!(
         "{}: {}",
         "Relationships".color(CliColors::muted()),
         graph
@@ -692,15 +747,15 @@ pub fn print_memory_graph(graph: &MemoryGraph) {
     );
 
     if !graph.memories.is_empty() {
-        println!();
-        println!("{}", "Memories:".color(CliColors::primary()).bold());
+        outln!();
+        outln!("{}", "Memories:".color(CliColors::primary()).bold());
         for memory in graph.memories.values() {
             let content = if memory.content.len() > 60 {
                 format!("{}...", &memory.content[..57])
             } else {
                 memory.content.clone()
             };
-            println!(
+            outln!(
                 "  {} [{}] {}",
                 "●".color(CliColors::accent()),
                 format_memory_type(&memory.memory_type),
@@ -710,10 +765,10 @@ pub fn print_memory_graph(graph: &MemoryGraph) {
     }
 
     if !graph.relationships.is_empty() {
-        println!();
-        println!("{}", "Relationships:".color(CliColors::primary()).bold());
+        outln!();
+        outln!("{}", "Relationships:".color(CliColors::primary()).bold());
         for rel in &graph.relationships {
-            println!(
+            outln!(
                 "  {} {} {} {}",
                 rel.source_id.color(CliColors::accent()),
                 "→".color(CliColors::info()),
@@ -724,16 +779,215 @@ pub fn print_memory_graph(graph: &MemoryGraph) {
     }
 }
 
+/// A memory or entity shown by `graph show`
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphViewNode {
+    pub id: String,
+
+    /// "memory" or "entity"
+    pub kind: String,
+
+    /// Memory content or entity name
+    pub label: String,
+
+    /// Memory type or entity type
+    pub node_type: String,
+}
+
+impl GraphViewNode {
+    pub fn from_memory(memory: &Memory) -> Self {
+        Self {
+            id: memory.id.clone(),
+            kind: "memory".to_string(),
+            label: memory.content.clone(),
+            node_type: memory.memory_type.to_string(),
+        }
+    }
+
+    pub fn from_entity(entity: &Entity) -> Self {
+        let label = entity
+            .properties
+            .get("name")
+            .and_then(|name| name.as_str())
+            .unwrap_or(&entity.id)
+            .to_string();
+        Self {
+            id: entity.id.clone(),
+            kind: "entity".to_string(),
+            label,
+            node_type: entity.entity_type.clone(),
+        }
+    }
+}
+
+/// The neighbourhood of a memory or entity, as shown by `graph show`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GraphView {
+    pub root_id: String,
+    pub nodes: BTreeMap<String, GraphViewNode>,
+    pub relationships: Vec<Relationship>,
+}
+
+fn truncate_label(label: &str, max_chars: usize) -> String {
+    let label = label.trim().replace('\n', " ");
+    if label.chars().count() > max_chars {
+        let cut: String = label.chars().take(max_chars - 3).collect();
+        format!("{}...", cut)
+    } else {
+        label
+    }
+}
+
+fn format_view_node(node: &GraphViewNode) -> String {
+    let short_id: String = node.id.chars().take(8).collect();
+    format!(
+        "{} [{}] {}",
+        truncate_label(&node.label, 50).color(CliColors::primary()),
+        node.node_type.color(CliColors::muted()),
+        short_id.color(CliColors::accent())
+    )
+}
+
+/// Print a graph view as a tree rooted at its root node
+///
+/// Relationships are followed in both directions; each node is expanded the
+/// first time it is reached and referenced after that.
+pub fn print_graph_tree(view: &GraphView) {
+    let Some(root) = view.nodes.get(&view.root_id) else {
+        outln!("{}", format_info("No graph found."));
+        return;
+    };
+
+    // node -> (relationship id, other node, relationship type, outgoing)
+    let mut adjacency: HashMap<&str, Vec<(&str, &str, &str, bool)>> = HashMap::new();
+    for rel in &view.relationships {
+        if !view.nodes.contains_key(&rel.source_id) || !view.nodes.contains_key(&rel.target_id) {
+            continue;
+        }
+        adjacency.entry(rel.source_id.as_str()).or_default().push((
+            rel.id.as_str(),
+            rel.target_id.as_str(),
+            rel.relationship_type.as_str(),
+            true,
+        ));
+        adjacency.entry(rel.target_id.as_str()).or_default().push((
+            rel.id.as_str(),
+            rel.source_id.as_str(),
+            rel.relationship_type.as_str(),
+            false,
+        ));
+    }
+
+    outln!(
+        "{} {}",
+        "●".color(CliColors::accent()).bold(),
+        format_view_node(root)
+    );
+    let mut visited = HashSet::from([view.root_id.as_str()]);
+    print_view_children(view, &adjacency, &view.root_id, None, "", &mut visited);
+}
+
+fn print_view_children<'a>(
+    view: &'a GraphView,
+    adjacency: &HashMap<&'a str, Vec<(&'a str, &'a str, &'a str, bool)>>,
+    node_id: &str,
+    via: Option<&str>,
+    prefix: &str,
+    visited: &mut HashSet<&'a str>,
+) {
+    let Some(edges) = adjacency.get(node_id) else {
+        return;
+    };
+    let edges: Vec<_> = edges
+        .iter()
+        .filter(|(rel_id, ..)| Some(*rel_id) != via)
+        .collect();
+
+    for (index, (rel_id, child_id, rel_type, outgoing)) in edges.iter().enumerate() {
+        let is_last = index == edges.len() - 1;
+        let connector = if is_last { "└──" } else { "├──" };
+        let edge = if *outgoing {
+            format!("─{}→", rel_type)
+        } else {
+            format!("←{}─", rel_type)
+        };
+        let first_visit = visited.insert(*child_id);
+
+        outln!(
+            "{}{} {} {}{}",
+            prefix.color(CliColors::muted()),
+            connector.color(CliColors::muted()),
+            edge.color(CliColors::info()),
+            format_view_node(&view.nodes[*child_id]),
+            if first_visit {
+                String::new()
+            } else {
+                " (see above)".color(CliColors::muted()).to_string()
+            }
+        );
+
+        if first_visit {
+            let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+            print_view_children(
+                view,
+                adjacency,
+                child_id,
+                Some(*rel_id),
+                &child_prefix,
+                visited,
+            );
+        }
+    }
+}
+
+/// Render a graph view as a Mermaid flowchart
+///
+/// Memories are drawn as boxes and entities as rounded nodes; the root comes
+/// first. The output is plain text, ready to paste into Markdown.
+pub fn render_graph_mermaid(view: &GraphView) -> String {
+    let node = |node: &GraphViewNode| DiagramNode {
+        id: node.id.clone(),
+        kind: if node.kind == "entity" {
+            RecordKind::Entity
+        } else {
+            RecordKind::Memory
+        },
+        label: node.label.clone(),
+        node_type: node.node_type.clone(),
+    };
+
+    let mut diagram = Diagram::default();
+    diagram
+        .nodes
+        .extend(view.nodes.get(&view.root_id).map(node));
+    diagram.nodes.extend(
+        view.nodes
+            .values()
+            .filter(|other| other.id != view.root_id)
+            .map(node),
+    );
+    diagram.edges = view
+        .relationships
+        .iter()
+        .map(|rel| DiagramEdge {
+            source_id: rel.source_id.clone(),
+            target_id: rel.target_id.clone(),
+            relationship_type: rel.relationship_type.clone(),
+        })
+        .collect();
+    diagram.to_mermaid()
+}
+
 pub fn print_paths(paths: &[MemoryPath]) {
     if paths.is_empty() {
-        println!("{}", format_info("No paths found."));
+        outln!("{}", format_info("No paths found."));
         return;
     }
 
-    println!("{}", format_info(&format!("Found {} paths:", paths.len())));
+    outln!("{}", format_info(&format!("Found {} paths:", paths.len())));
     for (i, path) in paths.iter().enumerate() {
-        println!();
-        println!(
+        outln!();
+        outln!(
             "{} {} {}",
             "Path".color(CliColors::primary()).bold(),
             format!("{}", i + 1).color(CliColors::accent()).bold(),
@@ -741,14 +995,14 @@ pub fn print_paths(paths: &[MemoryPath]) {
         );
         for (j, memory) in path.memories.iter().enumerate() {
             if j > 0 {
-                println!("  {}", "↓".color(CliColors::info()));
+                outln!("  {}", "↓".color(CliColors::info()));
             }
             let content = if memory.content.len() > 60 {
                 format!("{}...", &memory.content[..57])
             } else {
                 memory.content.clone()
             };
-            println!(
+            outln!(
                 "  {} [{}] {}",
                 "●".color(CliColors::accent()),
                 format_memory_type(&memory.memory_type),
@@ -0,0 +1,294 @@
+//! Interactive search query builder
+//!
+//! Walks through the `memory search` options with prompts and renders the
+//! equivalent non-interactive command, so a search found by exploring can be
+//! reused in scripts.
+
+use std::io::{BufRead, Write};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use locai::LocaiError;
+
+use crate::args::SearchArgs;
+use crate::utils::parse_memory_type;
+
+const DEFAULT_MODE: &str = "hybrid";
+const DEFAULT_LIMIT: usize = 10;
+
+/// Prompt for each search option, starting from the values already in `args`
+///
+/// Prompts are written to `out` so that stdout stays free for results.
+pub fn prompt_search_args<R: BufRead, W: Write>(
+    input: &mut R,
+    out: &mut W,
+    mut args: SearchArgs,
+) -> locai::Result<SearchArgs> {
+    let query = ask(input, out, "Search query", args.query.as_deref(), |s| {
+        if s.is_empty() {
+            Err("The query can't be empty".to_string())
+        } else {
+            Ok(s.to_string())
+        }
+    })?;
+    args.query = Some(query);
+
+    args.mode = ask(
+        input,
+        out,
+        "Strategy (hybrid, text, semantic)",
+        Some(&args.mode),
+        |s| match s {
+            "hybrid" | "text" | "semantic" => Ok(s.to_string()),
+            "vector" => Ok("semantic".to_string()),
+            "keyword" | "bm25" => Ok("text".to_string()),
+            _ => Err(format!("Unknown strategy '{}'", s)),
+        },
+    )?;
+
+    if args.mode != "text" {
+        args.threshold = ask_optional(
+            input,
+            out,
+            "Minimum similarity, 0.0 to 1.0 (blank for none)",
+            args.threshold.map(|t| t.to_string()).as_deref(),
+            |s| match s.parse::<f32>() {
+                Ok(t) if (0.0..=1.0).contains(&t) => Ok(t),
+                _ => Err("Enter a number between 0.0 and 1.0".to_string()),
+            },
+        )?;
+    }
+
+    args.memory_type = ask_optional(
+        input,
+        out,
+        "Memory type: fact, conversation, procedural, episodic, identity, world, action, event (blank for any)",
+        args.memory_type.as_deref(),
+        |s| {
+            parse_memory_type(s)
+                .map(|_| s.to_string())
+                .map_err(|e| e.to_string())
+        },
+    )?;
+
+    args.tag = ask_optional(
+        input,
+        out,
+        "Tag (blank for any)",
+        args.tag.as_deref(),
+        |s| Ok(s.to_string()),
+    )?;
+
+    let now = Utc::now();
+    args.created_after = ask_optional(
+        input,
+        out,
+        "Created after: RFC 3339, YYYY-MM-DD or an age like 7d or 12h (blank for no limit)",
+        args.created_after.as_deref(),
+        |s| parse_time(s, now).map(|t| t.to_rfc3339()),
+    )?;
+    args.created_before = ask_optional(
+        input,
+        out,
+        "Created before (blank for no limit)",
+        args.created_before.as_deref(),
+        |s| parse_time(s, now).map(|t| t.to_rfc3339()),
+    )?;
+
+    args.limit = ask(
+        input,
+        out,
+        "Maximum results",
+        Some(&args.limit.to_string()),
+        |s| match s.parse::<usize>() {
+            Ok(limit) if limit > 0 => Ok(limit),
+            _ => Err("Enter a whole number of at least 1".to_string()),
+        },
+    )?;
+
+    args.include_archived = ask(
+        input,
+        out,
+        "Include archived memories? (y/n)",
+        Some(if args.include_archived { "y" } else { "n" }),
+        |s| match s.to_lowercase().as_str() {
+            "y" | "yes" => Ok(true),
+            "n" | "no" => Ok(false),
+            _ => Err("Answer y or n".to_string()),
+        },
+    )?;
+
+    Ok(args)
+}
+
+/// The `memory search` command that runs the same search without prompts
+pub fn equivalent_command(args: &SearchArgs) -> String {
+    let mut parts = vec![
+        "locai-cli memory search".to_string(),
+        shell_quote(args.query.as_deref().unwrap_or_default()),
+    ];
+    if args.mode != DEFAULT_MODE {
+        parts.push(format!("--mode {}", args.mode));
+    }
+    if args.limit != DEFAULT_LIMIT {
+        parts.push(format!("--limit {}", args.limit));
+    }
+    if let Some(threshold) = args.threshold {
+        parts.push(format!("--threshold {}", threshold));
+    }
+    if let Some(memory_type) = &args.memory_type {
+        parts.push(format!("--memory-type {}", shell_quote(memory_type)));
+    }
+    if let Some(tag) = &args.tag {
+        parts.push(format!("--tag {}", shell_quote(tag)));
+    }
+    if let Some(created_after) = &args.created_after {
+        parts.push(format!("--created-after {}", shell_quote(created_after)));
+    }
+    if let Some(created_before) = &args.created_before {
+        parts.push(format!("--created-before {}", shell_quote(created_before)));
+    }
+    if let Some(collection) = &args.collection {
+        parts.push(format!("--collection {}", shell_quote(collection)));
+    }
+    if let Some(pin_scope) = &args.pin_scope {
+        parts.push(format!("--pin-scope {}", shell_quote(pin_scope)));
+    }
+    if args.include_archived {
+        parts.push("--include-archived".to_string());
+    }
+    for property in &args.properties {
+        parts.push(format!("--property {}", shell_quote(property)));
+    }
+    if let Some(filter) = &args.filter {
+        parts.push(format!("--filter {}", shell_quote(filter)));
+    }
+    if args.explain_plan {
+        parts.push("--explain-plan".to_string());
+    }
+    if let Some(near) = &args.near {
+        parts.push(format!(
+            "--near {} --radius {}",
+            shell_quote(near),
+            args.radius
+        ));
+    }
+    parts.join(" ")
+}
+
+/// Parse an RFC 3339 timestamp, a date (midnight UTC), or an age such as `7d`
+pub fn parse_time(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    let invalid = || format!("Can't read '{}' as a time", value);
+    let unit = value.chars().last().ok_or_else(invalid)?;
+    let amount: i64 = value[..value.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    let age = match unit {
+        'm' => Duration::minutes(amount),
+        'h' => Duration::hours(amount),
+        'd' => Duration::days(amount),
+        'w' => Duration::weeks(amount),
+        _ => return Err(invalid()),
+    };
+    Ok(now - age)
+}
+
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:+/@".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Ask until `parse` accepts the answer; a blank answer takes `default`
+fn ask<R, W, T>(
+    input: &mut R,
+    out: &mut W,
+    prompt: &str,
+    default: Option<&str>,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> locai::Result<T>
+where
+    R: BufRead,
+    W: Write,
+{
+    loop {
+        let answer = read_answer(input, out, prompt, default)?;
+        let answer = if answer.is_empty() {
+            default.unwrap_or_default()
+        } else {
+            answer.as_str()
+        };
+        match parse(answer) {
+            Ok(value) => return Ok(value),
+            Err(e) => write_line(out, &format!("  {}", e))?,
+        }
+    }
+}
+
+/// Like [`ask`], but a blank answer with no default, or `-`, means "none"
+fn ask_optional<R, W, T>(
+    input: &mut R,
+    out: &mut W,
+    prompt: &str,
+    default: Option<&str>,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> locai::Result<Option<T>>
+where
+    R: BufRead,
+    W: Write,
+{
+    loop {
+        let answer = read_answer(input, out, prompt, default)?;
+        let answer = match (answer.as_str(), default) {
+            ("-", _) | ("", None) => return Ok(None),
+            ("", Some(default)) => default,
+            (answer, _) => answer,
+        };
+        match parse(answer) {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => write_line(out, &format!("  {}", e))?,
+        }
+    }
+}
+
+fn read_answer<R: BufRead, W: Write>(
+    input: &mut R,
+    out: &mut W,
+    prompt: &str,
+    default: Option<&str>,
+) -> locai::Result<String> {
+    match default {
+        Some(default) => write!(out, "{} [{}]: ", prompt, default),
+        None => write!(out, "{}: ", prompt),
+    }
+    .and_then(|_| out.flush())
+    .map_err(|e| LocaiError::Other(format!("Failed to write prompt: {}", e)))?;
+
+    let mut answer = String::new();
+    let read = input
+        .read_line(&mut answer)
+        .map_err(|e| LocaiError::Other(format!("Failed to read input: {}", e)))?;
+    if read == 0 {
+        return Err(LocaiError::Other(
+            "Input ended before the search was complete".to_string(),
+        ));
+    }
+    Ok(answer.trim().to_string())
+}
+
+fn write_line<W: Write>(out: &mut W, line: &str) -> locai::Result<()> {
+    writeln!(out, "{}", line)
+        .map_err(|e| LocaiError::Other(format!("Failed to write prompt: {}", e)))
+}
@@ -0,0 +1,141 @@
+//! CSV and Markdown table output
+//!
+//! `--output csv` and `--output markdown` render list and search results as
+//! plain tables that paste straight into spreadsheets and docs. Cells hold
+//! the full values, without colors or truncation.
+
+use locai::prelude::Memory;
+use locai::storage::models::{Entity, Relationship};
+
+/// A table-shaped `--output` format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Markdown,
+}
+
+impl TableFormat {
+    /// The table format named by `--output`, if it is one
+    pub fn from_output(output_format: &str) -> Option<Self> {
+        match output_format {
+            "csv" => Some(Self::Csv),
+            "markdown" | "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// Rows of plain-text cells under a header row
+#[derive(Debug, Clone)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Render with a trailing newline
+    pub fn render(&self, format: TableFormat) -> String {
+        let mut out = String::new();
+        match format {
+            TableFormat::Csv => {
+                for row in std::iter::once(&self.headers).chain(&self.rows) {
+                    let cells: Vec<String> = row.iter().map(|c| csv_field(c)).collect();
+                    out.push_str(&cells.join(","));
+                    out.push('\n');
+                }
+            }
+            TableFormat::Markdown => {
+                let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+                out.push_str(&line(
+                    self.headers.iter().map(|h| markdown_cell(h)).collect(),
+                ));
+                out.push_str(&line(
+                    self.headers.iter().map(|_| "---".to_string()).collect(),
+                ));
+                for row in &self.rows {
+                    out.push_str(&line(row.iter().map(|c| markdown_cell(c)).collect()));
+                }
+            }
+        }
+        out
+    }
+
+    pub fn print(&self, format: TableFormat) {
+        out!("{}", self.render(format));
+    }
+}
+
+pub fn memory_table(memories: &[Memory]) -> Table {
+    let mut table = Table::new(&["ID", "Type", "Priority", "Tags", "Created", "Content"]);
+    for memory in memories {
+        table.push_row(memory_cells(memory));
+    }
+    table
+}
+
+/// The [`memory_table`] cells for one memory
+pub fn memory_cells(memory: &Memory) -> Vec<String> {
+    vec![
+        memory.id.clone(),
+        memory.memory_type.to_string(),
+        format!("{:?}", memory.priority),
+        memory.tags.join(", "),
+        memory.created_at.to_rfc3339(),
+        memory.content.clone(),
+    ]
+}
+
+pub fn entity_table(entities: &[Entity]) -> Table {
+    let mut table = Table::new(&["ID", "Type", "Created", "Properties"]);
+    for entity in entities {
+        table.push_row(vec![
+            entity.id.clone(),
+            entity.entity_type.clone(),
+            entity.created_at.to_rfc3339(),
+            entity.properties.to_string(),
+        ]);
+    }
+    table
+}
+
+pub fn relationship_table(relationships: &[Relationship]) -> Table {
+    let mut table = Table::new(&["ID", "Type", "Source", "Target", "Created"]);
+    for relationship in relationships {
+        table.push_row(vec![
+            relationship.id.clone(),
+            relationship.relationship_type.clone(),
+            relationship.source_id.clone(),
+            relationship.target_id.clone(),
+            relationship.created_at.to_rfc3339(),
+        ]);
+    }
+    table
+}
+
+/// Quote a CSV field when it holds a delimiter, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape pipes and keep multi-line values on one table row
+fn markdown_cell(value: &str) -> String {
+    value
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
@@ -0,0 +1,296 @@
+//! Output shaping for tool integrations
+//!
+//! `--fields` trims JSON output to the listed fields and `--template` renders
+//! each record through a small template language instead of printing JSON:
+//!
+//! ```text
+//! {{id}}\t{{content | truncate 80}}
+//! ```
+//!
+//! Paths are dot separated (`memory.id`, `tags.0`) and `.` is the record
+//! itself. Filters: `truncate N`, `upper`, `lower`, `join "sep"`,
+//! `default "text"` and `json`. When the output is an array, each element is
+//! a record; otherwise the whole value is.
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use locai::LocaiError;
+use serde_json::{Map, Value};
+
+static OUTPUT_SHAPE: RwLock<Option<Arc<OutputShape>>> = RwLock::new(None);
+
+/// How JSON output is trimmed and rendered
+#[derive(Debug, Default)]
+pub struct OutputShape {
+    pub fields: Option<Vec<String>>,
+    pub template: Option<Template>,
+}
+
+impl OutputShape {
+    /// Build from the `--fields` and `--template` arguments
+    pub fn parse(fields: Option<&str>, template: Option<&str>) -> locai::Result<Self> {
+        let fields = fields.map(|fields| {
+            fields
+                .split(',')
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(str::to_string)
+                .collect()
+        });
+        let template = template.map(Template::parse).transpose()?;
+        Ok(Self { fields, template })
+    }
+
+    /// Whether any shaping was requested
+    pub fn is_set(&self) -> bool {
+        self.fields.is_some() || self.template.is_some()
+    }
+
+    /// Shape `value` into the text to print
+    pub fn render(&self, value: Value) -> String {
+        let value = match &self.fields {
+            Some(fields) => map_records(value, |record| select_fields(record, fields)),
+            None => value,
+        };
+        match &self.template {
+            Some(template) => records(&value)
+                .map(|record| template.render(record))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => serde_json::to_string_pretty(&value).unwrap_or_else(|_| "{}".to_string()),
+        }
+    }
+}
+
+/// Install the shape used by [`crate::output::print_json`], replacing any
+/// earlier one (the daemon installs one per request)
+pub fn set_output_shape(shape: OutputShape) {
+    *OUTPUT_SHAPE.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(shape));
+}
+
+/// The installed shape, if any
+pub fn output_shape() -> Option<Arc<OutputShape>> {
+    OUTPUT_SHAPE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Keep only the dot-separated `fields` of `record`, preserving nesting
+pub fn select_fields(record: &Value, fields: &[String]) -> Value {
+    let mut selected = Value::Object(Map::new());
+    for field in fields {
+        if let Some(value) = lookup(record, field) {
+            insert_path(&mut selected, field, value.clone());
+        }
+    }
+    selected
+}
+
+fn map_records(value: Value, f: impl Fn(&Value) -> Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.iter().map(f).collect()),
+        value => f(&value),
+    }
+}
+
+fn records(value: &Value) -> Box<dyn Iterator<Item = &Value> + '_> {
+    match value {
+        Value::Array(items) => Box::new(items.iter()),
+        value => Box::new(std::iter::once(value)),
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path == "." {
+        return Some(value);
+    }
+    path.split('.').try_fold(value, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+fn insert_path(target: &mut Value, path: &str, value: Value) {
+    let mut keys = path.split('.').peekable();
+    let mut current = target;
+    while let Some(key) = keys.next() {
+        let Value::Object(map) = current else {
+            return;
+        };
+        if keys.peek().is_none() {
+            map.insert(key.to_string(), value);
+            return;
+        }
+        current = map
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+/// A parsed `--template`
+#[derive(Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug)]
+enum Part {
+    Text(String),
+    Field { path: String, filters: Vec<Filter> },
+}
+
+#[derive(Debug)]
+enum Filter {
+    Truncate(usize),
+    Upper,
+    Lower,
+    Join(String),
+    Default(String),
+    Json,
+}
+
+impl Template {
+    /// Parse a template, unescaping `\t`, `\n` and `\\` in the literal text
+    pub fn parse(source: &str) -> locai::Result<Self> {
+        let mut parts = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find("{{") {
+            parts.push(Part::Text(unescape(&rest[..start])));
+            let end = rest[start..].find("}}").ok_or_else(|| {
+                LocaiError::Other(format!("Unclosed '{{{{' in template '{}'", source))
+            })? + start;
+            parts.push(parse_field(rest[start + 2..end].trim())?);
+            rest = &rest[end + 2..];
+        }
+        parts.push(Part::Text(unescape(rest)));
+        parts.retain(|part| !matches!(part, Part::Text(text) if text.is_empty()));
+        Ok(Self { parts })
+    }
+
+    /// Render the template against one record
+    pub fn render(&self, record: &Value) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Field { path, filters } => {
+                    let value = lookup(record, path).cloned().unwrap_or(Value::Null);
+                    let value = filters.iter().fold(value, apply_filter);
+                    out.push_str(&display(&value));
+                }
+            }
+        }
+        out
+    }
+}
+
+fn parse_field(expression: &str) -> locai::Result<Part> {
+    let mut segments = expression.split('|').map(str::trim);
+    let path = segments.next().unwrap_or_default();
+    if path.is_empty() {
+        return Err(LocaiError::Other(
+            "Empty field in template; use {{.}} for the whole record".to_string(),
+        ));
+    }
+    let filters = segments.map(parse_filter).collect::<locai::Result<_>>()?;
+    Ok(Part::Field {
+        path: path.to_string(),
+        filters,
+    })
+}
+
+fn parse_filter(filter: &str) -> locai::Result<Filter> {
+    let (name, argument) = match filter.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, Some(argument.trim())),
+        None => (filter, None),
+    };
+    let text_argument = || {
+        argument
+            .map(|a| a.trim_matches('"').to_string())
+            .ok_or_else(|| LocaiError::Other(format!("Template filter '{}' needs a value", name)))
+    };
+    match name {
+        "truncate" => argument
+            .and_then(|a| a.parse().ok())
+            .map(Filter::Truncate)
+            .ok_or_else(|| {
+                LocaiError::Other("Template filter 'truncate' needs a length".to_string())
+            }),
+        "upper" => Ok(Filter::Upper),
+        "lower" => Ok(Filter::Lower),
+        "join" => Ok(Filter::Join(
+            text_argument().unwrap_or_else(|_| ", ".to_string()),
+        )),
+        "default" => text_argument().map(Filter::Default),
+        "json" => Ok(Filter::Json),
+        _ => Err(LocaiError::Other(format!(
+            "Unknown template filter '{}' (expected truncate, upper, lower, join, default or json)",
+            name
+        ))),
+    }
+}
+
+fn apply_filter(value: Value, filter: &Filter) -> Value {
+    match filter {
+        Filter::Truncate(max_chars) => {
+            let text = display(&value);
+            if text.chars().count() > *max_chars {
+                let cut: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+                Value::String(format!("{}...", cut))
+            } else {
+                Value::String(text)
+            }
+        }
+        Filter::Upper => Value::String(display(&value).to_uppercase()),
+        Filter::Lower => Value::String(display(&value).to_lowercase()),
+        Filter::Join(separator) => match &value {
+            Value::Array(items) => Value::String(
+                items
+                    .iter()
+                    .map(display)
+                    .collect::<Vec<_>>()
+                    .join(separator),
+            ),
+            _ => value,
+        },
+        Filter::Default(default) => match &value {
+            Value::Null => Value::String(default.clone()),
+            Value::String(s) if s.is_empty() => Value::String(default.clone()),
+            _ => value,
+        },
+        Filter::Json => Value::String(value.to_string()),
+    }
+}
+
+/// Strings print bare, missing values print empty, everything else as JSON
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
@@ -0,0 +1,931 @@
+//! Terminal dashboard for browsing memories
+//!
+//! `locai-cli tui` has four panes: recent memories, search, an entity browser
+//! and a graph view that walks the relationships around one memory or entity
+//! at a time. Memories can be edited (in `$VISUAL` or `$EDITOR`), tagged and
+//! deleted in place. Errors from storage show in the status line rather than
+//! ending the session.
+
+use std::process::Command;
+
+use locai::LocaiError;
+use locai::memory::search_extensions::SearchMode;
+use locai::prelude::Memory;
+use locai::storage::filters::RelationshipFilter;
+use locai::storage::models::{Entity, Relationship};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::context::LocaiCliContext;
+use crate::handlers::graph::resolve_view_node;
+use crate::output::GraphViewNode;
+
+// The CLI's accent, muted and entity colors
+const ACCENT: Color = Color::Rgb(59, 130, 246);
+const MUTED: Color = Color::Rgb(148, 163, 184);
+const ENTITY: Color = Color::Rgb(245, 158, 11);
+
+/// Run the dashboard until the user quits
+pub async fn run(ctx: &LocaiCliContext, limit: usize) -> locai::Result<()> {
+    let mut app = App::new(ctx, limit);
+    app.refresh_recent().await;
+    app.refresh_entities().await;
+
+    let mut terminal = ratatui::try_init().map_err(terminal_error)?;
+    let result = app.run(&mut terminal).await;
+    ratatui::restore();
+    result
+}
+
+fn terminal_error(e: std::io::Error) -> LocaiError {
+    LocaiError::Other(format!("Terminal error: {}", e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Recent,
+    Search,
+    Entities,
+    Graph,
+}
+
+impl Pane {
+    const ALL: [Pane; 4] = [Pane::Recent, Pane::Search, Pane::Entities, Pane::Graph];
+
+    fn title(self) -> &'static str {
+        match self {
+            Pane::Recent => "Recent",
+            Pane::Search => "Search",
+            Pane::Entities => "Entities",
+            Pane::Graph => "Graph",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|pane| *pane == self).unwrap_or(0)
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Where keystrokes go
+enum Mode {
+    Browse,
+    /// Typing a search query into `input`
+    Query,
+    /// Typing a tag for the memory with this ID into `input`
+    Tag(String),
+    /// Waiting for y/n before deleting the memory with this ID
+    ConfirmDelete(String),
+    Help,
+}
+
+/// A node one relationship away from the graph pane's center
+struct Neighbor {
+    relationship: Relationship,
+    node: GraphViewNode,
+    /// Whether the relationship points away from the center
+    outgoing: bool,
+}
+
+struct App<'a> {
+    ctx: &'a LocaiCliContext,
+    limit: usize,
+    pane: Pane,
+    mode: Mode,
+    input: String,
+    status: String,
+    quit: bool,
+
+    recent: Vec<Memory>,
+    recent_state: ListState,
+
+    query: String,
+    results: Vec<(Memory, Option<f32>)>,
+    results_state: ListState,
+
+    entities: Vec<Entity>,
+    entities_state: ListState,
+
+    /// The node the graph pane is centered on
+    center: Option<GraphViewNode>,
+    /// Earlier centers, for Backspace
+    history: Vec<String>,
+    neighbors: Vec<Neighbor>,
+    neighbors_state: ListState,
+}
+
+impl<'a> App<'a> {
+    fn new(ctx: &'a LocaiCliContext, limit: usize) -> Self {
+        Self {
+            ctx,
+            limit,
+            pane: Pane::Recent,
+            mode: Mode::Browse,
+            input: String::new(),
+            status: String::new(),
+            quit: false,
+            recent: Vec::new(),
+            recent_state: ListState::default(),
+            query: String::new(),
+            results: Vec::new(),
+            results_state: ListState::default(),
+            entities: Vec::new(),
+            entities_state: ListState::default(),
+            center: None,
+            history: Vec::new(),
+            neighbors: Vec::new(),
+            neighbors_state: ListState::default(),
+        }
+    }
+
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> locai::Result<()> {
+        while !self.quit {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .map_err(terminal_error)?;
+            if let Event::Key(key) = event::read().map_err(terminal_error)?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key, terminal).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // Loading
+
+    async fn refresh_recent(&mut self) {
+        match self
+            .ctx
+            .memory_manager
+            .get_recent_memories(self.limit)
+            .await
+        {
+            Ok(memories) => self.recent = memories,
+            Err(e) => self.status = format!("Failed to load recent memories: {}", e),
+        }
+        clamp_selection(&mut self.recent_state, self.recent.len());
+    }
+
+    async fn run_search(&mut self) {
+        if self.query.is_empty() {
+            self.results.clear();
+        } else {
+            match self
+                .ctx
+                .memory_manager
+                .search(&self.query, Some(self.limit), None, SearchMode::Text)
+                .await
+            {
+                Ok(results) => {
+                    self.results = results.into_iter().map(|r| (r.memory, r.score)).collect();
+                    if self.results.is_empty() {
+                        self.status = format!("No memories match '{}'", self.query);
+                    }
+                }
+                Err(e) => self.status = format!("Search failed: {}", e),
+            }
+        }
+        clamp_selection(&mut self.results_state, self.results.len());
+    }
+
+    async fn refresh_entities(&mut self) {
+        match self
+            .ctx
+            .memory_manager
+            .list_entities(None, Some(self.limit), None)
+            .await
+        {
+            Ok(entities) => self.entities = entities,
+            Err(e) => self.status = format!("Failed to load entities: {}", e),
+        }
+        clamp_selection(&mut self.entities_state, self.entities.len());
+    }
+
+    /// Center the graph pane on `id`, remembering the current center
+    async fn open_graph(&mut self, id: String) {
+        let previous = self.center.as_ref().map(|center| center.id.clone());
+        self.pane = Pane::Graph;
+        if self.load_graph(id.clone()).await
+            && let Some(previous) = previous
+            && previous != id
+        {
+            self.history.push(previous);
+        }
+    }
+
+    async fn graph_back(&mut self) {
+        match self.history.pop() {
+            Some(id) => {
+                self.load_graph(id).await;
+            }
+            None => self.status = "Nothing to go back to".to_string(),
+        }
+    }
+
+    /// Load the neighborhood of `id`, returning whether it exists
+    async fn load_graph(&mut self, id: String) -> bool {
+        let center = match resolve_view_node(self.ctx, &id).await {
+            Ok(Some(center)) => center,
+            Ok(None) => {
+                self.status = format!("'{}' is not a memory or entity", id);
+                return false;
+            }
+            Err(e) => {
+                self.status = format!("Failed to load '{}': {}", id, e);
+                return false;
+            }
+        };
+
+        let mut neighbors = Vec::new();
+        for outgoing in [true, false] {
+            let filter = if outgoing {
+                RelationshipFilter {
+                    source_id: Some(id.clone()),
+                    ..Default::default()
+                }
+            } else {
+                RelationshipFilter {
+                    target_id: Some(id.clone()),
+                    ..Default::default()
+                }
+            };
+            let relationships = match self
+                .ctx
+                .memory_manager
+                .list_relationships(Some(filter), Some(self.limit), None)
+                .await
+            {
+                Ok(relationships) => relationships,
+                Err(e) => {
+                    self.status = format!("Failed to load relationships: {}", e);
+                    continue;
+                }
+            };
+            for relationship in relationships {
+                let other_id = if outgoing {
+                    &relationship.target_id
+                } else {
+                    &relationship.source_id
+                };
+                // Skip relationships whose other end is gone
+                if let Ok(Some(node)) = resolve_view_node(self.ctx, other_id).await {
+                    neighbors.push(Neighbor {
+                        relationship,
+                        node,
+                        outgoing,
+                    });
+                }
+            }
+        }
+
+        self.center = Some(center);
+        self.neighbors = neighbors;
+        self.neighbors_state.select(None);
+        clamp_selection(&mut self.neighbors_state, self.neighbors.len());
+        true
+    }
+
+    async fn refresh(&mut self) {
+        match self.pane {
+            Pane::Recent => self.refresh_recent().await,
+            Pane::Search => self.run_search().await,
+            Pane::Entities => self.refresh_entities().await,
+            Pane::Graph => {
+                if let Some(center) = &self.center {
+                    self.load_graph(center.id.clone()).await;
+                }
+            }
+        }
+    }
+
+    // Editing
+
+    async fn edit_memory(&mut self, terminal: &mut DefaultTerminal) -> locai::Result<()> {
+        let Some(mut memory) = self.selected_memory().cloned() else {
+            self.status = "Select a memory to edit".to_string();
+            return Ok(());
+        };
+
+        ratatui::restore();
+        let edited = edit_in_editor(&memory.content);
+        *terminal = ratatui::try_init().map_err(terminal_error)?;
+        terminal.clear().map_err(terminal_error)?;
+
+        let content = match edited {
+            Ok(content) => content.trim_end().to_string(),
+            Err(e) => {
+                self.status = format!("Edit cancelled: {}", e);
+                return Ok(());
+            }
+        };
+        if content == memory.content || content.is_empty() {
+            self.status = "Memory unchanged".to_string();
+            return Ok(());
+        }
+
+        memory.content = content;
+        memory.embedding = None;
+        match self.ctx.memory_manager.update_memory(memory.clone()).await {
+            Ok(_) => {
+                self.status = format!("Updated {}", memory.id);
+                self.replace_memory(memory);
+            }
+            Err(e) => self.status = format!("Failed to update {}: {}", memory.id, e),
+        }
+        Ok(())
+    }
+
+    async fn tag_memory(&mut self, id: &str) {
+        let tag = self.input.trim().to_string();
+        if tag.is_empty() {
+            return;
+        }
+        match self.ctx.memory_manager.tag_memory(id, &tag).await {
+            Ok(_) => {
+                self.status = format!("Tagged {} with '{}'", id, tag);
+                if let Ok(Some(memory)) = self.ctx.memory_manager.get_memory(id).await {
+                    self.replace_memory(memory);
+                }
+            }
+            Err(e) => self.status = format!("Failed to tag {}: {}", id, e),
+        }
+    }
+
+    async fn delete_memory(&mut self, id: &str) {
+        match self.ctx.memory_manager.delete_memory(id).await {
+            Ok(true) => {
+                self.status = format!("Deleted {}", id);
+                self.recent.retain(|memory| memory.id != id);
+                self.results.retain(|(memory, _)| memory.id != id);
+                clamp_selection(&mut self.recent_state, self.recent.len());
+                clamp_selection(&mut self.results_state, self.results.len());
+            }
+            Ok(false) => self.status = format!("{} was already gone", id),
+            Err(e) => self.status = format!("Failed to delete {}: {}", id, e),
+        }
+    }
+
+    /// Show an updated memory wherever it is listed
+    fn replace_memory(&mut self, memory: Memory) {
+        let listed = self
+            .recent
+            .iter_mut()
+            .chain(self.results.iter_mut().map(|(listed, _)| listed));
+        for stale in listed.filter(|listed| listed.id == memory.id) {
+            *stale = memory.clone();
+        }
+    }
+
+    // Input
+
+    async fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        terminal: &mut DefaultTerminal,
+    ) -> locai::Result<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return Ok(());
+        }
+        self.status.clear();
+
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => self.handle_browse_key(key, terminal).await?,
+            // Any key closes the help
+            Mode::Help => {}
+            Mode::Query => match key.code {
+                KeyCode::Enter => {
+                    self.query = self.input.trim().to_string();
+                    self.run_search().await;
+                }
+                KeyCode::Esc => {}
+                code => {
+                    edit_input(&mut self.input, code);
+                    self.mode = Mode::Query;
+                }
+            },
+            Mode::Tag(id) => match key.code {
+                KeyCode::Enter => self.tag_memory(&id).await,
+                KeyCode::Esc => {}
+                code => {
+                    edit_input(&mut self.input, code);
+                    self.mode = Mode::Tag(id);
+                }
+            },
+            Mode::ConfirmDelete(id) => {
+                if matches!(key.code, KeyCode::Char('y' | 'Y')) {
+                    self.delete_memory(&id).await;
+                } else {
+                    self.status = "Kept the memory".to_string();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_browse_key(
+        &mut self,
+        key: KeyEvent,
+        terminal: &mut DefaultTerminal,
+    ) -> locai::Result<()> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab => self.pane = self.pane.next(),
+            KeyCode::BackTab => self.pane = self.pane.previous(),
+            KeyCode::Char(c @ '1'..='4') => self.pane = Pane::ALL[c as usize - '1' as usize],
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Char('/') => {
+                self.pane = Pane::Search;
+                self.input = self.query.clone();
+                self.mode = Mode::Query;
+            }
+            KeyCode::Enter | KeyCode::Char('g') => {
+                if let Some(id) = self.selected_id() {
+                    self.open_graph(id).await;
+                }
+            }
+            KeyCode::Backspace if self.pane == Pane::Graph => self.graph_back().await,
+            KeyCode::Char('e') => self.edit_memory(terminal).await?,
+            KeyCode::Char('t') => match self.selected_memory().map(|m| m.id.clone()) {
+                Some(id) => {
+                    self.mode = Mode::Tag(id);
+                    self.input.clear();
+                }
+                None => self.status = "Select a memory to tag".to_string(),
+            },
+            KeyCode::Char('d') => match self.selected_memory().map(|m| m.id.clone()) {
+                Some(id) => self.mode = Mode::ConfirmDelete(id),
+                None => self.status = "Select a memory to delete".to_string(),
+            },
+            KeyCode::Char('r') => self.refresh().await,
+            KeyCode::Char('?') => self.mode = Mode::Help,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (state, len) = match self.pane {
+            Pane::Recent => (&mut self.recent_state, self.recent.len()),
+            Pane::Search => (&mut self.results_state, self.results.len()),
+            Pane::Entities => (&mut self.entities_state, self.entities.len()),
+            Pane::Graph => (&mut self.neighbors_state, self.neighbors.len()),
+        };
+        if len > 0 {
+            let selected = state.selected().unwrap_or(0) as isize + delta;
+            state.select(Some(selected.clamp(0, len as isize - 1) as usize));
+        }
+    }
+
+    /// The memory selected in the Recent or Search pane
+    fn selected_memory(&self) -> Option<&Memory> {
+        match self.pane {
+            Pane::Recent => self
+                .recent_state
+                .selected()
+                .and_then(|i| self.recent.get(i)),
+            Pane::Search => self
+                .results_state
+                .selected()
+                .and_then(|i| self.results.get(i))
+                .map(|(memory, _)| memory),
+            Pane::Entities | Pane::Graph => None,
+        }
+    }
+
+    /// The ID of whatever is selected in the current pane
+    fn selected_id(&self) -> Option<String> {
+        match self.pane {
+            Pane::Recent | Pane::Search => self.selected_memory().map(|memory| memory.id.clone()),
+            Pane::Entities => self
+                .entities_state
+                .selected()
+                .and_then(|i| self.entities.get(i))
+                .map(|entity| entity.id.clone()),
+            Pane::Graph => self
+                .neighbors_state
+                .selected()
+                .and_then(|i| self.neighbors.get(i))
+                .map(|neighbor| neighbor.node.id.clone()),
+        }
+    }
+
+    // Drawing
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(body);
+
+        let tabs = Tabs::new(
+            Pane::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, pane)| format!("{} {}", i + 1, pane.title())),
+        )
+        .select(self.pane.index())
+        .block(Block::bordered().title(" locai "))
+        .highlight_style(Style::new().fg(ACCENT).add_modifier(Modifier::BOLD));
+        frame.render_widget(tabs, tabs_area);
+
+        match self.pane {
+            Pane::Recent => self.draw_recent(frame, list_area, detail_area),
+            Pane::Search => self.draw_search(frame, list_area, detail_area),
+            Pane::Entities => self.draw_entities(frame, list_area, detail_area),
+            Pane::Graph => self.draw_graph(frame, list_area, detail_area),
+        }
+
+        let footer_line = if self.status.is_empty() {
+            Line::from(self.key_hints()).fg(MUTED)
+        } else {
+            Line::from(self.status.as_str()).fg(ENTITY)
+        };
+        frame.render_widget(footer_line, footer);
+
+        match &self.mode {
+            Mode::Tag(id) => {
+                let area = popup_area(frame.area(), 60, 3);
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(self.input.as_str())
+                        .block(Block::bordered().title(format!(" Tag {} ", id))),
+                    area,
+                );
+                frame.set_cursor_position((
+                    area.x + 1 + self.input.chars().count() as u16,
+                    area.y + 1,
+                ));
+            }
+            Mode::ConfirmDelete(id) => {
+                let area = popup_area(frame.area(), 60, 3);
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(format!("Delete {}? (y/n)", id))
+                        .block(Block::bordered().title(" Delete ")),
+                    area,
+                );
+            }
+            Mode::Help => {
+                let area = popup_area(frame.area(), 60, 17);
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(help_text()).block(Block::bordered().title(" Keys ")),
+                    area,
+                );
+            }
+            Mode::Browse | Mode::Query => {}
+        }
+    }
+
+    fn key_hints(&self) -> &'static str {
+        match self.pane {
+            Pane::Recent | Pane::Search => {
+                "q quit · tab pane · / search · enter graph · e edit · t tag · d delete · r refresh · ? help"
+            }
+            Pane::Entities => "q quit · tab pane · enter graph · r refresh · ? help",
+            Pane::Graph => "q quit · tab pane · enter follow · backspace back · r refresh · ? help",
+        }
+    }
+
+    fn draw_recent(&mut self, frame: &mut Frame, list_area: Rect, detail_area: Rect) {
+        let items = self.recent.iter().map(|memory| memory_item(memory, None));
+        frame.render_stateful_widget(
+            selectable_list(" Recent memories ", items),
+            list_area,
+            &mut self.recent_state,
+        );
+        let selected = self
+            .recent_state
+            .selected()
+            .and_then(|i| self.recent.get(i));
+        frame.render_widget(memory_details(selected), detail_area);
+    }
+
+    fn draw_search(&mut self, frame: &mut Frame, list_area: Rect, detail_area: Rect) {
+        let [input_area, results_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(list_area);
+
+        let editing = matches!(self.mode, Mode::Query);
+        let text = if editing { &self.input } else { &self.query };
+        let border = if editing { ACCENT } else { MUTED };
+        frame.render_widget(
+            Paragraph::new(text.as_str()).block(
+                Block::bordered()
+                    .title(" Query (/) ")
+                    .border_style(Style::new().fg(border)),
+            ),
+            input_area,
+        );
+        if editing {
+            frame.set_cursor_position((
+                input_area.x + 1 + self.input.chars().count() as u16,
+                input_area.y + 1,
+            ));
+        }
+
+        let items = self
+            .results
+            .iter()
+            .map(|(memory, score)| memory_item(memory, *score));
+        frame.render_stateful_widget(
+            selectable_list(" Results ", items),
+            results_area,
+            &mut self.results_state,
+        );
+        let selected = self
+            .results_state
+            .selected()
+            .and_then(|i| self.results.get(i))
+            .map(|(memory, _)| memory);
+        frame.render_widget(memory_details(selected), detail_area);
+    }
+
+    fn draw_entities(&mut self, frame: &mut Frame, list_area: Rect, detail_area: Rect) {
+        let items = self.entities.iter().map(|entity| {
+            let node = GraphViewNode::from_entity(entity);
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<14}", node.node_type), Style::new().fg(ENTITY)),
+                Span::raw(node.label),
+            ]))
+        });
+        frame.render_stateful_widget(
+            selectable_list(" Entities ", items),
+            list_area,
+            &mut self.entities_state,
+        );
+
+        let details = match self
+            .entities_state
+            .selected()
+            .and_then(|i| self.entities.get(i))
+        {
+            Some(entity) => {
+                let mut lines = vec![
+                    field_line("ID", &entity.id),
+                    field_line("Type", &entity.entity_type),
+                    field_line("Created", &entity.created_at.to_rfc3339()),
+                    Line::default(),
+                ];
+                let properties = serde_json::to_string_pretty(&entity.properties)
+                    .unwrap_or_else(|_| entity.properties.to_string());
+                lines.extend(properties.lines().map(|line| Line::from(line.to_string())));
+                Text::from(lines)
+            }
+            None => Text::from("No entity selected").fg(MUTED),
+        };
+        frame.render_widget(
+            Paragraph::new(details)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(" Entity ")),
+            detail_area,
+        );
+    }
+
+    fn draw_graph(&mut self, frame: &mut Frame, list_area: Rect, detail_area: Rect) {
+        let title = match &self.center {
+            Some(center) => format!(" {} · {} ", center.kind, truncate(&center.label, 40)),
+            None => " Graph ".to_string(),
+        };
+        let items = self.neighbors.iter().map(|neighbor| {
+            let arrow = if neighbor.outgoing { "→ " } else { "← " };
+            let color = if neighbor.node.kind == "entity" {
+                ENTITY
+            } else {
+                ACCENT
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(arrow),
+                Span::styled(
+                    format!("{} ", neighbor.relationship.relationship_type),
+                    Style::new().fg(MUTED),
+                ),
+                Span::styled(one_line(&neighbor.node.label), Style::new().fg(color)),
+            ]))
+        });
+        frame.render_stateful_widget(
+            selectable_list(&title, items),
+            list_area,
+            &mut self.neighbors_state,
+        );
+
+        let details = match (&self.center, self.neighbors_state.selected()) {
+            (None, _) => {
+                Text::from("Press enter on a memory or entity to explore around it").fg(MUTED)
+            }
+            (Some(center), selected) => {
+                let mut lines = vec![
+                    field_line("Center", &center.id),
+                    field_line("Kind", &format!("{} ({})", center.kind, center.node_type)),
+                    field_line("History", &format!("{} earlier", self.history.len())),
+                    Line::default(),
+                ];
+                if let Some(neighbor) = selected.and_then(|i| self.neighbors.get(i)) {
+                    let relationship = &neighbor.relationship;
+                    lines.push(field_line("Relationship", &relationship.relationship_type));
+                    lines.push(field_line("From", &relationship.source_id));
+                    lines.push(field_line("To", &relationship.target_id));
+                    if !relationship.properties.is_null() {
+                        lines.push(field_line(
+                            "Properties",
+                            &relationship.properties.to_string(),
+                        ));
+                    }
+                    lines.push(Line::default());
+                    lines.extend(
+                        neighbor
+                            .node
+                            .label
+                            .lines()
+                            .map(|line| Line::from(line.to_string())),
+                    );
+                }
+                Text::from(lines)
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(details)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(" Details ")),
+            detail_area,
+        );
+    }
+}
+
+fn selectable_list<'a>(title: &str, items: impl IntoIterator<Item = ListItem<'a>>) -> List<'a> {
+    List::new(items)
+        .block(Block::bordered().title(title.to_string()))
+        .highlight_style(
+            Style::new()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ")
+}
+
+fn memory_item(memory: &Memory, score: Option<f32>) -> ListItem<'static> {
+    let mut spans = vec![Span::styled(
+        format!("{:<12}", memory.memory_type.to_string()),
+        Style::new().fg(ACCENT),
+    )];
+    if let Some(score) = score {
+        spans.push(Span::styled(
+            format!("{:.2} ", score),
+            Style::new().fg(MUTED),
+        ));
+    }
+    spans.push(Span::raw(one_line(&memory.content)));
+    ListItem::new(Line::from(spans))
+}
+
+fn memory_details(memory: Option<&Memory>) -> Paragraph<'static> {
+    let text = match memory {
+        Some(memory) => {
+            let mut lines = vec![
+                field_line("ID", &memory.id),
+                field_line("Type", &memory.memory_type.to_string()),
+                field_line("Priority", &format!("{:?}", memory.priority)),
+                field_line("Tags", &memory.tags.join(", ")),
+                field_line("Source", &memory.source),
+                field_line("Created", &memory.created_at.to_rfc3339()),
+                Line::default(),
+            ];
+            lines.extend(
+                memory
+                    .content
+                    .lines()
+                    .map(|line| Line::from(line.to_string())),
+            );
+            Text::from(lines)
+        }
+        None => Text::from("No memory selected").fg(MUTED),
+    };
+    Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::bordered().title(" Memory "))
+}
+
+fn field_line(name: &str, value: &str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{}: ", name), Style::new().fg(MUTED)),
+        Span::raw(value.to_string()),
+    ])
+}
+
+fn help_text() -> Text<'static> {
+    let keys = [
+        ("1-4, tab", "switch pane"),
+        ("↑/↓, j/k", "move"),
+        ("/", "search"),
+        ("enter, g", "explore the graph around the selection"),
+        ("backspace", "back to the previous graph center"),
+        ("e", "edit the memory in $VISUAL or $EDITOR"),
+        ("t", "add a tag to the memory"),
+        ("d", "delete the memory"),
+        ("r", "reload the pane"),
+        ("q, esc", "quit"),
+    ];
+    let mut lines: Vec<Line> = keys
+        .iter()
+        .map(|(key, action)| {
+            Line::from(vec![
+                Span::styled(format!("{:<12}", key), Style::new().fg(ACCENT).bold()),
+                Span::raw(*action),
+            ])
+        })
+        .collect();
+    lines.push(Line::default());
+    lines.push(Line::from("Press any key to close").fg(MUTED));
+    Text::from(lines)
+}
+
+/// A `width` by `height` area centered in `area`
+fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+fn edit_input(input: &mut String, code: KeyCode) {
+    match code {
+        KeyCode::Char(c) => input.push(c),
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        _ => {}
+    }
+}
+
+/// Keep a selection within `len` items, selecting the first when there are some
+fn clamp_selection(state: &mut ListState, len: usize) {
+    state.select(match len {
+        0 => None,
+        len => Some(state.selected().unwrap_or(0).min(len - 1)),
+    });
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let text = one_line(text);
+    if text.chars().count() > max_chars {
+        let cut: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+        format!("{}...", cut)
+    } else {
+        text
+    }
+}
+
+/// Edit `content` in the user's editor and return the saved text
+fn edit_in_editor(content: &str) -> std::io::Result<String> {
+    let path = std::env::temp_dir().join(format!("locai-memory-{}.md", uuid::Uuid::new_v4()));
+    std::fs::write(&path, content)?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // The setting may carry arguments, as in "code --wait"
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let edited = Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .and_then(|status| {
+            if status.success() {
+                std::fs::read_to_string(&path)
+            } else {
+                Err(std::io::Error::other(format!(
+                    "{} exited with {}",
+                    program, status
+                )))
+            }
+        });
+
+    let _ = std::fs::remove_file(&path);
+    edited
+}
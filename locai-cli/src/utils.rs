@@ -1,4 +1,5 @@
 use crate::context::LocaiCliContext;
+use chrono::{DateTime, NaiveDate, Utc};
 use locai::LocaiError;
 use locai::models::{MemoryPriority, MemoryType};
 
@@ -12,6 +13,8 @@ pub fn parse_memory_type(type_str: &str) -> locai::Result<MemoryType> {
         "world" => Ok(MemoryType::World),
         "action" => Ok(MemoryType::Action),
         "event" => Ok(MemoryType::Event),
+        "task" => Ok(MemoryType::Task),
+        "preference" => Ok(MemoryType::Preference),
         _ => Err(LocaiError::Other(format!(
             "Invalid memory type: {}",
             type_str
@@ -32,6 +35,34 @@ pub fn parse_priority(priority_str: &str) -> locai::Result<MemoryPriority> {
     }
 }
 
+/// Split a `name=value` template variable
+pub fn parse_template_var(var: &str) -> locai::Result<(String, String)> {
+    match var.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => Err(LocaiError::Other(format!(
+            "Invalid template variable '{}': expected name=value",
+            var
+        ))),
+    }
+}
+
+/// Parse an RFC 3339 timestamp, a date (midnight UTC), or a delay from now such as `2h`
+pub fn parse_when(value: &str, now: DateTime<Utc>) -> locai::Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    let delay = locai::memory::parse_delay(value)?;
+    chrono::Duration::from_std(delay)
+        .ok()
+        .and_then(|delay| now.checked_add_signed(delay))
+        .ok_or_else(|| LocaiError::Other(format!("'{}' is too far away", value)))
+}
+
 pub async fn resolve_memory_id(ctx: &LocaiCliContext, id: &str) -> locai::Result<String> {
     if ctx.memory_manager.get_memory(id).await?.is_some() {
         return Ok(id.to_string());
@@ -11,30 +11,18 @@
 //! requirements for graph node existence. The relationship CRUD operations are
 //! tested separately and work correctly.
 
-use locai::config::ConfigBuilder;
+mod common;
+
 use locai::prelude::*;
 use locai::relationships::{RelationshipTypeDef, RelationshipTypeRegistry};
 use std::fs;
 use tempfile::TempDir;
 
+use common::create_test_manager;
+
 /// Helper to create an isolated test CLI context
 async fn create_test_context() -> (TestCliContext, TempDir) {
-    let temp_dir = TempDir::new().expect("Failed to create temp directory");
-    let db_path = temp_dir.path().join("test_db");
-    std::fs::create_dir_all(&db_path).expect("Failed to create test database directory");
-
-    let config = ConfigBuilder::new()
-        .with_data_dir(db_path.to_str().unwrap())
-        .with_default_storage()
-        .with_default_ml()
-        .with_default_logging()
-        .build()
-        .expect("Failed to build config");
-
-    let memory_manager = locai::init(config)
-        .await
-        .expect("Failed to initialize Locai");
-
+    let (memory_manager, temp_dir) = create_test_manager().await;
     let relationship_type_registry = RelationshipTypeRegistry::new();
 
     let context = TestCliContext {
@@ -1071,3 +1059,41 @@ async fn test_vector_search_deserialization() {
         "Vector search should not fail with deserialization error"
     );
 }
+
+#[test]
+fn test_graph_show_mermaid() {
+    use locai::storage::models::{Entity, Relationship};
+    use locai_cli::{GraphView, GraphViewNode, render_graph_mermaid};
+
+    let entity = |id: &str, name: &str| {
+        GraphViewNode::from_entity(&Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties: serde_json::json!({ "name": name }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        })
+    };
+    let mut view = GraphView {
+        root_id: "bob".to_string(),
+        ..Default::default()
+    };
+    view.nodes
+        .insert("alice".to_string(), entity("alice", "Alice \"Al\""));
+    view.nodes.insert("bob".to_string(), entity("bob", "Bob"));
+    view.relationships.push(Relationship {
+        id: "r1".to_string(),
+        relationship_type: "knows".to_string(),
+        source_id: "alice".to_string(),
+        target_id: "bob".to_string(),
+        properties: serde_json::json!({}),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    });
+
+    // The root is drawn first
+    assert_eq!(
+        render_graph_mermaid(&view),
+        "flowchart LR\n    n0(\"Bob<br/><i>person</i>\")\n    n1(\"Alice #quot;Al#quot;<br/><i>person</i>\")\n    n1 -->|\"knows\"| n0"
+    );
+}
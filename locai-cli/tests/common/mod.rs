@@ -0,0 +1,26 @@
+//! Fixtures shared by the CLI integration tests
+//!
+//! Every store lives in its own temp directory, which is removed when the
+//! returned `TempDir` is dropped.
+
+#![allow(dead_code)]
+
+use locai::config::ConfigBuilder;
+use locai::core::MemoryManager;
+use tempfile::TempDir;
+
+/// A memory manager with the default storage, ML and logging configuration
+pub async fn create_test_manager() -> (MemoryManager, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let config = ConfigBuilder::new()
+        .with_data_dir(temp_dir.path().to_str().unwrap())
+        .with_default_storage()
+        .with_default_ml()
+        .with_default_logging()
+        .build()
+        .expect("Failed to build config");
+    let manager = locai::init(config)
+        .await
+        .expect("Failed to initialize Locai");
+    (manager, temp_dir)
+}
@@ -0,0 +1,111 @@
+//! Tests for forwarding commands to a daemon over its Unix socket
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use locai_cli::daemon::{DaemonRequest, forward, serve};
+use locai_cli::invocation;
+
+async fn wait_for(socket: &Path) {
+    for _ in 0..100 {
+        if socket.exists() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+fn request(args: &[&str]) -> DaemonRequest {
+    DaemonRequest {
+        args: args.iter().map(|a| a.to_string()).collect(),
+        output_format: "json".to_string(),
+        color: false,
+        cwd: std::env::current_dir().expect("Failed to read the working directory"),
+    }
+}
+
+#[test]
+fn test_forward_without_daemon() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let socket = dir.path().join("daemon.sock");
+
+    let exit_code = forward(&socket, &request(&["memory", "list"])).expect("Forward failed");
+    assert_eq!(exit_code, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_forward_returns_daemon_exit_code() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let socket: PathBuf = dir.path().join("nested").join("daemon.sock");
+
+    let daemon_socket = socket.clone();
+    let daemon = tokio::spawn(async move {
+        serve(&daemon_socket, |request| async move {
+            if request.args == ["memory", "get", "missing"] {
+                4
+            } else {
+                0
+            }
+        })
+        .await
+    });
+    wait_for(&socket).await;
+
+    let client_socket = socket.clone();
+    let exit_code = tokio::task::spawn_blocking(move || {
+        forward(&client_socket, &request(&["memory", "get", "missing"]))
+    })
+    .await
+    .expect("Client panicked")
+    .expect("Forward failed");
+    assert_eq!(exit_code, Some(4));
+
+    // A second daemon on the same socket is refused
+    assert!(serve(&socket, |_| async { 0 }).await.is_err());
+
+    daemon.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_requests_run_in_the_client_invocation() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let socket = dir.path().join("daemon.sock");
+
+    let daemon_socket = socket.clone();
+    let daemon = tokio::spawn(async move {
+        serve(&daemon_socket, |request| async move {
+            let resolved = invocation::resolve("notes.md") == request.cwd.join("notes.md");
+            if invocation::is_forwarded() && resolved {
+                0
+            } else {
+                1
+            }
+        })
+        .await
+    });
+    wait_for(&socket).await;
+
+    // Only the owner may connect, from the moment the socket appears
+    let mode = std::fs::metadata(&socket)
+        .expect("Failed to stat the socket")
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let mut forwarded = request(&["memory", "list"]);
+    forwarded.cwd = dir.path().to_path_buf();
+    let client_socket = socket.clone();
+    let exit_code = tokio::task::spawn_blocking(move || forward(&client_socket, &forwarded))
+        .await
+        .expect("Client panicked")
+        .expect("Forward failed");
+    assert_eq!(exit_code, Some(0));
+
+    // The daemon's own working directory and descriptors are untouched
+    assert!(!invocation::is_forwarded());
+    assert_eq!(invocation::resolve("notes.md"), PathBuf::from("notes.md"));
+
+    daemon.abort();
+}
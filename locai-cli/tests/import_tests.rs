@@ -0,0 +1,654 @@
+//! Import tests: conversation exports, mem0 and Zep migrations, and Obsidian vaults
+
+mod common;
+
+mod conversation {
+    //! ChatGPT and Claude conversation importer tests
+
+    use std::io::Write;
+
+    use locai::prelude::*;
+    use locai::storage::filters::MemoryFilter;
+    use locai_cli::import::{
+        self, SESSION_ENTITY_TYPE, chatgpt, claude, import_conversations, session_entity_id,
+    };
+    use tempfile::TempDir;
+
+    use crate::common::create_test_manager;
+
+    const CHATGPT_EXPORT: &str = r#"[
+  {
+    "title": "Trip planning",
+    "create_time": 1700000000.5,
+    "conversation_id": "c-123",
+    "current_node": "n4",
+    "mapping": {
+      "root": {"id": "root", "message": null, "parent": null, "children": ["n1"]},
+      "n1": {
+        "id": "n1",
+        "message": {
+          "author": {"role": "system"},
+          "create_time": null,
+          "content": {"content_type": "text", "parts": [""]},
+          "metadata": {"is_visually_hidden_from_conversation": true}
+        },
+        "parent": "root",
+        "children": ["n2"]
+      },
+      "n2": {
+        "id": "n2",
+        "message": {
+          "author": {"role": "user"},
+          "create_time": 1700000001.0,
+          "content": {"content_type": "text", "parts": ["I want to visit Lisbon in May"]},
+          "metadata": {}
+        },
+        "parent": "n1",
+        "children": ["n3", "n4"]
+      },
+      "n3": {
+        "id": "n3",
+        "message": {
+          "author": {"role": "assistant"},
+          "create_time": 1700000002.0,
+          "content": {"content_type": "text", "parts": ["An answer that was regenerated"]},
+          "metadata": {"model_slug": "gpt-4o"}
+        },
+        "parent": "n2",
+        "children": []
+      },
+      "n4": {
+        "id": "n4",
+        "message": {
+          "author": {"role": "assistant"},
+          "create_time": 1700000003.0,
+          "content": {"content_type": "text", "parts": ["May is a great time for Lisbon"]},
+          "metadata": {"model_slug": "gpt-4o"}
+        },
+        "parent": "n2",
+        "children": []
+      }
+    }
+  }
+]"#;
+
+    const CLAUDE_EXPORT: &str = r#"[
+  {
+    "uuid": "9f1c-77",
+    "name": "Garden soil",
+    "created_at": "2024-03-01T10:00:00.000000Z",
+    "chat_messages": [
+      {
+        "uuid": "m1",
+        "sender": "human",
+        "text": "What pH do blueberries like?",
+        "content": [{"type": "text", "text": "What pH do blueberries like?"}],
+        "created_at": "2024-03-01T10:00:01.000000Z"
+      },
+      {
+        "uuid": "m2",
+        "sender": "assistant",
+        "text": "",
+        "content": [
+          {"type": "thinking", "thinking": "internal"},
+          {"type": "text", "text": "Blueberries prefer acidic soil, around pH 4.5 to 5.5."}
+        ],
+        "created_at": "2024-03-01T10:00:05.000000Z"
+      }
+    ]
+  }
+]"#;
+
+    #[test]
+    fn test_chatgpt_export_follows_current_branch() {
+        let conversations = chatgpt::parse_conversations(CHATGPT_EXPORT).unwrap();
+        assert_eq!(conversations.len(), 1);
+
+        let conversation = &conversations[0];
+        assert_eq!(conversation.id, "c-123");
+        assert_eq!(conversation.title, "Trip planning");
+        assert_eq!(conversation.participants(), vec!["user", "gpt-4o"]);
+
+        let contents: Vec<&str> = conversation
+            .messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                "I want to visit Lisbon in May",
+                "May is a great time for Lisbon"
+            ]
+        );
+        assert_eq!(
+            conversation.messages[1].created_at.unwrap().timestamp(),
+            1700000003
+        );
+    }
+
+    #[test]
+    fn test_claude_export_uses_text_blocks() {
+        let conversations = claude::parse_conversations(CLAUDE_EXPORT).unwrap();
+        let conversation = &conversations[0];
+
+        assert_eq!(conversation.participants(), vec!["user", "Claude"]);
+        assert_eq!(conversation.messages[0].role, "user");
+        assert_eq!(
+            conversation.messages[1].content,
+            "Blueberries prefer acidic soil, around pH 4.5 to 5.5."
+        );
+    }
+
+    #[test]
+    fn test_read_export_file_from_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let zip_path = temp_dir.path().join("export.zip");
+
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file(
+                "export-2024/conversations.json",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(CLAUDE_EXPORT.as_bytes()).unwrap();
+        writer.finish().unwrap();
+
+        let json = import::read_export_file(&zip_path, claude::CONVERSATIONS_FILE).unwrap();
+        assert_eq!(json, CLAUDE_EXPORT);
+
+        assert!(import::read_export_file(&zip_path, "users.json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_groups_messages_by_session() {
+        let (manager, _temp_dir) = create_test_manager().await;
+        let conversations = claude::parse_conversations(CLAUDE_EXPORT).unwrap();
+
+        let summary = import_conversations(&manager, "claude", conversations.clone())
+            .await
+            .unwrap();
+        assert_eq!(summary.conversations_imported, 1);
+        assert_eq!(summary.messages_imported, 2);
+
+        let session_id = session_entity_id("claude", "9f1c-77");
+        let session = manager.get_entity(&session_id).await.unwrap().unwrap();
+        assert_eq!(session.entity_type, SESSION_ENTITY_TYPE);
+        assert_eq!(session.properties["name"], "Garden soil");
+
+        let memories = manager
+            .filter_memories(
+                MemoryFilter {
+                    tags: Some(vec!["session:9f1c-77".to_string()]),
+                    ..Default::default()
+                },
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(memories.len(), 2);
+        assert!(
+            memories
+                .iter()
+                .all(|m| m.memory_type == MemoryType::Conversation)
+        );
+        let question = memories
+            .iter()
+            .find(|m| m.properties["role"] == "user")
+            .unwrap();
+        assert_eq!(
+            question.created_at.to_rfc3339(),
+            "2024-03-01T10:00:01+00:00"
+        );
+
+        // Re-importing the same export skips the conversation
+        let again = import_conversations(&manager, "claude", conversations)
+            .await
+            .unwrap();
+        assert_eq!(again.conversations_imported, 0);
+        assert_eq!(again.conversations_skipped, 1);
+    }
+}
+
+mod migration {
+    //! mem0 and Zep importer tests
+
+    use locai::prelude::*;
+    use locai::storage::filters::{MemoryFilter, RelationshipFilter};
+    use locai_cli::import::{
+        SESSION_RELATIONSHIP_TYPE, graph_entity_id, import_data, mem0, session_entity_id, zep,
+    };
+
+    use crate::common::create_test_manager;
+
+    const MEM0_EXPORT: &str = r#"{
+  "results": [
+    {
+      "id": "m-1",
+      "memory": "Is vegetarian",
+      "hash": "abc",
+      "metadata": {"confidence": "high"},
+      "categories": ["food"],
+      "user_id": "alice",
+      "created_at": "2024-07-20T01:30:00.000000-07:00",
+      "updated_at": null
+    },
+    {
+      "id": "m-2",
+      "memory": "Works at Acme",
+      "user_id": "alice",
+      "run_id": "run-9",
+      "created_at": "2024-07-21T10:00:00.123456"
+    },
+    {"id": "m-3", "memory": "   "}
+  ],
+  "relations": [
+    {"source": "Alice", "source_type": "person", "relationship": "works_at", "destination": "Acme", "destination_type": "organization"},
+    {"source": "alice", "relationship": "WORKS_AT", "target": "acme"}
+  ]
+}"#;
+
+    const ZEP_EXPORT: &str = r#"{
+  "sessions": [
+    {
+      "session_id": "s-1",
+      "user_id": "bob",
+      "metadata": {"title": "Onboarding"},
+      "messages": [
+        {"uuid": "a", "role": "Bob", "role_type": "user", "content": "I moved to Denver", "created_at": "2024-05-01T09:00:00Z"},
+        {"uuid": "b", "role": "", "role_type": "assistant", "content": "Welcome to Denver!", "created_at": "2024-05-01T09:00:02Z"},
+        {"uuid": "c", "role": "", "role_type": "system", "content": "ignored"}
+      ],
+      "facts": [
+        {"uuid": "f-1", "fact": "Bob lives in Denver", "rating": 0.9, "created_at": "2024-05-01T09:00:03Z"},
+        "Bob is new to the team"
+      ]
+    }
+  ],
+  "collections": [
+    {"name": "handbook", "documents": [{"uuid": "d-1", "document_id": "pto", "content": "PTO is unlimited"}]}
+  ],
+  "nodes": [
+    {"uuid": "n-1", "name": "Bob", "labels": ["Entity", "Person"]},
+    {"uuid": "n-2", "name": "Denver", "labels": ["Entity"]}
+  ],
+  "edges": [
+    {"uuid": "e-1", "name": "LIVES_IN", "fact": "Bob lives in Denver since May", "source_node_uuid": "n-1", "target_node_uuid": "n-2"},
+    {"uuid": "e-2", "name": "KNOWS", "source_node_uuid": "n-1", "target_node_uuid": "n-404"}
+  ]
+}"#;
+
+    async fn memories_from(manager: &MemoryManager, source: &str) -> Vec<Memory> {
+        manager
+            .filter_memories(
+                MemoryFilter {
+                    source: Some(format!("import:{}", source)),
+                    ..Default::default()
+                },
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_mem0_export_maps_scopes_and_relations() {
+        let data = mem0::parse_export(MEM0_EXPORT).unwrap();
+
+        assert!(data.conversations.is_empty());
+        assert_eq!(data.memories.len(), 2);
+
+        let vegetarian = &data.memories[0];
+        assert_eq!(vegetarian.memory_type, MemoryType::Fact);
+        assert_eq!(vegetarian.tags, vec!["food", "user:alice"]);
+        assert_eq!(vegetarian.properties["metadata"]["confidence"], "high");
+        assert!(!vegetarian.properties.contains_key("updated_at"));
+        assert_eq!(
+            vegetarian.created_at.unwrap().to_rfc3339(),
+            "2024-07-20T08:30:00+00:00"
+        );
+
+        let job = &data.memories[1];
+        assert_eq!(job.session_id.as_deref(), Some("run-9"));
+        assert!(job.created_at.is_some());
+
+        assert_eq!(data.relations.len(), 2);
+        assert_eq!(data.relations[0].target, "Acme");
+        assert_eq!(
+            data.relations[0].target_type.as_deref(),
+            Some("organization")
+        );
+        assert_eq!(data.relations[1].target, "acme");
+    }
+
+    #[test]
+    fn test_mem0_export_accepts_bare_array() {
+        let data = mem0::parse_export(r#"[{"id": "x", "memory": "Likes tea"}]"#).unwrap();
+        assert_eq!(data.memories.len(), 1);
+        assert!(data.relations.is_empty());
+
+        assert!(mem0::parse_export(r#"{"unexpected": true}"#).is_err());
+    }
+
+    #[test]
+    fn test_zep_export_maps_sessions_collections_and_graph() {
+        let data = zep::parse_export(ZEP_EXPORT).unwrap();
+
+        assert_eq!(data.conversations.len(), 1);
+        let session = &data.conversations[0];
+        assert_eq!(session.title, "Onboarding");
+        assert_eq!(session.participants(), vec!["Bob", "assistant"]);
+        assert_eq!(session.messages[0].role, "user");
+
+        let ids: Vec<&str> = data.memories.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["f-1", "s-1_fact_1", "d-1", "e-1"]);
+        assert_eq!(data.memories[0].session_id.as_deref(), Some("s-1"));
+        assert_eq!(data.memories[0].tags, vec!["user:bob"]);
+        assert_eq!(
+            data.memories[2].memory_type,
+            MemoryType::Custom("document".to_string())
+        );
+        assert_eq!(data.memories[2].tags, vec!["collection:handbook"]);
+
+        // The edge to a missing node is dropped
+        assert_eq!(data.relations.len(), 1);
+        assert_eq!(data.relations[0].source_type.as_deref(), Some("Person"));
+        assert_eq!(data.relations[0].target_type, None);
+    }
+
+    #[tokio::test]
+    async fn test_import_mem0_creates_graph_and_skips_duplicates() {
+        let (manager, _temp_dir) = create_test_manager().await;
+        let data = mem0::parse_export(MEM0_EXPORT).unwrap();
+
+        let summary = import_data(&manager, "mem0", data.clone()).await.unwrap();
+        assert_eq!(summary.memories_imported, 2);
+        // Both relations name the same nodes, differing only in case
+        assert_eq!(summary.relationships_imported, 1);
+
+        let memories = memories_from(&manager, "mem0").await;
+        assert_eq!(memories.len(), 2);
+        assert!(
+            memories
+                .iter()
+                .all(|m| m.tags.contains(&"mem0".to_string()))
+        );
+
+        let alice = manager
+            .get_entity(&graph_entity_id("mem0", "Alice"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(alice.entity_type, "person");
+        let relationships = manager
+            .list_relationships(
+                Some(RelationshipFilter {
+                    source_id: Some(alice.id.clone()),
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(relationships.len(), 1);
+        assert_eq!(relationships[0].relationship_type, "works_at");
+        assert_eq!(relationships[0].target_id, graph_entity_id("mem0", "acme"));
+
+        let again = import_data(&manager, "mem0", data).await.unwrap();
+        assert_eq!(again.memories_imported, 0);
+        assert_eq!(again.memories_skipped, 2);
+        assert_eq!(again.relationships_imported, 0);
+        assert_eq!(memories_from(&manager, "mem0").await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_zep_links_facts_to_sessions() {
+        let (manager, _temp_dir) = create_test_manager().await;
+        let data = zep::parse_export(ZEP_EXPORT).unwrap();
+
+        let summary = import_data(&manager, "zep", data).await.unwrap();
+        assert_eq!(summary.conversations_imported, 1);
+        assert_eq!(summary.messages_imported, 2);
+        assert_eq!(summary.memories_imported, 4);
+        assert_eq!(summary.relationships_imported, 1);
+
+        let memories = memories_from(&manager, "zep").await;
+        let fact = memories
+            .iter()
+            .find(|m| m.properties["external_id"] == "f-1")
+            .unwrap();
+        assert_eq!(fact.properties["rating"], 0.9);
+
+        let session_id = session_entity_id("zep", "s-1");
+        let links = manager
+            .list_relationships(
+                Some(RelationshipFilter {
+                    source_id: Some(fact.id.clone()),
+                    relationship_type: Some(SESSION_RELATIONSHIP_TYPE.to_string()),
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target_id, session_id);
+    }
+}
+
+mod obsidian {
+    //! Obsidian vault importer tests
+
+    use locai::prelude::*;
+    use locai::storage::filters::{MemoryFilter, RelationshipFilter};
+    use locai_cli::import::obsidian::{self, LINK_RELATIONSHIP_TYPE, SOURCE};
+    use tempfile::TempDir;
+
+    use crate::common::create_test_manager;
+
+    const RECIPES_NOTE: &str = "---
+tags: [cooking, '#bread']
+aliases:
+  - Baking
+---
+# Recipes
+
+Start with [[Sourdough Starter|the starter]], then see [[Techniques/Folding#Stretch]].
+Also [[Missing Note]] and [[Recipes]].
+";
+
+    fn write_vault() -> TempDir {
+        let vault = TempDir::new().unwrap();
+        let root = vault.path();
+        std::fs::create_dir_all(root.join("Techniques")).unwrap();
+        std::fs::create_dir_all(root.join(".obsidian")).unwrap();
+        std::fs::write(root.join("Recipes.md"), RECIPES_NOTE).unwrap();
+        std::fs::write(
+            root.join("Sourdough Starter.md"),
+            "Feed it daily. Used in [[baking]].",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("Techniques/Folding.md"),
+            "## Stretch\nFold gently.",
+        )
+        .unwrap();
+        std::fs::write(root.join(".obsidian/workspace.md"), "not a note").unwrap();
+        vault
+    }
+
+    async fn outgoing_links(manager: &MemoryManager, memory_id: &str) -> Vec<String> {
+        let mut targets: Vec<String> = manager
+            .list_relationships(
+                Some(RelationshipFilter {
+                    source_id: Some(memory_id.to_string()),
+                    relationship_type: Some(LINK_RELATIONSHIP_TYPE.to_string()),
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.target_id)
+            .collect();
+        targets.sort();
+        targets
+    }
+
+    async fn note_ids(manager: &MemoryManager) -> std::collections::HashMap<String, String> {
+        manager
+            .filter_memories(
+                MemoryFilter {
+                    source: Some(SOURCE.to_string()),
+                    ..Default::default()
+                },
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.properties["path"].as_str().unwrap().to_string(), m.id))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_note_front_matter_and_links() {
+        let note = obsidian::parse_note("Recipes.md", RECIPES_NOTE);
+
+        assert_eq!(note.title, "Recipes");
+        assert_eq!(note.tags, vec!["cooking", "bread"]);
+        assert_eq!(note.aliases, vec!["Baking"]);
+        assert_eq!(
+            note.links,
+            vec![
+                "Sourdough Starter",
+                "Techniques/Folding",
+                "Missing Note",
+                "Recipes"
+            ]
+        );
+        assert!(note.body.starts_with("# Recipes"));
+        assert!(!note.body.contains("aliases"));
+    }
+
+    #[test]
+    fn test_parse_note_without_front_matter() {
+        let note = obsidian::parse_note("notes/Plain.md", "---\nnot closed");
+
+        assert_eq!(note.title, "Plain");
+        assert!(note.tags.is_empty());
+        assert!(note.front_matter.is_null());
+        assert_eq!(note.body, "---\nnot closed");
+    }
+
+    #[test]
+    fn test_scan_vault_skips_hidden_folders() {
+        let vault = write_vault();
+        let notes = obsidian::scan_vault(vault.path()).unwrap();
+
+        let paths: Vec<&str> = notes.iter().map(|n| n.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "Recipes.md",
+                "Sourdough Starter.md",
+                "Techniques/Folding.md"
+            ]
+        );
+        assert!(notes.iter().all(|n| n.modified_at.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_import_vault_creates_links() {
+        let (manager, _temp_dir) = create_test_manager().await;
+        let vault = write_vault();
+
+        let notes = obsidian::scan_vault(vault.path()).unwrap();
+        let summary = obsidian::import_vault(&manager, "kitchen", notes, false)
+            .await
+            .unwrap();
+        assert_eq!(summary.notes_created, 3);
+        // Recipes -> Sourdough Starter, Folding; Sourdough Starter -> Recipes (alias)
+        assert_eq!(summary.links_created, 3);
+        assert_eq!(summary.unresolved_links, 1);
+
+        let ids = note_ids(&manager).await;
+        let recipes = manager
+            .get_memory(&ids["Recipes.md"])
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(recipes.tags.contains(&"vault:kitchen".to_string()));
+        assert!(recipes.tags.contains(&"bread".to_string()));
+
+        let mut expected = vec![
+            ids["Sourdough Starter.md"].clone(),
+            ids["Techniques/Folding.md"].clone(),
+        ];
+        expected.sort();
+        assert_eq!(outgoing_links(&manager, &ids["Recipes.md"]).await, expected);
+        assert_eq!(
+            outgoing_links(&manager, &ids["Sourdough Starter.md"]).await,
+            vec![ids["Recipes.md"].clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reimport_only_updates_changed_notes() {
+        let (manager, _temp_dir) = create_test_manager().await;
+        let vault = write_vault();
+
+        let notes = obsidian::scan_vault(vault.path()).unwrap();
+        obsidian::import_vault(&manager, "kitchen", notes, false)
+            .await
+            .unwrap();
+        let ids = note_ids(&manager).await;
+
+        let notes = obsidian::scan_vault(vault.path()).unwrap();
+        let summary = obsidian::import_vault(&manager, "kitchen", notes, false)
+            .await
+            .unwrap();
+        assert_eq!(summary.notes_unchanged, 3);
+        assert_eq!(summary.notes_created + summary.notes_updated, 0);
+        assert_eq!(summary.links_created, 0);
+
+        // Drop the link to the starter and delete the starter note
+        std::fs::write(
+            vault.path().join("Recipes.md"),
+            "Only [[Techniques/Folding]] now.",
+        )
+        .unwrap();
+        std::fs::remove_file(vault.path().join("Sourdough Starter.md")).unwrap();
+
+        let notes = obsidian::scan_vault(vault.path()).unwrap();
+        let summary = obsidian::import_vault(&manager, "kitchen", notes, true)
+            .await
+            .unwrap();
+        assert_eq!(summary.notes_updated, 1);
+        assert_eq!(summary.notes_unchanged, 1);
+        assert_eq!(summary.notes_removed, 1);
+
+        assert_eq!(note_ids(&manager).await.len(), 2);
+        let recipes = manager
+            .get_memory(&ids["Recipes.md"])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recipes.content, "Only [[Techniques/Folding]] now.");
+        assert_eq!(
+            outgoing_links(&manager, &ids["Recipes.md"]).await,
+            vec![ids["Techniques/Folding.md"].clone()]
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Tests for the interactive search query builder
+
+use std::io::Cursor;
+
+use chrono::{Duration, TimeZone, Utc};
+use locai_cli::args::SearchArgs;
+use locai_cli::search_builder::{equivalent_command, parse_time, prompt_search_args};
+
+fn default_args() -> SearchArgs {
+    SearchArgs {
+        query: None,
+        limit: 10,
+        mode: "hybrid".to_string(),
+        threshold: None,
+        memory_type: None,
+        tag: None,
+        created_after: None,
+        created_before: None,
+        collection: None,
+        pin_scope: None,
+        include_archived: false,
+        properties: Vec::new(),
+        filter: None,
+        explain_plan: false,
+        near: None,
+        radius: 1000.0,
+        interactive: true,
+    }
+}
+
+#[test]
+fn test_prompt_builds_search_args() {
+    // Query, strategy, threshold, type, tag, after, before, limit, archived.
+    // The invalid type is re-asked before "fact" is accepted.
+    let answers = "dragon lore\nsemantic\n0.4\nbogus\nfact\nlore\n2024-01-01\n\n5\ny\n";
+    let mut input = Cursor::new(answers);
+    let mut prompts = Vec::new();
+
+    let args = prompt_search_args(&mut input, &mut prompts, default_args())
+        .expect("Failed to build search");
+
+    assert_eq!(args.query.as_deref(), Some("dragon lore"));
+    assert_eq!(args.mode, "semantic");
+    assert_eq!(args.threshold, Some(0.4));
+    assert_eq!(args.memory_type.as_deref(), Some("fact"));
+    assert_eq!(args.tag.as_deref(), Some("lore"));
+    assert_eq!(
+        args.created_after.as_deref(),
+        Some("2024-01-01T00:00:00+00:00")
+    );
+    assert_eq!(args.created_before, None);
+    assert_eq!(args.limit, 5);
+    assert!(args.include_archived);
+
+    let prompts = String::from_utf8(prompts).expect("Prompts are not UTF-8");
+    assert!(prompts.contains("Invalid memory type"));
+
+    assert_eq!(
+        equivalent_command(&args),
+        "locai-cli memory search 'dragon lore' --mode semantic --limit 5 --threshold 0.4 \
+         --memory-type fact --tag lore --created-after 2024-01-01T00:00:00+00:00 \
+         --include-archived"
+    );
+}
+
+#[test]
+fn test_prompt_keeps_defaults_and_skips_threshold_for_text() {
+    let mut args = default_args();
+    args.query = Some("warrior".to_string());
+    args.tag = Some("combat".to_string());
+
+    // Accept the query, switch to text search, clear the tag with "-"
+    let mut input = Cursor::new("\ntext\n\n-\n\n\n\n\n");
+    let args =
+        prompt_search_args(&mut input, &mut Vec::new(), args).expect("Failed to build search");
+
+    assert_eq!(args.query.as_deref(), Some("warrior"));
+    assert_eq!(args.mode, "text");
+    assert_eq!(args.threshold, None);
+    assert_eq!(args.tag, None);
+    assert_eq!(
+        equivalent_command(&args),
+        "locai-cli memory search warrior --mode text"
+    );
+}
+
+#[test]
+fn test_equivalent_command_keeps_property_filters() {
+    let mut args = default_args();
+    args.query = Some("deploys".to_string());
+    args.properties = vec!["project_id=alpha".to_string(), "owner=ops team".to_string()];
+    args.explain_plan = true;
+
+    assert_eq!(
+        equivalent_command(&args),
+        "locai-cli memory search deploys --property 'project_id=alpha' \
+         --property 'owner=ops team' --explain-plan"
+    );
+}
+
+#[test]
+fn test_prompt_fails_when_input_ends() {
+    let mut input = Cursor::new("warrior\n");
+    assert!(prompt_search_args(&mut input, &mut Vec::new(), default_args()).is_err());
+}
+
+#[test]
+fn test_parse_time() {
+    let now = Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+
+    assert_eq!(parse_time("7d", now), Ok(now - Duration::days(7)));
+    assert_eq!(parse_time("12h", now), Ok(now - Duration::hours(12)));
+    assert_eq!(parse_time("2w", now), Ok(now - Duration::weeks(2)));
+    assert_eq!(
+        parse_time("2024-06-01", now),
+        Ok(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        parse_time("2024-06-01T08:30:00+02:00", now),
+        Ok(Utc.with_ymd_and_hms(2024, 6, 1, 6, 30, 0).unwrap())
+    );
+    assert!(parse_time("yesterday", now).is_err());
+    assert!(parse_time("5é", now).is_err());
+}
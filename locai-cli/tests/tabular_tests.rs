@@ -0,0 +1,62 @@
+//! Tests for CSV and Markdown table output
+
+use locai::prelude::*;
+use locai_cli::tabular::{Table, TableFormat, memory_table};
+
+fn sample_table() -> Table {
+    let mut table = Table::new(&["ID", "Content"]);
+    table.push_row(vec!["memory:a".to_string(), "plain".to_string()]);
+    table.push_row(vec![
+        "memory:b".to_string(),
+        "has, comma and \"quotes\"\nand a | pipe".to_string(),
+    ]);
+    table
+}
+
+#[test]
+fn test_table_format_from_output() {
+    assert_eq!(TableFormat::from_output("csv"), Some(TableFormat::Csv));
+    assert_eq!(
+        TableFormat::from_output("markdown"),
+        Some(TableFormat::Markdown)
+    );
+    assert_eq!(TableFormat::from_output("md"), Some(TableFormat::Markdown));
+    assert_eq!(TableFormat::from_output("json"), None);
+    assert_eq!(TableFormat::from_output("table"), None);
+}
+
+#[test]
+fn test_csv_quotes_special_fields() {
+    assert_eq!(
+        sample_table().render(TableFormat::Csv),
+        "ID,Content\n\
+         memory:a,plain\n\
+         memory:b,\"has, comma and \"\"quotes\"\"\nand a | pipe\"\n"
+    );
+}
+
+#[test]
+fn test_markdown_escapes_cells() {
+    assert_eq!(
+        sample_table().render(TableFormat::Markdown),
+        "| ID | Content |\n\
+         | --- | --- |\n\
+         | memory:a | plain |\n\
+         | memory:b | has, comma and \"quotes\"<br>and a \\| pipe |\n"
+    );
+}
+
+#[test]
+fn test_memory_table_keeps_full_content() {
+    let content = "A long memory that the table view would cut off well before it ends";
+    let memory = MemoryBuilder::fact(content)
+        .tags(vec!["lore", "dragons"])
+        .build();
+
+    let csv = memory_table(&[memory]).render(TableFormat::Csv);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("ID,Type,Priority,Tags,Created,Content"));
+    let row = lines.next().expect("Missing memory row");
+    assert!(row.contains(",fact,Normal,\"lore, dragons\","));
+    assert!(row.ends_with(content));
+}
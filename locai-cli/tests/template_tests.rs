@@ -0,0 +1,69 @@
+//! Tests for --fields and --template output shaping
+
+use locai_cli::template::{OutputShape, Template, select_fields};
+use serde_json::json;
+
+#[test]
+fn test_template_renders_each_record() {
+    let shape = OutputShape::parse(None, Some(r"{{id}}\t{{content | truncate 10}}"))
+        .expect("Failed to parse template");
+    let output = shape.render(json!([
+        { "id": "memory:a", "content": "A very long piece of content" },
+        { "id": "memory:b", "content": "Short" }
+    ]));
+    assert_eq!(output, "memory:a\tA very ...\nmemory:b\tShort");
+}
+
+#[test]
+fn test_template_paths_and_filters() {
+    let record = json!({
+        "memory": { "id": "memory:a", "tags": ["lore", "dragons"] },
+        "score": 0.5,
+        "source": null
+    });
+    let render = |source: &str| {
+        Template::parse(source)
+            .expect("Failed to parse template")
+            .render(&record)
+    };
+
+    assert_eq!(render("{{ memory.id | upper }}"), "MEMORY:A");
+    assert_eq!(render("{{memory.tags | join \"/\"}}"), "lore/dragons");
+    assert_eq!(render("{{memory.tags.1}}"), "dragons");
+    assert_eq!(render("{{score}}"), "0.5");
+    assert_eq!(render("{{source | default \"none\"}}"), "none");
+    assert_eq!(render("{{missing}}"), "");
+    assert_eq!(render("{{memory.tags | json}}"), r#"["lore","dragons"]"#);
+    assert_eq!(render(r"a\\b\nc"), "a\\b\nc");
+}
+
+#[test]
+fn test_template_errors() {
+    assert!(Template::parse("{{id").is_err());
+    assert!(Template::parse("{{}}").is_err());
+    assert!(Template::parse("{{id | shout}}").is_err());
+    assert!(Template::parse("{{content | truncate}}").is_err());
+}
+
+#[test]
+fn test_fields_keep_nesting() {
+    let record = json!({
+        "id": "memory:a",
+        "content": "Hello",
+        "metadata": { "source": "import", "other": 1 }
+    });
+    let fields = vec![
+        "id".to_string(),
+        "metadata.source".to_string(),
+        "missing".to_string(),
+    ];
+    assert_eq!(
+        select_fields(&record, &fields),
+        json!({ "id": "memory:a", "metadata": { "source": "import" } })
+    );
+
+    let shape = OutputShape::parse(Some("id, content"), None).expect("Failed to parse fields");
+    let output: serde_json::Value =
+        serde_json::from_str(&shape.render(json!([record]))).expect("Output is not JSON");
+    assert_eq!(output, json!([{ "id": "memory:a", "content": "Hello" }]));
+}
@@ -0,0 +1,483 @@
+//! HTTP client for the server's REST API
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{Method, RequestBuilder, StatusCode, Url};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue};
+use tracing::debug;
+
+use crate::error::{ClientError, Result};
+use crate::models::{
+    AuthResponse, BatchSearchResult, CreateMemory, ListMemories, Memory, Relationship, Search,
+    SearchResult, UpdateMemory,
+};
+use crate::websocket::{EventStream, Subscription};
+
+/// Statuses where the server did no work and a retry may succeed
+const RETRYABLE_STATUSES: [StatusCode; 4] = [
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// How failed requests are retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubling for each one after
+    pub initial_backoff: Duration,
+    /// Longest delay between retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// Builder for [`LocaiClient`]
+#[derive(Debug, Clone)]
+pub struct LocaiClientBuilder {
+    base_url: String,
+    token: Option<String>,
+    credentials: Option<(String, String)>,
+    retry: RetryPolicy,
+    timeout: Option<Duration>,
+}
+
+impl LocaiClientBuilder {
+    /// Send this bearer token with every request
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Sign in with these credentials before the first request, and again
+    /// whenever the token expires
+    pub fn credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// Time limit for each request attempt
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn build(self) -> Result<LocaiClient> {
+        let mut base_url = Url::parse(&self.base_url)
+            .map_err(|e| ClientError::InvalidUrl(format!("{}: {}", self.base_url, e)))?;
+        if !matches!(base_url.scheme(), "http" | "https") {
+            return Err(ClientError::InvalidUrl(format!(
+                "{}: expected an http or https URL",
+                self.base_url
+            )));
+        }
+        // Joining paths onto the base keeps its own path only with a trailing slash
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+
+        let mut http = reqwest::Client::builder();
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+
+        Ok(LocaiClient {
+            http: http.build()?,
+            base_url,
+            token: Arc::new(RwLock::new(self.token)),
+            credentials: self.credentials,
+            retry: self.retry,
+        })
+    }
+}
+
+/// Typed async client for a Locai server
+///
+/// Cloning is cheap; clones share connections and the signed-in token.
+#[derive(Debug, Clone)]
+pub struct LocaiClient {
+    http: reqwest::Client,
+    base_url: Url,
+    token: Arc<RwLock<Option<String>>>,
+    credentials: Option<(String, String)>,
+    retry: RetryPolicy,
+}
+
+#[derive(Serialize)]
+struct LoginRequest<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Serialize)]
+struct SignupRequest<'a> {
+    username: &'a str,
+    password: &'a str,
+    email: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct CreateRelationshipRequest<'a> {
+    relationship_type: &'a str,
+    target_id: &'a str,
+    properties: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct BatchSearchRequest<'a> {
+    queries: &'a [Search],
+    defaults: &'a Search,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+    message: String,
+}
+
+impl LocaiClient {
+    /// A client for the server at `base_url`, such as `http://localhost:3000`
+    pub fn builder(base_url: impl Into<String>) -> LocaiClientBuilder {
+        LocaiClientBuilder {
+            base_url: base_url.into(),
+            token: None,
+            credentials: None,
+            retry: RetryPolicy::default(),
+            timeout: None,
+        }
+    }
+
+    /// A client with default settings and no authentication
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        Self::builder(base_url).build()
+    }
+
+    /// Sign in, using the returned token for later requests
+    pub async fn login(&self, username: &str, password: &str) -> Result<AuthResponse> {
+        self.authenticate("auth/login", &LoginRequest { username, password })
+            .await
+    }
+
+    /// Create a user and sign in as them
+    pub async fn signup(
+        &self,
+        username: &str,
+        password: &str,
+        email: Option<&str>,
+    ) -> Result<AuthResponse> {
+        let body = SignupRequest {
+            username,
+            password,
+            email,
+        };
+        self.authenticate("auth/signup", &body).await
+    }
+
+    /// The token sent with requests, if signed in
+    pub async fn token(&self) -> Option<String> {
+        self.token.read().await.clone()
+    }
+
+    /// Server health and capabilities
+    pub async fn health(&self) -> Result<serde_json::Value> {
+        self.get("health", None::<&()>).await
+    }
+
+    pub async fn create_memory(&self, memory: &CreateMemory) -> Result<Memory> {
+        // Only a keyed create can be repeated without storing a duplicate
+        let idempotent = memory.idempotency_key.is_some();
+        self.send(
+            Method::POST,
+            "memories",
+            None::<&()>,
+            Some(memory),
+            idempotent,
+        )
+        .await
+    }
+
+    /// The memory with this ID, or `None` if there isn't one
+    pub async fn get_memory(&self, id: &str) -> Result<Option<Memory>> {
+        match self.get(&format!("memories/{}", id), None::<&()>).await {
+            Ok(memory) => Ok(Some(memory)),
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn update_memory(&self, id: &str, update: &UpdateMemory) -> Result<Memory> {
+        self.send(
+            Method::PUT,
+            &format!("memories/{}", id),
+            None::<&()>,
+            Some(update),
+            true,
+        )
+        .await
+    }
+
+    pub async fn delete_memory(&self, id: &str) -> Result<()> {
+        self.send_empty(Method::DELETE, &format!("memories/{}", id))
+            .await
+    }
+
+    pub async fn list_memories(&self, list: &ListMemories) -> Result<Vec<Memory>> {
+        self.get("memories", Some(list)).await
+    }
+
+    pub async fn search(&self, search: &Search) -> Result<Vec<SearchResult>> {
+        self.get("memories/search", Some(search)).await
+    }
+
+    /// Run several searches in one request, each falling back to `defaults`
+    /// for parameters it doesn't set
+    pub async fn search_batch(
+        &self,
+        queries: &[Search],
+        defaults: &Search,
+    ) -> Result<Vec<BatchSearchResult>> {
+        let body = BatchSearchRequest { queries, defaults };
+        self.send(
+            Method::POST,
+            "memories/search/batch",
+            None::<&()>,
+            Some(&body),
+            true,
+        )
+        .await
+    }
+
+    pub async fn memory_relationships(&self, id: &str) -> Result<Vec<Relationship>> {
+        self.get(&format!("memories/{}/relationships", id), None::<&()>)
+            .await
+    }
+
+    /// Relate a memory to another memory or an entity
+    pub async fn create_relationship(
+        &self,
+        source_id: &str,
+        relationship_type: &str,
+        target_id: &str,
+        properties: serde_json::Value,
+    ) -> Result<Relationship> {
+        let body = CreateRelationshipRequest {
+            relationship_type,
+            target_id,
+            properties,
+        };
+        self.send(
+            Method::POST,
+            &format!("memories/{}/relationships", source_id),
+            None::<&()>,
+            Some(&body),
+            false,
+        )
+        .await
+    }
+
+    /// Receive change events matching `subscription` over a WebSocket
+    pub async fn subscribe(&self, subscription: &Subscription) -> Result<EventStream> {
+        self.ensure_signed_in().await?;
+        let mut url = self.url("ws")?;
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        url.set_scheme(scheme)
+            .map_err(|_| ClientError::InvalidUrl(url.to_string()))?;
+
+        let mut request = url.as_str().into_client_request()?;
+        if let Some(token) = self.token().await {
+            let header = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|e| ClientError::InvalidUrl(e.to_string()))?;
+            request.headers_mut().insert("Authorization", header);
+        }
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        EventStream::subscribe(socket, subscription).await
+    }
+
+    /// Sent directly rather than through `execute`, as signing in again is
+    /// part of executing other requests
+    async fn authenticate<B: Serialize>(&self, path: &str, body: &B) -> Result<AuthResponse> {
+        let response = self.http.post(self.url(path)?).json(body).send().await?;
+        if !response.status().is_success() {
+            return Err(Self::api_error(response).await);
+        }
+        let auth: AuthResponse = response.json().await?;
+        *self.token.write().await = Some(auth.token.clone());
+        Ok(auth)
+    }
+
+    fn url(&self, path: &str) -> Result<Url> {
+        self.base_url
+            .join("api/v1/")
+            .and_then(|api| api.join(path))
+            .map_err(|e| ClientError::InvalidUrl(format!("{}: {}", path, e)))
+    }
+
+    async fn get<Q, T>(&self, path: &str, query: Option<&Q>) -> Result<T>
+    where
+        Q: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.send(Method::GET, path, query, None::<&()>, true).await
+    }
+
+    async fn send_empty(&self, method: Method, path: &str) -> Result<()> {
+        self.execute(method, path, None::<&()>, None::<&()>, true)
+            .await?;
+        Ok(())
+    }
+
+    async fn send<Q, B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<&Q>,
+        body: Option<&B>,
+        idempotent: bool,
+    ) -> Result<T>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let response = self.execute(method, path, query, body, idempotent).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Send a request, signing in again once if the token was rejected and
+    /// retrying idempotent requests the server didn't get to
+    async fn execute<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<&Q>,
+        body: Option<&B>,
+        idempotent: bool,
+    ) -> Result<reqwest::Response>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        self.ensure_signed_in().await?;
+        let url = self.url(path)?;
+        let mut attempt = 0;
+        let mut signed_in_again = false;
+        loop {
+            let mut request = self.http.request(method.clone(), url.clone());
+            if let Some(query) = query {
+                request = request.query(query);
+            }
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            request = self.authorize(request).await;
+
+            let retryable = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response)
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        && self.credentials.is_some()
+                        && !signed_in_again =>
+                {
+                    signed_in_again = true;
+                    self.sign_in().await?;
+                    continue;
+                }
+                Ok(response) if RETRYABLE_STATUSES.contains(&response.status()) => {
+                    Self::api_error(response).await
+                }
+                Ok(response) => return Err(Self::api_error(response).await),
+                Err(e) if e.is_connect() || e.is_timeout() => ClientError::Http(e),
+                Err(e) => return Err(e.into()),
+            };
+
+            if !idempotent || attempt >= self.retry.max_retries {
+                return Err(retryable);
+            }
+            let backoff = self.retry.backoff(attempt);
+            debug!(
+                "{} {} failed ({}), retrying in {:?}",
+                method, url, retryable, backoff
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    async fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.token.read().await.as_deref() {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn ensure_signed_in(&self) -> Result<()> {
+        if self.credentials.is_some() && self.token.read().await.is_none() {
+            self.sign_in().await?;
+        }
+        Ok(())
+    }
+
+    async fn sign_in(&self) -> Result<()> {
+        if let Some((username, password)) = &self.credentials {
+            self.login(username, password).await?;
+        }
+        Ok(())
+    }
+
+    async fn api_error(response: reqwest::Response) -> ClientError {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        let (error, message) = match serde_json::from_str::<ErrorResponse>(&text) {
+            Ok(body) => (body.error, body.message),
+            Err(_) => (
+                status.canonical_reason().unwrap_or("error").to_string(),
+                text,
+            ),
+        };
+        ClientError::Api {
+            status: status.as_u16(),
+            error,
+            message,
+        }
+    }
+}
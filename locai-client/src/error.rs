@@ -0,0 +1,53 @@
+//! Client errors
+
+use thiserror::Error;
+
+/// Errors returned by [`LocaiClient`](crate::LocaiClient)
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// The server answered with an error status
+    #[error("{status} {error}: {message}")]
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Error type reported by the server, such as `bad_request`
+        error: String,
+        /// Error message reported by the server
+        message: String,
+    },
+
+    /// The request couldn't be sent or its response read
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The WebSocket connection failed
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    /// A message couldn't be encoded or decoded
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// The base URL can't be used to reach the server
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+}
+
+impl ClientError {
+    /// HTTP status of an API error
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            ClientError::Http(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Whether the server reported the resource as missing
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+}
+
+/// Result type for client operations
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,54 @@
+//! Typed async client for the Locai server
+//!
+//! [`LocaiClient`] wraps the server's REST API for memories, search and
+//! relationships, and its WebSocket for live change events:
+//!
+//! - Requests are retried with exponential backoff when the connection fails
+//!   or the server is overloaded, if repeating them is safe: reads, updates,
+//!   deletes, searches, and creates with an idempotency key
+//! - With [`credentials`](LocaiClientBuilder::credentials), the client signs
+//!   in before its first request and again when its token is rejected
+//! - Server errors come back as [`ClientError::Api`] with the status and
+//!   message the server reported
+//!
+//! ```rust,no_run
+//! use futures::StreamExt;
+//! use locai_client::{CreateMemory, LocaiClient, Search, SearchMode, Subscription};
+//!
+//! # async fn example() -> locai_client::Result<()> {
+//! let client = LocaiClient::builder("http://localhost:3000")
+//!     .credentials("agent", "correct horse battery staple")
+//!     .max_retries(5)
+//!     .build()?;
+//!
+//! let memory = client
+//!     .create_memory(&CreateMemory::new("The bridge closes at dusk").tags(["bridge"]))
+//!     .await?;
+//!
+//! let results = client
+//!     .search(&Search::new("bridge").mode(SearchMode::Hybrid).limit(5))
+//!     .await?;
+//!
+//! let mut events = client.subscribe(&Subscription::all()).await?;
+//! while let Some(event) = events.next().await {
+//!     println!("{:?}", event?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod client;
+pub mod error;
+pub mod models;
+pub mod websocket;
+
+pub use client::{LocaiClient, LocaiClientBuilder, RetryPolicy};
+pub use error::{ClientError, Result};
+pub use models::{
+    AuthResponse, BatchSearchResult, CreateMemory, ListMemories, Memory, Relationship, Search,
+    SearchMode, SearchResult, UpdateMemory,
+};
+pub use websocket::{
+    EntityEventFilter, EventStream, MemoryEventFilter, RelationshipEventFilter, ServerEvent,
+    Subscription,
+};
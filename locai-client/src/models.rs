@@ -0,0 +1,360 @@
+//! Request and response types of the server API
+//!
+//! These mirror the server's DTOs field for field, so they serialize to the
+//! JSON the server expects; fields the server adds later are ignored.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A stored memory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Memory {
+    pub id: String,
+    pub content: String,
+    /// Custom types are prefixed with "custom:"
+    pub memory_type: String,
+    pub created_at: DateTime<Utc>,
+    pub last_accessed: Option<DateTime<Utc>>,
+    pub access_count: u32,
+    /// "Low", "Normal", "High" or "Critical"
+    pub priority: String,
+    pub tags: Vec<String>,
+    pub source: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub properties: serde_json::Value,
+    pub related_memories: Vec<String>,
+}
+
+/// A memory to create
+///
+/// ```rust
+/// use locai_client::CreateMemory;
+///
+/// let memory = CreateMemory::new("The bridge closes at dusk")
+///     .memory_type("custom:observation")
+///     .tags(["bridge", "schedule"])
+///     .idempotency_key("observation-42");
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateMemory {
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    /// Makes retrying the request safe, so the client retries it on
+    /// connection failures and overload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+}
+
+impl CreateMemory {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn memory_type(mut self, memory_type: impl Into<String>) -> Self {
+        self.memory_type = Some(memory_type.into());
+        self
+    }
+
+    pub fn priority(mut self, priority: impl Into<String>) -> Self {
+        self.priority = Some(priority.into());
+        self
+    }
+
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    pub fn properties(mut self, properties: serde_json::Value) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    pub fn embedding(mut self, embedding: Vec<f32>) -> Self {
+        self.embedding = Some(embedding);
+        self
+    }
+
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+}
+
+/// Changes to a memory; fields left as `None` are unchanged
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateMemory {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<serde_json::Value>,
+    /// `Some(None)` removes the embedding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Option<Vec<f32>>>,
+}
+
+/// Filters and paging for listing memories
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListMemories {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// Comma-separated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// How a search matches memories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// BM25 keyword search
+    #[default]
+    Text,
+    /// Vector similarity, when the server has an ML service
+    Vector,
+    /// Keyword and vector search combined, when the server has an ML service
+    Hybrid,
+}
+
+/// A memory search
+///
+/// ```rust
+/// use locai_client::{Search, SearchMode};
+///
+/// let search = Search::new("deployment checklist")
+///     .mode(SearchMode::Hybrid)
+///     .limit(5)
+///     .tags(["ops"]);
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Search {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SearchMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_type: Option<String>,
+    /// Comma-separated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// JSON-encoded scoring configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoring_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin_scope: Option<String>,
+    /// "LAT,LON" in degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub near: Option<String>,
+    /// Meters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f64>,
+    /// Filter expression, such as `amount > 100`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+impl Search {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            q: Some(query.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Parameters shared by the queries of a batch, without a query of their own
+    pub fn defaults() -> Self {
+        Self::default()
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn mode(mut self, mode: SearchMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    pub fn memory_type(mut self, memory_type: impl Into<String>) -> Self {
+        self.memory_type = Some(memory_type.into());
+        self
+    }
+
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let tags: Vec<String> = tags
+            .into_iter()
+            .map(|tag| tag.as_ref().to_string())
+            .collect();
+        self.tags = Some(tags.join(","));
+        self
+    }
+
+    pub fn priority(mut self, priority: impl Into<String>) -> Self {
+        self.priority = Some(priority.into());
+        self
+    }
+
+    pub fn scoring(mut self, scoring: serde_json::Value) -> Self {
+        self.scoring = Some(scoring.to_string());
+        self
+    }
+
+    pub fn scoring_profile(mut self, profile: impl Into<String>) -> Self {
+        self.scoring_profile = Some(profile.into());
+        self
+    }
+
+    pub fn created_after(mut self, time: DateTime<Utc>) -> Self {
+        self.created_after = Some(time);
+        self
+    }
+
+    pub fn created_before(mut self, time: DateTime<Utc>) -> Self {
+        self.created_before = Some(time);
+        self
+    }
+
+    pub fn include_archived(mut self, include: bool) -> Self {
+        self.include_archived = Some(include);
+        self
+    }
+
+    pub fn collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = Some(collection.into());
+        self
+    }
+
+    pub fn pin_scope(mut self, scope: impl Into<String>) -> Self {
+        self.pin_scope = Some(scope.into());
+        self
+    }
+
+    pub fn near(mut self, lat: f64, lon: f64, radius_meters: f64) -> Self {
+        self.near = Some(format!("{},{}", lat, lon));
+        self.radius = Some(radius_meters);
+        self
+    }
+
+    pub fn filter(mut self, expression: impl Into<String>) -> Self {
+        self.filter = Some(expression.into());
+        self
+    }
+}
+
+/// A memory found by a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub memory: Memory,
+    pub score: Option<f32>,
+    /// "text", "semantic", "both" or "pinned"
+    #[serde(default)]
+    pub match_method: Option<String>,
+}
+
+/// Results of one query in a search batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchSearchResult {
+    pub q: String,
+    pub results: Vec<SearchResult>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A relationship from or to a memory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relationship {
+    pub id: String,
+    pub relationship_type: String,
+    pub source_id: String,
+    pub target_id: String,
+    pub properties: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A signed-in session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthResponse {
+    pub token: String,
+    pub user_id: String,
+    pub username: String,
+    pub role: String,
+    /// Unix timestamp the token expires at
+    pub expires_at: i64,
+}
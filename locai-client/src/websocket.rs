@@ -0,0 +1,223 @@
+//! Live change events over the server's WebSocket
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{SinkExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+use crate::error::{ClientError, Result};
+
+/// Only memory events matching every field set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryEventFilter {
+    pub memory_type: Option<String>,
+    pub importance_min: Option<f64>,
+    pub importance_max: Option<f64>,
+    pub content_contains: Option<String>,
+}
+
+/// Only entity events matching every field set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityEventFilter {
+    pub entity_type: Option<String>,
+    pub properties_contains: Option<String>,
+}
+
+/// Only relationship events matching every field set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelationshipEventFilter {
+    pub relationship_type: Option<String>,
+    pub source_id: Option<String>,
+    pub target_id: Option<String>,
+}
+
+/// Which events a connection receives; an empty subscription receives all
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Subscription {
+    pub memory_filter: Option<MemoryEventFilter>,
+    pub entity_filter: Option<EntityEventFilter>,
+    pub relationship_filter: Option<RelationshipEventFilter>,
+}
+
+impl Subscription {
+    /// Every event
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn memories(mut self, filter: MemoryEventFilter) -> Self {
+        self.memory_filter = Some(filter);
+        self
+    }
+
+    pub fn entities(mut self, filter: EntityEventFilter) -> Self {
+        self.entity_filter = Some(filter);
+        self
+    }
+
+    pub fn relationships(mut self, filter: RelationshipEventFilter) -> Self {
+        self.relationship_filter = Some(filter);
+        self
+    }
+}
+
+/// A message from the server's WebSocket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum ServerEvent {
+    MemoryCreated {
+        memory_id: String,
+        content: String,
+        memory_type: String,
+        metadata: serde_json::Value,
+        importance: Option<f64>,
+        node_id: Option<String>,
+    },
+    MemoryUpdated {
+        memory_id: String,
+        content: String,
+        metadata: serde_json::Value,
+        importance: Option<f64>,
+        node_id: Option<String>,
+    },
+    MemoryDeleted {
+        memory_id: String,
+        node_id: Option<String>,
+    },
+    RelationshipCreated {
+        relationship_id: String,
+        source_id: String,
+        target_id: String,
+        relationship_type: String,
+        properties: serde_json::Value,
+        node_id: Option<String>,
+    },
+    RelationshipDeleted {
+        relationship_id: String,
+        node_id: Option<String>,
+    },
+    EntityCreated {
+        entity_id: String,
+        entity_type: String,
+        properties: serde_json::Value,
+        node_id: Option<String>,
+    },
+    EntityUpdated {
+        entity_id: String,
+        entity_type: String,
+        properties: serde_json::Value,
+        node_id: Option<String>,
+    },
+    EntityDeleted {
+        entity_id: String,
+        node_id: Option<String>,
+    },
+    VersionCreated {
+        version_id: String,
+        description: String,
+        node_id: Option<String>,
+    },
+    Connected {
+        connection_id: String,
+    },
+    SubscriptionAck {
+        filters_applied: bool,
+        message: String,
+    },
+    Pong,
+    Error {
+        message: String,
+        code: Option<String>,
+    },
+    /// A message this client doesn't know
+    #[serde(other)]
+    Unknown,
+}
+
+/// Messages the client sends
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data")]
+enum ClientMessage<'a> {
+    Subscribe(&'a Subscription),
+    Ping,
+}
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Events from a subscribed WebSocket connection
+///
+/// Connection and subscription acknowledgements are consumed while
+/// subscribing; the stream ends when the server closes the connection.
+pub struct EventStream {
+    socket: Socket,
+}
+
+impl EventStream {
+    /// Send the subscription and wait for the server to acknowledge it
+    pub(crate) async fn subscribe(mut socket: Socket, subscription: &Subscription) -> Result<Self> {
+        let message = serde_json::to_string(&ClientMessage::Subscribe(subscription))?;
+        socket.send(Message::Text(message.into())).await?;
+
+        let mut events = Self { socket };
+        loop {
+            match events.next().await {
+                Some(Ok(ServerEvent::SubscriptionAck { .. })) => return Ok(events),
+                Some(Ok(ServerEvent::Error { message, code })) => {
+                    return Err(ClientError::Api {
+                        status: 400,
+                        error: code.unwrap_or_else(|| "websocket_error".to_string()),
+                        message,
+                    });
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e),
+                None => {
+                    return Err(ClientError::WebSocket(
+                        tokio_tungstenite::tungstenite::Error::ConnectionClosed,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Ask the server for a `Pong`, to check the connection is alive
+    pub async fn ping(&mut self) -> Result<()> {
+        let message = serde_json::to_string(&ClientMessage::Ping)?;
+        self.socket.send(Message::Text(message.into())).await?;
+        Ok(())
+    }
+
+    /// Close the connection
+    pub async fn close(mut self) -> Result<()> {
+        self.socket.close(None).await?;
+        Ok(())
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Result<ServerEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let message = match self.socket.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(message))) => message,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            match message {
+                Message::Text(text) => {
+                    return Poll::Ready(Some(
+                        serde_json::from_str(text.as_str()).map_err(ClientError::from),
+                    ));
+                }
+                Message::Close(_) => return Poll::Ready(None),
+                // Transport pings are answered by tungstenite
+                _ => continue,
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Client tests against a server listening on a local port
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use locai_client::{
+    ClientError, CreateMemory, LocaiClient, RetryPolicy, Search, ServerEvent, Subscription,
+    UpdateMemory,
+};
+use locai_server::{api::auth_service::AuthService, config::ServerConfig, state::AppState};
+use serde_json::json;
+
+/// Start a server, returning its base URL
+async fn start_server(enable_auth: bool) -> (String, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = locai::config::ConfigBuilder::new()
+        .with_data_dir(temp_dir.path())
+        .with_memory_storage()
+        .build()
+        .expect("Failed to create config");
+    let memory_manager = locai::init(config)
+        .await
+        .expect("Failed to initialize memory manager");
+
+    let mut server_config = ServerConfig::default();
+    server_config.enable_auth = enable_auth;
+    server_config.allow_signup = true;
+    server_config.jwt_secret = "test-secret-key-for-jwt-token-generation".to_string();
+
+    let mut state = AppState::new(memory_manager, server_config.clone());
+    if enable_auth {
+        state.set_auth_service(AuthService::new(server_config.jwt_secret.clone()));
+    }
+    let app = locai_server::create_router(Arc::new(state));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}", address), temp_dir)
+}
+
+#[tokio::test]
+async fn test_memory_round_trip() {
+    let (url, _temp_dir) = start_server(false).await;
+    let client = LocaiClient::new(&url).unwrap();
+
+    let memory = client
+        .create_memory(&CreateMemory::new("The bridge closes at dusk").tags(["bridge"]))
+        .await
+        .unwrap();
+    assert_eq!(memory.tags, vec!["bridge"]);
+
+    let update = UpdateMemory {
+        content: Some("The bridge closes at midnight".to_string()),
+        ..Default::default()
+    };
+    let updated = client.update_memory(&memory.id, &update).await.unwrap();
+    assert_eq!(updated.content, "The bridge closes at midnight");
+
+    let results = client
+        .search(&Search::new("midnight").tags(["bridge"]))
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory.id, memory.id);
+
+    client.delete_memory(&memory.id).await.unwrap();
+    assert_eq!(client.get_memory(&memory.id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_batch_search_and_relationships() {
+    let (url, _temp_dir) = start_server(false).await;
+    let client = LocaiClient::new(&url).unwrap();
+
+    let checklist = client
+        .create_memory(&CreateMemory::new("Deployment checklist"))
+        .await
+        .unwrap();
+    let incident = client
+        .create_memory(&CreateMemory::new("Incident report"))
+        .await
+        .unwrap();
+    client
+        .create_relationship(&incident.id, "references", &checklist.id, json!({}))
+        .await
+        .unwrap();
+
+    let results = client
+        .search_batch(
+            &[Search::new("deployment"), Search::new("incident")],
+            &Search::defaults().limit(5),
+        )
+        .await
+        .unwrap();
+    assert_eq!(results[0].results[0].memory.id, checklist.id);
+    assert_eq!(results[1].results[0].memory.id, incident.id);
+
+    let relationships = client.memory_relationships(&incident.id).await.unwrap();
+    assert_eq!(relationships.len(), 1);
+    assert_eq!(relationships[0].relationship_type, "references");
+}
+
+#[tokio::test]
+async fn test_api_errors_carry_server_message() {
+    let (url, _temp_dir) = start_server(false).await;
+    let client = LocaiClient::new(&url).unwrap();
+
+    let error = client
+        .search(&Search::defaults().limit(3))
+        .await
+        .unwrap_err();
+    match error {
+        ClientError::Api {
+            status, message, ..
+        } => {
+            assert_eq!(status, 400);
+            assert!(message.contains("'q'"), "{}", message);
+        }
+        other => panic!("expected an API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_credentials_sign_in_before_first_request() {
+    let (url, _temp_dir) = start_server(true).await;
+    LocaiClient::new(&url)
+        .unwrap()
+        .signup("reader", "correct horse battery", None)
+        .await
+        .unwrap();
+
+    let anonymous = LocaiClient::new(&url).unwrap();
+    let error = anonymous
+        .list_memories(&Default::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(401));
+
+    let client = LocaiClient::builder(&url)
+        .credentials("reader", "correct horse battery")
+        .build()
+        .unwrap();
+    client.list_memories(&Default::default()).await.unwrap();
+    assert!(client.token().await.is_some());
+}
+
+#[tokio::test]
+async fn test_subscription_receives_memory_events() {
+    let (url, _temp_dir) = start_server(false).await;
+    let client = LocaiClient::new(&url).unwrap();
+
+    let mut events = client.subscribe(&Subscription::all()).await.unwrap();
+    let memory = client
+        .create_memory(&CreateMemory::new("Watched memory"))
+        .await
+        .unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("no event before the timeout")
+        .expect("stream ended")
+        .unwrap();
+    match event {
+        ServerEvent::MemoryCreated { memory_id, .. } => assert_eq!(memory_id, memory.id),
+        other => panic!("expected MemoryCreated, got {:?}", other),
+    }
+    events.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unreachable_server_retries_then_fails() {
+    // Bind and drop a listener for a port nothing listens on
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let client = LocaiClient::builder(&url)
+        .retry_policy(RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(100),
+        })
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    let error = client.health().await.unwrap_err();
+    assert!(matches!(error, ClientError::Http(_)), "{:?}", error);
+    // Two retries, after 50ms and 100ms
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
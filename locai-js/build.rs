@@ -0,0 +1,9 @@
+//! Build script for locai-js
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // napi-rs needs platform-specific linker arguments for Node addons
+    #[cfg(feature = "node")]
+    napi_build::setup();
+}
@@ -0,0 +1,45 @@
+// IndexedDB persistence for the wasm build of locai-js.
+//
+//   import init, { Locai } from './pkg/locai_js.js';
+//   import { loadLocai, saveLocai } from './indexeddb.js';
+//
+//   await init();
+//   const locai = await loadLocai(Locai);
+//   locai.remember('The user prefers dark mode', 'fact', ['preferences']);
+//   await saveLocai(locai);
+
+const DB_NAME = 'locai';
+const STORE_NAME = 'snapshots';
+
+function openDb(dbName = DB_NAME) {
+  return new Promise((resolve, reject) => {
+    const request = indexedDB.open(dbName, 1);
+    request.onupgradeneeded = () => request.result.createObjectStore(STORE_NAME);
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error);
+  });
+}
+
+function run(db, mode, fn) {
+  return new Promise((resolve, reject) => {
+    const tx = db.transaction(STORE_NAME, mode);
+    const request = fn(tx.objectStore(STORE_NAME));
+    tx.oncomplete = () => resolve(request.result);
+    tx.onerror = () => reject(tx.error);
+  });
+}
+
+/** Restore a Locai instance from IndexedDB, or create an empty one. */
+export async function loadLocai(Locai, key = 'default', dbName = DB_NAME) {
+  const db = await openDb(dbName);
+  const snapshot = await run(db, 'readonly', (store) => store.get(key));
+  db.close();
+  return snapshot ? Locai.restore(snapshot) : new Locai();
+}
+
+/** Persist a Locai instance's snapshot to IndexedDB. */
+export async function saveLocai(locai, key = 'default', dbName = DB_NAME) {
+  const db = await openDb(dbName);
+  await run(db, 'readwrite', (store) => store.put(locai.snapshot(), key));
+  db.close();
+}
@@ -0,0 +1,25 @@
+//! Error types for the embeddable store
+
+use thiserror::Error;
+
+/// Errors returned by the embeddable store and its bindings
+#[derive(Error, Debug)]
+pub enum LocaiJsError {
+    #[error("Memory not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    #[error("Invalid scoring config: {0}")]
+    InvalidScoring(String),
+
+    #[error("Unsupported snapshot version {found} (expected {expected})")]
+    UnsupportedSnapshotVersion { found: u32, expected: u32 },
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Result type for the embeddable store
+pub type Result<T> = std::result::Result<T, LocaiJsError>;
@@ -0,0 +1,48 @@
+//! # Locai JS
+//!
+//! Embeddable Locai memory for browsers, browser extensions and Electron/Node
+//! agents.
+//!
+//! Locai's storage is built on SurrealDB, RocksDB and a multi-threaded tokio
+//! runtime, none of which target `wasm32-unknown-unknown`. This crate depends
+//! on `locai` without its `engine` feature, which leaves the memory model and
+//! the search scoring modules, and binds them for a JS host:
+//!
+//! - An in-memory store of `locai::models::Memory` records
+//! - BM25 keyword search from `locai::search::Bm25Index`, ranked by
+//!   `locai::search::ScoreCalculator` (no embeddings required)
+//! - JSON snapshots that can be persisted to IndexedDB, `localStorage` or disk
+//!
+//! ## Feature Flags
+//!
+//! - `wasm`: `wasm-bindgen` bindings (`LocaiWasm`), build with `wasm-pack`
+//! - `node`: napi-rs bindings (`LocaiNode`), build with `@napi-rs/cli`
+//!
+//! Without either flag the crate is a plain Rust library, which is how the
+//! store is tested.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use locai_js::LocalStore;
+//!
+//! let mut store = LocalStore::new();
+//! store.remember("The dragon sleeps beneath the mountain", "fact", vec![]);
+//! store.remember("Elena is a skilled blacksmith", "fact", vec!["npc".into()]);
+//!
+//! let results = store.search("dragon mountain", 5);
+//! assert_eq!(results.len(), 1);
+//! ```
+
+pub mod error;
+pub mod store;
+
+#[cfg(feature = "node")]
+pub mod node;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::{LocaiJsError, Result};
+pub use locai::models::Memory;
+pub use locai::search::ScoringConfig;
+pub use store::{LocalStore, ScoredMemory, StoreSnapshot};
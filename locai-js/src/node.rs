@@ -0,0 +1,99 @@
+//! napi-rs bindings for Node.js and Electron
+//!
+//! Node hosts usually persist with `fs.writeFileSync(path, locai.snapshot())`
+//! and `Locai.restore(fs.readFileSync(path, 'utf8'))`.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::LocaiJsError;
+use crate::store::LocalStore;
+
+fn to_napi(err: LocaiJsError) -> Error {
+    let status = match err {
+        LocaiJsError::NotFound(_)
+        | LocaiJsError::InvalidSnapshot(_)
+        | LocaiJsError::InvalidScoring(_) => Status::InvalidArg,
+        _ => Status::GenericFailure,
+    };
+    Error::new(status, err.to_string())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<serde_json::Value> {
+    serde_json::to_value(value).map_err(|e| to_napi(e.into()))
+}
+
+/// Locai memory store exposed to Node.js
+#[napi(js_name = "Locai")]
+#[derive(Default)]
+pub struct LocaiNode {
+    store: LocalStore,
+}
+
+#[napi]
+impl LocaiNode {
+    /// Create an empty in-memory store
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restore a store from a snapshot produced by `snapshot()`
+    #[napi(factory)]
+    pub fn restore(snapshot: String) -> Result<Self> {
+        Ok(Self {
+            store: LocalStore::from_json(&snapshot).map_err(to_napi)?,
+        })
+    }
+
+    /// Serialize the store for persistence
+    #[napi]
+    pub fn snapshot(&self) -> Result<String> {
+        self.store.to_json().map_err(to_napi)
+    }
+
+    /// Store a memory and return its ID
+    #[napi]
+    pub fn remember(
+        &mut self,
+        content: String,
+        memory_type: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> String {
+        self.store.remember(
+            &content,
+            memory_type.as_deref().unwrap_or("fact"),
+            tags.unwrap_or_default(),
+        )
+    }
+
+    /// Get a memory by ID
+    #[napi]
+    pub fn get(&self, id: String) -> Result<Option<serde_json::Value>> {
+        self.store.get(&id).map(to_json).transpose()
+    }
+
+    /// Delete a memory by ID
+    #[napi]
+    pub fn forget(&mut self, id: String) -> Result<()> {
+        self.store.delete(&id).map(|_| ()).map_err(to_napi)
+    }
+
+    /// BM25 keyword search, returning `{ memory, score }` objects
+    #[napi]
+    pub fn search(&self, query: String, limit: Option<u32>) -> Result<serde_json::Value> {
+        to_json(&self.store.search(&query, limit.unwrap_or(10) as usize))
+    }
+
+    /// Most recent memories
+    #[napi]
+    pub fn recent(&self, limit: Option<u32>) -> Result<serde_json::Value> {
+        to_json(&self.store.list(limit.unwrap_or(10) as usize))
+    }
+
+    /// Number of stored memories
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        self.store.len() as u32
+    }
+}
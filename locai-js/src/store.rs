@@ -0,0 +1,250 @@
+//! In-memory memory store with BM25 search and JSON snapshots
+//!
+//! Keyword scoring and ranking come from `locai::search`, so results are
+//! ordered the way a Locai server orders them: BM25 relevance combined with
+//! recency, access and priority boosts from a [`ScoringConfig`].
+
+use std::collections::HashMap;
+
+use locai::models::{Memory, MemoryBuilder, MemoryType};
+use locai::search::{Bm25Index, ScoreCalculator, ScoringConfig};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{LocaiJsError, Result};
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A search hit with its ranked score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredMemory {
+    pub memory: Memory,
+    pub score: f32,
+}
+
+/// Serializable state of a [`LocalStore`]
+///
+/// Memories use the `locai::models::Memory` shape, so a snapshot can be
+/// replayed into a full Locai instance. The BM25 index is rebuilt on restore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreSnapshot {
+    pub version: u32,
+    pub memories: Vec<Memory>,
+}
+
+/// In-memory memory store
+#[derive(Debug, Clone)]
+pub struct LocalStore {
+    memories: HashMap<String, Memory>,
+    index: Bm25Index,
+    scoring: ScoreCalculator,
+}
+
+impl Default for LocalStore {
+    fn default() -> Self {
+        Self {
+            memories: HashMap::new(),
+            index: Bm25Index::new(),
+            scoring: ScoreCalculator::new(ScoringConfig::default()),
+        }
+    }
+}
+
+impl LocalStore {
+    /// Create an empty store ranked with the default scoring
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty store ranked with `scoring`
+    pub fn with_scoring(scoring: ScoringConfig) -> Result<Self> {
+        Ok(Self {
+            memories: HashMap::new(),
+            index: Bm25Index::new(),
+            scoring: ScoreCalculator::try_new(scoring).map_err(LocaiJsError::InvalidScoring)?,
+        })
+    }
+
+    /// Number of stored memories
+    pub fn len(&self) -> usize {
+        self.memories.len()
+    }
+
+    /// Whether the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.memories.is_empty()
+    }
+
+    /// Store new content and return its generated ID
+    pub fn remember(&mut self, content: &str, memory_type: &str, tags: Vec<String>) -> String {
+        let mut memory = MemoryBuilder::new_with_content(content)
+            .memory_type(MemoryType::from_str(memory_type))
+            .source("locai-js")
+            .build();
+        memory.tags = tags;
+        let id = memory.id.clone();
+        self.insert(memory);
+        id
+    }
+
+    /// Insert or replace a fully-formed memory
+    pub fn insert(&mut self, memory: Memory) {
+        self.index.insert(&memory.id, &memory.content);
+        self.memories.insert(memory.id.clone(), memory);
+    }
+
+    /// Get a memory by ID
+    pub fn get(&self, id: &str) -> Option<&Memory> {
+        self.memories.get(id)
+    }
+
+    /// Delete a memory by ID
+    pub fn delete(&mut self, id: &str) -> Result<Memory> {
+        let memory = self
+            .memories
+            .remove(id)
+            .ok_or_else(|| LocaiJsError::NotFound(id.to_string()))?;
+        self.index.remove(id);
+        Ok(memory)
+    }
+
+    /// List memories, newest first
+    pub fn list(&self, limit: usize) -> Vec<&Memory> {
+        let mut memories: Vec<&Memory> = self.memories.values().collect();
+        memories.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        memories.truncate(limit);
+        memories
+    }
+
+    /// BM25 keyword search, ranked by the store's scoring
+    pub fn search(&self, query: &str, limit: usize) -> Vec<ScoredMemory> {
+        let mut results: Vec<ScoredMemory> = self
+            .index
+            .search(query, self.index.len())
+            .into_iter()
+            .filter_map(|(id, bm25)| {
+                self.memories.get(&id).map(|memory| ScoredMemory {
+                    score: self.scoring.calculate_final_score(bm25, None, memory),
+                    memory: memory.clone(),
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.memory.id.cmp(&b.memory.id))
+        });
+        results.truncate(limit);
+        results
+    }
+
+    /// Capture the store's contents for persistence
+    pub fn snapshot(&self) -> StoreSnapshot {
+        let mut memories: Vec<Memory> = self.memories.values().cloned().collect();
+        memories.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        StoreSnapshot {
+            version: SNAPSHOT_VERSION,
+            memories,
+        }
+    }
+
+    /// Rebuild a store from a snapshot
+    pub fn from_snapshot(snapshot: StoreSnapshot) -> Result<Self> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(LocaiJsError::UnsupportedSnapshotVersion {
+                found: snapshot.version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+
+        let mut store = Self::new();
+        for memory in snapshot.memories {
+            store.insert(memory);
+        }
+        Ok(store)
+    }
+
+    /// Serialize the store to a JSON snapshot string
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.snapshot())?)
+    }
+
+    /// Restore a store from a JSON snapshot string
+    pub fn from_json(json: &str) -> Result<Self> {
+        let snapshot: StoreSnapshot =
+            serde_json::from_str(json).map_err(|e| LocaiJsError::InvalidSnapshot(e.to_string()))?;
+        Self::from_snapshot(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remember_search_delete() {
+        let mut store = LocalStore::new();
+        let id = store.remember("The dragon sleeps beneath the mountain", "fact", vec![]);
+        store.remember(
+            "Elena is a skilled blacksmith",
+            "fact",
+            vec!["npc".to_string()],
+        );
+
+        let results = store.search("dragon", 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.id, id);
+
+        store.delete(&id).unwrap();
+        assert!(store.search("dragon", 10).is_empty());
+        assert!(matches!(store.delete(&id), Err(LocaiJsError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_search_ranks_with_scoring() {
+        let mut store = LocalStore::with_scoring(ScoringConfig::importance_focused()).unwrap();
+        store.insert(
+            MemoryBuilder::new("routine".to_string(), "The dragon sleeps".to_string()).build(),
+        );
+        store.insert(
+            MemoryBuilder::new("urgent".to_string(), "The dragon wakes".to_string())
+                .critical_priority()
+                .build(),
+        );
+
+        let results = store.search("dragon", 10);
+        assert_eq!(results[0].memory.id, "urgent");
+        assert!(results[0].score > results[1].score);
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let mut store = LocalStore::new();
+        store.remember("The dragon sleeps", "fact", vec!["lore".to_string()]);
+        store.remember("The knight wakes", "episodic", vec![]);
+
+        let restored = LocalStore::from_json(&store.to_json().unwrap()).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.search("knight", 10).len(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_version_mismatch() {
+        let snapshot = StoreSnapshot {
+            version: SNAPSHOT_VERSION + 1,
+            memories: vec![],
+        };
+        assert!(matches!(
+            LocalStore::from_snapshot(snapshot),
+            Err(LocaiJsError::UnsupportedSnapshotVersion { .. })
+        ));
+    }
+}
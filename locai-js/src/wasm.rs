@@ -0,0 +1,90 @@
+//! `wasm-bindgen` bindings for browsers and browser extensions
+//!
+//! Persistence is left to the host: call `snapshot()` and store the string in
+//! IndexedDB (see `js/indexeddb.js`), then pass it to `LocaiWasm.restore()` on
+//! the next load.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::LocaiJsError;
+use crate::store::LocalStore;
+
+impl From<LocaiJsError> for JsValue {
+    fn from(err: LocaiJsError) -> Self {
+        JsError::new(&err.to_string()).into()
+    }
+}
+
+/// Locai memory store exposed to JavaScript
+#[wasm_bindgen(js_name = Locai)]
+#[derive(Default)]
+pub struct LocaiWasm {
+    store: LocalStore,
+}
+
+#[wasm_bindgen(js_class = Locai)]
+impl LocaiWasm {
+    /// Create an empty in-memory store
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restore a store from a snapshot produced by `snapshot()`
+    pub fn restore(snapshot: &str) -> Result<LocaiWasm, JsValue> {
+        Ok(Self {
+            store: LocalStore::from_json(snapshot)?,
+        })
+    }
+
+    /// Serialize the store so it can be written to IndexedDB
+    pub fn snapshot(&self) -> Result<String, JsValue> {
+        Ok(self.store.to_json()?)
+    }
+
+    /// Store a memory and return its ID
+    pub fn remember(
+        &mut self,
+        content: &str,
+        memory_type: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> String {
+        self.store.remember(
+            content,
+            memory_type.as_deref().unwrap_or("fact"),
+            tags.unwrap_or_default(),
+        )
+    }
+
+    /// Get a memory by ID, or `undefined`
+    pub fn get(&self, id: &str) -> Result<JsValue, JsValue> {
+        match self.store.get(id) {
+            Some(memory) => Ok(serde_wasm_bindgen::to_value(memory)?),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    /// Delete a memory by ID
+    pub fn forget(&mut self, id: &str) -> Result<(), JsValue> {
+        self.store.delete(id)?;
+        Ok(())
+    }
+
+    /// BM25 keyword search, returning `{ memory, score }` objects
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Result<JsValue, JsValue> {
+        let results = self.store.search(query, limit.unwrap_or(10));
+        Ok(serde_wasm_bindgen::to_value(&results)?)
+    }
+
+    /// Most recent memories
+    pub fn recent(&self, limit: Option<usize>) -> Result<JsValue, JsValue> {
+        let memories = self.store.list(limit.unwrap_or(10));
+        Ok(serde_wasm_bindgen::to_value(&memories)?)
+    }
+
+    /// Number of stored memories
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.store.len()
+    }
+}
@@ -0,0 +1,162 @@
+//! Maintenance endpoints for operators
+//!
+//! Each endpoint queues a maintenance job (see [`crate::jobs`]) and answers
+//! `202 Accepted` with it; `GET /api/jobs/{id}` reports its progress and
+//! result. Request bodies are optional. Only one job per operation is pending
+//! at a time. With authentication enabled, these endpoints need the `admin` or
+//! `root` role.
+
+use std::sync::Arc;
+
+use axum::{Extension, extract::State, http::StatusCode, response::Json};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{auth::AuthContext, jobs::submit},
+    error::ServerResult,
+    jobs::{Job, JobRequest},
+    state::AppState,
+};
+
+/// Request to compact memory versions
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CompactRequest {
+    /// Only compact this memory's versions; all memories when absent
+    pub memory_id: Option<String>,
+    /// Versions to keep per memory
+    pub keep_count: Option<usize>,
+    /// Only drop versions older than this many days
+    pub older_than_days: Option<u64>,
+}
+
+/// Request to run retention
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RetentionRequest {
+    /// Also archive memories not accessed for this many days
+    pub archive_older_than_days: Option<u64>,
+    /// Archive at most this many memories
+    pub archive_limit: Option<usize>,
+    /// Also archive memories faded below the forgetting curve's threshold
+    #[serde(default)]
+    pub archive_forgotten: bool,
+}
+
+/// Request to repair memory versions
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RepairVersionsRequest {
+    /// Only repair this memory's versions; all memories when absent
+    pub memory_id: Option<String>,
+}
+
+/// Compact memory versions
+#[utoipa::path(
+    post,
+    path = "/api/admin/compact",
+    tag = "admin",
+    request_body = CompactRequest,
+    responses(
+        (status = 202, description = "Compaction queued", body = Job),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "A compaction is already pending"),
+    )
+)]
+pub async fn compact(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    request: Option<Json<CompactRequest>>,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let job = JobRequest::Compact {
+        memory_id: request.memory_id,
+        keep_count: request.keep_count,
+        older_than_days: request.older_than_days,
+    };
+    submit(&state, auth, job).await
+}
+
+/// Check and repair index consistency, then recount quota usage
+#[utoipa::path(
+    post,
+    path = "/api/admin/reindex",
+    tag = "admin",
+    responses(
+        (status = 202, description = "Reindexing queued", body = Job),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "A reindex is already pending"),
+    )
+)]
+pub async fn reindex(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    submit(&state, auth, JobRequest::Reindex).await
+}
+
+/// Enforce message retention and optionally archive inactive memories
+#[utoipa::path(
+    post,
+    path = "/api/admin/retention",
+    tag = "admin",
+    request_body = RetentionRequest,
+    responses(
+        (status = 202, description = "Retention run queued", body = Job),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "A retention run is already pending"),
+    )
+)]
+pub async fn run_retention(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    request: Option<Json<RetentionRequest>>,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let job = JobRequest::Retention {
+        archive_older_than_days: request.archive_older_than_days,
+        archive_limit: request.archive_limit,
+        archive_forgotten: request.archive_forgotten,
+    };
+    submit(&state, auth, job).await
+}
+
+/// Repair broken memory version chains
+#[utoipa::path(
+    post,
+    path = "/api/admin/versions/repair",
+    tag = "admin",
+    request_body = RepairVersionsRequest,
+    responses(
+        (status = 202, description = "Version repair queued", body = Job),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "A version repair is already pending"),
+    )
+)]
+pub async fn repair_versions(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    request: Option<Json<RepairVersionsRequest>>,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let job = JobRequest::RepairVersions {
+        memory_id: request.memory_id,
+    };
+    submit(&state, auth, job).await
+}
+
+/// Drop the search result and memory version caches
+#[utoipa::path(
+    post,
+    path = "/api/admin/cache/clear",
+    tag = "admin",
+    responses(
+        (status = 202, description = "Cache clearing queued", body = Job),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "Cache clearing is already pending"),
+    )
+)]
+pub async fn clear_caches(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    submit(&state, auth, JobRequest::ClearCaches).await
+}
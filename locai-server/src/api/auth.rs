@@ -119,7 +119,7 @@ pub async fn auth_middleware(
 fn is_public_endpoint(path: &str) -> bool {
     matches!(
         path,
-        "/health" | "/docs" | "/api-docs" | "/auth/login" | "/auth/signup"
+        "/health" | "/ready" | "/docs" | "/api-docs" | "/auth/login" | "/auth/signup"
     ) || path.starts_with("/docs")
         || path.starts_with("/api-docs")
 }
@@ -187,7 +187,6 @@ pub fn get_auth_context(request: &Request) -> Option<&AuthContext> {
 }
 
 /// Check if user has required role
-#[allow(dead_code)]
 pub fn check_role_permission(auth_context: &AuthContext, required_role: &str) -> bool {
     match (auth_context.role.as_str(), required_role) {
         ("root", _) => true,       // Root can do anything
@@ -196,6 +195,27 @@ pub fn check_role_permission(auth_context: &AuthContext, required_role: &str) ->
     }
 }
 
+/// Refuse callers without `required_role` when authentication is enabled
+pub fn require_role(
+    state: &AppState,
+    auth_context: Option<&AuthContext>,
+    required_role: &str,
+) -> Result<(), ServerError> {
+    if !state.config.enable_auth {
+        return Ok(());
+    }
+    match auth_context {
+        Some(auth_context) if check_role_permission(auth_context, required_role) => Ok(()),
+        Some(auth_context) => Err(ServerError::Forbidden(format!(
+            "This needs the {} role; '{}' has '{}'",
+            required_role, auth_context.username, auth_context.role
+        ))),
+        None => Err(ServerError::Auth(
+            "Missing authorization header".to_string(),
+        )),
+    }
+}
+
 /// Generate a secure random root password
 pub fn generate_root_password() -> String {
     use rand::Rng;
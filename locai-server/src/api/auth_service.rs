@@ -15,6 +15,9 @@ use crate::{
     error::{ServerError, ServerResult},
 };
 
+/// Entity type users are stored under
+pub const USER_ENTITY_TYPE: &str = "user";
+
 /// User data structure
 #[derive(Debug, Clone)]
 pub struct User {
@@ -111,7 +114,7 @@ impl User {
 
         Entity {
             id: self.id.to_string(),
-            entity_type: "user".to_string(),
+            entity_type: USER_ENTITY_TYPE.to_string(),
             properties: Value::Object(properties.into_iter().collect()),
             created_at: self.created_at,
             updated_at: self.updated_at,
@@ -225,7 +228,7 @@ impl AuthService {
 
         // Use entity filtering to find user by username
         let filter = EntityFilter {
-            entity_type: Some("user".to_string()),
+            entity_type: Some(USER_ENTITY_TYPE.to_string()),
             ..Default::default()
         };
         let entities = storage
@@ -254,7 +257,7 @@ impl AuthService {
 
         match storage.get_entity(&user_id.to_string()).await {
             Ok(Some(entity)) => {
-                if entity.entity_type == "user" {
+                if entity.entity_type == USER_ENTITY_TYPE {
                     Ok(Some(User::from_entity(entity)?))
                 } else {
                     Ok(None)
@@ -302,7 +305,7 @@ impl AuthService {
         let storage = memory_manager.storage();
 
         let filter = EntityFilter {
-            entity_type: Some("user".to_string()),
+            entity_type: Some(USER_ENTITY_TYPE.to_string()),
             ..Default::default()
         };
         let entities = storage
@@ -3,15 +3,21 @@
 //! This module provides REST endpoints for executing batch operations
 //! on memories and relationships in bulk.
 
-use axum::{extract::State, response::Json};
+use axum::{Extension, extract::State, response::Json};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::debug;
 use utoipa::ToSchema;
 
+use locai::LocaiError;
 use locai::batch::{BatchExecutor, BatchExecutorConfig, BatchOperation, BatchResponse};
+use locai::memory::idempotency;
+use serde_json::Value;
 
-use crate::error::{ServerError, ServerResult};
+use crate::api::auth::AuthContext;
+use crate::api::memories::scoped_idempotency_key;
+use crate::error::{ServerError, ServerResult, not_found};
+use crate::sharing::{Access, OWNER_PROPERTY, is_admin};
 use crate::state::AppState;
 
 /// Request to execute a batch of operations
@@ -23,6 +29,11 @@ pub struct BatchRequest {
     /// If true, execute operations as a single transaction (all or nothing)
     #[serde(default)]
     pub transaction: bool,
+
+    /// Makes retries safe: a request repeating an earlier one's key returns
+    /// that request's response instead of executing the operations again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 /// Execute a batch of operations
@@ -53,10 +64,19 @@ pub struct BatchRequest {
 ///       }
 ///     }
 ///   ],
-///   "transaction": false
+///   "transaction": false,
+///   "idempotency_key": "import-2024-06-01"
 /// }
 /// ```
 ///
+/// With an `idempotency_key`, a retried request returns the first response
+/// without executing again; reusing the key for other operations is a
+/// `409 Conflict`.
+///
+/// Operations are checked against shares as the single-record endpoints
+/// check them: created memories belong to the caller, updates take write
+/// access and deletes ownership. If any operation isn't allowed, none run.
+///
 /// # Response
 ///
 /// Returns a `BatchResponse` with results for each operation:
@@ -72,6 +92,9 @@ pub struct BatchRequest {
 /// # Errors
 ///
 /// - `400 Bad Request`: Batch validation failed or exceeds size limit
+/// - `403 Forbidden`: An operation changes a memory shared with the caller
+///   read-only, or deletes one the caller doesn't own
+/// - `404 Not Found`: An operation names a memory the caller can't see
 /// - `500 Internal Server Error`: Storage or processing error
 #[utoipa::path(
     post,
@@ -81,11 +104,14 @@ pub struct BatchRequest {
     responses(
         (status = 200, description = "Batch executed successfully", body = serde_json::Value),
         (status = 400, description = "Invalid batch request or size exceeded"),
+        (status = 403, description = "An operation needs more access than the caller has"),
+        (status = 404, description = "An operation names a memory the caller can't see"),
         (status = 500, description = "Internal server error"),
     ),
 )]
 pub async fn batch_execute(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     axum::extract::Json(request): axum::extract::Json<BatchRequest>,
 ) -> ServerResult<Json<BatchResponse>> {
     debug!(
@@ -94,12 +120,21 @@ pub async fn batch_execute(
         request.transaction
     );
 
+    let transaction = request.transaction;
+    let idempotency_key = scoped_idempotency_key(request.idempotency_key, auth.as_deref());
+    let submitted = serde_json::to_string(&request.operations).unwrap_or_default();
+    let fingerprint = idempotency::fingerprint([
+        submitted.as_str(),
+        if transaction { "transaction" } else { "" },
+    ]);
+
     // Deserialize operations from serde_json::Value to BatchOperation
-    let operations: Vec<BatchOperation> = request
+    let mut operations: Vec<BatchOperation> = request
         .operations
         .into_iter()
         .filter_map(|v| serde_json::from_value(v).ok())
         .collect();
+    authorize(&state, &mut operations, auth.as_deref()).await?;
 
     // Get storage from memory manager
     let storage = state.memory_manager.storage().clone();
@@ -108,13 +143,145 @@ pub async fn batch_execute(
     let config = BatchExecutorConfig::default();
     let executor = BatchExecutor::new(storage, config);
 
-    // Execute the batch
-    let response = executor
-        .execute(operations, request.transaction)
-        .await
-        .map_err(|e| ServerError::BadRequest(format!("Batch execution failed: {}", e)))?;
+    // Execute the batch, unless a request with the same idempotency key did;
+    // a failed batch is a bad request rather than a storage error
+    let mut rejected = None;
+    let slot = &mut rejected;
+    let response = state
+        .memory_manager
+        .idempotent(
+            idempotency_key.as_deref(),
+            &fingerprint,
+            move || async move {
+                executor
+                    .execute(operations, transaction)
+                    .await
+                    .map_err(|e| {
+                        let message = format!("Batch execution failed: {}", e);
+                        *slot = Some(message.clone());
+                        LocaiError::Other(message)
+                    })
+            },
+        )
+        .await;
+    if let Some(message) = rejected {
+        return Err(ServerError::BadRequest(message));
+    }
+
+    Ok(Json(response?))
+}
+
+/// Fail unless `user` may run every operation, and give created memories to
+/// `user` unless an admin names another owner
+///
+/// As with the memory endpoints, only a memory's owner can hand it over by
+/// changing its `owner` property.
+async fn authorize(
+    state: &AppState,
+    operations: &mut [BatchOperation],
+    user: Option<&AuthContext>,
+) -> ServerResult<()> {
+    let Some(user) = user else {
+        return Ok(());
+    };
+    let shares = &state.shares;
+    let memory_manager = &state.memory_manager;
+
+    for operation in operations.iter_mut() {
+        match operation {
+            BatchOperation::CreateMemory { properties, .. } => {
+                let properties =
+                    properties.get_or_insert_with(|| Value::Object(Default::default()));
+                if !(is_admin(user) && properties.get(OWNER_PROPERTY).is_some()) {
+                    set_owner(properties, Some(user.user_id.to_string().into()));
+                }
+            }
+            BatchOperation::UpdateMemory { id, properties, .. } => {
+                let memory = shares
+                    .load(memory_manager, id, Some(user), Access::Write)
+                    .await?;
+                if let Some(properties) = properties
+                    && (shares.access(&memory, Some(user)) < Access::Owner
+                        || properties.get(OWNER_PROPERTY).is_none())
+                {
+                    set_owner(properties, memory.properties.get(OWNER_PROPERTY).cloned());
+                }
+            }
+            BatchOperation::UpdateMetadata {
+                memory_id,
+                metadata,
+            } => {
+                let memory = shares
+                    .load(memory_manager, memory_id, Some(user), Access::Write)
+                    .await?;
+                // Metadata merges into the properties, so leaving the owner
+                // out keeps it
+                if shares.access(&memory, Some(user)) < Access::Owner
+                    && let Some(metadata) = metadata.as_object_mut()
+                {
+                    metadata.remove(OWNER_PROPERTY);
+                }
+            }
+            BatchOperation::DeleteMemory { id } => {
+                shares
+                    .load(memory_manager, id, Some(user), Access::Owner)
+                    .await?;
+            }
+            BatchOperation::CreateRelationship { source, target, .. } => {
+                let ends = [source.as_str(), target.as_str()];
+                let hidden = shares.hidden(memory_manager, ends, Some(user)).await?;
+                if let Some(id) = hidden.iter().next() {
+                    return Err(not_found("Memory", id));
+                }
+                if !shares
+                    .allows(memory_manager, &ends, Some(user), Access::Write)
+                    .await?
+                {
+                    return Err(ServerError::Forbidden(
+                        "The memory is shared with you read-only".to_string(),
+                    ));
+                }
+            }
+            BatchOperation::UpdateRelationship { id, .. }
+            | BatchOperation::DeleteRelationship { id } => {
+                let Some(relationship) = memory_manager.get_relationship(id).await? else {
+                    continue;
+                };
+                let ends = [
+                    relationship.source_id.as_str(),
+                    relationship.target_id.as_str(),
+                ];
+                if !shares
+                    .allows(memory_manager, &ends, Some(user), Access::Read)
+                    .await?
+                {
+                    return Err(not_found("Relationship", id));
+                }
+                if !shares
+                    .allows(memory_manager, &ends, Some(user), Access::Write)
+                    .await?
+                {
+                    return Err(ServerError::Forbidden(
+                        "The relationship links a memory shared with you read-only".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
 
-    Ok(Json(response))
+/// Set the `owner` of a memory's properties, or remove it for `None`
+fn set_owner(properties: &mut Value, owner: Option<Value>) {
+    if !properties.is_object() {
+        *properties = Value::Object(Default::default());
+    }
+    if let Some(properties) = properties.as_object_mut() {
+        match owner {
+            Some(owner) => properties.insert(OWNER_PROPERTY.to_string(), owner),
+            None => properties.remove(OWNER_PROPERTY),
+        };
+    }
 }
 
 #[cfg(test)]
@@ -133,6 +300,7 @@ mod tests {
                 }
             })],
             transaction: false,
+            idempotency_key: None,
         };
 
         let json = serde_json::to_string(&request).expect("Should serialize");
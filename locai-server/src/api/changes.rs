@@ -0,0 +1,175 @@
+//! Changefeed endpoint
+//!
+//! `GET /api/changes?since=<cursor>` pages through committed changes to
+//! memories, entities and relationships. Consumers store the returned
+//! `next_cursor` and pass it back as `since` to resume, so no connection has
+//! to stay open between polls.
+//!
+//! Changes to memories the caller can't see, and to relationships linking
+//! them, are left out; deletes only carry an ID and are always listed.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json,
+};
+use locai::replication::{ChangeOp, RecordKind};
+use locai::storage::models::ChangeFeedEntry;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::auth::AuthContext,
+    api::entities::is_reserved_entity_type,
+    error::{ServerError, ServerResult, bad_request},
+    sharing::{Access, is_admin},
+    state::AppState,
+};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ChangesParams {
+    /// Cursor from a previous response; omit to start from the oldest
+    /// retained change
+    pub since: Option<String>,
+
+    /// Maximum number of changes to return (default 100, max 1000)
+    pub limit: Option<usize>,
+}
+
+/// A committed change to a memory, entity or relationship
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangeDto {
+    /// Cursor of the transaction that made the change
+    pub cursor: String,
+
+    /// "memory", "entity" or "relationship"
+    pub kind: String,
+
+    /// Record ID
+    pub id: String,
+
+    /// "upsert" or "delete"
+    pub op: String,
+
+    /// Record after the change, absent for deletes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record: Option<serde_json::Value>,
+}
+
+impl From<ChangeFeedEntry> for ChangeDto {
+    fn from(entry: ChangeFeedEntry) -> Self {
+        Self {
+            cursor: entry.cursor.to_string(),
+            kind: match entry.kind {
+                RecordKind::Memory => "memory",
+                RecordKind::Entity => "entity",
+                RecordKind::Relationship => "relationship",
+            }
+            .to_string(),
+            id: entry.id,
+            op: match entry.op {
+                ChangeOp::Upsert => "upsert",
+                ChangeOp::Delete => "delete",
+            }
+            .to_string(),
+            record: entry.record,
+        }
+    }
+}
+
+/// A page of changes
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangesResponse {
+    /// Changes in commit order
+    pub changes: Vec<ChangeDto>,
+
+    /// Pass as `since` to get the following changes
+    pub next_cursor: String,
+
+    /// Whether more changes may be waiting; poll again right away if set
+    pub has_more: bool,
+}
+
+/// Read the changefeed
+#[utoipa::path(
+    get,
+    path = "/api/changes",
+    tag = "changes",
+    params(ChangesParams),
+    responses(
+        (status = 200, description = "Changes after the cursor", body = ChangesResponse),
+        (status = 400, description = "Invalid cursor"),
+    )
+)]
+pub async fn list_changes(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<ChangesParams>,
+) -> ServerResult<Json<ChangesResponse>> {
+    let since = match params.since.as_deref() {
+        None | Some("") => 0,
+        Some(cursor) => cursor
+            .parse::<u64>()
+            .map_err(|_| bad_request(&format!("Invalid cursor '{}'", cursor)))?,
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let page = state
+        .memory_manager
+        .storage()
+        .read_changes(since, limit)
+        .await
+        .map_err(|e| ServerError::Database(e.to_string()))?;
+
+    let has_more = page.changes.len() >= limit;
+    let mut changes = Vec::with_capacity(page.changes.len());
+    for entry in page.changes {
+        if readable(&state, &entry, auth.as_deref()).await? {
+            changes.push(ChangeDto::from(entry));
+        }
+    }
+
+    Ok(Json(ChangesResponse {
+        has_more,
+        next_cursor: page.cursor.to_string(),
+        changes,
+    }))
+}
+
+/// Whether `user` may see the record a change carries
+async fn readable(
+    state: &AppState,
+    entry: &ChangeFeedEntry,
+    user: Option<&AuthContext>,
+) -> ServerResult<bool> {
+    let Some(record) = &entry.record else {
+        return Ok(true);
+    };
+    match entry.kind {
+        RecordKind::Memory => {
+            let properties = &record["properties"];
+            Ok(state.shares.access_to(&entry.id, properties, user) >= Access::Read)
+        }
+        RecordKind::Relationship => {
+            let ends: Vec<&str> = ["source_id", "target_id"]
+                .iter()
+                .filter_map(|end| record.get(end).and_then(|id| id.as_str()))
+                .collect();
+            state
+                .shares
+                .allows(&state.memory_manager, &ends, user, Access::Read)
+                .await
+        }
+        // The server's own records, such as share grants, are for admins
+        // and replicas only
+        RecordKind::Entity => Ok(user.is_none_or(is_admin)
+            || !record["entity_type"]
+                .as_str()
+                .is_some_and(is_reserved_entity_type)),
+    }
+}
@@ -0,0 +1,406 @@
+//! Collection endpoints
+//!
+//! Collections group memories by hand-picked IDs, by a query, or both (see
+//! [`locai::memory::collections`]). Paths take a collection's ID or its name.
+//! Scope a search to a collection with the `collection` parameter of
+//! `GET /api/memories/search`. Member lists only show memories the caller
+//! can see (see [`crate::sharing`]).
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::{Collection, CollectionQuery};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::{auth::AuthContext, dto::MemoryDto},
+    error::{ServerError, ServerResult, bad_request, not_found},
+    state::AppState,
+};
+
+/// Which memories a query-based collection includes; every condition given must hold
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CollectionQueryDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_type: Option<String>,
+    /// Memories with any of these tags
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Memories whose content contains this text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl From<CollectionQuery> for CollectionQueryDto {
+    fn from(query: CollectionQuery) -> Self {
+        Self {
+            memory_type: query.memory_type,
+            tags: query.tags,
+            source: query.source,
+            content: query.content,
+            created_after: query.created_after,
+            created_before: query.created_before,
+        }
+    }
+}
+
+impl From<CollectionQueryDto> for CollectionQuery {
+    fn from(query: CollectionQueryDto) -> Self {
+        Self {
+            memory_type: query.memory_type,
+            tags: query.tags,
+            source: query.source,
+            content: query.content,
+            created_after: query.created_after,
+            created_before: query.created_before,
+        }
+    }
+}
+
+/// A named group of memories
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CollectionDto {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Memories added by hand
+    pub memory_ids: Vec<String>,
+    /// Memories matching this query are members too
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<CollectionQueryDto>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Collection> for CollectionDto {
+    fn from(collection: Collection) -> Self {
+        Self {
+            id: collection.id,
+            name: collection.name,
+            description: collection.description,
+            memory_ids: collection.memory_ids,
+            query: collection.query.map(CollectionQueryDto::from),
+            created_at: collection.created_at,
+            updated_at: collection.updated_at,
+        }
+    }
+}
+
+/// Request to create a collection
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCollectionRequest {
+    /// Unique name
+    pub name: String,
+    pub description: Option<String>,
+    /// Memories to add
+    #[serde(default)]
+    pub memory_ids: Vec<String>,
+    /// Include every memory matching this query
+    pub query: Option<CollectionQueryDto>,
+}
+
+/// Request to update a collection; absent fields are left alone
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateCollectionRequest {
+    pub name: Option<String>,
+    /// New description; an empty one removes it
+    pub description: Option<String>,
+    /// New query
+    pub query: Option<CollectionQueryDto>,
+    /// Drop the query, leaving only the memories added by hand
+    #[serde(default)]
+    pub remove_query: bool,
+}
+
+/// Memories to add to a collection
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddCollectionMemoriesRequest {
+    pub memory_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CollectionMemoriesParams {
+    /// Maximum number of memories, newest first
+    pub limit: Option<usize>,
+}
+
+/// Create a collection
+#[utoipa::path(
+    post,
+    path = "/api/collections",
+    tag = "collections",
+    request_body = CreateCollectionRequest,
+    responses(
+        (status = 201, description = "Collection created", body = CollectionDto),
+        (status = 400, description = "Empty name"),
+        (status = 404, description = "A memory to add was not found"),
+        (status = 409, description = "Name already taken"),
+    )
+)]
+pub async fn create_collection(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Json(request): Json<CreateCollectionRequest>,
+) -> ServerResult<(StatusCode, Json<CollectionDto>)> {
+    check_name_free(&state, &request.name, None).await?;
+    check_memories_visible(&state, auth.as_deref(), &request.memory_ids).await?;
+
+    let mut collection = Collection::new(request.name).with_memories(request.memory_ids);
+    collection.description = request.description.filter(|d| !d.is_empty());
+    collection.query = request.query.map(CollectionQuery::from);
+
+    let collection = state.memory_manager.create_collection(collection).await?;
+    Ok((StatusCode::CREATED, Json(collection.into())))
+}
+
+/// List collections by name
+#[utoipa::path(
+    get,
+    path = "/api/collections",
+    tag = "collections",
+    responses(
+        (status = 200, description = "All collections", body = Vec<CollectionDto>),
+    )
+)]
+pub async fn list_collections(
+    State(state): State<Arc<AppState>>,
+) -> ServerResult<Json<Vec<CollectionDto>>> {
+    let collections = state.memory_manager.list_collections().await?;
+    Ok(Json(
+        collections.into_iter().map(CollectionDto::from).collect(),
+    ))
+}
+
+/// Get a collection
+#[utoipa::path(
+    get,
+    path = "/api/collections/{id}",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection ID or name")
+    ),
+    responses(
+        (status = 200, description = "Collection found", body = CollectionDto),
+        (status = 404, description = "Collection not found"),
+    )
+)]
+pub async fn get_collection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<CollectionDto>> {
+    Ok(Json(find(&state, &id).await?.into()))
+}
+
+/// Update a collection's name, description or query
+#[utoipa::path(
+    put,
+    path = "/api/collections/{id}",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection ID or name")
+    ),
+    request_body = UpdateCollectionRequest,
+    responses(
+        (status = 200, description = "Collection updated", body = CollectionDto),
+        (status = 404, description = "Collection not found"),
+        (status = 409, description = "Name already taken"),
+    )
+)]
+pub async fn update_collection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateCollectionRequest>,
+) -> ServerResult<Json<CollectionDto>> {
+    let mut collection = find(&state, &id).await?;
+
+    if let Some(name) = request.name {
+        check_name_free(&state, &name, Some(&collection.id)).await?;
+        collection.name = name;
+    }
+    if let Some(description) = request.description {
+        collection.description = Some(description).filter(|d| !d.is_empty());
+    }
+    if request.remove_query {
+        collection.query = None;
+    }
+    if let Some(query) = request.query {
+        collection.query = Some(query.into());
+    }
+
+    let collection = state.memory_manager.update_collection(collection).await?;
+    Ok(Json(collection.into()))
+}
+
+/// Delete a collection, keeping its memories
+#[utoipa::path(
+    delete,
+    path = "/api/collections/{id}",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection ID or name")
+    ),
+    responses(
+        (status = 204, description = "Collection deleted"),
+        (status = 404, description = "Collection not found"),
+    )
+)]
+pub async fn delete_collection(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ServerResult<StatusCode> {
+    if !state.memory_manager.delete_collection(&id).await? {
+        return Err(not_found("Collection", &id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List a collection's memories, newest first
+#[utoipa::path(
+    get,
+    path = "/api/collections/{id}/memories",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection ID or name"),
+        CollectionMemoriesParams
+    ),
+    responses(
+        (status = 200, description = "Memories in the collection", body = Vec<MemoryDto>),
+        (status = 404, description = "Collection not found"),
+    )
+)]
+pub async fn list_collection_memories(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Query(params): Query<CollectionMemoriesParams>,
+) -> ServerResult<Json<Vec<MemoryDto>>> {
+    let collection = find(&state, &id).await?;
+    let memories = state
+        .memory_manager
+        .collection_memories(&collection, None)
+        .await?;
+    let memories = memories
+        .into_iter()
+        .filter(|memory| state.shares.can_read(memory, auth.as_deref()))
+        .take(params.limit.unwrap_or(usize::MAX))
+        .map(MemoryDto::from)
+        .collect();
+    Ok(Json(memories))
+}
+
+/// Add memories to a collection
+#[utoipa::path(
+    post,
+    path = "/api/collections/{id}/memories",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection ID or name")
+    ),
+    request_body = AddCollectionMemoriesRequest,
+    responses(
+        (status = 200, description = "Memories added", body = CollectionDto),
+        (status = 404, description = "Collection or memory not found"),
+    )
+)]
+pub async fn add_collection_memories(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Json(request): Json<AddCollectionMemoriesRequest>,
+) -> ServerResult<Json<CollectionDto>> {
+    check_memories_visible(&state, auth.as_deref(), &request.memory_ids).await?;
+    let collection = state
+        .memory_manager
+        .add_to_collection(&id, &request.memory_ids)
+        .await?
+        .ok_or_else(|| not_found("Collection", &id))?;
+    Ok(Json(collection.into()))
+}
+
+/// Remove a memory added by hand from a collection
+///
+/// A memory the collection's query matches stays a member.
+#[utoipa::path(
+    delete,
+    path = "/api/collections/{id}/memories/{memory_id}",
+    tag = "collections",
+    params(
+        ("id" = String, Path, description = "Collection ID or name"),
+        ("memory_id" = String, Path, description = "Memory ID")
+    ),
+    responses(
+        (status = 200, description = "Memory removed", body = CollectionDto),
+        (status = 404, description = "Collection not found"),
+    )
+)]
+pub async fn remove_collection_memory(
+    State(state): State<Arc<AppState>>,
+    Path((id, memory_id)): Path<(String, String)>,
+) -> ServerResult<Json<CollectionDto>> {
+    let collection = state
+        .memory_manager
+        .remove_from_collection(&id, &[memory_id])
+        .await?
+        .ok_or_else(|| not_found("Collection", &id))?;
+    Ok(Json(collection.into()))
+}
+
+/// Find a collection by ID or name
+pub(crate) async fn find(state: &AppState, id: &str) -> ServerResult<Collection> {
+    state
+        .memory_manager
+        .get_collection(id)
+        .await?
+        .ok_or_else(|| not_found("Collection", id))
+}
+
+/// Names are unique; `own_id` is the collection being renamed
+async fn check_name_free(state: &AppState, name: &str, own_id: Option<&str>) -> ServerResult<()> {
+    if name.trim().is_empty() {
+        return Err(bad_request("Collection name cannot be empty"));
+    }
+    let taken = state
+        .memory_manager
+        .list_collections()
+        .await?
+        .iter()
+        .any(|other| other.name == name && Some(other.id.as_str()) != own_id);
+    if taken {
+        return Err(ServerError::Conflict(format!(
+            "A collection named '{}' already exists",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Memories can only join a collection if the caller can see them
+async fn check_memories_visible(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    memory_ids: &[String],
+) -> ServerResult<()> {
+    for id in memory_ids {
+        state
+            .memory_manager
+            .get_memory(id)
+            .await?
+            .filter(|memory| state.shares.can_read(memory, auth))
+            .ok_or_else(|| not_found("Memory", id))?;
+    }
+    Ok(())
+}
@@ -4,10 +4,17 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use locai::export::rdf::TripleQueryResult;
+use locai::memory::{
+    EntityProfile, GraphChange, GraphDelta, GraphDiff, ScoredSubgraph, SubgraphFormat,
+};
 use locai::models::Memory;
+use locai::replication::RecordKind;
 use locai::storage::models::{
-    Entity, MemoryGraph, MemoryPath, Relationship, SearchResult, Version,
+    Entity, FieldChange, MemoryGraph, MemoryPath, RecordDiff, RecordOperation, RecordVersion,
+    Relationship, SearchResult, Version,
 };
+use locai::tokens::TokenCounter;
 
 /// Memory DTO for API responses
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -125,6 +132,11 @@ pub struct CreateMemoryRequest {
     /// to work with the SurrealDB M-Tree index.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embedding: Option<Vec<f32>>,
+
+    /// Makes retries safe: a request repeating an earlier one's key returns
+    /// the memory that request created instead of storing another
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 fn default_source() -> String {
@@ -432,6 +444,10 @@ pub struct MemoryPathDto {
 
     /// Path length (number of relationships)
     pub length: usize,
+
+    /// Total weight of the relationships, for weighted searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
 }
 
 impl From<MemoryPath> for MemoryPathDto {
@@ -450,6 +466,446 @@ impl From<MemoryPath> for MemoryPathDto {
             memories,
             relationships,
             length,
+            cost: path.cost,
+        }
+    }
+}
+
+/// Entity profile DTO for API responses
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EntityProfileDto {
+    pub entity: EntityDto,
+
+    /// Number of memories mentioning the entity
+    pub mention_count: usize,
+
+    /// Most important memories mentioning the entity
+    pub top_memories: Vec<MemoryDto>,
+
+    /// Relationships grouped by type
+    pub relationships: Vec<RelationshipSummaryDto>,
+
+    /// Creation time of the oldest mentioning memory
+    pub first_mentioned: Option<DateTime<Utc>>,
+
+    /// Creation time of the newest mentioning memory
+    pub last_mentioned: Option<DateTime<Utc>>,
+
+    pub sentiment: SentimentTrendDto,
+
+    /// Entities mentioned by the same memories, most frequent first
+    pub co_occurring: Vec<CoOccurrenceDto>,
+}
+
+/// Relationships of one type
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RelationshipSummaryDto {
+    #[schema(example = "knows")]
+    pub relationship_type: String,
+    pub count: usize,
+    pub related_ids: Vec<String>,
+}
+
+/// Sentiment of mentions over time, from -1.0 to 1.0
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SentimentTrendDto {
+    pub average: f32,
+
+    /// Increasing, Decreasing or Stable
+    #[schema(example = "Increasing")]
+    pub direction: String,
+
+    /// One score per mentioning memory, oldest first
+    pub points: Vec<SentimentPointDto>,
+}
+
+/// Sentiment of one mention
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SentimentPointDto {
+    pub memory_id: String,
+    pub at: DateTime<Utc>,
+    pub score: f32,
+}
+
+/// Entity appearing in the same memories
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CoOccurrenceDto {
+    pub entity_id: String,
+    pub entity_type: String,
+    pub name: Option<String>,
+    pub count: usize,
+}
+
+impl From<EntityProfile> for EntityProfileDto {
+    fn from(profile: EntityProfile) -> Self {
+        Self {
+            entity: EntityDto::from(profile.entity),
+            mention_count: profile.mention_count,
+            top_memories: profile
+                .top_memories
+                .into_iter()
+                .map(MemoryDto::from)
+                .collect(),
+            relationships: profile
+                .relationships
+                .into_iter()
+                .map(|summary| RelationshipSummaryDto {
+                    relationship_type: summary.relationship_type,
+                    count: summary.count,
+                    related_ids: summary.related_ids,
+                })
+                .collect(),
+            first_mentioned: profile.first_mentioned,
+            last_mentioned: profile.last_mentioned,
+            sentiment: SentimentTrendDto {
+                average: profile.sentiment.average,
+                direction: format!("{:?}", profile.sentiment.direction),
+                points: profile
+                    .sentiment
+                    .points
+                    .into_iter()
+                    .map(|point| SentimentPointDto {
+                        memory_id: point.memory_id,
+                        at: point.at,
+                        score: point.score,
+                    })
+                    .collect(),
+            },
+            co_occurring: profile
+                .co_occurring
+                .into_iter()
+                .map(|other| CoOccurrenceDto {
+                    entity_id: other.entity_id,
+                    entity_type: other.entity_type,
+                    name: other.name,
+                    count: other.count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What changed in the graph between two points in time
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GraphDiffDto {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+
+    /// Memories and entities
+    pub nodes: GraphDeltaDto,
+
+    /// Relationships
+    pub edges: GraphDeltaDto,
+}
+
+/// Records added, removed and changed within a window
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GraphDeltaDto {
+    pub added: Vec<GraphChangeDto>,
+    pub removed: Vec<GraphChangeDto>,
+    pub changed: Vec<GraphChangeDto>,
+}
+
+/// One record in a graph diff
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GraphChangeDto {
+    /// "memory", "entity" or "relationship"
+    #[schema(example = "entity")]
+    pub kind: String,
+
+    pub id: String,
+
+    /// Record at the start of the window, when known
+    pub before: Option<serde_json::Value>,
+
+    /// Record at the end of the window, absent if it was removed
+    pub after: Option<serde_json::Value>,
+}
+
+impl From<GraphDiff> for GraphDiffDto {
+    fn from(diff: GraphDiff) -> Self {
+        Self {
+            from: diff.from,
+            to: diff.to,
+            nodes: GraphDeltaDto::from(diff.nodes),
+            edges: GraphDeltaDto::from(diff.edges),
+        }
+    }
+}
+
+impl From<GraphDelta> for GraphDeltaDto {
+    fn from(delta: GraphDelta) -> Self {
+        let convert = |changes: Vec<GraphChange>| {
+            changes
+                .into_iter()
+                .map(|change| GraphChangeDto {
+                    kind: match change.kind {
+                        RecordKind::Memory => "memory",
+                        RecordKind::Entity => "entity",
+                        RecordKind::Relationship => "relationship",
+                    }
+                    .to_string(),
+                    id: change.id,
+                    before: change.before,
+                    after: change.after,
+                })
+                .collect()
+        };
+        Self {
+            added: convert(delta.added),
+            removed: convert(delta.removed),
+            changed: convert(delta.changed),
+        }
+    }
+}
+
+/// One version of an entity or relationship
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecordVersionDto {
+    pub version_id: String,
+
+    /// ID of the entity or relationship
+    pub record_id: String,
+
+    /// "created", "updated" or "deleted"
+    #[schema(example = "updated")]
+    pub operation: String,
+
+    /// Record after the write, absent for deletes
+    pub data: Option<serde_json::Value>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RecordVersion> for RecordVersionDto {
+    fn from(version: RecordVersion) -> Self {
+        Self {
+            version_id: version.version_id,
+            record_id: version.record_id,
+            operation: match version.operation {
+                RecordOperation::Created => "created",
+                RecordOperation::Updated => "updated",
+                RecordOperation::Deleted => "deleted",
+            }
+            .to_string(),
+            data: version.data,
+            created_at: version.created_at,
+        }
+    }
+}
+
+/// Field changes between two versions of an entity or relationship
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecordDiffDto {
+    pub record_id: String,
+    pub old_version_id: String,
+    pub new_version_id: String,
+    pub changes: Vec<FieldChangeDto>,
+}
+
+/// One changed field in a record diff
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FieldChangeDto {
+    /// Dotted field path
+    #[schema(example = "properties.name")]
+    pub field: String,
+
+    /// Absent if the field was added
+    pub old_value: Option<serde_json::Value>,
+
+    /// Absent if the field was removed
+    pub new_value: Option<serde_json::Value>,
+}
+
+impl From<RecordDiff> for RecordDiffDto {
+    fn from(diff: RecordDiff) -> Self {
+        Self {
+            record_id: diff.record_id,
+            old_version_id: diff.old_version_id,
+            new_version_id: diff.new_version_id,
+            changes: diff.changes.into_iter().map(FieldChangeDto::from).collect(),
+        }
+    }
+}
+
+impl From<FieldChange> for FieldChangeDto {
+    fn from(change: FieldChange) -> Self {
+        Self {
+            field: change.field,
+            old_value: change.old_value,
+            new_value: change.new_value,
+        }
+    }
+}
+
+/// Request for a scored subgraph around seed memories or entities
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubgraphRequest {
+    /// Memory or entity IDs to start from
+    pub seed_ids: Vec<String>,
+
+    /// Hops to walk out from the seeds
+    #[serde(default = "default_subgraph_depth")]
+    pub depth: u8,
+
+    /// Maximum number of nodes to keep
+    #[serde(default = "default_subgraph_max_nodes")]
+    pub max_nodes: usize,
+
+    /// Drop the lowest scoring nodes until the rendered subgraph fits
+    pub max_tokens: Option<usize>,
+
+    /// "json" (default) or "triples"
+    #[schema(example = "triples")]
+    pub format: Option<String>,
+
+    /// Score multiplier per hop from the nearest seed
+    pub distance_decay: Option<f32>,
+
+    /// Weight of memory priority
+    pub importance_weight: Option<f32>,
+
+    /// Weight of connectedness within the subgraph
+    pub degree_weight: Option<f32>,
+}
+
+fn default_subgraph_depth() -> u8 {
+    2
+}
+
+fn default_subgraph_max_nodes() -> usize {
+    50
+}
+
+/// A node of a scored subgraph
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubgraphNodeDto {
+    pub id: String,
+
+    /// "memory" or "entity"
+    pub kind: String,
+
+    /// Entity name or memory excerpt
+    pub label: String,
+
+    /// Entity type or memory type
+    pub node_type: String,
+
+    /// Hops from the nearest seed
+    pub distance: u8,
+
+    pub score: f32,
+}
+
+/// A relationship of a scored subgraph
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubgraphEdgeDto {
+    pub id: String,
+    pub relationship_type: String,
+    pub source_id: String,
+    pub target_id: String,
+}
+
+/// A scored subgraph with its rendering for prompts
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubgraphDto {
+    pub seed_ids: Vec<String>,
+
+    /// Nodes, best scored first
+    pub nodes: Vec<SubgraphNodeDto>,
+
+    pub edges: Vec<SubgraphEdgeDto>,
+
+    /// The subgraph rendered in the requested format
+    pub rendered: String,
+
+    /// Tokens taken by `rendered`, as the server's tokenizer counts them
+    pub estimated_tokens: usize,
+}
+
+impl SubgraphDto {
+    pub fn new(
+        subgraph: ScoredSubgraph,
+        format: SubgraphFormat,
+        counter: &dyn TokenCounter,
+    ) -> Self {
+        let rendered = subgraph.render(format);
+        let estimated_tokens = counter.count(&rendered);
+        Self {
+            seed_ids: subgraph.seed_ids,
+            nodes: subgraph
+                .nodes
+                .into_iter()
+                .map(|node| SubgraphNodeDto {
+                    id: node.id,
+                    kind: match node.kind {
+                        RecordKind::Memory => "memory",
+                        RecordKind::Entity => "entity",
+                        RecordKind::Relationship => "relationship",
+                    }
+                    .to_string(),
+                    label: node.label,
+                    node_type: node.node_type,
+                    distance: node.distance,
+                    score: node.score,
+                })
+                .collect(),
+            edges: subgraph
+                .edges
+                .into_iter()
+                .map(|edge| SubgraphEdgeDto {
+                    id: edge.id,
+                    relationship_type: edge.relationship_type,
+                    source_id: edge.source_id,
+                    target_id: edge.target_id,
+                })
+                .collect(),
+            rendered,
+            estimated_tokens,
+        }
+    }
+}
+
+/// Triple-pattern query over the entity graph
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TripleQueryRequest {
+    /// Patterns such as `?who works_at ?org . ?org name "Acme"`, optionally
+    /// wrapped in `SELECT ?who WHERE { ... } LIMIT n`
+    #[schema(example = "SELECT ?who WHERE { ?who works_at ?org . ?org name \"Acme\" }")]
+    pub query: String,
+
+    /// Also match memory triples
+    #[serde(default)]
+    pub include_memories: bool,
+
+    /// Maximum number of rows when the query has no LIMIT
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+/// Rows matching a triple-pattern query
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TripleQueryResultDto {
+    /// Variables returned, without the leading `?`
+    pub variables: Vec<String>,
+
+    /// Node ID, vocabulary term or literal bound to each variable
+    pub rows: Vec<std::collections::HashMap<String, String>>,
+}
+
+impl From<TripleQueryResult> for TripleQueryResultDto {
+    fn from(result: TripleQueryResult) -> Self {
+        Self {
+            variables: result.variables,
+            rows: result
+                .rows
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|(name, term)| (name, term.value().to_string()))
+                        .collect()
+                })
+                .collect(),
         }
     }
 }
@@ -539,6 +995,34 @@ impl From<SearchResult> for SearchResultDto {
     }
 }
 
+/// Results of one query in a search batch
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchSearchResultDto {
+    /// The query searched for
+    pub q: String,
+
+    /// The query's results, empty if it failed
+    pub results: Vec<SearchResultDto>,
+
+    /// Why the query failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One stage of a streamed search
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchStageDto {
+    /// Mode the stage searched with: "text", "vector" or "hybrid"
+    #[schema(example = "text")]
+    pub stage: String,
+
+    /// Whether this is the last stage, whose results are the search's answer
+    pub r#final: bool,
+
+    /// The stage's results, replacing any earlier stage's
+    pub results: Vec<SearchResultDto>,
+}
+
 /// Graph query request
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GraphQueryRequest {
@@ -773,6 +1257,17 @@ impl From<DecayFunctionDto> for locai::search::DecayFunction {
     }
 }
 
+impl From<locai::search::DecayFunction> for DecayFunctionDto {
+    fn from(decay: locai::search::DecayFunction) -> Self {
+        match decay {
+            locai::search::DecayFunction::None => DecayFunctionDto::None,
+            locai::search::DecayFunction::Linear => DecayFunctionDto::Linear,
+            locai::search::DecayFunction::Exponential => DecayFunctionDto::Exponential,
+            locai::search::DecayFunction::Logarithmic => DecayFunctionDto::Logarithmic,
+        }
+    }
+}
+
 /// Configuration for enhanced search scoring
 ///
 /// Controls how different scoring factors are weighted and combined to produce
@@ -869,6 +1364,20 @@ impl From<ScoringConfigDto> for locai::search::ScoringConfig {
     }
 }
 
+impl From<locai::search::ScoringConfig> for ScoringConfigDto {
+    fn from(config: locai::search::ScoringConfig) -> Self {
+        Self {
+            bm25_weight: config.bm25_weight,
+            vector_weight: config.vector_weight,
+            recency_boost: config.recency_boost,
+            access_boost: config.access_boost,
+            priority_boost: config.priority_boost,
+            decay_function: config.decay_function.into(),
+            decay_rate: config.decay_rate,
+        }
+    }
+}
+
 // Default functions for ScoringConfigDto
 fn default_bm25_weight() -> f32 {
     1.0
@@ -909,6 +1418,9 @@ pub enum WebhookEvent {
     /// Memory deleted event
     #[serde(rename = "memory.deleted")]
     MemoryDeleted,
+    /// A reminder set on a memory came due
+    #[serde(rename = "memory.reminder")]
+    MemoryReminder,
 }
 
 /// Webhook configuration DTO
@@ -0,0 +1,164 @@
+//! OpenAI-compatible embeddings endpoint
+//!
+//! Mounted at `/v1/embeddings` (and `/api/v1/embeddings`) when the embedding
+//! proxy is enabled, so OpenAI SDKs can use the server as their `base_url`.
+
+use std::sync::Arc;
+
+use axum::{Json as JsonExtractor, extract::State, response::Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    error::{ServerError, ServerResult},
+    state::AppState,
+};
+
+/// Embedding input: a single string or a batch of strings
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    Single(String),
+    Batch(Vec<String>),
+}
+
+impl EmbeddingInput {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            EmbeddingInput::Single(text) => vec![text],
+            EmbeddingInput::Batch(texts) => texts,
+        }
+    }
+}
+
+/// OpenAI-compatible embeddings request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingsRequest {
+    /// Text(s) to embed
+    pub input: EmbeddingInput,
+
+    /// Upstream model (defaults to the configured model)
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Requested output dimensions (defaults to the configured value)
+    #[serde(default)]
+    pub dimensions: Option<usize>,
+
+    /// Only "float" is supported
+    #[serde(default)]
+    pub encoding_format: Option<String>,
+
+    /// Accepted for compatibility, ignored
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+/// A single embedding in the response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingData {
+    pub object: String,
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// Token usage reported by the upstream provider
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingUsage {
+    pub prompt_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// OpenAI-compatible embeddings response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingsResponse {
+    pub object: String,
+    pub data: Vec<EmbeddingData>,
+    pub model: String,
+    pub usage: EmbeddingUsage,
+}
+
+/// Create embeddings through the caching proxy
+#[utoipa::path(
+    post,
+    path = "/v1/embeddings",
+    tag = "embeddings",
+    request_body = EmbeddingsRequest,
+    responses(
+        (status = 200, description = "Embeddings created", body = EmbeddingsResponse),
+        (status = 400, description = "Invalid request or embedding proxy disabled"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Upstream provider error")
+    )
+)]
+pub async fn create_embeddings(
+    State(state): State<Arc<AppState>>,
+    JsonExtractor(request): JsonExtractor<EmbeddingsRequest>,
+) -> ServerResult<Json<EmbeddingsResponse>> {
+    let proxy = state
+        .embedding_proxy
+        .as_ref()
+        .ok_or_else(|| ServerError::BadRequest("Embedding proxy is not enabled".to_string()))?;
+
+    if let Some(format) = &request.encoding_format
+        && format != "float"
+    {
+        return Err(ServerError::BadRequest(format!(
+            "Unsupported encoding_format '{}'. Only 'float' is supported.",
+            format
+        )));
+    }
+
+    let inputs = request.input.into_vec();
+    if inputs.is_empty() {
+        return Err(ServerError::BadRequest(
+            "'input' must not be empty".to_string(),
+        ));
+    }
+
+    let output = proxy
+        .embed(&inputs, request.model.as_deref(), request.dimensions)
+        .await?;
+    tracing::debug!(
+        "Served {} of {} embeddings from cache",
+        output.cache_hits,
+        inputs.len()
+    );
+
+    let data = output
+        .embeddings
+        .into_iter()
+        .enumerate()
+        .map(|(index, embedding)| EmbeddingData {
+            object: "embedding".to_string(),
+            index,
+            embedding,
+        })
+        .collect();
+
+    Ok(Json(EmbeddingsResponse {
+        object: "list".to_string(),
+        data,
+        model: output.model,
+        usage: EmbeddingUsage {
+            prompt_tokens: output.usage.prompt_tokens,
+            total_tokens: output.usage.total_tokens,
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_input_accepts_string_or_array() {
+        let single: EmbeddingsRequest = serde_json::from_str(r#"{"input": "hello"}"#).unwrap();
+        assert_eq!(single.input.into_vec(), vec!["hello"]);
+
+        let batch: EmbeddingsRequest =
+            serde_json::from_str(r#"{"input": ["a", "b"], "model": "m"}"#).unwrap();
+        assert_eq!(batch.model.as_deref(), Some("m"));
+        assert_eq!(batch.input.into_vec(), vec!["a", "b"]);
+    }
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use axum::{
-    Json as JsonExtractor,
+    Extension, Json as JsonExtractor,
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
@@ -11,15 +11,29 @@ use axum::{
 use serde::Deserialize;
 use utoipa::IntoParams;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use locai::memory::{
+    collections::COLLECTION_ENTITY_TYPE, idempotency::IDEMPOTENCY_ENTITY_TYPE,
+    pins::PIN_ENTITY_TYPE, reminders::REMINDER_ENTITY_TYPE, templates::TEMPLATE_ENTITY_TYPE,
+};
+use locai::messaging::presence::AGENT_ENTITY_TYPE;
+use locai::replication::RecordKind;
 use locai::storage::{
     filters::{EntityFilter, RelationshipFilter},
-    models::Entity,
+    models::{Entity, RecordVersion},
 };
 
 use crate::{
-    api::dto::{CreateEntityRequest, EntityDto, MemoryDto, RelationshipDto, UpdateEntityRequest},
-    error::{ServerResult, not_found},
+    api::auth::AuthContext,
+    api::auth_service::USER_ENTITY_TYPE,
+    api::dto::{
+        CreateEntityRequest, EntityDto, EntityProfileDto, MemoryDto, RecordDiffDto,
+        RecordVersionDto, RelationshipDto, UpdateEntityRequest,
+    },
+    api::relationships::readable_relationships,
+    error::{ServerResult, bad_request, not_found},
+    jobs::JOB_ENTITY_TYPE,
+    sharing::{Access, SHARE_ENTITY_TYPE},
     state::AppState,
     websocket::WebSocketMessage,
 };
@@ -49,6 +63,106 @@ fn default_page_size() -> usize {
     20
 }
 
+/// Entity types the server and library keep their own records under, such
+/// as users and share grants; the entity API neither shows nor accepts them
+const RESERVED_ENTITY_TYPES: &[&str] = &[
+    USER_ENTITY_TYPE,
+    SHARE_ENTITY_TYPE,
+    JOB_ENTITY_TYPE,
+    IDEMPOTENCY_ENTITY_TYPE,
+    AGENT_ENTITY_TYPE,
+    PIN_ENTITY_TYPE,
+    TEMPLATE_ENTITY_TYPE,
+    REMINDER_ENTITY_TYPE,
+    COLLECTION_ENTITY_TYPE,
+];
+
+/// Entities are read from storage in batches of this many while skipping
+/// reserved ones
+const LIST_BATCH_SIZE: usize = 100;
+
+/// Whether entities of `entity_type` are kept out of the entity API
+pub(super) fn is_reserved_entity_type(entity_type: &str) -> bool {
+    RESERVED_ENTITY_TYPES.contains(&entity_type)
+}
+
+fn check_entity_type(entity_type: &str) -> ServerResult<()> {
+    if is_reserved_entity_type(entity_type) {
+        return Err(bad_request(&format!(
+            "Entity type '{}' is reserved",
+            entity_type
+        )));
+    }
+    Ok(())
+}
+
+/// The entity with `id`, unless it's missing or of a reserved type
+pub(super) async fn visible_entity(state: &AppState, id: &str) -> ServerResult<Option<Entity>> {
+    Ok(state
+        .memory_manager
+        .get_entity(id)
+        .await?
+        .filter(|entity| !is_reserved_entity_type(&entity.entity_type)))
+}
+
+/// Load an entity, reporting entities of a reserved type as not found
+pub(super) async fn load_entity(state: &AppState, id: &str) -> ServerResult<Entity> {
+    visible_entity(state, id)
+        .await?
+        .ok_or_else(|| not_found("Entity", id))
+}
+
+/// A page of the entities matching `filter`, skipping reserved ones
+///
+/// Without a type filter, reserved entities are interleaved with the rest,
+/// so storage is read in batches until the page is full.
+async fn list_visible_entities(
+    state: &AppState,
+    filter: EntityFilter,
+    size: usize,
+    offset: usize,
+) -> ServerResult<Vec<Entity>> {
+    match filter.entity_type.as_deref() {
+        Some(entity_type) if is_reserved_entity_type(entity_type) => return Ok(Vec::new()),
+        Some(_) => {
+            return Ok(state
+                .memory_manager
+                .list_entities(Some(filter), Some(size), Some(offset))
+                .await?);
+        }
+        None => {}
+    }
+
+    let mut page = Vec::new();
+    let mut skipped = 0;
+    let mut start = 0;
+    while page.len() < size {
+        let batch = state
+            .memory_manager
+            .list_entities(Some(filter.clone()), Some(LIST_BATCH_SIZE), Some(start))
+            .await?;
+        let fetched = batch.len();
+        for entity in batch {
+            if is_reserved_entity_type(&entity.entity_type) {
+                continue;
+            }
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+            page.push(entity);
+            if page.len() == size {
+                break;
+            }
+        }
+        if fetched < LIST_BATCH_SIZE {
+            break;
+        }
+        start += fetched;
+    }
+    Ok(page)
+}
+
 /// List entities
 #[utoipa::path(
     get,
@@ -83,11 +197,8 @@ pub async fn list_entities(
     // Calculate offset for pagination
     let offset = params.page * params.size;
 
-    // Get entities
-    let entities = state
-        .memory_manager
-        .list_entities(Some(filter), Some(params.size), Some(offset))
-        .await?;
+    // Get entities, leaving out the server's own records
+    let entities = list_visible_entities(&state, filter, params.size, offset).await?;
 
     let entity_dtos: Vec<EntityDto> = entities.into_iter().map(EntityDto::from).collect();
     Ok(Json(entity_dtos))
@@ -112,11 +223,7 @@ pub async fn get_entity(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> ServerResult<Json<EntityDto>> {
-    let entity = state
-        .memory_manager
-        .get_entity(&id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &id))?;
+    let entity = load_entity(&state, &id).await?;
 
     let entity_dto = EntityDto::from(entity);
     Ok(Json(entity_dto))
@@ -139,6 +246,7 @@ pub async fn create_entity(
     State(state): State<Arc<AppState>>,
     JsonExtractor(request): JsonExtractor<CreateEntityRequest>,
 ) -> ServerResult<(StatusCode, Json<EntityDto>)> {
+    check_entity_type(&request.entity_type)?;
     let now = Utc::now();
 
     // Create the entity
@@ -189,14 +297,11 @@ pub async fn update_entity(
     JsonExtractor(request): JsonExtractor<UpdateEntityRequest>,
 ) -> ServerResult<Json<EntityDto>> {
     // Get the existing entity
-    let mut entity = state
-        .memory_manager
-        .get_entity(&id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &id))?;
+    let mut entity = load_entity(&state, &id).await?;
 
     // Update fields if provided
     if let Some(entity_type) = request.entity_type {
+        check_entity_type(&entity_type)?;
         entity.entity_type = entity_type;
     }
 
@@ -242,11 +347,7 @@ pub async fn delete_entity(
     Path(id): Path<String>,
 ) -> ServerResult<StatusCode> {
     // Check if entity exists first
-    let _entity = state
-        .memory_manager
-        .get_entity(&id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &id))?;
+    let _entity = load_entity(&state, &id).await?;
 
     // Delete the entity
     let deleted = state.memory_manager.delete_entity(&id).await?;
@@ -282,14 +383,11 @@ pub async fn delete_entity(
 )]
 pub async fn get_entity_memories(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
 ) -> ServerResult<Json<Vec<MemoryDto>>> {
     // Check if entity exists
-    let _entity = state
-        .memory_manager
-        .get_entity(&id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &id))?;
+    let _entity = load_entity(&state, &id).await?;
 
     // Find relationships where this entity is the target (memory contains entity)
     let filter = RelationshipFilter {
@@ -303,13 +401,15 @@ pub async fn get_entity_memories(
         .list_relationships(Some(filter), None, None)
         .await?;
 
-    // Get memories for each source (the memory that contains this entity)
+    // Get memories for each source (the memory that contains this entity),
+    // dropping those the caller can't see
     let mut memories = Vec::new();
     for relationship in relationships {
         if let Ok(Some(memory)) = state
             .memory_manager
             .get_memory(&relationship.source_id)
             .await
+            && state.shares.can_read(&memory, auth.as_deref())
         {
             memories.push(MemoryDto::from(memory));
         }
@@ -318,6 +418,197 @@ pub async fn get_entity_memories(
     Ok(Json(memories))
 }
 
+/// Get everything known about an entity
+///
+/// Aggregates the memories mentioning the entity, its relationships, first and
+/// last mention, the sentiment trend of those mentions and co-occurring entities.
+/// Only top memories the caller can see are listed.
+#[utoipa::path(
+    get,
+    path = "/api/entities/{id}/profile",
+    tag = "entities",
+    params(
+        ("id" = String, Path, description = "Entity ID")
+    ),
+    responses(
+        (status = 200, description = "Entity profile", body = EntityProfileDto),
+        (status = 404, description = "Entity not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_entity_profile(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<EntityProfileDto>> {
+    let mut profile = state
+        .memory_manager
+        .entity_profile(&id)
+        .await?
+        .filter(|profile| !is_reserved_entity_type(&profile.entity.entity_type))
+        .ok_or_else(|| not_found("Entity", &id))?;
+    profile
+        .top_memories
+        .retain(|memory| state.shares.can_read(memory, auth.as_deref()));
+
+    Ok(Json(EntityProfileDto::from(profile)))
+}
+
+/// Query parameters for reading a record as it was at a point in time
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AtTimeParams {
+    /// RFC 3339 timestamp
+    #[param(example = "2026-01-01T00:00:00Z")]
+    pub at: String,
+}
+
+impl AtTimeParams {
+    pub(super) fn time(&self) -> ServerResult<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.at)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|_| bad_request(&format!("Invalid time '{}'", self.at)))
+    }
+}
+
+/// Query parameters selecting two versions to diff
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RecordDiffParams {
+    /// Older version ID
+    pub old: String,
+
+    /// Newer version ID
+    pub new: String,
+}
+
+/// Diff two versions, both of which must belong to record `id`
+pub(super) async fn diff_record(
+    state: &AppState,
+    id: &str,
+    params: &RecordDiffParams,
+) -> ServerResult<RecordDiffDto> {
+    for version_id in [&params.old, &params.new] {
+        let version = state.memory_manager.get_record_version(version_id).await?;
+        if version.is_none_or(|version| version.record_id != id) {
+            return Err(not_found("Version", version_id));
+        }
+    }
+
+    let diff = state
+        .memory_manager
+        .diff_record_versions(&params.old, &params.new)
+        .await?;
+    Ok(RecordDiffDto::from(diff))
+}
+
+/// List the versions of an entity, oldest first
+///
+/// Every create, update and delete of the entity is recorded; deleted
+/// versions carry no data.
+#[utoipa::path(
+    get,
+    path = "/api/entities/{id}/versions",
+    tag = "entities",
+    params(
+        ("id" = String, Path, description = "Entity ID")
+    ),
+    responses(
+        (status = 200, description = "Entity versions", body = Vec<RecordVersionDto>),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_entity_versions(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<Vec<RecordVersionDto>>> {
+    let versions = state
+        .memory_manager
+        .list_record_versions(RecordKind::Entity, &id)
+        .await?;
+    if versions.iter().any(is_reserved_version) {
+        return Ok(Json(Vec::new()));
+    }
+
+    Ok(Json(
+        versions.into_iter().map(RecordVersionDto::from).collect(),
+    ))
+}
+
+/// Get an entity as it existed at a point in time
+#[utoipa::path(
+    get,
+    path = "/api/entities/{id}/at",
+    tag = "entities",
+    params(
+        ("id" = String, Path, description = "Entity ID"),
+        AtTimeParams
+    ),
+    responses(
+        (status = 200, description = "Entity at that time", body = EntityDto),
+        (status = 400, description = "Invalid time"),
+        (status = 404, description = "Entity did not exist at that time"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_entity_at_time(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<AtTimeParams>,
+) -> ServerResult<Json<EntityDto>> {
+    let entity = state
+        .memory_manager
+        .get_entity_at_time(&id, params.time()?)
+        .await?
+        .filter(|entity| !is_reserved_entity_type(&entity.entity_type))
+        .ok_or_else(|| not_found("Entity", &id))?;
+
+    Ok(Json(EntityDto::from(entity)))
+}
+
+/// Diff two versions of an entity
+#[utoipa::path(
+    get,
+    path = "/api/entities/{id}/versions/diff",
+    tag = "entities",
+    params(
+        ("id" = String, Path, description = "Entity ID"),
+        RecordDiffParams
+    ),
+    responses(
+        (status = 200, description = "Changed fields", body = RecordDiffDto),
+        (status = 404, description = "Version not found for this entity"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn diff_entity_versions(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<RecordDiffParams>,
+) -> ServerResult<Json<RecordDiffDto>> {
+    let versions = state
+        .memory_manager
+        .list_record_versions(RecordKind::Entity, &id)
+        .await?;
+    if versions.iter().any(is_reserved_version) {
+        return Err(not_found("Version", &params.old));
+    }
+
+    Ok(Json(diff_record(&state, &id, &params).await?))
+}
+
+/// Whether a version holds an entity of a reserved type
+fn is_reserved_version(version: &RecordVersion) -> bool {
+    version
+        .data
+        .as_ref()
+        .and_then(|data| data.get("entity_type"))
+        .and_then(|entity_type| entity_type.as_str())
+        .is_some_and(is_reserved_entity_type)
+}
+
 /// Create a relationship between entities
 #[utoipa::path(
     post,
@@ -337,6 +628,7 @@ pub async fn get_entity_memories(
 )]
 pub async fn create_entity_relationship(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     JsonExtractor(request): JsonExtractor<CreateEntityRelationshipRequest>,
 ) -> ServerResult<(StatusCode, Json<RelationshipDto>)> {
@@ -346,25 +638,22 @@ pub async fn create_entity_relationship(
     let source_id = id;
 
     // Validate that source entity exists
-    let _source_entity = state
-        .memory_manager
-        .get_entity(&source_id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &source_id))?;
+    let _source_entity = load_entity(&state, &source_id).await?;
 
     // Validate that target exists (can be entity OR memory)
-    let target_is_entity = state
-        .memory_manager
-        .get_entity(&request.target_id)
-        .await?
-        .is_some();
+    let target_is_entity = visible_entity(&state, &request.target_id).await?.is_some();
 
+    // Linking a memory changes it, so takes write access to it
     let target_is_memory = if !target_is_entity {
-        state
-            .memory_manager
-            .get_memory(&request.target_id)
-            .await?
-            .is_some()
+        match state.memory_manager.get_memory(&request.target_id).await? {
+            Some(memory) => {
+                state
+                    .shares
+                    .require(&memory, auth.as_deref(), Access::Write)?;
+                true
+            }
+            None => false,
+        }
     } else {
         false
     };
@@ -425,15 +714,12 @@ pub async fn create_entity_relationship(
 )]
 pub async fn get_entity_relationships(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     Query(params): Query<GetEntityRelationshipsParams>,
 ) -> ServerResult<Json<Vec<RelationshipDto>>> {
     // Validate that the entity exists
-    let _entity = state
-        .memory_manager
-        .get_entity(&id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &id))?;
+    let _entity = load_entity(&state, &id).await?;
 
     // Build filter based on direction
     let direction = params.direction.as_str();
@@ -469,7 +755,9 @@ pub async fn get_entity_relationships(
         all_relationships.extend(incoming);
     }
 
-    // Convert to DTOs
+    // Convert to DTOs, dropping relationships to memories the caller can't see
+    let all_relationships =
+        readable_relationships(&state, all_relationships, auth.as_deref()).await?;
     let relationship_dtos: Vec<RelationshipDto> = all_relationships
         .into_iter()
         .map(RelationshipDto::from)
@@ -1,21 +1,35 @@
 //! Graph operations API endpoints
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{
-    Json as JsonExtractor,
+    Extension, Json as JsonExtractor,
     extract::{Path, Query, State},
-    response::Json,
+    http::header,
+    response::{IntoResponse, Json, Response},
 };
+use chrono::{DateTime, Utc};
+use locai::export::rdf::{
+    DEFAULT_BASE_IRI, RdfExportOptions, RdfFormat, Term, TripleGraph, TripleQuery, export_triples,
+};
+use locai::memory::{DiagramFilter, DiagramFormat, SubgraphFormat, SubgraphScoring};
+use locai::replication::RecordKind;
+use locai::storage::models::{MemoryGraph, PathConstraints};
 use serde::Deserialize;
 use utoipa::IntoParams;
 
 use crate::{
+    api::auth::AuthContext,
     api::dto::{
-        CentralMemoryDto, EntityDto, GraphMetricsDto, GraphQueryRequest, MemoryGraphDto,
-        MemoryPathDto,
+        CentralMemoryDto, EntityDto, GraphDiffDto, GraphMetricsDto, GraphQueryRequest,
+        MemoryGraphDto, MemoryPathDto, SubgraphDto, SubgraphRequest, TripleQueryRequest,
+        TripleQueryResultDto,
     },
-    error::{ServerError, ServerResult, not_found},
+    api::entities::{is_reserved_entity_type, load_entity},
+    api::relationships::readable_relationships,
+    error::{ServerError, ServerResult, bad_request, not_found},
+    sharing::Access,
     state::AppState,
 };
 
@@ -36,20 +50,21 @@ use crate::{
 )]
 pub async fn get_memory_graph(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     Query(params): Query<GraphParams>,
 ) -> ServerResult<Json<MemoryGraphDto>> {
     let depth = params.depth.unwrap_or(2);
     let include_temporal_span = params.include_temporal_span.unwrap_or(false);
 
-    // First check if the memory exists
-    let _memory = state
-        .memory_manager
-        .get_memory(&id)
-        .await?
-        .ok_or_else(|| not_found("Memory", &id))?;
+    // First check if the memory exists and the caller can see it
+    state
+        .shares
+        .load(&state.memory_manager, &id, auth.as_deref(), Access::Read)
+        .await?;
 
-    let graph = state.memory_manager.get_memory_graph(&id, depth).await?;
+    let mut graph = state.memory_manager.get_memory_graph(&id, depth).await?;
+    prune_graph(&state, &mut graph, auth.as_deref());
     let mut graph_dto = MemoryGraphDto::from(graph.clone());
 
     // Calculate temporal span if requested
@@ -92,6 +107,7 @@ pub async fn get_memory_graph(
 )]
 pub async fn get_entity_graph(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     Query(params): Query<GraphParams>,
 ) -> ServerResult<Json<MemoryGraphDto>> {
@@ -99,14 +115,9 @@ pub async fn get_entity_graph(
     let include_temporal_span = params.include_temporal_span.unwrap_or(false);
 
     // Check if entity exists
-    let _entity = state
-        .memory_manager
-        .get_entity(&id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &id))?;
+    let _entity = load_entity(&state, &id).await?;
 
     // Create a graph centered on this entity
-    use locai::storage::models::MemoryGraph;
     use std::collections::HashMap;
 
     let mut graph = MemoryGraph {
@@ -116,9 +127,13 @@ pub async fn get_entity_graph(
     };
 
     // If the entity is actually a memory, get its memory graph
-    if let Ok(Some(_memory)) = state.memory_manager.get_memory(&id).await {
+    if let Ok(Some(memory)) = state.memory_manager.get_memory(&id).await {
         // This entity is also a memory, so we can get its full graph
-        let memory_graph = state.memory_manager.get_memory_graph(&id, depth).await?;
+        state
+            .shares
+            .require(&memory, auth.as_deref(), Access::Read)?;
+        let mut memory_graph = state.memory_manager.get_memory_graph(&id, depth).await?;
+        prune_graph(&state, &mut memory_graph, auth.as_deref());
         let mut graph_dto = MemoryGraphDto::from(memory_graph.clone());
 
         // Calculate temporal span if requested
@@ -180,13 +195,18 @@ pub async fn get_entity_graph(
         )
         .await?;
 
-    // Combine relationships
+    // Combine relationships, dropping those to memories the caller can't see
     let mut all_relationships = relationships;
     all_relationships.append(&mut target_relationships);
+    let all_relationships =
+        readable_relationships(&state, all_relationships, auth.as_deref()).await?;
 
-    // For each related entity, if it's a memory, add it to the graph
+    // For each related entity, if it's a memory the caller can see, add it to
+    // the graph
     for related_entity in related_entities {
-        if let Ok(Some(memory)) = state.memory_manager.get_memory(&related_entity.id).await {
+        if let Ok(Some(memory)) = state.memory_manager.get_memory(&related_entity.id).await
+            && state.shares.can_read(&memory, auth.as_deref())
+        {
             graph.memories.insert(related_entity.id.clone(), memory);
         }
     }
@@ -222,6 +242,10 @@ pub async fn get_entity_graph(
 }
 
 /// Find paths between memories
+///
+/// Paths are found breadth-first unless a weight or a constraint is given,
+/// in which case the cheapest paths come first, each with its cost. Paths
+/// through memories the caller can't see are left out.
 #[utoipa::path(
     get,
     path = "/api/graph/paths",
@@ -234,23 +258,347 @@ pub async fn get_entity_graph(
 )]
 pub async fn find_paths(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Query(params): Query<PathParams>,
 ) -> ServerResult<Json<Vec<MemoryPathDto>>> {
     let from_id = params
         .from
+        .clone()
         .ok_or_else(|| ServerError::BadRequest("Missing 'from' parameter".to_string()))?;
     let to_id = params
         .to
+        .clone()
         .ok_or_else(|| ServerError::BadRequest("Missing 'to' parameter".to_string()))?;
     let max_depth = params.max_depth.unwrap_or(5);
 
-    let paths = state
+    let paths = match params.constraints(max_depth)? {
+        Some(constraints) => {
+            state
+                .memory_manager
+                .find_weighted_paths(&from_id, &to_id, &constraints)
+                .await?
+        }
+        None => {
+            state
+                .memory_manager
+                .find_paths(&from_id, &to_id, max_depth)
+                .await?
+        }
+    };
+    let path_dtos: Vec<MemoryPathDto> = paths
+        .into_iter()
+        .filter(|path| {
+            path.memories
+                .iter()
+                .all(|memory| state.shares.can_read(memory, auth.as_deref()))
+        })
+        .map(MemoryPathDto::from)
+        .collect();
+
+    Ok(Json(path_dtos))
+}
+
+/// Extract a scored subgraph for prompt context
+#[utoipa::path(
+    post,
+    path = "/api/graph/subgraph",
+    tag = "graph",
+    request_body = SubgraphRequest,
+    responses(
+        (status = 200, description = "Scored subgraph around the seeds", body = SubgraphDto),
+        (status = 400, description = "No seeds or unknown format"),
+    )
+)]
+pub async fn extract_subgraph(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    JsonExtractor(request): JsonExtractor<SubgraphRequest>,
+) -> ServerResult<Json<SubgraphDto>> {
+    if request.seed_ids.is_empty() {
+        return Err(bad_request("At least one seed ID is required"));
+    }
+    let hidden_seeds = state
+        .shares
+        .hidden(
+            &state.memory_manager,
+            request.seed_ids.iter().map(String::as_str),
+            auth.as_deref(),
+        )
+        .await?;
+    if let Some(seed_id) = hidden_seeds.iter().next() {
+        return Err(not_found("Memory", seed_id));
+    }
+    let format = match request.format.as_deref() {
+        None | Some("json") => SubgraphFormat::Json,
+        Some("triples") => SubgraphFormat::Triples,
+        Some(other) => {
+            return Err(bad_request(&format!(
+                "Unknown format '{}', expected json or triples",
+                other
+            )));
+        }
+    };
+
+    let defaults = SubgraphScoring::default();
+    let scoring = SubgraphScoring {
+        distance_decay: request.distance_decay.unwrap_or(defaults.distance_decay),
+        importance_weight: request
+            .importance_weight
+            .unwrap_or(defaults.importance_weight),
+        degree_weight: request.degree_weight.unwrap_or(defaults.degree_weight),
+    };
+
+    let mut subgraph = state
         .memory_manager
-        .find_paths(&from_id, &to_id, max_depth)
+        .extract_subgraph(
+            &request.seed_ids,
+            request.depth,
+            request.max_nodes,
+            &scoring,
+        )
         .await?;
-    let path_dtos: Vec<MemoryPathDto> = paths.into_iter().map(MemoryPathDto::from).collect();
+    let hidden = state
+        .shares
+        .hidden(
+            &state.memory_manager,
+            subgraph
+                .nodes
+                .iter()
+                .filter(|node| node.kind == RecordKind::Memory)
+                .map(|node| node.id.as_str()),
+            auth.as_deref(),
+        )
+        .await?;
+    subgraph.nodes.retain(|node| !hidden.contains(&node.id));
+    subgraph
+        .edges
+        .retain(|edge| !hidden.contains(&edge.source_id) && !hidden.contains(&edge.target_id));
+    let counter = state.memory_manager.token_counter();
+    if let Some(max_tokens) = request.max_tokens {
+        subgraph = subgraph.fit_to_budget_with(max_tokens, format, counter.as_ref());
+    }
 
-    Ok(Json(path_dtos))
+    Ok(Json(SubgraphDto::new(subgraph, format, counter.as_ref())))
+}
+
+/// Export the entity graph as RDF
+#[utoipa::path(
+    get,
+    path = "/api/graph/export",
+    tag = "graph",
+    params(RdfExportParams),
+    responses(
+        (status = 200, description = "The graph as N-Triples or Turtle", body = String, content_type = "application/n-triples"),
+        (status = 400, description = "Unknown format"),
+    )
+)]
+pub async fn export_rdf(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<RdfExportParams>,
+) -> ServerResult<Response> {
+    let format = match params.format.as_deref() {
+        None | Some("ntriples") => RdfFormat::NTriples,
+        Some("turtle") => RdfFormat::Turtle,
+        Some(other) => {
+            return Err(bad_request(&format!(
+                "Unknown format '{}', expected ntriples or turtle",
+                other
+            )));
+        }
+    };
+    let options = RdfExportOptions {
+        include_memories: params.include_memories.unwrap_or(false),
+        ..RdfExportOptions::default()
+    };
+
+    let mut graph = export_triples(&state.memory_manager, &options).await?;
+    prune_triples(&state, &mut graph, auth.as_deref()).await?;
+    let base_iri = params.base_iri.as_deref().unwrap_or(DEFAULT_BASE_IRI);
+    Ok((
+        [(header::CONTENT_TYPE, format.content_type())],
+        graph.render(format, base_iri),
+    )
+        .into_response())
+}
+
+/// Draw part of the graph as Mermaid or PlantUML source
+#[utoipa::path(
+    get,
+    path = "/api/graph/diagram",
+    tag = "graph",
+    params(DiagramParams),
+    responses(
+        (status = 200, description = "Diagram source", body = String, content_type = "text/plain"),
+        (status = 400, description = "Unknown format"),
+    )
+)]
+pub async fn get_graph_diagram(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<DiagramParams>,
+) -> ServerResult<Response> {
+    let format = match params.format.as_deref() {
+        None | Some("mermaid") => DiagramFormat::Mermaid,
+        Some("plantuml") => DiagramFormat::PlantUml,
+        Some(other) => {
+            return Err(bad_request(&format!(
+                "Unknown format '{}', expected mermaid or plantuml",
+                other
+            )));
+        }
+    };
+    let split = |list: Option<String>| -> Vec<String> {
+        list.map(|list| {
+            list.split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+    };
+
+    let defaults = DiagramFilter::default();
+    let filter = DiagramFilter {
+        root_id: params.root,
+        depth: params.depth.unwrap_or(defaults.depth),
+        relationship_types: split(params.types),
+        entity_types: split(params.entity_types),
+        include_memories: params.include_memories.unwrap_or(defaults.include_memories),
+        max_nodes: params.max_nodes.unwrap_or(defaults.max_nodes).min(500),
+    };
+
+    let mut diagram = state.memory_manager.graph_diagram(&filter).await?;
+    let hidden = state
+        .shares
+        .hidden(
+            &state.memory_manager,
+            diagram
+                .nodes
+                .iter()
+                .filter(|node| node.kind == RecordKind::Memory)
+                .map(|node| node.id.as_str()),
+            auth.as_deref(),
+        )
+        .await?;
+    diagram.nodes.retain(|node| !hidden.contains(&node.id));
+    diagram
+        .edges
+        .retain(|edge| !hidden.contains(&edge.source_id) && !hidden.contains(&edge.target_id));
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        diagram.render(format),
+    )
+        .into_response())
+}
+
+/// Query the entity graph with triple patterns
+#[utoipa::path(
+    post,
+    path = "/api/graph/triples",
+    tag = "graph",
+    request_body = TripleQueryRequest,
+    responses(
+        (status = 200, description = "Variable bindings for each match", body = TripleQueryResultDto),
+        (status = 400, description = "Invalid query"),
+    )
+)]
+pub async fn query_triples(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    JsonExtractor(request): JsonExtractor<TripleQueryRequest>,
+) -> ServerResult<Json<TripleQueryResultDto>> {
+    let mut query = TripleQuery::parse(&request.query).map_err(|e| bad_request(&e.to_string()))?;
+    query.limit = Some(query.limit.unwrap_or(request.limit).min(1000));
+    let options = RdfExportOptions {
+        include_memories: request.include_memories,
+        ..RdfExportOptions::default()
+    };
+
+    let mut graph = export_triples(&state.memory_manager, &options).await?;
+    prune_triples(&state, &mut graph, auth.as_deref()).await?;
+    Ok(Json(TripleQueryResultDto::from(graph.query(&query))))
+}
+
+/// Diff the graph between two points in time
+#[utoipa::path(
+    get,
+    path = "/api/graph/diff",
+    tag = "graph",
+    params(GraphDiffParams),
+    responses(
+        (status = 200, description = "Nodes and edges added, removed and changed", body = GraphDiffDto),
+        (status = 400, description = "Missing or invalid time"),
+    )
+)]
+pub async fn get_graph_diff(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<GraphDiffParams>,
+) -> ServerResult<Json<GraphDiffDto>> {
+    let parse = |value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|_| bad_request(&format!("Invalid time '{}'", value)))
+    };
+    let from = parse(
+        params
+            .from
+            .as_deref()
+            .ok_or_else(|| ServerError::BadRequest("Missing 'from' parameter".to_string()))?,
+    )?;
+    let to = match params.to.as_deref() {
+        Some(to) => parse(to)?,
+        None => Utc::now(),
+    };
+    if from > to {
+        return Err(bad_request("'from' must not be after 'to'"));
+    }
+
+    let mut diff = state.memory_manager.graph_diff(from, to).await?;
+
+    // Drop memories the caller can't see, as last recorded, and their edges
+    let mut hidden = HashSet::new();
+    for delta in [
+        &mut diff.nodes.added,
+        &mut diff.nodes.removed,
+        &mut diff.nodes.changed,
+    ] {
+        delta.retain(|change| {
+            let record = change.after.as_ref().or(change.before.as_ref());
+            let readable = change.kind != RecordKind::Memory
+                || record.is_none_or(|record| {
+                    let properties = &record["properties"];
+                    state
+                        .shares
+                        .access_to(&change.id, properties, auth.as_deref())
+                        >= Access::Read
+                });
+            if !readable {
+                hidden.insert(change.id.clone());
+            }
+            readable
+        });
+    }
+    for delta in [
+        &mut diff.edges.added,
+        &mut diff.edges.removed,
+        &mut diff.edges.changed,
+    ] {
+        delta.retain(|change| {
+            let record = change.after.as_ref().or(change.before.as_ref());
+            record.is_none_or(|record| {
+                ["source_id", "target_id"].iter().all(|end| {
+                    record
+                        .get(end)
+                        .and_then(|id| id.as_str())
+                        .is_none_or(|id| !hidden.contains(id))
+                })
+            })
+        });
+    }
+
+    Ok(Json(GraphDiffDto::from(diff)))
 }
 
 /// Execute graph query
@@ -265,8 +613,10 @@ pub async fn find_paths(
 )]
 pub async fn query_graph(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     JsonExtractor(request): JsonExtractor<GraphQueryRequest>,
 ) -> ServerResult<Json<Vec<MemoryGraphDto>>> {
+    let user = auth.as_deref();
     // For now, implement a simple pattern matching system
     // In a full implementation, this would parse a graph query language
 
@@ -289,8 +639,13 @@ pub async fn query_graph(
             .await?;
 
         // For each memory, get its graph and check connectivity
-        for memory in all_memories.into_iter().take(limit) {
-            if let Ok(graph) = state.memory_manager.get_memory_graph(&memory.id, 1).await {
+        for memory in all_memories
+            .into_iter()
+            .filter(|memory| state.shares.can_read(memory, user))
+            .take(limit)
+        {
+            if let Ok(mut graph) = state.memory_manager.get_memory_graph(&memory.id, 1).await {
+                prune_graph(&state, &mut graph, user);
                 // If the memory has relationships, include it
                 if !graph.relationships.is_empty() {
                     results.push(MemoryGraphDto::from(graph));
@@ -309,8 +664,13 @@ pub async fn query_graph(
             )
             .await?;
 
-        for memory in all_memories.into_iter().take(limit) {
-            if let Ok(graph) = state.memory_manager.get_memory_graph(&memory.id, 1).await {
+        for memory in all_memories
+            .into_iter()
+            .filter(|memory| state.shares.can_read(memory, user))
+            .take(limit)
+        {
+            if let Ok(mut graph) = state.memory_manager.get_memory_graph(&memory.id, 1).await {
+                prune_graph(&state, &mut graph, user);
                 // If the memory has no relationships, include it
                 if graph.relationships.is_empty() && graph.memories.len() == 1 {
                     results.push(MemoryGraphDto::from(graph));
@@ -330,11 +690,15 @@ pub async fn query_graph(
             .await?;
 
         for search_result in search_results {
-            if let Ok(graph) = state
+            if !state.shares.can_read(&search_result.memory, user) {
+                continue;
+            }
+            if let Ok(mut graph) = state
                 .memory_manager
                 .get_memory_graph(&search_result.memory.id, 1)
                 .await
             {
+                prune_graph(&state, &mut graph, user);
                 results.push(MemoryGraphDto::from(graph));
             }
         }
@@ -354,6 +718,7 @@ pub async fn query_graph(
 )]
 pub async fn get_graph_metrics(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
 ) -> ServerResult<Json<GraphMetricsDto>> {
     // Get counts from memory manager
     let memory_count = state.memory_manager.count_memories(None).await?;
@@ -389,7 +754,10 @@ pub async fn get_graph_metrics(
     // Calculate centrality for each memory (simplified as relationship count)
     let mut memory_centrality: Vec<(String, usize, String)> = Vec::new();
 
-    for memory in sample_memories {
+    for memory in sample_memories
+        .into_iter()
+        .filter(|memory| state.shares.can_read(memory, auth.as_deref()))
+    {
         if let Ok(graph) = state.memory_manager.get_memory_graph(&memory.id, 1).await {
             let centrality_score = graph.relationships.len();
             memory_centrality.push((
@@ -437,17 +805,24 @@ pub async fn get_graph_metrics(
 )]
 pub async fn find_similar_structures(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Query(params): Query<SimilarStructuresParams>,
 ) -> ServerResult<Json<Vec<MemoryGraphDto>>> {
+    let user = auth.as_deref();
     let pattern_id = params
         .pattern
         .ok_or_else(|| ServerError::BadRequest("Missing 'pattern' parameter".to_string()))?;
 
     // Get the pattern memory's graph structure
-    let pattern_graph = state
+    state
+        .shares
+        .load(&state.memory_manager, &pattern_id, user, Access::Read)
+        .await?;
+    let mut pattern_graph = state
         .memory_manager
         .get_memory_graph(&pattern_id, 2)
         .await?;
+    prune_graph(&state, &mut pattern_graph, user);
 
     // Analyze the pattern structure
     let pattern_memory_count = pattern_graph.memories.len();
@@ -473,13 +848,15 @@ pub async fn find_similar_structures(
         .await?;
 
     for memory in candidate_memories {
-        // Skip the pattern memory itself
-        if memory.id == pattern_id {
+        // Skip the pattern memory itself, and memories the caller can't see
+        if memory.id == pattern_id || !state.shares.can_read(&memory, user) {
             continue;
         }
 
         // Get the candidate's graph structure
-        if let Ok(candidate_graph) = state.memory_manager.get_memory_graph(&memory.id, 2).await {
+        if let Ok(mut candidate_graph) = state.memory_manager.get_memory_graph(&memory.id, 2).await
+        {
+            prune_graph(&state, &mut candidate_graph, user);
             let candidate_memory_count = candidate_graph.memories.len();
             let candidate_relationship_count = candidate_graph.relationships.len();
             let candidate_relationship_types: std::collections::HashSet<String> = candidate_graph
@@ -562,11 +939,7 @@ pub async fn get_related_entities(
     Query(params): Query<RelatedEntitiesParams>,
 ) -> ServerResult<Json<Vec<EntityDto>>> {
     // Check if entity exists
-    let _entity = state
-        .memory_manager
-        .get_entity(&id)
-        .await?
-        .ok_or_else(|| not_found("Entity", &id))?;
+    let _entity = load_entity(&state, &id).await?;
 
     // Find related entities
     let related_entities = state
@@ -578,7 +951,11 @@ pub async fn get_related_entities(
         )
         .await?;
 
-    let entity_dtos: Vec<EntityDto> = related_entities.into_iter().map(EntityDto::from).collect();
+    let entity_dtos: Vec<EntityDto> = related_entities
+        .into_iter()
+        .filter(|entity| !is_reserved_entity_type(&entity.entity_type))
+        .map(EntityDto::from)
+        .collect();
 
     Ok(Json(entity_dtos))
 }
@@ -605,6 +982,10 @@ pub async fn get_central_entities(
     let mut entity_centrality: Vec<(String, usize, String)> = Vec::new();
 
     for entity in entities {
+        if is_reserved_entity_type(&entity.entity_type) {
+            continue;
+        }
+
         // Count relationships involving this entity
         let outgoing_relationships = state
             .memory_manager
@@ -666,6 +1047,44 @@ pub async fn get_central_entities(
     Ok(Json(central_entities))
 }
 
+/// Drop the memories `user` can't see from `graph`, with their relationships
+fn prune_graph(state: &AppState, graph: &mut MemoryGraph, user: Option<&AuthContext>) {
+    let hidden: HashSet<String> = graph
+        .memories
+        .values()
+        .filter(|memory| !state.shares.can_read(memory, user))
+        .map(|memory| memory.id.clone())
+        .collect();
+    graph.memories.retain(|id, _| !hidden.contains(id));
+    graph.relationships.retain(|relationship| {
+        !hidden.contains(&relationship.source_id) && !hidden.contains(&relationship.target_id)
+    });
+}
+
+/// Drop the triples about or pointing at memories `user` can't see
+async fn prune_triples(
+    state: &AppState,
+    graph: &mut TripleGraph,
+    user: Option<&AuthContext>,
+) -> ServerResult<()> {
+    let nodes = graph.triples.iter().flat_map(|triple| {
+        let object = match &triple.object {
+            Term::Node(id) => Some(id.as_str()),
+            _ => None,
+        };
+        std::iter::once(triple.subject.as_str()).chain(object)
+    });
+    let hidden = state
+        .shares
+        .hidden(&state.memory_manager, nodes, user)
+        .await?;
+    graph.triples.retain(|triple| {
+        !hidden.contains(&triple.subject)
+            && !matches!(&triple.object, Term::Node(id) if hidden.contains(id))
+    });
+    Ok(())
+}
+
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct GraphParams {
     /// Graph traversal depth
@@ -686,6 +1105,118 @@ pub struct PathParams {
 
     /// Maximum path depth
     pub max_depth: Option<u8>,
+
+    /// Comma-separated relationship types to follow; all when omitted
+    #[param(example = "works_at,acquired")]
+    pub relationship_types: Option<String>,
+
+    /// Comma-separated relationship types never to follow
+    pub exclude_relationship_types: Option<String>,
+
+    /// Comma-separated entity types paths may pass through; all when omitted
+    pub node_types: Option<String>,
+
+    /// Relationship property holding the cost of a hop; hops without it cost 1
+    #[param(example = "weight")]
+    pub weight_property: Option<String>,
+
+    /// Drop paths costing more than this
+    pub max_cost: Option<f64>,
+
+    /// Maximum number of paths to return (default 10)
+    pub limit: Option<usize>,
+}
+
+impl PathParams {
+    /// Constraints for a weighted search, or `None` when none were given
+    fn constraints(&self, max_depth: u8) -> ServerResult<Option<PathConstraints>> {
+        let weighted = self.relationship_types.is_some()
+            || self.exclude_relationship_types.is_some()
+            || self.node_types.is_some()
+            || self.weight_property.is_some()
+            || self.max_cost.is_some()
+            || self.limit.is_some();
+        if !weighted {
+            return Ok(None);
+        }
+        if self.max_cost.is_some_and(|max_cost| max_cost < 0.0) {
+            return Err(bad_request("'max_cost' must not be negative"));
+        }
+
+        let list = |value: &Option<String>| {
+            value
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let defaults = PathConstraints::default();
+        Ok(Some(PathConstraints {
+            max_depth,
+            relationship_types: list(&self.relationship_types),
+            excluded_relationship_types: list(&self.exclude_relationship_types),
+            node_types: list(&self.node_types),
+            weight_property: self
+                .weight_property
+                .clone()
+                .unwrap_or(defaults.weight_property),
+            max_cost: self.max_cost,
+            limit: self.limit.unwrap_or(defaults.limit),
+        }))
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GraphDiffParams {
+    /// Start of the window (RFC 3339)
+    #[param(example = "2026-01-01T00:00:00Z")]
+    pub from: Option<String>,
+
+    /// End of the window (RFC 3339); defaults to now
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RdfExportParams {
+    /// "ntriples" (default) or "turtle"
+    #[param(example = "turtle")]
+    pub format: Option<String>,
+
+    /// Also export memories; defaults to false
+    pub include_memories: Option<bool>,
+
+    /// Prefix of node and vocabulary IRIs; defaults to `urn:locai:`
+    pub base_iri: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DiagramParams {
+    /// "mermaid" (default) or "plantuml"
+    #[param(example = "mermaid")]
+    pub format: Option<String>,
+
+    /// Memory or entity to draw the neighbourhood of; the whole graph when
+    /// omitted
+    pub root: Option<String>,
+
+    /// Hops from the root (default 2)
+    pub depth: Option<u8>,
+
+    /// Relationship types to draw, comma separated
+    #[param(example = "works_at,knows")]
+    pub types: Option<String>,
+
+    /// Entity types to draw, comma separated
+    pub entity_types: Option<String>,
+
+    /// Draw memories as well as entities (default true)
+    pub include_memories: Option<bool>,
+
+    /// Most nodes drawn (default 50, at most 500)
+    pub max_nodes: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
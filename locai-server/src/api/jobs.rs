@@ -0,0 +1,122 @@
+//! Background job endpoints
+//!
+//! `POST /api/jobs` queues a job and answers `202 Accepted` with it; poll
+//! `GET /api/jobs/{id}` for progress and the result, or stop it with
+//! `POST /api/jobs/{id}/cancel`. See [`crate::jobs`] for how jobs run.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+
+use crate::{
+    api::auth::{AuthContext, require_role},
+    error::{ServerResult, not_found},
+    jobs::{self, Job, JobRequest},
+    state::AppState,
+};
+
+/// Submit a job
+#[utoipa::path(
+    post,
+    path = "/api/jobs",
+    tag = "jobs",
+    request_body = JobRequest,
+    responses(
+        (status = 202, description = "Job queued", body = Job),
+        (status = 403, description = "Maintenance jobs need the admin role"),
+        (status = 409, description = "A job of this kind is already pending"),
+    )
+)]
+pub async fn submit_job(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Json(request): Json<JobRequest>,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    submit(&state, auth, request).await
+}
+
+/// List jobs, newest first
+#[utoipa::path(
+    get,
+    path = "/api/jobs",
+    tag = "jobs",
+    responses(
+        (status = 200, description = "Pending and recently finished jobs", body = Vec<Job>),
+    )
+)]
+pub async fn list_jobs(State(state): State<Arc<AppState>>) -> ServerResult<Json<Vec<Job>>> {
+    Ok(Json(state.jobs.list()))
+}
+
+/// Get a job
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{id}",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "The job", body = Job),
+        (status = 404, description = "Job not found"),
+    )
+)]
+pub async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<Job>> {
+    state
+        .jobs
+        .get(&id)
+        .map(Json)
+        .ok_or_else(|| not_found("Job", &id))
+}
+
+/// Cancel a queued or running job
+///
+/// The job stops at its next await point; work it already wrote is kept.
+#[utoipa::path(
+    post,
+    path = "/api/jobs/{id}/cancel",
+    tag = "jobs",
+    params(
+        ("id" = String, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 202, description = "Cancellation requested", body = Job),
+        (status = 403, description = "Maintenance jobs need the admin role"),
+        (status = 404, description = "Job not found"),
+        (status = 409, description = "Job already finished"),
+    )
+)]
+pub async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    let job = state.jobs.get(&id).ok_or_else(|| not_found("Job", &id))?;
+    if job.kind.requires_admin() {
+        require_role(&state, auth.as_deref(), "admin")?;
+    }
+    let job = state.jobs.cancel(&id)?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Check the caller may run `request`, then queue it
+pub(crate) async fn submit(
+    state: &Arc<AppState>,
+    auth: Option<Extension<AuthContext>>,
+    request: JobRequest,
+) -> ServerResult<(StatusCode, Json<Job>)> {
+    if request.kind().requires_admin() {
+        require_role(state, auth.as_deref(), "admin")?;
+    }
+    let requested_by = auth.map(|auth| auth.username.clone());
+    let job = jobs::submit(state, request, requested_by).await?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
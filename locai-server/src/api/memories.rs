@@ -1,29 +1,52 @@
 //! Memory management API endpoints
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{
-    Json as JsonExtractor,
-    extract::{Path, Query, State},
+    Extension, Json as JsonExtractor,
+    extract::{Path, Query, RawQuery, State},
     http::StatusCode,
-    response::Json,
+    response::{
+        Json,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures::{
+    future::join_all,
+    stream::{self, Stream, StreamExt},
 };
 use serde::Deserialize;
-use utoipa::IntoParams;
+use utoipa::{IntoParams, ToSchema};
 
 use locai::{
-    memory::search_extensions::SearchMode as LocaiSearchMode,
-    models::{MemoryBuilder, MemoryPriority, MemoryType},
-    storage::filters::{MemoryFilter, SemanticSearchFilter},
+    memory::{
+        Collection, idempotency, lead_with_pinned, search_extensions::SearchMode as LocaiSearchMode,
+    },
+    models::{GeoPoint, GeoRadius, Memory, MemoryBuilder, MemoryPriority, MemoryType},
+    search::ScoringConfig,
+    storage::{
+        MemoryStore,
+        filter_expr::parse_filter_expression,
+        filters::{MemoryFilter, SemanticSearchFilter},
+        models::SearchResult,
+    },
 };
 
 use crate::{
-    api::dto::{
-        CreateMemoryRelationshipRequest, CreateMemoryRequest, GetMemoryRelationshipsParams,
-        MemoryDto, RelationshipDto, ScoringConfigDto, SearchMode, SearchResultDto,
-        UpdateMemoryRequest,
+    api::{
+        auth::AuthContext,
+        collections,
+        dto::{
+            BatchSearchResultDto, CreateMemoryRelationshipRequest, CreateMemoryRequest,
+            GetMemoryRelationshipsParams, MemoryDto, RelationshipDto, ScoringConfigDto, SearchMode,
+            SearchResultDto, SearchStageDto, UpdateMemoryRequest,
+        },
+        entities::visible_entity,
+        relationships::readable_relationships,
     },
     error::{ServerError, ServerResult, not_found},
+    sharing::{Access, OWNER_PROPERTY, is_admin},
     state::AppState,
     websocket::WebSocketMessage,
 };
@@ -36,13 +59,16 @@ use crate::{
     request_body = CreateMemoryRequest,
     responses(
         (status = 201, description = "Memory created successfully", body = MemoryDto),
+        (status = 202, description = "Memory is waiting for review", body = MemoryDto),
         (status = 400, description = "Bad request"),
         (status = 401, description = "Unauthorized"),
+        (status = 507, description = "Storage quota exceeded"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn create_memory(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     JsonExtractor(request): JsonExtractor<CreateMemoryRequest>,
 ) -> Result<(StatusCode, Json<MemoryDto>), ServerError> {
     // Convert string types to enums
@@ -54,6 +80,10 @@ pub async fn create_memory(
         _ => MemoryPriority::Normal,
     };
 
+    // Keep the content around in case the embedding proxy needs it
+    let content = request.content.clone();
+    let idempotency_key = scoped_idempotency_key(request.idempotency_key.clone(), auth.as_deref());
+
     // Build the memory
     let mut memory_builder = MemoryBuilder::new_with_content(request.content)
         .memory_type(memory_type)
@@ -99,6 +129,16 @@ pub async fn create_memory(
         }
 
         memory_builder = memory_builder.embedding(embedding);
+    } else if let Some(proxy) = state.embedding_proxy.as_ref().filter(|p| p.auto_attach()) {
+        // Auto-attach via the embedding proxy (cached by content hash)
+        match proxy.embed_for_memory(&content).await {
+            Ok(Some(embedding)) => memory_builder = memory_builder.embedding(embedding),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                "Embedding proxy failed, storing memory without embedding: {}",
+                e
+            ),
+        }
     } else if state.memory_manager.has_ml_service() {
         // Auto-generate embedding if ML service is configured and user didn't provide one
         // Note: Current EmbeddingManager only supports validation/normalization, not generation.
@@ -114,25 +154,23 @@ pub async fn create_memory(
         // Continue without embedding - memory will be stored but won't be searchable via vector search
     }
 
-    let memory = memory_builder.build();
-
-    // Store the memory
-    let memory_id = state.memory_manager.store_memory(memory.clone()).await?;
+    let mut memory = memory_builder.build();
 
-    // Get the stored memory to return with proper ID
-    let stored_memory = state
-        .memory_manager
-        .get_memory(&memory_id)
-        .await?
-        .ok_or_else(|| ServerError::Internal("Failed to retrieve stored memory".to_string()))?;
+    // With authentication, a memory belongs to its creator; admins may name
+    // another owner
+    if let Some(user) = auth.as_deref()
+        && !(is_admin(user) && memory.properties.get(OWNER_PROPERTY).is_some())
+    {
+        memory.set_property(OWNER_PROPERTY, user.user_id.to_string().into());
+    }
 
-    // Broadcast WebSocket message
+    // The WebSocket notification commits with the memory, which keeps its ID
     let ws_message = WebSocketMessage::MemoryCreated {
-        memory_id: stored_memory.id.clone(),
-        content: stored_memory.content.clone(),
-        memory_type: stored_memory.memory_type.to_string(),
-        metadata: stored_memory.properties.clone(),
-        importance: Some(match stored_memory.priority {
+        memory_id: memory.id.clone(),
+        content: memory.content.clone(),
+        memory_type: memory.memory_type.to_string(),
+        metadata: memory.properties.clone(),
+        importance: Some(match memory.priority {
             locai::models::MemoryPriority::Low => 0.25,
             locai::models::MemoryPriority::Normal => 0.5,
             locai::models::MemoryPriority::High => 0.75,
@@ -140,10 +178,50 @@ pub async fn create_memory(
         }),
         node_id: None, // Will be set by live query system if enabled
     };
-    state.broadcast_message(ws_message);
 
-    let memory_dto = MemoryDto::from(stored_memory);
-    Ok((StatusCode::CREATED, Json(memory_dto)))
+    // Store the memory, unless a request with the same idempotency key did
+    let fingerprint = idempotency::memory_fingerprint([&memory]);
+    let memory_id = state
+        .memory_manager
+        .idempotent(idempotency_key.as_deref(), &fingerprint, || {
+            state
+                .memory_manager
+                .store_memory_with_outbox(memory, vec![AppState::outbox_message(&ws_message)])
+        })
+        .await?;
+    state.relay_outbox().await;
+
+    // Get the stored memory to return with proper ID; a replayed memory may
+    // have been unshared since, so is checked like any other read
+    if let Some(stored_memory) = state.memory_manager.get_memory(&memory_id).await? {
+        state
+            .shares
+            .require(&stored_memory, auth.as_deref(), Access::Read)?;
+        return Ok((StatusCode::CREATED, Json(MemoryDto::from(stored_memory))));
+    }
+
+    // Memories from reviewed sources wait for approval instead
+    let pending = state
+        .memory_manager
+        .get_pending_review(&memory_id)
+        .await?
+        .ok_or_else(|| ServerError::Internal("Failed to retrieve stored memory".to_string()))?;
+    state
+        .shares
+        .require(&pending.memory, auth.as_deref(), Access::Read)?;
+    Ok((StatusCode::ACCEPTED, Json(MemoryDto::from(pending.memory))))
+}
+
+/// The key a write's results are recorded under: with authentication, each
+/// user has their own keys, so two users sending the same key don't collide
+pub(super) fn scoped_idempotency_key(
+    key: Option<String>,
+    user: Option<&AuthContext>,
+) -> Option<String> {
+    match user {
+        Some(user) => key.map(|key| format!("{}/{}", user.user_id, key)),
+        None => key,
+    }
 }
 
 /// Get a memory by ID
@@ -163,12 +241,14 @@ pub async fn create_memory(
 )]
 pub async fn get_memory(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
 ) -> ServerResult<Json<MemoryDto>> {
     let memory = state
         .memory_manager
         .get_memory(&id)
         .await?
+        .filter(|memory| state.shares.can_read(memory, auth.as_deref()))
         .ok_or_else(|| not_found("Memory", &id))?;
 
     let memory_dto = MemoryDto::from(memory);
@@ -203,12 +283,29 @@ pub struct ListMemoriesParams {
 
     /// Filter by content (substring search)
     pub content: Option<String>,
+
+    /// Typed comparisons on properties, such as `amount > 100 and due within next 7d`
+    #[param(example = "amount > 100 and due_date within next 7d")]
+    pub filter: Option<String>,
 }
 
 fn default_page_size() -> usize {
     20
 }
 
+/// Whether a memory has the requested priority ("Low", "Normal", "High", "Critical")
+fn has_priority(memory: &Memory, priority: Option<&str>) -> bool {
+    priority.is_none_or(|priority| format!("{:?}", memory.priority) == priority)
+}
+
+/// Memories are read from storage in batches of this many while filling a
+/// page of listed memories
+const LIST_BATCH_SIZE: usize = 100;
+
+/// Searches are run again with at most this many results while filling a
+/// page of search results
+const MAX_SEARCH_FETCH: usize = 1000;
+
 #[utoipa::path(
     get,
     path = "/api/memories",
@@ -222,6 +319,7 @@ fn default_page_size() -> usize {
 )]
 pub async fn list_memories(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Query(params): Query<ListMemoriesParams>,
 ) -> ServerResult<Json<Vec<MemoryDto>>> {
     let mut filter = MemoryFilter::default();
@@ -244,39 +342,51 @@ pub async fn list_memories(
         filter.source = Some(source);
     }
 
-    // Apply priority filter if specified
-    if let Some(priority_str) = params.priority {
-        let mut priority_properties = std::collections::HashMap::new();
-        priority_properties.insert(
-            "priority".to_string(),
-            serde_json::Value::String(priority_str),
-        );
-        filter.properties = Some(priority_properties);
+    if let Some(expression) = params.filter {
+        filter.conditions = Some(parse_filter_expression(&expression, chrono::Utc::now())?);
     }
 
+    // Priority is a memory field rather than a property, so it's matched here
+    let priority = params.priority;
+
     // Calculate offset for pagination
     let offset = params.page * params.size;
 
-    // Get memories
-    let memories = state
-        .memory_manager
-        .filter_memories(
-            filter,
-            None, // sort_by
-            None, // sort_order
-            Some(params.size),
-        )
-        .await?;
-
-    // Apply manual pagination (since filter_memories doesn't support offset)
-    let paginated_memories: Vec<_> = memories
-        .into_iter()
-        .skip(offset)
-        .take(params.size)
-        .map(MemoryDto::from)
-        .collect();
+    // Shares and priority are checked here rather than by storage, so
+    // storage is read in batches until the page is full
+    let mut page = Vec::new();
+    let mut skipped = 0;
+    let mut start = 0;
+    while page.len() < params.size {
+        let batch = state
+            .memory_manager
+            .storage()
+            .list_memories(Some(filter.clone()), Some(LIST_BATCH_SIZE), Some(start))
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to list memories: {}", e)))?;
+        let fetched = batch.len();
+        for memory in batch {
+            if !state.shares.can_read(&memory, auth.as_deref())
+                || !has_priority(&memory, priority.as_deref())
+            {
+                continue;
+            }
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+            page.push(MemoryDto::from(memory));
+            if page.len() == params.size {
+                break;
+            }
+        }
+        if fetched < LIST_BATCH_SIZE {
+            break;
+        }
+        start += fetched;
+    }
 
-    Ok(Json(paginated_memories))
+    Ok(Json(page))
 }
 
 /// Update a memory
@@ -291,6 +401,7 @@ pub async fn list_memories(
     responses(
         (status = 200, description = "Memory updated successfully", body = MemoryDto),
         (status = 404, description = "Memory not found"),
+        (status = 403, description = "Memory is shared read-only"),
         (status = 400, description = "Bad request"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
@@ -298,6 +409,7 @@ pub async fn list_memories(
 )]
 pub async fn update_memory(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     JsonExtractor(request): JsonExtractor<UpdateMemoryRequest>,
 ) -> ServerResult<Json<MemoryDto>> {
@@ -308,6 +420,16 @@ pub async fn update_memory(
         .await?
         .ok_or_else(|| not_found("Memory", &id))?;
 
+    let access = state.shares.access(&memory, auth.as_deref());
+    if access == Access::None {
+        return Err(not_found("Memory", &id));
+    }
+    if access < Access::Write {
+        return Err(ServerError::Forbidden(
+            "The memory is shared with you read-only".to_string(),
+        ));
+    }
+
     // Apply updates
     if let Some(content) = request.content {
         memory.content = content;
@@ -339,7 +461,19 @@ pub async fn update_memory(
     }
 
     if let Some(properties) = request.properties {
+        let owner = memory.properties.get(OWNER_PROPERTY).cloned();
         memory.properties = properties;
+        // Only the owner can hand the memory over, and never by omission
+        if access < Access::Owner || memory.properties.get(OWNER_PROPERTY).is_none() {
+            match owner {
+                Some(owner) => memory.set_property(OWNER_PROPERTY, owner),
+                None => {
+                    if let Some(properties) = memory.properties.as_object_mut() {
+                        properties.remove(OWNER_PROPERTY);
+                    }
+                }
+            }
+        }
     }
 
     // Handle embedding update
@@ -388,10 +522,7 @@ pub async fn update_memory(
         }
     }
 
-    // Update the memory
-    state.memory_manager.update_memory(memory.clone()).await?;
-
-    // Broadcast WebSocket message
+    // Update the memory, committing the WebSocket notification with it
     let ws_message = WebSocketMessage::MemoryUpdated {
         memory_id: id.clone(),
         content: memory.content.clone(),
@@ -404,7 +535,11 @@ pub async fn update_memory(
         }),
         node_id: None, // Will be set by live query system if enabled
     };
-    state.broadcast_message(ws_message);
+    state
+        .memory_manager
+        .update_memory_with_outbox(memory.clone(), vec![AppState::outbox_message(&ws_message)])
+        .await?;
+    state.relay_outbox().await;
 
     let memory_dto = MemoryDto::from(memory);
     Ok(Json(memory_dto))
@@ -421,34 +556,47 @@ pub async fn update_memory(
     responses(
         (status = 204, description = "Memory deleted successfully"),
         (status = 404, description = "Memory not found"),
+        (status = 403, description = "Caller does not own the memory"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn delete_memory(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
 ) -> ServerResult<StatusCode> {
     // Check if memory exists
-    let _memory = state
+    let memory = state
         .memory_manager
         .get_memory(&id)
         .await?
         .ok_or_else(|| not_found("Memory", &id))?;
 
-    // Delete the memory
-    let deleted = state.memory_manager.delete_memory(&id).await?;
-
-    if !deleted {
-        return Err(ServerError::Internal("Failed to delete memory".to_string()));
+    match state.shares.access(&memory, auth.as_deref()) {
+        Access::None => return Err(not_found("Memory", &id)),
+        Access::Read | Access::Write => {
+            return Err(ServerError::Forbidden(
+                "Only the owner of a memory can delete it".to_string(),
+            ));
+        }
+        Access::Owner => {}
     }
 
-    // Broadcast WebSocket message
+    // Delete the memory, committing the WebSocket notification with it
     let ws_message = WebSocketMessage::MemoryDeleted {
-        memory_id: id,
+        memory_id: id.clone(),
         node_id: None, // Will be set by live query system if enabled
     };
-    state.broadcast_message(ws_message);
+    let deleted = state
+        .memory_manager
+        .delete_memory_with_outbox(&id, vec![AppState::outbox_message(&ws_message)])
+        .await?;
+
+    if !deleted {
+        return Err(ServerError::Internal("Failed to delete memory".to_string()));
+    }
+    state.relay_outbox().await;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -499,10 +647,20 @@ pub async fn delete_memory(
 /// GET /api/memories/search?q=spell&scoring={"recency_boost":2.0,"decay_function":"exponential"}
 /// ```
 ///
+/// Scoring with a named profile:
+/// ```text
+/// GET /api/memories/search?q=spell&scoring_profile=recency
+/// ```
+///
 /// Temporal filtering:
 /// ```text
 /// GET /api/memories/search?q=battle&created_after=2025-11-01T00:00:00Z&created_before=2025-11-01T23:59:59Z
 /// ```
+///
+/// Scoped to a collection:
+/// ```text
+/// GET /api/memories/search?q=deploy&collection=runbooks
+/// ```
 #[utoipa::path(
     get,
     path = "/api/memories/search",
@@ -517,8 +675,206 @@ pub async fn delete_memory(
 )]
 pub async fn search_memories(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    RawQuery(raw_query): RawQuery,
     Query(params): Query<SearchParams>,
 ) -> Result<Json<Vec<SearchResultDto>>, ServerError> {
+    // Shared between replicas when hot state is in Redis; results depend on
+    // what the caller can read, so the caller is part of the key
+    let search_id = format!(
+        "{}?{}",
+        auth.as_ref()
+            .map(|auth| auth.user_id.to_string())
+            .unwrap_or_default(),
+        raw_query.unwrap_or_default()
+    );
+    let cache_key = state.hot_state.search_cache_key(&search_id).await;
+    if let Some(key) = &cache_key
+        && let Some(results) = state.hot_state.cached_search(key).await
+    {
+        return Ok(Json(results));
+    }
+
+    let search = prepare_search(&state, params).await?;
+    let results = run_search(&state, auth.as_deref(), &search, search.mode).await?;
+    if let Some(key) = &cache_key {
+        state.hot_state.cache_search(key, &results).await;
+    }
+    Ok(Json(results))
+}
+
+/// Stream search results as server-sent events, fastest stage first
+///
+/// Takes the same parameters as `GET /api/memories/search`. Each stage sends a
+/// `results` event carrying its full result list, which replaces the previous
+/// stage's:
+///
+/// 1. `text`: BM25 keyword hits, available immediately
+/// 2. `vector` or `hybrid`: the requested mode's re-ranked results, when it isn't `text`
+///
+/// A stage that fails sends an `error` event instead, and the stream ends with
+/// a `done` event. A search with scoring runs as a single stage.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/memories/search/stream?q=warrior&mode=hybrid&limit=10
+///
+/// event: results
+/// data: {"stage":"text","final":false,"results":[...]}
+///
+/// event: results
+/// data: {"stage":"hybrid","final":true,"results":[...]}
+///
+/// event: done
+/// data: {}
+/// ```
+#[utoipa::path(
+    get,
+    path = "/api/memories/search/stream",
+    tag = "memories",
+    params(SearchParams),
+    responses(
+        (status = 200, description = "Stream of `results` events, one per search stage", body = SearchStageDto, content_type = "text/event-stream"),
+        (status = 400, description = "Bad request (invalid query or scoring configuration)"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn search_memories_stream(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<SearchParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ServerError> {
+    let search = Arc::new(prepare_search(&state, params).await?);
+    let mut stages = Vec::new();
+    if search.mode != LocaiSearchMode::Text && search.scoring.is_none() {
+        stages.push(LocaiSearchMode::Text);
+    }
+    stages.push(search.mode);
+    let last = stages.len() - 1;
+
+    let results = stream::iter(stages.into_iter().enumerate()).then(move |(i, mode)| {
+        let state = state.clone();
+        let auth = auth.clone();
+        let search = search.clone();
+        async move {
+            match run_search(&state, auth.as_deref(), &search, mode).await {
+                Ok(results) => Event::default().event("results").json_data(SearchStageDto {
+                    stage: stage_name(mode).to_string(),
+                    r#final: i == last,
+                    results,
+                }),
+                Err(e) => Ok(Event::default().event("error").data(e.to_string())),
+            }
+        }
+    });
+    let done = stream::once(async { Ok(Event::default().event("done").data("{}")) });
+
+    Ok(Sse::new(results.chain(done)).keep_alive(KeepAlive::default()))
+}
+
+/// Run several searches in one request
+///
+/// Each query takes the parameters of `GET /api/memories/search`, falling back
+/// to `defaults` for any it doesn't set, and the queries run concurrently.
+/// Results come back in query order; a query that fails reports its `error`
+/// without failing the others. Invalid parameters in any query reject the
+/// whole batch with `400` before anything runs.
+///
+/// # Example Request
+///
+/// ```json
+/// {
+///   "defaults": { "mode": "hybrid", "limit": 5, "tags": "project-x" },
+///   "queries": [
+///     { "q": "deployment checklist" },
+///     { "q": "open incidents", "limit": 10 }
+///   ]
+/// }
+/// ```
+///
+/// # Limits
+///
+/// - Maximum 20 queries per batch
+#[utoipa::path(
+    post,
+    path = "/api/memories/search/batch",
+    tag = "memories",
+    request_body = BatchSearchRequest,
+    responses(
+        (status = 200, description = "Results for each query, in request order", body = Vec<BatchSearchResultDto>),
+        (status = 400, description = "Bad request (too many queries or invalid query parameters)"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn search_memories_batch(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    JsonExtractor(request): JsonExtractor<BatchSearchRequest>,
+) -> ServerResult<Json<Vec<BatchSearchResultDto>>> {
+    if request.queries.is_empty() || request.queries.len() > MAX_BATCH_QUERIES {
+        return Err(ServerError::BadRequest(format!(
+            "A search batch takes 1 to {} queries, got {}",
+            MAX_BATCH_QUERIES,
+            request.queries.len()
+        )));
+    }
+
+    let mut searches = Vec::with_capacity(request.queries.len());
+    for (i, params) in request.queries.into_iter().enumerate() {
+        let search = prepare_search(&state, params.or(&request.defaults))
+            .await
+            .map_err(|e| match e {
+                ServerError::BadRequest(message) => {
+                    ServerError::BadRequest(format!("Query {}: {}", i, message))
+                }
+                e => e,
+            })?;
+        searches.push(search);
+    }
+
+    let results = join_all(searches.iter().map(|search| async {
+        match run_search(&state, auth.as_deref(), search, search.mode).await {
+            Ok(results) => BatchSearchResultDto {
+                q: search.query.clone(),
+                results,
+                error: None,
+            },
+            Err(e) => BatchSearchResultDto {
+                q: search.query.clone(),
+                results: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        }
+    }))
+    .await;
+
+    Ok(Json(results))
+}
+
+fn stage_name(mode: LocaiSearchMode) -> &'static str {
+    match mode {
+        LocaiSearchMode::Text => "text",
+        LocaiSearchMode::Vector => "vector",
+        LocaiSearchMode::Hybrid => "hybrid",
+    }
+}
+
+/// A validated search request, which may run once per mode
+struct PreparedSearch {
+    query: String,
+    limit: usize,
+    mode: LocaiSearchMode,
+    filter: SemanticSearchFilter,
+    priority: Option<String>,
+    scoring: Option<ScoringConfig>,
+    collection: Option<Collection>,
+    pin_scope: Option<String>,
+}
+
+async fn prepare_search(state: &AppState, params: SearchParams) -> ServerResult<PreparedSearch> {
     let query = params
         .q
         .ok_or_else(|| ServerError::BadRequest("Missing query parameter 'q'".to_string()))?;
@@ -558,15 +914,8 @@ pub async fn search_memories(
         memory_filter.tags = Some(tags);
     }
 
-    // Apply priority filter for search if specified
-    if let Some(priority_str) = params.priority {
-        let mut priority_properties = std::collections::HashMap::new();
-        priority_properties.insert(
-            "priority".to_string(),
-            serde_json::Value::String(priority_str),
-        );
-        memory_filter.properties = Some(priority_properties);
-    }
+    // Priority is a memory field rather than a property, so it's matched here
+    let priority = params.priority;
 
     // Apply temporal filters if specified
     if let Some(created_after_str) = params.created_after {
@@ -593,9 +942,27 @@ pub async fn search_memories(
         }
     }
 
+    if let Some(expression) = params.filter {
+        memory_filter.conditions = Some(parse_filter_expression(&expression, chrono::Utc::now())?);
+    }
+
+    if let Some(near) = params.near {
+        let center = GeoPoint::parse(&near).ok_or_else(|| {
+            ServerError::BadRequest(format!(
+                "Invalid near location '{}'. Expected LAT,LON in degrees like: 51.5007,-0.1246",
+                near
+            ))
+        })?;
+        let near = GeoRadius::new(center, params.radius.unwrap_or(1000.0));
+        near.validate()
+            .map_err(|e| ServerError::BadRequest(e.to_string()))?;
+        memory_filter.near = Some(near);
+    }
+
     let semantic_filter = SemanticSearchFilter {
         similarity_threshold: params.threshold,
         memory_filter: Some(memory_filter),
+        include_archived: params.include_archived.unwrap_or(false),
     };
 
     // Parse scoring configuration if provided
@@ -609,30 +976,133 @@ pub async fn search_memories(
                 )));
             }
         }
+    } else if let Some(profile) = params.scoring_profile.as_deref() {
+        Some(state.memory_manager.scoring_profiles().resolve(profile)?)
     } else {
         None
     };
 
-    // Perform search (with or without scoring)
-    let search_results = if let Some(scoring) = scoring_config {
+    let collection = match params.collection.as_deref() {
+        Some(collection) => Some(collections::find(state, collection).await?),
+        None => None,
+    };
+
+    Ok(PreparedSearch {
+        query,
+        limit,
+        mode: locai_mode,
+        filter: semantic_filter,
+        priority,
+        scoring: scoring_config,
+        collection,
+        pin_scope: params.pin_scope,
+    })
+}
+
+/// Run a prepared search in `mode`, returning the results the caller can see
+async fn run_search(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    search: &PreparedSearch,
+    mode: LocaiSearchMode,
+) -> ServerResult<Vec<SearchResultDto>> {
+    let limit = search.limit;
+    let priority = &search.priority;
+    let visible = |memory: &Memory| {
+        state.shares.can_read(memory, auth) && has_priority(memory, priority.as_deref())
+    };
+
+    // Shares and priority are checked here rather than by the search, so a
+    // page they thin out is searched again for more results until it's full
+    // or the results run out
+    let mut fetch = limit;
+    let search_results = loop {
+        let (results, exhausted) = search_once(state, search, mode, fetch).await?;
+        let mut results: Vec<SearchResult> = results
+            .into_iter()
+            .filter(|result| visible(&result.memory))
+            .collect();
+        if results.len() >= limit || exhausted || fetch >= MAX_SEARCH_FETCH {
+            results.truncate(limit);
+            break results;
+        }
+        fetch = (fetch * 2).min(MAX_SEARCH_FETCH);
+    };
+
+    let mut pinned = match search.pin_scope.as_deref() {
+        Some(scope) => state.memory_manager.pinned_memories(Some(scope)).await?,
+        None => Vec::new(),
+    };
+    pinned.retain(&visible);
+    let pinned_ids: HashSet<String> = pinned.iter().map(|memory| memory.id.clone()).collect();
+    let search_results = lead_with_pinned(pinned, search_results);
+
+    let result_dtos: Vec<SearchResultDto> = search_results
+        .into_iter()
+        .map(|result| {
+            let is_pinned = pinned_ids.contains(&result.memory.id);
+            let mut dto = SearchResultDto::from(result);
+            if is_pinned {
+                dto.match_method = Some("pinned".to_string());
+            }
+            dto
+        })
+        .collect();
+
+    Ok(result_dtos)
+}
+
+/// Run a prepared search for up to `limit` results, also returning whether
+/// there were no more to find
+async fn search_once(
+    state: &AppState,
+    search: &PreparedSearch,
+    mode: LocaiSearchMode,
+    limit: usize,
+) -> ServerResult<(Vec<SearchResult>, bool)> {
+    let query = &search.query;
+    let collection = &search.collection;
+
+    let search_results = if let Some(scoring) = search.scoring.clone() {
+        let results = state
+            .memory_manager
+            .search_with_scoring(query, Some(limit), scoring)
+            .await?;
+        let exhausted = results.len() < limit;
+        let results = match &collection {
+            Some(collection) => {
+                let members = state
+                    .memory_manager
+                    .collection_member_ids(collection)
+                    .await?;
+                results
+                    .into_iter()
+                    .filter(|result| members.contains(&result.memory.id))
+                    .collect()
+            }
+            None => results,
+        };
+        return Ok((results, exhausted));
+    } else if let Some(collection) = &collection {
         state
             .memory_manager
-            .search_with_scoring(&query, Some(limit), scoring)
+            .search_collection(
+                collection,
+                query,
+                Some(limit),
+                Some(search.filter.clone()),
+                mode,
+            )
             .await?
     } else {
         state
             .memory_manager
-            .search(&query, Some(limit), Some(semantic_filter), locai_mode)
+            .search(query, Some(limit), Some(search.filter.clone()), mode)
             .await?
     };
 
-    // Convert to DTOs
-    let result_dtos: Vec<SearchResultDto> = search_results
-        .into_iter()
-        .map(SearchResultDto::from)
-        .collect();
-
-    Ok(Json(result_dtos))
+    let exhausted = search_results.len() < limit;
+    Ok((search_results, exhausted))
 }
 
 /// Create a relationship between memories
@@ -646,6 +1116,7 @@ pub async fn search_memories(
     request_body = CreateMemoryRelationshipRequest,
     responses(
         (status = 201, description = "Relationship created successfully", body = RelationshipDto),
+        (status = 403, description = "Source memory is shared read-only"),
         (status = 404, description = "Source or target memory not found"),
         (status = 400, description = "Bad request"),
         (status = 401, description = "Unauthorized"),
@@ -654,6 +1125,7 @@ pub async fn search_memories(
 )]
 pub async fn create_memory_relationship(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     JsonExtractor(request): JsonExtractor<CreateMemoryRelationshipRequest>,
 ) -> ServerResult<(StatusCode, Json<RelationshipDto>)> {
@@ -664,26 +1136,31 @@ pub async fn create_memory_relationship(
     // Use id as source_id for clarity in the logic
     let source_id = id;
 
-    // Validate that source memory exists
+    // Linking a memory changes it, so takes write access to the source
     let _source_memory = state
-        .memory_manager
-        .get_memory(&source_id)
-        .await?
-        .ok_or_else(|| not_found("Memory", &source_id))?;
+        .shares
+        .load(
+            &state.memory_manager,
+            &source_id,
+            auth.as_deref(),
+            Access::Write,
+        )
+        .await?;
 
-    // Validate that target exists (can be memory OR entity)
-    let target_is_memory = state
-        .memory_manager
-        .get_memory(&request.target_id)
-        .await?
-        .is_some();
+    // Validate that target exists (can be memory OR entity); a memory the
+    // caller can't see is reported as not found
+    let target_is_memory = match state.memory_manager.get_memory(&request.target_id).await? {
+        Some(memory) => {
+            state
+                .shares
+                .require(&memory, auth.as_deref(), Access::Read)?;
+            true
+        }
+        None => false,
+    };
 
     let target_is_entity = if !target_is_memory {
-        state
-            .memory_manager
-            .get_entity(&request.target_id)
-            .await?
-            .is_some()
+        visible_entity(&state, &request.target_id).await?.is_some()
     } else {
         false
     };
@@ -744,17 +1221,17 @@ pub async fn create_memory_relationship(
 )]
 pub async fn get_memory_relationships(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     Query(params): Query<GetMemoryRelationshipsParams>,
 ) -> ServerResult<Json<Vec<RelationshipDto>>> {
     use locai::storage::filters::RelationshipFilter;
 
-    // Validate that the memory exists
+    // Validate that the memory exists and the caller can see it
     let _memory = state
-        .memory_manager
-        .get_memory(&id)
-        .await?
-        .ok_or_else(|| not_found("Memory", &id))?;
+        .shares
+        .load(&state.memory_manager, &id, auth.as_deref(), Access::Read)
+        .await?;
 
     // Build filter based on direction
     let direction = params.direction.as_str();
@@ -790,7 +1267,9 @@ pub async fn get_memory_relationships(
         all_relationships.extend(incoming);
     }
 
-    // Convert to DTOs
+    // Convert to DTOs, dropping relationships to memories the caller can't see
+    let all_relationships =
+        readable_relationships(&state, all_relationships, auth.as_deref()).await?;
     let relationship_dtos: Vec<RelationshipDto> = all_relationships
         .into_iter()
         .map(RelationshipDto::from)
@@ -799,7 +1278,7 @@ pub async fn get_memory_relationships(
     Ok(Json(relationship_dtos))
 }
 
-#[derive(Debug, Deserialize, IntoParams)]
+#[derive(Debug, Default, Deserialize, IntoParams)]
 pub struct SearchParams {
     /// Search query
     pub q: Option<String>,
@@ -849,6 +1328,12 @@ pub struct SearchParams {
     #[param(example = r#"{"recency_boost":2.0,"decay_function":"exponential"}"#)]
     pub scoring: Option<String>,
 
+    /// Name of a scoring profile to rank with instead of a `scoring` configuration
+    ///
+    /// See `GET /api/scoring-profiles` for the profiles available.
+    #[param(example = "recency")]
+    pub scoring_profile: Option<String>,
+
     /// Filter by creation date - only memories created after this time (ISO 8601 format)
     ///
     /// Example: `2025-11-01T00:00:00Z`
@@ -860,4 +1345,74 @@ pub struct SearchParams {
     /// Example: `2025-11-01T23:59:59Z`
     #[param(example = "2025-11-01T23:59:59Z")]
     pub created_before: Option<String>,
+
+    /// Also search archived memories (default: false)
+    pub include_archived: Option<bool>,
+
+    /// Only search this collection's memories (collection ID or name)
+    pub collection: Option<String>,
+
+    /// Put the memories pinned for this scope first, whatever their score
+    ///
+    /// Global pins are included for every scope; use `global` for only those.
+    #[param(example = "global")]
+    pub pin_scope: Option<String>,
+
+    /// Only memories located within `radius` of this point, as "LAT,LON" in degrees
+    #[param(example = "51.5007,-0.1246")]
+    pub near: Option<String>,
+
+    /// Radius for `near`, in meters (default: 1000)
+    pub radius: Option<f64>,
+
+    /// Typed comparisons on properties, such as `amount > 100 and due within next 7d`
+    #[param(example = "amount > 100 and due_date within next 7d")]
+    pub filter: Option<String>,
+}
+
+impl SearchParams {
+    /// These parameters, with any they don't set taken from `defaults`
+    fn or(self, defaults: &SearchParams) -> SearchParams {
+        SearchParams {
+            q: self.q.or_else(|| defaults.q.clone()),
+            limit: self.limit.or(defaults.limit),
+            mode: self.mode.or_else(|| defaults.mode.clone()),
+            threshold: self.threshold.or(defaults.threshold),
+            memory_type: self.memory_type.or_else(|| defaults.memory_type.clone()),
+            tags: self.tags.or_else(|| defaults.tags.clone()),
+            priority: self.priority.or_else(|| defaults.priority.clone()),
+            scoring: self.scoring.or_else(|| defaults.scoring.clone()),
+            scoring_profile: self
+                .scoring_profile
+                .or_else(|| defaults.scoring_profile.clone()),
+            created_after: self
+                .created_after
+                .or_else(|| defaults.created_after.clone()),
+            created_before: self
+                .created_before
+                .or_else(|| defaults.created_before.clone()),
+            include_archived: self.include_archived.or(defaults.include_archived),
+            collection: self.collection.or_else(|| defaults.collection.clone()),
+            pin_scope: self.pin_scope.or_else(|| defaults.pin_scope.clone()),
+            near: self.near.or_else(|| defaults.near.clone()),
+            radius: self.radius.or(defaults.radius),
+            filter: self.filter.or_else(|| defaults.filter.clone()),
+        }
+    }
+}
+
+/// Most queries one search batch may run
+const MAX_BATCH_QUERIES: usize = 20;
+
+/// Searches to run together
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchSearchRequest {
+    /// Queries to run, each with the parameters of `GET /api/memories/search`
+    #[schema(value_type = Vec<Object>)]
+    pub queries: Vec<SearchParams>,
+
+    /// Parameters for every query that doesn't set its own
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub defaults: SearchParams,
 }
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use axum::{
     Router,
     extract::State,
+    http::StatusCode,
     middleware,
     response::Json,
     routing::{delete, get, post, put},
@@ -14,19 +15,42 @@ use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{state::AppState, websocket::websocket_handler};
 
+pub mod admin;
 pub mod auth;
 pub mod auth_endpoints;
 pub mod auth_service;
 pub mod batch;
+pub mod changes;
+pub mod collections;
 pub mod dto;
+pub mod embeddings;
 pub mod entities;
 pub mod graph;
+pub mod jobs;
 pub mod memories;
+pub mod notifications;
+pub mod personas;
+pub mod pins;
+pub mod preferences;
+pub mod procedures;
+pub mod quotas;
 pub mod relationship_types;
 pub mod relationships;
+pub mod reminders;
+pub mod replication;
+pub mod review;
+pub mod rules;
+pub mod scoring_profiles;
+pub mod scratchpad;
+pub mod shares;
+pub mod tasks;
+pub mod usage;
+pub mod vectorstore;
 pub mod versions;
 pub mod webhooks;
 
+use crate::hot_state::hot_state_middleware;
+use crate::usage::usage_middleware;
 use auth::auth_middleware;
 
 /// OpenAPI documentation
@@ -39,25 +63,114 @@ use auth::auth_middleware;
         auth_endpoints::get_user,
         auth_endpoints::update_user,
         auth_endpoints::delete_user,
+        admin::compact,
+        admin::reindex,
+        admin::run_retention,
+        admin::repair_versions,
+        admin::clear_caches,
+        jobs::submit_job,
+        jobs::list_jobs,
+        jobs::get_job,
+        jobs::cancel_job,
+        shares::create_share,
+        shares::list_shares,
+        shares::delete_share,
         batch::batch_execute,
+        changes::list_changes,
+        collections::create_collection,
+        collections::list_collections,
+        collections::get_collection,
+        collections::update_collection,
+        collections::delete_collection,
+        collections::list_collection_memories,
+        collections::add_collection_memories,
+        collections::remove_collection_memory,
         memories::create_memory,
         memories::get_memory,
         memories::list_memories,
         memories::update_memory,
         memories::delete_memory,
         memories::search_memories,
+        memories::search_memories_stream,
+        memories::search_memories_batch,
+        scoring_profiles::list_scoring_profiles,
+        scoring_profiles::get_scoring_profile,
+        scoring_profiles::set_scoring_profile,
+        scoring_profiles::remove_scoring_profile,
+        rules::list_rules,
+        rules::get_rule,
+        rules::set_rule,
+        rules::remove_rule,
+        rules::test_rule,
+        review::list_reviews,
+        review::get_review,
+        review::approve_memory,
+        review::reject_memory,
+        notifications::list_notification_targets,
+        notifications::get_notification_target,
+        notifications::set_notification_target,
+        notifications::remove_notification_target,
+        notifications::test_notification_target,
+        notifications::list_notification_rules,
+        notifications::get_notification_rule,
+        notifications::set_notification_rule,
+        notifications::remove_notification_rule,
+        usage::get_usage,
+        usage::export_usage,
+        pins::pin_memory,
+        pins::unpin_memory,
+        pins::list_pins,
+        scratchpad::list_scratchpad,
+        scratchpad::get_scratchpad_entry,
+        scratchpad::put_scratchpad_entry,
+        scratchpad::delete_scratchpad_entry,
+        reminders::set_reminder,
+        reminders::get_reminder,
+        reminders::cancel_reminder,
+        reminders::snooze_reminder,
+        reminders::list_reminders,
+        tasks::create_task,
+        tasks::list_tasks,
+        tasks::get_task,
+        tasks::update_task,
+        tasks::list_dependencies,
+        tasks::add_dependency,
+        tasks::remove_dependency,
+        procedures::create_procedure,
+        procedures::list_procedures,
+        procedures::find_procedures,
+        procedures::get_procedure,
+        preferences::list_preferences,
+        preferences::get_preference,
+        preferences::set_preference,
+        preferences::remove_preference,
+        preferences::preference_history,
+        personas::list_personas,
+        personas::get_persona,
+        personas::set_persona,
+        personas::remove_persona,
+        personas::persona_history,
+        quotas::list_quota_usage,
+        quotas::get_quota_usage,
         entities::list_entities,
         entities::get_entity,
         entities::create_entity,
         entities::update_entity,
         entities::delete_entity,
         entities::get_entity_memories,
+        entities::get_entity_profile,
+        entities::list_entity_versions,
+        entities::get_entity_at_time,
+        entities::diff_entity_versions,
         relationships::list_relationships,
         relationships::get_relationship,
         relationships::create_relationship,
         relationships::update_relationship,
         relationships::delete_relationship,
         relationships::find_related_entities,
+        relationships::list_relationship_versions,
+        relationships::get_relationship_at_time,
+        relationships::diff_relationship_versions,
         relationship_types::list_relationship_types,
         relationship_types::get_relationship_type,
         relationship_types::register_relationship_type,
@@ -71,6 +184,11 @@ use auth::auth_middleware;
         graph::get_memory_graph,
         graph::get_entity_graph,
         graph::find_paths,
+        graph::get_graph_diff,
+        graph::extract_subgraph,
+        graph::export_rdf,
+        graph::query_triples,
+        graph::get_graph_diagram,
         graph::query_graph,
         graph::get_graph_metrics,
         graph::find_similar_structures,
@@ -81,6 +199,11 @@ use auth::auth_middleware;
         webhooks::get_webhook,
         webhooks::update_webhook,
         webhooks::delete_webhook,
+        vectorstore::add_texts,
+        vectorstore::similarity_search,
+        vectorstore::similarity_search_with_score,
+        vectorstore::delete_documents,
+        embeddings::create_embeddings,
     ),
     components(
         schemas(
@@ -90,7 +213,41 @@ use auth::auth_middleware;
             auth_endpoints::UserDto,
             auth_endpoints::CreateUserRequest,
             auth_endpoints::UpdateUserRequest,
+            crate::jobs::Job,
+            crate::jobs::JobKind,
+            crate::jobs::JobStatus,
+            crate::jobs::JobProgress,
+            crate::jobs::JobRequest,
+            admin::CompactRequest,
+            admin::RetentionRequest,
+            admin::RepairVersionsRequest,
+            crate::sharing::ShareGrant,
+            crate::sharing::ShareRole,
+            shares::CreateShareRequest,
             batch::BatchRequest,
+            changes::ChangeDto,
+            changes::ChangesResponse,
+            collections::CollectionDto,
+            collections::CollectionQueryDto,
+            collections::CreateCollectionRequest,
+            collections::UpdateCollectionRequest,
+            collections::AddCollectionMemoriesRequest,
+            reminders::ReminderDto,
+            reminders::SetReminderRequest,
+            reminders::SnoozeReminderRequest,
+            tasks::TaskDto,
+            tasks::CreateTaskRequest,
+            tasks::UpdateTaskRequest,
+            tasks::AddDependencyRequest,
+            procedures::ProcedureDto,
+            procedures::ProcedureStepDto,
+            procedures::ProcedureMatchDto,
+            procedures::CreateProcedureRequest,
+            preferences::PreferenceDto,
+            preferences::PreferenceChangeDto,
+            preferences::SetPreferenceRequest,
+            personas::PersonaDto,
+            personas::SetPersonaRequest,
             dto::MemoryDto,
             dto::CreateMemoryRequest,
             dto::UpdateMemoryRequest,
@@ -104,10 +261,45 @@ use auth::auth_middleware;
             dto::CheckoutVersionRequest,
             dto::MemoryGraphDto,
             dto::MemoryPathDto,
+            dto::EntityProfileDto,
+            dto::RelationshipSummaryDto,
+            dto::SentimentTrendDto,
+            dto::SentimentPointDto,
+            dto::CoOccurrenceDto,
+            dto::GraphDiffDto,
+            dto::GraphDeltaDto,
+            dto::GraphChangeDto,
+            dto::RecordVersionDto,
+            dto::RecordDiffDto,
+            dto::FieldChangeDto,
+            dto::SubgraphRequest,
+            dto::SubgraphDto,
+            dto::SubgraphNodeDto,
+            dto::SubgraphEdgeDto,
+            dto::TripleQueryRequest,
+            dto::TripleQueryResultDto,
             dto::SearchRequest,
             dto::SearchResultDto,
+            dto::SearchStageDto,
+            dto::BatchSearchResultDto,
+            memories::BatchSearchRequest,
             dto::ScoringConfigDto,
             dto::DecayFunctionDto,
+            scoring_profiles::ScoringProfileDto,
+            rules::RuleDto,
+            rules::SetRuleRequest,
+            rules::TestRuleRequest,
+            rules::RuleMessageDto,
+            rules::RuleFailureDto,
+            rules::RuleOutcomeDto,
+            review::ReviewItemDto,
+            notifications::NotificationTargetDto,
+            notifications::SetNotificationTargetRequest,
+            notifications::NotificationRuleDto,
+            notifications::SetNotificationRuleRequest,
+            usage::UsageRecordDto,
+            scratchpad::PutScratchpadRequest,
+            crate::hot_state::ScratchpadEntry,
             dto::GraphQueryRequest,
             dto::GraphMetricsDto,
             dto::GraphMetadata,
@@ -119,23 +311,56 @@ use auth::auth_middleware;
             dto::CentralMemoryDto,
             dto::PaginationParams,
             dto::ErrorResponse,
+            quotas::QuotaUsageDto,
             relationship_types::RegisterTypeRequest,
             relationship_types::RelationshipTypeResponse,
             relationship_types::MetricsResponse,
             relationship_types::SeedResponse,
+            vectorstore::AddTextsRequest,
+            vectorstore::AddTextsResponse,
+            vectorstore::SimilaritySearchRequest,
+            vectorstore::DocumentDto,
+            vectorstore::ScoredDocumentDto,
+            vectorstore::DeleteDocumentsRequest,
+            vectorstore::DeleteDocumentsResponse,
+            embeddings::EmbeddingInput,
+            embeddings::EmbeddingsRequest,
+            embeddings::EmbeddingsResponse,
+            embeddings::EmbeddingData,
+            embeddings::EmbeddingUsage,
         )
     ),
     tags(
         (name = "auth", description = "Authentication and user management endpoints"),
+        (name = "admin", description = "Maintenance jobs for operators (admin role)"),
+        (name = "jobs", description = "Background jobs with progress and cancellation"),
+        (name = "shares", description = "Sharing memories and collections between users"),
         (name = "batch", description = "Batch operations for bulk memory and relationship operations"),
         (name = "memories", description = "Memory management endpoints"),
+        (name = "scoring-profiles", description = "Named scoring configurations searches can select"),
+        (name = "rules", description = "Scripted automation rules run on memory events"),
+        (name = "review", description = "Memories waiting for approval before they're stored (admin role)"),
+        (name = "notifications", description = "Where notifications about memory events go (admin role)"),
+        (name = "usage", description = "Usage per tenant for billing, with CSV export"),
+        (name = "pins", description = "Memories that lead search results"),
+        (name = "scratchpad", description = "Short-lived working state per user, shared between replicas"),
+        (name = "reminders", description = "Time-triggered memory surfacing"),
+        (name = "tasks", description = "Task memories with status changes and dependencies"),
+        (name = "procedures", description = "Structured how-to memories, found by goal"),
+        (name = "preferences", description = "Key-value preferences per scope, with history"),
+        (name = "personas", description = "Agent identity, traits, writing style and constraints"),
+        (name = "collections", description = "Named groups of memories, by ID or by query"),
         (name = "entities", description = "Manual entity management endpoints (CRUD operations)"),
         (name = "relationships", description = "Relationship management endpoints"),
         (name = "relationship-types", description = "Dynamic relationship type management endpoints"),
+        (name = "quotas", description = "Storage quota usage per tenant or agent"),
         (name = "versions", description = "Version management endpoints"),
+        (name = "changes", description = "Resumable changefeed of memory, entity and relationship mutations"),
         (name = "graph", description = "Graph operations and traversal endpoints"),
         (name = "websocket", description = "WebSocket real-time updates"),
         (name = "webhooks", description = "Webhook management endpoints"),
+        (name = "vectorstore", description = "LangChain/LlamaIndex compatible vector store endpoints (opt-in)"),
+        (name = "embeddings", description = "OpenAI-compatible embedding proxy with caching (opt-in)"),
     ),
     info(
                     title = "Locai Memory Service API",
@@ -159,7 +384,7 @@ pub struct ApiDoc;
 /// Create the main router with all API endpoints
 pub fn create_router(state: Arc<AppState>) -> Router {
     // v1 API router with all endpoints under /v1
-    let v1_router = Router::new()
+    let mut v1_router = Router::new()
         // Authentication endpoints (public, no auth middleware)
         .route("/auth/signup", post(auth_endpoints::signup))
         .route("/auth/login", post(auth_endpoints::login))
@@ -167,8 +392,42 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/auth/users/{id}", get(auth_endpoints::get_user))
         .route("/auth/users/{id}", put(auth_endpoints::update_user))
         .route("/auth/users/{id}", delete(auth_endpoints::delete_user))
+        // Admin maintenance endpoints
+        .route("/admin/compact", post(admin::compact))
+        .route("/admin/reindex", post(admin::reindex))
+        .route("/admin/retention", post(admin::run_retention))
+        .route("/admin/versions/repair", post(admin::repair_versions))
+        .route("/admin/cache/clear", post(admin::clear_caches))
+        // Background job endpoints
+        .route("/jobs", post(jobs::submit_job))
+        .route("/jobs", get(jobs::list_jobs))
+        .route("/jobs/{id}", get(jobs::get_job))
+        .route("/jobs/{id}/cancel", post(jobs::cancel_job))
+        // Share grant endpoints
+        .route("/shares", post(shares::create_share))
+        .route("/shares", get(shares::list_shares))
+        .route("/shares/{id}", delete(shares::delete_share))
         // Batch operations endpoint
         .route("/batch", post(batch::batch_execute))
+        .route("/changes", get(changes::list_changes))
+        // Collection endpoints
+        .route("/collections", post(collections::create_collection))
+        .route("/collections", get(collections::list_collections))
+        .route("/collections/{id}", get(collections::get_collection))
+        .route("/collections/{id}", put(collections::update_collection))
+        .route("/collections/{id}", delete(collections::delete_collection))
+        .route(
+            "/collections/{id}/memories",
+            get(collections::list_collection_memories),
+        )
+        .route(
+            "/collections/{id}/memories",
+            post(collections::add_collection_memories),
+        )
+        .route(
+            "/collections/{id}/memories/{memory_id}",
+            delete(collections::remove_collection_memory),
+        )
         // Memory endpoints
         .route("/memories", post(memories::create_memory))
         .route("/memories", get(memories::list_memories))
@@ -176,6 +435,130 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/memories/{id}", put(memories::update_memory))
         .route("/memories/{id}", delete(memories::delete_memory))
         .route("/memories/search", get(memories::search_memories))
+        .route(
+            "/memories/search/stream",
+            get(memories::search_memories_stream),
+        )
+        .route(
+            "/memories/search/batch",
+            post(memories::search_memories_batch),
+        )
+        // Scoring profile endpoints
+        .route(
+            "/scoring-profiles",
+            get(scoring_profiles::list_scoring_profiles),
+        )
+        .route(
+            "/scoring-profiles/{name}",
+            get(scoring_profiles::get_scoring_profile)
+                .put(scoring_profiles::set_scoring_profile)
+                .delete(scoring_profiles::remove_scoring_profile),
+        )
+        // Automation rule endpoints
+        .route("/rules", get(rules::list_rules))
+        .route("/rules/test", post(rules::test_rule))
+        .route(
+            "/rules/{name}",
+            get(rules::get_rule)
+                .put(rules::set_rule)
+                .delete(rules::remove_rule),
+        )
+        // Review queue endpoints
+        .route("/review", get(review::list_reviews))
+        .route("/review/{id}", get(review::get_review))
+        .route("/review/{id}/approve", post(review::approve_memory))
+        .route("/review/{id}/reject", post(review::reject_memory))
+        // Notification endpoints
+        .route(
+            "/notifications/targets",
+            get(notifications::list_notification_targets),
+        )
+        .route(
+            "/notifications/targets/{name}",
+            get(notifications::get_notification_target)
+                .put(notifications::set_notification_target)
+                .delete(notifications::remove_notification_target),
+        )
+        .route(
+            "/notifications/targets/{name}/test",
+            post(notifications::test_notification_target),
+        )
+        .route(
+            "/notifications/rules",
+            get(notifications::list_notification_rules),
+        )
+        .route(
+            "/notifications/rules/{name}",
+            get(notifications::get_notification_rule)
+                .put(notifications::set_notification_rule)
+                .delete(notifications::remove_notification_rule),
+        )
+        // Usage accounting endpoints
+        .route("/usage", get(usage::get_usage))
+        .route("/usage/export", get(usage::export_usage))
+        // Pin endpoints
+        .route("/memories/{id}/pin", post(pins::pin_memory))
+        .route("/memories/{id}/pin", delete(pins::unpin_memory))
+        .route("/pins", get(pins::list_pins))
+        // Working-memory scratchpad
+        .route("/scratchpad", get(scratchpad::list_scratchpad))
+        .route(
+            "/scratchpad/{name}",
+            get(scratchpad::get_scratchpad_entry)
+                .put(scratchpad::put_scratchpad_entry)
+                .delete(scratchpad::delete_scratchpad_entry),
+        )
+        // Reminder endpoints
+        .route(
+            "/memories/{id}/reminder",
+            get(reminders::get_reminder)
+                .put(reminders::set_reminder)
+                .delete(reminders::cancel_reminder),
+        )
+        .route(
+            "/memories/{id}/reminder/snooze",
+            post(reminders::snooze_reminder),
+        )
+        .route("/reminders", get(reminders::list_reminders))
+        // Task endpoints
+        .route("/tasks", get(tasks::list_tasks).post(tasks::create_task))
+        .route("/tasks/{id}", get(tasks::get_task).put(tasks::update_task))
+        .route(
+            "/tasks/{id}/dependencies",
+            get(tasks::list_dependencies).post(tasks::add_dependency),
+        )
+        .route(
+            "/tasks/{id}/dependencies/{depends_on}",
+            delete(tasks::remove_dependency),
+        )
+        // Procedure endpoints
+        .route(
+            "/procedures",
+            get(procedures::list_procedures).post(procedures::create_procedure),
+        )
+        .route("/procedures/search", get(procedures::find_procedures))
+        .route("/procedures/{id}", get(procedures::get_procedure))
+        // Preference endpoints
+        .route("/preferences", get(preferences::list_preferences))
+        .route(
+            "/preferences/{key}",
+            get(preferences::get_preference)
+                .put(preferences::set_preference)
+                .delete(preferences::remove_preference),
+        )
+        .route(
+            "/preferences/{key}/history",
+            get(preferences::preference_history),
+        )
+        // Persona endpoints
+        .route("/personas", get(personas::list_personas))
+        .route(
+            "/personas/{agent}",
+            get(personas::get_persona)
+                .put(personas::set_persona)
+                .delete(personas::remove_persona),
+        )
+        .route("/personas/{agent}/history", get(personas::persona_history))
         // Memory relationship endpoints
         .route(
             "/memories/{id}/relationships",
@@ -191,6 +574,16 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/entities/{id}/memories",
             get(entities::get_entity_memories),
         )
+        .route("/entities/{id}/profile", get(entities::get_entity_profile))
+        .route(
+            "/entities/{id}/versions",
+            get(entities::list_entity_versions),
+        )
+        .route(
+            "/entities/{id}/versions/diff",
+            get(entities::diff_entity_versions),
+        )
+        .route("/entities/{id}/at", get(entities::get_entity_at_time))
         // Entity relationship endpoints
         .route(
             "/entities/{id}/relationships",
@@ -212,6 +605,18 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/relationships/{id}/related",
             get(relationships::find_related_entities),
         )
+        .route(
+            "/relationships/{id}/versions",
+            get(relationships::list_relationship_versions),
+        )
+        .route(
+            "/relationships/{id}/versions/diff",
+            get(relationships::diff_relationship_versions),
+        )
+        .route(
+            "/relationships/{id}/at",
+            get(relationships::get_relationship_at_time),
+        )
         // Relationship type endpoints
         .route(
             "/relationship-types",
@@ -241,6 +646,9 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/relationship-types/seed",
             post(relationship_types::seed_common_types),
         )
+        // Quota endpoints
+        .route("/quotas", get(quotas::list_quota_usage))
+        .route("/quotas/{owner}", get(quotas::get_quota_usage))
         // Version endpoints
         .route("/versions", get(versions::list_versions))
         .route("/versions", post(versions::create_version))
@@ -249,6 +657,11 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/memories/{id}/graph", get(graph::get_memory_graph))
         .route("/entities/{id}/graph", get(graph::get_entity_graph))
         .route("/graph/paths", get(graph::find_paths))
+        .route("/graph/diff", get(graph::get_graph_diff))
+        .route("/graph/subgraph", post(graph::extract_subgraph))
+        .route("/graph/export", get(graph::export_rdf))
+        .route("/graph/triples", post(graph::query_triples))
+        .route("/graph/diagram", get(graph::get_graph_diagram))
         .route("/graph/query", post(graph::query_graph))
         .route("/graph/metrics", get(graph::get_graph_metrics))
         .route(
@@ -271,6 +684,35 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/messaging/ws", get(messaging_websocket_handler))
         // Health check endpoint (with capability reporting)
         .route("/health", get(health_check))
+        // Readiness, once the startup warmup has finished
+        .route("/ready", get(readiness_check));
+
+    // Optional LangChain/LlamaIndex compatible vector store interface
+    if state.config.enable_vectorstore_compat {
+        v1_router = v1_router.merge(vectorstore::routes());
+    }
+
+    // Optional OpenAI-compatible embedding proxy
+    if state.config.embedding_proxy.enabled {
+        v1_router = v1_router.route("/embeddings", post(embeddings::create_embeddings));
+    }
+
+    // Optional replication endpoint for peer instances
+    if state.config.replication.enabled {
+        v1_router = v1_router.route("/replication/ws", get(replication::replication_websocket));
+    }
+
+    let v1_router = v1_router
+        // Usage per tenant, counted once the request is authenticated
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            usage_middleware,
+        ))
+        // Rate limiting and search cache invalidation, after authentication
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            hot_state_middleware,
+        ))
         // Add authentication middleware if enabled
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
@@ -285,10 +727,28 @@ pub fn create_router(state: Arc<AppState>) -> Router {
     // Main router with both versioned and legacy paths
     let swagger_router = SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi());
 
-    Router::new()
+    let mut router = Router::new()
         .nest("/api/v1", v1_router) // Primary versioned API
         .nest("/api", legacy_router) // Backward compatible non-versioned API
-        .merge(swagger_router)
+        .merge(swagger_router);
+
+    // Expose the embedding proxy at /v1 too, so OpenAI SDKs can use the server as base_url
+    if state.config.embedding_proxy.enabled {
+        let openai_router = Router::new()
+            .route("/embeddings", post(embeddings::create_embeddings))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                usage_middleware,
+            ))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                auth_middleware,
+            ))
+            .with_state(state.clone());
+        router = router.nest("/v1", openai_router);
+    }
+
+    router
 }
 
 /// Health check endpoint with capability reporting
@@ -303,6 +763,7 @@ pub fn create_router(state: Arc<AppState>) -> Router {
 async fn health_check(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     let capabilities = serde_json::json!({
         "status": "OK",
+        "replica": state.events.replica_id(),
         "capabilities": {
             "text_search": true,
             "vector_search": state.memory_manager.has_ml_service(),
@@ -317,6 +778,16 @@ async fn health_check(State(state): State<Arc<AppState>>) -> Json<serde_json::Va
             "dynamic_relationship_types": true,  // RFC 001: Dynamic type registration
             "graph_operations": true,
             "messaging": state.messaging_server.is_some(),
+            "vectorstore_compat": state.config.enable_vectorstore_compat,
+            "embedding_proxy": state.embedding_proxy.as_ref().map(|proxy| serde_json::json!({
+                "model": proxy.config().model,
+                "auto_attach": proxy.auto_attach(),
+                "cache": proxy.stats(),
+            })),
+            "search_cache": state.memory_manager.search_cache_stats(),
+            "hot_state": state.hot_state.backend_name(),
+            "warmup": state.warmup.get(),
+            "degraded_storage": state.memory_manager.degraded_status(),
             "authentication": state.config.enable_auth
         },
         "search_modes": {
@@ -337,6 +808,34 @@ async fn health_check(State(state): State<Arc<AppState>>) -> Json<serde_json::Va
     Json(capabilities)
 }
 
+/// Readiness endpoint: 503 until the startup index warmup has finished
+#[utoipa::path(
+    get,
+    path = "/api/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready for queries", body = serde_json::Value),
+        (status = 503, description = "Still warming search indexes", body = serde_json::Value)
+    )
+)]
+async fn readiness_check(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let ready = state.is_ready();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(serde_json::json!({
+            "ready": ready,
+            "warmup": state.warmup.get(),
+        })),
+    )
+}
+
 /// Messaging WebSocket handler
 async fn messaging_websocket_handler(
     ws: axum::extract::ws::WebSocketUpgrade,
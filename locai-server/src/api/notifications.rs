@@ -0,0 +1,373 @@
+//! Notification endpoints
+//!
+//! Notification targets are where notifications about anomalies, review
+//! queue items and reminders go; rules pick which notifications go to which
+//! targets. Those in the `notifications` section of the Locai configuration
+//! are served from startup; changes made here last until the server
+//! restarts. Every endpoint needs the `admin` role, since targets carry
+//! webhook URLs and headers.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use locai::config::{NotificationChannel, NotificationRule, NotificationTarget};
+use locai::notifications::NotificationKind;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{auth::AuthContext, auth::require_role},
+    error::{ServerError, ServerResult, not_found},
+    state::AppState,
+};
+
+/// Where notifications can be sent
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationTargetDto {
+    pub name: String,
+
+    /// "slack" or "http"
+    pub channel: String,
+
+    /// Slack webhook URL, or the endpoint notifications are POSTed to
+    pub url: String,
+
+    /// Extra request headers (http targets only)
+    pub headers: HashMap<String, String>,
+}
+
+impl From<NotificationTarget> for NotificationTargetDto {
+    fn from(target: NotificationTarget) -> Self {
+        let (channel, url, headers) = match target.channel {
+            NotificationChannel::Slack { webhook_url } => ("slack", webhook_url, HashMap::new()),
+            NotificationChannel::Http { url, headers } => ("http", url, headers),
+        };
+        Self {
+            name: target.name,
+            channel: channel.to_string(),
+            url,
+            headers,
+        }
+    }
+}
+
+/// Request to set a notification target
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetNotificationTargetRequest {
+    /// "slack" or "http"
+    pub channel: String,
+
+    /// Slack webhook URL, or the endpoint to POST notifications to
+    pub url: String,
+
+    /// Extra request headers (http targets only)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// Which notifications go to which targets
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationRuleDto {
+    pub name: String,
+
+    /// Kinds of notification sent: "anomaly", "review" or "reminder"
+    pub events: Vec<String>,
+
+    /// Names of the targets to send them to
+    pub targets: Vec<String>,
+
+    /// Tags the notification's memory must all have
+    pub tags: Vec<String>,
+}
+
+impl From<NotificationRule> for NotificationRuleDto {
+    fn from(rule: NotificationRule) -> Self {
+        Self {
+            name: rule.name,
+            events: rule.events.iter().map(|kind| kind.to_string()).collect(),
+            targets: rule.targets,
+            tags: rule.tags,
+        }
+    }
+}
+
+/// Request to set a notification rule
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetNotificationRuleRequest {
+    /// Kinds of notification to send: "anomaly", "review" or "reminder"
+    pub events: Vec<String>,
+
+    /// Names of the targets to send them to
+    pub targets: Vec<String>,
+
+    /// Tags the notification's memory must all have
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// List notification targets
+#[utoipa::path(
+    get,
+    path = "/api/notifications/targets",
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Every configured target, by name", body = Vec<NotificationTargetDto>),
+        (status = 403, description = "Caller is not an admin"),
+    )
+)]
+pub async fn list_notification_targets(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<NotificationTargetDto>>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let targets = state
+        .memory_manager
+        .notifications()
+        .targets()
+        .into_iter()
+        .map(NotificationTargetDto::from)
+        .collect();
+    Ok(Json(targets))
+}
+
+/// Get a notification target
+#[utoipa::path(
+    get,
+    path = "/api/notifications/targets/{name}",
+    tag = "notifications",
+    params(("name" = String, Path, description = "Target name")),
+    responses(
+        (status = 200, description = "The target", body = NotificationTargetDto),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No target has this name"),
+    )
+)]
+pub async fn get_notification_target(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<Json<NotificationTargetDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let target = state
+        .memory_manager
+        .notifications()
+        .target(&name)
+        .ok_or_else(|| not_found("Notification target", &name))?;
+    Ok(Json(target.into()))
+}
+
+/// Set a notification target, replacing any with the same name
+#[utoipa::path(
+    put,
+    path = "/api/notifications/targets/{name}",
+    tag = "notifications",
+    params(("name" = String, Path, description = "Target name")),
+    request_body = SetNotificationTargetRequest,
+    responses(
+        (status = 200, description = "Target set", body = NotificationTargetDto),
+        (status = 400, description = "Unknown channel or invalid url"),
+        (status = 403, description = "Caller is not an admin"),
+    )
+)]
+pub async fn set_notification_target(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+    Json(request): Json<SetNotificationTargetRequest>,
+) -> ServerResult<Json<NotificationTargetDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let channel = match request.channel.as_str() {
+        "slack" => NotificationChannel::Slack {
+            webhook_url: request.url,
+        },
+        "http" => NotificationChannel::Http {
+            url: request.url,
+            headers: request.headers,
+        },
+        other => {
+            return Err(ServerError::BadRequest(format!(
+                "Unknown notification channel '{}'; expected slack or http",
+                other
+            )));
+        }
+    };
+    let target = NotificationTarget { name, channel };
+    state
+        .memory_manager
+        .notifications()
+        .set_target(target.clone())?;
+    Ok(Json(target.into()))
+}
+
+/// Remove a notification target
+#[utoipa::path(
+    delete,
+    path = "/api/notifications/targets/{name}",
+    tag = "notifications",
+    params(("name" = String, Path, description = "Target name")),
+    responses(
+        (status = 204, description = "Target removed"),
+        (status = 400, description = "A rule still sends to the target"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No target has this name"),
+    )
+)]
+pub async fn remove_notification_target(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<StatusCode> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    if state.memory_manager.notifications().remove_target(&name)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found("Notification target", &name))
+    }
+}
+
+/// Send a test notification to a target
+#[utoipa::path(
+    post,
+    path = "/api/notifications/targets/{name}/test",
+    tag = "notifications",
+    params(("name" = String, Path, description = "Target name")),
+    responses(
+        (status = 204, description = "The target accepted the notification"),
+        (status = 400, description = "The notification couldn't be sent"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No target has this name"),
+    )
+)]
+pub async fn test_notification_target(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<StatusCode> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let notifications = state.memory_manager.notifications();
+    if notifications.target(&name).is_none() {
+        return Err(not_found("Notification target", &name));
+    }
+    notifications.test(&name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List notification rules
+#[utoipa::path(
+    get,
+    path = "/api/notifications/rules",
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Every rule, by name", body = Vec<NotificationRuleDto>),
+        (status = 403, description = "Caller is not an admin"),
+    )
+)]
+pub async fn list_notification_rules(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<NotificationRuleDto>>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let rules = state
+        .memory_manager
+        .notifications()
+        .rules()
+        .into_iter()
+        .map(NotificationRuleDto::from)
+        .collect();
+    Ok(Json(rules))
+}
+
+/// Get a notification rule
+#[utoipa::path(
+    get,
+    path = "/api/notifications/rules/{name}",
+    tag = "notifications",
+    params(("name" = String, Path, description = "Rule name")),
+    responses(
+        (status = 200, description = "The rule", body = NotificationRuleDto),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No rule has this name"),
+    )
+)]
+pub async fn get_notification_rule(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<Json<NotificationRuleDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let rule = state
+        .memory_manager
+        .notifications()
+        .rule(&name)
+        .ok_or_else(|| not_found("Notification rule", &name))?;
+    Ok(Json(rule.into()))
+}
+
+/// Set a notification rule, replacing any with the same name
+#[utoipa::path(
+    put,
+    path = "/api/notifications/rules/{name}",
+    tag = "notifications",
+    params(("name" = String, Path, description = "Rule name")),
+    request_body = SetNotificationRuleRequest,
+    responses(
+        (status = 200, description = "Rule set", body = NotificationRuleDto),
+        (status = 400, description = "Unknown event or target"),
+        (status = 403, description = "Caller is not an admin"),
+    )
+)]
+pub async fn set_notification_rule(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+    Json(request): Json<SetNotificationRuleRequest>,
+) -> ServerResult<Json<NotificationRuleDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let events = request
+        .events
+        .iter()
+        .map(|event| event.parse::<NotificationKind>())
+        .collect::<locai::Result<Vec<_>>>()?;
+    let rule = NotificationRule {
+        name,
+        events,
+        targets: request.targets,
+        tags: request.tags,
+    };
+    state
+        .memory_manager
+        .notifications()
+        .set_rule(rule.clone())?;
+    Ok(Json(rule.into()))
+}
+
+/// Remove a notification rule
+#[utoipa::path(
+    delete,
+    path = "/api/notifications/rules/{name}",
+    tag = "notifications",
+    params(("name" = String, Path, description = "Rule name")),
+    responses(
+        (status = 204, description = "Rule removed"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No rule has this name"),
+    )
+)]
+pub async fn remove_notification_rule(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<StatusCode> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    if state.memory_manager.notifications().remove_rule(&name) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found("Notification rule", &name))
+    }
+}
@@ -0,0 +1,248 @@
+//! Persona endpoints
+//!
+//! A persona is an agent's identity, traits, writing style and constraints,
+//! kept as a memory of type `identity`. Each agent has one persona; setting it
+//! again overwrites it, and the earlier revisions are kept as versions of the
+//! persona's memory.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::Persona;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult, not_found},
+    sharing::{Access, OWNER_PROPERTY},
+    state::AppState,
+};
+
+/// An agent's persona
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonaDto {
+    /// ID of the persona's memory
+    pub id: String,
+    pub agent: String,
+    /// Who the agent is
+    pub identity: String,
+    pub traits: Vec<String>,
+    /// How the agent writes
+    pub writing_style: Option<String>,
+    /// What the agent must or must not do
+    pub constraints: Vec<String>,
+    /// When the persona was set; for a revision, when it was made
+    pub updated_at: DateTime<Utc>,
+    /// The persona as text, as it appears in built contexts
+    pub rendered: String,
+}
+
+impl From<Persona> for PersonaDto {
+    fn from(persona: Persona) -> Self {
+        let rendered = persona.render();
+        Self {
+            id: persona.id,
+            agent: persona.agent,
+            identity: persona.identity,
+            traits: persona.traits,
+            writing_style: persona.writing_style,
+            constraints: persona.constraints,
+            updated_at: persona.updated_at,
+            rendered,
+        }
+    }
+}
+
+/// Request to set an agent's persona
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetPersonaRequest {
+    pub identity: String,
+    #[serde(default)]
+    pub traits: Vec<String>,
+    pub writing_style: Option<String>,
+    #[serde(default)]
+    pub constraints: Vec<String>,
+}
+
+/// List every agent's persona
+#[utoipa::path(
+    get,
+    path = "/api/personas",
+    tag = "personas",
+    responses(
+        (status = 200, description = "Personas, by agent", body = Vec<PersonaDto>),
+    )
+)]
+pub async fn list_personas(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<PersonaDto>>> {
+    let mut personas = Vec::new();
+    for persona in state.memory_manager.list_personas().await? {
+        if access(&state, auth.as_deref(), &persona.id).await? > Access::None {
+            personas.push(PersonaDto::from(persona));
+        }
+    }
+    Ok(Json(personas))
+}
+
+/// Get an agent's persona
+#[utoipa::path(
+    get,
+    path = "/api/personas/{agent}",
+    tag = "personas",
+    params(("agent" = String, Path, description = "Agent ID")),
+    responses(
+        (status = 200, description = "The agent's persona", body = PersonaDto),
+        (status = 404, description = "The agent has no persona"),
+    )
+)]
+pub async fn get_persona(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(agent): Path<String>,
+) -> ServerResult<Json<PersonaDto>> {
+    let persona = state
+        .memory_manager
+        .get_persona(&agent)
+        .await?
+        .ok_or_else(|| not_found("Persona", &agent))?;
+    if access(&state, auth.as_deref(), &persona.id).await? == Access::None {
+        return Err(not_found("Persona", &agent));
+    }
+    Ok(Json(persona.into()))
+}
+
+/// Set an agent's persona
+#[utoipa::path(
+    put,
+    path = "/api/personas/{agent}",
+    tag = "personas",
+    params(("agent" = String, Path, description = "Agent ID")),
+    request_body = SetPersonaRequest,
+    responses(
+        (status = 200, description = "Persona set", body = PersonaDto),
+        (status = 400, description = "Empty identity"),
+        (status = 403, description = "The persona is shared with the caller read-only"),
+    )
+)]
+pub async fn set_persona(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(agent): Path<String>,
+    Json(request): Json<SetPersonaRequest>,
+) -> ServerResult<Json<PersonaDto>> {
+    let manager = &state.memory_manager;
+    let existing = manager.get_persona(&agent).await?;
+    if let Some(existing) = &existing
+        && access(&state, auth.as_deref(), &existing.id).await? < Access::Write
+    {
+        return Err(ServerError::Forbidden(
+            "The persona is shared with you read-only".to_string(),
+        ));
+    }
+
+    let persona = manager
+        .set_persona(Persona {
+            traits: request.traits,
+            writing_style: request.writing_style,
+            constraints: request.constraints,
+            ..Persona::new(agent, request.identity)
+        })
+        .await?;
+
+    // With authentication, a new persona belongs to its creator like any memory
+    if existing.is_none()
+        && let Some(user) = auth.as_deref()
+        && let Some(mut memory) = manager.get_memory(&persona.id).await?
+    {
+        memory.set_property(OWNER_PROPERTY, user.user_id.to_string().into());
+        manager.update_memory(memory).await?;
+    }
+    Ok(Json(persona.into()))
+}
+
+/// Remove an agent's persona
+#[utoipa::path(
+    delete,
+    path = "/api/personas/{agent}",
+    tag = "personas",
+    params(("agent" = String, Path, description = "Agent ID")),
+    responses(
+        (status = 204, description = "Persona removed"),
+        (status = 403, description = "The persona is shared with the caller read-only"),
+        (status = 404, description = "The agent has no persona"),
+    )
+)]
+pub async fn remove_persona(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(agent): Path<String>,
+) -> ServerResult<StatusCode> {
+    let existing = state
+        .memory_manager
+        .get_persona(&agent)
+        .await?
+        .ok_or_else(|| not_found("Persona", &agent))?;
+    match access(&state, auth.as_deref(), &existing.id).await? {
+        Access::None => return Err(not_found("Persona", &agent)),
+        Access::Read => {
+            return Err(ServerError::Forbidden(
+                "The persona is shared with you read-only".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    state.memory_manager.remove_persona(&agent).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the revisions of an agent's persona, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/personas/{agent}/history",
+    tag = "personas",
+    params(("agent" = String, Path, description = "Agent ID")),
+    responses(
+        (status = 200, description = "Revisions of the persona", body = Vec<PersonaDto>),
+        (status = 404, description = "The agent has no persona"),
+    )
+)]
+pub async fn persona_history(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(agent): Path<String>,
+) -> ServerResult<Json<Vec<PersonaDto>>> {
+    let manager = &state.memory_manager;
+    let existing = manager
+        .get_persona(&agent)
+        .await?
+        .ok_or_else(|| not_found("Persona", &agent))?;
+    if access(&state, auth.as_deref(), &existing.id).await? == Access::None {
+        return Err(not_found("Persona", &agent));
+    }
+
+    let history = manager.persona_history(&agent).await?;
+    Ok(Json(history.into_iter().map(Into::into).collect()))
+}
+
+async fn access(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    id: &str,
+) -> Result<Access, ServerError> {
+    Ok(state
+        .memory_manager
+        .get_memory(id)
+        .await?
+        .map(|memory| state.shares.access(&memory, auth))
+        .unwrap_or(Access::None))
+}
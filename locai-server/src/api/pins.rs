@@ -0,0 +1,130 @@
+//! Pinned memory endpoints
+//!
+//! Pinned memories lead search results whatever their score when a search
+//! names a `pin_scope`. Pins without a scope are global and apply to every
+//! scope. Callers can pin the memories they can read.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use locai::memory::GLOBAL_PIN_SCOPE;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    api::{auth::AuthContext, dto::MemoryDto},
+    error::{ServerResult, not_found},
+    state::AppState,
+};
+
+/// Which pin list to use
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PinParams {
+    /// Pin scope, such as an agent ID (default: global)
+    pub scope: Option<String>,
+}
+
+impl PinParams {
+    fn scope(&self) -> &str {
+        self.scope.as_deref().unwrap_or(GLOBAL_PIN_SCOPE)
+    }
+}
+
+/// Pin a memory
+#[utoipa::path(
+    post,
+    path = "/api/memories/{id}/pin",
+    tag = "pins",
+    params(
+        ("id" = String, Path, description = "Memory ID"),
+        PinParams
+    ),
+    responses(
+        (status = 204, description = "Memory pinned"),
+        (status = 404, description = "Memory not found"),
+    )
+)]
+pub async fn pin_memory(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Query(params): Query<PinParams>,
+) -> ServerResult<StatusCode> {
+    state
+        .memory_manager
+        .get_memory(&id)
+        .await?
+        .filter(|memory| state.shares.can_read(memory, auth.as_deref()))
+        .ok_or_else(|| not_found("Memory", &id))?;
+
+    state
+        .memory_manager
+        .pin_in_scope(params.scope(), &id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Unpin a memory
+#[utoipa::path(
+    delete,
+    path = "/api/memories/{id}/pin",
+    tag = "pins",
+    params(
+        ("id" = String, Path, description = "Memory ID"),
+        PinParams
+    ),
+    responses(
+        (status = 204, description = "Memory unpinned"),
+        (status = 404, description = "Memory not pinned in this scope"),
+    )
+)]
+pub async fn unpin_memory(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<PinParams>,
+) -> ServerResult<StatusCode> {
+    let unpinned = state
+        .memory_manager
+        .unpin_in_scope(params.scope(), &id)
+        .await?;
+    if !unpinned {
+        return Err(not_found("Pin", &id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the memories pinned for a scope
+///
+/// Global pins come first, then the scope's own, each in the order they
+/// were pinned.
+#[utoipa::path(
+    get,
+    path = "/api/pins",
+    tag = "pins",
+    params(PinParams),
+    responses(
+        (status = 200, description = "Pinned memories", body = Vec<MemoryDto>),
+    )
+)]
+pub async fn list_pins(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<PinParams>,
+) -> ServerResult<Json<Vec<MemoryDto>>> {
+    let memories = state
+        .memory_manager
+        .pinned_memories(params.scope.as_deref())
+        .await?;
+    Ok(Json(
+        memories
+            .into_iter()
+            .filter(|memory| state.shares.can_read(memory, auth.as_deref()))
+            .map(MemoryDto::from)
+            .collect(),
+    ))
+}
@@ -0,0 +1,281 @@
+//! Preference endpoints
+//!
+//! Preferences are key-value settings per scope, kept as memories of type
+//! `preference`. Preferences without a scope are global and apply to every
+//! scope that doesn't set the same key. Setting a key again overwrites it;
+//! the earlier values are kept as versions of the preference's memory.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::{GLOBAL_PREFERENCE_SCOPE, Preference, PreferenceChange};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult, not_found},
+    sharing::{Access, OWNER_PROPERTY},
+    state::AppState,
+};
+
+/// A preference
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreferenceDto {
+    /// ID of the preference's memory
+    pub id: String,
+    pub scope: String,
+    pub key: String,
+    pub value: String,
+    /// When the value was last set
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Preference> for PreferenceDto {
+    fn from(preference: Preference) -> Self {
+        Self {
+            id: preference.id,
+            scope: preference.scope,
+            key: preference.key,
+            value: preference.value,
+            updated_at: preference.updated_at,
+        }
+    }
+}
+
+/// A value a preference had
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PreferenceChangeDto {
+    pub value: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl From<PreferenceChange> for PreferenceChangeDto {
+    fn from(change: PreferenceChange) -> Self {
+        Self {
+            value: change.value,
+            changed_at: change.changed_at,
+        }
+    }
+}
+
+/// Request to set a preference
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetPreferenceRequest {
+    pub value: String,
+}
+
+/// Which preferences to use
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PreferenceParams {
+    /// Preference scope, such as a user or agent ID (default: global)
+    pub scope: Option<String>,
+}
+
+impl PreferenceParams {
+    fn scope(&self) -> &str {
+        self.scope.as_deref().unwrap_or(GLOBAL_PREFERENCE_SCOPE)
+    }
+}
+
+/// List the preferences in effect for a scope
+#[utoipa::path(
+    get,
+    path = "/api/preferences",
+    tag = "preferences",
+    params(PreferenceParams),
+    responses(
+        (status = 200, description = "The scope's preferences and the global ones it doesn't override, by key", body = Vec<PreferenceDto>),
+    )
+)]
+pub async fn list_preferences(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<PreferenceParams>,
+) -> ServerResult<Json<Vec<PreferenceDto>>> {
+    let mut preferences = Vec::new();
+    for preference in state
+        .memory_manager
+        .get_preferences(params.scope.as_deref())
+        .await?
+    {
+        if access(&state, auth.as_deref(), &preference.id).await? > Access::None {
+            preferences.push(PreferenceDto::from(preference));
+        }
+    }
+    Ok(Json(preferences))
+}
+
+/// Get the value in effect for a key
+#[utoipa::path(
+    get,
+    path = "/api/preferences/{key}",
+    tag = "preferences",
+    params(
+        ("key" = String, Path, description = "Preference key"),
+        PreferenceParams
+    ),
+    responses(
+        (status = 200, description = "The scope's value, or else the global one", body = PreferenceDto),
+        (status = 404, description = "Neither the scope nor the global scope sets the key"),
+    )
+)]
+pub async fn get_preference(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(key): Path<String>,
+    Query(params): Query<PreferenceParams>,
+) -> ServerResult<Json<PreferenceDto>> {
+    let preference = state
+        .memory_manager
+        .get_preference(&key, params.scope.as_deref())
+        .await?
+        .ok_or_else(|| not_found("Preference", &key))?;
+    if access(&state, auth.as_deref(), &preference.id).await? == Access::None {
+        return Err(not_found("Preference", &key));
+    }
+    Ok(Json(preference.into()))
+}
+
+/// Set a preference
+#[utoipa::path(
+    put,
+    path = "/api/preferences/{key}",
+    tag = "preferences",
+    params(
+        ("key" = String, Path, description = "Preference key"),
+        PreferenceParams
+    ),
+    request_body = SetPreferenceRequest,
+    responses(
+        (status = 200, description = "Preference set", body = PreferenceDto),
+        (status = 400, description = "Empty key or scope"),
+        (status = 403, description = "The preference is shared with the caller read-only"),
+    )
+)]
+pub async fn set_preference(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(key): Path<String>,
+    Query(params): Query<PreferenceParams>,
+    Json(request): Json<SetPreferenceRequest>,
+) -> ServerResult<Json<PreferenceDto>> {
+    let manager = &state.memory_manager;
+    let existing = manager.get_scoped_preference(params.scope(), &key).await?;
+    if let Some(existing) = &existing
+        && access(&state, auth.as_deref(), &existing.id).await? < Access::Write
+    {
+        return Err(ServerError::Forbidden(
+            "The preference is shared with you read-only".to_string(),
+        ));
+    }
+
+    let preference = manager
+        .set_scoped_preference(params.scope(), &key, &request.value)
+        .await?;
+
+    // With authentication, a new preference belongs to its creator like any memory
+    if existing.is_none()
+        && let Some(user) = auth.as_deref()
+        && let Some(mut memory) = manager.get_memory(&preference.id).await?
+    {
+        memory.set_property(OWNER_PROPERTY, user.user_id.to_string().into());
+        manager.update_memory(memory).await?;
+    }
+    Ok(Json(preference.into()))
+}
+
+/// Remove a scope's value for a key
+#[utoipa::path(
+    delete,
+    path = "/api/preferences/{key}",
+    tag = "preferences",
+    params(
+        ("key" = String, Path, description = "Preference key"),
+        PreferenceParams
+    ),
+    responses(
+        (status = 204, description = "Preference removed"),
+        (status = 403, description = "The preference is shared with the caller read-only"),
+        (status = 404, description = "The scope doesn't set the key"),
+    )
+)]
+pub async fn remove_preference(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(key): Path<String>,
+    Query(params): Query<PreferenceParams>,
+) -> ServerResult<StatusCode> {
+    let existing = state
+        .memory_manager
+        .get_scoped_preference(params.scope(), &key)
+        .await?
+        .ok_or_else(|| not_found("Preference", &key))?;
+    match access(&state, auth.as_deref(), &existing.id).await? {
+        Access::None => return Err(not_found("Preference", &key)),
+        Access::Read => {
+            return Err(ServerError::Forbidden(
+                "The preference is shared with you read-only".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    state
+        .memory_manager
+        .remove_preference(params.scope(), &key)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the values a scope's key has had, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/preferences/{key}/history",
+    tag = "preferences",
+    params(
+        ("key" = String, Path, description = "Preference key"),
+        PreferenceParams
+    ),
+    responses(
+        (status = 200, description = "Values the key has had in the scope", body = Vec<PreferenceChangeDto>),
+        (status = 404, description = "The scope doesn't set the key"),
+    )
+)]
+pub async fn preference_history(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(key): Path<String>,
+    Query(params): Query<PreferenceParams>,
+) -> ServerResult<Json<Vec<PreferenceChangeDto>>> {
+    let manager = &state.memory_manager;
+    let existing = manager
+        .get_scoped_preference(params.scope(), &key)
+        .await?
+        .ok_or_else(|| not_found("Preference", &key))?;
+    if access(&state, auth.as_deref(), &existing.id).await? == Access::None {
+        return Err(not_found("Preference", &key));
+    }
+
+    let history = manager.preference_history(params.scope(), &key).await?;
+    Ok(Json(history.into_iter().map(Into::into).collect()))
+}
+
+async fn access(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    id: &str,
+) -> Result<Access, ServerError> {
+    Ok(state
+        .memory_manager
+        .get_memory(id)
+        .await?
+        .map(|memory| state.shares.access(&memory, auth))
+        .unwrap_or(Access::None))
+}
@@ -0,0 +1,254 @@
+//! Procedure endpoints
+//!
+//! Procedures are memories of type `procedural` with structured steps,
+//! preconditions and success metrics, so the memory endpoints also read,
+//! search and delete them. These endpoints create procedures from their
+//! structure and find the procedures that reach a goal.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::{Procedure, ProcedureMatch, ProcedureStep};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult, not_found},
+    sharing::OWNER_PROPERTY,
+    state::AppState,
+};
+
+/// One step of a procedure
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProcedureStepDto {
+    /// What to do
+    pub instruction: String,
+    /// What should be true once the step is done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+/// A procedure
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProcedureDto {
+    /// ID of the procedure's memory
+    pub id: String,
+    pub name: String,
+    /// What following the procedure achieves
+    pub goal: String,
+    pub steps: Vec<ProcedureStepDto>,
+    pub preconditions: Vec<String>,
+    pub success_metrics: Vec<String>,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Procedure> for ProcedureDto {
+    fn from(procedure: Procedure) -> Self {
+        Self {
+            id: procedure.id,
+            name: procedure.name,
+            goal: procedure.goal,
+            steps: procedure
+                .steps
+                .into_iter()
+                .map(|step| ProcedureStepDto {
+                    instruction: step.instruction,
+                    expected: step.expected,
+                })
+                .collect(),
+            preconditions: procedure.preconditions,
+            success_metrics: procedure.success_metrics,
+            tags: procedure.tags,
+            created_at: procedure.created_at,
+        }
+    }
+}
+
+/// A procedure found for a goal
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProcedureMatchDto {
+    pub procedure: ProcedureDto,
+    /// Combined score, from 0.0 to 1.0
+    pub score: f32,
+    /// BM25 score relative to the best matching procedure
+    pub text_score: f32,
+    /// Share of the goal's entities the procedure is linked to
+    pub entity_score: f32,
+    /// Names of the goal's entities the procedure is linked to
+    pub matched_entities: Vec<String>,
+}
+
+impl From<ProcedureMatch> for ProcedureMatchDto {
+    fn from(found: ProcedureMatch) -> Self {
+        Self {
+            procedure: found.procedure.into(),
+            score: found.score,
+            text_score: found.text_score,
+            entity_score: found.entity_score,
+            matched_entities: found.matched_entities,
+        }
+    }
+}
+
+/// Request to create a procedure
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateProcedureRequest {
+    pub name: String,
+    pub goal: String,
+    pub steps: Vec<ProcedureStepDto>,
+    #[serde(default)]
+    pub preconditions: Vec<String>,
+    #[serde(default)]
+    pub success_metrics: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct FindProceduresParams {
+    /// What the caller is trying to achieve
+    pub goal: String,
+    /// Maximum number of procedures to return (default 5)
+    pub limit: Option<usize>,
+}
+
+/// Create a procedure
+#[utoipa::path(
+    post,
+    path = "/api/procedures",
+    tag = "procedures",
+    request_body = CreateProcedureRequest,
+    responses(
+        (status = 201, description = "Procedure created", body = ProcedureDto),
+        (status = 400, description = "No name, no steps or an empty step"),
+    )
+)]
+pub async fn create_procedure(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Json(request): Json<CreateProcedureRequest>,
+) -> ServerResult<(StatusCode, Json<ProcedureDto>)> {
+    let mut procedure = Procedure::new(request.name, request.goal);
+    procedure.steps = request
+        .steps
+        .into_iter()
+        .map(|step| ProcedureStep {
+            instruction: step.instruction,
+            expected: step.expected,
+        })
+        .collect();
+    procedure.preconditions = request.preconditions;
+    procedure.success_metrics = request.success_metrics;
+    procedure.tags = request.tags;
+    procedure.validate()?;
+
+    // With authentication, a procedure belongs to its creator like any memory
+    let mut memory = procedure.to_memory();
+    if let Some(user) = auth.as_deref() {
+        memory.set_property(OWNER_PROPERTY, user.user_id.to_string().into());
+    }
+    let id = state.memory_manager.store_memory(memory).await?;
+
+    let procedure = state
+        .memory_manager
+        .get_procedure(&id)
+        .await?
+        .ok_or_else(|| not_found("Procedure", &id))?;
+    Ok((StatusCode::CREATED, Json(procedure.into())))
+}
+
+/// List procedures, by name
+#[utoipa::path(
+    get,
+    path = "/api/procedures",
+    tag = "procedures",
+    responses(
+        (status = 200, description = "Procedures the caller can read", body = Vec<ProcedureDto>),
+    )
+)]
+pub async fn list_procedures(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<ProcedureDto>>> {
+    let mut procedures = Vec::new();
+    for procedure in state.memory_manager.list_procedures().await? {
+        if is_readable(&state, auth.as_deref(), &procedure.id).await? {
+            procedures.push(ProcedureDto::from(procedure));
+        }
+    }
+    Ok(Json(procedures))
+}
+
+/// Find the procedures most likely to reach a goal, best first
+#[utoipa::path(
+    get,
+    path = "/api/procedures/search",
+    tag = "procedures",
+    params(FindProceduresParams),
+    responses(
+        (status = 200, description = "Matching procedures the caller can read", body = Vec<ProcedureMatchDto>),
+    )
+)]
+pub async fn find_procedures(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<FindProceduresParams>,
+) -> ServerResult<Json<Vec<ProcedureMatchDto>>> {
+    let mut matches = Vec::new();
+    for found in state
+        .memory_manager
+        .find_procedures_for(&params.goal, params.limit)
+        .await?
+    {
+        if is_readable(&state, auth.as_deref(), &found.procedure.id).await? {
+            matches.push(ProcedureMatchDto::from(found));
+        }
+    }
+    Ok(Json(matches))
+}
+
+/// Get a procedure
+#[utoipa::path(
+    get,
+    path = "/api/procedures/{id}",
+    tag = "procedures",
+    params(
+        ("id" = String, Path, description = "Procedure ID")
+    ),
+    responses(
+        (status = 200, description = "Procedure found", body = ProcedureDto),
+        (status = 404, description = "Procedure not found"),
+    )
+)]
+pub async fn get_procedure(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<ProcedureDto>> {
+    let memory = state.memory_manager.get_memory(&id).await?;
+    let procedure = memory
+        .filter(|memory| state.shares.can_read(memory, auth.as_deref()))
+        .and_then(|memory| Procedure::from_memory(&memory))
+        .ok_or_else(|| not_found("Procedure", &id))?;
+    Ok(Json(procedure.into()))
+}
+
+async fn is_readable(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    id: &str,
+) -> Result<bool, ServerError> {
+    Ok(state
+        .memory_manager
+        .get_memory(id)
+        .await?
+        .is_some_and(|memory| state.shares.can_read(&memory, auth)))
+}
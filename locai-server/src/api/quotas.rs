@@ -0,0 +1,85 @@
+//! Quota usage endpoints
+//!
+//! `GET /api/quotas` lists what each tenant or agent has stored against its
+//! limits, and `GET /api/quotas/{owner}` reports a single owner. Writes past a
+//! limit fail with `507 Insufficient Storage` and a `quota_exceeded` error.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use locai::memory::quota::QuotaUsage;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{error::ServerResult, state::AppState};
+
+/// Storage used by one owner and its limits; absent limits are unlimited
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuotaUsageDto {
+    /// Tenant or agent the usage counts against
+    pub owner: String,
+    pub memories: u64,
+    /// Bytes of content, tags, properties and embeddings
+    pub bytes: u64,
+    /// Memories with an embedding
+    pub vectors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_memories: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_vectors: Option<u64>,
+}
+
+impl From<QuotaUsage> for QuotaUsageDto {
+    fn from(usage: QuotaUsage) -> Self {
+        Self {
+            owner: usage.owner,
+            memories: usage.memories,
+            bytes: usage.bytes,
+            vectors: usage.vectors,
+            max_memories: usage.limits.max_memories,
+            max_bytes: usage.limits.max_bytes,
+            max_vectors: usage.limits.max_vectors,
+        }
+    }
+}
+
+/// List quota usage of every owner
+#[utoipa::path(
+    get,
+    path = "/api/quotas",
+    tag = "quotas",
+    responses(
+        (status = 200, description = "Usage per owner, largest first", body = Vec<QuotaUsageDto>),
+    )
+)]
+pub async fn list_quota_usage(
+    State(state): State<Arc<AppState>>,
+) -> ServerResult<Json<Vec<QuotaUsageDto>>> {
+    let usage = state.memory_manager.list_quota_usage().await?;
+    Ok(Json(usage.into_iter().map(QuotaUsageDto::from).collect()))
+}
+
+/// Get quota usage of one owner
+#[utoipa::path(
+    get,
+    path = "/api/quotas/{owner}",
+    tag = "quotas",
+    params(
+        ("owner" = String, Path, description = "Tenant or agent name")
+    ),
+    responses(
+        (status = 200, description = "Usage of the owner", body = QuotaUsageDto),
+    )
+)]
+pub async fn get_quota_usage(
+    State(state): State<Arc<AppState>>,
+    Path(owner): Path<String>,
+) -> ServerResult<Json<QuotaUsageDto>> {
+    let usage = state.memory_manager.quota_usage(&owner).await?;
+    Ok(Json(usage.into()))
+}
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use axum::{
-    Json as JsonExtractor,
+    Extension, Json as JsonExtractor,
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
@@ -13,11 +13,17 @@ use serde::Deserialize;
 use utoipa::IntoParams;
 use uuid::Uuid;
 
+use locai::replication::RecordKind;
 use locai::storage::{filters::RelationshipFilter, models::Relationship};
 
 use crate::{
-    api::dto::{CreateRelationshipRequest, EntityDto, RelationshipDto},
+    api::auth::AuthContext,
+    api::dto::{
+        CreateRelationshipRequest, EntityDto, RecordDiffDto, RecordVersionDto, RelationshipDto,
+    },
+    api::entities::{AtTimeParams, RecordDiffParams, diff_record},
     error::{ServerError, ServerResult, not_found},
+    sharing::Access,
     state::AppState,
     websocket::WebSocketMessage,
 };
@@ -59,6 +65,7 @@ fn default_page_size() -> usize {
 )]
 pub async fn list_relationships(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Query(params): Query<ListRelationshipsParams>,
 ) -> ServerResult<Json<Vec<RelationshipDto>>> {
     let memory_manager = &state.memory_manager;
@@ -84,6 +91,7 @@ pub async fn list_relationships(
         .await
         .map_err(|e| ServerError::Internal(format!("Failed to list relationships: {}", e)))?;
 
+    let relationships = readable_relationships(&state, relationships, auth.as_deref()).await?;
     let relationship_dtos: Vec<RelationshipDto> = relationships
         .into_iter()
         .map(RelationshipDto::from)
@@ -107,19 +115,111 @@ pub async fn list_relationships(
 )]
 pub async fn get_relationship(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
 ) -> ServerResult<Json<RelationshipDto>> {
-    let memory_manager = &state.memory_manager;
+    let relationship = load_relationship(&state, &id, auth.as_deref(), Access::Read).await?;
 
-    let relationship = memory_manager
-        .get_relationship(&id)
-        .await
-        .map_err(|e| ServerError::Internal(format!("Failed to get relationship: {}", e)))?
+    Ok(Json(RelationshipDto::from(relationship)))
+}
+
+/// List the versions of a relationship, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/relationships/{id}/versions",
+    tag = "relationships",
+    params(
+        ("id" = String, Path, description = "Relationship ID")
+    ),
+    responses(
+        (status = 200, description = "Relationship versions", body = Vec<RecordVersionDto>),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_relationship_versions(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<Vec<RecordVersionDto>>> {
+    check_history(&state, &id, auth.as_deref()).await?;
+    let versions = state
+        .memory_manager
+        .list_record_versions(RecordKind::Relationship, &id)
+        .await?;
+
+    Ok(Json(
+        versions.into_iter().map(RecordVersionDto::from).collect(),
+    ))
+}
+
+/// Get a relationship as it existed at a point in time
+#[utoipa::path(
+    get,
+    path = "/api/relationships/{id}/at",
+    tag = "relationships",
+    params(
+        ("id" = String, Path, description = "Relationship ID"),
+        AtTimeParams
+    ),
+    responses(
+        (status = 200, description = "Relationship at that time", body = RelationshipDto),
+        (status = 400, description = "Invalid time"),
+        (status = 404, description = "Relationship did not exist at that time"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_relationship_at_time(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Query(params): Query<AtTimeParams>,
+) -> ServerResult<Json<RelationshipDto>> {
+    check_history(&state, &id, auth.as_deref()).await?;
+    let relationship = state
+        .memory_manager
+        .get_relationship_at_time(&id, params.time()?)
+        .await?
         .ok_or_else(|| not_found("Relationship", &id))?;
+    let ends = [
+        relationship.source_id.as_str(),
+        relationship.target_id.as_str(),
+    ];
+    if !state
+        .shares
+        .allows(&state.memory_manager, &ends, auth.as_deref(), Access::Read)
+        .await?
+    {
+        return Err(not_found("Relationship", &id));
+    }
 
     Ok(Json(RelationshipDto::from(relationship)))
 }
 
+/// Diff two versions of a relationship
+#[utoipa::path(
+    get,
+    path = "/api/relationships/{id}/versions/diff",
+    tag = "relationships",
+    params(
+        ("id" = String, Path, description = "Relationship ID"),
+        RecordDiffParams
+    ),
+    responses(
+        (status = 200, description = "Changed fields", body = RecordDiffDto),
+        (status = 404, description = "Version not found for this relationship"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn diff_relationship_versions(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Query(params): Query<RecordDiffParams>,
+) -> ServerResult<Json<RecordDiffDto>> {
+    check_history(&state, &id, auth.as_deref()).await?;
+    Ok(Json(diff_record(&state, &id, &params).await?))
+}
+
 /// Create a new relationship
 #[utoipa::path(
     post,
@@ -212,17 +312,14 @@ pub async fn create_relationship(
 )]
 pub async fn update_relationship(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
     JsonExtractor(request): JsonExtractor<UpdateRelationshipRequest>,
 ) -> ServerResult<Json<RelationshipDto>> {
     let memory_manager = &state.memory_manager;
 
     // Check if relationship exists and get it
-    let mut existing = memory_manager
-        .get_relationship(&id)
-        .await
-        .map_err(|e| ServerError::Internal(format!("Failed to get relationship: {}", e)))?
-        .ok_or_else(|| not_found("Relationship", &id))?;
+    let mut existing = load_relationship(&state, &id, auth.as_deref(), Access::Write).await?;
 
     // Update fields if provided
     if let Some(relationship_type) = request.relationship_type {
@@ -271,16 +368,13 @@ pub async fn update_relationship(
 )]
 pub async fn delete_relationship(
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
     Path(id): Path<String>,
 ) -> ServerResult<StatusCode> {
     let memory_manager = &state.memory_manager;
 
     // Check if relationship exists
-    let _existing = memory_manager
-        .get_relationship(&id)
-        .await
-        .map_err(|e| ServerError::Internal(format!("Failed to get relationship: {}", e)))?
-        .ok_or_else(|| not_found("Relationship", &id))?;
+    load_relationship(&state, &id, auth.as_deref(), Access::Write).await?;
 
     let deleted = memory_manager
         .delete_relationship(&id)
@@ -372,6 +466,77 @@ pub async fn find_related_entities(
     Ok(Json(entity_dtos))
 }
 
+/// Load a relationship, failing unless `user` has at least `needed` access
+/// to the memories it links
+///
+/// A relationship linking a memory the user can't see is reported as not
+/// found.
+async fn load_relationship(
+    state: &AppState,
+    id: &str,
+    user: Option<&AuthContext>,
+    needed: Access,
+) -> ServerResult<Relationship> {
+    let relationship = state
+        .memory_manager
+        .get_relationship(id)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Failed to get relationship: {}", e)))?
+        .ok_or_else(|| not_found("Relationship", id))?;
+
+    let ends = [
+        relationship.source_id.as_str(),
+        relationship.target_id.as_str(),
+    ];
+    let shares = &state.shares;
+    if !shares
+        .allows(&state.memory_manager, &ends, user, Access::Read)
+        .await?
+    {
+        return Err(not_found("Relationship", id));
+    }
+    if !shares
+        .allows(&state.memory_manager, &ends, user, needed)
+        .await?
+    {
+        return Err(ServerError::Forbidden(
+            "The relationship links a memory shared with you read-only".to_string(),
+        ));
+    }
+    Ok(relationship)
+}
+
+/// Fail if the relationship still exists and links a memory `user` can't see
+async fn check_history(state: &AppState, id: &str, user: Option<&AuthContext>) -> ServerResult<()> {
+    if state.memory_manager.get_relationship(id).await?.is_some() {
+        load_relationship(state, id, user, Access::Read).await?;
+    }
+    Ok(())
+}
+
+/// The relationships among `relationships` whose memories `user` can see
+pub(super) async fn readable_relationships(
+    state: &AppState,
+    relationships: Vec<Relationship>,
+    user: Option<&AuthContext>,
+) -> ServerResult<Vec<Relationship>> {
+    let mut readable = Vec::with_capacity(relationships.len());
+    for relationship in relationships {
+        let ends = [
+            relationship.source_id.as_str(),
+            relationship.target_id.as_str(),
+        ];
+        if state
+            .shares
+            .allows(&state.memory_manager, &ends, user, Access::Read)
+            .await?
+        {
+            readable.push(relationship);
+        }
+    }
+    Ok(readable)
+}
+
 /// Request to update a relationship
 #[derive(Debug, serde::Deserialize, utoipa::ToSchema)]
 pub struct UpdateRelationshipRequest {
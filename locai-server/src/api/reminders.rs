@@ -0,0 +1,245 @@
+//! Reminder endpoints
+//!
+//! A memory can have one reminder, once or recurring. The server checks for
+//! due reminders in the background (see [`crate::state::AppState::spawn_reminder_scheduler`])
+//! and announces each one on the `system.reminders` messaging topic and to
+//! `memory.reminder` webhooks. Callers can set reminders on the memories they
+//! can read.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::{Recurrence, Reminder, parse_delay};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult, bad_request, not_found},
+    state::AppState,
+};
+
+/// A memory's reminder
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReminderDto {
+    pub memory_id: String,
+    /// When the reminder is scheduled, ignoring any snooze
+    pub remind_at: DateTime<Utc>,
+    /// When it next fires
+    pub due_at: DateTime<Utc>,
+    /// How often it repeats, such as `weekly` or `every 2h`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    /// Times a recurring reminder has fired
+    pub fired_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_fired_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Reminder> for ReminderDto {
+    fn from(reminder: Reminder) -> Self {
+        Self {
+            due_at: reminder.due_at(),
+            memory_id: reminder.memory_id,
+            remind_at: reminder.remind_at,
+            recurrence: reminder.recurrence.map(|r| r.to_string()),
+            snoozed_until: reminder.snoozed_until,
+            fired_count: reminder.fired_count,
+            last_fired_at: reminder.last_fired_at,
+            created_at: reminder.created_at,
+        }
+    }
+}
+
+/// Request to set a memory's reminder
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetReminderRequest {
+    pub remind_at: DateTime<Utc>,
+    /// `daily`, `weekly`, `monthly` or an interval such as `90m`; omit for a one-off reminder
+    pub recurrence: Option<String>,
+}
+
+/// Request to snooze a reminder; give `until` or `delay`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SnoozeReminderRequest {
+    pub until: Option<DateTime<Utc>>,
+    /// How long to put the reminder off from now, such as `10m`
+    pub delay: Option<String>,
+}
+
+/// Set a memory's reminder, replacing any it had
+#[utoipa::path(
+    put,
+    path = "/api/memories/{id}/reminder",
+    tag = "reminders",
+    params(
+        ("id" = String, Path, description = "Memory ID")
+    ),
+    request_body = SetReminderRequest,
+    responses(
+        (status = 200, description = "Reminder set", body = ReminderDto),
+        (status = 400, description = "Unreadable recurrence"),
+        (status = 404, description = "Memory not found"),
+    )
+)]
+pub async fn set_reminder(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Json(request): Json<SetReminderRequest>,
+) -> ServerResult<Json<ReminderDto>> {
+    check_readable(&state, auth.as_deref(), &id).await?;
+
+    let mut reminder = Reminder::new(&id, request.remind_at);
+    if let Some(recurrence) = &request.recurrence {
+        reminder = reminder.with_recurrence(recurrence.parse::<Recurrence>()?);
+    }
+    let reminder = state.memory_manager.set_reminder(reminder).await?;
+    Ok(Json(reminder.into()))
+}
+
+/// Get a memory's reminder
+#[utoipa::path(
+    get,
+    path = "/api/memories/{id}/reminder",
+    tag = "reminders",
+    params(
+        ("id" = String, Path, description = "Memory ID")
+    ),
+    responses(
+        (status = 200, description = "Reminder found", body = ReminderDto),
+        (status = 404, description = "Memory has no reminder"),
+    )
+)]
+pub async fn get_reminder(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<ReminderDto>> {
+    check_readable(&state, auth.as_deref(), &id).await?;
+
+    let reminder = state
+        .memory_manager
+        .get_reminder(&id)
+        .await?
+        .ok_or_else(|| not_found("Reminder", &id))?;
+    Ok(Json(reminder.into()))
+}
+
+/// Cancel a memory's reminder
+#[utoipa::path(
+    delete,
+    path = "/api/memories/{id}/reminder",
+    tag = "reminders",
+    params(
+        ("id" = String, Path, description = "Memory ID")
+    ),
+    responses(
+        (status = 204, description = "Reminder cancelled"),
+        (status = 404, description = "Memory has no reminder"),
+    )
+)]
+pub async fn cancel_reminder(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<StatusCode> {
+    check_readable(&state, auth.as_deref(), &id).await?;
+
+    if !state.memory_manager.cancel_reminder(&id).await? {
+        return Err(not_found("Reminder", &id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Put off the current occurrence of a memory's reminder
+///
+/// A recurring reminder carries on from its original schedule afterwards.
+#[utoipa::path(
+    post,
+    path = "/api/memories/{id}/reminder/snooze",
+    tag = "reminders",
+    params(
+        ("id" = String, Path, description = "Memory ID")
+    ),
+    request_body = SnoozeReminderRequest,
+    responses(
+        (status = 200, description = "Reminder snoozed", body = ReminderDto),
+        (status = 400, description = "Neither or both of until and delay given"),
+        (status = 404, description = "Memory has no reminder"),
+    )
+)]
+pub async fn snooze_reminder(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Json(request): Json<SnoozeReminderRequest>,
+) -> ServerResult<Json<ReminderDto>> {
+    check_readable(&state, auth.as_deref(), &id).await?;
+
+    let until = match (request.until, request.delay.as_deref()) {
+        (Some(until), None) => until,
+        (None, Some(delay)) => {
+            let delay = chrono::Duration::from_std(parse_delay(delay)?)
+                .map_err(|_| bad_request("Snooze delay is too long"))?;
+            Utc::now() + delay
+        }
+        _ => return Err(bad_request("Give either until or delay")),
+    };
+    let reminder = state
+        .memory_manager
+        .snooze_reminder(&id, until)
+        .await?
+        .ok_or_else(|| not_found("Reminder", &id))?;
+    Ok(Json(reminder.into()))
+}
+
+/// List reminders, soonest first
+#[utoipa::path(
+    get,
+    path = "/api/reminders",
+    tag = "reminders",
+    responses(
+        (status = 200, description = "Reminders on memories the caller can read", body = Vec<ReminderDto>),
+    )
+)]
+pub async fn list_reminders(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<ReminderDto>>> {
+    let mut reminders = Vec::new();
+    for reminder in state.memory_manager.list_reminders().await? {
+        let readable = state
+            .memory_manager
+            .get_memory(&reminder.memory_id)
+            .await?
+            .is_some_and(|memory| state.shares.can_read(&memory, auth.as_deref()));
+        if readable {
+            reminders.push(ReminderDto::from(reminder));
+        }
+    }
+    Ok(Json(reminders))
+}
+
+async fn check_readable(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    memory_id: &str,
+) -> Result<(), ServerError> {
+    state
+        .memory_manager
+        .get_memory(memory_id)
+        .await?
+        .filter(|memory| state.shares.can_read(memory, auth))
+        .map(|_| ())
+        .ok_or_else(|| not_found("Memory", memory_id))
+}
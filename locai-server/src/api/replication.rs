@@ -0,0 +1,89 @@
+//! Replication endpoint
+//!
+//! Mounted at `/api/replication/ws` when replication is enabled. A peer opens
+//! the socket, sends a `Hello` frame saying whether it pushes or pulls, and
+//! the session then follows `locai::replication::transport`.
+//!
+//! A session reads and writes every record regardless of owner, so with
+//! authentication enabled only admins may open one.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use futures::{SinkExt, StreamExt};
+use locai::LocaiError;
+use locai::replication::Replicator;
+use locai::replication::transport::{ReplicationMessage, run_session};
+use tracing::{info, warn};
+
+use crate::{
+    api::auth::{AuthContext, require_role},
+    state::AppState,
+};
+
+/// Upgrade to a replication session
+pub async fn replication_websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> Response {
+    if let Err(e) = require_role(&state, auth.as_deref(), "admin") {
+        return e.into_response();
+    }
+    match &state.replicator {
+        Some(replicator) => {
+            let replicator = replicator.clone();
+            ws.on_upgrade(move |socket| handle_session(socket, replicator))
+        }
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Replication is not enabled",
+        )
+            .into_response(),
+    }
+}
+
+async fn handle_session(socket: WebSocket, replicator: Arc<Replicator>) {
+    let (write, read) = socket.split();
+    let outgoing = write
+        .sink_map_err(|e| LocaiError::Connection(e.to_string()))
+        .with(|message: ReplicationMessage| async move {
+            message.to_json().map(|json| Message::Text(json.into()))
+        });
+    let mut outgoing = std::pin::pin!(outgoing);
+
+    let incoming = read.filter_map(|frame| async move {
+        match frame {
+            Ok(Message::Text(text)) => Some(ReplicationMessage::from_json(&text)),
+            Ok(_) => None,
+            Err(e) => Some(Err(LocaiError::Connection(e.to_string()))),
+        }
+    });
+    let mut incoming = std::pin::pin!(incoming);
+
+    let (peer, mode) = match incoming.next().await {
+        Some(Ok(ReplicationMessage::Hello { node_id, mode })) => (node_id, mode),
+        _ => {
+            let _ = outgoing
+                .send(ReplicationMessage::Error {
+                    message: "Expected a Hello frame".to_string(),
+                })
+                .await;
+            return;
+        }
+    };
+
+    info!("Replication peer {} connected ({:?})", peer, mode);
+    match run_session(&replicator, mode.server_role(), incoming, outgoing).await {
+        Ok(()) => info!("Replication peer {} disconnected", peer),
+        Err(e) => warn!("Replication session with {} ended: {}", peer, e),
+    }
+}
@@ -0,0 +1,143 @@
+//! Review queue endpoints
+//!
+//! With `review.enabled`, memories created from the configured sources wait
+//! in a review queue instead of being stored; `POST /api/memories` answers
+//! 202 for them. Approving a memory stores it as usual, rejecting it drops
+//! it. With authentication enabled, every review endpoint needs the `admin`
+//! role.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::ReviewItem;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{auth::AuthContext, auth::require_role, dto::MemoryDto},
+    error::{ServerResult, not_found},
+    state::AppState,
+};
+
+/// A memory waiting for review
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReviewItemDto {
+    /// The memory as it will be stored once approved
+    pub memory: MemoryDto,
+    /// When the memory was queued
+    pub submitted_at: DateTime<Utc>,
+}
+
+impl From<ReviewItem> for ReviewItemDto {
+    fn from(item: ReviewItem) -> Self {
+        Self {
+            memory: item.memory.into(),
+            submitted_at: item.submitted_at,
+        }
+    }
+}
+
+/// List memories waiting for review
+#[utoipa::path(
+    get,
+    path = "/api/review",
+    tag = "review",
+    responses(
+        (status = 200, description = "Every memory waiting for review, oldest first", body = Vec<ReviewItemDto>),
+        (status = 403, description = "Caller is not an admin"),
+    )
+)]
+pub async fn list_reviews(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<ReviewItemDto>>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let items = state.memory_manager.list_pending_reviews().await?;
+    Ok(Json(items.into_iter().map(Into::into).collect()))
+}
+
+/// Get a memory waiting for review
+#[utoipa::path(
+    get,
+    path = "/api/review/{id}",
+    tag = "review",
+    params(("id" = String, Path, description = "Memory ID")),
+    responses(
+        (status = 200, description = "The memory waiting for review", body = ReviewItemDto),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No memory with this ID is waiting for review"),
+    )
+)]
+pub async fn get_review(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<ReviewItemDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let item = state
+        .memory_manager
+        .get_pending_review(&id)
+        .await?
+        .ok_or_else(|| not_found("Pending memory", &id))?;
+    Ok(Json(item.into()))
+}
+
+/// Approve a memory, storing it
+#[utoipa::path(
+    post,
+    path = "/api/review/{id}/approve",
+    tag = "review",
+    params(("id" = String, Path, description = "Memory ID")),
+    responses(
+        (status = 201, description = "Memory stored", body = MemoryDto),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No memory with this ID is waiting for review"),
+    )
+)]
+pub async fn approve_memory(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<(StatusCode, Json<MemoryDto>)> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let manager = &state.memory_manager;
+    if manager.get_pending_review(&id).await?.is_none() {
+        return Err(not_found("Pending memory", &id));
+    }
+    let memory_id = manager.approve_memory(&id).await?;
+    let memory = manager
+        .get_memory(&memory_id)
+        .await?
+        .ok_or_else(|| not_found("Memory", &memory_id))?;
+    Ok((StatusCode::CREATED, Json(memory.into())))
+}
+
+/// Reject a memory, dropping it
+#[utoipa::path(
+    post,
+    path = "/api/review/{id}/reject",
+    tag = "review",
+    params(("id" = String, Path, description = "Memory ID")),
+    responses(
+        (status = 204, description = "Memory dropped"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No memory with this ID is waiting for review"),
+    )
+)]
+pub async fn reject_memory(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<StatusCode> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    if !state.memory_manager.reject_memory(&id).await? {
+        return Err(not_found("Pending memory", &id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
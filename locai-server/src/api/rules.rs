@@ -0,0 +1,258 @@
+//! Automation rule endpoints
+//!
+//! Rules are small Rhai scripts run when a memory event happens, such as
+//! raising the priority of memories tagged `urgent` and sending a message
+//! about them. They're kept as memories, so changes apply without a
+//! restart. With authentication enabled, setting, removing and testing
+//! rules needs the `admin` role.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::{Rule, RuleEvent, RuleOutcome};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{auth::AuthContext, auth::require_role, dto::MemoryDto},
+    error::{ServerResult, not_found},
+    state::AppState,
+};
+
+/// An automation rule
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuleDto {
+    /// ID of the rule's memory
+    pub id: String,
+    pub name: String,
+    /// Event the rule runs on: created, updated, accessed, deleted or reminder
+    pub event: String,
+    /// Rhai source of the rule
+    pub script: String,
+    pub description: Option<String>,
+    /// Whether the rule runs
+    pub enabled: bool,
+    /// When the rule was last set
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Rule> for RuleDto {
+    fn from(rule: Rule) -> Self {
+        Self {
+            id: rule.id,
+            name: rule.name,
+            event: rule.event.to_string(),
+            script: rule.script,
+            description: rule.description,
+            enabled: rule.enabled,
+            updated_at: rule.updated_at,
+        }
+    }
+}
+
+/// Request to set a rule
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetRuleRequest {
+    /// Event the rule runs on: created, updated, accessed, deleted or reminder
+    pub event: String,
+    /// Rhai source of the rule
+    pub script: String,
+    pub description: Option<String>,
+    /// Whether the rule runs (default: true)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Request to run a script on a memory without saving anything
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TestRuleRequest {
+    /// Event to run the script as if it happened
+    pub event: String,
+    /// Rhai source to run
+    pub script: String,
+    /// ID of the memory to run the script on
+    pub memory_id: String,
+}
+
+/// A message a rule sent
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuleMessageDto {
+    /// Topic within the `app:rules` namespace
+    pub topic: String,
+    pub content: serde_json::Value,
+}
+
+/// A rule that stopped with an error
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuleFailureDto {
+    pub rule: String,
+    pub error: String,
+}
+
+/// What running a rule did
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuleOutcomeDto {
+    /// Rules that ran to the end
+    pub fired: Vec<String>,
+    /// The memory as the rule left it, if it would be changed
+    pub memory: Option<MemoryDto>,
+    /// Messages the rule would send
+    pub messages: Vec<RuleMessageDto>,
+    /// Rules that stopped with an error
+    pub failures: Vec<RuleFailureDto>,
+}
+
+impl From<RuleOutcome> for RuleOutcomeDto {
+    fn from(outcome: RuleOutcome) -> Self {
+        Self {
+            fired: outcome.fired,
+            memory: outcome.memory.map(Into::into),
+            messages: outcome
+                .messages
+                .into_iter()
+                .map(|message| RuleMessageDto {
+                    topic: message.topic,
+                    content: message.content,
+                })
+                .collect(),
+            failures: outcome
+                .failures
+                .into_iter()
+                .map(|failure| RuleFailureDto {
+                    rule: failure.rule,
+                    error: failure.error,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// List rules
+#[utoipa::path(
+    get,
+    path = "/api/rules",
+    tag = "rules",
+    responses(
+        (status = 200, description = "Every rule, by name", body = Vec<RuleDto>),
+    )
+)]
+pub async fn list_rules(State(state): State<Arc<AppState>>) -> ServerResult<Json<Vec<RuleDto>>> {
+    let rules = state.memory_manager.list_rules().await?;
+    Ok(Json(rules.into_iter().map(Into::into).collect()))
+}
+
+/// Get a rule
+#[utoipa::path(
+    get,
+    path = "/api/rules/{name}",
+    tag = "rules",
+    params(("name" = String, Path, description = "Rule name")),
+    responses(
+        (status = 200, description = "The rule", body = RuleDto),
+        (status = 404, description = "No rule has this name"),
+    )
+)]
+pub async fn get_rule(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> ServerResult<Json<RuleDto>> {
+    let rule = state
+        .memory_manager
+        .get_rule(&name)
+        .await?
+        .ok_or_else(|| not_found("Rule", &name))?;
+    Ok(Json(rule.into()))
+}
+
+/// Set a rule, replacing any with the same name
+#[utoipa::path(
+    put,
+    path = "/api/rules/{name}",
+    tag = "rules",
+    params(("name" = String, Path, description = "Rule name")),
+    request_body = SetRuleRequest,
+    responses(
+        (status = 200, description = "Rule set", body = RuleDto),
+        (status = 400, description = "Unknown event, or the script doesn't compile"),
+        (status = 403, description = "Caller is not an admin"),
+    )
+)]
+pub async fn set_rule(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+    Json(request): Json<SetRuleRequest>,
+) -> ServerResult<Json<RuleDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let event: RuleEvent = request.event.parse()?;
+    let rule = Rule {
+        description: request.description,
+        ..Rule::new(name, event, request.script).with_enabled(request.enabled)
+    };
+    let rule = state.memory_manager.set_rule(rule).await?;
+    Ok(Json(rule.into()))
+}
+
+/// Remove a rule
+#[utoipa::path(
+    delete,
+    path = "/api/rules/{name}",
+    tag = "rules",
+    params(("name" = String, Path, description = "Rule name")),
+    responses(
+        (status = 204, description = "Rule removed"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No rule has this name"),
+    )
+)]
+pub async fn remove_rule(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<StatusCode> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    if !state.memory_manager.remove_rule(&name).await? {
+        return Err(not_found("Rule", &name));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Run a script on a memory without saving changes or sending messages
+#[utoipa::path(
+    post,
+    path = "/api/rules/test",
+    tag = "rules",
+    request_body = TestRuleRequest,
+    responses(
+        (status = 200, description = "What the script would do", body = RuleOutcomeDto),
+        (status = 400, description = "Unknown event, or the script doesn't compile"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Memory not found"),
+    )
+)]
+pub async fn test_rule(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Json(request): Json<TestRuleRequest>,
+) -> ServerResult<Json<RuleOutcomeDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    let manager = &state.memory_manager;
+    let memory = manager
+        .get_memory(&request.memory_id)
+        .await?
+        .ok_or_else(|| not_found("Memory", &request.memory_id))?;
+    let event: RuleEvent = request.event.parse()?;
+    let rule = Rule::new("test", event, request.script);
+    let outcome = manager.test_rule(&rule, event, &memory)?;
+    Ok(Json(outcome.into()))
+}
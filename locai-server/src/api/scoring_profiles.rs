@@ -0,0 +1,138 @@
+//! Scoring profile endpoints
+//!
+//! Scoring profiles are named scoring configurations a search selects with
+//! its `scoring_profile` parameter. The built-in profiles and those in the
+//! `scoring_profiles` section of the Locai configuration are served from
+//! startup; profiles set here replace them until the server restarts. With
+//! authentication enabled, setting and removing profiles needs the `admin`
+//! role.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{auth::AuthContext, auth::require_role, dto::ScoringConfigDto},
+    error::{ServerResult, not_found},
+    state::AppState,
+};
+
+/// A named scoring configuration
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScoringProfileDto {
+    pub name: String,
+    pub scoring: ScoringConfigDto,
+}
+
+/// List scoring profiles
+#[utoipa::path(
+    get,
+    path = "/api/scoring-profiles",
+    tag = "scoring-profiles",
+    responses(
+        (status = 200, description = "Every scoring profile, by name", body = Vec<ScoringProfileDto>),
+    )
+)]
+pub async fn list_scoring_profiles(
+    State(state): State<Arc<AppState>>,
+) -> ServerResult<Json<Vec<ScoringProfileDto>>> {
+    let profiles = state
+        .memory_manager
+        .scoring_profiles()
+        .list()
+        .into_iter()
+        .map(|(name, scoring)| ScoringProfileDto {
+            name,
+            scoring: scoring.into(),
+        })
+        .collect();
+    Ok(Json(profiles))
+}
+
+/// Get a scoring profile
+#[utoipa::path(
+    get,
+    path = "/api/scoring-profiles/{name}",
+    tag = "scoring-profiles",
+    params(("name" = String, Path, description = "Profile name")),
+    responses(
+        (status = 200, description = "The profile", body = ScoringProfileDto),
+        (status = 404, description = "No profile has this name"),
+    )
+)]
+pub async fn get_scoring_profile(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> ServerResult<Json<ScoringProfileDto>> {
+    let scoring = state
+        .memory_manager
+        .scoring_profiles()
+        .get(&name)
+        .ok_or_else(|| not_found("Scoring profile", &name))?;
+    Ok(Json(ScoringProfileDto {
+        name,
+        scoring: scoring.into(),
+    }))
+}
+
+/// Set a scoring profile, replacing any with the same name
+#[utoipa::path(
+    put,
+    path = "/api/scoring-profiles/{name}",
+    tag = "scoring-profiles",
+    params(("name" = String, Path, description = "Profile name")),
+    request_body = ScoringConfigDto,
+    responses(
+        (status = 200, description = "Profile set", body = ScoringProfileDto),
+        (status = 400, description = "Invalid scoring configuration"),
+        (status = 403, description = "Caller is not an admin"),
+    )
+)]
+pub async fn set_scoring_profile(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+    Json(scoring): Json<ScoringConfigDto>,
+) -> ServerResult<Json<ScoringProfileDto>> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    state
+        .memory_manager
+        .scoring_profiles()
+        .set(&name, scoring.clone().into())?;
+    Ok(Json(ScoringProfileDto { name, scoring }))
+}
+
+/// Remove a scoring profile set at runtime or in the configuration
+///
+/// A built-in profile of the same name takes its place; built-in profiles
+/// themselves can't be removed.
+#[utoipa::path(
+    delete,
+    path = "/api/scoring-profiles/{name}",
+    tag = "scoring-profiles",
+    params(("name" = String, Path, description = "Profile name")),
+    responses(
+        (status = 204, description = "Profile removed"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No profile with this name was set"),
+    )
+)]
+pub async fn remove_scoring_profile(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<StatusCode> {
+    require_role(&state, auth.as_deref(), "admin")?;
+    if state.memory_manager.scoring_profiles().remove(&name) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found("Scoring profile", &name))
+    }
+}
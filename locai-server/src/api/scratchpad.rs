@@ -0,0 +1,164 @@
+//! Working-memory scratchpad endpoints
+//!
+//! A scratchpad holds an agent's short-lived working state (the current
+//! plan, intermediate results) as named JSON values that expire on their
+//! own. Each user has their own; without authentication there is one shared
+//! scratchpad. Entries live in the server's hot state, so with Redis
+//! configured every replica sees them.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult, not_found},
+    hot_state::ScratchpadEntry,
+    state::AppState,
+};
+
+/// Longest entry name accepted
+const MAX_NAME_LENGTH: usize = 256;
+
+/// Scratchpad owner when authentication is disabled
+const SHARED_OWNER: &str = "shared";
+
+/// Value to store in a scratchpad entry
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PutScratchpadRequest {
+    /// Any JSON value
+    #[schema(value_type = Object)]
+    pub value: serde_json::Value,
+
+    /// Seconds until the entry expires (default: the server's scratchpad TTL)
+    pub ttl_secs: Option<u64>,
+}
+
+fn owner(auth: Option<&AuthContext>) -> String {
+    auth.map(|auth| auth.user_id.to_string())
+        .unwrap_or_else(|| SHARED_OWNER.to_string())
+}
+
+fn validate_name(name: &str) -> ServerResult<()> {
+    if name.trim().is_empty() || name.len() > MAX_NAME_LENGTH {
+        return Err(ServerError::BadRequest(format!(
+            "Scratchpad entry names must be 1 to {} bytes",
+            MAX_NAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// List the caller's scratchpad entries, by name
+#[utoipa::path(
+    get,
+    path = "/api/scratchpad",
+    tag = "scratchpad",
+    responses(
+        (status = 200, description = "Scratchpad entries", body = Vec<ScratchpadEntry>),
+    )
+)]
+pub async fn list_scratchpad(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<ScratchpadEntry>>> {
+    let entries = state
+        .hot_state
+        .scratchpad_list(&owner(auth.as_deref()))
+        .await?;
+    Ok(Json(entries))
+}
+
+/// Get a scratchpad entry
+#[utoipa::path(
+    get,
+    path = "/api/scratchpad/{name}",
+    tag = "scratchpad",
+    params(("name" = String, Path, description = "Entry name")),
+    responses(
+        (status = 200, description = "Scratchpad entry", body = ScratchpadEntry),
+        (status = 404, description = "No entry by this name, or it expired"),
+    )
+)]
+pub async fn get_scratchpad_entry(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<Json<ScratchpadEntry>> {
+    state
+        .hot_state
+        .scratchpad_get(&owner(auth.as_deref()), &name)
+        .await?
+        .map(Json)
+        .ok_or_else(|| not_found("Scratchpad entry", &name))
+}
+
+/// Set a scratchpad entry, replacing any by the same name
+#[utoipa::path(
+    put,
+    path = "/api/scratchpad/{name}",
+    tag = "scratchpad",
+    params(("name" = String, Path, description = "Entry name")),
+    request_body = PutScratchpadRequest,
+    responses(
+        (status = 200, description = "Entry stored", body = ScratchpadEntry),
+        (status = 400, description = "Invalid entry name"),
+    )
+)]
+pub async fn put_scratchpad_entry(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+    Json(request): Json<PutScratchpadRequest>,
+) -> ServerResult<Json<ScratchpadEntry>> {
+    validate_name(&name)?;
+    if request.ttl_secs == Some(0) {
+        return Err(ServerError::BadRequest(
+            "ttl_secs must be at least 1".to_string(),
+        ));
+    }
+    let entry = state
+        .hot_state
+        .scratchpad_put(
+            &owner(auth.as_deref()),
+            &name,
+            request.value,
+            request.ttl_secs.map(Duration::from_secs),
+        )
+        .await?;
+    Ok(Json(entry))
+}
+
+/// Delete a scratchpad entry
+#[utoipa::path(
+    delete,
+    path = "/api/scratchpad/{name}",
+    tag = "scratchpad",
+    params(("name" = String, Path, description = "Entry name")),
+    responses(
+        (status = 204, description = "Entry deleted"),
+        (status = 404, description = "No entry by this name, or it expired"),
+    )
+)]
+pub async fn delete_scratchpad_entry(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(name): Path<String>,
+) -> ServerResult<StatusCode> {
+    let deleted = state
+        .hot_state
+        .scratchpad_delete(&owner(auth.as_deref()), &name)
+        .await?;
+    if !deleted {
+        return Err(not_found("Scratchpad entry", &name));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
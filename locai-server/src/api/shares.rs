@@ -0,0 +1,230 @@
+//! Share grant endpoints
+//!
+//! `POST /api/shares` shares a memory, or a collection of the caller's
+//! memories, with another user as `reader` or `writer`; the grantee is told
+//! on the `locai.shares.<username>` messaging topic when messaging is enabled.
+//! See [`crate::sharing`] for how grants are enforced. Sharing needs
+//! authentication to be enabled.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::auth::AuthContext,
+    cluster::ClusterEvent,
+    error::{ServerError, ServerResult, bad_request, not_found},
+    sharing::{Access, ShareGrant, ShareRole, is_admin, owner_of},
+    state::AppState,
+};
+
+/// Request to share a memory or a collection
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateShareRequest {
+    /// Memory to share; give either this or `collection`
+    pub memory_id: Option<String>,
+    /// Collection of the caller's memories to share
+    pub collection: Option<String>,
+    /// Username to share with
+    pub user: String,
+    pub role: ShareRole,
+}
+
+/// Share a memory or a collection with a user
+///
+/// Sharing the same memories with the same user again changes the role.
+#[utoipa::path(
+    post,
+    path = "/api/shares",
+    tag = "shares",
+    request_body = CreateShareRequest,
+    responses(
+        (status = 201, description = "Grant stored", body = ShareGrant),
+        (status = 400, description = "Invalid request, or authentication is disabled"),
+        (status = 403, description = "Caller does not own the memory"),
+        (status = 404, description = "Memory or user not found"),
+    )
+)]
+pub async fn create_share(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Json(request): Json<CreateShareRequest>,
+) -> ServerResult<(StatusCode, Json<ShareGrant>)> {
+    let caller = caller(&state, auth.as_deref())?;
+
+    let (owner_id, owner) = match (&request.memory_id, &request.collection) {
+        (Some(memory_id), None) => {
+            let memory = state
+                .memory_manager
+                .get_memory(memory_id)
+                .await?
+                .filter(|memory| state.shares.can_read(memory, Some(caller)))
+                .ok_or_else(|| not_found("Memory", memory_id))?;
+            let owner_id = owner_of(&memory)
+                .ok_or_else(|| {
+                    bad_request("The memory has no owner, so everyone can already see it")
+                })?
+                .to_string();
+            if state.shares.access(&memory, Some(caller)) < Access::Owner {
+                return Err(ServerError::Forbidden(
+                    "Only the owner of a memory can share it".to_string(),
+                ));
+            }
+            let owner = username_of(&state, &owner_id, caller).await?;
+            (owner_id, owner)
+        }
+        (None, Some(collection)) if !collection.is_empty() => {
+            (caller.user_id.to_string(), caller.username.clone())
+        }
+        _ => {
+            return Err(bad_request(
+                "Give either a memory_id or a non-empty collection",
+            ));
+        }
+    };
+
+    let grantee = state
+        .auth_service
+        .as_ref()
+        .ok_or_else(|| ServerError::Internal("Authentication service not available".to_string()))?
+        .get_user_by_username(&state.memory_manager, &request.user)
+        .await?
+        .ok_or_else(|| not_found("User", &request.user))?;
+    if grantee.id.to_string() == owner_id {
+        return Err(bad_request(
+            "The owner already has access to their memories",
+        ));
+    }
+
+    let grant = ShareGrant {
+        id: Uuid::new_v4().to_string(),
+        owner_id,
+        owner,
+        grantee_id: grantee.id.to_string(),
+        grantee: grantee.username,
+        memory_id: request.memory_id,
+        collection: request.collection,
+        role: request.role,
+        created_at: Utc::now(),
+    };
+    let grant = state.shares.grant(&state.memory_manager, grant).await?;
+    state.events.publish(ClusterEvent::SharesChanged);
+    notify(&state, &grant, "shared").await;
+
+    Ok((StatusCode::CREATED, Json(grant)))
+}
+
+/// List grants made by or to the caller, newest first
+///
+/// Admins see every grant.
+#[utoipa::path(
+    get,
+    path = "/api/shares",
+    tag = "shares",
+    responses(
+        (status = 200, description = "Share grants", body = Vec<ShareGrant>),
+        (status = 400, description = "Authentication is disabled"),
+    )
+)]
+pub async fn list_shares(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+) -> ServerResult<Json<Vec<ShareGrant>>> {
+    let caller = caller(&state, auth.as_deref())?;
+    Ok(Json(state.shares.list_for(caller)))
+}
+
+/// Revoke a grant
+///
+/// The owner of the shared memories, the grantee and admins can revoke it.
+#[utoipa::path(
+    delete,
+    path = "/api/shares/{id}",
+    tag = "shares",
+    params(
+        ("id" = String, Path, description = "Grant ID")
+    ),
+    responses(
+        (status = 204, description = "Grant revoked"),
+        (status = 400, description = "Authentication is disabled"),
+        (status = 404, description = "Grant not found"),
+    )
+)]
+pub async fn delete_share(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<StatusCode> {
+    let caller = caller(&state, auth.as_deref())?;
+    let user_id = caller.user_id.to_string();
+
+    // Grants the caller is not party to don't exist as far as they can tell
+    state
+        .shares
+        .get(&id)
+        .filter(|grant| {
+            is_admin(caller) || grant.owner_id == user_id || grant.grantee_id == user_id
+        })
+        .ok_or_else(|| not_found("Share", &id))?;
+
+    if let Some(grant) = state.shares.revoke(&state.memory_manager, &id).await? {
+        state.events.publish(ClusterEvent::SharesChanged);
+        notify(&state, &grant, "revoked").await;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The authenticated caller; sharing means nothing without authentication
+fn caller<'a>(state: &AppState, auth: Option<&'a AuthContext>) -> ServerResult<&'a AuthContext> {
+    if !state.config.enable_auth {
+        return Err(bad_request(
+            "Sharing needs authentication to be enabled; every memory is shared without it",
+        ));
+    }
+    auth.ok_or_else(|| ServerError::Auth("Authentication required".to_string()))
+}
+
+/// Username of the user with ID `user_id`, usually the caller themselves
+async fn username_of(
+    state: &AppState,
+    user_id: &str,
+    caller: &AuthContext,
+) -> ServerResult<String> {
+    if caller.user_id.to_string() == user_id {
+        return Ok(caller.username.clone());
+    }
+    let user = match (state.auth_service.as_ref(), Uuid::parse_str(user_id)) {
+        (Some(auth_service), Ok(id)) => {
+            auth_service
+                .get_user_by_id(&state.memory_manager, &id)
+                .await?
+        }
+        _ => None,
+    };
+    Ok(user.map(|user| user.username).unwrap_or_default())
+}
+
+/// Tell the grantee about a grant, if messaging is enabled
+async fn notify(state: &AppState, grant: &ShareGrant, event: &str) {
+    let Some(messaging) = state.messaging_server.as_ref() else {
+        return;
+    };
+    let topic = format!("locai.shares.{}", grant.grantee);
+    let content = json!({ "event": event, "grant": grant });
+    if let Err(e) = messaging
+        .send_message("locai-server", &topic, content, None)
+        .await
+    {
+        tracing::warn!("Failed to notify {} of a share: {}", grant.grantee, e);
+    }
+}
@@ -0,0 +1,414 @@
+//! Task endpoints
+//!
+//! Tasks are memories of type `task` with a status, an assignee and a due
+//! date, so the memory endpoints also read, search and delete them. These
+//! endpoints add status changes (checked against the task state machine) and
+//! dependencies between tasks. A status change the task can't make answers
+//! 409 Conflict.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use locai::memory::{Task, TaskQuery, TaskStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult, bad_request, not_found},
+    sharing::{Access, OWNER_PROPERTY},
+    state::AppState,
+};
+
+/// A task
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskDto {
+    /// ID of the task's memory
+    pub id: String,
+    pub title: String,
+    /// `open`, `in_progress`, `done` or `cancelled`
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>,
+    /// Whether the task is past its due date and not yet closed
+    pub overdue: bool,
+    /// When the task was last marked done or cancelled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Task> for TaskDto {
+    fn from(task: Task) -> Self {
+        Self {
+            overdue: task.is_overdue(Utc::now()),
+            id: task.id,
+            title: task.title,
+            status: task.status.to_string(),
+            assignee: task.assignee,
+            due_at: task.due_at,
+            closed_at: task.closed_at,
+            tags: task.tags,
+            created_at: task.created_at,
+        }
+    }
+}
+
+/// Request to create a task
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTaskRequest {
+    pub title: String,
+    pub assignee: Option<String>,
+    pub due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// IDs of tasks the new task waits on
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// Request to change a task; omitted fields are left alone
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTaskRequest {
+    /// New status, such as `in_progress` or `done`
+    pub status: Option<String>,
+    /// New assignee; an empty string unassigns the task
+    pub assignee: Option<String>,
+    pub due_at: Option<DateTime<Utc>>,
+    /// Remove the due date
+    #[serde(default)]
+    pub clear_due: bool,
+}
+
+/// Request to make a task wait on another
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddDependencyRequest {
+    pub depends_on: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListTasksParams {
+    /// Comma-separated statuses to include, such as `open,in_progress`
+    pub status: Option<String>,
+    pub assignee: Option<String>,
+    /// Only open tasks past their due date
+    #[serde(default)]
+    pub overdue: bool,
+}
+
+/// Create a task
+#[utoipa::path(
+    post,
+    path = "/api/tasks",
+    tag = "tasks",
+    request_body = CreateTaskRequest,
+    responses(
+        (status = 201, description = "Task created", body = TaskDto),
+        (status = 400, description = "Empty title"),
+        (status = 404, description = "A task to depend on was not found"),
+    )
+)]
+pub async fn create_task(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Json(request): Json<CreateTaskRequest>,
+) -> ServerResult<(StatusCode, Json<TaskDto>)> {
+    if request.title.trim().is_empty() {
+        return Err(bad_request("A task needs a title"));
+    }
+    for depends_on in &request.depends_on {
+        find_readable(&state, auth.as_deref(), depends_on).await?;
+    }
+
+    let mut task = Task::new(request.title);
+    task.assignee = request.assignee.filter(|a| !a.is_empty());
+    task.due_at = request.due_at;
+    task.tags = request.tags;
+
+    // With authentication, a task belongs to its creator like any memory
+    let mut memory = task.to_memory();
+    if let Some(user) = auth.as_deref() {
+        memory.set_property(OWNER_PROPERTY, user.user_id.to_string().into());
+    }
+    let id = state.memory_manager.store_memory(memory).await?;
+    for depends_on in &request.depends_on {
+        state
+            .memory_manager
+            .add_task_dependency(&id, depends_on)
+            .await?;
+    }
+
+    let task = state
+        .memory_manager
+        .get_task(&id)
+        .await?
+        .ok_or_else(|| not_found("Task", &id))?;
+    Ok((StatusCode::CREATED, Json(task.into())))
+}
+
+/// List tasks, soonest due first
+#[utoipa::path(
+    get,
+    path = "/api/tasks",
+    tag = "tasks",
+    params(ListTasksParams),
+    responses(
+        (status = 200, description = "Tasks the caller can read", body = Vec<TaskDto>),
+        (status = 400, description = "Unknown status"),
+    )
+)]
+pub async fn list_tasks(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<ListTasksParams>,
+) -> ServerResult<Json<Vec<TaskDto>>> {
+    let mut query = if params.overdue {
+        TaskQuery::open().due_before(Utc::now())
+    } else {
+        TaskQuery::default()
+    };
+    if let Some(statuses) = &params.status {
+        query.statuses = statuses
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(parse_status)
+            .collect::<Result<_, _>>()?;
+    }
+    query.assignee = params.assignee;
+
+    let mut tasks = Vec::new();
+    for task in state.memory_manager.list_tasks(&query).await? {
+        let readable = state
+            .memory_manager
+            .get_memory(&task.id)
+            .await?
+            .is_some_and(|memory| state.shares.can_read(&memory, auth.as_deref()));
+        if readable {
+            tasks.push(TaskDto::from(task));
+        }
+    }
+    Ok(Json(tasks))
+}
+
+/// Get a task
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    params(
+        ("id" = String, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Task found", body = TaskDto),
+        (status = 404, description = "Task not found"),
+    )
+)]
+pub async fn get_task(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<TaskDto>> {
+    let task = find_readable(&state, auth.as_deref(), &id).await?;
+    Ok(Json(task.into()))
+}
+
+/// Change a task's status, assignee or due date
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    params(
+        ("id" = String, Path, description = "Task ID")
+    ),
+    request_body = UpdateTaskRequest,
+    responses(
+        (status = 200, description = "Task updated", body = TaskDto),
+        (status = 400, description = "Unknown status"),
+        (status = 403, description = "The task is shared with the caller read-only"),
+        (status = 404, description = "Task not found"),
+        (status = 409, description = "The task can't make that status change, or is waiting on a dependency"),
+    )
+)]
+pub async fn update_task(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateTaskRequest>,
+) -> ServerResult<Json<TaskDto>> {
+    find_writable(&state, auth.as_deref(), &id).await?;
+    let manager = &state.memory_manager;
+
+    // The status goes first, as the one change that can be refused
+    if let Some(status) = &request.status {
+        manager.set_task_status(&id, parse_status(status)?).await?;
+    }
+    if let Some(assignee) = &request.assignee {
+        let assignee = Some(assignee.as_str()).filter(|a| !a.is_empty());
+        manager.assign_task(&id, assignee).await?;
+    }
+    if request.clear_due {
+        manager.set_task_due(&id, None).await?;
+    } else if let Some(due_at) = request.due_at {
+        manager.set_task_due(&id, Some(due_at)).await?;
+    }
+
+    let task = manager
+        .get_task(&id)
+        .await?
+        .ok_or_else(|| not_found("Task", &id))?;
+    Ok(Json(task.into()))
+}
+
+/// List the tasks a task waits on
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/dependencies",
+    tag = "tasks",
+    params(
+        ("id" = String, Path, description = "Task ID")
+    ),
+    responses(
+        (status = 200, description = "Tasks this task waits on", body = Vec<TaskDto>),
+        (status = 404, description = "Task not found"),
+    )
+)]
+pub async fn list_dependencies(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+) -> ServerResult<Json<Vec<TaskDto>>> {
+    find_readable(&state, auth.as_deref(), &id).await?;
+
+    let mut tasks = Vec::new();
+    for task in state.memory_manager.task_dependencies(&id).await? {
+        let readable = state
+            .memory_manager
+            .get_memory(&task.id)
+            .await?
+            .is_some_and(|memory| state.shares.can_read(&memory, auth.as_deref()));
+        if readable {
+            tasks.push(TaskDto::from(task));
+        }
+    }
+    Ok(Json(tasks))
+}
+
+/// Make a task wait on another
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/dependencies",
+    tag = "tasks",
+    params(
+        ("id" = String, Path, description = "Task ID")
+    ),
+    request_body = AddDependencyRequest,
+    responses(
+        (status = 201, description = "Dependency added"),
+        (status = 204, description = "The task already waited on that task"),
+        (status = 404, description = "Either task not found"),
+        (status = 409, description = "The dependency would make a cycle"),
+    )
+)]
+pub async fn add_dependency(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path(id): Path<String>,
+    Json(request): Json<AddDependencyRequest>,
+) -> ServerResult<StatusCode> {
+    find_writable(&state, auth.as_deref(), &id).await?;
+    find_readable(&state, auth.as_deref(), &request.depends_on).await?;
+
+    let added = state
+        .memory_manager
+        .add_task_dependency(&id, &request.depends_on)
+        .await?;
+    Ok(if added {
+        StatusCode::CREATED
+    } else {
+        StatusCode::NO_CONTENT
+    })
+}
+
+/// Stop a task waiting on another
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{id}/dependencies/{depends_on}",
+    tag = "tasks",
+    params(
+        ("id" = String, Path, description = "Task ID"),
+        ("depends_on" = String, Path, description = "ID of the task it waits on")
+    ),
+    responses(
+        (status = 204, description = "Dependency removed"),
+        (status = 404, description = "Task not found, or it didn't wait on that task"),
+    )
+)]
+pub async fn remove_dependency(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Path((id, depends_on)): Path<(String, String)>,
+) -> ServerResult<StatusCode> {
+    find_writable(&state, auth.as_deref(), &id).await?;
+
+    if !state
+        .memory_manager
+        .remove_task_dependency(&id, &depends_on)
+        .await?
+    {
+        return Err(not_found("Dependency", &depends_on));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn parse_status(value: &str) -> Result<TaskStatus, ServerError> {
+    value
+        .parse()
+        .map_err(|e: locai::LocaiError| bad_request(&e.to_string()))
+}
+
+async fn find_memory(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    id: &str,
+) -> Result<(Task, Access), ServerError> {
+    let memory = state.memory_manager.get_memory(id).await?;
+    memory
+        .and_then(|memory| {
+            let task = Task::from_memory(&memory)?;
+            let access = state.shares.access(&memory, auth);
+            (access > Access::None).then_some((task, access))
+        })
+        .ok_or_else(|| not_found("Task", id))
+}
+
+async fn find_readable(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    id: &str,
+) -> Result<Task, ServerError> {
+    let (task, _) = find_memory(state, auth, id).await?;
+    Ok(task)
+}
+
+async fn find_writable(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    id: &str,
+) -> Result<Task, ServerError> {
+    let (task, access) = find_memory(state, auth, id).await?;
+    if access < Access::Write {
+        return Err(ServerError::Forbidden(
+            "The task is shared with you read-only".to_string(),
+        ));
+    }
+    Ok(task)
+}
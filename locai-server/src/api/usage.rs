@@ -0,0 +1,182 @@
+//! Usage accounting endpoints
+//!
+//! `GET /api/usage` reports reads, writes, searches, embedding calls and
+//! stored bytes per tenant, by hour, day or month, and `GET
+//! /api/usage/export` returns the same report as CSV for billing. Usage is
+//! only counted with `LOCAI_USAGE_ACCOUNTING` set (see [`crate::usage`]).
+//! Admins see every tenant; other users see their own usage.
+
+use std::sync::Arc;
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use locai::memory::usage::{UsageInterval, UsageQuery, UsageRecord, to_csv};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult},
+    sharing::is_admin,
+    state::AppState,
+};
+
+/// Days a report covers when no start is given
+const DEFAULT_REPORT_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UsageParams {
+    /// Start of the report (default: 30 days before the end)
+    pub start: Option<DateTime<Utc>>,
+
+    /// End of the report (default: now)
+    pub end: Option<DateTime<Utc>>,
+
+    /// "hour", "day" or "month" (default: "day")
+    pub interval: Option<String>,
+
+    /// Only this tenant's usage; non-admins always get their own
+    pub tenant: Option<String>,
+}
+
+/// A tenant's usage in one bucket
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UsageRecordDto {
+    /// User ID of the tenant, or "anonymous"
+    pub tenant: String,
+    /// Start of the hour, day or month
+    pub bucket_start: DateTime<Utc>,
+    pub reads: u64,
+    pub writes: u64,
+    pub searches: u64,
+    /// Requests to the embedding provider; cached embeddings aren't counted
+    pub embedding_calls: u64,
+    /// Tokens the embedding provider billed
+    pub embedding_tokens: u64,
+    /// Most bytes the tenant was seen storing in the bucket
+    pub storage_bytes: u64,
+}
+
+impl From<UsageRecord> for UsageRecordDto {
+    fn from(record: UsageRecord) -> Self {
+        Self {
+            tenant: record.tenant,
+            bucket_start: record.bucket_start,
+            reads: record.counts.reads,
+            writes: record.counts.writes,
+            searches: record.counts.searches,
+            embedding_calls: record.counts.embedding_calls,
+            embedding_tokens: record.counts.embedding_tokens,
+            storage_bytes: record.counts.storage_bytes,
+        }
+    }
+}
+
+/// Report usage by tenant and bucket
+#[utoipa::path(
+    get,
+    path = "/api/usage",
+    tag = "usage",
+    params(UsageParams),
+    responses(
+        (status = 200, description = "Usage per tenant and bucket, oldest first", body = Vec<UsageRecordDto>),
+        (status = 400, description = "Unknown interval or an empty range"),
+        (status = 403, description = "Asked for another tenant's usage without the admin role"),
+    )
+)]
+pub async fn get_usage(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<UsageParams>,
+) -> ServerResult<Json<Vec<UsageRecordDto>>> {
+    let records = usage_report(&state, auth.as_deref(), params).await?;
+    Ok(Json(
+        records.into_iter().map(UsageRecordDto::from).collect(),
+    ))
+}
+
+/// Export usage as CSV
+///
+/// Columns: bucket_start, tenant, reads, writes, searches, embedding_calls,
+/// embedding_tokens, storage_bytes.
+#[utoipa::path(
+    get,
+    path = "/api/usage/export",
+    tag = "usage",
+    params(UsageParams),
+    responses(
+        (status = 200, description = "Usage per tenant and bucket as CSV", body = String, content_type = "text/csv"),
+        (status = 400, description = "Unknown interval or an empty range"),
+        (status = 403, description = "Asked for another tenant's usage without the admin role"),
+    )
+)]
+pub async fn export_usage(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    Query(params): Query<UsageParams>,
+) -> ServerResult<Response> {
+    let records = usage_report(&state, auth.as_deref(), params).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"locai-usage.csv\"",
+            ),
+        ],
+        to_csv(&records),
+    )
+        .into_response())
+}
+
+async fn usage_report(
+    state: &AppState,
+    auth: Option<&AuthContext>,
+    params: UsageParams,
+) -> ServerResult<Vec<UsageRecord>> {
+    let end = params.end.unwrap_or_else(Utc::now);
+    let start = params
+        .start
+        .unwrap_or(end - Duration::days(DEFAULT_REPORT_DAYS));
+    if start >= end {
+        return Err(ServerError::BadRequest(
+            "Usage start must be before its end".to_string(),
+        ));
+    }
+    let interval = match params.interval {
+        Some(interval) => interval.parse()?,
+        None => UsageInterval::Day,
+    };
+
+    let tenant = match auth {
+        _ if !state.config.enable_auth => params.tenant,
+        Some(user) if is_admin(user) => params.tenant,
+        Some(user) => {
+            let own = user.user_id.to_string();
+            if params.tenant.as_ref().is_some_and(|tenant| tenant != &own) {
+                return Err(ServerError::Forbidden(
+                    "Only admins can see other tenants' usage".to_string(),
+                ));
+            }
+            Some(own)
+        }
+        None => {
+            return Err(ServerError::Auth(
+                "Missing authorization header".to_string(),
+            ));
+        }
+    };
+
+    let query = UsageQuery {
+        start,
+        end,
+        interval,
+        tenant,
+    };
+    Ok(state.memory_manager.usage_report(&query).await?)
+}
@@ -0,0 +1,470 @@
+//! LangChain / LlamaIndex compatible vector store endpoints
+//!
+//! Exposes the common "vector store" REST shape (`add_texts`,
+//! `similarity_search`, `delete`) so existing retriever integrations can point
+//! at Locai without code changes. Documents map onto memories as follows:
+//!
+//! - `page_content` ↔ memory content
+//! - `metadata` ↔ memory properties (`tags` and `memory_type` keys are also
+//!   applied to the memory itself)
+//!
+//! Documents are memories like any other, so they belong to the user who
+//! added them and searches and deletes respect shares.
+//!
+//! The router is only mounted when `enable_vectorstore_compat` is set.
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Extension, Json as JsonExtractor, Router, extract::State, http::StatusCode, response::Json,
+    routing::post,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use locai::{
+    memory::search_extensions::SearchMode,
+    models::{Memory, MemoryBuilder, MemoryType},
+    storage::{
+        filters::{MemoryFilter, SemanticSearchFilter},
+        shared_storage::schema::EMBEDDING_DIMENSIONS,
+    },
+};
+
+use crate::{
+    api::auth::AuthContext,
+    error::{ServerError, ServerResult},
+    sharing::{Access, OWNER_PROPERTY},
+    state::AppState,
+    websocket::WebSocketMessage,
+};
+
+/// Source recorded on memories created through this interface
+const VECTORSTORE_SOURCE: &str = "vectorstore";
+
+/// Request body for `add_texts`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddTextsRequest {
+    /// Texts to store
+    pub texts: Vec<String>,
+
+    /// Optional metadata per text (same length as `texts`)
+    #[serde(default)]
+    pub metadatas: Option<Vec<serde_json::Value>>,
+
+    /// Optional embeddings per text (same length as `texts`, 1024 dimensions)
+    #[serde(default)]
+    pub embeddings: Option<Vec<Vec<f32>>>,
+}
+
+/// Response body for `add_texts`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AddTextsResponse {
+    /// IDs of the stored documents, in input order
+    pub ids: Vec<String>,
+}
+
+/// Request body for `similarity_search` and `similarity_search_with_score`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimilaritySearchRequest {
+    /// Query text
+    pub query: String,
+
+    /// Number of documents to return
+    #[serde(default = "default_k")]
+    pub k: usize,
+
+    /// Metadata equality filter
+    #[serde(default)]
+    pub filter: Option<HashMap<String, serde_json::Value>>,
+
+    /// Optional query embedding (BYOE). When present, vector search is used.
+    #[serde(default)]
+    pub embedding: Option<Vec<f32>>,
+}
+
+fn default_k() -> usize {
+    4
+}
+
+/// A LangChain-style document
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DocumentDto {
+    /// Document ID (the memory ID)
+    pub id: String,
+
+    /// Document text
+    pub page_content: String,
+
+    /// Document metadata
+    pub metadata: serde_json::Value,
+}
+
+/// A document with its relevance score
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScoredDocumentDto {
+    pub document: DocumentDto,
+    pub score: f32,
+}
+
+/// Request body for `delete`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeleteDocumentsRequest {
+    /// IDs of the documents to delete
+    pub ids: Vec<String>,
+}
+
+/// Response body for `delete`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeleteDocumentsResponse {
+    /// True if every requested document was deleted
+    pub success: bool,
+
+    /// Number of documents deleted
+    pub deleted: usize,
+}
+
+impl From<Memory> for DocumentDto {
+    fn from(memory: Memory) -> Self {
+        let metadata = match memory.properties {
+            serde_json::Value::Null => serde_json::json!({}),
+            properties => properties,
+        };
+
+        Self {
+            id: memory.id,
+            page_content: memory.content,
+            metadata,
+        }
+    }
+}
+
+/// Routes for the vector store compatibility interface
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/vectorstore/add_texts", post(add_texts))
+        .route("/vectorstore/similarity_search", post(similarity_search))
+        .route(
+            "/vectorstore/similarity_search_with_score",
+            post(similarity_search_with_score),
+        )
+        .route("/vectorstore/delete", post(delete_documents))
+}
+
+/// Add texts (with optional metadata and embeddings) to the store
+#[utoipa::path(
+    post,
+    path = "/api/v1/vectorstore/add_texts",
+    tag = "vectorstore",
+    request_body = AddTextsRequest,
+    responses(
+        (status = 201, description = "Texts stored", body = AddTextsResponse),
+        (status = 400, description = "Mismatched metadata/embedding lengths or invalid embedding"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn add_texts(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    JsonExtractor(request): JsonExtractor<AddTextsRequest>,
+) -> ServerResult<(StatusCode, Json<AddTextsResponse>)> {
+    let count = request.texts.len();
+    if let Some(metadatas) = &request.metadatas
+        && metadatas.len() != count
+    {
+        return Err(ServerError::BadRequest(format!(
+            "Expected {} metadatas, got {}",
+            count,
+            metadatas.len()
+        )));
+    }
+    if let Some(embeddings) = &request.embeddings
+        && embeddings.len() != count
+    {
+        return Err(ServerError::BadRequest(format!(
+            "Expected {} embeddings, got {}",
+            count,
+            embeddings.len()
+        )));
+    }
+
+    let mut metadatas = request.metadatas.map(|m| m.into_iter());
+    let mut embeddings = request.embeddings.map(|e| e.into_iter());
+    let mut ids = Vec::with_capacity(count);
+
+    for text in request.texts {
+        let metadata = metadatas
+            .as_mut()
+            .and_then(Iterator::next)
+            .unwrap_or(serde_json::Value::Null);
+        let mut embedding = embeddings.as_mut().and_then(Iterator::next);
+        if embedding.is_none()
+            && let Some(proxy) = state.embedding_proxy.as_ref().filter(|p| p.auto_attach())
+        {
+            embedding = proxy.embed_for_memory(&text).await?;
+        }
+
+        let mut memory = document_to_memory(text, metadata, embedding)?;
+        if let Some(user) = auth.as_deref() {
+            memory.set_property(OWNER_PROPERTY, user.user_id.to_string().into());
+        }
+        let memory_id = state.memory_manager.store_memory(memory.clone()).await?;
+
+        state.broadcast_message(WebSocketMessage::MemoryCreated {
+            memory_id: memory_id.clone(),
+            content: memory.content,
+            memory_type: memory.memory_type.to_string(),
+            metadata: memory.properties,
+            importance: Some(0.5),
+            node_id: None,
+        });
+        ids.push(memory_id);
+    }
+
+    Ok((StatusCode::CREATED, Json(AddTextsResponse { ids })))
+}
+
+/// Return the `k` documents most relevant to the query
+#[utoipa::path(
+    post,
+    path = "/api/v1/vectorstore/similarity_search",
+    tag = "vectorstore",
+    request_body = SimilaritySearchRequest,
+    responses(
+        (status = 200, description = "Matching documents", body = Vec<DocumentDto>),
+        (status = 400, description = "Invalid query embedding"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn similarity_search(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    JsonExtractor(request): JsonExtractor<SimilaritySearchRequest>,
+) -> ServerResult<Json<Vec<DocumentDto>>> {
+    let results = run_search(&state, request, auth.as_deref()).await?;
+    Ok(Json(
+        results.into_iter().map(|scored| scored.document).collect(),
+    ))
+}
+
+/// Return the `k` most relevant documents together with their scores
+#[utoipa::path(
+    post,
+    path = "/api/v1/vectorstore/similarity_search_with_score",
+    tag = "vectorstore",
+    request_body = SimilaritySearchRequest,
+    responses(
+        (status = 200, description = "Matching documents with scores", body = Vec<ScoredDocumentDto>),
+        (status = 400, description = "Invalid query embedding"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn similarity_search_with_score(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    JsonExtractor(request): JsonExtractor<SimilaritySearchRequest>,
+) -> ServerResult<Json<Vec<ScoredDocumentDto>>> {
+    Ok(Json(run_search(&state, request, auth.as_deref()).await?))
+}
+
+/// Delete documents by ID
+///
+/// Only documents the caller owns are deleted; the rest count as not found.
+#[utoipa::path(
+    post,
+    path = "/api/v1/vectorstore/delete",
+    tag = "vectorstore",
+    request_body = DeleteDocumentsRequest,
+    responses(
+        (status = 200, description = "Deletion result", body = DeleteDocumentsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_documents(
+    State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
+    JsonExtractor(request): JsonExtractor<DeleteDocumentsRequest>,
+) -> ServerResult<Json<DeleteDocumentsResponse>> {
+    let requested = request.ids.len();
+    let mut deleted = 0;
+
+    for id in request.ids {
+        let owned = state
+            .memory_manager
+            .get_memory(&id)
+            .await?
+            .is_some_and(|memory| state.shares.access(&memory, auth.as_deref()) == Access::Owner);
+        if owned && state.memory_manager.delete_memory(&id).await? {
+            state.broadcast_message(WebSocketMessage::MemoryDeleted {
+                memory_id: id,
+                node_id: None,
+            });
+            deleted += 1;
+        }
+    }
+
+    Ok(Json(DeleteDocumentsResponse {
+        success: deleted == requested,
+        deleted,
+    }))
+}
+
+async fn run_search(
+    state: &AppState,
+    request: SimilaritySearchRequest,
+    auth: Option<&AuthContext>,
+) -> ServerResult<Vec<ScoredDocumentDto>> {
+    let memory_filter = MemoryFilter {
+        properties: request.filter,
+        ..Default::default()
+    };
+    let filter = SemanticSearchFilter {
+        similarity_threshold: None,
+        memory_filter: Some(memory_filter),
+        include_archived: false,
+    };
+
+    let results = match request.embedding {
+        Some(embedding) => {
+            let embedding = check_embedding(embedding)?;
+            state
+                .memory_manager
+                .search_with_embedding(
+                    &request.query,
+                    Some(&embedding),
+                    Some(request.k),
+                    Some(filter),
+                    SearchMode::Vector,
+                )
+                .await?
+        }
+        None => {
+            state
+                .memory_manager
+                .search(
+                    &request.query,
+                    Some(request.k),
+                    Some(filter),
+                    SearchMode::Text,
+                )
+                .await?
+        }
+    };
+
+    Ok(results
+        .into_iter()
+        .filter(|result| state.shares.can_read(&result.memory, auth))
+        .map(|result| ScoredDocumentDto {
+            score: result.score.unwrap_or(0.0),
+            document: DocumentDto::from(result.memory),
+        })
+        .collect())
+}
+
+/// Build a memory from a LangChain-style document
+fn document_to_memory(
+    text: String,
+    metadata: serde_json::Value,
+    embedding: Option<Vec<f32>>,
+) -> ServerResult<Memory> {
+    let memory_type = metadata
+        .get("memory_type")
+        .and_then(|v| v.as_str())
+        .map(MemoryType::from_str)
+        .unwrap_or(MemoryType::Fact);
+    let tags: Vec<String> = metadata
+        .get("tags")
+        .and_then(|v| v.as_array())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut builder = MemoryBuilder::new_with_content(text)
+        .memory_type(memory_type)
+        .tags(tags.iter().map(|s| s.as_str()).collect())
+        .source(VECTORSTORE_SOURCE)
+        .properties_json(metadata);
+
+    if let Some(embedding) = embedding {
+        builder = builder.embedding(check_embedding(embedding)?);
+    }
+
+    Ok(builder.build())
+}
+
+/// Validate dimensions and values
+///
+/// Storage compares the embedding by the memory vector space's metric, and
+/// scales it to unit length if that space normalizes.
+fn check_embedding(embedding: Vec<f32>) -> ServerResult<Vec<f32>> {
+    if embedding.len() != EMBEDDING_DIMENSIONS {
+        return Err(ServerError::BadRequest(format!(
+            "Embedding dimension mismatch: expected {} dimensions, got {}",
+            EMBEDDING_DIMENSIONS,
+            embedding.len()
+        )));
+    }
+    if let Some((i, value)) = embedding.iter().enumerate().find(|(_, v)| !v.is_finite()) {
+        return Err(ServerError::BadRequest(format!(
+            "Invalid embedding value at index {}: {}",
+            i, value
+        )));
+    }
+
+    if embedding.iter().all(|value| *value == 0.0) {
+        return Err(ServerError::BadRequest(
+            "A zero vector has no direction to search by".to_string(),
+        ));
+    }
+
+    Ok(embedding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_to_memory_maps_metadata() {
+        let metadata = serde_json::json!({
+            "source": "notes.md",
+            "tags": ["docs", "guide"],
+            "memory_type": "episodic"
+        });
+        let memory = document_to_memory("hello".to_string(), metadata.clone(), None).unwrap();
+
+        assert_eq!(memory.content, "hello");
+        assert_eq!(memory.tags, vec!["docs", "guide"]);
+        assert_eq!(memory.memory_type, MemoryType::Episodic);
+        assert_eq!(memory.source, VECTORSTORE_SOURCE);
+
+        let document = DocumentDto::from(memory);
+        assert_eq!(document.metadata, metadata);
+    }
+
+    #[test]
+    fn test_check_embedding_rejects_wrong_dimensions() {
+        assert!(check_embedding(vec![1.0; 3]).is_err());
+        assert!(check_embedding(vec![0.0; EMBEDDING_DIMENSIONS]).is_err());
+
+        // Left as given; storage normalizes if configured to
+        let checked = check_embedding(vec![2.0; EMBEDDING_DIMENSIONS]).unwrap();
+        assert_eq!(checked, vec![2.0; EMBEDDING_DIMENSIONS]);
+    }
+
+    #[test]
+    fn test_similarity_search_request_defaults() {
+        let request: SimilaritySearchRequest =
+            serde_json::from_str(r#"{"query": "dragons"}"#).unwrap();
+        assert_eq!(request.k, 4);
+        assert!(request.filter.is_none());
+        assert!(request.embedding.is_none());
+    }
+}
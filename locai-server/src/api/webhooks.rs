@@ -74,6 +74,14 @@ impl MemoryHook for EventFilteredWebhook {
         }
     }
 
+    async fn on_memory_reminder(&self, memory: &Memory) -> locai::hooks::HookResult {
+        if self.event_type == "memory.reminder" {
+            self.inner.on_memory_reminder(memory).await
+        } else {
+            locai::hooks::HookResult::Continue
+        }
+    }
+
     fn timeout_ms(&self) -> u64 {
         self.inner.timeout_ms()
     }
@@ -106,6 +114,7 @@ pub async fn create_webhook(
         "memory.updated",
         "memory.accessed",
         "memory.deleted",
+        "memory.reminder",
     ];
     if !valid_events.contains(&request.event.as_str()) {
         return Err(ServerError::BadRequest(format!(
@@ -14,6 +14,7 @@ pub struct CliArgs {
     pub rate_limit_rpm: Option<u32>,
     pub websocket_timeout: Option<u64>,
     pub enable_live_queries: Option<bool>,
+    pub enable_vectorstore_compat: Option<bool>,
     pub messaging_enabled: Option<bool>,
     pub messaging_auth_required: Option<bool>,
     pub max_request_size: Option<usize>,
@@ -174,6 +175,18 @@ Environment variable: LOCAI_ENABLE_LIVE_QUERIES",
                     )
                     .action(ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("enable_vectorstore_compat")
+                    .long("enable-vectorstore-compat")
+                    .help("Enable LangChain/LlamaIndex compatible vector store endpoints")
+                    .long_help(
+                        "Mount the vector store compatibility endpoints under 
+/api/v1/vectorstore (add_texts, similarity_search, delete) so existing 
+LangChain or LlamaIndex retrievers can use Locai as a backend.
+Environment variable: LOCAI_ENABLE_VECTORSTORE_COMPAT",
+                    )
+                    .action(ArgAction::SetTrue),
+            )
             .arg(
                 Arg::new("messaging_enabled")
                     .long("messaging-enabled")
@@ -266,6 +279,11 @@ that can be used to configure the server.",
             } else {
                 None
             },
+            enable_vectorstore_compat: if matches.get_flag("enable_vectorstore_compat") {
+                Some(true)
+            } else {
+                None
+            },
             messaging_enabled: if matches.get_flag("messaging_enabled") {
                 Some(true)
             } else if matches.get_flag("messaging_disabled") {
@@ -316,6 +334,44 @@ that can be used to configure the server.",
         println!("  LOCAI_ENABLE_LIVE_QUERIES         - Enable live queries (default: false)");
         println!("  LOCAI_LIVE_QUERY_BUFFER_SIZE      - Event buffer size (default: 100)");
         println!();
+        println!("Integrations:");
+        println!(
+            "  LOCAI_ENABLE_VECTORSTORE_COMPAT   - LangChain-style vector store API (default: false)"
+        );
+        println!();
+        println!("Embedding Proxy:");
+        println!(
+            "  LOCAI_EMBEDDINGS_PROXY_ENABLED    - Enable /v1/embeddings proxy (default: false)"
+        );
+        println!("  LOCAI_EMBEDDINGS_UPSTREAM_URL     - Upstream base URL (default: OpenAI)");
+        println!("  LOCAI_EMBEDDINGS_API_KEY          - Upstream API key");
+        println!(
+            "  LOCAI_EMBEDDINGS_MODEL            - Default model (default: text-embedding-3-small)"
+        );
+        println!("  LOCAI_EMBEDDINGS_DIMENSIONS       - Output dimensions (default: 1024)");
+        println!("  LOCAI_EMBEDDINGS_AUTO_ATTACH      - Embed new memories (default: true)");
+        println!("  LOCAI_EMBEDDINGS_CACHE            - Cache by content hash (default: true)");
+        println!("  LOCAI_EMBEDDINGS_TIMEOUT          - Upstream timeout in seconds (default: 30)");
+        println!();
+        println!("Replication:");
+        println!(
+            "  LOCAI_REPLICATION_ENABLED         - Serve /api/replication/ws (default: false)"
+        );
+        println!(
+            "  LOCAI_REPLICATION_NODE_ID         - Node name in vector clocks (default: random)"
+        );
+        println!(
+            "  LOCAI_REPLICATION_CONFLICT_POLICY - vector_clock or last_write_wins (default: vector_clock)"
+        );
+        println!();
+        println!("Background Jobs:");
+        println!("  LOCAI_MAX_CONCURRENT_JOBS         - Jobs run at once (default: 2)");
+        println!();
+        println!("Usage Accounting:");
+        println!(
+            "  LOCAI_USAGE_ACCOUNTING            - Count usage per tenant for /api/usage (default: false)"
+        );
+        println!();
         println!("Messaging System:");
         println!("  LOCAI_MESSAGING_ENABLED           - Enable messaging (default: true)");
         println!(
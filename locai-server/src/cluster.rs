@@ -0,0 +1,202 @@
+//! Events shared between server replicas
+//!
+//! Several `locai-server` replicas can serve one remote SurrealDB behind a
+//! load balancer. Everything durable is in the database, but some events only
+//! reach the replica that produced them: WebSocket notifications, messages
+//! published to messaging subscribers, and changes to the share grants each
+//! replica keeps in memory. With Redis configured (see [`crate::hot_state`]),
+//! replicas publish these as [`ClusterEvent`]s on one Redis channel and
+//! deliver the ones they receive from the others to their own clients.
+//!
+//! Delivery is at most once: events published while a replica is
+//! reconnecting to Redis are lost to it.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::HotStateConfig;
+use crate::websocket::WebSocketMessage;
+
+#[cfg(feature = "redis")]
+use tokio::sync::mpsc;
+
+/// Channel events are published on, after the key prefix
+#[cfg(feature = "redis")]
+const EVENT_CHANNEL: &str = "events";
+
+/// How long to wait before resubscribing after losing Redis
+#[cfg(feature = "redis")]
+const RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Something every replica should hear about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClusterEvent {
+    /// A notification for WebSocket clients
+    WebSocket(WebSocketMessage),
+    /// A message for messaging subscribers
+    Message(locai::messaging::types::Message),
+    /// Share grants were created, changed or revoked
+    SharesChanged,
+}
+
+/// An event and the replica that published it
+#[cfg(feature = "redis")]
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    origin: Uuid,
+    event: ClusterEvent,
+}
+
+/// Publishes events to the other replicas
+///
+/// Without Redis there are no other replicas, and publishing does nothing.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    replica_id: Uuid,
+    #[cfg(feature = "redis")]
+    outgoing: Option<mpsc::UnboundedSender<String>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::local()
+    }
+}
+
+impl EventBus {
+    /// A bus for a server running alone
+    pub fn local() -> Self {
+        Self {
+            replica_id: Uuid::new_v4(),
+            #[cfg(feature = "redis")]
+            outgoing: None,
+        }
+    }
+
+    /// Join the replicas sharing the Redis `config` names
+    ///
+    /// Returns the bus and the events other replicas publish. Without a
+    /// Redis URL this is a local bus, and no events ever arrive.
+    pub async fn connect(
+        config: &HotStateConfig,
+    ) -> anyhow::Result<(Self, tokio::sync::mpsc::UnboundedReceiver<ClusterEvent>)> {
+        let (incoming_tx, incoming) = tokio::sync::mpsc::unbounded_channel();
+        let Some(url) = config.redis_url.clone() else {
+            return Ok((Self::local(), incoming));
+        };
+        #[cfg(feature = "redis")]
+        {
+            let client = redis::Client::open(url.as_str())?;
+            let connection = redis::aio::ConnectionManager::new(client.clone()).await?;
+            let channel = format!("{}{}", config.key_prefix, EVENT_CHANNEL);
+            let replica_id = Uuid::new_v4();
+
+            let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+            tokio::spawn(publish_events(connection, channel.clone(), outgoing_rx));
+            tokio::spawn(receive_events(client, channel, replica_id, incoming_tx));
+
+            Ok((
+                Self {
+                    replica_id,
+                    outgoing: Some(outgoing),
+                },
+                incoming,
+            ))
+        }
+        #[cfg(not(feature = "redis"))]
+        {
+            drop(incoming_tx);
+            anyhow::bail!(
+                "LOCAI_REDIS_URL is set to {}, but locai-server was built without the `redis` feature",
+                url
+            )
+        }
+    }
+
+    /// Identifies this replica for as long as it runs
+    pub fn replica_id(&self) -> Uuid {
+        self.replica_id
+    }
+
+    /// Tell the other replicas about `event`, in order with earlier events
+    ///
+    /// Returns at once; the event is sent in the background.
+    pub fn publish(&self, event: ClusterEvent) {
+        #[cfg(feature = "redis")]
+        if let Some(outgoing) = &self.outgoing {
+            let envelope = Envelope {
+                origin: self.replica_id,
+                event,
+            };
+            match serde_json::to_string(&envelope) {
+                Ok(payload) => {
+                    let _ = outgoing.send(payload);
+                }
+                Err(e) => tracing::warn!("Failed to encode cluster event: {}", e),
+            }
+        }
+        #[cfg(not(feature = "redis"))]
+        let _ = event;
+    }
+}
+
+/// Publish queued events one at a time, keeping their order
+#[cfg(feature = "redis")]
+async fn publish_events(
+    mut connection: redis::aio::ConnectionManager,
+    channel: String,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+) {
+    while let Some(payload) = outgoing.recv().await {
+        let published: redis::RedisResult<()> =
+            redis::AsyncCommands::publish(&mut connection, &channel, payload).await;
+        if let Err(e) = published {
+            tracing::warn!("Failed to publish cluster event: {}", e);
+        }
+    }
+}
+
+/// Forward events other replicas publish, resubscribing whenever the
+/// subscription drops
+#[cfg(feature = "redis")]
+async fn receive_events(
+    client: redis::Client,
+    channel: String,
+    replica_id: Uuid,
+    incoming: mpsc::UnboundedSender<ClusterEvent>,
+) {
+    use futures::StreamExt;
+
+    while !incoming.is_closed() {
+        match client.get_async_pubsub().await {
+            Ok(mut pubsub) => {
+                if let Err(e) = pubsub.subscribe(&channel).await {
+                    tracing::warn!("Failed to subscribe to cluster events: {}", e);
+                } else {
+                    let mut messages = pubsub.on_message();
+                    while let Some(message) = messages.next().await {
+                        let envelope = message
+                            .get_payload::<String>()
+                            .map_err(|e| e.to_string())
+                            .and_then(|payload| {
+                                serde_json::from_str::<Envelope>(&payload)
+                                    .map_err(|e| e.to_string())
+                            });
+                        match envelope {
+                            Ok(envelope) if envelope.origin == replica_id => {}
+                            Ok(envelope) => {
+                                if incoming.send(envelope.event).is_err() {
+                                    return;
+                                }
+                            }
+                            Err(e) => tracing::warn!("Dropping unreadable cluster event: {}", e),
+                        }
+                    }
+                    tracing::warn!("Lost the cluster event subscription; resubscribing");
+                }
+            }
+            Err(e) => tracing::warn!("Failed to connect for cluster events: {}", e),
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
@@ -1,6 +1,7 @@
 //! Server configuration module
 
 use anyhow::Result;
+use locai::replication::ConflictPolicy;
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::path::PathBuf;
@@ -44,8 +45,94 @@ pub struct ServerConfig {
     /// Live query buffer size for event channels
     pub live_query_buffer_size: usize,
 
+    /// Mount the LangChain/LlamaIndex compatible vector store endpoints
+    pub enable_vectorstore_compat: bool,
+
     /// Messaging configuration
     pub messaging: MessagingConfig,
+
+    /// OpenAI-compatible embedding proxy configuration
+    pub embedding_proxy: EmbeddingProxyConfig,
+
+    /// Replication endpoint configuration
+    pub replication: ReplicationEndpointConfig,
+
+    /// Background jobs run at once; later ones wait in the queue
+    pub max_concurrent_jobs: usize,
+
+    /// Count requests, embedding calls and storage per tenant for
+    /// `/api/usage` (see [`crate::usage`])
+    pub usage_accounting: bool,
+
+    /// Scratchpads, rate limits and cached searches shared between replicas
+    pub hot_state: HotStateConfig,
+}
+
+/// Hot state configuration
+///
+/// Without `redis_url`, hot state is kept in the server process; with it,
+/// every replica using the same Redis shares it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotStateConfig {
+    /// Redis URL, such as `redis://localhost:6379` (needs the `redis` feature)
+    pub redis_url: Option<String>,
+
+    /// Prefix of every Redis key, so deployments can share a Redis
+    pub key_prefix: String,
+
+    /// Seconds a search result stays in the shared cache; 0 disables it
+    pub search_cache_ttl_secs: u64,
+
+    /// Seconds a scratchpad entry lives unless it sets its own; 0 keeps
+    /// entries until they are deleted
+    pub scratchpad_ttl_secs: u64,
+}
+
+/// Replication endpoint configuration
+///
+/// When enabled, peers push changes to or pull changes from this server over
+/// `/api/replication/ws`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicationEndpointConfig {
+    /// Enable the replication endpoint
+    pub enabled: bool,
+
+    /// Name of this node in vector clocks (random if unset)
+    pub node_id: Option<String>,
+
+    /// How conflicting changes are settled
+    pub conflict_policy: ConflictPolicy,
+}
+
+/// Embedding proxy configuration
+///
+/// When enabled, `/v1/embeddings` forwards to `upstream_url` and caches
+/// results by content hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingProxyConfig {
+    /// Enable the embedding proxy
+    pub enabled: bool,
+
+    /// Base URL of the upstream OpenAI-compatible API (without `/embeddings`)
+    pub upstream_url: String,
+
+    /// API key sent as a bearer token to the upstream provider
+    pub api_key: Option<String>,
+
+    /// Default upstream model
+    pub model: String,
+
+    /// Requested output dimensions (1024 matches the memory vector index)
+    pub dimensions: Option<usize>,
+
+    /// Attach embeddings to memories created without one
+    pub auto_attach: bool,
+
+    /// Cache embeddings in storage keyed by content hash
+    pub cache_enabled: bool,
+
+    /// Upstream request timeout in seconds
+    pub timeout_secs: u64,
 }
 
 /// Messaging configuration for locai-server
@@ -110,6 +197,32 @@ impl Default for MessagingConfig {
     }
 }
 
+impl Default for EmbeddingProxyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            upstream_url: "https://api.openai.com/v1".to_string(),
+            api_key: None,
+            model: "text-embedding-3-small".to_string(),
+            dimensions: Some(1024),
+            auto_attach: true,
+            cache_enabled: true,
+            timeout_secs: 30,
+        }
+    }
+}
+
+impl Default for HotStateConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: None,
+            key_prefix: "locai:".to_string(),
+            search_cache_ttl_secs: 30,
+            scratchpad_ttl_secs: 3600,
+        }
+    }
+}
+
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
@@ -125,7 +238,13 @@ impl Default for ServerConfig {
             websocket_timeout: 300, // 5 minutes
             enable_live_queries: false,
             live_query_buffer_size: 100,
+            enable_vectorstore_compat: false,
             messaging: MessagingConfig::default(),
+            embedding_proxy: EmbeddingProxyConfig::default(),
+            replication: ReplicationEndpointConfig::default(),
+            max_concurrent_jobs: 2,
+            usage_accounting: false,
+            hot_state: HotStateConfig::default(),
         }
     }
 }
@@ -135,6 +254,7 @@ impl ServerConfig {
     /// CLI arguments take precedence over environment variables
     pub fn from_cli_and_env(cli_args: crate::cli::CliArgs) -> Result<Self> {
         let mut config = Self::default();
+        let mut generated_jwt_secret = false;
 
         // CLI arguments take precedence over environment variables
         if let Some(port) = cli_args.port {
@@ -160,6 +280,7 @@ impl ServerConfig {
         } else if let Ok(jwt_secret) = env::var("LOCAI_JWT_SECRET") {
             config.jwt_secret = jwt_secret;
         } else if config.jwt_secret.is_empty() {
+            generated_jwt_secret = true;
             // Generate a random JWT secret if not provided
             use rand::Rng;
             use rand::distr::Alphanumeric;
@@ -217,6 +338,12 @@ impl ServerConfig {
             config.live_query_buffer_size = live_query_buffer_size.parse()?;
         }
 
+        if let Some(enable_vectorstore_compat) = cli_args.enable_vectorstore_compat {
+            config.enable_vectorstore_compat = enable_vectorstore_compat;
+        } else if let Ok(enable_vectorstore_compat) = env::var("LOCAI_ENABLE_VECTORSTORE_COMPAT") {
+            config.enable_vectorstore_compat = enable_vectorstore_compat.parse().unwrap_or(false);
+        }
+
         // Messaging configuration
         if let Some(messaging_enabled) = cli_args.messaging_enabled {
             config.messaging.enabled = messaging_enabled;
@@ -273,6 +400,89 @@ impl ServerConfig {
             };
         }
 
+        // Embedding proxy configuration
+        if let Ok(enabled) = env::var("LOCAI_EMBEDDINGS_PROXY_ENABLED") {
+            config.embedding_proxy.enabled = enabled.parse().unwrap_or(false);
+        }
+
+        if let Ok(upstream_url) = env::var("LOCAI_EMBEDDINGS_UPSTREAM_URL") {
+            config.embedding_proxy.upstream_url = upstream_url;
+        }
+
+        if let Ok(api_key) = env::var("LOCAI_EMBEDDINGS_API_KEY") {
+            config.embedding_proxy.api_key = Some(api_key);
+        }
+
+        if let Ok(model) = env::var("LOCAI_EMBEDDINGS_MODEL") {
+            config.embedding_proxy.model = model;
+        }
+
+        if let Ok(dimensions) = env::var("LOCAI_EMBEDDINGS_DIMENSIONS") {
+            config.embedding_proxy.dimensions = match dimensions.as_str() {
+                "" | "none" => None,
+                value => Some(value.parse()?),
+            };
+        }
+
+        if let Ok(auto_attach) = env::var("LOCAI_EMBEDDINGS_AUTO_ATTACH") {
+            config.embedding_proxy.auto_attach = auto_attach.parse().unwrap_or(true);
+        }
+
+        if let Ok(cache_enabled) = env::var("LOCAI_EMBEDDINGS_CACHE") {
+            config.embedding_proxy.cache_enabled = cache_enabled.parse().unwrap_or(true);
+        }
+
+        if let Ok(timeout) = env::var("LOCAI_EMBEDDINGS_TIMEOUT") {
+            config.embedding_proxy.timeout_secs = timeout.parse()?;
+        }
+
+        // Replication configuration
+        if let Ok(enabled) = env::var("LOCAI_REPLICATION_ENABLED") {
+            config.replication.enabled = enabled.parse().unwrap_or(false);
+        }
+
+        if let Ok(node_id) = env::var("LOCAI_REPLICATION_NODE_ID") {
+            config.replication.node_id = Some(node_id);
+        }
+
+        if let Ok(policy) = env::var("LOCAI_REPLICATION_CONFLICT_POLICY") {
+            config.replication.conflict_policy = policy.parse()?;
+        }
+
+        // Background jobs
+        if let Ok(max_concurrent_jobs) = env::var("LOCAI_MAX_CONCURRENT_JOBS") {
+            config.max_concurrent_jobs = max_concurrent_jobs.parse()?;
+        }
+
+        // Usage accounting
+        if let Ok(enabled) = env::var("LOCAI_USAGE_ACCOUNTING") {
+            config.usage_accounting = enabled.parse().unwrap_or(false);
+        }
+
+        // Hot state configuration
+        if let Ok(redis_url) = env::var("LOCAI_REDIS_URL") {
+            config.hot_state.redis_url = Some(redis_url).filter(|url| !url.is_empty());
+        }
+
+        if let Ok(key_prefix) = env::var("LOCAI_REDIS_KEY_PREFIX") {
+            config.hot_state.key_prefix = key_prefix;
+        }
+
+        if let Ok(ttl) = env::var("LOCAI_SEARCH_CACHE_TTL") {
+            config.hot_state.search_cache_ttl_secs = ttl.parse()?;
+        }
+
+        if let Ok(ttl) = env::var("LOCAI_SCRATCHPAD_TTL") {
+            config.hot_state.scratchpad_ttl_secs = ttl.parse()?;
+        }
+
+        // Replicas each generating a secret would reject each other's tokens
+        if generated_jwt_secret && config.enable_auth && config.hot_state.redis_url.is_some() {
+            anyhow::bail!(
+                "Set LOCAI_JWT_SECRET when running replicas with LOCAI_REDIS_URL, so they accept each other's tokens"
+            );
+        }
+
         Ok(config)
     }
 
@@ -0,0 +1,351 @@
+//! Embedding proxy with content-hash caching
+//!
+//! When enabled, the server forwards OpenAI-compatible embedding requests to a
+//! configured upstream provider. Results are cached in the vector table keyed
+//! by a SHA-256 of `(model, dimensions, text)`, so repeated content never hits
+//! the provider twice. The same proxy is used to auto-attach embeddings to
+//! memories created without one.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::Utc;
+use locai::storage::models::Vector;
+use locai::storage::traits::GraphStore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::config::EmbeddingProxyConfig;
+use crate::error::{ServerError, ServerResult};
+
+/// Dimension accepted by the vector table and memory M-Tree index
+const STORABLE_DIMENSIONS: usize = 1024;
+
+/// Prefix for cache entries in the vector table
+const CACHE_ID_PREFIX: &str = "embcache_";
+
+/// Upstream request body (OpenAI embeddings API)
+#[derive(Debug, Serialize)]
+struct UpstreamRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<usize>,
+}
+
+/// Upstream response body (OpenAI embeddings API)
+#[derive(Debug, Deserialize)]
+struct UpstreamResponse {
+    data: Vec<UpstreamEmbedding>,
+    #[serde(default)]
+    usage: Option<UpstreamUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpstreamEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub struct UpstreamUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub total_tokens: u64,
+}
+
+/// Result of an embedding call
+#[derive(Debug, Clone)]
+pub struct EmbeddingOutput {
+    /// One embedding per input, in input order
+    pub embeddings: Vec<Vec<f32>>,
+    /// Model used upstream
+    pub model: String,
+    /// Tokens billed by the upstream provider (cache hits are free)
+    pub usage: UpstreamUsage,
+    /// Number of inputs served from cache
+    pub cache_hits: usize,
+}
+
+/// Cache statistics for the health endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub upstream_requests: u64,
+}
+
+/// Proxy to an upstream OpenAI-compatible embedding provider
+#[derive(Debug)]
+pub struct EmbeddingProxy {
+    config: EmbeddingProxyConfig,
+    client: reqwest::Client,
+    storage: Arc<dyn GraphStore>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    upstream_requests: AtomicU64,
+}
+
+impl EmbeddingProxy {
+    /// Create a proxy that caches into the given storage
+    pub fn new(config: EmbeddingProxyConfig, storage: Arc<dyn GraphStore>) -> ServerResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| ServerError::Internal(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            config,
+            client,
+            storage,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            upstream_requests: AtomicU64::new(0),
+        })
+    }
+
+    /// Proxy configuration
+    pub fn config(&self) -> &EmbeddingProxyConfig {
+        &self.config
+    }
+
+    /// Whether embeddings should be attached to new memories automatically
+    pub fn auto_attach(&self) -> bool {
+        self.config.auto_attach
+    }
+
+    /// Cache statistics since startup
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        EmbeddingCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            upstream_requests: self.upstream_requests.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Embed a batch of inputs, serving repeated content from cache
+    pub async fn embed(
+        &self,
+        inputs: &[String],
+        model: Option<&str>,
+        dimensions: Option<usize>,
+    ) -> ServerResult<EmbeddingOutput> {
+        let model = model.unwrap_or(&self.config.model).to_string();
+        let dimensions = dimensions.or(self.config.dimensions);
+
+        let keys: Vec<String> = inputs
+            .iter()
+            .map(|text| cache_key(&model, dimensions, text))
+            .collect();
+
+        let mut embeddings: Vec<Option<Vec<f32>>> = Vec::with_capacity(inputs.len());
+        for key in &keys {
+            embeddings.push(self.cache_get(key).await);
+        }
+
+        let missing: Vec<usize> = (0..inputs.len())
+            .filter(|&i| embeddings[i].is_none())
+            .collect();
+        let cache_hits = inputs.len() - missing.len();
+        self.hits.fetch_add(cache_hits as u64, Ordering::Relaxed);
+        self.misses
+            .fetch_add(missing.len() as u64, Ordering::Relaxed);
+
+        let mut usage = UpstreamUsage::default();
+        if !missing.is_empty() {
+            let batch: Vec<String> = missing.iter().map(|&i| inputs[i].clone()).collect();
+            let response = self.call_upstream(&model, &batch, dimensions).await?;
+            usage = response.usage.unwrap_or_default();
+            crate::usage::count_embedding_call(usage.total_tokens);
+
+            for item in response.data {
+                let Some(&input_index) = missing.get(item.index) else {
+                    return Err(ServerError::Internal(format!(
+                        "Upstream returned embedding for unknown index {}",
+                        item.index
+                    )));
+                };
+                self.cache_put(&keys[input_index], &model, &item.embedding)
+                    .await;
+                embeddings[input_index] = Some(item.embedding);
+            }
+        }
+
+        let embeddings = embeddings
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| {
+                e.ok_or_else(|| {
+                    ServerError::Internal(format!("Upstream returned no embedding for input {}", i))
+                })
+            })
+            .collect::<ServerResult<Vec<_>>>()?;
+
+        Ok(EmbeddingOutput {
+            embeddings,
+            model,
+            usage,
+            cache_hits,
+        })
+    }
+
+    /// Embed a single text for attaching to a memory
+    ///
+    /// The result is normalized. Returns `None` (with a warning) if the
+    /// configured model does not produce embeddings the memory index can store.
+    pub async fn embed_for_memory(&self, text: &str) -> ServerResult<Option<Vec<f32>>> {
+        let output = self.embed(&[text.to_string()], None, None).await?;
+        let embedding = output.embeddings.into_iter().next();
+
+        match embedding {
+            Some(mut embedding) if embedding.len() == STORABLE_DIMENSIONS => {
+                // Memories store unit vectors for cosine similarity
+                let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if norm == 0.0 || !norm.is_finite() {
+                    return Ok(None);
+                }
+                for value in embedding.iter_mut() {
+                    *value /= norm;
+                }
+                Ok(Some(embedding))
+            }
+            Some(embedding) => {
+                warn!(
+                    "Embedding proxy model '{}' returned {} dimensions; memories require {}. \
+                     Set LOCAI_EMBEDDINGS_DIMENSIONS=1024 or choose a 1024-dimensional model.",
+                    output.model,
+                    embedding.len(),
+                    STORABLE_DIMENSIONS
+                );
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn call_upstream(
+        &self,
+        model: &str,
+        input: &[String],
+        dimensions: Option<usize>,
+    ) -> ServerResult<UpstreamResponse> {
+        let url = format!(
+            "{}/embeddings",
+            self.config.upstream_url.trim_end_matches('/')
+        );
+        let mut request = self.client.post(&url).json(&UpstreamRequest {
+            model,
+            input,
+            dimensions,
+        });
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        self.upstream_requests.fetch_add(1, Ordering::Relaxed);
+        debug!(
+            "Embedding {} input(s) upstream with model {}",
+            input.len(),
+            model
+        );
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ServerError::Internal(format!("Embedding upstream unreachable: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ServerError::Internal(format!(
+                "Embedding upstream returned {}: {}",
+                status, body
+            )));
+        }
+
+        response.json::<UpstreamResponse>().await.map_err(|e| {
+            ServerError::Internal(format!("Invalid embedding upstream response: {}", e))
+        })
+    }
+
+    async fn cache_get(&self, key: &str) -> Option<Vec<f32>> {
+        if !self.config.cache_enabled {
+            return None;
+        }
+
+        match self.storage.get_vector(key).await {
+            Ok(Some(vector)) => Some(vector.vector),
+            Ok(None) => None,
+            Err(e) => {
+                debug!("Embedding cache lookup failed for {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    async fn cache_put(&self, key: &str, model: &str, embedding: &[f32]) {
+        // The vector table only stores index-compatible dimensions
+        if !self.config.cache_enabled || embedding.len() != STORABLE_DIMENSIONS {
+            return;
+        }
+
+        let vector = Vector {
+            id: key.to_string(),
+            vector: embedding.to_vec(),
+            dimension: embedding.len(),
+            metadata: serde_json::json!({
+                "kind": "embedding_cache",
+                "model": model,
+            }),
+            source_id: None,
+            created_at: Utc::now(),
+        };
+
+        if let Err(e) = self.storage.upsert_vector(vector).await {
+            warn!("Failed to cache embedding {}: {}", key, e);
+        }
+    }
+}
+
+/// Cache key for an input under a given model and dimension setting
+pub fn cache_key(model: &str, dimensions: Option<usize>, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(model.as_bytes());
+    hasher.update([0]);
+    hasher.update(dimensions.unwrap_or(0).to_le_bytes());
+    hasher.update([0]);
+    hasher.update(text.as_bytes());
+
+    let digest = hasher.finalize();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", CACHE_ID_PREFIX, hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_depends_on_model_dimensions_and_text() {
+        let base = cache_key("text-embedding-3-small", Some(1024), "hello");
+
+        assert_eq!(
+            base,
+            cache_key("text-embedding-3-small", Some(1024), "hello")
+        );
+        assert_ne!(
+            base,
+            cache_key("text-embedding-3-large", Some(1024), "hello")
+        );
+        assert_ne!(base, cache_key("text-embedding-3-small", None, "hello"));
+        assert_ne!(
+            base,
+            cache_key("text-embedding-3-small", Some(1024), "hello!")
+        );
+        assert!(base.starts_with(CACHE_ID_PREFIX));
+        assert_eq!(base.len(), CACHE_ID_PREFIX.len() + 64);
+    }
+}
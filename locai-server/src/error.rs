@@ -36,6 +36,10 @@ pub enum ServerError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Authenticated, but not allowed to do this
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     /// Not found error
     #[error("Resource not found: {0}")]
     NotFound(String),
@@ -44,13 +48,16 @@ pub enum ServerError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    /// Conflicts with work already in progress
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     /// Internal server error
     #[error("Internal server error: {0}")]
     Internal(String),
 
     /// Rate limit exceeded
     #[error("Rate limit exceeded")]
-    #[allow(dead_code)]
     RateLimit,
 
     /// WebSocket error
@@ -75,11 +82,33 @@ impl ServerError {
             ServerError::Auth(_) => StatusCode::UNAUTHORIZED,
             ServerError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ServerError::Validation(_) | ServerError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ServerError::Forbidden(_) => StatusCode::FORBIDDEN,
             ServerError::NotFound(_) => StatusCode::NOT_FOUND,
+            ServerError::Conflict(_) => StatusCode::CONFLICT,
             ServerError::RateLimit => StatusCode::TOO_MANY_REQUESTS,
             ServerError::Locai(locai::LocaiError::MLNotConfigured) => {
                 StatusCode::SERVICE_UNAVAILABLE
             }
+            ServerError::Locai(locai::LocaiError::QuotaExceeded { .. }) => {
+                StatusCode::INSUFFICIENT_STORAGE
+            }
+            ServerError::Locai(locai::LocaiError::Collection(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Template(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Reminder(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Task(_)) => StatusCode::CONFLICT,
+            ServerError::Locai(locai::LocaiError::Procedure(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Preference(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Persona(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Session(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Reflection(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Filter(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Scoring(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::InvalidEmbedding(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Idempotency(_)) => StatusCode::CONFLICT,
+            ServerError::Locai(locai::LocaiError::Rule(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Review(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Notification(_)) => StatusCode::BAD_REQUEST,
+            ServerError::Locai(locai::LocaiError::Usage(_)) => StatusCode::BAD_REQUEST,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -87,12 +116,15 @@ impl ServerError {
     /// Get the error type string
     pub fn error_type(&self) -> &'static str {
         match self {
+            ServerError::Locai(locai::LocaiError::QuotaExceeded { .. }) => "quota_exceeded",
             ServerError::Locai(_) => "locai_error",
             ServerError::Auth(_) => "authentication_error",
             ServerError::Database(_) => "database_error",
             ServerError::Validation(_) => "validation_error",
+            ServerError::Forbidden(_) => "forbidden",
             ServerError::NotFound(_) => "not_found",
             ServerError::BadRequest(_) => "bad_request",
+            ServerError::Conflict(_) => "conflict",
             ServerError::Internal(_) => "internal_error",
             ServerError::RateLimit => "rate_limit_exceeded",
             ServerError::WebSocket(_) => "websocket_error",
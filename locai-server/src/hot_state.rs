@@ -0,0 +1,544 @@
+//! Hot state shared between server replicas
+//!
+//! Scratchpads, rate-limit counters and cached search results are short-lived
+//! and read on nearly every request, so they live outside the memory store.
+//! By default they are kept in this process. With `LOCAI_REDIS_URL` set (and
+//! the `redis` feature), they are kept in Redis instead, so every replica
+//! behind a load balancer sees the same scratchpads, counts requests against
+//! the same limits and serves searches another replica already ran.
+//!
+//! Cached searches are keyed by a generation counter that every write through
+//! any replica bumps, so a write makes all earlier cached searches
+//! unreachable at once; they then expire on their own.
+//!
+//! Events replicas send each other go through [`crate::cluster`].
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::api::auth::AuthContext;
+use crate::api::dto::SearchResultDto;
+use crate::config::HotStateConfig;
+use crate::error::{ServerError, ServerResult};
+use crate::state::AppState;
+
+/// Local keys kept before expired ones are swept out
+const LOCAL_SWEEP_THRESHOLD: usize = 10_000;
+
+/// Key of the counter cached searches are keyed by
+const SEARCH_GENERATION_KEY: &str = "search:generation";
+
+/// A value in an agent's scratchpad
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScratchpadEntry {
+    /// Entry name, unique per user
+    pub name: String,
+    /// The stored value
+    #[schema(value_type = Object)]
+    pub value: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+    /// When the entry is dropped
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Values and sets kept in this process
+#[derive(Default)]
+struct LocalStore {
+    values: DashMap<String, (String, Option<Instant>)>,
+    sets: DashMap<String, HashSet<String>>,
+}
+
+/// Where hot state is kept
+enum Backend {
+    Local(LocalStore),
+    #[cfg(feature = "redis")]
+    Redis(redis::aio::ConnectionManager),
+}
+
+/// Scratchpads, rate limits and search results, local or in Redis
+pub struct HotState {
+    backend: Backend,
+    config: HotStateConfig,
+}
+
+impl std::fmt::Debug for HotState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HotState")
+            .field("backend", &self.backend_name())
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl HotState {
+    /// State kept in this process
+    pub fn local(config: HotStateConfig) -> Self {
+        Self {
+            backend: Backend::Local(LocalStore::default()),
+            config,
+        }
+    }
+
+    /// State kept where `config` says: Redis if it has a URL, else here
+    pub async fn connect(config: HotStateConfig) -> anyhow::Result<Self> {
+        let Some(url) = config.redis_url.clone() else {
+            return Ok(Self::local(config));
+        };
+        #[cfg(feature = "redis")]
+        {
+            let client = redis::Client::open(url.as_str())?;
+            let connection = redis::aio::ConnectionManager::new(client).await?;
+            Ok(Self {
+                backend: Backend::Redis(connection),
+                config,
+            })
+        }
+        #[cfg(not(feature = "redis"))]
+        {
+            anyhow::bail!(
+                "LOCAI_REDIS_URL is set to {}, but locai-server was built without the `redis` feature",
+                url
+            )
+        }
+    }
+
+    /// "local" or "redis", for the health endpoint
+    pub fn backend_name(&self) -> &'static str {
+        match &self.backend {
+            Backend::Local(_) => "local",
+            #[cfg(feature = "redis")]
+            Backend::Redis(_) => "redis",
+        }
+    }
+
+    /// Count a request from `client`, failing once it has made more than
+    /// `requests_per_minute` this minute; 0 allows any number
+    pub async fn check_rate_limit(
+        &self,
+        client: &str,
+        requests_per_minute: u32,
+    ) -> ServerResult<()> {
+        if requests_per_minute == 0 {
+            return Ok(());
+        }
+        let minute = unix_secs() / 60;
+        let key = format!("ratelimit:{}:{}", client, minute);
+        let count = self.incr(&key, Duration::from_secs(60)).await?;
+        if count > u64::from(requests_per_minute) {
+            return Err(ServerError::RateLimit);
+        }
+        Ok(())
+    }
+
+    /// An entry of `owner`'s scratchpad
+    pub async fn scratchpad_get(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> ServerResult<Option<ScratchpadEntry>> {
+        match self.get(&scratchpad_key(owner, name)).await? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set an entry of `owner`'s scratchpad, expiring after `ttl` or the
+    /// configured default
+    pub async fn scratchpad_put(
+        &self,
+        owner: &str,
+        name: &str,
+        value: serde_json::Value,
+        ttl: Option<Duration>,
+    ) -> ServerResult<ScratchpadEntry> {
+        let ttl = ttl.or_else(|| {
+            (self.config.scratchpad_ttl_secs > 0)
+                .then(|| Duration::from_secs(self.config.scratchpad_ttl_secs))
+        });
+        let now = Utc::now();
+        let entry = ScratchpadEntry {
+            name: name.to_string(),
+            value,
+            updated_at: now,
+            expires_at: ttl
+                .and_then(|ttl| chrono::Duration::from_std(ttl).ok().map(|ttl| now + ttl)),
+        };
+        self.set(
+            &scratchpad_key(owner, name),
+            &serde_json::to_string(&entry)?,
+            ttl,
+        )
+        .await?;
+        self.set_add(&scratchpad_index_key(owner), name).await?;
+        Ok(entry)
+    }
+
+    /// Remove an entry of `owner`'s scratchpad; false if it had none by this name
+    pub async fn scratchpad_delete(&self, owner: &str, name: &str) -> ServerResult<bool> {
+        self.set_remove(&scratchpad_index_key(owner), name).await?;
+        self.delete(&scratchpad_key(owner, name)).await
+    }
+
+    /// Every entry of `owner`'s scratchpad, by name
+    pub async fn scratchpad_list(&self, owner: &str) -> ServerResult<Vec<ScratchpadEntry>> {
+        let index = scratchpad_index_key(owner);
+        let mut entries = Vec::new();
+        for name in self.set_members(&index).await? {
+            match self.get(&scratchpad_key(owner, &name)).await? {
+                Some(json) => entries.push(serde_json::from_str::<ScratchpadEntry>(&json)?),
+                // Expired since it was indexed
+                None => self.set_remove(&index, &name).await?,
+            }
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Whether searches are cached here; only in Redis, since a single
+    /// process already has the memory manager's own search cache
+    pub fn caches_searches(&self) -> bool {
+        !matches!(self.backend, Backend::Local(_)) && self.config.search_cache_ttl_secs > 0
+    }
+
+    /// Cache key for `search` as of now, if searches are cached
+    ///
+    /// Read it before running the search, so results that raced a write are
+    /// stored under the generation the write already retired.
+    pub async fn search_cache_key(&self, search: &str) -> Option<String> {
+        if !self.caches_searches() {
+            return None;
+        }
+        self.search_key(search).await.map_or_else(
+            |e| {
+                warn!("Search cache lookup failed: {}", e);
+                None
+            },
+            Some,
+        )
+    }
+
+    /// Results cached under `key`, if any
+    pub async fn cached_search(&self, key: &str) -> Option<Vec<SearchResultDto>> {
+        self.lookup_search(key).await.unwrap_or_else(|e| {
+            warn!("Search cache lookup failed: {}", e);
+            None
+        })
+    }
+
+    /// Keep the results of a search until the next write or the TTL
+    pub async fn cache_search(&self, key: &str, results: &[SearchResultDto]) {
+        if let Err(e) = self.store_search(key, results).await {
+            warn!("Search cache store failed: {}", e);
+        }
+    }
+
+    /// Make every cached search stale, after a write
+    pub async fn invalidate_searches(&self) {
+        if !self.caches_searches() {
+            return;
+        }
+        if let Err(e) = self.incr(SEARCH_GENERATION_KEY, Duration::ZERO).await {
+            warn!("Search cache invalidation failed: {}", e);
+        }
+    }
+
+    async fn lookup_search(&self, key: &str) -> ServerResult<Option<Vec<SearchResultDto>>> {
+        match self.get(key).await? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn store_search(&self, key: &str, results: &[SearchResultDto]) -> ServerResult<()> {
+        let ttl = Duration::from_secs(self.config.search_cache_ttl_secs);
+        self.set(key, &serde_json::to_string(results)?, Some(ttl))
+            .await
+    }
+
+    async fn search_key(&self, search: &str) -> ServerResult<String> {
+        let generation = self
+            .get(SEARCH_GENERATION_KEY)
+            .await?
+            .unwrap_or_else(|| "0".to_string());
+        let hash = format!("{:x}", Sha256::digest(search.as_bytes()));
+        Ok(format!("search:{}:{}", generation, hash))
+    }
+
+    async fn get(&self, key: &str) -> ServerResult<Option<String>> {
+        match &self.backend {
+            Backend::Local(store) => {
+                let value = store.values.get(key).and_then(|entry| {
+                    let (value, expires) = entry.value();
+                    expires
+                        .is_none_or(|expires| Instant::now() < expires)
+                        .then(|| value.clone())
+                });
+                if value.is_none() {
+                    store
+                        .values
+                        .remove_if(key, |_, (_, expires)| is_expired(*expires));
+                }
+                Ok(value)
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(connection) => {
+                let mut connection = connection.clone();
+                redis::AsyncCommands::get(&mut connection, self.redis_key(key))
+                    .await
+                    .map_err(redis_error)
+            }
+        }
+    }
+
+    /// Store `value`, expiring after `ttl` if given
+    async fn set(&self, key: &str, value: &str, ttl: Option<Duration>) -> ServerResult<()> {
+        match &self.backend {
+            Backend::Local(store) => {
+                sweep(&store.values);
+                let expires = ttl.map(|ttl| Instant::now() + ttl);
+                store
+                    .values
+                    .insert(key.to_string(), (value.to_string(), expires));
+                Ok(())
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(connection) => {
+                let mut connection = connection.clone();
+                let key = self.redis_key(key);
+                match ttl {
+                    Some(ttl) => {
+                        redis::AsyncCommands::set_ex(
+                            &mut connection,
+                            key,
+                            value,
+                            ttl.as_secs().max(1),
+                        )
+                        .await
+                    }
+                    None => redis::AsyncCommands::set(&mut connection, key, value).await,
+                }
+                .map_err(redis_error)
+            }
+        }
+    }
+
+    async fn delete(&self, key: &str) -> ServerResult<bool> {
+        match &self.backend {
+            Backend::Local(store) => Ok(store
+                .values
+                .remove(key)
+                .is_some_and(|(_, (_, expires))| !is_expired(expires))),
+            #[cfg(feature = "redis")]
+            Backend::Redis(connection) => {
+                let mut connection = connection.clone();
+                let removed: u64 = redis::AsyncCommands::del(&mut connection, self.redis_key(key))
+                    .await
+                    .map_err(redis_error)?;
+                Ok(removed > 0)
+            }
+        }
+    }
+
+    async fn set_add(&self, key: &str, member: &str) -> ServerResult<()> {
+        match &self.backend {
+            Backend::Local(store) => {
+                store
+                    .sets
+                    .entry(key.to_string())
+                    .or_default()
+                    .insert(member.to_string());
+                Ok(())
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(connection) => {
+                let mut connection = connection.clone();
+                redis::AsyncCommands::sadd(&mut connection, self.redis_key(key), member)
+                    .await
+                    .map_err(redis_error)
+            }
+        }
+    }
+
+    async fn set_remove(&self, key: &str, member: &str) -> ServerResult<()> {
+        match &self.backend {
+            Backend::Local(store) => {
+                if let Some(mut members) = store.sets.get_mut(key) {
+                    members.remove(member);
+                }
+                Ok(())
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(connection) => {
+                let mut connection = connection.clone();
+                redis::AsyncCommands::srem(&mut connection, self.redis_key(key), member)
+                    .await
+                    .map_err(redis_error)
+            }
+        }
+    }
+
+    async fn set_members(&self, key: &str) -> ServerResult<Vec<String>> {
+        match &self.backend {
+            Backend::Local(store) => Ok(store
+                .sets
+                .get(key)
+                .map(|members| members.iter().cloned().collect())
+                .unwrap_or_default()),
+            #[cfg(feature = "redis")]
+            Backend::Redis(connection) => {
+                let mut connection = connection.clone();
+                redis::AsyncCommands::smembers(&mut connection, self.redis_key(key))
+                    .await
+                    .map_err(redis_error)
+            }
+        }
+    }
+
+    /// Increment a counter, starting it at 1 with an expiry of `ttl` (none
+    /// if zero)
+    async fn incr(&self, key: &str, ttl: Duration) -> ServerResult<u64> {
+        match &self.backend {
+            Backend::Local(store) => {
+                sweep(&store.values);
+                let mut entry = store
+                    .values
+                    .entry(key.to_string())
+                    .or_insert_with(|| ("0".to_string(), None));
+                let (value, expires) = entry.value_mut();
+                if is_expired(*expires) {
+                    *value = "0".to_string();
+                    *expires = None;
+                }
+                let count = value.parse::<u64>().unwrap_or(0) + 1;
+                *value = count.to_string();
+                if count == 1 && !ttl.is_zero() {
+                    *expires = Some(Instant::now() + ttl);
+                }
+                Ok(count)
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(connection) => {
+                let mut connection = connection.clone();
+                let key = self.redis_key(key);
+                let count: u64 = redis::AsyncCommands::incr(&mut connection, &key, 1)
+                    .await
+                    .map_err(redis_error)?;
+                if count == 1 && !ttl.is_zero() {
+                    let _: bool = redis::AsyncCommands::expire(
+                        &mut connection,
+                        &key,
+                        ttl.as_secs().max(1) as i64,
+                    )
+                    .await
+                    .map_err(redis_error)?;
+                }
+                Ok(count)
+            }
+        }
+    }
+
+    #[cfg(feature = "redis")]
+    fn redis_key(&self, key: &str) -> String {
+        format!("{}{}", self.config.key_prefix, key)
+    }
+}
+
+/// Rate-limit requests and, after a successful write, make cached searches
+/// stale
+///
+/// Runs after authentication, so signed-in users are limited by account and
+/// others by address (the first `X-Forwarded-For` hop behind a proxy).
+pub async fn hot_state_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ServerError> {
+    let path = request.uri().path();
+    // Probes shouldn't use up a client's requests
+    if !matches!(path, "/health" | "/ready") {
+        state
+            .hot_state
+            .check_rate_limit(&client_id(&request), state.config.rate_limit_rpm)
+            .await?;
+    }
+
+    let writes = !matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) && !path.contains("/search");
+    let response = next.run(request).await;
+    if writes && response.status().is_success() {
+        state.hot_state.invalidate_searches().await;
+    }
+    Ok(response)
+}
+
+/// Who a request counts against for rate limiting
+fn client_id(request: &Request) -> String {
+    if let Some(auth) = request.extensions().get::<AuthContext>() {
+        return format!("user:{}", auth.user_id);
+    }
+    let forwarded = request
+        .headers()
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty());
+    if let Some(ip) = forwarded {
+        return format!("ip:{}", ip);
+    }
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| format!("ip:{}", address.ip()))
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+fn scratchpad_key(owner: &str, name: &str) -> String {
+    format!("scratchpad:{}:{}", owner, name)
+}
+
+/// Set of the names in `owner`'s scratchpad
+fn scratchpad_index_key(owner: &str) -> String {
+    format!("scratchpad-index:{}", owner)
+}
+
+fn is_expired(expires: Option<Instant>) -> bool {
+    expires.is_some_and(|expires| Instant::now() >= expires)
+}
+
+/// Drop expired local entries once there are many, so counters for past
+/// minutes don't pile up
+fn sweep(entries: &DashMap<String, (String, Option<Instant>)>) {
+    if entries.len() > LOCAL_SWEEP_THRESHOLD {
+        entries.retain(|_, (_, expires)| !is_expired(*expires));
+    }
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(feature = "redis")]
+fn redis_error(e: redis::RedisError) -> ServerError {
+    ServerError::Internal(format!("Redis error: {}", e))
+}
@@ -0,0 +1,595 @@
+//! Background jobs
+//!
+//! Long operations (bulk imports, reindexing, consolidation, maintenance) run
+//! as jobs instead of inside HTTP handlers. At most
+//! [`ServerConfig::max_concurrent_jobs`](crate::config::ServerConfig) run at
+//! once; the rest wait as `queued`, oldest first. Cancelling a job stops it at
+//! its next await point, keeping whatever it already wrote.
+//!
+//! Every state change and progress update is stored as an entity of type
+//! [`JOB_ENTITY_TYPE`], so job history survives a restart. Jobs that were still
+//! queued or running when the server stopped are marked failed by
+//! [`JobQueue::restore`].
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use locai::core::{MemoryManager, check_consistency, repair_consistency};
+use locai::ingest::{IngestConfig, IngestJob};
+use locai::memory::consolidation::{ConsolidationConfig, MemoryConsolidator};
+use locai::models::{Memory, MemoryBuilder, MemoryPriority, MemoryType};
+use locai::storage::{filters::EntityFilter, models::Entity};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::sync::{Semaphore, watch};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::dto::CreateMemoryRequest;
+use crate::error::{ServerError, ServerResult, not_found};
+use crate::state::AppState;
+
+/// Entity type jobs are stored under
+pub const JOB_ENTITY_TYPE: &str = "server_job";
+
+/// Finished jobs kept; older ones are deleted
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// What a job does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// Store a batch of memories
+    Import,
+    /// Check index consistency, repair it and recount quota usage
+    Reindex,
+    /// Detect patterns and connections among recent memories
+    Consolidate,
+    /// Drop old memory versions
+    Compact,
+    /// Enforce message retention and archive inactive memories
+    Retention,
+    /// Promote delta versions with a broken chain to full copies
+    RepairVersions,
+    /// Drop the search result and version caches
+    ClearCaches,
+}
+
+impl JobKind {
+    /// Maintenance jobs need the admin role when authentication is enabled
+    pub fn requires_admin(self) -> bool {
+        !matches!(self, Self::Import | Self::Consolidate)
+    }
+
+    /// Only one job of an exclusive kind runs or waits at a time
+    fn is_exclusive(self) -> bool {
+        self != Self::Import
+    }
+}
+
+/// Lifecycle of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Whether the job has stopped for good
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// How far a job has got, for jobs that can tell
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct JobProgress {
+    /// Items processed so far
+    pub done: u64,
+    /// Items to process, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+}
+
+/// A background job and, once finished, its outcome
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<JobProgress>,
+    /// User who submitted the job; absent when authentication is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Report of a finished job; its shape depends on the kind
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Why a failed job failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Job {
+    fn from_entity(entity: Entity) -> Option<Self> {
+        serde_json::from_value(entity.properties).ok()
+    }
+
+    fn to_entity(&self) -> Entity {
+        Entity {
+            id: self.id.clone(),
+            entity_type: JOB_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(self).unwrap_or_default(),
+            created_at: self.created_at,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// A job to submit, with its parameters
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobRequest {
+    Import {
+        memories: Vec<CreateMemoryRequest>,
+        /// Memories written per storage call
+        batch_size: Option<usize>,
+    },
+    Reindex,
+    Consolidate {
+        /// Only consider memories created within this many days
+        max_memory_age_days: Option<i64>,
+    },
+    Compact {
+        /// Only compact this memory's versions; all memories when absent
+        memory_id: Option<String>,
+        /// Versions to keep per memory
+        keep_count: Option<usize>,
+        /// Only drop versions older than this many days
+        older_than_days: Option<u64>,
+    },
+    Retention {
+        /// Also archive memories not accessed for this many days
+        archive_older_than_days: Option<u64>,
+        /// Archive at most this many memories
+        archive_limit: Option<usize>,
+        /// Also archive memories faded below the forgetting curve's threshold
+        #[serde(default)]
+        archive_forgotten: bool,
+    },
+    RepairVersions {
+        /// Only repair this memory's versions; all memories when absent
+        memory_id: Option<String>,
+    },
+    ClearCaches,
+}
+
+impl JobRequest {
+    pub fn kind(&self) -> JobKind {
+        match self {
+            Self::Import { .. } => JobKind::Import,
+            Self::Reindex => JobKind::Reindex,
+            Self::Consolidate { .. } => JobKind::Consolidate,
+            Self::Compact { .. } => JobKind::Compact,
+            Self::Retention { .. } => JobKind::Retention,
+            Self::RepairVersions { .. } => JobKind::RepairVersions,
+            Self::ClearCaches => JobKind::ClearCaches,
+        }
+    }
+
+    async fn run(self, ctx: &JobContext) -> Result<Value, String> {
+        let manager = &ctx.state.memory_manager;
+        match self {
+            Self::Import {
+                memories,
+                batch_size,
+            } => import(ctx, memories, batch_size).await,
+            Self::Reindex => {
+                let report = check_consistency(manager)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let repair = repair_consistency(manager, &report)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                manager
+                    .refresh_quota_usage()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(json!({ "issues": report, "repair": repair }))
+            }
+            Self::Consolidate {
+                max_memory_age_days,
+            } => {
+                let mut config = ConsolidationConfig::default();
+                if let Some(days) = max_memory_age_days {
+                    config.max_memory_age_days = days;
+                }
+                let result = MemoryConsolidator::new()
+                    .consolidate_memories(manager, &config)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                serde_json::to_value(result).map_err(|e| e.to_string())
+            }
+            Self::Compact {
+                memory_id,
+                keep_count,
+                older_than_days,
+            } => {
+                let removed = version_store(manager)?
+                    .compact_versions(memory_id.as_deref(), keep_count, older_than_days)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(json!({ "versions_removed": removed }))
+            }
+            Self::Retention {
+                archive_older_than_days,
+                archive_limit,
+                archive_forgotten,
+            } => {
+                let messages = manager
+                    .enforce_message_retention()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let archived = match archive_older_than_days {
+                    Some(days) => manager
+                        .archive_inactive_memories(days, archive_limit)
+                        .await
+                        .map_err(|e| e.to_string())?,
+                    None => Vec::new(),
+                };
+                let forgotten = if archive_forgotten {
+                    manager
+                        .archive_forgotten_memories(archive_limit)
+                        .await
+                        .map_err(|e| e.to_string())?
+                } else {
+                    Vec::new()
+                };
+                Ok(json!({
+                    "messages": messages,
+                    "archived_memories": archived,
+                    "forgotten_memories": forgotten,
+                }))
+            }
+            Self::RepairVersions { memory_id } => {
+                let report = version_store(manager)?
+                    .repair_versions(memory_id.as_deref())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                serde_json::to_value(report).map_err(|e| e.to_string())
+            }
+            Self::ClearCaches => {
+                let cached_searches = manager
+                    .search_cache_stats()
+                    .map_or(0, |stats| stats.entries);
+                manager.clear_caches().await;
+                Ok(json!({
+                    "searches_dropped": cached_searches,
+                }))
+            }
+        }
+    }
+}
+
+/// Handle a running job uses to report progress
+struct JobContext {
+    state: Arc<AppState>,
+    id: String,
+}
+
+impl JobContext {
+    async fn progress(&self, done: u64, total: Option<u64>) {
+        let job = self.state.jobs.update(&self.id, |job| {
+            job.progress = Some(JobProgress { done, total });
+        });
+        if let Some(job) = job {
+            persist(&self.state.memory_manager, &job).await;
+        }
+    }
+}
+
+/// Jobs known to this server, with the cancellation switch of each live one
+#[derive(Debug)]
+pub struct JobQueue {
+    jobs: DashMap<String, Job>,
+    cancels: DashMap<String, watch::Sender<bool>>,
+    slots: Arc<Semaphore>,
+}
+
+impl JobQueue {
+    /// Create a queue running at most `max_concurrent` jobs at once
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            jobs: DashMap::new(),
+            cancels: DashMap::new(),
+            slots: Arc::new(Semaphore::new(max_concurrent.max(1))),
+        }
+    }
+
+    /// Load stored jobs, failing those a restart interrupted
+    ///
+    /// Returns the number of interrupted jobs.
+    pub async fn restore(&self, memory_manager: &MemoryManager) -> ServerResult<usize> {
+        let filter = EntityFilter {
+            entity_type: Some(JOB_ENTITY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let entities = memory_manager
+            .storage()
+            .list_entities(Some(filter), None, None)
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to load jobs: {}", e)))?;
+
+        let mut interrupted = 0;
+        for mut job in entities.into_iter().filter_map(Job::from_entity) {
+            if !job.status.is_finished() {
+                job.status = JobStatus::Failed;
+                job.error = Some("Interrupted by a server restart".to_string());
+                job.finished_at = Some(Utc::now());
+                persist(memory_manager, &job).await;
+                interrupted += 1;
+            }
+            self.jobs.insert(job.id.clone(), job);
+        }
+        self.prune(memory_manager).await;
+        Ok(interrupted)
+    }
+
+    /// All known jobs, newest first
+    pub fn list(&self) -> Vec<Job> {
+        let mut jobs: Vec<Job> = self.jobs.iter().map(|job| job.value().clone()).collect();
+        jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        jobs
+    }
+
+    /// Get a job by ID
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.get(id).map(|job| job.value().clone())
+    }
+
+    /// Ask a queued or running job to stop
+    pub fn cancel(&self, id: &str) -> ServerResult<Job> {
+        let job = self.get(id).ok_or_else(|| not_found("Job", id))?;
+        if job.status.is_finished() {
+            return Err(ServerError::Conflict(format!(
+                "Job {} has already finished",
+                id
+            )));
+        }
+        if let Some(cancel) = self.cancels.get(id) {
+            cancel.send_replace(true);
+        }
+        Ok(job)
+    }
+
+    fn update(&self, id: &str, change: impl FnOnce(&mut Job)) -> Option<Job> {
+        self.jobs.get_mut(id).map(|mut job| {
+            change(&mut job);
+            job.clone()
+        })
+    }
+
+    /// Delete the oldest finished jobs beyond [`MAX_FINISHED_JOBS`]
+    async fn prune(&self, memory_manager: &MemoryManager) {
+        let mut finished: Vec<(DateTime<Utc>, String)> = self
+            .jobs
+            .iter()
+            .filter(|job| job.status.is_finished())
+            .map(|job| (job.created_at, job.id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_JOBS {
+            return;
+        }
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            self.jobs.remove(id);
+            if let Err(e) = memory_manager.storage().delete_entity(id).await {
+                tracing::warn!("Failed to delete job {}: {}", id, e);
+            }
+        }
+    }
+}
+
+/// Queue a job and return it in its `queued` state
+pub async fn submit(
+    state: &Arc<AppState>,
+    request: JobRequest,
+    requested_by: Option<String>,
+) -> ServerResult<Job> {
+    let kind = request.kind();
+    if kind.is_exclusive()
+        && let Some(pending) = state
+            .jobs
+            .jobs
+            .iter()
+            .find(|job| job.kind == kind && !job.status.is_finished())
+    {
+        return Err(ServerError::Conflict(format!(
+            "A {:?} job is already {:?} ({})",
+            kind, pending.status, pending.id
+        )));
+    }
+
+    let job = Job {
+        id: Uuid::new_v4().to_string(),
+        kind,
+        status: JobStatus::Queued,
+        progress: None,
+        requested_by,
+        created_at: Utc::now(),
+        started_at: None,
+        finished_at: None,
+        result: None,
+        error: None,
+    };
+    // Listed before it is stored, so a concurrent submit sees it as pending
+    let (cancel, cancelled) = watch::channel(false);
+    state.jobs.jobs.insert(job.id.clone(), job.clone());
+    state.jobs.cancels.insert(job.id.clone(), cancel);
+    if let Err(e) = state
+        .memory_manager
+        .storage()
+        .create_entity(job.to_entity())
+        .await
+    {
+        state.jobs.jobs.remove(&job.id);
+        state.jobs.cancels.remove(&job.id);
+        return Err(ServerError::Database(format!("Failed to store job: {}", e)));
+    }
+    tracing::info!("Queued {:?} job {}", kind, job.id);
+
+    let ctx = JobContext {
+        state: state.clone(),
+        id: job.id.clone(),
+    };
+    tokio::spawn(run(ctx, request, cancelled));
+    Ok(job)
+}
+
+/// Wait for a slot, run the job and record how it ended
+async fn run(ctx: JobContext, request: JobRequest, mut cancelled: watch::Receiver<bool>) {
+    let state = &ctx.state;
+    let kind = request.kind();
+
+    let permit = tokio::select! {
+        permit = state.jobs.slots.clone().acquire_owned() => permit.ok(),
+        _ = cancelled.wait_for(|cancelled| *cancelled) => None,
+    };
+    let outcome = match permit {
+        Some(_permit) => {
+            if let Some(job) = state.jobs.update(&ctx.id, |job| {
+                job.status = JobStatus::Running;
+                job.started_at = Some(Utc::now());
+            }) {
+                persist(&state.memory_manager, &job).await;
+            }
+            tokio::select! {
+                outcome = request.run(&ctx) => Some(outcome),
+                _ = cancelled.wait_for(|cancelled| *cancelled) => None,
+            }
+        }
+        None => None,
+    };
+
+    match &outcome {
+        Some(Ok(_)) => tracing::info!("{:?} job {} succeeded", kind, ctx.id),
+        Some(Err(e)) => tracing::warn!("{:?} job {} failed: {}", kind, ctx.id, e),
+        None => tracing::info!("{:?} job {} cancelled", kind, ctx.id),
+    }
+    let job = state.jobs.update(&ctx.id, |job| {
+        job.finished_at = Some(Utc::now());
+        match outcome {
+            Some(Ok(result)) => {
+                job.status = JobStatus::Succeeded;
+                job.result = Some(result);
+            }
+            Some(Err(error)) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(error);
+            }
+            None => job.status = JobStatus::Cancelled,
+        }
+    });
+    state.jobs.cancels.remove(&ctx.id);
+    if let Some(job) = job {
+        persist(&state.memory_manager, &job).await;
+    }
+    state.jobs.prune(&state.memory_manager).await;
+}
+
+/// Store the job's current state; failures are logged, as the job itself is
+/// unaffected
+async fn persist(memory_manager: &MemoryManager, job: &Job) {
+    if let Err(e) = memory_manager
+        .storage()
+        .update_entity(job.to_entity())
+        .await
+    {
+        tracing::warn!("Failed to store job {}: {}", job.id, e);
+    }
+}
+
+fn version_store(
+    manager: &MemoryManager,
+) -> Result<&dyn locai::storage::traits::MemoryVersionStore, String> {
+    manager
+        .memory_version_store()
+        .ok_or_else(|| "The storage backend doesn't support memory versioning".to_string())
+}
+
+/// Cancels the ingestion when the job is cancelled, which drops its future
+struct CancelOnDrop(IngestJob);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+async fn import(
+    ctx: &JobContext,
+    memories: Vec<CreateMemoryRequest>,
+    batch_size: Option<usize>,
+) -> Result<Value, String> {
+    let total = memories.len() as u64;
+    ctx.progress(0, Some(total)).await;
+
+    let mut config = IngestConfig::default();
+    if let Some(batch_size) = batch_size {
+        config.batch_size = batch_size.max(1);
+    }
+    let memories: Vec<Memory> = memories.into_iter().map(memory_from_request).collect();
+    let ingest = CancelOnDrop(IngestJob::spawn(
+        ctx.state.memory_manager.clone(),
+        futures::stream::iter(memories),
+        config,
+    ));
+
+    let mut updates = ingest.0.subscribe();
+    while updates.changed().await.is_ok() {
+        let progress = updates.borrow_and_update().clone();
+        ctx.progress((progress.stored + progress.failed) as u64, Some(total))
+            .await;
+        if progress.state.is_finished() {
+            break;
+        }
+    }
+
+    let progress = ingest.0.progress();
+    ctx.progress((progress.stored + progress.failed) as u64, Some(total))
+        .await;
+    Ok(json!({
+        "stored": progress.stored,
+        "failed": progress.failed,
+        "failures": progress.failures,
+    }))
+}
+
+fn memory_from_request(request: CreateMemoryRequest) -> Memory {
+    let priority = match request.priority.to_lowercase().as_str() {
+        "low" => MemoryPriority::Low,
+        "high" => MemoryPriority::High,
+        "critical" => MemoryPriority::Critical,
+        _ => MemoryPriority::Normal,
+    };
+    let mut builder = MemoryBuilder::new_with_content(request.content)
+        .memory_type(MemoryType::from_str(&request.memory_type))
+        .priority(priority)
+        .tags(request.tags.iter().map(|s| s.as_str()).collect())
+        .source(request.source)
+        .properties_json(request.properties);
+    if let Some(embedding) = request.embedding {
+        builder = builder.embedding(embedding);
+    }
+    builder.build()
+}
@@ -1,11 +1,19 @@
 pub mod api;
 pub mod cli;
+pub mod cluster;
 pub mod config;
+pub mod embeddings;
 pub mod error;
+pub mod hot_state;
+pub mod jobs;
 pub mod messaging;
+pub mod server;
+pub mod sharing;
 pub mod state;
+pub mod usage;
 pub mod websocket;
 
 pub use api::create_router;
 pub use error::ServerError;
+pub use server::serve;
 pub use state::AppState;
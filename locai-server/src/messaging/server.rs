@@ -1,6 +1,7 @@
 //! Messaging server implementation
 
 use super::{MessagingStorage, Result};
+use crate::cluster::{ClusterEvent, EventBus};
 use crate::config::MessagingConfig;
 use locai::messaging::types::{Message, MessageFilter, MessageId};
 use std::{collections::HashMap, sync::Arc};
@@ -46,6 +47,9 @@ pub struct MessagingServer {
 
     // Global message broadcast
     global_broadcast: broadcast::Sender<Message>,
+
+    // Other replicas' subscribers
+    events: EventBus,
 }
 
 impl MessagingServer {
@@ -66,9 +70,15 @@ impl MessagingServer {
             app_connections: Arc::new(RwLock::new(HashMap::new())),
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
             global_broadcast,
+            events: EventBus::local(),
         }
     }
 
+    /// Share sent messages with the subscribers of other replicas
+    pub fn set_event_bus(&mut self, events: EventBus) {
+        self.events = events;
+    }
+
     /// Register a new connection
     pub async fn register_connection(&self, connection_id: String, app_id: String) -> Result<()> {
         let app_info = AppInfo {
@@ -158,7 +168,8 @@ impl MessagingServer {
         // Store message
         self.storage.store_message(&message).await?;
 
-        // Broadcast to subscribers
+        // Broadcast to subscribers, here and on other replicas
+        self.events.publish(ClusterEvent::Message(message.clone()));
         if let Err(e) = self.global_broadcast.send(message) {
             debug!("No active subscribers for message broadcast: {}", e);
         }
@@ -172,6 +183,13 @@ impl MessagingServer {
         Ok(message_id)
     }
 
+    /// Hand a message another replica sent to this replica's subscribers
+    pub fn deliver_remote(&self, message: Message) {
+        if let Err(e) = self.global_broadcast.send(message) {
+            debug!("No active subscribers for message broadcast: {}", e);
+        }
+    }
+
     /// Subscribe to messages
     pub async fn subscribe(
         &self,
@@ -0,0 +1,229 @@
+//! Server startup, shared by the `locai-server` binary and `locai-cli serve`
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use locai::core::MemoryManager;
+use tokio::net::TcpListener;
+use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tracing::{info, warn};
+
+use crate::api::create_router;
+use crate::cluster::EventBus;
+use crate::config::ServerConfig;
+use crate::hot_state::HotState;
+use crate::state::AppState;
+use crate::{embeddings, messaging};
+
+/// Start the optional subsystems and serve the HTTP and WebSocket API until
+/// the listener fails
+pub async fn serve(memory_manager: MemoryManager, server_config: ServerConfig) -> Result<()> {
+    // Create application state
+    let mut app_state = AppState::new(memory_manager, server_config.clone());
+
+    // Share hot state and events with other replicas through Redis if configured
+    let (events, incoming_events) = EventBus::connect(&server_config.hot_state).await?;
+    if server_config.hot_state.redis_url.is_some() {
+        let hot_state = HotState::connect(server_config.hot_state.clone()).await?;
+        info!(
+            "Hot state and events shared through Redis (replica {})",
+            events.replica_id()
+        );
+        app_state.set_hot_state(hot_state);
+    }
+    app_state.set_event_bus(events.clone());
+
+    // Initialize messaging server if enabled using shared storage from the memory manager
+    if server_config.messaging.enabled {
+        // Get the shared storage from the memory manager instead of creating a separate instance
+        let shared_storage = app_state.memory_manager.storage();
+        let mut messaging_server = messaging::MessagingServer::new_with_shared_storage(
+            server_config.messaging.clone(),
+            shared_storage,
+        );
+        messaging_server.set_event_bus(events.clone());
+        info!("Messaging server initialized successfully with shared storage from memory manager");
+        app_state.set_messaging_server(Arc::new(messaging_server));
+    }
+
+    // Initialize the embedding proxy if enabled, caching into the main storage
+    if server_config.embedding_proxy.enabled {
+        let proxy = embeddings::EmbeddingProxy::new(
+            server_config.embedding_proxy.clone(),
+            app_state.memory_manager.storage().clone(),
+        )?;
+        info!(
+            "Embedding proxy enabled (upstream: {}, model: {})",
+            server_config.embedding_proxy.upstream_url, server_config.embedding_proxy.model
+        );
+        app_state.set_embedding_proxy(Arc::new(proxy));
+    }
+
+    // Start replication if enabled, capturing changes from the main storage
+    if server_config.replication.enabled {
+        let mut replication_config = locai::replication::ReplicationConfig {
+            conflict_policy: server_config.replication.conflict_policy,
+            ..Default::default()
+        };
+        if let Some(node_id) = &server_config.replication.node_id {
+            replication_config.node_id = node_id.clone();
+        }
+        let replicator = locai::replication::Replicator::start(
+            app_state.memory_manager.storage().clone(),
+            replication_config,
+        )
+        .await?;
+        info!(
+            "Replication enabled (node: {}, policy: {:?})",
+            replicator.node_id(),
+            replicator.config().conflict_policy
+        );
+        app_state.set_replicator(replicator);
+    }
+
+    // Initialize authentication if enabled
+    if server_config.enable_auth
+        && let Err(e) = initialize_auth(&mut app_state, server_config.clone()).await
+    {
+        warn!(
+            "Failed to initialize authentication: {}. Auth may not work properly.",
+            e
+        );
+    }
+
+    // Load job history, failing jobs the last shutdown interrupted
+    match app_state.jobs.restore(&app_state.memory_manager).await {
+        Ok(0) => {}
+        Ok(interrupted) => warn!(
+            "{} background jobs were interrupted by a restart",
+            interrupted
+        ),
+        Err(e) => warn!("Failed to load background jobs: {}", e),
+    }
+    if let Err(e) = app_state.shares.restore(&app_state.memory_manager).await {
+        warn!("Failed to load share grants: {}", e);
+    }
+
+    let app_state = Arc::new(app_state);
+
+    // Pass on what other replicas publish
+    app_state.spawn_event_listener(incoming_events);
+
+    // Deliver notifications committed before a crash, then keep relaying
+    app_state.spawn_outbox_relay();
+
+    // Surface memories whose reminders come due
+    app_state.spawn_reminder_scheduler();
+
+    // Count usage per tenant for billing
+    if app_state.config.usage_accounting {
+        app_state.spawn_usage_flusher();
+    }
+
+    // Run automation rules on memory events
+    if app_state.memory_manager.config().rules.enabled
+        && let Err(e) = app_state.memory_manager.enable_rules().await
+    {
+        warn!("Failed to enable rules: {}. Continuing without rules.", e);
+    }
+
+    // Warm the search indexes; /api/ready reports ready once they are
+    if app_state.memory_manager.config().warmup.enabled {
+        app_state.spawn_warmup();
+    }
+
+    // Initialize live queries if enabled and using SurrealDB
+    if server_config.enable_live_queries
+        && let Err(e) = setup_live_queries(app_state.clone()).await
+    {
+        warn!(
+            "Failed to setup live queries: {}. Continuing without live query support.",
+            e
+        );
+    }
+
+    // Create the router with all API endpoints
+    let app = create_router(app_state.clone())
+        .layer(CorsLayer::permissive())
+        .layer(TraceLayer::new_for_http());
+
+    // Start the server
+    let addr = SocketAddr::from(([0, 0, 0, 0], server_config.port));
+    let listener = TcpListener::bind(addr).await?;
+
+    info!("Server listening on {}", addr);
+    info!("API documentation available at http://{}/docs", addr);
+
+    if server_config.enable_auth {
+        info!("Authentication is enabled");
+        if server_config.allow_signup {
+            info!("User signup is enabled");
+        } else {
+            info!("User signup is disabled");
+        }
+    } else {
+        info!("Authentication is disabled");
+    }
+
+    // Connection addresses identify unauthenticated clients for rate limiting
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Initialize authentication system and create root user if needed
+async fn initialize_auth(app_state: &mut AppState, server_config: ServerConfig) -> Result<()> {
+    use crate::api::auth_service::AuthService;
+
+    info!("Initializing authentication system using storage abstractions");
+
+    // Create the auth service
+    let auth_service = AuthService::new(server_config.jwt_secret.clone());
+
+    // Initialize the authentication system
+    if let Err(e) = auth_service
+        .initialize(
+            &app_state.memory_manager,
+            server_config.root_password.clone(),
+        )
+        .await
+    {
+        return Err(anyhow::anyhow!("Failed to initialize auth service: {}", e));
+    }
+
+    // Store the auth service in app state
+    app_state.set_auth_service(auth_service);
+
+    Ok(())
+}
+
+/// Setup live queries for SurrealDB if available
+async fn setup_live_queries(app_state: Arc<AppState>) -> Result<()> {
+    info!("Setting up live queries");
+
+    // Get the memory manager from app state
+    let memory_manager = &app_state.memory_manager;
+
+    // Get the graph store from the storage service
+    let graph_store = memory_manager.storage();
+
+    // Check if the store supports live queries
+    if graph_store.supports_live_queries() {
+        info!("Graph store supports live queries, setting up...");
+
+        // For now, we'll use a simpler approach that doesn't require unsafe downcasting
+        // The shared_storage module will handle live queries internally if available
+        // and broadcast events through the normal channels
+
+        info!("Live query system configured successfully");
+    } else {
+        info!("Graph store does not support live queries, skipping setup");
+    }
+
+    Ok(())
+}
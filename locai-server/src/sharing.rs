@@ -0,0 +1,381 @@
+//! Sharing memories between users
+//!
+//! With authentication enabled, a memory created through the API belongs to
+//! its creator: the `owner` property holds their user ID, and nobody else
+//! sees it. Owners share with [`ShareGrant`]s, either one memory or every
+//! memory of theirs whose `collection` property names a collection. Readers
+//! see shared memories in get, list and search; writers can also update them.
+//! Only the owner (or an admin) deletes a memory or shares it further.
+//! Memories without an owner, such as those created with authentication
+//! disabled, stay visible to and editable by everyone.
+//!
+//! Grants are stored as entities of type [`SHARE_ENTITY_TYPE`].
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use locai::core::MemoryManager;
+use locai::models::Memory;
+use locai::storage::{filters::EntityFilter, models::Entity};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::auth::{AuthContext, check_role_permission};
+use crate::error::{ServerError, ServerResult, not_found};
+
+/// Memory property holding the owner's user ID
+pub const OWNER_PROPERTY: &str = "owner";
+
+/// Memory property naming the owner's collection the memory belongs to
+pub const COLLECTION_PROPERTY: &str = "collection";
+
+/// Entity type grants are stored under
+pub const SHARE_ENTITY_TYPE: &str = "share_grant";
+
+/// What a grant lets the grantee do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareRole {
+    /// Read the memories
+    Reader,
+    /// Read and update the memories
+    Writer,
+}
+
+/// What a user may do with a memory, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Access {
+    None,
+    Read,
+    Write,
+    /// Everything, including deleting and sharing
+    Owner,
+}
+
+/// Access to a memory, or to a collection of memories, granted to a user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShareGrant {
+    pub id: String,
+    /// User ID of the owner of the shared memories
+    pub owner_id: String,
+    /// Username of the owner
+    pub owner: String,
+    /// User ID of the user the memories are shared with
+    pub grantee_id: String,
+    /// Username of the user the memories are shared with
+    pub grantee: String,
+    /// Shared memory, for a memory grant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_id: Option<String>,
+    /// Shared collection, for a collection grant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    pub role: ShareRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShareGrant {
+    /// Whether this grant applies to the memory with `memory_id` and
+    /// `properties`, owned by `owner_id`
+    fn covers(&self, memory_id: &str, properties: &serde_json::Value, owner_id: &str) -> bool {
+        if self.owner_id != owner_id {
+            return false;
+        }
+        match (&self.memory_id, &self.collection) {
+            (Some(granted), _) => granted == memory_id,
+            (None, Some(collection)) => {
+                string_property(properties, COLLECTION_PROPERTY) == Some(collection.as_str())
+            }
+            (None, None) => false,
+        }
+    }
+
+    /// Whether both grants give the same user access to the same memories
+    fn same_target(&self, other: &ShareGrant) -> bool {
+        self.owner_id == other.owner_id
+            && self.grantee_id == other.grantee_id
+            && self.memory_id == other.memory_id
+            && self.collection == other.collection
+    }
+
+    fn from_entity(entity: Entity) -> Option<Self> {
+        serde_json::from_value(entity.properties).ok()
+    }
+
+    fn to_entity(&self) -> Entity {
+        Entity {
+            id: self.id.clone(),
+            entity_type: SHARE_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(self).unwrap_or_default(),
+            created_at: self.created_at,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// User ID of the memory's owner; `None` for a public memory
+pub fn owner_of(memory: &Memory) -> Option<&str> {
+    string_property(&memory.properties, OWNER_PROPERTY)
+}
+
+/// Collection the memory belongs to, if any
+pub fn collection_of(memory: &Memory) -> Option<&str> {
+    string_property(&memory.properties, COLLECTION_PROPERTY)
+}
+
+fn string_property<'a>(properties: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    properties.get(key).and_then(|value| value.as_str())
+}
+
+/// Whether the user can see and change every memory
+pub fn is_admin(user: &AuthContext) -> bool {
+    check_role_permission(user, "admin")
+}
+
+/// Share grants known to this server
+#[derive(Debug, Default)]
+pub struct ShareRegistry {
+    grants: DashMap<String, ShareGrant>,
+}
+
+impl ShareRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load stored grants, returning how many there are
+    pub async fn restore(&self, memory_manager: &MemoryManager) -> ServerResult<usize> {
+        for grant in load_grants(memory_manager).await? {
+            self.grants.insert(grant.id.clone(), grant);
+        }
+        Ok(self.grants.len())
+    }
+
+    /// Replace the known grants with the stored ones, after another replica
+    /// changed them
+    pub async fn reload(&self, memory_manager: &MemoryManager) -> ServerResult<usize> {
+        let stored = load_grants(memory_manager).await?;
+        let ids: HashSet<String> = stored.iter().map(|grant| grant.id.clone()).collect();
+        for grant in stored {
+            self.grants.insert(grant.id.clone(), grant);
+        }
+        self.grants.retain(|id, _| ids.contains(id));
+        Ok(self.grants.len())
+    }
+
+    /// What `user` may do with `memory`
+    ///
+    /// Without a user, authentication is disabled and everything is allowed.
+    pub fn access(&self, memory: &Memory, user: Option<&AuthContext>) -> Access {
+        self.access_to(&memory.id, &memory.properties, user)
+    }
+
+    /// What `user` may do with the memory with `memory_id` and `properties`,
+    /// for memories only at hand serialized, as in change events
+    pub fn access_to(
+        &self,
+        memory_id: &str,
+        properties: &serde_json::Value,
+        user: Option<&AuthContext>,
+    ) -> Access {
+        let Some(user) = user else {
+            return Access::Owner;
+        };
+        if is_admin(user) {
+            return Access::Owner;
+        }
+        let Some(owner_id) = string_property(properties, OWNER_PROPERTY) else {
+            return Access::Write;
+        };
+        if owner_id == user.user_id.to_string() {
+            return Access::Owner;
+        }
+
+        let grantee_id = user.user_id.to_string();
+        self.grants
+            .iter()
+            .filter(|grant| {
+                grant.grantee_id == grantee_id && grant.covers(memory_id, properties, owner_id)
+            })
+            .map(|grant| match grant.role {
+                ShareRole::Reader => Access::Read,
+                ShareRole::Writer => Access::Write,
+            })
+            .max()
+            .unwrap_or(Access::None)
+    }
+
+    /// Whether `user` may see `memory`
+    pub fn can_read(&self, memory: &Memory, user: Option<&AuthContext>) -> bool {
+        self.access(memory, user) >= Access::Read
+    }
+
+    /// Fail unless `user` has at least `needed` access to `memory`
+    ///
+    /// A memory the user can't see is reported as not found, so its existence
+    /// doesn't leak.
+    pub fn require(
+        &self,
+        memory: &Memory,
+        user: Option<&AuthContext>,
+        needed: Access,
+    ) -> ServerResult<()> {
+        let access = self.access(memory, user);
+        if access == Access::None {
+            return Err(not_found("Memory", &memory.id));
+        }
+        if access < needed {
+            let reason = match needed {
+                Access::Owner => "Only the owner of a memory can do that",
+                _ => "The memory is shared with you read-only",
+            };
+            return Err(ServerError::Forbidden(reason.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Load a memory, failing as [`Self::require`] does unless `user` has at
+    /// least `needed` access to it
+    pub async fn load(
+        &self,
+        memory_manager: &MemoryManager,
+        id: &str,
+        user: Option<&AuthContext>,
+        needed: Access,
+    ) -> ServerResult<Memory> {
+        let memory = memory_manager
+            .get_memory(id)
+            .await?
+            .ok_or_else(|| not_found("Memory", id))?;
+        self.require(&memory, user, needed)?;
+        Ok(memory)
+    }
+
+    /// Whether `user` has at least `needed` access to every memory among
+    /// `ids`, such as the ends of a relationship; IDs of other records, like
+    /// entities, don't restrict access
+    pub async fn allows(
+        &self,
+        memory_manager: &MemoryManager,
+        ids: &[&str],
+        user: Option<&AuthContext>,
+        needed: Access,
+    ) -> ServerResult<bool> {
+        if user.is_none_or(is_admin) {
+            return Ok(true);
+        }
+        for id in ids {
+            if let Some(memory) = memory_manager.get_memory(id).await?
+                && self.access(&memory, user) < needed
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The IDs among `ids` of memories `user` can't see
+    pub async fn hidden<'a>(
+        &self,
+        memory_manager: &MemoryManager,
+        ids: impl IntoIterator<Item = &'a str>,
+        user: Option<&AuthContext>,
+    ) -> ServerResult<HashSet<String>> {
+        let mut hidden = HashSet::new();
+        if user.is_none_or(is_admin) {
+            return Ok(hidden);
+        }
+        let ids: HashSet<&str> = ids.into_iter().collect();
+        for id in ids {
+            if let Some(memory) = memory_manager.get_memory(id).await?
+                && !self.can_read(&memory, user)
+            {
+                hidden.insert(memory.id);
+            }
+        }
+        Ok(hidden)
+    }
+
+    pub fn get(&self, id: &str) -> Option<ShareGrant> {
+        self.grants.get(id).map(|grant| grant.value().clone())
+    }
+
+    /// Grants made by or to `user`, newest first; admins see all of them
+    pub fn list_for(&self, user: &AuthContext) -> Vec<ShareGrant> {
+        let user_id = user.user_id.to_string();
+        let mut grants: Vec<ShareGrant> = self
+            .grants
+            .iter()
+            .filter(|grant| {
+                is_admin(user) || grant.owner_id == user_id || grant.grantee_id == user_id
+            })
+            .map(|grant| grant.value().clone())
+            .collect();
+        grants.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        grants
+    }
+
+    /// Store a grant
+    ///
+    /// Granting the same memories to the same user again changes the role of
+    /// the existing grant, which is returned.
+    pub async fn grant(
+        &self,
+        memory_manager: &MemoryManager,
+        mut grant: ShareGrant,
+    ) -> ServerResult<ShareGrant> {
+        let existing = self
+            .grants
+            .iter()
+            .find(|existing| existing.same_target(&grant))
+            .map(|existing| existing.value().clone());
+
+        if let Some(existing) = existing {
+            grant.id = existing.id;
+            grant.created_at = existing.created_at;
+        }
+        memory_manager
+            .storage()
+            .upsert_entity(grant.to_entity())
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to store share grant: {}", e)))?;
+
+        self.grants.insert(grant.id.clone(), grant.clone());
+        Ok(grant)
+    }
+
+    /// Delete a grant, returning it if it existed
+    pub async fn revoke(
+        &self,
+        memory_manager: &MemoryManager,
+        id: &str,
+    ) -> ServerResult<Option<ShareGrant>> {
+        if !self.grants.contains_key(id) {
+            return Ok(None);
+        }
+        memory_manager
+            .storage()
+            .delete_entity(id)
+            .await
+            .map_err(|e| ServerError::Database(format!("Failed to delete share grant: {}", e)))?;
+        Ok(self.grants.remove(id).map(|(_, grant)| grant))
+    }
+}
+
+/// Every stored grant
+async fn load_grants(memory_manager: &MemoryManager) -> ServerResult<Vec<ShareGrant>> {
+    let filter = EntityFilter {
+        entity_type: Some(SHARE_ENTITY_TYPE.to_string()),
+        ..Default::default()
+    };
+    let entities = memory_manager
+        .storage()
+        .list_entities(Some(filter), None, None)
+        .await
+        .map_err(|e| ServerError::Database(format!("Failed to load share grants: {}", e)))?;
+    Ok(entities
+        .into_iter()
+        .filter_map(ShareGrant::from_entity)
+        .collect())
+}
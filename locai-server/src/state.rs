@@ -2,17 +2,44 @@
 
 use dashmap::DashMap;
 use locai::core::MemoryManager;
+use locai::memory::WarmupReport;
+use locai::messaging::{REMINDER_LEASE, REMINDER_TOPIC};
 use locai::relationships::{RelationshipMetrics, RelationshipTypeRegistry};
+use locai::replication::Replicator;
+use locai::runtime::Lease;
+use locai::storage::OutboxMessage;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 use tokio::sync::{RwLock, broadcast};
 use uuid::Uuid;
 
 use crate::api::auth_service::AuthService;
+use crate::cluster::{ClusterEvent, EventBus};
 use crate::config::ServerConfig;
+use crate::embeddings::EmbeddingProxy;
+use crate::hot_state::HotState;
+use crate::jobs::JobQueue;
 use crate::messaging::MessagingServer;
+use crate::sharing::ShareRegistry;
+use crate::usage::USAGE_FLUSH_INTERVAL;
 use crate::websocket::{EntityFilter, MemoryFilter, RelationshipFilter, WebSocketMessage};
 
+/// Outbox topic for WebSocket notifications; the payload is a serialized
+/// [`WebSocketMessage`]
+pub const WEBSOCKET_OUTBOX_TOPIC: &str = "websocket";
+
+/// How often the outbox is checked for messages no request relayed, such as
+/// those left by a crash
+pub const OUTBOX_RELAY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often reminders are checked; a reminder fires up to this long after
+/// it is due
+pub const REMINDER_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Sender of the reminder messages the server publishes
+const REMINDER_SENDER: &str = "locai-server";
+
 /// Subscription filters for a WebSocket connection
 #[derive(Debug, Clone)]
 pub struct SubscriptionFilters {
@@ -25,7 +52,7 @@ pub struct SubscriptionFilters {
 #[derive(Debug)]
 pub struct AppState {
     /// Locai memory manager
-    pub memory_manager: MemoryManager,
+    pub memory_manager: Arc<MemoryManager>,
 
     /// Server configuration
     pub config: ServerConfig,
@@ -36,6 +63,12 @@ pub struct AppState {
     /// Messaging server (optional, enabled via config)
     pub messaging_server: Option<Arc<MessagingServer>>,
 
+    /// Embedding proxy (optional, enabled via config)
+    pub embedding_proxy: Option<Arc<EmbeddingProxy>>,
+
+    /// Replicator serving `/replication/ws` (optional, enabled via config)
+    pub replicator: Option<Arc<Replicator>>,
+
     /// WebSocket connections
     pub websocket_connections: DashMap<Uuid, broadcast::Sender<WebSocketMessage>>,
 
@@ -53,24 +86,48 @@ pub struct AppState {
 
     /// Webhook registry (in-memory storage for Phase 1)
     pub webhook_registry: Arc<RwLock<HashMap<String, crate::api::webhooks::WebhookConfig>>>,
+
+    /// Background jobs, submitted through `/api/jobs` and `/api/admin`
+    pub jobs: JobQueue,
+
+    /// Memories and collections users share with each other
+    pub shares: ShareRegistry,
+
+    /// What the startup index warmup did, once it has finished
+    pub warmup: OnceLock<WarmupReport>,
+
+    /// Scratchpads, rate limits and cached searches, in this process or Redis
+    pub hot_state: HotState,
+
+    /// Events shared with other replicas
+    pub events: EventBus,
 }
 
 impl AppState {
     /// Create new application state
     pub fn new(memory_manager: MemoryManager, config: ServerConfig) -> Self {
         let (broadcast_tx, _) = broadcast::channel(1000);
+        let jobs = JobQueue::new(config.max_concurrent_jobs);
+        let hot_state = HotState::local(config.hot_state.clone());
 
         Self {
-            memory_manager,
+            memory_manager: Arc::new(memory_manager),
             config,
             auth_service: None,     // Will be set later if auth is enabled
             messaging_server: None, // Will be set later if messaging is enabled
+            embedding_proxy: None,  // Will be set later if the proxy is enabled
+            replicator: None,       // Will be set later if replication is enabled
             websocket_connections: DashMap::new(),
             websocket_subscriptions: DashMap::new(),
             broadcast_tx,
             relationship_type_registry: RelationshipTypeRegistry::new(),
             relationship_metrics: RelationshipMetrics::new(),
             webhook_registry: Arc::new(RwLock::new(HashMap::new())),
+            jobs,
+            shares: ShareRegistry::new(),
+            warmup: OnceLock::new(),
+            hot_state,
+            events: EventBus::local(),
         }
     }
 
@@ -84,6 +141,26 @@ impl AppState {
         self.messaging_server = Some(messaging_server);
     }
 
+    /// Set the embedding proxy (called after initialization if the proxy is enabled)
+    pub fn set_embedding_proxy(&mut self, embedding_proxy: Arc<EmbeddingProxy>) {
+        self.embedding_proxy = Some(embedding_proxy);
+    }
+
+    /// Set the replicator (called after initialization if replication is enabled)
+    pub fn set_replicator(&mut self, replicator: Arc<Replicator>) {
+        self.replicator = Some(replicator);
+    }
+
+    /// Set where hot state is kept (called after initialization if Redis is configured)
+    pub fn set_hot_state(&mut self, hot_state: HotState) {
+        self.hot_state = hot_state;
+    }
+
+    /// Set the bus events are shared with other replicas through
+    pub fn set_event_bus(&mut self, events: EventBus) {
+        self.events = events;
+    }
+
     /// Add a WebSocket connection
     pub fn add_websocket_connection(&self, id: Uuid, sender: broadcast::Sender<WebSocketMessage>) {
         self.websocket_connections.insert(id, sender);
@@ -248,8 +325,16 @@ impl AppState {
         true // No filters or filters match
     }
 
-    /// Broadcast a message to all connected WebSocket clients with filtering
+    /// Broadcast a message to the WebSocket clients of every replica, with
+    /// filtering
     pub fn broadcast_message(&self, message: WebSocketMessage) {
+        self.events
+            .publish(ClusterEvent::WebSocket(message.clone()));
+        self.deliver_message(message);
+    }
+
+    /// Send a message to this replica's WebSocket clients, with filtering
+    pub fn deliver_message(&self, message: WebSocketMessage) {
         // Send to the main broadcast channel (for connections without specific filters)
         let _ = self.broadcast_tx.send(message.clone());
 
@@ -263,6 +348,181 @@ impl AppState {
         });
     }
 
+    /// Outbox message that broadcasts `message` once relayed
+    pub fn outbox_message(message: &WebSocketMessage) -> OutboxMessage {
+        OutboxMessage::new(
+            WEBSOCKET_OUTBOX_TOPIC,
+            serde_json::to_value(message).unwrap_or_default(),
+        )
+    }
+
+    /// Broadcast pending outbox messages
+    ///
+    /// Messages for other topics or with unreadable payloads are logged and
+    /// dropped so they can't block the outbox.
+    pub async fn relay_outbox(&self) {
+        let relayed = self
+            .memory_manager
+            .relay_outbox(100, |message| {
+                if message.topic != WEBSOCKET_OUTBOX_TOPIC {
+                    tracing::warn!("Dropping outbox message with topic {}", message.topic);
+                    return Ok(());
+                }
+                match serde_json::from_value::<WebSocketMessage>(message.payload.clone()) {
+                    Ok(ws_message) => self.broadcast_message(ws_message),
+                    Err(e) => {
+                        tracing::warn!("Dropping unreadable outbox message {}: {}", message.id, e)
+                    }
+                }
+                Ok(())
+            })
+            .await;
+
+        if let Err(e) = relayed {
+            tracing::error!("Failed to relay outbox: {}", e);
+        }
+    }
+
+    /// Relay the outbox now and then every [`OUTBOX_RELAY_INTERVAL`]
+    ///
+    /// Requests relay their own messages as soon as they commit; this catches
+    /// messages left pending by a crash or a failed relay.
+    pub fn spawn_outbox_relay(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(OUTBOX_RELAY_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                state.relay_outbox().await;
+            }
+        })
+    }
+
+    /// Fire due reminders, publishing each one to messaging subscribers
+    ///
+    /// `memory.reminder` webhooks run as the reminders fire.
+    pub async fn fire_reminders(&self) {
+        let events = match self.memory_manager.fire_due_reminders().await {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::error!("Failed to fire reminders: {}", e);
+                return;
+            }
+        };
+        let Some(messaging_server) = &self.messaging_server else {
+            return;
+        };
+        for event in events {
+            let memory_id = event.memory.id.clone();
+            let content = serde_json::to_value(&event).unwrap_or_default();
+            if let Err(e) = messaging_server
+                .send_message(REMINDER_SENDER, REMINDER_TOPIC, content, None)
+                .await
+            {
+                tracing::warn!("Failed to publish reminder for memory {}: {}", memory_id, e);
+            }
+        }
+    }
+
+    /// Fire reminders every [`REMINDER_CHECK_INTERVAL`]
+    ///
+    /// With several replicas, the one holding the reminder lease checks, so
+    /// a reminder fires once.
+    pub fn spawn_reminder_scheduler(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let state = Arc::clone(self);
+        let lease = Lease::for_interval(
+            self.memory_manager.storage().clone(),
+            REMINDER_LEASE,
+            REMINDER_CHECK_INTERVAL,
+        );
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REMINDER_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if lease.try_acquire().await {
+                    state.fire_reminders().await;
+                }
+            }
+        })
+    }
+
+    /// Sample storage and flush usage every [`USAGE_FLUSH_INTERVAL`]
+    pub fn spawn_usage_flusher(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(USAGE_FLUSH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if let Err(e) = state.memory_manager.sample_storage_usage().await {
+                    tracing::warn!("Failed to sample storage usage: {}", e);
+                }
+                if let Err(e) = state.memory_manager.flush_usage().await {
+                    tracing::warn!("Failed to flush usage: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Deliver the events other replicas publish to this replica's clients
+    pub fn spawn_event_listener(
+        self: &Arc<Self>,
+        mut incoming: tokio::sync::mpsc::UnboundedReceiver<ClusterEvent>,
+    ) -> tokio::task::JoinHandle<()> {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            while let Some(event) = incoming.recv().await {
+                match event {
+                    ClusterEvent::WebSocket(message) => state.deliver_message(message),
+                    ClusterEvent::Message(message) => {
+                        if let Some(messaging_server) = &state.messaging_server {
+                            messaging_server.deliver_remote(message);
+                        }
+                    }
+                    ClusterEvent::SharesChanged => {
+                        if let Err(e) = state.shares.reload(&state.memory_manager).await {
+                            tracing::warn!("Failed to reload share grants: {}", e);
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Whether the server is ready for queries: the startup warmup has
+    /// finished, or isn't configured
+    pub fn is_ready(&self) -> bool {
+        !self.memory_manager.config().warmup.enabled || self.warmup.get().is_some()
+    }
+
+    /// Warm the search indexes in the background; the server reports ready
+    /// once this finishes
+    pub fn spawn_warmup(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            tracing::info!("Warming search indexes");
+            let report = state.memory_manager.warm_up().await;
+            if report.timed_out {
+                tracing::warn!(
+                    "Index warmup timed out after {} ms ({} text and {} vector searches)",
+                    report.duration_ms,
+                    report.text_queries,
+                    report.vector_queries
+                );
+            } else {
+                tracing::info!(
+                    "Index warmup finished in {} ms ({} text and {} vector searches)",
+                    report.duration_ms,
+                    report.text_queries,
+                    report.vector_queries
+                );
+            }
+            let _ = state.warmup.set(report);
+        })
+    }
+
     /// Get the number of active WebSocket connections
     #[allow(dead_code)]
     pub fn websocket_connection_count(&self) -> usize {
@@ -0,0 +1,138 @@
+//! Usage accounting for billing
+//!
+//! With `LOCAI_USAGE_ACCOUNTING` set, every successful API request counts as
+//! a read, write or search against the tenant making it, and the requests it
+//! makes to the embedding proxy's upstream provider count as embedding calls
+//! and tokens. The tenant is the signed-in user's ID (the `owner` of the
+//! memories they create), or `anonymous` without authentication. How much
+//! each quota owner stores is sampled every [`USAGE_FLUSH_INTERVAL`], when
+//! the counts are also flushed to the store (see [`locai::memory::usage`]).
+//!
+//! Embedding calls are counted through a task-local the middleware sets up
+//! for each request, so the proxy doesn't need to know who it's serving.
+
+use std::cell::Cell;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use locai::memory::UsageMetric;
+
+use crate::api::auth::AuthContext;
+use crate::state::AppState;
+
+/// How often usage is flushed to the store and storage is sampled
+pub const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tenant of requests made without signing in
+pub const ANONYMOUS_TENANT: &str = "anonymous";
+
+tokio::task_local! {
+    /// Embedding calls and tokens of the request being handled
+    static EMBEDDING_USAGE: Cell<(u64, u64)>;
+}
+
+/// Count a request to the embedding provider against the current request's
+/// tenant; does nothing outside a metered request
+pub fn count_embedding_call(tokens: u64) {
+    let _ = EMBEDDING_USAGE.try_with(|usage| {
+        let (calls, total) = usage.get();
+        usage.set((calls + 1, total + tokens));
+    });
+}
+
+/// Count each successful request, and the embedding calls it made, against
+/// its tenant
+///
+/// Runs after authentication, so requests are counted against the user
+/// making them.
+pub async fn usage_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.config.usage_accounting {
+        return next.run(request).await;
+    }
+
+    let tenant = tenant_of(&request);
+    let metric = operation_metric(request.method(), request.uri().path());
+    let (response, (calls, tokens)) = EMBEDDING_USAGE
+        .scope(Cell::new((0, 0)), async move {
+            let response = next.run(request).await;
+            (response, EMBEDDING_USAGE.with(Cell::get))
+        })
+        .await;
+
+    let usage = state.memory_manager.usage();
+    if response.status().is_success()
+        && let Some(metric) = metric
+    {
+        usage.record(&tenant, metric, 1);
+    }
+    usage.record(&tenant, UsageMetric::EmbeddingCalls, calls);
+    usage.record(&tenant, UsageMetric::EmbeddingTokens, tokens);
+    response
+}
+
+/// Who a request counts against
+fn tenant_of(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<AuthContext>()
+        .map(|auth| auth.user_id.to_string())
+        .unwrap_or_else(|| ANONYMOUS_TENANT.to_string())
+}
+
+/// What a request counts as; `None` for probes and preflights
+fn operation_metric(method: &Method, path: &str) -> Option<UsageMetric> {
+    if matches!(path, "/health" | "/ready") || method == Method::OPTIONS {
+        None
+    } else if path.contains("/search") {
+        Some(UsageMetric::Searches)
+    } else if matches!(*method, Method::GET | Method::HEAD) {
+        Some(UsageMetric::Reads)
+    } else {
+        Some(UsageMetric::Writes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_are_classified_by_method_and_path() {
+        assert_eq!(
+            operation_metric(&Method::GET, "/memories/abc"),
+            Some(UsageMetric::Reads)
+        );
+        assert_eq!(
+            operation_metric(&Method::POST, "/memories/search/batch"),
+            Some(UsageMetric::Searches)
+        );
+        assert_eq!(
+            operation_metric(&Method::DELETE, "/memories/abc"),
+            Some(UsageMetric::Writes)
+        );
+        assert_eq!(operation_metric(&Method::GET, "/health"), None);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_calls_count_within_a_request() {
+        count_embedding_call(5);
+        let counted = EMBEDDING_USAGE
+            .scope(Cell::new((0, 0)), async {
+                count_embedding_call(7);
+                count_embedding_call(3);
+                EMBEDDING_USAGE.with(Cell::get)
+            })
+            .await;
+        assert_eq!(counted, (2, 10));
+    }
+}
@@ -1,8 +1,12 @@
 //! WebSocket implementation for real-time updates
+//!
+//! Each socket is only sent events about memories its user can see, and
+//! about relationships between them.
 
 use std::sync::Arc;
 
 use axum::{
+    Extension,
     extract::{
         State, WebSocketUpgrade,
         ws::{Message, WebSocket},
@@ -15,7 +19,7 @@ use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::state::AppState;
+use crate::{api::auth::AuthContext, sharing::Access, state::AppState};
 
 /// Filter for memory events in subscriptions
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,12 +153,49 @@ pub enum WebSocketMessage {
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
+    auth: Option<Extension<AuthContext>>,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_websocket(socket, state))
+    let user = auth.map(|Extension(user)| user);
+    ws.on_upgrade(move |socket| handle_websocket(socket, state, user))
+}
+
+/// Whether the event in `message` is about records `user` can see
+async fn is_visible(
+    state: &AppState,
+    message: &WebSocketMessage,
+    user: Option<&AuthContext>,
+) -> bool {
+    match message {
+        WebSocketMessage::MemoryCreated {
+            memory_id,
+            metadata,
+            ..
+        }
+        | WebSocketMessage::MemoryUpdated {
+            memory_id,
+            metadata,
+            ..
+        } => state.shares.access_to(memory_id, metadata, user) >= Access::Read,
+        WebSocketMessage::RelationshipCreated {
+            source_id,
+            target_id,
+            ..
+        } => state
+            .shares
+            .allows(
+                &state.memory_manager,
+                &[source_id.as_str(), target_id.as_str()],
+                user,
+                Access::Read,
+            )
+            .await
+            .unwrap_or(false),
+        _ => true,
+    }
 }
 
 /// Handle individual WebSocket connection
-async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
+async fn handle_websocket(socket: WebSocket, state: Arc<AppState>, user: Option<AuthContext>) {
     let connection_id = Uuid::new_v4();
     info!("WebSocket connection established: {}", connection_id);
 
@@ -256,13 +297,18 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
     });
 
     // Spawn task to handle outgoing messages to client
+    let state_clone = state.clone();
     let outgoing_task = tokio::spawn(async move {
+        let user = user.as_ref();
         loop {
             tokio::select! {
                 // Messages from global broadcast
                 msg = global_rx.recv() => {
                     match msg {
                         Ok(ws_msg) => {
+                            if !is_visible(&state_clone, &ws_msg, user).await {
+                                continue;
+                            }
                             if let Ok(msg_text) = serde_json::to_string(&ws_msg)
                                 && sender.send(Message::Text(msg_text.into())).await.is_err()
                             {
@@ -285,6 +331,9 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
                 msg = rx.recv() => {
                     match msg {
                         Ok(ws_msg) => {
+                            if !is_visible(&state_clone, &ws_msg, user).await {
+                                continue;
+                            }
                             if let Ok(msg_text) = serde_json::to_string(&ws_msg)
                                 && sender.send(Message::Text(msg_text.into())).await.is_err()
                             {
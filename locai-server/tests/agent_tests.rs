@@ -0,0 +1,695 @@
+//! Agent state tests: personas, preferences, procedures, tasks, reminders and the scratchpad
+
+mod common;
+
+mod personas {
+    //! Persona tests
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::create_test_server;
+
+    #[tokio::test]
+    async fn test_set_and_get_persona() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let response = server
+            .put("/api/personas/agent-7")
+            .json(&json!({
+                "identity": "A release manager for the platform team",
+                "traits": ["methodical"],
+                "writing_style": "short sentences",
+                "constraints": ["Never promise a release date"]
+            }))
+            .await;
+        response.assert_status_ok();
+        let persona: Value = response.json();
+        assert_eq!(persona["agent"], "agent-7");
+        assert_eq!(persona["traits"], json!(["methodical"]));
+        assert_eq!(
+            persona["rendered"],
+            "A release manager for the platform team\nTraits:\n- methodical\n\
+             Writing style: short sentences\nConstraints:\n- Never promise a release date"
+        );
+
+        let fetched: Value = server.get("/api/personas/agent-7").await.json();
+        assert_eq!(fetched["id"], persona["id"]);
+
+        let all: Vec<Value> = server.get("/api/personas").await.json();
+        assert_eq!(all.len(), 1);
+
+        server
+            .put("/api/personas/agent-8")
+            .json(&json!({ "identity": "  " }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        server
+            .delete("/api/personas/agent-7")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .get("/api/personas/agent-7")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_persona_history() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        for identity in ["A release manager", "A release and incident manager"] {
+            server
+                .put("/api/personas/agent-7")
+                .json(&json!({ "identity": identity }))
+                .await
+                .assert_status_ok();
+        }
+
+        let history: Vec<Value> = server.get("/api/personas/agent-7/history").await.json();
+        let identities: Vec<&str> = history
+            .iter()
+            .map(|revision| revision["identity"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            identities,
+            vec!["A release manager", "A release and incident manager"]
+        );
+
+        server
+            .get("/api/personas/agent-8/history")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
+
+mod preferences {
+    //! Preference tests
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::create_test_server;
+
+    #[tokio::test]
+    async fn test_set_and_override_preferences() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let response = server
+            .put("/api/preferences/tone")
+            .json(&json!({ "value": "concise" }))
+            .await;
+        response.assert_status_ok();
+        let preference: Value = response.json();
+        assert_eq!(preference["scope"], "global");
+        assert_eq!(preference["value"], "concise");
+
+        server
+            .put("/api/preferences/tone?scope=agent-7")
+            .json(&json!({ "value": "playful" }))
+            .await
+            .assert_status_ok();
+
+        let tone: Value = server
+            .get("/api/preferences/tone?scope=agent-7")
+            .await
+            .json();
+        assert_eq!(tone["value"], "playful");
+        let tone: Value = server
+            .get("/api/preferences/tone?scope=agent-8")
+            .await
+            .json();
+        assert_eq!(tone["value"], "concise");
+
+        let all: Vec<Value> = server.get("/api/preferences?scope=agent-7").await.json();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0]["scope"], "agent-7");
+
+        server
+            .delete("/api/preferences/tone?scope=agent-7")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .delete("/api/preferences/tone?scope=agent-7")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        let tone: Value = server
+            .get("/api/preferences/tone?scope=agent-7")
+            .await
+            .json();
+        assert_eq!(tone["value"], "concise");
+
+        server
+            .get("/api/preferences/language")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_preference_history() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        for value in ["concise", "detailed"] {
+            server
+                .put("/api/preferences/tone")
+                .json(&json!({ "value": value }))
+                .await
+                .assert_status_ok();
+        }
+
+        let history: Vec<Value> = server.get("/api/preferences/tone/history").await.json();
+        let values: Vec<&str> = history
+            .iter()
+            .map(|change| change["value"].as_str().unwrap())
+            .collect();
+        assert_eq!(values, vec!["concise", "detailed"]);
+
+        server
+            .get("/api/preferences/language/history")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
+
+mod procedures {
+    //! Procedure tests
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::{create_auth_server, create_test_server, signup};
+
+    #[tokio::test]
+    async fn test_create_and_find_procedures() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let response = server
+            .post("/api/procedures")
+            .json(&json!({
+                "name": "Rotate API keys",
+                "goal": "Replace a leaked API key",
+                "preconditions": ["Admin access to the key vault"],
+                "steps": [
+                    { "instruction": "Create a new key in the vault" },
+                    { "instruction": "Deploy the new key", "expected": "Health checks pass" },
+                    { "instruction": "Revoke the old key" }
+                ],
+                "success_metrics": ["No requests signed with the old key"]
+            }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let procedure: Value = response.json();
+        let id = procedure["id"].as_str().unwrap().to_string();
+        assert_eq!(procedure["steps"][1]["expected"], "Health checks pass");
+
+        // Procedures are memories too
+        let memory: Value = server.get(&format!("/api/memories/{}", id)).await.json();
+        assert!(
+            memory["content"]
+                .as_str()
+                .unwrap()
+                .starts_with("Rotate API keys")
+        );
+
+        let fetched: Value = server.get(&format!("/api/procedures/{}", id)).await.json();
+        assert_eq!(fetched["name"], "Rotate API keys");
+        let all: Vec<Value> = server.get("/api/procedures").await.json();
+        assert_eq!(all.len(), 1);
+
+        let matches: Vec<Value> = server
+            .get("/api/procedures/search?goal=an%20API%20key%20leaked")
+            .await
+            .json();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["procedure"]["id"], id.as_str());
+        assert!(matches[0]["score"].as_f64().unwrap() > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_procedures() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        server
+            .post("/api/procedures")
+            .json(&json!({ "name": "Empty", "goal": "Nothing", "steps": [] }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        let response = server
+            .post("/api/memories")
+            .json(&json!({ "content": "Just a fact" }))
+            .await;
+        let fact_id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+        server
+            .get(&format!("/api/procedures/{}", fact_id))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_procedures_are_private_to_their_owner() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+        let alice = signup(&server, "alice").await;
+        let bob = signup(&server, "bob").await;
+        let response = server
+            .post("/api/procedures")
+            .add_header("Authorization", alice.clone())
+            .json(&json!({
+                "name": "Rotate API keys",
+                "goal": "Replace a leaked API key",
+                "steps": [{ "instruction": "Create a new key in the vault" }]
+            }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+
+        server
+            .get(&format!("/api/procedures/{}", id))
+            .add_header("Authorization", alice.clone())
+            .await
+            .assert_status_ok();
+        server
+            .get(&format!("/api/procedures/{}", id))
+            .add_header("Authorization", bob.clone())
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let all: Vec<Value> = server
+            .get("/api/procedures")
+            .add_header("Authorization", bob.clone())
+            .await
+            .json();
+        assert!(all.is_empty());
+        let matches: Vec<Value> = server
+            .get("/api/procedures/search?goal=an%20API%20key%20leaked")
+            .add_header("Authorization", bob)
+            .await
+            .json();
+        assert!(matches.is_empty());
+    }
+}
+
+mod tasks {
+    //! Task tests
+
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+
+    use crate::common::{create_auth_server, create_test_server, signup};
+
+    async fn create_task(server: &TestServer, body: Value) -> String {
+        let response = server.post("/api/tasks").json(&body).await;
+        response.assert_status(StatusCode::CREATED);
+        response.json::<Value>()["id"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_create_update_and_list_tasks() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let id = create_task(
+            &server,
+            json!({
+                "title": "Publish the changelog",
+                "assignee": "sam",
+                "due_at": Utc::now() - Duration::hours(1),
+            }),
+        )
+        .await;
+
+        let task: Value = server.get(&format!("/api/tasks/{}", id)).await.json();
+        assert_eq!(task["status"], "open");
+        assert_eq!(task["assignee"], "sam");
+        assert_eq!(task["overdue"], true);
+
+        // Tasks are memories too
+        let memory: Value = server.get(&format!("/api/memories/{}", id)).await.json();
+        assert_eq!(memory["content"], "Publish the changelog");
+
+        let response = server
+            .put(&format!("/api/tasks/{}", id))
+            .json(&json!({ "status": "in_progress", "assignee": "" }))
+            .await;
+        response.assert_status_ok();
+        let task: Value = response.json();
+        assert_eq!(task["status"], "in_progress");
+        assert!(task.get("assignee").is_none());
+
+        let open: Vec<Value> = server
+            .get("/api/tasks?status=open,in_progress")
+            .await
+            .json();
+        assert_eq!(open.len(), 1);
+        let overdue: Vec<Value> = server.get("/api/tasks?overdue=true").await.json();
+        assert_eq!(overdue.len(), 1);
+        let sams: Vec<Value> = server.get("/api/tasks?assignee=sam").await.json();
+        assert!(sams.is_empty());
+
+        server
+            .get("/api/tasks?status=paused")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_transitions_conflict() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let id = create_task(&server, json!({ "title": "Retire the old dashboard" })).await;
+        let path = format!("/api/tasks/{}", id);
+
+        server
+            .put(&path)
+            .json(&json!({ "status": "cancelled" }))
+            .await
+            .assert_status_ok();
+        server
+            .put(&path)
+            .json(&json!({ "status": "done" }))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+
+        server
+            .get("/api/tasks/no-such-task")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_task_dependencies() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let design = create_task(&server, json!({ "title": "Design the schema" })).await;
+        let build = create_task(
+            &server,
+            json!({ "title": "Build the API", "depends_on": [design] }),
+        )
+        .await;
+        let dependencies_path = format!("/api/tasks/{}/dependencies", build);
+
+        let dependencies: Vec<Value> = server.get(&dependencies_path).await.json();
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0]["id"], design);
+
+        // Waiting on an unfinished task blocks completion
+        server
+            .put(&format!("/api/tasks/{}", build))
+            .json(&json!({ "status": "done" }))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+
+        // The reverse dependency would be a cycle
+        server
+            .post(&format!("/api/tasks/{}/dependencies", design))
+            .json(&json!({ "depends_on": build }))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+
+        server
+            .delete(&format!("{}/{}", dependencies_path, design))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .delete(&format!("{}/{}", dependencies_path, design))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .put(&format!("/api/tasks/{}", build))
+            .json(&json!({ "status": "done" }))
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_tasks_respect_shares() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+        let alice = signup(&server, "alice").await;
+        let bob = signup(&server, "bob").await;
+        let carol = signup(&server, "carol").await;
+
+        let response = server
+            .post("/api/tasks")
+            .add_header("Authorization", alice.clone())
+            .json(&json!({ "title": "Renew the certificates" }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+        let path = format!("/api/tasks/{}", id);
+        server
+            .post("/api/shares")
+            .add_header("Authorization", alice.clone())
+            .json(&json!({ "memory_id": id, "user": "bob", "role": "reader" }))
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        // A reader sees the task but can't change it
+        server
+            .get(&path)
+            .add_header("Authorization", bob.clone())
+            .await
+            .assert_status_ok();
+        server
+            .put(&path)
+            .add_header("Authorization", bob.clone())
+            .json(&json!({ "status": "done" }))
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        let listed: Vec<Value> = server
+            .get("/api/tasks")
+            .add_header("Authorization", bob)
+            .await
+            .json();
+        assert_eq!(listed.len(), 1);
+
+        // Anyone else doesn't see it at all
+        server
+            .get(&path)
+            .add_header("Authorization", carol.clone())
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .get(&format!("{}/dependencies", path))
+            .add_header("Authorization", carol.clone())
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        let listed: Vec<Value> = server
+            .get("/api/tasks")
+            .add_header("Authorization", carol)
+            .await
+            .json();
+        assert!(listed.is_empty());
+
+        server
+            .put(&path)
+            .add_header("Authorization", alice)
+            .json(&json!({ "status": "done" }))
+            .await
+            .assert_status_ok();
+    }
+}
+
+mod reminders {
+    //! Reminder tests
+
+    use axum::http::StatusCode;
+    use chrono::{Duration, Utc};
+    use serde_json::{Value, json};
+
+    use crate::common::{
+        create_auth_server, create_memory, create_memory_as, create_test_server, signup,
+    };
+
+    #[tokio::test]
+    async fn test_set_snooze_and_cancel_reminder() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let id = create_memory(&server, json!({ "content": "Quarterly tax payment" })).await;
+        let path = format!("/api/memories/{}/reminder", id);
+
+        let remind_at = Utc::now() + Duration::days(3);
+        let response = server
+            .put(&path)
+            .json(&json!({ "remind_at": remind_at, "recurrence": "monthly" }))
+            .await;
+        response.assert_status_ok();
+        let reminder: Value = response.json();
+        assert_eq!(reminder["memory_id"], id);
+        assert_eq!(reminder["recurrence"], "monthly");
+
+        let reminders: Vec<Value> = server.get("/api/reminders").await.json();
+        assert_eq!(reminders.len(), 1);
+
+        let response = server
+            .post(&format!("{}/snooze", path))
+            .json(&json!({ "delay": "1h" }))
+            .await;
+        response.assert_status_ok();
+        let snoozed: Value = response.json();
+        assert!(snoozed["snoozed_until"].is_string());
+        assert_eq!(snoozed["due_at"], snoozed["snoozed_until"]);
+
+        server
+            .post(&format!("{}/snooze", path))
+            .json(&json!({}))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        server
+            .delete(&path)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server.get(&path).await.assert_status(StatusCode::NOT_FOUND);
+        server
+            .delete(&path)
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_reminder_validation() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let id = create_memory(&server, json!({ "content": "Renew the domain" })).await;
+
+        server
+            .put(&format!("/api/memories/{}/reminder", id))
+            .json(&json!({ "remind_at": Utc::now(), "recurrence": "fortnightly" }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        server
+            .put("/api/memories/no-such-memory/reminder")
+            .json(&json!({ "remind_at": Utc::now() }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_due_reminders_fire() {
+        let (server, state, _temp_dir) = create_test_server().await;
+        let id = create_memory(&server, json!({ "content": "Send the invoice" })).await;
+        let path = format!("/api/memories/{}/reminder", id);
+
+        server
+            .put(&path)
+            .json(&json!({ "remind_at": Utc::now() - Duration::seconds(1) }))
+            .await
+            .assert_status_ok();
+
+        state.fire_reminders().await;
+
+        // A one-off reminder is gone once it fires
+        server.get(&path).await.assert_status(StatusCode::NOT_FOUND);
+        let reminders: Vec<Value> = server.get("/api/reminders").await.json();
+        assert!(reminders.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reminders_respect_shares() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+        let alice = signup(&server, "alice").await;
+        let bob = signup(&server, "bob").await;
+        let id = create_memory_as(&server, &alice, json!({ "content": "Pay the rent" })).await;
+        let path = format!("/api/memories/{}/reminder", id);
+
+        server
+            .put(&path)
+            .add_header("Authorization", bob.clone())
+            .json(&json!({ "remind_at": Utc::now() + Duration::days(1) }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .put(&path)
+            .add_header("Authorization", alice.clone())
+            .json(&json!({ "remind_at": Utc::now() + Duration::days(1) }))
+            .await
+            .assert_status_ok();
+
+        server
+            .get(&path)
+            .add_header("Authorization", bob.clone())
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        let reminders: Vec<Value> = server
+            .get("/api/reminders")
+            .add_header("Authorization", bob)
+            .await
+            .json();
+        assert!(reminders.is_empty());
+        let reminders: Vec<Value> = server
+            .get("/api/reminders")
+            .add_header("Authorization", alice)
+            .await
+            .json();
+        assert_eq!(reminders.len(), 1);
+    }
+}
+
+mod scratchpad {
+    //! Scratchpad tests, with hot state kept locally
+
+    use std::time::Duration;
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::create_test_server;
+
+    #[tokio::test]
+    async fn test_scratchpad_round_trip() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        let response = server
+            .put("/api/scratchpad/plan")
+            .json(&json!({ "value": { "steps": ["search", "summarize"] } }))
+            .await;
+        response.assert_status_ok();
+        let entry: Value = response.json();
+        assert_eq!(entry["name"], "plan");
+        assert!(entry["expires_at"].is_string(), "default TTL applies");
+
+        let entry: Value = server.get("/api/scratchpad/plan").await.json();
+        assert_eq!(entry["value"]["steps"][1], "summarize");
+
+        server
+            .put("/api/scratchpad/notes")
+            .json(&json!({ "value": "draft" }))
+            .await
+            .assert_status_ok();
+        let entries: Vec<Value> = server.get("/api/scratchpad").await.json();
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["notes", "plan"]);
+
+        server
+            .delete("/api/scratchpad/plan")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .get("/api/scratchpad/plan")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .delete("/api/scratchpad/plan")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_scratchpad_entries_expire() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        server
+            .put("/api/scratchpad/fleeting")
+            .json(&json!({ "value": 1, "ttl_secs": 1 }))
+            .await
+            .assert_status_ok();
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        server
+            .get("/api/scratchpad/fleeting")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        let entries: Vec<Value> = server.get("/api/scratchpad").await.json();
+        assert!(entries.is_empty());
+
+        server
+            .put("/api/scratchpad/fleeting")
+            .json(&json!({ "value": 1, "ttl_secs": 0 }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+}
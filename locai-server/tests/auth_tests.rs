@@ -1,53 +1,13 @@
 //! Tests for JWT authentication and authorization
 
+mod common;
+
 use axum::http::StatusCode;
-use axum_test::TestServer;
 use jsonwebtoken::{DecodingKey, Validation, decode};
-use locai_server::{
-    api::auth::{Claims, generate_jwt_token},
-    config::ServerConfig,
-    state::AppState,
-};
-use std::sync::Arc;
+use locai_server::api::auth::{Claims, generate_jwt_token};
 use uuid::Uuid;
 
-async fn create_test_server_with_auth() -> (TestServer, Arc<AppState>, tempfile::TempDir) {
-    let temp_dir = tempfile::tempdir().unwrap();
-
-    // Create Locai configuration for testing with memory storage
-    let config = locai::config::ConfigBuilder::new()
-        .with_data_dir(temp_dir.path())
-        .with_memory_storage()
-        .build()
-        .expect("Failed to create config");
-
-    // Initialize MemoryManager
-    let memory_manager = locai::init(config)
-        .await
-        .expect("Failed to initialize memory manager");
-
-    // Create server configuration with authentication enabled
-    let mut server_config = ServerConfig::default();
-    server_config.enable_auth = true;
-    server_config.allow_signup = true;
-    server_config.jwt_secret = "test-secret-key-for-jwt-token-generation".to_string();
-
-    // Create AppState
-    let mut app_state = AppState::new(memory_manager, server_config.clone());
-
-    // Initialize auth service if auth is enabled
-    if server_config.enable_auth {
-        use locai_server::api::auth_service::AuthService;
-        let auth_service = AuthService::new(server_config.jwt_secret.clone());
-        app_state.set_auth_service(auth_service);
-    }
-
-    let state = Arc::new(app_state);
-    let app = locai_server::create_router(state.clone());
-    let server = TestServer::new(app).unwrap();
-
-    (server, state, temp_dir)
-}
+use common::create_auth_server;
 
 #[tokio::test]
 async fn test_jwt_token_generation() {
@@ -150,7 +110,7 @@ async fn test_jwt_token_expiration() {
 
 #[tokio::test]
 async fn test_signup_endpoint() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     let signup_request = serde_json::json!({
         "username": "newuser",
@@ -172,7 +132,7 @@ async fn test_signup_endpoint() {
 
 #[tokio::test]
 async fn test_signup_duplicate_username() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     let signup_request = serde_json::json!({
         "username": "duplicate",
@@ -190,7 +150,7 @@ async fn test_signup_duplicate_username() {
 
 #[tokio::test]
 async fn test_signup_validation() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     // Test empty username
     let request1 = serde_json::json!({
@@ -211,7 +171,7 @@ async fn test_signup_validation() {
 
 #[tokio::test]
 async fn test_login_endpoint() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     // First create a user
     let signup_request = serde_json::json!({
@@ -238,7 +198,7 @@ async fn test_login_endpoint() {
 
 #[tokio::test]
 async fn test_login_invalid_credentials() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     // Create a user
     let signup_request = serde_json::json!({
@@ -259,7 +219,7 @@ async fn test_login_invalid_credentials() {
 
 #[tokio::test]
 async fn test_auth_middleware_with_valid_token() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     // Create a user and get token
     let signup_request = serde_json::json!({
@@ -283,7 +243,7 @@ async fn test_auth_middleware_with_valid_token() {
 
 #[tokio::test]
 async fn test_auth_middleware_without_token() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     // Try to access protected endpoint without token
     let response = server.get("/api/memories").await;
@@ -293,7 +253,7 @@ async fn test_auth_middleware_without_token() {
 
 #[tokio::test]
 async fn test_auth_middleware_public_endpoints() {
-    let (server, _state, _temp_dir) = create_test_server_with_auth().await;
+    let (server, _state, _temp_dir) = create_auth_server().await;
 
     // Public endpoints should work without auth
     let response = server.get("/api/health").await;
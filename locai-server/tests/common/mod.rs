@@ -0,0 +1,126 @@
+//! Fixtures shared by the server integration tests
+//!
+//! Every server runs on an in-memory store in its own temp directory, which
+//! is removed when the returned `TempDir` is dropped.
+
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use locai::config::ConfigBuilder;
+use locai::core::MemoryManager;
+use locai_server::{api::auth_service::AuthService, config::ServerConfig, state::AppState};
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+/// Secret the auth-enabled servers sign their tokens with
+pub const JWT_SECRET: &str = "test-secret-key-for-jwt-token-generation";
+
+/// A memory manager with `configure` applied on top of the defaults
+pub async fn create_manager_with(
+    configure: impl FnOnce(ConfigBuilder) -> ConfigBuilder,
+) -> (MemoryManager, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let config = configure(
+        ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage(),
+    )
+    .build()
+    .expect("Failed to create config");
+    let memory_manager = locai::init(config)
+        .await
+        .expect("Failed to initialize memory manager");
+    (memory_manager, temp_dir)
+}
+
+/// Server state with `configure_locai` applied to the Locai configuration
+/// and `configure_server` to a server configuration that has auth disabled
+///
+/// Turning auth on in `configure_server` also sets up the auth service, with
+/// signup allowed and tokens signed with [`JWT_SECRET`].
+pub async fn create_state_with(
+    configure_locai: impl FnOnce(ConfigBuilder) -> ConfigBuilder,
+    configure_server: impl FnOnce(&mut ServerConfig),
+) -> (Arc<AppState>, TempDir) {
+    let (memory_manager, temp_dir) = create_manager_with(configure_locai).await;
+
+    let mut server_config = ServerConfig::default();
+    server_config.enable_auth = false;
+    server_config.allow_signup = true;
+    server_config.jwt_secret = JWT_SECRET.to_string();
+    configure_server(&mut server_config);
+
+    let mut app_state = AppState::new(memory_manager, server_config.clone());
+    if server_config.enable_auth {
+        app_state.set_auth_service(AuthService::new(server_config.jwt_secret));
+    }
+    (Arc::new(app_state), temp_dir)
+}
+
+/// A test server over [`create_state_with`]
+pub async fn create_test_server_with(
+    configure_locai: impl FnOnce(ConfigBuilder) -> ConfigBuilder,
+    configure_server: impl FnOnce(&mut ServerConfig),
+) -> (TestServer, Arc<AppState>, TempDir) {
+    let (state, temp_dir) = create_state_with(configure_locai, configure_server).await;
+    let app = locai_server::create_router(state.clone());
+    let server = TestServer::new(app).expect("Failed to create test server");
+    (server, state, temp_dir)
+}
+
+/// A test server with the default configuration and auth disabled
+pub async fn create_test_server() -> (TestServer, Arc<AppState>, TempDir) {
+    create_test_server_with(|config| config, |_| {}).await
+}
+
+/// A test server with auth enabled
+pub async fn create_auth_server() -> (TestServer, Arc<AppState>, TempDir) {
+    create_test_server_with(|config| config, |config| config.enable_auth = true).await
+}
+
+/// Sign a user up, returning their bearer header value
+pub async fn signup(server: &TestServer, username: &str) -> String {
+    let response = server
+        .post("/api/auth/signup")
+        .json(&json!({ "username": username, "password": "password123" }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    format!(
+        "Bearer {}",
+        response.json::<Value>()["token"].as_str().unwrap()
+    )
+}
+
+/// Create a memory, returning its ID
+pub async fn create_memory(server: &TestServer, body: Value) -> String {
+    let response = server.post("/api/memories").json(&body).await;
+    response.assert_status(StatusCode::CREATED);
+    response.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+/// Create a memory as the user `token` belongs to, returning its ID
+pub async fn create_memory_as(server: &TestServer, token: &str, body: Value) -> String {
+    let response = server
+        .post("/api/memories")
+        .add_header("Authorization", token.to_string())
+        .json(&body)
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.json::<Value>()["id"].as_str().unwrap().to_string()
+}
+
+/// Poll a job until it finishes
+pub async fn wait_for_job(server: &TestServer, id: &str) -> Value {
+    for _ in 0..100 {
+        let job: Value = server.get(&format!("/api/jobs/{}", id)).await.json();
+        if job["status"] != "queued" && job["status"] != "running" {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("Job {} did not finish", id);
+}
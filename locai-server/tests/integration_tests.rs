@@ -1,43 +1,18 @@
+mod common;
+
 use std::sync::Arc;
 
 use axum_test::TestServer;
 use http::StatusCode;
-use locai_server::create_router;
+use locai_server::AppState;
 use serde_json::{Value, json};
 use tempfile::TempDir;
 
-/// Helper function to create a test server with a temporary database
-async fn create_test_server() -> (TestServer, TempDir) {
-    let temp_dir = TempDir::new().expect("Failed to create temp directory");
-
-    // Create Locai configuration for testing with memory storage
-    let config = locai::config::ConfigBuilder::new()
-        .with_data_dir(temp_dir.path())
-        .with_memory_storage()
-        .build()
-        .expect("Failed to create config");
-
-    // Initialize MemoryManager
-    let memory_manager = locai::init(config)
-        .await
-        .expect("Failed to initialize memory manager");
-
-    // Create server configuration with authentication disabled for testing
-    let mut server_config = locai_server::config::ServerConfig::default();
-    server_config.enable_auth = false; // Disable auth for integration tests
-
-    // Create AppState
-    let state = Arc::new(locai_server::AppState::new(memory_manager, server_config));
-
-    let app = create_router(state);
-    let server = TestServer::new(app).expect("Failed to create test server");
-
-    (server, temp_dir)
-}
+use common::{create_test_server, create_test_server_with};
 
 #[tokio::test]
 async fn test_health_check() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     let response = server.get("/api/health").await;
 
@@ -49,7 +24,7 @@ async fn test_health_check() {
 
 #[tokio::test]
 async fn test_swagger_docs_available() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     let response = server.get("/docs/").await;
     response.assert_status_ok();
@@ -57,7 +32,7 @@ async fn test_swagger_docs_available() {
 
 #[tokio::test]
 async fn test_openapi_spec_available() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     let response = server.get("/api-docs/openapi.json").await;
     response.assert_status_ok();
@@ -71,7 +46,7 @@ mod memories {
 
     #[tokio::test]
     async fn test_create_memory() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let memory_data = json!({
             "content": "This is a test memory",
@@ -91,7 +66,7 @@ mod memories {
 
     #[tokio::test]
     async fn test_list_memories() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create a few memories
         for i in 0..3 {
@@ -123,12 +98,77 @@ mod memories {
         assert!(json.as_array().unwrap().len() >= 3);
     }
 
+    /// Test that the priority filter is applied before pagination, so pages
+    /// aren't cut short by memories of other priorities
+    #[tokio::test]
+    async fn test_priority_filter_fills_pages() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        for i in 0..12 {
+            let memory_data = json!({
+                "content": format!("Paging note {}", i),
+                "priority": "normal",
+                "memory_type": "fact"
+            });
+            server
+                .post("/api/memories")
+                .json(&memory_data)
+                .await
+                .assert_status(StatusCode::CREATED);
+        }
+        for i in 0..3 {
+            let memory_data = json!({
+                "content": format!("Paging note urgent {}", i),
+                "priority": "high",
+                "memory_type": "fact"
+            });
+            server
+                .post("/api/memories")
+                .json(&memory_data)
+                .await
+                .assert_status(StatusCode::CREATED);
+        }
+
+        let first: Value = server
+            .get("/api/memories?priority=High&page=0&size=2")
+            .await
+            .json();
+        let second: Value = server
+            .get("/api/memories?priority=High&page=1&size=2")
+            .await
+            .json();
+        assert_eq!(first.as_array().unwrap().len(), 2);
+        assert_eq!(second.as_array().unwrap().len(), 1);
+        assert!(
+            first
+                .as_array()
+                .unwrap()
+                .iter()
+                .chain(second.as_array().unwrap())
+                .all(|memory| memory["priority"] == "High")
+        );
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let results: Value = server
+            .get("/api/memories/search?q=paging&limit=2&priority=High")
+            .await
+            .json();
+        let results = results.as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(
+            results
+                .iter()
+                .all(|result| result["memory"]["priority"] == "High")
+        );
+    }
+
     /// Test that custom properties are persisted when creating a memory
     /// This test verifies the fix for the bug where properties were accepted
     /// but not stored in the database.
     #[tokio::test]
     async fn test_create_memory_with_properties() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create a memory with custom properties (matching the bug report example)
         let memory_data = json!({
@@ -182,7 +222,7 @@ mod memories {
     /// Test that empty properties object is handled correctly
     #[tokio::test]
     async fn test_create_memory_with_empty_properties() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let memory_data = json!({
             "content": "Memory with empty properties",
@@ -200,7 +240,7 @@ mod memories {
     /// Test that properties can be updated
     #[tokio::test]
     async fn test_update_memory_properties() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create a memory with initial properties
         let memory_data = json!({
@@ -240,7 +280,7 @@ mod memories {
     /// This test verifies the fix for the bug where memory_type filter was ignored
     #[tokio::test]
     async fn test_search_with_memory_type_filter() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create memories of different types with a common search term
         let obs1 = json!({
@@ -325,7 +365,7 @@ mod memories {
     /// Test that search works without filters (baseline)
     #[tokio::test]
     async fn test_search_without_filter() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create memories of different types
         let mem1 = json!({
@@ -368,7 +408,7 @@ mod memories {
     /// Test that tags filter continues to work correctly
     #[tokio::test]
     async fn test_search_with_tags_filter() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create memories with different tags
         let mem1 = json!({
@@ -420,7 +460,7 @@ mod memories {
     /// Test combining memory_type and tags filters
     #[tokio::test]
     async fn test_search_with_combined_filters() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create memories with different combinations
         let mem1 = json!({
@@ -488,7 +528,7 @@ mod entities {
 
     #[tokio::test]
     async fn test_list_entities() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/entities").await;
         response.assert_status_ok();
@@ -503,7 +543,7 @@ mod relationships {
 
     #[tokio::test]
     async fn test_list_relationships() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/relationships").await;
         response.assert_status_ok();
@@ -518,7 +558,7 @@ mod versions {
 
     #[tokio::test]
     async fn test_list_versions() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/versions").await;
         response.assert_status_ok();
@@ -533,7 +573,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_get_graph_metrics() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/graph/metrics").await;
         response.assert_status_ok();
@@ -549,7 +589,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_get_memory_graph() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create a test memory first
         let memory_data = json!({
@@ -580,7 +620,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_get_memory_graph_with_depth() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create a test memory first
         let memory_data = json!({
@@ -610,7 +650,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_get_memory_graph_not_found() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/memories/non-existent-id/graph").await;
 
@@ -619,7 +659,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_find_paths_missing_parameters() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Test missing 'from' parameter
         let response = server.get("/api/graph/paths?to=some-id").await;
@@ -634,7 +674,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_find_paths_with_valid_parameters() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create two test memories
         let memory1_data = json!({
@@ -675,7 +715,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_query_graph_connected() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let query_data = json!({
             "pattern": "connected",
@@ -692,7 +732,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_query_graph_isolated() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let query_data = json!({
             "pattern": "isolated",
@@ -709,7 +749,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_query_graph_semantic() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create a test memory first
         let memory_data = json!({
@@ -739,7 +779,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_find_similar_structures_missing_pattern() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/graph/similar_structures").await;
 
@@ -748,7 +788,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_find_similar_structures_with_pattern() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         // Create a test memory first
         let memory_data = json!({
@@ -778,7 +818,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_get_central_entities() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/entities/central").await;
 
@@ -800,7 +840,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_get_entity_graph_not_found() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server.get("/api/entities/non-existent-id/graph").await;
 
@@ -809,7 +849,7 @@ mod graph {
 
     #[tokio::test]
     async fn test_get_related_entities_not_found() {
-        let (server, _temp_dir) = create_test_server().await;
+        let (server, _state, _temp_dir) = create_test_server().await;
 
         let response = server
             .get("/api/entities/non-existent-id/related_entities")
@@ -821,7 +861,7 @@ mod graph {
 
 #[tokio::test]
 async fn test_authentication_middleware() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Test without API key (should work since auth is disabled in test)
     let response = server.get("/api/memories").await;
@@ -830,7 +870,7 @@ async fn test_authentication_middleware() {
 
 #[tokio::test]
 async fn test_error_handling() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Test 404 for non-existent memory
     let response = server.get("/api/memories/non-existent-id").await;
@@ -840,7 +880,7 @@ async fn test_error_handling() {
 /// Test temporal filtering in search API
 #[tokio::test]
 async fn test_search_with_temporal_filters() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Create memories with explicit timestamps (spaced 1 second apart)
     let mut memory_ids = Vec::new();
@@ -913,7 +953,7 @@ async fn test_search_with_temporal_filters() {
 /// Test temporal search with invalid timestamps
 #[tokio::test]
 async fn test_search_with_invalid_temporal_filters() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Test invalid created_after timestamp
     let response = server
@@ -947,7 +987,7 @@ async fn test_search_with_invalid_temporal_filters() {
 /// Test temporal search combined with other filters
 #[tokio::test]
 async fn test_search_temporal_with_combined_filters() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Create memories with different types and tags
     let memory_data = json!({
@@ -989,7 +1029,7 @@ async fn test_search_temporal_with_combined_filters() {
 /// Test graph API with temporal span enabled
 #[tokio::test]
 async fn test_graph_with_temporal_span() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Create a memory with relationships
     let memory1_data = json!({
@@ -1075,7 +1115,7 @@ async fn test_graph_with_temporal_span() {
 /// Test entity graph with temporal span
 #[tokio::test]
 async fn test_entity_graph_with_temporal_span() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Create an entity
     let entity_data = json!({
@@ -1108,7 +1148,7 @@ async fn test_entity_graph_with_temporal_span() {
 /// Test temporal span calculation accuracy
 #[tokio::test]
 async fn test_temporal_span_calculation() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Create first memory
     let memory1_data = json!({
@@ -1213,7 +1253,7 @@ async fn test_temporal_span_calculation() {
 /// Test backward compatibility - existing graph queries work unchanged
 #[tokio::test]
 async fn test_graph_backward_compatibility() {
-    let (server, _temp_dir) = create_test_server().await;
+    let (server, _state, _temp_dir) = create_test_server().await;
 
     // Create a memory
     let memory_data = json!({
@@ -1240,3 +1280,504 @@ async fn test_graph_backward_compatibility() {
     assert!(graph["metadata"].is_object());
     assert!(graph["metadata"]["temporal_span"].is_null()); // Default: not included
 }
+
+/// Test the changefeed pages through mutations and resumes from a cursor
+#[tokio::test]
+async fn test_changefeed_resumes_from_cursor() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let response = server
+        .post("/api/memories")
+        .json(&json!({ "content": "First change" }))
+        .await;
+    let memory: Value = response.json();
+    let memory_id = memory["id"].as_str().unwrap().to_string();
+
+    let response = server.get("/api/changes").await;
+    response.assert_status_ok();
+    let page: Value = response.json();
+    let changes = page["changes"].as_array().unwrap();
+    assert!(changes.iter().any(|change| change["kind"] == "memory"
+        && change["id"] == memory_id.as_str()
+        && change["op"] == "upsert"
+        && change["record"]["content"] == "First change"));
+    let cursor = page["next_cursor"].as_str().unwrap().to_string();
+
+    // Nothing new after the cursor
+    let response = server.get(&format!("/api/changes?since={}", cursor)).await;
+    let page: Value = response.json();
+    assert!(page["changes"].as_array().unwrap().is_empty());
+    assert_eq!(page["next_cursor"], cursor.as_str());
+
+    server
+        .delete(&format!("/api/memories/{}", memory_id))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let response = server.get(&format!("/api/changes?since={}", cursor)).await;
+    let page: Value = response.json();
+    let changes = page["changes"].as_array().unwrap();
+    let last = changes.last().unwrap();
+    assert_eq!(last["op"], "delete");
+    assert_eq!(last["id"], memory_id.as_str());
+    assert!(last.get("record").is_none());
+}
+
+/// Test the changefeed rejects malformed cursors
+#[tokio::test]
+async fn test_changefeed_invalid_cursor() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let response = server.get("/api/changes?since=yesterday").await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+/// Test server where each tenant may store two memories
+async fn create_quota_server() -> (TestServer, Arc<AppState>, TempDir) {
+    let mut quotas = locai::config::QuotaConfig::default();
+    quotas.default_limits.max_memories = Some(2);
+    create_test_server_with(|config| config.with_quotas(quotas), |_| {}).await
+}
+
+#[tokio::test]
+async fn test_quota_exceeded_and_usage() {
+    let (server, _state, _temp_dir) = create_quota_server().await;
+    let memory = |content: &str| {
+        json!({
+            "content": content,
+            "properties": { "tenant": "acme" }
+        })
+    };
+
+    for content in ["First note", "Second note"] {
+        server
+            .post("/api/memories")
+            .json(&memory(content))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+    let response = server
+        .post("/api/memories")
+        .json(&memory("Third note"))
+        .await;
+    response.assert_status(StatusCode::INSUFFICIENT_STORAGE);
+    let error: Value = response.json();
+    assert_eq!(error["error"], "quota_exceeded");
+
+    let response = server.get("/api/quotas/acme").await;
+    response.assert_status_ok();
+    let usage: Value = response.json();
+    assert_eq!(usage["memories"], 2);
+    assert_eq!(usage["max_memories"], 2);
+
+    let response = server.get("/api/quotas").await;
+    response.assert_status_ok();
+    let all: Value = response.json();
+    assert_eq!(all[0]["owner"], "acme");
+}
+
+#[tokio::test]
+async fn test_entity_profile() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let response = server
+        .post("/api/entities")
+        .json(&json!({
+            "entity_type": "person",
+            "properties": { "name": "Ada" }
+        }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let entity: Value = response.json();
+    let entity_id = entity["id"].as_str().unwrap();
+
+    for content in [
+        "Ada helped with the launch",
+        "Ada was upset about the delay",
+    ] {
+        let response = server
+            .post("/api/memories")
+            .json(&json!({ "content": content }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let memory: Value = response.json();
+        server
+            .post(&format!(
+                "/api/memories/{}/relationships",
+                memory["id"].as_str().unwrap()
+            ))
+            .json(&json!({
+                "target_id": entity_id,
+                "relationship_type": "contains"
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    let response = server
+        .get(&format!("/api/entities/{}/profile", entity_id))
+        .await;
+    response.assert_status_ok();
+    let profile: Value = response.json();
+    assert_eq!(profile["entity"]["id"], entity_id);
+    assert_eq!(profile["mention_count"], 2);
+    assert_eq!(profile["top_memories"].as_array().unwrap().len(), 2);
+    assert_eq!(profile["sentiment"]["direction"], "Decreasing");
+    assert!(profile["first_mentioned"].is_string());
+
+    server
+        .get("/api/entities/missing/profile")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_graph_diff() {
+    use chrono::{SecondsFormat, Utc};
+
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let start = Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true);
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    let response = server
+        .post("/api/entities")
+        .json(&json!({
+            "entity_type": "person",
+            "properties": { "name": "Ada" }
+        }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let entity: Value = response.json();
+
+    let response = server.get(&format!("/api/graph/diff?from={}", start)).await;
+    response.assert_status_ok();
+    let diff: Value = response.json();
+    let added = diff["nodes"]["added"].as_array().unwrap();
+    assert!(
+        added
+            .iter()
+            .any(|change| change["id"] == entity["id"] && change["kind"] == "entity")
+    );
+    assert!(diff["edges"]["removed"].as_array().unwrap().is_empty());
+
+    server
+        .get("/api/graph/diff")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server
+        .get("/api/graph/diff?from=yesterday")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_weighted_paths() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let mut entity_ids = Vec::new();
+    let mut memory_ids = Vec::new();
+    for name in ["Alice", "Acme", "Beta"] {
+        let response = server
+            .post("/api/entities")
+            .json(&json!({
+                "entity_type": "organization",
+                "properties": { "name": name }
+            }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let entity: Value = response.json();
+        let entity_id = entity["id"].as_str().unwrap().to_string();
+
+        let response = server
+            .post("/api/memories")
+            .json(&json!({ "content": format!("{} news", name) }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let memory: Value = response.json();
+        let memory_id = memory["id"].as_str().unwrap().to_string();
+        server
+            .post(&format!("/api/memories/{}/relationships", memory_id))
+            .json(&json!({
+                "target_id": entity_id,
+                "relationship_type": "contains"
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        entity_ids.push(entity_id);
+        memory_ids.push(memory_id);
+    }
+
+    for (source, target, kind, weight) in [
+        (0, 1, "works_at", 1.0),
+        (1, 2, "acquired", 1.0),
+        (0, 2, "invested_in", 5.0),
+    ] {
+        server
+            .post("/api/relationships")
+            .json(&json!({
+                "source_id": entity_ids[source],
+                "target_id": entity_ids[target],
+                "relationship_type": kind,
+                "properties": { "weight": weight }
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    let url = format!(
+        "/api/graph/paths?from={}&to={}&weight_property=weight",
+        memory_ids[0], memory_ids[2]
+    );
+    let response = server.get(&url).await;
+    response.assert_status_ok();
+    let paths: Vec<Value> = response.json();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0]["cost"], 2.0);
+    assert_eq!(paths[1]["cost"], 5.0);
+
+    let response = server
+        .get(&format!("{}&exclude_relationship_types=acquired", url))
+        .await;
+    response.assert_status_ok();
+    let paths: Vec<Value> = response.json();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(
+        paths[0]["relationships"][0]["relationship_type"],
+        "invested_in"
+    );
+
+    let response = server.get(&format!("{}&max_cost=3", url)).await;
+    let paths: Vec<Value> = response.json();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0]["length"], 2);
+
+    server
+        .get(&format!("{}&max_cost=-1", url))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_extract_subgraph() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let mut ids = Vec::new();
+    for name in ["Alice", "Acme"] {
+        let response = server
+            .post("/api/entities")
+            .json(&json!({
+                "entity_type": "organization",
+                "properties": { "name": name }
+            }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let entity: Value = response.json();
+        ids.push(entity["id"].as_str().unwrap().to_string());
+    }
+    server
+        .post("/api/relationships")
+        .json(&json!({
+            "source_id": ids[0],
+            "target_id": ids[1],
+            "relationship_type": "works_at"
+        }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let response = server
+        .post("/api/graph/subgraph")
+        .json(&json!({ "seed_ids": [ids[0]], "format": "triples" }))
+        .await;
+    response.assert_status_ok();
+    let subgraph: Value = response.json();
+    assert_eq!(subgraph["nodes"].as_array().unwrap().len(), 2);
+    assert_eq!(subgraph["nodes"][0]["id"], ids[0].as_str());
+    assert_eq!(subgraph["rendered"], "Alice works_at Acme");
+    assert_eq!(subgraph["estimated_tokens"], 5);
+
+    let response = server
+        .post("/api/graph/subgraph")
+        .json(&json!({ "seed_ids": [ids[0]], "format": "triples", "max_tokens": 2 }))
+        .await;
+    response.assert_status_ok();
+    let subgraph: Value = response.json();
+    assert!(subgraph["edges"].as_array().unwrap().is_empty());
+
+    server
+        .post("/api/graph/subgraph")
+        .json(&json!({ "seed_ids": [] }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server
+        .post("/api/graph/subgraph")
+        .json(&json!({ "seed_ids": [ids[0]], "format": "yaml" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_rdf_export_and_triple_query() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let mut ids = Vec::new();
+    for name in ["Alice", "Acme"] {
+        let response = server
+            .post("/api/entities")
+            .json(&json!({
+                "entity_type": "organization",
+                "properties": { "name": name }
+            }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let entity: Value = response.json();
+        ids.push(entity["id"].as_str().unwrap().to_string());
+    }
+    server
+        .post("/api/relationships")
+        .json(&json!({
+            "source_id": ids[0],
+            "target_id": ids[1],
+            "relationship_type": "works_at"
+        }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let response = server
+        .get("/api/graph/export?format=turtle&base_iri=https://example.org/")
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.header("content-type").to_str().unwrap(),
+        "text/turtle"
+    );
+    let turtle = response.text();
+    assert!(turtle.contains("@prefix node: <https://example.org/node/> ."));
+    assert!(turtle.contains("vocab:works_at"));
+
+    let response = server
+        .post("/api/graph/triples")
+        .json(&json!({ "query": "SELECT ?who WHERE { ?who works_at ?org . ?org name \"Acme\" }" }))
+        .await;
+    response.assert_status_ok();
+    let result: Value = response.json();
+    assert_eq!(result["variables"], json!(["who"]));
+    assert_eq!(result["rows"], json!([{ "who": ids[0] }]));
+
+    server
+        .post("/api/graph/triples")
+        .json(&json!({ "query": "?who works_at" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server
+        .get("/api/graph/export?format=rdfxml")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_graph_diagram() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let mut ids = Vec::new();
+    for name in ["Alice", "Acme"] {
+        let response = server
+            .post("/api/entities")
+            .json(&json!({
+                "entity_type": "organization",
+                "properties": { "name": name }
+            }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let entity: Value = response.json();
+        ids.push(entity["id"].as_str().unwrap().to_string());
+    }
+    server
+        .post("/api/relationships")
+        .json(&json!({
+            "source_id": ids[0],
+            "target_id": ids[1],
+            "relationship_type": "works_at"
+        }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let response = server
+        .get(&format!(
+            "/api/graph/diagram?root={}&types=works_at",
+            ids[0]
+        ))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.text(),
+        "flowchart LR\n    n0(\"Alice<br/><i>organization</i>\")\n    n1(\"Acme<br/><i>organization</i>\")\n    n0 -->|\"works_at\"| n1"
+    );
+
+    let response = server.get("/api/graph/diagram?format=plantuml").await;
+    response.assert_status_ok();
+    let plantuml = response.text();
+    assert!(plantuml.starts_with("@startuml"));
+    assert!(plantuml.contains("n0 --> n1 : works_at"));
+
+    server
+        .get("/api/graph/diagram?format=graphviz")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_entity_versions() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+
+    let response = server
+        .post("/api/entities")
+        .json(&json!({
+            "entity_type": "person",
+            "properties": { "name": "Ada" }
+        }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let entity: Value = response.json();
+    let id = entity["id"].as_str().unwrap();
+
+    server
+        .put(&format!("/api/entities/{}", id))
+        .json(&json!({ "properties": { "name": "Ada Lovelace" } }))
+        .await
+        .assert_status_ok();
+
+    let response = server.get(&format!("/api/entities/{}/versions", id)).await;
+    response.assert_status_ok();
+    let versions: Vec<Value> = response.json();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["operation"], "created");
+    assert_eq!(versions[1]["operation"], "updated");
+
+    let response = server
+        .get(&format!(
+            "/api/entities/{}/versions/diff?old={}&new={}",
+            id,
+            versions[0]["version_id"].as_str().unwrap(),
+            versions[1]["version_id"].as_str().unwrap()
+        ))
+        .await;
+    response.assert_status_ok();
+    let diff: Value = response.json();
+    assert_eq!(diff["changes"][0]["field"], "properties.name");
+    assert_eq!(diff["changes"][0]["new_value"], "Ada Lovelace");
+
+    server
+        .get(&format!(
+            "/api/entities/missing/versions/diff?old={}&new={}",
+            versions[0]["version_id"].as_str().unwrap(),
+            versions[1]["version_id"].as_str().unwrap()
+        ))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    server
+        .get(&format!("/api/entities/{}/at?at=yesterday", id))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,458 @@
+//! Operations tests: jobs, admin maintenance, review, notifications, usage and rate limiting
+
+mod common;
+
+mod jobs {
+    //! Background job queue tests
+
+    use axum::http::StatusCode;
+    use chrono::Utc;
+    use locai::storage::models::Entity;
+    use locai_server::jobs::JobQueue;
+    use serde_json::{Value, json};
+
+    use crate::common::{create_manager_with, create_test_server, wait_for_job};
+
+    #[tokio::test]
+    async fn test_import_job_reports_progress() {
+        let (server, state, _temp_dir) = create_test_server().await;
+
+        let memories: Vec<Value> = (0..5)
+            .map(|n| json!({ "content": format!("Imported note {}", n) }))
+            .collect();
+        let response = server
+            .post("/api/jobs")
+            .json(&json!({ "kind": "import", "memories": memories, "batch_size": 2 }))
+            .await;
+        response.assert_status(StatusCode::ACCEPTED);
+        let job: Value = response.json();
+        assert_eq!(job["kind"], "import");
+
+        let job = wait_for_job(&server, job["id"].as_str().unwrap()).await;
+        assert_eq!(job["status"], "succeeded", "{}", job);
+        assert_eq!(job["progress"], json!({ "done": 5, "total": 5 }));
+        assert_eq!(job["result"]["stored"], 5);
+        assert_eq!(state.memory_manager.count_memories(None).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_finished_job_conflicts() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        let response = server
+            .post("/api/jobs")
+            .json(&json!({ "kind": "clear_caches" }))
+            .await;
+        let id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+        wait_for_job(&server, &id).await;
+
+        let response = server.post(&format!("/api/jobs/{}/cancel", id)).await;
+        response.assert_status(StatusCode::CONFLICT);
+
+        let response = server.post("/api/jobs/missing/cancel").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        let response = server.get("/api/jobs/missing").await;
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_restore_fails_interrupted_jobs() {
+        let (memory_manager, _temp_dir) = create_manager_with(|config| config).await;
+
+        // A job left running by a previous server process
+        let created_at = Utc::now();
+        memory_manager
+            .storage()
+            .create_entity(Entity {
+                id: "interrupted-job".to_string(),
+                entity_type: locai_server::jobs::JOB_ENTITY_TYPE.to_string(),
+                properties: json!({
+                    "id": "interrupted-job",
+                    "kind": "reindex",
+                    "status": "running",
+                    "created_at": created_at,
+                    "started_at": created_at,
+                }),
+                created_at,
+                updated_at: created_at,
+            })
+            .await
+            .unwrap();
+
+        let queue = JobQueue::new(1);
+        assert_eq!(queue.restore(&memory_manager).await.unwrap(), 1);
+
+        let job = queue.get("interrupted-job").expect("Job was not restored");
+        assert!(job.status.is_finished());
+        assert!(job.error.is_some());
+
+        // The failure is stored too, so a second restart finds nothing to fail
+        assert_eq!(JobQueue::new(1).restore(&memory_manager).await.unwrap(), 0);
+    }
+}
+
+mod admin {
+    //! Admin maintenance endpoint tests
+
+    use axum::http::StatusCode;
+    use locai_server::api::auth::generate_jwt_token;
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    use crate::common::{JWT_SECRET, create_auth_server, create_test_server, wait_for_job};
+
+    fn bearer(role: &str) -> String {
+        let (token, _) = generate_jwt_token(&Uuid::new_v4(), "operator", role, JWT_SECRET, 1)
+            .expect("Failed to generate token");
+        format!("Bearer {}", token)
+    }
+
+    #[tokio::test]
+    async fn test_reindex_job_reports_result() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        let response = server.post("/api/admin/reindex").await;
+        response.assert_status(StatusCode::ACCEPTED);
+        let job: Value = response.json();
+        assert_eq!(job["kind"], "reindex");
+        assert_eq!(job["status"], "queued");
+
+        let job = wait_for_job(&server, job["id"].as_str().unwrap()).await;
+        assert_eq!(job["status"], "succeeded", "{}", job);
+        assert!(job["result"]["issues"].is_object());
+        assert!(job["result"]["repair"].is_object());
+
+        let jobs: Vec<Value> = server.get("/api/jobs").await.json();
+        assert_eq!(jobs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retention_job_accepts_options() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        let response = server
+            .post("/api/admin/retention")
+            .json(&json!({ "archive_older_than_days": 30, "archive_limit": 10 }))
+            .await;
+        response.assert_status(StatusCode::ACCEPTED);
+
+        let id = response.json::<Value>()["id"].as_str().unwrap().to_string();
+        let job = wait_for_job(&server, &id).await;
+        assert_eq!(job["status"], "succeeded", "{}", job);
+        assert_eq!(job["result"]["archived_memories"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_admin_endpoints_require_admin_role() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+
+        let response = server.post("/api/admin/cache/clear").await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+
+        let response = server
+            .post("/api/admin/cache/clear")
+            .add_header("Authorization", bearer("viewer"))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(response.json::<Value>()["error"], "forbidden");
+
+        let response = server
+            .post("/api/admin/cache/clear")
+            .add_header("Authorization", bearer("admin"))
+            .await;
+        response.assert_status(StatusCode::ACCEPTED);
+        assert_eq!(response.json::<Value>()["requested_by"], "operator");
+    }
+}
+
+mod review {
+    //! Review queue tests
+
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use locai::config::{AutoApproveRule, ReviewConfig};
+    use locai_server::state::AppState;
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    use crate::common::create_test_server_with;
+
+    async fn create_test_server() -> (TestServer, Arc<AppState>, TempDir) {
+        create_test_server_with(
+            |config| {
+                config.with_review(ReviewConfig {
+                    enabled: true,
+                    sources: vec!["agent:*".to_string()],
+                    auto_approve: vec![AutoApproveRule {
+                        name: "scratch".to_string(),
+                        tags: vec!["scratch".to_string()],
+                        memory_type: None,
+                        source: None,
+                        max_length: None,
+                    }],
+                })
+            },
+            |_| {},
+        )
+        .await
+    }
+
+    async fn create_memory(server: &TestServer, body: Value, status: StatusCode) -> String {
+        let response = server.post("/api/memories").json(&body).await;
+        response.assert_status(status);
+        response.json::<Value>()["id"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_memories_from_reviewed_sources_wait_for_approval() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let held = create_memory(
+            &server,
+            json!({ "content": "The office closes at noon on Fridays", "source": "agent:planner" }),
+            StatusCode::ACCEPTED,
+        )
+        .await;
+        create_memory(
+            &server,
+            json!({ "content": "Draft outline", "source": "agent:planner", "tags": ["scratch"] }),
+            StatusCode::CREATED,
+        )
+        .await;
+        create_memory(
+            &server,
+            json!({ "content": "Written by a person" }),
+            StatusCode::CREATED,
+        )
+        .await;
+
+        server
+            .get(&format!("/api/memories/{}", held))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        let queue: Vec<Value> = server.get("/api/review").await.json();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0]["memory"]["id"], held.as_str());
+        assert_eq!(queue[0]["memory"]["source"], "agent:planner");
+
+        let approved: Value = server
+            .post(&format!("/api/review/{}/approve", held))
+            .await
+            .json();
+        assert_eq!(approved["id"], held.as_str());
+        server
+            .get(&format!("/api/memories/{}", held))
+            .await
+            .assert_status(StatusCode::OK);
+        let queue: Vec<Value> = server.get("/api/review").await.json();
+        assert!(queue.is_empty());
+
+        server
+            .post(&format!("/api/review/{}/approve", held))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_memories_are_dropped() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let held = create_memory(
+            &server,
+            json!({ "content": "The moon is made of cheese", "source": "agent:dreamer" }),
+            StatusCode::ACCEPTED,
+        )
+        .await;
+
+        server
+            .get(&format!("/api/review/{}", held))
+            .await
+            .assert_status(StatusCode::OK);
+        server
+            .post(&format!("/api/review/{}/reject", held))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .get(&format!("/api/review/{}", held))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .get(&format!("/api/memories/{}", held))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .post(&format!("/api/review/{}/reject", held))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
+
+mod notifications {
+    //! Notification endpoint tests
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::create_test_server;
+
+    #[tokio::test]
+    async fn test_targets_and_rules_can_be_managed() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        server
+            .put("/api/notifications/targets/ops")
+            .json(&json!({ "channel": "slack", "url": "https://hooks.slack.com/services/T0/B0/X" }))
+            .await
+            .assert_status(StatusCode::OK);
+        server
+            .put("/api/notifications/targets/pager")
+            .json(&json!({ "channel": "email", "url": "https://example.com" }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        server
+            .put("/api/notifications/rules/reviews")
+            .json(&json!({ "events": ["review"], "targets": ["pager"] }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        let rule: Value = server
+            .put("/api/notifications/rules/reviews")
+            .json(&json!({ "events": ["review", "anomaly"], "targets": ["ops"] }))
+            .await
+            .json();
+        assert_eq!(rule["events"], json!(["review", "anomaly"]));
+
+        let targets: Vec<Value> = server.get("/api/notifications/targets").await.json();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0]["channel"], "slack");
+
+        server
+            .delete("/api/notifications/targets/ops")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        server
+            .delete("/api/notifications/rules/reviews")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .delete("/api/notifications/targets/ops")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .get("/api/notifications/targets/ops")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
+
+mod usage {
+    //! Usage accounting tests
+
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use locai_server::state::AppState;
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    use crate::common::create_test_server_with;
+
+    async fn create_test_server() -> (TestServer, Arc<AppState>, TempDir) {
+        create_test_server_with(|config| config, |config| config.usage_accounting = true).await
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_counted_per_tenant() {
+        let (server, state, _temp_dir) = create_test_server().await;
+
+        let created: Value = server
+            .post("/api/memories")
+            .json(&json!({ "content": "The deploy window is Tuesday" }))
+            .await
+            .json();
+        server
+            .get(&format!(
+                "/api/memories/{}",
+                created["id"].as_str().unwrap()
+            ))
+            .await
+            .assert_status(StatusCode::OK);
+        server
+            .get("/api/memories/search")
+            .add_query_param("q", "deploy")
+            .await
+            .assert_status(StatusCode::OK);
+
+        // Flushed and pending counts add up the same
+        state.memory_manager.flush_usage().await.unwrap();
+        server
+            .get("/api/memories/missing")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let usage: Vec<Value> = server
+            .get("/api/usage")
+            .add_query_param("interval", "month")
+            .await
+            .json();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0]["tenant"], "anonymous");
+        assert_eq!(usage[0]["writes"], 1);
+        assert_eq!(usage[0]["searches"], 1);
+        assert_eq!(usage[0]["reads"], 1);
+
+        let export = server.get("/api/usage/export").await;
+        export.assert_status(StatusCode::OK);
+        let csv = export.text();
+        assert!(csv.starts_with("bucket_start,tenant,reads,writes,searches"));
+        assert!(csv.lines().nth(1).unwrap().contains(",anonymous,"));
+
+        server
+            .get("/api/usage")
+            .add_query_param("interval", "week")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+mod rate_limit {
+    //! Rate limiting tests, with hot state kept locally
+
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use locai_server::state::AppState;
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use crate::common::create_test_server_with;
+
+    async fn create_test_server() -> (TestServer, Arc<AppState>, TempDir) {
+        create_test_server_with(|config| config, |config| config.rate_limit_rpm = 3).await
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_rejects_excess_requests() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        for _ in 0..3 {
+            server.get("/api/scratchpad").await.assert_status_ok();
+        }
+        let response = server.get("/api/scratchpad").await;
+        response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        let body: Value = response.json();
+        assert_eq!(body["error"], "rate_limit_exceeded");
+
+        // Health probes aren't counted
+        server.get("/api/health").await.assert_status_ok();
+
+        // Other clients have their own allowance
+        server
+            .get("/api/scratchpad")
+            .add_header("x-forwarded-for", "203.0.113.7")
+            .await
+            .assert_status_ok();
+    }
+}
@@ -0,0 +1,120 @@
+//! Tests for the remote memory manager against a server on a local port
+
+mod common;
+
+use locai::config::ConfigBuilder;
+use locai::memory::search_extensions::SearchMode;
+use locai::models::{Memory, MemoryPriority, MemoryType};
+use locai::storage::filters::MemoryFilter;
+
+use common::create_state_with;
+
+/// Start a server, returning its base URL
+async fn start_server() -> (String, tempfile::TempDir) {
+    let (state, temp_dir) = create_state_with(|config| config, |_| {}).await;
+    let app = locai_server::create_router(state);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}", address), temp_dir)
+}
+
+fn memory(content: &str, tags: &[&str]) -> Memory {
+    let mut memory = Memory::new(String::new(), content.to_string(), MemoryType::Fact);
+    memory.priority = MemoryPriority::High;
+    memory.tags = tags.iter().map(|tag| tag.to_string()).collect();
+    memory
+}
+
+#[tokio::test]
+async fn test_remote_memory_round_trip() {
+    let (url, _temp_dir) = start_server().await;
+    let config = ConfigBuilder::new().with_remote(&url).build().unwrap();
+    let memories = locai::connect(config).await.unwrap();
+
+    let id = memories
+        .store_memory(memory("The bridge closes at dusk", &["bridge"]))
+        .await
+        .unwrap();
+    let mut stored = memories.get_memory(&id).await.unwrap().unwrap();
+    assert_eq!(stored.memory_type, MemoryType::Fact);
+    assert_eq!(stored.priority, MemoryPriority::High);
+
+    stored.content = "The bridge closes at midnight".to_string();
+    assert!(memories.update_memory(stored).await.unwrap());
+    assert!(memories.tag_memory(&id, "schedule").await.unwrap());
+
+    let results = memories
+        .search("midnight", Some(5), None, SearchMode::Text)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].memory.tags, vec!["bridge", "schedule"]);
+
+    assert!(memories.delete_memory(&id).await.unwrap());
+    assert!(!memories.delete_memory(&id).await.unwrap());
+    assert_eq!(memories.get_memory(&id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_remote_filters_and_counts() {
+    let (url, _temp_dir) = start_server().await;
+    let config = ConfigBuilder::new().with_remote(&url).build().unwrap();
+    let memories = locai::connect(config).await.unwrap();
+
+    let checklist = memories
+        .store_memory(memory("Deployment checklist", &["ops"]))
+        .await
+        .unwrap();
+    let incident = memories
+        .store_memory(memory("Incident report", &["ops"]))
+        .await
+        .unwrap();
+    memories
+        .store_memory(memory("Lunch menu", &["food"]))
+        .await
+        .unwrap();
+
+    let ops = MemoryFilter {
+        tags: Some(vec!["ops".to_string()]),
+        ..Default::default()
+    };
+    assert_eq!(memories.count_memories(Some(ops)).await.unwrap(), 2);
+    assert_eq!(memories.count_memories(None).await.unwrap(), 3);
+
+    // IDs aren't a list parameter, so they're matched client-side
+    let by_id = MemoryFilter {
+        ids: Some(vec![incident.clone()]),
+        ..Default::default()
+    };
+    let found = memories
+        .filter_memories(by_id, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, incident);
+
+    assert!(
+        memories
+            .create_relationship(&incident, &checklist, "references")
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_connect_without_remote_initializes_locally() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = ConfigBuilder::new()
+        .with_data_dir(temp_dir.path())
+        .with_memory_storage()
+        .build()
+        .unwrap();
+    let memories = locai::connect(config).await.unwrap();
+
+    let mut local = memory("Stored locally", &[]);
+    local.id = "local-memory".to_string();
+    let id = memories.store_memory(local).await.unwrap();
+    assert!(memories.get_memory(&id).await.unwrap().is_some());
+}
@@ -0,0 +1,99 @@
+//! Replication endpoint tests
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use locai::prelude::*;
+use locai::replication::transport::{self, SyncMode};
+use locai::replication::{ReplicationConfig, Replicator};
+use locai_server::config::ServerConfig;
+use locai_server::{AppState, create_router};
+
+async fn create_manager() -> MemoryManager {
+    let config = ConfigBuilder::new().with_memory_storage().build().unwrap();
+    locai::init(config).await.unwrap()
+}
+
+/// Start a server with replication enabled; returns its state and address
+async fn start_home_server() -> (Arc<AppState>, std::net::SocketAddr) {
+    let mut server_config = ServerConfig::default();
+    server_config.enable_auth = false;
+    server_config.replication.enabled = true;
+
+    let mut app_state = AppState::new(create_manager().await, server_config);
+    let replicator = Replicator::start(
+        app_state.memory_manager.storage().clone(),
+        ReplicationConfig::with_node_id("home"),
+    )
+    .await
+    .unwrap();
+    app_state.set_replicator(replicator);
+    let app_state = Arc::new(app_state);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = create_router(app_state.clone());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    (app_state, address)
+}
+
+#[tokio::test]
+async fn test_edge_pushes_changes_to_home() {
+    let (home, address) = start_home_server().await;
+
+    let edge = create_manager().await;
+    let replicator = Replicator::start(
+        edge.storage().clone(),
+        ReplicationConfig::with_node_id("edge"),
+    )
+    .await
+    .unwrap();
+    let url = format!("ws://{}/api/replication/ws", address);
+    let session =
+        tokio::spawn(
+            async move { transport::connect(replicator, &url, SyncMode::Push, None).await },
+        );
+
+    // Give the session time to connect and subscribe
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let memory_id = edge
+        .store_memory(MemoryBuilder::fact("Edge agent saw the garage door open").build())
+        .await
+        .unwrap();
+
+    let replicated = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Some(memory) = home.memory_manager.get_memory(&memory_id).await.unwrap() {
+                return memory;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("memory was not replicated to the home server");
+    assert_eq!(replicated.content, "Edge agent saw the garage door open");
+
+    session.abort();
+}
+
+#[tokio::test]
+async fn test_endpoint_unavailable_when_disabled() {
+    let mut server_config = ServerConfig::default();
+    server_config.enable_auth = false;
+    let app_state = Arc::new(AppState::new(create_manager().await, server_config));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, create_router(app_state)).await });
+
+    let edge = create_manager().await;
+    let replicator = Replicator::start(edge.storage().clone(), ReplicationConfig::default())
+        .await
+        .unwrap();
+    let url = format!("ws://{}/api/replication/ws", address);
+    assert!(
+        transport::connect(replicator, &url, SyncMode::Pull, None)
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,638 @@
+//! Search tests: streaming and batch search, scoring profiles, pins and collections
+
+mod common;
+
+mod stream {
+    //! Streaming search tests
+    //!
+    //! The stream sends each search stage's results as a server-sent event,
+    //! then a final `done` event.
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::{create_auth_server, create_memory_as, create_test_server, signup};
+
+    /// `(event, data)` pairs of a server-sent event stream
+    fn parse_events(body: &str) -> Vec<(String, String)> {
+        body.split("\n\n")
+            .filter_map(|block| {
+                let mut event = None;
+                let mut data = Vec::new();
+                for line in block.lines() {
+                    if let Some(name) = line.strip_prefix("event:") {
+                        event = Some(name.trim().to_string());
+                    } else if let Some(line) = line.strip_prefix("data:") {
+                        data.push(line.trim_start());
+                    }
+                }
+                event.map(|event| (event, data.join("\n")))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_sends_stage_results_then_done() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        for content in ["Warrior training notes", "Gardening calendar"] {
+            server
+                .post("/api/memories")
+                .json(&json!({ "content": content }))
+                .await
+                .assert_status(StatusCode::CREATED);
+        }
+
+        let response = server
+            .get("/api/memories/search/stream")
+            .add_query_param("q", "warrior")
+            .await;
+        response.assert_status_ok();
+        assert_eq!(
+            response.header("content-type").to_str().unwrap(),
+            "text/event-stream"
+        );
+
+        let events = parse_events(&response.text());
+        let names: Vec<&str> = events.iter().map(|(event, _)| event.as_str()).collect();
+        assert_eq!(names, vec!["results", "done"]);
+
+        let stage: Value = serde_json::from_str(&events[0].1).unwrap();
+        assert_eq!(stage["stage"], "text");
+        assert_eq!(stage["final"], true);
+        let results = stage["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["memory"]["content"], "Warrior training notes");
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_rejects_invalid_request_before_streaming() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        server
+            .get("/api/memories/search/stream")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        // Hybrid needs an ML service, which this server doesn't have
+        server
+            .get("/api/memories/search/stream")
+            .add_query_param("q", "warrior")
+            .add_query_param("mode", "hybrid")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_hides_other_users_memories() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+        let alice = signup(&server, "alice").await;
+        let bob = signup(&server, "bob").await;
+        create_memory_as(
+            &server,
+            &alice,
+            json!({ "content": "Warrior training notes" }),
+        )
+        .await;
+
+        for (token, expected) in [(alice, 1), (bob, 0)] {
+            let response = server
+                .get("/api/memories/search/stream")
+                .add_query_param("q", "warrior")
+                .add_header("Authorization", token)
+                .await;
+            response.assert_status_ok();
+            let events = parse_events(&response.text());
+            let stage: Value = serde_json::from_str(&events[0].1).unwrap();
+            assert_eq!(stage["results"].as_array().unwrap().len(), expected);
+        }
+    }
+}
+
+mod batch {
+    //! Batch search tests
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::{
+        create_auth_server, create_memory, create_memory_as, create_test_server, signup,
+    };
+
+    #[tokio::test]
+    async fn test_batch_search_returns_results_per_query() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        create_memory(
+            &server,
+            json!({ "content": "Deployment checklist for release", "tags": ["ops"] }),
+        )
+        .await;
+        create_memory(
+            &server,
+            json!({ "content": "Deployment retrospective notes", "tags": ["team"] }),
+        )
+        .await;
+        create_memory(
+            &server,
+            json!({ "content": "Incident report for the outage", "tags": ["ops"] }),
+        )
+        .await;
+
+        let results: Vec<Value> = server
+            .post("/api/memories/search/batch")
+            .json(&json!({
+                "defaults": { "tags": "ops" },
+                "queries": [
+                    { "q": "deployment" },
+                    { "q": "incident" },
+                    { "q": "deployment", "tags": "team" }
+                ]
+            }))
+            .await
+            .json();
+
+        let contents: Vec<Vec<&str>> = results
+            .iter()
+            .map(|query| {
+                query["results"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|result| result["memory"]["content"].as_str().unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                vec!["Deployment checklist for release"],
+                vec!["Incident report for the outage"],
+                vec!["Deployment retrospective notes"],
+            ]
+        );
+        assert_eq!(results[1]["q"], "incident");
+        assert!(results[0].get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_search_rejects_invalid_batches() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        server
+            .post("/api/memories/search/batch")
+            .json(&json!({ "queries": [] }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        let too_many: Vec<Value> = (0..21).map(|i| json!({ "q": format!("q{}", i) })).collect();
+        server
+            .post("/api/memories/search/batch")
+            .json(&json!({ "queries": too_many }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        let response = server
+            .post("/api/memories/search/batch")
+            .json(&json!({ "queries": [{ "q": "fine" }, { "limit": 3 }] }))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert!(response.text().contains("Query 1"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_search_hides_other_users_memories() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+        let alice = signup(&server, "alice").await;
+        let bob = signup(&server, "bob").await;
+        create_memory_as(
+            &server,
+            &alice,
+            json!({ "content": "Deployment checklist" }),
+        )
+        .await;
+        create_memory_as(&server, &bob, json!({ "content": "Deployment budget" })).await;
+
+        let results: Vec<Value> = server
+            .post("/api/memories/search/batch")
+            .add_header("Authorization", bob)
+            .json(&json!({ "queries": [{ "q": "deployment" }, { "q": "checklist" }] }))
+            .await
+            .json();
+        assert_eq!(results[0]["results"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            results[0]["results"][0]["memory"]["content"],
+            "Deployment budget"
+        );
+        assert!(results[1]["results"].as_array().unwrap().is_empty());
+    }
+}
+
+mod scoring_profiles {
+    //! Scoring profile tests
+
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use axum_test::TestServer;
+    use locai::search::ScoringConfig;
+    use locai_server::state::AppState;
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    use crate::common::create_test_server_with;
+
+    async fn create_test_server() -> (TestServer, Arc<AppState>, TempDir) {
+        create_test_server_with(
+            |config| config.with_scoring_profile("graph-heavy", ScoringConfig::default()),
+            |_| {},
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_list_set_and_remove_profiles() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let profiles: Vec<Value> = server.get("/api/scoring-profiles").await.json();
+        let names: Vec<&str> = profiles
+            .iter()
+            .map(|profile| profile["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "default",
+                "graph-heavy",
+                "importance",
+                "recency",
+                "semantic"
+            ]
+        );
+
+        let response = server
+            .put("/api/scoring-profiles/precision")
+            .json(&json!({ "vector_weight": 0.0, "recency_boost": 0.0 }))
+            .await;
+        response.assert_status_ok();
+        let profile: Value = response.json();
+        assert_eq!(profile["scoring"]["vector_weight"], 0.0);
+        assert_eq!(profile["scoring"]["bm25_weight"], 1.0);
+
+        let profile: Value = server.get("/api/scoring-profiles/precision").await.json();
+        assert_eq!(profile["scoring"]["recency_boost"], 0.0);
+
+        server
+            .put("/api/scoring-profiles/slow")
+            .json(&json!({ "decay_rate": 0.0 }))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        server
+            .delete("/api/scoring-profiles/precision")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .get("/api/scoring-profiles/precision")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .delete("/api/scoring-profiles/recency")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_search_with_profile() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        server
+            .post("/api/memories")
+            .json(&json!({ "content": "The dragon guards the northern pass" }))
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let response = server
+            .get("/api/memories/search?q=dragon&scoring_profile=recency")
+            .await;
+        response.assert_status_ok();
+        let results: Vec<Value> = response.json();
+        assert_eq!(results.len(), 1);
+
+        server
+            .get("/api/memories/search?q=dragon&scoring_profile=unknown")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+}
+
+mod pins {
+    //! Pinned memory tests
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::{
+        create_auth_server, create_memory, create_memory_as, create_test_server, signup,
+    };
+
+    #[tokio::test]
+    async fn test_pinned_memories_lead_search() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let persona = create_memory(&server, json!({ "content": "You are a patient tutor" })).await;
+        let lesson = create_memory(&server, json!({ "content": "Fractions lesson plan" })).await;
+        let matched =
+            create_memory(&server, json!({ "content": "Fractions worksheet answers" })).await;
+
+        server
+            .post(&format!("/api/memories/{}/pin", persona))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .post(&format!("/api/memories/{}/pin", lesson))
+            .add_query_param("scope", "tutor")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+
+        let pinned: Vec<Value> = server
+            .get("/api/pins")
+            .add_query_param("scope", "tutor")
+            .await
+            .json();
+        let ids: Vec<&str> = pinned.iter().map(|m| m["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec![persona.as_str(), lesson.as_str()]);
+
+        let results: Vec<Value> = server
+            .get("/api/memories/search")
+            .add_query_param("q", "worksheet")
+            .add_query_param("pin_scope", "tutor")
+            .await
+            .json();
+        let ids: Vec<&str> = results
+            .iter()
+            .map(|r| r["memory"]["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![persona.as_str(), lesson.as_str(), matched.as_str()]
+        );
+        assert_eq!(results[0]["match_method"], "pinned");
+        assert!(results[2].get("match_method").is_none());
+
+        // Without a pin scope, search is unchanged
+        let results: Vec<Value> = server
+            .get("/api/memories/search")
+            .add_query_param("q", "worksheet")
+            .await
+            .json();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pin_errors() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let id = create_memory(&server, json!({ "content": "Standing instructions" })).await;
+
+        server
+            .post("/api/memories/no-such-memory/pin")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .delete(&format!("/api/memories/{}/pin", id))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        server
+            .post(&format!("/api/memories/{}/pin", id))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .delete(&format!("/api/memories/{}/pin", id))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        let pinned: Vec<Value> = server.get("/api/pins").await.json();
+        assert!(pinned.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pins_respect_shares() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+        let alice = signup(&server, "alice").await;
+        let bob = signup(&server, "bob").await;
+        let private = create_memory_as(
+            &server,
+            &alice,
+            json!({ "content": "Alice's standing orders" }),
+        )
+        .await;
+        create_memory_as(&server, &bob, json!({ "content": "Bob's standing orders" })).await;
+
+        server
+            .post(&format!("/api/memories/{}/pin", private))
+            .add_header("Authorization", bob.clone())
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .post(&format!("/api/memories/{}/pin", private))
+            .add_header("Authorization", alice.clone())
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+
+        let pinned: Vec<Value> = server
+            .get("/api/pins")
+            .add_header("Authorization", alice)
+            .await
+            .json();
+        assert_eq!(pinned.len(), 1);
+        let pinned: Vec<Value> = server
+            .get("/api/pins")
+            .add_header("Authorization", bob.clone())
+            .await
+            .json();
+        assert!(pinned.is_empty());
+
+        // Another user's pinned memory doesn't lead their search either
+        let results: Vec<Value> = server
+            .get("/api/memories/search")
+            .add_query_param("q", "orders")
+            .add_query_param("pin_scope", "agent")
+            .add_header("Authorization", bob)
+            .await
+            .json();
+        let contents: Vec<&str> = results
+            .iter()
+            .map(|r| r["memory"]["content"].as_str().unwrap())
+            .collect();
+        assert_eq!(contents, vec!["Bob's standing orders"]);
+    }
+}
+
+mod collections {
+    //! Collection tests
+
+    use axum::http::StatusCode;
+    use serde_json::{Value, json};
+
+    use crate::common::{
+        create_auth_server, create_memory, create_memory_as, create_test_server, signup,
+    };
+
+    /// Sorted memory IDs found at `pointer` in each value
+    fn ids(values: &[Value], pointer: &str) -> Vec<String> {
+        let mut ids: Vec<String> = values
+            .iter()
+            .map(|value| {
+                value
+                    .pointer(pointer)
+                    .unwrap()
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_collection_membership_and_search() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+        let picked = create_memory(
+            &server,
+            json!({ "content": "Deploy with the blue-green script" }),
+        )
+        .await;
+        let queried = create_memory(
+            &server,
+            json!({ "content": "Deploy rollback steps", "tags": ["ops"] }),
+        )
+        .await;
+        create_memory(&server, json!({ "content": "Deploy the garden gnome" })).await;
+
+        let response = server
+            .post("/api/collections")
+            .json(&json!({ "name": "runbooks", "query": { "tags": ["ops"] } }))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+
+        let response = server
+            .post("/api/collections/runbooks/memories")
+            .json(&json!({ "memory_ids": [picked] }))
+            .await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["memory_ids"], json!([picked]));
+
+        let mut expected = vec![picked.clone(), queried.clone()];
+        expected.sort();
+
+        let members: Vec<Value> = server
+            .get("/api/collections/runbooks/memories")
+            .await
+            .json();
+        assert_eq!(ids(&members, "/id"), expected);
+
+        let results: Vec<Value> = server
+            .get("/api/memories/search")
+            .add_query_param("q", "deploy")
+            .add_query_param("collection", "runbooks")
+            .await
+            .json();
+        assert_eq!(ids(&results, "/memory/id"), expected);
+
+        // Removing the query leaves the hand-picked memory
+        server
+            .put("/api/collections/runbooks")
+            .json(&json!({ "remove_query": true }))
+            .await
+            .assert_status_ok();
+        let members: Vec<Value> = server
+            .get("/api/collections/runbooks/memories")
+            .await
+            .json();
+        assert_eq!(ids(&members, "/id"), vec![picked]);
+    }
+
+    #[tokio::test]
+    async fn test_collection_errors() {
+        let (server, _state, _temp_dir) = create_test_server().await;
+
+        server
+            .post("/api/collections")
+            .json(&json!({ "name": "notes" }))
+            .await
+            .assert_status(StatusCode::CREATED);
+        server
+            .post("/api/collections")
+            .json(&json!({ "name": "notes" }))
+            .await
+            .assert_status(StatusCode::CONFLICT);
+        server
+            .post("/api/collections/notes/memories")
+            .json(&json!({ "memory_ids": ["no-such-memory"] }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .get("/api/collections/missing")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        server
+            .delete("/api/collections/notes")
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .get("/api/memories/search")
+            .add_query_param("q", "anything")
+            .add_query_param("collection", "notes")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_collections_respect_shares() {
+        let (server, _state, _temp_dir) = create_auth_server().await;
+        let alice = signup(&server, "alice").await;
+        let bob = signup(&server, "bob").await;
+        let private = create_memory_as(
+            &server,
+            &alice,
+            json!({ "content": "Deploy secrets rotation" }),
+        )
+        .await;
+
+        server
+            .post("/api/collections")
+            .add_header("Authorization", bob.clone())
+            .json(&json!({ "name": "stolen", "memory_ids": [private] }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .post("/api/collections")
+            .add_header("Authorization", alice.clone())
+            .json(&json!({ "name": "runbooks", "memory_ids": [private] }))
+            .await
+            .assert_status(StatusCode::CREATED);
+        server
+            .post("/api/collections/runbooks/memories")
+            .add_header("Authorization", bob.clone())
+            .json(&json!({ "memory_ids": [private] }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let members: Vec<Value> = server
+            .get("/api/collections/runbooks/memories")
+            .add_header("Authorization", alice)
+            .await
+            .json();
+        assert_eq!(ids(&members, "/id"), vec![private]);
+        let members: Vec<Value> = server
+            .get("/api/collections/runbooks/memories")
+            .add_header("Authorization", bob.clone())
+            .await
+            .json();
+        assert!(members.is_empty());
+        let results: Vec<Value> = server
+            .get("/api/memories/search")
+            .add_query_param("q", "deploy")
+            .add_query_param("collection", "runbooks")
+            .add_header("Authorization", bob)
+            .await
+            .json();
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,672 @@
+//! Tests for sharing memories between users
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use futures::StreamExt;
+use locai_server::state::AppState;
+use locai_server::websocket::WebSocketMessage;
+use serde_json::{Value, json};
+use tempfile::TempDir;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::{Message, client::IntoClientRequest};
+
+use common::{create_memory_as, create_test_server_with, signup};
+
+async fn create_test_server() -> (TestServer, Arc<AppState>, TempDir) {
+    create_test_server_with(
+        |config| config,
+        |config| {
+            config.enable_auth = true;
+            config.enable_vectorstore_compat = true;
+            config.replication.enabled = true;
+        },
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_memories_are_private_to_their_owner() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Alice's diary" })).await;
+
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", alice.clone())
+        .await
+        .assert_status_ok();
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob.clone())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let listed: Vec<Value> = server
+        .get("/api/memories")
+        .add_header("Authorization", bob)
+        .await
+        .json();
+    assert!(listed.is_empty());
+}
+
+#[tokio::test]
+async fn test_reader_grant_allows_reading_only() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Team roadmap" })).await;
+    let response = server
+        .post("/api/shares")
+        .add_header("Authorization", alice.clone())
+        .json(&json!({ "memory_id": id, "user": "bob", "role": "reader" }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let grant: Value = response.json();
+    assert_eq!(grant["owner"], "alice");
+    assert_eq!(grant["grantee"], "bob");
+
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob.clone())
+        .await
+        .assert_status_ok();
+    server
+        .put(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "content": "Bob's roadmap" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // Only the owner shares further
+    server
+        .post("/api/shares")
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "memory_id": id, "user": "alice", "role": "writer" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // Revoking hides the memory again
+    server
+        .delete(&format!("/api/shares/{}", grant["id"].as_str().unwrap()))
+        .add_header("Authorization", alice)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_reload_picks_up_grants_changed_by_another_replica() {
+    let (server, state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Replicated plan" })).await;
+    let grant: Value = server
+        .post("/api/shares")
+        .add_header("Authorization", alice)
+        .json(&json!({ "memory_id": id, "user": "bob", "role": "reader" }))
+        .await
+        .json();
+
+    // Another replica revokes the grant in the shared database
+    state
+        .memory_manager
+        .storage()
+        .delete_entity(grant["id"].as_str().unwrap())
+        .await
+        .unwrap();
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob.clone())
+        .await
+        .assert_status_ok();
+
+    assert_eq!(state.shares.reload(&state.memory_manager).await.unwrap(), 0);
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_collection_writer_grant_allows_updates() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let shared = create_memory_as(
+        &server,
+        &alice,
+        json!({ "content": "Deploy checklist", "properties": { "collection": "ops" } }),
+    )
+    .await;
+    create_memory_as(&server, &alice, json!({ "content": "Salary notes" })).await;
+
+    server
+        .post("/api/shares")
+        .add_header("Authorization", alice.clone())
+        .json(&json!({ "collection": "ops", "user": "bob", "role": "writer" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let listed: Vec<Value> = server
+        .get("/api/memories")
+        .add_header("Authorization", bob.clone())
+        .await
+        .json();
+    let ids: Vec<&str> = listed.iter().map(|m| m["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec![shared.as_str()]);
+
+    // A writer can't take the memory over by rewriting its properties
+    let response = server
+        .put(&format!("/api/memories/{}", shared))
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "content": "Deploy checklist v2", "properties": { "collection": "ops" } }))
+        .await;
+    response.assert_status_ok();
+    let updated: Value = response.json();
+    assert_eq!(updated["content"], "Deploy checklist v2");
+    assert!(updated["properties"]["owner"].is_string());
+
+    server
+        .delete(&format!("/api/memories/{}", shared))
+        .add_header("Authorization", bob)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_sharing_with_unknown_user_fails() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+
+    server
+        .post("/api/shares")
+        .add_header("Authorization", alice)
+        .json(&json!({ "collection": "ops", "user": "nobody", "role": "reader" }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_vector_store_respects_shares() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let response = server
+        .post("/api/vectorstore/add_texts")
+        .add_header("Authorization", alice.clone())
+        .json(&json!({ "texts": ["Alice's dragon diary"] }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let id = response.json::<Value>()["ids"][0]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let search = json!({ "query": "dragon diary" });
+    let found: Vec<Value> = server
+        .post("/api/vectorstore/similarity_search")
+        .add_header("Authorization", alice.clone())
+        .json(&search)
+        .await
+        .json();
+    assert_eq!(found.len(), 1);
+    assert!(found[0]["metadata"]["owner"].is_string());
+
+    let found: Vec<Value> = server
+        .post("/api/vectorstore/similarity_search_with_score")
+        .add_header("Authorization", bob.clone())
+        .json(&search)
+        .await
+        .json();
+    assert!(found.is_empty());
+
+    let deleted: Value = server
+        .post("/api/vectorstore/delete")
+        .add_header("Authorization", bob)
+        .json(&json!({ "ids": [id] }))
+        .await
+        .json();
+    assert_eq!(deleted["deleted"], 0);
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", alice)
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_batch_respects_shares() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Alice's diary" })).await;
+
+    for operation in [
+        json!({ "op": "DeleteMemory", "data": { "id": id } }),
+        json!({ "op": "UpdateMemory", "data": { "id": id, "content": "Bob was here" } }),
+        json!({ "op": "UpdateMetadata", "data": { "memory_id": id, "metadata": { "owner": "bob" } } }),
+    ] {
+        server
+            .post("/api/batch")
+            .add_header("Authorization", bob.clone())
+            .json(&json!({ "operations": [operation] }))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+    let memory: Value = server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", alice.clone())
+        .await
+        .json();
+    assert_eq!(memory["content"], "Alice's diary");
+
+    // Memories created in a batch belong to the caller, whatever they claim
+    server
+        .post("/api/batch")
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "operations": [{
+            "op": "CreateMemory",
+            "data": { "content": "Bob's notes", "memory_type": "fact", "properties": { "owner": "alice" } }
+        }] }))
+        .await
+        .assert_status_ok();
+    let listed: Vec<Value> = server
+        .get("/api/memories")
+        .add_header("Authorization", alice)
+        .await
+        .json();
+    assert!(
+        listed
+            .iter()
+            .all(|memory| memory["content"] != "Bob's notes")
+    );
+}
+
+#[tokio::test]
+async fn test_changefeed_hides_private_memories() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Alice's diary" })).await;
+
+    let mentions = |changes: &Value| {
+        changes["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|change| change["id"] == id.as_str() && change["record"].is_object())
+    };
+    let changes: Value = server
+        .get("/api/changes?limit=1000")
+        .add_header("Authorization", alice)
+        .await
+        .json();
+    assert!(mentions(&changes));
+    let changes: Value = server
+        .get("/api/changes?limit=1000")
+        .add_header("Authorization", bob)
+        .await
+        .json();
+    assert!(!mentions(&changes));
+}
+
+#[tokio::test]
+async fn test_graph_and_entity_routes_hide_private_memories() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Alice met Carol" })).await;
+    let response = server
+        .post("/api/entities")
+        .add_header("Authorization", alice.clone())
+        .json(&json!({ "entity_type": "person", "properties": { "name": "Carol" } }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let entity = response.json::<Value>()["id"].as_str().unwrap().to_string();
+    server
+        .post("/api/batch")
+        .add_header("Authorization", alice.clone())
+        .json(&json!({ "operations": [{
+            "op": "CreateRelationship",
+            "data": { "source": id, "target": entity, "relationship_type": "contains" }
+        }] }))
+        .await
+        .assert_status_ok();
+
+    let relationships: Vec<Value> = server
+        .get(&format!("/api/relationships?source_id={}", id))
+        .add_header("Authorization", alice.clone())
+        .await
+        .json();
+    assert_eq!(relationships.len(), 1);
+    let relationship = relationships[0]["id"].as_str().unwrap();
+    let memories: Vec<Value> = server
+        .get(&format!("/api/entities/{}/memories", entity))
+        .add_header("Authorization", alice)
+        .await
+        .json();
+    assert_eq!(memories.len(), 1);
+
+    server
+        .get(&format!("/api/memories/{}/graph", id))
+        .add_header("Authorization", bob.clone())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    let memories: Vec<Value> = server
+        .get(&format!("/api/entities/{}/memories", entity))
+        .add_header("Authorization", bob.clone())
+        .await
+        .json();
+    assert!(memories.is_empty());
+    let relationships: Vec<Value> = server
+        .get(&format!("/api/entities/{}/relationships", entity))
+        .add_header("Authorization", bob.clone())
+        .await
+        .json();
+    assert!(relationships.is_empty());
+    let relationships: Vec<Value> = server
+        .get(&format!("/api/relationships?source_id={}", id))
+        .add_header("Authorization", bob.clone())
+        .await
+        .json();
+    assert!(relationships.is_empty());
+    server
+        .get(&format!("/api/relationships/{}", relationship))
+        .add_header("Authorization", bob.clone())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .delete(&format!("/api/relationships/{}", relationship))
+        .add_header("Authorization", bob)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_memory_relationship_routes_respect_shares() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let private = create_memory_as(&server, &alice, json!({ "content": "Alice's diary" })).await;
+    let shared = create_memory_as(&server, &alice, json!({ "content": "Team roadmap" })).await;
+    let own = create_memory_as(&server, &bob, json!({ "content": "Bob's notes" })).await;
+    server
+        .post("/api/shares")
+        .add_header("Authorization", alice.clone())
+        .json(&json!({ "memory_id": shared, "user": "bob", "role": "reader" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .post(&format!("/api/memories/{}/relationships", shared))
+        .add_header("Authorization", alice.clone())
+        .json(&json!({ "relationship_type": "references", "target_id": private }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    // Linking from a memory takes write access to it, and a private target
+    // looks missing
+    server
+        .post(&format!("/api/memories/{}/relationships", private))
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "relationship_type": "references", "target_id": own }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .post(&format!("/api/memories/{}/relationships", shared))
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "relationship_type": "references", "target_id": own }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post(&format!("/api/memories/{}/relationships", own))
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "relationship_type": "references", "target_id": private }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .post(&format!("/api/memories/{}/relationships", own))
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "relationship_type": "references", "target_id": shared }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    server
+        .get(&format!("/api/memories/{}/relationships", private))
+        .add_header("Authorization", bob.clone())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    let relationships: Vec<Value> = server
+        .get(&format!("/api/memories/{}/relationships", shared))
+        .add_header("Authorization", bob)
+        .await
+        .json();
+    assert_eq!(relationships.len(), 1);
+    assert_eq!(relationships[0]["source_id"], own.as_str());
+    let relationships: Vec<Value> = server
+        .get(&format!("/api/memories/{}/relationships", shared))
+        .add_header("Authorization", alice)
+        .await
+        .json();
+    assert_eq!(relationships.len(), 1);
+    assert_eq!(relationships[0]["target_id"], private.as_str());
+}
+
+#[tokio::test]
+async fn test_idempotency_keys_are_per_user() {
+    let (server, _state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let body = json!({ "content": "Standup notes", "idempotency_key": "retry-1" });
+    let first = create_memory_as(&server, &alice, body.clone()).await;
+    assert_eq!(create_memory_as(&server, &alice, body.clone()).await, first);
+
+    // Bob's request with the same key stores his own memory rather than
+    // replaying Alice's
+    let own = create_memory_as(&server, &bob, body).await;
+    assert_ne!(own, first);
+    let memory: Value = server
+        .get(&format!("/api/memories/{}", own))
+        .add_header("Authorization", bob.clone())
+        .await
+        .json();
+    assert_eq!(memory["content"], "Standup notes");
+
+    // Nor does different content under Alice's key conflict
+    create_memory_as(
+        &server,
+        &bob,
+        json!({ "content": "Other notes", "idempotency_key": "retry-2" }),
+    )
+    .await;
+    create_memory_as(
+        &server,
+        &alice,
+        json!({ "content": "Alice's notes", "idempotency_key": "retry-2" }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_forged_grant_is_refused() {
+    let (server, state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Alice's diary" })).await;
+    let memory: Value = server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", alice)
+        .await
+        .json();
+    let bob_id = create_memory_as(&server, &bob, json!({ "content": "Bob's notes" })).await;
+    let bob_memory: Value = server
+        .get(&format!("/api/memories/{}", bob_id))
+        .add_header("Authorization", bob.clone())
+        .await
+        .json();
+
+    // Bob writes a grant from Alice to himself through the entity API
+    server
+        .post("/api/entities")
+        .add_header("Authorization", bob.clone())
+        .json(&json!({
+            "entity_type": "share_grant",
+            "properties": {
+                "id": "forged",
+                "owner_id": memory["properties"]["owner"],
+                "owner": "alice",
+                "grantee_id": bob_memory["properties"]["owner"],
+                "grantee": "bob",
+                "memory_id": id,
+                "role": "writer",
+                "created_at": "2026-01-01T00:00:00Z"
+            }
+        }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    // Nor can he turn an entity of his own into one
+    let entity: Value = server
+        .post("/api/entities")
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "entity_type": "person", "properties": { "name": "Bob" } }))
+        .await
+        .json();
+    server
+        .put(&format!("/api/entities/{}", entity["id"].as_str().unwrap()))
+        .add_header("Authorization", bob.clone())
+        .json(&json!({ "entity_type": "share_grant" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    assert_eq!(state.shares.reload(&state.memory_manager).await.unwrap(), 0);
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_grants_are_hidden_from_the_entity_api() {
+    let (server, state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+    let carol = signup(&server, "carol").await;
+
+    let id = create_memory_as(&server, &alice, json!({ "content": "Team roadmap" })).await;
+    let grant: Value = server
+        .post("/api/shares")
+        .add_header("Authorization", alice)
+        .json(&json!({ "memory_id": id, "user": "bob", "role": "reader" }))
+        .await
+        .json();
+    let grant_id = grant["id"].as_str().unwrap();
+
+    let listed: Vec<Value> = server
+        .get("/api/entities?entity_type=share_grant")
+        .add_header("Authorization", carol.clone())
+        .await
+        .json();
+    assert!(listed.is_empty());
+    let listed: Vec<Value> = server
+        .get("/api/entities")
+        .add_header("Authorization", carol.clone())
+        .await
+        .json();
+    assert!(
+        listed.is_empty(),
+        "users and grants are listed: {:?}",
+        listed
+    );
+    server
+        .get(&format!("/api/entities/{}", grant_id))
+        .add_header("Authorization", carol.clone())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // Carol can't delete the grant to lock Bob out
+    server
+        .delete(&format!("/api/entities/{}", grant_id))
+        .add_header("Authorization", carol)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    assert_eq!(state.shares.reload(&state.memory_manager).await.unwrap(), 1);
+    server
+        .get(&format!("/api/memories/{}", id))
+        .add_header("Authorization", bob)
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_websocket_only_streams_readable_memories() {
+    let (server, state, _temp_dir) = create_test_server().await;
+    let alice = signup(&server, "alice").await;
+    let bob = signup(&server, "bob").await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = locai_server::create_router(state);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let connect = |path: &str, token: &str| {
+        let mut request = format!("ws://{}{}", address, path)
+            .into_client_request()
+            .unwrap();
+        request
+            .headers_mut()
+            .insert("Authorization", token.parse().unwrap());
+        connect_async(request)
+    };
+
+    // Replication reads every record, so it's for admins only
+    match connect("/api/replication/ws", &bob).await {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+            assert_eq!(response.status(), StatusCode::FORBIDDEN)
+        }
+        other => panic!("Expected a 403, got {:?}", other.map(|_| ())),
+    }
+
+    let (mut socket, _) = connect("/api/ws", &bob).await.unwrap();
+    let private = create_memory_as(&server, &alice, json!({ "content": "Alice's diary" })).await;
+    let own = create_memory_as(&server, &bob, json!({ "content": "Bob's diary" })).await;
+
+    // Bob's own memory is the first one he hears about
+    let created = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(Ok(Message::Text(text))) = socket.next().await {
+            if let Ok(WebSocketMessage::MemoryCreated { memory_id, .. }) =
+                serde_json::from_str(&text)
+            {
+                return memory_id;
+            }
+        }
+        panic!("WebSocket closed");
+    })
+    .await
+    .expect("no MemoryCreated event");
+    assert_ne!(created, private);
+    assert_eq!(created, own);
+}
@@ -160,6 +160,66 @@ async fn test_websocket_ping_pong() {
     }
 }
 
+#[tokio::test]
+async fn test_memory_writes_are_broadcast_through_outbox() {
+    let app_state = create_test_app_state().await;
+    let mut broadcasts = app_state.broadcast_tx.subscribe();
+    let server = TestServer::new(create_router(app_state.clone())).unwrap();
+
+    let response = server
+        .post("/api/memories")
+        .json(&serde_json::json!({ "content": "The gate is open" }))
+        .await;
+    let id = response.json::<serde_json::Value>()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    match broadcasts.try_recv().expect("no broadcast") {
+        WebSocketMessage::MemoryCreated { memory_id, .. } => assert_eq!(memory_id, id),
+        other => panic!("Expected MemoryCreated, got: {:?}", other),
+    }
+    assert!(
+        app_state
+            .memory_manager
+            .pending_outbox(10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    server.delete(&format!("/api/memories/{}", id)).await;
+    assert!(matches!(
+        broadcasts.try_recv().expect("no broadcast"),
+        WebSocketMessage::MemoryDeleted { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_outbox_relay_recovers_unsent_notifications() {
+    let app_state = create_test_app_state().await;
+    let mut broadcasts = app_state.broadcast_tx.subscribe();
+
+    // A write whose request died before relaying, e.g. in a crash
+    let memory = MemoryBuilder::new_with_content("Written before the crash").build();
+    let ws_message = WebSocketMessage::MemoryDeleted {
+        memory_id: memory.id.clone(),
+        node_id: None,
+    };
+    app_state
+        .memory_manager
+        .store_memory_with_outbox(memory, vec![AppState::outbox_message(&ws_message)])
+        .await
+        .unwrap();
+    assert!(broadcasts.try_recv().is_err());
+
+    app_state.relay_outbox().await;
+    assert!(matches!(
+        broadcasts.try_recv().expect("no broadcast"),
+        WebSocketMessage::MemoryDeleted { .. }
+    ));
+}
+
 async fn create_test_app_state() -> Arc<AppState> {
     let config = ConfigBuilder::new().with_memory_storage().build().unwrap();
 
@@ -0,0 +1,123 @@
+//! Search assertions
+//!
+//! Each assertion runs a search and panics with the results it got when they
+//! don't match, so a failing test shows what was found instead. Results are
+//! matched by a needle: a memory or entity ID, or text the memory's content
+//! or the entity's name contains.
+
+use locai::core::{SearchContent, SearchOptions, SearchResult};
+use locai::prelude::Locai;
+
+/// Whether `result` is the memory or entity `needle` describes
+pub fn result_matches(result: &SearchResult, needle: &str) -> bool {
+    if result.id == needle {
+        return true;
+    }
+    match &result.content {
+        SearchContent::Memory(memory) => memory.id == needle || memory.content.contains(needle),
+        SearchContent::Entity(entity) => {
+            entity.id == needle
+                || entity.properties["name"]
+                    .as_str()
+                    .is_some_and(|name| name.contains(needle))
+        }
+        _ => false,
+    }
+}
+
+/// Panic unless some result matches `needle`
+pub fn assert_results_contain(results: &[SearchResult], needle: &str) {
+    assert!(
+        results.iter().any(|result| result_matches(result, needle)),
+        "Expected a result matching '{}', got:\n{}",
+        needle,
+        describe(results)
+    );
+}
+
+/// Panic if any result matches `needle`
+pub fn assert_results_exclude(results: &[SearchResult], needle: &str) {
+    assert!(
+        !results.iter().any(|result| result_matches(result, needle)),
+        "Expected no result matching '{}', got:\n{}",
+        needle,
+        describe(results)
+    );
+}
+
+/// Panic unless the results matching `needles` come in that order, ahead of
+/// anything else
+pub fn assert_results_ranked(results: &[SearchResult], needles: &[&str]) {
+    for (rank, needle) in needles.iter().enumerate() {
+        assert!(
+            results
+                .get(rank)
+                .is_some_and(|result| result_matches(result, needle)),
+            "Expected '{}' at rank {}, got:\n{}",
+            needle,
+            rank + 1,
+            describe(results)
+        );
+    }
+}
+
+/// Search for `query` and panic unless a result matches `needle`
+pub async fn assert_search_contains(locai: &Locai, query: &str, needle: &str) -> Vec<SearchResult> {
+    let results = search(locai, query, SearchOptions::default()).await;
+    assert_results_contain(&results, needle);
+    results
+}
+
+/// Search for `query` and panic if a result matches `needle`
+pub async fn assert_search_excludes(locai: &Locai, query: &str, needle: &str) -> Vec<SearchResult> {
+    let results = search(locai, query, SearchOptions::default()).await;
+    assert_results_exclude(&results, needle);
+    results
+}
+
+/// Search for `query` and panic unless the top result matches `needle`
+pub async fn assert_search_top(locai: &Locai, query: &str, needle: &str) -> Vec<SearchResult> {
+    let results = search(locai, query, SearchOptions::default()).await;
+    assert_results_ranked(&results, &[needle]);
+    results
+}
+
+/// Search for `query` with `options` and panic unless the results matching
+/// `needles` lead, in that order
+pub async fn assert_search_ranked(
+    locai: &Locai,
+    query: &str,
+    options: SearchOptions,
+    needles: &[&str],
+) -> Vec<SearchResult> {
+    let results = search(locai, query, options).await;
+    assert_results_ranked(&results, needles);
+    results
+}
+
+async fn search(locai: &Locai, query: &str, options: SearchOptions) -> Vec<SearchResult> {
+    locai
+        .search_with_options(query, options)
+        .await
+        .unwrap_or_else(|e| panic!("Search for '{}' failed: {}", query, e))
+}
+
+fn describe(results: &[SearchResult]) -> String {
+    if results.is_empty() {
+        return "  (no results)".to_string();
+    }
+    results
+        .iter()
+        .enumerate()
+        .map(|(rank, result)| {
+            format!(
+                "  {}. [{}] {} (score {:.3})",
+                rank + 1,
+                result.id,
+                result.summary(),
+                result.score
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,73 @@
+//! Controllable time
+//!
+//! A [`TestClock`] implements Locai's [`Clock`], so a store built with it
+//! versions, archives, scores decay and applies retention by the clock's
+//! time. The clock moves only when the test moves it, so time-dependent tests
+//! don't depend on how fast they run. Its [`now`](TestClock::now) also feeds
+//! functions that take the time as an argument, such as
+//! [`ScoreCalculator::calculate_final_score_at`].
+//!
+//! [`ScoreCalculator::calculate_final_score_at`]: locai::search::ScoreCalculator::calculate_final_score_at
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use locai::clock::{Clock, ClockHandle};
+
+/// A clock that stands still until advanced
+///
+/// Clones share the same time, so a clone handed to a fixture moves with the
+/// original.
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl TestClock {
+    /// A clock stopped at `start`
+    pub fn at(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// The clock's current time
+    pub fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Move the clock forward (or back, for a negative duration)
+    pub fn advance(&self, by: Duration) -> DateTime<Utc> {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+        *now
+    }
+
+    /// Move the clock to `time`
+    pub fn set(&self, time: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = time;
+    }
+
+    /// The time `duration` before the clock's current time
+    pub fn ago(&self, duration: Duration) -> DateTime<Utc> {
+        self.now() - duration
+    }
+
+    /// A handle Locai's configuration can hold; it follows this clock
+    pub fn handle(&self) -> ClockHandle {
+        ClockHandle::new(self.clone())
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        TestClock::now(self)
+    }
+}
+
+impl Default for TestClock {
+    /// A clock stopped at the current time
+    fn default() -> Self {
+        Self::at(Utc::now())
+    }
+}
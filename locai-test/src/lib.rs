@@ -0,0 +1,38 @@
+//! Test support for applications built on Locai
+//!
+//! - [`TestStore`]: an isolated in-memory Locai populated with memories,
+//!   entities and relationships, each added under a key
+//! - [`TestClock`]: a clock that only moves when told to, for decay,
+//!   forgetting and versioning tests
+//! - Assertions such as [`assert_search_contains`] that run a search and
+//!   show what was found when they fail
+//!
+//! ```rust,no_run
+//! use chrono::Duration;
+//! use locai_test::{TestStore, assert_search_contains, assert_search_top};
+//!
+//! # async fn example() -> locai::Result<()> {
+//! let store = TestStore::builder()
+//!     .memory("pass", "The dragon guards the northern pass")
+//!     .memory_aged("rumor", "A dragon was seen in the south", Duration::days(90))
+//!     .entity("smaug", "Creature", "Smaug")
+//!     .relationship("pass", "smaug", "mentions")
+//!     .build()
+//!     .await?;
+//!
+//! assert_search_contains(store.locai(), "dragon", "northern pass").await;
+//! assert_search_top(store.locai(), "guards", store.id("pass")).await;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod assertions;
+pub mod clock;
+pub mod store;
+
+pub use assertions::{
+    assert_results_contain, assert_results_exclude, assert_results_ranked, assert_search_contains,
+    assert_search_excludes, assert_search_ranked, assert_search_top,
+};
+pub use clock::TestClock;
+pub use store::{TestStore, TestStoreBuilder};
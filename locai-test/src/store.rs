@@ -0,0 +1,242 @@
+//! Populated stores
+//!
+//! [`TestStore::builder`] describes the memories, entities and relationships
+//! a test starts from, each under a key, and builds an isolated in-memory
+//! Locai holding them. Keys stand in for the IDs storage assigns, so a test
+//! can refer to "the dragon memory" without tracking what it was stored as.
+
+use std::collections::HashMap;
+
+use chrono::Duration;
+use locai::models::{Memory, MemoryBuilder, MemoryPriority};
+use locai::prelude::{Locai, MemoryManager};
+use locai::search::ScoringConfig;
+use locai::storage::models::{Entity, Relationship};
+use locai::{LocaiError, Result};
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+use crate::clock::TestClock;
+
+/// An isolated Locai populated by a [`TestStoreBuilder`]
+///
+/// The store's data directory is removed when it's dropped.
+pub struct TestStore {
+    locai: Locai,
+    clock: TestClock,
+    ids: HashMap<String, String>,
+    _data_dir: TempDir,
+}
+
+impl TestStore {
+    /// Describe a store to build
+    pub fn builder() -> TestStoreBuilder {
+        TestStoreBuilder::default()
+    }
+
+    /// An empty store
+    pub async fn empty() -> Result<Self> {
+        Self::builder().build().await
+    }
+
+    pub fn locai(&self) -> &Locai {
+        &self.locai
+    }
+
+    pub fn manager(&self) -> &MemoryManager {
+        self.locai.manager()
+    }
+
+    /// The clock the store's memories were dated by and the store reads
+    pub fn clock(&self) -> &TestClock {
+        &self.clock
+    }
+
+    /// The stored ID of the memory or entity added under `key`
+    ///
+    /// # Panics
+    ///
+    /// Panics if nothing was added under `key`.
+    pub fn id(&self, key: &str) -> &str {
+        self.ids
+            .get(key)
+            .unwrap_or_else(|| panic!("No memory or entity was added under the key '{}'", key))
+    }
+
+    /// The memory added under `key`, as it's stored now
+    ///
+    /// # Panics
+    ///
+    /// Panics if nothing was added under `key` or the memory has since been
+    /// deleted.
+    pub async fn memory(&self, key: &str) -> Memory {
+        let id = self.id(key);
+        self.manager()
+            .get_memory(id)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to get memory '{}': {}", key, e))
+            .unwrap_or_else(|| panic!("Memory '{}' ({}) no longer exists", key, id))
+    }
+}
+
+/// Memories, entities and relationships for a [`TestStore`]
+#[derive(Default)]
+pub struct TestStoreBuilder {
+    clock: TestClock,
+    scoring_profiles: Vec<(String, ScoringConfig)>,
+    memories: Vec<(String, Memory)>,
+    entities: Vec<(String, String, Value)>,
+    relationships: Vec<(String, String, String)>,
+}
+
+impl TestStoreBuilder {
+    /// Date memories, and run the store's versioning, decay and retention,
+    /// by `clock` instead of one stopped at the current time
+    pub fn clock(mut self, clock: TestClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Register a scoring profile, as the `scoring_profiles` configuration would
+    pub fn scoring_profile(mut self, name: impl Into<String>, scoring: ScoringConfig) -> Self {
+        self.scoring_profiles.push((name.into(), scoring));
+        self
+    }
+
+    /// A fact memory created at the clock's current time
+    pub fn memory(self, key: impl Into<String>, content: impl Into<String>) -> Self {
+        self.memory_aged(key, content, Duration::zero())
+    }
+
+    /// A fact memory created `age` before the clock's current time
+    pub fn memory_aged(
+        self,
+        key: impl Into<String>,
+        content: impl Into<String>,
+        age: Duration,
+    ) -> Self {
+        let key = key.into();
+        let mut memory = MemoryBuilder::new(key.clone(), content.into()).build();
+        memory.created_at = self.clock.ago(age);
+        self.memory_from(key, memory)
+    }
+
+    /// A fact memory with the given priority, created at the clock's current time
+    pub fn memory_with_priority(
+        self,
+        key: impl Into<String>,
+        content: impl Into<String>,
+        priority: MemoryPriority,
+    ) -> Self {
+        let key = key.into();
+        let mut memory = MemoryBuilder::new(key.clone(), content.into())
+            .priority(priority)
+            .build();
+        memory.created_at = self.clock.now();
+        self.memory_from(key, memory)
+    }
+
+    /// A memory built by the caller, stored as is
+    pub fn memory_from(mut self, key: impl Into<String>, memory: Memory) -> Self {
+        self.memories.push((key.into(), memory));
+        self
+    }
+
+    /// An entity of `entity_type` with a `name` property
+    pub fn entity(
+        self,
+        key: impl Into<String>,
+        entity_type: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        let properties = json!({ "name": name.into() });
+        self.entity_with_properties(key, entity_type, properties)
+    }
+
+    /// An entity of `entity_type` with the given properties
+    pub fn entity_with_properties(
+        mut self,
+        key: impl Into<String>,
+        entity_type: impl Into<String>,
+        properties: Value,
+    ) -> Self {
+        self.entities
+            .push((key.into(), entity_type.into(), properties));
+        self
+    }
+
+    /// A relationship between two memories or entities, by key
+    pub fn relationship(
+        mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        relationship_type: impl Into<String>,
+    ) -> Self {
+        self.relationships
+            .push((from.into(), to.into(), relationship_type.into()));
+        self
+    }
+
+    /// Build an isolated in-memory Locai and store everything described
+    pub async fn build(self) -> Result<TestStore> {
+        let data_dir = TempDir::new()
+            .map_err(|e| LocaiError::Other(format!("Failed to create data directory: {}", e)))?;
+        let mut builder = Locai::builder()
+            .with_data_dir(data_dir.path())
+            .with_memory_storage()
+            .with_clock(self.clock.handle());
+        for (name, scoring) in self.scoring_profiles {
+            builder = builder.with_scoring_profile(name, scoring);
+        }
+        let locai = builder.build().await?;
+        let manager = locai.manager();
+
+        let mut ids = HashMap::new();
+        for (key, entity_type, properties) in self.entities {
+            let now = self.clock.now();
+            let entity = manager
+                .create_entity(Entity {
+                    id: key.clone(),
+                    entity_type,
+                    properties,
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await?;
+            ids.insert(key, entity.id);
+        }
+        for (key, memory) in self.memories {
+            let id = manager.store_memory(memory).await?;
+            ids.insert(key, id);
+        }
+        for (from, to, relationship_type) in self.relationships {
+            let key_id = |key: &str| {
+                ids.get(key).cloned().ok_or_else(|| {
+                    LocaiError::Other(format!(
+                        "Relationship refers to '{}', which wasn't added",
+                        key
+                    ))
+                })
+            };
+            let now = self.clock.now();
+            manager
+                .create_relationship_entity(Relationship {
+                    id: String::new(),
+                    relationship_type,
+                    source_id: key_id(&from)?,
+                    target_id: key_id(&to)?,
+                    properties: json!({}),
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await?;
+        }
+
+        Ok(TestStore {
+            locai,
+            clock: self.clock,
+            ids,
+            _data_dir: data_dir,
+        })
+    }
+}
@@ -0,0 +1,97 @@
+//! Tests for the test fixtures, clock and assertions
+
+use chrono::Duration;
+use locai::core::{SearchOptions, SearchTypeFilter};
+use locai::models::MemoryPriority;
+use locai::search::{DecayFunction, ScoreCalculator, ScoringConfig};
+use locai_test::{
+    TestClock, TestStore, assert_search_contains, assert_search_excludes, assert_search_ranked,
+};
+
+#[tokio::test]
+async fn test_store_holds_what_was_described() {
+    let store = TestStore::builder()
+        .memory("pass", "The dragon guards the northern pass")
+        .memory_with_priority(
+            "raid",
+            "The dragon raided the village",
+            MemoryPriority::High,
+        )
+        .entity("smaug", "Creature", "Smaug")
+        .relationship("pass", "smaug", "mentions")
+        .build()
+        .await
+        .unwrap();
+
+    assert_eq!(store.memory("raid").await.priority, MemoryPriority::High);
+    assert!(
+        store
+            .manager()
+            .get_entity(store.id("smaug"))
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    assert_search_contains(store.locai(), "dragon", "northern pass").await;
+    assert_search_contains(store.locai(), "dragon", store.id("raid")).await;
+    assert_search_excludes(store.locai(), "dragon", "unicorn").await;
+}
+
+#[tokio::test]
+async fn test_clock_dates_memories_and_drives_decay() {
+    let clock = TestClock::default();
+    let store = TestStore::builder()
+        .clock(clock.clone())
+        .memory("new", "Fresh dragon sighting")
+        .memory_aged("old", "Old dragon sighting", Duration::days(60))
+        .scoring_profile("recent-first", ScoringConfig::recency_focused())
+        .build()
+        .await
+        .unwrap();
+
+    let old = store.memory("old").await;
+    let age = clock.now() - old.created_at;
+    assert_eq!(age.num_days(), 60);
+
+    let options = SearchOptions {
+        include_types: SearchTypeFilter::memories_only(),
+        scoring_profile: Some("recent-first".to_string()),
+        ..Default::default()
+    };
+    assert_search_ranked(
+        store.locai(),
+        "sighting",
+        options,
+        &[store.id("new"), store.id("old")],
+    )
+    .await;
+
+    // The clock only moves when told to, so decay is exact
+    let calculator = ScoreCalculator::new(ScoringConfig {
+        bm25_weight: 0.0,
+        vector_weight: 0.0,
+        recency_boost: 1.0,
+        access_boost: 0.0,
+        priority_boost: 0.0,
+        decay_function: DecayFunction::Linear,
+        decay_rate: 0.01,
+    });
+    let new = store.memory("new").await;
+    let fresh = calculator.calculate_final_score_at(0.0, None, &new, clock.now());
+    clock.advance(Duration::hours(50));
+    let later = calculator.calculate_final_score_at(0.0, None, &new, clock.now());
+    assert!((fresh - 1.0).abs() < 0.001);
+    assert!((later - 0.5).abs() < 0.001);
+}
+
+#[tokio::test]
+#[should_panic(expected = "Expected a result matching 'unicorn'")]
+async fn test_failed_assertion_shows_results() {
+    let store = TestStore::builder()
+        .memory("pass", "The dragon guards the northern pass")
+        .build()
+        .await
+        .unwrap();
+    assert_search_contains(store.locai(), "dragon", "unicorn").await;
+}
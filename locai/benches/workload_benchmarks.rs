@@ -0,0 +1,123 @@
+//! Workload benchmarks at several corpus sizes
+//!
+//! Run with: cargo bench --bench workload_benchmarks
+//!
+//! For a JSON report comparable across machines, use `locai-cli bench` instead.
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use locai::bench::{SplitMix64, bench_manager, corpus, embedding, link_corpus, text_query};
+use locai::core::MemoryManager;
+use locai::memory::search_extensions::SearchMode;
+use tokio::runtime::Runtime;
+
+const CORPUS_SIZES: &[usize] = &[100, 1_000, 5_000];
+const SEED: u64 = 42;
+
+/// Build a store holding a linked synthetic corpus
+fn populated(rt: &Runtime, size: usize) -> (MemoryManager, Vec<String>) {
+    rt.block_on(async {
+        let manager = bench_manager().await.unwrap();
+        let mut ids = Vec::with_capacity(size);
+        for memory in corpus(size, SEED) {
+            ids.push(manager.store_memory(memory).await.unwrap());
+        }
+        link_corpus(&manager, &ids, SEED).await.unwrap();
+        (manager, ids)
+    })
+}
+
+fn bench_store(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("store");
+    group.sample_size(10);
+
+    for &batch in &[100usize, 1_000] {
+        group.throughput(Throughput::Elements(batch as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(batch), &batch, |b, &batch| {
+            b.to_async(&rt).iter_batched(
+                || corpus(batch, SEED),
+                |memories| async {
+                    let manager = bench_manager().await.unwrap();
+                    for memory in memories {
+                        manager.store_memory(memory).await.unwrap();
+                    }
+                },
+                criterion::BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_search_and_traversal(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+
+    for &size in CORPUS_SIZES {
+        let (manager, ids) = populated(&rt, size);
+        let mut group = c.benchmark_group(format!("corpus_{}", size));
+        group.sample_size(20);
+
+        let mut rng = SplitMix64::new(SEED);
+        group.bench_function("bm25_search", |b| {
+            b.to_async(&rt).iter(|| {
+                let query = text_query(&mut rng);
+                let manager = &manager;
+                async move {
+                    black_box(
+                        manager
+                            .search(&query, Some(10), None, SearchMode::Text)
+                            .await
+                            .unwrap(),
+                    )
+                }
+            });
+        });
+
+        let mut rng = SplitMix64::new(SEED);
+        group.bench_function("vector_search", |b| {
+            b.to_async(&rt).iter(|| {
+                let query = embedding(rng.next_u64());
+                let manager = &manager;
+                async move {
+                    black_box(
+                        manager
+                            .search_with_embedding(
+                                "",
+                                Some(&query),
+                                Some(10),
+                                None,
+                                SearchMode::Vector,
+                            )
+                            .await
+                            .unwrap(),
+                    )
+                }
+            });
+        });
+
+        let mut rng = SplitMix64::new(SEED);
+        group.bench_function("graph_traversal", |b| {
+            b.to_async(&rt).iter(|| {
+                let id = ids[rng.below(ids.len())].clone();
+                let manager = &manager;
+                async move { black_box(manager.get_memory_graph(&id, 3).await.unwrap()) }
+            });
+        });
+
+        let mut rng = SplitMix64::new(SEED);
+        group.bench_function("shortest_path", |b| {
+            b.to_async(&rt).iter(|| {
+                let from = ids[rng.below(ids.len())].clone();
+                let to = ids[rng.below(ids.len())].clone();
+                let manager = &manager;
+                async move { black_box(manager.find_shortest_path(&from, &to, 3).await.unwrap()) }
+            });
+        });
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_store, bench_search_and_traversal);
+criterion_main!(benches);
@@ -36,6 +36,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         database: "locai_shared".to_string(),
         lifecycle_tracking: Default::default(),
         versioning: Default::default(),
+        ..Default::default()
     };
 
     // Create a SurrealDB client with embedded RocksDB engine
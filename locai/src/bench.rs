@@ -0,0 +1,393 @@
+//! Workload benchmarks
+//!
+//! Measures how Locai behaves as the corpus grows: store throughput, BM25 and
+//! vector search latency, and graph traversal times. Each corpus size runs
+//! against a fresh in-memory store filled with synthetic memories, so reports
+//! from different machines or releases can be compared side by side.
+//!
+//! The same workload backs `locai-cli bench` and the criterion benches in
+//! `benches/workload_benchmarks.rs`.
+//!
+//! ```rust,no_run
+//! use locai::bench::{BenchConfig, run};
+//!
+//! # async fn example() -> locai::Result<()> {
+//! let report = run(&BenchConfig {
+//!     corpus_sizes: vec![1_000, 10_000],
+//!     ..BenchConfig::default()
+//! })
+//! .await?;
+//! println!("{}", serde_json::to_string_pretty(&report).unwrap());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::ConfigBuilder;
+use crate::core::MemoryManager;
+use crate::memory::search_extensions::SearchMode;
+use crate::models::{Memory, MemoryBuilder, MemoryType};
+use crate::{LocaiError, Result};
+
+/// Embedding dimension accepted by the SurrealDB vector index
+pub const EMBEDDING_DIMENSION: usize = 1024;
+
+/// Relationship type linking consecutive synthetic memories
+const CHAIN_RELATIONSHIP: &str = "follows";
+
+/// Relationship type linking a synthetic memory to an earlier random one
+const REFERENCE_RELATIONSHIP: &str = "related_to";
+
+/// Words synthetic memories and queries are drawn from
+const VOCABULARY: &[&str] = &[
+    "agent", "archive", "battle", "bridge", "castle", "council", "dragon", "forest", "harbor",
+    "journey", "kingdom", "lantern", "library", "market", "merchant", "mountain", "oracle",
+    "palace", "prophecy", "quest", "river", "scholar", "ship", "storm", "temple", "tower",
+    "treaty", "valley", "village", "voyage", "warden", "winter",
+];
+
+/// What to measure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchConfig {
+    /// Corpus sizes to measure, each against a fresh store
+    pub corpus_sizes: Vec<usize>,
+    /// Number of queries timed per search and traversal measurement
+    pub queries: usize,
+    /// Maximum number of results requested per search
+    pub search_limit: usize,
+    /// Depth used for graph traversal and path finding
+    pub traversal_depth: u8,
+    /// Seed for the synthetic corpus, so runs are repeatable
+    pub seed: u64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            corpus_sizes: vec![1_000, 10_000],
+            queries: 50,
+            search_limit: 10,
+            traversal_depth: 3,
+            seed: 42,
+        }
+    }
+}
+
+/// Latency distribution of a timed operation, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    /// Summarize a set of timings
+    pub fn from_durations(durations: &[Duration]) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+
+        let mut millis: Vec<f64> = durations.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(|a, b| a.total_cmp(b));
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * millis.len() as f64).ceil() as usize;
+            millis[rank.clamp(1, millis.len()) - 1]
+        };
+
+        Self {
+            samples: millis.len(),
+            mean_ms: millis.iter().sum::<f64>() / millis.len() as f64,
+            p50_ms: percentile(50.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            max_ms: millis[millis.len() - 1],
+        }
+    }
+}
+
+/// Write throughput while filling a corpus
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputStats {
+    pub operations: usize,
+    pub elapsed_ms: f64,
+    pub per_second: f64,
+}
+
+impl ThroughputStats {
+    fn new(operations: usize, elapsed: Duration) -> Self {
+        let seconds = elapsed.as_secs_f64();
+        Self {
+            operations,
+            elapsed_ms: seconds * 1000.0,
+            per_second: if seconds > 0.0 {
+                operations as f64 / seconds
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Measurements for one corpus size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorpusReport {
+    pub corpus_size: usize,
+    /// Storing the memories, embeddings included
+    pub store: ThroughputStats,
+    /// Creating the relationships the traversal measurements walk
+    pub link: ThroughputStats,
+    pub bm25_search: LatencyStats,
+    pub vector_search: LatencyStats,
+    /// Loading a memory with its neighborhood up to the traversal depth
+    pub graph_traversal: LatencyStats,
+    pub shortest_path: LatencyStats,
+}
+
+/// Full benchmark report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub locai_version: String,
+    pub storage: String,
+    pub started_at: DateTime<Utc>,
+    pub config: BenchConfig,
+    pub corpora: Vec<CorpusReport>,
+}
+
+/// Run the workload for every configured corpus size
+pub async fn run(config: &BenchConfig) -> Result<BenchReport> {
+    if config.corpus_sizes.contains(&0) {
+        return Err(LocaiError::Configuration(
+            "Benchmark corpus sizes must be greater than zero".to_string(),
+        ));
+    }
+
+    let started_at = Utc::now();
+    let mut corpora = Vec::with_capacity(config.corpus_sizes.len());
+    for &size in &config.corpus_sizes {
+        corpora.push(run_corpus(config, size).await?);
+    }
+
+    Ok(BenchReport {
+        locai_version: crate::VERSION.to_string(),
+        storage: "surrealdb-memory".to_string(),
+        started_at,
+        config: config.clone(),
+        corpora,
+    })
+}
+
+/// Measure a single corpus size against a fresh store
+pub async fn run_corpus(config: &BenchConfig, size: usize) -> Result<CorpusReport> {
+    let manager = bench_manager().await?;
+    let mut rng = SplitMix64::new(config.seed);
+
+    let memories = corpus(size, config.seed);
+    let started = Instant::now();
+    let mut ids = Vec::with_capacity(size);
+    for memory in memories {
+        ids.push(manager.store_memory(memory).await?);
+    }
+    let store = ThroughputStats::new(size, started.elapsed());
+
+    let started = Instant::now();
+    let links = link_corpus(&manager, &ids, config.seed).await?;
+    let link = ThroughputStats::new(links, started.elapsed());
+
+    let mut bm25 = Vec::with_capacity(config.queries);
+    for _ in 0..config.queries {
+        let query = text_query(&mut rng);
+        let started = Instant::now();
+        manager
+            .search(&query, Some(config.search_limit), None, SearchMode::Text)
+            .await?;
+        bm25.push(started.elapsed());
+    }
+
+    let mut vector = Vec::with_capacity(config.queries);
+    for _ in 0..config.queries {
+        let query = embedding(rng.next_u64());
+        let started = Instant::now();
+        manager
+            .search_with_embedding(
+                "",
+                Some(&query),
+                Some(config.search_limit),
+                None,
+                SearchMode::Vector,
+            )
+            .await?;
+        vector.push(started.elapsed());
+    }
+
+    let mut traversal = Vec::with_capacity(config.queries);
+    let mut paths = Vec::with_capacity(config.queries);
+    for _ in 0..config.queries {
+        let from = &ids[rng.below(ids.len())];
+        let started = Instant::now();
+        manager
+            .get_memory_graph(from, config.traversal_depth)
+            .await?;
+        traversal.push(started.elapsed());
+
+        let to = &ids[rng.below(ids.len())];
+        let started = Instant::now();
+        manager
+            .find_shortest_path(from, to, config.traversal_depth)
+            .await?;
+        paths.push(started.elapsed());
+    }
+
+    Ok(CorpusReport {
+        corpus_size: size,
+        store,
+        link,
+        bm25_search: LatencyStats::from_durations(&bm25),
+        vector_search: LatencyStats::from_durations(&vector),
+        graph_traversal: LatencyStats::from_durations(&traversal),
+        shortest_path: LatencyStats::from_durations(&paths),
+    })
+}
+
+/// Create an empty in-memory manager to benchmark against
+///
+/// Access tracking is off, so its periodic background flush neither competes
+/// with the measured writes nor skews their timings.
+pub async fn bench_manager() -> Result<MemoryManager> {
+    let mut config = ConfigBuilder::new().with_memory_storage().build()?;
+    config.lifecycle_tracking.enabled = false;
+    crate::init(config).await
+}
+
+/// Generate a deterministic synthetic corpus
+///
+/// Memories have a few sentences of vocabulary words, a tag and a normalized
+/// embedding, all derived from `seed`.
+pub fn corpus(size: usize, seed: u64) -> Vec<Memory> {
+    let mut rng = SplitMix64::new(seed);
+    (0..size)
+        .map(|n| {
+            let words: Vec<&str> = (0..24)
+                .map(|_| VOCABULARY[rng.below(VOCABULARY.len())])
+                .collect();
+            MemoryBuilder::new_with_content(format!("Record {}: {}.", n, words.join(" ")))
+                .memory_type(MemoryType::Fact)
+                .tag(VOCABULARY[n % VOCABULARY.len()])
+                .source("bench")
+                .embedding(embedding(rng.next_u64()))
+                .build()
+        })
+        .collect()
+}
+
+/// Link stored memories into a chain with extra references to earlier ones
+///
+/// The chain keeps every memory reachable so path queries have work to do.
+/// Returns the number of relationships created.
+pub async fn link_corpus(manager: &MemoryManager, ids: &[String], seed: u64) -> Result<usize> {
+    let mut rng = SplitMix64::new(seed.wrapping_add(1));
+    let mut created = 0;
+    for (n, id) in ids.iter().enumerate().skip(1) {
+        manager
+            .create_relationship(id, &ids[n - 1], CHAIN_RELATIONSHIP)
+            .await?;
+        created += 1;
+
+        let earlier = rng.below(n);
+        if earlier + 1 < n {
+            manager
+                .create_relationship(id, &ids[earlier], REFERENCE_RELATIONSHIP)
+                .await?;
+            created += 1;
+        }
+    }
+    Ok(created)
+}
+
+/// A two-word BM25 query over the corpus vocabulary
+pub fn text_query(rng: &mut SplitMix64) -> String {
+    format!(
+        "{} {}",
+        VOCABULARY[rng.below(VOCABULARY.len())],
+        VOCABULARY[rng.below(VOCABULARY.len())]
+    )
+}
+
+/// A deterministic unit-length embedding
+pub fn embedding(seed: u64) -> Vec<f32> {
+    let mut rng = SplitMix64::new(seed);
+    let raw: Vec<f32> = (0..EMBEDDING_DIMENSION)
+        .map(|_| (rng.next_u64() >> 40) as f32 / (1u64 << 24) as f32 - 0.5)
+        .collect();
+    let norm = raw
+        .iter()
+        .map(|x| x * x)
+        .sum::<f32>()
+        .sqrt()
+        .max(f32::EPSILON);
+    raw.into_iter().map(|x| x / norm).collect()
+}
+
+/// Small deterministic generator for synthetic data
+#[derive(Debug, Clone)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..bound`
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_percentiles() {
+        let durations: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_durations(&durations);
+        assert_eq!(stats.samples, 100);
+        assert!((stats.p50_ms - 50.0).abs() < 1e-6);
+        assert!((stats.p95_ms - 95.0).abs() < 1e-6);
+        assert!((stats.p99_ms - 99.0).abs() < 1e-6);
+        assert!((stats.max_ms - 100.0).abs() < 1e-6);
+        assert!((stats.mean_ms - 50.5).abs() < 1e-6);
+        assert_eq!(LatencyStats::from_durations(&[]), LatencyStats::default());
+    }
+
+    #[test]
+    fn test_corpus_is_deterministic() {
+        let first = corpus(5, 7);
+        let second = corpus(5, 7);
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.content, b.content);
+            assert_eq!(a.embedding, b.embedding);
+        }
+        assert_ne!(first[0].content, corpus(1, 8)[0].content);
+
+        let embedding = first[0].embedding.as_ref().unwrap();
+        assert_eq!(embedding.len(), EMBEDDING_DIMENSION);
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-3);
+    }
+}
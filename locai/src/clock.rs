@@ -0,0 +1,77 @@
+//! Time source
+//!
+//! Versioning, decay scoring, retention and analytics read the current time
+//! through a [`Clock`] instead of calling `Utc::now()` themselves, so tests
+//! and simulations can run them at any time they choose. The clock comes from
+//! [`LocaiConfig::clock`](crate::config::LocaiConfig::clock) and is the
+//! [`SystemClock`] unless replaced:
+//!
+//! ```rust
+//! use chrono::{DateTime, TimeZone, Utc};
+//! use locai::clock::{Clock, ClockHandle};
+//! use locai::config::ConfigBuilder;
+//!
+//! #[derive(Debug)]
+//! struct NewYear;
+//!
+//! impl Clock for NewYear {
+//!     fn now(&self) -> DateTime<Utc> {
+//!         Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()
+//!     }
+//! }
+//!
+//! let config = ConfigBuilder::new()
+//!     .with_memory_storage()
+//!     .with_clock(ClockHandle::new(NewYear))
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(config.clock.now().to_rfc3339(), "2030-01-01T00:00:00+00:00");
+//! ```
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time
+pub trait Clock: Send + Sync + Debug {
+    /// The current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system's wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A shared [`Clock`], cheap to clone; the [`SystemClock`] by default
+#[derive(Debug, Clone)]
+pub struct ClockHandle(Arc<dyn Clock>);
+
+impl ClockHandle {
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        Self(Arc::new(clock))
+    }
+
+    /// The current time by this clock
+    pub fn now(&self) -> DateTime<Utc> {
+        self.0.now()
+    }
+}
+
+impl From<Arc<dyn Clock>> for ClockHandle {
+    fn from(clock: Arc<dyn Clock>) -> Self {
+        Self(clock)
+    }
+}
+
+impl Default for ClockHandle {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
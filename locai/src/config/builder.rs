@@ -32,6 +32,17 @@ impl ConfigBuilder {
         self
     }
 
+    /// Store data with the backend registered as `name`, passing it `options`
+    pub fn with_storage_backend(
+        mut self,
+        name: impl Into<String>,
+        options: serde_json::Value,
+    ) -> Self {
+        self.config.storage.graph.backend = Some(name.into());
+        self.config.storage.graph.backend_options = options;
+        self
+    }
+
     /// Configure vector storage type
     pub fn with_vector_storage_type(mut self, storage_type: VectorStorageType) -> Self {
         self.config.storage.vector.storage_type = storage_type;
@@ -101,6 +112,12 @@ impl ConfigBuilder {
         self
     }
 
+    /// Scale embeddings to unit length before they're written.
+    pub fn with_normalized_embeddings(mut self) -> Self {
+        self.config.ml.embedding.normalize_on_write = true;
+        self
+    }
+
     /// Set the model cache directory.
     pub fn with_model_cache_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
         self.config.ml.model_cache_dir = path.as_ref().to_path_buf();
@@ -167,6 +184,170 @@ impl ConfigBuilder {
         self
     }
 
+    /// Add a retention policy for stored messages.
+    pub fn with_message_retention(mut self, policy: TopicRetentionPolicy) -> Self {
+        self.config.messaging.retention.push(policy);
+        self
+    }
+
+    /// Partition storage across several SurrealDB namespaces.
+    pub fn with_sharding(mut self, sharding: ShardingConfig) -> Self {
+        self.config.storage.sharding = sharding;
+        self
+    }
+
+    /// Limit what each tenant or agent can store.
+    pub fn with_quotas(mut self, quotas: QuotaConfig) -> Self {
+        self.config.quotas = quotas;
+        self
+    }
+
+    /// Simulate forgetting with the given curve.
+    pub fn with_forgetting(mut self, forgetting: ForgettingConfig) -> Self {
+        self.config.forgetting = forgetting;
+        self
+    }
+
+    /// Configure rolling conversation summaries.
+    pub fn with_sessions(mut self, sessions: SessionConfig) -> Self {
+        self.config.sessions = sessions;
+        self
+    }
+
+    /// Configure how token budgets are counted.
+    pub fn with_tokenizer(mut self, tokenizer: TokenizerConfig) -> Self {
+        self.config.tokenizer = tokenizer;
+        self
+    }
+
+    /// Configure the search result cache.
+    pub fn with_search_cache(mut self, search_cache: SearchCacheConfig) -> Self {
+        self.config.search_cache = search_cache;
+        self
+    }
+
+    /// Configure the stages memories pass through on their way to storage.
+    pub fn with_pipeline(mut self, pipeline: PipelineConfig) -> Self {
+        self.config.pipeline = pipeline;
+        self
+    }
+
+    /// Enable plugins registered with the
+    /// [`PluginRegistry`](crate::plugins::PluginRegistry).
+    pub fn with_plugins(mut self, plugins: PluginsConfig) -> Self {
+        self.config.plugins = plugins;
+        self
+    }
+
+    /// Configure automation rules.
+    pub fn with_rules(mut self, rules: RulesConfig) -> Self {
+        self.config.rules = rules;
+        self
+    }
+
+    /// Configure the review queue.
+    pub fn with_review(mut self, review: ReviewConfig) -> Self {
+        self.config.review = review;
+        self
+    }
+
+    /// Configure where notifications go.
+    pub fn with_notifications(mut self, notifications: NotificationsConfig) -> Self {
+        self.config.notifications = notifications;
+        self
+    }
+
+    /// Configure index warmup on startup.
+    pub fn with_warmup(mut self, warmup: WarmupConfig) -> Self {
+        self.config.warmup = warmup;
+        self
+    }
+
+    /// Configure what happens when the embedded database is locked by
+    /// another process.
+    pub fn with_locked_storage_policy(mut self, policy: LockedStoragePolicy) -> Self {
+        self.config.storage.on_locked = policy;
+        self
+    }
+
+    /// Index memory properties so filters on them avoid full scans.
+    pub fn with_indexed_properties<I, S>(mut self, properties: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.storage.indexed_properties = properties.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Register a named scoring profile searches can select.
+    pub fn with_scoring_profile(
+        mut self,
+        name: impl Into<String>,
+        scoring: crate::search::ScoringConfig,
+    ) -> Self {
+        self.config.scoring_profiles.insert(name.into(), scoring);
+        self
+    }
+
+    /// Read the current time from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: crate::clock::ClockHandle) -> Self {
+        self.config.clock = clock;
+        self
+    }
+
+    /// Choose IDs for new memories and extracted entities by `strategy`.
+    pub fn with_id_strategy(mut self, strategy: crate::ids::IdStrategy) -> Self {
+        self.config.id_strategy = strategy;
+        self
+    }
+
+    /// Use the locai-server at `url` instead of local storage (see
+    /// [`crate::connect`]).
+    pub fn with_remote(mut self, url: impl Into<String>) -> Self {
+        self.config.remote = Some(RemoteConfig {
+            url: url.into(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Sign in to the remote server with these credentials.
+    pub fn with_remote_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        let remote = self.config.remote.get_or_insert_with(Default::default);
+        remote.username = Some(username.into());
+        remote.password = Some(password.into());
+        self
+    }
+
+    /// Compare memory embeddings as `space` describes.
+    pub fn with_memory_vector_space(mut self, space: VectorSpaceConfig) -> Self {
+        self.config.storage.vector.memories = space;
+        self
+    }
+
+    /// Compare vector store vectors as `space` describes.
+    pub fn with_vector_store_space(mut self, space: VectorSpaceConfig) -> Self {
+        self.config.storage.vector.vectors = space;
+        self
+    }
+
+    /// Configure automatic pruning of old versions.
+    pub fn with_version_retention(mut self, retention: VersionRetentionConfig) -> Self {
+        self.config.versioning.retention = retention;
+        self
+    }
+
+    /// Configure the tokio runtimes Locai creates.
+    pub fn with_runtime(mut self, runtime: RuntimeConfig) -> Self {
+        self.config.runtime = runtime;
+        self
+    }
+
     /// Create a configuration for development with in-memory databases.
     ///
     /// This creates a configuration suitable for development with:
@@ -98,12 +98,19 @@ impl ConfigLoader {
     }
 
     /// Extract and validate the configuration.
+    ///
+    /// Automations in `rules.automations_file` are added to the others.
     pub fn extract(&self) -> Result<LocaiConfig> {
-        let config: LocaiConfig = self
+        let mut config: LocaiConfig = self
             .figment
             .extract()
             .map_err(|e| ConfigError::ParseError(e.to_string()))?;
 
+        if let Some(path) = &config.rules.automations_file {
+            let automations = load_automations(path)?;
+            config.rules.automations.extend(automations);
+        }
+
         // Validate the configuration
         validation::validate_config(&config)?;
 
@@ -111,6 +118,30 @@ impl ConfigLoader {
     }
 }
 
+/// Read the `automations` list of an automations file.
+fn load_automations(path: &Path) -> Result<Vec<AutomationRule>> {
+    if !path.exists() {
+        return Err(ConfigError::FileLoadError(format!(
+            "Automations file not found: {}",
+            path.display()
+        )));
+    }
+
+    let file = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => Figment::from(Toml::file(path)),
+        Some("yaml") | Some("yml") => Figment::from(Yaml::file(path)),
+        Some("json") => Figment::from(Json::file(path)),
+        _ => {
+            return Err(ConfigError::FileLoadError(format!(
+                "Unsupported file format: {}",
+                path.display()
+            )));
+        }
+    };
+    file.extract_inner("automations")
+        .map_err(|e| ConfigError::ParseError(format!("{}: {}", path.display(), e)))
+}
+
 impl Default for ConfigLoader {
     fn default() -> Self {
         Self::new()
@@ -3,7 +3,9 @@
 //! This module contains the configuration structures for all Locai components.
 
 use crate::storage::config::SurrealDBConfig;
+use crate::storage::models::DistanceMetric;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -29,6 +31,74 @@ pub struct LocaiConfig {
 
     /// Memory versioning configuration
     pub versioning: VersioningConfig,
+
+    /// Memory archival (cold storage) configuration
+    pub archive: ArchiveConfig,
+
+    /// Messaging configuration
+    pub messaging: MessagingConfig,
+
+    /// Tokio runtime tuning
+    pub runtime: RuntimeConfig,
+
+    /// Storage quotas per tenant or agent
+    pub quotas: QuotaConfig,
+
+    /// Forgetting curve simulation
+    pub forgetting: ForgettingConfig,
+
+    /// Rolling conversation summaries
+    pub sessions: SessionConfig,
+
+    /// How token budgets are counted
+    pub tokenizer: TokenizerConfig,
+
+    /// Caching of search results
+    pub search_cache: SearchCacheConfig,
+
+    /// Index warmup on startup
+    pub warmup: WarmupConfig,
+
+    /// How long idempotency keys on writes are remembered
+    pub idempotency: IdempotencyConfig,
+
+    /// The stages a memory passes through on its way to storage (see
+    /// [`crate::memory::pipeline`])
+    pub pipeline: PipelineConfig,
+
+    /// Extensions from other crates to enable by name (see
+    /// [`crate::plugins`])
+    pub plugins: PluginsConfig,
+
+    /// Automation rules run on memory events: declarative automations (see
+    /// [`crate::hooks::automation`]), and Rhai scripts with the `rhai-rules`
+    /// feature
+    pub rules: RulesConfig,
+
+    /// Memories from designated sources wait for approval before they're
+    /// stored (see [`crate::memory::review`])
+    pub review: ReviewConfig,
+
+    /// Where notifications about anomalies, review queue items and
+    /// reminders go (see [`crate::notifications`])
+    pub notifications: NotificationsConfig,
+
+    /// Named scoring profiles searches can select, in addition to the
+    /// built-in ones (see [`crate::search::profiles`])
+    pub scoring_profiles: HashMap<String, crate::search::ScoringConfig>,
+
+    /// Time source for versioning, decay, retention and analytics; the
+    /// system clock unless replaced (see [`crate::clock`])
+    #[serde(skip)]
+    pub clock: crate::clock::ClockHandle,
+
+    /// How IDs are chosen for new memories and extracted entities; random
+    /// UUIDs unless set (see [`crate::ids`])
+    pub id_strategy: crate::ids::IdStrategy,
+
+    /// A locai-server to use instead of local storage; when set,
+    /// [`crate::connect`] returns a client for it
+    pub remote: Option<RemoteConfig>,
 }
 
 /// Configuration for automatic memory lifecycle tracking.
@@ -113,6 +183,17 @@ pub struct StorageConfig {
 
     /// Vector storage configuration
     pub vector: VectorStorageConfig,
+
+    /// Partitioning across several SurrealDB namespaces
+    pub sharding: ShardingConfig,
+
+    /// What to do when the embedded database is locked by another process
+    pub on_locked: LockedStoragePolicy,
+
+    /// Memory properties to index, such as `properties.project_id` (the
+    /// `properties.` prefix is optional). Filters on an indexed property
+    /// look records up through the index instead of scanning every memory.
+    pub indexed_properties: Vec<String>,
 }
 
 impl Default for StorageConfig {
@@ -125,10 +206,48 @@ impl Default for StorageConfig {
             data_dir,
             graph: GraphStorageConfig::default(),
             vector: VectorStorageConfig::default(),
+            sharding: ShardingConfig::default(),
+            on_locked: LockedStoragePolicy::default(),
+            indexed_properties: Vec::new(),
         }
     }
 }
 
+impl StorageConfig {
+    /// Names of the indexed properties, without the `properties.` prefix
+    pub fn indexed_property_names(&self) -> Vec<String> {
+        self.indexed_properties
+            .iter()
+            .map(|property| {
+                property
+                    .strip_prefix("properties.")
+                    .unwrap_or(property)
+                    .to_string()
+            })
+            .collect()
+    }
+}
+
+/// What to do when the embedded RocksDB database is locked by another
+/// process.
+///
+/// Both fallbacks work on a private copy of the database taken at startup,
+/// so they see the data as it was then; see [`crate::storage::degraded`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockedStoragePolicy {
+    /// Fail startup with `LocaiError::StorageNotAccessible`
+    #[default]
+    Fail,
+
+    /// Serve reads from the copy and reject writes
+    ReadOnly,
+
+    /// Serve reads and writes from an in-memory copy, queueing the writes to
+    /// replay into the database the next time it opens
+    Overlay,
+}
+
 /// Graph storage configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -141,6 +260,14 @@ pub struct GraphStorageConfig {
 
     /// SurrealDB-specific configuration
     pub surrealdb: SurrealDBConfig,
+
+    /// Name of a backend registered with
+    /// [`register_backend`](crate::storage::backend::register_backend) to use
+    /// in place of SurrealDB
+    pub backend: Option<String>,
+
+    /// Settings passed to the registered backend, in its own format
+    pub backend_options: serde_json::Value,
 }
 
 impl Default for GraphStorageConfig {
@@ -149,6 +276,8 @@ impl Default for GraphStorageConfig {
             storage_type: GraphStorageType::SurrealDB,
             path: PathBuf::from("graph"),
             surrealdb: SurrealDBConfig::default(),
+            backend: None,
+            backend_options: serde_json::Value::Null,
         }
     }
 }
@@ -162,6 +291,12 @@ pub struct VectorStorageConfig {
 
     /// Path to store vector data (relative to data_dir)
     pub path: PathBuf,
+
+    /// How memory embeddings are compared
+    pub memories: VectorSpaceConfig,
+
+    /// How vectors in the vector store are compared
+    pub vectors: VectorSpaceConfig,
 }
 
 impl Default for VectorStorageConfig {
@@ -169,10 +304,70 @@ impl Default for VectorStorageConfig {
         Self {
             storage_type: VectorStorageType::SurrealDB,
             path: PathBuf::from("vectors"),
+            memories: VectorSpaceConfig::default(),
+            vectors: VectorSpaceConfig::default(),
         }
     }
 }
 
+/// Similarity metric and normalization for one vector space.
+///
+/// Providers that return unnormalized vectors rank badly by dot product;
+/// `normalize` scales the space's vectors to unit length, which makes dot
+/// product rank like cosine.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct VectorSpaceConfig {
+    /// Metric search ranks by
+    pub metric: DistanceMetric,
+
+    /// Scale vectors to unit length when they're written, and query vectors
+    /// when searched. `ml.embedding.normalize_on_write` also turns this on
+    /// for memory embeddings.
+    pub normalize: bool,
+}
+
+/// Sharding configuration.
+///
+/// With more than one shard, each shard gets its own namespace
+/// (`<namespace>_shard<N>`) and embedded RocksDB shards their own directory
+/// (`<connection>/shard-<N>`). Shards are told apart by position, so the
+/// shard count can't change once data is written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShardingConfig {
+    /// Number of shards; 1 disables sharding
+    pub shards: usize,
+
+    /// How memories are assigned to shards
+    pub key: ShardKey,
+
+    /// Memory and entity property naming the tenant, for [`ShardKey::Tenant`]
+    pub tenant_property: String,
+}
+
+impl Default for ShardingConfig {
+    fn default() -> Self {
+        Self {
+            shards: 1,
+            key: ShardKey::Hash,
+            tenant_property: "tenant".to_string(),
+        }
+    }
+}
+
+/// How memories are assigned to shards.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ShardKey {
+    /// Spread memories evenly
+    Hash,
+
+    /// By tenant property, keeping each tenant on one shard. Memories without
+    /// the property go to the first shard.
+    Tenant,
+}
+
 /// Graph storage type.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -231,6 +426,11 @@ pub struct EmbeddingConfig {
 
     /// Remote service URL (if using remote)
     pub service_url: Option<String>,
+
+    /// Scale embeddings to unit length before they're written; otherwise
+    /// they're stored as given. Embeddings the vector index can't search are
+    /// rejected either way.
+    pub normalize_on_write: bool,
 }
 
 impl Default for EmbeddingConfig {
@@ -240,6 +440,7 @@ impl Default for EmbeddingConfig {
             model_name: "text-embedding-3-small".to_string(),
             service_type: EmbeddingServiceType::Remote,
             service_url: Some("https://api.openai.com/v1".to_string()),
+            normalize_on_write: false,
         }
     }
 }
@@ -411,6 +612,15 @@ pub struct VersioningConfig {
 
     /// Maximum versions per memory (None = unlimited)
     pub max_versions_per_memory: Option<usize>,
+
+    /// Automatic pruning of old versions
+    pub retention: VersionRetentionConfig,
+
+    /// Hash-chain new versions so their history can be verified
+    pub hash_chain: bool,
+
+    /// Key for signing the hash chain with HMAC-SHA256 (None = plain SHA-256)
+    pub hash_chain_key: Option<String>,
 }
 
 impl Default for VersioningConfig {
@@ -431,10 +641,871 @@ impl Default for VersioningConfig {
             enable_compression: true,
             compression_threshold_days: 30,
             max_versions_per_memory: None,
+            retention: VersionRetentionConfig::default(),
+            hash_chain: false,
+            hash_chain_key: None,
+        }
+    }
+}
+
+/// Configuration for pruning old versions in the background.
+///
+/// Applies to memory versions and to entity and relationship versions, per
+/// record. The newest `keep_last` versions are always kept. Of the rest,
+/// versions older than `max_age_days` are removed, and versions older than
+/// `daily_after_days` are thinned to the newest one per (UTC) day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersionRetentionConfig {
+    /// Whether to prune versions automatically in the background
+    pub auto_compact: bool,
+
+    /// Number of most recent versions of each record always kept
+    pub keep_last: usize,
+
+    /// Keep one version per day for versions older than this many days
+    pub daily_after_days: Option<u64>,
+
+    /// Remove versions older than this many days
+    pub max_age_days: Option<u64>,
+
+    /// Time interval (in seconds) between automatic retention runs
+    pub check_interval_secs: u64,
+}
+
+impl Default for VersionRetentionConfig {
+    fn default() -> Self {
+        Self {
+            auto_compact: false,
+            keep_last: 10,
+            daily_after_days: Some(7),
+            max_age_days: None,
+            check_interval_secs: 3600,
         }
     }
 }
 
+/// Configuration for archiving inactive memories to cold storage.
+///
+/// Archived memories are compressed and moved out of the search indexes. They
+/// can still be fetched, listed, searched with `include_archived`, and restored
+/// with `unarchive`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    /// Whether to archive inactive memories automatically in the background
+    pub auto_archive: bool,
+
+    /// Archive memories not accessed (or created, if never accessed) for this many days
+    pub archive_after_days: u64,
+
+    /// Time interval (in seconds) between automatic archive runs
+    pub check_interval_secs: u64,
+
+    /// Maximum number of memories archived per run
+    pub batch_size: usize,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            auto_archive: false,
+            archive_after_days: 90,
+            check_interval_secs: 3600,
+            batch_size: 500,
+        }
+    }
+}
+
+/// Configuration for the messaging system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessagingConfig {
+    /// Per-topic retention policies; a message is governed by the first
+    /// policy whose topic pattern matches, and kept forever if none does
+    pub retention: Vec<TopicRetentionPolicy>,
+
+    /// Time interval (in seconds) between background retention runs; 0
+    /// disables the background task
+    pub retention_interval_secs: u64,
+}
+
+impl Default for MessagingConfig {
+    fn default() -> Self {
+        Self {
+            retention: Vec::new(),
+            retention_interval_secs: 300,
+        }
+    }
+}
+
+/// Retention rules for the messages stored on matching topics.
+///
+/// Rules combine: compaction runs first, then messages older than
+/// `max_age_secs` are removed, then all but the newest `max_count`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TopicRetentionPolicy {
+    /// Full topic pattern, e.g. `app:game.chat.*` or `*.telemetry`
+    pub topic: String,
+
+    /// Delete messages older than this many seconds
+    pub max_age_secs: Option<u64>,
+
+    /// Keep at most this many messages across all matching topics
+    pub max_count: Option<usize>,
+
+    /// Keep only the newest message for each value of this header; messages
+    /// without the header are not compacted
+    pub compact_by_header: Option<String>,
+}
+
+/// Tuning for the tokio runtimes Locai creates.
+///
+/// Applications that already run a tuned runtime can leave this at its
+/// defaults and initialize Locai on their own runtime instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// Async worker threads; `None` uses one per CPU core
+    pub worker_threads: Option<usize>,
+
+    /// Upper bound on the blocking thread pool; `None` uses tokio's default of 512
+    pub max_blocking_threads: Option<usize>,
+
+    /// Seconds an idle blocking thread is kept before exiting; `None` uses
+    /// tokio's default of 10
+    pub blocking_keep_alive_secs: Option<u64>,
+
+    /// Stack size for runtime threads, in bytes
+    pub thread_stack_size: usize,
+
+    /// Prefix for runtime thread names
+    pub thread_name: String,
+
+    /// Worker threads for a separate runtime that runs the storage engine;
+    /// `None` runs storage on the runtime that initializes Locai
+    pub storage_io_threads: Option<usize>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads: None,
+            max_blocking_threads: None,
+            blocking_keep_alive_secs: None,
+            // SurrealDB recommends 10MiB stacks for embedded use
+            thread_stack_size: 10 * 1024 * 1024,
+            thread_name: "locai".to_string(),
+            storage_io_threads: None,
+        }
+    }
+}
+
+/// Storage quotas per tenant or agent.
+///
+/// A memory counts against the owner named by its `owner_property`
+/// property, or against its `source` when it has none. Unset limits are
+/// unlimited, and without any limits nothing is enforced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// Memory property naming the owner
+    pub owner_property: String,
+
+    /// Limits for owners without an entry in `owners`
+    pub default_limits: QuotaLimits,
+
+    /// Limits for specific owners
+    pub owners: HashMap<String, QuotaLimits>,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            owner_property: "tenant".to_string(),
+            default_limits: QuotaLimits::default(),
+            owners: HashMap::new(),
+        }
+    }
+}
+
+impl QuotaConfig {
+    /// Limits that apply to `owner`
+    pub fn limits_for(&self, owner: &str) -> &QuotaLimits {
+        self.owners.get(owner).unwrap_or(&self.default_limits)
+    }
+
+    /// Whether any limit is set
+    pub fn is_enforced(&self) -> bool {
+        !self.default_limits.is_unlimited()
+            || self.owners.values().any(|limits| !limits.is_unlimited())
+    }
+}
+
+/// Limits for one quota owner.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaLimits {
+    /// Maximum number of memories
+    pub max_memories: Option<u64>,
+
+    /// Maximum bytes of content, tags, properties and embeddings
+    pub max_bytes: Option<u64>,
+
+    /// Maximum number of memories with an embedding
+    pub max_vectors: Option<u64>,
+}
+
+impl QuotaLimits {
+    /// Whether no limit is set
+    pub fn is_unlimited(&self) -> bool {
+        self.max_memories.is_none() && self.max_bytes.is_none() && self.max_vectors.is_none()
+    }
+}
+
+/// Configuration for simulated forgetting.
+///
+/// Models an Ebbinghaus forgetting curve: a memory's retrievability is
+/// `exp(-t / S)`, where `t` is the time since it was last accessed (or
+/// created) and `S` its stability. Each access multiplies the stability by
+/// `reinforcement`, so memories recalled often fade more slowly. When enabled,
+/// search scores are scaled by retrievability, and memories that fall below
+/// `archive_threshold` are archived by `archive_forgotten_memories`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForgettingConfig {
+    /// Whether forgetting affects search ranking and archival
+    pub enabled: bool,
+
+    /// Days until an unreinforced memory's retrievability falls to 1/e (about 37%)
+    pub stability_days: f64,
+
+    /// Stability in days for specific memory types, overriding `stability_days`;
+    /// 0 exempts a type from forgetting
+    pub type_stability_days: HashMap<String, f64>,
+
+    /// Factor each access multiplies a memory's stability by
+    pub reinforcement: f64,
+
+    /// Retrievability below which a memory counts as forgotten
+    pub archive_threshold: f64,
+}
+
+impl Default for ForgettingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stability_days: 7.0,
+            type_stability_days: HashMap::new(),
+            reinforcement: 2.0,
+            archive_threshold: 0.05,
+        }
+    }
+}
+
+impl ForgettingConfig {
+    /// Base stability in days for a memory type; `None` if the type is exempt
+    pub fn stability_for(&self, memory_type: &str) -> Option<f64> {
+        let days = self
+            .type_stability_days
+            .get(memory_type)
+            .copied()
+            .unwrap_or(self.stability_days);
+        (days > 0.0).then_some(days)
+    }
+}
+
+/// Configuration for rolling conversation summaries.
+///
+/// Once a session has `keep_recent + summarize_every` turns not yet
+/// summarized, or its turns not yet summarized come to more than
+/// `max_pending_tokens`, all but the newest `keep_recent` are folded into the
+/// session's summary by the configured summarizer and demoted. Built contexts
+/// then give the summary and the recent turns instead of the whole
+/// conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Turns folded into the summary at a time; 0 turns automatic summaries off
+    pub summarize_every: usize,
+
+    /// Newest turns always kept out of the summary
+    pub keep_recent: usize,
+
+    /// Tokens the turns not yet summarized may take before they are
+    /// summarized, whatever their number; 0 for no limit
+    pub max_pending_tokens: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            summarize_every: 20,
+            keep_recent: 10,
+            max_pending_tokens: 0,
+        }
+    }
+}
+
+/// Configuration for counting tokens.
+///
+/// Context budgets, text chunking and session summary triggers all count
+/// tokens the same way: with the BPE vocabulary in `bpe_file` when one is
+/// set, otherwise by estimating from the number of characters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenizerConfig {
+    /// Vocabulary in tiktoken's format, such as `cl100k_base.tiktoken`
+    pub bpe_file: Option<PathBuf>,
+
+    /// Characters per token when estimating
+    pub chars_per_token: usize,
+}
+
+impl Default for TokenizerConfig {
+    fn default() -> Self {
+        Self {
+            bpe_file: None,
+            chars_per_token: 4,
+        }
+    }
+}
+
+/// Configuration for the search result cache.
+///
+/// When enabled, results of `MemoryManager::search` are kept by a hash of the
+/// query and its options, so an identical search is answered without going to
+/// storage. Memory changes seen through live queries drop the entries they
+/// could affect; a store without live queries gets no cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchCacheConfig {
+    /// Whether search results are cached
+    pub enabled: bool,
+
+    /// Most searches kept; the least recently used are dropped first
+    pub capacity: usize,
+
+    /// Seconds a result is kept even if nothing changes; 0 for no limit
+    pub ttl_secs: u64,
+}
+
+impl Default for SearchCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: 1000,
+            ttl_secs: 300,
+        }
+    }
+}
+
+/// Configuration for warming indexes on startup.
+///
+/// When enabled, the server runs BM25 searches for `queries` and for the
+/// terms most common in recent memories, then vector searches with recent
+/// memories' embeddings, before it reports ready. The postings and vectors
+/// those searches touch are loaded, so the first real queries after a restart
+/// don't pay for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarmupConfig {
+    /// Whether indexes are warmed before the server reports ready
+    pub enabled: bool,
+
+    /// Queries always warmed, such as the ones agents send most
+    pub queries: Vec<String>,
+
+    /// Recent memories sampled for common terms and embeddings
+    pub sample_size: usize,
+
+    /// Most text searches run, counting `queries`
+    pub max_queries: usize,
+
+    /// Most vector searches run
+    pub vector_probes: usize,
+
+    /// Seconds before warmup gives up and the server reports ready anyway;
+    /// 0 for no limit
+    pub timeout_secs: u64,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            queries: Vec::new(),
+            sample_size: 200,
+            max_queries: 50,
+            vector_probes: 10,
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// Configuration for idempotency keys on writes.
+///
+/// A write submitted with an idempotency key records its result under the
+/// key; submitting the key again within `retention_secs` returns that result
+/// instead of writing again, so a retried request can't store duplicates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdempotencyConfig {
+    /// Seconds a key's result is kept; a key seen again after that is a new
+    /// write
+    pub retention_secs: u64,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            retention_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+/// Store pipeline configuration.
+///
+/// Every memory stored passes through `stages` in order before it is
+/// written. Remove a stage to skip it, or list the names of middlewares
+/// added with
+/// [`MemoryManager::with_store_middleware`](crate::core::MemoryManager::with_store_middleware)
+/// to place them; middlewares not listed run at `hooks`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    /// Stage names in the order they run
+    pub stages: Vec<String>,
+
+    /// What the `redact` stage removes
+    pub redaction: RedactionConfig,
+
+    /// How the `chunk` stage splits long memories
+    pub chunking: ChunkingConfig,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            stages: crate::memory::pipeline::DEFAULT_STAGES
+                .iter()
+                .map(|stage| stage.to_string())
+                .collect(),
+            redaction: RedactionConfig::default(),
+            chunking: ChunkingConfig::default(),
+        }
+    }
+}
+
+/// Personal data the `redact` stage replaces before a memory is stored.
+///
+/// Nothing is redacted until `kinds` or `patterns` name something.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Built-in kinds of personal data to find
+    pub kinds: Vec<PiiKind>,
+
+    /// Further regular expressions whose matches are redacted
+    pub patterns: Vec<String>,
+
+    /// Text each match is replaced with
+    pub replacement: String,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            kinds: Vec::new(),
+            patterns: Vec::new(),
+            replacement: "[REDACTED]".to_string(),
+        }
+    }
+}
+
+/// Kinds of personal data the `redact` stage recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    /// Email addresses
+    Email,
+    /// Phone numbers, optionally with a country code
+    Phone,
+    /// Payment card numbers of 13 to 16 digits
+    CreditCard,
+    /// US social security numbers written `123-45-6789`
+    Ssn,
+    /// IPv4 addresses
+    IpAddress,
+}
+
+/// How the `chunk` stage splits long memories.
+///
+/// A memory longer than `max_tokens` is stored whole, with its chunks
+/// stored beside it as memories of their own. Each chunk records the memory
+/// it came from, so searches can match a passage of a long memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkingConfig {
+    /// Longest content stored without chunks; `None` never chunks
+    pub max_tokens: Option<usize>,
+
+    /// Tokens each chunk repeats from the end of the one before
+    pub overlap: usize,
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens: None,
+            overlap: 32,
+        }
+    }
+}
+
+/// Plugins to enable, by the names they were registered under.
+///
+/// Each must be registered with the
+/// [`PluginRegistry`](crate::plugins::PluginRegistry) before
+/// [`crate::init`] runs, or startup fails.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginsConfig {
+    /// Memory hooks, run on every memory event
+    pub hooks: Vec<PluginSpec>,
+
+    /// Entity extractors, run by the `extract` stage alongside the built-in
+    /// ones while entity extraction is enabled
+    pub extractors: Vec<PluginSpec>,
+
+    /// Store pipeline stages, placed by name in `pipeline.stages`
+    pub store_middlewares: Vec<PluginSpec>,
+
+    /// Reranker for searches that request `rerank_top_k`
+    pub reranker: Option<PluginSpec>,
+
+    /// Provider that embeds memories and queries
+    pub embedding_provider: Option<PluginSpec>,
+}
+
+/// A registered plugin and the options it is created with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginSpec {
+    /// Name the plugin was registered under
+    pub name: String,
+
+    /// Settings passed to the plugin's factory as-is
+    pub options: serde_json::Value,
+}
+
+impl PluginSpec {
+    /// Enable plugin `name` with no options
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            options: serde_json::Value::Null,
+        }
+    }
+
+    /// Create the plugin with `options`
+    pub fn with_options(mut self, options: serde_json::Value) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// Automation rule configuration.
+///
+/// Rules are small Rhai scripts stored with the memories and run when a
+/// memory event they listen for happens. A script that runs past
+/// `max_operations` is stopped and its rule skipped for that event.
+///
+/// `automations` are rules written as configuration instead, for when a
+/// script is more than a rule needs:
+///
+/// ```yaml
+/// rules:
+///   automations:
+///     - name: escalate-urgent
+///       trigger:
+///         event: memory.created
+///         tags: [urgent]
+///       actions:
+///         - set_priority: high
+///         - webhook: { url: "https://hooks.example.com/urgent" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RulesConfig {
+    /// Whether rules run on memory events: scripts on the server (with the
+    /// `rhai-rules` feature), automations wherever [`crate::init`] runs
+    pub enabled: bool,
+
+    /// Most operations a script may run per event
+    pub max_operations: u64,
+
+    /// Seconds rules are cached before they're read from storage again;
+    /// rules changed through this instance apply at once
+    pub refresh_secs: u64,
+
+    /// Rules written as configuration, run in order
+    pub automations: Vec<AutomationRule>,
+
+    /// File with more automations under an `automations` key (TOML, YAML
+    /// or JSON); [`ConfigLoader`](super::ConfigLoader) adds them after
+    /// those listed here
+    pub automations_file: Option<PathBuf>,
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_operations: 100_000,
+            refresh_secs: 30,
+            automations: Vec::new(),
+            automations_file: None,
+        }
+    }
+}
+
+/// A rule written as configuration: when its trigger matches a memory
+/// event, its actions run in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationRule {
+    /// Name the rule is logged and reported under
+    pub name: String,
+
+    /// The event and the memories it applies to
+    pub trigger: AutomationTrigger,
+
+    /// What to do, in order
+    pub actions: Vec<AutomationAction>,
+}
+
+/// The memory event an automation runs on and the memories it matches.
+///
+/// Every condition given must hold; a trigger with none matches every
+/// memory the event happens to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationTrigger {
+    /// The event, such as `memory.created` or `memory.reminder`
+    pub event: crate::memory::RuleEvent,
+
+    /// Tags the memory must all have
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Memory type the memory must have, such as `fact` or `custom:ticket`
+    #[serde(default)]
+    pub memory_type: Option<String>,
+
+    /// Text the memory's content must contain, ignoring case
+    #[serde(default)]
+    pub content_contains: Option<String>,
+
+    /// Source the memory must come from
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Something an automation does to the memory that matched.
+///
+/// Changes to the memory are skipped on `memory.deleted`, and actions that
+/// leave it as it is don't save it, so automations on `memory.updated`
+/// don't run again on their own changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationAction {
+    /// Set the memory's priority: low, normal, high or critical
+    SetPriority(String),
+
+    /// Add a tag to the memory
+    AddTag(String),
+
+    /// Remove a tag from the memory
+    RemoveTag(String),
+
+    /// Relate the memory to another memory
+    CreateRelationship {
+        /// ID of the memory to relate it to
+        target: String,
+
+        /// Type of the relationship, such as `part_of`
+        relationship_type: String,
+    },
+
+    /// POST the event to a URL, as [`Webhook`](crate::hooks::Webhook) does
+    Webhook {
+        url: String,
+
+        /// Extra request headers
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+/// Review queue configuration.
+///
+/// With `enabled`, memories stored from one of `sources` wait in the review
+/// queue until approved, unless an `auto_approve` rule matches them:
+///
+/// ```yaml
+/// review:
+///   enabled: true
+///   sources: ["agent:*"]
+///   auto_approve:
+///     - name: scratch-notes
+///       tags: [scratch]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewConfig {
+    /// Whether memories from `sources` wait for review
+    pub enabled: bool,
+
+    /// Sources whose memories wait for review; a trailing `*` matches any
+    /// source starting with what comes before it
+    pub sources: Vec<String>,
+
+    /// Memories any of these match are stored without review
+    pub auto_approve: Vec<AutoApproveRule>,
+}
+
+/// Memories stored without review despite their source.
+///
+/// Every condition given must hold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoApproveRule {
+    /// Name the rule is logged under
+    pub name: String,
+
+    /// Tags the memory must all have
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Memory type the memory must have, such as `fact` or `custom:ticket`
+    #[serde(default)]
+    pub memory_type: Option<String>,
+
+    /// Source the memory must come from
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// Most characters the memory's content may have
+    #[serde(default)]
+    pub max_length: Option<usize>,
+}
+
+/// Notification configuration.
+///
+/// Targets are where notifications can go; rules pick which notifications
+/// go to which targets. A notification no rule matches isn't sent.
+///
+/// ```yaml
+/// notifications:
+///   targets:
+///     - name: ops
+///       slack: { webhook_url: "https://hooks.slack.com/services/T000/B000/XXXX" }
+///     - name: pager
+///       http: { url: "https://pager.example.com/events" }
+///   rules:
+///     - name: review-to-ops
+///       events: [review, anomaly]
+///       targets: [ops]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Whether notifications are sent
+    pub enabled: bool,
+
+    /// Where notifications can go, by name
+    pub targets: Vec<NotificationTarget>,
+
+    /// Which notifications go where
+    pub rules: Vec<NotificationRule>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            targets: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// A place notifications can be sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationTarget {
+    /// Name rules send to the target by
+    pub name: String,
+
+    /// How notifications reach the target
+    #[serde(flatten)]
+    pub channel: NotificationChannel,
+}
+
+/// How notifications reach a target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// A Slack incoming webhook, posted a message per notification
+    Slack { webhook_url: String },
+
+    /// Any HTTP endpoint, POSTed each notification as JSON
+    Http {
+        url: String,
+
+        /// Extra request headers
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+/// Which notifications go to which targets.
+///
+/// Every condition given must hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationRule {
+    /// Name the rule is managed by
+    pub name: String,
+
+    /// Kinds of notification the rule sends
+    pub events: Vec<crate::notifications::NotificationKind>,
+
+    /// Names of the targets to send them to
+    pub targets: Vec<String>,
+
+    /// Tags the notification's memory must all have
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Connection to a locai-server, for [`crate::connect`].
+///
+/// With `username` and `password`, the client signs in before its first
+/// request and again when its token expires; a `token` is used as given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteConfig {
+    /// Server base URL, such as `http://localhost:3000`
+    pub url: String,
+
+    /// Bearer token to send with requests
+    pub token: Option<String>,
+
+    /// User to sign in as
+    pub username: Option<String>,
+
+    /// Password to sign in with
+    pub password: Option<String>,
+}
+
 /// Cache strategy for version reconstruction.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -124,4 +124,237 @@ mod tests {
             deserialized.ml.embedding.model_name
         );
     }
+
+    #[test]
+    fn test_message_retention_validation() {
+        let config = ConfigBuilder::new()
+            .with_message_retention(crate::config::TopicRetentionPolicy {
+                topic: "app:game.chat.*".to_string(),
+                max_count: Some(100),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(config.messaging.retention.len(), 1);
+
+        let result = ConfigBuilder::new()
+            .with_message_retention(crate::config::TopicRetentionPolicy {
+                topic: "app:game.chat.*".to_string(),
+                max_count: Some(0),
+                ..Default::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_version_retention_validation() {
+        let config = ConfigBuilder::new()
+            .with_version_retention(crate::config::VersionRetentionConfig {
+                auto_compact: true,
+                keep_last: 3,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(config.versioning.retention.keep_last, 3);
+
+        let result = ConfigBuilder::new()
+            .with_version_retention(crate::config::VersionRetentionConfig {
+                keep_last: 0,
+                ..Default::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_runtime_validation() {
+        let config = ConfigBuilder::new()
+            .with_runtime(crate::config::RuntimeConfig {
+                worker_threads: Some(2),
+                storage_io_threads: Some(1),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(config.runtime.storage_io_threads, Some(1));
+
+        let result = ConfigBuilder::new()
+            .with_runtime(crate::config::RuntimeConfig {
+                max_blocking_threads: Some(0),
+                ..Default::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_sharding_validation() {
+        let config = ConfigBuilder::new()
+            .with_sharding(crate::config::ShardingConfig {
+                shards: 4,
+                key: crate::config::ShardKey::Tenant,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(config.storage.sharding.shards, 4);
+        assert_eq!(config.storage.sharding.tenant_property, "tenant");
+
+        let result = ConfigBuilder::new()
+            .with_sharding(crate::config::ShardingConfig {
+                shards: 0,
+                ..Default::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_quota_limits() {
+        let mut quotas = crate::config::QuotaConfig::default();
+        assert!(!quotas.is_enforced());
+
+        quotas.owners.insert(
+            "noisy-agent".to_string(),
+            crate::config::QuotaLimits {
+                max_memories: Some(100),
+                ..Default::default()
+            },
+        );
+        assert!(quotas.is_enforced());
+        assert_eq!(quotas.limits_for("noisy-agent").max_memories, Some(100));
+        assert!(quotas.limits_for("someone-else").is_unlimited());
+
+        let config = ConfigBuilder::new().with_quotas(quotas).build().unwrap();
+        assert_eq!(config.quotas.owner_property, "tenant");
+
+        let result = ConfigBuilder::new()
+            .with_quotas(crate::config::QuotaConfig {
+                owner_property: String::new(),
+                ..Default::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_forgetting_config() {
+        let mut forgetting = crate::config::ForgettingConfig::default();
+        assert!(!forgetting.enabled);
+        forgetting
+            .type_stability_days
+            .insert("identity".to_string(), 0.0);
+        assert_eq!(forgetting.stability_for("fact"), Some(7.0));
+        assert_eq!(forgetting.stability_for("identity"), None);
+
+        let config = ConfigBuilder::new()
+            .with_forgetting(forgetting.clone())
+            .build()
+            .unwrap();
+        assert_eq!(config.forgetting.type_stability_days.len(), 1);
+
+        let result = ConfigBuilder::new()
+            .with_forgetting(crate::config::ForgettingConfig {
+                reinforcement: 0.5,
+                ..forgetting
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_tokenizer_config() {
+        let config = ConfigBuilder::new().build().unwrap();
+        assert_eq!(config.tokenizer.chars_per_token, 4);
+        assert!(config.tokenizer.bpe_file.is_none());
+
+        let result = ConfigBuilder::new()
+            .with_tokenizer(crate::config::TokenizerConfig {
+                chars_per_token: 0,
+                ..Default::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_warmup_config() {
+        let config = ConfigBuilder::new().build().unwrap();
+        assert!(!config.warmup.enabled);
+        assert_eq!(config.warmup.max_queries, 50);
+
+        let result = ConfigBuilder::new()
+            .with_warmup(crate::config::WarmupConfig {
+                enabled: true,
+                max_queries: 0,
+                vector_probes: 0,
+                ..Default::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_locked_storage_policy() {
+        let config = ConfigBuilder::new().build().unwrap();
+        assert_eq!(
+            config.storage.on_locked,
+            crate::config::LockedStoragePolicy::Fail
+        );
+
+        let config = ConfigBuilder::new()
+            .with_locked_storage_policy(crate::config::LockedStoragePolicy::ReadOnly)
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(&config.storage).unwrap();
+        assert_eq!(json["on_locked"], "read_only");
+    }
+
+    #[test]
+    fn test_indexed_properties() {
+        let config = ConfigBuilder::new()
+            .with_indexed_properties(["properties.project_id", "tenant"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.storage.indexed_property_names(),
+            vec!["project_id".to_string(), "tenant".to_string()]
+        );
+
+        let result = ConfigBuilder::new()
+            .with_indexed_properties(["project id"])
+            .build();
+        assert!(result.is_err());
+        let result = ConfigBuilder::new()
+            .with_indexed_properties(["properties."])
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_scoring_profiles() {
+        let precision = crate::search::ScoringConfig {
+            vector_weight: 0.0,
+            recency_boost: 0.0,
+            ..Default::default()
+        };
+        let config = ConfigBuilder::new()
+            .with_scoring_profile("precision", precision.clone())
+            .build()
+            .unwrap();
+        assert_eq!(config.scoring_profiles.get("precision"), Some(&precision));
+
+        let result = ConfigBuilder::new()
+            .with_scoring_profile(
+                "slow",
+                crate::search::ScoringConfig {
+                    decay_rate: -1.0,
+                    ..Default::default()
+                },
+            )
+            .build();
+        assert!(result.is_err());
+    }
 }
@@ -13,6 +13,302 @@ pub fn validate_config(config: &LocaiConfig) -> Result<(), ConfigError> {
     // Validate ML configuration
     validate_ml_config(&config.ml)?;
 
+    // Validate messaging configuration
+    validate_messaging_config(&config.messaging)?;
+
+    // Validate runtime configuration
+    validate_runtime_config(&config.runtime)?;
+
+    // Validate version retention configuration
+    if config.versioning.retention.keep_last == 0 {
+        return Err(ConfigError::ValidationError(
+            "Version retention must keep at least the latest version".to_string(),
+        ));
+    }
+    if config.versioning.retention.auto_compact
+        && config.versioning.retention.check_interval_secs == 0
+    {
+        return Err(ConfigError::ValidationError(
+            "Version retention check interval must be at least 1 second".to_string(),
+        ));
+    }
+
+    // Validate quota configuration
+    if config.quotas.owner_property.is_empty() {
+        return Err(ConfigError::ValidationError(
+            "Quota owner property cannot be empty".to_string(),
+        ));
+    }
+
+    // Validate forgetting configuration
+    validate_forgetting_config(&config.forgetting)?;
+
+    // Validate tokenizer configuration
+    if config.tokenizer.chars_per_token == 0 {
+        return Err(ConfigError::ValidationError(
+            "Tokenizer chars_per_token must be at least 1".to_string(),
+        ));
+    }
+
+    // Validate search cache configuration
+    if config.search_cache.enabled && config.search_cache.capacity == 0 {
+        return Err(ConfigError::ValidationError(
+            "Search cache capacity must be at least 1".to_string(),
+        ));
+    }
+
+    // Validate warmup configuration
+    if config.warmup.enabled && config.warmup.max_queries == 0 && config.warmup.vector_probes == 0 {
+        return Err(ConfigError::ValidationError(
+            "Warmup must run at least one text or vector search".to_string(),
+        ));
+    }
+
+    // Validate pipeline configuration
+    validate_pipeline_config(&config.pipeline)?;
+
+    // Validate plugin configuration
+    validate_plugins_config(&config.plugins)?;
+
+    // Validate rule configuration
+    validate_rules_config(&config.rules)?;
+
+    // Validate review configuration
+    validate_review_config(&config.review)?;
+
+    // Validate notification configuration
+    validate_notifications_config(&config.notifications)?;
+
+    // Validate scoring profiles
+    for (name, scoring) in &config.scoring_profiles {
+        crate::search::profiles::validate_scoring_profile(name, scoring)
+            .map_err(|e| ConfigError::ValidationError(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+/// Validate messaging configuration.
+fn validate_messaging_config(config: &MessagingConfig) -> Result<(), ConfigError> {
+    for policy in &config.retention {
+        if policy.topic.trim().is_empty() {
+            return Err(ConfigError::ValidationError(
+                "Message retention topic pattern cannot be empty".to_string(),
+            ));
+        }
+        if policy.max_count == Some(0) {
+            return Err(ConfigError::ValidationError(format!(
+                "Message retention max_count for '{}' must be at least 1",
+                policy.topic
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate forgetting configuration.
+fn validate_forgetting_config(config: &ForgettingConfig) -> Result<(), ConfigError> {
+    if config.stability_days <= 0.0 {
+        return Err(ConfigError::ValidationError(
+            "Forgetting stability_days must be positive".to_string(),
+        ));
+    }
+    if let Some((memory_type, _)) = config
+        .type_stability_days
+        .iter()
+        .find(|(_, days)| **days < 0.0)
+    {
+        return Err(ConfigError::ValidationError(format!(
+            "Forgetting stability for '{}' cannot be negative",
+            memory_type
+        )));
+    }
+    if config.reinforcement < 1.0 {
+        return Err(ConfigError::ValidationError(
+            "Forgetting reinforcement must be at least 1".to_string(),
+        ));
+    }
+    if !(0.0..1.0).contains(&config.archive_threshold) {
+        return Err(ConfigError::ValidationError(
+            "Forgetting archive_threshold must be between 0 and 1".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate pipeline configuration.
+fn validate_pipeline_config(config: &PipelineConfig) -> Result<(), ConfigError> {
+    if let Some(stage) = config.stages.iter().find(|stage| stage.trim().is_empty()) {
+        return Err(ConfigError::ValidationError(format!(
+            "Pipeline stage name cannot be empty: {:?}",
+            stage
+        )));
+    }
+    for pattern in &config.redaction.patterns {
+        if let Err(e) = regex::Regex::new(pattern) {
+            return Err(ConfigError::ValidationError(format!(
+                "Redaction pattern '{}' is invalid: {}",
+                pattern, e
+            )));
+        }
+    }
+    if config.chunking.max_tokens == Some(0) {
+        return Err(ConfigError::ValidationError(
+            "Chunking max_tokens must be at least 1".to_string(),
+        ));
+    }
+    if let Some(max_tokens) = config.chunking.max_tokens
+        && config.chunking.overlap >= max_tokens
+    {
+        return Err(ConfigError::ValidationError(
+            "Chunking overlap must be less than max_tokens".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate plugin configuration.
+fn validate_plugins_config(config: &PluginsConfig) -> Result<(), ConfigError> {
+    let specs = config
+        .hooks
+        .iter()
+        .chain(&config.extractors)
+        .chain(&config.store_middlewares)
+        .chain(&config.reranker)
+        .chain(&config.embedding_provider);
+    for spec in specs {
+        if spec.name.trim().is_empty() {
+            return Err(ConfigError::ValidationError(
+                "Plugin name cannot be empty".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate rule configuration.
+fn validate_rules_config(config: &RulesConfig) -> Result<(), ConfigError> {
+    if config.max_operations == 0 {
+        return Err(ConfigError::ValidationError(
+            "Rules max_operations must be at least 1".to_string(),
+        ));
+    }
+
+    for automation in &config.automations {
+        let invalid = |reason: &str| {
+            ConfigError::ValidationError(format!("Automation '{}' {}", automation.name, reason))
+        };
+        if automation.name.trim().is_empty() {
+            return Err(ConfigError::ValidationError(
+                "Automation name cannot be empty".to_string(),
+            ));
+        }
+        if automation.actions.is_empty() {
+            return Err(invalid("has no actions"));
+        }
+        for action in &automation.actions {
+            match action {
+                AutomationAction::SetPriority(priority) => {
+                    crate::hooks::automation::parse_priority(priority).map_err(|e| invalid(&e))?;
+                }
+                AutomationAction::AddTag(tag) | AutomationAction::RemoveTag(tag)
+                    if tag.trim().is_empty() =>
+                {
+                    return Err(invalid("has an empty tag"));
+                }
+                AutomationAction::CreateRelationship {
+                    target,
+                    relationship_type,
+                } if target.trim().is_empty() || relationship_type.trim().is_empty() => {
+                    return Err(invalid("needs a target and relationship_type"));
+                }
+                AutomationAction::Webhook { url, .. }
+                    if !url.starts_with("http://") && !url.starts_with("https://") =>
+                {
+                    return Err(invalid("needs an http(s) webhook url"));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate review configuration.
+fn validate_review_config(config: &ReviewConfig) -> Result<(), ConfigError> {
+    if config.enabled && config.sources.is_empty() {
+        return Err(ConfigError::ValidationError(
+            "Review is enabled but lists no sources".to_string(),
+        ));
+    }
+    if config.sources.iter().any(|source| source.trim().is_empty()) {
+        return Err(ConfigError::ValidationError(
+            "Review sources cannot be empty".to_string(),
+        ));
+    }
+    if config
+        .auto_approve
+        .iter()
+        .any(|rule| rule.name.trim().is_empty())
+    {
+        return Err(ConfigError::ValidationError(
+            "Auto-approve rule name cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate notification configuration.
+fn validate_notifications_config(config: &NotificationsConfig) -> Result<(), ConfigError> {
+    let mut names = std::collections::HashSet::new();
+    for target in &config.targets {
+        crate::notifications::check_target(target)
+            .map_err(|e| ConfigError::ValidationError(e.to_string()))?;
+        if !names.insert(target.name.as_str()) {
+            return Err(ConfigError::ValidationError(format!(
+                "Notification target '{}' is defined more than once",
+                target.name
+            )));
+        }
+    }
+
+    let mut rules = std::collections::HashSet::new();
+    for rule in &config.rules {
+        crate::notifications::check_rule(rule, |name| names.contains(name))
+            .map_err(|e| ConfigError::ValidationError(e.to_string()))?;
+        if !rules.insert(rule.name.as_str()) {
+            return Err(ConfigError::ValidationError(format!(
+                "Notification rule '{}' is defined more than once",
+                rule.name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate runtime configuration.
+fn validate_runtime_config(config: &RuntimeConfig) -> Result<(), ConfigError> {
+    let thread_counts = [
+        ("worker_threads", config.worker_threads),
+        ("max_blocking_threads", config.max_blocking_threads),
+        ("storage_io_threads", config.storage_io_threads),
+    ];
+    for (name, count) in thread_counts {
+        if count == Some(0) {
+            return Err(ConfigError::ValidationError(format!(
+                "Runtime {} must be at least 1",
+                name
+            )));
+        }
+    }
+
     Ok(())
 }
 
@@ -42,6 +338,44 @@ fn validate_storage_config(config: &StorageConfig) -> Result<(), ConfigError> {
         }
     }
 
+    if config.sharding.shards == 0 {
+        return Err(ConfigError::ValidationError(
+            "Storage needs at least one shard".to_string(),
+        ));
+    }
+
+    if let Some(backend) = &config.graph.backend {
+        if backend.trim().is_empty() {
+            return Err(ConfigError::ValidationError(
+                "Storage backend name cannot be empty".to_string(),
+            ));
+        }
+        if config.sharding.shards > 1 {
+            return Err(ConfigError::ValidationError(format!(
+                "Storage backend '{}' can't be sharded; shard inside the backend instead",
+                backend
+            )));
+        }
+    }
+    if config.sharding.key == ShardKey::Tenant && config.sharding.tenant_property.is_empty() {
+        return Err(ConfigError::ValidationError(
+            "Tenant sharding needs a tenant property".to_string(),
+        ));
+    }
+    for property in config.indexed_property_names() {
+        // Names become part of the index definition, so only plain identifiers are allowed
+        if property.is_empty()
+            || !property
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(ConfigError::ValidationError(format!(
+                "Indexed property '{}' must be a name of letters, digits and underscores",
+                property
+            )));
+        }
+    }
+
     // Validate vector storage configuration
     match config.vector.storage_type {
         VectorStorageType::SurrealDB => {
@@ -0,0 +1,119 @@
+//! The memory operations shared by local and remote deployments
+//!
+//! [`MemoryApi`] is implemented by the embedded [`MemoryManager`] and, with
+//! the `remote` feature, by [`RemoteMemoryManager`](super::remote::RemoteMemoryManager)
+//! against a locai-server. Code written against `dyn MemoryApi` runs on
+//! either; [`crate::connect`] picks one from the configuration.
+
+use async_trait::async_trait;
+
+use super::MemoryManager;
+use crate::Result;
+use crate::memory::search_extensions::SearchMode;
+use crate::models::Memory;
+use crate::storage::filters::{MemoryFilter, SemanticSearchFilter, SortOrder};
+use crate::storage::models::SearchResult;
+
+/// Core memory operations, wherever the memories are stored
+#[async_trait]
+pub trait MemoryApi: Send + Sync {
+    /// Store a memory, returning its ID
+    async fn store_memory(&self, memory: Memory) -> Result<String>;
+
+    /// The memory with this ID, if there is one
+    async fn get_memory(&self, id: &str) -> Result<Option<Memory>>;
+
+    /// Replace a stored memory; false if there was none with its ID
+    async fn update_memory(&self, memory: Memory) -> Result<bool>;
+
+    /// Delete a memory; false if there was none with this ID
+    async fn delete_memory(&self, id: &str) -> Result<bool>;
+
+    /// Search memories by text, vector similarity or both
+    async fn search(
+        &self,
+        query_text: &str,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchResult>>;
+
+    /// Memories matching a filter
+    async fn filter_memories(
+        &self,
+        filter: MemoryFilter,
+        sort_by: Option<&str>,
+        sort_order: Option<SortOrder>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Memory>>;
+
+    /// Number of memories, matching a filter if given
+    async fn count_memories(&self, filter: Option<MemoryFilter>) -> Result<usize>;
+
+    /// Add a tag to a memory; false if there was no memory with this ID
+    async fn tag_memory(&self, memory_id: &str, tag: &str) -> Result<bool>;
+
+    /// Relate a memory to another memory or an entity
+    async fn create_relationship(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: &str,
+    ) -> Result<bool>;
+}
+
+#[async_trait]
+impl MemoryApi for MemoryManager {
+    async fn store_memory(&self, memory: Memory) -> Result<String> {
+        MemoryManager::store_memory(self, memory).await
+    }
+
+    async fn get_memory(&self, id: &str) -> Result<Option<Memory>> {
+        MemoryManager::get_memory(self, id).await
+    }
+
+    async fn update_memory(&self, memory: Memory) -> Result<bool> {
+        MemoryManager::update_memory(self, memory).await
+    }
+
+    async fn delete_memory(&self, id: &str) -> Result<bool> {
+        MemoryManager::delete_memory(self, id).await
+    }
+
+    async fn search(
+        &self,
+        query_text: &str,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchResult>> {
+        MemoryManager::search(self, query_text, limit, filter, search_mode).await
+    }
+
+    async fn filter_memories(
+        &self,
+        filter: MemoryFilter,
+        sort_by: Option<&str>,
+        sort_order: Option<SortOrder>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        MemoryManager::filter_memories(self, filter, sort_by, sort_order, limit).await
+    }
+
+    async fn count_memories(&self, filter: Option<MemoryFilter>) -> Result<usize> {
+        MemoryManager::count_memories(self, filter).await
+    }
+
+    async fn tag_memory(&self, memory_id: &str, tag: &str) -> Result<bool> {
+        MemoryManager::tag_memory(self, memory_id, tag).await
+    }
+
+    async fn create_relationship(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: &str,
+    ) -> Result<bool> {
+        MemoryManager::create_relationship(self, source_id, target_id, relationship_type).await
+    }
+}
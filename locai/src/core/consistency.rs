@@ -0,0 +1,256 @@
+//! Consistency checks across the memory, vector, graph and version indexes
+//!
+//! Records in one index can outlive the records they point to, for example
+//! when a delete is interrupted half way. These checks find such leftovers
+//! and repair them.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::core::MemoryManager;
+use crate::storage::models::{IntegrityIssueType, VersionIntegrityIssue};
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// A relationship whose source or target no longer exists
+#[derive(Debug, Clone, Serialize)]
+pub struct DanglingRelationship {
+    /// Relationship ID
+    pub relationship_id: String,
+
+    /// Relationship type
+    pub relationship_type: String,
+
+    /// The endpoint IDs that could not be found
+    pub missing_endpoints: Vec<String>,
+}
+
+/// Inconsistencies found by [`check_consistency`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsistencyReport {
+    /// Vectors whose source memory no longer exists
+    pub orphaned_vectors: Vec<String>,
+
+    /// Relationships with a missing source or target
+    pub dangling_relationships: Vec<DanglingRelationship>,
+
+    /// Delta versions whose parent version is missing. Empty when the
+    /// storage backend doesn't support memory versioning.
+    pub broken_delta_chains: Vec<VersionIntegrityIssue>,
+}
+
+impl ConsistencyReport {
+    /// Total number of issues found
+    pub fn issue_count(&self) -> usize {
+        self.orphaned_vectors.len()
+            + self.dangling_relationships.len()
+            + self.broken_delta_chains.len()
+    }
+
+    /// Whether no issues were found
+    pub fn is_consistent(&self) -> bool {
+        self.issue_count() == 0
+    }
+}
+
+/// What [`repair_consistency`] changed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsistencyRepairReport {
+    /// Orphaned vectors deleted
+    pub vectors_deleted: usize,
+
+    /// Dangling relationships deleted
+    pub relationships_deleted: usize,
+
+    /// Delta versions promoted to full copies
+    pub versions_repaired: usize,
+
+    /// Issues that could not be repaired
+    pub failed: usize,
+
+    /// One line per repair attempt
+    pub details: Vec<String>,
+}
+
+/// Scan the storage indexes for records that point at missing records
+pub async fn check_consistency(manager: &MemoryManager) -> Result<ConsistencyReport> {
+    let storage = manager.storage().as_ref();
+    let mut report = ConsistencyReport::default();
+    let mut memories = HashMap::new();
+
+    let vectors = storage
+        .list_vectors(None, None, None)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to list vectors: {}", e)))?;
+    for vector in vectors {
+        if let Some(source_id) = &vector.source_id
+            && !memory_exists(storage, &mut memories, source_id).await?
+        {
+            report.orphaned_vectors.push(vector.id);
+        }
+    }
+
+    let relationships = storage
+        .list_relationships(None, None, None)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to list relationships: {}", e)))?;
+    let relationship_ids: HashSet<&str> = relationships.iter().map(|r| r.id.as_str()).collect();
+    let mut entities = HashMap::new();
+    for relationship in &relationships {
+        let mut missing_endpoints = Vec::new();
+        if !node_exists(
+            storage,
+            &mut memories,
+            &mut entities,
+            &relationship.source_id,
+        )
+        .await?
+        {
+            missing_endpoints.push(relationship.source_id.clone());
+        }
+        // "references" relationships point at another relationship
+        let target_exists = if relationship.relationship_type == "references" {
+            relationship_ids.contains(relationship.target_id.as_str())
+        } else {
+            node_exists(
+                storage,
+                &mut memories,
+                &mut entities,
+                &relationship.target_id,
+            )
+            .await?
+        };
+        if !target_exists {
+            missing_endpoints.push(relationship.target_id.clone());
+        }
+
+        if !missing_endpoints.is_empty() {
+            report.dangling_relationships.push(DanglingRelationship {
+                relationship_id: relationship.id.clone(),
+                relationship_type: relationship.relationship_type.clone(),
+                missing_endpoints,
+            });
+        }
+    }
+
+    if let Some(version_store) = manager.memory_version_store() {
+        report.broken_delta_chains = version_store
+            .validate_versions(None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to validate versions: {}", e)))?
+            .into_iter()
+            .filter(|issue| issue.issue_type == IntegrityIssueType::MissingParent)
+            .collect();
+    }
+
+    Ok(report)
+}
+
+/// Repair the issues in `report`
+///
+/// Orphaned vectors and dangling relationships are deleted. Broken delta
+/// chains go through the version store's own repair, which promotes the
+/// affected versions to full copies where their content can still be
+/// rebuilt.
+pub async fn repair_consistency(
+    manager: &MemoryManager,
+    report: &ConsistencyReport,
+) -> Result<ConsistencyRepairReport> {
+    let storage = manager.storage();
+    let mut repair = ConsistencyRepairReport::default();
+
+    for vector_id in &report.orphaned_vectors {
+        match storage.delete_vector(vector_id).await {
+            Ok(_) => {
+                repair.vectors_deleted += 1;
+                repair
+                    .details
+                    .push(format!("Deleted orphaned vector {}", vector_id));
+            }
+            Err(e) => {
+                repair.failed += 1;
+                repair
+                    .details
+                    .push(format!("Failed to delete vector {}: {}", vector_id, e));
+            }
+        }
+    }
+
+    for dangling in &report.dangling_relationships {
+        match storage.delete_relationship(&dangling.relationship_id).await {
+            Ok(_) => {
+                repair.relationships_deleted += 1;
+                repair.details.push(format!(
+                    "Deleted dangling relationship {}",
+                    dangling.relationship_id
+                ));
+            }
+            Err(e) => {
+                repair.failed += 1;
+                repair.details.push(format!(
+                    "Failed to delete relationship {}: {}",
+                    dangling.relationship_id, e
+                ));
+            }
+        }
+    }
+
+    if let Some(version_store) = manager.memory_version_store() {
+        let memory_ids: HashSet<&str> = report
+            .broken_delta_chains
+            .iter()
+            .map(|issue| issue.memory_id.as_str())
+            .collect();
+        for memory_id in memory_ids {
+            let version_repair = version_store
+                .repair_versions(Some(memory_id))
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to repair versions: {}", e)))?;
+            repair.versions_repaired += version_repair.versions_repaired;
+            repair.failed += version_repair.versions_failed;
+            repair.details.extend(version_repair.repair_details);
+        }
+    }
+
+    Ok(repair)
+}
+
+async fn memory_exists(
+    storage: &dyn GraphStore,
+    cache: &mut HashMap<String, bool>,
+    id: &str,
+) -> Result<bool> {
+    if let Some(exists) = cache.get(id) {
+        return Ok(*exists);
+    }
+    let exists = storage
+        .get_memory(id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+        .is_some();
+    cache.insert(id.to_string(), exists);
+    Ok(exists)
+}
+
+/// Relationship endpoints can be memories or entities
+async fn node_exists(
+    storage: &dyn GraphStore,
+    memories: &mut HashMap<String, bool>,
+    entities: &mut HashMap<String, bool>,
+    id: &str,
+) -> Result<bool> {
+    if memory_exists(storage, memories, id).await? {
+        return Ok(true);
+    }
+    if let Some(exists) = entities.get(id) {
+        return Ok(*exists);
+    }
+    let exists = storage
+        .get_entity(id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get entity: {}", e)))?
+        .is_some();
+    entities.insert(id.to_string(), exists);
+    Ok(exists)
+}
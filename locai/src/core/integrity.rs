@@ -0,0 +1,243 @@
+//! Storage integrity verification against content checksums
+//!
+//! Memories and vectors are stored with checksums of their content, so
+//! records whose data changed after it was written can be found. A
+//! corrupted memory is restored from whichever of its versions matches the
+//! checksum, or from a backup; a corrupted vector from its source memory's
+//! embedding.
+
+use serde::Serialize;
+
+use crate::core::MemoryManager;
+use crate::storage::models::{ChecksumIssue, ChecksumReport, ChecksummedRecord, SnapshotBundle};
+use crate::storage::shared_storage::checksum::{memory_checksum, vector_checksum};
+use crate::storage::traits::ChecksumStore;
+use crate::{LocaiError, Result};
+
+/// What [`repair_storage`] changed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IntegrityRepairReport {
+    /// Memories restored from one of their versions
+    pub restored_from_versions: usize,
+
+    /// Records restored from the backup
+    pub restored_from_backup: usize,
+
+    /// Vectors rebuilt from their source memory's embedding
+    pub vectors_rebuilt: usize,
+
+    /// Records written before checksums were stored that were given one
+    pub checksums_backfilled: usize,
+
+    /// Issues that could not be repaired
+    pub failed: usize,
+
+    /// One line per repair attempt
+    pub details: Vec<String>,
+}
+
+/// Verify the checksum of every memory and vector
+///
+/// Fails if the storage backend doesn't store checksums.
+pub async fn verify_storage(manager: &MemoryManager) -> Result<ChecksumReport> {
+    checksum_store(manager)?
+        .verify_checksums()
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to verify checksums: {}", e)))
+}
+
+/// Repair the corruption in `report`
+///
+/// A memory is restored from the newest of its versions whose content
+/// matches the stored checksum, and otherwise from `backup` when it holds
+/// the memory. A vector is rebuilt from its source memory's embedding (or
+/// the backup's) when that matches the checksum. Records with no checksum
+/// are given one when `backfill` is set.
+pub async fn repair_storage(
+    manager: &MemoryManager,
+    report: &ChecksumReport,
+    backup: Option<&SnapshotBundle>,
+    backfill: bool,
+) -> Result<IntegrityRepairReport> {
+    let checksums = checksum_store(manager)?;
+    let mut repair = IntegrityRepairReport::default();
+
+    for issue in &report.issues {
+        let outcome = match issue.record {
+            ChecksummedRecord::Memory => repair_memory(manager, issue, backup).await,
+            ChecksummedRecord::Vector => repair_vector(manager, issue, backup).await,
+        };
+        match outcome {
+            Ok(Some(RepairSource::Version(version_id))) => {
+                repair.restored_from_versions += 1;
+                repair.details.push(format!(
+                    "Restored memory {} from version {}",
+                    issue.id, version_id
+                ));
+            }
+            Ok(Some(RepairSource::Backup)) => {
+                repair.restored_from_backup += 1;
+                repair.details.push(format!(
+                    "Restored {} {} from backup",
+                    record_name(issue.record),
+                    issue.id
+                ));
+            }
+            Ok(Some(RepairSource::SourceMemory)) => {
+                repair.vectors_rebuilt += 1;
+                repair.details.push(format!(
+                    "Rebuilt vector {} from its source memory",
+                    issue.id
+                ));
+            }
+            Ok(None) => {
+                repair.failed += 1;
+                repair.details.push(format!(
+                    "No intact copy of {} {} found",
+                    record_name(issue.record),
+                    issue.id
+                ));
+            }
+            Err(e) => {
+                repair.failed += 1;
+                repair.details.push(format!(
+                    "Failed to repair {} {}: {}",
+                    record_name(issue.record),
+                    issue.id,
+                    e
+                ));
+            }
+        }
+    }
+
+    if backfill && report.unchecksummed > 0 {
+        repair.checksums_backfilled = checksums
+            .backfill_checksums()
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to backfill checksums: {}", e)))?;
+        repair.details.push(format!(
+            "Stored checksums for {} records",
+            repair.checksums_backfilled
+        ));
+    }
+
+    Ok(repair)
+}
+
+/// Where a corrupted record was restored from
+enum RepairSource {
+    Version(String),
+    Backup,
+    SourceMemory,
+}
+
+fn checksum_store(manager: &MemoryManager) -> Result<&dyn ChecksumStore> {
+    manager.checksum_store().ok_or_else(|| {
+        LocaiError::Storage("Content checksums are only supported with SharedStorage".to_string())
+    })
+}
+
+fn record_name(record: ChecksummedRecord) -> &'static str {
+    match record {
+        ChecksummedRecord::Memory => "memory",
+        ChecksummedRecord::Vector => "vector",
+    }
+}
+
+async fn repair_memory(
+    manager: &MemoryManager,
+    issue: &ChecksumIssue,
+    backup: Option<&SnapshotBundle>,
+) -> Result<Option<RepairSource>> {
+    let storage = manager.storage();
+    let Some(mut memory) = storage
+        .get_memory(&issue.id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+    else {
+        return Ok(None);
+    };
+
+    // A version only records content, so it is checked against the current embedding
+    if let (Some(versions), Some(expected)) = (manager.memory_version_store(), &issue.expected) {
+        let history = versions
+            .list_memory_versions(&issue.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list versions: {}", e)))?;
+        for info in history.iter().rev() {
+            let Some(version) = versions
+                .get_memory_version(&issue.id, &info.version_id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get version: {}", e)))?
+            else {
+                continue;
+            };
+            if memory_checksum(&version.content, memory.embedding.as_deref()) == *expected {
+                memory.content = version.content;
+                storage
+                    .update_memory(memory)
+                    .await
+                    .map_err(|e| LocaiError::Storage(format!("Failed to update memory: {}", e)))?;
+                return Ok(Some(RepairSource::Version(info.version_id.clone())));
+            }
+        }
+    }
+
+    let Some(saved) = backup.and_then(|bundle| bundle.memories.iter().find(|m| m.id == issue.id))
+    else {
+        return Ok(None);
+    };
+    memory.content = saved.content.clone();
+    memory.embedding = saved.embedding.clone();
+    storage
+        .update_memory(memory)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to update memory: {}", e)))?;
+    Ok(Some(RepairSource::Backup))
+}
+
+async fn repair_vector(
+    manager: &MemoryManager,
+    issue: &ChecksumIssue,
+    backup: Option<&SnapshotBundle>,
+) -> Result<Option<RepairSource>> {
+    let storage = manager.storage();
+    let Some(mut vector) = storage
+        .get_vector(&issue.id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get vector: {}", e)))?
+    else {
+        return Ok(None);
+    };
+    let Some(source_id) = vector.source_id.clone() else {
+        return Ok(None);
+    };
+
+    // Without a checksum, only the length of a candidate can be checked
+    let matches = |values: &[f32]| match &issue.expected {
+        Some(expected) => vector_checksum(values) == *expected,
+        None => values.len() == vector.dimension,
+    };
+
+    let source = storage
+        .get_memory(&source_id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+        .and_then(|memory| memory.embedding);
+    let saved = backup
+        .and_then(|bundle| bundle.memories.iter().find(|m| m.id == source_id))
+        .and_then(|memory| memory.embedding.clone());
+
+    let (values, from) = match (source, saved) {
+        (Some(values), _) if matches(&values) => (values, RepairSource::SourceMemory),
+        (_, Some(values)) if matches(&values) => (values, RepairSource::Backup),
+        _ => return Ok(None),
+    };
+    vector.dimension = values.len();
+    vector.vector = values;
+    storage
+        .upsert_vector(vector)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to store vector: {}", e)))?;
+    Ok(Some(from))
+}
@@ -3,28 +3,75 @@
 //! This module provides the primary interface for interacting with the Locai memory system.
 //! It orchestrates the various memory management components.
 
+use crate::clock::ClockHandle;
 use crate::config::LocaiConfig;
+use crate::entity_extraction::EntityExtractor;
+use crate::hooks::AutomationHook;
 use crate::ml::model_manager::EmbeddingManager;
-use crate::models::{Memory, MemoryBuilder, MemoryPriority, MemoryType};
+use crate::ml::provider::EmbeddingProvider;
+use crate::models::{
+    GeoPoint, GeoRadius, Memory, MemoryBuilder, MemoryPriority, MemoryType, SparseVector,
+};
+use crate::replication::RecordKind;
+use crate::search::profiles::ScoringProfiles;
+use crate::search::rerank::Reranker;
+use crate::search::transform::QueryTransformer;
+use crate::storage::degraded::{DegradedStatus, DegradedStorage};
 use crate::storage::filters::{
     EntityFilter, MemoryFilter, RelationshipFilter, SemanticSearchFilter,
 };
-use crate::storage::models::{Entity, MemoryGraph, MemoryPath, Relationship, SearchResult};
+use crate::storage::models::{
+    ArchiveStats, Entity, EntitySplit, MemoryGraph, MemoryPath, NearbyEntity, NearbyMemory,
+    OutboxMessage, PathConstraints, RecordDiff, RecordVersion, Relationship, SearchHit,
+    SearchResult,
+};
 use crate::{LocaiError, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 // Import the new modules
+#[cfg(feature = "rhai-rules")]
+use crate::memory::rules::{Rule, RuleEvent, RuleHook, RuleOutcome, RuleStore};
 use crate::memory::{
+    TimeRange,
     builders::MemoryBuilders,
+    collections::{Collection, CollectionStore, scope_to_members},
+    context::{ContextOptions, MemoryContext},
+    diagram::{Diagram, DiagramFilter},
     entity_operations::EntityOperations,
+    entity_profile::EntityProfile,
+    forgetting,
+    graph_diff::GraphDiff,
     graph_operations::GraphOperations,
+    idempotency::{self, IdempotencyStore},
     messaging::MessagingIntegration,
     operations::MemoryOperations,
+    path_narration::PathNarrator,
+    personas::{Persona, PersonaStore},
+    pins::{GLOBAL_PIN_SCOPE, PinList, PinStore, lead_with_pinned},
+    pipeline::{StoreMiddleware, StorePipeline},
+    preferences::{GLOBAL_PREFERENCE_SCOPE, Preference, PreferenceChange, PreferenceStore},
+    procedures::{Procedure, ProcedureMatch, ProcedureStore},
+    query_expansion::{ExpandedQuery, QueryExpansionConfig},
+    quota::QuotaUsage,
+    reflection::{MAX_REFLECTION_MEMORIES, Reflector, derived_from},
+    reminders::{Reminder, ReminderEvent, ReminderStore},
+    review::ReviewItem,
+    search_cache::{SearchCache, SearchCacheStats, SearchKey},
     search_extensions::{
         SearchExtensions, SearchMode, UniversalSearchOptions, UniversalSearchResult,
     },
+    sessions::{self, SessionStore, SessionSummary, Summarizer, Turn},
+    subgraph::{ScoredSubgraph, SubgraphScoring},
+    tasks::{Task, TaskQuery, TaskStatus, TaskStore},
+    templates::{MemoryTemplate, TemplateRegistry},
+    usage::{USAGE_SOURCE, UsageLedger, UsageQuery, UsageRecord},
+    warmup::{self, WarmupReport},
 };
+use crate::notifications::{NotificationHook, Notifications};
 use crate::relationships::storage::RelationshipStorage;
+use crate::tokens::{self, ApproxTokenCounter, TokenCounter};
 
 /// The primary interface for interacting with Locai's memory system.
 ///
@@ -53,6 +100,74 @@ pub struct MemoryManager {
     /// Relationship storage operations
     relationships: RelationshipStorage,
 
+    /// Named groups of memories
+    collections: CollectionStore,
+
+    /// Memories that lead search results
+    pins: PinStore,
+
+    /// Reusable shapes for memories
+    templates: TemplateRegistry,
+
+    /// Times memories should be surfaced again
+    reminders: ReminderStore,
+
+    /// Status changes and dependencies of task memories
+    tasks: TaskStore,
+
+    /// Structured procedural memories and goal-based retrieval
+    procedures: ProcedureStore,
+
+    /// Per-scope key-value preferences
+    preferences: PreferenceStore,
+
+    /// One persona per agent
+    personas: PersonaStore,
+
+    /// Scripts run on memory events
+    #[cfg(feature = "rhai-rules")]
+    rules: RuleStore,
+
+    /// Conversation turns and their rolling summaries
+    sessions: SessionStore,
+
+    /// Results of writes made with idempotency keys
+    idempotency: IdempotencyStore,
+
+    /// Second-stage reranker for search results
+    reranker: Option<Arc<dyn Reranker>>,
+
+    /// Rewrites queries before retrieval (HyDE, step-back prompting)
+    query_transformer: Option<Arc<dyn QueryTransformer>>,
+
+    /// Draws insights from memories for `reflect`
+    reflector: Option<Arc<dyn Reflector>>,
+
+    /// Folds older session turns into rolling summaries
+    summarizer: Option<Arc<dyn Summarizer>>,
+
+    /// Counts tokens for every token budget
+    token_counter: Arc<dyn TokenCounter>,
+
+    /// Results of recent searches, when enabled
+    search_cache: Option<SearchCache>,
+
+    /// Named scoring configurations searches can select
+    scoring_profiles: ScoringProfiles,
+
+    /// Where notifications about memory events go
+    notifications: Arc<Notifications>,
+
+    /// Usage per tenant, for billing
+    usage: UsageLedger,
+
+    /// Held while relaying the outbox, so concurrent relays don't deliver
+    /// the same message twice
+    outbox_relay: tokio::sync::Mutex<()>,
+
+    /// Runtime the storage engine runs on, when separate from the caller's
+    storage_runtime: Option<crate::runtime::StorageRuntime>,
+
     /// Configuration for the memory manager
     config: LocaiConfig,
 }
@@ -72,8 +187,36 @@ impl MemoryManager {
         let search = SearchExtensions::new(Arc::clone(&storage));
         let graph = GraphOperations::new(Arc::clone(&storage));
         let entities = EntityOperations::new(Arc::clone(&storage));
-        let messaging = MessagingIntegration::new(Arc::clone(&storage));
+        let messaging = MessagingIntegration::new(
+            Arc::clone(&storage),
+            &config.messaging,
+            config.clock.clone(),
+        );
         let relationships = RelationshipStorage::new(Arc::clone(&storage));
+        let collections = CollectionStore::new(Arc::clone(&storage));
+        let pins = PinStore::new(Arc::clone(&storage));
+        let templates = TemplateRegistry::new(Arc::clone(&storage));
+        let reminders = ReminderStore::new(Arc::clone(&storage));
+        let tasks = TaskStore::new(Arc::clone(&storage));
+        let procedures = ProcedureStore::new(Arc::clone(&storage));
+        let preferences = PreferenceStore::new(Arc::clone(&storage));
+        let personas = PersonaStore::new(Arc::clone(&storage));
+        #[cfg(feature = "rhai-rules")]
+        let rules = RuleStore::new(Arc::clone(&storage), &config.rules);
+        let sessions = SessionStore::new(Arc::clone(&storage));
+        let idempotency =
+            IdempotencyStore::new(Arc::clone(&storage), config.idempotency.retention_secs);
+        let search_cache = config
+            .search_cache
+            .enabled
+            .then(|| SearchCache::new(&config.search_cache, Arc::clone(&storage)));
+        let scoring_profiles = ScoringProfiles::new(&config.scoring_profiles);
+        let notifications = Arc::new(Notifications::new(&config.notifications));
+        let usage = UsageLedger::new(Arc::clone(&storage), config.clock.clone());
+        let token_counter = tokens::counter_for(&config.tokenizer).unwrap_or_else(|e| {
+            tracing::warn!("Falling back to estimated token counts: {}", e);
+            Arc::new(ApproxTokenCounter::new(config.tokenizer.chars_per_token))
+        });
 
         Self {
             memory_ops,
@@ -83,6 +226,29 @@ impl MemoryManager {
             entities,
             messaging,
             relationships,
+            collections,
+            pins,
+            templates,
+            reminders,
+            tasks,
+            procedures,
+            preferences,
+            personas,
+            #[cfg(feature = "rhai-rules")]
+            rules,
+            sessions,
+            idempotency,
+            reranker: None,
+            query_transformer: None,
+            reflector: None,
+            summarizer: None,
+            token_counter,
+            search_cache,
+            scoring_profiles,
+            notifications,
+            usage,
+            outbox_relay: tokio::sync::Mutex::new(()),
+            storage_runtime: None,
             config,
         }
     }
@@ -102,8 +268,33 @@ impl MemoryManager {
         let search = SearchExtensions::new(Arc::clone(&storage));
         let graph = GraphOperations::new(Arc::clone(&storage));
         let entities = EntityOperations::new(Arc::clone(&storage));
-        let messaging = MessagingIntegration::new(Arc::clone(&storage));
+        let messaging = MessagingIntegration::new(
+            Arc::clone(&storage),
+            &config.messaging,
+            config.clock.clone(),
+        );
         let relationships = RelationshipStorage::new(Arc::clone(&storage));
+        let collections = CollectionStore::new(Arc::clone(&storage));
+        let pins = PinStore::new(Arc::clone(&storage));
+        let templates = TemplateRegistry::new(Arc::clone(&storage));
+        let reminders = ReminderStore::new(Arc::clone(&storage));
+        let tasks = TaskStore::new(Arc::clone(&storage));
+        let procedures = ProcedureStore::new(Arc::clone(&storage));
+        let preferences = PreferenceStore::new(Arc::clone(&storage));
+        let personas = PersonaStore::new(Arc::clone(&storage));
+        #[cfg(feature = "rhai-rules")]
+        let rules = RuleStore::new(Arc::clone(&storage), &config.rules);
+        let sessions = SessionStore::new(Arc::clone(&storage));
+        let idempotency =
+            IdempotencyStore::new(Arc::clone(&storage), config.idempotency.retention_secs);
+        let search_cache = config
+            .search_cache
+            .enabled
+            .then(|| SearchCache::new(&config.search_cache, Arc::clone(&storage)));
+        let scoring_profiles = ScoringProfiles::new(&config.scoring_profiles);
+        let notifications = Arc::new(Notifications::new(&config.notifications));
+        let usage = UsageLedger::new(Arc::clone(&storage), config.clock.clone());
+        let token_counter = tokens::counter_for(&config.tokenizer)?;
 
         Ok(Self {
             memory_ops,
@@ -113,10 +304,45 @@ impl MemoryManager {
             entities,
             messaging,
             relationships,
+            collections,
+            pins,
+            templates,
+            reminders,
+            tasks,
+            procedures,
+            preferences,
+            personas,
+            #[cfg(feature = "rhai-rules")]
+            rules,
+            sessions,
+            idempotency,
+            reranker: None,
+            query_transformer: None,
+            reflector: None,
+            summarizer: None,
+            token_counter,
+            search_cache,
+            scoring_profiles,
+            notifications,
+            usage,
+            outbox_relay: tokio::sync::Mutex::new(()),
+            storage_runtime: None,
             config,
         })
     }
 
+    /// Keep the runtime running the storage engine alive with this manager
+    pub(crate) fn set_storage_runtime(&mut self, runtime: crate::runtime::StorageRuntime) {
+        self.storage_runtime = Some(runtime);
+    }
+
+    /// Handle to the storage engine's runtime, if it has its own
+    pub fn storage_runtime(&self) -> Option<&tokio::runtime::Handle> {
+        self.storage_runtime
+            .as_ref()
+            .map(|runtime| runtime.handle())
+    }
+
     /// Initialize ML extractors asynchronously after construction (deprecated - use new_with_ml instead)
     pub async fn initialize_ml_extractors(&mut self) -> Result<()> {
         tracing::info!(
@@ -149,6 +375,156 @@ impl MemoryManager {
         self.memory_ops.delete_memory(id).await
     }
 
+    /// Store several memories with one storage write
+    ///
+    /// Embedding and entity extraction run for up to `concurrency` memories at
+    /// once. Results are in input order, so one invalid memory doesn't fail the
+    /// rest. For large imports, see [`IngestJob`](crate::ingest::IngestJob).
+    pub async fn store_memory_batch(
+        &self,
+        memories: Vec<Memory>,
+        concurrency: usize,
+    ) -> Vec<Result<String>> {
+        self.memory_ops
+            .store_memory_batch(memories, concurrency)
+            .await
+    }
+
+    /// Store a new memory unless `idempotency_key` already stored one
+    ///
+    /// Within the [`IdempotencyConfig`](crate::config::IdempotencyConfig)
+    /// window, storing again under the same key returns the first memory's ID
+    /// instead of a duplicate. Fails with [`LocaiError::Idempotency`] if the
+    /// key was used for a memory with other content.
+    pub async fn store_memory_idempotent(
+        &self,
+        memory: Memory,
+        idempotency_key: &str,
+    ) -> Result<String> {
+        let fingerprint = idempotency::memory_fingerprint([&memory]);
+        self.idempotent(Some(idempotency_key), &fingerprint, || {
+            self.store_memory(memory)
+        })
+        .await
+    }
+
+    /// Store several memories unless `idempotency_key` already stored them
+    ///
+    /// Like [`store_memory_batch`](Self::store_memory_batch), but a batch
+    /// resubmitted under the same key returns the first submission's results;
+    /// memories that failed then come back as [`LocaiError::Memory`] with the
+    /// original error's message.
+    pub async fn store_memory_batch_idempotent(
+        &self,
+        memories: Vec<Memory>,
+        concurrency: usize,
+        idempotency_key: &str,
+    ) -> Result<Vec<Result<String>>> {
+        let fingerprint = idempotency::memory_fingerprint(&memories);
+        // A first submission returns its own errors rather than their messages
+        let mut first_results = None;
+        let slot = &mut first_results;
+        let recorded: Vec<std::result::Result<String, String>> = self
+            .idempotent(Some(idempotency_key), &fingerprint, move || async move {
+                let results = self.store_memory_batch(memories, concurrency).await;
+                let recorded = results
+                    .iter()
+                    .map(|result| result.as_ref().cloned().map_err(|e| e.to_string()))
+                    .collect();
+                *slot = Some(results);
+                Ok(recorded)
+            })
+            .await?;
+
+        Ok(first_results.unwrap_or_else(|| {
+            recorded
+                .into_iter()
+                .map(|result| result.map_err(LocaiError::Memory))
+                .collect()
+        }))
+    }
+
+    /// Run `write` unless `idempotency_key` already recorded its result
+    ///
+    /// The building block for idempotent writes: the result of `write` is
+    /// recorded under the key, and a later call with the key returns it
+    /// without running `write`. `fingerprint` identifies the write, so reusing
+    /// a key for a different one fails with [`LocaiError::Idempotency`].
+    /// Without a key, `write` just runs.
+    pub async fn idempotent<T, F, Fut>(
+        &self,
+        idempotency_key: Option<&str>,
+        fingerprint: &str,
+        write: F,
+    ) -> Result<T>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        self.idempotency
+            .run(idempotency_key, fingerprint, self.clock().now(), write)
+            .await
+    }
+
+    /// Delete idempotency records past their retention window
+    ///
+    /// Expired records are replaced when their key is reused, so this only
+    /// reclaims space.
+    pub async fn purge_idempotency_keys(&self) -> Result<usize> {
+        self.idempotency.purge_expired(self.clock().now()).await
+    }
+
+    /// Store a new memory now, and embed it and extract its entities in the background
+    ///
+    /// For quick capture: the memory is searchable by text as soon as this
+    /// returns, and the returned task fills in its embedding, entities and
+    /// automatic relationships. Await the task before the runtime shuts down,
+    /// or that indexing is lost.
+    pub async fn store_memory_deferred(
+        &self,
+        memory: Memory,
+    ) -> Result<(String, tokio::task::JoinHandle<()>)> {
+        self.memory_ops.store_memory_deferred(memory).await
+    }
+
+    /// Store a new memory and its outbox messages atomically
+    ///
+    /// The memory keeps its own ID, so the messages can refer to it. A memory
+    /// held for review keeps its messages until it's approved. See
+    /// [`relay_outbox`](Self::relay_outbox) for delivery.
+    pub async fn store_memory_with_outbox(
+        &self,
+        memory: Memory,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<String> {
+        self.memory_ops
+            .store_memory_with_outbox(memory, outbox)
+            .await
+    }
+
+    /// Update an existing memory and record its outbox messages atomically
+    pub async fn update_memory_with_outbox(
+        &self,
+        memory: Memory,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<bool> {
+        self.memory_ops
+            .update_memory_with_outbox(memory, outbox)
+            .await
+    }
+
+    /// Delete a memory and record its outbox messages atomically
+    ///
+    /// Nothing is recorded if the memory was not deleted.
+    pub async fn delete_memory_with_outbox(
+        &self,
+        id: &str,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<bool> {
+        self.memory_ops.delete_memory_with_outbox(id, outbox).await
+    }
+
     /// Filter memories using various criteria
     pub async fn filter_memories(
         &self,
@@ -160,6 +536,75 @@ impl MemoryManager {
         self.memory_ops.filter_memories(filter, limit).await
     }
 
+    /// Find the memories located within `radius_meters` of `center`, nearest first
+    pub async fn search_near(
+        &self,
+        center: GeoPoint,
+        radius_meters: f64,
+        limit: Option<usize>,
+    ) -> Result<Vec<NearbyMemory>> {
+        let near = GeoRadius::new(center, radius_meters);
+        near.validate()?;
+        let filter = MemoryFilter {
+            near: Some(near),
+            ..Default::default()
+        };
+        let mut nearby: Vec<NearbyMemory> = self
+            .filter_memories(filter, None, None, None)
+            .await?
+            .into_iter()
+            .filter_map(|memory| {
+                let distance_meters = center.distance_meters(&memory.location()?);
+                Some(NearbyMemory {
+                    memory,
+                    distance_meters,
+                })
+            })
+            .collect();
+        nearby.sort_by(|a, b| a.distance_meters.total_cmp(&b.distance_meters));
+        nearby.truncate(limit.unwrap_or(usize::MAX));
+        Ok(nearby)
+    }
+
+    /// Explain how storage executes a memory filter, including which
+    /// property indexes it uses
+    ///
+    /// Fails if the storage backend can't explain queries.
+    pub async fn explain_memory_filter(
+        &self,
+        filter: &MemoryFilter,
+    ) -> Result<crate::storage::models::FilterExplanation> {
+        use crate::storage::shared_storage::SharedStorage;
+
+        let storage_any = self.memory_ops.storage.as_any();
+        let explain_error = |e: crate::storage::errors::StorageError| {
+            LocaiError::Storage(format!("Failed to explain filter: {}", e))
+        };
+
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::local::Db>>()
+        {
+            return shared_storage
+                .explain_memory_filter(filter)
+                .await
+                .map_err(explain_error);
+        }
+
+        #[cfg(feature = "surrealdb-remote")]
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::remote::ws::Client>>()
+        {
+            return shared_storage
+                .explain_memory_filter(filter)
+                .await
+                .map_err(explain_error);
+        }
+
+        Err(LocaiError::Storage(
+            "Filter explanations are only supported with SharedStorage".to_string(),
+        ))
+    }
+
     /// Count memories with optional filtering
     pub async fn count_memories(&self, filter: Option<MemoryFilter>) -> Result<usize> {
         self.memory_ops.count_memories(filter).await
@@ -258,9 +703,22 @@ impl MemoryManager {
         filter: Option<SemanticSearchFilter>,
         search_mode: SearchMode,
     ) -> Result<Vec<SearchResult>> {
-        self.search
-            .search(query_text, limit, filter, search_mode)
-            .await
+        let key_filter = filter.clone();
+        let key = SearchKey {
+            query: query_text,
+            embedding: None,
+            sparse: None,
+            limit,
+            filter: key_filter.as_ref(),
+            mode: search_mode,
+        };
+        let results = self
+            .cached_search(
+                key,
+                self.search.search(query_text, limit, filter, search_mode),
+            )
+            .await?;
+        Ok(self.apply_forgetting(results))
     }
 
     /// Perform a search for memories with optional query embedding (BYOE approach)
@@ -304,12 +762,217 @@ impl MemoryManager {
         limit: Option<usize>,
         filter: Option<SemanticSearchFilter>,
         search_mode: SearchMode,
+    ) -> Result<Vec<SearchResult>> {
+        let key_filter = filter.clone();
+        let key = SearchKey {
+            query: query_text,
+            embedding: query_embedding,
+            sparse: None,
+            limit,
+            filter: key_filter.as_ref(),
+            mode: search_mode,
+        };
+        let results = self
+            .cached_search(
+                key,
+                self.search.search_with_embedding(
+                    query_text,
+                    query_embedding,
+                    limit,
+                    filter,
+                    search_mode,
+                ),
+            )
+            .await?;
+        Ok(self.apply_forgetting(results))
+    }
+
+    /// Search with a sparse query vector, fused with dense and text results
+    ///
+    /// See [`SearchExtensions::search_with_sparse`](crate::memory::search_extensions::SearchExtensions::search_with_sparse).
+    pub async fn search_with_sparse(
+        &self,
+        query_text: &str,
+        query_embedding: Option<&[f32]>,
+        sparse_query: &SparseVector,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchResult>> {
+        let key_filter = filter.clone();
+        let key = SearchKey {
+            query: query_text,
+            embedding: query_embedding,
+            sparse: Some(sparse_query),
+            limit,
+            filter: key_filter.as_ref(),
+            mode: search_mode,
+        };
+        let results = self
+            .cached_search(
+                key,
+                self.search.search_with_sparse(
+                    query_text,
+                    query_embedding,
+                    sparse_query,
+                    limit,
+                    filter,
+                    search_mode,
+                ),
+            )
+            .await?;
+        Ok(self.apply_forgetting(results))
+    }
+
+    /// Answer a search from the search cache when it can, otherwise run it
+    /// and cache its results
+    ///
+    /// Results are cached before forgetting is applied, as that changes with
+    /// time.
+    async fn cached_search(
+        &self,
+        key: SearchKey<'_>,
+        search: impl Future<Output = Result<Vec<SearchResult>>>,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(cache) = &self.search_cache else {
+            return search.await;
+        };
+        if let Some(results) = cache.get(&key).await {
+            return Ok(results);
+        }
+        let generation = cache.generation();
+        let results = search.await?;
+        cache.insert(&key, &results, generation);
+        Ok(results)
+    }
+
+    /// Hit and miss counts for the search result cache; `None` when it's off
+    pub fn search_cache_stats(&self) -> Option<SearchCacheStats> {
+        self.search_cache.as_ref().map(SearchCache::stats)
+    }
+
+    /// Rank faded memories lower when forgetting is simulated
+    fn apply_forgetting(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        if self.config.forgetting.enabled {
+            forgetting::apply_to_results(&self.config.forgetting, results, self.clock().now())
+        } else {
+            results
+        }
+    }
+
+    /// Search without loading full memories
+    ///
+    /// Returns IDs, scores and snippets, avoiding the cost of building and
+    /// cloning a [`Memory`] per result. Useful at high query rates when only a
+    /// few results are opened; load those with [`hydrate`](Self::hydrate).
+    ///
+    /// ```no_run
+    /// use locai::memory::SearchMode;
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let hits = manager.search_hits("deploy", None, Some(20), SearchMode::Text).await?;
+    /// for hit in &hits {
+    ///     println!("{} {:.2} {}", hit.id, hit.score, hit.snippet);
+    /// }
+    /// let top = manager.hydrate(&hits[..3.min(hits.len())]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_hits(
+        &self,
+        query_text: &str,
+        query_embedding: Option<&[f32]>,
+        limit: Option<usize>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchHit>> {
+        self.search
+            .search_hits(query_text, query_embedding, limit, search_mode)
+            .await
+    }
+
+    /// Load the memories behind search hits, keeping their order
+    ///
+    /// Hits whose memory has been deleted since the search are skipped.
+    pub async fn hydrate(&self, hits: &[SearchHit]) -> Result<Vec<SearchResult>> {
+        let memories =
+            futures::future::try_join_all(hits.iter().map(|hit| self.get_memory(&hit.id))).await?;
+        Ok(hits
+            .iter()
+            .zip(memories)
+            .filter_map(|(hit, memory)| {
+                memory.map(|memory| SearchResult {
+                    memory,
+                    score: Some(hit.score),
+                })
+            })
+            .collect())
+    }
+
+    /// Drop cached search results and reconstructed memory versions
+    ///
+    /// Both caches refill on demand, so this only slows the next few lookups.
+    /// Useful after editing the database outside this manager.
+    pub async fn clear_caches(&self) {
+        use crate::storage::shared_storage::SharedStorage;
+
+        if let Some(cache) = &self.search_cache {
+            cache.clear();
+        }
+
+        let storage_any = self.memory_ops.storage.as_any();
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::local::Db>>()
+        {
+            shared_storage.clear_version_cache().await;
+        }
+        #[cfg(feature = "surrealdb-remote")]
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::remote::ws::Client>>()
+        {
+            shared_storage.clear_version_cache().await;
+        }
+    }
+
+    /// Load the search indexes by running the searches the warmup
+    /// configuration describes
+    ///
+    /// BM25 searches for the configured queries and the terms most common in
+    /// recent memories load their postings; vector searches with recent
+    /// memories' embeddings load the vectors near them. Failed searches are
+    /// counted and skipped, so this never fails; it stops at the configured
+    /// timeout. Runs whether or not `warmup.enabled` is set, which only
+    /// decides whether the server calls it on startup.
+    pub async fn warm_up(&self) -> WarmupReport {
+        warmup::warm_up(self.storage(), &self.search, &self.config.warmup).await
+    }
+
+    /// Search memories with the query expanded by graph neighbors
+    ///
+    /// Entities named in the query are looked up in the graph and the names of
+    /// strongly related entities are searched alongside it, so "what did the
+    /// project lead say" also finds memories naming the lead directly.
+    pub async fn search_with_expansion(
+        &self,
+        query_text: &str,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+        config: &QueryExpansionConfig,
     ) -> Result<Vec<SearchResult>> {
         self.search
-            .search_with_embedding(query_text, query_embedding, limit, filter, search_mode)
+            .search_with_expansion(query_text, limit, filter, search_mode, config)
             .await
     }
 
+    /// Show how a query would be expanded without searching
+    pub async fn expand_query(
+        &self,
+        query_text: &str,
+        config: &QueryExpansionConfig,
+    ) -> Result<ExpandedQuery> {
+        self.search.expand_query(query_text, config).await
+    }
+
     /// Search memories with lifecycle-aware scoring
     ///
     /// This method enables enhanced search results ranked by multiple factors:
@@ -355,6 +1018,33 @@ impl MemoryManager {
             .await
     }
 
+    /// Search memories ranked by a named scoring profile
+    ///
+    /// Fails with [`LocaiError::Scoring`] if no profile has that name.
+    pub async fn search_with_profile(
+        &self,
+        query_text: &str,
+        limit: Option<usize>,
+        profile: &str,
+    ) -> Result<Vec<SearchResult>> {
+        let scoring = self.scoring_profiles.resolve(profile)?;
+        self.search_with_scoring(query_text, limit, scoring).await
+    }
+
+    /// Scoring profiles searches can select by name
+    ///
+    /// Profiles can be listed, and set or removed at runtime.
+    pub fn scoring_profiles(&self) -> &ScoringProfiles {
+        &self.scoring_profiles
+    }
+
+    /// Notification targets and the rules that pick them
+    ///
+    /// Targets and rules can be listed, and set or removed at runtime.
+    pub fn notifications(&self) -> &Arc<Notifications> {
+        &self.notifications
+    }
+
     /// Legacy method for backward compatibility - use search() instead
     #[deprecated(note = "Use search() instead")]
     pub async fn semantic_search(
@@ -423,6 +1113,33 @@ impl MemoryManager {
         self.graph.find_paths(from_id, to_id, max_depth).await
     }
 
+    /// Find the cheapest paths between two memories, weighting hops by a
+    /// relationship property and limiting the relationship and entity types
+    /// they pass through
+    pub async fn find_weighted_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        constraints: &PathConstraints,
+    ) -> Result<Vec<MemoryPath>> {
+        self.graph
+            .find_weighted_paths(from_id, to_id, constraints)
+            .await
+    }
+
+    /// Find paths between two memories and narrate each as a sentence, such
+    /// as "Alice WORKS_AT Acme, which ACQUIRED Beta.", for use in prompts
+    pub async fn explain_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        max_depth: u8,
+        narrator: &PathNarrator,
+    ) -> Result<Vec<String>> {
+        let paths = self.find_paths(from_id, to_id, max_depth).await?;
+        narrator.narrate_paths(self.storage(), &paths).await
+    }
+
     /// Find the shortest path between two memories
     pub async fn find_shortest_path(
         &self,
@@ -447,6 +1164,33 @@ impl MemoryManager {
             .await
     }
 
+    /// Extract a scored subgraph around seed memories or entities, for use
+    /// as graph context in prompts
+    pub async fn extract_subgraph(
+        &self,
+        seed_ids: &[String],
+        depth: u8,
+        max_nodes: usize,
+        scoring: &SubgraphScoring,
+    ) -> Result<ScoredSubgraph> {
+        self.graph
+            .extract_subgraph(seed_ids, depth, max_nodes, scoring)
+            .await
+    }
+
+    /// Select part of the graph, around a root or by relationship type, to
+    /// render as a Mermaid or PlantUML diagram
+    pub async fn graph_diagram(&self, filter: &DiagramFilter) -> Result<Diagram> {
+        self.graph.diagram(filter).await
+    }
+
+    /// Diff the graph between two points in time: memories, entities and
+    /// relationships added, removed or changed, as far back as the storage
+    /// changefeed goes
+    pub async fn graph_diff(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<GraphDiff> {
+        self.graph.graph_diff(from, to).await
+    }
+
     /// Create a relationship between two memories
     pub async fn create_relationship(
         &self,
@@ -560,6 +1304,36 @@ impl MemoryManager {
         self.entities.list_entities(filter, limit, offset).await
     }
 
+    /// Find the entities located within `radius_meters` of `center`, nearest first
+    pub async fn entities_near(
+        &self,
+        center: GeoPoint,
+        radius_meters: f64,
+        limit: Option<usize>,
+    ) -> Result<Vec<NearbyEntity>> {
+        let near = GeoRadius::new(center, radius_meters);
+        near.validate()?;
+        let filter = EntityFilter {
+            near: Some(near),
+            ..Default::default()
+        };
+        let mut nearby: Vec<NearbyEntity> = self
+            .list_entities(Some(filter), None, None)
+            .await?
+            .into_iter()
+            .filter_map(|entity| {
+                let distance_meters = center.distance_meters(&entity.location()?);
+                Some(NearbyEntity {
+                    entity,
+                    distance_meters,
+                })
+            })
+            .collect();
+        nearby.sort_by(|a, b| a.distance_meters.total_cmp(&b.distance_meters));
+        nearby.truncate(limit.unwrap_or(usize::MAX));
+        Ok(nearby)
+    }
+
     /// Count entities with optional filtering
     pub async fn count_entities(&self, filter: Option<EntityFilter>) -> Result<usize> {
         self.entities.count_entities(filter).await
@@ -577,6 +1351,22 @@ impl MemoryManager {
             .await
     }
 
+    /// Merge duplicate entities into `target_id`, moving their links to it
+    pub async fn merge_entities(&self, target_id: &str, source_ids: &[String]) -> Result<Entity> {
+        self.entities.merge_entities(target_id, source_ids).await
+    }
+
+    /// Split part of an entity off into a new one
+    pub async fn split_entity(&self, entity_id: &str, split: EntitySplit) -> Result<Entity> {
+        self.entities.split_entity(entity_id, split).await
+    }
+
+    /// Get the profile of an entity: top memories, relationships, first and
+    /// last mention, sentiment trend and co-occurring entities
+    pub async fn entity_profile(&self, entity_id: &str) -> Result<Option<EntityProfile>> {
+        self.entities.entity_profile(entity_id).await
+    }
+
     /// Get memories by priority level
     pub async fn get_memories_by_priority(
         &self,
@@ -678,62 +1468,1624 @@ impl MemoryManager {
     }
 
     // =============================================================================
-    // Messaging Operations (delegated to MessagingIntegration)
+    // Entity and Relationship History (delegated to storage)
     // =============================================================================
 
-    /// Subscribe to memory changes with live queries (for messaging system)
-    pub async fn subscribe_to_memory_changes(
+    /// List the versions of an entity or relationship, oldest first
+    pub async fn list_record_versions(
         &self,
-        filter: MemoryFilter,
-    ) -> Result<std::pin::Pin<Box<dyn futures::Stream<Item = Result<Memory>> + Send>>> {
-        self.messaging.subscribe_to_memory_changes(filter).await
+        kind: RecordKind,
+        id: &str,
+    ) -> Result<Vec<RecordVersion>> {
+        self.memory_ops
+            .storage()
+            .list_record_versions(kind, id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list record versions: {}", e)))
     }
 
-    /// Store a message as a memory record (specialized method for messaging)
-    pub async fn store_message(
+    /// Get a single entity or relationship version
+    pub async fn get_record_version(&self, version_id: &str) -> Result<Option<RecordVersion>> {
+        self.memory_ops
+            .storage()
+            .get_record_version(version_id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get record version: {}", e)))
+    }
+
+    /// Get an entity as it existed at `at_time`
+    pub async fn get_entity_at_time(
         &self,
-        message: &crate::messaging::types::Message,
-    ) -> Result<String> {
-        self.messaging
-            .store_message(message, &self.memory_ops)
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<Entity>> {
+        self.memory_ops
+            .storage()
+            .get_entity_at_time(id, at_time)
             .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get entity at time: {}", e)))
     }
 
-    /// Get message history (specialized method for messaging)
-    pub async fn get_message_history(
+    /// Get a relationship as it existed at `at_time`
+    pub async fn get_relationship_at_time(
         &self,
-        filter: &crate::messaging::types::MessageFilter,
-        limit: Option<usize>,
-    ) -> Result<Vec<crate::messaging::types::Message>> {
-        self.messaging.get_message_history(filter, limit).await
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<Relationship>> {
+        self.memory_ops
+            .storage()
+            .get_relationship_at_time(id, at_time)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get relationship at time: {}", e)))
     }
 
-    // =============================================================================
-    // Configuration and Utility Methods
-    // =============================================================================
+    /// Diff two versions of the same entity or relationship
+    pub async fn diff_record_versions(
+        &self,
+        old_version_id: &str,
+        new_version_id: &str,
+    ) -> Result<RecordDiff> {
+        self.memory_ops
+            .storage()
+            .diff_record_versions(old_version_id, new_version_id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to diff record versions: {}", e)))
+    }
+
+    // =============================================================================
+    // Archive Operations (delegated to storage)
+    // =============================================================================
+
+    /// Move a memory to the archive tier
+    ///
+    /// Archived memories are compressed and dropped from the search indexes.
+    /// Returns false if the memory does not exist.
+    pub async fn archive_memory(&self, id: &str) -> Result<bool> {
+        let changed = self
+            .memory_ops
+            .storage()
+            .archive_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to archive memory: {}", e)))?;
+        if changed {
+            self.memory_ops.quotas().invalidate().await;
+        }
+        Ok(changed)
+    }
+
+    /// Restore an archived memory to the active tier under its original ID
+    pub async fn unarchive_memory(&self, id: &str) -> Result<bool> {
+        let changed = self
+            .memory_ops
+            .storage()
+            .unarchive_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to unarchive memory: {}", e)))?;
+        if changed {
+            self.memory_ops.quotas().invalidate().await;
+        }
+        Ok(changed)
+    }
+
+    /// Get an archived memory without restoring it
+    pub async fn get_archived_memory(&self, id: &str) -> Result<Option<Memory>> {
+        self.memory_ops
+            .storage()
+            .get_archived_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get archived memory: {}", e)))
+    }
+
+    /// List archived memories, most recently archived first
+    pub async fn list_archived_memories(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        self.memory_ops
+            .storage()
+            .list_archived_memories(limit, offset)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list archived memories: {}", e)))
+    }
+
+    /// Archive memories not accessed in the last `older_than_days` days
+    ///
+    /// Memories that were never accessed are judged by their creation time.
+    /// Returns the IDs of the archived memories.
+    pub async fn archive_inactive_memories(
+        &self,
+        older_than_days: u64,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let cutoff = self.clock().now() - chrono::Duration::days(older_than_days as i64);
+        let archived = self
+            .memory_ops
+            .storage()
+            .archive_inactive_memories(cutoff, limit)
+            .await
+            .map_err(|e| {
+                LocaiError::Storage(format!("Failed to archive inactive memories: {}", e))
+            })?;
+        if !archived.is_empty() {
+            self.memory_ops.quotas().invalidate().await;
+        }
+        Ok(archived)
+    }
+
+    /// Archive memories faded below the forgetting curve's threshold
+    ///
+    /// Does nothing unless forgetting is enabled in the configuration.
+    /// Returns the IDs of the archived memories.
+    pub async fn archive_forgotten_memories(&self, limit: Option<usize>) -> Result<Vec<String>> {
+        let config = &self.config.forgetting;
+        if !config.enabled {
+            return Ok(Vec::new());
+        }
+
+        let now = self.clock().now();
+        let mut archived = Vec::new();
+        for memory in self
+            .filter_memories(MemoryFilter::default(), None, None, None)
+            .await?
+        {
+            if limit.is_some_and(|limit| archived.len() >= limit) {
+                break;
+            }
+            if forgetting::is_forgotten(config, &memory, now)
+                && self.archive_memory(&memory.id).await?
+            {
+                archived.push(memory.id);
+            }
+        }
+        Ok(archived)
+    }
+
+    /// Get statistics about the archive tier
+    pub async fn archive_stats(&self) -> Result<ArchiveStats> {
+        self.memory_ops
+            .storage()
+            .get_archive_stats()
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get archive stats: {}", e)))
+    }
+
+    // =============================================================================
+    // Quota Operations (delegated to QuotaTracker)
+    // =============================================================================
+
+    /// Get how much a tenant or agent has stored, against its quota
+    ///
+    /// Owners are named by the configured quota owner property, or by the
+    /// memory source. Archived memories don't count.
+    pub async fn quota_usage(&self, owner: &str) -> Result<QuotaUsage> {
+        self.memory_ops.quotas().usage(owner).await
+    }
+
+    /// List quota usage of every owner, largest first
+    pub async fn list_quota_usage(&self) -> Result<Vec<QuotaUsage>> {
+        self.memory_ops.quotas().all_usage().await
+    }
+
+    /// Recount quota usage from storage
+    ///
+    /// Needed when other processes write to the same store.
+    pub async fn refresh_quota_usage(&self) -> Result<()> {
+        self.memory_ops.quotas().refresh().await
+    }
+
+    // =============================================================================
+    // Usage Operations (delegated to UsageLedger)
+    // =============================================================================
+
+    /// Usage per tenant, to record operations against
+    pub fn usage(&self) -> &UsageLedger {
+        &self.usage
+    }
+
+    /// Note how many bytes each quota owner stores, in the current hour of
+    /// its usage
+    pub async fn sample_storage_usage(&self) -> Result<()> {
+        for usage in self.list_quota_usage().await? {
+            if usage.owner != USAGE_SOURCE {
+                self.usage.record_storage(&usage.owner, usage.bytes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Append recorded usage to the store, returning how many entries were
+    /// written
+    pub async fn flush_usage(&self) -> Result<usize> {
+        self.usage.flush().await
+    }
+
+    /// Usage by tenant and hour, day or month
+    pub async fn usage_report(&self, query: &UsageQuery) -> Result<Vec<UsageRecord>> {
+        self.usage.report(query).await
+    }
+
+    // =============================================================================
+    // Collection Operations (delegated to CollectionStore)
+    // =============================================================================
+
+    /// Store a new collection; names must be unique
+    pub async fn create_collection(&self, collection: Collection) -> Result<Collection> {
+        self.collections.create(collection).await
+    }
+
+    /// Find a collection by ID, or else by name
+    pub async fn get_collection(&self, id_or_name: &str) -> Result<Option<Collection>> {
+        self.collections.get(id_or_name).await
+    }
+
+    /// List all collections, by name
+    pub async fn list_collections(&self) -> Result<Vec<Collection>> {
+        self.collections.list().await
+    }
+
+    /// Save changes to a collection's name, description, members or query
+    pub async fn update_collection(&self, collection: Collection) -> Result<Collection> {
+        self.collections.update(collection).await
+    }
+
+    /// Delete a collection, keeping its memories
+    pub async fn delete_collection(&self, id_or_name: &str) -> Result<bool> {
+        self.collections.delete(id_or_name).await
+    }
+
+    /// Add memories to a collection, returning `None` when there is no such collection
+    pub async fn add_to_collection(
+        &self,
+        id_or_name: &str,
+        memory_ids: &[String],
+    ) -> Result<Option<Collection>> {
+        self.collections.add_memories(id_or_name, memory_ids).await
+    }
+
+    /// Remove memories added by hand from a collection, returning `None` when
+    /// there is no such collection
+    pub async fn remove_from_collection(
+        &self,
+        id_or_name: &str,
+        memory_ids: &[String],
+    ) -> Result<Option<Collection>> {
+        self.collections
+            .remove_memories(id_or_name, memory_ids)
+            .await
+    }
+
+    /// Get a collection's memories, newest first
+    pub async fn collection_memories(
+        &self,
+        collection: &Collection,
+        limit: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        self.collections.members(collection, limit).await
+    }
+
+    /// Get the IDs of a collection's memories
+    pub async fn collection_member_ids(&self, collection: &Collection) -> Result<HashSet<String>> {
+        self.collections.member_ids(collection).await
+    }
+
+    /// Search only the memories of a collection
+    ///
+    /// Like [`search`](Self::search), with the other conditions of `filter`
+    /// still applying.
+    pub async fn search_collection(
+        &self,
+        collection: &Collection,
+        query_text: &str,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchResult>> {
+        let members = self.collection_member_ids(collection).await?;
+        // Search deep enough that members ranked below other memories still
+        // make the cut
+        let fetch_limit = limit.map(|limit| limit.max(members.len()));
+        let mut results = self
+            .search(
+                query_text,
+                fetch_limit,
+                Some(scope_to_members(filter, members)),
+                search_mode,
+            )
+            .await?;
+        if let Some(limit) = limit {
+            results.truncate(limit);
+        }
+        Ok(results)
+    }
+
+    // =============================================================================
+    // Pin Operations (delegated to PinStore)
+    // =============================================================================
+
+    /// Pin a memory so it leads search results in every scope
+    ///
+    /// Returns `false` if it was already pinned.
+    pub async fn pin(&self, memory_id: &str) -> Result<bool> {
+        self.pins.pin(GLOBAL_PIN_SCOPE, memory_id).await
+    }
+
+    /// Unpin a memory pinned with [`pin`](Self::pin)
+    pub async fn unpin(&self, memory_id: &str) -> Result<bool> {
+        self.pins.unpin(GLOBAL_PIN_SCOPE, memory_id).await
+    }
+
+    /// Pin a memory so it leads search results in one scope, such as an agent
+    pub async fn pin_in_scope(&self, scope: &str, memory_id: &str) -> Result<bool> {
+        self.pins.pin(scope, memory_id).await
+    }
+
+    /// Unpin a memory pinned with [`pin_in_scope`](Self::pin_in_scope)
+    pub async fn unpin_in_scope(&self, scope: &str, memory_id: &str) -> Result<bool> {
+        self.pins.unpin(scope, memory_id).await
+    }
+
+    /// Get the memories pinned for a scope: global pins, then the scope's own
+    ///
+    /// `None` gives only the global pins.
+    pub async fn pinned_memories(&self, scope: Option<&str>) -> Result<Vec<Memory>> {
+        self.pins.pinned(scope).await
+    }
+
+    /// List every scope's pins
+    pub async fn pin_lists(&self) -> Result<Vec<PinList>> {
+        self.pins.list().await
+    }
+
+    /// Search, with the memories pinned for a scope leading the results
+    ///
+    /// Pinned memories come first, in pin order and without a score, whatever
+    /// the query or filter; they are dropped from further down the results.
+    /// `limit` applies to the search matches, with pinned memories added on
+    /// top of them.
+    pub async fn search_with_pins(
+        &self,
+        query_text: &str,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+        scope: Option<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        let results = self.search(query_text, limit, filter, search_mode).await?;
+        self.pins.pin_results(results, scope).await
+    }
+
+    // =============================================================================
+    // Template Operations (delegated to TemplateRegistry)
+    // =============================================================================
+
+    /// Register a memory template, replacing any registered under the same name
+    pub async fn register_template(&self, template: MemoryTemplate) -> Result<MemoryTemplate> {
+        self.templates.register(template).await
+    }
+
+    /// Find a built-in or registered template by name
+    pub async fn get_template(&self, name: &str) -> Result<Option<MemoryTemplate>> {
+        self.templates.get(name).await
+    }
+
+    /// List built-in and registered templates, by name
+    pub async fn list_templates(&self) -> Result<Vec<MemoryTemplate>> {
+        self.templates.list().await
+    }
+
+    /// Remove a registered template; built-in templates can't be removed
+    pub async fn remove_template(&self, name: &str) -> Result<bool> {
+        self.templates.remove(name).await
+    }
+
+    /// Store a memory made from a template
+    ///
+    /// See [`MemoryTemplate::render`] for how variables are checked.
+    ///
+    /// ```no_run
+    /// use serde_json::json;
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let id = manager
+    ///     .remember_from_template(
+    ///         "meeting",
+    ///         [("title", json!("Standup")), ("date", json!("2025-11-03"))],
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn remember_from_template<K, V>(
+        &self,
+        name: &str,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<String>
+    where
+        K: Into<String>,
+        V: Into<serde_json::Value>,
+    {
+        let template = self
+            .get_template(name)
+            .await?
+            .ok_or_else(|| LocaiError::Template(format!("No template named '{}'", name)))?;
+        let mut memory = template.render(vars)?;
+        memory.id = self.memory_ops.generate_id(&memory.content);
+        self.store_memory(memory).await
+    }
+
+    // =============================================================================
+    // Reminder Operations (delegated to ReminderStore)
+    // =============================================================================
+
+    /// Set a memory's reminder, replacing any it had
+    ///
+    /// ```no_run
+    /// use chrono::{Duration, Utc};
+    /// use locai::memory::{Recurrence, Reminder};
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager, id: &str) -> locai::Result<()> {
+    /// let reminder = Reminder::new(id, Utc::now() + Duration::days(7))
+    ///     .with_recurrence(Recurrence::Weekly);
+    /// manager.set_reminder(reminder).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_reminder(&self, reminder: Reminder) -> Result<Reminder> {
+        self.reminders.set(reminder).await
+    }
+
+    /// Get a memory's reminder
+    pub async fn get_reminder(&self, memory_id: &str) -> Result<Option<Reminder>> {
+        self.reminders.get(memory_id).await
+    }
+
+    /// List all reminders, soonest first
+    pub async fn list_reminders(&self) -> Result<Vec<Reminder>> {
+        self.reminders.list().await
+    }
+
+    /// Remove a memory's reminder, returning whether it had one
+    pub async fn cancel_reminder(&self, memory_id: &str) -> Result<bool> {
+        self.reminders.cancel(memory_id).await
+    }
+
+    /// Put off the current occurrence of a memory's reminder
+    pub async fn snooze_reminder(
+        &self,
+        memory_id: &str,
+        until: DateTime<Utc>,
+    ) -> Result<Option<Reminder>> {
+        self.reminders.snooze(memory_id, until).await
+    }
+
+    /// Fire the reminders that are due, running `on_memory_reminder` hooks
+    ///
+    /// Call this periodically, or let
+    /// [`LocaiMessaging::start_reminders`](crate::messaging::LocaiMessaging::start_reminders)
+    /// do it.
+    pub async fn fire_due_reminders(&self) -> Result<Vec<ReminderEvent>> {
+        let events = self.reminders.fire_due(self.clock().now()).await?;
+        if let Some(hooks) = self.hook_registry() {
+            for event in &events {
+                if let Err(e) = hooks.execute_on_reminder(&event.memory).await {
+                    tracing::warn!("Reminder hooks failed for {}: {}", event.memory.id, e);
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    // =============================================================================
+    // Task Operations (delegated to TaskStore)
+    // =============================================================================
+
+    /// Store a new task
+    ///
+    /// ```no_run
+    /// use chrono::{Duration, Utc};
+    /// use locai::memory::{Task, TaskStatus};
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let task = manager
+    ///     .create_task(Task::new("Draft the release notes").assigned_to("sam"))
+    ///     .await?;
+    /// manager.set_task_status(&task.id, TaskStatus::InProgress).await?;
+    /// let open = manager.open_tasks(Some("sam")).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_task(&self, task: Task) -> Result<Task> {
+        let id = self.store_memory(task.to_memory()).await?;
+        self.tasks
+            .get(&id)
+            .await?
+            .ok_or_else(|| LocaiError::Task(format!("Task {} was not stored", id)))
+    }
+
+    /// Get a task; `None` if the memory doesn't exist or isn't a task
+    pub async fn get_task(&self, id: &str) -> Result<Option<Task>> {
+        self.tasks.get(id).await
+    }
+
+    /// List tasks matching a query, soonest due first
+    pub async fn list_tasks(&self, query: &TaskQuery) -> Result<Vec<Task>> {
+        self.tasks.list(query).await
+    }
+
+    /// Tasks that are open or in progress, optionally only those assigned to `assignee`
+    pub async fn open_tasks(&self, assignee: Option<&str>) -> Result<Vec<Task>> {
+        let mut query = TaskQuery::open();
+        query.assignee = assignee.map(str::to_string);
+        self.tasks.list(&query).await
+    }
+
+    /// Open tasks past their due date
+    pub async fn overdue_tasks(&self) -> Result<Vec<Task>> {
+        self.tasks
+            .list(&TaskQuery::open().due_before(self.clock().now()))
+            .await
+    }
+
+    /// Move a task to another status
+    ///
+    /// See [`TaskStatus::can_become`] for the allowed moves. A task can't be
+    /// started or completed while a dependency is unfinished.
+    pub async fn set_task_status(&self, id: &str, status: TaskStatus) -> Result<Option<Task>> {
+        self.tasks.set_status(id, status).await
+    }
+
+    /// Assign a task, or unassign it with `None`
+    pub async fn assign_task(&self, id: &str, assignee: Option<&str>) -> Result<Option<Task>> {
+        self.tasks.assign(id, assignee.map(str::to_string)).await
+    }
+
+    /// Set or clear a task's due date
+    pub async fn set_task_due(
+        &self,
+        id: &str,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Task>> {
+        self.tasks.set_due(id, due_at).await
+    }
+
+    /// Make a task wait on another, returning whether it didn't already
+    pub async fn add_task_dependency(&self, id: &str, depends_on: &str) -> Result<bool> {
+        self.tasks.add_dependency(id, depends_on).await
+    }
+
+    /// Stop a task waiting on another, returning whether it was
+    pub async fn remove_task_dependency(&self, id: &str, depends_on: &str) -> Result<bool> {
+        self.tasks.remove_dependency(id, depends_on).await
+    }
+
+    /// The tasks a task waits on
+    pub async fn task_dependencies(&self, id: &str) -> Result<Vec<Task>> {
+        self.tasks.dependencies(id).await
+    }
+
+    /// The dependencies of a task that are still unfinished
+    pub async fn task_blockers(&self, id: &str) -> Result<Vec<Task>> {
+        self.tasks.blockers(id).await
+    }
+
+    // =============================================================================
+    // Procedure Operations (delegated to ProcedureStore)
+    // =============================================================================
+
+    /// Store a new procedure
+    ///
+    /// Fails if the procedure has no name or no steps.
+    ///
+    /// ```no_run
+    /// use locai::memory::{Procedure, ProcedureStep};
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let procedure = Procedure::new("Rotate API keys", "Replace a leaked API key")
+    ///     .precondition("Admin access to the key vault")
+    ///     .step("Create a new key in the vault")
+    ///     .step(ProcedureStep::new("Deploy the new key").expecting("Health checks pass"))
+    ///     .step("Revoke the old key")
+    ///     .success_metric("No requests signed with the old key");
+    /// manager.create_procedure(procedure).await?;
+    ///
+    /// for found in manager.find_procedures_for("the API key leaked", None).await? {
+    ///     println!("{} ({:.2})", found.procedure.name, found.score);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_procedure(&self, procedure: Procedure) -> Result<Procedure> {
+        procedure.validate()?;
+        let id = self.store_memory(procedure.to_memory()).await?;
+        self.procedures
+            .get(&id)
+            .await?
+            .ok_or_else(|| LocaiError::Procedure(format!("Procedure {} was not stored", id)))
+    }
+
+    /// Get a procedure; `None` if the memory doesn't exist or isn't procedural
+    pub async fn get_procedure(&self, id: &str) -> Result<Option<Procedure>> {
+        self.procedures.get(id).await
+    }
+
+    /// List all procedures, by name
+    pub async fn list_procedures(&self) -> Result<Vec<Procedure>> {
+        self.procedures.list().await
+    }
+
+    /// Find the procedures most likely to reach a goal, best first
+    ///
+    /// Ranks by BM25 relevance to the goal combined with the goal's entities
+    /// each procedure is linked to in the graph. Returns 5 matches unless
+    /// `limit` says otherwise.
+    pub async fn find_procedures_for(
+        &self,
+        goal: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ProcedureMatch>> {
+        self.procedures.find_for(goal, limit.unwrap_or(5)).await
+    }
+
+    // =============================================================================
+    // Preference Operations (delegated to PreferenceStore)
+    // =============================================================================
+
+    /// Set a preference for every scope
+    ///
+    /// The last value set wins; earlier values stay in the preference's
+    /// version history.
+    ///
+    /// ```no_run
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// manager.set_preference("tone", "concise").await?;
+    /// manager.set_scoped_preference("agent-7", "tone", "playful").await?;
+    ///
+    /// let preferences = manager.get_preferences(Some("agent-7")).await?;
+    /// assert_eq!(preferences[0].value, "playful");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_preference(&self, key: &str, value: &str) -> Result<Preference> {
+        self.set_scoped_preference(GLOBAL_PREFERENCE_SCOPE, key, value)
+            .await
+    }
+
+    /// Set a preference for one scope, such as a user or an agent
+    ///
+    /// Setting the value a preference already has changes nothing.
+    pub async fn set_scoped_preference(
+        &self,
+        scope: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<Preference> {
+        let preference = Preference::new(scope.trim(), key.trim(), value);
+        preference.validate()?;
+
+        match self
+            .preferences
+            .get(&preference.scope, &preference.key)
+            .await?
+        {
+            Some(existing) if existing.value == value => Ok(existing),
+            Some(existing) => {
+                let updated = self.preferences.replace(&existing, value).await?;
+                if let Some(versions) = self.memory_version_store() {
+                    versions
+                        .create_memory_version(&updated.id, &updated.render(), None)
+                        .await
+                        .map_err(|e| {
+                            LocaiError::Storage(format!("Failed to version preference: {}", e))
+                        })?;
+                }
+                Ok(updated)
+            }
+            None => {
+                let id = self.store_memory(preference.to_memory()).await?;
+                self.preferences.get_by_id(&id).await?.ok_or_else(|| {
+                    LocaiError::Preference(format!("Preference {} was not stored", id))
+                })
+            }
+        }
+    }
+
+    /// Get the value in effect for a key: the scope's own, else the global one
+    pub async fn get_preference(
+        &self,
+        key: &str,
+        scope: Option<&str>,
+    ) -> Result<Option<Preference>> {
+        Ok(self
+            .preferences
+            .effective(scope)
+            .await?
+            .into_iter()
+            .find(|preference| preference.key == key))
+    }
+
+    /// Get a scope's own value for a key, ignoring global preferences
+    pub async fn get_scoped_preference(
+        &self,
+        scope: &str,
+        key: &str,
+    ) -> Result<Option<Preference>> {
+        self.preferences.get(scope, key).await
+    }
+
+    /// Get the preferences in effect for a scope, by key
+    ///
+    /// Global preferences apply unless the scope sets the same key. `None`
+    /// gives only the global preferences.
+    pub async fn get_preferences(&self, scope: Option<&str>) -> Result<Vec<Preference>> {
+        self.preferences.effective(scope).await
+    }
+
+    /// List every scope's own preferences, by scope and key
+    pub async fn list_preferences(&self) -> Result<Vec<Preference>> {
+        self.preferences.list(None).await
+    }
+
+    /// Remove a scope's value for a key, returning whether it had one
+    ///
+    /// A global value for the key applies to the scope again.
+    pub async fn remove_preference(&self, scope: &str, key: &str) -> Result<bool> {
+        self.preferences.remove(scope, key).await
+    }
+
+    /// Get the values a scope's key has had, oldest first
+    ///
+    /// Fails if the storage backend doesn't keep memory versions.
+    pub async fn preference_history(
+        &self,
+        scope: &str,
+        key: &str,
+    ) -> Result<Vec<PreferenceChange>> {
+        let versions = self.memory_version_store().ok_or_else(|| {
+            LocaiError::Preference(
+                "Preference history needs a storage backend with memory versioning".to_string(),
+            )
+        })?;
+        self.preferences.history(versions, scope, key).await
+    }
+
+    // =============================================================================
+    // Persona Operations (delegated to PersonaStore)
+    // =============================================================================
+
+    /// Set an agent's persona, replacing any it had
+    ///
+    /// Earlier revisions stay in the persona's version history. Setting the
+    /// profile the agent already has changes nothing.
+    ///
+    /// ```no_run
+    /// use locai::memory::{ContextOptions, Persona};
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let persona = Persona::new("agent-7", "A release manager for the platform team")
+    ///     .with_trait("methodical")
+    ///     .with_writing_style("short sentences, no jargon")
+    ///     .with_constraint("Never promise a release date");
+    /// manager.set_persona(persona).await?;
+    ///
+    /// let options = ContextOptions::default().with_persona("agent-7");
+    /// let context = manager.build_context("release checklist", &options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_persona(&self, persona: Persona) -> Result<Persona> {
+        let persona = Persona {
+            agent: persona.agent.trim().to_string(),
+            ..persona
+        };
+        persona.validate()?;
+
+        match self.personas.get(&persona.agent).await? {
+            Some(existing) if existing.same_profile(&persona) => Ok(existing),
+            Some(existing) => {
+                let updated = self.personas.replace(&existing, &persona).await?;
+                if let Some(versions) = self.memory_version_store() {
+                    versions
+                        .create_memory_version(&updated.id, &updated.render(), None)
+                        .await
+                        .map_err(|e| {
+                            LocaiError::Storage(format!("Failed to version persona: {}", e))
+                        })?;
+                }
+                Ok(updated)
+            }
+            None => {
+                let id = self.store_memory(persona.to_memory()).await?;
+                self.personas
+                    .get_by_id(&id)
+                    .await?
+                    .ok_or_else(|| LocaiError::Persona(format!("Persona {} was not stored", id)))
+            }
+        }
+    }
+
+    /// Get an agent's persona
+    pub async fn get_persona(&self, agent: &str) -> Result<Option<Persona>> {
+        self.personas.get(agent).await
+    }
+
+    /// List every agent's persona, by agent
+    pub async fn list_personas(&self) -> Result<Vec<Persona>> {
+        self.personas.list().await
+    }
+
+    /// Remove an agent's persona, returning whether it had one
+    pub async fn remove_persona(&self, agent: &str) -> Result<bool> {
+        self.personas.remove(agent).await
+    }
+
+    /// Get the revisions an agent's persona has had, oldest first
+    ///
+    /// Fails if the storage backend doesn't keep memory versions.
+    pub async fn persona_history(&self, agent: &str) -> Result<Vec<Persona>> {
+        let versions = self.memory_version_store().ok_or_else(|| {
+            LocaiError::Persona(
+                "Persona history needs a storage backend with memory versioning".to_string(),
+            )
+        })?;
+        self.personas.history(versions, agent).await
+    }
+
+    // =============================================================================
+    // Rule Operations (delegated to RuleStore)
+    // =============================================================================
+
+    /// Set an automation rule, replacing any with the same name
+    ///
+    /// The rule takes effect on the next event it runs on. Fails with
+    /// [`LocaiError::Rule`] if the script doesn't compile.
+    ///
+    /// ```no_run
+    /// use locai::memory::{Rule, RuleEvent};
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let rule = Rule::new(
+    ///     "escalate-urgent",
+    ///     RuleEvent::Created,
+    ///     r#"if memory.tags.contains("urgent") {
+    ///         memory.priority = "high";
+    ///         send("alerts", memory.content);
+    ///     }"#,
+    /// );
+    /// manager.set_rule(rule).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rhai-rules")]
+    pub async fn set_rule(&self, rule: Rule) -> Result<Rule> {
+        let rule = Rule {
+            name: rule.name.trim().to_string(),
+            ..rule
+        };
+        self.rules.check(&rule)?;
+
+        match self.rules.get(&rule.name).await? {
+            Some(existing) => self.rules.replace(&existing, &rule).await,
+            None => {
+                let id = self.store_memory(rule.to_memory()).await?;
+                self.rules.invalidate();
+                self.rules
+                    .get_by_id(&id)
+                    .await?
+                    .ok_or_else(|| LocaiError::Rule(format!("Rule {} was not stored", id)))
+            }
+        }
+    }
+
+    /// Get a rule by name
+    #[cfg(feature = "rhai-rules")]
+    pub async fn get_rule(&self, name: &str) -> Result<Option<Rule>> {
+        self.rules.get(name).await
+    }
+
+    /// List every rule, by name
+    #[cfg(feature = "rhai-rules")]
+    pub async fn list_rules(&self) -> Result<Vec<Rule>> {
+        self.rules.list().await
+    }
+
+    /// Remove a rule, returning whether there was one
+    #[cfg(feature = "rhai-rules")]
+    pub async fn remove_rule(&self, name: &str) -> Result<bool> {
+        self.rules.remove(name).await
+    }
+
+    /// Run `rule` on a memory as if `event` had happened, without saving
+    /// changes or sending messages
+    #[cfg(feature = "rhai-rules")]
+    pub fn test_rule(&self, rule: &Rule, event: RuleEvent, memory: &Memory) -> Result<RuleOutcome> {
+        self.rules.dry_run(rule, event, memory)
+    }
+
+    /// Run the rules for `event` on a memory, saving the changes they make
+    /// and sending their messages
+    ///
+    /// [`enable_rules`](Self::enable_rules) calls this for every memory
+    /// event.
+    #[cfg(feature = "rhai-rules")]
+    pub async fn run_rules(
+        self: &Arc<Self>,
+        event: RuleEvent,
+        memory: &Memory,
+    ) -> Result<RuleOutcome> {
+        let outcome = self.rules.evaluate(event, memory).await?;
+        if let Some(changed) = &outcome.memory {
+            self.update_memory(changed.clone()).await?;
+        }
+        for message in &outcome.messages {
+            crate::messaging::embedded::send_message(
+                self,
+                crate::memory::rules::RULES_NAMESPACE,
+                crate::memory::rules::RULES_APP_ID,
+                &message.topic,
+                message.content.clone(),
+            )
+            .await?;
+        }
+        Ok(outcome)
+    }
+
+    /// Run rules on every event of this manager's memories
+    ///
+    /// Fails if the storage backend doesn't run hooks.
+    #[cfg(feature = "rhai-rules")]
+    pub async fn enable_rules(self: &Arc<Self>) -> Result<()> {
+        let hooks = self.hook_registry().ok_or_else(|| {
+            LocaiError::Rule("Rules need a storage backend that runs hooks".to_string())
+        })?;
+        hooks.register(Arc::new(RuleHook::new(self))).await;
+        Ok(())
+    }
+
+    /// Run the automations in the `rules` configuration on every event of
+    /// this manager's memories
+    ///
+    /// [`crate::init`] calls this when any are configured. Fails if the
+    /// storage backend doesn't run hooks.
+    pub async fn enable_automations(&self) -> Result<()> {
+        let hooks = self.hook_registry().ok_or_else(|| {
+            LocaiError::Rule("Automations need a storage backend that runs hooks".to_string())
+        })?;
+        let hook = AutomationHook::new(
+            self.config.rules.automations.clone(),
+            Arc::clone(&self.memory_ops.storage),
+        );
+        hooks.register(Arc::new(hook)).await;
+        Ok(())
+    }
+
+    /// Send notifications for review queue items and reminders
+    ///
+    /// [`crate::init`] calls this when notifications are enabled and the
+    /// storage backend runs hooks. Fails if it doesn't.
+    pub async fn enable_notifications(&self) -> Result<()> {
+        let hooks = self.hook_registry().ok_or_else(|| {
+            LocaiError::Notification(
+                "Notifications need a storage backend that runs hooks".to_string(),
+            )
+        })?;
+        hooks
+            .register(Arc::new(NotificationHook::new(Arc::clone(
+                &self.notifications,
+            ))))
+            .await;
+        Ok(())
+    }
+
+    // =============================================================================
+    // Review Operations (delegated to ReviewQueue)
+    // =============================================================================
+
+    /// Every memory waiting for review, oldest first
+    pub async fn list_pending_reviews(&self) -> Result<Vec<ReviewItem>> {
+        self.memory_ops.review().list().await
+    }
+
+    /// The memory with this ID, if it's waiting for review
+    pub async fn get_pending_review(&self, memory_id: &str) -> Result<Option<ReviewItem>> {
+        self.memory_ops.review().get(memory_id).await
+    }
+
+    /// Store a memory waiting for review, returning its ID
+    ///
+    /// The memory passes through the store pipeline as any other, committed
+    /// together with the outbox messages held with it, and leaves the queue
+    /// once it's stored. Fails with [`LocaiError::Review`] if no memory with
+    /// this ID is waiting.
+    pub async fn approve_memory(&self, memory_id: &str) -> Result<String> {
+        let review = self.memory_ops.review();
+        let item = review.get(memory_id).await?.ok_or_else(|| {
+            LocaiError::Review(format!("Memory {} is not waiting for review", memory_id))
+        })?;
+        let id = self
+            .memory_ops
+            .store_approved(item.memory.clone(), item.outbox.clone())
+            .await?;
+        review.remove(&item).await?;
+        tracing::info!("Approved memory {}", id);
+        Ok(id)
+    }
+
+    /// Drop a memory waiting for review, returning whether it was waiting
+    pub async fn reject_memory(&self, memory_id: &str) -> Result<bool> {
+        let review = self.memory_ops.review();
+        match review.get(memory_id).await? {
+            Some(item) => {
+                review.remove(&item).await?;
+                tracing::info!("Rejected memory {}", memory_id);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // =============================================================================
+    // Session Operations (delegated to SessionStore)
+    // =============================================================================
+
+    /// Add a turn to a session's conversation
+    ///
+    /// With a summarizer attached and automatic summaries on, a session with
+    /// `keep_recent + summarize_every` turns not yet summarized, or with more
+    /// than `max_pending_tokens` in them, is summarized right away; see
+    /// [`summarize_session`](Self::summarize_session).
+    ///
+    /// ```no_run
+    /// use locai::memory::ContextOptions;
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// manager.add_turn("support-42", "user", "The export keeps timing out").await?;
+    /// manager.add_turn("support-42", "assistant", "How large is the export?").await?;
+    ///
+    /// let options = ContextOptions::default().for_session("support-42");
+    /// let context = manager.build_context("export timeout", &options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_turn(&self, session: &str, role: &str, content: &str) -> Result<Turn> {
+        let session = session.trim();
+        let role = role.trim();
+        if session.is_empty() || role.is_empty() {
+            return Err(LocaiError::Session(
+                "A turn needs a session and a role".to_string(),
+            ));
+        }
+        if content.trim().is_empty() {
+            return Err(LocaiError::Session(format!(
+                "A turn in session '{}' needs content",
+                session
+            )));
+        }
+
+        let turns = self.sessions.turns(session).await?;
+        let turn = Turn {
+            index: turns.last().map_or(1, |last| last.index + 1),
+            ..Turn::new(session, role, content)
+        };
+        self.store_memory(turn.to_memory()).await?;
+
+        let config = &self.config.sessions;
+        let pending: Vec<&Turn> = turns
+            .iter()
+            .filter(|turn| !turn.summarized)
+            .chain(std::iter::once(&turn))
+            .collect();
+        let too_long = config.max_pending_tokens > 0
+            && pending.len() > config.keep_recent
+            && pending
+                .iter()
+                .map(|turn| self.token_counter.count(&turn.render()))
+                .sum::<usize>()
+                > config.max_pending_tokens;
+        if self.summarizer.is_some()
+            && config.summarize_every > 0
+            && (pending.len() >= config.keep_recent + config.summarize_every || too_long)
+        {
+            self.summarize_session(session).await?;
+        }
+        Ok(turn)
+    }
+
+    /// Fold a session's turns, all but the newest `keep_recent`, into its
+    /// summary
+    ///
+    /// The configured [`Summarizer`] gets the previous summary and the turns
+    /// not yet summarized; what it returns is stored as the new summary and
+    /// those turns are demoted to low priority. Demoted turns stay stored but
+    /// are left out of built contexts.
+    ///
+    /// # Returns
+    /// The new summary; `None` if no turns were left to fold
+    pub async fn summarize_session(&self, session: &str) -> Result<Option<SessionSummary>> {
+        let summarizer = self.summarizer.as_ref().ok_or_else(|| {
+            LocaiError::Session(
+                "No summarizer configured; attach one with MemoryManager::with_summarizer"
+                    .to_string(),
+            )
+        })?;
+
+        let mut turns = self.sessions.turns(session).await?;
+        turns.retain(|turn| !turn.summarized);
+        let fold = turns.len().saturating_sub(self.config.sessions.keep_recent);
+        if fold == 0 {
+            return Ok(None);
+        }
+        let turns = &turns[..fold];
+
+        let previous = self.sessions.summary(session).await?;
+        let content = summarizer
+            .summarize(
+                previous.as_ref().map(|summary| summary.content.as_str()),
+                turns,
+            )
+            .await?;
+        if content.trim().is_empty() {
+            return Err(LocaiError::Session(format!(
+                "Summarizer '{}' returned an empty summary",
+                summarizer.name()
+            )));
+        }
+
+        let summary = SessionSummary::new(session, content.trim(), turns[fold - 1].index);
+        self.store_memory(summary.to_memory()).await?;
+        for turn in turns {
+            self.sessions.demote(turn).await?;
+        }
+        Ok(Some(summary))
+    }
+
+    /// Get a session's turns, oldest first, summarized ones included
+    pub async fn session_turns(&self, session: &str) -> Result<Vec<Turn>> {
+        self.sessions.turns(session).await
+    }
+
+    /// Get a session's latest summary
+    pub async fn session_summary(&self, session: &str) -> Result<Option<SessionSummary>> {
+        self.sessions.summary(session).await
+    }
+
+    // =============================================================================
+    // Context Building
+    // =============================================================================
+
+    /// Gather the context an agent needs to answer `query`
+    ///
+    /// The agent's persona comes first when `options` asks for one, then the
+    /// preferences in effect for the scope, the session's summary and recent
+    /// turns when it names a session, the memories pinned there and the best
+    /// search matches. Persona, preference and session memories found by the
+    /// search are left out, as they are already listed on their own, and so
+    /// are turns already folded into a summary. With no query, the context
+    /// holds no search matches.
+    ///
+    /// ```no_run
+    /// use locai::memory::ContextOptions;
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let options = ContextOptions::default().for_scope("agent-7").max_tokens(1000);
+    /// let context = manager.build_context("release checklist", &options).await?;
+    /// println!("{}", context.render());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build_context(
+        &self,
+        query: &str,
+        options: &ContextOptions,
+    ) -> Result<MemoryContext> {
+        let scope = options.scope.as_deref();
+        let persona = match &options.persona {
+            Some(agent) => self.personas.get(agent).await?,
+            None => None,
+        };
+        let preferences = if options.include_preferences {
+            self.preferences.effective(scope).await?
+        } else {
+            Vec::new()
+        };
+        let (summary, turns) = match &options.session {
+            Some(session) => {
+                let mut turns = self.sessions.turns(session).await?;
+                turns.retain(|turn| !turn.summarized);
+                (self.sessions.summary(session).await?, turns)
+            }
+            None => (None, Vec::new()),
+        };
+
+        let mut memories = if query.trim().is_empty() {
+            lead_with_pinned(self.pinned_memories(scope).await?, Vec::new())
+        } else {
+            self.search_with_pins(query, Some(options.limit), None, options.search_mode, scope)
+                .await?
+        };
+        memories.retain(|result| {
+            result.memory.memory_type != MemoryType::Preference
+                && Persona::from_memory(&result.memory).is_none()
+                && !sessions::is_summarized_turn(&result.memory)
+                && options
+                    .session
+                    .as_deref()
+                    .is_none_or(|session| !sessions::in_session(&result.memory, session))
+        });
+
+        let context = MemoryContext {
+            persona,
+            preferences,
+            summary,
+            turns,
+            memories,
+        };
+        Ok(match options.max_tokens {
+            Some(max_tokens) => context.fit_to_budget_with(max_tokens, self.token_counter.as_ref()),
+            None => context,
+        })
+    }
+
+    // =============================================================================
+    // Reflection
+    // =============================================================================
+
+    /// Reflect on the memories from a time range and store the insights drawn
+    ///
+    /// The memories created in `time_range`, or with a `topic` those matching
+    /// it, are handed to the configured [`Reflector`], newest first and at most
+    /// [`MAX_REFLECTION_MEMORIES`] of them. Each insight it returns is stored
+    /// as a [`MemoryType::Wisdom`] memory with a `derived_from` relationship to
+    /// each memory it cites. Earlier insights in the range are reflected on
+    /// too, so insights can build on one another.
+    ///
+    /// ```no_run
+    /// use locai::memory::TimeRange;
+    ///
+    /// # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+    /// let insights = manager
+    ///     .reflect(&TimeRange::last_days(1), Some("deploys"))
+    ///     .await?;
+    /// for insight in insights {
+    ///     println!("{}", insight.content);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Returns
+    /// The stored insight memories; none if the range holds no memories
+    pub async fn reflect(
+        &self,
+        time_range: &TimeRange,
+        topic: Option<&str>,
+    ) -> Result<Vec<Memory>> {
+        let reflector = self.reflector.as_ref().ok_or_else(|| {
+            LocaiError::Reflection(
+                "No reflector configured; attach one with MemoryManager::with_reflector"
+                    .to_string(),
+            )
+        })?;
+        let topic = topic.map(str::trim).filter(|topic| !topic.is_empty());
+
+        let filter = MemoryFilter {
+            created_after: Some(time_range.start),
+            created_before: Some(time_range.end),
+            ..Default::default()
+        };
+        let mut memories = match topic {
+            Some(topic) => {
+                let filter = SemanticSearchFilter {
+                    memory_filter: Some(filter),
+                    ..Default::default()
+                };
+                self.search(
+                    topic,
+                    Some(MAX_REFLECTION_MEMORIES),
+                    Some(filter),
+                    SearchMode::Text,
+                )
+                .await?
+                .into_iter()
+                .map(|result| result.memory)
+                .collect()
+            }
+            None => self.filter_memories(filter, None, None, None).await?,
+        };
+        memories.retain(|memory| time_range.contains(memory.created_at));
+        memories.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        memories.truncate(MAX_REFLECTION_MEMORIES);
+        if memories.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let given: HashSet<&str> = memories.iter().map(|memory| memory.id.as_str()).collect();
+        let mut stored = Vec::new();
+        for mut insight in reflector.reflect(topic, &memories).await? {
+            if insight.content.trim().is_empty() {
+                continue;
+            }
+            let mut cited = HashSet::new();
+            insight
+                .sources
+                .retain(|id| given.contains(id.as_str()) && cited.insert(id.clone()));
+
+            let id = self
+                .store_memory(insight.to_memory(topic, time_range, reflector.name()))
+                .await?;
+            for source in &insight.sources {
+                self.storage()
+                    .create_relationship(derived_from(&id, source))
+                    .await
+                    .map_err(|e| {
+                        LocaiError::Storage(format!("Failed to link insight to source: {}", e))
+                    })?;
+            }
+            if let Some(memory) = self.get_memory(&id).await? {
+                stored.push(memory);
+            }
+        }
+        Ok(stored)
+    }
+
+    // =============================================================================
+    // Messaging Operations (delegated to MessagingIntegration)
+    // =============================================================================
+
+    /// Subscribe to memory changes with live queries (for messaging system)
+    pub async fn subscribe_to_memory_changes(
+        &self,
+        filter: MemoryFilter,
+    ) -> Result<std::pin::Pin<Box<dyn futures::Stream<Item = Result<Memory>> + Send>>> {
+        self.messaging.subscribe_to_memory_changes(filter).await
+    }
+
+    /// Store a message as a memory record (specialized method for messaging)
+    pub async fn store_message(
+        &self,
+        message: &crate::messaging::types::Message,
+    ) -> Result<String> {
+        self.messaging
+            .store_message(message, &self.memory_ops)
+            .await
+    }
+
+    /// Get message history (specialized method for messaging)
+    pub async fn get_message_history(
+        &self,
+        filter: &crate::messaging::types::MessageFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<crate::messaging::types::Message>> {
+        self.messaging.get_message_history(filter, limit).await
+    }
+
+    /// Apply the configured message retention policies now
+    ///
+    /// The same policies are also enforced in the background every
+    /// `messaging.retention_interval_secs`.
+    pub async fn enforce_message_retention(
+        &self,
+    ) -> Result<crate::messaging::retention::RetentionReport> {
+        self.messaging.enforce_retention().await
+    }
+
+    // =============================================================================
+    // Transactional Outbox
+    // =============================================================================
+
+    /// Deliver pending outbox messages, oldest first
+    ///
+    /// Each message is acknowledged once `deliver` succeeds. Delivery stops at
+    /// the first failure so messages are never reordered; that message and
+    /// any after it stay pending for the next relay. Messages pending after a
+    /// crash are delivered by the first relay after restart, so subscribers
+    /// may see a message more than once but never miss one.
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of messages to deliver
+    /// * `deliver` - Publishes one message
+    ///
+    /// # Returns
+    /// The number of messages delivered
+    pub async fn relay_outbox<F>(&self, limit: usize, mut deliver: F) -> Result<usize>
+    where
+        F: FnMut(&OutboxMessage) -> Result<()>,
+    {
+        let _relay = self.outbox_relay.lock().await;
+        let storage = self.memory_ops.storage();
+        let pending = storage
+            .pending_outbox(limit)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to read outbox: {}", e)))?;
+
+        let mut delivered = 0;
+        for message in &pending {
+            deliver(message)?;
+            storage.ack_outbox(&message.id).await.map_err(|e| {
+                LocaiError::Storage(format!("Failed to acknowledge outbox message: {}", e))
+            })?;
+            delivered += 1;
+        }
+        Ok(delivered)
+    }
+
+    /// Get undelivered outbox messages, oldest first
+    pub async fn pending_outbox(&self, limit: usize) -> Result<Vec<OutboxMessage>> {
+        self.memory_ops
+            .storage()
+            .pending_outbox(limit)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to read outbox: {}", e)))
+    }
+
+    // =============================================================================
+    // Configuration and Utility Methods
+    // =============================================================================
 
     /// Get the configuration for this memory manager
     pub fn config(&self) -> &LocaiConfig {
         &self.config
     }
 
+    /// The clock time-dependent operations read, from the configuration
+    pub fn clock(&self) -> &ClockHandle {
+        &self.config.clock
+    }
+
+    /// A builder for a memory with `content`, named by the configured
+    /// [`IdStrategy`](crate::ids::IdStrategy)
+    ///
+    /// Memories built with [`MemoryBuilder::new_with_content`] get random IDs
+    /// whatever the strategy; build through this to store them under
+    /// reproducible ones.
+    pub fn memory_builder(&self, content: impl Into<String>) -> MemoryBuilder {
+        self.memory_ops.memory_builder(content)
+    }
+
+    /// A new ID by the configured [`IdStrategy`](crate::ids::IdStrategy), for
+    /// a record with `content`
+    pub fn generate_id(&self, content: &str) -> String {
+        self.memory_ops.generate_id(content)
+    }
+
     /// Check if ML service is available for semantic search
     pub fn has_ml_service(&self) -> bool {
         self.memory_ops.has_ml_service()
     }
 
+    /// Attach an embedding provider
+    ///
+    /// Memories stored without an embedding are embedded with the provider, and
+    /// vector/hybrid searches without a query embedding embed the query text.
+    /// Explicitly supplied embeddings always take precedence.
+    pub fn with_embedding_provider(mut self, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        self.memory_ops
+            .set_embedding_provider(Some(Arc::clone(&provider)));
+        self.search.set_embedding_provider(Some(provider));
+        // Builders hold their own copy of the operations handler
+        self.builders = MemoryBuilders::new(Arc::new(self.memory_ops.clone()));
+        self
+    }
+
+    /// Get the configured embedding provider, if any
+    pub fn embedding_provider(&self) -> Option<&Arc<dyn EmbeddingProvider>> {
+        self.memory_ops.embedding_provider()
+    }
+
+    /// Add a stage to the store pipeline every stored memory passes through
+    ///
+    /// It runs where [`PipelineConfig::stages`](crate::config::PipelineConfig::stages)
+    /// lists its name, or at the `hooks` stage if the list doesn't. See
+    /// [`crate::memory::pipeline`].
+    pub fn with_store_middleware(mut self, middleware: Arc<dyn StoreMiddleware>) -> Self {
+        self.memory_ops.add_store_middleware(middleware);
+        self.builders = MemoryBuilders::new(Arc::new(self.memory_ops.clone()));
+        self
+    }
+
+    /// Extract entities from stored memories with `extractor` as well as
+    /// the configured extractors
+    ///
+    /// Like those, it only runs while entity extraction is enabled.
+    pub fn with_entity_extractor(mut self, extractor: Arc<dyn EntityExtractor>) -> Self {
+        self.memory_ops.add_entity_extractor(extractor);
+        self.builders = MemoryBuilders::new(Arc::new(self.memory_ops.clone()));
+        self
+    }
+
+    /// The stages every stored memory passes through, in order
+    pub fn store_pipeline(&self) -> &StorePipeline {
+        self.memory_ops.pipeline()
+    }
+
+    /// Attach a reranker used when searches request `rerank_top_k`
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
+    /// Get the configured reranker, if any
+    pub fn reranker(&self) -> Option<&Arc<dyn Reranker>> {
+        self.reranker.as_ref()
+    }
+
+    /// Attach a query transformer whose rewrite is searched alongside the query
+    pub fn with_query_transformer(mut self, transformer: Arc<dyn QueryTransformer>) -> Self {
+        self.query_transformer = Some(transformer);
+        self
+    }
+
+    /// Get the configured query transformer, if any
+    pub fn query_transformer(&self) -> Option<&Arc<dyn QueryTransformer>> {
+        self.query_transformer.as_ref()
+    }
+
+    /// Attach a reflector used by `reflect` to draw insights from memories
+    pub fn with_reflector(mut self, reflector: Arc<dyn Reflector>) -> Self {
+        self.reflector = Some(reflector);
+        self
+    }
+
+    /// Get the configured reflector, if any
+    pub fn reflector(&self) -> Option<&Arc<dyn Reflector>> {
+        self.reflector.as_ref()
+    }
+
+    /// Attach a summarizer used to fold older session turns into summaries
+    pub fn with_summarizer(mut self, summarizer: Arc<dyn Summarizer>) -> Self {
+        self.summarizer = Some(summarizer);
+        self
+    }
+
+    /// Get the configured summarizer, if any
+    pub fn summarizer(&self) -> Option<&Arc<dyn Summarizer>> {
+        self.summarizer.as_ref()
+    }
+
+    /// Count tokens with `counter` instead of the configured tokenizer
+    ///
+    /// Context budgets, chunking and session summary triggers all use it.
+    pub fn with_token_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.memory_ops.set_token_counter(Arc::clone(&counter));
+        self.builders = MemoryBuilders::new(Arc::new(self.memory_ops.clone()));
+        self.token_counter = counter;
+        self
+    }
+
+    /// Get the token counter used for every token budget
+    pub fn token_counter(&self) -> &Arc<dyn TokenCounter> {
+        &self.token_counter
+    }
+
+    /// Split text into chunks of at most `max_tokens`, each repeating up to
+    /// `overlap` tokens from the end of the one before
+    pub fn chunk_text(&self, text: &str, max_tokens: usize, overlap: usize) -> Vec<String> {
+        tokens::chunk_text(self.token_counter.as_ref(), text, max_tokens, overlap)
+    }
+
     /// Get access to the underlying storage service
     pub fn storage(&self) -> &Arc<dyn crate::storage::traits::GraphStore> {
         self.memory_ops.storage()
     }
 
+    /// What a store opened over a locked database is serving; `None` when
+    /// storage opened normally
+    pub fn degraded_status(&self) -> Option<DegradedStatus> {
+        self.storage()
+            .as_any()
+            .downcast_ref::<DegradedStorage>()
+            .map(DegradedStorage::status)
+    }
+
     /// Clear all data from the storage
     pub async fn clear_storage(&self) -> Result<()> {
         self.memory_ops
             .storage()
             .clear_storage()
             .await
-            .map_err(|e| LocaiError::Storage(format!("Failed to clear storage: {}", e)))
+            .map_err(|e| LocaiError::Storage(format!("Failed to clear storage: {}", e)))?;
+        self.memory_ops.quotas().invalidate().await;
+        Ok(())
     }
 
     /// Get the hook registry for registering memory hooks
@@ -747,7 +3099,7 @@ impl MemoryManager {
 
         // Try local storage first
         if let Some(shared_storage) =
-            storage_any.downcast_ref::<Arc<SharedStorage<surrealdb::engine::local::Db>>>()
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::local::Db>>()
         {
             return Some(shared_storage.hook_registry());
         }
@@ -755,13 +3107,61 @@ impl MemoryManager {
         // Try remote storage (only if remote feature is enabled)
         #[cfg(feature = "surrealdb-remote")]
         if let Some(shared_storage) =
-            storage_any.downcast_ref::<Arc<SharedStorage<surrealdb::engine::remote::ws::Client>>>()
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::remote::ws::Client>>()
         {
             return Some(shared_storage.hook_registry());
         }
 
         None
     }
+
+    /// Get the memory versioning store (versions, snapshots, restores)
+    ///
+    /// Returns None if the storage backend doesn't support memory versioning
+    pub fn memory_version_store(&self) -> Option<&dyn crate::storage::traits::MemoryVersionStore> {
+        use crate::storage::shared_storage::SharedStorage;
+
+        let storage_any = self.memory_ops.storage.as_any();
+
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::local::Db>>()
+        {
+            return Some(shared_storage);
+        }
+
+        #[cfg(feature = "surrealdb-remote")]
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::remote::ws::Client>>()
+        {
+            return Some(shared_storage);
+        }
+
+        None
+    }
+
+    /// Get the store of memory and vector content checksums
+    ///
+    /// Returns None if the storage backend doesn't store checksums
+    pub fn checksum_store(&self) -> Option<&dyn crate::storage::traits::ChecksumStore> {
+        use crate::storage::shared_storage::SharedStorage;
+
+        let storage_any = self.memory_ops.storage.as_any();
+
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::local::Db>>()
+        {
+            return Some(shared_storage);
+        }
+
+        #[cfg(feature = "surrealdb-remote")]
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::remote::ws::Client>>()
+        {
+            return Some(shared_storage);
+        }
+
+        None
+    }
 }
 
 #[cfg(test)]
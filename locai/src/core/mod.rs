@@ -1,10 +1,23 @@
 //! Core memory functionality
 
+pub mod api;
+pub mod consistency;
+pub mod integrity;
 pub mod memory_manager;
+#[cfg(feature = "remote")]
+pub mod remote;
 pub mod search;
 pub mod util;
 
+pub use api::MemoryApi;
+pub use consistency::{
+    ConsistencyRepairReport, ConsistencyReport, DanglingRelationship, check_consistency,
+    repair_consistency,
+};
+pub use integrity::{IntegrityRepairReport, repair_storage, verify_storage};
 pub use memory_manager::MemoryManager;
+#[cfg(feature = "remote")]
+pub use remote::RemoteMemoryManager;
 pub use search::{
     MatchInfo, SearchContent, SearchContext, SearchMetadata, SearchOptions, SearchResult,
     SearchStrategy, SearchTypeFilter,
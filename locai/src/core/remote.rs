@@ -0,0 +1,293 @@
+//! [`MemoryApi`] over a locai-server's REST API
+//!
+//! The server assigns memory IDs and doesn't return embeddings, so the ID of
+//! a stored memory is the one [`MemoryApi::store_memory`] returns, and
+//! fetched memories have no embedding. Filter fields the API has no
+//! parameter for (IDs, properties, conditions) are applied to what the
+//! server returns.
+
+use async_trait::async_trait;
+use locai_client::{
+    ClientError, CreateMemory, ListMemories, LocaiClient, Search, SearchMode as RemoteSearchMode,
+    UpdateMemory,
+};
+
+use super::api::MemoryApi;
+use crate::config::RemoteConfig;
+use crate::memory::search_extensions::SearchMode;
+use crate::memory::utils::{matches_memory_filter_detailed, parse_memory_priority};
+use crate::models::{Memory, MemoryType};
+use crate::storage::filters::{MemoryFilter, SemanticSearchFilter, SortOrder};
+use crate::storage::models::SearchResult;
+use crate::{LocaiError, Result};
+
+/// Memories listed when a filter needs checking here rather than on the server
+const MAX_LISTED: usize = 10_000;
+
+/// Memory operations on a locai-server
+#[derive(Debug, Clone)]
+pub struct RemoteMemoryManager {
+    client: LocaiClient,
+}
+
+impl RemoteMemoryManager {
+    /// A manager for the server `config` points at
+    pub fn from_config(config: &RemoteConfig) -> Result<Self> {
+        let mut builder = LocaiClient::builder(&config.url);
+        if let Some(token) = &config.token {
+            builder = builder.token(token);
+        }
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(username, password);
+        }
+        let client = builder.build().map_err(remote_error)?;
+        Ok(Self { client })
+    }
+
+    /// A manager using an existing client
+    pub fn new(client: LocaiClient) -> Self {
+        Self { client }
+    }
+
+    /// The underlying REST client, for endpoints outside [`MemoryApi`]
+    pub fn client(&self) -> &LocaiClient {
+        &self.client
+    }
+
+    /// Memories the server matches to `filter`, checked here for the rest
+    async fn list(&self, filter: &MemoryFilter, limit: Option<usize>) -> Result<Vec<Memory>> {
+        let local = needs_local_filter(filter);
+        let list = ListMemories {
+            size: Some(if local {
+                MAX_LISTED
+            } else {
+                limit.unwrap_or(MAX_LISTED)
+            }),
+            memory_type: filter.memory_type.clone(),
+            tags: filter.tags.as_ref().map(|tags| tags.join(",")),
+            source: filter.source.clone(),
+            content: filter.content.clone(),
+            ..Default::default()
+        };
+        let memories = self
+            .client
+            .list_memories(&list)
+            .await
+            .map_err(remote_error)?
+            .into_iter()
+            .map(from_remote)
+            .filter(|memory| !local || matches_memory_filter_detailed(memory, filter))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        Ok(memories)
+    }
+}
+
+#[async_trait]
+impl MemoryApi for RemoteMemoryManager {
+    async fn store_memory(&self, memory: Memory) -> Result<String> {
+        let create = CreateMemory {
+            content: memory.content,
+            memory_type: Some(memory.memory_type.to_string()),
+            priority: Some(format!("{:?}", memory.priority).to_lowercase()),
+            tags: memory.tags,
+            source: Some(memory.source),
+            expires_at: memory.expires_at,
+            properties: Some(memory.properties),
+            embedding: memory.embedding,
+            idempotency_key: None,
+        };
+        let stored = self
+            .client
+            .create_memory(&create)
+            .await
+            .map_err(remote_error)?;
+        Ok(stored.id)
+    }
+
+    async fn get_memory(&self, id: &str) -> Result<Option<Memory>> {
+        let memory = self.client.get_memory(id).await.map_err(remote_error)?;
+        Ok(memory.map(from_remote))
+    }
+
+    /// An embedding on `memory` replaces the stored one; without one, the
+    /// stored embedding is kept, since fetched memories don't carry it
+    async fn update_memory(&self, memory: Memory) -> Result<bool> {
+        let update = UpdateMemory {
+            content: Some(memory.content),
+            memory_type: Some(memory.memory_type.to_string()),
+            priority: Some(format!("{:?}", memory.priority).to_lowercase()),
+            tags: Some(memory.tags),
+            source: Some(memory.source),
+            expires_at: memory.expires_at,
+            properties: Some(memory.properties),
+            embedding: memory.embedding.map(Some),
+        };
+        match self.client.update_memory(&memory.id, &update).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.is_not_found() => Ok(false),
+            Err(e) => Err(remote_error(e)),
+        }
+    }
+
+    async fn delete_memory(&self, id: &str) -> Result<bool> {
+        match self.client.delete_memory(id).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.is_not_found() => Ok(false),
+            Err(e) => Err(remote_error(e)),
+        }
+    }
+
+    async fn search(
+        &self,
+        query_text: &str,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchResult>> {
+        let mode = match search_mode {
+            SearchMode::Text => RemoteSearchMode::Text,
+            SearchMode::Vector => RemoteSearchMode::Vector,
+            SearchMode::Hybrid => RemoteSearchMode::Hybrid,
+        };
+        let mut search = Search::new(query_text).mode(mode);
+        search.limit = limit;
+
+        let filter = filter.unwrap_or_default();
+        search.threshold = filter.similarity_threshold;
+        if filter.include_archived {
+            search.include_archived = Some(true);
+        }
+        let memory_filter = filter.memory_filter.unwrap_or_default();
+        search.memory_type = memory_filter.memory_type.clone();
+        search.tags = memory_filter.tags.as_ref().map(|tags| tags.join(","));
+        search.created_after = memory_filter.created_after;
+        search.created_before = memory_filter.created_before;
+        if let Some(near) = &memory_filter.near {
+            search = search.near(
+                near.center.latitude,
+                near.center.longitude,
+                near.radius_meters,
+            );
+        }
+
+        let results = self.client.search(&search).await.map_err(remote_error)?;
+        Ok(results
+            .into_iter()
+            .map(|result| SearchResult {
+                memory: from_remote(result.memory),
+                score: result.score,
+            })
+            .filter(|result| matches_memory_filter_detailed(&result.memory, &memory_filter))
+            .collect())
+    }
+
+    /// The server returns memories unsorted, as local storage does
+    async fn filter_memories(
+        &self,
+        filter: MemoryFilter,
+        _sort_by: Option<&str>,
+        _sort_order: Option<SortOrder>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        self.list(&filter, limit).await
+    }
+
+    async fn count_memories(&self, filter: Option<MemoryFilter>) -> Result<usize> {
+        let memories = self.list(&filter.unwrap_or_default(), None).await?;
+        Ok(memories.len())
+    }
+
+    async fn tag_memory(&self, memory_id: &str, tag: &str) -> Result<bool> {
+        let Some(memory) = self
+            .client
+            .get_memory(memory_id)
+            .await
+            .map_err(remote_error)?
+        else {
+            return Ok(false);
+        };
+        if memory.tags.iter().any(|existing| existing == tag) {
+            return Ok(true);
+        }
+        let mut tags = memory.tags;
+        tags.push(tag.to_string());
+        let update = UpdateMemory {
+            tags: Some(tags),
+            ..Default::default()
+        };
+        match self.client.update_memory(memory_id, &update).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.is_not_found() => Ok(false),
+            Err(e) => Err(remote_error(e)),
+        }
+    }
+
+    async fn create_relationship(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: &str,
+    ) -> Result<bool> {
+        let created = self
+            .client
+            .create_relationship(
+                source_id,
+                relationship_type,
+                target_id,
+                serde_json::json!({}),
+            )
+            .await;
+        match created {
+            Ok(_) => Ok(true),
+            Err(e) if e.is_not_found() => Ok(false),
+            Err(e) => Err(remote_error(e)),
+        }
+    }
+}
+
+/// Whether `filter` has fields the list endpoint can't match
+fn needs_local_filter(filter: &MemoryFilter) -> bool {
+    filter.ids.is_some()
+        || filter.created_after.is_some()
+        || filter.created_before.is_some()
+        || filter.properties.is_some()
+        || filter.near.is_some()
+        || filter.conditions.is_some()
+        || filter.custom_filter.is_some()
+}
+
+fn from_remote(memory: locai_client::Memory) -> Memory {
+    Memory {
+        id: memory.id,
+        content: memory.content,
+        memory_type: MemoryType::from_str(&memory.memory_type),
+        created_at: memory.created_at,
+        last_accessed: memory.last_accessed,
+        access_count: memory.access_count,
+        priority: parse_memory_priority(&memory.priority.to_lowercase()),
+        tags: memory.tags,
+        source: memory.source,
+        expires_at: memory.expires_at,
+        properties: memory.properties,
+        related_memories: memory.related_memories,
+        embedding: None,
+    }
+}
+
+fn remote_error(e: ClientError) -> LocaiError {
+    match e {
+        ClientError::Api {
+            status: 401 | 403,
+            message,
+            ..
+        } => LocaiError::Authentication(message),
+        ClientError::Api { message, .. } => LocaiError::Other(message),
+        ClientError::Http(e) if e.is_timeout() => LocaiError::Timeout(e.to_string()),
+        ClientError::Http(e) if e.is_connect() => LocaiError::Connection(e.to_string()),
+        ClientError::InvalidUrl(url) => {
+            LocaiError::Configuration(format!("Invalid remote URL: {}", url))
+        }
+        other => LocaiError::Protocol(other.to_string()),
+    }
+}
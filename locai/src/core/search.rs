@@ -2,6 +2,7 @@
 //!
 //! This module provides unified search capabilities across memories, entities, and graphs.
 
+use crate::memory::query_expansion::QueryExpansionConfig;
 use crate::models::{Memory, MemoryType};
 use crate::storage::models::{Entity, MemoryGraph};
 use chrono::{DateTime, Utc};
@@ -166,6 +167,33 @@ pub struct SearchOptions {
 
     /// Graph traversal depth
     pub graph_depth: u8,
+
+    /// Re-rank the top K first-stage results with the configured reranker
+    ///
+    /// Ignored when no reranker is configured. When set, at least K candidates
+    /// are retrieved even if `limit` is smaller.
+    pub rerank_top_k: Option<usize>,
+
+    /// Expand memory queries with names of entities related to those the
+    /// query mentions (see [`QueryExpansionConfig`])
+    pub query_expansion: Option<QueryExpansionConfig>,
+
+    /// Also search the query as rewritten by the configured query transformer
+    ///
+    /// Ignored when no transformer is configured. Results for the original and
+    /// transformed query are fused.
+    pub transform_query: bool,
+
+    /// Also search archived (cold storage) memories
+    pub include_archived: bool,
+
+    /// Rank memories with this named scoring profile (see
+    /// [`crate::search::profiles`])
+    ///
+    /// Memories are then found by keyword and ranked by the profile whatever
+    /// the strategy; entities and graphs are searched as usual. Fails if no
+    /// profile has this name.
+    pub scoring_profile: Option<String>,
 }
 
 impl Default for SearchOptions {
@@ -178,6 +206,11 @@ impl Default for SearchOptions {
             min_score: None,
             include_context: true,
             graph_depth: 2,
+            rerank_top_k: None,
+            query_expansion: None,
+            transform_query: true,
+            include_archived: false,
+            scoring_profile: None,
         }
     }
 }
@@ -11,10 +11,22 @@ pub fn enabled_features() -> Vec<&'static str> {
     // Storage features
     #[cfg(any(feature = "surrealdb-embedded", feature = "surrealdb-remote"))]
     features.push("surrealdb");
+    #[cfg(feature = "surrealdb-embedded")]
+    features.push("surrealdb-embedded");
+    #[cfg(feature = "surrealdb-remote")]
+    features.push("surrealdb-remote");
+
+    // Embedding providers
+    #[cfg(feature = "fastembed")]
+    features.push("fastembed");
+    #[cfg(feature = "ollama")]
+    features.push("ollama");
 
     // API features
     #[cfg(feature = "http")]
     features.push("http");
+    #[cfg(feature = "remote")]
+    features.push("remote");
 
     // Debugging features
     #[cfg(feature = "tokio-console")]
@@ -37,7 +49,12 @@ pub fn is_feature_enabled(feature: &str) -> bool {
             feature = "surrealdb-embedded",
             feature = "surrealdb-remote"
         )),
+        "surrealdb-embedded" => cfg!(feature = "surrealdb-embedded"),
+        "surrealdb-remote" => cfg!(feature = "surrealdb-remote"),
+        "fastembed" => cfg!(feature = "fastembed"),
+        "ollama" => cfg!(feature = "ollama"),
         "http" => cfg!(feature = "http"),
+        "remote" => cfg!(feature = "remote"),
         "tokio-console" => cfg!(feature = "tokio-console"),
         _ => false,
     }
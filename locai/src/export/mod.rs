@@ -0,0 +1,209 @@
+//! Export memory embeddings to external vector databases
+//!
+//! Locai remains the system of record: [`export_vectors`] copies each memory's
+//! embedding, together with its content and metadata as payload, into a
+//! [`VectorSink`]. Sinks upsert by memory ID, so running the export again
+//! refreshes the external copy instead of duplicating it.
+//!
+//! Sinks for [Qdrant](qdrant) (`qdrant` feature) and [pgvector](pgvector)
+//! (`pgvector` feature) are included; any other database can be targeted by
+//! implementing [`VectorSink`].
+//!
+//! The entity graph itself can be exported as RDF, and queried with triple
+//! patterns, through [`rdf`].
+
+#[cfg(feature = "pgvector")]
+pub mod pgvector;
+#[cfg(feature = "qdrant")]
+pub mod qdrant;
+pub mod rdf;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::core::MemoryManager;
+use crate::models::Memory;
+use crate::storage::filters::MemoryFilter;
+use crate::{LocaiError, Result};
+
+/// A memory's embedding and payload, as written to a vector database
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorRecord {
+    /// Memory ID
+    pub id: String,
+
+    /// Embedding vector
+    pub vector: Vec<f32>,
+
+    /// Memory content and metadata
+    pub payload: Value,
+}
+
+impl VectorRecord {
+    /// Build a record from a memory, or `None` if it has no embedding
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        let vector = memory.embedding.clone().filter(|v| !v.is_empty())?;
+        Some(Self {
+            id: memory.id.clone(),
+            vector,
+            payload: json!({
+                "locai_id": memory.id,
+                "content": memory.content,
+                "memory_type": memory.memory_type.to_string(),
+                "tags": memory.tags,
+                "source": memory.source,
+                "priority": memory.priority,
+                "properties": memory.properties,
+                "created_at": memory.created_at.to_rfc3339(),
+            }),
+        })
+    }
+}
+
+/// Destination of a vector export
+#[async_trait]
+pub trait VectorSink: Send + Sync {
+    /// Name of the sink, used in logs
+    fn name(&self) -> &str;
+
+    /// Create the target collection or table if it does not exist
+    ///
+    /// Called once, before the first upsert, with the dimension of the
+    /// embeddings being exported.
+    async fn prepare(&self, dimensions: usize) -> Result<()>;
+
+    /// Insert records, replacing any with the same ID
+    async fn upsert(&self, records: &[VectorRecord]) -> Result<()>;
+}
+
+/// Options for [`export_vectors`]
+#[derive(Debug, Clone)]
+pub struct VectorExportOptions {
+    /// Memories read and written per batch
+    pub batch_size: usize,
+
+    /// Only export memories matching this filter
+    pub filter: Option<MemoryFilter>,
+}
+
+impl Default for VectorExportOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 256,
+            filter: None,
+        }
+    }
+}
+
+/// Outcome of a vector export
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VectorExportReport {
+    /// Records written to the sink
+    pub exported: usize,
+
+    /// Memories skipped because they have no embedding
+    pub skipped_without_embedding: usize,
+
+    /// Memories skipped because their embedding dimension differs from the
+    /// first one exported
+    pub skipped_dimension_mismatch: usize,
+
+    /// Dimension of the exported embeddings
+    pub dimensions: Option<usize>,
+}
+
+/// Copy memory embeddings and metadata into a vector database
+pub async fn export_vectors(
+    manager: &MemoryManager,
+    sink: &dyn VectorSink,
+    options: VectorExportOptions,
+) -> Result<VectorExportReport> {
+    if options.batch_size == 0 {
+        return Err(LocaiError::Configuration(
+            "Vector export batch_size must be at least 1".to_string(),
+        ));
+    }
+
+    let mut report = VectorExportReport::default();
+    let mut offset = 0;
+    loop {
+        let memories = manager
+            .storage()
+            .list_memories(
+                options.filter.clone(),
+                Some(options.batch_size),
+                Some(offset),
+            )
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list memories: {}", e)))?;
+        offset += memories.len();
+
+        let mut records = Vec::with_capacity(memories.len());
+        for memory in &memories {
+            let Some(record) = VectorRecord::from_memory(memory) else {
+                report.skipped_without_embedding += 1;
+                continue;
+            };
+
+            match report.dimensions {
+                None => {
+                    sink.prepare(record.vector.len()).await?;
+                    report.dimensions = Some(record.vector.len());
+                }
+                Some(dimensions) if dimensions != record.vector.len() => {
+                    tracing::warn!(
+                        "Skipping memory {}: embedding has {} dimensions, expected {}",
+                        record.id,
+                        record.vector.len(),
+                        dimensions
+                    );
+                    report.skipped_dimension_mismatch += 1;
+                    continue;
+                }
+                Some(_) => {}
+            }
+            records.push(record);
+        }
+
+        if !records.is_empty() {
+            sink.upsert(&records).await?;
+            report.exported += records.len();
+            tracing::debug!(
+                "Exported {} vectors to {} ({} total)",
+                records.len(),
+                sink.name(),
+                report.exported
+            );
+        }
+
+        if memories.len() < options.batch_size {
+            break;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MemoryBuilder;
+
+    #[test]
+    fn test_record_requires_embedding() {
+        let memory = MemoryBuilder::fact("no vector").build();
+        assert!(VectorRecord::from_memory(&memory).is_none());
+
+        let memory = MemoryBuilder::fact("with vector")
+            .tag("science")
+            .embedding(vec![0.1, 0.2, 0.3])
+            .build();
+        let record = VectorRecord::from_memory(&memory).unwrap();
+        assert_eq!(record.id, memory.id);
+        assert_eq!(record.vector.len(), 3);
+        assert_eq!(record.payload["content"], "with vector");
+        assert_eq!(record.payload["memory_type"], "fact");
+        assert_eq!(record.payload["tags"], json!(["science"]));
+    }
+}
@@ -0,0 +1,226 @@
+//! pgvector sink
+//!
+//! Writes a SQL script that creates the table (and an HNSW index) and upserts
+//! each batch in its own transaction. Pipe it into `psql`, which keeps this
+//! crate free of a PostgreSQL driver:
+//!
+//! ```text
+//! locai-cli export pgvector --table agent_memories | psql "$DATABASE_URL"
+//! ```
+//!
+//! The table has `id`, `embedding`, `content`, `memory_type`, `metadata`
+//! (the full payload as JSONB) and `created_at` columns.
+
+use std::io::Write;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::{VectorRecord, VectorSink};
+use crate::{LocaiError, Result};
+
+/// Configuration for [`PgvectorSink`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PgvectorConfig {
+    /// Table to write to, optionally schema-qualified
+    pub table: String,
+
+    /// Create an HNSW cosine index on the embedding column
+    pub create_index: bool,
+}
+
+impl Default for PgvectorConfig {
+    fn default() -> Self {
+        Self {
+            table: "locai_memories".to_string(),
+            create_index: true,
+        }
+    }
+}
+
+/// Vector sink writing pgvector SQL to any writer
+pub struct PgvectorSink {
+    config: PgvectorConfig,
+    writer: Mutex<Box<dyn Write + Send>>,
+    name: String,
+}
+
+impl std::fmt::Debug for PgvectorSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PgvectorSink")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PgvectorSink {
+    /// Create a sink writing SQL to `writer`
+    pub fn new(config: PgvectorConfig, writer: Box<dyn Write + Send>) -> Result<Self> {
+        let valid = config.table.split('.').all(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        if !valid {
+            return Err(LocaiError::Configuration(format!(
+                "Invalid pgvector table name '{}'",
+                config.table
+            )));
+        }
+
+        Ok(Self {
+            name: format!("pgvector:{}", config.table),
+            config,
+            writer: Mutex::new(writer),
+        })
+    }
+
+    /// Sink configuration
+    pub fn config(&self) -> &PgvectorConfig {
+        &self.config
+    }
+
+    fn write(&self, sql: &str) -> Result<()> {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| LocaiError::Other("pgvector writer lock poisoned".to_string()))?;
+        writer
+            .write_all(sql.as_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|e| LocaiError::Other(format!("Failed to write pgvector SQL: {}", e)))
+    }
+}
+
+/// SQL creating the extension, table and index
+fn schema_sql(config: &PgvectorConfig, dimensions: usize) -> String {
+    let mut sql = format!(
+        "CREATE EXTENSION IF NOT EXISTS vector;\n\
+         CREATE TABLE IF NOT EXISTS {table} (\n    \
+             id TEXT PRIMARY KEY,\n    \
+             embedding vector({dimensions}) NOT NULL,\n    \
+             content TEXT NOT NULL,\n    \
+             memory_type TEXT,\n    \
+             metadata JSONB,\n    \
+             created_at TIMESTAMPTZ\n\
+         );\n",
+        table = config.table,
+    );
+    if config.create_index {
+        sql.push_str(&format!(
+            "CREATE INDEX IF NOT EXISTS {}_embedding_idx ON {} USING hnsw (embedding vector_cosine_ops);\n",
+            config.table.replace('.', "_"),
+            config.table
+        ));
+    }
+    sql
+}
+
+/// SQL upserting a batch of records in one transaction
+fn upsert_sql(table: &str, records: &[VectorRecord]) -> String {
+    let rows: Vec<String> = records
+        .iter()
+        .map(|record| {
+            let vector: Vec<String> = record.vector.iter().map(f32::to_string).collect();
+            let text_field = |key: &str| {
+                record
+                    .payload
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .map(literal)
+                    .unwrap_or_else(|| "NULL".to_string())
+            };
+            format!(
+                "({}, '[{}]', {}, {}, {}::jsonb, {}::timestamptz)",
+                literal(&record.id),
+                vector.join(","),
+                text_field("content"),
+                text_field("memory_type"),
+                literal(&record.payload.to_string()),
+                text_field("created_at"),
+            )
+        })
+        .collect();
+
+    format!(
+        "BEGIN;\n\
+         INSERT INTO {table} (id, embedding, content, memory_type, metadata, created_at) VALUES\n{rows}\n\
+         ON CONFLICT (id) DO UPDATE SET embedding = EXCLUDED.embedding, content = EXCLUDED.content, \
+         memory_type = EXCLUDED.memory_type, metadata = EXCLUDED.metadata, created_at = EXCLUDED.created_at;\n\
+         COMMIT;\n",
+        rows = rows.join(",\n"),
+    )
+}
+
+/// Quote a string literal (PostgreSQL text cannot hold NUL bytes)
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\0', "").replace('\'', "''"))
+}
+
+#[async_trait]
+impl VectorSink for PgvectorSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn prepare(&self, dimensions: usize) -> Result<()> {
+        self.write(&schema_sql(&self.config, dimensions))
+    }
+
+    async fn upsert(&self, records: &[VectorRecord]) -> Result<()> {
+        self.write(&upsert_sql(&self.config.table, records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_literal_escapes_quotes() {
+        assert_eq!(literal("it's"), "'it''s'");
+        assert_eq!(literal("a\0b"), "'ab'");
+    }
+
+    #[test]
+    fn test_upsert_sql() {
+        let records = vec![VectorRecord {
+            id: "m1".to_string(),
+            vector: vec![0.5, -1.0],
+            payload: json!({
+                "content": "O'Brien likes tea",
+                "memory_type": "fact",
+                "created_at": "2024-01-01T00:00:00+00:00",
+            }),
+        }];
+        let sql = upsert_sql("memories", &records);
+
+        assert!(sql.starts_with("BEGIN;\nINSERT INTO memories"));
+        assert!(sql.contains("('m1', '[0.5,-1]', 'O''Brien likes tea', 'fact', "));
+        assert!(sql.contains("'2024-01-01T00:00:00+00:00'::timestamptz)"));
+        assert!(sql.contains("ON CONFLICT (id) DO UPDATE"));
+        assert!(sql.ends_with("COMMIT;\n"));
+    }
+
+    #[test]
+    fn test_schema_sql_and_table_validation() {
+        let config = PgvectorConfig {
+            table: "agents.memories".to_string(),
+            create_index: true,
+        };
+        let sql = schema_sql(&config, 384);
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS agents.memories"));
+        assert!(sql.contains("embedding vector(384) NOT NULL"));
+        assert!(sql.contains("agents_memories_embedding_idx"));
+
+        let bad = PgvectorConfig {
+            table: "memories; DROP TABLE users".to_string(),
+            ..PgvectorConfig::default()
+        };
+        assert!(PgvectorSink::new(bad, Box::new(std::io::sink())).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Qdrant vector sink
+//!
+//! Writes points through Qdrant's REST API. Qdrant only accepts UUIDs and
+//! integers as point IDs, so memory IDs that are not UUIDs are mapped to a
+//! name-based (v5) UUID; the original ID is always in the `locai_id` payload
+//! field.
+//!
+//! ```rust,no_run
+//! use locai::export::{VectorExportOptions, export_vectors};
+//! use locai::export::qdrant::{QdrantConfig, QdrantSink};
+//! use locai::prelude::*;
+//!
+//! async fn example(manager: &MemoryManager) -> Result<()> {
+//!     let sink = QdrantSink::new(QdrantConfig {
+//!         collection: "agent-memories".to_string(),
+//!         ..QdrantConfig::default()
+//!     })?;
+//!     let report = export_vectors(manager, &sink, VectorExportOptions::default()).await?;
+//!     println!("exported {} vectors", report.exported);
+//!     Ok(())
+//! }
+//! ```
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use super::{VectorRecord, VectorSink};
+use crate::{LocaiError, Result};
+
+/// Default Qdrant REST endpoint
+pub const DEFAULT_QDRANT_URL: &str = "http://localhost:6333";
+
+/// Configuration for [`QdrantSink`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QdrantConfig {
+    /// Base URL of the Qdrant REST API
+    pub url: String,
+
+    /// Collection to write to; created on first export if missing
+    pub collection: String,
+
+    /// API key, for Qdrant Cloud or secured instances
+    pub api_key: Option<String>,
+
+    /// Distance used when creating the collection (`Cosine`, `Dot`, `Euclid`, `Manhattan`)
+    pub distance: String,
+
+    /// Request timeout in seconds
+    pub timeout_secs: u64,
+}
+
+impl Default for QdrantConfig {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_QDRANT_URL.to_string(),
+            collection: "locai".to_string(),
+            api_key: None,
+            distance: "Cosine".to_string(),
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// Vector sink writing to a Qdrant collection
+#[derive(Debug)]
+pub struct QdrantSink {
+    config: QdrantConfig,
+    client: reqwest::Client,
+    name: String,
+}
+
+impl QdrantSink {
+    /// Create a sink from configuration
+    pub fn new(config: QdrantConfig) -> Result<Self> {
+        if config.collection.is_empty() {
+            return Err(LocaiError::Configuration(
+                "Qdrant collection name cannot be empty".to_string(),
+            ));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| LocaiError::Other(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            name: format!("qdrant:{}", config.collection),
+            config,
+            client,
+        })
+    }
+
+    /// Sink configuration
+    pub fn config(&self) -> &QdrantConfig {
+        &self.config
+    }
+
+    fn collection_url(&self) -> String {
+        format!(
+            "{}/collections/{}",
+            self.config.url.trim_end_matches('/'),
+            self.config.collection
+        )
+    }
+
+    fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.config.api_key {
+            Some(key) => request.header("api-key", key),
+            None => request,
+        }
+    }
+}
+
+/// Point ID for a memory: the memory ID itself if it is a UUID, otherwise a
+/// UUID derived from it
+pub fn point_id(memory_id: &str) -> String {
+    Uuid::parse_str(memory_id)
+        .unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_OID, memory_id.as_bytes()))
+        .to_string()
+}
+
+/// Body of an upsert request
+fn points_body(records: &[VectorRecord]) -> Value {
+    let points: Vec<Value> = records
+        .iter()
+        .map(|record| {
+            json!({
+                "id": point_id(&record.id),
+                "vector": record.vector,
+                "payload": record.payload,
+            })
+        })
+        .collect();
+    json!({ "points": points })
+}
+
+async fn check_response(response: reqwest::Response, action: &str) -> Result<reqwest::Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Err(LocaiError::Other(format!(
+        "Qdrant {} failed ({}): {}",
+        action, status, body
+    )))
+}
+
+#[async_trait]
+impl VectorSink for QdrantSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn prepare(&self, dimensions: usize) -> Result<()> {
+        let response = self
+            .request(reqwest::Method::GET, self.collection_url())
+            .send()
+            .await
+            .map_err(|e| LocaiError::Connection(format!("Qdrant at {}: {}", self.config.url, e)))?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            let response = self
+                .request(reqwest::Method::PUT, self.collection_url())
+                .json(&json!({
+                    "vectors": { "size": dimensions, "distance": self.config.distance }
+                }))
+                .send()
+                .await
+                .map_err(|e| LocaiError::Connection(format!("Qdrant: {}", e)))?;
+            check_response(response, "collection creation").await?;
+            tracing::info!(
+                "Created Qdrant collection '{}' ({} dimensions)",
+                self.config.collection,
+                dimensions
+            );
+            return Ok(());
+        }
+
+        let response = check_response(response, "collection lookup").await?;
+        let info: Value = response
+            .json()
+            .await
+            .map_err(|e| LocaiError::Other(format!("Invalid Qdrant response: {}", e)))?;
+        let existing = info
+            .pointer("/result/config/params/vectors/size")
+            .and_then(Value::as_u64);
+        if let Some(existing) = existing
+            && existing as usize != dimensions
+        {
+            return Err(LocaiError::Configuration(format!(
+                "Qdrant collection '{}' has {} dimensions, embeddings have {}",
+                self.config.collection, existing, dimensions
+            )));
+        }
+        Ok(())
+    }
+
+    async fn upsert(&self, records: &[VectorRecord]) -> Result<()> {
+        let response = self
+            .request(
+                reqwest::Method::PUT,
+                format!("{}/points?wait=true", self.collection_url()),
+            )
+            .json(&points_body(records))
+            .send()
+            .await
+            .map_err(|e| LocaiError::Connection(format!("Qdrant: {}", e)))?;
+        check_response(response, "upsert").await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_id_keeps_uuids() {
+        let id = "0b8e3c2e-8f8e-4a43-9a53-5f8e9d1c0a11";
+        assert_eq!(point_id(id), id);
+
+        let derived = point_id("memory:abc");
+        assert!(Uuid::parse_str(&derived).is_ok());
+        assert_eq!(derived, point_id("memory:abc"));
+        assert_ne!(derived, point_id("memory:abd"));
+    }
+
+    #[test]
+    fn test_points_body() {
+        let records = vec![VectorRecord {
+            id: "m1".to_string(),
+            vector: vec![0.5, 0.25],
+            payload: json!({ "locai_id": "m1" }),
+        }];
+        let body = points_body(&records);
+        assert_eq!(body["points"][0]["id"], point_id("m1"));
+        assert_eq!(body["points"][0]["vector"], json!([0.5, 0.25]));
+        assert_eq!(body["points"][0]["payload"]["locai_id"], "m1");
+    }
+
+    #[test]
+    fn test_new_rejects_empty_collection() {
+        let config = QdrantConfig {
+            collection: String::new(),
+            ..QdrantConfig::default()
+        };
+        assert!(QdrantSink::new(config).is_err());
+    }
+}
@@ -0,0 +1,739 @@
+//! RDF export and triple-pattern queries over the entity graph
+//!
+//! [`export_triples`] reads entities and relationships (and optionally
+//! memories) into a [`TripleGraph`]. The graph renders as N-Triples or Turtle
+//! for knowledge-graph tooling and reasoners, and answers basic graph pattern
+//! queries through [`TripleQuery`], a small subset of SPARQL:
+//!
+//! ```text
+//! SELECT ?person ?org WHERE { ?person works_at ?org . ?org name "Acme" }
+//! ```
+//!
+//! Nodes are named `<base>node/<id>`, and relationship types, property keys
+//! and entity types share the `<base>vocab/` namespace. The `type` predicate
+//! is exported as `rdf:type`. Property values become plain literals: arrays
+//! give one triple per element and nested objects are JSON encoded.
+//!
+//! ```rust,no_run
+//! use locai::export::rdf::{RdfExportOptions, RdfFormat, TripleQuery, export_triples};
+//! use locai::prelude::*;
+//!
+//! async fn example(manager: &MemoryManager) -> Result<()> {
+//!     let graph = export_triples(manager, &RdfExportOptions::default()).await?;
+//!     println!("{}", graph.render(RdfFormat::Turtle, "https://example.org/"));
+//!
+//!     let query = TripleQuery::parse("?person works_at ?org")?;
+//!     for row in graph.query(&query).rows {
+//!         println!("{} works at {}", row["person"].value(), row["org"].value());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::MemoryManager;
+use crate::models::Memory;
+use crate::storage::models::{Entity, Relationship};
+use crate::{LocaiError, Result};
+
+/// Base IRI used when none is given
+pub const DEFAULT_BASE_IRI: &str = "urn:locai:";
+
+/// Predicate exported as `rdf:type`
+pub const TYPE_PREDICATE: &str = "type";
+
+/// Class of exported memories
+pub const MEMORY_CLASS: &str = "Memory";
+
+const RDF_TYPE_IRI: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// RDF serialization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RdfFormat {
+    #[default]
+    NTriples,
+    Turtle,
+}
+
+impl RdfFormat {
+    /// Media type of the serialization
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            RdfFormat::NTriples => "application/n-triples",
+            RdfFormat::Turtle => "text/turtle",
+        }
+    }
+}
+
+/// Options for [`export_triples`]
+#[derive(Debug, Clone)]
+pub struct RdfExportOptions {
+    /// Also export memories: their type, content, tags and creation time
+    pub include_memories: bool,
+
+    /// Records read per batch
+    pub batch_size: usize,
+}
+
+impl Default for RdfExportOptions {
+    fn default() -> Self {
+        Self {
+            include_memories: false,
+            batch_size: 500,
+        }
+    }
+}
+
+/// Object of a triple
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum Term {
+    /// A memory or entity, by ID
+    Node(String),
+
+    /// An entity type or other vocabulary term
+    Vocab(String),
+
+    /// A plain literal
+    Literal(String),
+}
+
+impl Term {
+    /// The ID, name or literal text
+    pub fn value(&self) -> &str {
+        match self {
+            Term::Node(value) | Term::Vocab(value) | Term::Literal(value) => value,
+        }
+    }
+}
+
+/// A `subject predicate object` statement about a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Triple {
+    /// Node ID
+    pub subject: String,
+
+    /// Relationship type, property key or [`TYPE_PREDICATE`]
+    pub predicate: String,
+
+    pub object: Term,
+}
+
+/// The entity graph as triples
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TripleGraph {
+    pub triples: Vec<Triple>,
+}
+
+impl TripleGraph {
+    /// Build triples from entities, relationships and memories
+    pub fn from_records(
+        entities: &[Entity],
+        relationships: &[Relationship],
+        memories: &[Memory],
+    ) -> Self {
+        let mut triples = Vec::new();
+        for entity in entities {
+            triples.push(Triple {
+                subject: entity.id.clone(),
+                predicate: TYPE_PREDICATE.to_string(),
+                object: Term::Vocab(entity.entity_type.clone()),
+            });
+            if let Value::Object(properties) = &entity.properties {
+                for (key, value) in properties {
+                    push_property(&mut triples, &entity.id, key, value);
+                }
+            }
+        }
+
+        for memory in memories {
+            triples.push(Triple {
+                subject: memory.id.clone(),
+                predicate: TYPE_PREDICATE.to_string(),
+                object: Term::Vocab(MEMORY_CLASS.to_string()),
+            });
+            let mut push = |predicate: &str, value: String| {
+                triples.push(Triple {
+                    subject: memory.id.clone(),
+                    predicate: predicate.to_string(),
+                    object: Term::Literal(value),
+                });
+            };
+            push("memory_type", memory.memory_type.to_string());
+            push("content", memory.content.clone());
+            push("created_at", memory.created_at.to_rfc3339());
+            for tag in &memory.tags {
+                push("tag", tag.clone());
+            }
+        }
+
+        for relationship in relationships {
+            triples.push(Triple {
+                subject: relationship.source_id.clone(),
+                predicate: relationship.relationship_type.clone(),
+                object: Term::Node(relationship.target_id.clone()),
+            });
+        }
+
+        Self { triples }
+    }
+
+    /// Serialize with node and vocabulary IRIs under `base_iri`
+    pub fn render(&self, format: RdfFormat, base_iri: &str) -> String {
+        match format {
+            RdfFormat::NTriples => self.to_ntriples(base_iri),
+            RdfFormat::Turtle => self.to_turtle(base_iri),
+        }
+    }
+
+    /// One line per triple, every IRI written out in full
+    pub fn to_ntriples(&self, base_iri: &str) -> String {
+        let mut out = String::new();
+        for triple in &self.triples {
+            let _ = writeln!(
+                out,
+                "<{}> <{}> {} .",
+                node_iri(base_iri, &triple.subject),
+                predicate_iri(base_iri, &triple.predicate),
+                object_ntriples(base_iri, &triple.object)
+            );
+        }
+        out
+    }
+
+    /// Triples grouped by subject, with `node:`, `vocab:` and `rdf:`
+    /// prefixes
+    pub fn to_turtle(&self, base_iri: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> ."
+        );
+        let _ = writeln!(out, "@prefix node: <{}node/> .", base_iri);
+        let _ = writeln!(out, "@prefix vocab: <{}vocab/> .", base_iri);
+
+        let mut subjects: BTreeMap<&str, Vec<&Triple>> = BTreeMap::new();
+        for triple in &self.triples {
+            subjects.entry(&triple.subject).or_default().push(triple);
+        }
+        for (subject, triples) in subjects {
+            let _ = write!(out, "\n{}", turtle_name(base_iri, "node", subject));
+            for (index, triple) in triples.iter().enumerate() {
+                let predicate = if triple.predicate == TYPE_PREDICATE {
+                    "a".to_string()
+                } else {
+                    turtle_name(base_iri, "vocab", &triple.predicate)
+                };
+                let object = match &triple.object {
+                    Term::Node(id) => turtle_name(base_iri, "node", id),
+                    Term::Vocab(name) => turtle_name(base_iri, "vocab", name),
+                    Term::Literal(text) => literal(text),
+                };
+                let separator = if index == 0 { " " } else { " ;\n    " };
+                let _ = write!(out, "{}{} {}", separator, predicate, object);
+            }
+            out.push_str(" .\n");
+        }
+        out
+    }
+
+    /// Evaluate a basic graph pattern query
+    ///
+    /// Each pattern is matched against every triple and joined with the rows
+    /// so far on shared variables.
+    pub fn query(&self, query: &TripleQuery) -> TripleQueryResult {
+        let mut rows: Vec<HashMap<String, Term>> = vec![HashMap::new()];
+        for pattern in &query.patterns {
+            let mut next = Vec::new();
+            for row in &rows {
+                for triple in &self.triples {
+                    let subject = Term::Node(triple.subject.clone());
+                    let predicate = Term::Vocab(triple.predicate.clone());
+                    let mut candidate = row.clone();
+                    if bind(&pattern.subject, &subject, &mut candidate)
+                        && bind(&pattern.predicate, &predicate, &mut candidate)
+                        && bind(&pattern.object, &triple.object, &mut candidate)
+                    {
+                        next.push(candidate);
+                    }
+                }
+            }
+            rows = next;
+        }
+
+        let variables = query.variables();
+        if let Some(limit) = query.limit {
+            rows.truncate(limit);
+        }
+        for row in &mut rows {
+            row.retain(|name, _| variables.contains(name));
+        }
+        TripleQueryResult { variables, rows }
+    }
+}
+
+/// A term in a [`TriplePattern`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternTerm {
+    /// `?name`, binding whatever it matches
+    Variable(String),
+
+    /// A node ID, relationship type, property key or entity type
+    Name(String),
+
+    /// `"text"`, matching a literal
+    Literal(String),
+}
+
+/// A `subject predicate object` pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriplePattern {
+    pub subject: PatternTerm,
+    pub predicate: PatternTerm,
+    pub object: PatternTerm,
+}
+
+/// A SPARQL-lite query: triple patterns joined on their variables
+///
+/// Accepts either bare patterns separated by `.`, or
+/// `SELECT ?a ?b WHERE { ... } LIMIT n` where `SELECT *` and the `LIMIT`
+/// clause are optional. `a` is shorthand for the `type` predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TripleQuery {
+    /// Variables to return; all of them when `None`
+    pub select: Option<Vec<String>>,
+    pub patterns: Vec<TriplePattern>,
+    pub limit: Option<usize>,
+}
+
+impl TripleQuery {
+    /// Parse a query
+    pub fn parse(query: &str) -> Result<Self> {
+        let tokens = tokenize(query)?;
+        let mut tokens = tokens.as_slice();
+
+        let mut select = None;
+        if tokens
+            .first()
+            .is_some_and(|token| token.eq_ignore_ascii_case("select"))
+        {
+            let end = tokens
+                .iter()
+                .position(|token| token.eq_ignore_ascii_case("where") || token == "{")
+                .ok_or_else(|| invalid("SELECT without WHERE"))?;
+            let projection = &tokens[1..end];
+            if projection != ["*"] {
+                let mut variables = Vec::new();
+                for token in projection {
+                    let Some(name) = token.strip_prefix('?') else {
+                        return Err(invalid(&format!("Expected a variable, found '{}'", token)));
+                    };
+                    variables.push(name.to_string());
+                }
+                select = Some(variables);
+            }
+            tokens = &tokens[end..];
+            if tokens
+                .first()
+                .is_some_and(|token| token.eq_ignore_ascii_case("where"))
+            {
+                tokens = &tokens[1..];
+            }
+        }
+
+        let mut limit = None;
+        if tokens.first().is_some_and(|token| token == "{") {
+            let close = tokens
+                .iter()
+                .position(|token| token == "}")
+                .ok_or_else(|| invalid("Missing closing '}'"))?;
+            match &tokens[close + 1..] {
+                [] => {}
+                [keyword, count] if keyword.eq_ignore_ascii_case("limit") => {
+                    limit = Some(
+                        count
+                            .parse()
+                            .map_err(|_| invalid(&format!("Invalid LIMIT '{}'", count)))?,
+                    );
+                }
+                rest => {
+                    return Err(invalid(&format!("Unexpected '{}' after patterns", rest[0])));
+                }
+            }
+            tokens = &tokens[1..close];
+        }
+
+        let mut patterns = Vec::new();
+        for group in tokens.split(|token| token == ".") {
+            match group {
+                [] => {}
+                [subject, predicate, object] => patterns.push(TriplePattern {
+                    subject: pattern_term(subject),
+                    predicate: match predicate.as_str() {
+                        "a" => PatternTerm::Name(TYPE_PREDICATE.to_string()),
+                        _ => pattern_term(predicate),
+                    },
+                    object: pattern_term(object),
+                }),
+                _ => {
+                    return Err(invalid(&format!(
+                        "Expected 'subject predicate object', found '{}'",
+                        group.join(" ")
+                    )));
+                }
+            }
+        }
+        if patterns.is_empty() {
+            return Err(invalid("Query has no patterns"));
+        }
+
+        Ok(Self {
+            select,
+            patterns,
+            limit,
+        })
+    }
+
+    /// Variables returned, in the order selected or first used
+    pub fn variables(&self) -> Vec<String> {
+        if let Some(select) = &self.select {
+            return select.clone();
+        }
+        let mut variables: Vec<String> = Vec::new();
+        for pattern in &self.patterns {
+            for term in [&pattern.subject, &pattern.predicate, &pattern.object] {
+                if let PatternTerm::Variable(name) = term
+                    && !variables.contains(name)
+                {
+                    variables.push(name.clone());
+                }
+            }
+        }
+        variables
+    }
+}
+
+/// Rows produced by [`TripleGraph::query`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TripleQueryResult {
+    pub variables: Vec<String>,
+    pub rows: Vec<HashMap<String, Term>>,
+}
+
+/// Read the entity graph, and optionally memories, as triples
+pub async fn export_triples(
+    manager: &MemoryManager,
+    options: &RdfExportOptions,
+) -> Result<TripleGraph> {
+    if options.batch_size == 0 {
+        return Err(LocaiError::Configuration(
+            "RDF export batch_size must be at least 1".to_string(),
+        ));
+    }
+    let storage = manager.storage();
+    let batch = Some(options.batch_size);
+
+    let mut entities = Vec::new();
+    loop {
+        let page = storage
+            .list_entities(None, batch, Some(entities.len()))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list entities: {}", e)))?;
+        let done = page.len() < options.batch_size;
+        entities.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    let mut relationships = Vec::new();
+    loop {
+        let page = storage
+            .list_relationships(None, batch, Some(relationships.len()))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list relationships: {}", e)))?;
+        let done = page.len() < options.batch_size;
+        relationships.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    let mut memories = Vec::new();
+    while options.include_memories {
+        let page = storage
+            .list_memories(None, batch, Some(memories.len()))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list memories: {}", e)))?;
+        let done = page.len() < options.batch_size;
+        memories.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    Ok(TripleGraph::from_records(
+        &entities,
+        &relationships,
+        &memories,
+    ))
+}
+
+fn push_property(triples: &mut Vec<Triple>, subject: &str, key: &str, value: &Value) {
+    let text = match value {
+        Value::Null => return,
+        Value::Array(items) => {
+            for item in items {
+                push_property(triples, subject, key, item);
+            }
+            return;
+        }
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    triples.push(Triple {
+        subject: subject.to_string(),
+        predicate: key.to_string(),
+        object: Term::Literal(text),
+    });
+}
+
+/// Match `pattern` against `term`, binding a variable if it is unbound
+fn bind(pattern: &PatternTerm, term: &Term, row: &mut HashMap<String, Term>) -> bool {
+    match pattern {
+        PatternTerm::Variable(name) => match row.get(name) {
+            Some(bound) => bound == term,
+            None => {
+                row.insert(name.clone(), term.clone());
+                true
+            }
+        },
+        PatternTerm::Name(name) => {
+            matches!(term, Term::Node(value) | Term::Vocab(value) if value == name)
+        }
+        PatternTerm::Literal(text) => matches!(term, Term::Literal(value) if value == text),
+    }
+}
+
+fn pattern_term(token: &str) -> PatternTerm {
+    if let Some(name) = token.strip_prefix('?') {
+        PatternTerm::Variable(name.to_string())
+    } else if let Some(text) = token.strip_prefix('"') {
+        PatternTerm::Literal(text.to_string())
+    } else {
+        PatternTerm::Name(token.to_string())
+    }
+}
+
+/// Split a query into words, `{`, `}` and `.`; quoted literals keep a
+/// leading `"` and lose the closing one
+fn tokenize(query: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '{' | '}' | '.' => tokens.push(c.to_string()),
+            '"' => {
+                let mut text = String::from('"');
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(other) => text.push(other),
+                            None => return Err(invalid("Unterminated literal")),
+                        },
+                        Some(other) => text.push(other),
+                        None => return Err(invalid("Unterminated literal")),
+                    }
+                }
+                tokens.push(text);
+            }
+            _ => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"') {
+                        break;
+                    }
+                    // A '.' ends the word only when it ends the pattern
+                    if next == '.' {
+                        let mut lookahead = chars.clone();
+                        lookahead.next();
+                        if lookahead
+                            .peek()
+                            .is_none_or(|after| after.is_whitespace() || *after == '}')
+                        {
+                            break;
+                        }
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(word);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn invalid(message: &str) -> LocaiError {
+    LocaiError::Other(format!("Invalid triple query: {}", message))
+}
+
+fn node_iri(base_iri: &str, id: &str) -> String {
+    format!("{}node/{}", base_iri, encode(id))
+}
+
+fn predicate_iri(base_iri: &str, predicate: &str) -> String {
+    if predicate == TYPE_PREDICATE {
+        RDF_TYPE_IRI.to_string()
+    } else {
+        format!("{}vocab/{}", base_iri, encode(predicate))
+    }
+}
+
+fn object_ntriples(base_iri: &str, object: &Term) -> String {
+    match object {
+        Term::Node(id) => format!("<{}>", node_iri(base_iri, id)),
+        Term::Vocab(name) => format!("<{}vocab/{}>", base_iri, encode(name)),
+        Term::Literal(text) => literal(text),
+    }
+}
+
+/// `prefix:name` when `name` is a valid local name, otherwise a full IRI
+fn turtle_name(base_iri: &str, prefix: &str, name: &str) -> String {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        format!("{}:{}", prefix, name)
+    } else {
+        format!("<{}{}/{}>", base_iri, prefix, encode(name))
+    }
+}
+
+fn literal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Percent-encode everything but unreserved characters
+fn encode(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "%{:02X}", byte);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn graph() -> TripleGraph {
+        let entity = |id: &str, entity_type: &str, name: &str| Entity {
+            id: id.to_string(),
+            entity_type: entity_type.to_string(),
+            properties: json!({ "name": name }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let relationship = Relationship {
+            id: "r1".to_string(),
+            relationship_type: "works_at".to_string(),
+            source_id: "alice".to_string(),
+            target_id: "acme".to_string(),
+            properties: json!({}),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        TripleGraph::from_records(
+            &[
+                entity("alice", "person", "Alice \"Al\""),
+                entity("acme", "organization", "Acme"),
+            ],
+            &[relationship],
+            &[],
+        )
+    }
+
+    #[test]
+    fn test_ntriples() {
+        let ntriples = graph().to_ntriples("https://example.org/");
+        assert!(ntriples.contains(
+            "<https://example.org/node/alice> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <https://example.org/vocab/person> .\n"
+        ));
+        assert!(ntriples.contains(
+            "<https://example.org/node/alice> <https://example.org/vocab/name> \"Alice \\\"Al\\\"\" .\n"
+        ));
+        assert!(ntriples.contains(
+            "<https://example.org/node/alice> <https://example.org/vocab/works_at> <https://example.org/node/acme> .\n"
+        ));
+    }
+
+    #[test]
+    fn test_turtle_groups_by_subject() {
+        let turtle = graph().to_turtle(DEFAULT_BASE_IRI);
+        assert!(turtle.contains("@prefix node: <urn:locai:node/> ."));
+        assert!(turtle.contains(
+            "node:alice a vocab:person ;\n    vocab:name \"Alice \\\"Al\\\"\" ;\n    vocab:works_at node:acme .\n"
+        ));
+    }
+
+    #[test]
+    fn test_query_joins_patterns() {
+        let query =
+            TripleQuery::parse("SELECT ?who WHERE { ?who works_at ?org . ?org name \"Acme\" }")
+                .unwrap();
+        let result = graph().query(&query);
+        assert_eq!(result.variables, vec!["who"]);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["who"], Term::Node("alice".to_string()));
+
+        let query = TripleQuery::parse("?x a organization").unwrap();
+        let result = graph().query(&query);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["x"].value(), "acme");
+
+        let query = TripleQuery::parse("SELECT * { ?s ?p ?o } LIMIT 2").unwrap();
+        assert_eq!(query.variables(), vec!["s", "p", "o"]);
+        assert_eq!(graph().query(&query).rows.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(TripleQuery::parse("").is_err());
+        assert!(TripleQuery::parse("?s works_at").is_err());
+        assert!(TripleQuery::parse("SELECT ?s WHERE { ?s a person").is_err());
+        assert!(TripleQuery::parse("?s name \"unterminated").is_err());
+    }
+}
@@ -0,0 +1,324 @@
+//! Automations: rules written as configuration
+//!
+//! [`AutomationHook`] runs the automations of the `rules` configuration
+//! (see [`AutomationRule`]) on the memory events the hook registry passes
+//! it. Every automation whose trigger matches runs its actions in order; an
+//! action that fails is logged and the rest still run. Changes to the memory
+//! are saved once, after every automation has run.
+//!
+//! Like scripted rules, automations don't run on rules or messages. The
+//! events both run on, [`RuleEvent`], are defined here so automations work
+//! without the `rhai-rules` feature.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::traits::{HookResult, MemoryHook};
+use super::webhook::Webhook;
+use crate::config::{AutomationAction, AutomationRule, AutomationTrigger};
+use crate::memory::review::REVIEW_MEMORY_TYPE;
+use crate::memory::usage::USAGE_MEMORY_TYPE;
+use crate::models::{Memory, MemoryPriority, MemoryType};
+use crate::relationships::storage::RelationshipStorage;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Custom memory type rules are stored as
+pub const RULE_MEMORY_TYPE: &str = "rule";
+
+/// The memory events a rule can run on
+///
+/// Each also reads from its webhook event name, such as `memory.created`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleEvent {
+    #[serde(alias = "memory.created")]
+    Created,
+    #[serde(alias = "memory.updated")]
+    Updated,
+    #[serde(alias = "memory.accessed")]
+    Accessed,
+    #[serde(alias = "memory.deleted")]
+    Deleted,
+    #[serde(alias = "memory.reminder")]
+    Reminder,
+}
+
+impl RuleEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Accessed => "accessed",
+            Self::Deleted => "deleted",
+            Self::Reminder => "reminder",
+        }
+    }
+
+    /// Whether changes rules make to the memory on this event are saved
+    pub fn saves_changes(&self) -> bool {
+        matches!(self, Self::Created | Self::Accessed | Self::Reminder)
+    }
+}
+
+impl fmt::Display for RuleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RuleEvent {
+    type Err = LocaiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase();
+        match name.strip_prefix("memory.").unwrap_or(&name) {
+            "created" => Ok(Self::Created),
+            "updated" => Ok(Self::Updated),
+            "accessed" => Ok(Self::Accessed),
+            "deleted" => Ok(Self::Deleted),
+            "reminder" => Ok(Self::Reminder),
+            other => Err(LocaiError::Rule(format!(
+                "Unknown rule event '{}' (expected created, updated, accessed, deleted or reminder)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Whether events on the memory run rules: rules, messages and review queue
+/// entries don't
+pub(crate) fn runs_rules(memory: &Memory) -> bool {
+    match &memory.memory_type {
+        MemoryType::Custom(name) => {
+            name != RULE_MEMORY_TYPE
+                && name != REVIEW_MEMORY_TYPE
+                && name != USAGE_MEMORY_TYPE
+                && !name.starts_with("msg:")
+        }
+        _ => true,
+    }
+}
+
+/// Runs configured automations on memory events
+#[derive(Debug)]
+pub struct AutomationHook {
+    automations: Vec<AutomationRule>,
+    storage: Arc<dyn GraphStore>,
+    relationships: RelationshipStorage,
+}
+
+impl AutomationHook {
+    pub fn new(automations: Vec<AutomationRule>, storage: Arc<dyn GraphStore>) -> Self {
+        Self {
+            automations,
+            relationships: RelationshipStorage::new(Arc::clone(&storage)),
+            storage,
+        }
+    }
+
+    /// Run the automations `event` triggers on `memory`
+    pub async fn run(&self, event: RuleEvent, memory: &Memory) {
+        if !runs_rules(memory) {
+            return;
+        }
+
+        let mut current = memory.clone();
+        for automation in &self.automations {
+            if !triggers(&automation.trigger, event, &current) {
+                continue;
+            }
+            for action in &automation.actions {
+                if let Err(e) = self.apply(automation, action, event, &mut current).await {
+                    warn!(
+                        "Automation '{}' failed on {} of {}: {}",
+                        automation.name, event, memory.id, e
+                    );
+                }
+            }
+        }
+
+        if event != RuleEvent::Deleted
+            && current != *memory
+            && let Err(e) = self.storage.update_memory(current).await
+        {
+            warn!("Failed to save automation changes to {}: {}", memory.id, e);
+        }
+    }
+
+    async fn apply(
+        &self,
+        automation: &AutomationRule,
+        action: &AutomationAction,
+        event: RuleEvent,
+        memory: &mut Memory,
+    ) -> Result<()> {
+        match action {
+            AutomationAction::SetPriority(priority) => {
+                memory.priority = parse_priority(priority).map_err(LocaiError::Rule)?;
+            }
+            AutomationAction::AddTag(tag) => memory.add_tag(tag),
+            AutomationAction::RemoveTag(tag) => memory.tags.retain(|existing| existing != tag),
+            AutomationAction::CreateRelationship {
+                target,
+                relationship_type,
+            } => {
+                self.relationships
+                    .create_memory_relationship(&memory.id, target, relationship_type)
+                    .await?;
+            }
+            AutomationAction::Webhook { url, headers } => {
+                let webhook = headers
+                    .iter()
+                    .fold(Webhook::new(url.clone()), |webhook, (key, value)| {
+                        webhook.with_header(key.clone(), value.clone())
+                    });
+                let event_name = format!("memory.{}", event);
+                let payload = serde_json::json!({
+                    "event": event_name,
+                    "automation": automation.name,
+                    "timestamp": Utc::now().to_rfc3339(),
+                    "data": memory,
+                });
+                webhook
+                    .send_with_retry(&event_name, payload)
+                    .await
+                    .map_err(LocaiError::Rule)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether the trigger matches `event` happening to `memory`
+fn triggers(trigger: &AutomationTrigger, event: RuleEvent, memory: &Memory) -> bool {
+    trigger.event == event
+        && trigger.tags.iter().all(|tag| memory.tags.contains(tag))
+        && trigger.memory_type.as_ref().is_none_or(|memory_type| {
+            memory
+                .memory_type
+                .to_string()
+                .eq_ignore_ascii_case(memory_type)
+        })
+        && trigger
+            .content_contains
+            .as_ref()
+            .is_none_or(|text| memory.content.to_lowercase().contains(&text.to_lowercase()))
+        && trigger
+            .source
+            .as_ref()
+            .is_none_or(|source| &memory.source == source)
+}
+
+#[async_trait]
+impl MemoryHook for AutomationHook {
+    async fn on_memory_created(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Created, memory).await;
+        HookResult::Continue
+    }
+
+    async fn on_memory_accessed(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Accessed, memory).await;
+        HookResult::Continue
+    }
+
+    async fn on_memory_updated(&self, _old: &Memory, new: &Memory) -> HookResult {
+        self.run(RuleEvent::Updated, new).await;
+        HookResult::Continue
+    }
+
+    async fn before_memory_deleted(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Deleted, memory).await;
+        HookResult::Continue
+    }
+
+    async fn on_memory_reminder(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Reminder, memory).await;
+        HookResult::Continue
+    }
+
+    /// Webhook actions retry with backoff, so allow them longer than the
+    /// default
+    fn timeout_ms(&self) -> u64 {
+        30_000
+    }
+
+    fn name(&self) -> &str {
+        "automations"
+    }
+}
+
+pub(crate) fn parse_priority(name: &str) -> std::result::Result<MemoryPriority, String> {
+    match name.to_lowercase().as_str() {
+        "low" => Ok(MemoryPriority::Low),
+        "normal" => Ok(MemoryPriority::Normal),
+        "high" => Ok(MemoryPriority::High),
+        "critical" => Ok(MemoryPriority::Critical),
+        other => Err(format!(
+            "Unknown priority '{}' (expected low, normal, high or critical)",
+            other
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(event: RuleEvent) -> AutomationTrigger {
+        AutomationTrigger {
+            event,
+            tags: Vec::new(),
+            memory_type: None,
+            content_contains: None,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_trigger_conditions_must_all_hold() {
+        let mut memory = Memory::new(
+            "m1".to_string(),
+            "The Deploy is failing".to_string(),
+            MemoryType::Fact,
+        );
+        memory.tags = vec!["urgent".to_string(), "ops".to_string()];
+
+        assert!(triggers(
+            &trigger(RuleEvent::Created),
+            RuleEvent::Created,
+            &memory
+        ));
+        assert!(!triggers(
+            &trigger(RuleEvent::Created),
+            RuleEvent::Updated,
+            &memory
+        ));
+
+        let matching = AutomationTrigger {
+            tags: vec!["urgent".to_string()],
+            memory_type: Some("Fact".to_string()),
+            content_contains: Some("deploy".to_string()),
+            ..trigger(RuleEvent::Created)
+        };
+        assert!(triggers(&matching, RuleEvent::Created, &memory));
+
+        let missing_tag = AutomationTrigger {
+            tags: vec!["urgent".to_string(), "billing".to_string()],
+            ..matching.clone()
+        };
+        assert!(!triggers(&missing_tag, RuleEvent::Created, &memory));
+
+        let other_type = AutomationTrigger {
+            memory_type: Some("episodic".to_string()),
+            ..matching
+        };
+        assert!(!triggers(&other_type, RuleEvent::Created, &memory));
+    }
+}
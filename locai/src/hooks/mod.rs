@@ -2,7 +2,7 @@
 //!
 //! This module provides a flexible hook/callback system for responding to memory lifecycle events.
 //! Hooks allow applications to:
-//! - React to memory creation, access, updates, and deletion
+//! - React to memory creation, access, updates, deletion, and reminders coming due
 //! - Implement custom logic (e.g., entity promotion, consolidation, notifications)
 //! - Veto deletion operations
 //! - Track metrics and analytics
@@ -10,17 +10,25 @@
 //! # Architecture
 //!
 //! - `traits.rs`: Core `MemoryHook` trait and `HookResult` types
+//! - `automation.rs`: Automations from the `rules` configuration, run as a hook
 //! - `registry.rs`: `HookRegistry` for managing hook registration and execution
 //! - `webhook.rs`: Webhook-based hook implementation for remote integrations
+//! - `wasm.rs`: Sandboxed hooks run as WebAssembly modules (`wasm-hooks` feature)
 //!
 //! # Examples
 //!
 //! See the examples directory for complete working examples of custom hooks.
 
+pub mod automation;
 pub mod registry;
 pub mod traits;
+#[cfg(feature = "wasm-hooks")]
+pub mod wasm;
 pub mod webhook;
 
+pub use automation::AutomationHook;
 pub use registry::HookRegistry;
 pub use traits::{HookResult, MemoryHook};
+#[cfg(feature = "wasm-hooks")]
+pub use wasm::{WasmHook, WasmHookConfig};
 pub use webhook::Webhook;
@@ -185,6 +185,37 @@ impl HookRegistry {
         Ok(())
     }
 
+    /// Execute the `on_memory_reminder` hook for all registered hooks
+    ///
+    /// # Arguments
+    /// * `memory` - The memory whose reminder came due
+    pub async fn execute_on_reminder(&self, memory: &Memory) -> Result<(), String> {
+        let hooks = self.hooks.read().await;
+
+        for entry in hooks.iter() {
+            let hook = entry.hook.clone();
+            let timeout_ms = hook.timeout_ms();
+            let name = hook.name();
+
+            let future = async { hook.on_memory_reminder(memory).await };
+
+            match tokio::time::timeout(std::time::Duration::from_millis(timeout_ms), future).await {
+                Ok(HookResult::Continue) => {
+                    debug!("Hook '{}' completed successfully", name);
+                }
+                Ok(HookResult::Veto(reason)) => {
+                    // Reminders have already fired, so a veto changes nothing
+                    debug!("Hook '{}' returned veto (ignored): {}", name, reason);
+                }
+                Err(_) => {
+                    warn!("Hook '{}' timed out after {}ms", name, timeout_ms);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Execute the `before_memory_deleted` hook for all registered hooks
     ///
     /// This hook can veto deletion. If any hook returns `HookResult::Veto`,
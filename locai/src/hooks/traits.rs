@@ -2,7 +2,7 @@
 //!
 //! This module provides trait definitions for implementing hooks that respond to
 //! memory lifecycle events. Hooks allow you to run custom logic when memories are
-//! created, accessed, updated, or deleted.
+//! created, accessed, updated, or deleted, or when their reminders come due.
 //!
 //! # Examples
 //!
@@ -110,6 +110,19 @@ pub trait MemoryHook: Send + Sync + std::fmt::Debug {
         HookResult::Continue
     }
 
+    /// Called when a reminder set on a memory comes due
+    ///
+    /// Recurring reminders call this once per occurrence.
+    ///
+    /// # Arguments
+    /// * `memory` - The memory being surfaced
+    ///
+    /// # Returns
+    /// `HookResult::Continue` to proceed
+    async fn on_memory_reminder(&self, _memory: &Memory) -> HookResult {
+        HookResult::Continue
+    }
+
     /// Get the priority of this hook (higher = runs first)
     ///
     /// Hooks with higher priority values execute before hooks with lower priority values.
@@ -0,0 +1,534 @@
+//! Sandboxed hooks written as WebAssembly modules
+//!
+//! A [`WasmHook`] runs a WebAssembly module for each memory event, so logic
+//! that isn't trusted, or isn't written in Rust, can react to memories
+//! without native code being linked into the process. The module gets no
+//! WASI: it can't reach files, the network or the clock, and sees only the
+//! events it is sent. Each event runs in a fresh instance with a fuel budget
+//! and a memory cap, so a module that loops forever or allocates without
+//! bound fails that one event.
+//!
+//! As a [`StoreMiddleware`], the same module validates memories before they
+//! are stored and can refuse them.
+//!
+//! # Module interface
+//!
+//! The module exports:
+//!
+//! - `memory`: its linear memory
+//! - `alloc(len: i32) -> i32`: space for the host to write an event into
+//! - `handle(event: i32, ptr: i32, len: i32) -> i64`: handle an event, sent
+//!   as JSON at `ptr`
+//!
+//! `event` is one of the `EVENT_*` constants. The JSON holds the `memory`,
+//! the `previous` memory for updates, and the `config` from
+//! [`WasmHookConfig`]. `handle` returns 0 to continue, or points at a JSON
+//! response in its memory as `(ptr << 32) | len`: `{"action": "continue"}` or
+//! `{"action": "veto", "reason": "..."}`. A veto stops a deletion or refuses a
+//! memory being validated; after other events it is logged and ignored.
+//!
+//! The only import is `locai.log(level: i32, ptr: i32, len: i32)`, which logs
+//! a UTF-8 message at a level from 0 (error) to 4 (trace).
+//!
+//! # Configuration
+//!
+//! With the `wasm-hooks` feature, modules are enabled as plugins named
+//! [`WASM_PLUGIN`], with [`WasmHookConfig`] as their options:
+//!
+//! ```toml
+//! [[plugins.hooks]]
+//! name = "wasm"
+//! options = { path = "hooks/audit.wasm", config = { channel = "memories" } }
+//!
+//! [[plugins.store_middlewares]]
+//! name = "wasm"
+//! options = { path = "hooks/no-secrets.wasm", name = "no-secrets" }
+//! ```
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use wasmtime::{
+    Caller, Config, Engine, InstancePre, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+};
+
+use super::{HookResult, MemoryHook};
+use crate::memory::pipeline::{PendingMemory, StoreFlow, StoreMiddleware};
+use crate::models::Memory;
+use crate::plugins::PluginRegistry;
+use crate::{LocaiError, Result};
+
+/// A memory was stored
+pub const EVENT_CREATED: i32 = 0;
+
+/// A memory was read
+pub const EVENT_ACCESSED: i32 = 1;
+
+/// A memory was updated; the event includes the `previous` version
+pub const EVENT_UPDATED: i32 = 2;
+
+/// A memory is about to be deleted; a veto keeps it
+pub const EVENT_BEFORE_DELETED: i32 = 3;
+
+/// A reminder set on a memory came due
+pub const EVENT_REMINDER: i32 = 4;
+
+/// A memory is about to be stored; a veto refuses it
+pub const EVENT_VALIDATE: i32 = 5;
+
+/// Name WebAssembly hooks and validators are registered under as plugins
+pub const WASM_PLUGIN: &str = "wasm";
+
+/// How a WebAssembly hook is loaded and sandboxed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WasmHookConfig {
+    /// Module to load, as a `.wasm` binary or `.wat` text
+    pub path: Option<PathBuf>,
+
+    /// Name for logs and the store pipeline; the file name without its
+    /// extension by default
+    pub name: Option<String>,
+
+    /// Fuel each event may burn, roughly one unit per instruction
+    pub fuel: u64,
+
+    /// Largest the module's memory may grow to, in bytes
+    pub max_memory_bytes: usize,
+
+    /// Hook priority (higher runs first)
+    pub priority: i32,
+
+    /// How long an event may take before it is abandoned
+    pub timeout_ms: u64,
+
+    /// Sent to the module with every event
+    pub config: serde_json::Value,
+}
+
+impl Default for WasmHookConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            name: None,
+            fuel: 10_000_000,
+            max_memory_bytes: 16 * 1024 * 1024,
+            priority: 0,
+            timeout_ms: 5000,
+            config: serde_json::Value::Null,
+        }
+    }
+}
+
+/// What the module decided about an event
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum GuestResponse {
+    Continue,
+    Veto { reason: String },
+}
+
+/// What an instance can reach
+struct HostState {
+    limits: StoreLimits,
+    name: String,
+}
+
+struct WasmModule {
+    name: String,
+    config: WasmHookConfig,
+    engine: Engine,
+    instance: InstancePre<HostState>,
+}
+
+/// A memory hook and store validator run in a WebAssembly sandbox
+///
+/// See the [module documentation](self) for the interface the module
+/// implements.
+#[derive(Clone)]
+pub struct WasmHook {
+    module: Arc<WasmModule>,
+}
+
+impl fmt::Debug for WasmHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmHook")
+            .field("name", &self.module.name)
+            .field("config", &self.module.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmHook {
+    /// Load the module at `config.path`
+    pub fn load(config: WasmHookConfig) -> Result<Self> {
+        let path = config.path.clone().ok_or_else(|| {
+            LocaiError::Configuration("WebAssembly hook needs a module path".to_string())
+        })?;
+        let bytes = std::fs::read(&path).map_err(|e| {
+            LocaiError::Configuration(format!(
+                "Failed to read WebAssembly module {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let name = config.name.clone().unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| WASM_PLUGIN.to_string())
+        });
+        Self::build(name, &bytes, config)
+    }
+
+    /// Compile a module from its `.wasm` bytes or `.wat` text
+    pub fn from_bytes(bytes: &[u8], config: WasmHookConfig) -> Result<Self> {
+        let name = config
+            .name
+            .clone()
+            .unwrap_or_else(|| WASM_PLUGIN.to_string());
+        Self::build(name, bytes, config)
+    }
+
+    /// Load the module plugin options describe
+    pub fn from_options(options: &serde_json::Value) -> Result<Self> {
+        let config = if options.is_null() {
+            WasmHookConfig::default()
+        } else {
+            serde_json::from_value(options.clone()).map_err(|e| {
+                LocaiError::Configuration(format!("Invalid WebAssembly hook options: {}", e))
+            })?
+        };
+        Self::load(config)
+    }
+
+    fn build(name: String, bytes: &[u8], config: WasmHookConfig) -> Result<Self> {
+        let load_error = |e: wasmtime::Error| {
+            LocaiError::Configuration(format!("Failed to load WebAssembly hook '{}': {}", name, e))
+        };
+
+        let mut engine_config = Config::new();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config).map_err(load_error)?;
+        let module = Module::new(&engine, bytes).map_err(load_error)?;
+
+        let mut linker = Linker::new(&engine);
+        linker
+            .func_wrap("locai", "log", host_log)
+            .map_err(load_error)?;
+        let instance = linker.instantiate_pre(&module).map_err(load_error)?;
+
+        Ok(Self {
+            module: Arc::new(WasmModule {
+                name,
+                config,
+                engine,
+                instance,
+            }),
+        })
+    }
+
+    /// The event sent to the module
+    fn event(&self, memory: &Memory, previous: Option<&Memory>) -> serde_json::Value {
+        json!({
+            "memory": memory,
+            "previous": previous,
+            "config": self.module.config.config,
+        })
+    }
+
+    /// Run `event` in a fresh instance, off the async workers
+    async fn run(&self, event: i32, input: serde_json::Value) -> Result<GuestResponse> {
+        let module = Arc::clone(&self.module);
+        tokio::task::spawn_blocking(move || module.call(event, &input))
+            .await
+            .map_err(|e| {
+                LocaiError::Other(format!(
+                    "WebAssembly hook '{}' task failed: {}",
+                    self.module.name, e
+                ))
+            })?
+    }
+
+    /// Run a hook event; failures are logged and don't stop the operation
+    async fn hook(&self, event: i32, input: serde_json::Value) -> HookResult {
+        match self.run(event, input).await {
+            Ok(GuestResponse::Continue) => HookResult::Continue,
+            Ok(GuestResponse::Veto { reason }) => HookResult::Veto(reason),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                HookResult::Continue
+            }
+        }
+    }
+}
+
+impl WasmModule {
+    fn call(&self, event: i32, input: &serde_json::Value) -> Result<GuestResponse> {
+        let error = |e: &dyn fmt::Display| {
+            LocaiError::Other(format!("WebAssembly hook '{}' failed: {}", self.name, e))
+        };
+
+        let state = HostState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.config.max_memory_bytes)
+                .instances(1)
+                .build(),
+            name: self.name.clone(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(self.config.fuel).map_err(|e| error(&e))?;
+
+        let instance = self
+            .instance
+            .instantiate(&mut store)
+            .map_err(|e| error(&e))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| error(&"module exports no memory"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| error(&e))?;
+        let handle = instance
+            .get_typed_func::<(i32, i32, i32), i64>(&mut store, "handle")
+            .map_err(|e| error(&e))?;
+
+        let bytes = serde_json::to_vec(input).map_err(|e| error(&e))?;
+        let len = i32::try_from(bytes.len()).map_err(|e| error(&e))?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| error(&e))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, &bytes)
+            .map_err(|e| error(&e))?;
+
+        let packed = handle
+            .call(&mut store, (event, ptr, len))
+            .map_err(|e| error(&e))? as u64;
+        if packed == 0 {
+            return Ok(GuestResponse::Continue);
+        }
+
+        let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        let response = memory
+            .data(&store)
+            .get(ptr..ptr.saturating_add(len))
+            .ok_or_else(|| error(&"response is outside the module's memory"))?;
+        serde_json::from_slice(response).map_err(|e| error(&e))
+    }
+}
+
+/// `locai.log`: log a message from the module's memory
+fn host_log(mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32) {
+    let Some(memory) = caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+    else {
+        return;
+    };
+    let (ptr, len) = (ptr as u32 as usize, len as u32 as usize);
+    let Some(bytes) = memory.data(&caller).get(ptr..ptr.saturating_add(len)) else {
+        return;
+    };
+    let message = String::from_utf8_lossy(bytes);
+    let name = &caller.data().name;
+    match level {
+        0 => tracing::error!("[{}] {}", name, message),
+        1 => tracing::warn!("[{}] {}", name, message),
+        2 => tracing::info!("[{}] {}", name, message),
+        3 => tracing::debug!("[{}] {}", name, message),
+        _ => tracing::trace!("[{}] {}", name, message),
+    }
+}
+
+#[async_trait]
+impl MemoryHook for WasmHook {
+    async fn on_memory_created(&self, memory: &Memory) -> HookResult {
+        self.hook(EVENT_CREATED, self.event(memory, None)).await
+    }
+
+    async fn on_memory_accessed(&self, memory: &Memory) -> HookResult {
+        self.hook(EVENT_ACCESSED, self.event(memory, None)).await
+    }
+
+    async fn on_memory_updated(&self, old: &Memory, new: &Memory) -> HookResult {
+        self.hook(EVENT_UPDATED, self.event(new, Some(old))).await
+    }
+
+    async fn before_memory_deleted(&self, memory: &Memory) -> HookResult {
+        self.hook(EVENT_BEFORE_DELETED, self.event(memory, None))
+            .await
+    }
+
+    async fn on_memory_reminder(&self, memory: &Memory) -> HookResult {
+        self.hook(EVENT_REMINDER, self.event(memory, None)).await
+    }
+
+    fn priority(&self) -> i32 {
+        self.module.config.priority
+    }
+
+    fn timeout_ms(&self) -> u64 {
+        self.module.config.timeout_ms
+    }
+
+    fn name(&self) -> &str {
+        &self.module.name
+    }
+}
+
+#[async_trait]
+impl StoreMiddleware for WasmHook {
+    fn name(&self) -> &str {
+        &self.module.name
+    }
+
+    /// Refuses the memory if the module vetoes it or fails
+    async fn before_store(&self, pending: &mut PendingMemory) -> Result<StoreFlow> {
+        let input = self.event(&pending.memory, None);
+        match self.run(EVENT_VALIDATE, input).await? {
+            GuestResponse::Continue => Ok(StoreFlow::Continue),
+            GuestResponse::Veto { reason } => Err(LocaiError::Memory(format!(
+                "Memory refused by '{}': {}",
+                self.module.name, reason
+            ))),
+        }
+    }
+}
+
+/// Register the `wasm` hook and store middleware plugins
+pub(crate) fn register_plugins(registry: &PluginRegistry) {
+    registry
+        .register_hook(WASM_PLUGIN, |options| {
+            Ok(Arc::new(WasmHook::from_options(options)?))
+        })
+        .expect("the wasm hook plugin is registered first");
+    registry
+        .register_store_middleware(WASM_PLUGIN, |options| {
+            Ok(Arc::new(WasmHook::from_options(options)?))
+        })
+        .expect("the wasm store middleware plugin is registered first");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MemoryType;
+    use crate::plugins::PluginKind;
+
+    /// Vetoes deletions and validations, logging every event
+    const VETO_MODULE: &str = r#"
+        (module
+          (import "locai" "log" (func $log (param i32 i32 i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{\"action\":\"veto\",\"reason\":\"kept\"}")
+          (data (i32.const 64) "event")
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "handle") (param $event i32) (param $ptr i32) (param $len i32) (result i64)
+            (call $log (i32.const 3) (i32.const 64) (i32.const 5))
+            (if (result i64)
+              (i32.or
+                (i32.eq (local.get $event) (i32.const 3))
+                (i32.eq (local.get $event) (i32.const 5)))
+              (then (i64.const 33))
+              (else (i64.const 0)))))
+    "#;
+
+    /// Never returns
+    const LOOP_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "handle") (param i32 i32 i32) (result i64)
+            (loop $forever (br $forever))
+            (i64.const 0)))
+    "#;
+
+    fn memory() -> Memory {
+        Memory::new(
+            "memory-1".to_string(),
+            "The ferry leaves at nine".to_string(),
+            MemoryType::Fact,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_veto_stops_deletion_and_refuses_memory() {
+        let hook = WasmHook::from_bytes(VETO_MODULE.as_bytes(), WasmHookConfig::default()).unwrap();
+        let memory = memory();
+
+        assert_eq!(hook.on_memory_created(&memory).await, HookResult::Continue);
+        assert_eq!(
+            hook.before_memory_deleted(&memory).await,
+            HookResult::Veto("kept".to_string())
+        );
+
+        let mut pending = PendingMemory::new(memory);
+        let error = hook.before_store(&mut pending).await.unwrap_err();
+        assert!(error.to_string().contains("kept"));
+    }
+
+    #[tokio::test]
+    async fn test_module_out_of_fuel_fails_the_event() {
+        let config = WasmHookConfig {
+            fuel: 10_000,
+            ..WasmHookConfig::default()
+        };
+        let hook = WasmHook::from_bytes(LOOP_MODULE.as_bytes(), config).unwrap();
+
+        // Hook failures are logged; validation failures refuse the memory
+        assert_eq!(
+            hook.before_memory_deleted(&memory()).await,
+            HookResult::Continue
+        );
+        let mut pending = PendingMemory::new(memory());
+        assert!(hook.before_store(&mut pending).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_module_over_memory_cap_fails_the_event() {
+        // 100 pages of 64KiB is well over a 1MiB cap
+        let module = r#"
+            (module
+              (memory (export "memory") 100)
+              (func (export "alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "handle") (param i32 i32 i32) (result i64) (i64.const 0)))
+        "#;
+        let config = WasmHookConfig {
+            max_memory_bytes: 1024 * 1024,
+            ..WasmHookConfig::default()
+        };
+        let hook = WasmHook::from_bytes(module.as_bytes(), config).unwrap();
+
+        let mut pending = PendingMemory::new(memory());
+        assert!(hook.before_store(&mut pending).await.is_err());
+    }
+
+    #[test]
+    fn test_invalid_modules_are_rejected() {
+        assert!(WasmHook::from_bytes(b"not a module", WasmHookConfig::default()).is_err());
+
+        // Only the `locai` host functions can be imported
+        let module = r#"(module (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))))"#;
+        assert!(WasmHook::from_bytes(module.as_bytes(), WasmHookConfig::default()).is_err());
+
+        assert!(WasmHook::from_options(&serde_json::Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_wasm_plugins_are_registered() {
+        let registry = PluginRegistry::global();
+        assert!(
+            registry
+                .registered(PluginKind::Hook)
+                .contains(&WASM_PLUGIN.to_string())
+        );
+        assert!(
+            registry
+                .registered(PluginKind::StoreMiddleware)
+                .contains(&WASM_PLUGIN.to_string())
+        );
+    }
+}
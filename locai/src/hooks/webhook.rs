@@ -119,7 +119,7 @@ impl Webhook {
     }
 
     /// Send a webhook request with retry logic
-    async fn send_with_retry(
+    pub(crate) async fn send_with_retry(
         &self,
         event_type: &str,
         payload: serde_json::Value,
@@ -297,6 +297,25 @@ impl MemoryHook for Webhook {
         }
     }
 
+    async fn on_memory_reminder(&self, memory: &Memory) -> HookResult {
+        let memory_json = serde_json::to_value(memory)
+            .unwrap_or_else(|_| serde_json::json!({"error": "Failed to serialize memory"}));
+
+        let payload = serde_json::json!({
+            "event": "memory.reminder",
+            "timestamp": Utc::now().to_rfc3339(),
+            "data": memory_json,
+        });
+
+        match self.send_with_retry("memory.reminder", payload).await {
+            Ok(_) => HookResult::Continue,
+            Err(e) => {
+                error!("Webhook hook failed for on_memory_reminder: {}", e);
+                HookResult::Continue
+            }
+        }
+    }
+
     fn timeout_ms(&self) -> u64 {
         self.timeout.as_millis() as u64
     }
@@ -0,0 +1,157 @@
+//! ID generation
+//!
+//! Memories and extracted entities get random UUIDs unless
+//! [`LocaiConfig::id_strategy`](crate::config::LocaiConfig::id_strategy)
+//! picks a deterministic [`IdStrategy`]:
+//!
+//! - [`IdStrategy::ContentHash`] derives the ID from the content (a name-based
+//!   UUID), so importing the same data twice finds the memories already there
+//!   instead of adding copies
+//! - [`IdStrategy::Ulid`] makes time-ordered ULIDs from the configured
+//!   [`Clock`](crate::clock::Clock) and, given a seed, reproducible entropy, so
+//!   tests produce the same IDs on every run
+//!
+//! The strategy names what Locai creates: memories added through the
+//! manager's `add_*` methods and [`Locai`](crate::simple::Locai)'s `remember*`
+//! methods, related memories, stored messages and extracted entities. A
+//! [`Memory`](crate::models::Memory) passed to `store_memory` keeps the ID it
+//! was built with; build it from
+//! [`MemoryManager::memory_builder`](crate::core::MemoryManager::memory_builder)
+//! to name it by the strategy.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::bench::SplitMix64;
+use crate::clock::ClockHandle;
+
+/// How new IDs are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IdStrategy {
+    /// Random (v4) UUIDs
+    #[default]
+    Random,
+    /// Name-based (v5) UUIDs of the content; the same content always gets the
+    /// same ID
+    ContentHash,
+    /// ULIDs: a millisecond timestamp from the clock followed by 80 bits of
+    /// entropy, drawn from `seed` when one is given
+    Ulid { seed: Option<u64> },
+}
+
+/// Makes IDs by an [`IdStrategy`]
+#[derive(Debug)]
+pub struct IdGenerator {
+    strategy: IdStrategy,
+    clock: ClockHandle,
+    entropy: Mutex<SplitMix64>,
+}
+
+impl IdStrategy {
+    /// Whether the strategy can produce the same ID again: from the same
+    /// content, or from the same seed and clock
+    pub fn is_deterministic(&self) -> bool {
+        !matches!(self, IdStrategy::Random)
+    }
+}
+
+impl IdGenerator {
+    pub fn new(strategy: IdStrategy, clock: ClockHandle) -> Self {
+        let seed = match strategy {
+            IdStrategy::Ulid { seed: Some(seed) } => seed,
+            _ => Uuid::new_v4().as_u64_pair().0,
+        };
+        Self {
+            strategy,
+            clock,
+            entropy: Mutex::new(SplitMix64::new(seed)),
+        }
+    }
+
+    pub fn strategy(&self) -> IdStrategy {
+        self.strategy
+    }
+
+    /// A new ID for a record with `content`
+    ///
+    /// Only [`IdStrategy::ContentHash`] looks at `content`.
+    pub fn generate(&self, content: &str) -> String {
+        match self.strategy {
+            IdStrategy::Random => Uuid::new_v4().to_string(),
+            IdStrategy::ContentHash => content_hash_id(content),
+            IdStrategy::Ulid { .. } => {
+                let millis = self.clock.now().timestamp_millis().max(0) as u128;
+                let mut entropy = self.entropy.lock().unwrap_or_else(|e| e.into_inner());
+                let random =
+                    (u128::from(entropy.next_u64()) << 16) | u128::from(entropy.next_u64() >> 48);
+                encode_ulid(((millis & 0xFFFF_FFFF_FFFF) << 80) | random)
+            }
+        }
+    }
+}
+
+/// The ID [`IdStrategy::ContentHash`] gives `content`
+pub fn content_hash_id(content: &str) -> String {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, content.as_bytes()).to_string()
+}
+
+/// Crockford base32, as ULIDs are written
+const ULID_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+fn encode_ulid(value: u128) -> String {
+    (0..26)
+        .map(|n| {
+            let shift = 125 - 5 * n;
+            char::from(ULID_ALPHABET[((value >> shift) & 0x1F) as usize])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Clock;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    #[derive(Debug)]
+    struct Fixed;
+
+    impl Clock for Fixed {
+        fn now(&self) -> DateTime<Utc> {
+            Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        let ids = IdGenerator::new(IdStrategy::ContentHash, ClockHandle::default());
+        assert_eq!(ids.generate("same"), ids.generate("same"));
+        assert_ne!(ids.generate("same"), ids.generate("different"));
+        assert_eq!(ids.generate("same"), content_hash_id("same"));
+    }
+
+    #[test]
+    fn test_seeded_ulids_repeat() {
+        let strategy = IdStrategy::Ulid { seed: Some(7) };
+        let first = IdGenerator::new(strategy, ClockHandle::new(Fixed));
+        let second = IdGenerator::new(strategy, ClockHandle::new(Fixed));
+        let a: Vec<String> = (0..3).map(|_| first.generate("")).collect();
+        let b: Vec<String> = (0..3).map(|_| second.generate("")).collect();
+        assert_eq!(a, b);
+        assert_ne!(a[0], a[1]);
+
+        // 26 characters, starting with the timestamp
+        assert_eq!(a[0].len(), 26);
+        let millis = Fixed.now().timestamp_millis() as u128;
+        assert_eq!(a[0][..10], encode_ulid(millis << 80)[..10]);
+    }
+
+    #[test]
+    fn test_random_ids_differ() {
+        let ids = IdGenerator::new(IdStrategy::Random, ClockHandle::default());
+        assert_ne!(ids.generate("same"), ids.generate("same"));
+    }
+}
@@ -0,0 +1,371 @@
+//! Bulk ingestion pipeline
+//!
+//! [`IngestJob`] stores a large stream of memories in the background. Memories
+//! are pulled from the source one batch at a time, so at most
+//! [`IngestConfig::batch_size`] of them are held in memory and a slow store
+//! applies backpressure to the producer. Each batch is written with a single
+//! storage call, while embedding and entity extraction run with bounded
+//! concurrency.
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use futures::stream;
+//! use locai::ingest::{IngestConfig, IngestJob};
+//! use locai::models::MemoryBuilder;
+//!
+//! # async fn example(manager: Arc<locai::core::MemoryManager>) -> locai::Result<()> {
+//! let memories = (0..500_000).map(|n| MemoryBuilder::new_with_content(format!("Note {}", n)).build());
+//! let job = IngestJob::spawn(manager, stream::iter(memories), IngestConfig::default());
+//!
+//! let mut progress = job.subscribe();
+//! while progress.changed().await.is_ok() {
+//!     println!("{} stored", progress.borrow().stored);
+//! }
+//! let summary = job.wait().await?;
+//! println!("{} stored, {} failed", summary.stored, summary.failed);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::{FutureExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::core::MemoryManager;
+use crate::models::Memory;
+use crate::{LocaiError, Result};
+
+/// Ingestion tuning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestConfig {
+    /// Memories written per storage call
+    pub batch_size: usize,
+    /// Memories embedded or run through entity extraction at once
+    pub concurrency: usize,
+    /// Failures kept in [`IngestProgress::failures`]; later ones are only counted
+    pub max_recorded_failures: usize,
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            concurrency: 4,
+            max_recorded_failures: 100,
+        }
+    }
+}
+
+/// Lifecycle of an ingestion job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestState {
+    Running,
+    Paused,
+    Cancelled,
+    Completed,
+}
+
+impl IngestState {
+    /// Whether the job has stopped for good
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Cancelled | Self::Completed)
+    }
+}
+
+/// A memory that could not be stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFailure {
+    /// Position of the memory in the source
+    pub index: usize,
+    pub error: String,
+}
+
+/// Snapshot of an ingestion job's progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestProgress {
+    pub state: IngestState,
+    /// Memories taken from the source so far
+    pub read: usize,
+    pub stored: usize,
+    pub failed: usize,
+    pub batches: usize,
+    pub failures: Vec<IngestFailure>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IngestProgress {
+    fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            state: IngestState::Running,
+            read: 0,
+            stored: 0,
+            failed: 0,
+            batches: 0,
+            failures: Vec::new(),
+            started_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Memories stored per second since the job started
+    pub fn per_second(&self) -> f64 {
+        let seconds = (self.updated_at - self.started_at).num_milliseconds() as f64 / 1000.0;
+        if seconds > 0.0 {
+            self.stored as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Requested state, set through the job handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Handle to a running ingestion job
+///
+/// Dropping the handle leaves a running job running, while a paused job is
+/// cancelled since nothing could resume it.
+#[derive(Debug)]
+pub struct IngestJob {
+    control: watch::Sender<Control>,
+    progress: watch::Receiver<IngestProgress>,
+    task: JoinHandle<IngestProgress>,
+}
+
+impl IngestJob {
+    /// Start ingesting `source` in the background
+    pub fn spawn<S>(manager: Arc<MemoryManager>, source: S, config: IngestConfig) -> Self
+    where
+        S: Stream<Item = Memory> + Send + 'static,
+    {
+        let (control, control_rx) = watch::channel(Control::Run);
+        let (progress_tx, progress) = watch::channel(IngestProgress::new());
+        let task = tokio::spawn(run(
+            manager,
+            Box::pin(source),
+            config,
+            control_rx,
+            progress_tx,
+        ));
+
+        Self {
+            control,
+            progress,
+            task,
+        }
+    }
+
+    /// Start a job fed through a bounded channel
+    ///
+    /// Sends wait while a full batch is queued, so producers can't outrun the
+    /// store. The job completes once every sender is dropped.
+    pub fn channel(manager: Arc<MemoryManager>, config: IngestConfig) -> (IngestSender, Self) {
+        let (tx, rx) = mpsc::channel(config.batch_size.max(1));
+        let source = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|memory| (memory, rx))
+        });
+        (IngestSender(tx), Self::spawn(manager, source, config))
+    }
+
+    /// The latest progress snapshot
+    pub fn progress(&self) -> IngestProgress {
+        self.progress.borrow().clone()
+    }
+
+    /// Watch progress, which is published after every batch and state change
+    pub fn subscribe(&self) -> watch::Receiver<IngestProgress> {
+        self.progress.clone()
+    }
+
+    /// Stop taking new batches until [`resume`](Self::resume) is called
+    ///
+    /// The batch in flight is finished first.
+    pub fn pause(&self) {
+        self.request(Control::Pause);
+    }
+
+    /// Continue a paused job
+    pub fn resume(&self) {
+        self.request(Control::Run);
+    }
+
+    /// Stop the job after the batch in flight
+    ///
+    /// Memories already stored are kept.
+    pub fn cancel(&self) {
+        self.request(Control::Cancel);
+    }
+
+    /// Whether the job has stopped
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Wait for the job to complete or be cancelled
+    pub async fn wait(self) -> Result<IngestProgress> {
+        self.task
+            .await
+            .map_err(|e| LocaiError::Other(format!("Ingestion job failed: {}", e)))
+    }
+
+    fn request(&self, requested: Control) {
+        // A cancelled job stays cancelled
+        self.control.send_if_modified(|current| {
+            let changed = *current != Control::Cancel && *current != requested;
+            if changed {
+                *current = requested;
+            }
+            changed
+        });
+    }
+}
+
+/// Producer side of [`IngestJob::channel`]
+#[derive(Debug, Clone)]
+pub struct IngestSender(mpsc::Sender<Memory>);
+
+impl IngestSender {
+    /// Queue a memory, waiting while the job is behind
+    ///
+    /// Fails once the job has been cancelled or has stopped.
+    pub async fn send(&self, memory: Memory) -> Result<()> {
+        self.0
+            .send(memory)
+            .await
+            .map_err(|_| LocaiError::Other("Ingestion job is no longer running".to_string()))
+    }
+}
+
+async fn run(
+    manager: Arc<MemoryManager>,
+    mut source: std::pin::Pin<Box<dyn Stream<Item = Memory> + Send>>,
+    config: IngestConfig,
+    mut control: watch::Receiver<Control>,
+    progress: watch::Sender<IngestProgress>,
+) -> IngestProgress {
+    let batch_size = config.batch_size.max(1);
+    let concurrency = config.concurrency.max(1);
+    let mut exhausted = false;
+
+    loop {
+        if !wait_while_paused(&mut control, &progress).await {
+            set_state(&progress, IngestState::Cancelled);
+            break;
+        }
+
+        // Wait for the next memory, unless asked to pause or stop meanwhile
+        let first = if control.has_changed().is_ok() {
+            tokio::select! {
+                memory = source.next() => memory,
+                _ = control.changed() => continue,
+            }
+        } else {
+            source.next().await
+        };
+        let Some(first) = first else {
+            set_state(&progress, IngestState::Completed);
+            break;
+        };
+
+        // Then batch up whatever else is ready, so a slow producer isn't held
+        // back waiting for a full batch
+        let mut batch = Vec::with_capacity(batch_size);
+        batch.push(first);
+        while batch.len() < batch_size {
+            match source.next().now_or_never() {
+                Some(Some(memory)) => batch.push(memory),
+                Some(None) => {
+                    exhausted = true;
+                    break;
+                }
+                None => break,
+            }
+        }
+
+        let first_index = progress.borrow().read;
+        let read = batch.len();
+        let results = manager.store_memory_batch(batch, concurrency).await;
+
+        progress.send_modify(|p| {
+            p.read += read;
+            p.batches += 1;
+            for (offset, result) in results.into_iter().enumerate() {
+                match result {
+                    Ok(_) => p.stored += 1,
+                    Err(e) => {
+                        p.failed += 1;
+                        if p.failures.len() < config.max_recorded_failures {
+                            p.failures.push(IngestFailure {
+                                index: first_index + offset,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            p.updated_at = Utc::now();
+        });
+
+        if exhausted {
+            set_state(&progress, IngestState::Completed);
+            break;
+        }
+    }
+
+    let summary = progress.borrow().clone();
+    tracing::info!(
+        "Ingestion {:?}: {} stored, {} failed in {} batches",
+        summary.state,
+        summary.stored,
+        summary.failed,
+        summary.batches
+    );
+    summary
+}
+
+/// Block while paused; returns false if the job should stop
+async fn wait_while_paused(
+    control: &mut watch::Receiver<Control>,
+    progress: &watch::Sender<IngestProgress>,
+) -> bool {
+    loop {
+        let requested = *control.borrow_and_update();
+        match requested {
+            Control::Run => {
+                set_state(progress, IngestState::Running);
+                return true;
+            }
+            Control::Cancel => return false,
+            Control::Pause => {
+                set_state(progress, IngestState::Paused);
+                // Every handle is gone, so nobody can resume the job
+                if control.changed().await.is_err() {
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+fn set_state(progress: &watch::Sender<IngestProgress>, state: IngestState) {
+    progress.send_if_modified(|p| {
+        if p.state == state {
+            return false;
+        }
+        p.state = state;
+        p.updated_at = Utc::now();
+        true
+    });
+}
@@ -51,27 +51,64 @@
 //! - **Core**: BM25 search, memory storage, graph relationships (always available)
 //! - **Optional**: Local ML models for advanced users (candle-embeddings feature)
 //! - **BYOE**: User-provided embeddings for vector/hybrid search
+//! - **Embedded**: With default features off, only [`models`] and [`search`]
+//!   build (no storage or tokio), so they compile to wasm32 for locai-js.
+//!   Storage and everything built on it need the `engine` feature.
 //!
 //! This crate provides the core library functionality that can be used directly
 //! in Rust applications or through the separate service crate.
 
+#[cfg(feature = "engine")]
 pub mod batch;
+#[cfg(feature = "engine")]
+pub mod bench;
+#[cfg(feature = "engine")]
+pub mod clock;
+#[cfg(feature = "engine")]
 pub mod config;
+#[cfg(feature = "engine")]
 pub mod core;
+#[cfg(feature = "engine")]
 pub mod entity_extraction;
+#[cfg(feature = "engine")]
+pub mod export;
+#[cfg(feature = "engine")]
 pub mod hooks;
+#[cfg(feature = "engine")]
+pub mod ids;
+#[cfg(feature = "engine")]
+pub mod ingest;
+#[cfg(feature = "engine")]
 pub mod logging;
+#[cfg(feature = "engine")]
 pub mod memory;
+#[cfg(feature = "engine")]
 pub mod messaging;
+#[cfg(feature = "engine")]
 pub mod ml;
 pub mod models;
+#[cfg(feature = "engine")]
+pub mod notifications;
+#[cfg(feature = "engine")]
+pub mod plugins;
+#[cfg(feature = "engine")]
 pub mod relationships;
+#[cfg(feature = "engine")]
+pub mod replication;
+#[cfg(feature = "engine")]
 pub mod runtime;
 pub mod search;
+#[cfg(feature = "engine")]
 pub mod simple;
+#[cfg(feature = "engine")]
 pub mod storage;
+#[cfg(feature = "engine")]
+pub mod synthetic;
+#[cfg(feature = "engine")]
+pub mod tokens;
 
 /// The prelude re-exports commonly used types for convenience
+#[cfg(feature = "engine")]
 pub mod prelude {
     // Re-export the simplified API (recommended for new users)
     pub use crate::simple::{Locai, LocaiBuilder, RememberBuilder, SearchBuilder};
@@ -92,17 +129,17 @@ pub mod prelude {
     };
 
     // Re-export model types
-    pub use crate::models::{Memory, MemoryBuilder, MemoryPriority, MemoryType};
+    pub use crate::models::{Memory, MemoryBuilder, MemoryPriority, MemoryType, SparseVector};
 
     // Re-export core types for advanced usage
     pub use crate::core::{
-        MemoryManager, SearchOptions, SearchResult, SearchStrategy, SearchTypeFilter,
+        MemoryApi, MemoryManager, SearchOptions, SearchResult, SearchStrategy, SearchTypeFilter,
     };
 
     // Re-export storage types for advanced usage
     pub use crate::storage::{
         StorageError,
-        models::{MemoryGraph, MemoryPath},
+        models::{MemoryGraph, MemoryPath, SearchHit},
     };
 
     // Re-export hooks types for custom hook implementations
@@ -139,6 +176,7 @@ pub enum LocaiError {
     Configuration(String),
 
     /// Logging error
+    #[cfg(feature = "engine")]
     #[error("Logging error: {0}")]
     Logging(#[from] crate::logging::LogError),
 
@@ -158,17 +196,83 @@ pub enum LocaiError {
     #[error("Version error: {0}")]
     Version(String),
 
+    /// Errors related to memory collections
+    #[error("Collection error: {0}")]
+    Collection(String),
+
+    /// Errors related to memory templates, such as missing or mistyped variables
+    #[error("Template error: {0}")]
+    Template(String),
+
+    /// Errors related to memory reminders, such as an unreadable recurrence
+    #[error("Reminder error: {0}")]
+    Reminder(String),
+
+    /// Errors related to tasks, such as a status change the task can't make
+    #[error("Task error: {0}")]
+    Task(String),
+
+    /// Errors related to procedures, such as one with no steps
+    #[error("Procedure error: {0}")]
+    Procedure(String),
+
+    /// Errors related to preferences, such as an empty key
+    #[error("Preference error: {0}")]
+    Preference(String),
+
+    /// Errors related to personas, such as one without an identity
+    #[error("Persona error: {0}")]
+    Persona(String),
+
+    /// Errors related to reflection, such as no reflector being configured
+    #[error("Reflection error: {0}")]
+    Reflection(String),
+
+    /// Errors related to sessions, such as no summarizer being configured
+    #[error("Session error: {0}")]
+    Session(String),
+
+    /// Errors reading a filter expression
+    #[error("Filter error: {0}")]
+    Filter(String),
+
+    /// Errors with scoring profiles
+    #[error("Scoring error: {0}")]
+    Scoring(String),
+
+    /// An embedding the vector index can't search
+    #[error("Invalid embedding: {0}")]
+    InvalidEmbedding(String),
+
+    /// An idempotency key reused for a different write
+    #[error("Idempotency error: {0}")]
+    Idempotency(String),
+
+    /// Errors with automation rules, such as a script that doesn't compile
+    #[error("Rule error: {0}")]
+    Rule(String),
+
+    /// Errors with the review queue, such as approving a memory not in it
+    #[error("Review error: {0}")]
+    Review(String),
+
+    /// Errors with notifications, such as an unknown target
+    #[error("Notification error: {0}")]
+    Notification(String),
+
+    /// Errors with usage accounting, such as an unknown report interval
+    #[error("Usage error: {0}")]
+    Usage(String),
+
     /// ML service not configured (with helpful guidance)
     #[error(
         "ML service not configured. To use semantic search, initialize with: Locai::builder().with_defaults().build().await or use ConfigBuilder::new().with_default_ml()"
     )]
     MLNotConfigured,
 
-    /// Storage directory not accessible
-    #[error(
-        "Storage directory not accessible: {path}. Ensure the directory exists and has write permissions, or try using in-memory storage with Locai::for_testing()"
-    )]
-    StorageNotAccessible { path: String },
+    /// Storage directory not accessible, with what to do about it
+    #[error("Storage directory not accessible: {path}. {hint}")]
+    StorageNotAccessible { path: String, hint: String },
 
     /// Invalid embedding model
     #[error(
@@ -204,6 +308,17 @@ pub enum LocaiError {
     )]
     NoMemoriesFound,
 
+    /// A write would take a tenant or agent past its quota
+    #[error(
+        "Quota exceeded for '{owner}': this write would use {used} {resource} of {limit} allowed. Delete or archive memories, or raise the limit in the quotas configuration"
+    )]
+    QuotaExceeded {
+        owner: String,
+        resource: String,
+        used: u64,
+        limit: u64,
+    },
+
     /// Feature not enabled
     #[error(
         "Feature '{feature}' is not enabled. Enable it in Cargo.toml with: features = [\"{feature}\"]"
@@ -215,12 +330,14 @@ pub enum LocaiError {
     Other(String),
 }
 
+#[cfg(feature = "engine")]
 impl From<crate::config::ConfigError> for LocaiError {
     fn from(err: crate::config::ConfigError) -> Self {
         LocaiError::Configuration(err.to_string())
     }
 }
 
+#[cfg(feature = "engine")]
 impl From<crate::ml::error::MLError> for LocaiError {
     fn from(err: crate::ml::error::MLError) -> Self {
         LocaiError::ML(err.to_string())
@@ -254,6 +371,7 @@ pub type Result<T> = std::result::Result<T, LocaiError>;
 ///     Ok(())
 /// }
 /// ```
+#[cfg(feature = "engine")]
 pub async fn init_with_defaults() -> Result<core::MemoryManager> {
     let config = config::ConfigBuilder::defaults().build()?;
     init(config).await
@@ -292,6 +410,7 @@ pub async fn init_with_defaults() -> Result<core::MemoryManager> {
 ///     Ok(())
 /// }
 /// ```
+#[cfg(feature = "engine")]
 pub async fn init(config: config::LocaiConfig) -> Result<core::MemoryManager> {
     // Initialize logging - always initialize since there's no "enabled" flag
     // Ignore errors if tracing is already initialized
@@ -299,20 +418,154 @@ pub async fn init(config: config::LocaiConfig) -> Result<core::MemoryManager> {
 
     // Entity extraction is now handled via examples - no auto-initialization needed
 
-    // Create storage service
+    // Create storage service, on its own runtime if configured so the
+    // engine's tasks stay off the caller's workers
     // Note: Explicitly mapping StorageError to LocaiError
-    let storage = storage::create_storage_service(&config)
-        .await
-        .map_err(|e| LocaiError::Storage(e.to_string()))?;
-    let storage = std::sync::Arc::from(storage);
+    let storage_runtime = match config.runtime.storage_io_threads {
+        Some(threads) => Some(
+            runtime::StorageRuntime::new(threads, &config.runtime).map_err(|e| {
+                LocaiError::Configuration(format!("Failed to create storage runtime: {}", e))
+            })?,
+        ),
+        None => None,
+    };
+    let storage = match &storage_runtime {
+        Some(storage_runtime) => {
+            let storage_config = config.clone();
+            storage_runtime
+                .handle()
+                .spawn(async move { storage::create_storage_service(&storage_config).await })
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Storage startup task failed: {}", e)))?
+        }
+        None => storage::create_storage_service(&config).await,
+    }
+    .map_err(|e| storage_startup_error(&config, e))?;
+    let storage: std::sync::Arc<dyn storage::GraphStore> = std::sync::Arc::from(storage);
+
+    // Apply writes queued while a previous run found the database locked
+    if storage
+        .as_any()
+        .downcast_ref::<storage::degraded::DegradedStorage>()
+        .is_none()
+        && let Some(report) =
+            storage::degraded::replay_pending_writes(storage.clone(), &config).await?
+    {
+        tracing::info!(
+            "Replayed writes queued while storage was locked: {} applied, {} conflicts, {} failed",
+            report.applied,
+            report.conflicts.len(),
+            report.failed.len()
+        );
+    }
 
     // Don't create ML service by default - users must explicitly configure it
     // Having URLs/defaults configured doesn't mean it will actually work without API keys, etc.
     let ml_service = None;
 
     // Create MemoryManager with ML extractors initialized
-    let memory_manager =
+    let mut memory_manager =
         core::MemoryManager::new_with_ml(storage, ml_service, config.clone()).await?;
+    if let Some(storage_runtime) = storage_runtime {
+        memory_manager.set_storage_runtime(storage_runtime);
+    }
+
+    // Attach the plugins extension crates registered and config enables
+    let memory_manager = plugins::PluginRegistry::global()
+        .apply(memory_manager, &config.plugins)
+        .await?;
+
+    // Run the automations the rules configuration lists
+    if config.rules.enabled && !config.rules.automations.is_empty() {
+        memory_manager.enable_automations().await?;
+    }
+
+    // Send notifications for review queue items and reminders
+    if config.notifications.enabled && memory_manager.hook_registry().is_some() {
+        memory_manager.enable_notifications().await?;
+    }
 
     Ok(memory_manager)
 }
+
+/// Explain a locked database; other storage errors pass through
+#[cfg(feature = "engine")]
+fn storage_startup_error(config: &config::LocaiConfig, e: storage::StorageError) -> LocaiError {
+    let message = e.to_string();
+    if !storage::degraded::is_lock_error(&message) {
+        return LocaiError::Storage(message);
+    }
+    let hint = if config.storage.sharding.shards > 1 {
+        "Another process has the database open. Stop it, or reach it through locai-server."
+    } else {
+        "Another process has the database open. Stop it, reach it through locai-server, or set storage.on_locked to \"read_only\" or \"overlay\" to start from a snapshot of it."
+    };
+    LocaiError::StorageNotAccessible {
+        path: config.storage.graph.surrealdb.connection.clone(),
+        hint: hint.to_string(),
+    }
+}
+
+/// Initialize Locai on an existing tokio runtime
+///
+/// Like [`init`], but initialization runs on `handle`, so the storage engine
+/// and Locai's background tasks (lifecycle flushes, retention, archiving) are
+/// spawned there instead of on the calling runtime. Use this to embed Locai
+/// in a server that already runs a tuned runtime when the caller isn't on it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use locai::prelude::*;
+///
+/// async fn example(server_runtime: tokio::runtime::Handle) -> Result<()> {
+///     let config = ConfigBuilder::new().with_memory_storage().build()?;
+///     let memory_manager = locai::init_on_runtime(config, &server_runtime).await?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "engine")]
+pub async fn init_on_runtime(
+    config: config::LocaiConfig,
+    handle: &tokio::runtime::Handle,
+) -> Result<core::MemoryManager> {
+    handle
+        .spawn(init(config))
+        .await
+        .map_err(|e| LocaiError::Other(format!("Initialization task failed: {}", e)))?
+}
+
+/// Connect to the memories `config` describes, local or remote
+///
+/// With `remote` set, memories live on that locai-server, which needs the
+/// `remote` feature; otherwise this initializes local storage like [`init`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use locai::prelude::*;
+///
+/// async fn example() -> Result<()> {
+///     let config = ConfigBuilder::new()
+///         .with_remote("http://localhost:3000")
+///         .with_remote_credentials("agent", "secret")
+///         .build()?;
+///     let memories = locai::connect(config).await?;
+///     memories.store_memory(Memory::new(String::new(), "The sky is blue".into(), MemoryType::Fact)).await?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "engine")]
+pub async fn connect(config: config::LocaiConfig) -> Result<std::sync::Arc<dyn core::MemoryApi>> {
+    match &config.remote {
+        #[cfg(feature = "remote")]
+        Some(remote) => Ok(std::sync::Arc::new(core::RemoteMemoryManager::from_config(
+            remote,
+        )?)),
+        #[cfg(not(feature = "remote"))]
+        Some(_) => Err(LocaiError::FeatureNotEnabled {
+            feature: "remote".to_string(),
+        }),
+        None => Ok(std::sync::Arc::new(init(config).await?)),
+    }
+}
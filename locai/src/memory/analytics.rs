@@ -6,6 +6,7 @@
 use super::TimeRange;
 use crate::core::MemoryManager;
 use crate::models::{Memory, MemoryType};
+use crate::notifications::Notification;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -85,6 +86,19 @@ impl MemoryAnalyticsEngine {
         Ok(self.calculate_efficiency_metrics_sync(&filtered_memories))
     }
 
+    /// Detect anomalies in memory usage and send a notification for each
+    /// (see [`crate::notifications`])
+    pub async fn notify_anomalies(&self, time_range: &TimeRange) -> Result<Vec<MemoryAnomaly>> {
+        let anomalies = self.detect_anomalies(time_range).await?;
+        let notifications = self.memory_manager.notifications();
+        for anomaly in &anomalies {
+            notifications
+                .dispatch(&Notification::anomaly(anomaly))
+                .await;
+        }
+        Ok(anomalies)
+    }
+
     /// Detect anomalies in memory usage
     async fn detect_anomalies(&self, time_range: &TimeRange) -> Result<Vec<MemoryAnomaly>> {
         let memories = self.memory_manager.search_memories("", Some(10000)).await?;
@@ -276,7 +290,7 @@ impl MemoryAnalyticsEngine {
                         memory.content.len() as f32 / avg_size
                     ),
                     severity: AnomalySeverity::Medium,
-                    detected_at: Utc::now(),
+                    detected_at: self.memory_manager.clock().now(),
                 });
             }
         }
@@ -290,7 +304,7 @@ impl MemoryAnalyticsEngine {
                     memory_id: memory.id.clone(),
                     description: "Memory has no tags, reducing discoverability".to_string(),
                     severity: AnomalySeverity::Low,
-                    detected_at: Utc::now(),
+                    detected_at: self.memory_manager.clock().now(),
                 });
             }
         }
@@ -28,7 +28,11 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_fact<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::fact(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::Fact)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -40,7 +44,11 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_conversation<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::conversation(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::Conversation)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -52,7 +60,11 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_procedural<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::procedural(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::Procedural)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -64,7 +76,11 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_episodic<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::episodic(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::Episodic)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -76,7 +92,12 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_identity<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::identity(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::Identity)
+            .priority(MemoryPriority::High)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -88,7 +109,11 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_world<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::world(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::World)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -100,7 +125,11 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_action<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::action(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::Action)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -112,7 +141,11 @@ impl MemoryBuilders {
     /// # Returns
     /// The ID of the stored memory
     pub async fn add_event<S: Into<String>>(&self, content: S) -> Result<String> {
-        let memory = MemoryBuilder::event(content).build();
+        let memory = self
+            .operations
+            .memory_builder(content)
+            .memory_type(MemoryType::Event)
+            .build();
         self.operations.store_memory(memory).await
     }
 
@@ -129,7 +162,9 @@ impl MemoryBuilders {
         content: S,
         memory_type: MemoryType,
     ) -> Result<String> {
-        let memory = MemoryBuilder::new_with_content(content)
+        let memory = self
+            .operations
+            .memory_builder(content)
             .memory_type(memory_type)
             .build();
         self.operations.store_memory(memory).await
@@ -148,7 +183,7 @@ impl MemoryBuilders {
         S: Into<String>,
         F: FnOnce(MemoryBuilder) -> MemoryBuilder,
     {
-        let builder = MemoryBuilder::new_with_content(content);
+        let builder = self.operations.memory_builder(content);
         let memory = options(builder).build();
         self.operations.store_memory(memory).await
     }
@@ -168,7 +203,9 @@ impl MemoryBuilders {
         memory_type: MemoryType,
         priority: MemoryPriority,
     ) -> Result<String> {
-        let memory = MemoryBuilder::new_with_content(content)
+        let memory = self
+            .operations
+            .memory_builder(content)
             .memory_type(memory_type)
             .priority(priority)
             .build();
@@ -0,0 +1,364 @@
+//! Collections of memories
+//!
+//! A collection (or notebook) groups memories under a unique name. Members
+//! are listed by ID, picked by a [`CollectionQuery`], or both; a query keeps
+//! the collection up to date with memories stored later. Memories can belong
+//! to any number of collections, and deleting a collection leaves its
+//! memories alone. IDs of memories deleted since they were added are skipped.
+//!
+//! Collections are stored as entities of type [`COLLECTION_ENTITY_TYPE`].
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Memory;
+use crate::storage::filters::{EntityFilter, MemoryFilter, SemanticSearchFilter};
+use crate::storage::models::Entity;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Entity type collections are stored under
+pub const COLLECTION_ENTITY_TYPE: &str = "memory_collection";
+
+/// A named group of memories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: String,
+
+    /// Unique name
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Memories added by hand, in the order they were added
+    #[serde(default)]
+    pub memory_ids: Vec<String>,
+
+    /// Memories matching this query belong to the collection too
+    // Written even when absent, so an update clears it
+    #[serde(default)]
+    pub query: Option<CollectionQuery>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Collection {
+    /// Create an empty collection
+    pub fn new(name: impl Into<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.into(),
+            description: None,
+            memory_ids: Vec::new(),
+            query: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_query(mut self, query: CollectionQuery) -> Self {
+        self.query = Some(query);
+        self
+    }
+
+    pub fn with_memories(mut self, memory_ids: impl IntoIterator<Item = String>) -> Self {
+        self.add(memory_ids);
+        self
+    }
+
+    /// Add memory IDs not already listed; returns how many were new
+    fn add(&mut self, memory_ids: impl IntoIterator<Item = String>) -> usize {
+        let before = self.memory_ids.len();
+        for id in memory_ids {
+            if !self.memory_ids.contains(&id) {
+                self.memory_ids.push(id);
+            }
+        }
+        self.memory_ids.len() - before
+    }
+
+    fn from_entity(entity: Entity) -> Option<Self> {
+        serde_json::from_value(entity.properties).ok()
+    }
+
+    fn to_entity(&self) -> Entity {
+        Entity {
+            id: self.id.clone(),
+            entity_type: COLLECTION_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(self).unwrap_or_default(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// Which memories a query-based collection includes
+///
+/// Every condition given must hold.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectionQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_type: Option<String>,
+
+    /// Memories with any of these tags
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Memories whose content contains this text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl CollectionQuery {
+    pub fn to_filter(&self) -> MemoryFilter {
+        MemoryFilter {
+            memory_type: self.memory_type.clone(),
+            tags: self.tags.clone(),
+            source: self.source.clone(),
+            content: self.content.clone(),
+            created_after: self.created_after,
+            created_before: self.created_before,
+            ..Default::default()
+        }
+    }
+}
+
+/// Narrow a search filter to the given memories
+///
+/// IDs the filter already names are kept only if they are members.
+pub fn scope_to_members(
+    filter: Option<SemanticSearchFilter>,
+    members: HashSet<String>,
+) -> SemanticSearchFilter {
+    let mut filter = filter.unwrap_or_default();
+    let mut memory_filter = filter.memory_filter.take().unwrap_or_default();
+    memory_filter.ids = Some(match memory_filter.ids {
+        Some(ids) => ids.into_iter().filter(|id| members.contains(id)).collect(),
+        None => members.into_iter().collect(),
+    });
+    filter.memory_filter = Some(memory_filter);
+    filter
+}
+
+/// Stores collections and resolves their members
+#[derive(Debug)]
+pub struct CollectionStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl CollectionStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// Store a new collection
+    pub async fn create(&self, collection: Collection) -> Result<Collection> {
+        self.check_name(&collection).await?;
+        self.storage
+            .create_entity(collection.to_entity())
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to store collection: {}", e)))?;
+        Ok(collection)
+    }
+
+    /// Find a collection by ID, or else by name
+    pub async fn get(&self, id_or_name: &str) -> Result<Option<Collection>> {
+        let entity = self
+            .storage
+            .get_entity(id_or_name)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get collection: {}", e)))?;
+        if let Some(entity) = entity.filter(|e| e.entity_type == COLLECTION_ENTITY_TYPE) {
+            return Ok(Collection::from_entity(entity));
+        }
+        Ok(self
+            .list()
+            .await?
+            .into_iter()
+            .find(|collection| collection.name == id_or_name))
+    }
+
+    /// All collections, by name
+    pub async fn list(&self) -> Result<Vec<Collection>> {
+        let filter = EntityFilter {
+            entity_type: Some(COLLECTION_ENTITY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let entities = self
+            .storage
+            .list_entities(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list collections: {}", e)))?;
+        let mut collections: Vec<Collection> = entities
+            .into_iter()
+            .filter_map(Collection::from_entity)
+            .collect();
+        collections.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(collections)
+    }
+
+    /// Save changes to a collection
+    pub async fn update(&self, mut collection: Collection) -> Result<Collection> {
+        self.check_name(&collection).await?;
+        collection.updated_at = Utc::now();
+        self.storage
+            .update_entity(collection.to_entity())
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to update collection: {}", e)))?;
+        Ok(collection)
+    }
+
+    /// Delete a collection, keeping its memories
+    pub async fn delete(&self, id_or_name: &str) -> Result<bool> {
+        let Some(collection) = self.get(id_or_name).await? else {
+            return Ok(false);
+        };
+        self.storage
+            .delete_entity(&collection.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to delete collection: {}", e)))
+    }
+
+    /// Add memories to a collection
+    ///
+    /// Fails if any of the memories doesn't exist. Returns `None` when there is
+    /// no such collection.
+    pub async fn add_memories(
+        &self,
+        id_or_name: &str,
+        memory_ids: &[String],
+    ) -> Result<Option<Collection>> {
+        let Some(mut collection) = self.get(id_or_name).await? else {
+            return Ok(None);
+        };
+        for id in memory_ids {
+            let exists = self
+                .storage
+                .get_memory(id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+                .is_some();
+            if !exists {
+                return Err(LocaiError::Collection(format!(
+                    "Memory {} not found, so it can't join collection '{}'",
+                    id, collection.name
+                )));
+            }
+        }
+        if collection.add(memory_ids.iter().cloned()) == 0 {
+            return Ok(Some(collection));
+        }
+        self.update(collection).await.map(Some)
+    }
+
+    /// Remove memories added by hand from a collection
+    ///
+    /// Memories the collection's query matches stay members. Returns `None`
+    /// when there is no such collection.
+    pub async fn remove_memories(
+        &self,
+        id_or_name: &str,
+        memory_ids: &[String],
+    ) -> Result<Option<Collection>> {
+        let Some(mut collection) = self.get(id_or_name).await? else {
+            return Ok(None);
+        };
+        let before = collection.memory_ids.len();
+        collection.memory_ids.retain(|id| !memory_ids.contains(id));
+        if collection.memory_ids.len() == before {
+            return Ok(Some(collection));
+        }
+        self.update(collection).await.map(Some)
+    }
+
+    /// IDs of the collection's members
+    ///
+    /// May include IDs of memories deleted since they were added.
+    pub async fn member_ids(&self, collection: &Collection) -> Result<HashSet<String>> {
+        let mut ids: HashSet<String> = collection.memory_ids.iter().cloned().collect();
+        if let Some(query) = &collection.query {
+            ids.extend(self.query_members(query).await?.into_iter().map(|m| m.id));
+        }
+        Ok(ids)
+    }
+
+    /// The collection's memories, newest first
+    pub async fn members(
+        &self,
+        collection: &Collection,
+        limit: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        let mut members = Vec::new();
+        for id in &collection.memory_ids {
+            let memory = self
+                .storage
+                .get_memory(id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+            members.extend(memory);
+        }
+        if let Some(query) = &collection.query {
+            let listed: HashSet<String> = collection.memory_ids.iter().cloned().collect();
+            members.extend(
+                self.query_members(query)
+                    .await?
+                    .into_iter()
+                    .filter(|memory| !listed.contains(&memory.id)),
+            );
+        }
+
+        members.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        if let Some(limit) = limit {
+            members.truncate(limit);
+        }
+        Ok(members)
+    }
+
+    async fn query_members(&self, query: &CollectionQuery) -> Result<Vec<Memory>> {
+        self.storage
+            .list_memories(Some(query.to_filter()), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to query collection members: {}", e)))
+    }
+
+    /// Names are unique, and not empty
+    async fn check_name(&self, collection: &Collection) -> Result<()> {
+        if collection.name.trim().is_empty() {
+            return Err(LocaiError::Collection(
+                "Collection name cannot be empty".to_string(),
+            ));
+        }
+        let taken = self
+            .list()
+            .await?
+            .iter()
+            .any(|other| other.name == collection.name && other.id != collection.id);
+        if taken {
+            return Err(LocaiError::Collection(format!(
+                "A collection named '{}' already exists",
+                collection.name
+            )));
+        }
+        Ok(())
+    }
+}
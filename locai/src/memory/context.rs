@@ -0,0 +1,201 @@
+//! Context building for prompts
+//!
+//! [`MemoryContext`] gathers what an agent should know before it answers a
+//! query: its persona, the preferences in effect for its scope, the
+//! conversation so far, then the memories pinned there and the best search
+//! matches for the query. It renders as one block of text and can be trimmed
+//! to a token budget.
+
+use serde::{Deserialize, Serialize};
+
+use crate::memory::SearchMode;
+use crate::memory::personas::Persona;
+use crate::memory::preferences::Preference;
+use crate::memory::sessions::{SessionSummary, Turn};
+use crate::storage::models::SearchResult;
+use crate::tokens::{ApproxTokenCounter, TokenCounter};
+
+/// What goes into a built context
+#[derive(Debug, Clone)]
+pub struct ContextOptions {
+    /// Scope for preferences and pins, such as an agent or user ID; global
+    /// preferences and pins apply either way
+    pub scope: Option<String>,
+
+    /// Most search matches to include, on top of pinned memories
+    pub limit: usize,
+
+    pub search_mode: SearchMode,
+
+    /// Approximate token budget for the rendered context
+    pub max_tokens: Option<usize>,
+
+    /// Whether the scope's preferences lead the context
+    pub include_preferences: bool,
+
+    /// Agent whose persona opens the context
+    pub persona: Option<String>,
+
+    /// Session whose summary and recent turns are included
+    pub session: Option<String>,
+}
+
+impl Default for ContextOptions {
+    fn default() -> Self {
+        Self {
+            scope: None,
+            limit: 10,
+            search_mode: SearchMode::Text,
+            max_tokens: None,
+            include_preferences: true,
+            persona: None,
+            session: None,
+        }
+    }
+}
+
+impl ContextOptions {
+    pub fn for_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn search_mode(mut self, search_mode: SearchMode) -> Self {
+        self.search_mode = search_mode;
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn without_preferences(mut self) -> Self {
+        self.include_preferences = false;
+        self
+    }
+
+    pub fn with_persona(mut self, agent: impl Into<String>) -> Self {
+        self.persona = Some(agent.into());
+        self
+    }
+
+    pub fn for_session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(session.into());
+        self
+    }
+}
+
+/// Persona, preferences, conversation and memories gathered for a query
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryContext {
+    /// The agent's persona, if one was asked for and is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<Persona>,
+
+    /// Preferences in effect for the scope, by key
+    pub preferences: Vec<Preference>,
+
+    /// The session's summary of its older turns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<SessionSummary>,
+
+    /// The session's turns not yet summarized, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub turns: Vec<Turn>,
+
+    /// Pinned memories, then search matches, best first
+    pub memories: Vec<SearchResult>,
+}
+
+impl MemoryContext {
+    /// The context as text for a prompt
+    pub fn render(&self) -> String {
+        let mut sections = Vec::new();
+        if let Some(persona) = &self.persona {
+            sections.push(format!("Persona:\n{}", persona.render()));
+        }
+        if !self.preferences.is_empty() {
+            let mut section = "Preferences:".to_string();
+            for preference in &self.preferences {
+                section.push_str(&format!("\n- {}", preference.render()));
+            }
+            sections.push(section);
+        }
+        if let Some(summary) = &self.summary {
+            sections.push(format!("Conversation so far:\n{}", summary.content));
+        }
+        if !self.turns.is_empty() {
+            let mut section = "Recent turns:".to_string();
+            for turn in &self.turns {
+                section.push_str(&format!("\n- {}", turn.render()));
+            }
+            sections.push(section);
+        }
+        if !self.memories.is_empty() {
+            let mut section = "Memories:".to_string();
+            for result in &self.memories {
+                section.push_str(&format!("\n- {}", result.memory.content));
+            }
+            sections.push(section);
+        }
+        sections.join("\n\n")
+    }
+
+    /// Approximate tokens taken by the rendered context
+    pub fn estimated_tokens(&self) -> usize {
+        self.count_tokens(&ApproxTokenCounter::default())
+    }
+
+    /// Tokens taken by the rendered context, as `counter` counts them
+    pub fn count_tokens(&self, counter: &dyn TokenCounter) -> usize {
+        counter.count(&self.render())
+    }
+
+    /// Drop memories, lowest ranked first, until the rendered context fits
+    /// in about `max_tokens`
+    ///
+    /// See [`fit_to_budget_with`](Self::fit_to_budget_with), which counts
+    /// tokens exactly.
+    pub fn fit_to_budget(self, max_tokens: usize) -> Self {
+        self.fit_to_budget_with(max_tokens, &ApproxTokenCounter::default())
+    }
+
+    /// Drop memories, lowest ranked first, until the rendered context fits
+    /// in `max_tokens` as `counter` counts them
+    ///
+    /// Once no memories are left, the oldest turns go, then the summary.
+    /// Preferences are dropped, last key first, only after the conversation,
+    /// and the persona only once no preferences are.
+    pub fn fit_to_budget_with(mut self, max_tokens: usize, counter: &dyn TokenCounter) -> Self {
+        while self.count_tokens(counter) > max_tokens {
+            if self.memories.pop().is_some() {
+                continue;
+            }
+            if !self.turns.is_empty() {
+                self.turns.remove(0);
+                continue;
+            }
+            if self.summary.take().is_none()
+                && self.preferences.pop().is_none()
+                && self.persona.take().is_none()
+            {
+                break;
+            }
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.persona.is_none()
+            && self.preferences.is_empty()
+            && self.summary.is_none()
+            && self.turns.is_empty()
+            && self.memories.is_empty()
+    }
+}
@@ -0,0 +1,431 @@
+//! Mermaid and PlantUML diagrams of the graph
+//!
+//! [`build_diagram`] selects part of the graph, either the neighbourhood of a
+//! memory or entity or every relationship of the chosen types, and
+//! [`Diagram`] renders it as Mermaid or PlantUML source that wikis and
+//! Markdown renderers draw directly.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::memory::subgraph::neighbors;
+use crate::replication::RecordKind;
+use crate::storage::filters::RelationshipFilter;
+use crate::storage::models::Relationship;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Longest label drawn, in characters
+const LABEL_CHARS: usize = 40;
+
+/// Relationships read when drawing the whole graph
+const RELATIONSHIP_LIMIT: usize = 1000;
+
+/// Which part of the graph a diagram shows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagramFilter {
+    /// Draw the neighbourhood of this memory or entity; every matching
+    /// relationship when `None`
+    pub root_id: Option<String>,
+
+    /// Hops walked out from the root
+    pub depth: u8,
+
+    /// Only follow these relationship types; all of them when empty
+    pub relationship_types: Vec<String>,
+
+    /// Only draw entities of these types; all of them when empty
+    pub entity_types: Vec<String>,
+
+    /// Draw memories as well as entities
+    pub include_memories: bool,
+
+    /// Most nodes drawn
+    pub max_nodes: usize,
+}
+
+impl Default for DiagramFilter {
+    fn default() -> Self {
+        Self {
+            root_id: None,
+            depth: 2,
+            relationship_types: Vec::new(),
+            entity_types: Vec::new(),
+            include_memories: true,
+            max_nodes: 50,
+        }
+    }
+}
+
+/// Diagram source format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagramFormat {
+    #[default]
+    Mermaid,
+    PlantUml,
+}
+
+/// A memory or entity in a diagram
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagramNode {
+    pub id: String,
+    pub kind: RecordKind,
+
+    /// Memory content or entity name
+    pub label: String,
+
+    /// Memory type or entity type
+    pub node_type: String,
+}
+
+/// A relationship drawn between two diagram nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagramEdge {
+    pub source_id: String,
+    pub target_id: String,
+    pub relationship_type: String,
+}
+
+/// Nodes, in drawing order, and the relationships between them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Diagram {
+    pub nodes: Vec<DiagramNode>,
+    pub edges: Vec<DiagramEdge>,
+}
+
+impl Diagram {
+    /// Render in `format`
+    pub fn render(&self, format: DiagramFormat) -> String {
+        match format {
+            DiagramFormat::Mermaid => self.to_mermaid(),
+            DiagramFormat::PlantUml => self.to_plantuml(),
+        }
+    }
+
+    /// A left-to-right Mermaid flowchart
+    ///
+    /// Memories are drawn as boxes and entities as rounded nodes, each
+    /// labelled with its name and type.
+    pub fn to_mermaid(&self) -> String {
+        let aliases = self.aliases();
+        let mut lines = vec!["flowchart LR".to_string()];
+        for node in &self.nodes {
+            let text = format!(
+                "{}<br/><i>{}</i>",
+                mermaid_text(&node.label),
+                mermaid_text(&node.node_type)
+            );
+            let shape = match node.kind {
+                RecordKind::Entity => format!("(\"{}\")", text),
+                _ => format!("[\"{}\"]", text),
+            };
+            lines.push(format!("    {}{}", aliases[node.id.as_str()], shape));
+        }
+        for edge in &self.edges {
+            if let (Some(source), Some(target)) = (
+                aliases.get(edge.source_id.as_str()),
+                aliases.get(edge.target_id.as_str()),
+            ) {
+                lines.push(format!(
+                    "    {} -->|\"{}\"| {}",
+                    source,
+                    mermaid_text(&edge.relationship_type),
+                    target
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// A left-to-right PlantUML diagram
+    ///
+    /// Memories are drawn as cards and entities as rectangles.
+    pub fn to_plantuml(&self) -> String {
+        let aliases = self.aliases();
+        let mut lines = vec![
+            "@startuml".to_string(),
+            "left to right direction".to_string(),
+        ];
+        for node in &self.nodes {
+            let element = match node.kind {
+                RecordKind::Entity => "rectangle",
+                _ => "card",
+            };
+            lines.push(format!(
+                "{} \"{}\\n//{}//\" as {}",
+                element,
+                plantuml_text(&node.label),
+                plantuml_text(&node.node_type),
+                aliases[node.id.as_str()]
+            ));
+        }
+        for edge in &self.edges {
+            if let (Some(source), Some(target)) = (
+                aliases.get(edge.source_id.as_str()),
+                aliases.get(edge.target_id.as_str()),
+            ) {
+                lines.push(format!(
+                    "{} --> {} : {}",
+                    source,
+                    target,
+                    plantuml_text(&edge.relationship_type)
+                ));
+            }
+        }
+        lines.push("@enduml".to_string());
+        lines.join("\n")
+    }
+
+    /// `n0`, `n1`, ... by drawing order, since IDs are not valid identifiers
+    /// in either syntax
+    fn aliases(&self) -> HashMap<&str, String> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id.as_str(), format!("n{}", index)))
+            .collect()
+    }
+}
+
+/// Select the part of the graph described by `filter`
+///
+/// With a root, the root is drawn first and always kept; nodes are added in
+/// the order the walk reaches them until `max_nodes` is reached.
+pub async fn build_diagram(
+    storage: &Arc<dyn GraphStore>,
+    filter: &DiagramFilter,
+) -> Result<Diagram> {
+    let mut builder = DiagramBuilder {
+        storage,
+        filter,
+        diagram: Diagram::default(),
+        drawn: HashSet::new(),
+        skipped: HashSet::new(),
+    };
+
+    match &filter.root_id {
+        Some(root_id) => {
+            let Some(root) = lookup(storage, root_id).await? else {
+                return Ok(Diagram::default());
+            };
+            builder.drawn.insert(root.id.clone());
+            builder.diagram.nodes.push(root);
+
+            let mut seen = HashSet::new();
+            let mut queue = VecDeque::from([(root_id.clone(), 0u8)]);
+            while let Some((id, distance)) = queue.pop_front() {
+                if distance >= filter.depth {
+                    continue;
+                }
+                for relationship in neighbors(storage, &id).await? {
+                    if !builder.follows(&relationship) || !seen.insert(relationship.id.clone()) {
+                        continue;
+                    }
+                    let other_id = if relationship.source_id == id {
+                        &relationship.target_id
+                    } else {
+                        &relationship.source_id
+                    };
+                    let newly_drawn = !builder.drawn.contains(other_id);
+                    if builder.draw(other_id).await? {
+                        if newly_drawn {
+                            queue.push_back((other_id.clone(), distance + 1));
+                        }
+                        builder.push_edge(relationship);
+                    }
+                }
+            }
+        }
+        None => {
+            let filters: Vec<Option<RelationshipFilter>> = if filter.relationship_types.is_empty() {
+                vec![None]
+            } else {
+                filter
+                    .relationship_types
+                    .iter()
+                    .map(|relationship_type| {
+                        Some(RelationshipFilter {
+                            relationship_type: Some(relationship_type.clone()),
+                            ..Default::default()
+                        })
+                    })
+                    .collect()
+            };
+            for relationship_filter in filters {
+                let relationships = storage
+                    .list_relationships(relationship_filter, Some(RELATIONSHIP_LIMIT), None)
+                    .await
+                    .map_err(|e| {
+                        LocaiError::Storage(format!("Failed to list relationships: {}", e))
+                    })?;
+                for relationship in relationships {
+                    if builder.draw(&relationship.source_id).await?
+                        && builder.draw(&relationship.target_id).await?
+                    {
+                        builder.push_edge(relationship);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(builder.diagram)
+}
+
+struct DiagramBuilder<'a> {
+    storage: &'a Arc<dyn GraphStore>,
+    filter: &'a DiagramFilter,
+    diagram: Diagram,
+    drawn: HashSet<String>,
+
+    /// Nodes looked up and left out, so they are not looked up again
+    skipped: HashSet<String>,
+}
+
+impl DiagramBuilder<'_> {
+    fn follows(&self, relationship: &Relationship) -> bool {
+        self.filter.relationship_types.is_empty()
+            || self
+                .filter
+                .relationship_types
+                .contains(&relationship.relationship_type)
+    }
+
+    /// Make sure `id` is drawn, returning whether it is
+    async fn draw(&mut self, id: &str) -> Result<bool> {
+        if self.drawn.contains(id) {
+            return Ok(true);
+        }
+        if self.skipped.contains(id) || self.diagram.nodes.len() >= self.filter.max_nodes {
+            return Ok(false);
+        }
+
+        let node = lookup(self.storage, id)
+            .await?
+            .filter(|node| match node.kind {
+                RecordKind::Entity => {
+                    self.filter.entity_types.is_empty()
+                        || self.filter.entity_types.contains(&node.node_type)
+                }
+                _ => self.filter.include_memories,
+            });
+        match node {
+            Some(node) => {
+                self.drawn.insert(id.to_string());
+                self.diagram.nodes.push(node);
+                Ok(true)
+            }
+            None => {
+                self.skipped.insert(id.to_string());
+                Ok(false)
+            }
+        }
+    }
+
+    fn push_edge(&mut self, relationship: Relationship) {
+        self.diagram.edges.push(DiagramEdge {
+            source_id: relationship.source_id,
+            target_id: relationship.target_id,
+            relationship_type: relationship.relationship_type,
+        });
+    }
+}
+
+/// Look `id` up as a memory, then as an entity
+async fn lookup(storage: &Arc<dyn GraphStore>, id: &str) -> Result<Option<DiagramNode>> {
+    let memory = storage
+        .get_memory(id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+    if let Some(memory) = memory {
+        return Ok(Some(DiagramNode {
+            id: memory.id,
+            kind: RecordKind::Memory,
+            label: memory.content,
+            node_type: memory.memory_type.to_string(),
+        }));
+    }
+
+    let entity = storage
+        .get_entity(id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get entity: {}", e)))?;
+    Ok(entity.map(|entity| DiagramNode {
+        label: entity
+            .properties
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or(&entity.id)
+            .to_string(),
+        id: entity.id,
+        kind: RecordKind::Entity,
+        node_type: entity.entity_type,
+    }))
+}
+
+/// Single line, cut to [`LABEL_CHARS`]
+fn short_label(text: &str) -> String {
+    let text = text.trim().replace('\n', " ");
+    if text.chars().count() <= LABEL_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(LABEL_CHARS - 3).collect();
+    format!("{}...", cut)
+}
+
+fn mermaid_text(text: &str) -> String {
+    short_label(text).replace('"', "#quot;")
+}
+
+fn plantuml_text(text: &str) -> String {
+    short_label(text).replace('"', "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagram() -> Diagram {
+        Diagram {
+            nodes: vec![
+                DiagramNode {
+                    id: "alice".to_string(),
+                    kind: RecordKind::Entity,
+                    label: "Alice \"Al\"".to_string(),
+                    node_type: "person".to_string(),
+                },
+                DiagramNode {
+                    id: "m1".to_string(),
+                    kind: RecordKind::Memory,
+                    label: "Alice gave a talk about knowledge graphs at the meetup".to_string(),
+                    node_type: "fact".to_string(),
+                },
+            ],
+            edges: vec![DiagramEdge {
+                source_id: "m1".to_string(),
+                target_id: "alice".to_string(),
+                relationship_type: "mentions".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_mermaid() {
+        assert_eq!(
+            diagram().to_mermaid(),
+            "flowchart LR\n    n0(\"Alice #quot;Al#quot;<br/><i>person</i>\")\n    n1[\"Alice gave a talk about knowledge gra...<br/><i>fact</i>\"]\n    n1 -->|\"mentions\"| n0"
+        );
+    }
+
+    #[test]
+    fn test_plantuml() {
+        assert_eq!(
+            diagram().to_plantuml(),
+            "@startuml\nleft to right direction\nrectangle \"Alice 'Al'\\n//person//\" as n0\ncard \"Alice gave a talk about knowledge gra...\\n//fact//\" as n1\nn1 --> n0 : mentions\n@enduml"
+        );
+    }
+}
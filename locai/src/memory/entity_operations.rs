@@ -3,9 +3,10 @@
 //! This module handles entity CRUD operations, entity queries,
 //! and entity-memory relationships.
 
+use crate::memory::entity_profile::{EntityProfile, build_entity_profile};
 use crate::models::{Memory, MemoryPriority, MemoryType};
 use crate::storage::filters::EntityFilter;
-use crate::storage::models::Entity;
+use crate::storage::models::{Entity, EntitySplit};
 use crate::storage::traits::GraphStore;
 use crate::{LocaiError, Result};
 use std::sync::Arc;
@@ -134,6 +135,47 @@ impl EntityOperations {
             .map_err(|e| LocaiError::Storage(format!("Failed to find related entities: {}", e)))
     }
 
+    /// Merge duplicate entities into one
+    ///
+    /// # Arguments
+    /// * `target_id` - The ID of the entity to keep
+    /// * `source_ids` - The IDs of the entities to merge into it
+    ///
+    /// # Returns
+    /// The merged entity
+    pub async fn merge_entities(&self, target_id: &str, source_ids: &[String]) -> Result<Entity> {
+        self.storage
+            .merge_entities(target_id, source_ids)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to merge entities: {}", e)))
+    }
+
+    /// Split part of an entity off into a new one
+    ///
+    /// # Arguments
+    /// * `entity_id` - The ID of the entity to split
+    /// * `split` - The new entity and the links that move to it
+    ///
+    /// # Returns
+    /// The new entity
+    pub async fn split_entity(&self, entity_id: &str, split: EntitySplit) -> Result<Entity> {
+        self.storage
+            .split_entity(entity_id, split)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to split entity: {}", e)))
+    }
+
+    /// Aggregate everything known about an entity
+    ///
+    /// # Arguments
+    /// * `entity_id` - The ID of the entity to profile
+    ///
+    /// # Returns
+    /// The entity profile, or None if the entity doesn't exist
+    pub async fn entity_profile(&self, entity_id: &str) -> Result<Option<EntityProfile>> {
+        build_entity_profile(&self.storage, entity_id).await
+    }
+
     /// Get memories by priority level
     ///
     /// # Arguments
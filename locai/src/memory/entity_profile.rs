@@ -0,0 +1,328 @@
+//! Entity profiles
+//!
+//! Everything known about one entity in a single structure: the memories that
+//! mention it, its relationships, when it was first and last mentioned, how
+//! the tone of those mentions has shifted and which other entities come up
+//! alongside it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::memory::analytics::TrendDirection;
+use crate::models::Memory;
+use crate::storage::models::Entity;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memories listed in a profile
+const TOP_MEMORIES: usize = 5;
+
+/// Co-occurring entities listed in a profile
+const TOP_CO_OCCURRING: usize = 10;
+
+/// Change in average sentiment that counts as a trend
+const TREND_THRESHOLD: f32 = 0.1;
+
+const POSITIVE_WORDS: &[&str] = &[
+    "good",
+    "great",
+    "happy",
+    "love",
+    "loved",
+    "help",
+    "helped",
+    "helpful",
+    "support",
+    "praise",
+    "thanks",
+    "excellent",
+    "glad",
+    "success",
+    "enjoyed",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "bad", "angry", "sad", "hate", "attack", "betray", "betrayed", "insult", "hurt", "failed",
+    "problem", "annoyed", "upset", "terrible", "worried",
+];
+
+/// Aggregated view of one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityProfile {
+    pub entity: Entity,
+
+    /// Number of memories mentioning the entity
+    pub mention_count: usize,
+
+    /// Most important mentioning memories: priority first, then access count, then recency
+    pub top_memories: Vec<Memory>,
+
+    /// Relationships to other entities, grouped by type
+    pub relationships: Vec<RelationshipSummary>,
+
+    pub first_mentioned: Option<DateTime<Utc>>,
+    pub last_mentioned: Option<DateTime<Utc>>,
+
+    pub sentiment: SentimentTrend,
+
+    /// Entities mentioned by the same memories, most frequent first
+    pub co_occurring: Vec<CoOccurrence>,
+}
+
+/// Relationships of one type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationshipSummary {
+    pub relationship_type: String,
+    pub count: usize,
+
+    /// IDs at the other end of the relationships
+    pub related_ids: Vec<String>,
+}
+
+/// Sentiment of mentions over time
+///
+/// Scores run from -1.0 (negative) to 1.0 (positive). A memory's `sentiment`
+/// property is used when present, as "positive"/"negative" or a number;
+/// otherwise a small keyword list is consulted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentimentTrend {
+    pub average: f32,
+
+    /// Whether later mentions are more positive than earlier ones
+    pub direction: TrendDirection,
+
+    /// One score per mentioning memory, oldest first
+    pub points: Vec<SentimentPoint>,
+}
+
+/// Sentiment of one mention
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentimentPoint {
+    pub memory_id: String,
+    pub at: DateTime<Utc>,
+    pub score: f32,
+}
+
+/// An entity that appears in the same memories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoOccurrence {
+    pub entity_id: String,
+    pub entity_type: String,
+
+    /// Name property of the entity, if it has one
+    pub name: Option<String>,
+
+    /// Number of memories mentioning both entities
+    pub count: usize,
+}
+
+/// Build the profile of `entity_id`, or `None` if there is no such entity
+pub async fn build_entity_profile(
+    storage: &Arc<dyn GraphStore>,
+    entity_id: &str,
+) -> Result<Option<EntityProfile>> {
+    let Some(entity) = storage
+        .get_entity(entity_id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get entity: {}", e)))?
+    else {
+        return Ok(None);
+    };
+
+    let mut memories = storage
+        .get_memories_containing_entity(entity_id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get entity memories: {}", e)))?;
+    memories.sort_by_key(|memory| memory.created_at);
+    memories.dedup_by(|a, b| a.id == b.id);
+
+    let relationships = storage
+        .get_entity_relationships(entity_id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get entity relationships: {}", e)))?;
+    let mut by_type: HashMap<String, Vec<String>> = HashMap::new();
+    for relationship in relationships {
+        let other = if relationship.source_id == entity_id {
+            relationship.target_id
+        } else {
+            relationship.source_id
+        };
+        by_type
+            .entry(relationship.relationship_type)
+            .or_default()
+            .push(other);
+    }
+    let mut relationships: Vec<RelationshipSummary> = by_type
+        .into_iter()
+        .map(|(relationship_type, related_ids)| RelationshipSummary {
+            relationship_type,
+            count: related_ids.len(),
+            related_ids,
+        })
+        .collect();
+    relationships.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.relationship_type.cmp(&b.relationship_type))
+    });
+
+    let mut co_occurring: HashMap<String, CoOccurrence> = HashMap::new();
+    for memory in &memories {
+        let entities = storage
+            .get_entities_from_memory(&memory.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory entities: {}", e)))?;
+        for other in entities.into_iter().filter(|other| other.id != entity_id) {
+            co_occurring
+                .entry(other.id.clone())
+                .or_insert_with(|| CoOccurrence {
+                    name: entity_name(&other),
+                    entity_id: other.id,
+                    entity_type: other.entity_type,
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+    let mut co_occurring: Vec<CoOccurrence> = co_occurring.into_values().collect();
+    co_occurring.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
+    });
+    co_occurring.truncate(TOP_CO_OCCURRING);
+
+    let sentiment = sentiment_trend(&memories);
+    let first_mentioned = memories.first().map(|memory| memory.created_at);
+    let last_mentioned = memories.last().map(|memory| memory.created_at);
+    let mention_count = memories.len();
+
+    let mut top_memories = memories;
+    top_memories.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| b.access_count.cmp(&a.access_count))
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    });
+    top_memories.truncate(TOP_MEMORIES);
+
+    Ok(Some(EntityProfile {
+        entity,
+        mention_count,
+        top_memories,
+        relationships,
+        first_mentioned,
+        last_mentioned,
+        sentiment,
+        co_occurring,
+    }))
+}
+
+/// Sentiment of `memories`, which must be oldest first
+fn sentiment_trend(memories: &[Memory]) -> SentimentTrend {
+    let points: Vec<SentimentPoint> = memories
+        .iter()
+        .map(|memory| SentimentPoint {
+            memory_id: memory.id.clone(),
+            at: memory.created_at,
+            score: memory_sentiment(memory),
+        })
+        .collect();
+
+    let mean = |points: &[SentimentPoint]| {
+        if points.is_empty() {
+            0.0
+        } else {
+            points.iter().map(|p| p.score).sum::<f32>() / points.len() as f32
+        }
+    };
+    let (earlier, later) = points.split_at(points.len() / 2);
+    let change = mean(later) - mean(earlier);
+    let direction = if earlier.is_empty() || change.abs() < TREND_THRESHOLD {
+        TrendDirection::Stable
+    } else if change > 0.0 {
+        TrendDirection::Increasing
+    } else {
+        TrendDirection::Decreasing
+    };
+
+    SentimentTrend {
+        average: mean(&points),
+        direction,
+        points,
+    }
+}
+
+fn memory_sentiment(memory: &Memory) -> f32 {
+    match memory.properties.get("sentiment") {
+        Some(serde_json::Value::String(label)) => match label.as_str() {
+            "positive" => 1.0,
+            "negative" => -1.0,
+            _ => 0.0,
+        },
+        Some(serde_json::Value::Number(score)) => {
+            score.as_f64().map_or(0.0, |s| (s as f32).clamp(-1.0, 1.0))
+        }
+        _ => keyword_sentiment(&memory.content),
+    }
+}
+
+fn keyword_sentiment(text: &str) -> f32 {
+    let (mut positive, mut negative) = (0, 0);
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if POSITIVE_WORDS.contains(&word.as_str()) {
+            positive += 1;
+        } else if NEGATIVE_WORDS.contains(&word.as_str()) {
+            negative += 1;
+        }
+    }
+    if positive + negative == 0 {
+        0.0
+    } else {
+        (positive - negative) as f32 / (positive + negative) as f32
+    }
+}
+
+fn entity_name(entity: &Entity) -> Option<String> {
+    entity
+        .properties
+        .get("name")
+        .and_then(|name| name.as_str())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MemoryBuilder;
+    use serde_json::json;
+
+    #[test]
+    fn test_sentiment_prefers_property() {
+        let memory = MemoryBuilder::new_with_content("A terrible, bad day")
+            .property("sentiment", json!("positive"))
+            .build();
+        assert_eq!(memory_sentiment(&memory), 1.0);
+        assert_eq!(keyword_sentiment("A terrible, bad day"), -1.0);
+        assert_eq!(keyword_sentiment("Nothing to report"), 0.0);
+    }
+
+    #[test]
+    fn test_sentiment_trend_direction() {
+        let memories: Vec<Memory> = ["She helped a lot", "Great support", "He was upset", "Bad"]
+            .iter()
+            .map(|content| MemoryBuilder::new_with_content(*content).build())
+            .collect();
+        let trend = sentiment_trend(&memories);
+        assert!(matches!(trend.direction, TrendDirection::Decreasing));
+        assert_eq!(trend.points.len(), 4);
+        assert_eq!(trend.average, 0.0);
+
+        let single = sentiment_trend(&memories[..1]);
+        assert!(matches!(single.direction, TrendDirection::Stable));
+    }
+}
@@ -0,0 +1,63 @@
+//! Forgetting curve simulation
+//!
+//! With [`ForgettingConfig::enabled`], memories fade the way Ebbinghaus
+//! described: retrievability falls as `exp(-t / S)` from the last time a
+//! memory was accessed, and every access makes the next fall slower by
+//! multiplying its stability `S`. Search scores are scaled by retrievability,
+//! so faded memories sink in the ranking until
+//! [`MemoryManager::archive_forgotten_memories`] archives them.
+//!
+//! Access counts and times come from lifecycle tracking, which should be
+//! enabled for reinforcement to have any effect.
+//!
+//! [`MemoryManager::archive_forgotten_memories`]: crate::core::MemoryManager::archive_forgotten_memories
+
+use chrono::{DateTime, Utc};
+
+use crate::config::ForgettingConfig;
+use crate::models::Memory;
+use crate::storage::models::SearchResult;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// A memory's current stability in days; `None` if its type never fades
+pub fn stability_days(config: &ForgettingConfig, memory: &Memory) -> Option<f64> {
+    let base = config.stability_for(&memory.memory_type.to_string())?;
+    Some(base * config.reinforcement.powf(f64::from(memory.access_count)))
+}
+
+/// How likely a memory is to be recalled at `now`, from 1.0 down to 0.0
+pub fn retrievability(config: &ForgettingConfig, memory: &Memory, now: DateTime<Utc>) -> f32 {
+    let Some(stability) = stability_days(config, memory) else {
+        return 1.0;
+    };
+    let last_seen = memory.last_accessed.unwrap_or(memory.created_at);
+    let elapsed_days = (now - last_seen).num_seconds().max(0) as f64 / SECONDS_PER_DAY;
+    (-elapsed_days / stability).exp() as f32
+}
+
+/// Whether a memory has faded below the archive threshold
+pub fn is_forgotten(config: &ForgettingConfig, memory: &Memory, now: DateTime<Utc>) -> bool {
+    f64::from(retrievability(config, memory, now)) < config.archive_threshold
+}
+
+/// Scale search scores by retrievability and re-sort, best first
+///
+/// Results without a score are treated as scoring 1.0 and keep no score.
+pub fn apply_to_results(
+    config: &ForgettingConfig,
+    results: Vec<SearchResult>,
+    now: DateTime<Utc>,
+) -> Vec<SearchResult> {
+    let mut scored: Vec<(SearchResult, f32)> = results
+        .into_iter()
+        .map(|mut result| {
+            let retrievability = retrievability(config, &result.memory, now);
+            result.score = result.score.map(|score| score * retrievability);
+            let rank = result.score.unwrap_or(retrievability);
+            (result, rank)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().map(|(result, _)| result).collect()
+}
@@ -4,6 +4,7 @@
 //! relationship tracing, and community detection that can be used by any application.
 
 use crate::core::MemoryManager;
+use crate::memory::graph_diff::GraphDiff;
 use crate::models::Memory;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -158,6 +159,13 @@ impl MemoryGraphAnalyzer {
         })
     }
 
+    /// What changed in the graph between `from` and `to`: memories, entities
+    /// and relationships added, removed or edited, replayed from the storage
+    /// changefeed
+    pub async fn graph_diff(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<GraphDiff> {
+        Ok(self.memory_manager.graph_diff(from, to).await?)
+    }
+
     /// Find memories similar to a given memory based on content and tags
     async fn find_similar_memories(
         &self,
@@ -0,0 +1,292 @@
+//! Graph diffs between two points in time
+//!
+//! The storage changefeed keeps every write to memories, entities and
+//! relationships for its retention period (seven days by default), each
+//! carrying the record as written and when. Replaying it gives the state of
+//! every record at both ends of a window, so a diff can list what was added,
+//! removed or changed in between, like "what the agent learned this week".
+//!
+//! Deletes carry no time of their own. They are dated by the next write
+//! after them, or by the last write before them when nothing was written
+//! since.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::replication::{ChangeOp, RecordKind};
+use crate::storage::models::ChangeFeedEntry;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Changes read per changefeed page
+const PAGE_SIZE: usize = 1000;
+
+/// Fields that change on every read or write and don't make a record different
+const VOLATILE_FIELDS: &[&str] = &["access_count", "last_accessed", "updated_at"];
+
+/// What changed in the graph between two points in time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDiff {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+
+    /// Memories and entities
+    pub nodes: GraphDelta,
+
+    /// Relationships
+    pub edges: GraphDelta,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+}
+
+/// Records added, removed and changed within a window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDelta {
+    pub added: Vec<GraphChange>,
+    pub removed: Vec<GraphChange>,
+    pub changed: Vec<GraphChange>,
+}
+
+impl GraphDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// One record in a diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphChange {
+    pub kind: RecordKind,
+    pub id: String,
+
+    /// The record at the start of the window; `None` when it was added, or
+    /// when it predates the changefeed
+    pub before: Option<Value>,
+
+    /// The record at the end of the window; `None` when it was removed
+    pub after: Option<Value>,
+}
+
+/// A change to one record, with when it was made
+type Event = (DateTime<Utc>, ChangeFeedEntry);
+
+/// A record's state at a point in time
+enum State {
+    Absent,
+    /// Present, with the record as last written if the changefeed has it
+    Present(Option<Value>),
+}
+
+/// Compute what changed in the graph between `from` and `to`
+pub async fn compute_graph_diff(
+    storage: &Arc<dyn GraphStore>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<GraphDiff> {
+    if from > to {
+        return Err(LocaiError::Other(format!(
+            "Diff window starts at {} after it ends at {}",
+            from, to
+        )));
+    }
+
+    let mut entries = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let page = storage
+            .read_changes(cursor, PAGE_SIZE)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to read changes: {}", e)))?;
+        if page.changes.is_empty() || page.cursor == cursor {
+            entries.extend(page.changes);
+            break;
+        }
+        entries.extend(page.changes);
+        cursor = page.cursor;
+    }
+
+    let times = date_changes(&entries, to);
+    let mut history: HashMap<(RecordKind, String), Vec<Event>> = HashMap::new();
+    for (entry, time) in entries.into_iter().zip(times) {
+        history
+            .entry((entry.kind, entry.id.clone()))
+            .or_default()
+            .push((time, entry));
+    }
+
+    let mut diff = GraphDiff {
+        from,
+        to,
+        ..Default::default()
+    };
+    for ((kind, id), events) in history {
+        let delta = match kind {
+            RecordKind::Relationship => &mut diff.edges,
+            RecordKind::Memory | RecordKind::Entity => &mut diff.nodes,
+        };
+        let change = |before, after| GraphChange {
+            kind,
+            id: id.clone(),
+            before,
+            after,
+        };
+
+        match (state_at(&events, from), state_at(&events, to)) {
+            (State::Absent, State::Present(after)) => delta.added.push(change(None, after)),
+            (State::Present(before), State::Absent) => {
+                let before = before.or_else(|| last_written(&events, to));
+                delta.removed.push(change(before, None));
+            }
+            (State::Present(before), State::Present(after)) => {
+                let written = events.iter().any(|(time, entry)| {
+                    entry.op == ChangeOp::Upsert && *time > from && *time <= to
+                });
+                let same = match (&before, &after) {
+                    (Some(before), Some(after)) => same_record(before, after),
+                    _ => false,
+                };
+                if written && !same {
+                    delta.changed.push(change(before, after));
+                }
+            }
+            (State::Absent, State::Absent) => {}
+        }
+    }
+
+    for delta in [&mut diff.nodes, &mut diff.edges] {
+        for changes in [&mut delta.added, &mut delta.removed, &mut delta.changed] {
+            changes.sort_by(|a, b| a.id.cmp(&b.id));
+        }
+    }
+    Ok(diff)
+}
+
+/// When each change was made
+///
+/// Writes carry their own time. Deletes take the time of the next write, or
+/// of the last write before them when none followed, so they never move with
+/// the time of the query.
+fn date_changes(entries: &[ChangeFeedEntry], end: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let mut times = vec![None; entries.len()];
+    let mut next = None;
+    for (i, entry) in entries.iter().enumerate().rev() {
+        next = entry.changed_at.or(next);
+        times[i] = next;
+    }
+
+    let mut previous: Option<DateTime<Utc>> = None;
+    entries
+        .iter()
+        .zip(times)
+        .map(|(entry, time)| {
+            let time = match (time, previous) {
+                (Some(time), Some(previous)) => time.max(previous),
+                (time, previous) => time.or(previous).unwrap_or(end),
+            };
+            if entry.changed_at.is_some() {
+                previous = Some(time);
+            }
+            time
+        })
+        .collect()
+}
+
+/// A record's state at `at`, from its changes in commit order
+fn state_at(events: &[Event], at: DateTime<Utc>) -> State {
+    if let Some((_, entry)) = events.iter().rev().find(|(time, _)| *time <= at) {
+        return match entry.op {
+            ChangeOp::Upsert => State::Present(entry.record.clone()),
+            ChangeOp::Delete => State::Absent,
+        };
+    }
+
+    // Nothing written by then: the record existed already if its first change
+    // deletes or updates it, rather than creating it
+    let Some((_, first)) = events.first() else {
+        return State::Absent;
+    };
+    let created_at = first
+        .record
+        .as_ref()
+        .and_then(|record| record.get("created_at"))
+        .and_then(|created_at| serde_json::from_value::<DateTime<Utc>>(created_at.clone()).ok());
+    match (first.op, created_at) {
+        (ChangeOp::Delete, _) => State::Present(None),
+        (ChangeOp::Upsert, Some(created_at)) if created_at <= at => State::Present(None),
+        _ => State::Absent,
+    }
+}
+
+/// The record as last written up to `at`
+fn last_written(events: &[Event], at: DateTime<Utc>) -> Option<Value> {
+    events
+        .iter()
+        .rev()
+        .filter(|(time, _)| *time <= at)
+        .find_map(|(_, entry)| entry.record.clone())
+}
+
+fn same_record(a: &Value, b: &Value) -> bool {
+    let strip = |record: &Value| {
+        let mut record = record.clone();
+        if let Some(fields) = record.as_object_mut() {
+            for field in VOLATILE_FIELDS {
+                fields.remove(*field);
+            }
+        }
+        record
+    };
+    strip(a) == strip(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn entry(id: &str, op: ChangeOp, changed_at: Option<DateTime<Utc>>) -> ChangeFeedEntry {
+        ChangeFeedEntry {
+            cursor: 0,
+            kind: RecordKind::Entity,
+            id: id.to_string(),
+            op,
+            record: changed_at.map(|at| json!({ "id": id, "created_at": at })),
+            changed_at,
+        }
+    }
+
+    #[test]
+    fn test_deletes_take_the_next_write_time() {
+        let start = Utc::now() - Duration::hours(3);
+        let later = start + Duration::hours(2);
+        let end = start + Duration::hours(3);
+        let entries = vec![
+            entry("a", ChangeOp::Upsert, Some(start)),
+            entry("a", ChangeOp::Delete, None),
+            entry("b", ChangeOp::Upsert, Some(later)),
+            entry("b", ChangeOp::Delete, None),
+        ];
+        assert_eq!(
+            date_changes(&entries, end),
+            vec![start, later, later, later]
+        );
+    }
+
+    #[test]
+    fn test_volatile_fields_are_ignored() {
+        let before = json!({ "content": "x", "access_count": 1, "last_accessed": null });
+        let read = json!({ "content": "x", "access_count": 2, "last_accessed": "2026-01-01" });
+        let edited = json!({ "content": "y", "access_count": 2 });
+        assert!(same_record(&before, &read));
+        assert!(!same_record(&before, &edited));
+    }
+}
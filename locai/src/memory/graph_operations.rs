@@ -3,12 +3,16 @@
 //! This module handles graph traversal, path finding, and relationship
 //! navigation for memories and entities.
 
+use crate::memory::diagram::{Diagram, DiagramFilter, build_diagram};
+use crate::memory::graph_diff::{GraphDiff, compute_graph_diff};
+use crate::memory::subgraph::{ScoredSubgraph, SubgraphScoring, extract_subgraph};
 use crate::models::Memory;
 use crate::relationships::storage::RelationshipStorage;
 use crate::storage::filters::RelationshipFilter;
-use crate::storage::models::{MemoryGraph, MemoryPath, Relationship};
+use crate::storage::models::{MemoryGraph, MemoryPath, PathConstraints, Relationship};
 use crate::storage::traits::{GraphStore, GraphTraversal};
 use crate::{LocaiError, Result};
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 
 /// Graph-based operations for memories
@@ -64,6 +68,27 @@ impl GraphOperations {
             })
     }
 
+    /// Find the cheapest paths between two memories
+    ///
+    /// # Arguments
+    /// * `from_id` - The ID of the starting memory
+    /// * `to_id` - The ID of the target memory
+    /// * `constraints` - Edge weights, allowed relationship and entity types,
+    ///   and depth, cost and result limits
+    ///
+    /// # Returns
+    /// Paths ordered by cost, cheapest first
+    pub async fn find_weighted_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        constraints: &PathConstraints,
+    ) -> Result<Vec<MemoryPath>> {
+        GraphTraversal::find_weighted_paths(&*self.storage, from_id, to_id, constraints)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to find weighted paths: {}", e)))
+    }
+
     /// Find the shortest path between two memories
     ///
     /// # Arguments
@@ -105,6 +130,55 @@ impl GraphOperations {
             .map_err(|e| LocaiError::Storage(format!("Failed to find connected memories: {}", e)))
     }
 
+    /// Extract a scored subgraph around seed memories or entities
+    ///
+    /// # Arguments
+    /// * `seed_ids` - Memory or entity IDs to start from
+    /// * `depth` - How many hops to walk out from the seeds
+    /// * `max_nodes` - How many of the best scored nodes to keep
+    /// * `scoring` - Weights for distance, priority and connectedness
+    ///
+    /// # Returns
+    /// The kept nodes, best first, and the relationships between them; use
+    /// [`ScoredSubgraph::fit_to_budget`] to trim it to a token budget
+    pub async fn extract_subgraph(
+        &self,
+        seed_ids: &[String],
+        depth: u8,
+        max_nodes: usize,
+        scoring: &SubgraphScoring,
+    ) -> Result<ScoredSubgraph> {
+        extract_subgraph(&self.storage, seed_ids, depth, max_nodes, scoring).await
+    }
+
+    /// Select the part of the graph described by `filter` for drawing
+    pub async fn diagram(&self, filter: &DiagramFilter) -> Result<Diagram> {
+        build_diagram(&self.storage, filter).await
+    }
+
+    /// Mermaid flowchart source for the part of the graph described by
+    /// `filter`
+    pub async fn to_mermaid(&self, filter: &DiagramFilter) -> Result<String> {
+        Ok(self.diagram(filter).await?.to_mermaid())
+    }
+
+    /// PlantUML source for the part of the graph described by `filter`
+    pub async fn to_plantuml(&self, filter: &DiagramFilter) -> Result<String> {
+        Ok(self.diagram(filter).await?.to_plantuml())
+    }
+
+    /// Diff the graph between two points in time
+    ///
+    /// # Arguments
+    /// * `from` - Start of the window
+    /// * `to` - End of the window
+    ///
+    /// # Returns
+    /// Memories, entities and relationships added, removed or changed in between
+    pub async fn graph_diff(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<GraphDiff> {
+        compute_graph_diff(&self.storage, from, to).await
+    }
+
     /// Create a relationship between two memories
     ///
     /// # Arguments
@@ -164,10 +238,9 @@ impl GraphOperations {
         memory_type: Option<crate::models::MemoryType>,
         memory_operations: &crate::memory::operations::MemoryOperations,
     ) -> Result<String> {
-        use crate::models::MemoryBuilder;
-
         // Create the new memory
-        let memory = MemoryBuilder::new_with_content(content)
+        let memory = memory_operations
+            .memory_builder(content)
             .memory_type(memory_type.unwrap_or(crate::models::MemoryType::Fact))
             .build();
 
@@ -202,10 +275,9 @@ impl GraphOperations {
         memory_type: Option<crate::models::MemoryType>,
         memory_operations: &crate::memory::operations::MemoryOperations,
     ) -> Result<String> {
-        use crate::models::MemoryBuilder;
-
         // Create the new memory
-        let memory = MemoryBuilder::new_with_content(content)
+        let memory = memory_operations
+            .memory_builder(content)
             .memory_type(memory_type.unwrap_or(crate::models::MemoryType::Fact))
             .build();
 
@@ -0,0 +1,257 @@
+//! Idempotency keys
+//!
+//! A write made with an idempotency key records its result under the key.
+//! Making it again with the same key, within the retention window set by
+//! [`IdempotencyConfig`](crate::config::IdempotencyConfig), returns the
+//! recorded result without writing, so a client that retries after a timeout
+//! (an LLM tool call, say) can't store the same memories twice. Reusing a key
+//! for a different write is an error.
+//!
+//! Only successful writes are recorded; a write that failed can be retried
+//! under the same key. Writes sharing a key wait for one another within a
+//! process. Records are stored as entities of type
+//! [`IDEMPOTENCY_ENTITY_TYPE`] and replaced once expired.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::ids::content_hash_id;
+use crate::models::Memory;
+use crate::storage::models::Entity;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Entity type idempotency records are stored under
+pub const IDEMPOTENCY_ENTITY_TYPE: &str = "idempotency_key";
+
+/// The result of a write, recorded under its idempotency key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    pub key: String,
+
+    /// Identifies the write the key was first used for
+    pub fingerprint: String,
+
+    /// The write's result
+    pub response: serde_json::Value,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl IdempotencyRecord {
+    /// Whether the record has outlived `retention` at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>, retention: Duration) -> bool {
+        self.created_at
+            .checked_add_signed(retention)
+            .is_some_and(|expires_at| expires_at <= now)
+    }
+
+    fn entity_id(key: &str) -> String {
+        format!("idempotency:{}", content_hash_id(key))
+    }
+
+    fn from_entity(entity: Entity) -> Option<Self> {
+        serde_json::from_value(entity.properties).ok()
+    }
+
+    fn to_entity(&self) -> Entity {
+        Entity {
+            id: Self::entity_id(&self.key),
+            entity_type: IDEMPOTENCY_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(self).unwrap_or_default(),
+            created_at: self.created_at,
+            updated_at: self.created_at,
+        }
+    }
+}
+
+/// A fingerprint of a write's content, to tell whether a key is reused for a
+/// different write
+pub fn fingerprint<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    let joined: Vec<&str> = parts.into_iter().collect();
+    content_hash_id(&joined.join("\u{1f}"))
+}
+
+/// A fingerprint of memories' types and content
+pub fn memory_fingerprint<'a>(memories: impl IntoIterator<Item = &'a Memory>) -> String {
+    let parts: Vec<String> = memories
+        .into_iter()
+        .flat_map(|memory| [memory.memory_type.to_string(), memory.content.clone()])
+        .collect();
+    fingerprint(parts.iter().map(String::as_str))
+}
+
+/// Records writes' results under their idempotency keys
+#[derive(Debug)]
+pub struct IdempotencyStore {
+    storage: Arc<dyn GraphStore>,
+    retention: Duration,
+    in_flight: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl IdempotencyStore {
+    pub fn new(storage: Arc<dyn GraphStore>, retention_secs: u64) -> Self {
+        Self {
+            storage,
+            retention: i64::try_from(retention_secs)
+                .ok()
+                .and_then(Duration::try_seconds)
+                .unwrap_or(Duration::MAX),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The unexpired record for `key` at `now`, if any
+    pub async fn get(&self, key: &str, now: DateTime<Utc>) -> Result<Option<IdempotencyRecord>> {
+        let entity = self
+            .storage
+            .get_entity(&IdempotencyRecord::entity_id(key))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get idempotency key: {}", e)))?;
+        Ok(entity
+            .and_then(IdempotencyRecord::from_entity)
+            .filter(|record| !record.is_expired(now, self.retention)))
+    }
+
+    /// Run `write` unless `key` already recorded a result for it
+    ///
+    /// With no key, `write` just runs. Fails with
+    /// [`LocaiError::Idempotency`] if `key` holds the result of a write with
+    /// another `fingerprint`.
+    pub async fn run<T, F, Fut>(
+        &self,
+        key: Option<&str>,
+        fingerprint: &str,
+        now: DateTime<Utc>,
+        write: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let Some(key) = key else {
+            return write().await;
+        };
+
+        let lock = Arc::clone(
+            self.in_flight
+                .lock()
+                .await
+                .entry(key.to_string())
+                .or_default(),
+        );
+        let result = {
+            let _guard = lock.lock().await;
+            self.run_once(key, fingerprint, now, write).await
+        };
+
+        let mut in_flight = self.in_flight.lock().await;
+        if Arc::strong_count(&lock) == 2 {
+            in_flight.remove(key);
+        }
+        result
+    }
+
+    async fn run_once<T, F, Fut>(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        now: DateTime<Utc>,
+        write: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if let Some(record) = self.get(key, now).await? {
+            if record.fingerprint != fingerprint {
+                return Err(LocaiError::Idempotency(format!(
+                    "Idempotency key '{}' was already used for a different write",
+                    key
+                )));
+            }
+            return serde_json::from_value(record.response).map_err(|e| {
+                LocaiError::Idempotency(format!(
+                    "Result recorded under idempotency key '{}' can't be read: {}",
+                    key, e
+                ))
+            });
+        }
+
+        let result = write().await?;
+
+        // The write happened; failing to record it shouldn't report it failed
+        let record = IdempotencyRecord {
+            key: key.to_string(),
+            fingerprint: fingerprint.to_string(),
+            response: serde_json::to_value(&result).unwrap_or_default(),
+            created_at: now,
+        };
+        if let Err(e) = self.storage.upsert_entity(record.to_entity()).await {
+            tracing::warn!("Failed to record idempotency key '{}': {}", key, e);
+        }
+        Ok(result)
+    }
+
+    /// Delete records expired at `now`, returning how many were removed
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize> {
+        let filter = crate::storage::filters::EntityFilter {
+            entity_type: Some(IDEMPOTENCY_ENTITY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let entities = self
+            .storage
+            .list_entities(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list idempotency keys: {}", e)))?;
+
+        let mut removed = 0;
+        for entity in entities {
+            let id = entity.id.clone();
+            let expired = IdempotencyRecord::from_entity(entity)
+                .is_none_or(|record| record.is_expired(now, self.retention));
+            if expired
+                && self.storage.delete_entity(&id).await.map_err(|e| {
+                    LocaiError::Storage(format!("Failed to delete idempotency key: {}", e))
+                })?
+            {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_expiry() {
+        let now = Utc::now();
+        let record = IdempotencyRecord {
+            key: "k".to_string(),
+            fingerprint: fingerprint(["a"]),
+            response: serde_json::json!("id"),
+            created_at: now - Duration::hours(2),
+        };
+        assert!(!record.is_expired(now, Duration::hours(3)));
+        assert!(record.is_expired(now, Duration::hours(2)));
+        assert!(!record.is_expired(now, Duration::MAX));
+    }
+
+    #[test]
+    fn test_fingerprint_separates_parts() {
+        assert_eq!(fingerprint(["a", "b"]), fingerprint(["a", "b"]));
+        assert_ne!(fingerprint(["a", "b"]), fingerprint(["ab"]));
+        assert_ne!(fingerprint(["a", "b"]), fingerprint(["b", "a"]));
+    }
+}
@@ -3,23 +3,49 @@
 //! This module handles integration with the messaging system,
 //! including message storage as memories and live query subscriptions.
 
+use crate::clock::ClockHandle;
+use crate::config::{MessagingConfig, TopicRetentionPolicy};
+use crate::messaging::retention::{self, RetentionReport};
 use crate::models::{Memory, MemoryType};
 use crate::storage::filters::MemoryFilter;
 use crate::storage::traits::GraphStore;
 use crate::{LocaiError, Result};
 use async_stream;
 use std::sync::Arc;
+use tokio::task::JoinHandle;
 
 /// Messaging system integration
 #[derive(Debug)]
 pub struct MessagingIntegration {
     storage: Arc<dyn GraphStore>,
+    retention: Vec<TopicRetentionPolicy>,
+    retention_task: Option<JoinHandle<()>>,
+    clock: ClockHandle,
 }
 
 impl MessagingIntegration {
     /// Create a new messaging integration handler
-    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
-        Self { storage }
+    ///
+    /// Starts the background retention task when `config` has retention
+    /// policies; it stops when the handler is dropped. Message ages are
+    /// measured by `clock`.
+    pub fn new(storage: Arc<dyn GraphStore>, config: &MessagingConfig, clock: ClockHandle) -> Self {
+        let retention_task =
+            retention::spawn_retention_task(Arc::clone(&storage), config, clock.clone());
+        Self {
+            storage,
+            retention: config.retention.clone(),
+            retention_task,
+            clock,
+        }
+    }
+
+    /// Apply the configured retention policies to stored messages now
+    ///
+    /// # Returns
+    /// Counts of removed messages by rule
+    pub async fn enforce_retention(&self) -> Result<RetentionReport> {
+        retention::enforce_retention(self.storage.as_ref(), &self.retention, self.clock.now()).await
     }
 
     /// Subscribe to memory changes with live queries (for messaging system)
@@ -102,7 +128,8 @@ impl MessagingIntegration {
 
         memory_operations
             .store_memory(
-                crate::models::MemoryBuilder::new_with_content(content)
+                memory_operations
+                    .memory_builder(content)
                     .memory_type(MemoryType::Custom(format!("msg:{}", topic_base)))
                     .source(&message.sender)
                     .tags(tag_refs)
@@ -137,7 +164,7 @@ impl MessagingIntegration {
         for memory in memories {
             match serde_json::from_str::<crate::messaging::types::Message>(&memory.content) {
                 Ok(message) => {
-                    if filter.include_expired || !message.is_expired() {
+                    if crate::messaging::stream::utils::matches_filter(&message, filter) {
                         messages.push(message);
                     }
                 }
@@ -273,12 +300,25 @@ impl MessagingIntegration {
     }
 }
 
+impl Drop for MessagingIntegration {
+    fn drop(&mut self) {
+        if let Some(task) = self.retention_task.take() {
+            task.abort();
+        }
+    }
+}
+
 /// Convert a database event to a Memory object
 fn convert_db_event_to_memory(
     event: &crate::storage::shared_storage::live_query::DbEvent,
 ) -> Option<Memory> {
     use serde_json::Value;
 
+    // Events from the storage change stream carry the record in model form
+    if let Ok(memory) = serde_json::from_value::<Memory>(event.result.clone()) {
+        return Some(memory);
+    }
+
     // Extract memory data from the event result
     let result = &event.result;
 
@@ -408,6 +448,8 @@ fn parse_memory_type(type_str: &str) -> MemoryType {
         "world" => MemoryType::World,
         "action" => MemoryType::Action,
         "event" => MemoryType::Event,
+        "task" => MemoryType::Task,
+        "preference" => MemoryType::Preference,
         s if s.starts_with("custom:") => MemoryType::Custom(s[7..].to_string()),
         s => MemoryType::Custom(s.to_string()),
     }
@@ -5,15 +5,42 @@
 
 pub mod analytics;
 pub mod builders;
+pub mod collections;
 pub mod consolidation;
+pub mod context;
+pub mod diagram;
 pub mod entity_operations;
+pub mod entity_profile;
+pub mod forgetting;
 pub mod graph_analysis;
+pub mod graph_diff;
 pub mod graph_operations;
+pub mod idempotency;
 pub mod messaging;
 pub mod operations;
+pub mod path_narration;
+pub mod personas;
+pub mod pins;
+pub mod pipeline;
+pub mod preferences;
+pub mod procedures;
+pub mod query_expansion;
+pub mod quota;
+pub mod reflection;
+pub mod reminders;
+pub mod review;
+#[cfg(feature = "rhai-rules")]
+pub mod rules;
+pub mod search_cache;
 pub mod search_extensions;
+pub mod sessions;
+pub mod subgraph;
+pub mod tasks;
+pub mod templates;
+pub mod usage;
 pub mod utils;
 pub mod versioning;
+pub mod warmup;
 
 // Re-export consolidation types
 pub use consolidation::{
@@ -33,17 +60,48 @@ pub use analytics::{
 pub use versioning::{MemoryVersion as MemoryVersioning, VersionMetadata};
 
 // Re-export graph analysis types
+pub use diagram::{Diagram, DiagramEdge, DiagramFilter, DiagramFormat, DiagramNode};
 pub use graph_analysis::{InfluenceNetwork, MemoryCommunity, MemoryGraphAnalyzer, TemporalSpan};
+pub use graph_diff::{GraphChange, GraphDelta, GraphDiff};
 
 // Re-export new module types
+pub use crate::hooks::automation::RuleEvent;
 pub use builders::MemoryBuilders;
+pub use collections::{Collection, CollectionQuery, CollectionStore, scope_to_members};
+pub use context::{ContextOptions, MemoryContext};
 pub use entity_operations::EntityOperations;
+pub use entity_profile::{
+    CoOccurrence, EntityProfile, RelationshipSummary, SentimentPoint, SentimentTrend,
+};
 pub use graph_operations::GraphOperations;
+pub use idempotency::{IdempotencyRecord, IdempotencyStore};
 pub use messaging::MessagingIntegration;
 pub use operations::MemoryOperations;
+pub use path_narration::PathNarrator;
+pub use personas::{Persona, PersonaStore};
+pub use pins::{GLOBAL_PIN_SCOPE, PinList, PinStore, lead_with_pinned};
+pub use pipeline::{PendingMemory, StoreFlow, StoreMiddleware, StorePipeline};
+pub use preferences::{GLOBAL_PREFERENCE_SCOPE, Preference, PreferenceChange, PreferenceStore};
+pub use procedures::{Procedure, ProcedureMatch, ProcedureStep, ProcedureStore};
+pub use query_expansion::{ExpandedQuery, QueryExpander, QueryExpansionConfig};
+pub use quota::{QuotaTracker, QuotaUsage};
+pub use reflection::{CallbackReflector, DERIVED_FROM, Insight, Reflector};
+pub use reminders::{Recurrence, Reminder, ReminderEvent, ReminderStore, parse_delay};
+pub use review::{ReviewItem, ReviewQueue};
+#[cfg(feature = "rhai-rules")]
+pub use rules::{Rule, RuleFailure, RuleMessage, RuleOutcome, RuleStore};
+pub use search_cache::{SearchCache, SearchCacheStats};
 pub use search_extensions::{
     SearchExtensions, SearchMode, UniversalSearchOptions, UniversalSearchResult,
 };
+pub use sessions::{CallbackSummarizer, SessionStore, SessionSummary, Summarizer, Turn};
+pub use subgraph::{ScoredSubgraph, SubgraphEdge, SubgraphFormat, SubgraphNode, SubgraphScoring};
+pub use tasks::{Task, TaskQuery, TaskStatus, TaskStore};
+pub use templates::{
+    MemoryTemplate, Placeholder, PlaceholderType, TemplateRegistry, builtin_templates,
+};
+pub use usage::{UsageInterval, UsageLedger, UsageMetric, UsageQuery, UsageRecord};
+pub use warmup::WarmupReport;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
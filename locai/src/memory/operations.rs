@@ -8,23 +8,57 @@ use crate::entity_extraction::{
     AutomaticRelationshipCreator, BasicEntityExtractor, EntityExtractor, EntityResolver,
     ExtractorType,
 };
+use crate::ids::{IdGenerator, IdStrategy};
+use crate::memory::pipeline::{
+    PendingMemory, StageDeps, StoreFlow, StoreMiddleware, StorePipeline,
+};
+use crate::memory::quota::QuotaTracker;
+use crate::memory::review::ReviewQueue;
+use crate::ml::error::MLError;
 use crate::ml::model_manager::EmbeddingManager;
-use crate::models::Memory;
+use crate::ml::provider::EmbeddingProvider;
+use crate::models::{Memory, MemoryBuilder, SPARSE_EMBEDDING_PROPERTY, SparseVector};
 use crate::storage::filters::MemoryFilter;
+use crate::storage::models::OutboxMessage;
+use crate::storage::shared_storage::schema::EMBEDDING_DIMENSIONS;
 use crate::storage::traits::GraphStore;
+use crate::tokens::{self, ApproxTokenCounter, TokenCounter};
 
 use crate::{LocaiError, Result};
+use futures::{StreamExt, stream};
 use std::sync::Arc;
+use tokio::task::JoinHandle;
 
 /// Core memory operations handler
 #[derive(Debug, Clone)]
 pub struct MemoryOperations {
     pub(crate) storage: Arc<dyn GraphStore>,
     ml_service: Option<Arc<EmbeddingManager>>,
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+    /// Checks embeddings against the vector index before they're written
+    embedding_validator: EmbeddingManager,
     config: LocaiConfig,
     entity_extractors: Vec<Arc<dyn EntityExtractor>>,
     entity_resolver: Option<EntityResolver>,
     relationship_creator: Option<AutomaticRelationshipCreator>,
+    quotas: Arc<QuotaTracker>,
+    ids: Arc<IdGenerator>,
+    /// Sizes chunks for the `chunk` stage
+    token_counter: Arc<dyn TokenCounter>,
+    /// Middlewares added by the application, in the order added
+    store_middlewares: Vec<Arc<dyn StoreMiddleware>>,
+    /// Stages every stored memory passes through
+    pipeline: StorePipeline,
+    /// Holds memories from reviewed sources until they're approved
+    review: Arc<ReviewQueue>,
+}
+
+/// A memory through the store pipeline
+enum Prepared {
+    /// Ready to be written
+    New(Box<PendingMemory>),
+    /// Already stored, or held for review, under this ID
+    Existing(String),
 }
 
 impl MemoryOperations {
@@ -103,14 +137,49 @@ impl MemoryOperations {
             None
         };
 
-        Self {
+        let quotas = Arc::new(QuotaTracker::new(
+            Arc::clone(&storage),
+            config.quotas.clone(),
+        ));
+
+        let ids = Arc::new(IdGenerator::new(config.id_strategy, config.clock.clone()));
+        let review = Arc::new(ReviewQueue::new(
+            Arc::clone(&storage),
+            config.review.clone(),
+        ));
+
+        if let Some(dimensions) = ml_service.as_ref().and_then(|ml| ml.expected_dimensions())
+            && dimensions != EMBEDDING_DIMENSIONS
+        {
+            tracing::warn!(
+                "Embedding manager expects {}-dimensional embeddings, but the vector index holds {}; embeddings are checked against the index",
+                dimensions,
+                EMBEDDING_DIMENSIONS
+            );
+        }
+        let embedding_validator = EmbeddingManager::with_expected_dimensions(EMBEDDING_DIMENSIONS);
+        let token_counter = tokens::counter_for(&config.tokenizer).unwrap_or_else(|_| {
+            Arc::new(ApproxTokenCounter::new(config.tokenizer.chars_per_token))
+        });
+
+        let mut ops = Self {
             storage,
             ml_service,
+            embedding_provider: None,
+            embedding_validator,
             config,
             entity_extractors,
             entity_resolver,
             relationship_creator,
-        }
+            quotas,
+            ids,
+            token_counter,
+            store_middlewares: Vec::new(),
+            pipeline: StorePipeline::default(),
+            review,
+        };
+        ops.rebuild_pipeline();
+        ops
     }
 
     /// Create a new MemoryOperations with ML extractors initialized asynchronously
@@ -186,9 +255,29 @@ impl MemoryOperations {
             }
         }
 
+        self.rebuild_pipeline();
         Ok(())
     }
 
+    /// Assemble the store pipeline from the configured stages
+    ///
+    /// Called whenever something a stage works with changes.
+    fn rebuild_pipeline(&mut self) {
+        let extractors = if self.config.entity_extraction.enabled {
+            self.entity_extractors.clone()
+        } else {
+            Vec::new()
+        };
+        let deps = StageDeps {
+            storage: Arc::clone(&self.storage),
+            ids: Arc::clone(&self.ids),
+            extractors,
+            embedding_provider: self.embedding_provider.clone(),
+            token_counter: Arc::clone(&self.token_counter),
+        };
+        self.pipeline = StorePipeline::build(&self.config.pipeline, deps, &self.store_middlewares);
+    }
+
     /// Store a new memory
     ///
     /// # Arguments
@@ -197,110 +286,433 @@ impl MemoryOperations {
     /// # Returns
     /// The ID of the stored memory
     pub async fn store_memory(&self, memory: Memory) -> Result<String> {
-        // BYOE approach: Users provide their own embeddings via Memory.with_embedding()
-        // No automatic embedding generation - embeddings are provided by the user when needed
-
-        // Validate embedding dimensions before storage (fail fast, don't silently skip in search)
-        // SurrealDB M-Tree index requires 1024 dimensions - reject mismatched dimensions early
-        if let Some(embedding) = &memory.embedding {
-            const EXPECTED_DIMENSIONS: usize = 1024;
-            if embedding.len() != EXPECTED_DIMENSIONS {
-                return Err(LocaiError::Memory(format!(
-                    "Embedding dimension mismatch: expected {} dimensions (required for SurrealDB M-Tree index), but got {}. \
-                     Vector search will fail with this dimension. Please provide a {}-dimensional embedding or omit the embedding field.",
-                    EXPECTED_DIMENSIONS,
-                    embedding.len(),
-                    EXPECTED_DIMENSIONS
-                )));
-            }
+        self.store_memory_with_outbox(memory, Vec::new()).await
+    }
+
+    /// Store a new memory, recording outbox messages in the same transaction
+    ///
+    /// A memory held for review is queued instead, and its messages wait with
+    /// it until it's approved.
+    ///
+    /// # Arguments
+    /// * `memory` - The memory to store; with messages it keeps its own ID
+    /// * `outbox` - Notifications to commit with the memory
+    ///
+    /// # Returns
+    /// The ID of the stored memory
+    pub async fn store_memory_with_outbox(
+        &self,
+        memory: Memory,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<String> {
+        if self.review.holds(&memory) {
+            return self.hold_for_review(memory, outbox).await;
         }
+        self.write_memory(memory, outbox).await
+    }
+
+    /// Store a memory approved in the review queue, committing the outbox
+    /// messages held with it in the same transaction
+    pub(crate) async fn store_approved(
+        &self,
+        memory: Memory,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<String> {
+        self.write_memory(memory, outbox).await
+    }
+
+    /// Queue a memory for review under the ID it will be stored with
+    async fn hold_for_review(
+        &self,
+        mut memory: Memory,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<String> {
+        self.check_embedding(&mut memory)?;
+        if memory.id.is_empty() {
+            memory.id = self.ids.generate(&memory.content);
+        }
+        self.review.submit(memory, outbox).await
+    }
+
+    /// Pass a memory through the store pipeline and write it
+    async fn write_memory(&self, memory: Memory, outbox: Vec<OutboxMessage>) -> Result<String> {
+        let PendingMemory {
+            memory,
+            entities,
+            companions,
+        } = match self.prepare_memory(memory, false).await? {
+            Prepared::New(pending) => *pending,
+            Prepared::Existing(id) => return Ok(id),
+        };
+        let reservation = self.quotas.reserve(&memory).await?;
 
         // Store the memory first
-        let created = self
-            .storage
-            .create_memory(memory)
-            .await
-            .map_err(|e| LocaiError::Storage(format!("Failed to store memory: {}", e)))?;
+        let created = if outbox.is_empty() {
+            self.storage.create_memory(memory).await
+        } else {
+            self.storage.create_memory_with_outbox(memory, outbox).await
+        };
+        let created = match created {
+            Ok(created) => created,
+            Err(e) => {
+                if let Some(reservation) = reservation {
+                    self.quotas.cancel(reservation).await;
+                }
+                return Err(LocaiError::Storage(format!(
+                    "Failed to store memory: {}",
+                    e
+                )));
+            }
+        };
 
         // Vector table removed - embeddings are stored directly in memory.embedding
         // with M-Tree index for vector search. No separate vector records needed.
 
-        // Extract entities if entity extraction is enabled
-        if self.config.entity_extraction.enabled && !self.entity_extractors.is_empty() {
-            let mut all_extracted_entities = Vec::new();
+        self.link_memory(&created.id, entities).await;
+        self.store_companions(companions).await;
+        self.pipeline.after_store(&created).await;
 
-            // Run all extractors and collect results
-            for extractor in &self.entity_extractors {
-                match extractor.extract_entities(&created.content).await {
-                    Ok(extracted_entities) => {
-                        all_extracted_entities.extend(extracted_entities);
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Extractor '{}' failed to extract entities from memory {}: {}",
-                            extractor.name(),
-                            created.id,
-                            e
-                        );
-                        // Continue with other extractors even if one fails
-                    }
+        Ok(created.id)
+    }
+
+    /// Store several memories with one storage write
+    ///
+    /// The store pipeline runs for up to `concurrency` memories at once, while
+    /// the entities and relationships it produces are written one memory at a
+    /// time. If the batched write fails, memories are written
+    /// individually so one bad memory doesn't fail the rest.
+    ///
+    /// # Returns
+    /// The ID of each stored memory or the error that stopped it, in input order
+    pub async fn store_memory_batch(
+        &self,
+        memories: Vec<Memory>,
+        concurrency: usize,
+    ) -> Vec<Result<String>> {
+        let concurrency = concurrency.max(1);
+        let prepared: Vec<Result<Prepared>> = stream::iter(memories)
+            .map(|memory| async move {
+                if self.review.holds(&memory) {
+                    return self
+                        .hold_for_review(memory, Vec::new())
+                        .await
+                        .map(Prepared::Existing);
+                }
+                self.prepare_memory(memory, false).await
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        let mut results: Vec<Result<String>> = Vec::with_capacity(prepared.len());
+        let mut pending = Vec::new();
+        let mut valid = Vec::new();
+        let mut reservations = Vec::new();
+        // Entities and companions of each memory to write, by input index
+        let mut linked = std::collections::HashMap::new();
+        for (index, prepared) in prepared.into_iter().enumerate() {
+            let PendingMemory {
+                memory,
+                entities,
+                companions,
+            } = match prepared {
+                Ok(Prepared::New(prepared)) => *prepared,
+                Ok(Prepared::Existing(id)) => {
+                    results.push(Ok(id));
+                    continue;
+                }
+                Err(e) => {
+                    results.push(Err(e));
+                    continue;
                 }
+            };
+            match self.quotas.reserve(&memory).await {
+                Ok(reservation) => {
+                    pending.push(index);
+                    valid.push(memory);
+                    reservations.push(reservation);
+                    linked.insert(index, (entities, companions));
+                    results.push(Ok(String::new()));
+                }
+                Err(e) => results.push(Err(e)),
             }
+        }
 
-            // Process each extracted entity with Phase 2 resolution
-            for extracted in all_extracted_entities {
-                if extracted.confidence >= self.config.entity_extraction.confidence_threshold {
-                    match self
-                        .process_extracted_entity_with_resolution(&created.id, &extracted)
-                        .await
+        let created: Vec<(usize, Memory)> =
+            match self.storage.batch_create_memories(valid.clone()).await {
+                Ok(created) => pending.into_iter().zip(created).collect(),
+                Err(e) => {
+                    tracing::warn!(
+                        "Batched write of {} memories failed, writing individually: {}",
+                        valid.len(),
+                        e
+                    );
+                    let mut created = Vec::with_capacity(valid.len());
+                    for ((index, memory), reservation) in
+                        pending.into_iter().zip(valid).zip(reservations)
                     {
-                        Ok(_) => {
-                            tracing::debug!(
-                                "Successfully processed entity: {}",
-                                extracted.format()
-                            );
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to process entity '{}': {}", extracted.text, e);
-                            // Continue processing other entities even if one fails
+                        match self.storage.create_memory(memory).await {
+                            Ok(memory) => created.push((index, memory)),
+                            Err(e) => {
+                                if let Some(reservation) = reservation {
+                                    self.quotas.cancel(reservation).await;
+                                }
+                                results[index] = Err(LocaiError::Storage(format!(
+                                    "Failed to store memory: {}",
+                                    e
+                                )))
+                            }
                         }
                     }
-                } else {
-                    tracing::debug!(
-                        "Skipping entity '{}' due to low confidence: {:.2} < {:.2}",
-                        extracted.text,
-                        extracted.confidence,
-                        self.config.entity_extraction.confidence_threshold
-                    );
+                    created
                 }
+            };
+
+        for (index, memory) in created {
+            if let Some((entities, companions)) = linked.remove(&index) {
+                self.link_memory(&memory.id, entities).await;
+                self.store_companions(companions).await;
+            }
+            self.pipeline.after_store(&memory).await;
+            results[index] = Ok(memory.id);
+        }
+
+        results
+    }
+
+    /// Validate a memory, then pass it through the store pipeline
+    ///
+    /// With `deferred`, the stages a deferred store runs afterwards are
+    /// skipped.
+    async fn prepare_memory(&self, mut memory: Memory, deferred: bool) -> Result<Prepared> {
+        // BYOE approach: Users provide their own embeddings via Memory.with_embedding(),
+        // and one the vector index couldn't search is refused
+        self.check_embedding(&mut memory)?;
+
+        let mut pending = PendingMemory::new(memory);
+        let flow = self
+            .pipeline
+            .before_store_matching(&mut pending, |stage| !deferred || !stage.deferrable())
+            .await?;
+        if let StoreFlow::Existing(id) = flow {
+            return Ok(Prepared::Existing(id));
+        }
+        self.check_pipeline_embeddings(&mut pending);
+        Ok(Prepared::New(Box::new(pending)))
+    }
+
+    /// Drop embeddings the pipeline filled in that the vector index couldn't
+    /// search, storing those memories without one
+    fn check_pipeline_embeddings(&self, pending: &mut PendingMemory) {
+        let memories = std::iter::once(&mut pending.memory).chain(pending.companions.iter_mut());
+        for memory in memories {
+            if let Err(e) = self.check_embedding(memory) {
+                tracing::warn!(
+                    "Store pipeline left memory {} with an unusable embedding, storing it without embedding: {}",
+                    memory.id,
+                    e
+                );
+                memory.embedding = None;
+            }
+        }
+    }
+
+    /// Store the memories the pipeline added beside another, such as its chunks
+    ///
+    /// Failures are logged; the memory they came with is already stored.
+    async fn store_companions(&self, companions: Vec<Memory>) {
+        for memory in companions {
+            let id = memory.id.clone();
+            let reservation = match self.quotas.reserve(&memory).await {
+                Ok(reservation) => reservation,
+                Err(e) => {
+                    tracing::warn!("Not storing memory {}: {}", id, e);
+                    continue;
+                }
+            };
+            if let Err(e) = self.storage.create_memory(memory).await {
+                if let Some(reservation) = reservation {
+                    self.quotas.cancel(reservation).await;
+                }
+                tracing::warn!("Failed to store memory {}: {}", id, e);
+            }
+        }
+    }
+
+    /// Refuse an embedding the vector index couldn't search
+    ///
+    /// The embedding must have the index's dimensions and finite values, not
+    /// all zero. With `ml.embedding.normalize_on_write` it's then scaled to
+    /// unit length. A sparse vector must pair each index with a finite value.
+    fn check_embedding(&self, memory: &mut Memory) -> Result<()> {
+        if let Some(sparse) = memory.properties.get(SPARSE_EMBEDDING_PROPERTY) {
+            serde_json::from_value::<SparseVector>(sparse.clone())
+                .map_err(|e| {
+                    LocaiError::InvalidEmbedding(format!(
+                        "Sparse vector should be {{\"indices\": [..], \"values\": [..]}}: {}",
+                        e
+                    ))
+                })?
+                .validate()?;
+        }
+
+        let Some(embedding) = memory.embedding.as_mut() else {
+            return Ok(());
+        };
+        let invalid = |reason: &str| {
+            LocaiError::InvalidEmbedding(format!(
+                "{}. Vector search covers {}-dimensional embeddings of finite values, not all zero; \
+                 provide one like that or omit the embedding",
+                reason, EMBEDDING_DIMENSIONS
+            ))
+        };
+        let rejected = |e: MLError| match e {
+            MLError::Embedding(reason) => invalid(&reason),
+            other => invalid(&other.to_string()),
+        };
+
+        self.embedding_validator
+            .validate_embedding(embedding)
+            .map_err(rejected)?;
+        if embedding.iter().all(|value| *value == 0.0) {
+            return Err(invalid("A zero vector has no direction to search by"));
+        }
+        if self.config.ml.embedding.normalize_on_write {
+            self.embedding_validator
+                .normalize_embedding(embedding)
+                .map_err(rejected)?;
+        }
+        Ok(())
+    }
+
+    /// Store a new memory now, and embed it and extract its entities in the background
+    ///
+    /// The memory is written and searchable by text when this returns. The
+    /// store pipeline's deferrable stages (embedding and entity extraction)
+    /// run in the returned task, and its embedding, entities, automatic
+    /// relationships and companions follow when that finishes; failures there
+    /// are logged rather than returned.
+    ///
+    /// # Returns
+    /// The ID of the stored memory and the indexing task
+    pub async fn store_memory_deferred(&self, memory: Memory) -> Result<(String, JoinHandle<()>)> {
+        if self.review.holds(&memory) {
+            let id = self.hold_for_review(memory, Vec::new()).await?;
+            return Ok((id, tokio::spawn(async {})));
+        }
+        let mut pending = match self.prepare_memory(memory, true).await? {
+            Prepared::New(pending) => *pending,
+            Prepared::Existing(id) => return Ok((id, tokio::spawn(async {}))),
+        };
+        let reservation = self.quotas.reserve(&pending.memory).await?;
+
+        let created = match self.storage.create_memory(pending.memory).await {
+            Ok(created) => created,
+            Err(e) => {
+                if let Some(reservation) = reservation {
+                    self.quotas.cancel(reservation).await;
+                }
+                return Err(LocaiError::Storage(format!(
+                    "Failed to store memory: {}",
+                    e
+                )));
+            }
+        };
+
+        let id = created.id.clone();
+        pending.memory = created;
+        let operations = self.clone();
+        let indexing = tokio::spawn(async move { operations.index_memory(pending).await });
+        Ok((id, indexing))
+    }
+
+    /// Run the stages a deferred store skipped over a stored memory, then
+    /// store what they produced
+    async fn index_memory(&self, mut pending: PendingMemory) {
+        let had_embedding = pending.memory.embedding.is_some();
+        if let Err(e) = self
+            .pipeline
+            .before_store_matching(&mut pending, |stage| stage.deferrable())
+            .await
+        {
+            tracing::warn!(
+                "Store pipeline failed for memory {}: {}",
+                pending.memory.id,
+                e
+            );
+        }
+        self.check_pipeline_embeddings(&mut pending);
+
+        let PendingMemory {
+            memory,
+            entities,
+            companions,
+        } = pending;
+        if !had_embedding
+            && memory.embedding.is_some()
+            && let Err(e) = self.update_memory(memory.clone()).await
+        {
+            tracing::warn!("Failed to store embedding for memory {}: {}", memory.id, e);
+        }
+        self.link_memory(&memory.id, entities).await;
+        self.store_companions(companions).await;
+        self.pipeline.after_store(&memory).await;
+    }
+
+    /// Store extracted entities and automatic relationships for a memory
+    async fn link_memory(
+        &self,
+        memory_id: &str,
+        extracted_entities: Vec<crate::entity_extraction::ExtractedEntity>,
+    ) {
+        // Process each extracted entity with Phase 2 resolution
+        for extracted in extracted_entities {
+            if extracted.confidence >= self.config.entity_extraction.confidence_threshold {
+                match self
+                    .process_extracted_entity_with_resolution(memory_id, &extracted)
+                    .await
+                {
+                    Ok(_) => {
+                        tracing::debug!("Successfully processed entity: {}", extracted.format());
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to process entity '{}': {}", extracted.text, e);
+                        // Continue processing other entities even if one fails
+                    }
+                }
+            } else {
+                tracing::debug!(
+                    "Skipping entity '{}' due to low confidence: {:.2} < {:.2}",
+                    extracted.text,
+                    extracted.confidence,
+                    self.config.entity_extraction.confidence_threshold
+                );
             }
         }
 
         // Create automatic relationships (Phase 2)
         if let Some(relationship_creator) = &self.relationship_creator {
             match relationship_creator
-                .create_relationships_for_memory(&created.id, self.storage.as_ref())
+                .create_relationships_for_memory(memory_id, self.storage.as_ref())
                 .await
             {
                 Ok(relationship_ids) => {
                     tracing::debug!(
                         "Created {} automatic relationships for memory {}",
                         relationship_ids.len(),
-                        created.id
+                        memory_id
                     );
                 }
                 Err(e) => {
                     tracing::warn!(
                         "Failed to create automatic relationships for memory {}: {}",
-                        created.id,
+                        memory_id,
                         e
                     );
                     // Don't fail the memory storage if relationship creation fails
                 }
             }
         }
-
-        Ok(created.id)
     }
 
     /// Process an extracted entity with Phase 2 resolution and deduplication
@@ -397,9 +809,23 @@ impl MemoryOperations {
             properties.insert(key.clone(), serde_json::Value::String(value.clone()));
         }
 
+        let entity_type = extracted.entity_type.as_str().to_string();
+        let id = self
+            .ids
+            .generate(&format!("{}:{}", entity_type, extracted.text));
+        if self.ids.strategy() == IdStrategy::ContentHash
+            && let Some(existing) = self
+                .storage
+                .get_entity(&id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to look up entity: {}", e)))?
+        {
+            return Ok(existing);
+        }
+
         let new_entity = Entity {
-            id: uuid::Uuid::new_v4().to_string(),
-            entity_type: extracted.entity_type.as_str().to_string(),
+            id,
+            entity_type,
             properties: serde_json::Value::Object(properties.into_iter().collect()),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
@@ -574,25 +1000,54 @@ impl MemoryOperations {
     /// # Returns
     /// Whether the update was successful
     pub async fn update_memory(&self, memory: Memory) -> Result<bool> {
-        // Validate embedding dimensions before storage (fail fast, don't silently skip in search)
-        // SurrealDB M-Tree index requires 1024 dimensions - reject mismatched dimensions early
-        if let Some(embedding) = &memory.embedding {
-            const EXPECTED_DIMENSIONS: usize = 1024;
-            if embedding.len() != EXPECTED_DIMENSIONS {
-                return Err(LocaiError::Memory(format!(
-                    "Embedding dimension mismatch: expected {} dimensions (required for SurrealDB M-Tree index), but got {}. \
-                     Vector search will fail with this dimension. Please provide a {}-dimensional embedding or omit the embedding field.",
-                    EXPECTED_DIMENSIONS,
-                    embedding.len(),
-                    EXPECTED_DIMENSIONS
-                )));
-            }
+        self.update_memory_with_outbox(memory, Vec::new()).await
+    }
+
+    /// Update an existing memory, recording outbox messages in the same transaction
+    ///
+    /// # Arguments
+    /// * `memory` - The updated memory
+    /// * `outbox` - Notifications to commit with the update
+    ///
+    /// # Returns
+    /// Whether the update was successful
+    pub async fn update_memory_with_outbox(
+        &self,
+        mut memory: Memory,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<bool> {
+        // Fail fast rather than store an embedding search would silently skip
+        self.check_embedding(&mut memory)?;
+
+        // Growing a memory counts against the quota like storing a new one
+        let previous = if self.quotas.is_tracking().await {
+            self.storage
+                .get_memory(&memory.id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+        } else {
+            None
+        };
+        if let Some(previous) = &previous {
+            self.quotas.replace(previous, &memory).await?;
         }
 
-        self.storage
-            .update_memory(memory)
-            .await
-            .map_err(|e| LocaiError::Storage(format!("Failed to update memory: {}", e)))?;
+        let updated = if outbox.is_empty() {
+            self.storage.update_memory(memory.clone()).await
+        } else {
+            self.storage
+                .update_memory_with_outbox(memory.clone(), outbox)
+                .await
+        };
+        if let Err(e) = updated {
+            if let Some(previous) = &previous {
+                self.quotas.revert(previous, &memory).await;
+            }
+            return Err(LocaiError::Storage(format!(
+                "Failed to update memory: {}",
+                e
+            )));
+        }
 
         // Vector table removed - embeddings are stored directly in memory.embedding
         // with M-Tree index for vector search. No separate vector records needed.
@@ -608,6 +1063,22 @@ impl MemoryOperations {
     /// # Returns
     /// Whether the deletion was successful
     pub async fn delete_memory(&self, id: &str) -> Result<bool> {
+        self.delete_memory_with_outbox(id, Vec::new()).await
+    }
+
+    /// Delete a memory by ID, recording outbox messages in the same transaction
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the memory to delete
+    /// * `outbox` - Notifications to commit with the deletion
+    ///
+    /// # Returns
+    /// Whether the deletion was successful
+    pub async fn delete_memory_with_outbox(
+        &self,
+        id: &str,
+        outbox: Vec<OutboxMessage>,
+    ) -> Result<bool> {
         // Delete associated vector first (if it exists)
         let vector_id = format!("mem_{}", id);
         match self.storage.delete_vector(&vector_id).await {
@@ -622,11 +1093,24 @@ impl MemoryOperations {
             }
         }
 
+        let previous = if self.quotas.is_tracking().await {
+            self.storage.get_memory(id).await.ok().flatten()
+        } else {
+            None
+        };
+
         // Delete the memory
-        self.storage
-            .delete_memory(id)
-            .await
-            .map_err(|e| LocaiError::Storage(format!("Failed to delete memory: {}", e)))
+        let deleted = if outbox.is_empty() {
+            self.storage.delete_memory(id).await
+        } else {
+            self.storage.delete_memory_with_outbox(id, outbox).await
+        };
+        let deleted =
+            deleted.map_err(|e| LocaiError::Storage(format!("Failed to delete memory: {}", e)))?;
+        if deleted && let Some(previous) = &previous {
+            self.quotas.release(previous).await;
+        }
+        Ok(deleted)
     }
 
     /// Filter memories using various criteria
@@ -699,6 +1183,29 @@ impl MemoryOperations {
         &self.config
     }
 
+    /// A new ID by the configured [`IdStrategy`](crate::ids::IdStrategy),
+    /// for a record with `content`
+    pub fn generate_id(&self, content: &str) -> String {
+        self.ids.generate(content)
+    }
+
+    /// A builder for a memory with `content`, named by the configured
+    /// [`IdStrategy`](crate::ids::IdStrategy)
+    pub fn memory_builder(&self, content: impl Into<String>) -> MemoryBuilder {
+        let content = content.into();
+        MemoryBuilder::new(self.ids.generate(&content), content)
+    }
+
+    /// Get the quota tracker
+    pub(crate) fn quotas(&self) -> &Arc<QuotaTracker> {
+        &self.quotas
+    }
+
+    /// Get the review queue
+    pub(crate) fn review(&self) -> &Arc<ReviewQueue> {
+        &self.review
+    }
+
     /// Check if ML service is available
     pub fn has_ml_service(&self) -> bool {
         self.ml_service.is_some()
@@ -708,4 +1215,41 @@ impl MemoryOperations {
     pub fn ml_service(&self) -> Option<&Arc<EmbeddingManager>> {
         self.ml_service.as_ref()
     }
+
+    /// Set the provider used to embed memories stored without an embedding
+    pub fn set_embedding_provider(&mut self, provider: Option<Arc<dyn EmbeddingProvider>>) {
+        self.embedding_provider = provider;
+        self.rebuild_pipeline();
+    }
+
+    /// Get the embedding provider, if configured
+    pub fn embedding_provider(&self) -> Option<&Arc<dyn EmbeddingProvider>> {
+        self.embedding_provider.as_ref()
+    }
+
+    /// Size the `chunk` stage's chunks with `counter`
+    pub fn set_token_counter(&mut self, counter: Arc<dyn TokenCounter>) {
+        self.token_counter = counter;
+        self.rebuild_pipeline();
+    }
+
+    /// Run `extractor` in the `extract` stage after the configured ones
+    pub fn add_entity_extractor(&mut self, extractor: Arc<dyn EntityExtractor>) {
+        self.entity_extractors.push(extractor);
+        self.rebuild_pipeline();
+    }
+
+    /// Add a stage to the store pipeline
+    ///
+    /// It runs where [`PipelineConfig::stages`](crate::config::PipelineConfig::stages)
+    /// names it, or at `hooks` if it isn't named.
+    pub fn add_store_middleware(&mut self, middleware: Arc<dyn StoreMiddleware>) {
+        self.store_middlewares.push(middleware);
+        self.rebuild_pipeline();
+    }
+
+    /// The stages every stored memory passes through
+    pub fn pipeline(&self) -> &StorePipeline {
+        &self.pipeline
+    }
 }
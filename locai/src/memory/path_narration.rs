@@ -0,0 +1,233 @@
+//! Natural-language narration of graph paths
+//!
+//! `find_paths` returns memories and the relationships that connect them.
+//! [`PathNarrator`] turns the relationships into a sentence such as
+//! "Alice WORKS_AT Acme, which ACQUIRED Beta." that can go straight into a
+//! prompt. Each relationship type can have its own template, so the same path
+//! can read "Alice works at Acme, which acquired Beta." instead.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::storage::models::{MemoryPath, Relationship};
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Placeholder for the source of a hop in a template
+pub const SOURCE_PLACEHOLDER: &str = "{source}";
+
+/// Placeholder for the target of a hop in a template
+pub const TARGET_PLACEHOLDER: &str = "{target}";
+
+/// Entity properties tried for a display name, in order
+const NAME_PROPERTIES: &[&str] = &["name", "title", "text"];
+
+/// Longest memory excerpt used when a hop ends at a memory
+const MEMORY_EXCERPT_CHARS: usize = 60;
+
+/// Renders paths as text using per-relationship-type templates
+///
+/// Without a template a hop reads `{source} RELATIONSHIP_TYPE {target}`.
+/// Consecutive hops that continue from the previous target are chained with
+/// "which", and other hops are separated by semicolons.
+#[derive(Debug, Clone, Default)]
+pub struct PathNarrator {
+    templates: HashMap<String, String>,
+}
+
+impl PathNarrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `template` for hops of `relationship_type`
+    ///
+    /// The template should contain [`SOURCE_PLACEHOLDER`] and
+    /// [`TARGET_PLACEHOLDER`], e.g. `"{source} works at {target}"`.
+    /// Relationship types match case-insensitively.
+    pub fn with_template(
+        mut self,
+        relationship_type: impl Into<String>,
+        template: impl Into<String>,
+    ) -> Self {
+        self.templates
+            .insert(relationship_type.into().to_lowercase(), template.into());
+        self
+    }
+
+    /// Render a single hop
+    pub fn render_hop(&self, relationship_type: &str, source: &str, target: &str) -> String {
+        match self.templates.get(&relationship_type.to_lowercase()) {
+            Some(template) => template
+                .replace(SOURCE_PLACEHOLDER, source)
+                .replace(TARGET_PLACEHOLDER, target),
+            None => format!("{} {} {}", source, relationship_type.to_uppercase(), target),
+        }
+    }
+
+    /// Narrate a sequence of relationships, naming endpoints from `names` and
+    /// falling back to their IDs
+    ///
+    /// Returns an empty string when there are no relationships.
+    pub fn narrate(
+        &self,
+        relationships: &[Relationship],
+        names: &HashMap<String, String>,
+    ) -> String {
+        let name = |id: &str| names.get(id).map(String::as_str).unwrap_or(id).to_string();
+
+        let mut text = String::new();
+        let mut previous_target: Option<&str> = None;
+        for relationship in relationships {
+            let target = name(&relationship.target_id);
+            if previous_target == Some(relationship.source_id.as_str()) {
+                text.push_str(", ");
+                text.push_str(&self.render_hop(&relationship.relationship_type, "which", &target));
+            } else {
+                if !text.is_empty() {
+                    text.push_str("; ");
+                }
+                let source = name(&relationship.source_id);
+                text.push_str(&self.render_hop(&relationship.relationship_type, &source, &target));
+            }
+            previous_target = Some(relationship.target_id.as_str());
+        }
+
+        if !text.is_empty() {
+            text.push('.');
+        }
+        text
+    }
+
+    /// Narrate a path, looking up the names of the entities and memories it
+    /// passes through
+    pub async fn narrate_path(
+        &self,
+        storage: &Arc<dyn GraphStore>,
+        path: &MemoryPath,
+    ) -> Result<String> {
+        let names = resolve_names(storage, &path.relationships).await?;
+        Ok(self.narrate(&path.relationships, &names))
+    }
+
+    /// Narrate several paths, one string per path
+    pub async fn narrate_paths(
+        &self,
+        storage: &Arc<dyn GraphStore>,
+        paths: &[MemoryPath],
+    ) -> Result<Vec<String>> {
+        let relationships: Vec<Relationship> = paths
+            .iter()
+            .flat_map(|path| path.relationships.iter().cloned())
+            .collect();
+        let names = resolve_names(storage, &relationships).await?;
+        Ok(paths
+            .iter()
+            .map(|path| self.narrate(&path.relationships, &names))
+            .collect())
+    }
+}
+
+/// Display names for the endpoints of `relationships`
+///
+/// Entities are named by their name property, and memories by an excerpt of
+/// their content. Anything else keeps its ID.
+async fn resolve_names(
+    storage: &Arc<dyn GraphStore>,
+    relationships: &[Relationship],
+) -> Result<HashMap<String, String>> {
+    let mut names = HashMap::new();
+    for id in relationships
+        .iter()
+        .flat_map(|relationship| [&relationship.source_id, &relationship.target_id])
+    {
+        if names.contains_key(id) {
+            continue;
+        }
+
+        let entity = storage
+            .get_entity(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get entity: {}", e)))?;
+        let name = match entity {
+            Some(entity) => NAME_PROPERTIES
+                .iter()
+                .find_map(|property| entity.properties.get(*property)?.as_str())
+                .map(str::to_string),
+            None => storage
+                .get_memory(id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+                .map(|memory| excerpt(&memory.content)),
+        };
+        if let Some(name) = name {
+            names.insert(id.clone(), name);
+        }
+    }
+    Ok(names)
+}
+
+fn excerpt(content: &str) -> String {
+    let content = content.trim();
+    if content.chars().count() <= MEMORY_EXCERPT_CHARS {
+        return format!("\"{}\"", content);
+    }
+    let cut: String = content.chars().take(MEMORY_EXCERPT_CHARS).collect();
+    format!("\"{}...\"", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn hop(relationship_type: &str, source: &str, target: &str) -> Relationship {
+        Relationship {
+            id: format!("{}-{}", source, target),
+            relationship_type: relationship_type.to_string(),
+            source_id: source.to_string(),
+            target_id: target.to_string(),
+            properties: json!({}),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn names() -> HashMap<String, String> {
+        [("alice", "Alice"), ("acme", "Acme"), ("beta", "Beta")]
+            .into_iter()
+            .map(|(id, name)| (id.to_string(), name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_chained_hops() {
+        let path = [
+            hop("works_at", "alice", "acme"),
+            hop("acquired", "acme", "beta"),
+        ];
+        assert_eq!(
+            PathNarrator::new().narrate(&path, &names()),
+            "Alice WORKS_AT Acme, which ACQUIRED Beta."
+        );
+
+        let narrator = PathNarrator::new().with_template("WORKS_AT", "{source} works at {target}");
+        assert_eq!(
+            narrator.narrate(&path, &names()),
+            "Alice works at Acme, which ACQUIRED Beta."
+        );
+    }
+
+    #[test]
+    fn test_unchained_hops_and_unknown_names() {
+        let path = [
+            hop("works_at", "alice", "acme"),
+            hop("invested_in", "carol", "acme"),
+        ];
+        assert_eq!(
+            PathNarrator::new().narrate(&path, &names()),
+            "Alice WORKS_AT Acme; carol INVESTED_IN Acme."
+        );
+        assert_eq!(PathNarrator::new().narrate(&[], &names()), "");
+    }
+}
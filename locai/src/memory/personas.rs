@@ -0,0 +1,374 @@
+//! Personas: who an agent is
+//!
+//! A persona is an agent's identity, traits, writing style and constraints,
+//! kept next to its memories as a [`MemoryType::Identity`] memory with the
+//! structure in its `persona` property. Each agent has one persona; setting
+//! it again rewrites the same memory, and the memory version store keeps
+//! every earlier revision. Identity memories stored without the property are
+//! not personas.
+//!
+//! The memory's content is the persona written out as text, which is also
+//! what versions keep, so [`Persona::parse`] reads revisions back from it.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Memory, MemoryType};
+use crate::storage::filters::MemoryFilter;
+use crate::storage::traits::{GraphStore, MemoryVersionStore};
+use crate::{LocaiError, Result};
+
+/// Memory property holding a persona's structure
+pub const PERSONA_PROPERTY: &str = "persona";
+
+const TRAITS_HEADER: &str = "Traits:";
+const WRITING_STYLE_PREFIX: &str = "Writing style: ";
+const CONSTRAINTS_HEADER: &str = "Constraints:";
+
+/// The part of a persona kept in its memory's `persona` property
+#[derive(Debug, Serialize, Deserialize)]
+struct PersonaState {
+    agent: String,
+    identity: String,
+    #[serde(default)]
+    traits: Vec<String>,
+    #[serde(default)]
+    writing_style: Option<String>,
+    #[serde(default)]
+    constraints: Vec<String>,
+    updated_at: DateTime<Utc>,
+}
+
+/// An agent's identity and how it should behave
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Persona {
+    /// ID of the persona's memory
+    pub id: String,
+
+    /// The agent this persona belongs to
+    pub agent: String,
+
+    /// Who the agent is, in its own or its author's words
+    pub identity: String,
+
+    #[serde(default)]
+    pub traits: Vec<String>,
+
+    /// How the agent writes
+    #[serde(default)]
+    pub writing_style: Option<String>,
+
+    /// What the agent must or must not do
+    #[serde(default)]
+    pub constraints: Vec<String>,
+
+    /// When the persona was last set
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Persona {
+    pub fn new(agent: impl Into<String>, identity: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            agent: agent.into(),
+            identity: identity.into(),
+            traits: Vec::new(),
+            writing_style: None,
+            constraints: Vec::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn with_trait(mut self, value: impl Into<String>) -> Self {
+        self.traits.push(value.into());
+        self
+    }
+
+    pub fn with_writing_style(mut self, style: impl Into<String>) -> Self {
+        self.writing_style = Some(style.into());
+        self
+    }
+
+    pub fn with_constraint(mut self, constraint: impl Into<String>) -> Self {
+        self.constraints.push(constraint.into());
+        self
+    }
+
+    /// Check the persona can be stored: it needs an agent and an identity
+    pub fn validate(&self) -> Result<()> {
+        if self.agent.trim().is_empty() {
+            return Err(LocaiError::Persona("A persona needs an agent".to_string()));
+        }
+        if self.identity.trim().is_empty() {
+            return Err(LocaiError::Persona(format!(
+                "The persona of '{}' needs an identity",
+                self.agent
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether two personas describe the agent the same way
+    pub fn same_profile(&self, other: &Persona) -> bool {
+        self.agent == other.agent && self.render() == other.render()
+    }
+
+    /// The persona written out as text
+    pub fn render(&self) -> String {
+        let mut text = self.identity.trim().to_string();
+        if !self.traits.is_empty() {
+            text.push_str(&format!("\n{}", TRAITS_HEADER));
+            for value in &self.traits {
+                text.push_str(&format!("\n- {}", value));
+            }
+        }
+        if let Some(style) = &self.writing_style {
+            text.push_str(&format!("\n{}{}", WRITING_STYLE_PREFIX, style));
+        }
+        if !self.constraints.is_empty() {
+            text.push_str(&format!("\n{}", CONSTRAINTS_HEADER));
+            for constraint in &self.constraints {
+                text.push_str(&format!("\n- {}", constraint));
+            }
+        }
+        text
+    }
+
+    /// Read a persona back from its rendered text
+    ///
+    /// Lines outside the traits and constraints lists are the identity. An
+    /// identity with a line of its own reading `Traits:`, `Constraints:` or
+    /// starting `Writing style: ` does not survive the round trip.
+    pub fn parse(agent: impl Into<String>, text: &str) -> Self {
+        enum Section {
+            Identity,
+            Traits,
+            Constraints,
+        }
+
+        let mut persona = Self::new(agent, "");
+        let mut identity = Vec::new();
+        let mut section = Section::Identity;
+        for line in text.lines() {
+            if line == TRAITS_HEADER {
+                section = Section::Traits;
+            } else if line == CONSTRAINTS_HEADER {
+                section = Section::Constraints;
+            } else if let Some(style) = line.strip_prefix(WRITING_STYLE_PREFIX) {
+                persona.writing_style = Some(style.to_string());
+            } else {
+                match (&section, line.strip_prefix("- ")) {
+                    (Section::Traits, Some(value)) => persona.traits.push(value.to_string()),
+                    (Section::Constraints, Some(value)) => {
+                        persona.constraints.push(value.to_string())
+                    }
+                    _ => identity.push(line),
+                }
+            }
+        }
+        persona.identity = identity.join("\n");
+        persona
+    }
+
+    /// Read a persona from its memory; `None` if the memory isn't a persona
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        if memory.memory_type != MemoryType::Identity {
+            return None;
+        }
+        let state: PersonaState = memory
+            .properties
+            .get(PERSONA_PROPERTY)
+            .and_then(|state| serde_json::from_value(state.clone()).ok())?;
+        Some(Self {
+            id: memory.id.clone(),
+            agent: state.agent,
+            identity: state.identity,
+            traits: state.traits,
+            writing_style: state.writing_style,
+            constraints: state.constraints,
+            updated_at: state.updated_at,
+        })
+    }
+
+    /// The memory to store for a new persona
+    pub fn to_memory(&self) -> Memory {
+        let mut memory = Memory::new(self.id.clone(), self.render(), MemoryType::Identity);
+        memory.created_at = self.updated_at;
+        self.write_state(&mut memory);
+        memory
+    }
+
+    fn write_state(&self, memory: &mut Memory) {
+        memory.content = self.render();
+        let state = PersonaState {
+            agent: self.agent.clone(),
+            identity: self.identity.clone(),
+            traits: self.traits.clone(),
+            writing_style: self.writing_style.clone(),
+            constraints: self.constraints.clone(),
+            updated_at: self.updated_at,
+        };
+        memory.set_property(
+            PERSONA_PROPERTY,
+            serde_json::to_value(state).unwrap_or_default(),
+        );
+    }
+}
+
+/// Reads and changes persona memories
+#[derive(Debug)]
+pub struct PersonaStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl PersonaStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// An agent's persona
+    pub async fn get(&self, agent: &str) -> Result<Option<Persona>> {
+        Ok(self
+            .entries(agent)
+            .await?
+            .into_iter()
+            .max_by_key(|persona| persona.updated_at))
+    }
+
+    /// A persona by the ID of its memory
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<Persona>> {
+        let memory = self
+            .storage
+            .get_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+        Ok(memory.as_ref().and_then(Persona::from_memory))
+    }
+
+    /// Every agent's persona, by agent
+    pub async fn list(&self) -> Result<Vec<Persona>> {
+        let mut personas: Vec<Persona> = Vec::new();
+        let mut all = self.all().await?;
+        all.sort_by(|a, b| {
+            a.agent
+                .cmp(&b.agent)
+                .then_with(|| b.updated_at.cmp(&a.updated_at))
+        });
+        for persona in all {
+            if personas
+                .last()
+                .is_none_or(|last| last.agent != persona.agent)
+            {
+                personas.push(persona);
+            }
+        }
+        Ok(personas)
+    }
+
+    /// Rewrite an agent's persona memory with a new profile
+    ///
+    /// Any other memory left for the same agent is removed.
+    pub async fn replace(&self, existing: &Persona, persona: &Persona) -> Result<Persona> {
+        for duplicate in self.entries(&existing.agent).await? {
+            if duplicate.id != existing.id {
+                self.delete(&duplicate.id).await?;
+            }
+        }
+
+        let mut memory = self
+            .storage
+            .get_memory(&existing.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+            .ok_or_else(|| {
+                LocaiError::Persona(format!("Persona {} no longer exists", existing.id))
+            })?;
+        let updated = Persona {
+            id: existing.id.clone(),
+            agent: existing.agent.clone(),
+            updated_at: Utc::now(),
+            ..persona.clone()
+        };
+        updated.write_state(&mut memory);
+        self.storage
+            .update_memory(memory)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to save persona: {}", e)))?;
+        Ok(updated)
+    }
+
+    /// Remove an agent's persona, returning whether it had one
+    pub async fn remove(&self, agent: &str) -> Result<bool> {
+        let entries = self.entries(agent).await?;
+        for persona in &entries {
+            self.delete(&persona.id).await?;
+        }
+        Ok(!entries.is_empty())
+    }
+
+    /// The revisions an agent's persona has had, oldest first
+    pub async fn history(
+        &self,
+        versions: &dyn MemoryVersionStore,
+        agent: &str,
+    ) -> Result<Vec<Persona>> {
+        let Some(persona) = self.get(agent).await? else {
+            return Ok(Vec::new());
+        };
+        let infos = versions
+            .list_memory_versions(&persona.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list versions: {}", e)))?;
+
+        let mut revisions = Vec::new();
+        for info in infos {
+            let version = versions
+                .get_memory_version(&persona.id, &info.version_id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get version: {}", e)))?;
+            if let Some(version) = version {
+                revisions.push(Persona {
+                    id: persona.id.clone(),
+                    updated_at: info.created_at,
+                    ..Persona::parse(agent, &version.content)
+                });
+            }
+        }
+        revisions.sort_by_key(|revision| revision.updated_at);
+        Ok(revisions)
+    }
+
+    /// Every memory holding a persona for the agent
+    async fn entries(&self, agent: &str) -> Result<Vec<Persona>> {
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|persona| persona.agent == agent)
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<Persona>> {
+        let filter = MemoryFilter {
+            memory_type: Some(MemoryType::Identity.to_string()),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list personas: {}", e)))?;
+        Ok(memories.iter().filter_map(Persona::from_memory).collect())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.storage
+            .delete_memory(id)
+            .await
+            .map(|_| ())
+            .map_err(|e| LocaiError::Storage(format!("Failed to delete persona: {}", e)))
+    }
+}
@@ -0,0 +1,222 @@
+//! Pinned memories
+//!
+//! Pinned memories, such as an agent's persona or standing instructions, lead
+//! search results whatever their score. Pins are kept in lists per scope (an
+//! agent, a user, a conversation); pins in the [`GLOBAL_PIN_SCOPE`] apply to
+//! every scope. A list keeps the order memories were pinned in.
+//!
+//! Pin lists are stored as entities of type [`PIN_ENTITY_TYPE`].
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Memory;
+use crate::storage::filters::EntityFilter;
+use crate::storage::models::{Entity, SearchResult};
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Entity type pin lists are stored under
+pub const PIN_ENTITY_TYPE: &str = "memory_pins";
+
+/// Scope whose pins apply everywhere
+pub const GLOBAL_PIN_SCOPE: &str = "global";
+
+/// The memories pinned in one scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinList {
+    pub id: String,
+    pub scope: String,
+
+    /// Pinned memory IDs, in the order they were pinned
+    #[serde(default)]
+    pub memory_ids: Vec<String>,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PinList {
+    fn new(scope: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            scope: scope.to_string(),
+            memory_ids: Vec::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn from_entity(entity: Entity) -> Option<Self> {
+        serde_json::from_value(entity.properties).ok()
+    }
+
+    fn to_entity(&self) -> Entity {
+        Entity {
+            id: self.id.clone(),
+            entity_type: PIN_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(self).unwrap_or_default(),
+            created_at: self.updated_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// Stores pin lists and puts pinned memories first
+#[derive(Debug)]
+pub struct PinStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl PinStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// Pin a memory in a scope
+    ///
+    /// Returns `false` if it was already pinned there. Fails if the memory
+    /// doesn't exist.
+    pub async fn pin(&self, scope: &str, memory_id: &str) -> Result<bool> {
+        let exists = self
+            .storage
+            .get_memory(memory_id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+            .is_some();
+        if !exists {
+            return Err(LocaiError::Memory(format!(
+                "Memory {} not found, so it can't be pinned",
+                memory_id
+            )));
+        }
+
+        let mut list = self
+            .get(scope)
+            .await?
+            .unwrap_or_else(|| PinList::new(scope));
+        if list.memory_ids.iter().any(|id| id == memory_id) {
+            return Ok(false);
+        }
+        list.memory_ids.push(memory_id.to_string());
+        self.save(list).await?;
+        Ok(true)
+    }
+
+    /// Unpin a memory from a scope, returning whether it was pinned there
+    pub async fn unpin(&self, scope: &str, memory_id: &str) -> Result<bool> {
+        let Some(mut list) = self.get(scope).await? else {
+            return Ok(false);
+        };
+        let before = list.memory_ids.len();
+        list.memory_ids.retain(|id| id != memory_id);
+        if list.memory_ids.len() == before {
+            return Ok(false);
+        }
+        self.save(list).await?;
+        Ok(true)
+    }
+
+    /// The pin list of a scope, if anything was ever pinned there
+    pub async fn get(&self, scope: &str) -> Result<Option<PinList>> {
+        Ok(self
+            .list()
+            .await?
+            .into_iter()
+            .find(|list| list.scope == scope))
+    }
+
+    /// All pin lists, by scope
+    pub async fn list(&self) -> Result<Vec<PinList>> {
+        let filter = EntityFilter {
+            entity_type: Some(PIN_ENTITY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let entities = self
+            .storage
+            .list_entities(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list pins: {}", e)))?;
+        let mut lists: Vec<PinList> = entities
+            .into_iter()
+            .filter_map(PinList::from_entity)
+            .collect();
+        lists.sort_by(|a, b| a.scope.cmp(&b.scope));
+        Ok(lists)
+    }
+
+    /// IDs of the memories pinned for a scope: global pins, then the scope's own
+    ///
+    /// `None` gives only the global pins.
+    pub async fn pinned_ids(&self, scope: Option<&str>) -> Result<Vec<String>> {
+        let lists = self.list().await?;
+        let mut ids: Vec<String> = Vec::new();
+        for wanted in std::iter::once(GLOBAL_PIN_SCOPE).chain(scope) {
+            for list in lists.iter().filter(|list| list.scope == wanted) {
+                for id in &list.memory_ids {
+                    if !ids.contains(id) {
+                        ids.push(id.clone());
+                    }
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// The memories pinned for a scope, in pin order
+    ///
+    /// Memories deleted since they were pinned are skipped.
+    pub async fn pinned(&self, scope: Option<&str>) -> Result<Vec<Memory>> {
+        let mut memories = Vec::new();
+        for id in self.pinned_ids(scope).await? {
+            let memory = self
+                .storage
+                .get_memory(&id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+            memories.extend(memory);
+        }
+        Ok(memories)
+    }
+
+    /// Put the memories pinned for a scope ahead of search results
+    ///
+    /// See [`lead_with_pinned`].
+    pub async fn pin_results(
+        &self,
+        results: Vec<SearchResult>,
+        scope: Option<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        Ok(lead_with_pinned(self.pinned(scope).await?, results))
+    }
+
+    async fn save(&self, mut list: PinList) -> Result<()> {
+        list.updated_at = Utc::now();
+        self.storage
+            .upsert_entity(list.to_entity())
+            .await
+            .map(|_| ())
+            .map_err(|e| LocaiError::Storage(format!("Failed to save pins: {}", e)))
+    }
+}
+
+/// Put pinned memories ahead of search results
+///
+/// Pinned memories come first, without a score, and are dropped from further
+/// down the results. They are added on top of however many results there were.
+pub fn lead_with_pinned(pinned: Vec<Memory>, results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let ids: HashSet<&str> = pinned.iter().map(|memory| memory.id.as_str()).collect();
+    let unpinned: Vec<SearchResult> = results
+        .into_iter()
+        .filter(|result| !ids.contains(result.memory.id.as_str()))
+        .collect();
+    pinned
+        .into_iter()
+        .map(|memory| SearchResult {
+            memory,
+            score: None,
+        })
+        .chain(unpinned)
+        .collect()
+}
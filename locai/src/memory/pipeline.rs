@@ -0,0 +1,492 @@
+//! Store pipeline: what happens to a memory on its way to storage
+//!
+//! Every memory stored through [`MemoryManager`](crate::core::MemoryManager)
+//! passes through an ordered chain of [`StoreMiddleware`]s before it is
+//! written, each seeing what the ones before it did. The order comes from
+//! [`PipelineConfig::stages`](crate::config::PipelineConfig::stages), which
+//! lists the built-in stages in this order by default:
+//!
+//! - `dedup`: under [`IdStrategy::ContentHash`], a memory already stored with
+//!   the same content is returned instead of being stored again
+//! - `redact`: replaces the personal data named in
+//!   [`RedactionConfig`](crate::config::RedactionConfig)
+//! - `chunk`: splits memories longer than
+//!   [`ChunkingConfig::max_tokens`](crate::config::ChunkingConfig::max_tokens)
+//!   into chunk memories stored beside them
+//! - `extract`: runs the entity extractors; the entities are linked to the
+//!   memory once it is stored
+//! - `embed`: fills in missing embeddings from the embedding provider
+//! - `hooks`: middlewares added with
+//!   [`MemoryManager::with_store_middleware`](crate::core::MemoryManager::with_store_middleware),
+//!   in the order added, unless the stage list names them itself
+//!
+//! Once the memory, its entities and its companions are stored, every
+//! stage's [`after_store`](StoreMiddleware::after_store) runs in the same
+//! order. Memory hooks' `on_memory_created` still fires from storage, for
+//! every memory written.
+//!
+//! ```rust
+//! use async_trait::async_trait;
+//! use locai::memory::pipeline::{PendingMemory, StoreFlow, StoreMiddleware};
+//!
+//! /// Tags every memory with the application that stored it
+//! #[derive(Debug)]
+//! struct TagWithApp;
+//!
+//! #[async_trait]
+//! impl StoreMiddleware for TagWithApp {
+//!     fn name(&self) -> &str {
+//!         "tag-with-app"
+//!     }
+//!
+//!     async fn before_store(&self, pending: &mut PendingMemory) -> locai::Result<StoreFlow> {
+//!         pending.memory.add_tag("my-app");
+//!         Ok(StoreFlow::Continue)
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::json;
+
+use crate::config::{ChunkingConfig, PiiKind, PipelineConfig, RedactionConfig};
+use crate::entity_extraction::{EntityExtractor, ExtractedEntity};
+use crate::ids::{IdGenerator, IdStrategy};
+use crate::ml::provider::EmbeddingProvider;
+use crate::models::{Memory, SPARSE_EMBEDDING_PROPERTY};
+use crate::storage::traits::GraphStore;
+use crate::tokens::{TokenCounter, chunk_text};
+use crate::{LocaiError, Result};
+
+/// Returns the stored memory a new one repeats
+pub const DEDUP_STAGE: &str = "dedup";
+
+/// Replaces personal data in content
+pub const REDACT_STAGE: &str = "redact";
+
+/// Splits long memories into chunks
+pub const CHUNK_STAGE: &str = "chunk";
+
+/// Finds the entities a memory mentions
+pub const EXTRACT_STAGE: &str = "extract";
+
+/// Fills in missing embeddings
+pub const EMBED_STAGE: &str = "embed";
+
+/// Where added middlewares run
+pub const HOOKS_STAGE: &str = "hooks";
+
+/// The stages of a default pipeline, in order
+pub const DEFAULT_STAGES: [&str; 6] = [
+    DEDUP_STAGE,
+    REDACT_STAGE,
+    CHUNK_STAGE,
+    EXTRACT_STAGE,
+    EMBED_STAGE,
+    HOOKS_STAGE,
+];
+
+/// Property on a chunk naming the memory it was split from
+pub const CHUNK_OF_PROPERTY: &str = "chunk_of";
+
+/// Property on a chunk giving its position in that memory, from 0
+pub const CHUNK_INDEX_PROPERTY: &str = "chunk_index";
+
+/// Property on a chunked memory giving how many chunks it was split into
+pub const CHUNK_COUNT_PROPERTY: &str = "chunk_count";
+
+/// A memory on its way to storage
+#[derive(Debug, Clone)]
+pub struct PendingMemory {
+    pub memory: Memory,
+
+    /// Entities found in the memory, linked to it once it is stored
+    pub entities: Vec<ExtractedEntity>,
+
+    /// Further memories to store once this one is, such as its chunks
+    pub companions: Vec<Memory>,
+}
+
+impl PendingMemory {
+    pub fn new(memory: Memory) -> Self {
+        Self {
+            memory,
+            entities: Vec::new(),
+            companions: Vec::new(),
+        }
+    }
+}
+
+/// What a middleware decided about a pending memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreFlow {
+    /// Pass the memory on to the next stage
+    Continue,
+    /// Store nothing; the store returns this stored memory's ID instead
+    Existing(String),
+}
+
+/// A stage of the store pipeline
+#[async_trait]
+pub trait StoreMiddleware: fmt::Debug + Send + Sync {
+    /// Name the stage is listed under in
+    /// [`PipelineConfig::stages`](crate::config::PipelineConfig::stages)
+    fn name(&self) -> &str;
+
+    /// Inspect or change a memory before it is written
+    ///
+    /// An error stops the store and is returned to the caller.
+    async fn before_store(&self, _pending: &mut PendingMemory) -> Result<StoreFlow> {
+        Ok(StoreFlow::Continue)
+    }
+
+    /// React to a memory once it is stored
+    ///
+    /// The memory is already written, so failures should be logged rather
+    /// than stop anything.
+    async fn after_store(&self, _memory: &Memory) {}
+
+    /// Whether a deferred store may run this stage after returning
+    ///
+    /// See [`MemoryManager::store_memory_deferred`](crate::core::MemoryManager::store_memory_deferred).
+    fn deferrable(&self) -> bool {
+        false
+    }
+}
+
+/// The stages a memory passes through, in order
+#[derive(Clone, Default)]
+pub struct StorePipeline {
+    stages: Vec<Arc<dyn StoreMiddleware>>,
+}
+
+impl fmt::Debug for StorePipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.stage_names()).finish()
+    }
+}
+
+/// What the built-in stages work with
+pub(crate) struct StageDeps {
+    pub storage: Arc<dyn GraphStore>,
+    pub ids: Arc<IdGenerator>,
+    /// Empty when entity extraction is disabled
+    pub extractors: Vec<Arc<dyn EntityExtractor>>,
+    pub embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+    pub token_counter: Arc<dyn TokenCounter>,
+}
+
+impl StorePipeline {
+    /// The stages `config` lists, built from `deps` and `middlewares`
+    ///
+    /// Names that are neither built in nor a middleware's are skipped.
+    pub(crate) fn build(
+        config: &PipelineConfig,
+        deps: StageDeps,
+        middlewares: &[Arc<dyn StoreMiddleware>],
+    ) -> Self {
+        let mut stages: Vec<Arc<dyn StoreMiddleware>> = Vec::new();
+        for name in &config.stages {
+            match name.as_str() {
+                DEDUP_STAGE => stages.push(Arc::new(DedupStage {
+                    storage: Arc::clone(&deps.storage),
+                    strategy: deps.ids.strategy(),
+                })),
+                REDACT_STAGE => stages.push(Arc::new(RedactStage::new(&config.redaction))),
+                CHUNK_STAGE => stages.push(Arc::new(ChunkStage {
+                    config: config.chunking.clone(),
+                    token_counter: Arc::clone(&deps.token_counter),
+                    ids: Arc::clone(&deps.ids),
+                })),
+                EXTRACT_STAGE => stages.push(Arc::new(ExtractStage {
+                    extractors: deps.extractors.clone(),
+                })),
+                EMBED_STAGE => stages.push(Arc::new(EmbedStage {
+                    provider: deps.embedding_provider.clone(),
+                })),
+                HOOKS_STAGE => stages.extend(
+                    middlewares
+                        .iter()
+                        .filter(|middleware| {
+                            !config
+                                .stages
+                                .iter()
+                                .any(|stage| stage.as_str() == middleware.name())
+                        })
+                        .cloned(),
+                ),
+                other => match middlewares
+                    .iter()
+                    .find(|middleware| middleware.name() == other)
+                {
+                    Some(middleware) => stages.push(Arc::clone(middleware)),
+                    None => tracing::debug!("No store middleware named '{}', skipping it", other),
+                },
+            }
+        }
+        Self { stages }
+    }
+
+    /// Names of the stages, in the order they run
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// Run each stage's [`before_store`](StoreMiddleware::before_store) in
+    /// order, stopping at the first that doesn't continue
+    pub async fn before_store(&self, pending: &mut PendingMemory) -> Result<StoreFlow> {
+        self.before_store_matching(pending, |_| true).await
+    }
+
+    /// Like [`before_store`](Self::before_store), for the stages `include`
+    /// picks
+    pub(crate) async fn before_store_matching<F>(
+        &self,
+        pending: &mut PendingMemory,
+        include: F,
+    ) -> Result<StoreFlow>
+    where
+        F: Fn(&dyn StoreMiddleware) -> bool,
+    {
+        for stage in &self.stages {
+            if !include(stage.as_ref()) {
+                continue;
+            }
+            match stage.before_store(pending).await? {
+                StoreFlow::Continue => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(StoreFlow::Continue)
+    }
+
+    /// Run each stage's [`after_store`](StoreMiddleware::after_store) in order
+    pub async fn after_store(&self, memory: &Memory) {
+        for stage in &self.stages {
+            stage.after_store(memory).await;
+        }
+    }
+}
+
+/// Returns the stored memory with the same content-hash ID
+#[derive(Debug)]
+struct DedupStage {
+    storage: Arc<dyn GraphStore>,
+    strategy: IdStrategy,
+}
+
+#[async_trait]
+impl StoreMiddleware for DedupStage {
+    fn name(&self) -> &str {
+        DEDUP_STAGE
+    }
+
+    /// Under content-hash IDs a memory's ID names its content, so a memory
+    /// already stored under that ID holds the same content and isn't written
+    /// again; repeating an import leaves the store as it was
+    async fn before_store(&self, pending: &mut PendingMemory) -> Result<StoreFlow> {
+        if self.strategy != IdStrategy::ContentHash || pending.memory.id.is_empty() {
+            return Ok(StoreFlow::Continue);
+        }
+        let existing = self
+            .storage
+            .get_memory(&pending.memory.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to look up memory: {}", e)))?;
+        Ok(match existing {
+            Some(memory) => StoreFlow::Existing(memory.id),
+            None => StoreFlow::Continue,
+        })
+    }
+}
+
+/// Replaces matches of the configured patterns
+#[derive(Debug)]
+struct RedactStage {
+    patterns: Vec<Regex>,
+    replacement: String,
+}
+
+impl RedactStage {
+    fn new(config: &RedactionConfig) -> Self {
+        let builtin = config.kinds.iter().map(|kind| pii_pattern(*kind));
+        let patterns = builtin
+            .chain(config.patterns.iter().map(String::as_str))
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!("Skipping invalid redaction pattern '{}': {}", pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            patterns,
+            replacement: config.replacement.clone(),
+        }
+    }
+
+    fn redact(&self, text: &mut String) {
+        for pattern in &self.patterns {
+            if let std::borrow::Cow::Owned(redacted) =
+                pattern.replace_all(text, self.replacement.as_str())
+            {
+                *text = redacted;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StoreMiddleware for RedactStage {
+    fn name(&self) -> &str {
+        REDACT_STAGE
+    }
+
+    async fn before_store(&self, pending: &mut PendingMemory) -> Result<StoreFlow> {
+        self.redact(&mut pending.memory.content);
+        for companion in &mut pending.companions {
+            self.redact(&mut companion.content);
+        }
+        Ok(StoreFlow::Continue)
+    }
+}
+
+/// Regular expression finding personal data of `kind`
+fn pii_pattern(kind: PiiKind) -> &'static str {
+    match kind {
+        PiiKind::Email => r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+        PiiKind::Phone => r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{3}\)|\b\d{3})[ .-]?\d{3}[ .-]\d{4}\b",
+        PiiKind::CreditCard => r"\b(?:\d[ -]?){12,15}\d\b",
+        PiiKind::Ssn => r"\b\d{3}-\d{2}-\d{4}\b",
+        PiiKind::IpAddress => r"\b(?:\d{1,3}\.){3}\d{1,3}\b",
+    }
+}
+
+/// Splits long memories into chunk memories
+#[derive(Debug)]
+struct ChunkStage {
+    config: ChunkingConfig,
+    token_counter: Arc<dyn TokenCounter>,
+    ids: Arc<IdGenerator>,
+}
+
+#[async_trait]
+impl StoreMiddleware for ChunkStage {
+    fn name(&self) -> &str {
+        CHUNK_STAGE
+    }
+
+    async fn before_store(&self, pending: &mut PendingMemory) -> Result<StoreFlow> {
+        let Some(max_tokens) = self.config.max_tokens else {
+            return Ok(StoreFlow::Continue);
+        };
+        if self.token_counter.count(&pending.memory.content) <= max_tokens {
+            return Ok(StoreFlow::Continue);
+        }
+        if pending.memory.id.is_empty() {
+            pending.memory.id = self.ids.generate(&pending.memory.content);
+        }
+
+        let chunks = chunk_text(
+            self.token_counter.as_ref(),
+            &pending.memory.content,
+            max_tokens,
+            self.config.overlap,
+        );
+        let parent = &pending.memory;
+        for (index, content) in chunks.iter().enumerate() {
+            let mut chunk = parent.clone();
+            // Distinct per parent, so chunks of two memories never share an ID
+            chunk.id = self.ids.generate(&format!("{}#{}", parent.id, index));
+            chunk.content = content.clone();
+            chunk.embedding = None;
+            chunk.related_memories = vec![parent.id.clone()];
+            if let Some(properties) = chunk.properties.as_object_mut() {
+                properties.remove(SPARSE_EMBEDDING_PROPERTY);
+            }
+            chunk.set_property(CHUNK_OF_PROPERTY, json!(parent.id));
+            chunk.set_property(CHUNK_INDEX_PROPERTY, json!(index));
+            pending.companions.push(chunk);
+        }
+        pending
+            .memory
+            .set_property(CHUNK_COUNT_PROPERTY, json!(chunks.len()));
+        Ok(StoreFlow::Continue)
+    }
+}
+
+/// Runs the entity extractors over the memory's content
+#[derive(Debug)]
+struct ExtractStage {
+    extractors: Vec<Arc<dyn EntityExtractor>>,
+}
+
+#[async_trait]
+impl StoreMiddleware for ExtractStage {
+    fn name(&self) -> &str {
+        EXTRACT_STAGE
+    }
+
+    async fn before_store(&self, pending: &mut PendingMemory) -> Result<StoreFlow> {
+        for extractor in &self.extractors {
+            match extractor.extract_entities(&pending.memory.content).await {
+                Ok(extracted) => pending.entities.extend(extracted),
+                // Continue with other extractors even if one fails
+                Err(e) => tracing::warn!(
+                    "Extractor '{}' failed to extract entities from memory {}: {}",
+                    extractor.name(),
+                    pending.memory.id,
+                    e
+                ),
+            }
+        }
+        Ok(StoreFlow::Continue)
+    }
+
+    fn deferrable(&self) -> bool {
+        true
+    }
+}
+
+/// Embeds memories stored without an embedding
+#[derive(Debug)]
+struct EmbedStage {
+    provider: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+#[async_trait]
+impl StoreMiddleware for EmbedStage {
+    fn name(&self) -> &str {
+        EMBED_STAGE
+    }
+
+    /// Explicitly supplied embeddings are kept; a provider failure leaves
+    /// the memory without one
+    async fn before_store(&self, pending: &mut PendingMemory) -> Result<StoreFlow> {
+        let Some(provider) = &self.provider else {
+            return Ok(StoreFlow::Continue);
+        };
+        let memories = std::iter::once(&mut pending.memory).chain(pending.companions.iter_mut());
+        for memory in memories.filter(|memory| memory.embedding.is_none()) {
+            match provider.embed(&memory.content).await {
+                Ok(embedding) => memory.embedding = Some(embedding),
+                Err(e) => tracing::warn!(
+                    "Embedding provider '{}' failed, storing memory {} without embedding: {}",
+                    provider.name(),
+                    memory.id,
+                    e
+                ),
+            }
+        }
+        Ok(StoreFlow::Continue)
+    }
+
+    fn deferrable(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,313 @@
+//! Preferences: key-value settings kept as memories
+//!
+//! A preference, such as `tone = concise`, is a [`MemoryType::Preference`]
+//! memory whose content reads `tone: concise`, with the key, value and scope
+//! in its `preference` property. Preferences are kept per scope (a user, an
+//! agent, a conversation); those in the [`GLOBAL_PREFERENCE_SCOPE`] apply to
+//! every scope unless the scope sets the same key itself.
+//!
+//! Setting a key again rewrites the same memory, so the last write wins. The
+//! memory version store keeps every value a preference has had. Should two
+//! first writes race and leave two memories for one key, the latest write is
+//! read and the other memory is removed on the next change.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Memory, MemoryType};
+use crate::storage::filters::MemoryFilter;
+use crate::storage::traits::{GraphStore, MemoryVersionStore};
+use crate::{LocaiError, Result};
+
+/// Memory property holding a preference's key, value and scope
+pub const PREFERENCE_PROPERTY: &str = "preference";
+
+/// Scope whose preferences apply everywhere
+pub const GLOBAL_PREFERENCE_SCOPE: &str = "global";
+
+/// The part of a preference kept in its memory's `preference` property
+#[derive(Debug, Serialize, Deserialize)]
+struct PreferenceState {
+    scope: String,
+    key: String,
+    value: String,
+    updated_at: DateTime<Utc>,
+}
+
+/// One preference setting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preference {
+    /// ID of the preference's memory
+    pub id: String,
+
+    pub scope: String,
+    pub key: String,
+    pub value: String,
+
+    /// When the value was last set
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Preference {
+    pub fn new(scope: impl Into<String>, key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            scope: scope.into(),
+            key: key.into(),
+            value: value.into(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Check the preference can be stored: it needs a scope and a key
+    pub fn validate(&self) -> Result<()> {
+        if self.scope.trim().is_empty() {
+            return Err(LocaiError::Preference(
+                "A preference needs a scope".to_string(),
+            ));
+        }
+        if self.key.trim().is_empty() {
+            return Err(LocaiError::Preference(
+                "A preference needs a key".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The preference written out as text
+    pub fn render(&self) -> String {
+        format!("{}: {}", self.key, self.value)
+    }
+
+    /// Read a preference from its memory; `None` if the memory isn't a preference
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        if memory.memory_type != MemoryType::Preference {
+            return None;
+        }
+        let state: PreferenceState = memory
+            .properties
+            .get(PREFERENCE_PROPERTY)
+            .and_then(|state| serde_json::from_value(state.clone()).ok())?;
+        Some(Self {
+            id: memory.id.clone(),
+            scope: state.scope,
+            key: state.key,
+            value: state.value,
+            updated_at: state.updated_at,
+        })
+    }
+
+    /// The memory to store for a new preference
+    pub fn to_memory(&self) -> Memory {
+        let mut memory = Memory::new(self.id.clone(), self.render(), MemoryType::Preference);
+        memory.created_at = self.updated_at;
+        self.write_state(&mut memory);
+        memory
+    }
+
+    fn write_state(&self, memory: &mut Memory) {
+        memory.content = self.render();
+        let state = PreferenceState {
+            scope: self.scope.clone(),
+            key: self.key.clone(),
+            value: self.value.clone(),
+            updated_at: self.updated_at,
+        };
+        memory.set_property(
+            PREFERENCE_PROPERTY,
+            serde_json::to_value(state).unwrap_or_default(),
+        );
+    }
+}
+
+/// A value a preference had, from its memory's versions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreferenceChange {
+    pub value: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Reads and changes preference memories
+#[derive(Debug)]
+pub struct PreferenceStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl PreferenceStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// A scope's own value for a key, ignoring global preferences
+    pub async fn get(&self, scope: &str, key: &str) -> Result<Option<Preference>> {
+        Ok(self
+            .entries(scope, key)
+            .await?
+            .into_iter()
+            .max_by_key(|preference| preference.updated_at))
+    }
+
+    /// A preference by the ID of its memory
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<Preference>> {
+        let memory = self
+            .storage
+            .get_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+        Ok(memory.as_ref().and_then(Preference::from_memory))
+    }
+
+    /// Every scope's preferences, or one scope's own, by scope and key
+    pub async fn list(&self, scope: Option<&str>) -> Result<Vec<Preference>> {
+        let mut latest: HashMap<(String, String), Preference> = HashMap::new();
+        for preference in self.all().await? {
+            if scope.is_some_and(|scope| scope != preference.scope) {
+                continue;
+            }
+            let slot = (preference.scope.clone(), preference.key.clone());
+            match latest.get(&slot) {
+                Some(existing) if existing.updated_at >= preference.updated_at => {}
+                _ => {
+                    latest.insert(slot, preference);
+                }
+            }
+        }
+        let mut preferences: Vec<Preference> = latest.into_values().collect();
+        preferences.sort_by(|a, b| (&a.scope, &a.key).cmp(&(&b.scope, &b.key)));
+        Ok(preferences)
+    }
+
+    /// The preferences in effect for a scope, by key
+    ///
+    /// Global preferences apply unless the scope sets the same key. `None`
+    /// gives only the global preferences.
+    pub async fn effective(&self, scope: Option<&str>) -> Result<Vec<Preference>> {
+        let mut by_key: HashMap<String, Preference> = HashMap::new();
+        for preference in self.list(Some(GLOBAL_PREFERENCE_SCOPE)).await? {
+            by_key.insert(preference.key.clone(), preference);
+        }
+        if let Some(scope) = scope.filter(|scope| *scope != GLOBAL_PREFERENCE_SCOPE) {
+            for preference in self.list(Some(scope)).await? {
+                by_key.insert(preference.key.clone(), preference);
+            }
+        }
+        let mut preferences: Vec<Preference> = by_key.into_values().collect();
+        preferences.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(preferences)
+    }
+
+    /// Give an existing preference a new value
+    ///
+    /// Any other memory left for the same scope and key is removed.
+    pub async fn replace(&self, preference: &Preference, value: &str) -> Result<Preference> {
+        for duplicate in self.entries(&preference.scope, &preference.key).await? {
+            if duplicate.id != preference.id {
+                self.delete(&duplicate.id).await?;
+            }
+        }
+
+        let mut memory = self
+            .storage
+            .get_memory(&preference.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+            .ok_or_else(|| {
+                LocaiError::Preference(format!("Preference {} no longer exists", preference.id))
+            })?;
+        let updated = Preference {
+            value: value.to_string(),
+            updated_at: Utc::now(),
+            ..preference.clone()
+        };
+        updated.write_state(&mut memory);
+        self.storage
+            .update_memory(memory)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to save preference: {}", e)))?;
+        Ok(updated)
+    }
+
+    /// Remove a scope's value for a key, returning whether it had one
+    pub async fn remove(&self, scope: &str, key: &str) -> Result<bool> {
+        let entries = self.entries(scope, key).await?;
+        for preference in &entries {
+            self.delete(&preference.id).await?;
+        }
+        Ok(!entries.is_empty())
+    }
+
+    /// The values a scope's key has had, oldest first
+    pub async fn history(
+        &self,
+        versions: &dyn MemoryVersionStore,
+        scope: &str,
+        key: &str,
+    ) -> Result<Vec<PreferenceChange>> {
+        let Some(preference) = self.get(scope, key).await? else {
+            return Ok(Vec::new());
+        };
+        let infos = versions
+            .list_memory_versions(&preference.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list versions: {}", e)))?;
+
+        let prefix = format!("{}: ", preference.key);
+        let mut changes = Vec::new();
+        for info in infos {
+            let version = versions
+                .get_memory_version(&preference.id, &info.version_id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get version: {}", e)))?;
+            if let Some(version) = version {
+                let value = version
+                    .content
+                    .strip_prefix(&prefix)
+                    .unwrap_or(&version.content);
+                changes.push(PreferenceChange {
+                    value: value.to_string(),
+                    changed_at: info.created_at,
+                });
+            }
+        }
+        changes.sort_by_key(|change| change.changed_at);
+        Ok(changes)
+    }
+
+    /// Every memory holding a value for a scope's key
+    async fn entries(&self, scope: &str, key: &str) -> Result<Vec<Preference>> {
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|preference| preference.scope == scope && preference.key == key)
+            .collect())
+    }
+
+    async fn all(&self) -> Result<Vec<Preference>> {
+        let filter = MemoryFilter {
+            memory_type: Some(MemoryType::Preference.to_string()),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list preferences: {}", e)))?;
+        Ok(memories
+            .iter()
+            .filter_map(Preference::from_memory)
+            .collect())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.storage
+            .delete_memory(id)
+            .await
+            .map(|_| ())
+            .map_err(|e| LocaiError::Storage(format!("Failed to delete preference: {}", e)))
+    }
+}
@@ -0,0 +1,407 @@
+//! Procedures: reusable how-to knowledge
+//!
+//! A procedure is a [`MemoryType::Procedural`] memory with structured steps,
+//! the preconditions that must hold before starting, and the metrics that say
+//! whether it worked. The structure lives in the memory's `procedure`
+//! property; the content is the procedure written out as text, so full-text
+//! and vector search see all of it. Procedural memories stored without the
+//! property read as procedures with no steps.
+//!
+//! [`ProcedureStore::find_for`] ranks procedures for a goal by combining two
+//! signals:
+//!
+//! - **text**: the procedure's BM25 score for the goal, relative to the best
+//!   scoring procedure
+//! - **entities**: how many of the goal's entities the procedure is linked to
+//!   in the graph, through the `contains` edges entity extraction creates or
+//!   relationships made by hand
+//!
+//! An entity counts as one of the goal's when its name appears in the goal.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Memory, MemoryType};
+use crate::storage::filters::MemoryFilter;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memory property holding a procedure's structure
+pub const PROCEDURE_PROPERTY: &str = "procedure";
+
+/// Weight of the BM25 signal when ranking procedures for a goal
+const TEXT_WEIGHT: f32 = 0.6;
+
+/// Weight of the goal-entity signal when ranking procedures for a goal
+const ENTITY_WEIGHT: f32 = 0.4;
+
+/// One step of a procedure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcedureStep {
+    /// What to do
+    pub instruction: String,
+
+    /// What should be true once the step is done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+impl ProcedureStep {
+    pub fn new(instruction: impl Into<String>) -> Self {
+        Self {
+            instruction: instruction.into(),
+            expected: None,
+        }
+    }
+
+    pub fn expecting(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+}
+
+/// The part of a procedure kept in its memory's `procedure` property
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProcedureState {
+    name: String,
+    #[serde(default)]
+    goal: String,
+    #[serde(default)]
+    steps: Vec<ProcedureStep>,
+    #[serde(default)]
+    preconditions: Vec<String>,
+    #[serde(default)]
+    success_metrics: Vec<String>,
+}
+
+/// A procedural memory: how to reach a goal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Procedure {
+    /// ID of the procedure's memory
+    pub id: String,
+
+    pub name: String,
+
+    /// What following the procedure achieves
+    pub goal: String,
+
+    pub steps: Vec<ProcedureStep>,
+
+    /// What must hold before starting
+    #[serde(default)]
+    pub preconditions: Vec<String>,
+
+    /// How to tell the procedure worked
+    #[serde(default)]
+    pub success_metrics: Vec<String>,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Procedure {
+    pub fn new(name: impl Into<String>, goal: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.into(),
+            goal: goal.into(),
+            steps: Vec::new(),
+            preconditions: Vec::new(),
+            success_metrics: Vec::new(),
+            tags: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn step(mut self, step: impl Into<ProcedureStep>) -> Self {
+        self.steps.push(step.into());
+        self
+    }
+
+    pub fn precondition(mut self, precondition: impl Into<String>) -> Self {
+        self.preconditions.push(precondition.into());
+        self
+    }
+
+    pub fn success_metric(mut self, metric: impl Into<String>) -> Self {
+        self.success_metrics.push(metric.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Check the procedure can be stored: it needs a name and at least one step
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(LocaiError::Procedure(
+                "A procedure needs a name".to_string(),
+            ));
+        }
+        if self.steps.is_empty() {
+            return Err(LocaiError::Procedure(format!(
+                "Procedure '{}' has no steps",
+                self.name
+            )));
+        }
+        if let Some(index) = self
+            .steps
+            .iter()
+            .position(|step| step.instruction.trim().is_empty())
+        {
+            return Err(LocaiError::Procedure(format!(
+                "Step {} of procedure '{}' has no instruction",
+                index + 1,
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// The procedure written out as text
+    pub fn render(&self) -> String {
+        let mut text = self.name.clone();
+        if !self.goal.is_empty() {
+            text.push_str(&format!("\nGoal: {}", self.goal));
+        }
+        if !self.preconditions.is_empty() {
+            text.push_str("\nPreconditions:");
+            for precondition in &self.preconditions {
+                text.push_str(&format!("\n- {}", precondition));
+            }
+        }
+        text.push_str("\nSteps:");
+        for (index, step) in self.steps.iter().enumerate() {
+            text.push_str(&format!("\n{}. {}", index + 1, step.instruction));
+            if let Some(expected) = &step.expected {
+                text.push_str(&format!(" (expect: {})", expected));
+            }
+        }
+        if !self.success_metrics.is_empty() {
+            text.push_str("\nSuccess:");
+            for metric in &self.success_metrics {
+                text.push_str(&format!("\n- {}", metric));
+            }
+        }
+        text
+    }
+
+    /// Read a procedure from its memory; `None` if the memory isn't procedural
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        if memory.memory_type != MemoryType::Procedural {
+            return None;
+        }
+        let state = memory
+            .properties
+            .get(PROCEDURE_PROPERTY)
+            .and_then(|state| serde_json::from_value::<ProcedureState>(state.clone()).ok())
+            .unwrap_or_else(|| ProcedureState {
+                name: memory
+                    .content
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                ..Default::default()
+            });
+        Some(Self {
+            id: memory.id.clone(),
+            name: state.name,
+            goal: state.goal,
+            steps: state.steps,
+            preconditions: state.preconditions,
+            success_metrics: state.success_metrics,
+            tags: memory.tags.clone(),
+            created_at: memory.created_at,
+        })
+    }
+
+    /// The memory to store for a new procedure
+    pub fn to_memory(&self) -> Memory {
+        let mut memory = Memory::new(self.id.clone(), self.render(), MemoryType::Procedural);
+        memory.tags = self.tags.clone();
+        memory.created_at = self.created_at;
+        let state = ProcedureState {
+            name: self.name.clone(),
+            goal: self.goal.clone(),
+            steps: self.steps.clone(),
+            preconditions: self.preconditions.clone(),
+            success_metrics: self.success_metrics.clone(),
+        };
+        memory.set_property(
+            PROCEDURE_PROPERTY,
+            serde_json::to_value(state).unwrap_or_default(),
+        );
+        memory
+    }
+}
+
+impl From<&str> for ProcedureStep {
+    fn from(instruction: &str) -> Self {
+        Self::new(instruction)
+    }
+}
+
+impl From<String> for ProcedureStep {
+    fn from(instruction: String) -> Self {
+        Self::new(instruction)
+    }
+}
+
+/// A procedure found for a goal, with why it matched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcedureMatch {
+    pub procedure: Procedure,
+
+    /// Combined score, from 0.0 to 1.0
+    pub score: f32,
+
+    /// BM25 score relative to the best matching procedure
+    pub text_score: f32,
+
+    /// Share of the goal's entities the procedure is linked to
+    pub entity_score: f32,
+
+    /// Names of the goal's entities the procedure is linked to
+    pub matched_entities: Vec<String>,
+}
+
+/// Reads procedural memories and finds them for goals
+#[derive(Debug)]
+pub struct ProcedureStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl ProcedureStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// A procedure by ID; `None` if there is no such memory or it isn't procedural
+    pub async fn get(&self, id: &str) -> Result<Option<Procedure>> {
+        let memory = self
+            .storage
+            .get_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+        Ok(memory.as_ref().and_then(Procedure::from_memory))
+    }
+
+    /// All procedures, by name
+    pub async fn list(&self) -> Result<Vec<Procedure>> {
+        let filter = MemoryFilter {
+            memory_type: Some(MemoryType::Procedural.to_string()),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list procedures: {}", e)))?;
+        let mut procedures: Vec<Procedure> =
+            memories.iter().filter_map(Procedure::from_memory).collect();
+        procedures.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(procedures)
+    }
+
+    /// The procedures most likely to reach `goal`, best first
+    ///
+    /// Each procedure's entities are looked up in the graph, so this reads
+    /// every procedure; it suits a library of procedures, not a corpus.
+    pub async fn find_for(&self, goal: &str, limit: usize) -> Result<Vec<ProcedureMatch>> {
+        let procedures = self.list().await?;
+        if procedures.is_empty() || goal.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Text signal: BM25 over the rendered procedures
+        let hits = self
+            .storage
+            .bm25_search_memories(goal, Some(procedures.len().max(limit)))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to search procedures: {}", e)))?;
+        let text_scores: HashMap<String, f32> = hits
+            .into_iter()
+            .filter(|(memory, _, _)| memory.memory_type == MemoryType::Procedural)
+            .map(|(memory, score, _)| (memory.id, score))
+            .collect();
+        let best_text = text_scores.values().copied().fold(0.0f32, f32::max);
+
+        // Entity signal: the goal's entities linked to each procedure
+        let goal_lower = goal.to_lowercase();
+        let mut linked: HashMap<String, Vec<String>> = HashMap::new();
+        let mut goal_entities = HashSet::new();
+        for procedure in &procedures {
+            let entities = self
+                .storage
+                .get_entities_from_memory(&procedure.id)
+                .await
+                .map_err(|e| {
+                    LocaiError::Storage(format!("Failed to get procedure entities: {}", e))
+                })?;
+            let mut names: Vec<String> = entities
+                .iter()
+                .filter_map(|entity| entity.properties.get("name").and_then(|v| v.as_str()))
+                .filter(|name| mentions(&goal_lower, name))
+                .map(str::to_string)
+                .collect();
+            names.sort();
+            names.dedup();
+            goal_entities.extend(names.iter().map(|name| name.to_lowercase()));
+            linked.insert(procedure.id.clone(), names);
+        }
+
+        let mut matches: Vec<ProcedureMatch> = procedures
+            .into_iter()
+            .filter_map(|procedure| {
+                let text_score = match text_scores.get(&procedure.id) {
+                    Some(score) if best_text > 0.0 => score / best_text,
+                    _ => 0.0,
+                };
+                let matched_entities = linked.remove(&procedure.id).unwrap_or_default();
+                let entity_score = if goal_entities.is_empty() {
+                    0.0
+                } else {
+                    matched_entities.len() as f32 / goal_entities.len() as f32
+                };
+                let score = TEXT_WEIGHT * text_score + ENTITY_WEIGHT * entity_score;
+                (score > 0.0).then_some(ProcedureMatch {
+                    procedure,
+                    score,
+                    text_score,
+                    entity_score,
+                    matched_entities,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        matches.truncate(limit);
+        Ok(matches)
+    }
+}
+
+/// Whether `text` (already lowercase) mentions `name` as a whole word or phrase
+fn mentions(text: &str, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return false;
+    }
+    text.match_indices(&name).any(|(start, _)| {
+        let end = start + name.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
@@ -0,0 +1,274 @@
+//! Query expansion via graph neighbors
+//!
+//! Detects entities named in a search query and adds the names of entities
+//! strongly related to them (one hop in the graph). A query like "what did the
+//! project lead say" then also finds memories that only mention the lead by
+//! name.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::models::{Entity, Relationship};
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Entity properties that hold a name the entity may be referred to by
+const NAME_PROPERTIES: &[&str] = &["name", "text", "value", "title", "role"];
+
+/// Relationship properties read as strength, in order of preference
+const STRENGTH_PROPERTIES: &[&str] = &["weight", "strength", "confidence"];
+
+/// Configuration for graph-neighbor query expansion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryExpansionConfig {
+    /// Maximum number of entities detected in the query to expand from
+    pub max_seed_entities: usize,
+
+    /// Maximum number of neighbor names added to the query
+    pub max_expansions: usize,
+
+    /// Minimum relationship strength to follow (relationships without a
+    /// weight, strength or confidence property count as 1.0)
+    pub min_strength: f32,
+
+    /// Weight of expansion results relative to the original query when fused
+    pub expansion_weight: f32,
+
+    /// Maximum number of entities scanned when detecting query entities
+    pub entity_scan_limit: usize,
+}
+
+impl Default for QueryExpansionConfig {
+    fn default() -> Self {
+        Self {
+            max_seed_entities: 5,
+            max_expansions: 5,
+            min_strength: 0.5,
+            expansion_weight: 0.5,
+            entity_scan_limit: 1000,
+        }
+    }
+}
+
+/// Result of expanding a query
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExpandedQuery {
+    /// The query as given
+    pub original: String,
+
+    /// IDs of entities detected in the query
+    pub seed_entities: Vec<String>,
+
+    /// Names of related entities to search for in addition to the query
+    pub added_terms: Vec<String>,
+}
+
+/// Expands queries with the names of related entities
+#[derive(Debug, Clone)]
+pub struct QueryExpander {
+    storage: Arc<dyn GraphStore>,
+    config: QueryExpansionConfig,
+}
+
+impl QueryExpander {
+    /// Create an expander over the given storage
+    pub fn new(storage: Arc<dyn GraphStore>, config: QueryExpansionConfig) -> Self {
+        Self { storage, config }
+    }
+
+    /// Detect entities in `query` and collect names of their strong neighbors
+    pub async fn expand(&self, query: &str) -> Result<ExpandedQuery> {
+        let mut expanded = ExpandedQuery {
+            original: query.to_string(),
+            ..Default::default()
+        };
+
+        let query_tokens = tokenize(query);
+        if query_tokens.is_empty() || self.config.max_expansions == 0 {
+            return Ok(expanded);
+        }
+
+        let entities = self
+            .storage
+            .list_entities(None, Some(self.config.entity_scan_limit), None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list entities: {}", e)))?;
+
+        // Longest matching name first: "project lead" is more specific than "lead"
+        let mut seeds: Vec<(usize, &Entity)> = entities
+            .iter()
+            .filter_map(|entity| {
+                entity_names(entity)
+                    .iter()
+                    .map(|name| tokenize(name))
+                    .filter(|name_tokens| contains_phrase(&query_tokens, name_tokens))
+                    .map(|name_tokens| name_tokens.len())
+                    .max()
+                    .map(|len| (len, entity))
+            })
+            .collect();
+        seeds.sort_by_key(|(len, _)| std::cmp::Reverse(*len));
+        seeds.truncate(self.config.max_seed_entities);
+
+        let seed_ids: HashSet<&str> = seeds.iter().map(|(_, e)| e.id.as_str()).collect();
+        expanded.seed_entities = seeds.iter().map(|(_, e)| e.id.clone()).collect();
+
+        // Strongest edge to each neighbor across all seeds
+        let mut neighbors: HashMap<String, f32> = HashMap::new();
+        for (_, seed) in &seeds {
+            let relationships = self
+                .storage
+                .get_entity_relationships(&seed.id)
+                .await
+                .map_err(|e| {
+                    LocaiError::Storage(format!("Failed to get entity relationships: {}", e))
+                })?;
+
+            for relationship in relationships {
+                let strength = relationship_strength(&relationship);
+                if strength < self.config.min_strength {
+                    continue;
+                }
+                let neighbor = if relationship.source_id == seed.id {
+                    &relationship.target_id
+                } else {
+                    &relationship.source_id
+                };
+                if seed_ids.contains(neighbor.as_str()) {
+                    continue;
+                }
+                let best = neighbors.entry(neighbor.clone()).or_insert(strength);
+                *best = best.max(strength);
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = neighbors.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        for (neighbor_id, _) in ranked {
+            if expanded.added_terms.len() >= self.config.max_expansions {
+                break;
+            }
+            // Relationships may also point at memories; only entities have names
+            let Ok(Some(entity)) = self.storage.get_entity(&neighbor_id).await else {
+                continue;
+            };
+            let Some(name) = entity_names(&entity).into_iter().next() else {
+                continue;
+            };
+            let name_tokens = tokenize(&name);
+            if name_tokens.is_empty()
+                || contains_phrase(&query_tokens, &name_tokens)
+                || expanded.added_terms.contains(&name)
+            {
+                continue;
+            }
+            expanded.added_terms.push(name);
+        }
+
+        Ok(expanded)
+    }
+}
+
+/// Names an entity may be referred to by, primary name first
+fn entity_names(entity: &Entity) -> Vec<String> {
+    let mut names: Vec<String> = NAME_PROPERTIES
+        .iter()
+        .filter_map(|key| entity.properties.get(*key))
+        .filter_map(|value| value.as_str())
+        .map(str::to_string)
+        .collect();
+
+    if let Some(aliases) = entity.properties.get("aliases").and_then(|v| v.as_array()) {
+        names.extend(
+            aliases
+                .iter()
+                .filter_map(|a| a.as_str())
+                .map(str::to_string),
+        );
+    }
+
+    names.retain(|name| !name.trim().is_empty());
+    names
+}
+
+/// Strength of a relationship from its properties, defaulting to 1.0
+fn relationship_strength(relationship: &Relationship) -> f32 {
+    STRENGTH_PROPERTIES
+        .iter()
+        .find_map(|key| relationship.properties.get(*key).and_then(|v| v.as_f64()))
+        .map(|v| v as f32)
+        .unwrap_or(1.0)
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether `phrase` occurs as a contiguous token sequence in `tokens`
+fn contains_phrase(tokens: &[String], phrase: &[String]) -> bool {
+    !phrase.is_empty() && tokens.windows(phrase.len()).any(|window| window == phrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn entity(properties: serde_json::Value) -> Entity {
+        Entity {
+            id: "e1".to_string(),
+            entity_type: "person".to_string(),
+            properties,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn relationship(properties: serde_json::Value) -> Relationship {
+        Relationship {
+            id: "r1".to_string(),
+            relationship_type: "holds_role".to_string(),
+            source_id: "a".to_string(),
+            target_id: "b".to_string(),
+            properties,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_contains_phrase_matches_whole_tokens() {
+        let query = tokenize("What did the Project Lead say?");
+        assert!(contains_phrase(&query, &tokenize("project lead")));
+        assert!(!contains_phrase(&query, &tokenize("lead project")));
+        assert!(!contains_phrase(&query, &tokenize("pro")));
+        assert!(!contains_phrase(&query, &[]));
+    }
+
+    #[test]
+    fn test_entity_names_include_aliases() {
+        let names = entity(json!({"name": "Alice Chen", "aliases": ["Al", ""]}));
+        assert_eq!(entity_names(&names), vec!["Alice Chen", "Al"]);
+        assert!(entity_names(&entity(json!({}))).is_empty());
+    }
+
+    #[test]
+    fn test_relationship_strength() {
+        assert_eq!(relationship_strength(&relationship(json!({}))), 1.0);
+        assert_eq!(
+            relationship_strength(&relationship(json!({"confidence": 0.3}))),
+            0.3
+        );
+        assert_eq!(
+            relationship_strength(&relationship(json!({"weight": 0.9, "confidence": 0.3}))),
+            0.9
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Storage quotas per tenant or agent
+//!
+//! [`QuotaTracker`] keeps a running total of the memories, bytes and vectors
+//! each owner has stored and refuses writes that would take an owner past
+//! its [`QuotaLimits`]. Totals are counted from storage the first time they
+//! are needed and then kept up to date by the writes going through this
+//! process; [`refresh`](QuotaTracker::refresh) recounts them, for example
+//! after other processes have written to the same store.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::config::{QuotaConfig, QuotaLimits};
+use crate::models::Memory;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memories read per page when counting usage
+const SCAN_PAGE: usize = 1000;
+
+/// What one owner has stored, against its limits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub owner: String,
+    pub memories: u64,
+    /// Bytes of content, tags, properties and embeddings
+    pub bytes: u64,
+    /// Memories with an embedding
+    pub vectors: u64,
+    pub limits: QuotaLimits,
+}
+
+/// Usage totals for one owner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Totals {
+    memories: u64,
+    bytes: u64,
+    vectors: u64,
+}
+
+impl Totals {
+    fn of(memory: &Memory) -> Self {
+        Self {
+            memories: 1,
+            bytes: memory_bytes(memory),
+            vectors: u64::from(memory.embedding.is_some()),
+        }
+    }
+
+    fn add(&mut self, other: Totals) {
+        self.memories += other.memories;
+        self.bytes += other.bytes;
+        self.vectors += other.vectors;
+    }
+
+    fn sub(&mut self, other: Totals) {
+        self.memories = self.memories.saturating_sub(other.memories);
+        self.bytes = self.bytes.saturating_sub(other.bytes);
+        self.vectors = self.vectors.saturating_sub(other.vectors);
+    }
+}
+
+/// Usage counted for a memory about to be stored
+#[derive(Debug)]
+pub struct Reservation {
+    owner: String,
+    totals: Totals,
+}
+
+/// Approximate stored size of a memory
+pub fn memory_bytes(memory: &Memory) -> u64 {
+    let tags: usize = memory.tags.iter().map(String::len).sum();
+    let properties = if memory.properties.is_null() {
+        0
+    } else {
+        memory.properties.to_string().len()
+    };
+    let embedding = memory
+        .embedding
+        .as_ref()
+        .map_or(0, |e| e.len() * std::mem::size_of::<f32>());
+    (memory.content.len() + tags + properties + embedding) as u64
+}
+
+/// Tracks and enforces [`QuotaConfig`]
+#[derive(Debug)]
+pub struct QuotaTracker {
+    config: QuotaConfig,
+    storage: Arc<dyn GraphStore>,
+    /// Totals per owner, once counted
+    usage: Mutex<Option<HashMap<String, Totals>>>,
+}
+
+impl QuotaTracker {
+    pub fn new(storage: Arc<dyn GraphStore>, config: QuotaConfig) -> Self {
+        Self {
+            config,
+            storage,
+            usage: Mutex::new(None),
+        }
+    }
+
+    /// Owner a memory counts against
+    pub fn owner_of<'a>(&self, memory: &'a Memory) -> &'a str {
+        memory
+            .properties
+            .get(&self.config.owner_property)
+            .and_then(|owner| owner.as_str())
+            .unwrap_or(&memory.source)
+    }
+
+    /// Whether writes need to be accounted for
+    ///
+    /// Without limits, usage is only counted once someone asks for it.
+    pub async fn is_tracking(&self) -> bool {
+        self.config.is_enforced() || self.usage.lock().await.is_some()
+    }
+
+    /// Count `memory` against its owner, failing if that exceeds a limit
+    ///
+    /// Returns the reservation to [`cancel`](Self::cancel) if the memory isn't
+    /// stored after all, or `None` when nothing is being tracked.
+    pub async fn reserve(&self, memory: &Memory) -> Result<Option<Reservation>> {
+        if !self.is_tracking().await {
+            return Ok(None);
+        }
+        let mut guard = self.usage.lock().await;
+        let usage = self.loaded(&mut guard).await?;
+        let owner = self.owner_of(memory);
+        let totals = Totals::of(memory);
+        let mut after = usage.get(owner).copied().unwrap_or_default();
+        after.add(totals);
+        self.check(owner, &after)?;
+        usage.insert(owner.to_string(), after);
+        Ok(Some(Reservation {
+            owner: owner.to_string(),
+            totals,
+        }))
+    }
+
+    /// Give back a reservation for a memory that wasn't stored
+    pub async fn cancel(&self, reservation: Reservation) {
+        if let Some(usage) = self.usage.lock().await.as_mut()
+            && let Some(totals) = usage.get_mut(&reservation.owner)
+        {
+            totals.sub(reservation.totals);
+        }
+    }
+
+    /// Stop counting a deleted memory
+    pub async fn release(&self, memory: &Memory) {
+        self.cancel(Reservation {
+            owner: self.owner_of(memory).to_string(),
+            totals: Totals::of(memory),
+        })
+        .await;
+    }
+
+    /// Account for `old` being replaced by `new`
+    ///
+    /// Fails if the change takes the owner of `new` past a limit. Shrinking a
+    /// memory is always allowed, even for an owner already over its limits.
+    pub async fn replace(&self, old: &Memory, new: &Memory) -> Result<()> {
+        self.apply_replace(old, new, true).await
+    }
+
+    /// Undo a [`replace`](Self::replace) whose write failed
+    pub async fn revert(&self, old: &Memory, new: &Memory) {
+        // Without enforcement this can't fail
+        let _ = self.apply_replace(new, old, false).await;
+    }
+
+    /// Forget the counted totals, so they are recounted when next needed
+    ///
+    /// For changes made around the tracker, such as archiving.
+    pub async fn invalidate(&self) {
+        *self.usage.lock().await = None;
+    }
+
+    async fn apply_replace(&self, old: &Memory, new: &Memory, enforce: bool) -> Result<()> {
+        let mut guard = self.usage.lock().await;
+        let Some(usage) = guard.as_mut() else {
+            return Ok(());
+        };
+        let (old_owner, new_owner) = (self.owner_of(old), self.owner_of(new));
+        let (old_totals, new_totals) = (Totals::of(old), Totals::of(new));
+
+        let mut after = usage.get(new_owner).copied().unwrap_or_default();
+        if old_owner == new_owner {
+            after.sub(old_totals);
+        }
+        after.add(new_totals);
+        let grows = old_owner != new_owner
+            || new_totals.bytes > old_totals.bytes
+            || new_totals.vectors > old_totals.vectors;
+        if enforce && grows {
+            self.check(new_owner, &after)?;
+        }
+
+        if old_owner != new_owner
+            && let Some(totals) = usage.get_mut(old_owner)
+        {
+            totals.sub(old_totals);
+        }
+        usage.insert(new_owner.to_string(), after);
+        Ok(())
+    }
+
+    /// Usage of one owner
+    pub async fn usage(&self, owner: &str) -> Result<QuotaUsage> {
+        let mut guard = self.usage.lock().await;
+        let usage = self.loaded(&mut guard).await?;
+        let totals = usage.get(owner).copied().unwrap_or_default();
+        Ok(self.report(owner, totals))
+    }
+
+    /// Usage of every owner with stored memories, largest first
+    pub async fn all_usage(&self) -> Result<Vec<QuotaUsage>> {
+        let mut guard = self.usage.lock().await;
+        let usage = self.loaded(&mut guard).await?;
+        let mut reports: Vec<QuotaUsage> = usage
+            .iter()
+            .filter(|(_, totals)| totals.memories > 0)
+            .map(|(owner, totals)| self.report(owner, *totals))
+            .collect();
+        reports.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.owner.cmp(&b.owner)));
+        Ok(reports)
+    }
+
+    /// Recount usage from storage
+    pub async fn refresh(&self) -> Result<()> {
+        let counted = self.count().await?;
+        *self.usage.lock().await = Some(counted);
+        Ok(())
+    }
+
+    async fn loaded<'a>(
+        &self,
+        usage: &'a mut Option<HashMap<String, Totals>>,
+    ) -> Result<&'a mut HashMap<String, Totals>> {
+        if usage.is_none() {
+            *usage = Some(self.count().await?);
+        }
+        Ok(usage.get_or_insert_with(HashMap::new))
+    }
+
+    async fn count(&self) -> Result<HashMap<String, Totals>> {
+        let mut usage: HashMap<String, Totals> = HashMap::new();
+        let mut offset = 0;
+        loop {
+            let page = self
+                .storage
+                .list_memories(None, Some(SCAN_PAGE), Some(offset))
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to count quota usage: {}", e)))?;
+            for memory in &page {
+                usage
+                    .entry(self.owner_of(memory).to_string())
+                    .or_default()
+                    .add(Totals::of(memory));
+            }
+            if page.len() < SCAN_PAGE {
+                break;
+            }
+            offset += page.len();
+        }
+        tracing::debug!("Counted quota usage for {} owners", usage.len());
+        Ok(usage)
+    }
+
+    fn check(&self, owner: &str, after: &Totals) -> Result<()> {
+        let limits = self.config.limits_for(owner);
+        let checks = [
+            ("memories", after.memories, limits.max_memories),
+            ("bytes", after.bytes, limits.max_bytes),
+            ("vectors", after.vectors, limits.max_vectors),
+        ];
+        for (resource, used, limit) in checks {
+            if let Some(limit) = limit
+                && used > limit
+            {
+                return Err(LocaiError::QuotaExceeded {
+                    owner: owner.to_string(),
+                    resource: resource.to_string(),
+                    used,
+                    limit,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn report(&self, owner: &str, totals: Totals) -> QuotaUsage {
+        QuotaUsage {
+            owner: owner.to_string(),
+            memories: totals.memories,
+            bytes: totals.bytes,
+            vectors: totals.vectors,
+            limits: self.config.limits_for(owner).clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MemoryBuilder;
+
+    #[test]
+    fn test_memory_bytes_counts_embedding() {
+        let plain = MemoryBuilder::new_with_content("abcd").build();
+        let mut embedded = plain.clone();
+        embedded.embedding = Some(vec![0.0; 8]);
+        assert_eq!(memory_bytes(&embedded) - memory_bytes(&plain), 32);
+    }
+
+    #[test]
+    fn test_totals_never_underflow() {
+        let memory = MemoryBuilder::new_with_content("abcd").build();
+        let mut totals = Totals::default();
+        totals.sub(Totals::of(&memory));
+        assert_eq!(totals, Totals::default());
+    }
+}
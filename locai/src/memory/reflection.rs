@@ -0,0 +1,171 @@
+//! Reflection: insights derived from memories
+//!
+//! Reflection, as in the generative agents paper, steps back from what an
+//! agent remembers and writes down what it adds up to. [`MemoryManager::reflect`]
+//! gathers the memories from a time range, optionally about a topic, hands
+//! them to a [`Reflector`] and stores each [`Insight`] it returns as a
+//! [`MemoryType::Wisdom`] memory with a `derived_from` relationship to every
+//! memory the insight cites. Locai does not call an LLM itself: implement
+//! [`Reflector`] around your own model client.
+//!
+//! [`MemoryManager::reflect`]: crate::core::MemoryManager::reflect
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::Result;
+use crate::memory::TimeRange;
+use crate::models::{Memory, MemoryType};
+use crate::storage::models::Relationship;
+
+/// Relationship from an insight memory to a memory it was derived from
+pub const DERIVED_FROM: &str = "derived_from";
+
+/// Memory property recording how an insight was reflected
+pub const REFLECTION_PROPERTY: &str = "reflection";
+
+/// Source set on insight memories
+pub const REFLECTION_SOURCE: &str = "reflection";
+
+/// Most memories handed to a reflector at once, newest first
+pub const MAX_REFLECTION_MEMORIES: usize = 100;
+
+/// A conclusion a [`Reflector`] drew from some memories
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Insight {
+    pub content: String,
+
+    /// IDs of the memories the insight was drawn from
+    pub sources: Vec<String>,
+}
+
+impl Insight {
+    pub fn new(content: impl Into<String>, sources: Vec<String>) -> Self {
+        Self {
+            content: content.into(),
+            sources,
+        }
+    }
+
+    /// The memory to store for the insight
+    ///
+    /// `sources` should hold only IDs of memories that exist; the memory
+    /// records them along with the topic, time range and reflector.
+    pub(crate) fn to_memory(
+        &self,
+        topic: Option<&str>,
+        time_range: &TimeRange,
+        reflector: &str,
+    ) -> Memory {
+        let mut memory = Memory::new(
+            Uuid::new_v4().to_string(),
+            self.content.trim().to_string(),
+            MemoryType::Wisdom,
+        );
+        memory.source = REFLECTION_SOURCE.to_string();
+        memory.related_memories = self.sources.clone();
+        memory.set_property(
+            REFLECTION_PROPERTY,
+            json!({
+                "topic": topic,
+                "start": time_range.start,
+                "end": time_range.end,
+                "reflector": reflector,
+                "sources": self.sources,
+            }),
+        );
+        memory
+    }
+}
+
+/// The `derived_from` relationship from an insight to one of its sources
+pub(crate) fn derived_from(insight_id: &str, source_id: &str) -> Relationship {
+    let now = Utc::now();
+    Relationship {
+        id: format!("{}_{}_{}", insight_id, DERIVED_FROM, source_id),
+        relationship_type: DERIVED_FROM.to_string(),
+        source_id: insight_id.to_string(),
+        target_id: source_id.to_string(),
+        properties: serde_json::Value::Null,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Draws insights from a set of memories
+#[async_trait]
+pub trait Reflector: fmt::Debug + Send + Sync {
+    /// Short human-readable name
+    fn name(&self) -> &str;
+
+    /// Produce insights from `memories`, newest first
+    ///
+    /// Each insight should cite the IDs of the memories it rests on. Citations
+    /// of memories that weren't given are dropped, and insights with no
+    /// content are skipped.
+    async fn reflect(&self, topic: Option<&str>, memories: &[Memory]) -> Result<Vec<Insight>>;
+}
+
+type ReflectFn = dyn Fn(Option<&str>, &[Memory]) -> Result<Vec<Insight>> + Send + Sync;
+
+/// A [`Reflector`] backed by a user-provided function
+///
+/// ```rust
+/// use locai::memory::reflection::{CallbackReflector, Insight};
+///
+/// // Note how often deploys came up
+/// let reflector = CallbackReflector::new("deploy-counter", |_topic, memories| {
+///     let deploys: Vec<String> = memories
+///         .iter()
+///         .filter(|memory| memory.content.contains("deploy"))
+///         .map(|memory| memory.id.clone())
+///         .collect();
+///     Ok(vec![Insight::new(
+///         format!("Deploys came up {} times", deploys.len()),
+///         deploys,
+///     )])
+/// });
+/// ```
+#[derive(Clone)]
+pub struct CallbackReflector {
+    name: String,
+    callback: Arc<ReflectFn>,
+}
+
+impl CallbackReflector {
+    /// Wrap a reflect function
+    pub fn new<F>(name: impl Into<String>, callback: F) -> Self
+    where
+        F: Fn(Option<&str>, &[Memory]) -> Result<Vec<Insight>> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+impl fmt::Debug for CallbackReflector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackReflector")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Reflector for CallbackReflector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn reflect(&self, topic: Option<&str>, memories: &[Memory]) -> Result<Vec<Insight>> {
+        (self.callback)(topic, memories)
+    }
+}
@@ -0,0 +1,349 @@
+//! Memory reminders
+//!
+//! A reminder asks for a memory to be surfaced at a time, once or on a
+//! [`Recurrence`]. Something has to notice when reminders come due: call
+//! [`ReminderStore::fire_due`] (through
+//! [`MemoryManager::fire_due_reminders`](crate::core::MemoryManager::fire_due_reminders))
+//! periodically, or let
+//! [`LocaiMessaging::start_reminders`](crate::messaging::LocaiMessaging::start_reminders)
+//! do it and publish each firing as a message.
+//!
+//! Snoozing delays only the current occurrence; a recurring reminder then
+//! carries on from its original schedule. Occurrences missed while nothing
+//! was checking fire once, not once per missed occurrence.
+//!
+//! A memory has at most one reminder, stored as an entity of type
+//! [`REMINDER_ENTITY_TYPE`].
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Memory;
+use crate::storage::filters::EntityFilter;
+use crate::storage::models::Entity;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Entity type reminders are stored under
+pub const REMINDER_ENTITY_TYPE: &str = "memory_reminder";
+
+/// How often a reminder repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    /// Same day of the month, or the month's last day if it is shorter
+    Monthly,
+    /// A fixed interval, such as `"90m"`
+    Every(#[serde(with = "humantime_serde")] Duration),
+}
+
+impl Recurrence {
+    /// The first occurrence after `now`, counting on from `from`
+    pub fn next_after(&self, from: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+        if now < from {
+            return from;
+        }
+        let step_ms = match self {
+            Self::Daily => chrono::Duration::days(1).num_milliseconds(),
+            Self::Weekly => chrono::Duration::weeks(1).num_milliseconds(),
+            Self::Every(interval) => i64::try_from(interval.as_millis()).unwrap_or(i64::MAX),
+            Self::Monthly => {
+                let mut months = 1;
+                loop {
+                    match from.checked_add_months(Months::new(months)) {
+                        Some(next) if next > now => return next,
+                        Some(_) => months += 1,
+                        None => return DateTime::<Utc>::MAX_UTC,
+                    }
+                }
+            }
+        }
+        .max(1);
+
+        // Skip every occurrence up to now in one step
+        let elapsed_ms = (now - from).num_milliseconds();
+        (elapsed_ms / step_ms + 1)
+            .checked_mul(step_ms)
+            .and_then(|offset| from.checked_add_signed(chrono::Duration::milliseconds(offset)))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "daily"),
+            Self::Weekly => write!(f, "weekly"),
+            Self::Monthly => write!(f, "monthly"),
+            Self::Every(interval) => {
+                write!(
+                    f,
+                    "every {}",
+                    humantime_serde::re::humantime::format_duration(*interval)
+                )
+            }
+        }
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = LocaiError;
+
+    /// Parse `daily`, `weekly`, `monthly` or an interval such as `90m` or `every 2h`
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            other => {
+                let interval = parse_delay(other.strip_prefix("every ").unwrap_or(other))?;
+                if interval.is_zero() {
+                    return Err(LocaiError::Reminder(
+                        "A recurrence interval must be longer than zero".to_string(),
+                    ));
+                }
+                Ok(Self::Every(interval))
+            }
+        }
+    }
+}
+
+/// Parse a delay such as `10m`, `2h 30m` or `3days`
+pub fn parse_delay(value: &str) -> Result<Duration> {
+    humantime_serde::re::humantime::parse_duration(value.trim())
+        .map_err(|e| LocaiError::Reminder(format!("Can't read '{}' as a delay: {}", value, e)))
+}
+
+/// A request to surface a memory at a time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub memory_id: String,
+
+    /// When the reminder is scheduled, ignoring any snooze
+    pub remind_at: DateTime<Utc>,
+
+    /// How often it repeats; `None` for a one-off reminder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+
+    /// When the current occurrence fires instead, after a snooze
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime<Utc>>,
+
+    /// Times a recurring reminder has fired
+    #[serde(default)]
+    pub fired_count: u32,
+
+    #[serde(default)]
+    pub last_fired_at: Option<DateTime<Utc>>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Reminder {
+    /// A one-off reminder
+    pub fn new(memory_id: impl Into<String>, remind_at: DateTime<Utc>) -> Self {
+        Self {
+            memory_id: memory_id.into(),
+            remind_at,
+            recurrence: None,
+            snoozed_until: None,
+            fired_count: 0,
+            last_fired_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Repeat the reminder
+    pub fn with_recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = Some(recurrence);
+        self
+    }
+
+    /// When the reminder next fires
+    pub fn due_at(&self) -> DateTime<Utc> {
+        self.snoozed_until.unwrap_or(self.remind_at)
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.due_at() <= now
+    }
+
+    fn entity_id(memory_id: &str) -> String {
+        format!("reminder:{}", memory_id)
+    }
+
+    fn from_entity(entity: Entity) -> Option<Self> {
+        serde_json::from_value(entity.properties).ok()
+    }
+
+    fn to_entity(&self) -> Entity {
+        Entity {
+            id: Self::entity_id(&self.memory_id),
+            entity_type: REMINDER_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(self).unwrap_or_default(),
+            created_at: self.created_at,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// A reminder that came due
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderEvent {
+    /// The memory being surfaced
+    pub memory: Memory,
+
+    /// When the reminder was due
+    pub due_at: DateTime<Utc>,
+
+    pub fired_at: DateTime<Utc>,
+
+    /// When a recurring reminder fires next; `None` once a reminder is done
+    pub next_at: Option<DateTime<Utc>>,
+}
+
+/// Stores reminders and fires the ones that come due
+#[derive(Debug)]
+pub struct ReminderStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl ReminderStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// Set a memory's reminder, replacing any it had
+    ///
+    /// Fails if the memory doesn't exist.
+    pub async fn set(&self, reminder: Reminder) -> Result<Reminder> {
+        let exists = self
+            .storage
+            .get_memory(&reminder.memory_id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+            .is_some();
+        if !exists {
+            return Err(LocaiError::Memory(format!(
+                "Memory {} not found, so no reminder can be set",
+                reminder.memory_id
+            )));
+        }
+        self.save(&reminder).await?;
+        Ok(reminder)
+    }
+
+    /// A memory's reminder, if it has one
+    pub async fn get(&self, memory_id: &str) -> Result<Option<Reminder>> {
+        let entity = self
+            .storage
+            .get_entity(&Reminder::entity_id(memory_id))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get reminder: {}", e)))?;
+        Ok(entity.and_then(Reminder::from_entity))
+    }
+
+    /// All reminders, soonest first
+    pub async fn list(&self) -> Result<Vec<Reminder>> {
+        let filter = EntityFilter {
+            entity_type: Some(REMINDER_ENTITY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let entities = self
+            .storage
+            .list_entities(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list reminders: {}", e)))?;
+        let mut reminders: Vec<Reminder> = entities
+            .into_iter()
+            .filter_map(Reminder::from_entity)
+            .collect();
+        reminders.sort_by_key(Reminder::due_at);
+        Ok(reminders)
+    }
+
+    /// Remove a memory's reminder, returning whether it had one
+    pub async fn cancel(&self, memory_id: &str) -> Result<bool> {
+        self.storage
+            .delete_entity(&Reminder::entity_id(memory_id))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to delete reminder: {}", e)))
+    }
+
+    /// Put off the current occurrence of a memory's reminder until `until`
+    ///
+    /// Returns `None` if the memory has no reminder.
+    pub async fn snooze(&self, memory_id: &str, until: DateTime<Utc>) -> Result<Option<Reminder>> {
+        let Some(mut reminder) = self.get(memory_id).await? else {
+            return Ok(None);
+        };
+        reminder.snoozed_until = Some(until);
+        self.save(&reminder).await?;
+        Ok(Some(reminder))
+    }
+
+    /// Fire the reminders due at `now`
+    ///
+    /// One-off reminders are removed once fired and recurring ones move to
+    /// their next occurrence. Reminders whose memory was deleted are dropped
+    /// without firing.
+    pub async fn fire_due(&self, now: DateTime<Utc>) -> Result<Vec<ReminderEvent>> {
+        let mut events = Vec::new();
+        for mut reminder in self.list().await? {
+            if !reminder.is_due(now) {
+                break;
+            }
+
+            let memory = self
+                .storage
+                .get_memory(&reminder.memory_id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+            let Some(memory) = memory else {
+                self.cancel(&reminder.memory_id).await?;
+                continue;
+            };
+
+            let due_at = reminder.due_at();
+            let next_at = reminder
+                .recurrence
+                .map(|recurrence| recurrence.next_after(reminder.remind_at, now));
+            match next_at {
+                Some(next_at) => {
+                    reminder.remind_at = next_at;
+                    reminder.snoozed_until = None;
+                    reminder.fired_count += 1;
+                    reminder.last_fired_at = Some(now);
+                    self.save(&reminder).await?;
+                }
+                None => {
+                    self.cancel(&reminder.memory_id).await?;
+                }
+            }
+
+            events.push(ReminderEvent {
+                memory,
+                due_at,
+                fired_at: now,
+                next_at,
+            });
+        }
+        Ok(events)
+    }
+
+    async fn save(&self, reminder: &Reminder) -> Result<()> {
+        self.storage
+            .upsert_entity(reminder.to_entity())
+            .await
+            .map(|_| ())
+            .map_err(|e| LocaiError::Storage(format!("Failed to save reminder: {}", e)))
+    }
+}
@@ -0,0 +1,284 @@
+//! Review queue: memories that wait for approval
+//!
+//! With [`ReviewConfig::enabled`], a memory stored from one of
+//! [`ReviewConfig::sources`] isn't written. It waits in the queue, kept in
+//! the `review` property of a `custom:review` memory whose content doesn't
+//! repeat it, so it stays out of search until someone approves it, which
+//! stores it as any other memory, or rejects it, which drops it. Memories an
+//! [`AutoApproveRule`] matches are stored without waiting.
+//!
+//! The store returns a held memory's ID straight away; it's the ID the
+//! memory is stored under once approved. Queue entries are looked up by
+//! that ID. Outbox messages stored with a held memory wait with it, and are
+//! committed with the memory when it's approved.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::{AutoApproveRule, ReviewConfig};
+use crate::models::{Memory, MemoryType};
+use crate::storage::filters::MemoryFilter;
+use crate::storage::models::OutboxMessage;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memory property holding a queued memory
+pub const REVIEW_PROPERTY: &str = "review";
+
+/// Custom memory type queue entries are stored as
+pub const REVIEW_MEMORY_TYPE: &str = "review";
+
+/// The part of a queue entry kept in its memory's `review` property
+#[derive(Debug, Serialize, Deserialize)]
+struct ReviewState {
+    memory: Memory,
+    submitted_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    outbox: Vec<OutboxMessage>,
+}
+
+/// A memory waiting for approval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewItem {
+    /// ID of the queue entry's memory
+    pub entry_id: String,
+
+    /// The memory as it will be stored once approved
+    pub memory: Memory,
+
+    /// When the memory was queued
+    pub submitted_at: DateTime<Utc>,
+
+    /// Outbox messages to commit with the memory once approved
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outbox: Vec<OutboxMessage>,
+}
+
+impl ReviewItem {
+    /// Read a queue entry from its memory; `None` if the memory isn't one
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        if memory.memory_type != MemoryType::Custom(REVIEW_MEMORY_TYPE.to_string()) {
+            return None;
+        }
+        let state: ReviewState = memory
+            .properties
+            .get(REVIEW_PROPERTY)
+            .and_then(|state| serde_json::from_value(state.clone()).ok())?;
+        Some(Self {
+            entry_id: memory.id.clone(),
+            memory: state.memory,
+            submitted_at: state.submitted_at,
+            outbox: state.outbox,
+        })
+    }
+
+    /// The memory to store for a queue entry
+    pub fn to_memory(&self) -> Memory {
+        let content = format!(
+            "Memory {} from {} is waiting for review",
+            self.memory.id, self.memory.source
+        );
+        let mut memory = Memory::new(
+            self.entry_id.clone(),
+            content,
+            MemoryType::Custom(REVIEW_MEMORY_TYPE.to_string()),
+        );
+        memory.created_at = self.submitted_at;
+        let state = ReviewState {
+            memory: self.memory.clone(),
+            submitted_at: self.submitted_at,
+            outbox: self.outbox.clone(),
+        };
+        memory.set_property(
+            REVIEW_PROPERTY,
+            serde_json::to_value(state).unwrap_or_default(),
+        );
+        memory
+    }
+}
+
+/// Holds memories from reviewed sources until they're approved
+#[derive(Debug)]
+pub struct ReviewQueue {
+    storage: Arc<dyn GraphStore>,
+    config: ReviewConfig,
+}
+
+impl ReviewQueue {
+    pub fn new(storage: Arc<dyn GraphStore>, config: ReviewConfig) -> Self {
+        Self { storage, config }
+    }
+
+    /// Whether `memory` must wait for approval before it's stored
+    pub fn holds(&self, memory: &Memory) -> bool {
+        if !self.config.enabled
+            || !self
+                .config
+                .sources
+                .iter()
+                .any(|pattern| source_matches(pattern, &memory.source))
+        {
+            return false;
+        }
+        match self
+            .config
+            .auto_approve
+            .iter()
+            .find(|rule| approves(rule, memory))
+        {
+            Some(rule) => {
+                tracing::debug!("Auto-approved memory {} by '{}'", memory.id, rule.name);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Queue a memory and the outbox messages to store with it, returning its ID
+    pub async fn submit(&self, memory: Memory, outbox: Vec<OutboxMessage>) -> Result<String> {
+        let item = ReviewItem {
+            entry_id: Uuid::new_v4().to_string(),
+            memory,
+            submitted_at: Utc::now(),
+            outbox,
+        };
+        self.storage
+            .create_memory(item.to_memory())
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to queue memory: {}", e)))?;
+        tracing::info!(
+            "Memory {} from {} is waiting for review",
+            item.memory.id,
+            item.memory.source
+        );
+        Ok(item.memory.id)
+    }
+
+    /// The queued memory with this ID
+    pub async fn get(&self, memory_id: &str) -> Result<Option<ReviewItem>> {
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .find(|item| item.memory.id == memory_id))
+    }
+
+    /// Every queued memory, oldest first
+    pub async fn list(&self) -> Result<Vec<ReviewItem>> {
+        let mut items = self.all().await?;
+        items.sort_by_key(|item| item.submitted_at);
+        Ok(items)
+    }
+
+    /// Drop a queue entry, returning whether it was there
+    pub async fn remove(&self, item: &ReviewItem) -> Result<bool> {
+        self.storage
+            .delete_memory(&item.entry_id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to remove review entry: {}", e)))
+    }
+
+    async fn all(&self) -> Result<Vec<ReviewItem>> {
+        let filter = MemoryFilter {
+            memory_type: Some(REVIEW_MEMORY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list review queue: {}", e)))?;
+        Ok(memories
+            .iter()
+            .filter_map(ReviewItem::from_memory)
+            .collect())
+    }
+}
+
+/// Whether `source` matches a configured source; a trailing `*` matches
+/// any source starting with what comes before it
+fn source_matches(pattern: &str, source: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => source.starts_with(prefix),
+        None => pattern == source,
+    }
+}
+
+/// Whether every condition the rule gives holds for `memory`
+fn approves(rule: &AutoApproveRule, memory: &Memory) -> bool {
+    rule.tags.iter().all(|tag| memory.tags.contains(tag))
+        && rule.memory_type.as_ref().is_none_or(|memory_type| {
+            memory
+                .memory_type
+                .to_string()
+                .eq_ignore_ascii_case(memory_type)
+        })
+        && rule
+            .source
+            .as_ref()
+            .is_none_or(|source| &memory.source == source)
+        && rule
+            .max_length
+            .is_none_or(|max| memory.content.chars().count() <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(source: &str, tags: &[&str]) -> Memory {
+        let mut memory = Memory::new(
+            "m1".to_string(),
+            "The deploy is failing".to_string(),
+            MemoryType::Fact,
+        );
+        memory.source = source.to_string();
+        memory.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        memory
+    }
+
+    fn rule(name: &str) -> AutoApproveRule {
+        AutoApproveRule {
+            name: name.to_string(),
+            tags: Vec::new(),
+            memory_type: None,
+            source: None,
+            max_length: None,
+        }
+    }
+
+    #[test]
+    fn test_source_patterns() {
+        assert!(source_matches("agent:*", "agent:planner"));
+        assert!(source_matches("*", "anything"));
+        assert!(source_matches("importer", "importer"));
+        assert!(!source_matches("importer", "importer:v2"));
+        assert!(!source_matches("agent:*", "user"));
+    }
+
+    #[test]
+    fn test_auto_approve_conditions_must_all_hold() {
+        let scratch = AutoApproveRule {
+            tags: vec!["scratch".to_string()],
+            max_length: Some(40),
+            ..rule("scratch")
+        };
+        assert!(approves(&scratch, &memory("agent:a", &["scratch", "ops"])));
+        assert!(!approves(&scratch, &memory("agent:a", &["ops"])));
+
+        let short = AutoApproveRule {
+            max_length: Some(5),
+            ..scratch
+        };
+        assert!(!approves(&short, &memory("agent:a", &["scratch"])));
+
+        let facts = AutoApproveRule {
+            memory_type: Some("Fact".to_string()),
+            ..rule("facts")
+        };
+        assert!(approves(&facts, &memory("agent:a", &[])));
+    }
+}
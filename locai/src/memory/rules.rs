@@ -0,0 +1,650 @@
+//! Automation rules: small scripts run on memory events
+//!
+//! A rule is a [Rhai](https://rhai.rs) script that runs whenever its event
+//! happens to a memory, such as "if a memory is tagged urgent, bump its
+//! priority and send a message":
+//!
+//! ```text
+//! if memory.tags.contains("urgent") {
+//!     memory.priority = "high";
+//!     send("alerts", "Urgent: " + memory.content);
+//! }
+//! ```
+//!
+//! Scripts see the memory as the map `memory` (its `id`, `content`,
+//! `memory_type`, `priority`, `tags`, `source` and `properties`) and the
+//! event's name as `event`. Changes a script makes to `priority`, `tags` or
+//! `properties` are saved when the memory was created, accessed or came due
+//! for a reminder; on updates and deletions they're discarded, so a rule
+//! can't set off itself. `send(topic, content)` sends a message from the
+//! `rules` app.
+//!
+//! Rules are kept as memories of type `custom:rule` with their structure in
+//! the `rule` property, so they can be changed at runtime; rules and
+//! messages themselves never set off rules.
+//!
+//! Needs the `rhai-rules` feature; the declarative automations in
+//! [`crate::hooks::automation`] work without it.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rhai::{AST, Dynamic, Engine, Scope};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::RulesConfig;
+use crate::core::MemoryManager;
+use crate::hooks::automation::{parse_priority, runs_rules};
+use crate::hooks::{HookResult, MemoryHook};
+use crate::models::{Memory, MemoryPriority, MemoryType};
+use crate::storage::filters::MemoryFilter;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memory property holding a rule's structure
+pub const RULE_PROPERTY: &str = "rule";
+
+/// App ID messages sent by rules come from
+pub const RULES_APP_ID: &str = "rules";
+
+/// Namespace messages sent by rules are sent in
+pub const RULES_NAMESPACE: &str = "app:rules";
+
+pub use crate::hooks::automation::{RULE_MEMORY_TYPE, RuleEvent};
+
+/// The part of a rule kept in its memory's `rule` property
+#[derive(Debug, Serialize, Deserialize)]
+struct RuleState {
+    name: String,
+    event: RuleEvent,
+    script: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+    updated_at: DateTime<Utc>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// A script run when a memory event happens
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    /// ID of the rule's memory
+    pub id: String,
+
+    /// Unique name of the rule
+    pub name: String,
+
+    /// The event the rule runs on
+    pub event: RuleEvent,
+
+    /// Rhai source of the rule
+    pub script: String,
+
+    /// What the rule is for
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Whether the rule runs; disabled rules are kept but skipped
+    pub enabled: bool,
+
+    /// When the rule was last set
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Rule {
+    pub fn new(name: impl Into<String>, event: RuleEvent, script: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.into(),
+            event,
+            script: script.into(),
+            description: None,
+            enabled: true,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Check the rule can be stored: it needs a name without whitespace and
+    /// a script
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name.chars().any(char::is_whitespace) {
+            return Err(LocaiError::Rule(format!(
+                "Rule name '{}' must be non-empty and without whitespace",
+                self.name
+            )));
+        }
+        if self.script.trim().is_empty() {
+            return Err(LocaiError::Rule(format!(
+                "Rule '{}' needs a script",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// The rule written out as text
+    pub fn render(&self) -> String {
+        let mut text = format!("Rule '{}' on {}", self.name, self.event);
+        if let Some(description) = &self.description {
+            text.push_str(&format!(": {}", description));
+        }
+        text.push('\n');
+        text.push_str(self.script.trim());
+        text
+    }
+
+    /// Read a rule from its memory; `None` if the memory isn't a rule
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        if !is_rule_memory(memory) {
+            return None;
+        }
+        let state: RuleState = memory
+            .properties
+            .get(RULE_PROPERTY)
+            .and_then(|state| serde_json::from_value(state.clone()).ok())?;
+        Some(Self {
+            id: memory.id.clone(),
+            name: state.name,
+            event: state.event,
+            script: state.script,
+            description: state.description,
+            enabled: state.enabled,
+            updated_at: state.updated_at,
+        })
+    }
+
+    /// The memory to store for a new rule
+    pub fn to_memory(&self) -> Memory {
+        let mut memory = Memory::new(
+            self.id.clone(),
+            self.render(),
+            MemoryType::Custom(RULE_MEMORY_TYPE.to_string()),
+        );
+        memory.created_at = self.updated_at;
+        memory.source = RULES_APP_ID.to_string();
+        self.write_state(&mut memory);
+        memory
+    }
+
+    fn write_state(&self, memory: &mut Memory) {
+        memory.content = self.render();
+        let state = RuleState {
+            name: self.name.clone(),
+            event: self.event,
+            script: self.script.clone(),
+            description: self.description.clone(),
+            enabled: self.enabled,
+            updated_at: self.updated_at,
+        };
+        memory.set_property(
+            RULE_PROPERTY,
+            serde_json::to_value(state).unwrap_or_default(),
+        );
+    }
+}
+
+fn is_rule_memory(memory: &Memory) -> bool {
+    matches!(&memory.memory_type, MemoryType::Custom(name) if name == RULE_MEMORY_TYPE)
+}
+
+/// A message a rule sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleMessage {
+    /// Topic within the rules namespace
+    pub topic: String,
+    pub content: serde_json::Value,
+}
+
+/// A rule that stopped with an error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleFailure {
+    pub rule: String,
+    pub error: String,
+}
+
+/// What running rules on a memory did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleOutcome {
+    /// Rules that ran to the end, in the order they ran
+    pub fired: Vec<String>,
+
+    /// The memory as the rules left it, if they changed it
+    pub memory: Option<Memory>,
+
+    /// Messages the rules sent, in the order they sent them
+    pub messages: Vec<RuleMessage>,
+
+    /// Rules that stopped with an error; the others still ran
+    pub failures: Vec<RuleFailure>,
+}
+
+thread_local! {
+    /// Messages `send` was called with during the running script
+    static OUTBOX: RefCell<Vec<RuleMessage>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A rule with its compiled script
+#[derive(Debug)]
+struct CompiledRule {
+    rule: Rule,
+    ast: AST,
+}
+
+/// Rules loaded from storage, and when
+struct CachedRules {
+    rules: Arc<Vec<CompiledRule>>,
+    loaded_at: Instant,
+}
+
+/// Reads, changes and runs rule memories
+pub struct RuleStore {
+    storage: Arc<dyn GraphStore>,
+    engine: Engine,
+    refresh: Duration,
+    cache: RwLock<Option<CachedRules>>,
+}
+
+impl fmt::Debug for RuleStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuleStore")
+            .field("refresh", &self.refresh)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RuleStore {
+    pub fn new(storage: Arc<dyn GraphStore>, config: &RulesConfig) -> Self {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(config.max_operations)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(64 * 1024)
+            .set_max_array_size(10_000)
+            .set_max_map_size(10_000);
+        engine.on_print(|text| tracing::info!(target: "locai::rules", "{}", text));
+        engine.on_debug(
+            |text, _, position| tracing::debug!(target: "locai::rules", "{} ({})", text, position),
+        );
+        engine.register_fn("send", |topic: &str, content: Dynamic| {
+            let content = rhai::serde::from_dynamic::<serde_json::Value>(&content)
+                .unwrap_or_else(|_| serde_json::Value::String(content.to_string()));
+            OUTBOX.with_borrow_mut(|outbox| {
+                outbox.push(RuleMessage {
+                    topic: topic.to_string(),
+                    content,
+                })
+            });
+        });
+
+        Self {
+            storage,
+            engine,
+            refresh: Duration::from_secs(config.refresh_secs),
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Compile a rule's script, failing with [`LocaiError::Rule`] if it
+    /// doesn't parse
+    pub fn check(&self, rule: &Rule) -> Result<()> {
+        rule.validate()?;
+        self.compile(rule).map(|_| ())
+    }
+
+    /// A rule by name
+    pub async fn get(&self, name: &str) -> Result<Option<Rule>> {
+        Ok(self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|rule| rule.name == name)
+            .max_by_key(|rule| rule.updated_at))
+    }
+
+    /// A rule by the ID of its memory
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<Rule>> {
+        let memory = self
+            .storage
+            .get_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+        Ok(memory.as_ref().and_then(Rule::from_memory))
+    }
+
+    /// Every rule, by name
+    pub async fn list(&self) -> Result<Vec<Rule>> {
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut all = self.all().await?;
+        all.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| b.updated_at.cmp(&a.updated_at))
+        });
+        for rule in all {
+            if rules.last().is_none_or(|last| last.name != rule.name) {
+                rules.push(rule);
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Rewrite a rule's memory with a new script and settings
+    pub async fn replace(&self, existing: &Rule, rule: &Rule) -> Result<Rule> {
+        let mut memory = self
+            .storage
+            .get_memory(&existing.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+            .ok_or_else(|| LocaiError::Rule(format!("Rule {} no longer exists", existing.id)))?;
+        let updated = Rule {
+            id: existing.id.clone(),
+            name: existing.name.clone(),
+            updated_at: Utc::now(),
+            ..rule.clone()
+        };
+        updated.write_state(&mut memory);
+        self.storage
+            .update_memory(memory)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to save rule: {}", e)))?;
+        self.invalidate();
+        Ok(updated)
+    }
+
+    /// Remove a rule, returning whether there was one
+    pub async fn remove(&self, name: &str) -> Result<bool> {
+        let entries: Vec<Rule> = self
+            .all()
+            .await?
+            .into_iter()
+            .filter(|rule| rule.name == name)
+            .collect();
+        for rule in &entries {
+            self.storage
+                .delete_memory(&rule.id)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to delete rule: {}", e)))?;
+        }
+        self.invalidate();
+        Ok(!entries.is_empty())
+    }
+
+    /// Read rules from storage on the next event instead of the cache
+    pub fn invalidate(&self) {
+        *self.cache.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Run the enabled rules for `event` on `memory`, in name order
+    ///
+    /// Nothing is saved or sent; the outcome says what the rules did.
+    pub async fn evaluate(&self, event: RuleEvent, memory: &Memory) -> Result<RuleOutcome> {
+        if !runs_rules(memory) {
+            return Ok(RuleOutcome::default());
+        }
+        let rules = self.compiled().await?;
+        let rules: Vec<&CompiledRule> = rules
+            .iter()
+            .filter(|compiled| compiled.rule.event == event)
+            .collect();
+        Ok(self.run(event, memory, &rules))
+    }
+
+    /// Run one rule on `memory` as if `event` had happened, whether or not
+    /// the rule is enabled or runs on that event
+    pub fn dry_run(&self, rule: &Rule, event: RuleEvent, memory: &Memory) -> Result<RuleOutcome> {
+        rule.validate()?;
+        let compiled = CompiledRule {
+            rule: rule.clone(),
+            ast: self.compile(rule)?,
+        };
+        Ok(self.run(event, memory, &[&compiled]))
+    }
+
+    fn run(&self, event: RuleEvent, memory: &Memory, rules: &[&CompiledRule]) -> RuleOutcome {
+        let mut outcome = RuleOutcome::default();
+        let mut current = memory.clone();
+        for compiled in rules {
+            let name = &compiled.rule.name;
+            match self.run_script(&compiled.ast, event, &current) {
+                Ok((changed, messages)) => {
+                    outcome.fired.push(name.clone());
+                    outcome.messages.extend(messages);
+                    current = changed;
+                }
+                Err(error) => {
+                    tracing::warn!("Rule '{}' failed on {}: {}", name, event, error);
+                    outcome.failures.push(RuleFailure {
+                        rule: name.clone(),
+                        error,
+                    });
+                }
+            }
+        }
+        if event.saves_changes() && current != *memory {
+            outcome.memory = Some(current);
+        }
+        outcome
+    }
+
+    /// Run a script on the memory, returning the memory as the script left
+    /// it and the messages it sent
+    fn run_script(
+        &self,
+        ast: &AST,
+        event: RuleEvent,
+        memory: &Memory,
+    ) -> std::result::Result<(Memory, Vec<RuleMessage>), String> {
+        let view = rhai::serde::to_dynamic(memory_view(memory)).map_err(|e| e.to_string())?;
+        let mut scope = Scope::new();
+        scope.push_dynamic("memory", view);
+        scope.push_constant("event", event.as_str().to_string());
+
+        OUTBOX.with_borrow_mut(Vec::clear);
+        let result = self.engine.run_ast_with_scope(&mut scope, ast);
+        let messages = OUTBOX.with_borrow_mut(std::mem::take);
+        result.map_err(|e| e.to_string())?;
+
+        let view = scope
+            .get_value::<Dynamic>("memory")
+            .ok_or_else(|| "The script removed `memory`".to_string())?;
+        let view: serde_json::Value =
+            rhai::serde::from_dynamic(&view).map_err(|e| e.to_string())?;
+        Ok((apply_view(memory, &view)?, messages))
+    }
+
+    fn compile(&self, rule: &Rule) -> Result<AST> {
+        self.engine
+            .compile(&rule.script)
+            .map_err(|e| LocaiError::Rule(format!("Rule '{}' doesn't compile: {}", rule.name, e)))
+    }
+
+    /// The enabled rules, compiled, from the cache while it's fresh
+    async fn compiled(&self) -> Result<Arc<Vec<CompiledRule>>> {
+        if let Some(rules) = self.cached() {
+            return Ok(rules);
+        }
+
+        let mut compiled = Vec::new();
+        for rule in self.list().await? {
+            if !rule.enabled {
+                continue;
+            }
+            match self.compile(&rule) {
+                Ok(ast) => compiled.push(CompiledRule { rule, ast }),
+                Err(e) => tracing::warn!("Skipping rule: {}", e),
+            }
+        }
+        let compiled = Arc::new(compiled);
+        *self.cache.write().unwrap_or_else(|e| e.into_inner()) = Some(CachedRules {
+            rules: Arc::clone(&compiled),
+            loaded_at: Instant::now(),
+        });
+        Ok(compiled)
+    }
+
+    fn cached(&self) -> Option<Arc<Vec<CompiledRule>>> {
+        let cache = self.cache.read().unwrap_or_else(|e| e.into_inner());
+        cache
+            .as_ref()
+            .filter(|cached| cached.loaded_at.elapsed() < self.refresh)
+            .map(|cached| Arc::clone(&cached.rules))
+    }
+
+    async fn all(&self) -> Result<Vec<Rule>> {
+        let filter = MemoryFilter {
+            memory_type: Some(RULE_MEMORY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list rules: {}", e)))?;
+        Ok(memories.iter().filter_map(Rule::from_memory).collect())
+    }
+}
+
+/// The memory as scripts see it
+fn memory_view(memory: &Memory) -> serde_json::Value {
+    let properties = match &memory.properties {
+        serde_json::Value::Null => serde_json::json!({}),
+        properties => properties.clone(),
+    };
+    serde_json::json!({
+        "id": memory.id,
+        "content": memory.content,
+        "memory_type": memory.memory_type.to_string(),
+        "priority": priority_name(memory.priority),
+        "tags": memory.tags,
+        "source": memory.source,
+        "properties": properties,
+    })
+}
+
+/// The memory with the changes a script made to its view
+fn apply_view(memory: &Memory, view: &serde_json::Value) -> std::result::Result<Memory, String> {
+    let mut changed = memory.clone();
+    if let Some(priority) = view.get("priority").and_then(|p| p.as_str()) {
+        changed.priority = parse_priority(priority)?;
+    }
+    if let Some(tags) = view.get("tags").and_then(|t| t.as_array()) {
+        let mut seen = HashSet::new();
+        changed.tags = tags
+            .iter()
+            .filter_map(|tag| tag.as_str())
+            .filter(|tag| seen.insert(tag.to_string()))
+            .map(str::to_string)
+            .collect();
+    }
+    if let Some(properties) = view.get("properties") {
+        let unset = memory.properties.is_null()
+            && properties
+                .as_object()
+                .is_some_and(|object| object.is_empty());
+        if !unset {
+            changed.properties = properties.clone();
+        }
+    }
+    Ok(changed)
+}
+
+fn priority_name(priority: MemoryPriority) -> &'static str {
+    match priority {
+        MemoryPriority::Low => "low",
+        MemoryPriority::Normal => "normal",
+        MemoryPriority::High => "high",
+        MemoryPriority::Critical => "critical",
+    }
+}
+
+/// Runs rules on the events of the memory manager's storage
+#[derive(Debug)]
+pub(crate) struct RuleHook {
+    manager: Weak<MemoryManager>,
+}
+
+impl RuleHook {
+    pub(crate) fn new(manager: &Arc<MemoryManager>) -> Self {
+        Self {
+            manager: Arc::downgrade(manager),
+        }
+    }
+
+    async fn run(&self, event: RuleEvent, memory: &Memory) -> HookResult {
+        if let Some(manager) = self.manager.upgrade()
+            && let Err(e) = manager.run_rules(event, memory).await
+        {
+            tracing::warn!("Failed to run rules on {} of {}: {}", event, memory.id, e);
+        }
+        HookResult::Continue
+    }
+}
+
+#[async_trait]
+impl MemoryHook for RuleHook {
+    async fn on_memory_created(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Created, memory).await
+    }
+
+    async fn on_memory_accessed(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Accessed, memory).await
+    }
+
+    async fn on_memory_updated(&self, _old: &Memory, new: &Memory) -> HookResult {
+        self.run(RuleEvent::Updated, new).await
+    }
+
+    async fn before_memory_deleted(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Deleted, memory).await
+    }
+
+    async fn on_memory_reminder(&self, memory: &Memory) -> HookResult {
+        self.run(RuleEvent::Reminder, memory).await
+    }
+
+    fn name(&self) -> &str {
+        "rules"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_round_trips_unchanged() {
+        let mut memory = Memory::new("m1".to_string(), "x".to_string(), MemoryType::Fact);
+        memory.tags = vec!["a".to_string()];
+        let view = memory_view(&memory);
+        assert_eq!(apply_view(&memory, &view).unwrap(), memory);
+    }
+
+    #[test]
+    fn test_unknown_priority_is_an_error() {
+        let memory = Memory::new("m1".to_string(), "x".to_string(), MemoryType::Fact);
+        let mut view = memory_view(&memory);
+        view["priority"] = "urgent".into();
+        assert!(apply_view(&memory, &view).is_err());
+    }
+}
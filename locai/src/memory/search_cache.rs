@@ -0,0 +1,307 @@
+//! Search result caching
+//!
+//! [`SearchCache`] keeps the results of recent searches by a hash of the
+//! query and its options, so the identical searches agents tend to repeat are
+//! answered from memory. Entries are dropped when a memory change seen
+//! through the store's live queries could affect them: a change to a memory
+//! in the results, or to one matching the search's type and tag filters.
+//! Entries also expire after a configured time, since live queries are
+//! asynchronous and can lag a write by a moment.
+
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OnceCell, broadcast, broadcast::error::RecvError};
+
+use crate::config::SearchCacheConfig;
+use crate::memory::SearchMode;
+use crate::memory::utils::convert_db_event_to_memory;
+use crate::models::{Memory, SparseVector};
+use crate::storage::filters::SemanticSearchFilter;
+use crate::storage::models::SearchResult;
+use crate::storage::shared_storage::live_query::DbEvent;
+use crate::storage::traits::GraphStore;
+
+/// Hit and miss counts for the search cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchCacheStats {
+    /// Searches answered from the cache
+    pub hits: u64,
+
+    /// Searches that went to storage
+    pub misses: u64,
+
+    /// Entries dropped because a memory changed
+    pub invalidations: u64,
+
+    /// Searches currently cached
+    pub entries: usize,
+}
+
+/// The identity of a search, before hashing
+pub(crate) struct SearchKey<'a> {
+    pub query: &'a str,
+    pub embedding: Option<&'a [f32]>,
+    pub sparse: Option<&'a SparseVector>,
+    pub limit: Option<usize>,
+    pub filter: Option<&'a SemanticSearchFilter>,
+    pub mode: SearchMode,
+}
+
+impl SearchKey<'_> {
+    fn hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.query.hash(&mut hasher);
+        self.embedding
+            .map(|embedding| embedding.iter().map(|x| x.to_bits()).collect::<Vec<_>>())
+            .hash(&mut hasher);
+        self.sparse
+            .map(|sparse| {
+                let values: Vec<u32> = sparse.values.iter().map(|x| x.to_bits()).collect();
+                (&sparse.indices, values)
+            })
+            .hash(&mut hasher);
+        self.limit.hash(&mut hasher);
+        self.filter
+            .map(|filter| serde_json::to_string(filter).unwrap_or_default())
+            .hash(&mut hasher);
+        format!("{:?}", self.mode).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A cached search and what it depends on
+#[derive(Debug)]
+struct CachedSearch {
+    results: Vec<SearchResult>,
+    /// IDs of the memories in `results`
+    ids: HashSet<String>,
+    memory_type: Option<String>,
+    tags: Option<Vec<String>>,
+    cached_at: Instant,
+}
+
+impl CachedSearch {
+    /// Whether a change to `memory` could change this search's results
+    fn affected_by(&self, memory: &Memory) -> bool {
+        self.ids.contains(&memory.id)
+            || (self
+                .memory_type
+                .as_ref()
+                .is_none_or(|memory_type| *memory_type == memory.memory_type.to_string())
+                && self
+                    .tags
+                    .as_ref()
+                    .is_none_or(|tags| tags.iter().any(|tag| memory.tags.contains(tag))))
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    entries: Mutex<LruCache<u64, CachedSearch>>,
+    ttl: Option<Duration>,
+    /// Whether change events are arriving; without them nothing is served
+    live: AtomicBool,
+    /// Bumped on every invalidation, so a search that raced a change isn't
+    /// cached
+    generation: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+impl Shared {
+    fn invalidate(&self, memory: &Memory) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let stale: Vec<u64> = entries
+            .iter()
+            .filter(|(_, entry)| entry.affected_by(memory))
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &stale {
+            entries.pop(key);
+        }
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.invalidations
+            .fetch_add(stale.len() as u64, Ordering::Relaxed);
+    }
+
+    fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        self.invalidations
+            .fetch_add(entries.len() as u64, Ordering::Relaxed);
+        entries.clear();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// Caches search results, invalidated by live-query change events
+#[derive(Debug)]
+pub struct SearchCache {
+    shared: Arc<Shared>,
+    storage: Arc<dyn GraphStore>,
+    listening: OnceCell<()>,
+}
+
+impl SearchCache {
+    pub fn new(config: &SearchCacheConfig, storage: Arc<dyn GraphStore>) -> Self {
+        let capacity = NonZeroUsize::new(config.capacity.max(1)).expect("non-zero capacity");
+        Self {
+            shared: Arc::new(Shared {
+                entries: Mutex::new(LruCache::new(capacity)),
+                ttl: (config.ttl_secs > 0).then(|| Duration::from_secs(config.ttl_secs)),
+                live: AtomicBool::new(false),
+                generation: AtomicU64::new(0),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                invalidations: AtomicU64::new(0),
+            }),
+            storage,
+            listening: OnceCell::new(),
+        }
+    }
+
+    /// Cached results for a search, counting a hit or a miss
+    ///
+    /// Always a miss while the store's change events aren't arriving.
+    pub(crate) async fn get(&self, key: &SearchKey<'_>) -> Option<Vec<SearchResult>> {
+        self.listen().await;
+        let shared = &self.shared;
+        let found = if shared.live.load(Ordering::Acquire) {
+            let mut entries = shared.entries.lock().unwrap_or_else(|e| e.into_inner());
+            let hash = key.hash();
+            let expired = entries.peek(&hash).is_some_and(|entry| {
+                shared
+                    .ttl
+                    .is_some_and(|ttl| entry.cached_at.elapsed() > ttl)
+            });
+            if expired {
+                entries.pop(&hash);
+                None
+            } else {
+                entries.get(&hash).map(|entry| entry.results.clone())
+            }
+        } else {
+            None
+        };
+
+        let counter = if found.is_some() {
+            &shared.hits
+        } else {
+            &shared.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Current generation, to pass to [`insert`](Self::insert) for a search
+    /// about to go to storage
+    pub(crate) fn generation(&self) -> u64 {
+        self.shared.generation.load(Ordering::Acquire)
+    }
+
+    /// Keep the results of a search, unless a memory changed since
+    /// `generation` was read
+    pub(crate) fn insert(&self, key: &SearchKey<'_>, results: &[SearchResult], generation: u64) {
+        if !self.shared.live.load(Ordering::Acquire) {
+            return;
+        }
+        let memory_filter = key.filter.and_then(|filter| filter.memory_filter.as_ref());
+        let entry = CachedSearch {
+            results: results.to_vec(),
+            ids: results
+                .iter()
+                .map(|result| result.memory.id.clone())
+                .collect(),
+            memory_type: memory_filter.and_then(|filter| filter.memory_type.clone()),
+            tags: memory_filter.and_then(|filter| filter.tags.clone()),
+            cached_at: Instant::now(),
+        };
+        // Invalidations bump the generation under the same lock
+        let mut entries = self
+            .shared
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if self.generation() == generation {
+            entries.put(key.hash(), entry);
+        }
+    }
+
+    /// Drop every cached search
+    pub fn clear(&self) {
+        self.shared.clear();
+    }
+
+    pub fn stats(&self) -> SearchCacheStats {
+        let shared = &self.shared;
+        SearchCacheStats {
+            hits: shared.hits.load(Ordering::Relaxed),
+            misses: shared.misses.load(Ordering::Relaxed),
+            invalidations: shared.invalidations.load(Ordering::Relaxed),
+            entries: shared
+                .entries
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .len(),
+        }
+    }
+
+    /// Subscribe to the store's change events, once
+    async fn listen(&self) {
+        self.listening
+            .get_or_init(|| async {
+                let receiver = match self.storage.setup_live_queries().await {
+                    Ok(receiver) => receiver.and_then(|receiver| {
+                        receiver.downcast::<broadcast::Receiver<DbEvent>>().ok()
+                    }),
+                    Err(e) => {
+                        tracing::warn!("Search cache disabled, live queries failed: {}", e);
+                        None
+                    }
+                };
+                match receiver {
+                    Some(events) => {
+                        self.shared.live.store(true, Ordering::Release);
+                        tokio::spawn(forward_changes(Arc::downgrade(&self.shared), *events));
+                    }
+                    None => tracing::warn!(
+                        "Search cache disabled, the store has no live queries to invalidate it"
+                    ),
+                }
+            })
+            .await;
+    }
+}
+
+/// Invalidate entries as memory changes arrive, until the cache is dropped
+async fn forward_changes(shared: Weak<Shared>, mut events: broadcast::Receiver<DbEvent>) {
+    loop {
+        let event = events.recv().await;
+        let Some(shared) = shared.upgrade() else {
+            break;
+        };
+        match event {
+            Ok(event) if event.table == "memory" => {
+                if let Some(memory) = convert_db_event_to_memory(&event) {
+                    shared.invalidate(&memory);
+                }
+            }
+            Ok(_) => {}
+            // Changes were missed, so any entry could be stale
+            Err(RecvError::Lagged(_)) => shared.clear(),
+            Err(RecvError::Closed) => {
+                shared.live.store(false, Ordering::Release);
+                shared.clear();
+                break;
+            }
+        }
+    }
+}
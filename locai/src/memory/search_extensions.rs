@@ -3,9 +3,11 @@
 //! This module provides enhanced search capabilities including universal search
 //! across all data types, semantic search, and advanced filtering options.
 
-use crate::models::{Memory, MemoryType};
+use crate::memory::query_expansion::{ExpandedQuery, QueryExpander, QueryExpansionConfig};
+use crate::ml::provider::EmbeddingProvider;
+use crate::models::{Memory, MemoryType, SparseVector};
 use crate::storage::filters::{MemoryFilter, SemanticSearchFilter};
-use crate::storage::models::{MemoryGraph, SearchResult};
+use crate::storage::models::{MemoryGraph, SearchHit, SearchResult};
 use crate::storage::traits::GraphStore;
 use crate::{LocaiError, Result};
 use serde::{Deserialize, Serialize};
@@ -125,6 +127,10 @@ pub struct UniversalSearchOptions {
     pub similarity_threshold: Option<f32>,
     /// Whether to expand results with related data
     pub expand_with_relations: bool,
+    /// Expand the memory query with names of related graph entities
+    pub query_expansion: Option<QueryExpansionConfig>,
+    /// Also search archived (cold storage) memories
+    pub include_archived: bool,
 }
 
 impl Default for UniversalSearchOptions {
@@ -138,6 +144,8 @@ impl Default for UniversalSearchOptions {
             entity_type_filter: None,
             similarity_threshold: None,
             expand_with_relations: true,
+            query_expansion: None,
+            include_archived: false,
         }
     }
 }
@@ -187,16 +195,112 @@ fn reciprocal_rank_fusion(
         .collect()
 }
 
+/// Weighted RRF over any number of ranked result lists
+///
+/// Each list contributes `weight / (k + rank)` per item. Fused scores are
+/// scaled so the best result scores 1.0.
+pub(crate) fn weighted_rank_fusion(
+    lists: Vec<(Vec<SearchResult>, f32)>,
+    k: f32,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut scores: HashMap<String, f32> = HashMap::new();
+    let mut memories: HashMap<String, Memory> = HashMap::new();
+
+    for (results, weight) in lists {
+        for (rank, result) in results.into_iter().enumerate() {
+            let rrf_score = weight / (k + rank as f32 + 1.0);
+            *scores.entry(result.memory.id.clone()).or_insert(0.0) += rrf_score;
+            memories
+                .entry(result.memory.id.clone())
+                .or_insert(result.memory);
+        }
+    }
+
+    let mut scored_items: Vec<(String, f32)> = scores.into_iter().collect();
+    scored_items.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scored_items.truncate(limit);
+
+    let max_score = scored_items.first().map(|(_, s)| *s).unwrap_or(0.0);
+    scored_items
+        .into_iter()
+        .filter_map(|(id, score)| {
+            memories.remove(&id).map(|memory| SearchResult {
+                memory,
+                score: Some(if max_score > 0.0 {
+                    score / max_score
+                } else {
+                    0.0
+                }),
+            })
+        })
+        .collect()
+}
+
+/// RRF over ranked hit lists, keeping the first non-empty snippet per memory
+fn fuse_hits(lists: Vec<Vec<SearchHit>>, k: f32, limit: usize) -> Vec<SearchHit> {
+    let mut fused: HashMap<String, SearchHit> = HashMap::new();
+
+    for hits in lists {
+        for (rank, hit) in hits.into_iter().enumerate() {
+            let rrf_score = 1.0 / (k + rank as f32 + 1.0);
+            let entry = fused.entry(hit.id.clone()).or_insert(SearchHit {
+                score: 0.0,
+                ..hit.clone()
+            });
+            entry.score += rrf_score;
+            if entry.snippet.is_empty() {
+                entry.snippet = hit.snippet;
+            }
+        }
+    }
+
+    let mut hits: Vec<SearchHit> = fused.into_values().collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+    hits.truncate(limit);
+
+    let max_score = hits.first().map(|h| h.score).unwrap_or(0.0);
+    if max_score > 0.0 {
+        for hit in &mut hits {
+            hit.score /= max_score;
+        }
+    }
+    hits
+}
+
 /// Advanced search operations for memories
 #[derive(Debug)]
 pub struct SearchExtensions {
     storage: Arc<dyn GraphStore>,
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
 }
 
 impl SearchExtensions {
     /// Create a new search extensions handler
     pub fn new(storage: Arc<dyn GraphStore>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            embedding_provider: None,
+        }
+    }
+
+    /// Set the provider used to embed queries for vector and hybrid search
+    pub fn set_embedding_provider(&mut self, provider: Option<Arc<dyn EmbeddingProvider>>) {
+        self.embedding_provider = provider;
+    }
+
+    /// Embed the query with the configured provider, if any
+    async fn embed_query(&self, query_text: &str) -> Result<Option<Vec<f32>>> {
+        let Some(provider) = &self.embedding_provider else {
+            return Ok(None);
+        };
+        provider.embed(query_text).await.map(Some).map_err(|e| {
+            LocaiError::ML(format!(
+                "Embedding provider '{}' failed to embed query: {}",
+                provider.name(),
+                e
+            ))
+        })
     }
 
     /// Perform a search for memories using the specified mode.
@@ -219,20 +323,120 @@ impl SearchExtensions {
         filter: Option<SemanticSearchFilter>,
         search_mode: SearchMode,
     ) -> Result<Vec<SearchResult>> {
-        match search_mode {
+        if search_mode != SearchMode::Text
+            && let Some(embedding) = self.embed_query(query_text).await?
+        {
+            return self
+                .search_with_embedding(query_text, Some(&embedding), limit, filter, search_mode)
+                .await;
+        }
+
+        let archived_filter = filter.clone().filter(|f| f.include_archived);
+        let results = match search_mode {
             SearchMode::Text => {
                 // BM25 full-text search using SharedStorage
-                self.text_search(query_text, limit, filter).await
+                self.text_search(query_text, limit, filter).await?
             }
             SearchMode::Vector => {
                 // Vector similarity search (requires embeddings)
-                self.vector_search(query_text, limit, filter).await
+                self.vector_search(query_text, limit, filter).await?
             }
             SearchMode::Hybrid => {
                 // Combine Text and Vector with RRF
-                self.hybrid_search(query_text, limit, filter).await
+                self.hybrid_search(query_text, limit, filter).await?
             }
+        };
+
+        self.merge_archived(results, query_text, None, limit, archived_filter)
+            .await
+    }
+
+    /// Fuse archived matches into active results when a filter is given
+    async fn merge_archived(
+        &self,
+        results: Vec<SearchResult>,
+        query_text: &str,
+        query_embedding: Option<&[f32]>,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(filter) = filter else {
+            return Ok(results);
+        };
+
+        let mut archived = self
+            .storage
+            .search_archived_memories(query_text, query_embedding, limit)
+            .await
+            .map_err(|e| {
+                LocaiError::Storage(format!("Failed to search archived memories: {}", e))
+            })?;
+        if let Some(memory_filter) = &filter.memory_filter {
+            archived.retain(|r| {
+                crate::memory::utils::matches_memory_filter_detailed(&r.memory, memory_filter)
+            });
+        }
+        if archived.is_empty() {
+            return Ok(results);
+        }
+
+        // Scores from the active indexes and the archive scan aren't comparable
+        Ok(weighted_rank_fusion(
+            vec![(results, 1.0), (archived, 1.0)],
+            60.0,
+            limit.unwrap_or(10),
+        ))
+    }
+
+    /// Detect entities in a query and collect names of strongly related entities
+    pub async fn expand_query(
+        &self,
+        query_text: &str,
+        config: &QueryExpansionConfig,
+    ) -> Result<ExpandedQuery> {
+        QueryExpander::new(self.storage.clone(), config.clone())
+            .expand(query_text)
+            .await
+    }
+
+    /// Search with the query expanded by graph neighbors of entities it mentions
+    ///
+    /// BM25 matches all query terms, so appending names to the query would
+    /// narrow results. Instead the original query and each added name are
+    /// searched separately and fused with weighted RRF, the added names
+    /// weighted by `config.expansion_weight`.
+    pub async fn search_with_expansion(
+        &self,
+        query_text: &str,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+        config: &QueryExpansionConfig,
+    ) -> Result<Vec<SearchResult>> {
+        let expanded = self.expand_query(query_text, config).await?;
+        if expanded.added_terms.is_empty() {
+            return self.search(query_text, limit, filter, search_mode).await;
+        }
+
+        tracing::debug!(
+            "Expanded query '{}' with {:?}",
+            query_text,
+            expanded.added_terms
+        );
+
+        let mut lists = vec![(
+            self.search(query_text, limit, filter.clone(), search_mode)
+                .await?,
+            1.0,
+        )];
+        for term in &expanded.added_terms {
+            let results = self
+                .search(term, limit, filter.clone(), search_mode)
+                .await?;
+            lists.push((results, config.expansion_weight));
         }
+
+        Ok(weighted_rank_fusion(lists, 60.0, limit.unwrap_or(10)))
     }
 
     /// Perform a search for memories with optional query embedding (BYOE approach)
@@ -257,20 +461,141 @@ impl SearchExtensions {
         filter: Option<SemanticSearchFilter>,
         search_mode: SearchMode,
     ) -> Result<Vec<SearchResult>> {
-        match search_mode {
+        // Fall back to the configured provider when no embedding was supplied
+        let provided_embedding = if query_embedding.is_none() && search_mode != SearchMode::Text {
+            self.embed_query(query_text).await?
+        } else {
+            None
+        };
+        let query_embedding = query_embedding.or(provided_embedding.as_deref());
+
+        let archived_filter = filter.clone().filter(|f| f.include_archived);
+        let results = match search_mode {
             SearchMode::Text => {
                 // BM25 full-text search - query_embedding is ignored
-                self.text_search(query_text, limit, filter).await
+                self.text_search(query_text, limit, filter).await?
             }
             SearchMode::Vector => {
                 // Vector similarity search with user-provided embedding
                 self.vector_search_with_embedding(query_embedding, limit, filter)
-                    .await
+                    .await?
             }
             SearchMode::Hybrid => {
                 // Combine Text and Vector with RRF using query embedding
                 self.hybrid_search_with_embedding(query_text, query_embedding, limit, filter)
-                    .await
+                    .await?
+            }
+        };
+
+        let archive_embedding = query_embedding.filter(|_| search_mode != SearchMode::Text);
+        self.merge_archived(
+            results,
+            query_text,
+            archive_embedding,
+            limit,
+            archived_filter,
+        )
+        .await
+    }
+
+    /// Search with a sparse query vector as well as, or instead of, a dense one
+    ///
+    /// Sparse matches (see [`SparseVector`]) are fused with RRF into what
+    /// `search_mode` finds on its own: BM25 and vector results in Hybrid mode,
+    /// vector results in Vector mode, or sparse matches alone in Vector mode
+    /// with no query embedding to search by. Text mode ignores the sparse
+    /// vector.
+    pub async fn search_with_sparse(
+        &self,
+        query_text: &str,
+        query_embedding: Option<&[f32]>,
+        sparse_query: &SparseVector,
+        limit: Option<usize>,
+        filter: Option<SemanticSearchFilter>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchResult>> {
+        if search_mode == SearchMode::Text {
+            return self
+                .search_with_embedding(query_text, query_embedding, limit, filter, search_mode)
+                .await;
+        }
+
+        // Fetch extra from each side so overlap doesn't starve the fused list
+        let fetch_limit = limit.unwrap_or(10) * 2;
+        let sparse = self
+            .sparse_search(sparse_query, fetch_limit, filter.clone())
+            .await?;
+        let others = match (search_mode, query_embedding) {
+            (_, Some(embedding)) => {
+                self.search_with_embedding(
+                    query_text,
+                    Some(embedding),
+                    Some(fetch_limit),
+                    filter,
+                    search_mode,
+                )
+                .await?
+            }
+            (SearchMode::Vector, None) if self.embedding_provider.is_none() => Vec::new(),
+            (_, None) => {
+                self.search(query_text, Some(fetch_limit), filter, search_mode)
+                    .await?
+            }
+        };
+
+        Ok(weighted_rank_fusion(
+            vec![(others, 1.0), (sparse, 1.0)],
+            60.0,
+            limit.unwrap_or(10),
+        ))
+    }
+
+    /// Search returning memory IDs, scores and snippets instead of full memories
+    ///
+    /// Hybrid mode fuses BM25 and vector hits with RRF, and falls back to BM25
+    /// alone when there is no query embedding. Filters and archived memories
+    /// need full records, so they're only supported by
+    /// [`search_with_embedding`](Self::search_with_embedding).
+    pub async fn search_hits(
+        &self,
+        query_text: &str,
+        query_embedding: Option<&[f32]>,
+        limit: Option<usize>,
+        search_mode: SearchMode,
+    ) -> Result<Vec<SearchHit>> {
+        let limit = limit.unwrap_or(10);
+        let provided_embedding = if query_embedding.is_none() && search_mode != SearchMode::Text {
+            self.embed_query(query_text).await?
+        } else {
+            None
+        };
+        let query_embedding = query_embedding.or(provided_embedding.as_deref());
+
+        let text_hits = |limit| async move {
+            self.storage
+                .bm25_search_memory_hits(query_text, Some(limit))
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to perform BM25 search: {}", e)))
+        };
+        let vector_hits = |embedding, limit| async move {
+            self.storage
+                .vector_search_memory_hits(embedding, Some(limit))
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to perform vector search: {}", e)))
+        };
+
+        match (search_mode, query_embedding) {
+            (SearchMode::Text, _) | (SearchMode::Hybrid, None) => text_hits(limit).await,
+            (SearchMode::Vector, Some(embedding)) => vector_hits(embedding, limit).await,
+            (SearchMode::Vector, None) => Err(LocaiError::Other(
+                "Vector search requires a query embedding or a configured embedding provider"
+                    .to_string(),
+            )),
+            (SearchMode::Hybrid, Some(embedding)) => {
+                // Fetch extra from each side so overlap doesn't starve the fused list
+                let text = text_hits(limit * 2).await?;
+                let vector = vector_hits(embedding, limit * 2).await?;
+                Ok(fuse_hits(vec![text, vector], 60.0, limit))
             }
         }
     }
@@ -360,6 +685,36 @@ impl SearchExtensions {
             .collect())
     }
 
+    /// Perform sparse vector search
+    async fn sparse_search(
+        &self,
+        sparse_query: &SparseVector,
+        limit: usize,
+        filter: Option<SemanticSearchFilter>,
+    ) -> Result<Vec<SearchResult>> {
+        // Fetch more results to account for filtering
+        let results = self
+            .storage
+            .sparse_search_memories(sparse_query, Some(limit * 3))
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to perform sparse search: {}", e)))?;
+
+        let memory_filter = filter.and_then(|f| f.memory_filter);
+        Ok(results
+            .into_iter()
+            .filter(|(memory, _score)| {
+                memory_filter.as_ref().is_none_or(|memory_filter| {
+                    crate::memory::utils::matches_memory_filter_detailed(memory, memory_filter)
+                })
+            })
+            .take(limit)
+            .map(|(memory, score)| SearchResult {
+                memory,
+                score: Some(score),
+            })
+            .collect())
+    }
+
     /// Perform vector similarity search (requires embeddings)
     async fn vector_search(
         &self,
@@ -636,6 +991,7 @@ impl SearchExtensions {
                 Some(SemanticSearchFilter {
                     memory_filter: None, // No restrictive filters
                     similarity_threshold: None,
+                    include_archived: false,
                 }),
                 SearchMode::Text,
             )
@@ -750,17 +1106,22 @@ impl SearchExtensions {
             filter.memory_type = Some(memory_type.to_string());
         }
 
-        let search_results = self
-            .search(
-                query,
-                limit,
-                Some(SemanticSearchFilter {
-                    memory_filter: Some(filter),
-                    similarity_threshold: options.similarity_threshold,
-                }),
-                SearchMode::Text, // Use BM25 text search
-            )
-            .await?;
+        let search_filter = Some(SemanticSearchFilter {
+            memory_filter: Some(filter),
+            similarity_threshold: options.similarity_threshold,
+            include_archived: options.include_archived,
+        });
+        // Use BM25 text search
+        let search_results = match &options.query_expansion {
+            Some(config) => {
+                self.search_with_expansion(query, limit, search_filter, SearchMode::Text, config)
+                    .await?
+            }
+            None => {
+                self.search(query, limit, search_filter, SearchMode::Text)
+                    .await?
+            }
+        };
 
         // Debug: Log memory search results for problematic query
         if query.contains("nonexistent") {
@@ -0,0 +1,375 @@
+//! Sessions: conversations that summarize themselves as they grow
+//!
+//! A session is a conversation, kept as its turns, each a
+//! [`MemoryType::Conversation`] memory with the turn's place in the
+//! conversation in its `session` property. Once enough turns pile up, the
+//! older ones are folded into a rolling [`SessionSummary`] by a
+//! [`Summarizer`] and demoted: they stay stored and searchable by ID, but
+//! built contexts give the summary and the recent turns in their place.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Memory, MemoryPriority, MemoryType};
+use crate::storage::filters::MemoryFilter;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memory property holding a turn's or summary's place in its session
+pub const SESSION_PROPERTY: &str = "session";
+
+/// The part of a session memory kept in its `session` property
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SessionState {
+    Turn {
+        session: String,
+        index: usize,
+        role: String,
+        #[serde(default)]
+        summarized: bool,
+    },
+    Summary {
+        session: String,
+        through_turn: usize,
+    },
+}
+
+impl SessionState {
+    fn read(memory: &Memory) -> Option<Self> {
+        if memory.memory_type != MemoryType::Conversation {
+            return None;
+        }
+        memory
+            .properties
+            .get(SESSION_PROPERTY)
+            .and_then(|state| serde_json::from_value(state.clone()).ok())
+    }
+
+    fn session(&self) -> &str {
+        match self {
+            Self::Turn { session, .. } | Self::Summary { session, .. } => session,
+        }
+    }
+}
+
+/// One message in a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    /// ID of the turn's memory
+    pub id: String,
+
+    pub session: String,
+
+    /// Position in the session, counting from 1
+    pub index: usize,
+
+    /// Who spoke, such as `user` or `assistant`
+    pub role: String,
+
+    pub content: String,
+
+    pub created_at: DateTime<Utc>,
+
+    /// Whether the turn has been folded into the session's summary
+    #[serde(default)]
+    pub summarized: bool,
+}
+
+impl Turn {
+    pub fn new(
+        session: impl Into<String>,
+        role: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            session: session.into(),
+            index: 1,
+            role: role.into(),
+            content: content.into(),
+            created_at: Utc::now(),
+            summarized: false,
+        }
+    }
+
+    /// The turn as a line of the conversation
+    pub fn render(&self) -> String {
+        format!("{}: {}", self.role, self.content)
+    }
+
+    /// Read a turn from its memory; `None` if the memory isn't a turn
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        match SessionState::read(memory)? {
+            SessionState::Turn {
+                session,
+                index,
+                role,
+                summarized,
+            } => Some(Self {
+                id: memory.id.clone(),
+                session,
+                index,
+                role,
+                content: memory.content.clone(),
+                created_at: memory.created_at,
+                summarized,
+            }),
+            SessionState::Summary { .. } => None,
+        }
+    }
+
+    /// The memory to store for a new turn
+    pub fn to_memory(&self) -> Memory {
+        let mut memory = Memory::new(
+            self.id.clone(),
+            self.content.clone(),
+            MemoryType::Conversation,
+        );
+        memory.created_at = self.created_at;
+        memory.set_property(
+            SESSION_PROPERTY,
+            serde_json::to_value(SessionState::Turn {
+                session: self.session.clone(),
+                index: self.index,
+                role: self.role.clone(),
+                summarized: self.summarized,
+            })
+            .unwrap_or_default(),
+        );
+        memory
+    }
+}
+
+/// What a session's older turns amount to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// ID of the summary's memory
+    pub id: String,
+
+    pub session: String,
+
+    pub content: String,
+
+    /// Index of the last turn folded into the summary
+    pub through_turn: usize,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionSummary {
+    pub fn new(
+        session: impl Into<String>,
+        content: impl Into<String>,
+        through_turn: usize,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            session: session.into(),
+            content: content.into(),
+            through_turn,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Read a summary from its memory; `None` if the memory isn't a summary
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        match SessionState::read(memory)? {
+            SessionState::Summary {
+                session,
+                through_turn,
+            } => Some(Self {
+                id: memory.id.clone(),
+                session,
+                content: memory.content.clone(),
+                through_turn,
+                created_at: memory.created_at,
+            }),
+            SessionState::Turn { .. } => None,
+        }
+    }
+
+    /// The memory to store for a new summary
+    pub fn to_memory(&self) -> Memory {
+        let mut memory = Memory::new(
+            self.id.clone(),
+            self.content.trim().to_string(),
+            MemoryType::Conversation,
+        );
+        memory.created_at = self.created_at;
+        memory.set_property(
+            SESSION_PROPERTY,
+            serde_json::to_value(SessionState::Summary {
+                session: self.session.clone(),
+                through_turn: self.through_turn,
+            })
+            .unwrap_or_default(),
+        );
+        memory
+    }
+}
+
+/// Whether a memory belongs to the session, as a turn or a summary
+pub fn in_session(memory: &Memory, session: &str) -> bool {
+    SessionState::read(memory).is_some_and(|state| state.session() == session)
+}
+
+/// Whether a memory is a turn already folded into its session's summary
+pub fn is_summarized_turn(memory: &Memory) -> bool {
+    matches!(
+        SessionState::read(memory),
+        Some(SessionState::Turn {
+            summarized: true,
+            ..
+        })
+    )
+}
+
+/// Folds a session's older turns into its summary
+#[async_trait]
+pub trait Summarizer: fmt::Debug + Send + Sync {
+    /// Short human-readable name
+    fn name(&self) -> &str;
+
+    /// Summarize `turns`, oldest first, on top of the `previous` summary
+    ///
+    /// The result replaces the previous summary, so it should carry forward
+    /// whatever of it still matters.
+    async fn summarize(&self, previous: Option<&str>, turns: &[Turn]) -> Result<String>;
+}
+
+type SummarizeFn = dyn Fn(Option<&str>, &[Turn]) -> Result<String> + Send + Sync;
+
+/// A [`Summarizer`] backed by a user-provided function
+///
+/// ```rust
+/// use locai::memory::sessions::CallbackSummarizer;
+///
+/// // Keep the first line of every turn
+/// let summarizer = CallbackSummarizer::new("first-lines", |previous, turns| {
+///     let mut lines: Vec<String> = previous.map(str::to_string).into_iter().collect();
+///     for turn in turns {
+///         let first = turn.content.lines().next().unwrap_or_default();
+///         lines.push(format!("{}: {}", turn.role, first));
+///     }
+///     Ok(lines.join("\n"))
+/// });
+/// ```
+#[derive(Clone)]
+pub struct CallbackSummarizer {
+    name: String,
+    callback: Arc<SummarizeFn>,
+}
+
+impl CallbackSummarizer {
+    /// Wrap a summarize function
+    pub fn new<F>(name: impl Into<String>, callback: F) -> Self
+    where
+        F: Fn(Option<&str>, &[Turn]) -> Result<String> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+impl fmt::Debug for CallbackSummarizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackSummarizer")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Summarizer for CallbackSummarizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn summarize(&self, previous: Option<&str>, turns: &[Turn]) -> Result<String> {
+        (self.callback)(previous, turns)
+    }
+}
+
+/// Reads and changes session memories
+#[derive(Debug)]
+pub struct SessionStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl SessionStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// A session's turns, oldest first
+    pub async fn turns(&self, session: &str) -> Result<Vec<Turn>> {
+        let mut turns: Vec<Turn> = self
+            .memories(session)
+            .await?
+            .iter()
+            .filter_map(Turn::from_memory)
+            .collect();
+        turns.sort_by_key(|turn| (turn.index, turn.created_at));
+        Ok(turns)
+    }
+
+    /// A session's latest summary
+    pub async fn summary(&self, session: &str) -> Result<Option<SessionSummary>> {
+        Ok(self
+            .memories(session)
+            .await?
+            .iter()
+            .filter_map(SessionSummary::from_memory)
+            .max_by_key(|summary| (summary.through_turn, summary.created_at)))
+    }
+
+    /// Mark a turn as folded into its session's summary and lower its
+    /// priority
+    pub async fn demote(&self, turn: &Turn) -> Result<()> {
+        let Some(mut memory) = self
+            .storage
+            .get_memory(&turn.id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?
+        else {
+            return Ok(());
+        };
+        let demoted = Turn {
+            summarized: true,
+            ..turn.clone()
+        };
+        if let Some(state) = demoted.to_memory().properties.get(SESSION_PROPERTY) {
+            memory.set_property(SESSION_PROPERTY, state.clone());
+        }
+        memory.priority = MemoryPriority::Low;
+        self.storage
+            .update_memory(memory)
+            .await
+            .map(|_| ())
+            .map_err(|e| LocaiError::Storage(format!("Failed to demote turn: {}", e)))
+    }
+
+    async fn memories(&self, session: &str) -> Result<Vec<Memory>> {
+        let filter = MemoryFilter {
+            memory_type: Some(MemoryType::Conversation.to_string()),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list session: {}", e)))?;
+        Ok(memories
+            .into_iter()
+            .filter(|memory| in_session(memory, session))
+            .collect())
+    }
+}
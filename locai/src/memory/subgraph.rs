@@ -0,0 +1,421 @@
+//! Scored subgraph extraction for prompt context
+//!
+//! [`extract_subgraph`] walks out from a set of seed memories or entities,
+//! scores every node it reaches and keeps the best `max_nodes`. The result
+//! renders as JSON or as one `subject predicate object` triple per line, and
+//! can be pruned further until it fits a token budget, which makes it a
+//! compact block of graph context for retrieval-augmented prompts.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::MemoryPriority;
+use crate::replication::RecordKind;
+use crate::storage::filters::RelationshipFilter;
+use crate::storage::models::Relationship;
+use crate::storage::traits::GraphStore;
+use crate::tokens::{ApproxTokenCounter, TokenCounter};
+use crate::{LocaiError, Result};
+
+/// Relationships read per node while expanding
+const NEIGHBOR_LIMIT: usize = 100;
+
+/// Longest memory excerpt used as a node label
+const LABEL_CHARS: usize = 80;
+
+/// How nodes are ranked when a subgraph is pruned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubgraphScoring {
+    /// Score multiplier per hop away from the nearest seed, from 0.0 to 1.0
+    pub distance_decay: f32,
+
+    /// Weight of a memory's priority, scaled from 0.0 (low) to 1.0
+    /// (critical); entities count as normal priority
+    pub importance_weight: f32,
+
+    /// Weight of a node's share of the subgraph's relationships
+    pub degree_weight: f32,
+}
+
+impl Default for SubgraphScoring {
+    fn default() -> Self {
+        Self {
+            distance_decay: 0.5,
+            importance_weight: 0.3,
+            degree_weight: 0.2,
+        }
+    }
+}
+
+/// How a subgraph is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubgraphFormat {
+    #[default]
+    Json,
+    Triples,
+}
+
+/// A memory or entity in a subgraph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubgraphNode {
+    pub id: String,
+    pub kind: RecordKind,
+
+    /// Entity name or memory excerpt
+    pub label: String,
+
+    /// Entity type or memory type
+    pub node_type: String,
+
+    /// Hops from the nearest seed
+    pub distance: u8,
+
+    pub score: f32,
+}
+
+/// A relationship between two nodes of a subgraph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubgraphEdge {
+    pub id: String,
+    pub relationship_type: String,
+    pub source_id: String,
+    pub target_id: String,
+}
+
+/// A pruned subgraph, nodes ordered by score
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoredSubgraph {
+    pub seed_ids: Vec<String>,
+    pub nodes: Vec<SubgraphNode>,
+    pub edges: Vec<SubgraphEdge>,
+}
+
+impl ScoredSubgraph {
+    /// Render as pretty JSON or as triples
+    pub fn render(&self, format: SubgraphFormat) -> String {
+        match format {
+            SubgraphFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+            SubgraphFormat::Triples => self.to_triples(),
+        }
+    }
+
+    /// One `subject predicate object` line per relationship, naming nodes by
+    /// their labels, followed by the nodes no relationship touches
+    pub fn to_triples(&self) -> String {
+        let labels: HashMap<&str, &str> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node.label.as_str()))
+            .collect();
+        let mut lines: Vec<String> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                format!(
+                    "{} {} {}",
+                    labels[edge.source_id.as_str()],
+                    edge.relationship_type,
+                    labels[edge.target_id.as_str()]
+                )
+            })
+            .collect();
+
+        let connected: HashSet<&str> = self
+            .edges
+            .iter()
+            .flat_map(|edge| [edge.source_id.as_str(), edge.target_id.as_str()])
+            .collect();
+        lines.extend(
+            self.nodes
+                .iter()
+                .filter(|node| !connected.contains(node.id.as_str()))
+                .map(|node| format!("{} is_a {}", node.label, node.node_type)),
+        );
+        lines.join("\n")
+    }
+
+    /// Approximate tokens taken by the rendered subgraph
+    pub fn estimated_tokens(&self, format: SubgraphFormat) -> usize {
+        self.count_tokens(format, &ApproxTokenCounter::default())
+    }
+
+    /// Tokens taken by the rendered subgraph, as `counter` counts them
+    pub fn count_tokens(&self, format: SubgraphFormat, counter: &dyn TokenCounter) -> usize {
+        counter.count(&self.render(format))
+    }
+
+    /// Drop the lowest scoring nodes, and their relationships, until the
+    /// rendered subgraph fits in about `max_tokens`
+    ///
+    /// Seeds are dropped last.
+    pub fn fit_to_budget(self, max_tokens: usize, format: SubgraphFormat) -> Self {
+        self.fit_to_budget_with(max_tokens, format, &ApproxTokenCounter::default())
+    }
+
+    /// Drop the lowest scoring nodes, and their relationships, until the
+    /// rendered subgraph fits in `max_tokens` as `counter` counts them
+    ///
+    /// Seeds are dropped last.
+    pub fn fit_to_budget_with(
+        mut self,
+        max_tokens: usize,
+        format: SubgraphFormat,
+        counter: &dyn TokenCounter,
+    ) -> Self {
+        while !self.nodes.is_empty() && self.count_tokens(format, counter) > max_tokens {
+            let position = self
+                .nodes
+                .iter()
+                .rposition(|node| !self.seed_ids.contains(&node.id))
+                .unwrap_or(self.nodes.len() - 1);
+            let removed = self.nodes.remove(position);
+            self.edges
+                .retain(|edge| edge.source_id != removed.id && edge.target_id != removed.id);
+        }
+        self
+    }
+}
+
+/// A node reached during the walk, before scoring
+struct Reached {
+    kind: RecordKind,
+    label: String,
+    node_type: String,
+    importance: f32,
+    distance: u8,
+}
+
+/// Walk `depth` hops out from `seed_ids` and keep the `max_nodes` best scored
+/// nodes and the relationships between them
+///
+/// Seeds that are neither a memory nor an entity are skipped.
+pub async fn extract_subgraph(
+    storage: &Arc<dyn GraphStore>,
+    seed_ids: &[String],
+    depth: u8,
+    max_nodes: usize,
+    scoring: &SubgraphScoring,
+) -> Result<ScoredSubgraph> {
+    let mut reached: HashMap<String, Reached> = HashMap::new();
+    let mut relationships: HashMap<String, Relationship> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for seed_id in seed_ids {
+        if reached.contains_key(seed_id) {
+            continue;
+        }
+        if let Some(node) = resolve(storage, seed_id, 0).await? {
+            reached.insert(seed_id.clone(), node);
+            queue.push_back(seed_id.clone());
+        }
+    }
+
+    while let Some(id) = queue.pop_front() {
+        let distance = reached[&id].distance;
+        if distance >= depth {
+            continue;
+        }
+
+        for relationship in neighbors(storage, &id).await? {
+            let other_id = if relationship.source_id == id {
+                relationship.target_id.clone()
+            } else {
+                relationship.source_id.clone()
+            };
+            if !reached.contains_key(&other_id) {
+                let Some(node) = resolve(storage, &other_id, distance + 1).await? else {
+                    continue;
+                };
+                reached.insert(other_id.clone(), node);
+                queue.push_back(other_id);
+            }
+            relationships.insert(relationship.id.clone(), relationship);
+        }
+    }
+
+    // Degree within the reached subgraph, as a share of the busiest node's
+    let mut degrees: HashMap<&str, usize> = HashMap::new();
+    for relationship in relationships.values() {
+        *degrees.entry(relationship.source_id.as_str()).or_default() += 1;
+        *degrees.entry(relationship.target_id.as_str()).or_default() += 1;
+    }
+    let max_degree = degrees.values().copied().max().unwrap_or(0).max(1) as f32;
+
+    let mut nodes: Vec<SubgraphNode> = reached
+        .iter()
+        .map(|(id, node)| {
+            let degree = degrees.get(id.as_str()).copied().unwrap_or(0) as f32 / max_degree;
+            let proximity = scoring.distance_decay.powi(node.distance as i32);
+            SubgraphNode {
+                id: id.clone(),
+                kind: node.kind,
+                label: node.label.clone(),
+                node_type: node.node_type.clone(),
+                distance: node.distance,
+                score: proximity
+                    + scoring.importance_weight * node.importance
+                    + scoring.degree_weight * degree,
+            }
+        })
+        .collect();
+    nodes.sort_by(|a, b| {
+        a.distance
+            .min(1)
+            .cmp(&b.distance.min(1))
+            .then(b.score.total_cmp(&a.score))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    nodes.truncate(max_nodes);
+
+    let kept: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+    let mut edges: Vec<SubgraphEdge> = relationships
+        .into_values()
+        .filter(|relationship| {
+            kept.contains(relationship.source_id.as_str())
+                && kept.contains(relationship.target_id.as_str())
+        })
+        .map(|relationship| SubgraphEdge {
+            id: relationship.id,
+            relationship_type: relationship.relationship_type,
+            source_id: relationship.source_id,
+            target_id: relationship.target_id,
+        })
+        .collect();
+    edges.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let seed_ids = seed_ids
+        .iter()
+        .filter(|id| kept.contains(id.as_str()))
+        .cloned()
+        .collect();
+    Ok(ScoredSubgraph {
+        seed_ids,
+        nodes,
+        edges,
+    })
+}
+
+/// Relationships in which `id` is the source or the target
+pub(crate) async fn neighbors(
+    storage: &Arc<dyn GraphStore>,
+    id: &str,
+) -> Result<Vec<Relationship>> {
+    let mut relationships = Vec::new();
+    for filter in [
+        RelationshipFilter {
+            source_id: Some(id.to_string()),
+            ..Default::default()
+        },
+        RelationshipFilter {
+            target_id: Some(id.to_string()),
+            ..Default::default()
+        },
+    ] {
+        relationships.extend(
+            storage
+                .list_relationships(Some(filter), Some(NEIGHBOR_LIMIT), None)
+                .await
+                .map_err(|e| LocaiError::Storage(format!("Failed to list relationships: {}", e)))?,
+        );
+    }
+    Ok(relationships)
+}
+
+/// Look `id` up as a memory, then as an entity
+async fn resolve(storage: &Arc<dyn GraphStore>, id: &str, distance: u8) -> Result<Option<Reached>> {
+    let memory = storage
+        .get_memory(id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))?;
+    if let Some(memory) = memory {
+        return Ok(Some(Reached {
+            kind: RecordKind::Memory,
+            label: excerpt(&memory.content),
+            node_type: memory.memory_type.to_string(),
+            importance: memory.priority as i32 as f32 / MemoryPriority::Critical as i32 as f32,
+            distance,
+        }));
+    }
+
+    let entity = storage
+        .get_entity(id)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to get entity: {}", e)))?;
+    Ok(entity.map(|entity| Reached {
+        kind: RecordKind::Entity,
+        label: entity
+            .properties
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or(&entity.id)
+            .to_string(),
+        node_type: entity.entity_type,
+        importance: MemoryPriority::Normal as i32 as f32 / MemoryPriority::Critical as i32 as f32,
+        distance,
+    }))
+}
+
+fn excerpt(content: &str) -> String {
+    let content = content.trim();
+    if content.chars().count() <= LABEL_CHARS {
+        return content.to_string();
+    }
+    let cut: String = content.chars().take(LABEL_CHARS).collect();
+    format!("{}...", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, label: &str, score: f32) -> SubgraphNode {
+        SubgraphNode {
+            id: id.to_string(),
+            kind: RecordKind::Entity,
+            label: label.to_string(),
+            node_type: "person".to_string(),
+            distance: 1,
+            score,
+        }
+    }
+
+    fn subgraph() -> ScoredSubgraph {
+        ScoredSubgraph {
+            seed_ids: vec!["alice".to_string()],
+            nodes: vec![
+                node("alice", "Alice", 1.0),
+                node("acme", "Acme", 0.6),
+                node("bob", "Bob", 0.2),
+            ],
+            edges: vec![SubgraphEdge {
+                id: "r1".to_string(),
+                relationship_type: "works_at".to_string(),
+                source_id: "alice".to_string(),
+                target_id: "acme".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_triples() {
+        assert_eq!(
+            subgraph().to_triples(),
+            "Alice works_at Acme\nBob is_a person"
+        );
+    }
+
+    #[test]
+    fn test_fit_to_budget_drops_lowest_scores_first() {
+        let fitted = subgraph().fit_to_budget(5, SubgraphFormat::Triples);
+        assert_eq!(fitted.nodes.len(), 2);
+        assert_eq!(fitted.to_triples(), "Alice works_at Acme");
+
+        let fitted = subgraph().fit_to_budget(1, SubgraphFormat::Triples);
+        assert!(fitted.nodes.is_empty());
+        assert!(fitted.edges.is_empty());
+    }
+}
@@ -0,0 +1,500 @@
+//! Task memories
+//!
+//! A task is a memory of type [`MemoryType::Task`] that moves through a small
+//! state machine:
+//!
+//! ```text
+//! open ⇄ in_progress
+//!   │         │
+//!   └──► done / cancelled ──► open (reopened)
+//! ```
+//!
+//! Its status, assignee and due date live in the memory's `task` property, so
+//! tasks are searched, versioned and shared like any other memory. A memory
+//! stored with type `task` but no such property is an open, unassigned task.
+//!
+//! Dependencies are `depends_on` relationships from a task to the tasks it
+//! waits on. A task can't be started or completed while any of them is still
+//! open or in progress.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Memory, MemoryType};
+use crate::storage::filters::{MemoryFilter, RelationshipFilter};
+use crate::storage::models::Relationship;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memory property holding a task's state
+pub const TASK_PROPERTY: &str = "task";
+
+/// Relationship type from a task to a task it waits on
+pub const DEPENDS_ON: &str = "depends_on";
+
+/// Where a task is in its life
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    #[default]
+    Open,
+    InProgress,
+    Done,
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// Whether the task is finished, one way or the other
+    pub fn is_closed(self) -> bool {
+        matches!(self, Self::Done | Self::Cancelled)
+    }
+
+    /// Whether a task can move from this status to `next`
+    pub fn can_become(self, next: TaskStatus) -> bool {
+        use TaskStatus::*;
+        matches!(
+            (self, next),
+            (Open, InProgress | Done | Cancelled)
+                | (InProgress, Open | Done | Cancelled)
+                | (Done | Cancelled, Open)
+        )
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open => write!(f, "open"),
+            Self::InProgress => write!(f, "in_progress"),
+            Self::Done => write!(f, "done"),
+            Self::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl FromStr for TaskStatus {
+    type Err = LocaiError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "open" => Ok(Self::Open),
+            "in_progress" => Ok(Self::InProgress),
+            "done" => Ok(Self::Done),
+            "cancelled" | "canceled" => Ok(Self::Cancelled),
+            other => Err(LocaiError::Task(format!(
+                "Unknown task status '{}'; expected open, in_progress, done or cancelled",
+                other
+            ))),
+        }
+    }
+}
+
+/// The part of a task kept in its memory's `task` property
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TaskState {
+    #[serde(default)]
+    status: TaskStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    closed_at: Option<DateTime<Utc>>,
+}
+
+/// A task memory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Task {
+    /// ID of the task's memory
+    pub id: String,
+
+    /// What needs doing; the memory's content
+    pub title: String,
+
+    pub status: TaskStatus,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>,
+
+    /// When the task was last marked done or cancelled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Task {
+    /// An open, unassigned task
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title: title.into(),
+            status: TaskStatus::Open,
+            assignee: None,
+            due_at: None,
+            closed_at: None,
+            tags: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn assigned_to(mut self, assignee: impl Into<String>) -> Self {
+        self.assignee = Some(assignee.into());
+        self
+    }
+
+    pub fn due(mut self, due_at: DateTime<Utc>) -> Self {
+        self.due_at = Some(due_at);
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Whether the task is past its due date and not yet closed
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        !self.status.is_closed() && self.due_at.is_some_and(|due_at| due_at < now)
+    }
+
+    /// Read a task from its memory; `None` if the memory isn't a task
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        if memory.memory_type != MemoryType::Task {
+            return None;
+        }
+        let state: TaskState = memory
+            .properties
+            .get(TASK_PROPERTY)
+            .and_then(|state| serde_json::from_value(state.clone()).ok())
+            .unwrap_or_default();
+        Some(Self {
+            id: memory.id.clone(),
+            title: memory.content.clone(),
+            status: state.status,
+            assignee: state.assignee,
+            due_at: state.due_at,
+            closed_at: state.closed_at,
+            tags: memory.tags.clone(),
+            created_at: memory.created_at,
+        })
+    }
+
+    /// The memory to store for a new task
+    pub fn to_memory(&self) -> Memory {
+        let mut memory = Memory::new(self.id.clone(), self.title.clone(), MemoryType::Task);
+        memory.tags = self.tags.clone();
+        memory.created_at = self.created_at;
+        self.write_state(&mut memory);
+        memory
+    }
+
+    fn write_state(&self, memory: &mut Memory) {
+        let state = TaskState {
+            status: self.status,
+            assignee: self.assignee.clone(),
+            due_at: self.due_at,
+            closed_at: self.closed_at,
+        };
+        memory.set_property(
+            TASK_PROPERTY,
+            serde_json::to_value(state).unwrap_or_default(),
+        );
+    }
+}
+
+/// Which tasks to list
+#[derive(Debug, Clone, Default)]
+pub struct TaskQuery {
+    /// Statuses to include; empty for any
+    pub statuses: Vec<TaskStatus>,
+
+    pub assignee: Option<String>,
+
+    /// Only tasks due before this time
+    pub due_before: Option<DateTime<Utc>>,
+}
+
+impl TaskQuery {
+    /// Tasks that are open or in progress
+    pub fn open() -> Self {
+        Self {
+            statuses: vec![TaskStatus::Open, TaskStatus::InProgress],
+            ..Default::default()
+        }
+    }
+
+    pub fn assigned_to(mut self, assignee: impl Into<String>) -> Self {
+        self.assignee = Some(assignee.into());
+        self
+    }
+
+    pub fn due_before(mut self, due_before: DateTime<Utc>) -> Self {
+        self.due_before = Some(due_before);
+        self
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&task.status))
+            && self
+                .assignee
+                .as_ref()
+                .is_none_or(|assignee| task.assignee.as_ref() == Some(assignee))
+            && self
+                .due_before
+                .is_none_or(|before| task.due_at.is_some_and(|due_at| due_at < before))
+    }
+}
+
+/// Reads and changes task memories
+#[derive(Debug)]
+pub struct TaskStore {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl TaskStore {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// A task by ID; `None` if there is no such memory or it isn't a task
+    pub async fn get(&self, id: &str) -> Result<Option<Task>> {
+        Ok(self
+            .get_memory(id)
+            .await?
+            .as_ref()
+            .and_then(Task::from_memory))
+    }
+
+    /// Tasks matching `query`, soonest due first and undated tasks last
+    pub async fn list(&self, query: &TaskQuery) -> Result<Vec<Task>> {
+        let filter = MemoryFilter {
+            memory_type: Some(MemoryType::Task.to_string()),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list tasks: {}", e)))?;
+        let mut tasks: Vec<Task> = memories
+            .iter()
+            .filter_map(Task::from_memory)
+            .filter(|task| query.matches(task))
+            .collect();
+        tasks.sort_by_key(|task| (task.due_at.is_none(), task.due_at, task.created_at));
+        Ok(tasks)
+    }
+
+    /// Move a task to `status`
+    ///
+    /// Fails if the task can't go from its current status to `status`, or if
+    /// it is being started or completed while a dependency is unfinished.
+    /// Setting the status a task already has changes nothing. Returns `None`
+    /// if there is no such task.
+    pub async fn set_status(&self, id: &str, status: TaskStatus) -> Result<Option<Task>> {
+        let Some((mut memory, mut task)) = self.get_task_memory(id).await? else {
+            return Ok(None);
+        };
+        if task.status == status {
+            return Ok(Some(task));
+        }
+        if !task.status.can_become(status) {
+            return Err(LocaiError::Task(format!(
+                "Task {} is {} and can't become {}",
+                id, task.status, status
+            )));
+        }
+        if matches!(status, TaskStatus::InProgress | TaskStatus::Done) {
+            let blockers = self.blockers(id).await?;
+            if !blockers.is_empty() {
+                let ids: Vec<&str> = blockers.iter().map(|t| t.id.as_str()).collect();
+                return Err(LocaiError::Task(format!(
+                    "Task {} is waiting on {}",
+                    id,
+                    ids.join(", ")
+                )));
+            }
+        }
+
+        task.status = status;
+        task.closed_at = status.is_closed().then(Utc::now);
+        self.save(&mut memory, &task).await?;
+        Ok(Some(task))
+    }
+
+    /// Assign a task, or unassign it with `None`
+    pub async fn assign(&self, id: &str, assignee: Option<String>) -> Result<Option<Task>> {
+        let Some((mut memory, mut task)) = self.get_task_memory(id).await? else {
+            return Ok(None);
+        };
+        task.assignee = assignee;
+        self.save(&mut memory, &task).await?;
+        Ok(Some(task))
+    }
+
+    /// Set or clear a task's due date
+    pub async fn set_due(&self, id: &str, due_at: Option<DateTime<Utc>>) -> Result<Option<Task>> {
+        let Some((mut memory, mut task)) = self.get_task_memory(id).await? else {
+            return Ok(None);
+        };
+        task.due_at = due_at;
+        self.save(&mut memory, &task).await?;
+        Ok(Some(task))
+    }
+
+    /// Make `id` wait on `depends_on`, returning whether it didn't already
+    ///
+    /// Both must be tasks, and the dependency must not lead back to `id`.
+    pub async fn add_dependency(&self, id: &str, depends_on: &str) -> Result<bool> {
+        for task_id in [id, depends_on] {
+            if self.get(task_id).await?.is_none() {
+                return Err(LocaiError::Task(format!("No task with ID {}", task_id)));
+            }
+        }
+        if id == depends_on {
+            return Err(LocaiError::Task(format!(
+                "Task {} can't depend on itself",
+                id
+            )));
+        }
+        if self
+            .dependency_ids(id)
+            .await?
+            .iter()
+            .any(|dep| dep == depends_on)
+        {
+            return Ok(false);
+        }
+        if self.reaches(depends_on, id).await? {
+            return Err(LocaiError::Task(format!(
+                "Task {} already depends on {}, so the reverse would be a cycle",
+                depends_on, id
+            )));
+        }
+
+        let now = Utc::now();
+        let relationship = Relationship {
+            id: format!("{}_{}_{}", id, DEPENDS_ON, depends_on),
+            relationship_type: DEPENDS_ON.to_string(),
+            source_id: id.to_string(),
+            target_id: depends_on.to_string(),
+            properties: serde_json::Value::Null,
+            created_at: now,
+            updated_at: now,
+        };
+        self.storage
+            .create_relationship(relationship)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to add dependency: {}", e)))?;
+        Ok(true)
+    }
+
+    /// Stop `id` waiting on `depends_on`, returning whether it was
+    pub async fn remove_dependency(&self, id: &str, depends_on: &str) -> Result<bool> {
+        let mut removed = false;
+        for relationship in self.dependency_relationships(id).await? {
+            if relationship.target_id == depends_on {
+                removed |= self
+                    .storage
+                    .delete_relationship(&relationship.id)
+                    .await
+                    .map_err(|e| {
+                        LocaiError::Storage(format!("Failed to remove dependency: {}", e))
+                    })?;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// The tasks `id` waits on
+    ///
+    /// Dependencies on tasks deleted since are left out.
+    pub async fn dependencies(&self, id: &str) -> Result<Vec<Task>> {
+        let mut tasks = Vec::new();
+        for dep in self.dependency_ids(id).await? {
+            if let Some(task) = self.get(&dep).await? {
+                tasks.push(task);
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// The dependencies of `id` that are still open or in progress
+    pub async fn blockers(&self, id: &str) -> Result<Vec<Task>> {
+        let mut tasks = self.dependencies(id).await?;
+        tasks.retain(|task| !task.status.is_closed());
+        Ok(tasks)
+    }
+
+    /// Whether `to` can be reached from `from` by following dependencies
+    async fn reaches(&self, from: &str, to: &str) -> Result<bool> {
+        let mut seen = HashSet::new();
+        let mut pending = vec![from.to_string()];
+        while let Some(id) = pending.pop() {
+            if id == to {
+                return Ok(true);
+            }
+            if seen.insert(id.clone()) {
+                pending.extend(self.dependency_ids(&id).await?);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn dependency_ids(&self, id: &str) -> Result<Vec<String>> {
+        Ok(self
+            .dependency_relationships(id)
+            .await?
+            .into_iter()
+            .map(|relationship| relationship.target_id)
+            .collect())
+    }
+
+    async fn dependency_relationships(&self, id: &str) -> Result<Vec<Relationship>> {
+        let filter = RelationshipFilter {
+            source_id: Some(id.to_string()),
+            relationship_type: Some(DEPENDS_ON.to_string()),
+            ..Default::default()
+        };
+        self.storage
+            .list_relationships(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list dependencies: {}", e)))
+    }
+
+    async fn get_memory(&self, id: &str) -> Result<Option<Memory>> {
+        self.storage
+            .get_memory(id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to get memory: {}", e)))
+    }
+
+    async fn get_task_memory(&self, id: &str) -> Result<Option<(Memory, Task)>> {
+        Ok(self.get_memory(id).await?.and_then(|memory| {
+            let task = Task::from_memory(&memory)?;
+            Some((memory, task))
+        }))
+    }
+
+    async fn save(&self, memory: &mut Memory, task: &Task) -> Result<()> {
+        task.write_state(memory);
+        self.storage
+            .update_memory(memory.clone())
+            .await
+            .map(|_| ())
+            .map_err(|e| LocaiError::Storage(format!("Failed to save task: {}", e)))
+    }
+}
@@ -0,0 +1,554 @@
+//! Memory templates
+//!
+//! A template ("meeting", "bug-report") fills a memory's content, type, tags
+//! and properties from variables, so memories of the same kind share one
+//! shape. Content, tags and string properties name variables as
+//! `{{variable}}`; a property that is exactly one placeholder takes the
+//! variable's typed value instead of its text.
+//!
+//! Placeholders are typed. Values are checked, and text given for a number,
+//! boolean, date or list (as from a command line) is converted:
+//!
+//! ```
+//! use locai::memory::{MemoryTemplate, Placeholder, PlaceholderType};
+//! use serde_json::json;
+//!
+//! let template = MemoryTemplate::new("reading", "Read {{title}} ({{pages}} pages)")
+//!     .with_placeholder(Placeholder::new("title", PlaceholderType::Text))
+//!     .with_placeholder(Placeholder::new("pages", PlaceholderType::Number))
+//!     .with_property("pages", json!("{{pages}}"));
+//!
+//! let memory = template
+//!     .render([("title", json!("Dune")), ("pages", json!("412"))])
+//!     .unwrap();
+//! assert_eq!(memory.content, "Read Dune (412 pages)");
+//! assert_eq!(memory.properties["pages"], json!(412));
+//! ```
+//!
+//! [`TemplateRegistry`] serves the [`builtin_templates`] and templates
+//! registered at runtime, which are stored as entities of type
+//! [`TEMPLATE_ENTITY_TYPE`]. A registered template replaces a built-in one
+//! with the same name.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::models::{Memory, MemoryBuilder, MemoryType};
+use crate::storage::filters::EntityFilter;
+use crate::storage::models::Entity;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Entity type registered templates are stored under
+pub const TEMPLATE_ENTITY_TYPE: &str = "memory_template";
+
+/// Property recording the template a memory was made from
+pub const TEMPLATE_PROPERTY: &str = "template";
+
+/// Type of a template variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderType {
+    Text,
+    Number,
+    Boolean,
+    /// An RFC 3339 timestamp or a `YYYY-MM-DD` date
+    Date,
+    /// A list of text; text is split on commas
+    List,
+}
+
+impl std::fmt::Display for PlaceholderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Number => write!(f, "number"),
+            Self::Boolean => write!(f, "boolean"),
+            Self::Date => write!(f, "date"),
+            Self::List => write!(f, "list"),
+        }
+    }
+}
+
+/// A variable a template expects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Placeholder {
+    pub name: String,
+
+    #[serde(rename = "type")]
+    pub kind: PlaceholderType,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Rendering fails when a required variable is missing and has no default
+    #[serde(default)]
+    pub required: bool,
+
+    #[serde(default)]
+    pub default: Option<Value>,
+}
+
+impl Placeholder {
+    /// A required placeholder
+    pub fn new(name: impl Into<String>, kind: PlaceholderType) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            description: None,
+            required: true,
+            default: None,
+        }
+    }
+
+    /// An optional placeholder; missing values render as empty
+    pub fn optional(name: impl Into<String>, kind: PlaceholderType) -> Self {
+        Self {
+            required: false,
+            ..Self::new(name, kind)
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_default(mut self, default: Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Check a value against this placeholder's type, converting text
+    fn coerce(&self, value: Value) -> Result<Value> {
+        let invalid = |value: &Value| {
+            LocaiError::Template(format!(
+                "'{}' should be {}, not {}",
+                self.name,
+                match self.kind {
+                    PlaceholderType::Text => "text",
+                    PlaceholderType::Number => "a number",
+                    PlaceholderType::Boolean => "true or false",
+                    PlaceholderType::Date => "a date (YYYY-MM-DD or RFC 3339)",
+                    PlaceholderType::List => "a list",
+                },
+                value
+            ))
+        };
+
+        match (self.kind, value) {
+            (PlaceholderType::Text, Value::String(text)) => Ok(Value::String(text)),
+            (PlaceholderType::Text, value @ (Value::Number(_) | Value::Bool(_))) => {
+                Ok(Value::String(value.to_string()))
+            }
+            (PlaceholderType::Number, Value::Number(number)) => Ok(Value::Number(number)),
+            (PlaceholderType::Number, Value::String(text)) => text
+                .trim()
+                .parse::<serde_json::Number>()
+                .map(Value::Number)
+                .map_err(|_| invalid(&Value::String(text))),
+            (PlaceholderType::Boolean, Value::Bool(flag)) => Ok(Value::Bool(flag)),
+            (PlaceholderType::Boolean, Value::String(text)) => {
+                match text.trim().to_lowercase().as_str() {
+                    "true" | "yes" => Ok(Value::Bool(true)),
+                    "false" | "no" => Ok(Value::Bool(false)),
+                    _ => Err(invalid(&Value::String(text))),
+                }
+            }
+            (PlaceholderType::Date, Value::String(text)) => parse_date(text.trim())
+                .map(Value::String)
+                .ok_or_else(|| invalid(&Value::String(text))),
+            (PlaceholderType::List, Value::Array(items)) => Ok(Value::Array(items)),
+            (PlaceholderType::List, Value::String(text)) => Ok(Value::Array(
+                text.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Value::String(item.to_string()))
+                    .collect(),
+            )),
+            (_, value) => Err(invalid(&value)),
+        }
+    }
+}
+
+/// Dates keep their form; RFC 3339 timestamps are normalized to UTC
+fn parse_date(text: &str) -> Option<String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Utc).to_rfc3339());
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.to_string())
+}
+
+/// A reusable shape for memories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryTemplate {
+    /// Unique name
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Memory content with `{{variable}}` placeholders
+    pub content: String,
+
+    /// Memory type of memories made from this template
+    #[serde(default = "default_memory_type")]
+    pub memory_type: String,
+
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Memory properties; string values may contain placeholders
+    #[serde(default)]
+    pub properties: Map<String, Value>,
+
+    #[serde(default)]
+    pub placeholders: Vec<Placeholder>,
+}
+
+fn default_memory_type() -> String {
+    "fact".to_string()
+}
+
+impl MemoryTemplate {
+    pub fn new(name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            content: content.into(),
+            memory_type: default_memory_type(),
+            tags: Vec::new(),
+            properties: Map::new(),
+            placeholders: Vec::new(),
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_memory_type(mut self, memory_type: impl Into<String>) -> Self {
+        self.memory_type = memory_type.into();
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn with_property(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.properties.insert(key.into(), value);
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: Placeholder) -> Self {
+        self.placeholders.push(placeholder);
+        self
+    }
+
+    /// Check that every `{{variable}}` the template uses is declared
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(LocaiError::Template(
+                "Template name cannot be empty".to_string(),
+            ));
+        }
+
+        let mut texts: Vec<&str> = vec![&self.content];
+        texts.extend(self.tags.iter().map(String::as_str));
+        texts.extend(self.properties.values().filter_map(Value::as_str));
+        for text in texts {
+            for name in variables(text)? {
+                if !self.placeholders.iter().any(|p| p.name == name) {
+                    return Err(LocaiError::Template(format!(
+                        "Template '{}' uses undeclared variable '{}'",
+                        self.name, name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Make a memory from the template
+    ///
+    /// Fails on variables the template doesn't declare, missing required
+    /// variables and values of the wrong type. The memory's `template`
+    /// property names the template.
+    pub fn render<K, V>(&self, vars: impl IntoIterator<Item = (K, V)>) -> Result<Memory>
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        let values = self.resolve(vars)?;
+
+        let content = fill(&self.content, &values)?;
+        let mut builder = MemoryBuilder::new_with_content(content)
+            .memory_type(MemoryType::from_str(&self.memory_type));
+        for tag in &self.tags {
+            let tag = fill(tag, &values)?;
+            if !tag.is_empty() {
+                builder = builder.tag(tag);
+            }
+        }
+        for (key, value) in &self.properties {
+            if let Some(value) = fill_property(value, &values)? {
+                builder = builder.property(key, value);
+            }
+        }
+        builder = builder.property(TEMPLATE_PROPERTY, json!(self.name));
+
+        Ok(builder.build())
+    }
+
+    /// Typed values of every variable given or defaulted
+    fn resolve<K, V>(
+        &self,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<HashMap<String, Value>>
+    where
+        K: Into<String>,
+        V: Into<Value>,
+    {
+        let mut given: HashMap<String, Value> = vars
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+
+        let mut values = HashMap::new();
+        for placeholder in &self.placeholders {
+            match given
+                .remove(&placeholder.name)
+                .or_else(|| placeholder.default.clone())
+            {
+                Some(value) => {
+                    values.insert(placeholder.name.clone(), placeholder.coerce(value)?);
+                }
+                None if placeholder.required => {
+                    return Err(LocaiError::Template(format!(
+                        "Template '{}' needs a value for '{}'",
+                        self.name, placeholder.name
+                    )));
+                }
+                None => {}
+            }
+        }
+
+        if let Some(unknown) = given.keys().min() {
+            return Err(LocaiError::Template(format!(
+                "Template '{}' has no variable '{}'",
+                self.name, unknown
+            )));
+        }
+        Ok(values)
+    }
+}
+
+/// Names of the `{{variable}}` placeholders in `text`
+fn variables(text: &str) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| LocaiError::Template(format!("Unclosed '{{{{' in '{}'", text)))?;
+        names.push(after[..end].trim().to_string());
+        rest = &after[end + 2..];
+    }
+    Ok(names)
+}
+
+/// Replace placeholders in `text`; missing optional variables become empty
+fn fill(text: &str, values: &HashMap<String, Value>) -> Result<String> {
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        filled.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| LocaiError::Template(format!("Unclosed '{{{{' in '{}'", text)))?;
+        if let Some(value) = values.get(after[..end].trim()) {
+            filled.push_str(&display(value));
+        }
+        rest = &after[end + 2..];
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+/// A property value with its placeholders filled
+///
+/// A value that is exactly one placeholder takes the variable's typed value,
+/// and is left out when the variable is missing.
+fn fill_property(value: &Value, values: &HashMap<String, Value>) -> Result<Option<Value>> {
+    let Some(text) = value.as_str() else {
+        return Ok(Some(value.clone()));
+    };
+    let trimmed = text.trim();
+    if let Some(name) = trimmed
+        .strip_prefix("{{")
+        .and_then(|inner| inner.strip_suffix("}}"))
+        .filter(|inner| !inner.contains("{{"))
+    {
+        return Ok(values.get(name.trim()).cloned());
+    }
+    fill(text, values).map(|filled| Some(Value::String(filled)))
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Templates available without registering them
+pub fn builtin_templates() -> Vec<MemoryTemplate> {
+    vec![
+        MemoryTemplate::new(
+            "meeting",
+            "Meeting: {{title}} on {{date}} with {{attendees}}. Notes: {{notes}}",
+        )
+        .with_description("Notes from a meeting")
+        .with_memory_type("episodic")
+        .with_tag("meeting")
+        .with_property("title", json!("{{title}}"))
+        .with_property("date", json!("{{date}}"))
+        .with_property("attendees", json!("{{attendees}}"))
+        .with_placeholder(Placeholder::new("title", PlaceholderType::Text))
+        .with_placeholder(Placeholder::new("date", PlaceholderType::Date))
+        .with_placeholder(Placeholder::optional("attendees", PlaceholderType::List))
+        .with_placeholder(Placeholder::optional("notes", PlaceholderType::Text)),
+        MemoryTemplate::new(
+            "bug-report",
+            "Bug in {{component}}: {{summary}}. Steps to reproduce: {{steps}}",
+        )
+        .with_description("A bug and how to reproduce it")
+        .with_memory_type("event")
+        .with_tag("bug")
+        .with_tag("{{component}}")
+        .with_property("component", json!("{{component}}"))
+        .with_property("severity", json!("{{severity}}"))
+        .with_placeholder(Placeholder::new("component", PlaceholderType::Text))
+        .with_placeholder(Placeholder::new("summary", PlaceholderType::Text))
+        .with_placeholder(Placeholder::optional("steps", PlaceholderType::Text))
+        .with_placeholder(
+            Placeholder::optional("severity", PlaceholderType::Text).with_default(json!("normal")),
+        ),
+    ]
+}
+
+/// Built-in and registered templates
+#[derive(Debug)]
+pub struct TemplateRegistry {
+    storage: Arc<dyn GraphStore>,
+}
+
+impl TemplateRegistry {
+    pub fn new(storage: Arc<dyn GraphStore>) -> Self {
+        Self { storage }
+    }
+
+    /// Register a template, replacing any registered under the same name
+    pub async fn register(&self, template: MemoryTemplate) -> Result<MemoryTemplate> {
+        template.validate()?;
+        let id = self
+            .registered()
+            .await?
+            .into_iter()
+            .find(|(_, existing)| existing.name == template.name)
+            .map(|(id, _)| id)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        let now = Utc::now();
+        let entity = Entity {
+            id,
+            entity_type: TEMPLATE_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(&template).unwrap_or_default(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.storage
+            .upsert_entity(entity)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to store template: {}", e)))?;
+        Ok(template)
+    }
+
+    /// Find a template by name
+    pub async fn get(&self, name: &str) -> Result<Option<MemoryTemplate>> {
+        Ok(self
+            .list()
+            .await?
+            .into_iter()
+            .find(|template| template.name == name))
+    }
+
+    /// All templates, by name
+    pub async fn list(&self) -> Result<Vec<MemoryTemplate>> {
+        let mut templates: Vec<MemoryTemplate> = self
+            .registered()
+            .await?
+            .into_iter()
+            .map(|(_, template)| template)
+            .collect();
+        for builtin in builtin_templates() {
+            if !templates.iter().any(|t| t.name == builtin.name) {
+                templates.push(builtin);
+            }
+        }
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    /// Remove a registered template
+    ///
+    /// Returns `false` when no template of that name was registered; built-in
+    /// templates can't be removed.
+    pub async fn remove(&self, name: &str) -> Result<bool> {
+        let Some((id, _)) = self
+            .registered()
+            .await?
+            .into_iter()
+            .find(|(_, template)| template.name == name)
+        else {
+            return Ok(false);
+        };
+        self.storage
+            .delete_entity(&id)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to delete template: {}", e)))
+    }
+
+    /// Registered templates with their entity IDs
+    async fn registered(&self) -> Result<Vec<(String, MemoryTemplate)>> {
+        let filter = EntityFilter {
+            entity_type: Some(TEMPLATE_ENTITY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let entities = self
+            .storage
+            .list_entities(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list templates: {}", e)))?;
+        Ok(entities
+            .into_iter()
+            .filter_map(|entity| {
+                let template = serde_json::from_value(entity.properties).ok()?;
+                Some((entity.id, template))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,440 @@
+//! Usage accounting per tenant
+//!
+//! [`UsageLedger`] counts what each tenant does (reads, writes, searches and
+//! embedding provider calls) in hourly buckets, along with the most bytes it
+//! was seen storing in each hour. Counts are kept in memory until
+//! [`flush`](UsageLedger::flush) appends them to the store as `custom:usage`
+//! memories, one per tenant and hour per flush. Entries are only ever added,
+//! so processes sharing a store never overwrite each other's counts.
+//!
+//! [`report`](UsageLedger::report) adds up flushed and pending counts by
+//! hour, day or month, and [`to_csv`] writes a report out for billing.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Datelike, Duration, DurationRound, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::clock::ClockHandle;
+use crate::models::{Memory, MemoryType};
+use crate::storage::filters::MemoryFilter;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Memory property holding a ledger entry's counts
+pub const USAGE_PROPERTY: &str = "usage";
+
+/// Custom memory type ledger entries are stored as
+pub const USAGE_MEMORY_TYPE: &str = "usage";
+
+/// Source of ledger entries
+pub const USAGE_SOURCE: &str = "locai:usage";
+
+/// Something a tenant is charged for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageMetric {
+    Reads,
+    Writes,
+    Searches,
+    /// Requests to an embedding provider; cached embeddings aren't counted
+    EmbeddingCalls,
+    /// Tokens an embedding provider billed
+    EmbeddingTokens,
+}
+
+/// What a tenant used in one bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageCounts {
+    pub reads: u64,
+    pub writes: u64,
+    pub searches: u64,
+    pub embedding_calls: u64,
+    pub embedding_tokens: u64,
+    /// Most bytes the tenant was seen storing
+    pub storage_bytes: u64,
+}
+
+impl UsageCounts {
+    fn add(&mut self, metric: UsageMetric, count: u64) {
+        let total = match metric {
+            UsageMetric::Reads => &mut self.reads,
+            UsageMetric::Writes => &mut self.writes,
+            UsageMetric::Searches => &mut self.searches,
+            UsageMetric::EmbeddingCalls => &mut self.embedding_calls,
+            UsageMetric::EmbeddingTokens => &mut self.embedding_tokens,
+        };
+        *total += count;
+    }
+
+    fn merge(&mut self, other: &UsageCounts) {
+        self.reads += other.reads;
+        self.writes += other.writes;
+        self.searches += other.searches;
+        self.embedding_calls += other.embedding_calls;
+        self.embedding_tokens += other.embedding_tokens;
+        self.storage_bytes = self.storage_bytes.max(other.storage_bytes);
+    }
+}
+
+/// A tenant's usage in one bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub tenant: String,
+
+    /// Start of the bucket
+    pub bucket_start: DateTime<Utc>,
+
+    #[serde(flatten)]
+    pub counts: UsageCounts,
+}
+
+impl UsageRecord {
+    /// Read a ledger entry from its memory; `None` if the memory isn't one
+    pub fn from_memory(memory: &Memory) -> Option<Self> {
+        if memory.memory_type != MemoryType::Custom(USAGE_MEMORY_TYPE.to_string()) {
+            return None;
+        }
+        memory
+            .properties
+            .get(USAGE_PROPERTY)
+            .and_then(|record| serde_json::from_value(record.clone()).ok())
+    }
+
+    /// The memory to store for a ledger entry
+    pub fn to_memory(&self) -> Memory {
+        let content = format!(
+            "Usage of {} for the hour from {}",
+            self.tenant,
+            self.bucket_start.to_rfc3339()
+        );
+        let mut memory = Memory::new(
+            Uuid::new_v4().to_string(),
+            content,
+            MemoryType::Custom(USAGE_MEMORY_TYPE.to_string()),
+        );
+        memory.source = USAGE_SOURCE.to_string();
+        memory.created_at = self.bucket_start;
+        memory.set_property(
+            USAGE_PROPERTY,
+            serde_json::to_value(self).unwrap_or_default(),
+        );
+        memory
+    }
+}
+
+/// How finely a report is bucketed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageInterval {
+    #[default]
+    Hour,
+    Day,
+    Month,
+}
+
+impl UsageInterval {
+    /// Start of the bucket `time` falls in
+    pub fn bucket_start(self, time: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            UsageInterval::Hour => time.duration_trunc(Duration::hours(1)).unwrap_or(time),
+            UsageInterval::Day => time.duration_trunc(Duration::days(1)).unwrap_or(time),
+            UsageInterval::Month => Utc
+                .with_ymd_and_hms(time.year(), time.month(), 1, 0, 0, 0)
+                .single()
+                .unwrap_or(time),
+        }
+    }
+}
+
+impl fmt::Display for UsageInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UsageInterval::Hour => "hour",
+            UsageInterval::Day => "day",
+            UsageInterval::Month => "month",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for UsageInterval {
+    type Err = LocaiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "hour" => Ok(UsageInterval::Hour),
+            "day" => Ok(UsageInterval::Day),
+            "month" => Ok(UsageInterval::Month),
+            other => Err(LocaiError::Usage(format!(
+                "Unknown usage interval '{}'; expected hour, day or month",
+                other
+            ))),
+        }
+    }
+}
+
+/// Which usage a report covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageQuery {
+    /// Start of the first hour covered
+    pub start: DateTime<Utc>,
+
+    /// Hours starting at or after this aren't covered
+    pub end: DateTime<Utc>,
+
+    pub interval: UsageInterval,
+
+    /// Only this tenant's usage; every tenant's if `None`
+    pub tenant: Option<String>,
+}
+
+/// Counts usage per tenant and hour
+#[derive(Debug)]
+pub struct UsageLedger {
+    storage: Arc<dyn GraphStore>,
+    clock: ClockHandle,
+    /// Counts not yet flushed, by tenant and hour
+    pending: Mutex<HashMap<(String, DateTime<Utc>), UsageCounts>>,
+}
+
+impl UsageLedger {
+    pub fn new(storage: Arc<dyn GraphStore>, clock: ClockHandle) -> Self {
+        Self {
+            storage,
+            clock,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count `count` of `metric` against `tenant` in the current hour
+    pub fn record(&self, tenant: &str, metric: UsageMetric, count: u64) {
+        if count == 0 {
+            return;
+        }
+        let hour = UsageInterval::Hour.bucket_start(self.clock.now());
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry((tenant.to_string(), hour))
+            .or_default()
+            .add(metric, count);
+    }
+
+    /// Note that `tenant` stores `bytes`, keeping the hour's largest figure
+    pub fn record_storage(&self, tenant: &str, bytes: u64) {
+        let hour = UsageInterval::Hour.bucket_start(self.clock.now());
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let counts = pending.entry((tenant.to_string(), hour)).or_default();
+        counts.storage_bytes = counts.storage_bytes.max(bytes);
+    }
+
+    /// Append the pending counts to the store, returning how many entries
+    /// were written
+    ///
+    /// Counts that fail to store stay pending for the next flush.
+    pub async fn flush(&self) -> Result<usize> {
+        let pending: Vec<_> = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .collect();
+
+        let mut written = 0;
+        let mut failed = Vec::new();
+        let mut error = None;
+        for ((tenant, bucket_start), counts) in pending {
+            let record = UsageRecord {
+                tenant,
+                bucket_start,
+                counts,
+            };
+            match self.storage.create_memory(record.to_memory()).await {
+                Ok(_) => written += 1,
+                Err(e) => {
+                    error = Some(e);
+                    failed.push(record);
+                }
+            }
+        }
+
+        if let Some(e) = error {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            for record in failed {
+                pending
+                    .entry((record.tenant, record.bucket_start))
+                    .or_default()
+                    .merge(&record.counts);
+            }
+            return Err(LocaiError::Storage(format!("Failed to flush usage: {}", e)));
+        }
+        Ok(written)
+    }
+
+    /// Usage in the query's range, flushed or not, by tenant and bucket;
+    /// ordered by bucket, then tenant
+    pub async fn report(&self, query: &UsageQuery) -> Result<Vec<UsageRecord>> {
+        let filter = MemoryFilter {
+            memory_type: Some(USAGE_MEMORY_TYPE.to_string()),
+            created_after: Some(query.start - Duration::hours(1)),
+            created_before: Some(query.end),
+            ..Default::default()
+        };
+        let memories = self
+            .storage
+            .list_memories(Some(filter), None, None)
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Failed to list usage: {}", e)))?;
+
+        let mut records: Vec<UsageRecord> = memories
+            .iter()
+            .filter_map(UsageRecord::from_memory)
+            .collect();
+        records.extend(
+            self.pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|((tenant, bucket_start), counts)| UsageRecord {
+                    tenant: tenant.clone(),
+                    bucket_start: *bucket_start,
+                    counts: *counts,
+                }),
+        );
+
+        Ok(aggregate(records, query))
+    }
+}
+
+/// Add up hourly records into the query's buckets
+fn aggregate(records: Vec<UsageRecord>, query: &UsageQuery) -> Vec<UsageRecord> {
+    let mut buckets: BTreeMap<(DateTime<Utc>, String), UsageCounts> = BTreeMap::new();
+    for record in records {
+        if record.bucket_start < query.start
+            || record.bucket_start >= query.end
+            || query
+                .tenant
+                .as_ref()
+                .is_some_and(|tenant| &record.tenant != tenant)
+        {
+            continue;
+        }
+        let bucket_start = query.interval.bucket_start(record.bucket_start);
+        buckets
+            .entry((bucket_start, record.tenant))
+            .or_default()
+            .merge(&record.counts);
+    }
+    buckets
+        .into_iter()
+        .map(|((bucket_start, tenant), counts)| UsageRecord {
+            tenant,
+            bucket_start,
+            counts,
+        })
+        .collect()
+}
+
+/// Write a report as CSV, one row per tenant and bucket
+pub fn to_csv(records: &[UsageRecord]) -> String {
+    let mut csv = String::from(
+        "bucket_start,tenant,reads,writes,searches,embedding_calls,embedding_tokens,storage_bytes\n",
+    );
+    for record in records {
+        let counts = &record.counts;
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            record.bucket_start.to_rfc3339(),
+            csv_field(&record.tenant),
+            counts.reads,
+            counts.writes,
+            counts.searches,
+            counts.embedding_calls,
+            counts.embedding_tokens,
+            counts.storage_bytes
+        ));
+    }
+    csv
+}
+
+/// Quote a field that would otherwise break the row
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2030, 1, day, hour, 0, 0).unwrap()
+    }
+
+    fn record(tenant: &str, bucket_start: DateTime<Utc>, reads: u64, bytes: u64) -> UsageRecord {
+        UsageRecord {
+            tenant: tenant.to_string(),
+            bucket_start,
+            counts: UsageCounts {
+                reads,
+                storage_bytes: bytes,
+                ..UsageCounts::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_aggregate_sums_counts_and_keeps_peak_storage() {
+        let records = vec![
+            record("acme", at(1, 9), 3, 100),
+            record("acme", at(1, 9), 2, 80),
+            record("acme", at(1, 17), 1, 120),
+            record("globex", at(1, 9), 7, 10),
+            record("acme", at(2, 9), 5, 0),
+        ];
+        let query = UsageQuery {
+            start: at(1, 0),
+            end: at(2, 0),
+            interval: UsageInterval::Day,
+            tenant: None,
+        };
+
+        let report = aggregate(records, &query);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].tenant, "acme");
+        assert_eq!(report[0].bucket_start, at(1, 0));
+        assert_eq!(report[0].counts.reads, 6);
+        assert_eq!(report[0].counts.storage_bytes, 120);
+        assert_eq!(report[1].tenant, "globex");
+    }
+
+    #[test]
+    fn test_month_buckets_start_on_the_first() {
+        let time = Utc.with_ymd_and_hms(2030, 3, 17, 13, 45, 0).unwrap();
+        assert_eq!(
+            UsageInterval::Month.bucket_start(time),
+            Utc.with_ymd_and_hms(2030, 3, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            UsageInterval::Hour.bucket_start(time),
+            Utc.with_ymd_and_hms(2030, 3, 17, 13, 0, 0).unwrap()
+        );
+        assert!("week".parse::<UsageInterval>().is_err());
+    }
+
+    #[test]
+    fn test_csv_quotes_awkward_tenants() {
+        let csv = to_csv(&[record("acme, inc", at(1, 9), 3, 0)]);
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(row, "2030-01-01T09:00:00+00:00,\"acme, inc\",3,0,0,0,0,0");
+    }
+}
@@ -17,6 +17,8 @@ pub fn parse_memory_type(type_str: &str) -> MemoryType {
         "world" => MemoryType::World,
         "action" => MemoryType::Action,
         "event" => MemoryType::Event,
+        "task" => MemoryType::Task,
+        "preference" => MemoryType::Preference,
         s if s.starts_with("custom:") => MemoryType::Custom(s[7..].to_string()),
         s => MemoryType::Custom(s.to_string()),
     }
@@ -35,6 +37,13 @@ pub fn parse_memory_priority(priority_str: &str) -> MemoryPriority {
 
 /// Check if a memory matches the filter criteria
 pub fn matches_memory_filter_detailed(memory: &Memory, filter: &MemoryFilter) -> bool {
+    // Check IDs
+    if let Some(ids) = &filter.ids
+        && !ids.contains(&memory.id)
+    {
+        return false;
+    }
+
     // Check memory type
     if let Some(filter_type) = &filter.memory_type {
         let memory_type_str = memory.memory_type.to_string();
@@ -66,6 +75,33 @@ pub fn matches_memory_filter_detailed(memory: &Memory, filter: &MemoryFilter) ->
         return false;
     }
 
+    // Check properties
+    if let Some(properties) = &filter.properties
+        && !properties
+            .iter()
+            .all(|(key, value)| memory.properties.get(key) == Some(value))
+    {
+        return false;
+    }
+
+    // Check typed property comparisons
+    if let Some(conditions) = &filter.conditions
+        && !conditions
+            .iter()
+            .all(|condition| condition.matches(&memory.properties))
+    {
+        return false;
+    }
+
+    // Check location
+    if let Some(near) = &filter.near
+        && !memory
+            .location()
+            .is_some_and(|location| near.contains(&location))
+    {
+        return false;
+    }
+
     // Check time range
     if let Some(created_after) = &filter.created_after
         && memory.created_at < *created_after
@@ -88,6 +124,11 @@ pub fn convert_db_event_to_memory(
 ) -> Option<Memory> {
     use serde_json::Value;
 
+    // Events from the storage change stream carry the record in model form
+    if let Ok(memory) = serde_json::from_value::<Memory>(event.result.clone()) {
+        return Some(memory);
+    }
+
     // Extract memory data from the event result
     let result = &event.result;
 
@@ -43,7 +43,7 @@ impl MemoryVersionManager {
             content: new_content.to_string(),
             version_type,
             change_reason: change_reason.to_string(),
-            created_at: Utc::now(),
+            created_at: self.memory_manager.clock().now(),
             metadata: self.collect_version_metadata(memory_id).await?,
         };
 
@@ -121,7 +121,7 @@ impl MemoryVersionManager {
         affected_memories: &[String],
     ) -> Result<CampaignSnapshot> {
         let snapshot_id = Uuid::new_v4().to_string();
-        let timestamp = Utc::now();
+        let timestamp = self.memory_manager.clock().now();
 
         let mut memory_snapshots = Vec::new();
 
@@ -269,7 +269,7 @@ impl MemoryVersionManager {
             snapshot_id: snapshot_id.to_string(),
             content: memory.content.clone(),
             metadata: memory.tags.clone(),
-            captured_at: Utc::now(),
+            captured_at: self.memory_manager.clock().now(),
         })
     }
 
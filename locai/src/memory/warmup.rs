@@ -0,0 +1,177 @@
+//! Index warmup
+//!
+//! The first searches after a restart are slow while the store pages its
+//! full-text postings and vector index back in. [`warm_up`] takes that cost
+//! up front: it runs BM25 searches for the configured queries and for the
+//! terms most common in recent memories, then vector searches with the
+//! embeddings of recent memories, so the postings and vectors agents are
+//! likely to hit next are already loaded.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::WarmupConfig;
+use crate::memory::{SearchExtensions, SearchMode};
+use crate::models::Memory;
+use crate::storage::traits::GraphStore;
+
+/// Results a warmup search asks for; enough to touch the index without
+/// loading much
+const WARMUP_SEARCH_LIMIT: usize = 10;
+
+/// Shortest word worth warming as a search term
+const MIN_TERM_LENGTH: usize = 4;
+
+/// What a warmup did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmupReport {
+    /// Recent memories sampled for terms and embeddings
+    pub memories_sampled: usize,
+
+    /// BM25 searches run
+    pub text_queries: usize,
+
+    /// Vector searches run
+    pub vector_queries: usize,
+
+    /// Searches that failed; warmup carries on past them
+    pub errors: usize,
+
+    pub duration_ms: u64,
+
+    /// Whether the warmup stopped at its timeout before finishing
+    pub timed_out: bool,
+}
+
+/// The terms appearing in the most memories, most common first
+///
+/// Words shorter than four characters and numbers are skipped, as they are
+/// mostly stopwords and rarely searched for.
+pub fn common_terms(memories: &[Memory], limit: usize) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for memory in memories {
+        let mut words: Vec<String> = memory
+            .content
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| {
+                word.chars().count() >= MIN_TERM_LENGTH && !word.chars().all(char::is_numeric)
+            })
+            .map(str::to_lowercase)
+            .collect();
+        words.sort();
+        words.dedup();
+        for word in words {
+            *counts.entry(word).or_default() += 1;
+        }
+    }
+
+    let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms
+        .into_iter()
+        .take(limit)
+        .map(|(term, _)| term)
+        .collect()
+}
+
+/// Run the searches that load the indexes, giving up at the configured
+/// timeout
+pub async fn warm_up(
+    storage: &Arc<dyn GraphStore>,
+    search: &SearchExtensions,
+    config: &WarmupConfig,
+) -> WarmupReport {
+    let started = Instant::now();
+    let mut report = WarmupReport::default();
+    let run = run(storage, search, config, &mut report);
+    let timed_out = if config.timeout_secs > 0 {
+        tokio::time::timeout(Duration::from_secs(config.timeout_secs), run)
+            .await
+            .is_err()
+    } else {
+        run.await;
+        false
+    };
+    report.timed_out = timed_out;
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+async fn run(
+    storage: &Arc<dyn GraphStore>,
+    search: &SearchExtensions,
+    config: &WarmupConfig,
+    report: &mut WarmupReport,
+) {
+    let recent = if config.sample_size > 0 {
+        match storage
+            .list_memories(None, Some(config.sample_size), None)
+            .await
+        {
+            Ok(memories) => memories,
+            Err(e) => {
+                tracing::warn!("Warmup could not sample recent memories: {}", e);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    report.memories_sampled = recent.len();
+
+    let mut queries: Vec<String> = config
+        .queries
+        .iter()
+        .filter(|query| !query.trim().is_empty())
+        .take(config.max_queries)
+        .cloned()
+        .collect();
+    // Enough terms to fill the rest even if all the queries are among them
+    for term in common_terms(&recent, config.max_queries) {
+        if queries.len() >= config.max_queries {
+            break;
+        }
+        if !queries.contains(&term) {
+            queries.push(term);
+        }
+    }
+
+    for query in &queries {
+        match search
+            .search_hits(query, None, Some(WARMUP_SEARCH_LIMIT), SearchMode::Text)
+            .await
+        {
+            Ok(_) => report.text_queries += 1,
+            Err(e) => {
+                tracing::debug!("Warmup search for {:?} failed: {}", query, e);
+                report.errors += 1;
+            }
+        }
+    }
+
+    let embeddings = recent
+        .iter()
+        .filter_map(|memory| memory.embedding.as_deref())
+        .filter(|embedding| !embedding.is_empty())
+        .take(config.vector_probes);
+    for embedding in embeddings {
+        match search
+            .search_hits(
+                "",
+                Some(embedding),
+                Some(WARMUP_SEARCH_LIMIT),
+                SearchMode::Vector,
+            )
+            .await
+        {
+            Ok(_) => report.vector_queries += 1,
+            Err(e) => {
+                tracing::debug!("Warmup vector search failed: {}", e);
+                report.errors += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Kafka bridge transport
+//!
+//! Goes through a Confluent-compatible Kafka REST Proxy (API v2) rather than
+//! the native Kafka protocol, so no librdkafka build is involved. Records use
+//! the binary embedded format, so payloads pass through unchanged. Each
+//! subscription is its own consumer instance in
+//! [`KafkaConfig::consumer_group`], polled every
+//! [`KafkaConfig::poll_interval_ms`] while idle; the instance is deleted when
+//! the stream is dropped.
+//!
+//! Kafka topic names only allow letters, digits, `.`, `_` and `-`, so Locai
+//! topics used with this transport should stick to those.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::{BridgeRecord, BridgeStream, BridgeTransport};
+use crate::{LocaiError, Result};
+
+/// Default Kafka REST Proxy endpoint
+pub const DEFAULT_KAFKA_REST_URL: &str = "http://localhost:8082";
+
+const BINARY_V2: &str = "application/vnd.kafka.binary.v2+json";
+const KAFKA_V2: &str = "application/vnd.kafka.v2+json";
+
+/// Configuration for [`KafkaTransport`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KafkaConfig {
+    /// Base URL of the REST Proxy
+    pub rest_url: String,
+
+    /// Consumer group for inbound subscriptions
+    pub consumer_group: String,
+
+    /// Where a new consumer group starts (`latest` or `earliest`)
+    pub auto_offset_reset: String,
+
+    /// Delay between polls when no records are waiting
+    pub poll_interval_ms: u64,
+
+    /// Basic auth user for the REST Proxy
+    pub username: Option<String>,
+
+    /// Basic auth password for the REST Proxy
+    pub password: Option<String>,
+
+    /// Request timeout in seconds
+    pub timeout_secs: u64,
+}
+
+impl Default for KafkaConfig {
+    fn default() -> Self {
+        Self {
+            rest_url: DEFAULT_KAFKA_REST_URL.to_string(),
+            consumer_group: "locai-bridge".to_string(),
+            auto_offset_reset: "latest".to_string(),
+            poll_interval_ms: 500,
+            username: None,
+            password: None,
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// Bridge transport through a Kafka REST Proxy
+#[derive(Debug, Clone)]
+pub struct KafkaTransport {
+    config: KafkaConfig,
+    client: reqwest::Client,
+    name: String,
+}
+
+/// A record returned by a consumer poll
+#[derive(Debug, Deserialize)]
+struct ConsumedRecord {
+    topic: String,
+    value: Option<String>,
+}
+
+/// Consumer instance created for one subscription
+struct Consumer {
+    transport: KafkaTransport,
+    base_uri: String,
+}
+
+impl Drop for Consumer {
+    fn drop(&mut self) {
+        // Frees the instance on the proxy instead of waiting for it to time out
+        let request = self
+            .transport
+            .request(reqwest::Method::DELETE, self.base_uri.clone());
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = request.header("Content-Type", KAFKA_V2).send().await;
+            });
+        }
+    }
+}
+
+impl KafkaTransport {
+    /// Create a transport from configuration
+    pub fn new(config: KafkaConfig) -> Result<Self> {
+        if config.consumer_group.is_empty() {
+            return Err(LocaiError::Configuration(
+                "Kafka consumer group cannot be empty".to_string(),
+            ));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| LocaiError::Other(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            name: format!("kafka:{}", config.rest_url.trim_end_matches('/')),
+            config,
+            client,
+        })
+    }
+
+    /// Transport configuration
+    pub fn config(&self) -> &KafkaConfig {
+        &self.config
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.rest_url.trim_end_matches('/'), path)
+    }
+
+    fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.config.username {
+            Some(username) => request.basic_auth(username, self.config.password.as_ref()),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, action: &str) -> Result<Value> {
+        let response = request.send().await.map_err(|e| {
+            LocaiError::Connection(format!(
+                "Kafka REST Proxy at {}: {}",
+                self.config.rest_url, e
+            ))
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LocaiError::Other(format!(
+                "Kafka {} failed ({}): {}",
+                action, status, body
+            )));
+        }
+        if status == reqwest::StatusCode::NO_CONTENT {
+            return Ok(Value::Null);
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| LocaiError::Protocol(format!("Invalid Kafka REST Proxy response: {}", e)))
+    }
+
+    async fn create_consumer(&self, topic: &str) -> Result<Consumer> {
+        let instance = format!("locai-{}", uuid::Uuid::new_v4());
+        let created = self
+            .send(
+                self.request(
+                    reqwest::Method::POST,
+                    self.url(&format!("/consumers/{}", self.config.consumer_group)),
+                )
+                .header("Content-Type", KAFKA_V2)
+                .json(&json!({
+                    "name": instance,
+                    "format": "binary",
+                    "auto.offset.reset": self.config.auto_offset_reset,
+                    "auto.commit.enable": "true",
+                })),
+                "consumer creation",
+            )
+            .await?;
+        let base_uri = created
+            .get("base_uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                LocaiError::Protocol("Kafka REST Proxy returned no consumer base_uri".to_string())
+            })?
+            .to_string();
+        let consumer = Consumer {
+            transport: self.clone(),
+            base_uri,
+        };
+
+        self.send(
+            self.request(
+                reqwest::Method::POST,
+                format!("{}/subscription", consumer.base_uri),
+            )
+            .header("Content-Type", KAFKA_V2)
+            .json(&json!({ "topics": [topic] })),
+            "subscription",
+        )
+        .await?;
+        Ok(consumer)
+    }
+}
+
+impl Consumer {
+    async fn poll(&self) -> Result<Vec<BridgeRecord>> {
+        let records = self
+            .transport
+            .send(
+                self.transport
+                    .request(reqwest::Method::GET, format!("{}/records", self.base_uri))
+                    .header("Accept", BINARY_V2),
+                "poll",
+            )
+            .await?;
+        let records: Vec<ConsumedRecord> = serde_json::from_value(records)
+            .map_err(|e| LocaiError::Protocol(format!("Invalid Kafka records: {}", e)))?;
+        records.into_iter().map(decode_record).collect()
+    }
+}
+
+fn decode_record(record: ConsumedRecord) -> Result<BridgeRecord> {
+    let payload = match record.value {
+        Some(value) => BASE64.decode(value).map_err(|e| {
+            LocaiError::Protocol(format!("Invalid record value on {}: {}", record.topic, e))
+        })?,
+        None => Vec::new(),
+    };
+    Ok(BridgeRecord {
+        subject: record.topic,
+        payload,
+    })
+}
+
+fn produce_body(payload: &[u8]) -> Value {
+    json!({ "records": [{ "value": BASE64.encode(payload) }] })
+}
+
+#[async_trait]
+impl BridgeTransport for KafkaTransport {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<()> {
+        self.send(
+            self.request(
+                reqwest::Method::POST,
+                self.url(&format!("/topics/{}", subject)),
+            )
+            .header("Content-Type", BINARY_V2)
+            .header("Accept", KAFKA_V2)
+            .json(&produce_body(&payload)),
+            "produce",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<BridgeStream> {
+        let consumer = self.create_consumer(subject).await?;
+        let interval = Duration::from_millis(self.config.poll_interval_ms);
+
+        let stream = async_stream::stream! {
+            loop {
+                match consumer.poll().await {
+                    Ok(records) if records.is_empty() => tokio::time::sleep(interval).await,
+                    Ok(records) => {
+                        for record in records {
+                            yield Ok(record);
+                        }
+                    }
+                    Err(e) => {
+                        yield Err(e);
+                        tokio::time::sleep(interval).await;
+                    }
+                }
+            }
+        };
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_payloads_round_trip() {
+        let body = produce_body(b"{\"a\":1}");
+        let value = body["records"][0]["value"].as_str().unwrap().to_string();
+
+        let record = decode_record(ConsumedRecord {
+            topic: "locai.alerts".to_string(),
+            value: Some(value),
+        })
+        .unwrap();
+        assert_eq!(record.subject, "locai.alerts");
+        assert_eq!(record.payload, b"{\"a\":1}");
+
+        let empty = decode_record(ConsumedRecord {
+            topic: "locai.alerts".to_string(),
+            value: None,
+        })
+        .unwrap();
+        assert!(empty.payload.is_empty());
+    }
+
+    #[test]
+    fn test_new_rejects_empty_group() {
+        let config = KafkaConfig {
+            consumer_group: String::new(),
+            ..KafkaConfig::default()
+        };
+        assert!(KafkaTransport::new(config).is_err());
+    }
+}
@@ -0,0 +1,431 @@
+//! Bridges between Locai messaging and external brokers
+//!
+//! A [`MessagingBridge`] mirrors Locai topics onto subjects of an external
+//! broker and republishes the broker's messages into Locai, so agent events
+//! can flow through existing event infrastructure. The broker is reached
+//! through a [`BridgeTransport`]; [NATS](nats) (`nats` feature) and
+//! [Kafka](kafka) (`kafka` feature) transports are included.
+//!
+//! Topics map to subjects by dropping the messaging namespace and adding
+//! [`BridgeConfig::subject_prefix`]: with the default prefix, `character.moved`
+//! in app `game` (`app:game.character.moved`) becomes `locai.character.moved`,
+//! and a broker message on `locai.character.moved` arrives back on
+//! `app:game.character.moved`.
+//!
+//! Outbound payloads are the JSON-encoded [`Message`]. Inbound payloads that
+//! decode as a [`Message`] keep their ID, sender and headers; anything else
+//! becomes the content of a new message (parsed as JSON when possible).
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use super::LocaiMessaging;
+use super::stream::MessageStream;
+use super::types::Message;
+use crate::{LocaiError, Result};
+
+/// Header marking a message that crossed a bridge
+///
+/// Outbound messages carry the ID of the bridge that published them, so the
+/// bridge can drop its own messages when they come back. Inbound messages
+/// carry the transport name and are not forwarded out again.
+pub const BRIDGE_ORIGIN_HEADER: &str = "x-locai-bridge";
+
+/// A message as carried by an external broker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeRecord {
+    /// Subject (NATS) or topic (Kafka) the message was published on
+    pub subject: String,
+
+    /// Raw message body
+    pub payload: Vec<u8>,
+}
+
+/// Stream of messages received from a broker
+pub type BridgeStream = Pin<Box<dyn Stream<Item = Result<BridgeRecord>> + Send>>;
+
+/// Connection to an external message broker
+#[async_trait]
+pub trait BridgeTransport: Send + Sync {
+    /// Name of the transport, used in logs and as the sender of inbound
+    /// messages that are not Locai messages
+    fn name(&self) -> &str;
+
+    /// Publish a payload on a subject
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<()>;
+
+    /// Receive messages published on a subject
+    ///
+    /// The subscription is active once this returns. Wildcards are passed
+    /// through, so their syntax is the broker's.
+    async fn subscribe(&self, subject: &str) -> Result<BridgeStream>;
+}
+
+/// What a [`MessagingBridge`] mirrors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BridgeConfig {
+    /// Locai topic patterns (without the namespace) to publish to the broker
+    pub outbound: Vec<String>,
+
+    /// Broker subjects to republish into Locai
+    pub inbound: Vec<String>,
+
+    /// Prefix added to a topic to form its subject, and removed again from
+    /// inbound subjects
+    pub subject_prefix: String,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            outbound: Vec::new(),
+            inbound: Vec::new(),
+            subject_prefix: "locai.".to_string(),
+        }
+    }
+}
+
+/// Message counts for a running bridge
+#[derive(Debug, Default)]
+pub struct BridgeStats {
+    published: AtomicU64,
+    received: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl BridgeStats {
+    /// Locai messages published to the broker
+    pub fn published(&self) -> u64 {
+        self.published.load(Ordering::Relaxed)
+    }
+
+    /// Broker messages republished into Locai
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    /// Messages that could not be forwarded in either direction
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+}
+
+/// Running bridge between Locai messaging and a broker
+///
+/// Forwarding stops when the bridge is stopped or dropped.
+#[derive(Debug)]
+pub struct MessagingBridge {
+    id: String,
+    stats: Arc<BridgeStats>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+/// Topic/subject mapping for one bridge
+#[derive(Debug, Clone)]
+struct SubjectMap {
+    namespace: String,
+    prefix: String,
+}
+
+impl SubjectMap {
+    fn subject_for(&self, topic: &str) -> String {
+        let local = topic
+            .strip_prefix(&self.namespace)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .unwrap_or(topic);
+        format!("{}{}", self.prefix, local)
+    }
+
+    fn topic_for(&self, subject: &str) -> String {
+        let local = subject.strip_prefix(&self.prefix).unwrap_or(subject);
+        format!("{}.{}", self.namespace, local)
+    }
+}
+
+impl MessagingBridge {
+    /// Subscribe on both sides and start forwarding
+    pub async fn start(
+        messaging: Arc<LocaiMessaging>,
+        transport: Arc<dyn BridgeTransport>,
+        config: BridgeConfig,
+    ) -> Result<Self> {
+        if config.outbound.is_empty() && config.inbound.is_empty() {
+            return Err(LocaiError::Configuration(
+                "Messaging bridge needs at least one outbound or inbound topic".to_string(),
+            ));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let stats = Arc::new(BridgeStats::default());
+        let subjects = SubjectMap {
+            namespace: messaging.namespace().to_string(),
+            prefix: config.subject_prefix,
+        };
+
+        // Subscribe everything before spawning so nothing sent after start()
+        // returns is missed
+        let mut outbound = Vec::with_capacity(config.outbound.len());
+        for pattern in &config.outbound {
+            outbound.push(messaging.subscribe(pattern).await?);
+        }
+        let mut inbound = Vec::with_capacity(config.inbound.len());
+        for subject in &config.inbound {
+            inbound.push(transport.subscribe(subject).await?);
+        }
+
+        let mut tasks = Vec::with_capacity(outbound.len() + inbound.len());
+        for stream in outbound {
+            tasks.push(tokio::spawn(forward_outbound(
+                stream,
+                transport.clone(),
+                subjects.clone(),
+                id.clone(),
+                stats.clone(),
+            )));
+        }
+        for stream in inbound {
+            tasks.push(tokio::spawn(forward_inbound(
+                stream,
+                messaging.clone(),
+                transport.clone(),
+                subjects.clone(),
+                id.clone(),
+                stats.clone(),
+            )));
+        }
+
+        tracing::info!(
+            "Started messaging bridge over {} ({} outbound, {} inbound)",
+            transport.name(),
+            config.outbound.len(),
+            config.inbound.len()
+        );
+        Ok(Self { id, stats, tasks })
+    }
+
+    /// ID stamped in [`BRIDGE_ORIGIN_HEADER`] on published messages
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Message counts
+    pub fn stats(&self) -> &BridgeStats {
+        &self.stats
+    }
+
+    /// Stop forwarding
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for MessagingBridge {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn forward_outbound(
+    mut stream: MessageStream,
+    transport: Arc<dyn BridgeTransport>,
+    subjects: SubjectMap,
+    bridge_id: String,
+    stats: Arc<BridgeStats>,
+) {
+    while let Some(result) = stream.next().await {
+        let mut message = match result {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("Messaging bridge subscription error: {}", e);
+                stats.errors.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        };
+        if message.headers.contains_key(BRIDGE_ORIGIN_HEADER) {
+            continue;
+        }
+
+        let subject = subjects.subject_for(&message.topic);
+        message
+            .headers
+            .insert(BRIDGE_ORIGIN_HEADER.to_string(), bridge_id.clone());
+        let published = match serde_json::to_vec(&message) {
+            Ok(payload) => transport.publish(&subject, payload).await,
+            Err(e) => Err(LocaiError::Other(format!(
+                "Failed to serialize message: {}",
+                e
+            ))),
+        };
+        match published {
+            Ok(()) => {
+                stats.published.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to publish message {} to {} on {}: {}",
+                    message.id,
+                    transport.name(),
+                    subject,
+                    e
+                );
+                stats.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+    tracing::debug!("Messaging bridge outbound subscription ended");
+}
+
+async fn forward_inbound(
+    mut stream: BridgeStream,
+    messaging: Arc<LocaiMessaging>,
+    transport: Arc<dyn BridgeTransport>,
+    subjects: SubjectMap,
+    bridge_id: String,
+    stats: Arc<BridgeStats>,
+) {
+    while let Some(result) = stream.next().await {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                tracing::warn!("{} subscription error: {}", transport.name(), e);
+                stats.errors.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        };
+        let Some(message) = inbound_message(&record, &subjects, &bridge_id, transport.name())
+        else {
+            continue;
+        };
+
+        match messaging.send_with_options(message).await {
+            Ok(_) => {
+                stats.received.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to republish {} message from {}: {}",
+                    transport.name(),
+                    record.subject,
+                    e
+                );
+                stats.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+    tracing::debug!("{} inbound subscription ended", transport.name());
+}
+
+/// Build the Locai message for a broker record, or `None` if this bridge
+/// published it
+fn inbound_message(
+    record: &BridgeRecord,
+    subjects: &SubjectMap,
+    bridge_id: &str,
+    transport_name: &str,
+) -> Option<Message> {
+    let topic = subjects.topic_for(&record.subject);
+    let mut message = match serde_json::from_slice::<Message>(&record.payload) {
+        Ok(message) => {
+            if message
+                .get_header(BRIDGE_ORIGIN_HEADER)
+                .is_some_and(|origin| origin == bridge_id)
+            {
+                return None;
+            }
+            Message { topic, ..message }
+        }
+        Err(_) => {
+            let content = serde_json::from_slice(&record.payload).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&record.payload).into_owned())
+            });
+            Message::new(topic, transport_name.to_string(), content)
+        }
+    };
+    message
+        .headers
+        .insert(BRIDGE_ORIGIN_HEADER.to_string(), transport_name.to_string());
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subjects() -> SubjectMap {
+        SubjectMap {
+            namespace: "app:game".to_string(),
+            prefix: "locai.".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_subject_mapping_round_trips() {
+        let subjects = subjects();
+        assert_eq!(
+            subjects.subject_for("app:game.character.moved"),
+            "locai.character.moved"
+        );
+        assert_eq!(
+            subjects.topic_for("locai.character.moved"),
+            "app:game.character.moved"
+        );
+        // Subjects outside the prefix keep their full name
+        assert_eq!(
+            subjects.topic_for("orders.created"),
+            "app:game.orders.created"
+        );
+    }
+
+    #[test]
+    fn test_inbound_message_wraps_foreign_payloads() {
+        let record = BridgeRecord {
+            subject: "locai.alerts".to_string(),
+            payload: br#"{"level":"high"}"#.to_vec(),
+        };
+        let message = inbound_message(&record, &subjects(), "bridge-1", "nats").unwrap();
+        assert_eq!(message.topic, "app:game.alerts");
+        assert_eq!(message.sender, "nats");
+        assert_eq!(message.content["level"], "high");
+        assert_eq!(message.get_header(BRIDGE_ORIGIN_HEADER).unwrap(), "nats");
+
+        let record = BridgeRecord {
+            subject: "locai.alerts".to_string(),
+            payload: b"disk full".to_vec(),
+        };
+        let message = inbound_message(&record, &subjects(), "bridge-1", "nats").unwrap();
+        assert_eq!(message.content, "disk full");
+    }
+
+    #[test]
+    fn test_inbound_message_drops_own_echo() {
+        let message = Message::new(
+            "app:game.alerts".to_string(),
+            "game".to_string(),
+            serde_json::json!({}),
+        )
+        .add_header(BRIDGE_ORIGIN_HEADER, "bridge-1");
+        let record = BridgeRecord {
+            subject: "locai.alerts".to_string(),
+            payload: serde_json::to_vec(&message).unwrap(),
+        };
+        assert!(inbound_message(&record, &subjects(), "bridge-1", "nats").is_none());
+
+        let relayed = inbound_message(&record, &subjects(), "bridge-2", "nats").unwrap();
+        assert_eq!(relayed.id, message.id);
+        assert_eq!(relayed.sender, "game");
+    }
+}
@@ -0,0 +1,153 @@
+//! Message consumers with retry and dead-lettering
+//!
+//! [`LocaiMessaging::consume`] runs a handler for every message on a topic
+//! pattern. A handler error is retried with exponential backoff up to the
+//! messaging instance's [`DeadLetterPolicy::max_attempts`]; after that the
+//! message goes to the dead letter queue instead of being dropped.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures::StreamExt;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::LocaiMessaging;
+use super::dead_letter::{DeadLetterPolicy, DeadLetterReason, is_dead_letter};
+use super::types::Message;
+use crate::Result;
+
+/// Counters for a running consumer
+#[derive(Debug, Default)]
+pub struct ConsumerStats {
+    handled: AtomicU64,
+    retried: AtomicU64,
+    dead_lettered: AtomicU64,
+}
+
+impl ConsumerStats {
+    /// Messages the handler accepted
+    pub fn handled(&self) -> u64 {
+        self.handled.load(Ordering::Relaxed)
+    }
+
+    /// Handler attempts that failed and were retried
+    pub fn retried(&self) -> u64 {
+        self.retried.load(Ordering::Relaxed)
+    }
+
+    /// Messages sent to the dead letter queue
+    pub fn dead_lettered(&self) -> u64 {
+        self.dead_lettered.load(Ordering::Relaxed)
+    }
+}
+
+/// Handle to a running consumer; the consumer stops when it is dropped
+#[derive(Debug)]
+pub struct Consumer {
+    stats: Arc<ConsumerStats>,
+    task: JoinHandle<()>,
+}
+
+impl Consumer {
+    pub(crate) async fn start<F, Fut>(
+        messaging: Arc<LocaiMessaging>,
+        topic_pattern: &str,
+        handler: F,
+    ) -> Result<Self>
+    where
+        F: Fn(Message) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send,
+    {
+        let mut messages = messaging.subscribe(topic_pattern).await?;
+        let stats = Arc::new(ConsumerStats::default());
+        let task_stats = stats.clone();
+
+        let task = tokio::spawn(async move {
+            let policy = messaging.dead_letter_policy().clone();
+            while let Some(result) = messages.next().await {
+                match result {
+                    // Dead letters are never dead-lettered again, so a
+                    // consumer on a wildcard covering `dlq` cannot loop
+                    Ok(message) if is_dead_letter(&message) => {}
+                    Ok(message) => {
+                        handle(&messaging, &policy, &handler, &task_stats, message).await
+                    }
+                    Err(e) => warn!("Consumer stream error: {}", e),
+                }
+            }
+            debug!("Consumer stream ended");
+        });
+
+        Ok(Self { stats, task })
+    }
+
+    /// Consumer counters
+    pub fn stats(&self) -> &ConsumerStats {
+        &self.stats
+    }
+
+    /// Stop consuming
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Consumer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle<F, Fut>(
+    messaging: &LocaiMessaging,
+    policy: &DeadLetterPolicy,
+    handler: &F,
+    stats: &ConsumerStats,
+    message: Message,
+) where
+    F: Fn(Message) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match handler(message.clone()).await {
+            Ok(()) => {
+                stats.handled.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            Err(e) if attempt >= max_attempts => {
+                warn!(
+                    "Message {} on {} failed {} times, dead-lettering: {}",
+                    message.id, message.topic, attempt, e
+                );
+                match messaging
+                    .dead_letter(
+                        &message,
+                        DeadLetterReason::HandlerFailed,
+                        &e.to_string(),
+                        attempt,
+                    )
+                    .await
+                {
+                    Ok(_) => {
+                        stats.dead_lettered.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(e) => warn!("Failed to dead-letter message {}: {}", message.id, e),
+                }
+                return;
+            }
+            Err(e) => {
+                debug!(
+                    "Handler failed on message {} (attempt {}): {}",
+                    message.id, attempt, e
+                );
+                stats.retried.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(policy.backoff(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! Dead letter queue for messages that cannot be delivered
+//!
+//! A message is dead-lettered when a [`consume`](super::LocaiMessaging::consume)
+//! handler keeps failing on it, or when it is rejected at send time for being
+//! too large or having a malformed topic. Dead letters are ordinary messages on
+//! the `dlq` topic of the sender's namespace, tagged `dead-letter`, with the
+//! failure recorded in `x-dlq-*` headers, so they can be subscribed to like
+//! any other topic and listed with
+//! [`get_dead_letters`](super::LocaiMessaging::get_dead_letters).
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::types::{Message, MessageFilter};
+use crate::core::MemoryManager;
+use crate::{LocaiError, Result};
+
+/// Topic (within a namespace) that dead letters are sent to
+pub const DEAD_LETTER_TOPIC: &str = "dlq";
+
+/// Tag carried by every dead letter
+pub const DEAD_LETTER_TAG: &str = "dead-letter";
+
+const HEADER_ORIGINAL_TOPIC: &str = "x-dlq-original-topic";
+const HEADER_ORIGINAL_ID: &str = "x-dlq-original-id";
+const HEADER_REASON: &str = "x-dlq-reason";
+const HEADER_ERROR: &str = "x-dlq-error";
+const HEADER_ATTEMPTS: &str = "x-dlq-attempts";
+const HEADER_FAILED_AT: &str = "x-dlq-failed-at";
+
+/// Why a message was dead-lettered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLetterReason {
+    /// A consumer handler failed on every attempt
+    HandlerFailed,
+    /// The serialized content exceeded the size limit; the content is dropped
+    TooLarge,
+    /// The message failed format validation
+    InvalidFormat,
+}
+
+impl DeadLetterReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::HandlerFailed => "handler_failed",
+            Self::TooLarge => "too_large",
+            Self::InvalidFormat => "invalid_format",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "handler_failed" => Some(Self::HandlerFailed),
+            "too_large" => Some(Self::TooLarge),
+            "invalid_format" => Some(Self::InvalidFormat),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DeadLetterReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Limits and retry behaviour that decide when a message is dead-lettered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeadLetterPolicy {
+    /// Handler attempts per message before it is dead-lettered
+    pub max_attempts: u32,
+
+    /// Delay before the first retry; doubles with each further attempt
+    #[serde(with = "humantime_serde")]
+    pub retry_delay: Duration,
+
+    /// Largest serialized content accepted by `send`, in bytes
+    pub max_message_size: usize,
+}
+
+impl Default for DeadLetterPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_delay: Duration::from_millis(100),
+            max_message_size: 1024 * 1024,
+        }
+    }
+}
+
+impl DeadLetterPolicy {
+    /// Delay before the given retry (1 = first retry)
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.retry_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+
+    /// Check a message before it is sent
+    pub(crate) fn validate(&self, message: &Message) -> std::result::Result<(), Rejection> {
+        if message.topic.is_empty()
+            || message.topic.ends_with('.')
+            || message.topic.chars().any(char::is_whitespace)
+        {
+            return Err(Rejection {
+                reason: DeadLetterReason::InvalidFormat,
+                error: format!("Invalid topic '{}'", message.topic),
+            });
+        }
+        let size = serde_json::to_vec(&message.content)
+            .map(|bytes| bytes.len())
+            .unwrap_or(usize::MAX);
+        if size > self.max_message_size {
+            return Err(Rejection {
+                reason: DeadLetterReason::TooLarge,
+                error: format!(
+                    "Message content is {} bytes, limit is {}",
+                    size, self.max_message_size
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A message rejected by [`DeadLetterPolicy::validate`]
+#[derive(Debug, Clone)]
+pub(crate) struct Rejection {
+    pub reason: DeadLetterReason,
+    pub error: String,
+}
+
+/// A dead-lettered message with its failure metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// The message as stored on the dead letter topic
+    pub message: Message,
+
+    /// Topic the message was originally sent to
+    pub original_topic: String,
+
+    /// ID of the original message
+    pub original_id: String,
+
+    /// Why it was dead-lettered
+    pub reason: DeadLetterReason,
+
+    /// Last error seen
+    pub error: String,
+
+    /// Handler attempts made (0 for messages rejected at send time)
+    pub attempts: u32,
+
+    /// When it was dead-lettered
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    /// Read the failure metadata back from a dead letter message
+    pub fn from_message(message: Message) -> Option<Self> {
+        let reason = DeadLetterReason::parse(message.get_header(HEADER_REASON)?)?;
+        Some(Self {
+            original_topic: message.get_header(HEADER_ORIGINAL_TOPIC)?.clone(),
+            original_id: message
+                .get_header(HEADER_ORIGINAL_ID)
+                .cloned()
+                .unwrap_or_default(),
+            reason,
+            error: message
+                .get_header(HEADER_ERROR)
+                .cloned()
+                .unwrap_or_default(),
+            attempts: message
+                .get_header(HEADER_ATTEMPTS)
+                .and_then(|attempts| attempts.parse().ok())
+                .unwrap_or_default(),
+            failed_at: message
+                .get_header(HEADER_FAILED_AT)
+                .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                .map(|at| at.with_timezone(&Utc))
+                .unwrap_or(message.timestamp),
+            message,
+        })
+    }
+}
+
+/// Build the dead letter for a failed message
+///
+/// The dead letter keeps the sender, recipients, headers and tags of the
+/// original, and its content unless the content was the problem.
+pub(crate) fn dead_letter_message(
+    namespace: &str,
+    original: &Message,
+    reason: DeadLetterReason,
+    error: &str,
+    attempts: u32,
+) -> Message {
+    let content = match reason {
+        DeadLetterReason::TooLarge => serde_json::Value::Null,
+        _ => original.content.clone(),
+    };
+    let mut message = Message::new(
+        format!("{}.{}", namespace, DEAD_LETTER_TOPIC),
+        original.sender.clone(),
+        content,
+    )
+    .add_recipients(original.recipients.clone())
+    .add_tags(original.tags.clone())
+    .add_tag(DEAD_LETTER_TAG);
+    message.headers = original.headers.clone();
+    message.importance = original.importance;
+    message
+        .add_header(HEADER_ORIGINAL_TOPIC, original.topic.clone())
+        .add_header(HEADER_ORIGINAL_ID, original.id.to_string())
+        .add_header(HEADER_REASON, reason.as_str())
+        .add_header(HEADER_ERROR, error)
+        .add_header(HEADER_ATTEMPTS, attempts.to_string())
+        .add_header(HEADER_FAILED_AT, Utc::now().to_rfc3339())
+}
+
+/// Whether a message is itself a dead letter
+pub(crate) fn is_dead_letter(message: &Message) -> bool {
+    message.has_tag(DEAD_LETTER_TAG)
+}
+
+/// Filter selecting dead letters, optionally for one namespace
+pub(crate) fn dead_letter_filter(namespace: Option<&str>) -> MessageFilter {
+    MessageFilter {
+        topic_patterns: namespace
+            .map(|namespace| vec![format!("{}.{}", namespace, DEAD_LETTER_TOPIC)]),
+        tags: Some(vec![DEAD_LETTER_TAG.to_string()]),
+        include_expired: true,
+        ..MessageFilter::default()
+    }
+}
+
+/// List dead letters stored in a memory manager, most recent first
+///
+/// `app_id` restricts the list to one application's namespace. Used by tools
+/// that read the store directly rather than through [`super::LocaiMessaging`].
+pub async fn list_dead_letters(
+    memory_manager: &MemoryManager,
+    app_id: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<DeadLetter>> {
+    let namespace = app_id.map(|app_id| format!("app:{}", app_id));
+    let filter = dead_letter_filter(namespace.as_deref());
+    let messages = memory_manager
+        .get_message_history(&filter, None)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to read dead letters: {}", e)))?;
+    Ok(collect_dead_letters(messages, limit))
+}
+
+/// Parse dead letters out of message history, most recent first
+pub(crate) fn collect_dead_letters(
+    messages: Vec<Message>,
+    limit: Option<usize>,
+) -> Vec<DeadLetter> {
+    let mut letters: Vec<DeadLetter> = messages
+        .into_iter()
+        .filter_map(DeadLetter::from_message)
+        .collect();
+    letters.sort_by(|a, b| b.failed_at.cmp(&a.failed_at));
+    if let Some(limit) = limit {
+        letters.truncate(limit);
+    }
+    letters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_dead_letter_round_trips_metadata() {
+        let original = Message::new(
+            "app:game.character.moved".to_string(),
+            "game".to_string(),
+            json!({ "to": "tavern" }),
+        )
+        .add_header("trace", "t-1")
+        .add_tag("movement");
+        let letter = dead_letter_message(
+            "app:game",
+            &original,
+            DeadLetterReason::HandlerFailed,
+            "boom",
+            3,
+        );
+        assert_eq!(letter.topic, "app:game.dlq");
+        assert!(is_dead_letter(&letter));
+        assert_eq!(letter.get_header("trace").unwrap(), "t-1");
+
+        let parsed = DeadLetter::from_message(letter).unwrap();
+        assert_eq!(parsed.original_topic, "app:game.character.moved");
+        assert_eq!(parsed.original_id, original.id.to_string());
+        assert_eq!(parsed.reason, DeadLetterReason::HandlerFailed);
+        assert_eq!(parsed.error, "boom");
+        assert_eq!(parsed.attempts, 3);
+        assert_eq!(parsed.message.content["to"], "tavern");
+        assert!(parsed.message.has_tag("movement"));
+    }
+
+    #[test]
+    fn test_validate_rejects_large_and_malformed_messages() {
+        let policy = DeadLetterPolicy {
+            max_message_size: 16,
+            ..DeadLetterPolicy::default()
+        };
+        let ok = Message::new("app:a.t".to_string(), "a".to_string(), json!("hi"));
+        assert!(policy.validate(&ok).is_ok());
+
+        let large = Message::new(
+            "app:a.t".to_string(),
+            "a".to_string(),
+            json!("x".repeat(32)),
+        );
+        assert_eq!(
+            policy.validate(&large).unwrap_err().reason,
+            DeadLetterReason::TooLarge
+        );
+
+        let malformed = Message::new("app:a.bad topic".to_string(), "a".to_string(), json!(1));
+        assert_eq!(
+            policy.validate(&malformed).unwrap_err().reason,
+            DeadLetterReason::InvalidFormat
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let policy = DeadLetterPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    }
+}
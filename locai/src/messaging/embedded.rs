@@ -318,12 +318,17 @@ pub async fn get_message_history(
     let mut messages = Vec::new();
     for memory in memories {
         // Only process memories that are message type
-        let memory_type_str = memory.memory_type.to_string();
-        if memory_type_str.starts_with("msg:") {
+        if is_message_memory(&memory.memory_type) {
             match serde_json::from_str::<Message>(&memory.content) {
                 Ok(message) => {
-                    // Additional filtering for expired messages if needed
-                    if filter.as_ref().is_none_or(|f| f.include_expired) || !message.is_expired() {
+                    // Apply what the memory filter could not express
+                    let matches = match &filter {
+                        Some(filter) => {
+                            crate::messaging::stream::utils::matches_filter(&message, filter)
+                        }
+                        None => true,
+                    };
+                    if matches {
                         messages.push(message);
                     }
                 }
@@ -355,15 +360,19 @@ async fn create_message_stream_from_memory_manager(
         .subscribe_to_memory_changes(memory_filter)
         .await?;
 
-    // Convert memory events to message events
-    let message_stream = memory_stream.filter_map(|memory_result| async move {
-        match memory_result {
+    // Convert memory events to message events. Storing a message touches its
+    // memory more than once, so each message is yielded only the first time
+    let mut seen = lru::LruCache::new(SEEN_MESSAGES.try_into().expect("non-zero capacity"));
+    let message_stream = memory_stream.filter_map(move |memory_result| {
+        let result = match memory_result {
             Ok(memory) => {
                 // Only process memories that are message type
-                let memory_type_str = memory.memory_type.to_string();
-                if memory_type_str.starts_with("msg:") {
+                if is_message_memory(&memory.memory_type) {
                     match serde_json::from_str::<Message>(&memory.content) {
-                        Ok(message) => Some(Ok(message)),
+                        Ok(message) if seen.put(message.id.clone(), ()).is_none() => {
+                            Some(Ok(message))
+                        }
+                        Ok(_) => None,
                         Err(e) => {
                             warn!("Failed to deserialize message from memory: {}", e);
                             Some(Err(LocaiError::Storage(format!(
@@ -377,12 +386,21 @@ async fn create_message_stream_from_memory_manager(
                 }
             }
             Err(e) => Some(Err(e)),
-        }
+        };
+        async move { result }
     });
 
     Ok(Box::pin(message_stream))
 }
 
+/// Message IDs remembered per subscription to drop repeated change events
+const SEEN_MESSAGES: usize = 1024;
+
+/// Messages are stored as `msg:<topic>` custom memories
+fn is_message_memory(memory_type: &MemoryType) -> bool {
+    matches!(memory_type, MemoryType::Custom(name) if name.starts_with("msg:"))
+}
+
 /// Convert a database event to a message (if it's a message-related event)
 async fn convert_db_event_to_message(event: &DbEvent) -> Option<Message> {
     // Only process CREATE and UPDATE events for memory table
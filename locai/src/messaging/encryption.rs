@@ -0,0 +1,580 @@
+//! End-to-end encrypted message payloads
+//!
+//! Each application holds an X25519 [`AppKeyPair`] and announces its public
+//! key on the global [`KEY_TOPIC`]. [`LocaiMessaging::send_encrypted`](super::LocaiMessaging::send_encrypted)
+//! seals the content with a fresh ChaCha20-Poly1305 key and wraps that key
+//! once per recipient, under a key derived with HKDF-SHA256 from the X25519
+//! shared secret of sender and recipient. Storage, bridges and locai-server
+//! only ever see the [`EncryptedPayload`]; topic, sender and headers stay in
+//! the clear for routing, and are bound to the ciphertext so a payload can't
+//! be replayed under another topic or sender.
+//!
+//! Keys read from [`KEY_TOPIC`] are trusted on first use. An operator who can
+//! rewrite messages could announce their own key before the real one is
+//! seen; pin keys exchanged out of band with
+//! [`LocaiMessaging::trust_public_key`](super::LocaiMessaging::trust_public_key)
+//! to rule that out.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use base64::{Engine, engine::general_purpose};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::hkdf::{HKDF_SHA256, Salt};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Value, json};
+
+use super::LocaiMessaging;
+use super::types::{Message, MessageFilter, MessageId};
+use crate::{LocaiError, Result};
+
+/// Topic public keys are announced on (not namespaced by app)
+pub const KEY_TOPIC: &str = "system.keys";
+
+/// Header marking an encrypted message; the value names the algorithm
+pub const ENCRYPTION_HEADER: &str = "x-encryption";
+
+/// Algorithm identifier stored in [`ENCRYPTION_HEADER`]
+pub const ALGORITHM: &str = "x25519-hkdf-sha256-chacha20poly1305";
+
+/// HKDF info for per-recipient key wrapping
+const WRAP_INFO: &[u8] = b"locai-e2e-v1 key wrap";
+
+/// An application's X25519 public key, shown and serialized as base64
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublicKey([u8; 32]);
+
+impl PublicKey {
+    /// Wrap raw key bytes
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Raw key bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&general_purpose::STANDARD.encode(self.0))
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PublicKey({})", self)
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = LocaiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes = decode(s, "public key")?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| LocaiError::Other("Public key must be 32 bytes".to_string()))?;
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// An application's X25519 key pair
+///
+/// Persist [`secret_bytes`](Self::secret_bytes) somewhere only the
+/// application can read; anyone holding it can read messages sent to the app.
+#[derive(Clone)]
+pub struct AppKeyPair {
+    secret: [u8; 32],
+    public: PublicKey,
+}
+
+impl AppKeyPair {
+    /// Generate a new random key pair
+    pub fn generate() -> Result<Self> {
+        let mut secret = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut secret)
+            .map_err(|_| LocaiError::Other("Failed to generate key pair".to_string()))?;
+        Ok(Self::from_secret_bytes(secret))
+    }
+
+    /// Restore a key pair from its secret key
+    pub fn from_secret_bytes(secret: [u8; 32]) -> Self {
+        let public = PublicKey(MontgomeryPoint::mul_base_clamped(secret).to_bytes());
+        Self { secret, public }
+    }
+
+    /// The secret key, for persisting the key pair
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.secret
+    }
+
+    /// The public key to share with other applications
+    pub fn public_key(&self) -> PublicKey {
+        self.public
+    }
+
+    /// X25519 shared secret with a peer
+    fn shared_secret(&self, peer: &PublicKey) -> Result<[u8; 32]> {
+        let shared = MontgomeryPoint(peer.0).mul_clamped(self.secret).to_bytes();
+        // Low-order peer keys give an all-zero secret
+        if shared == [0u8; 32] {
+            return Err(LocaiError::Other("Invalid peer public key".to_string()));
+        }
+        Ok(shared)
+    }
+}
+
+impl fmt::Debug for AppKeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppKeyPair")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The content key, sealed for one recipient
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    /// Base64 nonce
+    pub nonce: String,
+    /// Base64 sealed content key
+    pub key: String,
+}
+
+/// Content of an encrypted message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedPayload {
+    /// Algorithm identifier, [`ALGORITHM`]
+    pub algorithm: String,
+    /// Public key of the sender
+    pub sender_key: PublicKey,
+    /// Base64 nonce for the content
+    pub nonce: String,
+    /// Base64 sealed JSON content
+    pub ciphertext: String,
+    /// Wrapped content key per recipient app ID
+    pub recipients: BTreeMap<String, WrappedKey>,
+}
+
+impl EncryptedPayload {
+    /// Seal `content` for `recipients`
+    ///
+    /// `topic` and `sender` must be the message's own; they are
+    /// authenticated along with the content.
+    pub fn seal(
+        keys: &AppKeyPair,
+        sender: &str,
+        topic: &str,
+        content: &Value,
+        recipients: &[(String, PublicKey)],
+    ) -> Result<Self> {
+        let rng = SystemRandom::new();
+        let content_key = random::<32>(&rng)?;
+        let nonce = random::<NONCE_LEN>(&rng)?;
+        let plaintext = serde_json::to_vec(content)
+            .map_err(|e| LocaiError::Other(format!("Failed to serialize content: {}", e)))?;
+        let ciphertext = seal(&content_key, nonce, &content_aad(topic, sender), plaintext)?;
+
+        let mut wrapped = BTreeMap::new();
+        for (app_id, public_key) in recipients {
+            let wrap_key = wrap_key(&keys.shared_secret(public_key)?, &nonce)?;
+            let wrap_nonce = random::<NONCE_LEN>(&rng)?;
+            let sealed_key = seal(
+                &wrap_key,
+                wrap_nonce,
+                &wrap_aad(sender, app_id),
+                content_key.to_vec(),
+            )?;
+            wrapped.insert(
+                app_id.clone(),
+                WrappedKey {
+                    nonce: encode(&wrap_nonce),
+                    key: encode(&sealed_key),
+                },
+            );
+        }
+
+        Ok(Self {
+            algorithm: ALGORITHM.to_string(),
+            sender_key: keys.public_key(),
+            nonce: encode(&nonce),
+            ciphertext: encode(&ciphertext),
+            recipients: wrapped,
+        })
+    }
+
+    /// Open the payload as `recipient`
+    ///
+    /// `sender_key` is the key the caller trusts for `sender`; a payload
+    /// sealed with any other key is rejected.
+    pub fn open(
+        &self,
+        keys: &AppKeyPair,
+        recipient: &str,
+        sender: &str,
+        topic: &str,
+        sender_key: &PublicKey,
+    ) -> Result<Value> {
+        if self.algorithm != ALGORITHM {
+            return Err(LocaiError::Other(format!(
+                "Unsupported encryption algorithm: {}",
+                self.algorithm
+            )));
+        }
+        if &self.sender_key != sender_key {
+            return Err(LocaiError::Authentication(format!(
+                "Message was not sealed with the known key of '{}'",
+                sender
+            )));
+        }
+        let wrapped = self.recipients.get(recipient).ok_or_else(|| {
+            LocaiError::Other(format!("Message is not encrypted for '{}'", recipient))
+        })?;
+
+        let content_nonce = nonce(&decode(&self.nonce, "nonce")?)?;
+        let wrap_key = wrap_key(&keys.shared_secret(sender_key)?, &content_nonce)?;
+        let content_key: [u8; 32] = open(
+            &wrap_key,
+            nonce(&decode(&wrapped.nonce, "nonce")?)?,
+            &wrap_aad(sender, recipient),
+            decode(&wrapped.key, "wrapped key")?,
+        )?
+        .try_into()
+        .map_err(|_| LocaiError::Other("Wrapped key has the wrong length".to_string()))?;
+
+        let plaintext = open(
+            &content_key,
+            content_nonce,
+            &content_aad(topic, sender),
+            decode(&self.ciphertext, "ciphertext")?,
+        )?;
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| LocaiError::Other(format!("Decrypted content is not JSON: {}", e)))
+    }
+}
+
+/// Whether a message carries an encrypted payload
+pub fn is_encrypted(message: &Message) -> bool {
+    message.get_header(ENCRYPTION_HEADER).is_some()
+}
+
+impl LocaiMessaging {
+    /// Enable end-to-end encryption with this application's key pair
+    pub fn with_encryption(mut self, keys: AppKeyPair) -> Self {
+        self.encryption = Some(keys);
+        self
+    }
+
+    /// This application's public key, if encryption is enabled
+    pub fn public_key(&self) -> Option<PublicKey> {
+        self.encryption.as_ref().map(AppKeyPair::public_key)
+    }
+
+    /// Announce this application's public key on [`KEY_TOPIC`]
+    pub async fn publish_public_key(&self) -> Result<MessageId> {
+        let public_key = self.keys()?.public_key();
+        let message = Message::new(
+            KEY_TOPIC.to_string(),
+            self.app_id.clone(),
+            json!({ "app_id": self.app_id, "public_key": public_key }),
+        );
+        self.send_with_options(message).await
+    }
+
+    /// Pin the public key of another application
+    ///
+    /// Pinned keys take precedence over announcements on [`KEY_TOPIC`].
+    pub fn trust_public_key(&self, app_id: &str, public_key: PublicKey) {
+        self.peer_keys
+            .write()
+            .expect("peer key lock poisoned")
+            .insert(app_id.to_string(), public_key);
+    }
+
+    /// Look up the public key of another application
+    ///
+    /// Uses a pinned key if there is one, otherwise the latest announcement
+    /// on [`KEY_TOPIC`], which is then pinned.
+    pub async fn peer_public_key(&self, app_id: &str) -> Result<PublicKey> {
+        if app_id == self.app_id {
+            return Ok(self.keys()?.public_key());
+        }
+        if let Some(key) = self
+            .peer_keys
+            .read()
+            .expect("peer key lock poisoned")
+            .get(app_id)
+        {
+            return Ok(*key);
+        }
+
+        let filter = MessageFilter {
+            topic_patterns: Some(vec![KEY_TOPIC.to_string()]),
+            source_app: Some(app_id.to_string()),
+            ..Default::default()
+        };
+        let announcement = self
+            .get_message_history(Some(filter), None)
+            .await?
+            .into_iter()
+            .filter(|message| message.sender == app_id)
+            .max_by_key(|message| message.timestamp)
+            .ok_or_else(|| {
+                LocaiError::Other(format!("No public key announced for '{}'", app_id))
+            })?;
+        let public_key: PublicKey =
+            serde_json::from_value(announcement.content["public_key"].clone()).map_err(|e| {
+                LocaiError::Other(format!(
+                    "Invalid public key announced for '{}': {}",
+                    app_id, e
+                ))
+            })?;
+
+        self.trust_public_key(app_id, public_key);
+        Ok(public_key)
+    }
+
+    /// Send a message whose content only `recipients` (and this app) can read
+    ///
+    /// # Arguments
+    /// * `topic` - Topic to send to, within this app's namespace
+    /// * `content` - Message content, encrypted before it leaves the process
+    /// * `recipients` - App IDs allowed to read the content
+    ///
+    /// # Returns
+    /// Message ID of the sent message
+    pub async fn send_encrypted(
+        &self,
+        topic: &str,
+        content: Value,
+        recipients: &[&str],
+    ) -> Result<MessageId> {
+        let keys = self.keys()?;
+        let topic = format!("{}.{}", self.namespace, topic);
+
+        let mut readers = vec![(self.app_id.clone(), keys.public_key())];
+        for app_id in recipients {
+            if *app_id != self.app_id {
+                readers.push((app_id.to_string(), self.peer_public_key(app_id).await?));
+            }
+        }
+        let payload = EncryptedPayload::seal(keys, &self.app_id, &topic, &content, &readers)?;
+
+        let mut message = Message::new(
+            topic,
+            self.app_id.clone(),
+            serde_json::to_value(payload).map_err(|e| {
+                LocaiError::Other(format!("Failed to serialize encrypted payload: {}", e))
+            })?,
+        )
+        .add_header(ENCRYPTION_HEADER, ALGORITHM);
+        message.recipients = recipients.iter().map(|r| r.to_string()).collect();
+        self.send_with_options(message).await
+    }
+
+    /// Decrypt a received message
+    ///
+    /// Messages that aren't encrypted are returned unchanged, so a mixed
+    /// stream can be passed through as is.
+    pub async fn decrypt(&self, message: &Message) -> Result<Message> {
+        if !is_encrypted(message) {
+            return Ok(message.clone());
+        }
+        let keys = self.keys()?;
+        let payload: EncryptedPayload = serde_json::from_value(message.content.clone())
+            .map_err(|e| LocaiError::Other(format!("Invalid encrypted payload: {}", e)))?;
+        let sender_key = self.peer_public_key(&message.sender).await?;
+        let content = payload.open(
+            keys,
+            &self.app_id,
+            &message.sender,
+            &message.topic,
+            &sender_key,
+        )?;
+
+        let mut decrypted = message.clone();
+        decrypted.content = content;
+        decrypted.headers.remove(ENCRYPTION_HEADER);
+        Ok(decrypted)
+    }
+
+    fn keys(&self) -> Result<&AppKeyPair> {
+        self.encryption
+            .as_ref()
+            .ok_or_else(|| LocaiError::Other("Encryption is not enabled for this app".to_string()))
+    }
+}
+
+fn content_aad(topic: &str, sender: &str) -> Vec<u8> {
+    format!("{}\n{}", topic, sender).into_bytes()
+}
+
+fn wrap_aad(sender: &str, recipient: &str) -> Vec<u8> {
+    format!("{}\n{}", sender, recipient).into_bytes()
+}
+
+/// Per-message key for wrapping the content key, salted with the content nonce
+fn wrap_key(shared_secret: &[u8; 32], salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Salt::new(HKDF_SHA256, salt)
+        .extract(shared_secret)
+        .expand(&[WRAP_INFO], &CHACHA20_POLY1305)
+        .and_then(|okm| okm.fill(&mut key))
+        .map_err(|_| LocaiError::Other("Failed to derive wrapping key".to_string()))?;
+    Ok(key)
+}
+
+fn seal(key: &[u8; 32], nonce: [u8; NONCE_LEN], aad: &[u8], mut data: Vec<u8>) -> Result<Vec<u8>> {
+    aead_key(key)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut data,
+        )
+        .map_err(|_| LocaiError::Other("Failed to encrypt message".to_string()))?;
+    Ok(data)
+}
+
+fn open(key: &[u8; 32], nonce: [u8; NONCE_LEN], aad: &[u8], mut data: Vec<u8>) -> Result<Vec<u8>> {
+    let plaintext_len = aead_key(key)?
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut data,
+        )
+        .map_err(|_| LocaiError::Other("Failed to decrypt message".to_string()))?
+        .len();
+    data.truncate(plaintext_len);
+    Ok(data)
+}
+
+fn aead_key(key: &[u8; 32]) -> Result<LessSafeKey> {
+    UnboundKey::new(&CHACHA20_POLY1305, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| LocaiError::Other("Invalid encryption key".to_string()))
+}
+
+fn random<const N: usize>(rng: &SystemRandom) -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    rng.fill(&mut bytes)
+        .map_err(|_| LocaiError::Other("Failed to generate random bytes".to_string()))?;
+    Ok(bytes)
+}
+
+fn nonce(bytes: &[u8]) -> Result<[u8; NONCE_LEN]> {
+    bytes
+        .try_into()
+        .map_err(|_| LocaiError::Other("Nonce has the wrong length".to_string()))
+}
+
+fn encode(bytes: &[u8]) -> String {
+    general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode(text: &str, what: &str) -> Result<Vec<u8>> {
+    general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| LocaiError::Other(format!("Invalid base64 {}: {}", what, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> AppKeyPair {
+        AppKeyPair::generate().unwrap()
+    }
+
+    #[test]
+    fn test_recipients_can_open_payload() {
+        let (alice, bob, carol) = (keys(), keys(), keys());
+        let content = json!({ "plan": "meet at dawn", "step": null });
+        let payload = EncryptedPayload::seal(
+            &alice,
+            "alice",
+            "app:alice.plans",
+            &content,
+            &[("bob".to_string(), bob.public_key())],
+        )
+        .unwrap();
+        assert!(!payload.ciphertext.contains("dawn"));
+
+        let opened = payload
+            .open(&bob, "bob", "alice", "app:alice.plans", &alice.public_key())
+            .unwrap();
+        assert_eq!(opened, content);
+
+        // Not a recipient
+        assert!(
+            payload
+                .open(
+                    &carol,
+                    "carol",
+                    "alice",
+                    "app:alice.plans",
+                    &alice.public_key()
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_payload_is_bound_to_topic_and_sender() {
+        let (alice, bob, mallory) = (keys(), keys(), keys());
+        let payload = EncryptedPayload::seal(
+            &alice,
+            "alice",
+            "app:alice.plans",
+            &json!("secret"),
+            &[("bob".to_string(), bob.public_key())],
+        )
+        .unwrap();
+
+        assert!(
+            payload
+                .open(&bob, "bob", "alice", "app:alice.other", &alice.public_key())
+                .is_err()
+        );
+        assert!(
+            payload
+                .open(
+                    &bob,
+                    "bob",
+                    "alice",
+                    "app:alice.plans",
+                    &mallory.public_key()
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_key_pair_round_trips() {
+        let pair = keys();
+        let restored = AppKeyPair::from_secret_bytes(pair.secret_bytes());
+        assert_eq!(restored.public_key(), pair.public_key());
+
+        let text = pair.public_key().to_string();
+        assert_eq!(text.parse::<PublicKey>().unwrap(), pair.public_key());
+        assert!(!format!("{:?}", pair).contains(&encode(&pair.secret_bytes())));
+    }
+}
@@ -119,24 +119,11 @@ pub fn convert_message_filter_to_memory_filter(
         }
     }
 
-    // Convert topic patterns to tag filters (simplified approach)
-    if let Some(patterns) = &filter.topic_patterns {
-        let tags: Vec<String> = patterns
-            .iter()
-            .filter_map(|pattern| {
-                // Convert patterns to tag searches
-                if pattern.contains('*') {
-                    // Extract the base part before the wildcard
-                    pattern.split('*').next().map(|s| s.to_string())
-                } else {
-                    Some(pattern.clone())
-                }
-            })
-            .collect();
-
-        if !tags.is_empty() {
-            memory_filter.tags = Some(tags);
-        }
+    // Memories only carry the topic base as a tag, so wildcard patterns
+    // cannot be pushed down; narrow to message memories and leave the
+    // pattern match to `matches_filter`
+    if filter.topic_patterns.is_some() {
+        memory_filter.tags = Some(vec!["message".to_string()]);
     }
 
     // Convert sender filter to source filter
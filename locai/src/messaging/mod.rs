@@ -11,23 +11,67 @@
 //! ## Remote Mode (Inter-Process)
 //! Enables true inter-process communication via WebSocket connections to locai-server,
 //! supporting distributed deployments and cross-application messaging.
+//!
+//! ## Bridges
+//! [`bridge::MessagingBridge`] mirrors topics to and from NATS or Kafka (behind
+//! the `nats` and `kafka` features) for integration with existing event
+//! infrastructure.
+//!
+//! ## Dead Letters
+//! Messages that fail validation on send, or whose [`consumer::Consumer`]
+//! handler keeps failing, are routed to the namespace's `dlq` topic with the
+//! failure recorded in headers; see [`dead_letter`].
+//!
+//! ## Retention
+//! Stored messages are kept until a [`crate::config::TopicRetentionPolicy`]
+//! in [`crate::config::MessagingConfig`] removes them by age, count or
+//! header-keyed compaction; see [`retention`].
+//!
+//! ## Presence
+//! Agents register their capabilities and heartbeat; [`LocaiMessaging::list_agents`]
+//! returns the ones online and changes are announced on
+//! [`presence::PRESENCE_TOPIC`].
+//!
+//! ## Reminders
+//! [`LocaiMessaging::start_reminders`] publishes memory reminders as they
+//! come due on [`reminders::REMINDER_TOPIC`].
+//!
+//! ## Encryption
+//! With an [`AppKeyPair`], [`LocaiMessaging::send_encrypted`] seals message
+//! content for specific apps so the store and server operator can't read it;
+//! see [`encryption`].
 
+pub mod bridge;
+pub mod consumer;
+pub mod dead_letter;
 pub mod embedded;
+pub mod encryption;
 pub mod filters;
+pub mod presence;
+pub mod reminders;
 pub mod remote;
+pub mod retention;
 pub mod stream;
 pub mod types;
 pub mod websocket;
 
+pub use consumer::{Consumer, ConsumerStats};
+pub use dead_letter::{DeadLetter, DeadLetterPolicy, DeadLetterReason};
 pub use embedded::EmbeddedMessaging;
+pub use encryption::{AppKeyPair, EncryptedPayload, PublicKey};
 pub use filters::TopicMatcher;
+pub use presence::{AgentInfo, AgentRegistration, Heartbeat, PresenceEvent};
+pub use reminders::{REMINDER_LEASE, REMINDER_TOPIC, ReminderScheduler};
 pub use remote::RemoteMessaging;
+pub use retention::RetentionReport;
 pub use stream::MessageStream;
 pub use types::{Message, MessageBuilder, MessageFilter, MessageId};
 pub use websocket::WebSocketClient;
 
 use crate::core::MemoryManager;
 use crate::{LocaiError, Result};
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
 /// Messaging mode configuration
@@ -55,6 +99,10 @@ pub struct LocaiMessaging {
     mode: MessagingMode,
     app_id: String,
     namespace: String,
+    dead_letters: DeadLetterPolicy,
+    encryption: Option<AppKeyPair>,
+    /// Public keys of other apps, pinned or first seen on the key topic
+    peer_keys: std::sync::RwLock<HashMap<String, PublicKey>>,
 }
 
 impl LocaiMessaging {
@@ -74,6 +122,9 @@ impl LocaiMessaging {
             mode: MessagingMode::Embedded { memory_manager },
             app_id: app_id.clone(),
             namespace: format!("app:{}", app_id),
+            dead_letters: DeadLetterPolicy::default(),
+            encryption: None,
+            peer_keys: Default::default(),
         })
     }
 
@@ -99,6 +150,9 @@ impl LocaiMessaging {
             },
             app_id: app_id.clone(),
             namespace: format!("app:{}", app_id),
+            dead_letters: DeadLetterPolicy::default(),
+            encryption: None,
+            peer_keys: Default::default(),
         })
     }
 
@@ -111,20 +165,23 @@ impl LocaiMessaging {
     /// # Returns
     /// Message ID of the sent message
     pub async fn send(&self, topic: &str, content: serde_json::Value) -> Result<MessageId> {
+        let message = Message::new(
+            format!("{}.{}", self.namespace, topic),
+            self.app_id.clone(),
+            content,
+        );
+        self.check_or_dead_letter(&message).await?;
+
         match &self.mode {
             MessagingMode::Embedded { memory_manager } => {
-                embedded::send_message(
-                    memory_manager,
-                    &self.namespace,
-                    &self.app_id,
-                    topic,
-                    content,
-                )
-                .await
+                embedded::send_complete_message(memory_manager, message).await
             }
             MessagingMode::Remote {
                 websocket_client, ..
-            } => self.send_remote(websocket_client, topic, content).await,
+            } => {
+                self.send_remote(websocket_client, topic, message.content)
+                    .await
+            }
         }
     }
 
@@ -179,6 +236,11 @@ impl LocaiMessaging {
     /// # Returns
     /// Message ID of the sent message
     pub async fn send_with_options(&self, message: Message) -> Result<MessageId> {
+        self.check_or_dead_letter(&message).await?;
+        self.deliver(message).await
+    }
+
+    async fn deliver(&self, message: Message) -> Result<MessageId> {
         match &self.mode {
             MessagingMode::Embedded { memory_manager } => {
                 embedded::send_complete_message(memory_manager, message).await
@@ -258,6 +320,97 @@ impl LocaiMessaging {
         }
     }
 
+    /// Set the policy deciding when messages are dead-lettered
+    pub fn with_dead_letter_policy(mut self, policy: DeadLetterPolicy) -> Self {
+        self.dead_letters = policy;
+        self
+    }
+
+    /// Policy deciding when messages are dead-lettered
+    pub fn dead_letter_policy(&self) -> &DeadLetterPolicy {
+        &self.dead_letters
+    }
+
+    /// Run a handler for every message matching a topic pattern
+    ///
+    /// Failed messages are retried per the dead letter policy and then sent
+    /// to the dead letter queue. The consumer runs until the returned handle
+    /// is dropped.
+    ///
+    /// # Arguments
+    /// * `topic_pattern` - Pattern to match topics (supports wildcards like "character.*")
+    /// * `handler` - Called once per attempt; an `Err` counts as a failure
+    ///
+    /// # Returns
+    /// Handle to the running consumer
+    pub async fn consume<F, Fut>(
+        self: &Arc<Self>,
+        topic_pattern: &str,
+        handler: F,
+    ) -> Result<Consumer>
+    where
+        F: Fn(Message) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send,
+    {
+        Consumer::start(self.clone(), topic_pattern, handler).await
+    }
+
+    /// Route a message to this namespace's dead letter queue
+    ///
+    /// # Arguments
+    /// * `message` - The message that could not be processed
+    /// * `reason` - Why it is being dead-lettered
+    /// * `error` - Description of the last failure
+    /// * `attempts` - Processing attempts made
+    ///
+    /// # Returns
+    /// Message ID of the dead letter
+    pub async fn dead_letter(
+        &self,
+        message: &Message,
+        reason: DeadLetterReason,
+        error: &str,
+        attempts: u32,
+    ) -> Result<MessageId> {
+        let letter =
+            dead_letter::dead_letter_message(&self.namespace, message, reason, error, attempts);
+        self.deliver(letter).await
+    }
+
+    /// Get dead letters for this namespace, most recent first
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of dead letters to return
+    ///
+    /// # Returns
+    /// Dead letters with their failure metadata
+    pub async fn get_dead_letters(&self, limit: Option<usize>) -> Result<Vec<DeadLetter>> {
+        let filter = dead_letter::dead_letter_filter(Some(&self.namespace));
+        let messages = self.get_message_history(Some(filter), None).await?;
+        Ok(dead_letter::collect_dead_letters(messages, limit))
+    }
+
+    /// Validate an outgoing message, dead-lettering it if it is rejected
+    async fn check_or_dead_letter(&self, message: &Message) -> Result<()> {
+        let Err(rejection) = self.dead_letters.validate(message) else {
+            return Ok(());
+        };
+        if let Err(e) = self
+            .dead_letter(message, rejection.reason, &rejection.error, 0)
+            .await
+        {
+            tracing::warn!(
+                "Failed to dead-letter rejected message {}: {}",
+                message.id,
+                e
+            );
+        }
+        Err(LocaiError::Other(format!(
+            "Message rejected ({}): {}",
+            rejection.reason, rejection.error
+        )))
+    }
+
     /// Get the application ID for this messaging instance
     pub fn app_id(&self) -> &str {
         &self.app_id
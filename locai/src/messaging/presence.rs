@@ -0,0 +1,482 @@
+//! Agent registry and presence
+//!
+//! Agents register with [`LocaiMessaging::register_agent`](super::LocaiMessaging::register_agent),
+//! advertising capabilities and free-form metadata, and then heartbeat to stay
+//! online. Each agent is stored as an `agent` entity (`agent:<app_id>`), so
+//! every instance sharing the store sees the same registry. An agent whose
+//! last heartbeat is older than its TTL counts as offline.
+//!
+//! Presence changes are published as [`PresenceEvent`] messages on the
+//! global [`PRESENCE_TOPIC`]; subscribe with
+//! [`LocaiMessaging::subscribe_presence`](super::LocaiMessaging::subscribe_presence).
+//! Timeouts are noticed lazily, by `list_agents` or a running [`Heartbeat`],
+//! so an offline event may trail the missed heartbeat by up to one interval.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::task::JoinHandle;
+
+use super::LocaiMessaging;
+use super::types::Message;
+use crate::core::MemoryManager;
+use crate::storage::filters::EntityFilter;
+use crate::storage::models::Entity;
+use crate::{LocaiError, Result};
+
+/// Topic presence events are published on (not namespaced by app)
+pub const PRESENCE_TOPIC: &str = "system.presence";
+
+/// Entity type used for registered agents
+pub const AGENT_ENTITY_TYPE: &str = "agent";
+
+/// Default time after the last heartbeat before an agent counts as offline
+pub const DEFAULT_HEARTBEAT_TTL: Duration = Duration::from_secs(30);
+
+/// What an agent advertises when it registers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRegistration {
+    /// Capabilities other agents can look for, e.g. `"summarize"`
+    pub capabilities: Vec<String>,
+
+    /// Free-form metadata (version, model, owner, ...)
+    pub metadata: Value,
+
+    /// Time after the last heartbeat before the agent counts as offline
+    #[serde(with = "humantime_serde")]
+    pub heartbeat_ttl: Duration,
+}
+
+impl Default for AgentRegistration {
+    fn default() -> Self {
+        Self {
+            capabilities: Vec::new(),
+            metadata: Value::Null,
+            heartbeat_ttl: DEFAULT_HEARTBEAT_TTL,
+        }
+    }
+}
+
+impl AgentRegistration {
+    /// Registration with the given capabilities
+    pub fn new<I, S>(capabilities: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            capabilities: capabilities.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Attach metadata
+    pub fn metadata(mut self, metadata: Value) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Set the heartbeat TTL
+    pub fn heartbeat_ttl(mut self, ttl: Duration) -> Self {
+        self.heartbeat_ttl = ttl;
+        self
+    }
+}
+
+/// A registered agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentInfo {
+    /// Application ID of the agent
+    pub app_id: String,
+
+    /// Advertised capabilities
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+
+    /// Advertised metadata
+    #[serde(default)]
+    pub metadata: Value,
+
+    /// When the agent first registered
+    pub registered_at: DateTime<Utc>,
+
+    /// When the agent last heartbeated
+    pub last_heartbeat: DateTime<Utc>,
+
+    /// Heartbeat TTL in seconds
+    pub heartbeat_ttl_secs: u64,
+
+    /// Whether the agent is online
+    pub online: bool,
+}
+
+impl AgentInfo {
+    /// Whether the agent advertises a capability
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    fn is_alive(&self, now: DateTime<Utc>) -> bool {
+        self.online
+            && now - self.last_heartbeat
+                <= chrono::Duration::seconds(self.heartbeat_ttl_secs as i64)
+    }
+
+    fn from_entity(entity: &Entity) -> Option<Self> {
+        serde_json::from_value(entity.properties.clone()).ok()
+    }
+
+    fn to_entity(&self) -> Entity {
+        Entity {
+            id: agent_entity_id(&self.app_id),
+            entity_type: AGENT_ENTITY_TYPE.to_string(),
+            properties: serde_json::to_value(self).unwrap_or_default(),
+            created_at: self.registered_at,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Presence status carried by a [`PresenceEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    /// The agent is heartbeating
+    Online,
+    /// The agent deregistered or timed out
+    Offline,
+}
+
+/// Why presence changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceReason {
+    /// The agent registered (or re-registered)
+    Registered,
+    /// The agent deregistered
+    Deregistered,
+    /// The agent stopped heartbeating
+    HeartbeatTimeout,
+    /// A timed-out agent heartbeated again
+    HeartbeatResumed,
+}
+
+/// Content of a message on [`PRESENCE_TOPIC`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresenceEvent {
+    /// Agent whose presence changed
+    pub app_id: String,
+    /// New status
+    pub status: PresenceStatus,
+    /// Why it changed
+    pub reason: PresenceReason,
+    /// Capabilities of the agent
+    pub capabilities: Vec<String>,
+    /// When the change was detected
+    pub timestamp: DateTime<Utc>,
+}
+
+impl PresenceEvent {
+    fn new(agent: &AgentInfo, status: PresenceStatus, reason: PresenceReason) -> Self {
+        Self {
+            app_id: agent.app_id.clone(),
+            status,
+            reason,
+            capabilities: agent.capabilities.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Parse a presence event from a message on [`PRESENCE_TOPIC`]
+    pub fn from_message(message: &Message) -> Option<Self> {
+        if message.topic != PRESENCE_TOPIC {
+            return None;
+        }
+        serde_json::from_value(message.content.clone()).ok()
+    }
+
+    fn into_message(self, sender: &str) -> Message {
+        Message::new(PRESENCE_TOPIC.to_string(), sender.to_string(), json!(self))
+            .add_tag("presence")
+    }
+}
+
+fn agent_entity_id(app_id: &str) -> String {
+    format!("agent:{}", app_id)
+}
+
+/// Handle to a background heartbeat; heartbeating stops when it is dropped
+#[derive(Debug)]
+pub struct Heartbeat {
+    task: JoinHandle<()>,
+}
+
+impl Heartbeat {
+    /// Stop heartbeating; the agent times out unless it deregisters
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl LocaiMessaging {
+    /// Register this application as an agent and announce it online
+    ///
+    /// Registering again replaces the capabilities and metadata.
+    ///
+    /// # Arguments
+    /// * `registration` - Capabilities, metadata and heartbeat TTL
+    ///
+    /// # Returns
+    /// The stored agent record
+    pub async fn register_agent(&self, registration: AgentRegistration) -> Result<AgentInfo> {
+        let memory_manager = self.registry()?;
+        let now = Utc::now();
+        let existing = load_agent(memory_manager, self.app_id()).await?;
+        let agent = AgentInfo {
+            app_id: self.app_id().to_string(),
+            capabilities: registration.capabilities,
+            metadata: registration.metadata,
+            registered_at: existing.as_ref().map_or(now, |a| a.registered_at),
+            last_heartbeat: now,
+            heartbeat_ttl_secs: registration.heartbeat_ttl.as_secs().max(1),
+            online: true,
+        };
+        store_agent(memory_manager, &agent, existing.is_some()).await?;
+        self.publish_presence(&agent, PresenceStatus::Online, PresenceReason::Registered)
+            .await?;
+        Ok(agent)
+    }
+
+    /// Remove this application from the registry and announce it offline
+    ///
+    /// # Returns
+    /// Whether the agent was registered
+    pub async fn deregister_agent(&self) -> Result<bool> {
+        let memory_manager = self.registry()?;
+        let Some(agent) = load_agent(memory_manager, self.app_id()).await? else {
+            return Ok(false);
+        };
+        memory_manager
+            .delete_entity(&agent_entity_id(self.app_id()))
+            .await?;
+        self.publish_presence(
+            &agent,
+            PresenceStatus::Offline,
+            PresenceReason::Deregistered,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Record a heartbeat for this application's agent
+    ///
+    /// An agent that had timed out is announced online again.
+    pub async fn heartbeat(&self) -> Result<()> {
+        let memory_manager = self.registry()?;
+        let mut agent = load_agent(memory_manager, self.app_id())
+            .await?
+            .ok_or_else(|| {
+                LocaiError::Other(format!("Agent '{}' is not registered", self.app_id()))
+            })?;
+        let resumed = !agent.is_alive(Utc::now());
+        agent.last_heartbeat = Utc::now();
+        agent.online = true;
+        store_agent(memory_manager, &agent, true).await?;
+        if resumed {
+            self.publish_presence(
+                &agent,
+                PresenceStatus::Online,
+                PresenceReason::HeartbeatResumed,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Heartbeat in the background every `interval`
+    ///
+    /// Each beat also checks the other agents, so their timeouts are
+    /// announced even when nobody calls [`list_agents`](Self::list_agents).
+    pub fn start_heartbeat(self: &Arc<Self>, interval: Duration) -> Heartbeat {
+        let messaging = Arc::clone(self);
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = messaging.heartbeat().await {
+                    tracing::warn!("Heartbeat for {} failed: {}", messaging.app_id(), e);
+                }
+                if let Err(e) = messaging.all_agents().await {
+                    tracing::warn!("Presence check failed: {}", e);
+                }
+            }
+        });
+        Heartbeat { task }
+    }
+
+    /// List online agents
+    ///
+    /// Agents found to have timed out are marked offline and announced.
+    pub async fn list_agents(&self) -> Result<Vec<AgentInfo>> {
+        Ok(self
+            .all_agents()
+            .await?
+            .into_iter()
+            .filter(|agent| agent.online)
+            .collect())
+    }
+
+    /// List online agents advertising a capability
+    pub async fn find_agents(&self, capability: &str) -> Result<Vec<AgentInfo>> {
+        Ok(self
+            .list_agents()
+            .await?
+            .into_iter()
+            .filter(|agent| agent.has_capability(capability))
+            .collect())
+    }
+
+    /// Get a registered agent, online or not
+    pub async fn get_agent(&self, app_id: &str) -> Result<Option<AgentInfo>> {
+        load_agent(self.registry()?, app_id).await
+    }
+
+    /// Subscribe to presence changes of all agents
+    pub async fn subscribe_presence(&self) -> Result<super::MessageStream> {
+        self.subscribe_filtered(super::MessageFilter::new().topic_patterns([PRESENCE_TOPIC]))
+            .await
+    }
+
+    /// All registered agents, with timeouts applied
+    async fn all_agents(&self) -> Result<Vec<AgentInfo>> {
+        let memory_manager = self.registry()?;
+        let filter = EntityFilter {
+            entity_type: Some(AGENT_ENTITY_TYPE.to_string()),
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let mut agents = Vec::new();
+        for entity in memory_manager
+            .list_entities(Some(filter), None, None)
+            .await?
+        {
+            let Some(mut agent) = AgentInfo::from_entity(&entity) else {
+                continue;
+            };
+            if agent.online && !agent.is_alive(now) {
+                agent.online = false;
+                store_agent(memory_manager, &agent, true).await?;
+                self.publish_presence(
+                    &agent,
+                    PresenceStatus::Offline,
+                    PresenceReason::HeartbeatTimeout,
+                )
+                .await?;
+            }
+            agents.push(agent);
+        }
+        agents.sort_by(|a, b| a.app_id.cmp(&b.app_id));
+        Ok(agents)
+    }
+
+    fn registry(&self) -> Result<&Arc<MemoryManager>> {
+        self.memory_manager().ok_or_else(|| {
+            LocaiError::Other("The agent registry requires embedded mode".to_string())
+        })
+    }
+
+    async fn publish_presence(
+        &self,
+        agent: &AgentInfo,
+        status: PresenceStatus,
+        reason: PresenceReason,
+    ) -> Result<()> {
+        let message = PresenceEvent::new(agent, status, reason).into_message(self.app_id());
+        self.send_with_options(message).await?;
+        Ok(())
+    }
+}
+
+async fn load_agent(memory_manager: &MemoryManager, app_id: &str) -> Result<Option<AgentInfo>> {
+    Ok(memory_manager
+        .get_entity(&agent_entity_id(app_id))
+        .await?
+        .as_ref()
+        .and_then(AgentInfo::from_entity))
+}
+
+async fn store_agent(
+    memory_manager: &MemoryManager,
+    agent: &AgentInfo,
+    exists: bool,
+) -> Result<()> {
+    if exists {
+        memory_manager.update_entity(agent.to_entity()).await?;
+    } else {
+        memory_manager.create_entity(agent.to_entity()).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(last_heartbeat: DateTime<Utc>) -> AgentInfo {
+        AgentInfo {
+            app_id: "planner".to_string(),
+            capabilities: vec!["plan".to_string()],
+            metadata: json!({ "version": 2 }),
+            registered_at: last_heartbeat,
+            last_heartbeat,
+            heartbeat_ttl_secs: 30,
+            online: true,
+        }
+    }
+
+    #[test]
+    fn test_agent_round_trips_through_entity() {
+        let info = agent(Utc::now());
+        let entity = info.to_entity();
+        assert_eq!(entity.id, "agent:planner");
+        assert_eq!(entity.entity_type, AGENT_ENTITY_TYPE);
+
+        let parsed = AgentInfo::from_entity(&entity).unwrap();
+        assert!(parsed.has_capability("plan"));
+        assert_eq!(parsed.metadata["version"], 2);
+    }
+
+    #[test]
+    fn test_agent_times_out_after_ttl() {
+        let now = Utc::now();
+        assert!(agent(now - chrono::Duration::seconds(10)).is_alive(now));
+        assert!(!agent(now - chrono::Duration::seconds(31)).is_alive(now));
+    }
+
+    #[test]
+    fn test_presence_event_message() {
+        let event = PresenceEvent::new(
+            &agent(Utc::now()),
+            PresenceStatus::Offline,
+            PresenceReason::HeartbeatTimeout,
+        );
+        let message = event.into_message("watcher");
+        assert_eq!(message.topic, PRESENCE_TOPIC);
+        assert!(message.has_tag("presence"));
+
+        let parsed = PresenceEvent::from_message(&message).unwrap();
+        assert_eq!(parsed.app_id, "planner");
+        assert_eq!(parsed.status, PresenceStatus::Offline);
+        assert_eq!(parsed.reason, PresenceReason::HeartbeatTimeout);
+    }
+}
@@ -0,0 +1,121 @@
+//! Reminder delivery
+//!
+//! [`LocaiMessaging::start_reminders`] checks for due reminders in the
+//! background and publishes each one that fires as a [`ReminderEvent`] on the
+//! global [`REMINDER_TOPIC`]; subscribe with
+//! [`LocaiMessaging::subscribe_reminders`]. Reminders are set on memories with
+//! [`MemoryManager::set_reminder`](crate::core::MemoryManager::set_reminder).
+//!
+//! Schedulers sharing a store take turns through a
+//! [`Lease`](crate::runtime::Lease), so each reminder fires once however
+//! many processes run one.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::task::JoinHandle;
+
+use super::types::Message;
+use super::{LocaiMessaging, MessageFilter, MessageStream};
+use crate::memory::ReminderEvent;
+use crate::runtime::Lease;
+use crate::{LocaiError, Result};
+
+/// Topic reminder events are published on (not namespaced by app)
+pub const REMINDER_TOPIC: &str = "system.reminders";
+
+/// Lease on checking for due reminders
+pub const REMINDER_LEASE: &str = "reminders";
+
+impl ReminderEvent {
+    /// Parse a reminder event from a message on [`REMINDER_TOPIC`]
+    pub fn from_message(message: &Message) -> Option<Self> {
+        if message.topic != REMINDER_TOPIC {
+            return None;
+        }
+        serde_json::from_value(message.content.clone()).ok()
+    }
+
+    fn into_message(self, sender: &str) -> Message {
+        Message::new(REMINDER_TOPIC.to_string(), sender.to_string(), json!(self))
+            .add_tag("reminder")
+    }
+}
+
+/// Handle to a background reminder scheduler; it stops when dropped
+#[derive(Debug)]
+pub struct ReminderScheduler {
+    task: JoinHandle<()>,
+}
+
+impl ReminderScheduler {
+    /// Stop checking for due reminders
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for ReminderScheduler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl LocaiMessaging {
+    /// Fire due reminders and publish them on [`REMINDER_TOPIC`]
+    ///
+    /// Requires embedded mode.
+    ///
+    /// # Returns
+    /// The reminders that fired
+    pub async fn publish_due_reminders(&self) -> Result<Vec<ReminderEvent>> {
+        let memory_manager = self.memory_manager().ok_or_else(|| {
+            LocaiError::Other("Reminder scheduling requires embedded mode".to_string())
+        })?;
+        let events = memory_manager.fire_due_reminders().await?;
+        for event in &events {
+            let message = event.clone().into_message(self.app_id());
+            if let Err(e) = self.send_with_options(message).await {
+                tracing::warn!(
+                    "Failed to publish reminder for memory {}: {}",
+                    event.memory.id,
+                    e
+                );
+            }
+        }
+        Ok(events)
+    }
+
+    /// Publish due reminders in the background, checking every `interval`
+    ///
+    /// A reminder fires up to one interval after it is due.
+    pub fn start_reminders(self: &Arc<Self>, interval: Duration) -> ReminderScheduler {
+        let messaging = Arc::clone(self);
+        let lease = self.memory_manager().map(|memory_manager| {
+            Lease::for_interval(memory_manager.storage().clone(), REMINDER_LEASE, interval)
+        });
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                if let Some(lease) = &lease
+                    && !lease.try_acquire().await
+                {
+                    continue;
+                }
+                if let Err(e) = messaging.publish_due_reminders().await {
+                    tracing::warn!("Reminder check failed: {}", e);
+                }
+            }
+        });
+        ReminderScheduler { task }
+    }
+
+    /// Subscribe to reminders as they fire
+    pub async fn subscribe_reminders(&self) -> Result<MessageStream> {
+        self.subscribe_filtered(MessageFilter::new().topic_patterns([REMINDER_TOPIC]))
+            .await
+    }
+}
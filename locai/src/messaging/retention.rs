@@ -0,0 +1,266 @@
+//! Retention and compaction for stored messages
+//!
+//! Messages are stored as memories and would otherwise accumulate forever.
+//! [`enforce_retention`] applies the [`TopicRetentionPolicy`] list from
+//! [`MessagingConfig`](crate::config::MessagingConfig) to every stored
+//! message; the memory manager also runs it in the background every
+//! `retention_interval_secs`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use super::filters::TopicMatcher;
+use super::types::Message;
+use crate::clock::ClockHandle;
+use crate::config::{MessagingConfig, TopicRetentionPolicy};
+use crate::runtime::Lease;
+use crate::storage::filters::MemoryFilter;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Messages removed by one retention run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionReport {
+    /// Messages superseded by a newer message with the same compaction key
+    pub compacted: usize,
+    /// Messages older than their policy's maximum age
+    pub expired: usize,
+    /// Messages beyond their policy's maximum count
+    pub trimmed: usize,
+}
+
+impl RetentionReport {
+    /// Total messages removed
+    pub fn total(&self) -> usize {
+        self.compacted + self.expired + self.trimmed
+    }
+}
+
+/// A stored message and the memory holding it
+struct StoredMessage {
+    memory_id: String,
+    message: Message,
+}
+
+/// Apply retention policies to all stored messages
+///
+/// # Arguments
+/// * `storage` - Storage holding the message memories
+/// * `policies` - Policies to apply; the first matching policy governs a message
+/// * `now` - Reference time for age limits
+///
+/// # Returns
+/// Counts of removed messages by rule
+pub async fn enforce_retention(
+    storage: &dyn GraphStore,
+    policies: &[TopicRetentionPolicy],
+    now: DateTime<Utc>,
+) -> Result<RetentionReport> {
+    if policies.is_empty() {
+        return Ok(RetentionReport::default());
+    }
+
+    let filter = MemoryFilter {
+        tags: Some(vec!["message".to_string()]),
+        ..Default::default()
+    };
+    let memories = storage
+        .list_memories(Some(filter), None, None)
+        .await
+        .map_err(|e| LocaiError::Storage(format!("Failed to list messages: {}", e)))?;
+
+    let matchers: Vec<TopicMatcher> = policies
+        .iter()
+        .map(|policy| TopicMatcher::new(vec![policy.topic.clone()]))
+        .collect();
+    let mut groups: Vec<Vec<StoredMessage>> = policies.iter().map(|_| Vec::new()).collect();
+    for memory in memories {
+        let Ok(message) = serde_json::from_str::<Message>(&memory.content) else {
+            continue;
+        };
+        if let Some(index) = matchers.iter().position(|m| m.matches(&message.topic)) {
+            groups[index].push(StoredMessage {
+                memory_id: memory.id,
+                message,
+            });
+        }
+    }
+
+    let mut report = RetentionReport::default();
+    for (policy, group) in policies.iter().zip(groups) {
+        let doomed = select_for_removal(policy, group, now, &mut report);
+        for memory_id in doomed {
+            storage.delete_memory(&memory_id).await.map_err(|e| {
+                LocaiError::Storage(format!("Failed to delete message {}: {}", memory_id, e))
+            })?;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Pick the messages one policy removes, counting them by rule
+fn select_for_removal(
+    policy: &TopicRetentionPolicy,
+    mut group: Vec<StoredMessage>,
+    now: DateTime<Utc>,
+    report: &mut RetentionReport,
+) -> Vec<String> {
+    // Newest first, so the first message seen for a key is the one kept
+    group.sort_by(|a, b| b.message.timestamp.cmp(&a.message.timestamp));
+    let mut doomed = Vec::new();
+
+    if let Some(header) = &policy.compact_by_header {
+        let mut seen = HashSet::new();
+        group.retain(|stored| match stored.message.get_header(header) {
+            Some(key) if !seen.insert(key.clone()) => {
+                doomed.push(stored.memory_id.clone());
+                report.compacted += 1;
+                false
+            }
+            _ => true,
+        });
+    }
+
+    if let Some(max_age) = policy.max_age_secs {
+        let cutoff = now - chrono::Duration::seconds(max_age as i64);
+        group.retain(|stored| {
+            if stored.message.timestamp < cutoff {
+                doomed.push(stored.memory_id.clone());
+                report.expired += 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    if let Some(max_count) = policy.max_count
+        && group.len() > max_count
+    {
+        for stored in group.drain(max_count..) {
+            doomed.push(stored.memory_id);
+            report.trimmed += 1;
+        }
+    }
+
+    doomed
+}
+
+/// Lease on enforcing retention, so one process sharing the database does it
+const RETENTION_LEASE: &str = "messaging-retention";
+
+/// Start the background retention task, if any policies are configured
+pub(crate) fn spawn_retention_task(
+    storage: Arc<dyn GraphStore>,
+    config: &MessagingConfig,
+    clock: ClockHandle,
+) -> Option<JoinHandle<()>> {
+    if config.retention.is_empty() || config.retention_interval_secs == 0 {
+        return None;
+    }
+    let runtime = tokio::runtime::Handle::try_current().ok()?;
+    let policies = config.retention.clone();
+    let check_interval = Duration::from_secs(config.retention_interval_secs);
+
+    let lease = Lease::for_interval(Arc::clone(&storage), RETENTION_LEASE, check_interval);
+
+    Some(runtime.spawn(async move {
+        tracing::info!(
+            "Message retention task started (interval: {:?}, {} policies)",
+            check_interval,
+            policies.len()
+        );
+
+        let mut interval = tokio::time::interval(check_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+            // Another process sharing the database may be enforcing it
+            if !lease.try_acquire().await {
+                continue;
+            }
+            match enforce_retention(storage.as_ref(), &policies, clock.now()).await {
+                Ok(report) if report.total() > 0 => {
+                    tracing::info!(
+                        "Message retention removed {} messages ({} compacted, {} expired, {} trimmed)",
+                        report.total(),
+                        report.compacted,
+                        report.expired,
+                        report.trimmed
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to enforce message retention: {}", e),
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn stored(topic: &str, age_secs: i64, now: DateTime<Utc>, key: Option<&str>) -> StoredMessage {
+        let mut message = Message::new(topic.to_string(), "app".to_string(), json!(age_secs));
+        message.timestamp = now - chrono::Duration::seconds(age_secs);
+        if let Some(key) = key {
+            message = message.add_header("entity", key);
+        }
+        StoredMessage {
+            memory_id: format!("m{}", age_secs),
+            message,
+        }
+    }
+
+    #[test]
+    fn test_compaction_keeps_newest_per_key() {
+        let now = Utc::now();
+        let policy = TopicRetentionPolicy {
+            topic: "app:a.state".to_string(),
+            compact_by_header: Some("entity".to_string()),
+            ..Default::default()
+        };
+        let group = vec![
+            stored("app:a.state", 30, now, Some("door")),
+            stored("app:a.state", 10, now, Some("door")),
+            stored("app:a.state", 20, now, Some("lamp")),
+            stored("app:a.state", 40, now, None),
+        ];
+        let mut report = RetentionReport::default();
+        let doomed = select_for_removal(&policy, group, now, &mut report);
+        assert_eq!(doomed, vec!["m30".to_string()]);
+        assert_eq!(report.compacted, 1);
+    }
+
+    #[test]
+    fn test_age_then_count_limits() {
+        let now = Utc::now();
+        let policy = TopicRetentionPolicy {
+            topic: "app:a.*".to_string(),
+            max_age_secs: Some(100),
+            max_count: Some(2),
+            ..Default::default()
+        };
+        let group = vec![
+            stored("app:a.x", 10, now, None),
+            stored("app:a.y", 20, now, None),
+            stored("app:a.x", 30, now, None),
+            stored("app:a.x", 500, now, None),
+        ];
+        let mut report = RetentionReport::default();
+        let mut doomed = select_for_removal(&policy, group, now, &mut report);
+        doomed.sort();
+        assert_eq!(doomed, vec!["m30".to_string(), "m500".to_string()]);
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.trimmed, 1);
+        assert_eq!(report.total(), 2);
+    }
+}
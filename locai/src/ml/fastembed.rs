@@ -0,0 +1,185 @@
+//! In-process embeddings with fastembed
+//!
+//! Runs a small ONNX embedding model locally, so vector and hybrid search work
+//! with no external service. The model is downloaded to the model cache
+//! directory on first use and loaded from there afterwards.
+//!
+//! The default model, BGE-small-en-v1.5, produces 384-dimensional embeddings.
+//! Locai's vector index stores 1024 dimensions, so embeddings are zero-padded
+//! to [`INDEX_DIMENSIONS`]. Padding leaves dot products and norms unchanged, so
+//! cosine similarity between padded vectors equals that of the originals.
+//!
+//! ```rust,no_run
+//! use locai::prelude::Locai;
+//!
+//! async fn example() -> locai::Result<()> {
+//!     let locai = Locai::builder().with_local_embeddings().build().await?;
+//!     locai.remember("The dragon sleeps beneath the mountain").await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use ::fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+use async_trait::async_trait;
+
+use super::error::{MLError, Result};
+use super::provider::EmbeddingProvider;
+
+/// Dimension of Locai's memory vector index
+pub const INDEX_DIMENSIONS: usize = 1024;
+
+/// Default local model
+pub const DEFAULT_FASTEMBED_MODEL: EmbeddingModel = EmbeddingModel::BGESmallENV15;
+
+/// Embedding provider running an ONNX model in-process
+pub struct FastEmbedProvider {
+    model: Arc<Mutex<TextEmbedding>>,
+    name: String,
+    model_dimensions: usize,
+    target_dimensions: Option<usize>,
+    batch_size: Option<usize>,
+}
+
+impl std::fmt::Debug for FastEmbedProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FastEmbedProvider")
+            .field("name", &self.name)
+            .field("model_dimensions", &self.model_dimensions)
+            .field("target_dimensions", &self.target_dimensions)
+            .finish()
+    }
+}
+
+impl FastEmbedProvider {
+    /// Load `model`, downloading it into `cache_dir` if it isn't cached yet
+    ///
+    /// Embeddings are padded to [`INDEX_DIMENSIONS`] so they can be stored on
+    /// memories; use [`FastEmbedProvider::with_target_dimensions`] to change that.
+    /// Loading blocks while the model downloads, so call this from
+    /// `spawn_blocking` in async contexts.
+    pub fn new(model: EmbeddingModel, cache_dir: impl Into<PathBuf>) -> Result<Self> {
+        let info = TextEmbedding::get_model_info(&model)
+            .map_err(|e| MLError::model_not_found(format!("{:?}: {}", model, e)))?;
+        let name = format!("fastembed:{}", info.model_code);
+        let model_dimensions = info.dim;
+
+        let options = InitOptions::new(model)
+            .with_cache_dir(cache_dir.into())
+            .with_show_download_progress(false);
+        let embedding = TextEmbedding::try_new(options)
+            .map_err(|e| MLError::model_loading(format!("{}: {}", name, e)))?;
+
+        Ok(Self {
+            model: Arc::new(Mutex::new(embedding)),
+            name,
+            model_dimensions,
+            target_dimensions: (model_dimensions < INDEX_DIMENSIONS).then_some(INDEX_DIMENSIONS),
+            batch_size: None,
+        })
+    }
+
+    /// Load the default model into `cache_dir`
+    pub fn default_model(cache_dir: impl Into<PathBuf>) -> Result<Self> {
+        Self::new(DEFAULT_FASTEMBED_MODEL, cache_dir)
+    }
+
+    /// Zero-pad embeddings to this many dimensions (`None` returns them as-is)
+    pub fn with_target_dimensions(mut self, dimensions: Option<usize>) -> Self {
+        self.target_dimensions = dimensions.filter(|&d| d > self.model_dimensions);
+        self
+    }
+
+    /// Batch size used by the ONNX runtime (defaults to fastembed's)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Dimensions produced by the model before padding
+    pub fn model_dimensions(&self) -> usize {
+        self.model_dimensions
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for FastEmbedProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        Some(self.target_dimensions.unwrap_or(self.model_dimensions))
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| MLError::embedding("fastembed returned no embedding"))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let model = Arc::clone(&self.model);
+        let texts = texts.to_vec();
+        let batch_size = self.batch_size;
+
+        // Inference is CPU-bound; keep it off the async runtime
+        let embeddings = tokio::task::spawn_blocking(move || {
+            let mut model = model
+                .lock()
+                .map_err(|_| MLError::embedding("fastembed model lock poisoned"))?;
+            model
+                .embed(texts, batch_size)
+                .map_err(|e| MLError::embedding(e.to_string()))
+        })
+        .await
+        .map_err(|e| MLError::embedding(format!("Embedding task failed: {}", e)))??;
+
+        Ok(embeddings
+            .into_iter()
+            .map(|embedding| pad(embedding, self.target_dimensions))
+            .collect())
+    }
+}
+
+/// Zero-pad `embedding` to `dimensions`, if it is shorter
+fn pad(mut embedding: Vec<f32>, dimensions: Option<usize>) -> Vec<f32> {
+    if let Some(dimensions) = dimensions
+        && embedding.len() < dimensions
+    {
+        embedding.resize(dimensions, 0.0);
+    }
+    embedding
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let na: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let nb: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (na * nb)
+    }
+
+    #[test]
+    fn test_pad_preserves_cosine_similarity() {
+        let a = vec![0.3, -0.2, 0.9];
+        let b = vec![0.1, 0.4, 0.5];
+        let padded_a = pad(a.clone(), Some(INDEX_DIMENSIONS));
+        let padded_b = pad(b.clone(), Some(INDEX_DIMENSIONS));
+
+        assert_eq!(padded_a.len(), INDEX_DIMENSIONS);
+        assert!((cosine(&a, &b) - cosine(&padded_a, &padded_b)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pad_never_truncates() {
+        assert_eq!(pad(vec![1.0; 8], Some(4)).len(), 8);
+        assert_eq!(pad(vec![1.0; 8], None).len(), 8);
+    }
+}
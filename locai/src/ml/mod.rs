@@ -26,11 +26,21 @@
 //! ```
 
 pub mod error;
+#[cfg(feature = "fastembed")]
+pub mod fastembed;
 pub mod model_manager;
+#[cfg(feature = "ollama")]
+pub mod ollama;
+pub mod provider;
 
 // Re-export core BYOE functionality
+#[cfg(feature = "fastembed")]
+pub use self::fastembed::FastEmbedProvider;
 pub use error::{MLError, Result};
 pub use model_manager::{EmbeddingManager, EmbeddingManagerBuilder};
+#[cfg(feature = "ollama")]
+pub use ollama::{OllamaConfig, OllamaEmbeddingProvider};
+pub use provider::EmbeddingProvider;
 
 // Type aliases for convenience
 pub type EmbeddingVector = Vec<f32>;
@@ -46,7 +56,13 @@ pub mod utils {
 
     /// Get available embedding backends
     pub fn available_backends() -> Vec<&'static str> {
-        vec!["byoe"] // Only BYOE approach
+        #[allow(unused_mut)]
+        let mut backends = vec!["byoe"];
+        #[cfg(feature = "ollama")]
+        backends.push("ollama");
+        #[cfg(feature = "fastembed")]
+        backends.push("fastembed");
+        backends
     }
 
     /// Check if this is a valid embedding dimension for common providers
@@ -0,0 +1,328 @@
+//! Ollama embedding provider
+//!
+//! Talks to a local [Ollama](https://ollama.com) server through its `/api/embed`
+//! endpoint. The default model, `mxbai-embed-large`, produces 1024-dimensional
+//! embeddings and so works with Locai's vector index as-is.
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use locai::ml::{EmbeddingProvider, OllamaEmbeddingProvider};
+//! use locai::prelude::Locai;
+//!
+//! async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//!     let provider = OllamaEmbeddingProvider::with_model("mxbai-embed-large")?;
+//!     provider.health_check().await?;
+//!
+//!     let locai = Locai::builder()
+//!         .with_embedding_provider(Arc::new(provider))
+//!         .build()
+//!         .await?;
+//!     locai.remember("The dragon sleeps beneath the mountain").await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::error::{MLError, Result};
+use super::provider::EmbeddingProvider;
+
+/// Default Ollama endpoint
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Default embedding model (1024 dimensions)
+pub const DEFAULT_OLLAMA_MODEL: &str = "mxbai-embed-large";
+
+/// Configuration for [`OllamaEmbeddingProvider`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaConfig {
+    /// Base URL of the Ollama server
+    pub base_url: String,
+
+    /// Embedding model to use
+    pub model: String,
+
+    /// Maximum number of texts sent per request
+    pub batch_size: usize,
+
+    /// Request timeout in seconds
+    pub timeout_secs: u64,
+
+    /// Expected embedding dimensions, if known ahead of time
+    pub dimensions: Option<usize>,
+}
+
+impl Default for OllamaConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_OLLAMA_URL.to_string(),
+            model: DEFAULT_OLLAMA_MODEL.to_string(),
+            batch_size: 32,
+            timeout_secs: 60,
+            dimensions: Some(1024),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagsModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsModel {
+    name: String,
+}
+
+/// Embedding provider backed by a local Ollama server
+#[derive(Debug)]
+pub struct OllamaEmbeddingProvider {
+    config: OllamaConfig,
+    client: reqwest::Client,
+    name: String,
+    observed_dimensions: OnceLock<usize>,
+}
+
+impl OllamaEmbeddingProvider {
+    /// Create a provider from configuration
+    pub fn new(config: OllamaConfig) -> Result<Self> {
+        if config.batch_size == 0 {
+            return Err(MLError::configuration(
+                "Ollama batch_size must be at least 1",
+            ));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| MLError::initialization(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            name: format!("ollama:{}", config.model),
+            config,
+            client,
+            observed_dimensions: OnceLock::new(),
+        })
+    }
+
+    /// Create a provider for the given model on the default endpoint
+    ///
+    /// Dimensions are learned from the first response, since they depend on
+    /// the model.
+    pub fn with_model(model: impl Into<String>) -> Result<Self> {
+        Self::new(OllamaConfig {
+            model: model.into(),
+            dimensions: None,
+            ..OllamaConfig::default()
+        })
+    }
+
+    /// Provider configuration
+    pub fn config(&self) -> &OllamaConfig {
+        &self.config
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    async fn embed_chunk(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(self.url("/api/embed"))
+            .json(&EmbedRequest {
+                model: &self.config.model,
+                input: texts,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                MLError::embedding(format!(
+                    "Ollama unreachable at {}: {}",
+                    self.config.base_url, e
+                ))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(MLError::embedding(format!(
+                "Ollama returned {} for model '{}': {}",
+                status, self.config.model, body
+            )));
+        }
+
+        let body: EmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| MLError::embedding(format!("Invalid Ollama response: {}", e)))?;
+
+        if body.embeddings.len() != texts.len() {
+            return Err(MLError::embedding(format!(
+                "Ollama returned {} embeddings for {} inputs",
+                body.embeddings.len(),
+                texts.len()
+            )));
+        }
+
+        if let Some(first) = body.embeddings.first() {
+            let expected = self.dimensions().unwrap_or(first.len());
+            if let Some(bad) = body.embeddings.iter().find(|e| e.len() != expected) {
+                return Err(MLError::embedding(format!(
+                    "Model '{}' returned {} dimensions, expected {}",
+                    self.config.model,
+                    bad.len(),
+                    expected
+                )));
+            }
+            let _ = self.observed_dimensions.set(expected);
+        }
+
+        Ok(body.embeddings)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn dimensions(&self) -> Option<usize> {
+        self.config
+            .dimensions
+            .or_else(|| self.observed_dimensions.get().copied())
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.embed_chunk(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| MLError::embedding("Ollama returned no embedding"))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(self.config.batch_size) {
+            embeddings.extend(self.embed_chunk(chunk).await?);
+        }
+        Ok(embeddings)
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        let response = self
+            .client
+            .get(self.url("/api/tags"))
+            .send()
+            .await
+            .map_err(|e| {
+                MLError::initialization(format!(
+                    "Ollama unreachable at {}: {}",
+                    self.config.base_url, e
+                ))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(MLError::initialization(format!(
+                "Ollama health check returned {}",
+                response.status()
+            )));
+        }
+
+        let tags: TagsResponse = response
+            .json()
+            .await
+            .map_err(|e| MLError::initialization(format!("Invalid Ollama response: {}", e)))?;
+
+        if tags
+            .models
+            .iter()
+            .any(|m| model_matches(&m.name, &self.config.model))
+        {
+            Ok(())
+        } else {
+            Err(MLError::model_not_found(format!(
+                "'{}' is not pulled on {}. Run `ollama pull {}`.",
+                self.config.model, self.config.base_url, self.config.model
+            )))
+        }
+    }
+}
+
+/// Whether an installed model name satisfies a requested one
+///
+/// Ollama reports installed models with an explicit tag (`name:latest`), while
+/// users usually request them without one.
+fn model_matches(installed: &str, requested: &str) -> bool {
+    if installed == requested {
+        return true;
+    }
+    !requested.contains(':') && installed.strip_suffix(":latest") == Some(requested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_matches() {
+        assert!(model_matches(
+            "mxbai-embed-large:latest",
+            "mxbai-embed-large"
+        ));
+        assert!(model_matches(
+            "nomic-embed-text:v1.5",
+            "nomic-embed-text:v1.5"
+        ));
+        assert!(!model_matches("nomic-embed-text:v1.5", "nomic-embed-text"));
+        assert!(!model_matches("mxbai-embed-large:latest", "mxbai-embed"));
+    }
+
+    #[test]
+    fn test_new_rejects_zero_batch_size() {
+        let config = OllamaConfig {
+            batch_size: 0,
+            ..OllamaConfig::default()
+        };
+        assert!(OllamaEmbeddingProvider::new(config).is_err());
+    }
+
+    #[test]
+    fn test_dimensions_and_name() {
+        let provider = OllamaEmbeddingProvider::new(OllamaConfig::default()).unwrap();
+        assert_eq!(provider.name(), "ollama:mxbai-embed-large");
+        assert_eq!(provider.dimensions(), Some(1024));
+
+        let provider = OllamaEmbeddingProvider::with_model("nomic-embed-text").unwrap();
+        assert_eq!(provider.dimensions(), None);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_errors() {
+        let provider = OllamaEmbeddingProvider::new(OllamaConfig {
+            base_url: "http://127.0.0.1:1".to_string(),
+            timeout_secs: 2,
+            ..OllamaConfig::default()
+        })
+        .unwrap();
+
+        assert!(provider.health_check().await.is_err());
+        assert!(provider.embed("hello").await.is_err());
+    }
+}
@@ -0,0 +1,43 @@
+//! Embedding provider interface
+//!
+//! Locai stays BYOE by default: callers attach embeddings themselves. An
+//! [`EmbeddingProvider`] lets the memory manager do that on the caller's behalf,
+//! embedding memory content on store and query text on vector/hybrid search.
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+
+use super::error::Result;
+
+/// A source of text embeddings
+#[async_trait]
+pub trait EmbeddingProvider: Debug + Send + Sync {
+    /// Short human-readable name, e.g. `ollama:mxbai-embed-large`
+    fn name(&self) -> &str;
+
+    /// Embedding dimensions, if known without calling the provider
+    fn dimensions(&self) -> Option<usize> {
+        None
+    }
+
+    /// Embed a single text
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Embed a batch of texts, returning one embedding per input in order
+    ///
+    /// The default implementation embeds texts one at a time. Providers with a
+    /// native batch API should override it.
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            embeddings.push(self.embed(text).await?);
+        }
+        Ok(embeddings)
+    }
+
+    /// Check that the provider is reachable and ready to serve embeddings
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+}
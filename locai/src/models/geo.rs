@@ -0,0 +1,188 @@
+//! Geographic locations of memories and entities
+//!
+//! A location is a [`GeoPoint`] kept in the `location` property of a memory
+//! or entity, as `{"latitude": .., "longitude": ..}` in degrees. A
+//! [`GeoRadius`] selects the records within a distance of a point; distances
+//! are great-circle distances on a spherical Earth, in meters.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{LocaiError, Result};
+
+/// Property a memory's or entity's location is kept in
+pub const LOCATION_PROPERTY: &str = "location";
+
+/// Mean radius of the Earth in meters
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// A point on the Earth's surface, in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Whether the latitude is within ±90° and the longitude within ±180°
+    pub fn is_valid(&self) -> bool {
+        (-90.0..=90.0).contains(&self.latitude) && (-180.0..=180.0).contains(&self.longitude)
+    }
+
+    /// Great-circle distance to another point, in meters
+    pub fn distance_meters(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.longitude - self.longitude).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().min(1.0).asin()
+    }
+
+    /// Read the location from a memory's or entity's properties
+    pub fn from_properties(properties: &serde_json::Value) -> Option<Self> {
+        let point: GeoPoint = properties
+            .get(LOCATION_PROPERTY)
+            .and_then(|location| serde_json::from_value(location.clone()).ok())?;
+        point.is_valid().then_some(point)
+    }
+
+    /// Parse `LAT,LON` in degrees
+    pub fn parse(value: &str) -> Option<Self> {
+        let (latitude, longitude) = value.split_once(',')?;
+        let point = Self::new(
+            latitude.trim().parse().ok()?,
+            longitude.trim().parse().ok()?,
+        );
+        point.is_valid().then_some(point)
+    }
+}
+
+/// The area within a distance of a point
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoRadius {
+    pub center: GeoPoint,
+    pub radius_meters: f64,
+}
+
+/// Latitude and longitude ranges that cover a [`GeoRadius`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoBounds {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    /// `None` when the area covers every longitude, near a pole or across
+    /// the antimeridian
+    pub longitude: Option<(f64, f64)>,
+}
+
+impl GeoRadius {
+    pub fn new(center: GeoPoint, radius_meters: f64) -> Self {
+        Self {
+            center,
+            radius_meters,
+        }
+    }
+
+    /// Check the center is a valid point and the radius a non-negative distance
+    pub fn validate(&self) -> Result<()> {
+        if !self.center.is_valid() {
+            return Err(LocaiError::Other(format!(
+                "Invalid location {},{}: latitude must be within ±90 and longitude within ±180",
+                self.center.latitude, self.center.longitude
+            )));
+        }
+        if !(self.radius_meters.is_finite() && self.radius_meters >= 0.0) {
+            return Err(LocaiError::Other(format!(
+                "Invalid search radius {}: expected a distance in meters",
+                self.radius_meters
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether a point is within the radius
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        self.center.distance_meters(point) <= self.radius_meters
+    }
+
+    /// Ranges every point within the radius falls in, so storage can narrow
+    /// candidates before distances are checked
+    pub fn bounds(&self) -> GeoBounds {
+        let angular = (self.radius_meters / EARTH_RADIUS_METERS).to_degrees();
+        let min_latitude = self.center.latitude - angular;
+        let max_latitude = self.center.latitude + angular;
+        let longitude = if min_latitude <= -90.0 || max_latitude >= 90.0 {
+            None
+        } else {
+            // Degrees of longitude shrink towards the poles; use the widest
+            // spread, at the latitude furthest from the equator
+            let widest = min_latitude.abs().max(max_latitude.abs()).to_radians();
+            let spread = angular / widest.cos();
+            let (min, max) = (
+                self.center.longitude - spread,
+                self.center.longitude + spread,
+            );
+            (min >= -180.0 && max <= 180.0).then_some((min, max))
+        };
+        GeoBounds {
+            min_latitude: min_latitude.max(-90.0),
+            max_latitude: max_latitude.min(90.0),
+            longitude,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        let london = GeoPoint::new(51.5074, -0.1278);
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        let distance = london.distance_meters(&paris);
+        assert!((distance - 343_500.0).abs() < 1_000.0, "got {}", distance);
+        assert_eq!(london.distance_meters(&london), 0.0);
+    }
+
+    #[test]
+    fn test_bounds_cover_radius() {
+        let radius = GeoRadius::new(GeoPoint::new(60.0, 10.0), 50_000.0);
+        let bounds = radius.bounds();
+        let (min_lon, max_lon) = bounds.longitude.unwrap();
+        // Points due north and due east at the edge of the radius fall inside
+        assert!(bounds.max_latitude > 60.0 + 0.44);
+        assert!(max_lon > 10.0 + 0.89);
+        assert!(min_lon < 10.0 - 0.89);
+
+        // Near a pole or the antimeridian every longitude is covered
+        let polar = GeoRadius::new(GeoPoint::new(89.9, 0.0), 50_000.0);
+        assert_eq!(polar.bounds().longitude, None);
+        let dateline = GeoRadius::new(GeoPoint::new(0.0, 179.9), 50_000.0);
+        assert_eq!(dateline.bounds().longitude, None);
+    }
+
+    #[test]
+    fn test_parse_and_properties() {
+        assert_eq!(
+            GeoPoint::parse("51.5, -0.12"),
+            Some(GeoPoint::new(51.5, -0.12))
+        );
+        assert_eq!(GeoPoint::parse("91,0"), None);
+        assert_eq!(GeoPoint::parse("north"), None);
+
+        let properties = serde_json::json!({
+            "location": {"latitude": 51.5, "longitude": -0.12}
+        });
+        assert_eq!(
+            GeoPoint::from_properties(&properties),
+            Some(GeoPoint::new(51.5, -0.12))
+        );
+        assert_eq!(GeoPoint::from_properties(&serde_json::json!({})), None);
+    }
+}
@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use super::geo::{GeoPoint, LOCATION_PROPERTY};
+use super::sparse::{SPARSE_EMBEDDING_PROPERTY, SparseVector};
+
 /// Memory priority levels
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub enum MemoryPriority {
@@ -44,6 +47,10 @@ pub enum MemoryType {
     Event,
     /// Wisdom/insight memory
     Wisdom,
+    /// Task/goal memory, tracked through [`crate::memory::TaskStatus`]
+    Task,
+    /// Preference memory, one key-value setting kept by [`crate::memory::PreferenceStore`]
+    Preference,
     /// Custom memory type
     Custom(String),
 }
@@ -60,6 +67,8 @@ impl std::fmt::Display for MemoryType {
             Self::Action => write!(f, "action"),
             Self::Event => write!(f, "event"),
             Self::Wisdom => write!(f, "wisdom"),
+            Self::Task => write!(f, "task"),
+            Self::Preference => write!(f, "preference"),
             Self::Custom(s) => write!(f, "custom:{}", s),
         }
     }
@@ -79,6 +88,8 @@ impl MemoryType {
             "action" => Self::Action,
             "event" => Self::Event,
             "wisdom" => Self::Wisdom,
+            "task" => Self::Task,
+            "preference" => Self::Preference,
             _ => {
                 if let Some(stripped) = s.strip_prefix("custom:") {
                     Self::Custom(stripped.to_string())
@@ -190,6 +201,32 @@ impl Memory {
         }
     }
 
+    /// Where the memory happened, if it has a location
+    pub fn location(&self) -> Option<GeoPoint> {
+        GeoPoint::from_properties(&self.properties)
+    }
+
+    /// Set where the memory happened
+    pub fn set_location(&mut self, location: GeoPoint) {
+        self.set_property(
+            LOCATION_PROPERTY,
+            serde_json::to_value(location).unwrap_or_default(),
+        );
+    }
+
+    /// The memory's sparse vector, if it has one
+    pub fn sparse_embedding(&self) -> Option<SparseVector> {
+        SparseVector::from_properties(&self.properties)
+    }
+
+    /// Set the memory's sparse vector, kept alongside any dense embedding
+    pub fn set_sparse_embedding(&mut self, vector: SparseVector) {
+        self.set_property(
+            SPARSE_EMBEDDING_PROPERTY,
+            serde_json::to_value(vector).unwrap_or_default(),
+        );
+    }
+
     /// Set the embedding vector for this memory
     pub fn with_embedding(mut self, embedding: Vec<f32>) -> Self {
         self.embedding = Some(embedding);
@@ -266,6 +303,13 @@ impl MemoryBuilder {
         Self::new_with_content(content.into()).memory_type(MemoryType::Event)
     }
 
+    /// Create a task memory (convenience method)
+    ///
+    /// Prefer [`crate::memory::Task`], which also sets the task's status.
+    pub fn task<S: Into<String>>(content: S) -> Self {
+        Self::new_with_content(content.into()).memory_type(MemoryType::Task)
+    }
+
     /// Set the memory type
     pub fn memory_type(mut self, memory_type: MemoryType) -> Self {
         self.memory.memory_type = memory_type;
@@ -348,12 +392,24 @@ impl MemoryBuilder {
         self
     }
 
+    /// Set where the memory happened
+    pub fn location(mut self, location: GeoPoint) -> Self {
+        self.memory.set_location(location);
+        self
+    }
+
     /// Set the embedding vector
     pub fn embedding(mut self, embedding: Vec<f32>) -> Self {
         self.memory.embedding = Some(embedding);
         self
     }
 
+    /// Set the sparse vector
+    pub fn sparse_embedding(mut self, vector: SparseVector) -> Self {
+        self.memory.set_sparse_embedding(vector);
+        self
+    }
+
     /// Build the final Memory instance
     pub fn build(self) -> Memory {
         self.memory
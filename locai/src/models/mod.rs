@@ -1,8 +1,12 @@
 //! Domain models for memories, entities, and relationships
 
+pub mod geo;
 pub mod memory;
+pub mod sparse;
 
 // Re-export important models
+pub use geo::{GeoPoint, GeoRadius, LOCATION_PROPERTY};
 pub use memory::{Memory, MemoryBuilder, MemoryPriority, MemoryType};
+pub use sparse::{SPARSE_EMBEDDING_PROPERTY, SparseVector};
 
 // Placeholder for future implementation
@@ -0,0 +1,129 @@
+//! Sparse vectors for learned sparse retrieval
+//!
+//! A [`SparseVector`] holds the non-zero weights of a vocabulary-sized vector,
+//! as produced by SPLADE-style models or BM25 term weighting. A memory's
+//! sparse vector is kept in its `sparse_embedding` property, as
+//! `{"indices": [..], "values": [..]}`, alongside any dense embedding; sparse
+//! search ranks memories by dot product with a query's sparse vector.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{LocaiError, Result};
+
+/// Property a memory's sparse vector is kept in
+pub const SPARSE_EMBEDDING_PROPERTY: &str = "sparse_embedding";
+
+/// Non-zero weights of a sparse vector, by dimension index
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SparseVector {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+}
+
+impl SparseVector {
+    /// A sparse vector from `(index, weight)` pairs, sorted by index
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (u32, f32)>) -> Self {
+        let mut pairs: Vec<(u32, f32)> = pairs.into_iter().collect();
+        pairs.sort_by_key(|(index, _)| *index);
+        let (indices, values) = pairs.into_iter().unzip();
+        Self { indices, values }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Check indices and values pair up, indices are unique and values finite
+    pub fn validate(&self) -> Result<()> {
+        if self.indices.len() != self.values.len() {
+            return Err(LocaiError::InvalidEmbedding(format!(
+                "Sparse vector has {} indices but {} values",
+                self.indices.len(),
+                self.values.len()
+            )));
+        }
+        let mut indices = self.indices.clone();
+        indices.sort_unstable();
+        if let Some(pair) = indices.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(LocaiError::InvalidEmbedding(format!(
+                "Sparse vector repeats index {}",
+                pair[0]
+            )));
+        }
+        if let Some(value) = self.values.iter().find(|value| !value.is_finite()) {
+            return Err(LocaiError::InvalidEmbedding(format!(
+                "Sparse vector has invalid value {}",
+                value
+            )));
+        }
+        Ok(())
+    }
+
+    /// Dot product with another sparse vector
+    pub fn dot(&self, other: &SparseVector) -> f32 {
+        let (small, large) = if self.indices.len() <= other.indices.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let weights: std::collections::HashMap<u32, f32> = small
+            .indices
+            .iter()
+            .copied()
+            .zip(small.values.iter().copied())
+            .collect();
+        large
+            .indices
+            .iter()
+            .zip(&large.values)
+            .filter_map(|(index, value)| weights.get(index).map(|weight| weight * value))
+            .sum()
+    }
+
+    /// Read the sparse vector from a memory's properties
+    pub fn from_properties(properties: &serde_json::Value) -> Option<Self> {
+        let vector: SparseVector = properties
+            .get(SPARSE_EMBEDDING_PROPERTY)
+            .and_then(|vector| serde_json::from_value(vector.clone()).ok())?;
+        vector.validate().is_ok().then_some(vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_matches_shared_indices() {
+        let a = SparseVector::from_pairs([(7, 2.0), (1, 0.5), (30, 1.0)]);
+        let b = SparseVector::from_pairs([(1, 4.0), (30, 3.0)]);
+        assert_eq!(a.indices, vec![1, 7, 30]);
+        assert_eq!(a.dot(&b), 5.0);
+        assert_eq!(b.dot(&a), 5.0);
+        assert_eq!(a.dot(&SparseVector::default()), 0.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            SparseVector::from_pairs([(1, 1.0), (2, 0.5)])
+                .validate()
+                .is_ok()
+        );
+        let mismatched = SparseVector {
+            indices: vec![1, 2],
+            values: vec![1.0],
+        };
+        assert!(mismatched.validate().is_err());
+        assert!(
+            SparseVector::from_pairs([(1, 1.0), (1, 0.5)])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            SparseVector::from_pairs([(1, f32::NAN)])
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,634 @@
+//! Notifications about memory events
+//!
+//! Some events need a person: a memory waiting in the
+//! [review queue](crate::memory::review), a reminder coming due, an
+//! [anomaly](crate::memory::analytics::MemoryAnomaly) in what's stored.
+//! [`Notifications`] sends these to the targets the `notifications`
+//! configuration names, as its rules pick:
+//!
+//! - a [`SlackNotifier`] posts a message to a Slack incoming webhook
+//! - an [`HttpNotifier`] POSTs the [`Notification`] as JSON to any endpoint
+//!
+//! Any other channel, such as email, implements [`Notifier`] and is added
+//! under a name with [`Notifications::add_notifier`]; rules set at runtime
+//! can then send to it.
+//!
+//! Review and reminder notifications are sent by [`NotificationHook`], which
+//! [`init`](crate::init) registers when notifications are enabled. Anomaly
+//! notifications are sent by
+//! [`MemoryAnalyticsEngine::notify_anomalies`](crate::memory::analytics::MemoryAnalyticsEngine::notify_anomalies).
+//! Targets and rules can be set or removed at runtime; changes last until
+//! the process exits. A notification that fails to send is logged.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::config::{
+    NotificationChannel, NotificationRule, NotificationTarget, NotificationsConfig,
+};
+use crate::hooks::{HookResult, MemoryHook, Webhook};
+use crate::memory::analytics::MemoryAnomaly;
+use crate::memory::review::ReviewItem;
+use crate::models::Memory;
+use crate::{LocaiError, Result};
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Something unusual in what's stored
+    Anomaly,
+    /// A memory waiting for review
+    Review,
+    /// A reminder that came due
+    Reminder,
+    /// Sent by [`Notifications::test`] to check a target; rules never send it
+    Test,
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NotificationKind::Anomaly => "anomaly",
+            NotificationKind::Review => "review",
+            NotificationKind::Reminder => "reminder",
+            NotificationKind::Test => "test",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for NotificationKind {
+    type Err = LocaiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "anomaly" => Ok(NotificationKind::Anomaly),
+            "review" => Ok(NotificationKind::Review),
+            "reminder" => Ok(NotificationKind::Reminder),
+            "test" => Ok(NotificationKind::Test),
+            other => Err(LocaiError::Notification(format!(
+                "Unknown notification event '{}'; expected anomaly, review or reminder",
+                other
+            ))),
+        }
+    }
+}
+
+/// A message about a memory event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    /// What the notification is about
+    pub kind: NotificationKind,
+
+    /// One-line summary
+    pub title: String,
+
+    /// The details
+    pub text: String,
+
+    /// The memory the notification is about, if any
+    pub memory_id: Option<String>,
+
+    /// Tags of that memory
+    pub tags: Vec<String>,
+
+    /// When the event happened
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Notification {
+    /// A notification about an anomaly analytics detected
+    pub fn anomaly(anomaly: &MemoryAnomaly) -> Self {
+        Self {
+            kind: NotificationKind::Anomaly,
+            title: format!(
+                "{:?} anomaly ({:?} severity)",
+                anomaly.anomaly_type, anomaly.severity
+            ),
+            text: anomaly.description.clone(),
+            memory_id: Some(anomaly.memory_id.clone()),
+            tags: Vec::new(),
+            timestamp: anomaly.detected_at,
+        }
+    }
+
+    /// A notification about a memory waiting for review
+    pub fn review(item: &ReviewItem) -> Self {
+        Self {
+            kind: NotificationKind::Review,
+            title: format!("Memory from {} is waiting for review", item.memory.source),
+            text: item.memory.content.clone(),
+            memory_id: Some(item.memory.id.clone()),
+            tags: item.memory.tags.clone(),
+            timestamp: item.submitted_at,
+        }
+    }
+
+    /// A notification about a reminder that came due
+    pub fn reminder(memory: &Memory) -> Self {
+        Self {
+            kind: NotificationKind::Reminder,
+            title: "Reminder".to_string(),
+            text: memory.content.clone(),
+            memory_id: Some(memory.id.clone()),
+            tags: memory.tags.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn test(target: &str) -> Self {
+        Self {
+            kind: NotificationKind::Test,
+            title: "Test notification".to_string(),
+            text: format!("Notifications to '{}' are working", target),
+            memory_id: None,
+            tags: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Sends notifications somewhere
+#[async_trait]
+pub trait Notifier: fmt::Debug + Send + Sync {
+    /// Send one notification
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Posts notifications to a Slack incoming webhook
+#[derive(Debug, Clone)]
+pub struct SlackNotifier {
+    webhook: Webhook,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            webhook: Webhook::new(webhook_url.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let text = format!("*{}*\n{}", notification.title, notification.text);
+        self.webhook
+            .send_with_retry(
+                &format!("notification.{}", notification.kind),
+                serde_json::json!({ "text": text }),
+            )
+            .await
+            .map_err(LocaiError::Notification)
+    }
+}
+
+/// POSTs notifications as JSON to an HTTP endpoint
+///
+/// The `X-Webhook-Event` header is `notification.<kind>`.
+#[derive(Debug, Clone)]
+pub struct HttpNotifier {
+    webhook: Webhook,
+}
+
+impl HttpNotifier {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            webhook: Webhook::new(url.into()),
+        }
+    }
+
+    /// Send a header with every request
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.webhook = self.webhook.with_header(key.into(), value.into());
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for HttpNotifier {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let payload = serde_json::to_value(notification)
+            .map_err(|e| LocaiError::Notification(e.to_string()))?;
+        self.webhook
+            .send_with_retry(&format!("notification.{}", notification.kind), payload)
+            .await
+            .map_err(LocaiError::Notification)
+    }
+}
+
+/// Check a target before it's registered
+pub fn check_target(target: &NotificationTarget) -> Result<()> {
+    if target.name.trim().is_empty() {
+        return Err(LocaiError::Notification(
+            "Notification target name cannot be empty".to_string(),
+        ));
+    }
+    let url = match &target.channel {
+        NotificationChannel::Slack { webhook_url } => webhook_url,
+        NotificationChannel::Http { url, .. } => url,
+    };
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(LocaiError::Notification(format!(
+            "Notification target '{}' needs an http(s) url",
+            target.name
+        )));
+    }
+    Ok(())
+}
+
+/// Check a rule before it's registered; `target_exists` says whether a
+/// target name is known
+pub fn check_rule(rule: &NotificationRule, target_exists: impl Fn(&str) -> bool) -> Result<()> {
+    let invalid = |reason: String| {
+        LocaiError::Notification(format!("Notification rule '{}' {}", rule.name, reason))
+    };
+    if rule.name.trim().is_empty() {
+        return Err(LocaiError::Notification(
+            "Notification rule name cannot be empty".to_string(),
+        ));
+    }
+    if rule.events.is_empty() {
+        return Err(invalid("has no events".to_string()));
+    }
+    if rule.events.contains(&NotificationKind::Test) {
+        return Err(invalid("can't send test notifications".to_string()));
+    }
+    if rule.targets.is_empty() {
+        return Err(invalid("has no targets".to_string()));
+    }
+    if let Some(target) = rule.targets.iter().find(|target| !target_exists(target)) {
+        return Err(invalid(format!("sends to unknown target '{}'", target)));
+    }
+    Ok(())
+}
+
+/// The notifier for a configured channel
+fn notifier_for(channel: &NotificationChannel) -> Arc<dyn Notifier> {
+    match channel {
+        NotificationChannel::Slack { webhook_url } => Arc::new(SlackNotifier::new(webhook_url)),
+        NotificationChannel::Http { url, headers } => {
+            let notifier = headers
+                .iter()
+                .fold(HttpNotifier::new(url), |notifier, (key, value)| {
+                    notifier.with_header(key, value)
+                });
+            Arc::new(notifier)
+        }
+    }
+}
+
+/// Whether every condition the rule gives holds for `notification`
+fn rule_matches(rule: &NotificationRule, notification: &Notification) -> bool {
+    rule.events.contains(&notification.kind)
+        && rule.tags.iter().all(|tag| notification.tags.contains(tag))
+}
+
+/// A target notifications can be sent to
+#[derive(Debug, Clone)]
+struct Target {
+    /// How it was configured; `None` for notifiers added in code
+    config: Option<NotificationTarget>,
+    notifier: Arc<dyn Notifier>,
+}
+
+/// Notification targets and the rules that pick them, by name
+#[derive(Debug)]
+pub struct Notifications {
+    enabled: bool,
+    targets: RwLock<BTreeMap<String, Target>>,
+    rules: RwLock<BTreeMap<String, NotificationRule>>,
+}
+
+impl Notifications {
+    /// Serve the configured targets and rules
+    pub fn new(config: &NotificationsConfig) -> Self {
+        let targets = config
+            .targets
+            .iter()
+            .map(|target| {
+                let entry = Target {
+                    config: Some(target.clone()),
+                    notifier: notifier_for(&target.channel),
+                };
+                (target.name.clone(), entry)
+            })
+            .collect();
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| (rule.name.clone(), rule.clone()))
+            .collect();
+        Self {
+            enabled: config.enabled,
+            targets: RwLock::new(targets),
+            rules: RwLock::new(rules),
+        }
+    }
+
+    /// Whether notifications are sent at all
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Every configured target, by name; notifiers added in code aren't
+    /// listed
+    pub fn targets(&self) -> Vec<NotificationTarget> {
+        self.targets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter_map(|target| target.config.clone())
+            .collect()
+    }
+
+    /// The configured target with this name
+    pub fn target(&self, name: &str) -> Option<NotificationTarget> {
+        self.targets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .and_then(|target| target.config.clone())
+    }
+
+    /// Register a target, replacing any with the same name
+    pub fn set_target(&self, target: NotificationTarget) -> Result<()> {
+        check_target(&target)?;
+        let entry = Target {
+            notifier: notifier_for(&target.channel),
+            config: Some(target.clone()),
+        };
+        self.targets
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(target.name, entry);
+        Ok(())
+    }
+
+    /// Register a notifier for a channel locai doesn't build in, replacing
+    /// any target with the same name
+    pub fn add_notifier(&self, name: &str, notifier: Arc<dyn Notifier>) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(LocaiError::Notification(
+                "Notification target name cannot be empty".to_string(),
+            ));
+        }
+        let entry = Target {
+            config: None,
+            notifier,
+        };
+        self.targets
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), entry);
+        Ok(())
+    }
+
+    /// Remove a target, returning whether there was one to remove
+    ///
+    /// Fails while a rule sends to it.
+    pub fn remove_target(&self, name: &str) -> Result<bool> {
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(rule) = rules
+            .values()
+            .find(|rule| rule.targets.iter().any(|target| target == name))
+        {
+            return Err(LocaiError::Notification(format!(
+                "Notification target '{}' is used by rule '{}'",
+                name, rule.name
+            )));
+        }
+        Ok(self
+            .targets
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+            .is_some())
+    }
+
+    /// Every rule, by name
+    pub fn rules(&self) -> Vec<NotificationRule> {
+        self.rules
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// The rule with this name
+    pub fn rule(&self, name: &str) -> Option<NotificationRule> {
+        self.rules
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    /// Register a rule, replacing any with the same name
+    pub fn set_rule(&self, rule: NotificationRule) -> Result<()> {
+        {
+            let targets = self.targets.read().unwrap_or_else(|e| e.into_inner());
+            check_rule(&rule, |name| targets.contains_key(name))?;
+        }
+        self.rules
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(rule.name.clone(), rule);
+        Ok(())
+    }
+
+    /// Remove a rule, returning whether there was one to remove
+    pub fn remove_rule(&self, name: &str) -> bool {
+        self.rules
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+            .is_some()
+    }
+
+    /// Send a notification to every target a rule picks for it, returning
+    /// how many it reached
+    pub async fn dispatch(&self, notification: &Notification) -> usize {
+        if !self.enabled {
+            return 0;
+        }
+
+        let notifiers: Vec<(String, Arc<dyn Notifier>)> = {
+            let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+            let names: BTreeSet<&String> = rules
+                .values()
+                .filter(|rule| rule_matches(rule, notification))
+                .flat_map(|rule| &rule.targets)
+                .collect();
+            let targets = self.targets.read().unwrap_or_else(|e| e.into_inner());
+            names
+                .into_iter()
+                .filter_map(|name| {
+                    let target = targets.get(name);
+                    if target.is_none() {
+                        warn!("Notification target '{}' isn't registered", name);
+                    }
+                    target.map(|target| (name.clone(), Arc::clone(&target.notifier)))
+                })
+                .collect()
+        };
+
+        let mut sent = 0;
+        for (name, notifier) in notifiers {
+            match notifier.send(notification).await {
+                Ok(()) => {
+                    debug!("Sent {} notification to '{}'", notification.kind, name);
+                    sent += 1;
+                }
+                Err(e) => warn!(
+                    "Failed to send {} notification to '{}': {}",
+                    notification.kind, name, e
+                ),
+            }
+        }
+        sent
+    }
+
+    /// Send a test notification to one target, whatever the rules say
+    pub async fn test(&self, name: &str) -> Result<()> {
+        let notifier = self
+            .targets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .map(|target| Arc::clone(&target.notifier))
+            .ok_or_else(|| {
+                LocaiError::Notification(format!("Unknown notification target '{}'", name))
+            })?;
+        notifier.send(&Notification::test(name)).await
+    }
+}
+
+/// Sends notifications for review queue items and reminders
+#[derive(Debug)]
+pub struct NotificationHook {
+    notifications: Arc<Notifications>,
+}
+
+impl NotificationHook {
+    pub fn new(notifications: Arc<Notifications>) -> Self {
+        Self { notifications }
+    }
+}
+
+#[async_trait]
+impl MemoryHook for NotificationHook {
+    async fn on_memory_created(&self, memory: &Memory) -> HookResult {
+        if let Some(item) = ReviewItem::from_memory(memory) {
+            self.notifications
+                .dispatch(&Notification::review(&item))
+                .await;
+        }
+        HookResult::Continue
+    }
+
+    async fn on_memory_reminder(&self, memory: &Memory) -> HookResult {
+        self.notifications
+            .dispatch(&Notification::reminder(memory))
+            .await;
+        HookResult::Continue
+    }
+
+    fn timeout_ms(&self) -> u64 {
+        30_000
+    }
+
+    fn name(&self) -> &str {
+        "notifications"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct Recorder {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for Recorder {
+        async fn send(&self, notification: &Notification) -> Result<()> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    fn rule(name: &str, events: &[NotificationKind], targets: &[&str]) -> NotificationRule {
+        NotificationRule {
+            name: name.to_string(),
+            events: events.to_vec(),
+            targets: targets.iter().map(|target| target.to_string()).collect(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn reminder(tags: &[&str]) -> Notification {
+        let mut memory = Memory::new(
+            "m1".to_string(),
+            "Renew the certificate".to_string(),
+            crate::models::MemoryType::Fact,
+        );
+        memory.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        Notification::reminder(&memory)
+    }
+
+    #[test]
+    fn test_rules_match_events_and_tags() {
+        let ops = NotificationRule {
+            tags: vec!["ops".to_string()],
+            ..rule("ops", &[NotificationKind::Reminder], &["slack"])
+        };
+        assert!(rule_matches(&ops, &reminder(&["ops", "tls"])));
+        assert!(!rule_matches(&ops, &reminder(&["tls"])));
+
+        let reviews = rule("reviews", &[NotificationKind::Review], &["slack"]);
+        assert!(!rule_matches(&reviews, &reminder(&[])));
+    }
+
+    #[test]
+    fn test_rules_must_name_known_targets() {
+        let known = |name: &str| name == "slack";
+        assert!(check_rule(&rule("a", &[NotificationKind::Anomaly], &["slack"]), known).is_ok());
+        assert!(check_rule(&rule("a", &[NotificationKind::Anomaly], &["email"]), known).is_err());
+        assert!(check_rule(&rule("a", &[], &["slack"]), known).is_err());
+        assert!(check_rule(&rule("a", &[NotificationKind::Test], &["slack"]), known).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_sends_once_per_target() {
+        let notifications = Notifications::new(&NotificationsConfig::default());
+        let recorder = Arc::new(Recorder::default());
+        notifications
+            .add_notifier("recorder", recorder.clone())
+            .unwrap();
+        notifications
+            .set_rule(rule("all", &[NotificationKind::Reminder], &["recorder"]))
+            .unwrap();
+        notifications
+            .set_rule(rule("again", &[NotificationKind::Reminder], &["recorder"]))
+            .unwrap();
+
+        assert_eq!(notifications.dispatch(&reminder(&[])).await, 1);
+        assert_eq!(recorder.sent.lock().unwrap().len(), 1);
+        assert!(notifications.remove_target("recorder").is_err());
+        assert!(notifications.remove_rule("all"));
+        assert!(notifications.remove_rule("again"));
+        assert!(notifications.remove_target("recorder").unwrap());
+    }
+}
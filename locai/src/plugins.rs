@@ -0,0 +1,386 @@
+//! Plugins supplied by other crates
+//!
+//! A `locai-*` extension crate registers what it provides with the
+//! [`PluginRegistry`] under a name: memory hooks, entity extractors, store
+//! middlewares, rerankers and embedding providers. Each is registered as a
+//! factory that builds it from the options given in configuration, so an
+//! application can enable it without naming its type:
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use locai::config::{ConfigBuilder, PluginSpec, PluginsConfig};
+//! use locai::hooks::MemoryHook;
+//! use locai::plugins::PluginRegistry;
+//!
+//! #[derive(Debug)]
+//! struct AuditHook {
+//!     path: String,
+//! }
+//!
+//! #[async_trait::async_trait]
+//! impl MemoryHook for AuditHook {}
+//!
+//! # async fn example() -> locai::Result<()> {
+//! // In the extension crate
+//! PluginRegistry::global().register_hook("audit", |options| {
+//!     let path = options["path"].as_str().unwrap_or("audit.jsonl").to_string();
+//!     Ok(Arc::new(AuditHook { path }))
+//! })?;
+//!
+//! // In the application, or the `[plugins]` section of its config file
+//! let config = ConfigBuilder::new()
+//!     .with_plugins(PluginsConfig {
+//!         hooks: vec![PluginSpec::new("audit").with_options(serde_json::json!({ "path": "audit.jsonl" }))],
+//!         ..PluginsConfig::default()
+//!     })
+//!     .build()?;
+//! let memory_manager = locai::init(config).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`init`](crate::init) creates every plugin the configuration enables and
+//! fails if one isn't registered.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::config::{PluginSpec, PluginsConfig};
+use crate::core::MemoryManager;
+use crate::entity_extraction::EntityExtractor;
+use crate::hooks::MemoryHook;
+use crate::memory::pipeline::StoreMiddleware;
+use crate::ml::EmbeddingProvider;
+use crate::search::rerank::Reranker;
+use crate::{LocaiError, Result};
+
+/// The kinds of plugin the registry holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluginKind {
+    Hook,
+    Extractor,
+    StoreMiddleware,
+    Reranker,
+    EmbeddingProvider,
+}
+
+impl fmt::Display for PluginKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PluginKind::Hook => "hook",
+            PluginKind::Extractor => "entity extractor",
+            PluginKind::StoreMiddleware => "store middleware",
+            PluginKind::Reranker => "reranker",
+            PluginKind::EmbeddingProvider => "embedding provider",
+        };
+        f.write_str(name)
+    }
+}
+
+type Factory<T> = Arc<dyn Fn(&serde_json::Value) -> Result<Arc<T>> + Send + Sync>;
+
+/// Factories for one kind of plugin, by name
+struct Factories<T: ?Sized> {
+    kind: PluginKind,
+    factories: RwLock<HashMap<String, Factory<T>>>,
+}
+
+impl<T: ?Sized> Factories<T> {
+    fn new(kind: PluginKind) -> Self {
+        Self {
+            kind,
+            factories: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn register(&self, name: String, factory: Factory<T>) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(LocaiError::Configuration(
+                "Plugin name cannot be empty".to_string(),
+            ));
+        }
+
+        let mut factories = self.factories.write().unwrap_or_else(|e| e.into_inner());
+        if factories.contains_key(&name) {
+            return Err(LocaiError::Configuration(format!(
+                "Another {} plugin is already registered as '{}'",
+                self.kind, name
+            )));
+        }
+        factories.insert(name, factory);
+        Ok(())
+    }
+
+    fn unregister(&self, name: &str) -> bool {
+        self.factories
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+            .is_some()
+    }
+
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .factories
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn create(&self, spec: &PluginSpec) -> Result<Arc<T>> {
+        // Release the lock before calling the factory, which may register
+        // plugins of its own
+        let factory = self
+            .factories
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&spec.name)
+            .cloned();
+        let Some(factory) = factory else {
+            let registered = self.names();
+            return Err(LocaiError::Configuration(format!(
+                "No {} plugin registered as '{}' (registered: {})",
+                self.kind,
+                spec.name,
+                if registered.is_empty() {
+                    "none".to_string()
+                } else {
+                    registered.join(", ")
+                }
+            )));
+        };
+        factory(&spec.options).map_err(|e| {
+            LocaiError::Configuration(format!(
+                "Failed to create {} plugin '{}': {}",
+                self.kind, spec.name, e
+            ))
+        })
+    }
+}
+
+/// Named factories for hooks, extractors, store middlewares, rerankers and
+/// embedding providers
+///
+/// Extension crates register with [`PluginRegistry::global`], which
+/// [`init`](crate::init) reads. A registry of its own is useful for tests or
+/// for applying plugins to a manager with [`apply`](Self::apply).
+pub struct PluginRegistry {
+    hooks: Factories<dyn MemoryHook>,
+    extractors: Factories<dyn EntityExtractor>,
+    store_middlewares: Factories<dyn StoreMiddleware>,
+    rerankers: Factories<dyn Reranker>,
+    embedding_providers: Factories<dyn EmbeddingProvider>,
+}
+
+impl fmt::Debug for PluginRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginRegistry")
+            .field("hooks", &self.hooks.names())
+            .field("extractors", &self.extractors.names())
+            .field("store_middlewares", &self.store_middlewares.names())
+            .field("rerankers", &self.rerankers.names())
+            .field("embedding_providers", &self.embedding_providers.names())
+            .finish()
+    }
+}
+
+impl Default for PluginRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static GLOBAL: LazyLock<PluginRegistry> = LazyLock::new(|| {
+    let registry = PluginRegistry::new();
+    #[cfg(feature = "wasm-hooks")]
+    crate::hooks::wasm::register_plugins(&registry);
+    registry
+});
+
+impl PluginRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self {
+            hooks: Factories::new(PluginKind::Hook),
+            extractors: Factories::new(PluginKind::Extractor),
+            store_middlewares: Factories::new(PluginKind::StoreMiddleware),
+            rerankers: Factories::new(PluginKind::Reranker),
+            embedding_providers: Factories::new(PluginKind::EmbeddingProvider),
+        }
+    }
+
+    /// The registry [`init`](crate::init) creates configured plugins from
+    ///
+    /// With the `wasm-hooks` feature it starts out holding the `wasm` hook
+    /// and store middleware (see `hooks::wasm`).
+    pub fn global() -> &'static PluginRegistry {
+        &GLOBAL
+    }
+
+    /// Register a memory hook factory as `name`
+    ///
+    /// Fails if the name is empty or a hook is already registered under it.
+    pub fn register_hook<F>(&self, name: impl Into<String>, factory: F) -> Result<()>
+    where
+        F: Fn(&serde_json::Value) -> Result<Arc<dyn MemoryHook>> + Send + Sync + 'static,
+    {
+        self.hooks.register(name.into(), Arc::new(factory))
+    }
+
+    /// Register an entity extractor factory as `name`
+    pub fn register_extractor<F>(&self, name: impl Into<String>, factory: F) -> Result<()>
+    where
+        F: Fn(&serde_json::Value) -> Result<Arc<dyn EntityExtractor>> + Send + Sync + 'static,
+    {
+        self.extractors.register(name.into(), Arc::new(factory))
+    }
+
+    /// Register a store middleware factory as `name`
+    ///
+    /// The middleware is placed in the pipeline by its own
+    /// [`name`](StoreMiddleware::name), which need not match this one.
+    pub fn register_store_middleware<F>(&self, name: impl Into<String>, factory: F) -> Result<()>
+    where
+        F: Fn(&serde_json::Value) -> Result<Arc<dyn StoreMiddleware>> + Send + Sync + 'static,
+    {
+        self.store_middlewares
+            .register(name.into(), Arc::new(factory))
+    }
+
+    /// Register a reranker factory as `name`
+    pub fn register_reranker<F>(&self, name: impl Into<String>, factory: F) -> Result<()>
+    where
+        F: Fn(&serde_json::Value) -> Result<Arc<dyn Reranker>> + Send + Sync + 'static,
+    {
+        self.rerankers.register(name.into(), Arc::new(factory))
+    }
+
+    /// Register an embedding provider factory as `name`
+    pub fn register_embedding_provider<F>(&self, name: impl Into<String>, factory: F) -> Result<()>
+    where
+        F: Fn(&serde_json::Value) -> Result<Arc<dyn EmbeddingProvider>> + Send + Sync + 'static,
+    {
+        self.embedding_providers
+            .register(name.into(), Arc::new(factory))
+    }
+
+    /// Remove a registered plugin; false if none of this kind had the name
+    ///
+    /// Plugins already created keep working.
+    pub fn unregister(&self, kind: PluginKind, name: &str) -> bool {
+        match kind {
+            PluginKind::Hook => self.hooks.unregister(name),
+            PluginKind::Extractor => self.extractors.unregister(name),
+            PluginKind::StoreMiddleware => self.store_middlewares.unregister(name),
+            PluginKind::Reranker => self.rerankers.unregister(name),
+            PluginKind::EmbeddingProvider => self.embedding_providers.unregister(name),
+        }
+    }
+
+    /// Names of the registered plugins of `kind`, sorted
+    pub fn registered(&self, kind: PluginKind) -> Vec<String> {
+        match kind {
+            PluginKind::Hook => self.hooks.names(),
+            PluginKind::Extractor => self.extractors.names(),
+            PluginKind::StoreMiddleware => self.store_middlewares.names(),
+            PluginKind::Reranker => self.rerankers.names(),
+            PluginKind::EmbeddingProvider => self.embedding_providers.names(),
+        }
+    }
+
+    /// Create the hook `spec` names
+    pub fn create_hook(&self, spec: &PluginSpec) -> Result<Arc<dyn MemoryHook>> {
+        self.hooks.create(spec)
+    }
+
+    /// Create the entity extractor `spec` names
+    pub fn create_extractor(&self, spec: &PluginSpec) -> Result<Arc<dyn EntityExtractor>> {
+        self.extractors.create(spec)
+    }
+
+    /// Create the store middleware `spec` names
+    pub fn create_store_middleware(&self, spec: &PluginSpec) -> Result<Arc<dyn StoreMiddleware>> {
+        self.store_middlewares.create(spec)
+    }
+
+    /// Create the reranker `spec` names
+    pub fn create_reranker(&self, spec: &PluginSpec) -> Result<Arc<dyn Reranker>> {
+        self.rerankers.create(spec)
+    }
+
+    /// Create the embedding provider `spec` names
+    pub fn create_embedding_provider(
+        &self,
+        spec: &PluginSpec,
+    ) -> Result<Arc<dyn EmbeddingProvider>> {
+        self.embedding_providers.create(spec)
+    }
+
+    /// Create the plugins `config` enables and attach them to `manager`
+    ///
+    /// Every plugin is created before any is attached, so a missing or
+    /// failing plugin leaves nothing half-applied.
+    pub async fn apply(
+        &self,
+        manager: MemoryManager,
+        config: &PluginsConfig,
+    ) -> Result<MemoryManager> {
+        let hooks = config
+            .hooks
+            .iter()
+            .map(|spec| self.create_hook(spec))
+            .collect::<Result<Vec<_>>>()?;
+        let extractors = config
+            .extractors
+            .iter()
+            .map(|spec| self.create_extractor(spec))
+            .collect::<Result<Vec<_>>>()?;
+        let store_middlewares = config
+            .store_middlewares
+            .iter()
+            .map(|spec| self.create_store_middleware(spec))
+            .collect::<Result<Vec<_>>>()?;
+        let reranker = config
+            .reranker
+            .as_ref()
+            .map(|spec| self.create_reranker(spec))
+            .transpose()?;
+        let embedding_provider = config
+            .embedding_provider
+            .as_ref()
+            .map(|spec| self.create_embedding_provider(spec))
+            .transpose()?;
+
+        if !hooks.is_empty() {
+            let registry = manager.hook_registry().ok_or_else(|| {
+                LocaiError::Configuration(
+                    "Hook plugins are enabled but the storage backend doesn't support hooks"
+                        .to_string(),
+                )
+            })?;
+            for hook in hooks {
+                registry.register(hook).await;
+            }
+        }
+
+        let mut manager = manager;
+        for extractor in extractors {
+            manager = manager.with_entity_extractor(extractor);
+        }
+        for middleware in store_middlewares {
+            manager = manager.with_store_middleware(middleware);
+        }
+        if let Some(reranker) = reranker {
+            manager = manager.with_reranker(reranker);
+        }
+        if let Some(provider) = embedding_provider {
+            manager = manager.with_embedding_provider(provider);
+        }
+        Ok(manager)
+    }
+}
@@ -0,0 +1,102 @@
+//! Vector clocks for ordering changes across nodes
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How two vector clocks relate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockOrdering {
+    /// Both clocks have seen exactly the same changes
+    Equal,
+    /// This clock happened before the other
+    Before,
+    /// This clock happened after the other
+    After,
+    /// Neither clock has seen all of the other's changes
+    Concurrent,
+}
+
+/// Per-node change counters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VectorClock(BTreeMap<String, u64>);
+
+impl VectorClock {
+    /// Create an empty clock
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counter for a node, zero if it has never written
+    pub fn get(&self, node_id: &str) -> u64 {
+        self.0.get(node_id).copied().unwrap_or(0)
+    }
+
+    /// Record a change made by `node_id`
+    pub fn increment(&mut self, node_id: &str) {
+        *self.0.entry(node_id.to_string()).or_insert(0) += 1;
+    }
+
+    /// Take the element-wise maximum with another clock
+    pub fn merge(&mut self, other: &VectorClock) {
+        for (node, &count) in &other.0 {
+            let entry = self.0.entry(node.clone()).or_insert(0);
+            *entry = (*entry).max(count);
+        }
+    }
+
+    /// Compare with another clock
+    pub fn compare(&self, other: &VectorClock) -> ClockOrdering {
+        let mut ordering = Ordering::Equal;
+        for node in self.0.keys().chain(other.0.keys()) {
+            match (self.get(node).cmp(&other.get(node)), ordering) {
+                (Ordering::Equal, _) => {}
+                (step, Ordering::Equal) => ordering = step,
+                (step, current) if step != current => return ClockOrdering::Concurrent,
+                _ => {}
+            }
+        }
+        match ordering {
+            Ordering::Equal => ClockOrdering::Equal,
+            Ordering::Less => ClockOrdering::Before,
+            Ordering::Greater => ClockOrdering::After,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let mut a = VectorClock::new();
+        let mut b = VectorClock::new();
+        assert_eq!(a.compare(&b), ClockOrdering::Equal);
+
+        a.increment("edge");
+        assert_eq!(a.compare(&b), ClockOrdering::After);
+        assert_eq!(b.compare(&a), ClockOrdering::Before);
+
+        b.increment("home");
+        assert_eq!(a.compare(&b), ClockOrdering::Concurrent);
+
+        b.merge(&a);
+        assert_eq!(b.get("edge"), 1);
+        assert_eq!(b.get("home"), 1);
+        assert_eq!(b.compare(&a), ClockOrdering::After);
+    }
+
+    #[test]
+    fn test_serializes_as_map() {
+        let mut clock = VectorClock::new();
+        clock.increment("edge");
+        clock.increment("edge");
+        assert_eq!(
+            serde_json::to_value(&clock).unwrap(),
+            serde_json::json!({ "edge": 2 })
+        );
+    }
+}
@@ -0,0 +1,584 @@
+//! Replication between Locai instances
+//!
+//! A [`Replicator`] tails the storage change stream (live queries on the
+//! memory, entity and relationship tables) and publishes every local change
+//! as a [`ChangeEvent`]. The replicator on another instance applies those
+//! events with [`Replicator::apply`], writing records under their original
+//! IDs. Events travel over a WebSocket; see [`transport`] for pushing local
+//! changes to a peer or pulling the peer's changes.
+//!
+//! Each record carries a [`VectorClock`] that counts writes per node, so a
+//! replica can tell a newer change from a stale one even when both nodes
+//! write. How concurrent writes are settled is set by [`ConflictPolicy`].
+//!
+//! ```rust,no_run
+//! use locai::prelude::*;
+//! use locai::replication::{ReplicationConfig, Replicator, transport::{self, SyncMode}};
+//!
+//! async fn sync_to_home(manager: &MemoryManager) -> Result<()> {
+//!     let replicator = Replicator::start(
+//!         manager.storage().clone(),
+//!         ReplicationConfig::with_node_id("edge-agent"),
+//!     )
+//!     .await?;
+//!     let url = "ws://home:3000/api/replication/ws";
+//!     transport::connect(replicator, url, SyncMode::Push, None).await
+//! }
+//! ```
+//!
+//! Only changes made while the replicator runs are captured; existing
+//! records are not backfilled. Clocks are kept in memory, so after a restart
+//! the first incoming change for a record is always accepted.
+//!
+//! For a node that loses its store rather than its peer, [`offline`] queues
+//! writes locally and reconciles them when the store returns.
+
+pub mod clock;
+pub mod offline;
+pub mod transport;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+pub use clock::{ClockOrdering, VectorClock};
+
+use crate::storage::errors::StorageError;
+use crate::storage::shared_storage::live_query::DbEvent;
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// Kind of record a change applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordKind {
+    Memory,
+    Entity,
+    Relationship,
+}
+
+impl RecordKind {
+    pub(crate) fn from_table(table: &str) -> Option<Self> {
+        match table {
+            "memory" => Some(Self::Memory),
+            "entity" => Some(Self::Entity),
+            "relationship" => Some(Self::Relationship),
+            _ => None,
+        }
+    }
+}
+
+/// What happened to the record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOp {
+    /// The record was created or updated
+    Upsert,
+    /// The record was deleted
+    Delete,
+}
+
+/// A change to one record, as shipped between nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// Node the change was made on
+    pub origin: String,
+
+    /// Kind of record
+    pub kind: RecordKind,
+
+    /// Record ID
+    pub id: String,
+
+    /// Operation
+    pub op: ChangeOp,
+
+    /// Full record (a serialized `Memory`, `Entity` or `Relationship`) for upserts
+    pub record: Option<Value>,
+
+    /// When the origin node captured the change
+    pub timestamp: DateTime<Utc>,
+
+    /// Version of the record after the change
+    pub clock: VectorClock,
+}
+
+/// How to settle a change that conflicts with the local copy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// The change with the later timestamp wins, ties broken by node ID
+    LastWriteWins,
+
+    /// Vector clocks decide; only changes made concurrently on two nodes
+    /// fall back to last-write-wins
+    #[default]
+    VectorClock,
+}
+
+impl std::str::FromStr for ConflictPolicy {
+    type Err = LocaiError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "lww" | "last_write_wins" | "last-write-wins" => Ok(Self::LastWriteWins),
+            "vector_clock" | "vector-clock" => Ok(Self::VectorClock),
+            _ => Err(LocaiError::Configuration(format!(
+                "Unknown conflict policy '{}' (expected last_write_wins or vector_clock)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Replication settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicationConfig {
+    /// Stable, unique name of this node
+    pub node_id: String,
+
+    /// Conflict resolution policy
+    pub conflict_policy: ConflictPolicy,
+
+    /// Changes buffered per subscriber before a slow peer starts missing them
+    pub buffer_size: usize,
+}
+
+impl ReplicationConfig {
+    /// Default settings with the given node ID
+    pub fn with_node_id(node_id: impl Into<String>) -> Self {
+        Self {
+            node_id: node_id.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            node_id: uuid::Uuid::new_v4().to_string(),
+            conflict_policy: ConflictPolicy::default(),
+            buffer_size: 1024,
+        }
+    }
+}
+
+/// Result of applying a remote change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyOutcome {
+    /// The change was written locally
+    Applied,
+
+    /// The local copy already includes the change, or is newer
+    Stale,
+
+    /// Both nodes changed the record independently; `applied` tells whether
+    /// the remote change won
+    Conflict { applied: bool },
+
+    /// The change originated on this node
+    Ignored,
+}
+
+/// Local version of a replicated record
+#[derive(Debug, Clone, Default)]
+struct RecordVersion {
+    clock: VectorClock,
+    timestamp: DateTime<Utc>,
+    origin: String,
+}
+
+type RecordKey = (RecordKind, String);
+
+/// Captures local changes and applies remote ones
+pub struct Replicator {
+    storage: Arc<dyn GraphStore>,
+    config: ReplicationConfig,
+    versions: Mutex<HashMap<RecordKey, RecordVersion>>,
+    /// Writes made by `apply` whose change-stream echo must not be
+    /// republished as a local change
+    pending_echoes: Mutex<HashMap<RecordKey, usize>>,
+    changes: broadcast::Sender<ChangeEvent>,
+}
+
+impl std::fmt::Debug for Replicator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Replicator")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Replicator {
+    /// Start capturing changes from `storage`
+    ///
+    /// Fails if the store has no live query support.
+    pub async fn start(
+        storage: Arc<dyn GraphStore>,
+        config: ReplicationConfig,
+    ) -> Result<Arc<Self>> {
+        let mut events = storage
+            .setup_live_queries()
+            .await
+            .map_err(|e| LocaiError::Storage(format!("Live query setup failed: {}", e)))?
+            .and_then(|receiver| receiver.downcast::<broadcast::Receiver<DbEvent>>().ok())
+            .ok_or_else(|| {
+                LocaiError::Configuration(
+                    "Replication requires a storage backend with live queries".to_string(),
+                )
+            })?;
+
+        let (changes, _) = broadcast::channel(config.buffer_size.max(1));
+        let replicator = Arc::new(Self {
+            storage,
+            config,
+            versions: Mutex::new(HashMap::new()),
+            pending_echoes: Mutex::new(HashMap::new()),
+            changes,
+        });
+
+        let weak = Arc::downgrade(&replicator);
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!("Replication missed {} local changes", missed);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let Some(replicator) = weak.upgrade() else {
+                    break;
+                };
+                replicator.capture(event);
+            }
+        });
+
+        tracing::info!("Replication started on node {}", replicator.node_id());
+        Ok(replicator)
+    }
+
+    /// Name of this node
+    pub fn node_id(&self) -> &str {
+        &self.config.node_id
+    }
+
+    /// Replication settings
+    pub fn config(&self) -> &ReplicationConfig {
+        &self.config
+    }
+
+    /// Subscribe to changes made on this node
+    ///
+    /// Changes applied from peers are not included, so two nodes can
+    /// replicate to each other without echoing changes back.
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.changes.subscribe()
+    }
+
+    /// Local vector clock of a record, if it has been changed since start
+    pub fn clock(&self, kind: RecordKind, id: &str) -> Option<VectorClock> {
+        self.versions
+            .lock()
+            .unwrap()
+            .get(&(kind, id.to_string()))
+            .map(|version| version.clock.clone())
+    }
+
+    /// Apply a change received from a peer
+    pub async fn apply(&self, event: ChangeEvent) -> Result<ApplyOutcome> {
+        if event.origin == self.config.node_id {
+            return Ok(ApplyOutcome::Ignored);
+        }
+
+        let key = (event.kind, event.id.clone());
+        let outcome = match self.versions.lock().unwrap().get(&key) {
+            None => ApplyOutcome::Applied,
+            Some(local) => self.resolve(local, &event),
+        };
+
+        let applied = matches!(
+            outcome,
+            ApplyOutcome::Applied | ApplyOutcome::Conflict { applied: true }
+        );
+        if applied {
+            *self
+                .pending_echoes
+                .lock()
+                .unwrap()
+                .entry(key.clone())
+                .or_insert(0) += 1;
+            match self.write(&event).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.take_echo(&key);
+                }
+                Err(e) => {
+                    self.take_echo(&key);
+                    return Err(e);
+                }
+            }
+        }
+
+        let republish = {
+            let mut versions = self.versions.lock().unwrap();
+            let version = versions.entry(key).or_default();
+            version.clock.merge(&event.clock);
+            if applied {
+                version.timestamp = event.timestamp;
+                version.origin = event.origin.clone();
+                None
+            } else if outcome == (ApplyOutcome::Conflict { applied: false }) {
+                // The local copy won: bump its clock past both versions so
+                // the peer accepts it when it comes back
+                version.clock.increment(&self.config.node_id);
+                Some(version.clone())
+            } else {
+                None
+            }
+        };
+        if let Some(version) = republish {
+            self.republish(event.kind, &event.id, version).await?;
+        }
+
+        tracing::debug!(
+            "Replicated {:?} {:?} {} from {}: {:?}",
+            event.op,
+            event.kind,
+            event.id,
+            event.origin,
+            outcome
+        );
+        Ok(outcome)
+    }
+
+    fn resolve(&self, local: &RecordVersion, event: &ChangeEvent) -> ApplyOutcome {
+        let remote_is_later =
+            (event.timestamp, event.origin.as_str()) > (local.timestamp, local.origin.as_str());
+        match self.config.conflict_policy {
+            ConflictPolicy::LastWriteWins if remote_is_later => ApplyOutcome::Applied,
+            ConflictPolicy::LastWriteWins => ApplyOutcome::Stale,
+            ConflictPolicy::VectorClock => match event.clock.compare(&local.clock) {
+                ClockOrdering::After => ApplyOutcome::Applied,
+                ClockOrdering::Before | ClockOrdering::Equal => ApplyOutcome::Stale,
+                ClockOrdering::Concurrent => ApplyOutcome::Conflict {
+                    applied: remote_is_later,
+                },
+            },
+        }
+    }
+
+    /// Write a remote change; returns whether the store was modified
+    async fn write(&self, event: &ChangeEvent) -> Result<bool> {
+        let storage = self.storage.as_ref();
+        match (event.op, &event.record) {
+            (ChangeOp::Delete, _) => delete_record(storage, event.kind, &event.id).await,
+            (ChangeOp::Upsert, Some(record)) => {
+                upsert_record(storage, event.kind, &event.id, record.clone()).await?;
+                Ok(true)
+            }
+            (ChangeOp::Upsert, None) => Err(LocaiError::Protocol(format!(
+                "Upsert of {} carries no record",
+                event.id
+            ))),
+        }
+    }
+
+    /// Publish the current local copy of a record under `version`
+    async fn republish(&self, kind: RecordKind, id: &str, version: RecordVersion) -> Result<()> {
+        let record = read_record(self.storage.as_ref(), kind, id).await?;
+        let _ = self.changes.send(ChangeEvent {
+            origin: self.config.node_id.clone(),
+            kind,
+            id: id.to_string(),
+            op: if record.is_some() {
+                ChangeOp::Upsert
+            } else {
+                ChangeOp::Delete
+            },
+            record,
+            timestamp: Utc::now(),
+            clock: version.clock,
+        });
+        Ok(())
+    }
+
+    /// Record a change from the local change stream and publish it
+    fn capture(&self, event: DbEvent) {
+        let Some(kind) = RecordKind::from_table(&event.table) else {
+            return;
+        };
+        if event.id.is_empty() {
+            return;
+        }
+        let key = (kind, event.id.clone());
+        if self.take_echo(&key) {
+            return;
+        }
+
+        let op = if event.action == "DELETE" {
+            ChangeOp::Delete
+        } else {
+            ChangeOp::Upsert
+        };
+        let timestamp = Utc::now();
+        let clock = {
+            let mut versions = self.versions.lock().unwrap();
+            let version = versions.entry(key).or_default();
+            version.clock.increment(&self.config.node_id);
+            version.timestamp = timestamp;
+            version.origin = self.config.node_id.clone();
+            version.clock.clone()
+        };
+
+        // Sending only fails when no peer is connected
+        let _ = self.changes.send(ChangeEvent {
+            origin: self.config.node_id.clone(),
+            kind,
+            id: event.id,
+            op,
+            record: (op == ChangeOp::Upsert).then_some(event.result),
+            timestamp,
+            clock,
+        });
+    }
+
+    /// Consume one pending echo for `key`; returns whether there was one
+    fn take_echo(&self, key: &RecordKey) -> bool {
+        let mut echoes = self.pending_echoes.lock().unwrap();
+        match echoes.get_mut(key) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                true
+            }
+            Some(_) => {
+                echoes.remove(key);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Read a record as JSON
+pub(crate) async fn read_record(
+    storage: &dyn GraphStore,
+    kind: RecordKind,
+    id: &str,
+) -> Result<Option<Value>> {
+    let storage_error =
+        |e: StorageError| LocaiError::Storage(format!("Failed to read {:?} {}: {}", kind, id, e));
+    match kind {
+        RecordKind::Memory => storage
+            .get_memory(id)
+            .await
+            .map_err(storage_error)?
+            .map(serde_json::to_value),
+        RecordKind::Entity => storage
+            .get_entity(id)
+            .await
+            .map_err(storage_error)?
+            .map(serde_json::to_value),
+        RecordKind::Relationship => storage
+            .get_relationship(id)
+            .await
+            .map_err(storage_error)?
+            .map(serde_json::to_value),
+    }
+    .transpose()
+    .map_err(|e| LocaiError::Other(format!("Failed to serialize {}: {}", id, e)))
+}
+
+/// Write a JSON record under its own ID; returns the stored record
+pub(crate) async fn upsert_record(
+    storage: &dyn GraphStore,
+    kind: RecordKind,
+    id: &str,
+    record: Value,
+) -> Result<Value> {
+    let storage_error =
+        |e: StorageError| LocaiError::Storage(format!("Failed to write {:?} {}: {}", kind, id, e));
+    let invalid = |e: serde_json::Error| {
+        LocaiError::Protocol(format!("Invalid {:?} record {}: {}", kind, id, e))
+    };
+    match kind {
+        RecordKind::Memory => {
+            let memory = serde_json::from_value(record).map_err(invalid)?;
+            serde_json::to_value(storage.upsert_memory(memory).await.map_err(storage_error)?)
+        }
+        RecordKind::Entity => {
+            let entity = serde_json::from_value(record).map_err(invalid)?;
+            serde_json::to_value(storage.upsert_entity(entity).await.map_err(storage_error)?)
+        }
+        RecordKind::Relationship => {
+            let relationship = serde_json::from_value(record).map_err(invalid)?;
+            serde_json::to_value(
+                storage
+                    .upsert_relationship(relationship)
+                    .await
+                    .map_err(storage_error)?,
+            )
+        }
+    }
+    .map_err(|e| LocaiError::Other(format!("Failed to serialize {}: {}", id, e)))
+}
+
+/// Delete a record; returns whether it existed
+pub(crate) async fn delete_record(
+    storage: &dyn GraphStore,
+    kind: RecordKind,
+    id: &str,
+) -> Result<bool> {
+    match kind {
+        RecordKind::Memory => storage.delete_memory(id).await,
+        RecordKind::Entity => storage.delete_entity(id).await,
+        RecordKind::Relationship => storage.delete_relationship(id).await,
+    }
+    .map_err(|e| LocaiError::Storage(format!("Failed to delete {:?} {}: {}", kind, id, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_policy_from_str() {
+        assert_eq!(
+            "lww".parse::<ConflictPolicy>().unwrap(),
+            ConflictPolicy::LastWriteWins
+        );
+        assert_eq!(
+            "vector-clock".parse::<ConflictPolicy>().unwrap(),
+            ConflictPolicy::VectorClock
+        );
+        assert!("newest".parse::<ConflictPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_change_event_round_trip() {
+        let mut clock = VectorClock::new();
+        clock.increment("edge");
+        let event = ChangeEvent {
+            origin: "edge".to_string(),
+            kind: RecordKind::Entity,
+            id: "alice".to_string(),
+            op: ChangeOp::Upsert,
+            record: Some(serde_json::json!({ "id": "alice" })),
+            timestamp: Utc::now(),
+            clock,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["kind"], "entity");
+        assert_eq!(json["op"], "upsert");
+        assert_eq!(json["clock"]["edge"], 1);
+        assert_eq!(serde_json::from_value::<ChangeEvent>(json).unwrap(), event);
+    }
+}
@@ -0,0 +1,692 @@
+//! Offline-first writes
+//!
+//! An [`OfflineQueue`] sits in front of a store that can drop off the
+//! network, typically a remote SurrealDB reached over a flaky link. While the
+//! store answers, writes go straight through. When a write fails and a health
+//! check confirms the store is unreachable, the queue goes offline and appends
+//! each mutation to a local write-ahead log instead. Reads made through the
+//! queue see queued writes, so an agent keeps working against its own view.
+//!
+//! [`OfflineQueue::reconcile`] replays the log in order once the store is
+//! back, and runs on its own every [`OfflineConfig::retry_interval`]. A queued
+//! write conflicts when the remote record no longer matches the copy the
+//! write was made against; the [`OfflineConflictPolicy`] decides which side
+//! is kept, and every conflict is reported so nothing is dropped silently.
+//!
+//! ```rust,no_run
+//! use locai::prelude::*;
+//! use locai::replication::offline::{OfflineConfig, OfflineQueue};
+//!
+//! async fn remember(manager: &MemoryManager) -> Result<()> {
+//!     let config = OfflineConfig {
+//!         queue_path: Some("/var/lib/agent/locai-queue.jsonl".into()),
+//!         ..OfflineConfig::default()
+//!     };
+//!     let queue = OfflineQueue::open(manager.storage().clone(), config).await?;
+//!     queue
+//!         .put_memory(MemoryBuilder::fact("Door code changed to 4812").build())
+//!         .await?;
+//!     println!("{} writes waiting", queue.pending_writes().len());
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{HashSet, VecDeque};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use lru::LruCache;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+
+use super::{ChangeOp, RecordKey, RecordKind, delete_record, read_record, upsert_record};
+use crate::models::Memory;
+use crate::storage::models::{Entity, Relationship};
+use crate::storage::traits::GraphStore;
+use crate::{LocaiError, Result};
+
+/// How long a health check may take before the store counts as unreachable
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Which side wins when a queued write conflicts with the remote record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineConflictPolicy {
+    /// Leave the remote record as it is and report the queued write
+    #[default]
+    KeepRemote,
+
+    /// Overwrite the remote record with the queued write and report the
+    /// remote copy that was replaced
+    KeepLocal,
+}
+
+/// Offline queue settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineConfig {
+    /// Write-ahead log for queued writes; `None` keeps the queue in memory
+    /// only, so it does not survive a restart
+    pub queue_path: Option<PathBuf>,
+
+    /// Conflict resolution policy
+    pub conflict_policy: OfflineConflictPolicy,
+
+    /// How often to retry the store while offline; `None` leaves
+    /// reconciliation to explicit [`OfflineQueue::reconcile`] calls
+    #[serde(with = "humantime_serde")]
+    pub retry_interval: Option<Duration>,
+
+    /// Number of remote records remembered as the base for conflict checks
+    pub base_cache_size: usize,
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            queue_path: None,
+            conflict_policy: OfflineConflictPolicy::default(),
+            retry_interval: Some(Duration::from_secs(30)),
+            base_cache_size: 10_000,
+        }
+    }
+}
+
+/// A mutation waiting for the store to come back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingWrite {
+    /// Position in the queue
+    pub seq: u64,
+
+    /// Kind of record
+    pub kind: RecordKind,
+
+    /// Record ID
+    pub id: String,
+
+    /// Operation
+    pub op: ChangeOp,
+
+    /// Full record for upserts
+    pub record: Option<Value>,
+
+    /// When the write was queued
+    pub queued_at: DateTime<Utc>,
+
+    /// Copy of the record the write was made against, or `None` if the
+    /// record was not known to exist
+    pub base: Option<Value>,
+}
+
+/// Which side was kept when a conflict was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// The queued write was applied over the remote record
+    KeptLocal,
+    /// The queued write was dropped
+    KeptRemote,
+}
+
+/// A queued write whose remote record changed while offline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteConflict {
+    /// The queued write
+    pub write: PendingWrite,
+
+    /// Remote record found at reconcile time (`None` if it was deleted)
+    pub remote: Option<Value>,
+
+    /// How the conflict was resolved
+    pub resolution: ConflictResolution,
+
+    /// When the conflict was found
+    pub detected_at: DateTime<Utc>,
+}
+
+/// A queued write the store rejected during reconciliation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedWrite {
+    /// The queued write, removed from the queue
+    pub write: PendingWrite,
+
+    /// Error returned by the store
+    pub error: String,
+}
+
+/// Outcome of one reconciliation pass
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    /// Writes applied without conflict
+    pub applied: usize,
+
+    /// Writes that conflicted, whichever side was kept
+    pub conflicts: Vec<WriteConflict>,
+
+    /// Writes the store rejected
+    pub failed: Vec<FailedWrite>,
+
+    /// Writes still queued because the store dropped out again mid-pass
+    pub remaining: usize,
+}
+
+/// Buffers writes while the store is unreachable and replays them later
+pub struct OfflineQueue {
+    storage: Arc<dyn GraphStore>,
+    config: OfflineConfig,
+    online: AtomicBool,
+    writes: Mutex<VecDeque<PendingWrite>>,
+    next_seq: Mutex<u64>,
+    /// Serializes writes and replay so the log keeps the order writes were made
+    order: tokio::sync::Mutex<()>,
+    /// Last known remote copy of each record (`None` when known to be absent)
+    bases: Mutex<LruCache<RecordKey, Option<Value>>>,
+    conflicts: Mutex<Vec<WriteConflict>>,
+}
+
+impl std::fmt::Debug for OfflineQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OfflineQueue")
+            .field("config", &self.config)
+            .field("online", &self.is_online())
+            .finish_non_exhaustive()
+    }
+}
+
+impl OfflineQueue {
+    /// Open the queue in front of `storage`, loading writes left in the log
+    ///
+    /// The queue starts offline when the log holds writes, and replays them
+    /// on the first reconciliation.
+    pub async fn open(storage: Arc<dyn GraphStore>, config: OfflineConfig) -> Result<Arc<Self>> {
+        let writes = match &config.queue_path {
+            Some(path) => load_log(path).await?,
+            None => VecDeque::new(),
+        };
+        let next_seq = writes.back().map_or(0, |write| write.seq + 1);
+        let cache_size = NonZeroUsize::new(config.base_cache_size).unwrap_or(NonZeroUsize::MIN);
+
+        let queue = Arc::new(Self {
+            storage,
+            online: AtomicBool::new(writes.is_empty()),
+            writes: Mutex::new(writes),
+            next_seq: Mutex::new(next_seq),
+            order: tokio::sync::Mutex::new(()),
+            bases: Mutex::new(LruCache::new(cache_size)),
+            conflicts: Mutex::new(Vec::new()),
+            config,
+        });
+
+        if let Some(interval) = queue.config.retry_interval {
+            let weak = Arc::downgrade(&queue);
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let Some(queue) = weak.upgrade() else {
+                        break;
+                    };
+                    if queue.is_online() {
+                        continue;
+                    }
+                    match queue.reconcile().await {
+                        Ok(report) if !report.conflicts.is_empty() => tracing::warn!(
+                            "Offline queue reconciled with {} conflicts",
+                            report.conflicts.len()
+                        ),
+                        Ok(_) => {}
+                        Err(e) => tracing::debug!("Store still unreachable: {}", e),
+                    }
+                }
+            });
+        }
+
+        Ok(queue)
+    }
+
+    /// Whether writes currently go straight to the store
+    pub fn is_online(&self) -> bool {
+        self.online.load(Ordering::SeqCst)
+    }
+
+    /// Queue writes from now on without trying the store, e.g. when the host
+    /// knows the network is down; the next reconciliation goes back online
+    pub fn go_offline(&self) {
+        if self.online.swap(false, Ordering::SeqCst) {
+            tracing::info!("Offline queue switched to offline mode");
+        }
+    }
+
+    /// Writes waiting to be replayed, oldest first
+    pub fn pending_writes(&self) -> Vec<PendingWrite> {
+        self.writes.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Conflicts found by every reconciliation so far
+    pub fn conflicts(&self) -> Vec<WriteConflict> {
+        self.conflicts.lock().unwrap().clone()
+    }
+
+    /// Take the reported conflicts, clearing the list
+    pub fn take_conflicts(&self) -> Vec<WriteConflict> {
+        std::mem::take(&mut *self.conflicts.lock().unwrap())
+    }
+
+    /// Store a memory under its ID, generating one if it is empty
+    ///
+    /// Like [`GraphStore::upsert_memory`], this skips versioning and hooks.
+    pub async fn put_memory(&self, mut memory: Memory) -> Result<Memory> {
+        if memory.id.is_empty() {
+            memory.id = uuid::Uuid::new_v4().to_string();
+        }
+        let id = memory.id.clone();
+        self.put(RecordKind::Memory, &id, to_value(&memory)?).await
+    }
+
+    /// Store an entity under its ID
+    pub async fn put_entity(&self, entity: Entity) -> Result<Entity> {
+        let id = entity.id.clone();
+        self.put(RecordKind::Entity, &id, to_value(&entity)?).await
+    }
+
+    /// Store a relationship under its ID, generating one if it is empty
+    pub async fn put_relationship(&self, mut relationship: Relationship) -> Result<Relationship> {
+        if relationship.id.is_empty() {
+            relationship.id = uuid::Uuid::new_v4().to_string();
+        }
+        let id = relationship.id.clone();
+        self.put(RecordKind::Relationship, &id, to_value(&relationship)?)
+            .await
+    }
+
+    /// Delete a memory
+    pub async fn delete_memory(&self, id: &str) -> Result<bool> {
+        self.delete(RecordKind::Memory, id).await
+    }
+
+    /// Delete an entity
+    pub async fn delete_entity(&self, id: &str) -> Result<bool> {
+        self.delete(RecordKind::Entity, id).await
+    }
+
+    /// Delete a relationship
+    pub async fn delete_relationship(&self, id: &str) -> Result<bool> {
+        self.delete(RecordKind::Relationship, id).await
+    }
+
+    /// Get a memory, including queued writes
+    pub async fn get_memory(&self, id: &str) -> Result<Option<Memory>> {
+        self.get(RecordKind::Memory, id).await
+    }
+
+    /// Get an entity, including queued writes
+    pub async fn get_entity(&self, id: &str) -> Result<Option<Entity>> {
+        self.get(RecordKind::Entity, id).await
+    }
+
+    /// Get a relationship, including queued writes
+    pub async fn get_relationship(&self, id: &str) -> Result<Option<Relationship>> {
+        self.get(RecordKind::Relationship, id).await
+    }
+
+    /// Replay queued writes against the store
+    ///
+    /// Fails without touching the queue if the store is still unreachable.
+    /// Rejected writes are dropped from the queue and listed in the report.
+    pub async fn reconcile(&self) -> Result<ReconcileReport> {
+        let _order = self.order.lock().await;
+        if !self.reachable().await {
+            return Err(LocaiError::Connection(
+                "Store is still unreachable".to_string(),
+            ));
+        }
+
+        let mut report = ReconcileReport::default();
+        let mut written = HashSet::new();
+        loop {
+            let Some(write) = self.writes.lock().unwrap().front().cloned() else {
+                break;
+            };
+            match self.replay(&write, &mut written).await {
+                Ok(None) => report.applied += 1,
+                Ok(Some(conflict)) => report.conflicts.push(conflict),
+                Err(_) if !self.reachable().await => break,
+                Err(e) => {
+                    tracing::warn!("Dropping queued write to {}: {}", write.id, e);
+                    report.failed.push(FailedWrite {
+                        write,
+                        error: e.to_string(),
+                    });
+                }
+            }
+            self.writes.lock().unwrap().pop_front();
+        }
+
+        report.remaining = self.writes.lock().unwrap().len();
+        self.rewrite_log().await?;
+        self.conflicts
+            .lock()
+            .unwrap()
+            .extend(report.conflicts.iter().cloned());
+        if report.remaining == 0 {
+            self.online.store(true, Ordering::SeqCst);
+        }
+
+        tracing::info!(
+            "Offline queue reconciled: {} applied, {} conflicts, {} failed, {} remaining",
+            report.applied,
+            report.conflicts.len(),
+            report.failed.len(),
+            report.remaining
+        );
+        Ok(report)
+    }
+
+    async fn put<T: DeserializeOwned>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        record: Value,
+    ) -> Result<T> {
+        let _order = self.order.lock().await;
+        if self.can_write_through() {
+            match upsert_record(self.storage.as_ref(), kind, id, record.clone()).await {
+                Ok(stored) => {
+                    self.remember(kind, id, Some(stored.clone()));
+                    return from_value(stored);
+                }
+                Err(e) if self.reachable().await => return Err(e),
+                Err(_) => self.lose_connection(),
+            }
+        }
+        self.enqueue(kind, id, ChangeOp::Upsert, Some(record.clone()))
+            .await?;
+        from_value(record)
+    }
+
+    async fn delete(&self, kind: RecordKind, id: &str) -> Result<bool> {
+        let _order = self.order.lock().await;
+        if self.can_write_through() {
+            match delete_record(self.storage.as_ref(), kind, id).await {
+                Ok(existed) => {
+                    self.remember(kind, id, None);
+                    return Ok(existed);
+                }
+                Err(e) if self.reachable().await => return Err(e),
+                Err(_) => self.lose_connection(),
+            }
+        }
+        // Without the store, assume the record exists unless known otherwise
+        let existed = !matches!(self.local_copy(kind, id), Some(None));
+        self.enqueue(kind, id, ChangeOp::Delete, None).await?;
+        Ok(existed)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, kind: RecordKind, id: &str) -> Result<Option<T>> {
+        if let Some(record) = self.queued_copy(kind, id) {
+            return record.map(from_value).transpose();
+        }
+        if self.is_online() {
+            match read_record(self.storage.as_ref(), kind, id).await {
+                Ok(record) => {
+                    self.remember(kind, id, record.clone());
+                    return record.map(from_value).transpose();
+                }
+                Err(e) if self.reachable().await => return Err(e),
+                Err(_) => self.lose_connection(),
+            }
+        }
+        self.local_copy(kind, id)
+            .flatten()
+            .map(from_value)
+            .transpose()
+    }
+
+    /// Replay one write; returns the conflict it raised, if any
+    async fn replay(
+        &self,
+        write: &PendingWrite,
+        written: &mut HashSet<RecordKey>,
+    ) -> Result<Option<WriteConflict>> {
+        let key = (write.kind, write.id.clone());
+        let storage = self.storage.as_ref();
+
+        // Later writes to a record this pass already wrote build on that write
+        let mut conflict = None;
+        if !written.contains(&key) {
+            let current = read_record(storage, write.kind, &write.id).await?;
+            if !same_record(&current, &write.base) && !same_record(&current, &write.record) {
+                let resolution = match self.config.conflict_policy {
+                    OfflineConflictPolicy::KeepLocal => ConflictResolution::KeptLocal,
+                    OfflineConflictPolicy::KeepRemote => ConflictResolution::KeptRemote,
+                };
+                tracing::warn!(
+                    "Queued {:?} of {:?} {} conflicts with the remote record ({:?})",
+                    write.op,
+                    write.kind,
+                    write.id,
+                    resolution
+                );
+                let keep_remote = resolution == ConflictResolution::KeptRemote;
+                conflict = Some(WriteConflict {
+                    write: write.clone(),
+                    remote: current.clone(),
+                    resolution,
+                    detected_at: Utc::now(),
+                });
+                if keep_remote {
+                    self.remember(write.kind, &write.id, current);
+                    return Ok(conflict);
+                }
+            }
+        }
+
+        let stored = match (&write.record, write.op) {
+            (Some(record), ChangeOp::Upsert) => {
+                Some(upsert_record(storage, write.kind, &write.id, record.clone()).await?)
+            }
+            _ => {
+                delete_record(storage, write.kind, &write.id).await?;
+                None
+            }
+        };
+        self.remember(write.kind, &write.id, stored);
+        written.insert(key);
+        Ok(conflict)
+    }
+
+    /// Online with nothing queued ahead of a new write
+    fn can_write_through(&self) -> bool {
+        self.is_online() && self.writes.lock().unwrap().is_empty()
+    }
+
+    fn lose_connection(&self) {
+        if self.online.swap(false, Ordering::SeqCst) {
+            tracing::warn!("Store unreachable; queueing writes until it returns");
+        }
+    }
+
+    async fn reachable(&self) -> bool {
+        matches!(
+            tokio::time::timeout(PROBE_TIMEOUT, self.storage.health_check()).await,
+            Ok(Ok(true))
+        )
+    }
+
+    async fn enqueue(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        op: ChangeOp,
+        record: Option<Value>,
+    ) -> Result<()> {
+        let base = self.local_copy(kind, id).flatten();
+        let write = {
+            let mut next_seq = self.next_seq.lock().unwrap();
+            let write = PendingWrite {
+                seq: *next_seq,
+                kind,
+                id: id.to_string(),
+                op,
+                record,
+                queued_at: Utc::now(),
+                base,
+            };
+            *next_seq += 1;
+            write
+        };
+
+        if let Some(path) = &self.config.queue_path {
+            append_to_log(path, &write).await?;
+        }
+
+        tracing::debug!("Queued {:?} of {:?} {}", op, kind, id);
+        self.writes.lock().unwrap().push_back(write);
+        Ok(())
+    }
+
+    /// Replace the log with the writes still queued
+    async fn rewrite_log(&self) -> Result<()> {
+        let Some(path) = &self.config.queue_path else {
+            return Ok(());
+        };
+        let mut contents = String::new();
+        for write in self.writes.lock().unwrap().iter() {
+            let line = serde_json::to_string(write)
+                .map_err(|e| LocaiError::Other(format!("Failed to encode queued write: {}", e)))?;
+            contents.push_str(&line);
+            contents.push('\n');
+        }
+        tokio::fs::write(path, contents)
+            .await
+            .map_err(|e| log_error(path, e))
+    }
+
+    /// Latest queued version of a record: `Some(None)` for a queued delete
+    fn queued_copy(&self, kind: RecordKind, id: &str) -> Option<Option<Value>> {
+        self.writes
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|write| write.kind == kind && write.id == id)
+            .map(|write| write.record.clone())
+    }
+
+    /// Record as this node sees it: the latest queued write, else the last
+    /// known remote copy; `None` if neither is known
+    fn local_copy(&self, kind: RecordKind, id: &str) -> Option<Option<Value>> {
+        self.queued_copy(kind, id).or_else(|| {
+            self.bases
+                .lock()
+                .unwrap()
+                .get(&(kind, id.to_string()))
+                .cloned()
+        })
+    }
+
+    fn remember(&self, kind: RecordKind, id: &str, record: Option<Value>) {
+        self.bases
+            .lock()
+            .unwrap()
+            .put((kind, id.to_string()), record);
+    }
+}
+
+/// Append a write to a write-ahead log, syncing it to disk
+pub(crate) async fn append_to_log(path: &Path, write: &PendingWrite) -> Result<()> {
+    let mut line = serde_json::to_string(write)
+        .map_err(|e| LocaiError::Other(format!("Failed to encode queued write: {}", e)))?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|e| log_error(path, e))?;
+    file.write_all(line.as_bytes())
+        .await
+        .map_err(|e| log_error(path, e))?;
+    file.sync_data().await.map_err(|e| log_error(path, e))
+}
+
+pub(crate) async fn load_log(path: &Path) -> Result<VecDeque<PendingWrite>> {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VecDeque::new()),
+        Err(e) => return Err(log_error(path, e)),
+    };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| {
+                LocaiError::Storage(format!(
+                    "Corrupt offline queue entry in {}: {}",
+                    path.display(),
+                    e
+                ))
+            })
+        })
+        .collect()
+}
+
+fn log_error(path: &std::path::Path, e: std::io::Error) -> LocaiError {
+    LocaiError::Storage(format!(
+        "Failed to write offline queue {}: {}",
+        path.display(),
+        e
+    ))
+}
+
+/// Compare two copies of a record, ignoring the access statistics that
+/// every memory read updates
+pub(crate) fn same_record(a: &Option<Value>, b: &Option<Value>) -> bool {
+    const ACCESS_FIELDS: [&str; 2] = ["last_accessed", "access_count"];
+    let strip = |record: &Option<Value>| {
+        record.clone().map(|mut record| {
+            if let Some(fields) = record.as_object_mut() {
+                for field in ACCESS_FIELDS {
+                    fields.remove(field);
+                }
+            }
+            record
+        })
+    };
+    strip(a) == strip(b)
+}
+
+fn to_value<T: Serialize>(record: &T) -> Result<Value> {
+    serde_json::to_value(record)
+        .map_err(|e| LocaiError::Other(format!("Failed to serialize record: {}", e)))
+}
+
+fn from_value<T: DeserializeOwned>(record: Value) -> Result<T> {
+    serde_json::from_value(record)
+        .map_err(|e| LocaiError::Other(format!("Failed to deserialize record: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_write_log_line() {
+        let write = PendingWrite {
+            seq: 3,
+            kind: RecordKind::Entity,
+            id: "alice".to_string(),
+            op: ChangeOp::Delete,
+            record: None,
+            queued_at: Utc::now(),
+            base: Some(serde_json::json!({ "id": "alice" })),
+        };
+        let line = serde_json::to_string(&write).unwrap();
+        assert!(!line.contains('\n'));
+        assert_eq!(serde_json::from_str::<PendingWrite>(&line).unwrap(), write);
+    }
+}
@@ -0,0 +1,214 @@
+//! WebSocket transport for replication
+//!
+//! The connecting node opens a session with [`ReplicationMessage::Hello`],
+//! naming the direction. With [`SyncMode::Push`] it streams its own changes
+//! and the server applies them; with [`SyncMode::Pull`] the server streams
+//! its changes and the connecting node applies them. Run one session in each
+//! direction for two-way sync.
+//!
+//! [`run_session`] drives either side over any message stream; locai-server
+//! serves it at `/api/replication/ws`.
+
+use std::sync::Arc;
+
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Message as WsMessage, http::HeaderValue};
+
+use super::{ChangeEvent, Replicator};
+use crate::{LocaiError, Result};
+
+/// Which node's changes flow through a session, seen from the connecting node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncMode {
+    /// Send local changes to the server
+    Push,
+    /// Receive the server's changes
+    Pull,
+}
+
+impl SyncMode {
+    /// Role of the connecting node
+    pub fn client_role(self) -> Role {
+        match self {
+            Self::Push => Role::Send,
+            Self::Pull => Role::Receive,
+        }
+    }
+
+    /// Role of the accepting node
+    pub fn server_role(self) -> Role {
+        match self {
+            Self::Push => Role::Receive,
+            Self::Pull => Role::Send,
+        }
+    }
+}
+
+/// What one side of a session does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Stream local changes to the peer
+    Send,
+    /// Apply changes from the peer
+    Receive,
+}
+
+/// Frames exchanged over a replication WebSocket, as JSON text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum ReplicationMessage {
+    /// First frame from the connecting node
+    Hello { node_id: String, mode: SyncMode },
+
+    /// A change to apply
+    Change(ChangeEvent),
+
+    /// The peer is closing the session because of an error
+    Error { message: String },
+}
+
+impl ReplicationMessage {
+    /// Encode as a JSON text frame
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self)
+            .map_err(|e| LocaiError::Protocol(format!("Failed to encode replication frame: {}", e)))
+    }
+
+    /// Decode a JSON text frame
+    pub fn from_json(text: &str) -> Result<Self> {
+        serde_json::from_str(text)
+            .map_err(|e| LocaiError::Protocol(format!("Invalid replication frame: {}", e)))
+    }
+}
+
+/// Drive one side of a session until the peer disconnects
+///
+/// Changes that fail to apply are logged and skipped, so one bad record does
+/// not stall the stream.
+pub async fn run_session<I, O>(
+    replicator: &Replicator,
+    role: Role,
+    mut incoming: I,
+    mut outgoing: O,
+) -> Result<()>
+where
+    I: Stream<Item = Result<ReplicationMessage>> + Unpin,
+    O: Sink<ReplicationMessage, Error = LocaiError> + Unpin,
+{
+    match role {
+        Role::Send => {
+            let mut changes = replicator.subscribe();
+            loop {
+                tokio::select! {
+                    change = changes.recv() => match change {
+                        Ok(change) => outgoing.send(ReplicationMessage::Change(change)).await?,
+                        Err(RecvError::Lagged(missed)) => {
+                            tracing::warn!("Replication peer fell behind; {} changes were not sent", missed);
+                        }
+                        Err(RecvError::Closed) => return Ok(()),
+                    },
+                    message = incoming.next() => match message {
+                        None => return Ok(()),
+                        Some(Ok(ReplicationMessage::Error { message })) => {
+                            return Err(LocaiError::Protocol(message));
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => return Err(e),
+                    },
+                }
+            }
+        }
+        Role::Receive => {
+            while let Some(message) = incoming.next().await {
+                match message? {
+                    ReplicationMessage::Change(event) => {
+                        let id = event.id.clone();
+                        if let Err(e) = replicator.apply(event).await {
+                            tracing::warn!("Failed to apply replicated change to {}: {}", id, e);
+                        }
+                    }
+                    ReplicationMessage::Error { message } => {
+                        return Err(LocaiError::Protocol(message));
+                    }
+                    ReplicationMessage::Hello { .. } => {}
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Connect to a peer's replication endpoint and sync until it disconnects
+///
+/// `token` is sent as a bearer token, for servers with authentication on.
+pub async fn connect(
+    replicator: Arc<Replicator>,
+    url: &str,
+    mode: SyncMode,
+    token: Option<&str>,
+) -> Result<()> {
+    let mut request = url.into_client_request().map_err(|e| {
+        LocaiError::Configuration(format!("Invalid replication URL {}: {}", url, e))
+    })?;
+    if let Some(token) = token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| LocaiError::Configuration(format!("Invalid token: {}", e)))?;
+        request.headers_mut().insert("Authorization", value);
+    }
+
+    let (socket, _) = connect_async(request)
+        .await
+        .map_err(|e| LocaiError::Connection(format!("Failed to connect to {}: {}", url, e)))?;
+    let (write, read) = socket.split();
+
+    let outgoing = write
+        .sink_map_err(|e| LocaiError::Connection(e.to_string()))
+        .with(|message: ReplicationMessage| async move {
+            message.to_json().map(|json| WsMessage::Text(json.into()))
+        });
+    let mut outgoing = std::pin::pin!(outgoing);
+    outgoing
+        .send(ReplicationMessage::Hello {
+            node_id: replicator.node_id().to_string(),
+            mode,
+        })
+        .await?;
+
+    let incoming = read.filter_map(|frame| async move {
+        match frame {
+            Ok(WsMessage::Text(text)) => Some(ReplicationMessage::from_json(&text)),
+            Ok(_) => None,
+            Err(e) => Some(Err(LocaiError::Connection(e.to_string()))),
+        }
+    });
+    let incoming = std::pin::pin!(incoming);
+
+    tracing::info!("Replicating with {} ({:?})", url, mode);
+    run_session(&replicator, mode.client_role(), incoming, outgoing).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hello_frame() {
+        let hello = ReplicationMessage::Hello {
+            node_id: "edge".to_string(),
+            mode: SyncMode::Push,
+        };
+        let json = hello.to_json().unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"Hello","data":{"node_id":"edge","mode":"push"}}"#
+        );
+        assert_eq!(ReplicationMessage::from_json(&json).unwrap(), hello);
+        assert_eq!(SyncMode::Push.server_role(), Role::Receive);
+        assert_eq!(SyncMode::Pull.server_role(), Role::Send);
+    }
+}
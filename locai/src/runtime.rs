@@ -1,9 +1,109 @@
 //! Runtime configuration optimized for SurrealDB embedded use
 //!
 //! This module provides utilities for creating and configuring tokio runtimes
-//! according to SurrealDB performance best practices.
+//! according to SurrealDB performance best practices. Runtimes are tuned with
+//! [`RuntimeConfig`], which is also read from the `runtime` section of
+//! [`LocaiConfig`](crate::config::LocaiConfig).
+//!
+//! Applications with a runtime of their own can keep it: call
+//! [`init`](crate::init) from it, or [`init_on_runtime`](crate::init_on_runtime)
+//! to have Locai's background tasks run on a specific runtime handle.
+//!
+//! When several processes share one database, [`Lease`] makes sure a
+//! periodic background task runs in only one of them at a time.
 
 use std::io;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use tokio::runtime::{Builder, Handle, Runtime};
+
+pub use crate::config::RuntimeConfig;
+use crate::storage::traits::GraphStore;
+
+impl RuntimeConfig {
+    /// Build a multi-threaded runtime with these settings
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use locai::runtime::RuntimeConfig;
+    ///
+    /// let runtime = RuntimeConfig {
+    ///     worker_threads: Some(4),
+    ///     max_blocking_threads: Some(32),
+    ///     ..RuntimeConfig::default()
+    /// }
+    /// .build()
+    /// .expect("Failed to create runtime");
+    /// ```
+    pub fn build(&self) -> io::Result<Runtime> {
+        self.builder(self.worker_threads, &self.thread_name).build()
+    }
+
+    fn builder(&self, worker_threads: Option<usize>, thread_name: &str) -> Builder {
+        let mut builder = Builder::new_multi_thread();
+        builder
+            .enable_all()
+            .thread_stack_size(self.thread_stack_size)
+            .thread_name(thread_name);
+
+        if let Some(threads) = worker_threads {
+            builder.worker_threads(threads);
+        }
+        if let Some(threads) = self.max_blocking_threads {
+            builder.max_blocking_threads(threads);
+        }
+        if let Some(secs) = self.blocking_keep_alive_secs {
+            builder.thread_keep_alive(Duration::from_secs(secs));
+        }
+        builder
+    }
+}
+
+/// Runtime dedicated to the storage engine
+///
+/// Keeps database work off the application's worker threads. Created by
+/// [`init`](crate::init) when [`RuntimeConfig::storage_io_threads`] is set and
+/// owned by the resulting [`MemoryManager`](crate::core::MemoryManager).
+pub struct StorageRuntime {
+    runtime: Option<Runtime>,
+}
+
+impl StorageRuntime {
+    /// Create a storage runtime with `threads` workers
+    pub fn new(threads: usize, config: &RuntimeConfig) -> io::Result<Self> {
+        let thread_name = format!("{}-storage", config.thread_name);
+        let runtime = config.builder(Some(threads), &thread_name).build()?;
+        Ok(Self {
+            runtime: Some(runtime),
+        })
+    }
+
+    /// Handle for spawning work onto the storage runtime
+    pub fn handle(&self) -> &Handle {
+        self.runtime
+            .as_ref()
+            .expect("storage runtime is only taken on drop")
+            .handle()
+    }
+}
+
+impl std::fmt::Debug for StorageRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageRuntime").finish_non_exhaustive()
+    }
+}
+
+impl Drop for StorageRuntime {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks, which panics inside async code, and the
+        // manager owning this is usually dropped from a task
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
 
 /// Creates an optimized tokio runtime for SurrealDB embedded applications
 ///
@@ -22,11 +122,8 @@ use std::io;
 ///     // Your application code here
 /// });
 /// ```
-pub fn create_optimized_runtime() -> io::Result<tokio::runtime::Runtime> {
-    tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .thread_stack_size(10 * 1024 * 1024) // 10MiB
-        .build()
+pub fn create_optimized_runtime() -> io::Result<Runtime> {
+    RuntimeConfig::default().build()
 }
 
 /// Creates an optimized tokio runtime with custom thread count
@@ -42,18 +139,12 @@ pub fn create_optimized_runtime() -> io::Result<tokio::runtime::Runtime> {
 /// let runtime = create_optimized_runtime_with_threads(Some(4))
 ///     .expect("Failed to create runtime");
 /// ```
-pub fn create_optimized_runtime_with_threads(
-    worker_threads: Option<usize>,
-) -> io::Result<tokio::runtime::Runtime> {
-    let mut builder = tokio::runtime::Builder::new_multi_thread();
-
-    builder.enable_all().thread_stack_size(10 * 1024 * 1024); // 10MiB
-
-    if let Some(threads) = worker_threads {
-        builder.worker_threads(threads);
+pub fn create_optimized_runtime_with_threads(worker_threads: Option<usize>) -> io::Result<Runtime> {
+    RuntimeConfig {
+        worker_threads,
+        ..RuntimeConfig::default()
     }
-
-    builder.build()
+    .build()
 }
 
 /// Helper function to check if we're already in a tokio runtime
@@ -61,7 +152,144 @@ pub fn create_optimized_runtime_with_threads(
 /// This is useful for applications that need to conditionally create a runtime
 /// or use an existing one.
 pub fn is_in_tokio_runtime() -> bool {
-    tokio::runtime::Handle::try_current().is_ok()
+    Handle::try_current().is_ok()
+}
+
+/// How long a lease outlasts the last renewal, on top of the task's interval
+const LEASE_GRACE: Duration = Duration::from_secs(30);
+
+/// Identifies this process as a lease holder
+///
+/// The host name and process ID, plus a random suffix so two processes
+/// never share an identity.
+pub fn lease_holder() -> &'static str {
+    static HOLDER: OnceLock<String> = OnceLock::new();
+    HOLDER.get_or_init(|| {
+        let host = std::env::var("HOSTNAME")
+            .ok()
+            .filter(|host| !host.is_empty())
+            .unwrap_or_else(|| "locai".to_string());
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        format!("{}:{}:{}", host, std::process::id(), &suffix[..8])
+    })
+}
+
+/// Leadership of a background task that many processes start but only one
+/// should run, such as retention or compaction against a shared database
+///
+/// The lease is stored with the data, so whichever process holds it runs the
+/// task. Call [`try_acquire`](Self::try_acquire) before each run: it takes
+/// the lease if it is free or expired and renews it if this process already
+/// holds it. If the holder stops renewing, another process takes over once
+/// the lease expires.
+///
+/// ```rust,no_run
+/// # async fn example(memory_manager: locai::core::MemoryManager) {
+/// use std::time::Duration;
+/// use locai::runtime::Lease;
+///
+/// let interval = Duration::from_secs(300);
+/// let lease = Lease::for_interval(memory_manager.storage().clone(), "nightly-report", interval);
+/// let mut ticker = tokio::time::interval(interval);
+/// loop {
+///     ticker.tick().await;
+///     if lease.try_acquire().await {
+///         // Only the process holding the lease gets here
+///     }
+/// }
+/// # }
+/// ```
+///
+/// A process that stalls past its lease can overlap with its successor for
+/// one run, so tasks guarded by a lease should be safe to repeat.
+#[derive(Clone)]
+pub struct Lease {
+    store: Arc<dyn GraphStore>,
+    name: String,
+    holder: String,
+    ttl: Duration,
+}
+
+impl std::fmt::Debug for Lease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lease")
+            .field("name", &self.name)
+            .field("holder", &self.holder)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Lease {
+    /// A lease `name` in `store`, held by this process for `ttl` at a time
+    pub fn new(store: Arc<dyn GraphStore>, name: impl Into<String>, ttl: Duration) -> Self {
+        Self {
+            store,
+            name: name.into(),
+            holder: lease_holder().to_string(),
+            ttl,
+        }
+    }
+
+    /// A lease for a task that runs every `interval`
+    ///
+    /// It lasts through one late run, so the holder keeps it as long as it
+    /// keeps running.
+    pub fn for_interval(
+        store: Arc<dyn GraphStore>,
+        name: impl Into<String>,
+        interval: Duration,
+    ) -> Self {
+        Self::new(store, name, lease_ttl(interval))
+    }
+
+    /// Hold the lease as `holder` instead of this process
+    pub fn with_holder(mut self, holder: impl Into<String>) -> Self {
+        self.holder = holder.into();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Take or renew the lease; true if this holder now has it
+    ///
+    /// Storage errors are logged and count as not holding it.
+    pub async fn try_acquire(&self) -> bool {
+        match self
+            .store
+            .acquire_lease(&self.name, &self.holder, self.ttl)
+            .await
+        {
+            Ok(granted) => granted,
+            Err(e) => {
+                tracing::debug!("Failed to acquire lease '{}': {}", self.name, e);
+                false
+            }
+        }
+    }
+
+    /// Give the lease up so another process can take it without waiting for
+    /// it to expire
+    pub async fn release(&self) -> bool {
+        match self.store.release_lease(&self.name, &self.holder).await {
+            Ok(released) => released,
+            Err(e) => {
+                tracing::warn!("Failed to release lease '{}': {}", self.name, e);
+                false
+            }
+        }
+    }
+}
+
+/// How long to lease a task that runs every `interval` for
+pub fn lease_ttl(interval: Duration) -> Duration {
+    interval.saturating_mul(2).saturating_add(LEASE_GRACE)
 }
 
 #[cfg(test)]
@@ -87,4 +315,33 @@ mod tests {
         let _in_runtime = is_in_tokio_runtime();
         // We can't assert specific values since test environment varies
     }
+
+    #[test]
+    fn test_runtime_config_applies_thread_name() {
+        let runtime = RuntimeConfig {
+            worker_threads: Some(1),
+            max_blocking_threads: Some(2),
+            blocking_keep_alive_secs: Some(1),
+            thread_name: "tuned".to_string(),
+            ..RuntimeConfig::default()
+        }
+        .build()
+        .unwrap();
+
+        let name = runtime
+            .block_on(async {
+                tokio::task::spawn_blocking(|| std::thread::current().name().map(str::to_string))
+                    .await
+            })
+            .unwrap();
+        assert_eq!(name.as_deref(), Some("tuned"));
+    }
+
+    #[tokio::test]
+    async fn test_storage_runtime_drops_inside_async_context() {
+        let storage = StorageRuntime::new(1, &RuntimeConfig::default()).unwrap();
+        let value = storage.handle().spawn(async { 7 }).await.unwrap();
+        assert_eq!(value, 7);
+        drop(storage);
+    }
 }
@@ -0,0 +1,186 @@
+//! In-process BM25 index
+//!
+//! Storage delegates BM25 to SurrealDB's full-text analyzer, which isn't
+//! available without the `engine` feature. This index keeps the same
+//! defaults (k1 = 1.2, b = 0.75) and a comparable tokenizer (lowercase, split
+//! on non-alphanumerics), so keyword scores from an embedded host such as
+//! locai-js stay in the same ballpark as the server's.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// BM25 tuning parameters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bm25Params {
+    /// Term frequency saturation
+    pub k1: f32,
+    /// Document length normalization
+    pub b: f32,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+/// Inverted index scoring documents with BM25
+#[derive(Debug, Clone, Default)]
+pub struct Bm25Index {
+    params: Bm25Params,
+    /// term -> (document id -> term frequency)
+    postings: HashMap<String, HashMap<String, u32>>,
+    /// document id -> token count
+    doc_lengths: HashMap<String, u32>,
+    total_length: u64,
+}
+
+impl Bm25Index {
+    /// Create an empty index with default parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty index with custom parameters
+    pub fn with_params(params: Bm25Params) -> Self {
+        Self {
+            params,
+            ..Self::default()
+        }
+    }
+
+    /// Number of indexed documents
+    pub fn len(&self) -> usize {
+        self.doc_lengths.len()
+    }
+
+    /// Whether the index has no documents
+    pub fn is_empty(&self) -> bool {
+        self.doc_lengths.is_empty()
+    }
+
+    /// Index a document, replacing any previous version with the same id
+    pub fn insert(&mut self, id: &str, text: &str) {
+        self.remove(id);
+
+        let tokens = tokenize(text);
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for token in &tokens {
+            *frequencies.entry(token.clone()).or_insert(0) += 1;
+        }
+
+        for (term, tf) in frequencies {
+            self.postings
+                .entry(term)
+                .or_default()
+                .insert(id.to_string(), tf);
+        }
+
+        self.doc_lengths.insert(id.to_string(), tokens.len() as u32);
+        self.total_length += tokens.len() as u64;
+    }
+
+    /// Remove a document from the index
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(length) = self.doc_lengths.remove(id) else {
+            return false;
+        };
+        self.total_length -= length as u64;
+
+        self.postings.retain(|_, docs| {
+            docs.remove(id);
+            !docs.is_empty()
+        });
+        true
+    }
+
+    /// Score all documents matching at least one query term, best first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<(String, f32)> {
+        if self.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let doc_count = self.doc_lengths.len() as f32;
+        let avg_length = self.total_length as f32 / doc_count;
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+
+        let mut terms = tokenize(query);
+        terms.sort();
+        terms.dedup();
+
+        for term in &terms {
+            let Some(docs) = self.postings.get(term) else {
+                continue;
+            };
+
+            let df = docs.len() as f32;
+            let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+
+            for (id, tf) in docs {
+                let tf = *tf as f32;
+                let length = self.doc_lengths.get(id).copied().unwrap_or(0) as f32;
+                let norm =
+                    self.params.k1 * (1.0 - self.params.b + self.params.b * length / avg_length);
+                *scores.entry(id.as_str()).or_insert(0.0) +=
+                    idf * (tf * (self.params.k1 + 1.0)) / (tf + norm);
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = scores
+            .into_iter()
+            .map(|(id, score)| (id.to_string(), score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+/// Lowercase and split on anything that isn't alphanumeric
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Hello, World! it's 2024"),
+            vec!["hello", "world", "it", "s", "2024"]
+        );
+    }
+
+    #[test]
+    fn test_rare_terms_score_higher() {
+        let mut index = Bm25Index::new();
+        index.insert("a", "the dragon sleeps");
+        index.insert("b", "the knight sleeps");
+        index.insert("c", "the castle stands");
+
+        let results = index.search("dragon sleeps", 10);
+        assert_eq!(results[0].0, "a");
+        assert_eq!(results.len(), 2);
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn test_reinsert_and_remove() {
+        let mut index = Bm25Index::new();
+        index.insert("a", "dragon");
+        index.insert("a", "knight");
+        assert_eq!(index.len(), 1);
+        assert!(index.search("dragon", 10).is_empty());
+
+        assert!(index.remove("a"));
+        assert!(!index.remove("a"));
+        assert!(index.is_empty());
+        assert!(index.search("knight", 10).is_empty());
+    }
+}
@@ -4,7 +4,7 @@
 //! BM25 scores, vector similarity scores, and memory lifecycle metadata.
 
 use crate::models::memory::Memory;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
 use super::scoring::{DecayFunction, ScoringConfig};
 
@@ -12,6 +12,7 @@ use super::scoring::{DecayFunction, ScoringConfig};
 ///
 /// This struct takes BM25 scores, vector similarity scores, and memory metadata
 /// and combines them according to a ScoringConfig to produce a final relevance rank.
+#[derive(Debug, Clone)]
 pub struct ScoreCalculator {
     config: ScoringConfig,
 }
@@ -51,6 +52,21 @@ impl ScoreCalculator {
         bm25_score: f32,
         vector_score: Option<f32>,
         memory: &Memory,
+    ) -> f32 {
+        self.calculate_final_score_at(bm25_score, vector_score, memory, Utc::now())
+    }
+
+    /// Calculate the final relevance score as of `now`
+    ///
+    /// Same as [`calculate_final_score`](Self::calculate_final_score), with
+    /// memory age measured from `now` instead of the current time, so decay
+    /// can be checked at any point in time.
+    pub fn calculate_final_score_at(
+        &self,
+        bm25_score: f32,
+        vector_score: Option<f32>,
+        memory: &Memory,
+        now: DateTime<Utc>,
     ) -> f32 {
         let mut score = bm25_score * self.config.bm25_weight;
 
@@ -60,7 +76,7 @@ impl ScoreCalculator {
         }
 
         // Apply boosts
-        score += self.calculate_recency_boost(memory);
+        score += self.calculate_recency_boost(memory, now);
         score += self.calculate_access_boost(memory);
         score += self.calculate_priority_boost(memory);
 
@@ -71,9 +87,9 @@ impl ScoreCalculator {
     ///
     /// The boost decreases over time according to the configured decay function.
     /// This encourages recent memories to rank higher.
-    fn calculate_recency_boost(&self, memory: &Memory) -> f32 {
+    fn calculate_recency_boost(&self, memory: &Memory, now: DateTime<Utc>) -> f32 {
         // Calculate age in hours
-        let age_duration = Utc::now().signed_duration_since(memory.created_at);
+        let age_duration = now.signed_duration_since(memory.created_at);
         let age_hours = age_duration.num_hours() as f32;
 
         match self.config.decay_function {
@@ -268,6 +284,30 @@ mod tests {
         assert!(score_old <= 0.0);
     }
 
+    #[test]
+    fn test_recency_boost_at_fixed_time() {
+        let config = ScoringConfig {
+            bm25_weight: 0.0,
+            vector_weight: 0.0,
+            recency_boost: 10.0,
+            access_boost: 0.0,
+            priority_boost: 0.0,
+            decay_function: DecayFunction::Linear,
+            decay_rate: 0.1,
+            ..Default::default()
+        };
+        let calc = ScoreCalculator::new(config);
+        let created = Utc::now() - chrono::Duration::days(30);
+        let memory = create_test_memory("old", created, 0, MemoryPriority::Normal);
+
+        // Scored as of its creation it's fresh; five hours later it's half decayed
+        let fresh = calc.calculate_final_score_at(0.0, None, &memory, created);
+        assert!((fresh - 10.0).abs() < 0.001);
+        let later = created + chrono::Duration::hours(5);
+        let half = calc.calculate_final_score_at(0.0, None, &memory, later);
+        assert!((half - 5.0).abs() < 0.001);
+    }
+
     #[test]
     fn test_recency_boost_exponential_decay() {
         let config = ScoringConfig {
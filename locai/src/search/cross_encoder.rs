@@ -0,0 +1,178 @@
+//! Local cross-encoder reranker using candle
+//!
+//! Loads a BERT-based cross-encoder (by default
+//! `cross-encoder/ms-marco-MiniLM-L-6-v2`) from the Hugging Face Hub and scores
+//! query/document pairs on the CPU.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use candle_core::{DType, Device, IndexOp, Module, Tensor};
+use candle_nn::{Linear, VarBuilder};
+use candle_transformers::models::bert::{BertModel, Config};
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+use super::rerank::Reranker;
+use crate::{LocaiError, Result};
+
+/// Default cross-encoder model
+pub const DEFAULT_CROSS_ENCODER: &str = "cross-encoder/ms-marco-MiniLM-L-6-v2";
+
+/// Maximum tokens per query/document pair
+const MAX_SEQUENCE_LENGTH: usize = 512;
+
+struct CrossEncoderModel {
+    bert: BertModel,
+    pooler: Linear,
+    classifier: Linear,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl CrossEncoderModel {
+    fn score(&self, query: &str, documents: &[String]) -> candle_core::Result<Vec<f32>> {
+        let pairs: Vec<(String, String)> = documents
+            .iter()
+            .map(|d| (query.to_string(), d.clone()))
+            .collect();
+        let encodings = self
+            .tokenizer
+            .encode_batch(pairs, true)
+            .map_err(candle_core::Error::msg)?;
+
+        let batch = encodings.len();
+        let seq_len = encodings.first().map(|e| e.len()).unwrap_or(0);
+        let flatten = |f: fn(&tokenizers::Encoding) -> &[u32]| -> Vec<u32> {
+            encodings.iter().flat_map(|e| f(e).to_vec()).collect()
+        };
+
+        let input_ids = Tensor::from_vec(
+            flatten(tokenizers::Encoding::get_ids),
+            (batch, seq_len),
+            &self.device,
+        )?;
+        let type_ids = Tensor::from_vec(
+            flatten(tokenizers::Encoding::get_type_ids),
+            (batch, seq_len),
+            &self.device,
+        )?;
+        let attention_mask = Tensor::from_vec(
+            flatten(tokenizers::Encoding::get_attention_mask),
+            (batch, seq_len),
+            &self.device,
+        )?;
+
+        let hidden = self
+            .bert
+            .forward(&input_ids, &type_ids, Some(&attention_mask))?;
+        let cls = hidden.i((.., 0))?;
+        let pooled = self.pooler.forward(&cls)?.tanh()?;
+        let logits = self.classifier.forward(&pooled)?.squeeze(1)?;
+
+        // Map logits to 0..1 relevance
+        candle_nn::ops::sigmoid(&logits)?.to_vec1::<f32>()
+    }
+}
+
+/// Cross-encoder reranker running in-process
+#[derive(Clone)]
+pub struct CrossEncoderReranker {
+    name: String,
+    model: Arc<CrossEncoderModel>,
+}
+
+impl std::fmt::Debug for CrossEncoderReranker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrossEncoderReranker")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl CrossEncoderReranker {
+    /// Download (or load from the Hugging Face cache) and initialize a model
+    ///
+    /// This blocks on network and disk I/O; call it from `spawn_blocking` in
+    /// async contexts.
+    pub fn from_pretrained(model_id: &str) -> Result<Self> {
+        let load_err = |e: &dyn std::fmt::Display| {
+            LocaiError::ML(format!(
+                "Failed to load cross-encoder '{}': {}",
+                model_id, e
+            ))
+        };
+
+        let api = hf_hub::api::sync::Api::new().map_err(|e| load_err(&e))?;
+        let repo = api.model(model_id.to_string());
+        let config_path = repo.get("config.json").map_err(|e| load_err(&e))?;
+        let tokenizer_path = repo.get("tokenizer.json").map_err(|e| load_err(&e))?;
+        let weights_path = repo.get("model.safetensors").map_err(|e| load_err(&e))?;
+
+        let config: Config =
+            serde_json::from_str(&std::fs::read_to_string(config_path).map_err(|e| load_err(&e))?)
+                .map_err(|e| load_err(&e))?;
+
+        let mut tokenizer = Tokenizer::from_file(tokenizer_path).map_err(|e| load_err(&e))?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_SEQUENCE_LENGTH,
+                ..Default::default()
+            }))
+            .map_err(|e| load_err(&e))?;
+
+        let device = Device::Cpu;
+        // SAFETY: the weights file is not modified while mapped
+        let vb =
+            unsafe { VarBuilder::from_mmaped_safetensors(&[weights_path], DType::F32, &device) }
+                .map_err(|e| load_err(&e))?;
+
+        let bert = BertModel::load(vb.clone(), &config).map_err(|e| load_err(&e))?;
+        let pooler = candle_nn::linear(
+            config.hidden_size,
+            config.hidden_size,
+            vb.pp("bert.pooler.dense"),
+        )
+        .map_err(|e| load_err(&e))?;
+        let classifier = candle_nn::linear(config.hidden_size, 1, vb.pp("classifier"))
+            .map_err(|e| load_err(&e))?;
+
+        Ok(Self {
+            name: format!("cross-encoder:{}", model_id),
+            model: Arc::new(CrossEncoderModel {
+                bert,
+                pooler,
+                classifier,
+                tokenizer,
+                device,
+            }),
+        })
+    }
+
+    /// Load [`DEFAULT_CROSS_ENCODER`]
+    pub fn default_model() -> Result<Self> {
+        Self::from_pretrained(DEFAULT_CROSS_ENCODER)
+    }
+}
+
+#[async_trait]
+impl Reranker for CrossEncoderReranker {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let model = Arc::clone(&self.model);
+        let query = query.to_string();
+        let documents = documents.to_vec();
+
+        tokio::task::spawn_blocking(move || model.score(&query, &documents))
+            .await
+            .map_err(|e| LocaiError::ML(format!("Rerank task failed: {}", e)))?
+            .map_err(|e| LocaiError::ML(format!("Cross-encoder inference failed: {}", e)))
+    }
+}
@@ -14,6 +14,10 @@
 //! - Access frequency
 //! - Priority/importance level
 //!
+//! [`Bm25Index`] scores keywords in process for hosts without storage, such
+//! as the wasm32 build used by locai-js, where this module and
+//! [`crate::models`] are all that's compiled.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -38,8 +42,24 @@
 //! );
 //! ```
 
+pub mod bm25;
 pub mod calculator;
+#[cfg(feature = "cross-encoder")]
+pub mod cross_encoder;
+pub mod profiles;
+#[cfg(feature = "engine")]
+pub mod rerank;
 pub mod scoring;
+#[cfg(feature = "engine")]
+pub mod transform;
 
+pub use bm25::{Bm25Index, Bm25Params};
 pub use calculator::ScoreCalculator;
+#[cfg(feature = "cross-encoder")]
+pub use cross_encoder::CrossEncoderReranker;
+pub use profiles::{ScoringProfiles, builtin_scoring_profiles};
+#[cfg(feature = "engine")]
+pub use rerank::{CallbackReranker, Reranker};
 pub use scoring::{DecayFunction, ScoringConfig};
+#[cfg(feature = "engine")]
+pub use transform::{CallbackQueryTransformer, QueryTransformer};
@@ -0,0 +1,170 @@
+//! Named scoring profiles
+//!
+//! A profile is a [`ScoringConfig`] registered under a name ("recency",
+//! "precision"), so a search can pick its ranking by name through
+//! [`SearchOptions::scoring_profile`](crate::core::SearchOptions::scoring_profile)
+//! instead of carrying the whole configuration.
+//!
+//! [`ScoringProfiles`] serves the [`builtin_scoring_profiles`], the profiles
+//! in the `scoring_profiles` section of the configuration, and profiles set
+//! at runtime. A configured or runtime profile replaces a built-in one with
+//! the same name. Runtime changes last until the process exits.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+
+use super::scoring::ScoringConfig;
+use crate::{LocaiError, Result};
+
+/// Profiles available without any configuration
+pub fn builtin_scoring_profiles() -> BTreeMap<String, ScoringConfig> {
+    BTreeMap::from([
+        ("default".to_string(), ScoringConfig::default()),
+        ("recency".to_string(), ScoringConfig::recency_focused()),
+        ("semantic".to_string(), ScoringConfig::semantic_focused()),
+        (
+            "importance".to_string(),
+            ScoringConfig::importance_focused(),
+        ),
+    ])
+}
+
+/// Check a profile's name and configuration before it's registered
+pub fn validate_scoring_profile(name: &str, config: &ScoringConfig) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(LocaiError::Scoring(
+            "Scoring profile names cannot be empty".to_string(),
+        ));
+    }
+    config
+        .validate()
+        .map_err(|e| LocaiError::Scoring(format!("Invalid scoring profile '{}': {}", name, e)))
+}
+
+/// Scoring profiles by name
+#[derive(Debug)]
+pub struct ScoringProfiles {
+    builtin: BTreeMap<String, ScoringConfig>,
+    registered: RwLock<HashMap<String, ScoringConfig>>,
+}
+
+impl ScoringProfiles {
+    /// Serve the built-in profiles and `configured`, which replace built-ins
+    /// of the same name
+    pub fn new(configured: &HashMap<String, ScoringConfig>) -> Self {
+        Self {
+            builtin: builtin_scoring_profiles(),
+            registered: RwLock::new(configured.clone()),
+        }
+    }
+
+    /// The profile registered under `name`
+    pub fn get(&self, name: &str) -> Option<ScoringConfig> {
+        self.registered
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .or_else(|| self.builtin.get(name))
+            .cloned()
+    }
+
+    /// The profile registered under `name`, or an error naming the ones that are
+    pub fn resolve(&self, name: &str) -> Result<ScoringConfig> {
+        self.get(name).ok_or_else(|| {
+            let names: Vec<String> = self.list().into_keys().collect();
+            LocaiError::Scoring(format!(
+                "Unknown scoring profile '{}'; available: {}",
+                name,
+                names.join(", ")
+            ))
+        })
+    }
+
+    /// Every profile, by name
+    pub fn list(&self) -> BTreeMap<String, ScoringConfig> {
+        let mut profiles = self.builtin.clone();
+        profiles.extend(
+            self.registered
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|(name, config)| (name.clone(), config.clone())),
+        );
+        profiles
+    }
+
+    /// Register a profile, replacing any with the same name
+    pub fn set(&self, name: &str, config: ScoringConfig) -> Result<()> {
+        validate_scoring_profile(name, &config)?;
+        self.registered
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), config);
+        Ok(())
+    }
+
+    /// Remove a configured or runtime profile; a built-in one of the same
+    /// name takes its place. Returns whether there was one to remove.
+    pub fn remove(&self, name: &str) -> bool {
+        self.registered
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+            .is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_profiles_replace_builtins() {
+        let configured = HashMap::from([(
+            "recency".to_string(),
+            ScoringConfig {
+                recency_boost: 5.0,
+                ..Default::default()
+            },
+        )]);
+        let profiles = ScoringProfiles::new(&configured);
+        assert_eq!(profiles.get("recency").unwrap().recency_boost, 5.0);
+        assert_eq!(
+            profiles.get("semantic"),
+            Some(ScoringConfig::semantic_focused())
+        );
+
+        let precision = ScoringConfig {
+            vector_weight: 0.0,
+            ..Default::default()
+        };
+        profiles.set("precision", precision.clone()).unwrap();
+        assert_eq!(profiles.get("precision"), Some(precision));
+        assert!(profiles.list().contains_key("precision"));
+
+        assert!(profiles.remove("recency"));
+        assert_eq!(
+            profiles.get("recency"),
+            Some(ScoringConfig::recency_focused())
+        );
+        assert!(!profiles.remove("recency"));
+    }
+
+    #[test]
+    fn test_invalid_and_unknown_profiles() {
+        let profiles = ScoringProfiles::new(&HashMap::new());
+        let invalid = ScoringConfig {
+            decay_rate: 0.0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            profiles.set("slow", invalid),
+            Err(LocaiError::Scoring(_))
+        ));
+        assert!(profiles.set(" ", ScoringConfig::default()).is_err());
+        assert!(matches!(
+            profiles.resolve("graph-heavy"),
+            Err(LocaiError::Scoring(_))
+        ));
+    }
+}
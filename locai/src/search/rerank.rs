@@ -0,0 +1,192 @@
+//! Second-stage re-ranking of search results
+//!
+//! First-stage retrieval (BM25, vector, hybrid) scores the query and each
+//! document independently. A [`Reranker`] sees the query and a candidate
+//! together, which is much better at resolving ambiguous queries but too slow
+//! to run over the whole store. It is therefore only applied to the top
+//! `rerank_top_k` candidates (see [`SearchOptions`](crate::core::SearchOptions)).
+//!
+//! Implement [`Reranker`] for your own model, wrap a closure with
+//! [`CallbackReranker`], or enable the `cross-encoder` feature for a local
+//! candle cross-encoder.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::Result;
+use crate::core::{SearchContent, SearchResult};
+
+/// Scores query/document pairs for re-ranking
+#[async_trait]
+pub trait Reranker: fmt::Debug + Send + Sync {
+    /// Short human-readable name
+    fn name(&self) -> &str;
+
+    /// Score each document against the query
+    ///
+    /// Must return one score per document, in input order. Higher is more
+    /// relevant; scores in `0.0..=1.0` are preferred since they replace the
+    /// first-stage scores on re-ranked results.
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>>;
+}
+
+type RerankFn = dyn Fn(&str, &[String]) -> Result<Vec<f32>> + Send + Sync;
+
+/// A [`Reranker`] backed by a user-provided function
+///
+/// ```rust
+/// use locai::search::rerank::CallbackReranker;
+///
+/// // Prefer shorter documents that mention the query verbatim
+/// let reranker = CallbackReranker::new("contains", |query, documents| {
+///     Ok(documents
+///         .iter()
+///         .map(|d| if d.contains(query) { 1.0 / (1.0 + d.len() as f32 / 100.0) } else { 0.0 })
+///         .collect())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct CallbackReranker {
+    name: String,
+    callback: Arc<RerankFn>,
+}
+
+impl CallbackReranker {
+    /// Wrap a scoring function
+    pub fn new<F>(name: impl Into<String>, callback: F) -> Self
+    where
+        F: Fn(&str, &[String]) -> Result<Vec<f32>> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+impl fmt::Debug for CallbackReranker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackReranker")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Reranker for CallbackReranker {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        (self.callback)(query, documents)
+    }
+}
+
+/// Text the reranker sees for a result
+fn rerank_text(result: &SearchResult) -> String {
+    match &result.content {
+        SearchContent::Memory(memory) => memory.content.clone(),
+        _ => result.summary(),
+    }
+}
+
+/// Re-rank the first `top_k` results, leaving the rest in their original order
+///
+/// Re-ranked results take the reranker's score and are placed ahead of the
+/// untouched tail.
+pub async fn rerank_results(
+    reranker: &dyn Reranker,
+    query: &str,
+    mut results: Vec<SearchResult>,
+    top_k: usize,
+) -> Result<Vec<SearchResult>> {
+    let top_k = top_k.min(results.len());
+    if top_k == 0 {
+        return Ok(results);
+    }
+
+    let tail = results.split_off(top_k);
+    let documents: Vec<String> = results.iter().map(rerank_text).collect();
+    let scores = reranker.rerank(query, &documents).await?;
+
+    if scores.len() != results.len() {
+        return Err(crate::LocaiError::Other(format!(
+            "Reranker '{}' returned {} scores for {} documents",
+            reranker.name(),
+            scores.len(),
+            results.len()
+        )));
+    }
+
+    let mut reranked: Vec<(SearchResult, f32)> = results.into_iter().zip(scores).collect();
+    reranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut output: Vec<SearchResult> = reranked
+        .into_iter()
+        .map(|(mut result, score)| {
+            result.score = score;
+            result
+                .match_info
+                .details
+                .push(format!("Re-ranked by {}", reranker.name()));
+            result
+        })
+        .collect();
+    output.extend(tail);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::search_extensions::UniversalSearchResult;
+    use crate::models::{Memory, MemoryType};
+
+    fn memory_result(id: &str, content: &str, score: f32) -> SearchResult {
+        SearchResult::from_universal(UniversalSearchResult::Memory {
+            memory: Memory::new(id.to_string(), content.to_string(), MemoryType::Fact),
+            score: Some(score),
+            match_reason: "test".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rerank_reorders_top_k_only() {
+        let reranker = CallbackReranker::new("length", |_, documents| {
+            Ok(documents.iter().map(|d| d.len() as f32 / 100.0).collect())
+        });
+        let results = vec![
+            memory_result("a", "short", 0.9),
+            memory_result("b", "a much longer document", 0.8),
+            memory_result("c", "mid length", 0.7),
+            memory_result("d", "the longest document of them all", 0.6),
+        ];
+
+        let reranked = rerank_results(&reranker, "query", results, 3)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = reranked.iter().map(|r| r.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["b", "c", "a", "d"]);
+        assert!((reranked[0].score - 0.22).abs() < 1e-6);
+        assert_eq!(reranked[3].score, 0.6);
+    }
+
+    #[tokio::test]
+    async fn test_rerank_rejects_wrong_score_count() {
+        let reranker = CallbackReranker::new("broken", |_, _| Ok(vec![1.0]));
+        let results = vec![
+            memory_result("a", "one", 0.5),
+            memory_result("b", "two", 0.4),
+        ];
+
+        assert!(
+            rerank_results(&reranker, "query", results, 2)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -70,7 +70,8 @@ impl fmt::Display for DecayFunction {
 ///     decay_rate: 0.1,
 /// };
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ScoringConfig {
     /// Weight for BM25 keyword matching (0.0 - 1.0)
     ///
@@ -0,0 +1,178 @@
+//! Query transformation before retrieval
+//!
+//! A [`QueryTransformer`] rewrites the query before it is searched. The usual
+//! use is HyDE (embed an LLM-written hypothetical answer instead of the
+//! question) or step-back prompting (search a more general question). Locai
+//! does not call an LLM itself: implement [`QueryTransformer`] around your own
+//! model client.
+//!
+//! A rewrite can drift from what the user asked, so the original and the
+//! transformed query are both searched and their results fused with
+//! reciprocal rank fusion.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::Result;
+use crate::core::SearchResult;
+
+/// RRF smoothing constant, as used for hybrid search
+const RRF_K: f32 = 60.0;
+
+/// Rewrites a search query before retrieval
+#[async_trait]
+pub trait QueryTransformer: fmt::Debug + Send + Sync {
+    /// Short human-readable name
+    fn name(&self) -> &str;
+
+    /// Produce the query to search alongside the original
+    ///
+    /// For HyDE this is a hypothetical answer; for step-back prompting, a more
+    /// general question. Returning the query unchanged (or an empty string)
+    /// skips the second search.
+    async fn transform(&self, query: &str) -> Result<String>;
+}
+
+type TransformFn = dyn Fn(&str) -> Result<String> + Send + Sync;
+
+/// A [`QueryTransformer`] backed by a user-provided function
+///
+/// ```rust
+/// use locai::search::transform::CallbackQueryTransformer;
+///
+/// // Step back from a specific question to its topic
+/// let transformer = CallbackQueryTransformer::new("step-back", |query| {
+///     Ok(query.trim_start_matches("what did ").to_string())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct CallbackQueryTransformer {
+    name: String,
+    callback: Arc<TransformFn>,
+}
+
+impl CallbackQueryTransformer {
+    /// Wrap a transform function
+    pub fn new<F>(name: impl Into<String>, callback: F) -> Self
+    where
+        F: Fn(&str) -> Result<String> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+impl fmt::Debug for CallbackQueryTransformer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackQueryTransformer")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl QueryTransformer for CallbackQueryTransformer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn transform(&self, query: &str) -> Result<String> {
+        (self.callback)(query)
+    }
+}
+
+/// Fuse results for the original and transformed query
+///
+/// Scores are reciprocal-rank sums scaled so the best result scores 1.0.
+/// Results found only through the transformed query are annotated with the
+/// transformer's name.
+pub fn fuse_transformed_results(
+    original: Vec<SearchResult>,
+    transformed: Vec<SearchResult>,
+    transformer_name: &str,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let key = |result: &SearchResult| format!("{:?}:{}", result.result_type, result.id);
+
+    let mut fused: HashMap<String, (SearchResult, f32)> = HashMap::new();
+    for (rank, result) in original.into_iter().enumerate() {
+        fused.insert(key(&result), (result, 1.0 / (RRF_K + rank as f32 + 1.0)));
+    }
+    for (rank, mut result) in transformed.into_iter().enumerate() {
+        let rrf_score = 1.0 / (RRF_K + rank as f32 + 1.0);
+        fused
+            .entry(key(&result))
+            .and_modify(|(_, score)| *score += rrf_score)
+            .or_insert_with(|| {
+                result
+                    .match_info
+                    .details
+                    .push(format!("Matched query transformed by {}", transformer_name));
+                (result, rrf_score)
+            });
+    }
+
+    let mut results: Vec<(SearchResult, f32)> = fused.into_values().collect();
+    results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+    results.truncate(limit);
+
+    let max_score = results.first().map(|(_, s)| *s).unwrap_or(0.0);
+    results
+        .into_iter()
+        .map(|(mut result, score)| {
+            result.score = if max_score > 0.0 {
+                score / max_score
+            } else {
+                0.0
+            };
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::search_extensions::UniversalSearchResult;
+    use crate::models::{Memory, MemoryType};
+
+    fn memory_result(id: &str) -> SearchResult {
+        SearchResult::from_universal(UniversalSearchResult::Memory {
+            memory: Memory::new(id.to_string(), format!("content {}", id), MemoryType::Fact),
+            score: Some(0.5),
+            match_reason: "test".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_fusion_favors_results_in_both_lists() {
+        let original = vec![memory_result("a"), memory_result("b")];
+        let transformed = vec![memory_result("c"), memory_result("b")];
+
+        let fused = fuse_transformed_results(original, transformed, "hyde", 10);
+        let ids: Vec<&str> = fused.iter().map(|r| r.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["b", "a", "c"]);
+        assert_eq!(fused[0].score, 1.0);
+        assert!(
+            fused[2]
+                .match_info
+                .details
+                .contains(&"Matched query transformed by hyde".to_string())
+        );
+        assert!(fused[1].match_info.details.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_callback_transformer() {
+        let transformer = CallbackQueryTransformer::new("upper", |query| Ok(query.to_uppercase()));
+
+        assert_eq!(transformer.name(), "upper");
+        assert_eq!(transformer.transform("dragons").await.unwrap(), "DRAGONS");
+    }
+}
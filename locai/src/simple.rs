@@ -6,11 +6,17 @@
 use crate::Result;
 use crate::config::{ConfigBuilder, LogLevel};
 use crate::core::memory_manager::MemoryManager;
+use crate::memory::pipeline::StoreMiddleware;
 use crate::memory::search_extensions::SearchMode;
-use crate::models::memory::{Memory, MemoryBuilder, MemoryPriority, MemoryType};
+use crate::ml::provider::EmbeddingProvider;
+use crate::models::memory::{Memory, MemoryPriority, MemoryType};
+use crate::models::sparse::SparseVector;
+use crate::search::rerank::Reranker;
+use crate::search::transform::QueryTransformer;
 use crate::storage::filters::SemanticSearchFilter;
 use crate::storage::filters::helpers;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Simplified Locai interface for easy memory management
 ///
@@ -254,7 +260,11 @@ impl Locai {
     /// }
     /// ```
     pub async fn remember(&self, content: impl Into<String>) -> Result<String> {
-        let memory = MemoryBuilder::episodic(content).build();
+        let memory = self
+            .manager
+            .memory_builder(content)
+            .memory_type(MemoryType::Episodic)
+            .build();
         self.manager.store_memory(memory).await
     }
 
@@ -318,6 +328,40 @@ impl Locai {
         RememberBuilder::new(&self.manager, content.into())
     }
 
+    /// Remember something using a template such as `"meeting"` or `"bug-report"`
+    ///
+    /// The template fills the memory's content, type, tags and properties from
+    /// `vars`; see [`MemoryTemplate`](crate::memory::MemoryTemplate).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use locai::prelude::Locai;
+    /// use serde_json::json;
+    ///
+    /// async fn example() -> locai::Result<()> {
+    ///     let locai = Locai::new().await?;
+    ///     locai
+    ///         .remember_from_template(
+    ///             "bug-report",
+    ///             [("component", json!("parser")), ("summary", json!("Crash on empty input"))],
+    ///         )
+    ///         .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn remember_from_template<K, V>(
+        &self,
+        template: &str,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<String>
+    where
+        K: Into<String>,
+        V: Into<serde_json::Value>,
+    {
+        self.manager.remember_from_template(template, vars).await
+    }
+
     /// Universal search - searches everything intelligently
     ///
     /// This automatically searches across memories, entities, and graphs using the best
@@ -375,6 +419,58 @@ impl Locai {
         &self,
         query: &str,
         options: crate::core::SearchOptions,
+    ) -> Result<Vec<crate::core::SearchResult>> {
+        // Retrieve enough first-stage candidates for re-ranking
+        let rerank = options
+            .rerank_top_k
+            .zip(self.manager.reranker().cloned())
+            .filter(|(top_k, _)| *top_k > 0);
+        let fetch_limit = match &rerank {
+            Some((top_k, _)) => options.limit.max(*top_k),
+            None => options.limit,
+        };
+
+        let mut results = self.retrieve(query, &options, fetch_limit).await?;
+
+        // Search the rewritten query too and fuse, in case the rewrite drifts
+        if options.transform_query
+            && let Some(transformer) = self.manager.query_transformer()
+        {
+            let transformed = transformer.transform(query).await?;
+            let transformed = transformed.trim();
+            if !transformed.is_empty() && transformed != query.trim() {
+                tracing::debug!(
+                    "Query transformer '{}' rewrote '{}' to '{}'",
+                    transformer.name(),
+                    query,
+                    transformed
+                );
+                let transformed_results = self.retrieve(transformed, &options, fetch_limit).await?;
+                results = crate::search::transform::fuse_transformed_results(
+                    results,
+                    transformed_results,
+                    transformer.name(),
+                    fetch_limit,
+                );
+            }
+        }
+
+        if let Some((top_k, reranker)) = rerank {
+            results =
+                crate::search::rerank::rerank_results(reranker.as_ref(), query, results, top_k)
+                    .await?;
+            results.truncate(options.limit);
+        }
+
+        Ok(results)
+    }
+
+    /// First-stage retrieval for `search_with_options`, dispatched on strategy
+    async fn retrieve(
+        &self,
+        query: &str,
+        options: &crate::core::SearchOptions,
+        fetch_limit: usize,
     ) -> Result<Vec<crate::core::SearchResult>> {
         use crate::memory::search_extensions::{SearchMode, UniversalSearchOptions};
         use crate::storage::filters::SemanticSearchFilter;
@@ -389,14 +485,25 @@ impl Locai {
             entity_type_filter: None, // TODO: Add entity type filtering to SearchOptions
             similarity_threshold: options.min_score,
             expand_with_relations: options.include_context,
+            query_expansion: options.query_expansion.clone(),
+            include_archived: options.include_archived,
         };
 
+        // A scoring profile ranks memories itself, whatever the strategy
+        if let Some(profile) = &options.scoring_profile
+            && options.include_types.memories
+        {
+            return self
+                .retrieve_with_profile(query, profile, universal_options, fetch_limit)
+                .await;
+        }
+
         // Handle different search strategies
         let results = match options.strategy {
             crate::core::SearchStrategy::Auto => {
                 // Use universal search which automatically determines the best approach
                 self.manager
-                    .universal_search(query, Some(options.limit), Some(universal_options))
+                    .universal_search(query, Some(fetch_limit), Some(universal_options))
                     .await?
             }
             crate::core::SearchStrategy::Semantic => {
@@ -405,11 +512,26 @@ impl Locai {
                     let filter = SemanticSearchFilter {
                         similarity_threshold: options.min_score,
                         memory_filter: None,
+                        include_archived: options.include_archived,
+                    };
+                    let search_results = match &options.query_expansion {
+                        Some(config) => {
+                            self.manager
+                                .search_with_expansion(
+                                    query,
+                                    Some(fetch_limit),
+                                    Some(filter),
+                                    SearchMode::Vector,
+                                    config,
+                                )
+                                .await?
+                        }
+                        None => {
+                            self.manager
+                                .search(query, Some(fetch_limit), Some(filter), SearchMode::Vector)
+                                .await?
+                        }
                     };
-                    let search_results = self
-                        .manager
-                        .search(query, Some(options.limit), Some(filter), SearchMode::Vector)
-                        .await?;
                     search_results
                         .into_iter()
                         .map(
@@ -423,7 +545,7 @@ impl Locai {
                 } else {
                     // If not including memories, fall back to universal search
                     self.manager
-                        .universal_search(query, Some(options.limit), Some(universal_options))
+                        .universal_search(query, Some(fetch_limit), Some(universal_options))
                         .await?
                 }
             }
@@ -433,11 +555,26 @@ impl Locai {
                     let filter = SemanticSearchFilter {
                         similarity_threshold: options.min_score,
                         memory_filter: None,
+                        include_archived: options.include_archived,
+                    };
+                    let search_results = match &options.query_expansion {
+                        Some(config) => {
+                            self.manager
+                                .search_with_expansion(
+                                    query,
+                                    Some(fetch_limit),
+                                    Some(filter),
+                                    SearchMode::Text,
+                                    config,
+                                )
+                                .await?
+                        }
+                        None => {
+                            self.manager
+                                .search(query, Some(fetch_limit), Some(filter), SearchMode::Text)
+                                .await?
+                        }
                     };
-                    let search_results = self
-                        .manager
-                        .search(query, Some(options.limit), Some(filter), SearchMode::Text)
-                        .await?;
                     search_results
                         .into_iter()
                         .map(
@@ -451,7 +588,7 @@ impl Locai {
                 } else {
                     // If not including memories, fall back to universal search
                     self.manager
-                        .universal_search(query, Some(options.limit), Some(universal_options))
+                        .universal_search(query, Some(fetch_limit), Some(universal_options))
                         .await?
                 }
             }
@@ -461,7 +598,7 @@ impl Locai {
                 graph_options.include_graphs = true;
                 graph_options.graph_depth = options.graph_depth.max(2); // Ensure meaningful graph depth
                 self.manager
-                    .universal_search(query, Some(options.limit), Some(graph_options))
+                    .universal_search(query, Some(fetch_limit), Some(graph_options))
                     .await?
             }
             crate::core::SearchStrategy::Hybrid => {
@@ -471,7 +608,7 @@ impl Locai {
                 hybrid_options.include_entities = true;
                 hybrid_options.include_graphs = options.include_types.graphs;
                 self.manager
-                    .universal_search(query, Some(options.limit), Some(hybrid_options))
+                    .universal_search(query, Some(fetch_limit), Some(hybrid_options))
                     .await?
             }
         };
@@ -483,6 +620,46 @@ impl Locai {
             .collect())
     }
 
+    /// Memories ranked by a scoring profile, merged with the entities and
+    /// graphs universal search finds when the options include them
+    async fn retrieve_with_profile(
+        &self,
+        query: &str,
+        profile: &str,
+        mut universal_options: crate::memory::search_extensions::UniversalSearchOptions,
+        fetch_limit: usize,
+    ) -> Result<Vec<crate::core::SearchResult>> {
+        use crate::memory::search_extensions::UniversalSearchResult;
+
+        let mut results: Vec<UniversalSearchResult> = self
+            .manager
+            .search_with_profile(query, Some(fetch_limit), profile)
+            .await?
+            .into_iter()
+            .map(|sr| UniversalSearchResult::Memory {
+                memory: sr.memory,
+                score: sr.score,
+                match_reason: format!("scored with profile '{}'", profile),
+            })
+            .collect();
+
+        if universal_options.include_entities || universal_options.include_graphs {
+            universal_options.include_memories = false;
+            results.extend(
+                self.manager
+                    .universal_search(query, Some(fetch_limit), Some(universal_options))
+                    .await?,
+            );
+            results.sort_by(|a, b| b.score().total_cmp(&a.score()));
+            results.truncate(fetch_limit);
+        }
+
+        Ok(results
+            .into_iter()
+            .map(crate::core::SearchResult::from_universal)
+            .collect())
+    }
+
     /// Search only memories (legacy compatibility)
     ///
     /// This method is deprecated. Use `search()` for universal search or
@@ -577,15 +754,14 @@ impl Locai {
         self.manager.get_recent_memories(limit.unwrap_or(10)).await
     }
 
-    /// Check if vector search is available (BYOE approach)
+    /// Check if vector search works without supplying embeddings
     ///
-    /// In the BYOE approach, vector search is available when memories have embeddings.
-    /// Users need to provide their own embeddings via the Memory.with_embedding() method.
+    /// This is true when an embedding provider was configured via
+    /// [`LocaiBuilder::with_embedding_provider`]. Without one (the BYOE default),
+    /// users provide embeddings via `Memory.with_embedding()` and
+    /// `SearchBuilder::with_query_embedding()`.
     pub fn has_semantic_search(&self) -> bool {
-        // For BYOE approach, vector search depends on memories having embeddings,
-        // not on having an ML service. Return false to encourage users to use
-        // the new SearchMode::Vector explicitly.
-        false
+        self.manager.embedding_provider().is_some()
     }
 
     /// Get the underlying MemoryManager for advanced operations
@@ -616,6 +792,41 @@ impl Locai {
         self.manager.clear_storage().await
     }
 
+    /// Move a memory to the compressed archive tier
+    ///
+    /// Archived memories no longer appear in search unless
+    /// `SearchOptions::include_archived` is set. Returns false if the memory
+    /// does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use locai::prelude::Locai;
+    ///
+    /// async fn example() -> locai::Result<()> {
+    ///     let locai = Locai::for_testing().await?;
+    ///     let id = locai.remember("An old note").await?;
+    ///     locai.archive(&id).await?;
+    ///     locai.unarchive(&id).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn archive(&self, memory_id: &str) -> Result<bool> {
+        self.manager.archive_memory(memory_id).await
+    }
+
+    /// Restore an archived memory to the active tier
+    pub async fn unarchive(&self, memory_id: &str) -> Result<bool> {
+        self.manager.unarchive_memory(memory_id).await
+    }
+
+    /// Archive every memory not accessed in the last `days` days
+    ///
+    /// Returns the IDs of the archived memories.
+    pub async fn archive_inactive(&self, days: u64) -> Result<Vec<String>> {
+        self.manager.archive_inactive_memories(days, None).await
+    }
+
     /// Create a new version of an existing memory
     ///
     /// # Arguments
@@ -1012,6 +1223,96 @@ impl Locai {
         }
     }
 
+    /// Preview a snapshot restore without changing anything
+    ///
+    /// # Arguments
+    /// * `snapshot` - The snapshot to restore
+    /// * `restore_mode` - How to handle existing memories
+    ///
+    /// # Returns
+    /// Which memories would be overwritten, versioned or skipped, with content diffs
+    pub async fn restore_snapshot_preview(
+        &self,
+        snapshot: &crate::storage::models::MemorySnapshot,
+        restore_mode: crate::storage::models::RestoreMode,
+    ) -> Result<crate::storage::models::RestorePreview> {
+        self.memory_version_store()?
+            .restore_snapshot_preview(snapshot, restore_mode)
+            .await
+            .map_err(|e| crate::LocaiError::Storage(e.to_string()))
+    }
+
+    /// Get a stored snapshot by ID
+    pub async fn get_snapshot(
+        &self,
+        snapshot_id: &str,
+    ) -> Result<Option<crate::storage::models::MemorySnapshot>> {
+        self.memory_version_store()?
+            .get_snapshot(snapshot_id)
+            .await
+            .map_err(|e| crate::LocaiError::Storage(e.to_string()))
+    }
+
+    /// Export a snapshot to a self-contained JSON file
+    ///
+    /// The file carries the snapshot's memory content and embeddings, so it
+    /// can be imported into another instance with [`Self::import_snapshot`].
+    ///
+    /// # Arguments
+    /// * `snapshot` - The snapshot to export
+    /// * `path` - File to write
+    pub async fn export_snapshot(
+        &self,
+        snapshot: &crate::storage::models::MemorySnapshot,
+        path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let bundle = self
+            .memory_version_store()?
+            .export_snapshot(snapshot)
+            .await
+            .map_err(|e| crate::LocaiError::Storage(e.to_string()))?;
+        let json = serde_json::to_vec(&bundle).map_err(|e| {
+            crate::LocaiError::Other(format!("Failed to serialize snapshot bundle: {}", e))
+        })?;
+        std::fs::write(path, json).map_err(|e| {
+            crate::LocaiError::Other(format!("Failed to write snapshot bundle: {}", e))
+        })
+    }
+
+    /// Import a snapshot file written by [`Self::export_snapshot`]
+    ///
+    /// # Arguments
+    /// * `path` - File to read
+    /// * `restore_mode` - How to handle memories that already exist
+    ///
+    /// # Returns
+    /// A new local snapshot of the imported memories
+    pub async fn import_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        restore_mode: crate::storage::models::RestoreMode,
+    ) -> Result<crate::storage::models::MemorySnapshot> {
+        let json = std::fs::read(path).map_err(|e| {
+            crate::LocaiError::Other(format!("Failed to read snapshot bundle: {}", e))
+        })?;
+        let bundle: crate::storage::models::SnapshotBundle = serde_json::from_slice(&json)
+            .map_err(|e| {
+                crate::LocaiError::Other(format!("Failed to parse snapshot bundle: {}", e))
+            })?;
+        self.memory_version_store()?
+            .import_snapshot(&bundle, restore_mode)
+            .await
+            .map_err(|e| crate::LocaiError::Storage(e.to_string()))
+    }
+
+    fn memory_version_store(&self) -> Result<&dyn crate::storage::traits::MemoryVersionStore> {
+        self.manager.memory_version_store().ok_or_else(|| {
+            crate::LocaiError::Storage(
+                "Memory versioning is only supported with SharedStorage".to_string(),
+            )
+        })
+    }
+
     /// Search memories in a snapshot state
     ///
     /// # Arguments
@@ -1274,17 +1575,70 @@ impl Locai {
             ))
         }
     }
+
+    /// Verify a memory's version hash chain
+    ///
+    /// Requires `versioning.hash_chain`; versions created before it was
+    /// enabled are counted but not checked.
+    ///
+    /// # Arguments
+    /// * `memory_id` - The memory ID
+    ///
+    /// # Returns
+    /// Report with any altered, missing or misplaced versions
+    pub async fn verify_integrity(
+        &self,
+        memory_id: &str,
+    ) -> Result<crate::storage::models::VersionChainReport> {
+        use crate::storage::shared_storage::SharedStorage;
+        use crate::storage::traits::MemoryVersionStore;
+
+        let storage = self.manager.storage();
+        let storage_any = storage.as_any();
+
+        if let Some(shared_storage) =
+            storage_any.downcast_ref::<SharedStorage<surrealdb::engine::local::Db>>()
+        {
+            MemoryVersionStore::verify_integrity(shared_storage, memory_id)
+                .await
+                .map_err(|e| crate::LocaiError::Storage(e.to_string()))
+        } else {
+            #[cfg(feature = "surrealdb-remote")]
+            if let Some(shared_storage) =
+                storage_any.downcast_ref::<SharedStorage<surrealdb::engine::remote::ws::Client>>()
+            {
+                return MemoryVersionStore::verify_integrity(shared_storage, memory_id)
+                    .await
+                    .map_err(|e| crate::LocaiError::Storage(e.to_string()));
+            }
+            Err(crate::LocaiError::Storage(
+                "Memory versioning is only supported with SharedStorage".to_string(),
+            ))
+        }
+    }
 }
 
 /// Builder for advanced Locai configuration
 pub struct LocaiBuilder {
     config_builder: ConfigBuilder,
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+    reranker: Option<Arc<dyn Reranker>>,
+    query_transformer: Option<Arc<dyn QueryTransformer>>,
+    store_middlewares: Vec<Arc<dyn StoreMiddleware>>,
+    #[cfg(feature = "fastembed")]
+    local_embeddings: bool,
 }
 
 impl LocaiBuilder {
     fn new() -> Self {
         Self {
             config_builder: ConfigBuilder::new(),
+            embedding_provider: None,
+            reranker: None,
+            query_transformer: None,
+            store_middlewares: Vec::new(),
+            #[cfg(feature = "fastembed")]
+            local_embeddings: false,
         }
     }
 
@@ -1300,6 +1654,112 @@ impl LocaiBuilder {
         self
     }
 
+    /// Scale embeddings to unit length before they're written
+    pub fn with_normalized_embeddings(mut self) -> Self {
+        self.config_builder = self.config_builder.with_normalized_embeddings();
+        self
+    }
+
+    /// Embed memories and search queries automatically with this provider
+    pub fn with_embedding_provider(mut self, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedding_provider = Some(provider);
+        self
+    }
+
+    /// Re-rank search results with this reranker when `rerank_top_k` is set
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
+    /// Rewrite queries with this transformer (e.g. HyDE) before searching
+    ///
+    /// The original and rewritten query are both searched and fused by
+    /// `search_with_options` unless `transform_query` is turned off.
+    pub fn with_query_transformer(mut self, transformer: Arc<dyn QueryTransformer>) -> Self {
+        self.query_transformer = Some(transformer);
+        self
+    }
+
+    /// Pass memories through `pipeline`'s stages on their way to storage
+    pub fn with_pipeline(mut self, pipeline: crate::config::PipelineConfig) -> Self {
+        self.config_builder = self.config_builder.with_pipeline(pipeline);
+        self
+    }
+
+    /// Enable plugins registered with the
+    /// [`PluginRegistry`](crate::plugins::PluginRegistry)
+    pub fn with_plugins(mut self, plugins: crate::config::PluginsConfig) -> Self {
+        self.config_builder = self.config_builder.with_plugins(plugins);
+        self
+    }
+
+    /// Configure automation rules (see [`crate::config::RulesConfig`])
+    pub fn with_rules(mut self, rules: crate::config::RulesConfig) -> Self {
+        self.config_builder = self.config_builder.with_rules(rules);
+        self
+    }
+
+    /// Hold memories from designated sources for approval (see
+    /// [`crate::memory::review`])
+    pub fn with_review(mut self, review: crate::config::ReviewConfig) -> Self {
+        self.config_builder = self.config_builder.with_review(review);
+        self
+    }
+
+    /// Send notifications about anomalies, review queue items and reminders
+    /// (see [`crate::notifications`])
+    pub fn with_notifications(mut self, notifications: crate::config::NotificationsConfig) -> Self {
+        self.config_builder = self.config_builder.with_notifications(notifications);
+        self
+    }
+
+    /// Add a stage to the store pipeline (see [`crate::memory::pipeline`])
+    pub fn with_store_middleware(mut self, middleware: Arc<dyn StoreMiddleware>) -> Self {
+        self.store_middlewares.push(middleware);
+        self
+    }
+
+    /// Register a named scoring profile `SearchOptions::scoring_profile` can select
+    pub fn with_scoring_profile(
+        mut self,
+        name: impl Into<String>,
+        scoring: crate::search::ScoringConfig,
+    ) -> Self {
+        self.config_builder = self.config_builder.with_scoring_profile(name, scoring);
+        self
+    }
+
+    /// Read the current time from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: crate::clock::ClockHandle) -> Self {
+        self.config_builder = self.config_builder.with_clock(clock);
+        self
+    }
+
+    /// Choose IDs for new memories and extracted entities by `strategy`
+    /// instead of at random
+    pub fn with_id_strategy(mut self, strategy: crate::ids::IdStrategy) -> Self {
+        self.config_builder = self.config_builder.with_id_strategy(strategy);
+        self
+    }
+
+    /// Rank memory embeddings by `space`'s metric
+    pub fn with_memory_vector_space(mut self, space: crate::config::VectorSpaceConfig) -> Self {
+        self.config_builder = self.config_builder.with_memory_vector_space(space);
+        self
+    }
+
+    /// Embed memories and search queries with a bundled local model
+    ///
+    /// Uses [`FastEmbedProvider`](crate::ml::FastEmbedProvider) with its default
+    /// model, downloaded to the model cache directory on first build. An
+    /// explicit [`with_embedding_provider`](Self::with_embedding_provider) wins.
+    #[cfg(feature = "fastembed")]
+    pub fn with_local_embeddings(mut self) -> Self {
+        self.local_embeddings = true;
+        self
+    }
+
     /// Use in-memory storage (good for testing)
     pub fn with_memory_storage(mut self) -> Self {
         self.config_builder = self.config_builder.with_memory_storage();
@@ -1323,7 +1783,38 @@ impl LocaiBuilder {
             .with_default_storage()
             .with_default_ml()
             .build()?;
-        let manager = crate::init(config).await?;
+        #[cfg(feature = "fastembed")]
+        let model_cache_dir = config.ml.model_cache_dir.clone();
+
+        let mut manager = crate::init(config).await?;
+
+        #[allow(unused_mut)]
+        let mut provider = self.embedding_provider;
+        #[cfg(feature = "fastembed")]
+        if provider.is_none() && self.local_embeddings {
+            // Model download and ONNX session setup block
+            let local = tokio::task::spawn_blocking(move || {
+                crate::ml::FastEmbedProvider::default_model(model_cache_dir)
+            })
+            .await
+            .map_err(|e| {
+                crate::LocaiError::ML(format!("Failed to load local embeddings: {}", e))
+            })??;
+            provider = Some(Arc::new(local));
+        }
+
+        if let Some(provider) = provider {
+            manager = manager.with_embedding_provider(provider);
+        }
+        if let Some(reranker) = self.reranker {
+            manager = manager.with_reranker(reranker);
+        }
+        if let Some(transformer) = self.query_transformer {
+            manager = manager.with_query_transformer(transformer);
+        }
+        for middleware in self.store_middlewares {
+            manager = manager.with_store_middleware(middleware);
+        }
         Ok(Locai { manager })
     }
 }
@@ -1427,6 +1918,8 @@ pub struct SearchBuilder<'a> {
     since: Option<chrono::DateTime<chrono::Utc>>,
     mode: SearchMode,
     query_embedding: Option<Vec<f32>>,
+    sparse_query: Option<SparseVector>,
+    include_archived: bool,
 }
 
 impl<'a> SearchBuilder<'a> {
@@ -1440,6 +1933,8 @@ impl<'a> SearchBuilder<'a> {
             since: None,
             mode: SearchMode::Text,
             query_embedding: None,
+            sparse_query: None,
+            include_archived: false,
         }
     }
 
@@ -1467,6 +1962,12 @@ impl<'a> SearchBuilder<'a> {
         self
     }
 
+    /// Also search archived memories
+    pub fn include_archived(mut self) -> Self {
+        self.include_archived = true;
+        self
+    }
+
     /// Set the search mode
     pub fn mode(mut self, mode: SearchMode) -> Self {
         self.mode = mode;
@@ -1505,6 +2006,16 @@ impl<'a> SearchBuilder<'a> {
         self
     }
 
+    /// Provide a sparse query vector (SPLADE, BM25 term weights) to fuse
+    /// with vector or hybrid search
+    ///
+    /// In Vector mode without a query embedding, only sparse vectors are
+    /// searched.
+    pub fn with_sparse_query(mut self, vector: SparseVector) -> Self {
+        self.sparse_query = Some(vector);
+        self
+    }
+
     /// Execute the search
     pub async fn execute(&self) -> Result<Vec<Memory>> {
         let query = self.query.clone();
@@ -1524,8 +2035,24 @@ impl<'a> SearchBuilder<'a> {
         }
 
         // For vector and hybrid search, pass the query embedding if provided
-        let results = match self.mode {
-            SearchMode::Vector | SearchMode::Hybrid => {
+        let results = match (self.mode, &self.sparse_query) {
+            (SearchMode::Vector | SearchMode::Hybrid, Some(sparse_query)) => {
+                self.manager
+                    .search_with_sparse(
+                        &query,
+                        self.query_embedding.as_deref(),
+                        sparse_query,
+                        Some(limit),
+                        Some(SemanticSearchFilter {
+                            memory_filter: Some(filter),
+                            similarity_threshold: None,
+                            include_archived: self.include_archived,
+                        }),
+                        self.mode,
+                    )
+                    .await?
+            }
+            (SearchMode::Vector | SearchMode::Hybrid, None) => {
                 self.manager
                     .search_with_embedding(
                         &query,
@@ -1534,12 +2061,13 @@ impl<'a> SearchBuilder<'a> {
                         Some(SemanticSearchFilter {
                             memory_filter: Some(filter),
                             similarity_threshold: None,
+                            include_archived: self.include_archived,
                         }),
                         self.mode,
                     )
                     .await?
             }
-            SearchMode::Text => {
+            (SearchMode::Text, _) => {
                 self.manager
                     .search(
                         &query,
@@ -1547,6 +2075,7 @@ impl<'a> SearchBuilder<'a> {
                         Some(SemanticSearchFilter {
                             memory_filter: Some(filter),
                             similarity_threshold: None,
+                            include_archived: self.include_archived,
                         }),
                         self.mode,
                     )
@@ -0,0 +1,154 @@
+//! Storage backends supplied by other crates
+//!
+//! Locai stores everything through [`GraphStore`], which SurrealDB implements
+//! out of the box. Another crate can provide its own implementation (for
+//! example over Postgres) by registering a [`StorageBackend`] under a name:
+//!
+//! ```rust,no_run
+//! use locai::config::{ConfigBuilder, LocaiConfig};
+//! use locai::storage::backend::register_backend;
+//! use locai::storage::errors::StorageError;
+//! use locai::storage::traits::GraphStore;
+//!
+//! async fn connect_postgres(
+//!     config: LocaiConfig,
+//! ) -> Result<Box<dyn GraphStore>, StorageError> {
+//!     // Read connection settings from config.storage.graph.backend_options
+//!     # unimplemented!()
+//! }
+//!
+//! # async fn example() -> locai::Result<()> {
+//! register_backend("postgres", connect_postgres)?;
+//!
+//! let config = ConfigBuilder::new()
+//!     .with_storage_backend("postgres", serde_json::json!({ "url": "postgres://localhost/locai" }))
+//!     .build()?;
+//! let memory_manager = locai::init(config).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A backend must implement every trait [`GraphStore`] requires. Operations
+//! it can't support should return [`StorageError::Backend`] rather than
+//! panic. `storage.graph.backend_options` is passed through untouched for the
+//! backend's own settings.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use async_trait::async_trait;
+
+use crate::config::LocaiConfig;
+use crate::storage::errors::StorageError;
+use crate::storage::traits::GraphStore;
+
+/// Name of the built-in backend, which can't be registered over
+pub const BUILTIN_BACKEND: &str = "surrealdb";
+
+/// Creates a [`GraphStore`] from the configuration
+///
+/// Implemented for async functions and closures taking a [`LocaiConfig`].
+#[async_trait]
+pub trait StorageBackend: Send + Sync + 'static {
+    /// Open the store `config` describes
+    async fn create(&self, config: &LocaiConfig) -> Result<Box<dyn GraphStore>, StorageError>;
+}
+
+#[async_trait]
+impl<F, Fut> StorageBackend for F
+where
+    F: Fn(LocaiConfig) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Box<dyn GraphStore>, StorageError>> + Send,
+{
+    async fn create(&self, config: &LocaiConfig) -> Result<Box<dyn GraphStore>, StorageError> {
+        self(config.clone()).await
+    }
+}
+
+static BACKENDS: LazyLock<RwLock<HashMap<String, Arc<dyn StorageBackend>>>> =
+    LazyLock::new(Default::default);
+
+/// Make `backend` selectable as `storage.graph.backend = "<name>"`
+///
+/// Names are case-sensitive. Registering a name twice, or registering
+/// `surrealdb`, is an error, so two crates can't silently replace each
+/// other's backend.
+pub fn register_backend(
+    name: impl Into<String>,
+    backend: impl StorageBackend,
+) -> Result<(), StorageError> {
+    let name = name.into();
+    if name.trim().is_empty() {
+        return Err(StorageError::Configuration(
+            "Storage backend name cannot be empty".to_string(),
+        ));
+    }
+    if name == BUILTIN_BACKEND {
+        return Err(StorageError::AlreadyExists(format!(
+            "Storage backend '{}' is built in",
+            name
+        )));
+    }
+
+    let mut backends = BACKENDS.write().unwrap_or_else(|e| e.into_inner());
+    if backends.contains_key(&name) {
+        return Err(StorageError::AlreadyExists(format!(
+            "Storage backend '{}' is already registered",
+            name
+        )));
+    }
+    backends.insert(name, Arc::new(backend));
+    Ok(())
+}
+
+/// Remove a registered backend; false if none had this name
+///
+/// Stores it already created keep working.
+pub fn unregister_backend(name: &str) -> bool {
+    BACKENDS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(name)
+        .is_some()
+}
+
+/// Names of the registered backends, sorted
+pub fn registered_backends() -> Vec<String> {
+    let mut names: Vec<String> = BACKENDS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+/// Open a store with the backend registered as `name`
+pub(crate) async fn create_backend_storage(
+    name: &str,
+    config: &LocaiConfig,
+) -> Result<Box<dyn GraphStore>, StorageError> {
+    // Clone out of the lock so it isn't held across the await
+    let backend = BACKENDS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .cloned();
+    let Some(backend) = backend else {
+        let registered = registered_backends();
+        return Err(StorageError::Configuration(format!(
+            "No storage backend registered as '{}' (registered: {})",
+            name,
+            if registered.is_empty() {
+                "none".to_string()
+            } else {
+                registered.join(", ")
+            }
+        )));
+    };
+
+    tracing::info!("Creating storage with the '{}' backend", name);
+    backend.create(config).await
+}
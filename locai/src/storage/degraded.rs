@@ -0,0 +1,994 @@
+//! Degraded startup when the database is locked
+//!
+//! An embedded RocksDB database can only be opened by one process at a time.
+//! When another process holds it, [`LockedStoragePolicy`] decides what
+//! startup does: fail with
+//! [`LocaiError::StorageNotAccessible`](crate::LocaiError::StorageNotAccessible),
+//! or read the database through a RocksDB secondary instance, which doesn't
+//! need the lock, into a private snapshot, and open a [`DegradedStorage`]
+//! over that:
+//!
+//! - [`DegradedMode::ReadOnly`] serves reads from the snapshot and rejects
+//!   every write.
+//! - [`DegradedMode::Overlay`] loads the snapshot into an in-memory database
+//!   and serves reads and writes from that. Every change to a memory, entity
+//!   or relationship is appended to a pending-writes log beside the database,
+//!   in the [`OfflineQueue`] format. The next time the database opens
+//!   normally, [`replay_pending_writes`] applies the log with the queue's
+//!   conflict checks, taking the snapshot's copy of each record as the base,
+//!   and removes it.
+//!
+//! Either way the data is as it was at startup; changes the other process
+//! makes afterwards aren't seen. In overlay mode, writes to versions,
+//! vectors, the archive and the outbox stay in memory and are lost on exit.
+//! Only unsharded stores fall back; a locked shard always fails startup.
+//!
+//! [`LockedStoragePolicy`]: crate::config::LockedStoragePolicy
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::config::{LocaiConfig, LockedStoragePolicy};
+use crate::models::{Memory, SparseVector};
+use crate::replication::offline::{
+    OfflineConfig, OfflineQueue, PendingWrite, ReconcileReport, append_to_log, load_log,
+    same_record,
+};
+use crate::replication::{ChangeOp, RecordKind, read_record};
+use crate::storage::config::SurrealDBEngine;
+use crate::storage::errors::StorageError;
+use crate::storage::filters::{EntityFilter, MemoryFilter, RelationshipFilter, VectorFilter};
+use crate::storage::models::{
+    ArchiveStats, ChangeFeedPage, Entity, EntitySplit, MemoryGraph, MemoryPath, OutboxMessage,
+    PathConstraints, RecordDiff, RecordVersion, Relationship, SearchHit, SearchResult, Vector,
+    VectorSearchParams, Version,
+};
+use crate::storage::shared_storage::changefeed::embedded_engine_config;
+use crate::storage::shared_storage::live_query::DbEvent;
+use crate::storage::shared_storage::{
+    SharedStorage, SharedStorageConfig, create_embedded_shared_storage,
+};
+use crate::storage::traits::{
+    ArchiveStore, BaseStore, EntityStore, GraphStore, GraphTraversal, MemoryStore, OutboxStore,
+    RecordVersionStore, RelationshipStore, VectorStore, VersionStore,
+};
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Records written per batch when copying the database into a snapshot
+const SNAPSHOT_BATCH_SIZE: usize = 10_000;
+
+/// How a [`DegradedStorage`] handles writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DegradedMode {
+    /// Writes are rejected
+    ReadOnly,
+
+    /// Writes go to memory and are queued for the database
+    Overlay,
+}
+
+impl DegradedMode {
+    /// The fallback a policy asks for; `None` for failing
+    pub fn for_policy(policy: LockedStoragePolicy) -> Option<Self> {
+        match policy {
+            LockedStoragePolicy::Fail => None,
+            LockedStoragePolicy::ReadOnly => Some(Self::ReadOnly),
+            LockedStoragePolicy::Overlay => Some(Self::Overlay),
+        }
+    }
+}
+
+/// What a degraded store is serving
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DegradedStatus {
+    pub mode: DegradedMode,
+
+    /// The locked database
+    pub path: String,
+
+    /// When the snapshot was copied
+    pub snapshot_taken_at: DateTime<Utc>,
+
+    /// Writes waiting in the pending-writes log, including any left by
+    /// earlier runs
+    pub pending_writes: usize,
+}
+
+/// Whether a storage error says the database is locked by another process
+///
+/// RocksDB reports a held lock as an IO error on its `LOCK` file, such as
+/// "While lock file: graph/LOCK: Resource temporarily unavailable", or "lock
+/// hold by current process" when this process already has it open.
+pub fn is_lock_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("lock file") || message.contains("lock hold by current process")
+}
+
+/// The pending-writes log for a database directory
+pub fn pending_writes_path(database: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "{}.pending-writes.jsonl",
+        database.trim_end_matches('/')
+    ))
+}
+
+/// A store serving a private snapshot of a locked database
+#[derive(Debug)]
+pub struct DegradedStorage {
+    mode: DegradedMode,
+    path: String,
+    snapshot_taken_at: DateTime<Utc>,
+    /// Serves every read, and writes in overlay mode
+    store: Arc<dyn GraphStore>,
+    snapshot_dir: PathBuf,
+    pending_writes: Arc<AtomicUsize>,
+}
+
+impl DegradedStorage {
+    /// Snapshot the database at `path` and open it in `mode`
+    pub async fn open(path: &str, config: SharedStorageConfig, mode: DegradedMode) -> Result<Self> {
+        let snapshot_dir =
+            std::env::temp_dir().join(format!("locai-snapshot-{}", uuid::Uuid::new_v4()));
+        let snapshot_taken_at = Utc::now();
+        copy_database(Path::new(path), &snapshot_dir).await?;
+        let snapshot =
+            create_embedded_shared_storage(&snapshot_dir.to_string_lossy(), config.clone()).await?;
+
+        let log = pending_writes_path(path);
+        let queued = load_log(&log)
+            .await
+            .map_err(|e| StorageError::Operation(e.to_string()))?;
+        let pending_writes = Arc::new(AtomicUsize::new(queued.len()));
+
+        let store: Arc<dyn GraphStore> = match mode {
+            DegradedMode::ReadOnly => Arc::new(snapshot),
+            DegradedMode::Overlay => {
+                let overlay = Arc::new(load_into_memory(&snapshot, &snapshot_dir, config).await?);
+                let events = overlay
+                    .setup_live_queries()
+                    .await?
+                    .and_then(|receiver| receiver.downcast::<broadcast::Receiver<DbEvent>>().ok())
+                    .ok_or_else(|| {
+                        StorageError::Configuration(
+                            "Overlay storage requires live queries to queue writes".to_string(),
+                        )
+                    })?;
+                let next_seq = queued.back().map_or(0, |write| write.seq + 1);
+                tokio::spawn(queue_changes(
+                    *events,
+                    Arc::new(snapshot),
+                    log,
+                    AtomicU64::new(next_seq),
+                    Arc::clone(&pending_writes),
+                ));
+                overlay
+            }
+        };
+
+        tracing::warn!(
+            "{} is locked by another process; serving a snapshot {}",
+            path,
+            match mode {
+                DegradedMode::ReadOnly => "read-only",
+                DegradedMode::Overlay => "with writes queued",
+            }
+        );
+        Ok(Self {
+            mode,
+            path: path.to_string(),
+            snapshot_taken_at,
+            store,
+            snapshot_dir,
+            pending_writes,
+        })
+    }
+
+    pub fn mode(&self) -> DegradedMode {
+        self.mode
+    }
+
+    pub fn status(&self) -> DegradedStatus {
+        DegradedStatus {
+            mode: self.mode,
+            path: self.path.clone(),
+            snapshot_taken_at: self.snapshot_taken_at,
+            pending_writes: self.pending_writes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Fail unless writes are allowed
+    fn writable(&self) -> Result<()> {
+        match self.mode {
+            DegradedMode::Overlay => Ok(()),
+            DegradedMode::ReadOnly => Err(StorageError::Operation(format!(
+                "Storage is read-only: {} is locked by another process",
+                self.path
+            ))),
+        }
+    }
+}
+
+impl Drop for DegradedStorage {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.snapshot_dir) {
+            tracing::debug!(
+                "Failed to remove snapshot {}: {}",
+                self.snapshot_dir.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Copy a RocksDB database that another process holds open
+///
+/// The copy is read through a secondary instance caught up with the primary.
+/// The secondary doesn't move again until it's told to catch up, so every
+/// column family is read at the same point, which copying the live files
+/// can't promise.
+async fn copy_database(from: &Path, to: &Path) -> Result<()> {
+    let (from, to) = (from.to_path_buf(), to.to_path_buf());
+    tokio::task::spawn_blocking(move || {
+        let secondary = to.with_extension("secondary");
+        let copied = copy_through_secondary(&from, &secondary, &to);
+        let _ = std::fs::remove_dir_all(&secondary);
+        copied.map_err(|e| {
+            let _ = std::fs::remove_dir_all(&to);
+            StorageError::Operation(format!("Failed to snapshot {}: {}", from.display(), e))
+        })
+    })
+    .await
+    .map_err(|e| StorageError::Internal(format!("Snapshot task failed: {}", e)))?
+}
+
+fn copy_through_secondary(
+    from: &Path,
+    secondary: &Path,
+    to: &Path,
+) -> std::result::Result<(), rocksdb::Error> {
+    let families = rocksdb::DB::list_cf(&rocksdb::Options::default(), from)?;
+
+    let mut options = rocksdb::Options::default();
+    // Keeps every file open, so the primary deleting one after a compaction
+    // doesn't break the copy
+    options.set_max_open_files(-1);
+    let source = rocksdb::DB::open_cf_as_secondary(&options, from, secondary, &families)?;
+    source.try_catch_up_with_primary()?;
+
+    let mut options = rocksdb::Options::default();
+    options.create_if_missing(true);
+    options.create_missing_column_families(true);
+    let target = rocksdb::DB::open_cf(&options, to, &families)?;
+    for family in &families {
+        let (Some(read), Some(write)) = (source.cf_handle(family), target.cf_handle(family)) else {
+            continue;
+        };
+        let mut batch = rocksdb::WriteBatch::default();
+        for record in source.iterator_cf(read, rocksdb::IteratorMode::Start) {
+            let (key, value) = record?;
+            batch.put_cf(write, key, value);
+            if batch.len() >= SNAPSHOT_BATCH_SIZE {
+                target.write(std::mem::take(&mut batch))?;
+            }
+        }
+        target.write(batch)?;
+        target.flush_cf(write)?;
+    }
+    Ok(())
+}
+
+/// Copy a snapshot into an in-memory database
+async fn load_into_memory(
+    snapshot: &SharedStorage<surrealdb::engine::local::Db>,
+    snapshot_dir: &Path,
+    config: SharedStorageConfig,
+) -> Result<SharedStorage<surrealdb::engine::local::Db>> {
+    let dump = snapshot_dir.join("overlay.surql");
+    snapshot
+        .client
+        .export(&dump)
+        .await
+        .map_err(|e| StorageError::Operation(format!("Failed to export snapshot: {}", e)))?;
+
+    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(embedded_engine_config())
+        .await
+        .map_err(|e| StorageError::Connection(format!("Failed to create memory client: {}", e)))?;
+    client
+        .use_ns(&config.namespace)
+        .use_db(&config.database)
+        .await
+        .map_err(|e| {
+            StorageError::Connection(format!("Failed to set namespace/database: {}", e))
+        })?;
+    client
+        .import(&dump)
+        .await
+        .map_err(|e| StorageError::Operation(format!("Failed to load snapshot: {}", e)))?;
+    SharedStorage::new(client, config).await
+}
+
+/// Append every change made in the overlay to the pending-writes log
+async fn queue_changes(
+    mut events: broadcast::Receiver<DbEvent>,
+    snapshot: Arc<dyn GraphStore>,
+    log: PathBuf,
+    next_seq: AtomicU64,
+    pending_writes: Arc<AtomicUsize>,
+) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                tracing::error!(
+                    "{} overlay changes were missed and won't be written to {}",
+                    missed,
+                    log.display()
+                );
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        let Some(kind) = RecordKind::from_table(&event.table) else {
+            continue;
+        };
+        if event.id.is_empty() {
+            continue;
+        }
+
+        let op = if event.action == "DELETE" {
+            ChangeOp::Delete
+        } else {
+            ChangeOp::Upsert
+        };
+        let record = (op == ChangeOp::Upsert).then_some(event.result);
+        let base = read_record(snapshot.as_ref(), kind, &event.id)
+            .await
+            .ok()
+            .flatten();
+        // Reads update access statistics; those alone aren't worth replaying
+        if record.is_some() && same_record(&record, &base) {
+            continue;
+        }
+
+        let write = PendingWrite {
+            seq: next_seq.fetch_add(1, Ordering::Relaxed),
+            kind,
+            id: event.id,
+            op,
+            record,
+            queued_at: Utc::now(),
+            base,
+        };
+        match append_to_log(&log, &write).await {
+            Ok(()) => {
+                pending_writes.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => tracing::error!("Failed to queue overlay write to {}: {}", write.id, e),
+        }
+    }
+}
+
+/// Apply the writes a degraded run queued for the configured database
+///
+/// Does nothing unless the store is an unsharded embedded database with a
+/// pending-writes log. Writes whose record changed in the database since the
+/// snapshot conflict; the database's copy is kept and the conflict logged.
+/// The log is removed once every write has been applied or dropped.
+pub async fn replay_pending_writes(
+    storage: Arc<dyn GraphStore>,
+    config: &LocaiConfig,
+) -> crate::Result<Option<ReconcileReport>> {
+    let surrealdb = &config.storage.graph.surrealdb;
+    if surrealdb.engine != SurrealDBEngine::RocksDB || config.storage.sharding.shards > 1 {
+        return Ok(None);
+    }
+    let log = pending_writes_path(&surrealdb.connection);
+    if !log.exists() {
+        return Ok(None);
+    }
+
+    let queue = OfflineQueue::open(
+        storage,
+        OfflineConfig {
+            queue_path: Some(log.clone()),
+            retry_interval: None,
+            ..OfflineConfig::default()
+        },
+    )
+    .await?;
+    let report = queue.reconcile().await?;
+    if report.remaining == 0
+        && let Err(e) = tokio::fs::remove_file(&log).await
+    {
+        tracing::warn!("Failed to remove {}: {}", log.display(), e);
+    }
+    Ok(Some(report))
+}
+
+#[async_trait]
+impl BaseStore for DegradedStorage {
+    async fn health_check(&self) -> Result<bool> {
+        self.store.health_check().await
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.writable()?;
+        self.store.clear().await
+    }
+
+    async fn get_metadata(&self) -> Result<serde_json::Value> {
+        let mut metadata = self.store.get_metadata().await?;
+        if let Some(fields) = metadata.as_object_mut() {
+            fields.insert("degraded".to_string(), serde_json::to_value(self.status())?);
+        }
+        Ok(metadata)
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.store.close().await
+    }
+}
+
+#[async_trait]
+impl MemoryStore for DegradedStorage {
+    async fn create_memory(&self, memory: Memory) -> Result<Memory> {
+        self.writable()?;
+        self.store.create_memory(memory).await
+    }
+
+    async fn get_memory(&self, id: &str) -> Result<Option<Memory>> {
+        self.store.get_memory(id).await
+    }
+
+    async fn update_memory(&self, memory: Memory) -> Result<Memory> {
+        self.writable()?;
+        self.store.update_memory(memory).await
+    }
+
+    async fn delete_memory(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.delete_memory(id).await
+    }
+
+    async fn upsert_memory(&self, memory: Memory) -> Result<Memory> {
+        self.writable()?;
+        self.store.upsert_memory(memory).await
+    }
+
+    async fn list_memories(
+        &self,
+        filter: Option<MemoryFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        self.store.list_memories(filter, limit, offset).await
+    }
+
+    async fn count_memories(&self, filter: Option<MemoryFilter>) -> Result<usize> {
+        self.store.count_memories(filter).await
+    }
+
+    async fn batch_create_memories(&self, memories: Vec<Memory>) -> Result<Vec<Memory>> {
+        self.writable()?;
+        self.store.batch_create_memories(memories).await
+    }
+
+    async fn bm25_search_memories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32, String)>> {
+        self.store.bm25_search_memories(query, limit).await
+    }
+
+    async fn fuzzy_search_memories(
+        &self,
+        query: &str,
+        similarity_threshold: Option<f32>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        self.store
+            .fuzzy_search_memories(query, similarity_threshold, limit)
+            .await
+    }
+
+    async fn vector_search_memories(
+        &self,
+        query_vector: &[f32],
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32, String)>> {
+        self.store.vector_search_memories(query_vector, limit).await
+    }
+
+    async fn bm25_search_memory_hits(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>> {
+        self.store.bm25_search_memory_hits(query, limit).await
+    }
+
+    async fn vector_search_memory_hits(
+        &self,
+        query_vector: &[f32],
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>> {
+        self.store
+            .vector_search_memory_hits(query_vector, limit)
+            .await
+    }
+
+    async fn sparse_search_memories(
+        &self,
+        query: &SparseVector,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        self.store.sparse_search_memories(query, limit).await
+    }
+
+    async fn search_memories_with_scoring(
+        &self,
+        query: &str,
+        scoring: Option<crate::search::ScoringConfig>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        self.store
+            .search_memories_with_scoring(query, scoring, limit)
+            .await
+    }
+}
+
+#[async_trait]
+impl EntityStore for DegradedStorage {
+    async fn create_entity(&self, entity: Entity) -> Result<Entity> {
+        self.writable()?;
+        self.store.create_entity(entity).await
+    }
+
+    async fn get_entity(&self, id: &str) -> Result<Option<Entity>> {
+        self.store.get_entity(id).await
+    }
+
+    async fn update_entity(&self, entity: Entity) -> Result<Entity> {
+        self.writable()?;
+        self.store.update_entity(entity).await
+    }
+
+    async fn delete_entity(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.delete_entity(id).await
+    }
+
+    async fn upsert_entity(&self, entity: Entity) -> Result<Entity> {
+        self.writable()?;
+        self.store.upsert_entity(entity).await
+    }
+
+    async fn list_entities(
+        &self,
+        filter: Option<EntityFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Entity>> {
+        self.store.list_entities(filter, limit, offset).await
+    }
+
+    async fn count_entities(&self, filter: Option<EntityFilter>) -> Result<usize> {
+        self.store.count_entities(filter).await
+    }
+
+    async fn merge_entities(&self, target_id: &str, source_ids: &[String]) -> Result<Entity> {
+        self.writable()?;
+        self.store.merge_entities(target_id, source_ids).await
+    }
+
+    async fn split_entity(&self, entity_id: &str, split: EntitySplit) -> Result<Entity> {
+        self.writable()?;
+        self.store.split_entity(entity_id, split).await
+    }
+}
+
+#[async_trait]
+impl RelationshipStore for DegradedStorage {
+    async fn create_relationship(&self, relationship: Relationship) -> Result<Relationship> {
+        self.writable()?;
+        self.store.create_relationship(relationship).await
+    }
+
+    async fn get_relationship(&self, id: &str) -> Result<Option<Relationship>> {
+        self.store.get_relationship(id).await
+    }
+
+    async fn update_relationship(&self, relationship: Relationship) -> Result<Relationship> {
+        self.writable()?;
+        self.store.update_relationship(relationship).await
+    }
+
+    async fn delete_relationship(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.delete_relationship(id).await
+    }
+
+    async fn upsert_relationship(&self, relationship: Relationship) -> Result<Relationship> {
+        self.writable()?;
+        self.store.upsert_relationship(relationship).await
+    }
+
+    async fn list_relationships(
+        &self,
+        filter: Option<RelationshipFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Relationship>> {
+        self.store.list_relationships(filter, limit, offset).await
+    }
+
+    async fn count_relationships(&self, filter: Option<RelationshipFilter>) -> Result<usize> {
+        self.store.count_relationships(filter).await
+    }
+
+    async fn get_relationship_by_entities(
+        &self,
+        source_id: &str,
+        target_id: &str,
+    ) -> Result<Option<Relationship>> {
+        self.store
+            .get_relationship_by_entities(source_id, target_id)
+            .await
+    }
+
+    async fn get_relationship_properties(&self, id: &str) -> Result<serde_json::Value> {
+        self.store.get_relationship_properties(id).await
+    }
+
+    async fn find_relationships(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: Option<String>,
+    ) -> Result<Vec<Relationship>> {
+        self.store
+            .find_relationships(source_id, target_id, relationship_type)
+            .await
+    }
+
+    async fn find_related_entities(
+        &self,
+        entity_id: &str,
+        relationship_type: Option<String>,
+        direction: Option<String>,
+    ) -> Result<Vec<Entity>> {
+        self.store
+            .find_related_entities(entity_id, relationship_type, direction)
+            .await
+    }
+}
+
+#[async_trait]
+impl VersionStore for DegradedStorage {
+    async fn create_version(&self, version: Version) -> Result<Version> {
+        self.writable()?;
+        self.store.create_version(version).await
+    }
+
+    async fn get_version(&self, id: &str) -> Result<Option<Version>> {
+        self.store.get_version(id).await
+    }
+
+    async fn list_versions(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Version>> {
+        self.store.list_versions(limit, offset).await
+    }
+
+    async fn checkout_version(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.checkout_version(id).await
+    }
+}
+
+#[async_trait]
+impl VectorStore for DegradedStorage {
+    async fn add_vector(&self, vector: Vector) -> Result<Vector> {
+        self.writable()?;
+        self.store.add_vector(vector).await
+    }
+
+    async fn get_vector(&self, id: &str) -> Result<Option<Vector>> {
+        self.store.get_vector(id).await
+    }
+
+    async fn delete_vector(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.delete_vector(id).await
+    }
+
+    async fn update_vector_metadata(
+        &self,
+        id: &str,
+        metadata: serde_json::Value,
+    ) -> Result<Vector> {
+        self.writable()?;
+        self.store.update_vector_metadata(id, metadata).await
+    }
+
+    async fn search_vectors(
+        &self,
+        query_vector: &[f32],
+        params: VectorSearchParams,
+    ) -> Result<Vec<(Vector, f32)>> {
+        self.store.search_vectors(query_vector, params).await
+    }
+
+    async fn list_vectors(
+        &self,
+        filter: Option<VectorFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Vector>> {
+        self.store.list_vectors(filter, limit, offset).await
+    }
+
+    async fn count_vectors(&self, filter: Option<VectorFilter>) -> Result<usize> {
+        self.store.count_vectors(filter).await
+    }
+
+    async fn batch_add_vectors(&self, vectors: Vec<Vector>) -> Result<Vec<Vector>> {
+        self.writable()?;
+        self.store.batch_add_vectors(vectors).await
+    }
+
+    async fn upsert_vector(&self, vector: Vector) -> Result<()> {
+        self.writable()?;
+        self.store.upsert_vector(vector).await
+    }
+}
+
+#[async_trait]
+impl GraphTraversal for DegradedStorage {
+    async fn get_memory_subgraph(&self, memory_id: &str, depth: u8) -> Result<MemoryGraph> {
+        self.store.get_memory_subgraph(memory_id, depth).await
+    }
+
+    async fn find_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        max_depth: u8,
+    ) -> Result<Vec<MemoryPath>> {
+        self.store.find_paths(from_id, to_id, max_depth).await
+    }
+
+    async fn find_weighted_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        constraints: &PathConstraints,
+    ) -> Result<Vec<MemoryPath>> {
+        self.store
+            .find_weighted_paths(from_id, to_id, constraints)
+            .await
+    }
+
+    async fn find_connected_memories(
+        &self,
+        memory_id: &str,
+        relationship_type: Option<&str>,
+        max_depth: u8,
+    ) -> Result<Vec<Memory>> {
+        self.store
+            .find_connected_memories(memory_id, relationship_type, max_depth)
+            .await
+    }
+
+    async fn get_entities_from_memory(&self, memory_id: &str) -> Result<Vec<Entity>> {
+        self.store.get_entities_from_memory(memory_id).await
+    }
+
+    async fn get_memories_containing_entity(&self, entity_id: &str) -> Result<Vec<Memory>> {
+        self.store.get_memories_containing_entity(entity_id).await
+    }
+
+    async fn get_entity_relationships(&self, entity_id: &str) -> Result<Vec<Relationship>> {
+        self.store.get_entity_relationships(entity_id).await
+    }
+}
+
+#[async_trait]
+impl ArchiveStore for DegradedStorage {
+    async fn archive_memory(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.archive_memory(id).await
+    }
+
+    async fn unarchive_memory(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.unarchive_memory(id).await
+    }
+
+    async fn get_archived_memory(&self, id: &str) -> Result<Option<Memory>> {
+        self.store.get_archived_memory(id).await
+    }
+
+    async fn list_archived_memories(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        self.store.list_archived_memories(limit, offset).await
+    }
+
+    async fn search_archived_memories(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchResult>> {
+        self.store
+            .search_archived_memories(query, query_embedding, limit)
+            .await
+    }
+
+    async fn archive_inactive_memories(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        self.writable()?;
+        self.store.archive_inactive_memories(cutoff, limit).await
+    }
+
+    async fn get_archive_stats(&self) -> Result<ArchiveStats> {
+        self.store.get_archive_stats().await
+    }
+}
+
+#[async_trait]
+impl OutboxStore for DegradedStorage {
+    async fn create_memory_with_outbox(
+        &self,
+        memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<Memory> {
+        self.writable()?;
+        self.store.create_memory_with_outbox(memory, messages).await
+    }
+
+    async fn update_memory_with_outbox(
+        &self,
+        memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<Memory> {
+        self.writable()?;
+        self.store.update_memory_with_outbox(memory, messages).await
+    }
+
+    async fn delete_memory_with_outbox(
+        &self,
+        id: &str,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<bool> {
+        self.writable()?;
+        self.store.delete_memory_with_outbox(id, messages).await
+    }
+
+    async fn pending_outbox(&self, limit: usize) -> Result<Vec<OutboxMessage>> {
+        self.store.pending_outbox(limit).await
+    }
+
+    async fn ack_outbox(&self, id: &str) -> Result<bool> {
+        self.writable()?;
+        self.store.ack_outbox(id).await
+    }
+}
+
+#[async_trait]
+impl RecordVersionStore for DegradedStorage {
+    async fn list_record_versions(&self, kind: RecordKind, id: &str) -> Result<Vec<RecordVersion>> {
+        self.store.list_record_versions(kind, id).await
+    }
+
+    async fn get_record_version(&self, version_id: &str) -> Result<Option<RecordVersion>> {
+        self.store.get_record_version(version_id).await
+    }
+
+    async fn get_entity_at_time(&self, id: &str, at_time: DateTime<Utc>) -> Result<Option<Entity>> {
+        self.store.get_entity_at_time(id, at_time).await
+    }
+
+    async fn get_relationship_at_time(
+        &self,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<Relationship>> {
+        self.store.get_relationship_at_time(id, at_time).await
+    }
+
+    async fn diff_record_versions(
+        &self,
+        old_version_id: &str,
+        new_version_id: &str,
+    ) -> Result<RecordDiff> {
+        self.store
+            .diff_record_versions(old_version_id, new_version_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl GraphStore for DegradedStorage {
+    async fn clear_storage(&self) -> Result<()> {
+        self.writable()?;
+        self.store.clear_storage().await
+    }
+
+    fn supports_live_queries(&self) -> bool {
+        self.store.supports_live_queries()
+    }
+
+    fn get_live_query_info(&self) -> Option<&'static str> {
+        self.store.get_live_query_info()
+    }
+
+    async fn setup_live_queries(&self) -> Result<Option<Box<dyn std::any::Any + Send>>> {
+        self.store.setup_live_queries().await
+    }
+
+    async fn read_changes(&self, since: u64, limit: usize) -> Result<ChangeFeedPage> {
+        self.store.read_changes(since, limit).await
+    }
+
+    async fn acquire_lease(
+        &self,
+        name: &str,
+        holder: &str,
+        ttl: std::time::Duration,
+    ) -> Result<bool> {
+        self.store.acquire_lease(name, holder, ttl).await
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<bool> {
+        self.store.release_lease(name, holder).await
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_lock_error() {
+        assert!(is_lock_error(
+            "IO error: While lock file: /data/graph/LOCK: Resource temporarily unavailable"
+        ));
+        assert!(is_lock_error(
+            "IO error: lock hold by current process, acquire time 1700000000 acquiring thread 1: /data/graph/LOCK: No locks available"
+        ));
+        assert!(!is_lock_error(
+            "Failed to create RocksDB client: permission denied"
+        ));
+    }
+
+    #[test]
+    fn test_pending_writes_path() {
+        assert_eq!(
+            pending_writes_path("./data/graph/"),
+            PathBuf::from("./data/graph.pending-writes.jsonl")
+        );
+    }
+}
@@ -132,10 +132,27 @@ impl From<crate::LocaiError> for StorageError {
             crate::LocaiError::Entity(s) => StorageError::Other(s),
             crate::LocaiError::Relationship(s) => StorageError::Other(s),
             crate::LocaiError::Version(s) => StorageError::Other(s),
+            crate::LocaiError::Collection(s) => StorageError::Other(s),
+            crate::LocaiError::Template(s) => StorageError::Other(s),
+            crate::LocaiError::Reminder(s) => StorageError::Other(s),
+            crate::LocaiError::Task(s) => StorageError::Other(s),
+            crate::LocaiError::Procedure(s) => StorageError::Other(s),
+            crate::LocaiError::Preference(s) => StorageError::Other(s),
+            crate::LocaiError::Persona(s) => StorageError::Other(s),
+            crate::LocaiError::Session(s) => StorageError::Other(s),
+            crate::LocaiError::Reflection(s) => StorageError::Other(s),
+            crate::LocaiError::Filter(s) => StorageError::Other(s),
+            crate::LocaiError::Scoring(s) => StorageError::Other(s),
+            crate::LocaiError::InvalidEmbedding(s) => StorageError::Validation(s),
+            crate::LocaiError::Idempotency(s) => StorageError::Other(s),
+            crate::LocaiError::Rule(s) => StorageError::Other(s),
+            crate::LocaiError::Review(s) => StorageError::Other(s),
+            crate::LocaiError::Notification(s) => StorageError::Other(s),
+            crate::LocaiError::Usage(s) => StorageError::Other(s),
             crate::LocaiError::MLNotConfigured => {
                 StorageError::Configuration("ML service not configured".to_string())
             }
-            crate::LocaiError::StorageNotAccessible { path } => {
+            crate::LocaiError::StorageNotAccessible { path, .. } => {
                 StorageError::Configuration(format!("Storage not accessible: {}", path))
             }
             crate::LocaiError::InvalidEmbeddingModel { model } => {
@@ -154,6 +171,9 @@ impl From<crate::LocaiError> for StorageError {
             crate::LocaiError::FeatureNotEnabled { feature } => {
                 StorageError::Configuration(format!("Feature not enabled: {}", feature))
             }
+            err @ crate::LocaiError::QuotaExceeded { .. } => {
+                StorageError::Validation(err.to_string())
+            }
             crate::LocaiError::Other(s) => StorageError::Other(s),
             crate::LocaiError::Logging(_) => StorageError::Other("Logging error".to_string()),
         }
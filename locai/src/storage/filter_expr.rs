@@ -0,0 +1,368 @@
+//! Filter expressions
+//!
+//! A small language for typed property comparisons, shared by the API's and
+//! the CLI's `filter` options:
+//!
+//! ```text
+//! amount > 100 and status != "closed"
+//! due_date within next 7d, reviewed = false
+//! created <= 2024-06-01 and updated >= now-2h
+//! ```
+//!
+//! Clauses are joined by `and` or `,`, and all must hold. A clause compares a
+//! property (`.` between nested keys, an optional `properties.` prefix) with
+//! `=`, `!=`, `>`, `>=`, `<` or `<=`. The value's type decides how:
+//!
+//! - numbers such as `100` or `-2.5` compare with numeric properties
+//! - dates (`2024-06-01`, an RFC 3339 time, `now`, `today`, `tomorrow`,
+//!   `yesterday`, or `now+7d` / `now-2h`) compare with properties holding a
+//!   date or time
+//! - `true` and `false` compare with booleans
+//! - anything else, quoted or not, compares with strings
+//!
+//! `KEY within next 7d` and `KEY within last 2 weeks` are shorthand for a
+//! date range from or to now. Relative dates are resolved when parsed.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::storage::filters::{ComparisonOp, ConditionValue, PropertyCondition, parse_date};
+use crate::{LocaiError, Result};
+
+/// Parse a filter expression into the conditions it requires
+///
+/// `now` anchors relative dates such as `now+7d` and `within next 7d`.
+pub fn parse_filter_expression(
+    expression: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<PropertyCondition>> {
+    let tokens = tokenize(expression)?;
+    let mut conditions = Vec::new();
+    for clause in tokens.split(|token| is_separator(token)) {
+        if clause.is_empty() {
+            return Err(filter_error(expression, "a clause is empty"));
+        }
+        parse_clause(clause, now, &mut conditions)
+            .map_err(|reason| filter_error(expression, &reason))?;
+    }
+    Ok(conditions)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(ComparisonOp),
+    Comma,
+}
+
+fn is_separator(token: &Token) -> bool {
+    match token {
+        Token::Comma => true,
+        Token::Word(word) => word.eq_ignore_ascii_case("and"),
+        _ => false,
+    }
+}
+
+fn filter_error(expression: &str, reason: &str) -> LocaiError {
+    LocaiError::Filter(format!("Can't read '{}': {}", expression, reason))
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(ch) => text.push(ch),
+                        None => return Err(filter_error(expression, "a quote is not closed")),
+                    }
+                }
+                tokens.push(Token::Quoted(text));
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let equals = chars.next_if_eq(&'=').is_some();
+                let op = match (c, equals) {
+                    ('=', _) => ComparisonOp::Eq,
+                    ('!', true) => ComparisonOp::Ne,
+                    ('<', false) => ComparisonOp::Lt,
+                    ('<', true) => ComparisonOp::Lte,
+                    ('>', false) => ComparisonOp::Gt,
+                    ('>', true) => ComparisonOp::Gte,
+                    _ => return Err(filter_error(expression, "'!' must be followed by '='")),
+                };
+                tokens.push(Token::Op(op));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || ",\"'=!<>".contains(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_clause(
+    clause: &[Token],
+    now: DateTime<Utc>,
+    conditions: &mut Vec<PropertyCondition>,
+) -> std::result::Result<(), String> {
+    let Token::Word(property) = &clause[0] else {
+        return Err("each clause must start with a property name".to_string());
+    };
+    let property = property.strip_prefix("properties.").unwrap_or(property);
+
+    match &clause[1..] {
+        [Token::Op(op), value] => {
+            conditions.push(PropertyCondition::new(
+                property,
+                *op,
+                parse_value(value, now)?,
+            ));
+            Ok(())
+        }
+        [Token::Word(within), Token::Word(direction), duration @ ..]
+            if within.eq_ignore_ascii_case("within") && !duration.is_empty() =>
+        {
+            let duration = parse_duration(duration)?;
+            let (from, to) = match direction.to_ascii_lowercase().as_str() {
+                "next" => (now, now + duration),
+                "last" | "past" => (now - duration, now),
+                _ => {
+                    return Err(format!(
+                        "expected 'next' or 'last' after 'within', found '{}'",
+                        direction
+                    ));
+                }
+            };
+            conditions.push(PropertyCondition::new(
+                property,
+                ComparisonOp::Gte,
+                ConditionValue::Date(from),
+            ));
+            conditions.push(PropertyCondition::new(
+                property,
+                ComparisonOp::Lte,
+                ConditionValue::Date(to),
+            ));
+            Ok(())
+        }
+        _ => Err(format!(
+            "expected '{} <op> <value>' or '{} within next|last <duration>'",
+            property, property
+        )),
+    }
+}
+
+fn parse_value(token: &Token, now: DateTime<Utc>) -> std::result::Result<ConditionValue, String> {
+    let word = match token {
+        Token::Quoted(text) => return Ok(ConditionValue::Text(text.clone())),
+        Token::Word(word) => word,
+        _ => return Err("a comparison is missing its value".to_string()),
+    };
+
+    if let Some(date) = parse_relative_date(word, now)? {
+        return Ok(ConditionValue::Date(date));
+    }
+    if let Some(date) = parse_date(word) {
+        return Ok(ConditionValue::Date(date));
+    }
+    if let Ok(number) = word.parse::<f64>()
+        && number.is_finite()
+    {
+        return Ok(ConditionValue::Number(number));
+    }
+    Ok(match word.as_str() {
+        "true" => ConditionValue::Bool(true),
+        "false" => ConditionValue::Bool(false),
+        _ => ConditionValue::Text(word.clone()),
+    })
+}
+
+/// `now`, `today`, `tomorrow`, `yesterday`, or `now` plus or minus a duration
+fn parse_relative_date(
+    word: &str,
+    now: DateTime<Utc>,
+) -> std::result::Result<Option<DateTime<Utc>>, String> {
+    let today = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    let lower = word.to_ascii_lowercase();
+    let date = match lower.as_str() {
+        "now" => now,
+        "today" => today,
+        "tomorrow" => today + Duration::days(1),
+        "yesterday" => today - Duration::days(1),
+        _ => {
+            let Some(offset) = lower.strip_prefix("now") else {
+                return Ok(None);
+            };
+            if let Some(amount) = offset.strip_prefix('+') {
+                now + parse_duration(&[Token::Word(amount.to_string())])?
+            } else if let Some(amount) = offset.strip_prefix('-') {
+                now - parse_duration(&[Token::Word(amount.to_string())])?
+            } else {
+                return Ok(None);
+            }
+        }
+    };
+    Ok(Some(date))
+}
+
+fn parse_duration(tokens: &[Token]) -> std::result::Result<Duration, String> {
+    let parts = tokens
+        .iter()
+        .map(|token| match token {
+            Token::Word(word) | Token::Quoted(word) => Ok(word.as_str()),
+            _ => Err("a duration can only hold amounts and units".to_string()),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let text = parts.join(" ");
+    let duration = humantime_serde::re::humantime::parse_duration(&text)
+        .map_err(|e| format!("'{}' is not a duration: {}", text, e))?;
+    Duration::from_std(duration).map_err(|_| format!("'{}' is too long", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_typed_comparisons() {
+        let conditions = parse_filter_expression(
+            r#"amount > 100 and properties.status != "closed", reviewed = false"#,
+            now(),
+        )
+        .unwrap();
+        assert_eq!(
+            conditions,
+            vec![
+                PropertyCondition::new("amount", ComparisonOp::Gt, ConditionValue::Number(100.0)),
+                PropertyCondition::new(
+                    "status",
+                    ComparisonOp::Ne,
+                    ConditionValue::Text("closed".to_string())
+                ),
+                PropertyCondition::new("reviewed", ComparisonOp::Eq, ConditionValue::Bool(false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_dates() {
+        let conditions =
+            parse_filter_expression("due_date within next 7d, created <= 2024-06-01", now())
+                .unwrap();
+        assert_eq!(
+            conditions,
+            vec![
+                PropertyCondition::new("due_date", ComparisonOp::Gte, ConditionValue::Date(now())),
+                PropertyCondition::new(
+                    "due_date",
+                    ComparisonOp::Lte,
+                    ConditionValue::Date(now() + Duration::days(7))
+                ),
+                PropertyCondition::new(
+                    "created",
+                    ComparisonOp::Lte,
+                    ConditionValue::Date(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap())
+                ),
+            ]
+        );
+
+        let conditions =
+            parse_filter_expression("seen >= now-2h and seen < tomorrow", now()).unwrap();
+        assert_eq!(
+            conditions[0].value,
+            ConditionValue::Date(now() - Duration::hours(2))
+        );
+        assert_eq!(
+            conditions[1].value,
+            ConditionValue::Date(Utc.with_ymd_and_hms(2024, 6, 16, 0, 0, 0).unwrap())
+        );
+
+        let conditions = parse_filter_expression("seen within last 2 weeks", now()).unwrap();
+        assert_eq!(
+            conditions[0].value,
+            ConditionValue::Date(now() - Duration::weeks(2))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for expression in [
+            "",
+            "amount >",
+            "amount 100",
+            "> 100",
+            "amount > 100 and",
+            "status = \"open",
+            "due within soon 7d",
+            "due within next forever",
+            "amount ! 100",
+        ] {
+            assert!(
+                matches!(
+                    parse_filter_expression(expression, now()),
+                    Err(LocaiError::Filter(_))
+                ),
+                "'{}' should not parse",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn test_conditions_match_properties() {
+        let properties = serde_json::json!({
+            "amount": 150,
+            "due_date": "2024-06-18",
+            "status": "open",
+            "invoice": {"paid": false}
+        });
+        let matches = |expression: &str| {
+            parse_filter_expression(expression, now())
+                .unwrap()
+                .iter()
+                .all(|condition| condition.matches(&properties))
+        };
+
+        assert!(matches("amount > 100"));
+        assert!(!matches("amount > 150"));
+        assert!(matches("amount >= 150 and amount <= 150"));
+        assert!(matches("due_date within next 7d"));
+        assert!(!matches("due_date within last 7d"));
+        assert!(matches("status = open"));
+        assert!(!matches("status = nowhere"));
+        assert!(matches("invoice.paid = false"));
+        // A type mismatch or a missing property never matches
+        assert!(!matches("status > 100"));
+        assert!(!matches("missing != 1"));
+    }
+}
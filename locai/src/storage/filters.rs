@@ -1,9 +1,12 @@
 //! Filter types for storage queries
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
+use crate::models::GeoRadius;
+
 /// Filter for memory queries
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MemoryFilter {
@@ -29,10 +32,124 @@ pub struct MemoryFilter {
     /// Filter by custom properties
     pub properties: Option<HashMap<String, serde_json::Value>>,
 
+    /// Filter by location: only memories within this radius
+    pub near: Option<GeoRadius>,
+
+    /// Filter by typed comparisons on properties; all must hold
+    pub conditions: Option<Vec<PropertyCondition>>,
+
     /// Custom filter expression (backend-specific)
     pub custom_filter: Option<serde_json::Value>,
 }
 
+/// A typed comparison on a property, such as `amount > 100`
+///
+/// Usually parsed from a filter expression with
+/// [`parse_filter_expression`](crate::storage::filter_expr::parse_filter_expression).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyCondition {
+    /// Property path, with `.` between nested keys
+    pub property: String,
+    pub op: ComparisonOp,
+    pub value: ConditionValue,
+}
+
+/// How a property is compared with a condition's value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl ComparisonOp {
+    /// The operator as written in SurrealQL and filter expressions
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ComparisonOp::Eq => "=",
+            ComparisonOp::Ne => "!=",
+            ComparisonOp::Gt => ">",
+            ComparisonOp::Gte => ">=",
+            ComparisonOp::Lt => "<",
+            ComparisonOp::Lte => "<=",
+        }
+    }
+
+    /// Whether a property comparing as `ordering` to the value passes
+    pub fn accepts(&self, ordering: Ordering) -> bool {
+        match self {
+            ComparisonOp::Eq => ordering == Ordering::Equal,
+            ComparisonOp::Ne => ordering != Ordering::Equal,
+            ComparisonOp::Gt => ordering == Ordering::Greater,
+            ComparisonOp::Gte => ordering != Ordering::Less,
+            ComparisonOp::Lt => ordering == Ordering::Less,
+            ComparisonOp::Lte => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// The value a property is compared with; its type decides how
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ConditionValue {
+    /// Matches JSON numbers
+    Number(f64),
+    /// Matches strings holding an RFC 3339 time or a `YYYY-MM-DD` date
+    Date(DateTime<Utc>),
+    /// Matches strings, ordered by character
+    Text(String),
+    /// Matches booleans
+    Bool(bool),
+}
+
+impl PropertyCondition {
+    pub fn new(property: impl Into<String>, op: ComparisonOp, value: ConditionValue) -> Self {
+        Self {
+            property: property.into(),
+            op,
+            value,
+        }
+    }
+
+    /// Whether `properties` passes the condition
+    ///
+    /// A missing property, or one of a different type than the value, fails
+    /// every comparison, `!=` included.
+    pub fn matches(&self, properties: &serde_json::Value) -> bool {
+        let Some(property) = self
+            .property
+            .split('.')
+            .try_fold(properties, |value, key| value.get(key))
+        else {
+            return false;
+        };
+        let ordering = match &self.value {
+            ConditionValue::Number(n) => property.as_f64().and_then(|p| p.partial_cmp(n)),
+            ConditionValue::Date(date) => {
+                property.as_str().and_then(parse_date).map(|p| p.cmp(date))
+            }
+            ConditionValue::Text(text) => property.as_str().map(|p| p.cmp(text.as_str())),
+            ConditionValue::Bool(b) => property.as_bool().map(|p| p.cmp(b)),
+        };
+        ordering.is_some_and(|ordering| self.op.accepts(ordering))
+    }
+}
+
+/// Read an RFC 3339 time, or a `YYYY-MM-DD` date as its midnight UTC
+pub fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| time.and_utc())
+}
+
 /// Filter for entity queries
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct EntityFilter {
@@ -53,6 +170,9 @@ pub struct EntityFilter {
     /// Filter by entity properties
     pub properties: Option<HashMap<String, serde_json::Value>>,
 
+    /// Filter by location: only entities within this radius
+    pub near: Option<GeoRadius>,
+
     /// Filter by related entity
     pub related_to: Option<String>,
 
@@ -127,6 +247,10 @@ pub struct SemanticSearchFilter {
     /// Minimum similarity threshold for results (e.g., 0.0 to 1.0)
     /// Only memories with a score above this threshold will be returned.
     pub similarity_threshold: Option<f32>,
+
+    /// Also search archived (cold storage) memories
+    #[serde(default)]
+    pub include_archived: bool,
     // TODO: Consider adding other specific filters relevant to semantic search,
     // e.g., filter by source of embedding, or specific model used for embedding.
 }
@@ -11,12 +11,18 @@
 //!   GraphTraversal). Recommended for new applications.
 //! - **SurrealDB**: Direct SurrealDB integration with comprehensive functionality
 //! - **Memory**: Simple in-memory storage for testing and development
+//! - **Registered backends**: [`GraphStore`] implementations from other crates,
+//!   selected by name through [`backend::register_backend`]
 
+pub mod backend;
 pub mod config;
+pub mod degraded;
 pub mod errors;
+pub mod filter_expr;
 pub mod filters;
 pub mod lifecycle;
 pub mod models;
+pub mod sharded;
 pub mod shared_storage;
 pub mod traits;
 
@@ -43,22 +49,6 @@ mod memory_vector_store {
                 vectors: RwLock::new(HashMap::new()),
             }
         }
-
-        fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-            if a.len() != b.len() {
-                return 0.0;
-            }
-
-            let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-            let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-            let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-            if norm_a == 0.0 || norm_b == 0.0 {
-                0.0
-            } else {
-                dot_product / (norm_a * norm_b)
-            }
-        }
     }
 
     #[async_trait]
@@ -132,10 +122,11 @@ mod memory_vector_store {
             params: VectorSearchParams,
         ) -> Result<Vec<(Vector, f32)>, StorageError> {
             let vectors = self.vectors.read().unwrap();
+            let metric = params.distance_metric.unwrap_or_default();
             let mut results: Vec<(Vector, f32)> = vectors
                 .values()
                 .map(|vector| {
-                    let similarity = Self::cosine_similarity(query_vector, &vector.vector);
+                    let similarity = metric.similarity(query_vector, &vector.vector);
                     (vector.clone(), similarity)
                 })
                 .collect();
@@ -223,13 +214,15 @@ pub use config::{
     VectorStorageConfig, VectorStorageType,
 };
 pub use errors::StorageError;
+pub use filter_expr::parse_filter_expression;
 pub use filters::{
-    EntityFilter, FilterCondition, MemoryFilter, RelationshipFilter, SortDirection, SortOrder,
-    VectorFilter,
+    ComparisonOp, ConditionValue, EntityFilter, FilterCondition, MemoryFilter, PropertyCondition,
+    RelationshipFilter, SortDirection, SortOrder, VectorFilter,
 };
-pub use models::{Entity, Relationship, Vector, VectorSearchParams, Version};
+pub use models::{Entity, OutboxMessage, Relationship, Vector, VectorSearchParams, Version};
 pub use traits::{
-    BaseStore, EntityStore, GraphStore, MemoryStore, RelationshipStore, VectorStore, VersionStore,
+    ArchiveStore, BaseStore, EntityStore, GraphStore, MemoryStore, OutboxStore, RelationshipStore,
+    VectorStore, VersionStore,
 };
 
 pub use shared_storage::{
@@ -243,6 +236,8 @@ pub use traits::VectorStore as VectorStorage;
 
 // Old surrealdb storage re-exports removed - use shared_storage instead
 
+use shared_storage::changefeed::embedded_engine_config;
+
 /// Create a graph storage backend based on configuration
 ///
 /// **Deprecated**: Use `create_storage_service` instead for unified storage.
@@ -258,25 +253,29 @@ pub async fn create_graph_storage(
                 database: config.database.clone(),
                 lifecycle_tracking: Default::default(),
                 versioning: Default::default(),
+                ..Default::default()
             };
 
             match config.engine {
                 crate::storage::config::SurrealDBEngine::Memory => {
-                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
-                        .await
-                        .map_err(|e| {
-                            errors::StorageError::Connection(format!(
-                                "Failed to create memory client: {}",
-                                e
-                            ))
-                        })?;
+                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(
+                        embedded_engine_config(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        errors::StorageError::Connection(format!(
+                            "Failed to create memory client: {}",
+                            e
+                        ))
+                    })?;
                     let shared_storage = SharedStorage::new(client, shared_config).await?;
                     Ok(Box::new(shared_storage))
                 }
                 crate::storage::config::SurrealDBEngine::RocksDB => {
-                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::RocksDb>(
+                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::RocksDb>((
                         &config.connection,
-                    )
+                        embedded_engine_config(),
+                    ))
                     .await
                     .map_err(|e| {
                         errors::StorageError::Connection(format!(
@@ -290,14 +289,16 @@ pub async fn create_graph_storage(
                 #[cfg(feature = "surrealdb-remote")]
                 _ => {
                     // For remote connections, use the memory fallback for now
-                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
-                        .await
-                        .map_err(|e| {
-                            errors::StorageError::Connection(format!(
-                                "Failed to create memory client: {}",
-                                e
-                            ))
-                        })?;
+                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(
+                        embedded_engine_config(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        errors::StorageError::Connection(format!(
+                            "Failed to create memory client: {}",
+                            e
+                        ))
+                    })?;
                     let shared_storage = SharedStorage::new(client, shared_config).await?;
                     Ok(Box::new(shared_storage))
                 }
@@ -314,15 +315,17 @@ pub async fn create_graph_storage(
                 database: "main".to_string(),
                 lifecycle_tracking: Default::default(),
                 versioning: Default::default(),
+                ..Default::default()
             };
-            let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
-                .await
-                .map_err(|e| {
-                    errors::StorageError::Connection(format!(
-                        "Failed to create memory client: {}",
-                        e
-                    ))
-                })?;
+            let client =
+                surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(embedded_engine_config())
+                    .await
+                    .map_err(|e| {
+                        errors::StorageError::Connection(format!(
+                            "Failed to create memory client: {}",
+                            e
+                        ))
+                    })?;
             let shared_storage = SharedStorage::new(client, shared_config).await?;
             Ok(Box::new(shared_storage))
         }
@@ -345,25 +348,29 @@ pub async fn create_vector_storage(
                 database: config.database.clone(),
                 lifecycle_tracking: Default::default(),
                 versioning: Default::default(),
+                ..Default::default()
             };
 
             match config.engine {
                 crate::storage::config::SurrealDBEngine::Memory => {
-                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
-                        .await
-                        .map_err(|e| {
-                            errors::StorageError::Connection(format!(
-                                "Failed to create memory client: {}",
-                                e
-                            ))
-                        })?;
+                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(
+                        embedded_engine_config(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        errors::StorageError::Connection(format!(
+                            "Failed to create memory client: {}",
+                            e
+                        ))
+                    })?;
                     let shared_storage = SharedStorage::new(client, shared_config).await?;
                     Ok(Box::new(shared_storage))
                 }
                 crate::storage::config::SurrealDBEngine::RocksDB => {
-                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::RocksDb>(
+                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::RocksDb>((
                         &config.connection,
-                    )
+                        embedded_engine_config(),
+                    ))
                     .await
                     .map_err(|e| {
                         errors::StorageError::Connection(format!(
@@ -377,14 +384,16 @@ pub async fn create_vector_storage(
                 #[cfg(feature = "surrealdb-remote")]
                 _ => {
                     // For remote connections, use the memory fallback for now
-                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
-                        .await
-                        .map_err(|e| {
-                            errors::StorageError::Connection(format!(
-                                "Failed to create memory client: {}",
-                                e
-                            ))
-                        })?;
+                    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(
+                        embedded_engine_config(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        errors::StorageError::Connection(format!(
+                            "Failed to create memory client: {}",
+                            e
+                        ))
+                    })?;
                     let shared_storage = SharedStorage::new(client, shared_config).await?;
                     Ok(Box::new(shared_storage))
                 }
@@ -422,60 +431,117 @@ pub async fn create_storage(
 /// * `config` - The Locai configuration
 ///
 /// # Returns
-/// A storage service backed by SharedStorage
+/// A storage service backed by SharedStorage, or by the backend registered
+/// under `storage.graph.backend` (see [`backend`])
 pub async fn create_storage_service(
     config: &crate::config::LocaiConfig,
+) -> Result<Box<dyn crate::storage::traits::GraphStore>, errors::StorageError> {
+    if let Some(name) = config.storage.graph.backend.as_deref()
+        && name != backend::BUILTIN_BACKEND
+    {
+        return backend::create_backend_storage(name, config).await;
+    }
+
+    let sharding = &config.storage.sharding;
+    if sharding.shards <= 1 {
+        return create_shared_storage(config, &config.storage.graph.surrealdb).await;
+    }
+
+    tracing::info!(
+        "Creating sharded storage with {} shards by {:?}",
+        sharding.shards,
+        sharding.key
+    );
+    let mut shards: Vec<std::sync::Arc<dyn crate::storage::traits::GraphStore>> =
+        Vec::with_capacity(sharding.shards);
+    for shard in 0..sharding.shards {
+        let surrealdb = sharded::shard_config(&config.storage.graph.surrealdb, shard);
+        shards.push(std::sync::Arc::from(
+            create_shared_storage(config, &surrealdb).await?,
+        ));
+    }
+    Ok(Box::new(sharded::ShardedStorage::new(shards, sharding)?))
+}
+
+/// Create a single SharedStorage for `surrealdb`
+async fn create_shared_storage(
+    config: &crate::config::LocaiConfig,
+    surrealdb: &crate::storage::config::SurrealDBConfig,
 ) -> Result<Box<dyn crate::storage::traits::GraphStore>, errors::StorageError> {
     let shared_config = SharedStorageConfig {
-        namespace: config.storage.graph.surrealdb.namespace.clone(),
-        database: config.storage.graph.surrealdb.database.clone(),
+        namespace: surrealdb.namespace.clone(),
+        database: surrealdb.database.clone(),
         lifecycle_tracking: config.lifecycle_tracking.clone(),
         versioning: config.versioning.clone(),
+        archive: config.archive.clone(),
+        indexed_properties: config.storage.indexed_property_names(),
+        clock: config.clock.clone(),
+        id_strategy: config.id_strategy,
+        memory_space: crate::config::VectorSpaceConfig {
+            normalize: config.storage.vector.memories.normalize
+                || config.ml.embedding.normalize_on_write,
+            ..config.storage.vector.memories
+        },
+        vector_space: config.storage.vector.vectors,
     };
 
     // Create SharedStorage based on engine type
-    match config.storage.graph.surrealdb.engine {
+    match surrealdb.engine {
         crate::storage::config::SurrealDBEngine::Memory => {
             tracing::info!("Creating SharedStorage with in-memory engine");
-            let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
-                .await
-                .map_err(|e| {
-                    errors::StorageError::Connection(format!(
-                        "Failed to create memory client: {}",
-                        e
-                    ))
-                })?;
+            let client =
+                surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(embedded_engine_config())
+                    .await
+                    .map_err(|e| {
+                        errors::StorageError::Connection(format!(
+                            "Failed to create memory client: {}",
+                            e
+                        ))
+                    })?;
             let shared_storage = SharedStorage::new(client, shared_config).await?;
             Ok(Box::new(shared_storage))
         }
         crate::storage::config::SurrealDBEngine::RocksDB => {
             tracing::info!(
                 "Creating SharedStorage with RocksDB engine at {}",
-                config.storage.graph.surrealdb.connection
+                surrealdb.connection
             );
-            let shared_storage = create_embedded_shared_storage(
-                &config.storage.graph.surrealdb.connection,
-                shared_config,
-            )
-            .await?;
-            Ok(Box::new(shared_storage))
+            let e =
+                match create_embedded_shared_storage(&surrealdb.connection, shared_config.clone())
+                    .await
+                {
+                    Ok(shared_storage) => return Ok(Box::new(shared_storage)),
+                    Err(e) => e,
+                };
+            let fallback = degraded::DegradedMode::for_policy(config.storage.on_locked);
+            match fallback {
+                Some(mode)
+                    if degraded::is_lock_error(&e.to_string())
+                        && config.storage.sharding.shards <= 1 =>
+                {
+                    let storage =
+                        degraded::DegradedStorage::open(&surrealdb.connection, shared_config, mode)
+                            .await?;
+                    Ok(Box::new(storage))
+                }
+                _ => Err(e),
+            }
         }
         #[cfg(feature = "surrealdb-remote")]
         crate::storage::config::SurrealDBEngine::WebSocket => {
             tracing::info!(
                 "Creating SharedStorage with WebSocket connection to {}",
-                config.storage.graph.surrealdb.connection
+                surrealdb.connection
             );
-            let client = surrealdb::Surreal::new::<surrealdb::engine::remote::ws::Ws>(
-                &config.storage.graph.surrealdb.connection,
-            )
-            .await
-            .map_err(|e| {
-                errors::StorageError::Connection(format!(
-                    "Failed to create WebSocket client: {}",
-                    e
-                ))
-            })?;
+            let client =
+                surrealdb::Surreal::new::<surrealdb::engine::remote::ws::Ws>(&surrealdb.connection)
+                    .await
+                    .map_err(|e| {
+                        errors::StorageError::Connection(format!(
+                            "Failed to create WebSocket client: {}",
+                            e
+                        ))
+                    })?;
             let shared_storage = SharedStorage::new(client, shared_config).await?;
             Ok(Box::new(shared_storage))
         }
@@ -483,10 +549,10 @@ pub async fn create_storage_service(
         crate::storage::config::SurrealDBEngine::Http => {
             tracing::info!(
                 "Creating SharedStorage with HTTP connection to {}",
-                config.storage.graph.surrealdb.connection
+                surrealdb.connection
             );
             let client = surrealdb::Surreal::new::<surrealdb::engine::remote::http::Http>(
-                &config.storage.graph.surrealdb.connection,
+                &surrealdb.connection,
             )
             .await
             .map_err(|e| {
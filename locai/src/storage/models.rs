@@ -1,6 +1,8 @@
 //! Data structures and models for storage operations
 
 use crate::models::Memory;
+use crate::models::geo::{GeoPoint, LOCATION_PROPERTY};
+use crate::replication::{ChangeOp, RecordKind};
 use crate::storage::filters::VectorFilter;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -25,6 +27,26 @@ pub struct Entity {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Entity {
+    /// Where the entity is, if it has a location
+    pub fn location(&self) -> Option<GeoPoint> {
+        GeoPoint::from_properties(&self.properties)
+    }
+
+    /// Set where the entity is
+    pub fn set_location(&mut self, location: GeoPoint) {
+        if !self.properties.is_object() {
+            self.properties = serde_json::Value::Object(serde_json::Map::new());
+        }
+        if let Some(map) = self.properties.as_object_mut() {
+            map.insert(
+                LOCATION_PROPERTY.to_string(),
+                serde_json::to_value(location).unwrap_or_default(),
+            );
+        }
+    }
+}
+
 /// Relationship model representing an edge in the graph
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Relationship {
@@ -50,6 +72,21 @@ pub struct Relationship {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Part of an entity to move into a new entity with `EntityStore::split_entity`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EntitySplit {
+    /// The new entity; an empty ID lets the store assign one
+    pub entity: Entity,
+
+    /// Relationships of the original entity to move to the new one
+    #[serde(default)]
+    pub relationship_ids: Vec<String>,
+
+    /// Memories whose links to the original entity move to the new one
+    #[serde(default)]
+    pub memory_ids: Vec<String>,
+}
+
 /// Version model for representing a snapshot in time
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Version {
@@ -108,7 +145,8 @@ pub struct VectorSearchParams {
     #[serde(default = "default_true")]
     pub include_metadata: bool,
 
-    /// Distance metric to use for vector search
+    /// Distance metric to use for vector search; `None` uses the metric
+    /// configured for the vector space
     pub distance_metric: Option<DistanceMetric>,
 }
 
@@ -120,7 +158,7 @@ impl Default for VectorSearchParams {
             filter: None,
             include_vectors: true,
             include_metadata: true,
-            distance_metric: Some(DistanceMetric::Cosine),
+            distance_metric: None,
         }
     }
 }
@@ -130,15 +168,53 @@ impl Default for VectorSearchParams {
 pub enum DistanceMetric {
     /// Cosine similarity (default, good for normalized vectors)
     #[default]
+    #[serde(alias = "cosine")]
     Cosine,
     /// Euclidean distance (L2 norm)
+    #[serde(alias = "euclidean")]
     Euclidean,
     /// Dot product similarity
+    #[serde(alias = "dot", alias = "dot_product")]
     DotProduct,
     /// Manhattan distance (L1 norm)
+    #[serde(alias = "manhattan")]
     Manhattan,
 }
 
+impl DistanceMetric {
+    /// Similarity of `a` and `b` under this metric, higher meaning closer
+    ///
+    /// Distances `d` are reported as `1 / (1 + d)`. Vectors of different
+    /// lengths have similarity 0.
+    pub fn similarity(self, a: &[f32], b: &[f32]) -> f32 {
+        if a.len() != b.len() {
+            return 0.0;
+        }
+        let pairs = a.iter().zip(b);
+        match self {
+            DistanceMetric::Cosine => {
+                let dot: f32 = pairs.map(|(x, y)| x * y).sum();
+                let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+                let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if norm_a == 0.0 || norm_b == 0.0 {
+                    0.0
+                } else {
+                    dot / (norm_a * norm_b)
+                }
+            }
+            DistanceMetric::DotProduct => pairs.map(|(x, y)| x * y).sum(),
+            DistanceMetric::Euclidean => {
+                let distance = pairs.map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt();
+                1.0 / (1.0 + distance)
+            }
+            DistanceMetric::Manhattan => {
+                let distance: f32 = pairs.map(|(x, y)| (x - y).abs()).sum();
+                1.0 / (1.0 + distance)
+            }
+        }
+    }
+}
+
 /// Helper function for serde default values
 fn default_true() -> bool {
     true
@@ -234,6 +310,10 @@ pub struct MemoryPath {
 
     /// Ordered list of relationships on the path
     pub relationships: Vec<Relationship>,
+
+    /// Total weight of the relationships, set by weighted searches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
 }
 
 impl MemoryPath {
@@ -244,6 +324,7 @@ impl MemoryPath {
             to_id,
             memories: Vec::new(),
             relationships: Vec::new(),
+            cost: None,
         }
     }
 
@@ -263,6 +344,83 @@ impl MemoryPath {
     }
 }
 
+/// Constraints for weighted path searches
+///
+/// Each hop costs the number in its relationship's `weight_property`, or 1.0
+/// when that is missing, not a number or negative.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathConstraints {
+    /// Maximum number of relationships on a path
+    pub max_depth: u8,
+
+    /// Follow only these relationship types; all when empty
+    #[serde(default)]
+    pub relationship_types: Vec<String>,
+
+    /// Never follow these relationship types
+    #[serde(default)]
+    pub excluded_relationship_types: Vec<String>,
+
+    /// Pass only through entities of these types; all when empty
+    #[serde(default)]
+    pub node_types: Vec<String>,
+
+    /// Relationship property holding the cost of a hop
+    pub weight_property: String,
+
+    /// Drop paths costing more than this
+    pub max_cost: Option<f64>,
+
+    /// Maximum number of paths to return
+    pub limit: usize,
+}
+
+impl Default for PathConstraints {
+    fn default() -> Self {
+        Self {
+            max_depth: 5,
+            relationship_types: Vec::new(),
+            excluded_relationship_types: Vec::new(),
+            node_types: Vec::new(),
+            weight_property: "weight".to_string(),
+            max_cost: None,
+            limit: 10,
+        }
+    }
+}
+
+impl PathConstraints {
+    /// Whether a hop may follow a relationship of this type
+    pub fn allows_relationship_type(&self, relationship_type: &str) -> bool {
+        let matches = |types: &[String]| {
+            types
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(relationship_type))
+        };
+        (self.relationship_types.is_empty() || matches(&self.relationship_types))
+            && !matches(&self.excluded_relationship_types)
+    }
+
+    /// Whether a path may pass through an entity of this type
+    pub fn allows_node_type(&self, entity_type: &str) -> bool {
+        self.node_types.is_empty()
+            || self
+                .node_types
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(entity_type))
+    }
+
+    /// Cost of following `relationship`
+    pub fn edge_cost(&self, relationship: &Relationship) -> f64 {
+        relationship
+            .properties
+            .get(&self.weight_property)
+            .and_then(|weight| weight.as_f64())
+            .filter(|weight| weight.is_finite() && *weight >= 0.0)
+            .unwrap_or(1.0)
+    }
+}
+
 /// Represents a single result from a semantic search query.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -278,6 +436,53 @@ pub struct SearchResult {
     // or explainability features if supported.
 }
 
+/// A memory found by a radius search, with its distance from the center
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NearbyMemory {
+    pub memory: Memory,
+
+    /// Distance from the search center in meters
+    pub distance_meters: f64,
+}
+
+/// An entity found by a radius search, with its distance from the center
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NearbyEntity {
+    pub entity: Entity,
+
+    /// Distance from the search center in meters
+    pub distance_meters: f64,
+}
+
+/// A search match without its memory record
+///
+/// Returned by [`MemoryManager::search_hits`](crate::core::MemoryManager::search_hits),
+/// which skips loading and cloning full [`Memory`] structs. Load the memories
+/// that are actually needed with [`hydrate`](Self::hydrate) or
+/// [`MemoryManager::hydrate`](crate::core::MemoryManager::hydrate).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// ID of the matching memory
+    pub id: String,
+
+    /// Relevance score, comparable only within one search
+    pub score: f32,
+
+    /// Content excerpt around the first match, with matched terms wrapped in
+    /// `<mark>` tags for text search
+    pub snippet: String,
+}
+
+impl SearchHit {
+    /// Load the memory behind this hit, if it still exists
+    pub async fn hydrate(
+        &self,
+        manager: &crate::core::MemoryManager,
+    ) -> crate::Result<Option<Memory>> {
+        manager.get_memory(&self.id).await
+    }
+}
+
 // Memory Versioning Models
 
 /// Information about a memory version
@@ -406,6 +611,66 @@ pub enum RestoreMode {
     CreateVersions,
 }
 
+/// What restoring a snapshot would do to one memory
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RestoreAction {
+    /// Overwrite the memory with its snapshot content
+    Overwrite,
+    /// Add a new version holding the snapshot content
+    CreateVersion,
+    /// Leave the memory as it is
+    Skip,
+}
+
+/// Planned restore of one memory from a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePreviewEntry {
+    /// Memory ID
+    pub memory_id: String,
+    /// Version recorded for the memory in the snapshot
+    pub version_id: String,
+    /// What the restore would do
+    pub action: RestoreAction,
+    /// Why the memory would be skipped
+    pub skip_reason: Option<String>,
+    /// Changes from the current content to the snapshot content
+    pub changes: Vec<Change>,
+}
+
+/// What restoring a snapshot would change, computed without writing anything
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePreview {
+    /// Snapshot ID
+    pub snapshot_id: String,
+    /// Restore mode the preview was computed for
+    pub restore_mode: RestoreMode,
+    /// One entry per memory in the snapshot, ordered by memory ID
+    pub entries: Vec<RestorePreviewEntry>,
+}
+
+impl RestorePreview {
+    /// Number of memories the restore would handle with `action`
+    pub fn count(&self, action: RestoreAction) -> usize {
+        self.entries.iter().filter(|e| e.action == action).count()
+    }
+}
+
+/// Current format version written by `export_snapshot`
+pub const SNAPSHOT_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// A snapshot packaged with the memory state it refers to, so it can be
+/// imported into a different Locai instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotBundle {
+    /// Bundle format version
+    pub format_version: u32,
+    /// The snapshot as recorded on the exporting instance
+    pub snapshot: MemorySnapshot,
+    /// Each memory with its content as of the snapshot. The embedding is
+    /// only included while it still matches that content.
+    pub memories: Vec<Memory>,
+}
+
 /// Versioning statistics for a memory or all memories
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersioningStats {
@@ -427,6 +692,19 @@ pub struct VersioningStats {
     pub memory_id: Option<String>,
 }
 
+/// Statistics for the memory archive (cold storage)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveStats {
+    /// Number of archived memories
+    pub archived_count: usize,
+    /// Size of the archived memories before compression, in bytes
+    pub original_bytes: usize,
+    /// Size of the archived memories as stored, in bytes
+    pub stored_bytes: usize,
+    /// When the oldest archived memory was archived
+    pub oldest_archived_at: Option<DateTime<Utc>>,
+}
+
 /// Version integrity issue found during validation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionIntegrityIssue {
@@ -453,6 +731,30 @@ pub enum IntegrityIssueType {
     MissingBase,
     /// Orphaned version (no parent chain to base)
     OrphanedVersion,
+    /// Version content no longer matches its stored hash
+    HashMismatch,
+    /// Version hash chain is missing a link or points at the wrong parent
+    BrokenHashChain,
+}
+
+/// Result of verifying a memory's version hash chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionChainReport {
+    /// Memory ID
+    pub memory_id: String,
+    /// Number of hashed versions checked
+    pub versions_checked: usize,
+    /// Number of versions created before hash chaining was enabled
+    pub unhashed_versions: usize,
+    /// Integrity issues found
+    pub issues: Vec<VersionIntegrityIssue>,
+}
+
+impl VersionChainReport {
+    /// Whether the chain verified without issues
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
 }
 
 /// Repair report from version repair operation
@@ -465,3 +767,185 @@ pub struct RepairReport {
     /// Details of repairs
     pub repair_details: Vec<String>,
 }
+
+/// How storage executes a memory filter, as reported by SurrealDB's planner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterExplanation {
+    /// The query the filter runs as
+    pub query: String,
+    /// Indexes the planner reads
+    pub indexes_used: Vec<String>,
+    /// Whether the planner scans the whole memory table
+    pub full_scan: bool,
+    /// Filtered properties with no configured index
+    pub unindexed_properties: Vec<String>,
+    /// The plan as returned by `EXPLAIN`
+    pub plan: serde_json::Value,
+}
+
+/// Kind of record covered by a content checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksummedRecord {
+    /// A memory (content and embedding)
+    Memory,
+    /// A stored vector
+    Vector,
+}
+
+/// Type of checksum issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChecksumIssueType {
+    /// The record's data no longer matches its stored checksum
+    Mismatch,
+    /// A vector's length differs from its recorded dimension
+    DimensionMismatch,
+}
+
+/// Corruption found while verifying checksums
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumIssue {
+    /// Kind of record
+    pub record: ChecksummedRecord,
+    /// Record ID
+    pub id: String,
+    /// Issue type
+    pub issue_type: ChecksumIssueType,
+    /// The checksum stored when the record was written, if any
+    pub expected: Option<String>,
+    /// Issue description
+    pub description: String,
+}
+
+/// Result of verifying the checksums of all memories and vectors
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChecksumReport {
+    /// Number of memories checked
+    pub memories_checked: usize,
+    /// Number of vectors checked
+    pub vectors_checked: usize,
+    /// Number of records written before checksums were stored
+    pub unchecksummed: usize,
+    /// Corruption found
+    pub issues: Vec<ChecksumIssue>,
+}
+
+impl ChecksumReport {
+    /// Whether every checksummed record matched its checksum
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+// Entity and Relationship Versioning Models
+
+/// The write that produced a record version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordOperation {
+    /// The record was created (or recreated after a delete)
+    Created,
+    /// The record was updated
+    Updated,
+    /// The record was deleted
+    Deleted,
+}
+
+/// A snapshot of an entity or relationship taken when it was written
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordVersion {
+    /// Unique version identifier
+    pub version_id: String,
+    /// Kind of record versioned
+    pub kind: RecordKind,
+    /// ID of the versioned record
+    pub record_id: String,
+    /// Write that produced this version
+    pub operation: RecordOperation,
+    /// Record after the write (a serialized `Entity` or `Relationship`);
+    /// `None` for deletes
+    pub data: Option<serde_json::Value>,
+    /// When this version was recorded
+    pub created_at: DateTime<Utc>,
+}
+
+/// A field that differs between two record versions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Dotted path of the field, e.g. `properties.name`
+    pub field: String,
+    /// Old value (None if added)
+    pub old_value: Option<serde_json::Value>,
+    /// New value (None if removed)
+    pub new_value: Option<serde_json::Value>,
+}
+
+/// Diff between two versions of an entity or relationship
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordDiff {
+    /// Kind of record
+    pub kind: RecordKind,
+    /// Record ID
+    pub record_id: String,
+    /// Old version ID
+    pub old_version_id: String,
+    /// New version ID
+    pub new_version_id: String,
+    /// Changed fields, sorted by path
+    pub changes: Vec<FieldChange>,
+}
+
+/// One committed change read from the storage changefeed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeFeedEntry {
+    /// Position of the transaction that made the change
+    pub cursor: u64,
+    /// Kind of record changed
+    pub kind: RecordKind,
+    /// Record ID
+    pub id: String,
+    /// Operation
+    pub op: ChangeOp,
+    /// Record after the change (a serialized `Memory`, `Entity` or
+    /// `Relationship`); `None` for deletes
+    pub record: Option<serde_json::Value>,
+    /// When the record was written; `None` for deletes, which keep no time
+    #[serde(default)]
+    pub changed_at: Option<DateTime<Utc>>,
+}
+
+/// A page of changefeed entries
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeFeedPage {
+    /// Changes in commit order
+    pub changes: Vec<ChangeFeedEntry>,
+    /// Cursor to resume from; equals the requested cursor when there were no
+    /// new changes
+    pub cursor: u64,
+}
+
+/// A notification recorded in the outbox by the same transaction as the
+/// memory write it describes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    /// Unique ID, used to acknowledge delivery
+    pub id: String,
+    /// Application-defined topic
+    pub topic: String,
+    /// Message body
+    pub payload: serde_json::Value,
+    /// When the message was recorded
+    pub created_at: DateTime<Utc>,
+}
+
+impl OutboxMessage {
+    /// Create a message with a fresh ID
+    pub fn new(topic: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            topic: topic.into(),
+            payload,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,1254 @@
+//! Sharded storage
+//!
+//! [`ShardedStorage`] spreads data over several [`GraphStore`]s, normally one
+//! SurrealDB namespace each, while being a `GraphStore` itself, so the rest of
+//! Locai works with it unchanged. [`create_storage_service`] builds one when
+//! [`ShardingConfig::shards`] is above one.
+//!
+//! Memories are placed by [`ShardKey`]. The store assigns memory IDs on write,
+//! so placement can't be worked out from an ID afterwards and lookups by ID
+//! ask every shard. Searches and listings go to every shard too, and the
+//! results are merged; BM25 scores come from each shard's own
+//! index, so the merged ranking is approximate. A shard failing during a read
+//! is logged and skipped, and the read only fails when no shard answers.
+//!
+//! Relationships are stored on their source's shard. An entity they point at
+//! on another shard is copied over, so shared entities can live on several
+//! shards, and updates and deletes apply to every copy. Memories are never
+//! copied: linking memories on different shards fails, which with tenant
+//! routing only happens across tenants.
+//!
+//! Vectors keep their IDs, so they are placed and found by ID hash. Versions
+//! and leases live on the first shard. Memory
+//! version history, snapshots and entity merges and splits need an unsharded
+//! store.
+//!
+//! [`create_storage_service`]: super::create_storage_service
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ShardKey, ShardingConfig};
+use crate::models::{Memory, SparseVector};
+use crate::replication::RecordKind;
+use crate::storage::config::{SurrealDBConfig, SurrealDBEngine};
+use crate::storage::errors::StorageError;
+use crate::storage::filters::{EntityFilter, MemoryFilter, RelationshipFilter, VectorFilter};
+use crate::storage::models::{
+    ArchiveStats, Entity, MemoryGraph, MemoryPath, OutboxMessage, PathConstraints, RecordDiff,
+    RecordVersion, Relationship, SearchHit, SearchResult, Vector, VectorSearchParams, Version,
+};
+use crate::storage::shared_storage::record_version::diff_fields;
+use crate::storage::traits::{
+    ArchiveStore, BaseStore, EntityStore, GraphStore, GraphTraversal, MemoryStore, OutboxStore,
+    RecordVersionStore, RelationshipStore, VectorStore, VersionStore,
+};
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Health of one shard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardHealth {
+    /// Position of the shard
+    pub shard: usize,
+    pub healthy: bool,
+    /// Memories stored on the shard, when it could be counted
+    pub memories: Option<usize>,
+    pub error: Option<String>,
+}
+
+/// A [`GraphStore`] partitioned across several stores
+#[derive(Debug)]
+pub struct ShardedStorage {
+    shards: Vec<Arc<dyn GraphStore>>,
+    key: ShardKey,
+    tenant_property: String,
+}
+
+impl ShardedStorage {
+    /// Combine `shards`, placing memories as `config` describes
+    ///
+    /// Shards must be passed in the same order every time, since placement
+    /// depends on their position.
+    pub fn new(shards: Vec<Arc<dyn GraphStore>>, config: &ShardingConfig) -> Result<Self> {
+        if shards.is_empty() {
+            return Err(StorageError::Configuration(
+                "Sharded storage needs at least one shard".to_string(),
+            ));
+        }
+        Ok(Self {
+            shards,
+            key: config.key,
+            tenant_property: config.tenant_property.clone(),
+        })
+    }
+
+    /// The underlying stores, in placement order
+    pub fn shards(&self) -> &[Arc<dyn GraphStore>] {
+        &self.shards
+    }
+
+    /// Shard a memory belongs on, judged from its ID or tenant property
+    pub fn shard_for_memory(&self, memory: &Memory) -> usize {
+        match self.key {
+            ShardKey::Hash => self.shard_for_key(&memory.id),
+            ShardKey::Tenant => self.tenant_shard(&memory.properties),
+        }
+    }
+
+    /// Health and memory count of every shard
+    pub async fn shard_health(&self) -> Vec<ShardHealth> {
+        join_all(
+            self.shards
+                .iter()
+                .enumerate()
+                .map(|(shard, store)| async move {
+                    match store.health_check().await {
+                        Ok(healthy) => ShardHealth {
+                            shard,
+                            healthy,
+                            memories: if healthy {
+                                store.count_memories(None).await.ok()
+                            } else {
+                                None
+                            },
+                            error: None,
+                        },
+                        Err(e) => ShardHealth {
+                            shard,
+                            healthy: false,
+                            memories: None,
+                            error: Some(e.to_string()),
+                        },
+                    }
+                }),
+        )
+        .await
+    }
+
+    fn shard_for_key(&self, key: &str) -> usize {
+        (fnv1a(key) % self.shards.len() as u64) as usize
+    }
+
+    fn tenant_shard(&self, properties: &serde_json::Value) -> usize {
+        properties
+            .get(&self.tenant_property)
+            .and_then(|tenant| tenant.as_str())
+            .map_or(0, |tenant| self.shard_for_key(tenant))
+    }
+
+    fn entity_shard(&self, entity: &Entity) -> usize {
+        match self.key {
+            ShardKey::Hash => self.shard_for_key(&entity.id),
+            ShardKey::Tenant => self.tenant_shard(&entity.properties),
+        }
+    }
+
+    /// Shard holding memory `id`, if it exists anywhere
+    async fn find_memory(&self, id: &str) -> Result<Option<usize>> {
+        let results = join_all(self.shards.iter().map(|s| s.get_memory(id))).await;
+        found(located(results))
+    }
+
+    /// Shard to run an operation on memory `id` against
+    ///
+    /// Falls back to the first shard for unknown memories, which then reports
+    /// them missing the way an unsharded store would.
+    async fn memory_shard(&self, id: &str) -> Result<usize> {
+        Ok(self.find_memory(id).await?.unwrap_or(0))
+    }
+
+    /// Shard to write `memory` to: where it already is, else where it belongs
+    ///
+    /// Changing a memory's tenant doesn't move it.
+    async fn memory_write_shard(&self, memory: &Memory) -> Result<usize> {
+        Ok(self
+            .find_memory(&memory.id)
+            .await?
+            .unwrap_or_else(|| self.shard_for_memory(memory)))
+    }
+
+    /// Shards holding a copy of entity `id`
+    async fn entity_holders(&self, id: &str) -> Result<Vec<usize>> {
+        let results = join_all(self.shards.iter().map(|s| s.get_entity(id))).await;
+        let holders = gathered("entity lookup", located(results))?;
+        Ok(holders.into_iter().flatten().collect())
+    }
+
+    /// Shard holding relationship `id`
+    async fn find_relationship(&self, id: &str) -> Result<Option<usize>> {
+        let results = join_all(self.shards.iter().map(|s| s.get_relationship(id))).await;
+        found(located(results))
+    }
+
+    /// Shard a relationship from node `id` is stored on
+    async fn node_shard(&self, id: &str) -> Result<Option<usize>> {
+        if let Some(shard) = self.find_memory(id).await? {
+            return Ok(Some(shard));
+        }
+        let Some(entity) = self.get_entity(id).await? else {
+            return Ok(None);
+        };
+        // Prefer the entity's own shard over the copies
+        let holders = self.entity_holders(id).await?;
+        let home = self.entity_shard(&entity);
+        Ok(holders
+            .iter()
+            .copied()
+            .find(|&shard| shard == home)
+            .or(holders.first().copied()))
+    }
+
+    /// Make node `id` available on `shard`, copying an entity if needed
+    async fn bring_to_shard(&self, id: &str, shard: usize) -> Result<()> {
+        let store = &self.shards[shard];
+        if store.get_memory(id).await?.is_some() || store.get_entity(id).await?.is_some() {
+            return Ok(());
+        }
+        if let Some(entity) = self.get_entity(id).await? {
+            store.create_entity(entity).await?;
+            return Ok(());
+        }
+        if let Some(other) = self.find_memory(id).await? {
+            return Err(StorageError::Validation(format!(
+                "Memory {} is on shard {} and can't be linked from shard {}",
+                id, other, shard
+            )));
+        }
+        // Missing everywhere; the shard reports it
+        Ok(())
+    }
+
+    /// Run a per-shard batch for each shard's share of `items`, keeping order
+    async fn batch_by_shard<T, F, Fut>(
+        &self,
+        items: Vec<T>,
+        place: impl Fn(&T) -> usize,
+        write: F,
+    ) -> Result<Vec<T>>
+    where
+        F: Fn(usize, Vec<T>) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<T>>>,
+    {
+        let total = items.len();
+        let mut groups: Vec<(Vec<usize>, Vec<T>)> = (0..self.shards.len())
+            .map(|_| (Vec::new(), Vec::new()))
+            .collect();
+        for (position, item) in items.into_iter().enumerate() {
+            let (positions, group) = &mut groups[place(&item)];
+            positions.push(position);
+            group.push(item);
+        }
+
+        let (positions, writes): (Vec<_>, Vec<_>) = groups
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (positions, _))| !positions.is_empty())
+            .map(|(shard, (positions, group))| (positions, write(shard, group)))
+            .unzip();
+
+        let mut ordered: Vec<Option<T>> = (0..total).map(|_| None).collect();
+        for (positions, written) in positions.into_iter().zip(join_all(writes).await) {
+            for (position, item) in positions.into_iter().zip(written?) {
+                ordered[position] = Some(item);
+            }
+        }
+        Ok(ordered.into_iter().flatten().collect())
+    }
+}
+
+/// Create the configuration for one shard of `config`
+///
+/// Each shard gets its own namespace, and embedded RocksDB shards their own
+/// directory below the configured path.
+pub fn shard_config(config: &SurrealDBConfig, shard: usize) -> SurrealDBConfig {
+    let mut shard_config = config.clone();
+    shard_config.namespace = format!("{}_shard{}", config.namespace, shard);
+    if let SurrealDBEngine::RocksDB = config.engine {
+        shard_config.connection = format!("{}/shard-{}", config.connection, shard);
+    }
+    shard_config
+}
+
+/// FNV-1a, which unlike the std hasher is stable across builds
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Turn per-shard lookups into the positions of the shards that had a match
+fn located<T>(results: Vec<Result<Option<T>>>) -> Vec<Result<Option<usize>>> {
+    results
+        .into_iter()
+        .enumerate()
+        .map(|(shard, result)| result.map(|found| found.map(|_| shard)))
+        .collect()
+}
+
+/// Results from every shard that answered; fails only if none did
+fn gathered<T>(operation: &str, results: Vec<Result<T>>) -> Result<Vec<T>> {
+    let mut values = Vec::with_capacity(results.len());
+    let mut failure = None;
+    for (shard, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(value) => values.push(value),
+            Err(e) => {
+                tracing::warn!("Shard {} failed during {}: {}", shard, operation, e);
+                failure = Some(e);
+            }
+        }
+    }
+    match failure {
+        Some(e) if values.is_empty() => Err(e),
+        _ => Ok(values),
+    }
+}
+
+/// Concatenated results from every shard that answered
+fn merged<T>(operation: &str, results: Vec<Result<Vec<T>>>) -> Result<Vec<T>> {
+    Ok(gathered(operation, results)?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// The first match from any shard
+///
+/// A shard failing only matters when no other shard has a match, since the
+/// failed one might have held it.
+fn found<T>(results: Vec<Result<Option<T>>>) -> Result<Option<T>> {
+    let mut failure = None;
+    for result in results {
+        match result {
+            Ok(Some(value)) => return Ok(Some(value)),
+            Ok(None) => {}
+            Err(e) => failure = Some(e),
+        }
+    }
+    failure.map_or(Ok(None), Err)
+}
+
+/// Whether any shard reported success, as for deletes
+fn any(results: Vec<Result<bool>>) -> Result<bool> {
+    let results = results
+        .into_iter()
+        .map(|result| result.map(|done| done.then_some(())))
+        .collect();
+    Ok(found(results)?.is_some())
+}
+
+/// Every shard's result, failing on the first error
+fn all<T>(results: Vec<Result<T>>) -> Result<Vec<T>> {
+    results.into_iter().collect()
+}
+
+/// Page through results merged from several shards, newest first
+fn page<T>(
+    mut items: Vec<T>,
+    created_at: impl Fn(&T) -> DateTime<Utc>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Vec<T> {
+    items.sort_by_key(|item| std::cmp::Reverse(created_at(item)));
+    items
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Per-shard limit needed to serve a merged page
+fn shard_limit(limit: Option<usize>, offset: Option<usize>) -> Option<usize> {
+    limit.map(|limit| limit + offset.unwrap_or(0))
+}
+
+/// Best-scoring results merged from several shards
+fn top<T>(mut items: Vec<T>, score: impl Fn(&T) -> f32, limit: Option<usize>) -> Vec<T> {
+    items.sort_by(|a, b| score(b).total_cmp(&score(a)));
+    if let Some(limit) = limit {
+        items.truncate(limit);
+    }
+    items
+}
+
+/// Drop copies of the same record returned by several shards
+fn unique<T>(items: Vec<T>, id: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .filter(|item| seen.insert(id(item).to_string()))
+        .collect()
+}
+
+/// Give vectors an ID before placing them by it
+fn ensure_vector_id(vector: &mut Vector) {
+    if vector.id.is_empty() {
+        vector.id = uuid::Uuid::new_v4().to_string();
+    }
+}
+
+/// Give memories an ID to place them by; the store may still assign its own
+fn ensure_id(memory: &mut Memory) {
+    if memory.id.is_empty() {
+        memory.id = uuid::Uuid::new_v4().to_string();
+    }
+}
+
+#[async_trait]
+impl BaseStore for ShardedStorage {
+    async fn health_check(&self) -> Result<bool> {
+        Ok(self.shard_health().await.iter().all(|shard| shard.healthy))
+    }
+
+    async fn clear(&self) -> Result<()> {
+        all(join_all(self.shards.iter().map(|s| s.clear())).await)?;
+        Ok(())
+    }
+
+    async fn get_metadata(&self) -> Result<serde_json::Value> {
+        let shards: Vec<serde_json::Value> = join_all(self.shards.iter().map(|s| s.get_metadata()))
+            .await
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() })))
+            .collect();
+        Ok(serde_json::json!({
+            "type": "sharded",
+            "key": self.key,
+            "tenant_property": self.tenant_property,
+            "shards": shards,
+        }))
+    }
+
+    async fn close(&self) -> Result<()> {
+        all(join_all(self.shards.iter().map(|s| s.close())).await)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MemoryStore for ShardedStorage {
+    async fn create_memory(&self, mut memory: Memory) -> Result<Memory> {
+        ensure_id(&mut memory);
+        self.shards[self.shard_for_memory(&memory)]
+            .create_memory(memory)
+            .await
+    }
+
+    async fn get_memory(&self, id: &str) -> Result<Option<Memory>> {
+        found(join_all(self.shards.iter().map(|s| s.get_memory(id))).await)
+    }
+
+    async fn update_memory(&self, memory: Memory) -> Result<Memory> {
+        let shard = self.memory_write_shard(&memory).await?;
+        self.shards[shard].update_memory(memory).await
+    }
+
+    async fn delete_memory(&self, id: &str) -> Result<bool> {
+        any(join_all(self.shards.iter().map(|s| s.delete_memory(id))).await)
+    }
+
+    async fn upsert_memory(&self, mut memory: Memory) -> Result<Memory> {
+        ensure_id(&mut memory);
+        let shard = self.memory_write_shard(&memory).await?;
+        self.shards[shard].upsert_memory(memory).await
+    }
+
+    async fn list_memories(
+        &self,
+        filter: Option<MemoryFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        let per_shard = shard_limit(limit, offset);
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.list_memories(filter.clone(), per_shard, None)),
+        )
+        .await;
+        let memories = merged("memory listing", results)?;
+        Ok(page(memories, |m| m.created_at, limit, offset))
+    }
+
+    async fn count_memories(&self, filter: Option<MemoryFilter>) -> Result<usize> {
+        let results = join_all(self.shards.iter().map(|s| s.count_memories(filter.clone()))).await;
+        Ok(gathered("memory count", results)?.into_iter().sum())
+    }
+
+    /// Each shard's share of the batch is written atomically, but the shards
+    /// are written independently
+    async fn batch_create_memories(&self, mut memories: Vec<Memory>) -> Result<Vec<Memory>> {
+        memories.iter_mut().for_each(ensure_id);
+        self.batch_by_shard(
+            memories,
+            |memory| self.shard_for_memory(memory),
+            |shard, group| self.shards[shard].batch_create_memories(group),
+        )
+        .await
+    }
+
+    async fn bm25_search_memories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32, String)>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.bm25_search_memories(query, limit)),
+        )
+        .await;
+        Ok(top(merged("BM25 search", results)?, |r| r.1, limit))
+    }
+
+    async fn fuzzy_search_memories(
+        &self,
+        query: &str,
+        similarity_threshold: Option<f32>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.fuzzy_search_memories(query, similarity_threshold, limit)),
+        )
+        .await;
+        Ok(top(merged("fuzzy search", results)?, |r| r.1, limit))
+    }
+
+    async fn vector_search_memories(
+        &self,
+        query_vector: &[f32],
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32, String)>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.vector_search_memories(query_vector, limit)),
+        )
+        .await;
+        Ok(top(merged("vector search", results)?, |r| r.1, limit))
+    }
+
+    async fn bm25_search_memory_hits(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.bm25_search_memory_hits(query, limit)),
+        )
+        .await;
+        Ok(top(merged("BM25 search", results)?, |hit| hit.score, limit))
+    }
+
+    async fn vector_search_memory_hits(
+        &self,
+        query_vector: &[f32],
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.vector_search_memory_hits(query_vector, limit)),
+        )
+        .await;
+        Ok(top(
+            merged("vector search", results)?,
+            |hit| hit.score,
+            limit,
+        ))
+    }
+
+    async fn sparse_search_memories(
+        &self,
+        query: &SparseVector,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.sparse_search_memories(query, limit)),
+        )
+        .await;
+        Ok(top(merged("sparse search", results)?, |r| r.1, limit))
+    }
+
+    async fn search_memories_with_scoring(
+        &self,
+        query: &str,
+        scoring: Option<crate::search::ScoringConfig>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.search_memories_with_scoring(query, scoring.clone(), limit)),
+        )
+        .await;
+        Ok(top(merged("scored search", results)?, |r| r.1, limit))
+    }
+}
+
+#[async_trait]
+impl EntityStore for ShardedStorage {
+    async fn create_entity(&self, entity: Entity) -> Result<Entity> {
+        self.shards[self.entity_shard(&entity)]
+            .create_entity(entity)
+            .await
+    }
+
+    async fn get_entity(&self, id: &str) -> Result<Option<Entity>> {
+        found(join_all(self.shards.iter().map(|s| s.get_entity(id))).await)
+    }
+
+    async fn update_entity(&self, entity: Entity) -> Result<Entity> {
+        let holders = self.entity_holders(&entity.id).await?;
+        if holders.is_empty() {
+            return self.shards[self.entity_shard(&entity)]
+                .update_entity(entity)
+                .await;
+        }
+        let updates = holders
+            .iter()
+            .map(|&shard| self.shards[shard].update_entity(entity.clone()));
+        Ok(all(join_all(updates).await)?.remove(0))
+    }
+
+    async fn delete_entity(&self, id: &str) -> Result<bool> {
+        any(join_all(self.shards.iter().map(|s| s.delete_entity(id))).await)
+    }
+
+    async fn upsert_entity(&self, entity: Entity) -> Result<Entity> {
+        let holders = self.entity_holders(&entity.id).await?;
+        if holders.is_empty() {
+            return self.shards[self.entity_shard(&entity)]
+                .upsert_entity(entity)
+                .await;
+        }
+        let upserts = holders
+            .iter()
+            .map(|&shard| self.shards[shard].upsert_entity(entity.clone()));
+        Ok(all(join_all(upserts).await)?.remove(0))
+    }
+
+    async fn list_entities(
+        &self,
+        filter: Option<EntityFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Entity>> {
+        let per_shard = shard_limit(limit, offset);
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.list_entities(filter.clone(), per_shard, None)),
+        )
+        .await;
+        let entities = unique(merged("entity listing", results)?, |e| &e.id);
+        Ok(page(entities, |e| e.created_at, limit, offset))
+    }
+
+    /// Counts each entity once, however many shards hold a copy
+    async fn count_entities(&self, filter: Option<EntityFilter>) -> Result<usize> {
+        Ok(self.list_entities(filter, None, None).await?.len())
+    }
+}
+
+#[async_trait]
+impl RelationshipStore for ShardedStorage {
+    async fn create_relationship(&self, relationship: Relationship) -> Result<Relationship> {
+        let shard = self
+            .node_shard(&relationship.source_id)
+            .await?
+            .ok_or_else(|| {
+                StorageError::NotFound(format!(
+                    "Source node (memory or entity) with ID {} not found",
+                    relationship.source_id
+                ))
+            })?;
+        // A "references" target is a relationship, which has to be local already
+        if relationship.relationship_type != "references" {
+            self.bring_to_shard(&relationship.target_id, shard).await?;
+        }
+        self.shards[shard].create_relationship(relationship).await
+    }
+
+    async fn get_relationship(&self, id: &str) -> Result<Option<Relationship>> {
+        found(join_all(self.shards.iter().map(|s| s.get_relationship(id))).await)
+    }
+
+    async fn update_relationship(&self, relationship: Relationship) -> Result<Relationship> {
+        match self.find_relationship(&relationship.id).await? {
+            Some(shard) => self.shards[shard].update_relationship(relationship).await,
+            None => Err(StorageError::NotFound(format!(
+                "Relationship with ID {} not found",
+                relationship.id
+            ))),
+        }
+    }
+
+    async fn delete_relationship(&self, id: &str) -> Result<bool> {
+        any(join_all(self.shards.iter().map(|s| s.delete_relationship(id))).await)
+    }
+
+    async fn upsert_relationship(&self, relationship: Relationship) -> Result<Relationship> {
+        match self.find_relationship(&relationship.id).await? {
+            Some(shard) => self.shards[shard].upsert_relationship(relationship).await,
+            None => self.create_relationship(relationship).await,
+        }
+    }
+
+    async fn list_relationships(
+        &self,
+        filter: Option<RelationshipFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Relationship>> {
+        let per_shard = shard_limit(limit, offset);
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.list_relationships(filter.clone(), per_shard, None)),
+        )
+        .await;
+        let relationships = merged("relationship listing", results)?;
+        Ok(page(relationships, |r| r.created_at, limit, offset))
+    }
+
+    async fn count_relationships(&self, filter: Option<RelationshipFilter>) -> Result<usize> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.count_relationships(filter.clone())),
+        )
+        .await;
+        Ok(gathered("relationship count", results)?.into_iter().sum())
+    }
+
+    async fn get_relationship_by_entities(
+        &self,
+        source_id: &str,
+        target_id: &str,
+    ) -> Result<Option<Relationship>> {
+        found(
+            join_all(
+                self.shards
+                    .iter()
+                    .map(|s| s.get_relationship_by_entities(source_id, target_id)),
+            )
+            .await,
+        )
+    }
+
+    async fn get_relationship_properties(&self, id: &str) -> Result<serde_json::Value> {
+        match self.get_relationship(id).await? {
+            Some(relationship) => Ok(relationship.properties),
+            None => Err(StorageError::NotFound(format!(
+                "Relationship with ID {} not found",
+                id
+            ))),
+        }
+    }
+
+    async fn find_relationships(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relationship_type: Option<String>,
+    ) -> Result<Vec<Relationship>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.find_relationships(source_id, target_id, relationship_type.clone())),
+        )
+        .await;
+        Ok(unique(merged("relationship lookup", results)?, |r| &r.id))
+    }
+
+    async fn find_related_entities(
+        &self,
+        entity_id: &str,
+        relationship_type: Option<String>,
+        direction: Option<String>,
+    ) -> Result<Vec<Entity>> {
+        let results = join_all(self.shards.iter().map(|s| {
+            s.find_related_entities(entity_id, relationship_type.clone(), direction.clone())
+        }))
+        .await;
+        Ok(unique(merged("related entity lookup", results)?, |e| &e.id))
+    }
+}
+
+#[async_trait]
+impl VersionStore for ShardedStorage {
+    async fn create_version(&self, version: Version) -> Result<Version> {
+        self.shards[0].create_version(version).await
+    }
+
+    async fn get_version(&self, id: &str) -> Result<Option<Version>> {
+        self.shards[0].get_version(id).await
+    }
+
+    async fn list_versions(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Version>> {
+        self.shards[0].list_versions(limit, offset).await
+    }
+
+    async fn checkout_version(&self, id: &str) -> Result<bool> {
+        self.shards[0].checkout_version(id).await
+    }
+}
+
+#[async_trait]
+impl VectorStore for ShardedStorage {
+    async fn add_vector(&self, mut vector: Vector) -> Result<Vector> {
+        ensure_vector_id(&mut vector);
+        self.shards[self.shard_for_key(&vector.id)]
+            .add_vector(vector)
+            .await
+    }
+
+    async fn get_vector(&self, id: &str) -> Result<Option<Vector>> {
+        self.shards[self.shard_for_key(id)].get_vector(id).await
+    }
+
+    async fn delete_vector(&self, id: &str) -> Result<bool> {
+        self.shards[self.shard_for_key(id)].delete_vector(id).await
+    }
+
+    async fn update_vector_metadata(
+        &self,
+        id: &str,
+        metadata: serde_json::Value,
+    ) -> Result<Vector> {
+        self.shards[self.shard_for_key(id)]
+            .update_vector_metadata(id, metadata)
+            .await
+    }
+
+    async fn search_vectors(
+        &self,
+        query_vector: &[f32],
+        params: VectorSearchParams,
+    ) -> Result<Vec<(Vector, f32)>> {
+        let limit = params.limit;
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.search_vectors(query_vector, params.clone())),
+        )
+        .await;
+        // Vector store results are distances, nearest first
+        Ok(top(merged("vector search", results)?, |r| -r.1, limit))
+    }
+
+    async fn list_vectors(
+        &self,
+        filter: Option<VectorFilter>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Vector>> {
+        let per_shard = shard_limit(limit, offset);
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.list_vectors(filter.clone(), per_shard, None)),
+        )
+        .await;
+        let vectors = merged("vector listing", results)?;
+        Ok(page(vectors, |v| v.created_at, limit, offset))
+    }
+
+    async fn count_vectors(&self, filter: Option<VectorFilter>) -> Result<usize> {
+        let results = join_all(self.shards.iter().map(|s| s.count_vectors(filter.clone()))).await;
+        Ok(gathered("vector count", results)?.into_iter().sum())
+    }
+
+    async fn batch_add_vectors(&self, mut vectors: Vec<Vector>) -> Result<Vec<Vector>> {
+        vectors.iter_mut().for_each(ensure_vector_id);
+        self.batch_by_shard(
+            vectors,
+            |vector| self.shard_for_key(&vector.id),
+            |shard, group| self.shards[shard].batch_add_vectors(group),
+        )
+        .await
+    }
+
+    async fn upsert_vector(&self, vector: Vector) -> Result<()> {
+        self.shards[self.shard_for_key(&vector.id)]
+            .upsert_vector(vector)
+            .await
+    }
+}
+
+#[async_trait]
+impl GraphTraversal for ShardedStorage {
+    async fn get_memory_subgraph(&self, memory_id: &str, depth: u8) -> Result<MemoryGraph> {
+        let shard = self.memory_shard(memory_id).await?;
+        self.shards[shard]
+            .get_memory_subgraph(memory_id, depth)
+            .await
+    }
+
+    async fn find_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        max_depth: u8,
+    ) -> Result<Vec<MemoryPath>> {
+        let shard = self.memory_shard(from_id).await?;
+        self.shards[shard]
+            .find_paths(from_id, to_id, max_depth)
+            .await
+    }
+
+    async fn find_weighted_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        constraints: &PathConstraints,
+    ) -> Result<Vec<MemoryPath>> {
+        let shard = self.memory_shard(from_id).await?;
+        self.shards[shard]
+            .find_weighted_paths(from_id, to_id, constraints)
+            .await
+    }
+
+    async fn find_connected_memories(
+        &self,
+        memory_id: &str,
+        relationship_type: Option<&str>,
+        max_depth: u8,
+    ) -> Result<Vec<Memory>> {
+        let shard = self.memory_shard(memory_id).await?;
+        self.shards[shard]
+            .find_connected_memories(memory_id, relationship_type, max_depth)
+            .await
+    }
+
+    async fn get_entities_from_memory(&self, memory_id: &str) -> Result<Vec<Entity>> {
+        let shard = self.memory_shard(memory_id).await?;
+        self.shards[shard].get_entities_from_memory(memory_id).await
+    }
+
+    async fn get_memories_containing_entity(&self, entity_id: &str) -> Result<Vec<Memory>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.get_memories_containing_entity(entity_id)),
+        )
+        .await;
+        Ok(unique(merged("entity traversal", results)?, |m| &m.id))
+    }
+
+    async fn get_entity_relationships(&self, entity_id: &str) -> Result<Vec<Relationship>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.get_entity_relationships(entity_id)),
+        )
+        .await;
+        Ok(unique(merged("entity traversal", results)?, |r| &r.id))
+    }
+}
+
+#[async_trait]
+impl ArchiveStore for ShardedStorage {
+    async fn archive_memory(&self, id: &str) -> Result<bool> {
+        let shard = self.memory_shard(id).await?;
+        self.shards[shard].archive_memory(id).await
+    }
+
+    async fn unarchive_memory(&self, id: &str) -> Result<bool> {
+        any(join_all(self.shards.iter().map(|s| s.unarchive_memory(id))).await)
+    }
+
+    async fn get_archived_memory(&self, id: &str) -> Result<Option<Memory>> {
+        found(join_all(self.shards.iter().map(|s| s.get_archived_memory(id))).await)
+    }
+
+    /// Merged by creation time, as archive times aren't part of [`Memory`]
+    async fn list_archived_memories(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Memory>> {
+        let per_shard = shard_limit(limit, offset);
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.list_archived_memories(per_shard, None)),
+        )
+        .await;
+        let memories = merged("archive listing", results)?;
+        Ok(page(memories, |m| m.created_at, limit, offset))
+    }
+
+    async fn search_archived_memories(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchResult>> {
+        let results = join_all(
+            self.shards
+                .iter()
+                .map(|s| s.search_archived_memories(query, query_embedding, limit)),
+        )
+        .await;
+        Ok(top(
+            merged("archive search", results)?,
+            |r| r.score.unwrap_or(0.0),
+            limit,
+        ))
+    }
+
+    async fn archive_inactive_memories(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let mut archived = Vec::new();
+        for shard in &self.shards {
+            let remaining = limit.map(|limit| limit - archived.len());
+            if remaining == Some(0) {
+                break;
+            }
+            archived.extend(shard.archive_inactive_memories(cutoff, remaining).await?);
+        }
+        Ok(archived)
+    }
+
+    async fn get_archive_stats(&self) -> Result<ArchiveStats> {
+        let results = join_all(self.shards.iter().map(|s| s.get_archive_stats())).await;
+        Ok(gathered("archive stats", results)?.into_iter().fold(
+            ArchiveStats {
+                archived_count: 0,
+                original_bytes: 0,
+                stored_bytes: 0,
+                oldest_archived_at: None,
+            },
+            |total, stats| ArchiveStats {
+                archived_count: total.archived_count + stats.archived_count,
+                original_bytes: total.original_bytes + stats.original_bytes,
+                stored_bytes: total.stored_bytes + stats.stored_bytes,
+                oldest_archived_at: match (total.oldest_archived_at, stats.oldest_archived_at) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                },
+            },
+        ))
+    }
+}
+
+#[async_trait]
+impl OutboxStore for ShardedStorage {
+    async fn create_memory_with_outbox(
+        &self,
+        mut memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<Memory> {
+        ensure_id(&mut memory);
+        self.shards[self.shard_for_memory(&memory)]
+            .create_memory_with_outbox(memory, messages)
+            .await
+    }
+
+    async fn update_memory_with_outbox(
+        &self,
+        memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<Memory> {
+        let shard = self.memory_write_shard(&memory).await?;
+        self.shards[shard]
+            .update_memory_with_outbox(memory, messages)
+            .await
+    }
+
+    async fn delete_memory_with_outbox(
+        &self,
+        id: &str,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<bool> {
+        let shard = self.memory_shard(id).await?;
+        self.shards[shard]
+            .delete_memory_with_outbox(id, messages)
+            .await
+    }
+
+    async fn pending_outbox(&self, limit: usize) -> Result<Vec<OutboxMessage>> {
+        let results = join_all(self.shards.iter().map(|s| s.pending_outbox(limit))).await;
+        let mut messages = merged("outbox read", results)?;
+        messages.sort_by_key(|message| message.created_at);
+        messages.truncate(limit);
+        Ok(messages)
+    }
+
+    async fn ack_outbox(&self, id: &str) -> Result<bool> {
+        any(join_all(self.shards.iter().map(|s| s.ack_outbox(id))).await)
+    }
+}
+
+impl ShardedStorage {
+    /// A record as of `at_time`, from its latest version on any shard
+    async fn record_at_time<T: serde::de::DeserializeOwned>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<T>> {
+        let latest = self
+            .list_record_versions(kind, id)
+            .await?
+            .into_iter()
+            .rev()
+            .find(|version| version.created_at <= at_time);
+        let Some(data) = latest.and_then(|version| version.data) else {
+            return Ok(None);
+        };
+        serde_json::from_value(data).map(Some).map_err(|e| {
+            StorageError::Serialization(format!("Failed to parse record version: {}", e))
+        })
+    }
+}
+
+/// Entities copied to several shards keep a history on each copy, so a write
+/// to a copied entity is listed once per copy.
+#[async_trait]
+impl RecordVersionStore for ShardedStorage {
+    async fn list_record_versions(&self, kind: RecordKind, id: &str) -> Result<Vec<RecordVersion>> {
+        let results = join_all(self.shards.iter().map(|s| s.list_record_versions(kind, id))).await;
+        let mut versions = merged("version listing", results)?;
+        versions.sort_by_key(|version| version.created_at);
+        Ok(versions)
+    }
+
+    async fn get_record_version(&self, version_id: &str) -> Result<Option<RecordVersion>> {
+        found(join_all(self.shards.iter().map(|s| s.get_record_version(version_id))).await)
+    }
+
+    async fn get_entity_at_time(&self, id: &str, at_time: DateTime<Utc>) -> Result<Option<Entity>> {
+        self.record_at_time(RecordKind::Entity, id, at_time).await
+    }
+
+    async fn get_relationship_at_time(
+        &self,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<Relationship>> {
+        self.record_at_time(RecordKind::Relationship, id, at_time)
+            .await
+    }
+
+    /// Versions may come from different shards, so the diff is computed here
+    async fn diff_record_versions(
+        &self,
+        old_version_id: &str,
+        new_version_id: &str,
+    ) -> Result<RecordDiff> {
+        let old_version = self
+            .get_record_version(old_version_id)
+            .await?
+            .ok_or_else(|| {
+                StorageError::NotFound(format!("Old version not found: {}", old_version_id))
+            })?;
+        let new_version = self
+            .get_record_version(new_version_id)
+            .await?
+            .ok_or_else(|| {
+                StorageError::NotFound(format!("New version not found: {}", new_version_id))
+            })?;
+        if old_version.kind != new_version.kind || old_version.record_id != new_version.record_id {
+            return Err(StorageError::Validation(format!(
+                "Versions {} and {} belong to different records",
+                old_version_id, new_version_id
+            )));
+        }
+
+        Ok(RecordDiff {
+            kind: new_version.kind,
+            record_id: new_version.record_id,
+            old_version_id: old_version.version_id,
+            new_version_id: new_version.version_id,
+            changes: diff_fields(old_version.data.as_ref(), new_version.data.as_ref()),
+        })
+    }
+}
+
+#[async_trait]
+impl GraphStore for ShardedStorage {
+    async fn clear_storage(&self) -> Result<()> {
+        all(join_all(self.shards.iter().map(|s| s.clear_storage())).await)?;
+        Ok(())
+    }
+
+    async fn acquire_lease(
+        &self,
+        name: &str,
+        holder: &str,
+        ttl: std::time::Duration,
+    ) -> Result<bool> {
+        self.shards[0].acquire_lease(name, holder, ttl).await
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<bool> {
+        self.shards[0].release_lease(name, holder).await
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_is_stable() {
+        // Placement must not change between builds or platforms
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_shard_config_separates_shards() {
+        let config = SurrealDBConfig {
+            engine: SurrealDBEngine::RocksDB,
+            connection: "./data/graph".to_string(),
+            namespace: "locai".to_string(),
+            database: "main".to_string(),
+            auth: None,
+            settings: None,
+        };
+        let shard = shard_config(&config, 2);
+        assert_eq!(shard.namespace, "locai_shard2");
+        assert_eq!(shard.connection, "./data/graph/shard-2");
+        assert_eq!(shard.database, "main");
+    }
+
+    #[test]
+    fn test_found_prefers_a_match_over_a_failure() {
+        let results = vec![
+            Err(StorageError::Connection("down".to_string())),
+            Ok(Some(1)),
+        ];
+        assert_eq!(found(results).unwrap(), Some(1));
+
+        let results: Vec<Result<Option<i32>>> =
+            vec![Err(StorageError::Connection("down".to_string())), Ok(None)];
+        assert!(found(results).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! Entity alias expansion for full-text search
+//!
+//! Entities may list other names in an `aliases` property. Every memory that
+//! mentions such an entity, by any of its names or through a `contains` edge,
+//! gets a `memory_alias` record holding all of the entity's names. That table
+//! has its own BM25 index, so a search for "Bob" also finds memories that only
+//! say "Robert Smith". Records are rebuilt whenever a memory, an entity with
+//! aliases or a link between them changes; failures are logged rather than
+//! failing the write, like other derived data.
+
+use std::collections::{BTreeSet, HashSet};
+
+use serde::Deserialize;
+use surrealdb::{Connection, RecordId};
+
+use super::base::{SharedStorage, record_key};
+use super::entity::SurrealEntity;
+use super::memory::{SurrealMemory, snippet};
+use crate::models::Memory;
+use crate::storage::errors::StorageError;
+use crate::storage::models::{Entity, SearchHit};
+
+/// Entity properties holding the canonical name, in order of preference
+const NAME_PROPERTIES: &[&str] = &["name", "text", "value", "title"];
+
+/// A memory found through the names of an entity it mentions
+#[derive(Debug, Deserialize)]
+struct AliasMatch {
+    id: RecordId,
+    content: String,
+    score: f32,
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// Memories whose entity names match `query`, best first
+    async fn search_aliases(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<AliasMatch>, StorageError> {
+        // Skip records left behind by deleted or archived memories
+        let search = r#"
+            SELECT memory AS id,
+                   memory.content AS content,
+                   search::score(0) AS score
+            FROM memory_alias
+            WHERE terms @0@ $query AND memory.content != NONE
+            ORDER BY score DESC
+            LIMIT $limit
+        "#;
+
+        let mut result = self
+            .client
+            .query(search)
+            .bind(("query", query.to_string()))
+            .bind(("limit", limit))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to search aliases: {}", e)))?;
+
+        result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract alias matches: {}", e)))
+    }
+
+    /// Add memories found through entity names to BM25 results
+    ///
+    /// A memory matched both ways keeps the better score.
+    pub(super) async fn merge_alias_matches(
+        &self,
+        query: &str,
+        limit: usize,
+        results: &mut Vec<(Memory, f32, String)>,
+    ) -> Result<(), StorageError> {
+        let mut missing = Vec::new();
+        for found in self.search_aliases(query, limit).await? {
+            let id = record_key(&found.id);
+            match results.iter_mut().find(|(memory, ..)| memory.id == id) {
+                Some(result) => result.1 = result.1.max(found.score),
+                None => missing.push(found),
+            }
+        }
+
+        if !missing.is_empty() {
+            let ids: Vec<RecordId> = missing.iter().map(|found| found.id.clone()).collect();
+            let mut result = self
+                .client
+                .query("SELECT * FROM $ids")
+                .bind(("ids", ids))
+                .await
+                .map_err(|e| {
+                    StorageError::Query(format!("Failed to load aliased memories: {}", e))
+                })?;
+            let memories: Vec<SurrealMemory> = result.take(0).map_err(|e| {
+                StorageError::Query(format!("Failed to extract aliased memories: {}", e))
+            })?;
+            for memory in memories.into_iter().map(Memory::from) {
+                if let Some(found) = missing.iter().find(|f| record_key(&f.id) == memory.id) {
+                    let content = found.content.clone();
+                    results.push((memory, found.score, content));
+                }
+            }
+        }
+
+        results.sort_by(|a, b| b.1.total_cmp(&a.1));
+        results.truncate(limit);
+        Ok(())
+    }
+
+    /// Add memories found through entity names to BM25 hits
+    pub(super) async fn merge_alias_hits(
+        &self,
+        query: &str,
+        limit: usize,
+        hits: &mut Vec<SearchHit>,
+    ) -> Result<(), StorageError> {
+        for found in self.search_aliases(query, limit).await? {
+            let id = record_key(&found.id);
+            match hits.iter_mut().find(|hit| hit.id == id) {
+                Some(hit) => hit.score = hit.score.max(found.score),
+                None => hits.push(SearchHit {
+                    id,
+                    score: found.score,
+                    snippet: snippet(&found.content),
+                }),
+            }
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(())
+    }
+
+    /// Rebuild the alias record of a memory after it was written or linked
+    pub(super) async fn refresh_memory_aliases(&self, memory_id: &str) {
+        let refreshed = match self.alias_entities().await {
+            Ok(entities) => self.index_memory_aliases(memory_id, &entities).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = refreshed {
+            tracing::warn!("Failed to index aliases for memory {}: {}", memory_id, e);
+        }
+    }
+
+    /// Rebuild the alias records of memories mentioning an entity after it changed
+    pub(super) async fn refresh_entity_aliases(&self, entity_id: &str) {
+        if let Err(e) = self.reindex_entity_aliases(entity_id).await {
+            tracing::warn!("Failed to index aliases of entity {}: {}", entity_id, e);
+        }
+    }
+
+    /// Drop the alias record of a deleted memory
+    pub(super) async fn drop_memory_aliases(&self, memory_id: &str) {
+        if let Err(e) = self
+            .client
+            .query("DELETE $alias")
+            .bind(("alias", RecordId::from(("memory_alias", memory_id))))
+            .await
+        {
+            tracing::warn!("Failed to drop aliases of memory {}: {}", memory_id, e);
+        }
+    }
+
+    async fn reindex_entity_aliases(&self, entity_id: &str) -> Result<(), StorageError> {
+        let entities = self.alias_entities().await?;
+        let entity = RecordId::from(("entity", entity_id));
+
+        // Memories indexed with the entity's old names, or linked to it
+        let mut result = self
+            .client
+            .query("SELECT VALUE memory FROM memory_alias WHERE entities CONTAINS $entity_id")
+            .query("SELECT VALUE in FROM contains WHERE out = $entity")
+            .bind(("entity_id", entity_id.to_string()))
+            .bind(("entity", entity))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to find aliased memories: {}", e)))?;
+        let indexed: Vec<RecordId> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract aliased memories: {}", e))
+        })?;
+        let linked: Vec<RecordId> = result.take(1).map_err(|e| {
+            StorageError::Query(format!("Failed to extract linked memories: {}", e))
+        })?;
+
+        let mut memories: HashSet<String> = indexed.iter().chain(&linked).map(record_key).collect();
+
+        // Memories that use one of its names now
+        if let Some(entity) = entities.iter().find(|e| e.id == entity_id) {
+            for name in entity_names(entity) {
+                let mut result = self
+                    .client
+                    .query("SELECT VALUE id FROM memory WHERE content @@ $name")
+                    .bind(("name", name))
+                    .await
+                    .map_err(|e| {
+                        StorageError::Query(format!("Failed to find mentioning memories: {}", e))
+                    })?;
+                let mentioning: Vec<RecordId> = result.take(0).map_err(|e| {
+                    StorageError::Query(format!("Failed to extract mentioning memories: {}", e))
+                })?;
+                memories.extend(mentioning.iter().map(record_key));
+            }
+        }
+
+        for memory_id in memories {
+            self.index_memory_aliases(&memory_id, &entities).await?;
+        }
+        Ok(())
+    }
+
+    /// Entities with at least one alias
+    async fn alias_entities(&self) -> Result<Vec<Entity>, StorageError> {
+        let mut result = self
+            .client
+            .query(
+                "SELECT * FROM entity WHERE type::is::array(properties.aliases) \
+                 AND array::len(properties.aliases) > 0",
+            )
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to list aliased entities: {}", e)))?;
+        let entities: Vec<SurrealEntity> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract aliased entities: {}", e))
+        })?;
+        Ok(entities.into_iter().map(Entity::from).collect())
+    }
+
+    async fn index_memory_aliases(
+        &self,
+        memory_id: &str,
+        entities: &[Entity],
+    ) -> Result<(), StorageError> {
+        let alias = RecordId::from(("memory_alias", memory_id));
+        if entities.is_empty() {
+            self.client
+                .query("DELETE $alias")
+                .bind(("alias", alias))
+                .await
+                .map_err(|e| StorageError::Query(format!("Failed to drop aliases: {}", e)))?;
+            return Ok(());
+        }
+
+        let memory = RecordId::from(("memory", memory_id));
+        let mut result = self
+            .client
+            .query("SELECT VALUE content FROM $memory")
+            .query("SELECT VALUE out FROM contains WHERE in = $memory")
+            .bind(("memory", memory.clone()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to read memory: {}", e)))?;
+        let content: Vec<String> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract content: {}", e)))?;
+        let linked: Vec<RecordId> = result
+            .take(1)
+            .map_err(|e| StorageError::Query(format!("Failed to extract links: {}", e)))?;
+
+        let tokens = content.first().map(|c| tokenize(c)).unwrap_or_default();
+        let linked: HashSet<String> = linked.iter().map(record_key).collect();
+        let mut terms = BTreeSet::new();
+        let mut sources = Vec::new();
+        for entity in entities {
+            let names = entity_names(entity);
+            let mentioned = linked.contains(&entity.id)
+                || names
+                    .iter()
+                    .any(|name| contains_phrase(&tokens, &tokenize(name)));
+            if mentioned {
+                terms.extend(names);
+                sources.push(entity.id.clone());
+            }
+        }
+
+        let query = if sources.is_empty() || content.is_empty() {
+            "DELETE $alias"
+        } else {
+            "UPSERT $alias CONTENT { memory: $memory, terms: $terms, entities: $entities }"
+        };
+        self.client
+            .query(query)
+            .bind(("alias", alias))
+            .bind(("memory", memory))
+            .bind(("terms", terms.into_iter().collect::<Vec<_>>().join(" ")))
+            .bind(("entities", sources))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to index aliases: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Canonical name of an entity
+pub(super) fn canonical_name(entity: &Entity) -> Option<&str> {
+    NAME_PROPERTIES
+        .iter()
+        .find_map(|key| entity.properties.get(*key).and_then(|v| v.as_str()))
+}
+
+/// Canonical name and aliases of an entity
+pub(super) fn entity_names(entity: &Entity) -> Vec<String> {
+    let name = canonical_name(entity);
+    let aliases = entity
+        .properties
+        .get("aliases")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|alias| alias.as_str());
+
+    name.into_iter()
+        .chain(aliases)
+        .filter(|name| !name.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether `phrase` occurs as a contiguous token sequence in `tokens`
+fn contains_phrase(tokens: &[String], phrase: &[String]) -> bool {
+    !phrase.is_empty() && tokens.windows(phrase.len()).any(|window| window == phrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn test_entity_names_put_canonical_name_first() {
+        let entity = Entity {
+            id: "e1".to_string(),
+            entity_type: "person".to_string(),
+            properties: json!({ "aliases": ["Bob", " "], "name": "Robert Smith" }),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert_eq!(entity_names(&entity), vec!["Robert Smith", "Bob"]);
+    }
+
+    #[test]
+    fn test_names_match_whole_words() {
+        let tokens = tokenize("Met Robert Smith, then Bobby.");
+        assert!(contains_phrase(&tokens, &tokenize("robert smith")));
+        assert!(!contains_phrase(&tokens, &tokenize("Bob")));
+    }
+}
@@ -0,0 +1,395 @@
+//! Archive (cold storage) implementation for SharedStorage
+//!
+//! Archived memories live in the `memory_archive` table as gzip-compressed
+//! JSON. That table has no full-text or vector index, so archived memories
+//! drop out of normal search; `search_archived_memories` scans them instead.
+//! Entries in the `relationship` table are kept, but graph edges attached to
+//! the memory record are removed with it.
+
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use surrealdb::{Connection, RecordId, Surreal};
+
+use super::base::SharedStorage;
+use super::checksum::memory_checksum;
+use super::memory::{SurrealMemory, memory_metadata};
+use super::memory_version::{compress_content, decompress_content};
+use crate::models::Memory;
+use crate::storage::errors::StorageError;
+use crate::storage::models::{ArchiveStats, SearchResult};
+use crate::storage::traits::ArchiveStore;
+
+/// Archive record as read back from SurrealDB
+#[derive(Debug, Clone, Deserialize)]
+struct SurrealArchivedMemory {
+    data: String,
+}
+
+impl SurrealArchivedMemory {
+    fn into_memory(self) -> Result<Memory, StorageError> {
+        let compressed = general_purpose::STANDARD.decode(&self.data).map_err(|e| {
+            StorageError::Serialization(format!("Failed to decode archived memory: {}", e))
+        })?;
+        let json = decompress_content(&compressed)?;
+        serde_json::from_str(&json).map_err(|e| {
+            StorageError::Serialization(format!("Failed to parse archived memory: {}", e))
+        })
+    }
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// Move one memory record into the archive table
+    pub(super) async fn archive_record(
+        client: &Surreal<C>,
+        memory: &Memory,
+    ) -> Result<(), StorageError> {
+        let json = serde_json::to_string(memory).map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize memory: {}", e))
+        })?;
+        let data = general_purpose::STANDARD.encode(compress_content(&json)?);
+
+        let query = r#"
+            BEGIN TRANSACTION;
+            CREATE $archive_id CONTENT {
+                memory_id: $memory_id,
+                data: $data,
+                memory_type: $memory_type,
+                last_accessed: $last_accessed,
+                created_at: type::datetime($created_at),
+                archived_at: time::now(),
+                original_bytes: $original_bytes,
+                stored_bytes: $stored_bytes
+            };
+            DELETE $memory_record;
+            COMMIT TRANSACTION;
+        "#;
+
+        client
+            .query(query)
+            .bind((
+                "archive_id",
+                RecordId::from(("memory_archive", memory.id.as_str())),
+            ))
+            .bind((
+                "memory_record",
+                RecordId::from(("memory", memory.id.as_str())),
+            ))
+            .bind(("memory_id", memory.id.clone()))
+            .bind(("memory_type", memory.memory_type.to_string()))
+            .bind((
+                "last_accessed",
+                memory.last_accessed.map(|dt| dt.to_rfc3339()),
+            ))
+            .bind(("created_at", memory.created_at.to_rfc3339()))
+            .bind(("original_bytes", json.len()))
+            .bind(("stored_bytes", data.len()))
+            .bind(("data", data))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to archive memory: {}", e)))?
+            .check()
+            .map_err(|e| StorageError::Query(format!("Failed to archive memory: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Archive memories inactive since `cutoff`, returning their IDs
+    pub(super) async fn archive_inactive(
+        client: &Surreal<C>,
+        cutoff: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<String>, StorageError> {
+        // last_accessed is stored as an RFC 3339 string (or null) in metadata
+        let query = r#"
+            SELECT * FROM memory
+            WHERE type::datetime(metadata.last_accessed ?? created_at) < type::datetime($cutoff)
+            LIMIT $limit
+        "#;
+
+        let mut result = client
+            .query(query)
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .bind(("limit", limit))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to find inactive memories: {}", e)))?;
+        let candidates: Vec<SurrealMemory> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract inactive memories: {}", e))
+        })?;
+
+        let mut archived = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            let memory = Memory::from(candidate);
+            match Self::archive_record(client, &memory).await {
+                Ok(()) => archived.push(memory.id),
+                Err(e) => tracing::warn!("Failed to archive memory {}: {}", memory.id, e),
+            }
+        }
+
+        Ok(archived)
+    }
+
+    async fn load_archived(&self, id: &str) -> Result<Option<SurrealArchivedMemory>, StorageError> {
+        let mut result = self
+            .client
+            .query("SELECT data FROM $id")
+            .bind(("id", RecordId::from(("memory_archive", id))))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get archived memory: {}", e)))?;
+        let records: Vec<SurrealArchivedMemory> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract archived memory: {}", e))
+        })?;
+        Ok(records.into_iter().next())
+    }
+}
+
+#[async_trait]
+impl<C> ArchiveStore for SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    async fn archive_memory(&self, id: &str) -> Result<bool, StorageError> {
+        let memories: Vec<SurrealMemory> = self
+            .client
+            .query("SELECT * FROM $id")
+            .bind(("id", RecordId::from(("memory", id))))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get memory: {}", e)))?
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract memory: {}", e)))?;
+
+        let Some(memory) = memories.into_iter().next().map(Memory::from) else {
+            return Ok(false);
+        };
+
+        Self::archive_record(&self.client, &memory).await?;
+        Ok(true)
+    }
+
+    async fn unarchive_memory(&self, id: &str) -> Result<bool, StorageError> {
+        let Some(archived) = self.load_archived(id).await? else {
+            return Ok(false);
+        };
+        let memory = archived.into_memory()?;
+
+        self.ensure_system_user().await?;
+
+        let query = r#"
+            BEGIN TRANSACTION;
+            CREATE $memory_record CONTENT {
+                content: $content,
+                metadata: $metadata,
+                embedding: $embedding,
+                checksum: $checksum,
+                importance: $importance,
+                owner: $owner,
+                shared_with: $shared_with,
+                created_at: type::datetime($created_at),
+                version_count: 0
+            };
+            DELETE $archive_id;
+            COMMIT TRANSACTION;
+        "#;
+
+        self.client
+            .query(query)
+            .bind(("memory_record", RecordId::from(("memory", id))))
+            .bind(("archive_id", RecordId::from(("memory_archive", id))))
+            .bind(("content", memory.content.clone()))
+            .bind(("metadata", memory_metadata(&memory)))
+            .bind(("embedding", memory.embedding.clone()))
+            .bind((
+                "checksum",
+                memory_checksum(&memory.content, memory.embedding.as_deref()),
+            ))
+            .bind(("importance", None::<f32>))
+            .bind(("owner", RecordId::from(("user", "system"))))
+            .bind(("shared_with", None::<Vec<RecordId>>))
+            .bind(("created_at", memory.created_at.to_rfc3339()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to unarchive memory: {}", e)))?
+            .check()
+            .map_err(|e| StorageError::Query(format!("Failed to unarchive memory: {}", e)))?;
+
+        Ok(true)
+    }
+
+    async fn get_archived_memory(&self, id: &str) -> Result<Option<Memory>, StorageError> {
+        self.load_archived(id)
+            .await?
+            .map(SurrealArchivedMemory::into_memory)
+            .transpose()
+    }
+
+    async fn list_archived_memories(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Memory>, StorageError> {
+        let query = r#"
+            SELECT data, archived_at FROM memory_archive
+            ORDER BY archived_at DESC
+            LIMIT $limit START $offset
+        "#;
+
+        let mut result = self
+            .client
+            .query(query)
+            .bind(("limit", limit.unwrap_or(100)))
+            .bind(("offset", offset.unwrap_or(0)))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to list archived memories: {}", e)))?;
+        let records: Vec<SurrealArchivedMemory> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract archived memories: {}", e))
+        })?;
+
+        records
+            .into_iter()
+            .map(SurrealArchivedMemory::into_memory)
+            .collect()
+    }
+
+    async fn search_archived_memories(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchResult>, StorageError> {
+        let terms = query_terms(query);
+        if terms.is_empty() && query_embedding.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut result = self
+            .client
+            .query("SELECT data FROM memory_archive")
+            .await
+            .map_err(|e| {
+                StorageError::Query(format!("Failed to search archived memories: {}", e))
+            })?;
+        let records: Vec<SurrealArchivedMemory> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract archived memories: {}", e))
+        })?;
+        let query_embedding = query_embedding.map(|query| self.normalized_embedding(query));
+
+        let mut results = Vec::new();
+        for record in records {
+            let memory = match record.into_memory() {
+                Ok(memory) => memory,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable archived memory: {}", e);
+                    continue;
+                }
+            };
+
+            let text_score = term_score(&terms, &memory.content);
+            let vector_score = match (query_embedding.as_deref(), &memory.embedding) {
+                (Some(query), Some(embedding)) => {
+                    self.config.memory_space.metric.similarity(query, embedding)
+                }
+                _ => 0.0,
+            };
+            let score = text_score.max(vector_score);
+            if score > 0.0 {
+                results.push(SearchResult {
+                    memory,
+                    score: Some(score),
+                });
+            }
+        }
+
+        results.sort_by(|a, b| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)));
+        results.truncate(limit.unwrap_or(10));
+        Ok(results)
+    }
+
+    async fn archive_inactive_memories(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>, StorageError> {
+        let limit = limit.unwrap_or(self.config.archive.batch_size);
+        Self::archive_inactive(&self.client, cutoff, limit).await
+    }
+
+    async fn get_archive_stats(&self) -> Result<ArchiveStats, StorageError> {
+        #[derive(Deserialize)]
+        struct StatsRow {
+            archived_count: usize,
+            original_bytes: Option<usize>,
+            stored_bytes: Option<usize>,
+            oldest_archived_at: Option<DateTime<Utc>>,
+        }
+
+        let query = r#"
+            SELECT
+                count() AS archived_count,
+                math::sum(original_bytes) AS original_bytes,
+                math::sum(stored_bytes) AS stored_bytes,
+                time::min(archived_at) AS oldest_archived_at
+            FROM memory_archive
+            GROUP ALL
+        "#;
+
+        let mut result = self
+            .client
+            .query(query)
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get archive stats: {}", e)))?;
+        let rows: Vec<StatsRow> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract archive stats: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .next()
+            .map(|row| ArchiveStats {
+                archived_count: row.archived_count,
+                original_bytes: row.original_bytes.unwrap_or(0),
+                stored_bytes: row.stored_bytes.unwrap_or(0),
+                oldest_archived_at: row.oldest_archived_at,
+            })
+            .unwrap_or_default())
+    }
+}
+
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Fraction of query terms that occur in `content`
+fn term_score(terms: &[String], content: &str) -> f32 {
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let content_terms = query_terms(content);
+    let matched = terms
+        .iter()
+        .filter(|term| content_terms.contains(term))
+        .count();
+    matched as f32 / terms.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_term_score_counts_whole_terms() {
+        let terms = query_terms("Dragon mountain");
+        assert_eq!(
+            term_score(&terms, "The dragon sleeps beneath the mountain"),
+            1.0
+        );
+        assert_eq!(term_score(&terms, "A dragon-shaped kite"), 0.5);
+        assert_eq!(term_score(&terms, "Dragonfly"), 0.0);
+        assert_eq!(term_score(&[], "anything"), 0.0);
+    }
+}
@@ -4,12 +4,13 @@ use async_trait::async_trait;
 use std::sync::Arc;
 use std::time::Duration;
 use surrealdb::{Connection, RecordId, Surreal};
-use tokio::sync::Notify;
+use tokio::sync::{Notify, OnceCell, broadcast};
 
 use super::config::SharedStorageConfig;
 use super::intelligence::{
     IntelligentSearch, IntelligentSearchResult, QueryAnalysis, SearchIntelligence, SearchSuggestion,
 };
+use super::live_query::DbEvent;
 use super::version_access::VersionAccessTracker;
 use super::version_cache::VersionCache;
 use crate::hooks::HookRegistry;
@@ -17,6 +18,25 @@ use crate::storage::errors::StorageError;
 use crate::storage::lifecycle::{LifecycleUpdate, LifecycleUpdateQueue};
 use crate::storage::traits::BaseStore;
 
+/// Key part of a record ID as a plain string
+///
+/// `RecordId::key()` displays keys that are not plain identifiers (such as
+/// UUIDs) escaped as `⟨key⟩`; the brackets are removed here.
+pub(crate) fn record_key(id: &RecordId) -> String {
+    let key = id.key().to_string();
+    key.strip_prefix('⟨')
+        .and_then(|s| s.strip_suffix('⟩'))
+        .map(str::to_string)
+        .unwrap_or(key)
+}
+
+/// Lease on archiving inactive memories, so one process sharing the
+/// database does it
+const ARCHIVE_LEASE: &str = "memory-archive";
+
+/// Lease on pruning old versions
+const VERSION_RETENTION_LEASE: &str = "version-retention";
+
 /// Main shared storage manager
 #[derive(Debug)]
 pub struct SharedStorage<C>
@@ -31,6 +51,8 @@ where
     pub(crate) shutdown: Arc<Notify>,
     pub(crate) version_cache: VersionCache,
     pub(crate) version_access_tracker: VersionAccessTracker,
+    /// Change stream fed by live queries, started on first subscription
+    pub(crate) change_events: Arc<OnceCell<broadcast::Sender<DbEvent>>>,
 }
 
 impl<C> SharedStorage<C>
@@ -59,7 +81,7 @@ where
 
         // Initialize versioning cache and access tracker
         let version_cache = VersionCache::new(&config.versioning);
-        let version_access_tracker = VersionAccessTracker::new();
+        let version_access_tracker = VersionAccessTracker::with_clock(config.clock.clone());
 
         let storage = Self {
             client: client.clone(),
@@ -70,6 +92,7 @@ where
             shutdown: shutdown.clone(),
             version_cache,
             version_access_tracker,
+            change_events: Arc::new(OnceCell::new()),
         };
 
         // Initialize schema
@@ -142,12 +165,108 @@ where
             });
         }
 
+        // Start background archive task if automatic archiving is enabled
+        if config.archive.auto_archive {
+            let check_interval = Duration::from_secs(config.archive.check_interval_secs);
+            let archive_after = chrono::Duration::days(config.archive.archive_after_days as i64);
+            let batch_size = config.archive.batch_size;
+            let clock = config.clock.clone();
+            let client_clone = client.clone();
+            let shutdown_clone = shutdown.clone();
+
+            tokio::spawn(async move {
+                tracing::info!(
+                    "Archive task started (interval: {:?}, archive after: {} days)",
+                    check_interval,
+                    archive_after.num_days()
+                );
+
+                let mut interval = tokio::time::interval(check_interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+                let lease_ttl = crate::runtime::lease_ttl(check_interval);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {
+                            if !Self::hold_lease(&client_clone, ARCHIVE_LEASE, lease_ttl).await {
+                                continue;
+                            }
+                            let cutoff = clock.now() - archive_after;
+                            match Self::archive_inactive(&client_clone, cutoff, batch_size).await {
+                                Ok(archived) if !archived.is_empty() => {
+                                    tracing::info!("Archived {} inactive memories", archived.len());
+                                }
+                                Ok(_) => {}
+                                Err(e) => tracing::error!("Failed to archive inactive memories: {}", e),
+                            }
+                        }
+                        _ = shutdown_clone.notified() => break,
+                    }
+                }
+
+                tracing::info!("Archive task stopped");
+            });
+        }
+
+        // Start background version retention task if auto-compaction is enabled
+        if config.versioning.retention.auto_compact {
+            let retention = config.versioning.retention.clone();
+            let check_interval = Duration::from_secs(retention.check_interval_secs);
+            let client_clone = client.clone();
+            let shutdown_clone = shutdown.clone();
+
+            tokio::spawn(async move {
+                tracing::info!(
+                    "Version retention task started (interval: {:?}, keep last: {})",
+                    check_interval,
+                    retention.keep_last
+                );
+
+                let mut interval = tokio::time::interval(check_interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+                let lease_ttl = crate::runtime::lease_ttl(check_interval);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {
+                            if !Self::hold_lease(&client_clone, VERSION_RETENTION_LEASE, lease_ttl).await {
+                                continue;
+                            }
+                            match Self::prune_versions(&client_clone, &retention).await {
+                                Ok(pruned) if pruned > 0 => {
+                                    tracing::info!("Pruned {} old versions", pruned);
+                                }
+                                Ok(_) => {}
+                                Err(e) => tracing::error!("Failed to prune old versions: {}", e),
+                            }
+                        }
+                        _ = shutdown_clone.notified() => break,
+                    }
+                }
+
+                tracing::info!("Version retention task stopped");
+            });
+        }
+
         Ok(storage)
     }
 
+    /// Take or renew a background task's lease for this process; false if
+    /// another process holds it
+    async fn hold_lease(client: &Surreal<C>, name: &str, ttl: Duration) -> bool {
+        match super::lease::acquire_lease(client, name, crate::runtime::lease_holder(), ttl).await {
+            Ok(granted) => granted,
+            Err(e) => {
+                tracing::debug!("Failed to acquire lease '{}': {}", name, e);
+                false
+            }
+        }
+    }
+
     /// Initialize the database schema with all required tables
     async fn initialize_schema(&self) -> Result<(), StorageError> {
-        super::schema::initialize_schema(&self.client).await
+        super::schema::initialize_schema(&self.client).await?;
+        super::schema::define_property_indexes(&self.client, &self.config.indexed_properties).await
     }
 
     /// Get the underlying client for advanced operations
@@ -165,6 +284,11 @@ where
         self.hook_registry.clone()
     }
 
+    /// Drop reconstructed memory versions; they are rebuilt on next access
+    pub async fn clear_version_cache(&self) {
+        self.version_cache.clear().await;
+    }
+
     /// Gracefully shutdown the storage, flushing any pending updates
     pub async fn shutdown(&self) -> Result<(), StorageError> {
         tracing::info!("Initiating graceful shutdown");
@@ -357,10 +481,14 @@ where
         // Clear all data from tables
         let queries = [
             "DELETE FROM memory",
+            "DELETE FROM memory_archive",
+            "DELETE FROM memory_alias",
             "DELETE FROM vector",
             "DELETE FROM entity",
             "DELETE FROM relationship",
             "DELETE FROM message",
+            "DELETE FROM outbox",
+            "DELETE FROM record_version",
         ];
 
         for query in queries {
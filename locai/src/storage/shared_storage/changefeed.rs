@@ -0,0 +1,239 @@
+//! Changefeed reads for SharedStorage
+//!
+//! The memory, entity and relationship tables are defined with a SurrealDB
+//! `CHANGEFEED`, so every committed write is kept for the retention period
+//! and can be read back with `SHOW CHANGES`. Unlike live queries, nothing is
+//! lost while no one is listening, so consumers can poll and resume from a
+//! cursor.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use surrealdb::opt::Config;
+use surrealdb::{Connection, RecordId, Surreal};
+
+use super::base::record_key;
+use super::entity::SurrealEntity;
+use super::memory::SurrealMemory;
+use super::relationship::SurrealRelationship;
+use crate::models::Memory;
+use crate::replication::{ChangeOp, RecordKind};
+use crate::storage::errors::StorageError;
+use crate::storage::models::{ChangeFeedEntry, ChangeFeedPage, Entity, Relationship};
+
+/// SurrealDB reports versionstamps shifted left by this many bits, while
+/// `SHOW CHANGES ... SINCE` takes the unshifted sequence number
+const VERSIONSTAMP_SHIFT: u32 = 16;
+
+/// How often embedded engines prune expired changefeed entries
+///
+/// The pruning transaction conflicts with writes to changefeed tables that
+/// commit at the same moment, failing one of them. SurrealDB prunes every ten
+/// seconds by default; with retention measured in days, hourly is plenty and
+/// makes such conflicts far rarer.
+pub const CHANGEFEED_GC_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Engine configuration for embedded (memory and RocksDB) clients
+pub fn embedded_engine_config() -> Config {
+    Config::new().changefeed_gc_interval(CHANGEFEED_GC_INTERVAL)
+}
+
+/// One transaction's changes to a table, as returned by `SHOW CHANGES`
+#[derive(Debug, Deserialize)]
+struct ChangeSet<R> {
+    versionstamp: u64,
+    changes: Vec<TableChange<R>>,
+}
+
+/// A single change; schema changes such as `define_table` carry neither field
+#[derive(Debug, Deserialize)]
+struct TableChange<R> {
+    update: Option<R>,
+    delete: Option<DeletedRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeletedRecord {
+    id: RecordId,
+}
+
+/// Raw records that know when they were last written
+trait Written {
+    fn written_at(&self) -> DateTime<Utc>;
+}
+
+impl Written for SurrealMemory {
+    fn written_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+impl Written for SurrealEntity {
+    fn written_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+impl Written for SurrealRelationship {
+    fn written_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+/// Changes from one table, grouped by transaction
+struct TableChanges {
+    groups: Vec<(u64, Vec<ChangeFeedEntry>)>,
+    /// Cursor of the last change set read, including schema-only ones
+    last: Option<u64>,
+    /// Whether the read stopped at the limit, so later changes may exist
+    truncated: bool,
+}
+
+/// Read committed changes after `since` from all three tables, merged in
+/// commit order
+///
+/// Changes from one transaction are never split across pages, so a page can
+/// hold a few more than `limit` entries.
+pub(crate) async fn read_changes<C>(
+    client: &Surreal<C>,
+    since: u64,
+    limit: usize,
+) -> Result<ChangeFeedPage, StorageError>
+where
+    C: Connection,
+{
+    let limit = limit.max(1);
+    let tables = [
+        read_table::<C, SurrealMemory, Memory>(client, "memory", RecordKind::Memory, since, limit)
+            .await?,
+        read_table::<C, SurrealEntity, Entity>(client, "entity", RecordKind::Entity, since, limit)
+            .await?,
+        read_table::<C, SurrealRelationship, Relationship>(
+            client,
+            "relationship",
+            RecordKind::Relationship,
+            since,
+            limit,
+        )
+        .await?,
+    ];
+
+    // A table cut off at the limit may have more changes past its last one,
+    // so nothing later than that can be returned yet. Otherwise everything up
+    // to the last change set read has been seen, even sets with no entries.
+    let scanned = tables
+        .iter()
+        .filter(|table| table.truncated)
+        .filter_map(|table| table.last)
+        .min()
+        .or_else(|| tables.iter().filter_map(|table| table.last).max())
+        .unwrap_or(since)
+        .max(since);
+
+    let mut groups: Vec<(u64, Vec<ChangeFeedEntry>)> = tables
+        .into_iter()
+        .flat_map(|table| table.groups)
+        .filter(|(cursor, _)| *cursor <= scanned)
+        .collect();
+    groups.sort_by_key(|(cursor, _)| *cursor);
+
+    let mut page = ChangeFeedPage {
+        changes: Vec::new(),
+        cursor: scanned,
+    };
+    let mut last_included = since;
+    for (cursor, entries) in groups {
+        if page.changes.len() >= limit && cursor != last_included {
+            // Stopped early: resume right after the last transaction returned
+            page.cursor = last_included;
+            break;
+        }
+        last_included = cursor;
+        page.changes.extend(entries);
+    }
+    Ok(page)
+}
+
+async fn read_table<C, R, M>(
+    client: &Surreal<C>,
+    table: &str,
+    kind: RecordKind,
+    since: u64,
+    limit: usize,
+) -> Result<TableChanges, StorageError>
+where
+    C: Connection,
+    R: DeserializeOwned + Written,
+    M: From<R> + Serialize,
+{
+    // SINCE is inclusive; both values are plain integers, not user input
+    let query = format!(
+        "SHOW CHANGES FOR TABLE {} SINCE {} LIMIT {}",
+        table,
+        since + 1,
+        limit
+    );
+    let mut result = client
+        .query(query)
+        .await
+        .map_err(|e| StorageError::Query(format!("Failed to read {} changefeed: {}", table, e)))?;
+    let sets: Vec<ChangeSet<R>> = result.take(0).map_err(|e| {
+        StorageError::Query(format!("Failed to decode {} changefeed: {}", table, e))
+    })?;
+
+    let truncated = sets.len() >= limit;
+    let last = sets
+        .last()
+        .map(|set| set.versionstamp >> VERSIONSTAMP_SHIFT);
+    let mut groups = Vec::with_capacity(sets.len());
+    for set in sets {
+        let cursor = set.versionstamp >> VERSIONSTAMP_SHIFT;
+        let mut entries = Vec::new();
+        for change in set.changes {
+            let entry = match (change.update, change.delete) {
+                (Some(record), _) => {
+                    let changed_at = record.written_at();
+                    let record = serde_json::to_value(M::from(record)).map_err(|e| {
+                        StorageError::Serialization(format!(
+                            "Failed to serialize {} change: {}",
+                            table, e
+                        ))
+                    })?;
+                    ChangeFeedEntry {
+                        cursor,
+                        kind,
+                        id: record
+                            .get("id")
+                            .and_then(|id| id.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        op: ChangeOp::Upsert,
+                        record: Some(record),
+                        changed_at: Some(changed_at),
+                    }
+                }
+                (None, Some(deleted)) => ChangeFeedEntry {
+                    cursor,
+                    kind,
+                    id: record_key(&deleted.id),
+                    op: ChangeOp::Delete,
+                    record: None,
+                    changed_at: None,
+                },
+                (None, None) => continue,
+            };
+            entries.push(entry);
+        }
+        if !entries.is_empty() {
+            groups.push((cursor, entries));
+        }
+    }
+
+    Ok(TableChanges {
+        groups,
+        last,
+        truncated,
+    })
+}
@@ -0,0 +1,258 @@
+//! Content checksums for SharedStorage
+//!
+//! Every memory write stores a SHA-256 checksum of the memory's content and
+//! embedding in a `checksum` field beside them, and every vector write one
+//! of the vector's values. Metadata is left out, since access statistics
+//! change on every read. Recomputing the checksums finds records whose data
+//! no longer matches what was written, whether from disk corruption or an
+//! edit that went around Locai.
+
+use async_trait::async_trait;
+use ring::digest;
+use serde::Deserialize;
+use surrealdb::{Connection, RecordId};
+
+use super::base::SharedStorage;
+use crate::storage::errors::StorageError;
+use crate::storage::models::{ChecksumIssue, ChecksumIssueType, ChecksumReport, ChecksummedRecord};
+use crate::storage::traits::ChecksumStore;
+
+/// Records read per query while scanning
+const SCAN_PAGE_SIZE: usize = 500;
+
+/// Checksum of a memory's content and embedding
+pub(crate) fn memory_checksum(content: &str, embedding: Option<&[f32]>) -> String {
+    // Length-prefix each part so no two inputs share an encoding
+    let mut message = Vec::new();
+    message.extend_from_slice(&(content.len() as u64).to_be_bytes());
+    message.extend_from_slice(content.as_bytes());
+    if let Some(embedding) = embedding {
+        message.extend_from_slice(&(embedding.len() as u64).to_be_bytes());
+        message.extend(embedding.iter().flat_map(|x| x.to_le_bytes()));
+    }
+    sha256_hex(&message)
+}
+
+/// Checksum of a vector's values
+pub(crate) fn vector_checksum(vector: &[f32]) -> String {
+    let message: Vec<u8> = vector.iter().flat_map(|x| x.to_le_bytes()).collect();
+    sha256_hex(&message)
+}
+
+fn sha256_hex(message: &[u8]) -> String {
+    digest::digest(&digest::SHA256, message)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredMemory {
+    id: RecordId,
+    content: String,
+    embedding: Option<Vec<f32>>,
+    checksum: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredVector {
+    id: RecordId,
+    vector: Vec<f32>,
+    dimension: usize,
+    checksum: Option<String>,
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// One page of a table's records, in ID order
+    async fn scan_page<T: serde::de::DeserializeOwned>(
+        &self,
+        fields: &str,
+        table: &str,
+        start: usize,
+    ) -> Result<Vec<T>, StorageError> {
+        let query = format!(
+            "SELECT {} FROM type::table($table) ORDER BY id LIMIT $limit START $start",
+            fields
+        );
+        self.client
+            .query(query)
+            .bind(("table", table.to_string()))
+            .bind(("limit", SCAN_PAGE_SIZE))
+            .bind(("start", start))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to scan {}: {}", table, e)))?
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to read {}: {}", table, e)))
+    }
+
+    async fn set_checksum(&self, id: RecordId, checksum: String) -> Result<(), StorageError> {
+        self.client
+            .query("UPDATE $id SET checksum = $checksum")
+            .bind(("id", id))
+            .bind(("checksum", checksum))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to store checksum: {}", e)))?
+            .check()
+            .map_err(|e| StorageError::Query(format!("Failed to store checksum: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<C> ChecksumStore for SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    async fn verify_checksums(&self) -> Result<ChecksumReport, StorageError> {
+        let mut report = ChecksumReport::default();
+
+        let mut start = 0;
+        loop {
+            let memories: Vec<StoredMemory> = self
+                .scan_page("id, content, embedding, checksum", "memory", start)
+                .await?;
+            for memory in &memories {
+                report.memories_checked += 1;
+                let Some(expected) = &memory.checksum else {
+                    report.unchecksummed += 1;
+                    continue;
+                };
+                let actual = memory_checksum(&memory.content, memory.embedding.as_deref());
+                if actual != *expected {
+                    report.issues.push(ChecksumIssue {
+                        record: ChecksummedRecord::Memory,
+                        id: memory.id.key().to_string(),
+                        issue_type: ChecksumIssueType::Mismatch,
+                        expected: Some(expected.clone()),
+                        description: "Content or embedding no longer matches its checksum"
+                            .to_string(),
+                    });
+                }
+            }
+            if memories.len() < SCAN_PAGE_SIZE {
+                break;
+            }
+            start += memories.len();
+        }
+
+        let mut start = 0;
+        loop {
+            let vectors: Vec<StoredVector> = self
+                .scan_page("id, vector, dimension, checksum", "vector", start)
+                .await?;
+            for vector in &vectors {
+                report.vectors_checked += 1;
+                let id = vector.id.key().to_string();
+                if vector.vector.len() != vector.dimension {
+                    report.issues.push(ChecksumIssue {
+                        record: ChecksummedRecord::Vector,
+                        id: id.clone(),
+                        issue_type: ChecksumIssueType::DimensionMismatch,
+                        expected: vector.checksum.clone(),
+                        description: format!(
+                            "Has {} values but dimension {}",
+                            vector.vector.len(),
+                            vector.dimension
+                        ),
+                    });
+                    continue;
+                }
+                let Some(expected) = &vector.checksum else {
+                    report.unchecksummed += 1;
+                    continue;
+                };
+                if vector_checksum(&vector.vector) != *expected {
+                    report.issues.push(ChecksumIssue {
+                        record: ChecksummedRecord::Vector,
+                        id,
+                        issue_type: ChecksumIssueType::Mismatch,
+                        expected: Some(expected.clone()),
+                        description: "Values no longer match their checksum".to_string(),
+                    });
+                }
+            }
+            if vectors.len() < SCAN_PAGE_SIZE {
+                break;
+            }
+            start += vectors.len();
+        }
+
+        Ok(report)
+    }
+
+    async fn backfill_checksums(&self) -> Result<usize, StorageError> {
+        let mut stamped = 0;
+
+        let mut start = 0;
+        loop {
+            let memories: Vec<StoredMemory> = self
+                .scan_page("id, content, embedding, checksum", "memory", start)
+                .await?;
+            for memory in &memories {
+                if memory.checksum.is_none() {
+                    let checksum = memory_checksum(&memory.content, memory.embedding.as_deref());
+                    self.set_checksum(memory.id.clone(), checksum).await?;
+                    stamped += 1;
+                }
+            }
+            if memories.len() < SCAN_PAGE_SIZE {
+                break;
+            }
+            start += memories.len();
+        }
+
+        let mut start = 0;
+        loop {
+            let vectors: Vec<StoredVector> = self
+                .scan_page("id, vector, dimension, checksum", "vector", start)
+                .await?;
+            for vector in &vectors {
+                if vector.checksum.is_none() && vector.vector.len() == vector.dimension {
+                    self.set_checksum(vector.id.clone(), vector_checksum(&vector.vector))
+                        .await?;
+                    stamped += 1;
+                }
+            }
+            if vectors.len() < SCAN_PAGE_SIZE {
+                break;
+            }
+            start += vectors.len();
+        }
+
+        Ok(stamped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_checksum_covers_content_and_embedding() {
+        let base = memory_checksum("Deploys run on Tuesday", Some(&[0.5, 0.25]));
+        assert_eq!(base.len(), 64);
+        assert_eq!(
+            base,
+            memory_checksum("Deploys run on Tuesday", Some(&[0.5, 0.25]))
+        );
+        assert_ne!(
+            base,
+            memory_checksum("Deploys run on Wednesday", Some(&[0.5, 0.25]))
+        );
+        assert_ne!(
+            base,
+            memory_checksum("Deploys run on Tuesday", Some(&[0.5, 0.125]))
+        );
+        assert_ne!(base, memory_checksum("Deploys run on Tuesday", None));
+    }
+
+    #[test]
+    fn test_vector_checksum() {
+        assert_eq!(vector_checksum(&[1.0, 2.0]), vector_checksum(&[1.0, 2.0]));
+        assert_ne!(vector_checksum(&[1.0, 2.0]), vector_checksum(&[2.0, 1.0]));
+    }
+}
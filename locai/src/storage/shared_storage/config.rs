@@ -1,6 +1,8 @@
 //! Configuration for shared storage
 
-use crate::config::{LifecycleTrackingConfig, VersioningConfig};
+use crate::clock::ClockHandle;
+use crate::config::{ArchiveConfig, LifecycleTrackingConfig, VectorSpaceConfig, VersioningConfig};
+use crate::ids::IdStrategy;
 
 /// Configuration for the shared storage
 #[derive(Debug, Clone)]
@@ -9,6 +11,18 @@ pub struct SharedStorageConfig {
     pub database: String,
     pub lifecycle_tracking: LifecycleTrackingConfig,
     pub versioning: VersioningConfig,
+    pub archive: ArchiveConfig,
+    /// Memory properties (under `metadata.properties`) to define indexes on
+    pub indexed_properties: Vec<String>,
+    /// Time source for versions, decay scoring and retention
+    pub clock: ClockHandle,
+    /// How new memory IDs are chosen; unless random, created memories keep
+    /// the ID they were built with instead of one the database assigns
+    pub id_strategy: IdStrategy,
+    /// How memory embeddings are compared and whether they're normalized
+    pub memory_space: VectorSpaceConfig,
+    /// How vector store vectors are compared and whether they're normalized
+    pub vector_space: VectorSpaceConfig,
 }
 
 impl Default for SharedStorageConfig {
@@ -18,6 +32,12 @@ impl Default for SharedStorageConfig {
             database: "main".to_string(),
             lifecycle_tracking: LifecycleTrackingConfig::default(),
             versioning: VersioningConfig::default(),
+            archive: ArchiveConfig::default(),
+            indexed_properties: Vec::new(),
+            clock: ClockHandle::default(),
+            id_strategy: IdStrategy::default(),
+            memory_space: VectorSpaceConfig::default(),
+            vector_space: VectorSpaceConfig::default(),
         }
     }
 }
@@ -5,21 +5,23 @@ use chrono::{DateTime, Utc};
 use serde_json::Value;
 use surrealdb::{Connection, RecordId};
 
-use super::base::SharedStorage;
+use super::base::{SharedStorage, record_key};
+use super::memory::bounds_conditions;
+use crate::replication::RecordKind;
 use crate::storage::errors::StorageError;
 use crate::storage::filters::EntityFilter;
-use crate::storage::models::Entity;
+use crate::storage::models::{Entity, EntitySplit, RecordOperation};
 use crate::storage::traits::EntityStore;
 
 /// Internal representation of an Entity record for SurrealDB
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-struct SurrealEntity {
+pub(super) struct SurrealEntity {
     id: RecordId,
     entity_type: String,
     properties: Value,
     owner: RecordId,
     created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+    pub(super) updated_at: DateTime<Utc>,
 }
 
 /// Struct for creating entities (without generated fields)
@@ -45,20 +47,8 @@ impl From<Entity> for SurrealEntity {
 
 impl From<SurrealEntity> for Entity {
     fn from(surreal_entity: SurrealEntity) -> Self {
-        // Extract the key string from RecordId
-        // RecordId::key() returns a RecordId, and to_string() includes brackets ⟨⟩
-        // We need to extract just the key part without brackets
-        let key_record = surreal_entity.id.key();
-        let key_string = key_record.to_string();
-        // Remove angle brackets if present: ⟨key⟩ -> key
-        let clean_id = key_string
-            .strip_prefix('⟨')
-            .and_then(|s| s.strip_suffix('⟩'))
-            .unwrap_or(&key_string)
-            .to_string();
-
         Self {
-            id: clean_id,
+            id: record_key(&surreal_entity.id),
             entity_type: surreal_entity.entity_type,
             properties: surreal_entity.properties,
             created_at: surreal_entity.created_at,
@@ -99,9 +89,19 @@ where
                 .map_err(|e| StorageError::Query(format!("Failed to create entity: {}", e)))?
         };
 
-        created
+        let created = created
             .map(Entity::from)
-            .ok_or_else(|| StorageError::Internal("No entity created".to_string()))
+            .ok_or_else(|| StorageError::Internal("No entity created".to_string()))?;
+
+        self.refresh_entity_aliases(&created.id).await;
+        self.record_version(
+            RecordKind::Entity,
+            &created.id,
+            RecordOperation::Created,
+            Some(&created),
+        )
+        .await;
+        Ok(created)
     }
 
     /// Get an entity by its ID
@@ -140,9 +140,56 @@ where
             .take(0)
             .map_err(|e| StorageError::Query(format!("Failed to extract updated entity: {}", e)))?;
 
-        updated.map(Entity::from).ok_or_else(|| {
+        let updated = updated.map(Entity::from).ok_or_else(|| {
             StorageError::NotFound(format!("Entity with id {} not found", entity.id))
-        })
+        })?;
+
+        self.refresh_entity_aliases(&updated.id).await;
+        self.record_version(
+            RecordKind::Entity,
+            &updated.id,
+            RecordOperation::Updated,
+            Some(&updated),
+        )
+        .await;
+        Ok(updated)
+    }
+
+    async fn upsert_entity(&self, entity: Entity) -> Result<Entity, StorageError> {
+        self.ensure_system_user().await?;
+        let operation = self.upsert_operation(RecordKind::Entity, &entity.id).await;
+
+        let query = r#"
+            UPSERT $record_id SET
+                entity_type = $entity_type,
+                properties = $properties,
+                owner = $owner,
+                created_at = type::datetime($created_at)
+        "#;
+
+        let mut response = self
+            .client
+            .query(query)
+            .bind(("record_id", RecordId::from(("entity", entity.id.as_str()))))
+            .bind(("entity_type", entity.entity_type))
+            .bind(("properties", entity.properties))
+            .bind(("owner", RecordId::from(("user", "system"))))
+            .bind(("created_at", entity.created_at.to_rfc3339()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to upsert entity: {}", e)))?;
+
+        let upserted: Option<SurrealEntity> = response.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract upserted entity: {}", e))
+        })?;
+
+        let upserted = upserted
+            .map(Entity::from)
+            .ok_or_else(|| StorageError::Internal("No entity upserted".to_string()))?;
+
+        self.refresh_entity_aliases(&upserted.id).await;
+        self.record_version(RecordKind::Entity, &upserted.id, operation, Some(&upserted))
+            .await;
+        Ok(upserted)
     }
 
     /// Delete an entity by its ID
@@ -155,6 +202,11 @@ where
             .await
             .map_err(|e| StorageError::Query(format!("Failed to delete entity: {}", e)))?;
 
+        if deleted.is_some() {
+            self.refresh_entity_aliases(id).await;
+            self.record_version::<Entity>(RecordKind::Entity, id, RecordOperation::Deleted, None)
+                .await;
+        }
         Ok(deleted.is_some())
     }
 
@@ -232,6 +284,11 @@ where
                     }
                 }
             }
+
+            // Narrow to a box around the radius; distances are checked after the query
+            if let Some(near) = &f.near {
+                conditions.extend(bounds_conditions(&near.bounds(), "properties.location"));
+            }
         }
 
         if !conditions.is_empty() {
@@ -241,12 +298,16 @@ where
 
         query.push_str(" ORDER BY created_at DESC");
 
-        if let Some(limit) = limit {
-            query.push_str(&format!(" LIMIT {}", limit));
-        }
+        // Distances are checked after the query, so page the results afterwards
+        let near = filter.as_ref().and_then(|f| f.near);
+        if near.is_none() {
+            if let Some(limit) = limit {
+                query.push_str(&format!(" LIMIT {}", limit));
+            }
 
-        if let Some(offset) = offset {
-            query.push_str(&format!(" START {}", offset));
+            if let Some(offset) = offset {
+                query.push_str(&format!(" START {}", offset));
+            }
         }
 
         let mut result = self
@@ -259,7 +320,19 @@ where
             .take(0)
             .map_err(|e| StorageError::Query(format!("Failed to extract entities: {}", e)))?;
 
-        Ok(entities.into_iter().map(Entity::from).collect())
+        let entities = entities.into_iter().map(Entity::from);
+        Ok(match near {
+            Some(near) => entities
+                .filter(|entity| {
+                    entity
+                        .location()
+                        .is_some_and(|location| near.contains(&location))
+                })
+                .skip(offset.unwrap_or(0))
+                .take(limit.unwrap_or(usize::MAX))
+                .collect(),
+            None => entities.collect(),
+        })
     }
 
     /// Count entities with optional filtering
@@ -268,4 +341,20 @@ where
         let entities = self.list_entities(filter, None, None).await?;
         Ok(entities.len())
     }
+
+    async fn merge_entities(
+        &self,
+        target_id: &str,
+        source_ids: &[String],
+    ) -> Result<Entity, StorageError> {
+        self.merge_entity_records(target_id, source_ids).await
+    }
+
+    async fn split_entity(
+        &self,
+        entity_id: &str,
+        split: EntitySplit,
+    ) -> Result<Entity, StorageError> {
+        self.split_entity_record(entity_id, split).await
+    }
 }
@@ -0,0 +1,440 @@
+//! Entity merge and split for SharedStorage
+//!
+//! Merging moves every relationship and memory link of the source entities
+//! onto the target, folds their properties and names into it and deletes
+//! them. Splitting is the reverse for a chosen part of an entity: a new entity
+//! takes over the listed relationships and memory links. Both are recorded in
+//! the entities' properties: `merged_from` on a merge target keeps each source
+//! as it was, and `split_from`/`split_into` connect the halves of a split.
+
+use std::collections::HashSet;
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use surrealdb::{Connection, RecordId};
+
+use super::aliases::{canonical_name, entity_names};
+use super::base::{SharedStorage, record_key};
+use super::relationship::SurrealRelationship;
+use crate::replication::RecordKind;
+use crate::storage::errors::StorageError;
+use crate::storage::models::{Entity, EntitySplit, RecordOperation, Relationship};
+use crate::storage::traits::EntityStore;
+
+/// Relationship types that link a memory to an entity
+const MEMORY_LINK_TYPES: &[&str] = &["contains", "mentions", "references_entity", "has_entity"];
+
+/// Properties holding merge and split history, which are never merged
+const HISTORY_PROPERTIES: &[&str] = &["merged_from", "split_from", "split_into"];
+
+/// The parts of a `relates` edge kept when it moves
+#[derive(Debug, Deserialize)]
+struct RelatesEdge {
+    properties: Option<Value>,
+    confidence: Option<f64>,
+}
+
+/// Links of an entity to move to another
+enum LinkSelection<'a> {
+    All,
+    Only {
+        relationship_ids: &'a [String],
+        memory_ids: &'a [String],
+    },
+}
+
+impl LinkSelection<'_> {
+    fn relationship(&self, id: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only {
+                relationship_ids, ..
+            } => relationship_ids.iter().any(|selected| selected == id),
+        }
+    }
+
+    fn memory(&self, id: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only { memory_ids, .. } => memory_ids.iter().any(|selected| selected == id),
+        }
+    }
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    pub(super) async fn merge_entity_records(
+        &self,
+        target_id: &str,
+        source_ids: &[String],
+    ) -> Result<Entity, StorageError> {
+        let mut target = self.require_entity(target_id).await?;
+
+        let mut sources: Vec<Entity> = Vec::new();
+        for source_id in source_ids {
+            if source_id == target_id {
+                return Err(StorageError::Validation(format!(
+                    "Cannot merge entity {} into itself",
+                    source_id
+                )));
+            }
+            if !sources.iter().any(|source| &source.id == source_id) {
+                sources.push(self.require_entity(source_id).await?);
+            }
+        }
+
+        let merged_at = Utc::now().to_rfc3339();
+        let mut properties = into_object(target.properties);
+        for source in &sources {
+            self.move_entity_links(&source.id, target_id, &LinkSelection::All)
+                .await?;
+
+            if let Value::Object(other) = &source.properties {
+                for (key, value) in other {
+                    if !HISTORY_PROPERTIES.contains(&key.as_str()) {
+                        properties
+                            .entry(key.clone())
+                            .or_insert_with(|| value.clone());
+                    }
+                }
+            }
+            add_aliases(&mut properties, entity_names(source));
+            push_history(
+                &mut properties,
+                "merged_from",
+                json!({
+                    "id": source.id,
+                    "entity_type": source.entity_type,
+                    "properties": source.properties,
+                    "merged_at": merged_at,
+                }),
+            );
+        }
+        target.properties = Value::Object(properties);
+        if let Some(name) = canonical_name(&target) {
+            let name = name.to_string();
+            remove_aliases(&mut target.properties, &[name]);
+        }
+
+        let merged = self.update_entity(target).await?;
+        for source in &sources {
+            self.delete_entity(&source.id).await?;
+        }
+        Ok(merged)
+    }
+
+    pub(super) async fn split_entity_record(
+        &self,
+        entity_id: &str,
+        split: EntitySplit,
+    ) -> Result<Entity, StorageError> {
+        let mut original = self.require_entity(entity_id).await?;
+        if split.entity.id == entity_id {
+            return Err(StorageError::Validation(format!(
+                "Split of entity {} needs a new ID",
+                entity_id
+            )));
+        }
+
+        let split_at = Utc::now().to_rfc3339();
+        let mut entity = split.entity;
+        let mut properties = into_object(entity.properties);
+        properties.insert(
+            "split_from".to_string(),
+            json!({ "id": entity_id, "split_at": split_at }),
+        );
+        entity.properties = Value::Object(properties);
+        let created = self.create_entity(entity).await?;
+
+        let selection = LinkSelection::Only {
+            relationship_ids: &split.relationship_ids,
+            memory_ids: &split.memory_ids,
+        };
+        self.move_entity_links(entity_id, &created.id, &selection)
+            .await?;
+
+        // Names that now belong to the new entity stop being aliases of the old one
+        let mut properties = into_object(original.properties);
+        push_history(
+            &mut properties,
+            "split_into",
+            json!({ "id": created.id, "split_at": split_at }),
+        );
+        original.properties = Value::Object(properties);
+        remove_aliases(&mut original.properties, &entity_names(&created));
+        self.update_entity(original).await?;
+
+        Ok(created)
+    }
+
+    async fn require_entity(&self, id: &str) -> Result<Entity, StorageError> {
+        self.get_entity(id)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("Entity with id {} not found", id)))
+    }
+
+    /// Point the selected relationships and memory links of `from` at `to`
+    ///
+    /// Relationships that would link `to` to itself are dropped, as are
+    /// duplicates of ones `to` already has.
+    async fn move_entity_links(
+        &self,
+        from: &str,
+        to: &str,
+        selection: &LinkSelection<'_>,
+    ) -> Result<(), StorageError> {
+        let mut result = self
+            .client
+            .query("SELECT * FROM relationship WHERE source_id = $to OR target_id = $to")
+            .query("SELECT * FROM relationship WHERE source_id = $from OR target_id = $from")
+            .bind(("to", to.to_string()))
+            .bind(("from", from.to_string()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to read relationships: {}", e)))?;
+        let existing: Vec<SurrealRelationship> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract relationships: {}", e)))?;
+        let moving: Vec<SurrealRelationship> = result
+            .take(1)
+            .map_err(|e| StorageError::Query(format!("Failed to extract relationships: {}", e)))?;
+
+        let mut known: HashSet<(String, String, String)> = existing
+            .into_iter()
+            .map(Relationship::from)
+            .map(|r| (r.relationship_type, r.source_id, r.target_id))
+            .collect();
+        let mut memories = HashSet::new();
+
+        for relationship in moving.into_iter().map(Relationship::from) {
+            let memory = memory_end(&relationship, from);
+            let selected = selection.relationship(&relationship.id)
+                || memory.is_some_and(|memory| selection.memory(memory));
+            if !selected {
+                continue;
+            }
+            if let Some(memory) = memory {
+                memories.insert(memory.to_string());
+            }
+
+            let swap = |id: &str| if id == from { to } else { id }.to_string();
+            let source_id = swap(&relationship.source_id);
+            let target_id = swap(&relationship.target_id);
+            let keep = source_id != target_id
+                && known.insert((
+                    relationship.relationship_type.clone(),
+                    source_id.clone(),
+                    target_id.clone(),
+                ));
+            self.move_relationship(&relationship, keep.then_some((source_id, target_id)))
+                .await?;
+        }
+
+        // Memory links are also `contains` edges, which carry no relationship ID
+        let mut result = self
+            .client
+            .query("SELECT VALUE in FROM contains WHERE out = $from")
+            .bind(("from", RecordId::from(("entity", from))))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to read memory links: {}", e)))?;
+        let linked: Vec<RecordId> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract memory links: {}", e)))?;
+        memories.extend(
+            linked
+                .iter()
+                .map(record_key)
+                .filter(|memory| selection.memory(memory)),
+        );
+
+        for memory_id in &memories {
+            self.client
+                .query(
+                    r#"
+                    LET $linked = (SELECT VALUE id FROM contains WHERE in = $memory AND out = $from);
+                    LET $already = (SELECT VALUE id FROM contains WHERE in = $memory AND out = $to);
+                    IF array::len($linked) > 0 AND array::len($already) = 0 {
+                        RELATE $memory->contains->$to CONTENT { confidence: 1.0 };
+                    };
+                    DELETE contains WHERE in = $memory AND out = $from;
+                    "#,
+                )
+                .bind(("memory", RecordId::from(("memory", memory_id.as_str()))))
+                .bind(("from", RecordId::from(("entity", from))))
+                .bind(("to", RecordId::from(("entity", to))))
+                .await
+                .map_err(|e| StorageError::Query(format!("Failed to move memory link: {}", e)))?;
+            self.refresh_memory_aliases(memory_id).await;
+        }
+        Ok(())
+    }
+
+    /// Give a relationship new endpoints, or delete it when `ends` is `None`,
+    /// moving its `relates` edge along with it
+    async fn move_relationship(
+        &self,
+        relationship: &Relationship,
+        ends: Option<(String, String)>,
+    ) -> Result<(), StorageError> {
+        let record = RecordId::from(("relationship", relationship.id.as_str()));
+        let (query, source_id, target_id) = match ends {
+            Some((source_id, target_id)) => (
+                "UPDATE $record SET source_id = $source_id, target_id = $target_id, \
+                 updated_at = time::now()",
+                source_id,
+                target_id,
+            ),
+            None => ("DELETE $record", String::new(), String::new()),
+        };
+
+        let mut response = self
+            .client
+            .query(
+                "SELECT properties, confidence FROM relates \
+                 WHERE in = type::thing('entity', $old_source) \
+                 AND out = type::thing('entity', $old_target) \
+                 AND relationship_type = $relationship_type",
+            )
+            .query(query)
+            .query(
+                "DELETE relates WHERE in = type::thing('entity', $old_source) \
+                 AND out = type::thing('entity', $old_target) \
+                 AND relationship_type = $relationship_type",
+            )
+            .bind(("record", record))
+            .bind(("source_id", source_id.clone()))
+            .bind(("target_id", target_id.clone()))
+            .bind(("old_source", relationship.source_id.clone()))
+            .bind(("old_target", relationship.target_id.clone()))
+            .bind(("relationship_type", relationship.relationship_type.clone()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to move relationship: {}", e)))?;
+        let edges: Vec<RelatesEdge> = response
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract relates edge: {}", e)))?;
+
+        if source_id.is_empty() {
+            self.record_version::<Relationship>(
+                RecordKind::Relationship,
+                &relationship.id,
+                RecordOperation::Deleted,
+                None,
+            )
+            .await;
+        } else {
+            let moved = Relationship {
+                source_id: source_id.clone(),
+                target_id: target_id.clone(),
+                updated_at: Utc::now(),
+                ..relationship.clone()
+            };
+            self.record_version(
+                RecordKind::Relationship,
+                &moved.id,
+                RecordOperation::Updated,
+                Some(&moved),
+            )
+            .await;
+        }
+
+        // Only entity-to-entity relationships have a relates edge to recreate
+        let Some(edge) = edges.into_iter().next() else {
+            return Ok(());
+        };
+        if source_id.is_empty() {
+            return Ok(());
+        }
+        self.client
+            .query(
+                "RELATE $source->relates->$target CONTENT { \
+                 relationship_type: $relationship_type, \
+                 properties: $properties, \
+                 confidence: $confidence }",
+            )
+            .bind(("source", RecordId::from(("entity", source_id.as_str()))))
+            .bind(("target", RecordId::from(("entity", target_id.as_str()))))
+            .bind(("relationship_type", relationship.relationship_type.clone()))
+            .bind(("properties", edge.properties.unwrap_or_else(|| json!({}))))
+            .bind(("confidence", edge.confidence.unwrap_or(1.0)))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to move relates edge: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// The memory at the other end of a memory-to-entity link to `entity_id`
+fn memory_end<'a>(relationship: &'a Relationship, entity_id: &str) -> Option<&'a str> {
+    (MEMORY_LINK_TYPES.contains(&relationship.relationship_type.as_str())
+        && relationship.target_id == entity_id)
+        .then_some(relationship.source_id.as_str())
+}
+
+fn into_object(properties: Value) -> Map<String, Value> {
+    match properties {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Add `names` to the `aliases` property, ignoring case-insensitive repeats
+fn add_aliases(properties: &mut Map<String, Value>, names: Vec<String>) {
+    let mut aliases: Vec<String> = properties
+        .get("aliases")
+        .and_then(|aliases| aliases.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|alias| alias.as_str().map(str::to_string))
+        .collect();
+    for name in names {
+        if !aliases
+            .iter()
+            .any(|alias| alias.eq_ignore_ascii_case(&name))
+        {
+            aliases.push(name);
+        }
+    }
+    properties.insert("aliases".to_string(), json!(aliases));
+}
+
+fn remove_aliases(properties: &mut Value, names: &[String]) {
+    if let Some(aliases) = properties
+        .get_mut("aliases")
+        .and_then(|aliases| aliases.as_array_mut())
+    {
+        aliases.retain(|alias| {
+            alias
+                .as_str()
+                .is_none_or(|alias| !names.iter().any(|name| name.eq_ignore_ascii_case(alias)))
+        });
+    }
+}
+
+fn push_history(properties: &mut Map<String, Value>, key: &str, entry: Value) {
+    match properties
+        .get_mut(key)
+        .and_then(|entries| entries.as_array_mut())
+    {
+        Some(entries) => entries.push(entry),
+        None => {
+            properties.insert(key.to_string(), json!([entry]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases_skip_repeats() {
+        let mut properties = into_object(json!({ "name": "Robert", "aliases": ["Bob"] }));
+        add_aliases(&mut properties, vec!["bob".to_string(), "Rob".to_string()]);
+        assert_eq!(properties["aliases"], json!(["Bob", "Rob"]));
+
+        let mut properties = Value::Object(properties);
+        remove_aliases(&mut properties, &["ROB".to_string()]);
+        assert_eq!(properties["aliases"], json!(["Bob"]));
+    }
+}
@@ -4,10 +4,13 @@ use async_trait::async_trait;
 use std::collections::{HashSet, VecDeque};
 use surrealdb::{Connection, RecordId};
 
-use super::base::SharedStorage;
+use super::base::{SharedStorage, record_key};
+use super::relationship::SurrealRelationship;
 use crate::models::Memory;
 use crate::storage::errors::StorageError;
-use crate::storage::models::{Entity, MemoryGraph, MemoryPath, Relationship};
+use crate::storage::models::{
+    ChangeFeedPage, Entity, MemoryGraph, MemoryPath, PathConstraints, Relationship,
+};
 use crate::storage::traits::{
     BaseStore, EntityStore, GraphStore, GraphTraversal, MemoryStore, RelationshipStore,
 };
@@ -32,9 +35,30 @@ where
     async fn setup_live_queries(
         &self,
     ) -> Result<Option<Box<dyn std::any::Any + Send>>, StorageError> {
-        // For now, return None - live queries can be implemented later
-        // TODO: Implement live query manager similar to SurrealDB implementation
-        Ok(None)
+        let sender = self
+            .change_events
+            .get_or_try_init(|| {
+                super::live_query::start_change_stream(&self.client, self.shutdown.clone())
+            })
+            .await?;
+        Ok(Some(Box::new(sender.subscribe())))
+    }
+
+    async fn read_changes(&self, since: u64, limit: usize) -> Result<ChangeFeedPage, StorageError> {
+        super::changefeed::read_changes(&self.client, since, limit).await
+    }
+
+    async fn acquire_lease(
+        &self,
+        name: &str,
+        holder: &str,
+        ttl: std::time::Duration,
+    ) -> Result<bool, StorageError> {
+        super::lease::acquire_lease(&self.client, name, holder, ttl).await
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> Result<bool, StorageError> {
+        super::lease::release_lease(&self.client, name, holder).await
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -111,6 +135,16 @@ where
         Ok(paths)
     }
 
+    async fn find_weighted_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        constraints: &PathConstraints,
+    ) -> Result<Vec<MemoryPath>, StorageError> {
+        self.search_weighted_paths(from_id, to_id, constraints)
+            .await
+    }
+
     /// Find memories connected to a given memory by following specific relationship types
     ///
     /// This method traverses through entities filtered by relationship type:
@@ -236,7 +270,7 @@ where
 
         let mut entities = Vec::new();
         for entity_id in entity_ids {
-            if let Some(entity) = self.get_entity(&entity_id.key().to_string()).await? {
+            if let Some(entity) = self.get_entity(&record_key(&entity_id)).await? {
                 entities.push(entity);
             }
         }
@@ -270,7 +304,7 @@ where
 
         let mut memories = Vec::new();
         for memory_id in memory_ids {
-            if let Some(memory) = self.get_memory(&memory_id.key().to_string()).await? {
+            if let Some(memory) = self.get_memory(&record_key(&memory_id)).await? {
                 memories.push(memory);
             }
         }
@@ -278,14 +312,15 @@ where
         Ok(memories)
     }
 
-    /// Get all relationships for an entity using the relates edge table
+    /// Get all relationships between an entity and other entities
     async fn get_entity_relationships(
         &self,
         entity_id: &str,
     ) -> Result<Vec<Relationship>, StorageError> {
+        // The relates edges carry no relationship ID, so read the relationship table
         let query = r#"
-            SELECT * FROM relates 
-            WHERE in = type::thing("entity", $entity_id) OR out = type::thing("entity", $entity_id)
+            SELECT * FROM relationship
+            WHERE source_id = $entity_id OR target_id = $entity_id
         "#;
 
         let entity_id_owned = entity_id.to_string();
@@ -298,9 +333,43 @@ where
                 StorageError::Query(format!("Failed to get entity relationships: {}", e))
             })?;
 
-        let relationships: Vec<Relationship> = response
+        let surreal_relationships: Vec<SurrealRelationship> = response
             .take(0)
             .map_err(|e| StorageError::Query(format!("Failed to extract relationships: {}", e)))?;
+        let relationships: Vec<Relationship> = surreal_relationships
+            .into_iter()
+            .map(Relationship::from)
+            .collect();
+
+        if relationships.is_empty() {
+            return Ok(relationships);
+        }
+
+        // Skip memory "mentions" links; only entity-to-entity relationships
+        // count here, so look up which other ends are entities in one query
+        let other_ids: HashSet<&str> = relationships
+            .iter()
+            .map(|relationship| other_endpoint(relationship, entity_id))
+            .collect();
+        let other_ids: Vec<RecordId> = other_ids
+            .into_iter()
+            .map(|id| RecordId::from(("entity", id)))
+            .collect();
+        let mut response = self
+            .client
+            .query("SELECT VALUE id FROM entity WHERE id IN $ids")
+            .bind(("ids", other_ids))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get related entities: {}", e)))?;
+        let entity_ids: Vec<RecordId> = response
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract entity IDs: {}", e)))?;
+        let entity_ids: HashSet<String> = entity_ids.iter().map(record_key).collect();
+
+        let relationships = relationships
+            .into_iter()
+            .filter(|relationship| entity_ids.contains(other_endpoint(relationship, entity_id)))
+            .collect();
 
         Ok(relationships)
     }
@@ -498,3 +567,12 @@ where
         Ok(paths)
     }
 }
+
+/// The end of `relationship` that isn't `entity_id`
+fn other_endpoint<'a>(relationship: &'a Relationship, entity_id: &str) -> &'a str {
+    if relationship.source_id == entity_id {
+        &relationship.target_id
+    } else {
+        &relationship.source_id
+    }
+}
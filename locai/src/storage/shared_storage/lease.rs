@@ -0,0 +1,86 @@
+//! Leases for SharedStorage
+//!
+//! A lease is a `lease` record naming its holder and when it expires. Taking
+//! one reads and writes the record in a single transaction, so when several
+//! processes share the database exactly one of them gets it. Expiry is
+//! computed with the database's clock, so processes whose clocks disagree
+//! still agree on who holds a lease.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use surrealdb::{Connection, Surreal};
+
+use crate::storage::errors::StorageError;
+
+const ACQUIRE_LEASE_QUERY: &str = r#"
+    BEGIN TRANSACTION;
+    LET $lease = type::thing('lease', $name);
+    LET $current = (SELECT holder, expires_at FROM $lease)[0];
+    LET $granted = $current = NONE
+        OR $current.holder = $holder
+        OR $current.expires_at < time::now();
+    IF $granted {
+        UPSERT $lease CONTENT {
+            holder: $holder,
+            expires_at: time::now() + duration::from::millis($ttl_ms)
+        };
+    };
+    RETURN $granted;
+    COMMIT TRANSACTION;
+"#;
+
+/// Lease record as read back from SurrealDB
+#[derive(Debug, Deserialize)]
+struct SurrealLease {
+    #[allow(dead_code)]
+    holder: String,
+}
+
+/// Take or renew the lease `name` for `holder`, for `ttl`
+///
+/// Fails if another process takes the same lease at the same moment; treat
+/// that as not getting it.
+pub(crate) async fn acquire_lease<C>(
+    client: &Surreal<C>,
+    name: &str,
+    holder: &str,
+    ttl: Duration,
+) -> Result<bool, StorageError>
+where
+    C: Connection,
+{
+    let mut result = client
+        .query(ACQUIRE_LEASE_QUERY)
+        .bind(("name", name.to_string()))
+        .bind(("holder", holder.to_string()))
+        .bind(("ttl_ms", ttl.as_millis().max(1) as u64))
+        .await
+        .map_err(|e| StorageError::Query(format!("Failed to acquire lease: {}", e)))?;
+
+    let last = result.num_statements().saturating_sub(1);
+    let granted: Option<bool> = result
+        .take(last)
+        .map_err(|e| StorageError::Query(format!("Failed to read lease: {}", e)))?;
+    Ok(granted.unwrap_or(false))
+}
+
+/// Delete the lease `name` if `holder` holds it
+pub(crate) async fn release_lease<C>(
+    client: &Surreal<C>,
+    name: &str,
+    holder: &str,
+) -> Result<bool, StorageError>
+where
+    C: Connection,
+{
+    let released: Vec<SurrealLease> = client
+        .query("DELETE type::thing('lease', $name) WHERE holder = $holder RETURN BEFORE")
+        .bind(("name", name.to_string()))
+        .bind(("holder", holder.to_string()))
+        .await
+        .map_err(|e| StorageError::Query(format!("Failed to release lease: {}", e)))?
+        .take(0)
+        .map_err(|e| StorageError::Query(format!("Failed to read released lease: {}", e)))?;
+    Ok(!released.is_empty())
+}
@@ -4,16 +4,27 @@
 //! Works with both embedded (memory/RocksDB) and remote SurrealDB instances.
 
 use chrono;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
-use surrealdb::{Connection, Surreal};
-use tokio::sync::broadcast;
+use surrealdb::{Action, Connection, Surreal};
+use tokio::sync::{Notify, broadcast};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use super::entity::SurrealEntity;
+use super::memory::SurrealMemory;
+use super::relationship::SurrealRelationship;
+use crate::models::Memory;
 use crate::storage::errors::StorageError;
+use crate::storage::models::{Entity, Relationship};
+
+/// Capacity of the change stream; slower subscribers see `Lagged` errors
+const CHANGE_STREAM_CAPACITY: usize = 1024;
 
 /// Database change event from SurrealDB live queries
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +35,99 @@ pub struct DbEvent {
     pub result: Value,  // The changed record
 }
 
+/// Start native live queries on the memory, entity and relationship tables
+///
+/// Every notification is published as a [`DbEvent`] whose `result` is the
+/// record in its model form ([`Memory`], [`Entity`] or [`Relationship`]), so
+/// `id` is the bare record key. Deletes carry the record as it was before
+/// removal. The queries run until `shutdown` is notified.
+pub(crate) async fn start_change_stream<C>(
+    client: &Surreal<C>,
+    shutdown: Arc<Notify>,
+) -> Result<broadcast::Sender<DbEvent>, StorageError>
+where
+    C: Connection + Clone + Send + Sync + 'static,
+{
+    let (event_tx, _) = broadcast::channel(CHANGE_STREAM_CAPACITY);
+    forward_table::<C, SurrealMemory, Memory>(client, "memory", &event_tx, &shutdown).await?;
+    forward_table::<C, SurrealEntity, Entity>(client, "entity", &event_tx, &shutdown).await?;
+    forward_table::<C, SurrealRelationship, Relationship>(
+        client,
+        "relationship",
+        &event_tx,
+        &shutdown,
+    )
+    .await?;
+    info!("Live queries started for memory, entity and relationship tables");
+    Ok(event_tx)
+}
+
+async fn forward_table<C, R, M>(
+    client: &Surreal<C>,
+    table: &str,
+    event_tx: &broadcast::Sender<DbEvent>,
+    shutdown: &Arc<Notify>,
+) -> Result<(), StorageError>
+where
+    C: Connection + Clone + Send + Sync + 'static,
+    R: DeserializeOwned + Send + Unpin + 'static,
+    M: From<R> + Serialize,
+{
+    let mut stream = client.select::<Vec<R>>(table).live().await.map_err(|e| {
+        StorageError::Connection(format!("Failed to start live query on {}: {}", table, e))
+    })?;
+
+    let table = table.to_string();
+    let event_tx = event_tx.clone();
+    let shutdown = shutdown.clone();
+    tokio::spawn(async move {
+        loop {
+            let notification = tokio::select! {
+                _ = shutdown.notified() => break,
+                notification = stream.next() => notification,
+            };
+            let notification = match notification {
+                Some(Ok(notification)) => notification,
+                Some(Err(e)) => {
+                    warn!("Dropping undecodable {} live notification: {}", table, e);
+                    continue;
+                }
+                None => break,
+            };
+
+            let action = match notification.action {
+                Action::Create => "CREATE",
+                Action::Update => "UPDATE",
+                Action::Delete => "DELETE",
+                _ => continue,
+            };
+            let result = match serde_json::to_value(M::from(notification.data)) {
+                Ok(result) => result,
+                Err(e) => {
+                    warn!("Failed to serialize {} live notification: {}", table, e);
+                    continue;
+                }
+            };
+            let id = result
+                .get("id")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+
+            // Sending only fails when nobody is subscribed
+            let _ = event_tx.send(DbEvent {
+                id,
+                action: action.to_string(),
+                table: table.clone(),
+                result,
+            });
+        }
+        debug!("Live query on {} stopped", table);
+    });
+
+    Ok(())
+}
+
 /// Live query subscription handle
 #[derive(Debug)]
 pub struct LiveQuerySubscription {
@@ -5,32 +5,244 @@ use chrono::{DateTime, Utc};
 use serde_json::Value;
 use surrealdb::{Connection, RecordId};
 
-use super::base::SharedStorage;
-use crate::models::Memory;
+use super::base::{SharedStorage, record_key};
+use super::checksum::memory_checksum;
+use super::outbox::{outbox_binding, with_outbox};
+use crate::models::geo::GeoBounds;
+use crate::models::{Memory, SPARSE_EMBEDDING_PROPERTY, SparseVector};
 use crate::storage::errors::StorageError;
-use crate::storage::filters::MemoryFilter;
+use crate::storage::filters::{ConditionValue, MemoryFilter};
+use crate::storage::models::{DistanceMetric, FilterExplanation, OutboxMessage, SearchHit};
 use crate::storage::traits::MemoryStore;
 
-/// Calculate cosine similarity between two vectors
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
+/// SurrealQL scoring `field` against `$query_vector` the way
+/// [`DistanceMetric::similarity`] does, higher meaning closer
+fn similarity_expression(metric: DistanceMetric, field: &str) -> String {
+    match metric {
+        DistanceMetric::Cosine => format!("vector::similarity::cosine({}, $query_vector)", field),
+        DistanceMetric::DotProduct => format!("vector::dot({}, $query_vector)", field),
+        DistanceMetric::Euclidean => format!(
+            "(1.0 / (1.0 + vector::distance::euclidean({}, $query_vector)))",
+            field
+        ),
+        DistanceMetric::Manhattan => format!(
+            "(1.0 / (1.0 + vector::distance::manhattan({}, $query_vector)))",
+            field
+        ),
     }
+}
+
+/// Field path of a memory property, quoted unless it's a plain identifier
+fn property_field(key: &str) -> String {
+    format!("metadata.properties.{}", field_name(key))
+}
 
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+/// Field path of a nested memory property, with `.` between keys
+fn property_path(path: &str) -> String {
+    let names: Vec<String> = path.split('.').map(field_name).collect();
+    format!("metadata.properties.{}", names.join("."))
+}
 
-    if norm_a == 0.0 || norm_b == 0.0 {
-        0.0
+fn field_name(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        key.to_string()
     } else {
-        dot_product / (norm_a * norm_b)
+        format!("`{}`", key.replace('`', "\\`"))
+    }
+}
+
+/// Whether part of `filter` is only checked on the query's results, so
+/// they have to be paged afterwards
+fn checked_after_query(filter: &MemoryFilter) -> bool {
+    filter.near.is_some()
+        || filter
+            .conditions
+            .iter()
+            .flatten()
+            .any(|condition| matches!(condition.value, ConditionValue::Date(_)))
+}
+
+/// The parts of `filter` the query can't check: exact distances and dates
+fn matches_after_query(filter: &MemoryFilter, memory: &Memory) -> bool {
+    filter.near.is_none_or(|near| {
+        memory
+            .location()
+            .is_some_and(|location| near.contains(&location))
+    }) && filter
+        .conditions
+        .iter()
+        .flatten()
+        .all(|condition| condition.matches(&memory.properties))
+}
+
+/// The query `list_memories` runs for a filter, with the values it binds
+pub(super) fn list_memories_query(
+    filter: Option<&MemoryFilter>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> (String, Vec<(String, Value)>) {
+    let mut query = "SELECT * FROM memory".to_string();
+    let mut conditions = Vec::new();
+    let mut bindings = Vec::new();
+
+    // Add filter conditions
+    if let Some(f) = filter {
+        if let Some(memory_type) = &f.memory_type {
+            // Memory type can be stored as either a string or an enum variant
+            // Try both representations for compatibility
+            let mt_lower = memory_type.to_lowercase();
+            conditions.push(format!(
+                "(type::string(metadata.memory_type) = '{}' OR string::lowercase(type::string(metadata.memory_type)) CONTAINS '{}')",
+                mt_lower, mt_lower
+            ));
+        }
+
+        if let Some(content) = &f.content {
+            conditions.push(format!("content CONTAINS '{}'", content));
+        }
+
+        if let Some(tags) = &f.tags
+            && !tags.is_empty()
+        {
+            let tag_conditions: Vec<String> = tags
+                .iter()
+                .map(|tag| format!("'{}' IN metadata.tags", tag))
+                .collect();
+            conditions.push(format!("({})", tag_conditions.join(" OR ")));
+        }
+
+        if let Some(source) = &f.source {
+            conditions.push(format!("metadata.source = '{}'", source));
+        }
+
+        if let Some(created_after) = &f.created_after {
+            conditions.push(format!("created_at > d'{}'", created_after.to_rfc3339()));
+        }
+
+        if let Some(created_before) = &f.created_before {
+            conditions.push(format!("created_at < d'{}'", created_before.to_rfc3339()));
+        }
+
+        // Plain equality on the field path, so configured property indexes apply
+        if let Some(properties) = &f.properties {
+            for (n, (key, value)) in properties.iter().enumerate() {
+                conditions.push(format!("{} = $property{}", property_field(key), n));
+                bindings.push((format!("property{}", n), value.clone()));
+            }
+        }
+
+        // Dates in properties are strings, so they are compared after the query
+        for (n, condition) in f.conditions.iter().flatten().enumerate() {
+            let field = property_path(&condition.property);
+            let (is_type, value) = match &condition.value {
+                ConditionValue::Number(number) => ("number", Value::from(*number)),
+                ConditionValue::Text(text) => ("string", Value::from(text.as_str())),
+                ConditionValue::Bool(b) => ("bool", Value::from(*b)),
+                ConditionValue::Date(_) => continue,
+            };
+            conditions.push(format!(
+                "(type::is::{}({}) AND {} {} $condition{})",
+                is_type,
+                field,
+                field,
+                condition.op.symbol(),
+                n
+            ));
+            bindings.push((format!("condition{}", n), value));
+        }
+
+        // Narrow to a box around the radius; distances are checked after the query
+        if let Some(near) = &f.near {
+            conditions.extend(bounds_conditions(
+                &near.bounds(),
+                "metadata.properties.location",
+            ));
+        }
+    }
+
+    if !conditions.is_empty() {
+        query.push_str(" WHERE ");
+        query.push_str(&conditions.join(" AND "));
+    }
+
+    query.push_str(" ORDER BY created_at DESC");
+
+    if let Some(limit) = limit {
+        query.push_str(&format!(" LIMIT {}", limit));
+    }
+
+    if let Some(offset) = offset {
+        query.push_str(&format!(" START {}", offset));
+    }
+
+    (query, bindings)
+}
+
+/// Conditions keeping records whose location at `field` is within `bounds`
+pub(super) fn bounds_conditions(bounds: &GeoBounds, field: &str) -> Vec<String> {
+    let mut conditions = vec![
+        format!("{}.latitude >= {}", field, bounds.min_latitude),
+        format!("{}.latitude <= {}", field, bounds.max_latitude),
+    ];
+    if let Some((min_longitude, max_longitude)) = bounds.longitude {
+        conditions.push(format!("{}.longitude >= {}", field, min_longitude));
+        conditions.push(format!("{}.longitude <= {}", field, max_longitude));
+    }
+    conditions
+}
+
+/// Characters of content kept in a search hit snippet
+const SNIPPET_CHARS: usize = 160;
+
+/// Cut highlighted content down to a window around its first match
+pub(super) fn snippet(highlighted: &str) -> String {
+    let chars: Vec<char> = highlighted.chars().collect();
+    let first_match = highlighted
+        .find("<mark>")
+        .map(|i| highlighted[..i].chars().count())
+        .unwrap_or(0);
+    // Keep some context before the match
+    let start = first_match.saturating_sub(SNIPPET_CHARS / 4);
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+    let mut snippet: String = chars[start..end].iter().collect();
+
+    // Don't leave a tag cut in half or a highlight unclosed
+    if let Some(open) = snippet.rfind('<')
+        && !snippet[open..].contains('>')
+    {
+        snippet.truncate(open);
+    }
+    if snippet.matches("<mark>").count() > snippet.matches("</mark>").count() {
+        snippet.push_str("</mark>");
     }
+
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Metadata object stored alongside memory content
+pub(super) fn memory_metadata(memory: &Memory) -> Value {
+    serde_json::json!({
+        "memory_type": memory.memory_type,
+        "last_accessed": memory.last_accessed.map(|dt| dt.to_rfc3339()),
+        "access_count": memory.access_count,
+        "priority": memory.priority,
+        "tags": memory.tags,
+        "source": memory.source,
+        "expires_at": memory.expires_at.map(|dt| dt.to_rfc3339()),
+        "properties": memory.properties,
+        "related_memories": memory.related_memories,
+    })
 }
 
 /// Internal representation of a Memory record for SurrealDB (matching working implementation exactly)
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-struct SurrealMemory {
+pub(super) struct SurrealMemory {
     id: RecordId,
     content: String,
     metadata: Value,
@@ -39,7 +251,7 @@ struct SurrealMemory {
     owner: RecordId,
     shared_with: Option<Vec<RecordId>>,
     created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+    pub(super) updated_at: DateTime<Utc>,
 }
 
 impl From<Memory> for SurrealMemory {
@@ -141,7 +353,7 @@ impl From<SurrealMemory> for Memory {
             .unwrap_or_default();
 
         Self {
-            id: surreal_memory.id.key().to_string(),
+            id: record_key(&surreal_memory.id),
             content: surreal_memory.content,
             memory_type,
             created_at: surreal_memory.created_at,
@@ -165,83 +377,7 @@ where
 {
     /// Create a new memory
     async fn create_memory(&self, memory: Memory) -> Result<Memory, StorageError> {
-        // Ensure system user exists
-        self.ensure_system_user().await?;
-
-        // Build metadata object exactly like the working implementation
-        let metadata = serde_json::json!({
-            "memory_type": memory.memory_type,
-            "last_accessed": memory.last_accessed.map(|dt| dt.to_rfc3339()),
-            "access_count": memory.access_count,
-            "priority": memory.priority,
-            "tags": memory.tags,
-            "source": memory.source,
-            "expires_at": memory.expires_at.map(|dt| dt.to_rfc3339()),
-            "properties": memory.properties,
-            "related_memories": memory.related_memories,
-        });
-
-        // Use the EXACT working query from memory.rs
-        let query = r#"
-            CREATE memory CONTENT {
-                content: $content,
-                metadata: $metadata,
-                embedding: $embedding,
-                importance: $importance,
-                owner: $owner,
-                shared_with: $shared_with,
-                created_at: type::datetime($created_at),
-                version_count: 0
-            }
-        "#;
-
-        let mut result = self
-            .client
-            .query(query)
-            .bind(("content", memory.content.clone()))
-            .bind(("metadata", metadata))
-            .bind(("embedding", memory.embedding.clone()))
-            .bind(("importance", None::<f32>))
-            .bind(("owner", RecordId::from(("user", "system"))))
-            .bind(("shared_with", None::<Vec<RecordId>>))
-            .bind(("created_at", memory.created_at.to_rfc3339()))
-            .await
-            .map_err(|e| StorageError::Query(format!("Failed to create memory: {}", e)))?;
-
-        let created: Vec<SurrealMemory> = result
-            .take(0)
-            .map_err(|e| StorageError::Query(format!("Failed to extract created memory: {}", e)))?;
-
-        let created_memory = created
-            .into_iter()
-            .next()
-            .map(Memory::from)
-            .ok_or_else(|| StorageError::Internal("No memory created".to_string()))?;
-
-        // Create initial version automatically
-        use crate::storage::traits::MemoryVersionStore;
-        if let Err(e) = self
-            .create_memory_version(&created_memory.id, &created_memory.content, None)
-            .await
-        {
-            tracing::warn!(
-                "Failed to create initial version for memory {}: {}",
-                created_memory.id,
-                e
-            );
-            // Don't fail memory creation if versioning fails
-        }
-
-        // Execute on_memory_created hooks (non-blocking, fire-and-forget)
-        let hooks = self.hook_registry.clone();
-        let memory_clone = created_memory.clone();
-        tokio::spawn(async move {
-            if let Err(e) = hooks.execute_on_created(&memory_clone).await {
-                tracing::warn!("Hook execution failed for on_memory_created: {}", e);
-            }
-        });
-
-        Ok(created_memory)
+        self.create_memory_record(memory, &[]).await
     }
 
     /// Get a memory by its ID
@@ -264,100 +400,54 @@ where
 
     /// Update an existing memory
     async fn update_memory(&self, memory: Memory) -> Result<Memory, StorageError> {
-        let record_id = RecordId::from(("memory", memory.id.as_str()));
-
-        // Get the old memory before updating (use internal to avoid hook recursion)
-        let old_memory = self.get_memory_internal(&memory.id).await?;
+        self.update_memory_record(memory, &[]).await
+    }
 
-        // Build metadata exactly like create_memory
-        let metadata = serde_json::json!({
-            "memory_type": memory.memory_type,
-            "last_accessed": memory.last_accessed.map(|dt| dt.to_rfc3339()),
-            "access_count": memory.access_count,
-            "priority": memory.priority,
-            "tags": memory.tags,
-            "source": memory.source,
-            "expires_at": memory.expires_at.map(|dt| dt.to_rfc3339()),
-            "properties": memory.properties,
-            "related_memories": memory.related_memories,
-        });
+    async fn upsert_memory(&self, memory: Memory) -> Result<Memory, StorageError> {
+        self.ensure_system_user().await?;
 
+        let record = SurrealMemory::from(self.in_memory_space(memory));
         let query = r#"
-            UPDATE $id SET 
+            UPSERT $record_id SET
                 content = $content,
                 metadata = $metadata,
                 embedding = $embedding,
-                updated_at = time::now()
+                checksum = $checksum,
+                owner = $owner,
+                created_at = type::datetime($created_at)
         "#;
+        let checksum = memory_checksum(&record.content, record.embedding.as_deref());
 
         let mut result = self
             .client
             .query(query)
-            .bind(("id", record_id))
-            .bind(("content", memory.content.clone()))
-            .bind(("metadata", metadata))
-            .bind(("embedding", memory.embedding.clone()))
+            .bind(("record_id", record.id))
+            .bind(("content", record.content))
+            .bind(("metadata", record.metadata))
+            .bind(("embedding", record.embedding))
+            .bind(("checksum", checksum))
+            .bind(("owner", record.owner))
+            .bind(("created_at", record.created_at.to_rfc3339()))
             .await
-            .map_err(|e| StorageError::Query(format!("Failed to update memory: {}", e)))?;
+            .map_err(|e| StorageError::Query(format!("Failed to upsert memory: {}", e)))?;
 
-        let updated: Vec<SurrealMemory> = result
-            .take(0)
-            .map_err(|e| StorageError::Query(format!("Failed to extract updated memory: {}", e)))?;
+        let upserted: Vec<SurrealMemory> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract upserted memory: {}", e))
+        })?;
 
-        let updated_memory = updated
+        let upserted = upserted
             .into_iter()
             .next()
             .map(Memory::from)
-            .ok_or_else(|| {
-                StorageError::NotFound(format!("Memory with id {} not found", memory.id))
-            })?;
-
-        // Execute on_memory_updated hooks (non-blocking, fire-and-forget)
-        if let Some(old_mem) = old_memory {
-            let hooks = self.hook_registry.clone();
-            let updated_clone = updated_memory.clone();
-            tokio::spawn(async move {
-                if let Err(e) = hooks.execute_on_updated(&old_mem, &updated_clone).await {
-                    tracing::warn!("Hook execution failed for on_memory_updated: {}", e);
-                }
-            });
-        }
+            .ok_or_else(|| StorageError::Internal("No memory upserted".to_string()))?;
 
-        Ok(updated_memory)
+        self.refresh_memory_aliases(&upserted.id).await;
+        Ok(upserted)
     }
 
     /// Delete a memory by its ID
     async fn delete_memory(&self, id: &str) -> Result<bool, StorageError> {
-        // Get memory before deletion (use internal to avoid hook recursion)
-        let memory_to_delete = self.get_memory_internal(id).await?;
-
-        // Execute before_memory_deleted hooks (blocking for veto support)
-        if let Some(mem) = &memory_to_delete {
-            match self.hook_registry.execute_before_deleted(mem).await {
-                Ok(true) => {
-                    // Hooks allowed deletion, proceed
-                    tracing::debug!("Deletion allowed by hooks for memory {}", id);
-                }
-                Ok(false) => {
-                    // Hooks vetoed deletion
-                    tracing::warn!("Deletion vetoed by hooks for memory {}", id);
-                    return Ok(false);
-                }
-                Err(e) => {
-                    // Hook execution error - log but continue (don't fail the operation)
-                    tracing::warn!("Hook execution failed during deletion check: {}", e);
-                }
-            }
-        }
-
-        // Use SDK method directly like VectorStore for consistency
-        let deleted: Option<SurrealMemory> = self
-            .client
-            .delete(("memory", id))
-            .await
-            .map_err(|e| StorageError::Query(format!("Failed to delete memory: {}", e)))?;
-
-        Ok(deleted.is_some())
+        self.delete_memory_record(id, &[]).await
     }
 
     /// List memories with optional filtering
@@ -367,66 +457,17 @@ where
         limit: Option<usize>,
         offset: Option<usize>,
     ) -> Result<Vec<Memory>, StorageError> {
-        let mut query = "SELECT * FROM memory".to_string();
-        let mut conditions = Vec::new();
-
-        // Add filter conditions
-        if let Some(f) = &filter {
-            if let Some(memory_type) = &f.memory_type {
-                // Memory type can be stored as either a string or an enum variant
-                // Try both representations for compatibility
-                let mt_lower = memory_type.to_lowercase();
-                conditions.push(format!(
-                    "(type::string(metadata.memory_type) = '{}' OR string::lowercase(type::string(metadata.memory_type)) CONTAINS '{}')",
-                    mt_lower, mt_lower
-                ));
-            }
-
-            if let Some(content) = &f.content {
-                conditions.push(format!("content CONTAINS '{}'", content));
-            }
-
-            if let Some(tags) = &f.tags
-                && !tags.is_empty()
-            {
-                let tag_conditions: Vec<String> = tags
-                    .iter()
-                    .map(|tag| format!("'{}' IN metadata.tags", tag))
-                    .collect();
-                conditions.push(format!("({})", tag_conditions.join(" OR ")));
-            }
-
-            if let Some(source) = &f.source {
-                conditions.push(format!("metadata.source = '{}'", source));
-            }
-
-            if let Some(created_after) = &f.created_after {
-                conditions.push(format!("created_at > d'{}'", created_after.to_rfc3339()));
-            }
-
-            if let Some(created_before) = &f.created_before {
-                conditions.push(format!("created_at < d'{}'", created_before.to_rfc3339()));
-            }
-        }
-
-        if !conditions.is_empty() {
-            query.push_str(" WHERE ");
-            query.push_str(&conditions.join(" AND "));
-        }
-
-        query.push_str(" ORDER BY created_at DESC");
-
-        if let Some(limit) = limit {
-            query.push_str(&format!(" LIMIT {}", limit));
-        }
-
-        if let Some(offset) = offset {
-            query.push_str(&format!(" START {}", offset));
-        }
+        let post_filter = filter.as_ref().filter(|f| checked_after_query(f));
+        let (query, bindings) = match post_filter {
+            Some(_) => list_memories_query(filter.as_ref(), None, None),
+            None => list_memories_query(filter.as_ref(), limit, offset),
+        };
 
-        let mut result = self
-            .client
-            .query(&query)
+        let mut result = bindings
+            .into_iter()
+            .fold(self.client.query(&query), |query, binding| {
+                query.bind(binding)
+            })
             .await
             .map_err(|e| StorageError::Query(format!("Failed to list memories: {}", e)))?;
 
@@ -434,7 +475,15 @@ where
             .take(0)
             .map_err(|e| StorageError::Query(format!("Failed to extract memories: {}", e)))?;
 
-        Ok(memories.into_iter().map(Memory::from).collect())
+        let memories = memories.into_iter().map(Memory::from);
+        Ok(match post_filter {
+            Some(filter) => memories
+                .filter(|memory| matches_after_query(filter, memory))
+                .skip(offset.unwrap_or(0))
+                .take(limit.unwrap_or(usize::MAX))
+                .collect(),
+            None => memories.collect(),
+        })
     }
 
     /// Count memories with optional filtering
@@ -445,16 +494,93 @@ where
     }
 
     /// Batch create multiple memories
+    ///
+    /// All memories are created in one transaction, so either every memory is
+    /// stored or none is.
     async fn batch_create_memories(
         &self,
         memories: Vec<Memory>,
     ) -> Result<Vec<Memory>, StorageError> {
-        let mut created_memories = Vec::new();
+        if memories.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.ensure_system_user().await?;
+
+        let memories: Vec<Memory> = memories
+            .into_iter()
+            .map(|memory| self.in_memory_space(memory))
+            .collect();
+        let records: Vec<Value> = memories
+            .iter()
+            .map(|memory| {
+                serde_json::json!({
+                    "id": memory.id,
+                    "content": memory.content,
+                    "metadata": memory_metadata(memory),
+                    "embedding": memory.embedding,
+                    "checksum": memory_checksum(&memory.content, memory.embedding.as_deref()),
+                    "created_at": memory.created_at.to_rfc3339(),
+                })
+            })
+            .collect();
 
-        // For now, create memories one by one to avoid complex binding issues
-        for memory in memories {
-            let created = self.create_memory(memory).await?;
-            created_memories.push(created);
+        let keep_ids = self.config.id_strategy.is_deterministic();
+        let mut query = String::from("BEGIN TRANSACTION;\n");
+        for n in 0..records.len() {
+            let target = if keep_ids {
+                format!("type::thing('memory', $records[{n}].id)")
+            } else {
+                "memory".to_string()
+            };
+            query.push_str(&format!(
+                r#"LET $created{n} = (CREATE {target} CONTENT {{
+                    content: $records[{n}].content,
+                    metadata: $records[{n}].metadata,
+                    embedding: $records[{n}].embedding,
+                    checksum: $records[{n}].checksum,
+                    importance: NONE,
+                    owner: $owner,
+                    shared_with: NONE,
+                    created_at: type::datetime($records[{n}].created_at),
+                    version_count: 0
+                }});
+                "#
+            ));
+        }
+        let created_vars: Vec<String> = (0..records.len())
+            .map(|n| format!("$created{}", n))
+            .collect();
+        query.push_str(&format!(
+            "RETURN array::flatten([{}]);\nCOMMIT TRANSACTION;",
+            created_vars.join(", ")
+        ));
+
+        let mut result = self
+            .client
+            .query(query)
+            .bind(("records", records))
+            .bind(("owner", RecordId::from(("user", "system"))))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to create memories: {}", e)))?;
+
+        let last = result.num_statements().saturating_sub(1);
+        let created: Vec<SurrealMemory> = result.take(last).map_err(|e| {
+            StorageError::Query(format!("Failed to extract created memories: {}", e))
+        })?;
+        if created.len() != memories.len() {
+            return Err(StorageError::Internal(format!(
+                "Created {} of {} memories",
+                created.len(),
+                memories.len()
+            )));
+        }
+
+        let mut created_memories = Vec::with_capacity(created.len());
+        for record in created {
+            let memory = Memory::from(record);
+            self.memory_created(&memory).await;
+            created_memories.push(memory);
         }
 
         Ok(created_memories)
@@ -519,7 +645,7 @@ where
         }
 
         // Convert BM25SearchResult to SurrealMemory then to Memory
-        Ok(results
+        let mut matches: Vec<(Memory, f32, String)> = results
             .into_iter()
             .map(|r| {
                 let surreal_memory = SurrealMemory {
@@ -539,7 +665,10 @@ where
                     r.highlighted_content,
                 )
             })
-            .collect())
+            .collect();
+
+        self.merge_alias_matches(query, limit, &mut matches).await?;
+        Ok(matches)
     }
 
     /// Fuzzy search for typo tolerance
@@ -597,6 +726,12 @@ where
     ) -> Result<Vec<(Memory, f32, String)>, StorageError> {
         // Use the same implementation as our concrete method
         let limit = limit.unwrap_or(10);
+        let query_vector = &self.normalized_embedding(query_vector);
+
+        // The M-Tree index only ranks by cosine distance
+        if self.config.memory_space.metric != DistanceMetric::Cosine {
+            return self.brute_force_vector_search(query_vector, limit).await;
+        }
 
         // Search memories that have embeddings using SurrealDB KNN vector similarity
         // Note: Uses M-Tree index on embedding field (defined in schema) for exact nearest neighbor search
@@ -704,6 +839,187 @@ where
             .collect())
     }
 
+    /// BM25 search returning only memory IDs, scores and snippets
+    async fn bm25_search_memory_hits(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>, StorageError> {
+        let search_query = r#"
+            SELECT id,
+                   search::score(0) AS score,
+                   search::highlight('<mark>', '</mark>', 0) AS highlighted_content
+            FROM memory
+            WHERE content @0@ $query
+            ORDER BY score DESC
+            LIMIT $limit
+        "#;
+
+        let mut result = self
+            .client
+            .query(search_query)
+            .bind(("query", query.to_string()))
+            .bind(("limit", limit.unwrap_or(10)))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to perform BM25 search: {}", e)))?;
+
+        #[derive(serde::Deserialize)]
+        struct BM25Hit {
+            id: RecordId,
+            score: f32,
+            highlighted_content: String,
+        }
+
+        let hits: Vec<BM25Hit> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract BM25 results: {}", e)))?;
+
+        let mut hits: Vec<SearchHit> = hits
+            .into_iter()
+            .map(|hit| SearchHit {
+                id: record_key(&hit.id),
+                score: hit.score,
+                snippet: snippet(&hit.highlighted_content),
+            })
+            .collect();
+
+        self.merge_alias_hits(query, limit.unwrap_or(10), &mut hits)
+            .await?;
+        Ok(hits)
+    }
+
+    /// Vector similarity search returning only memory IDs, scores and snippets
+    async fn vector_search_memory_hits(
+        &self,
+        query_vector: &[f32],
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>, StorageError> {
+        let limit = limit.unwrap_or(10);
+        let query_vector = &self.normalized_embedding(query_vector);
+        let metric = self.config.memory_space.metric;
+
+        // Same KNN query as vector_search_memories, falling back to scanning
+        // in the database when the M-Tree index returns nothing or, since it
+        // only ranks by cosine distance, another metric is configured
+        let knn_query = format!(
+            r#"
+                SELECT id,
+                       (1.0 - vector::distance::knn()) AS score,
+                       string::slice(content, 0, $snippet_chars) AS snippet
+                FROM memory
+                WHERE embedding IS NOT NULL
+                  AND embedding <|{}|> $query_vector
+                ORDER BY score DESC
+                LIMIT {}
+            "#,
+            limit, limit
+        );
+        let scan_query = format!(
+            r#"
+                SELECT id,
+                       {} AS score,
+                       string::slice(content, 0, $snippet_chars) AS snippet
+                FROM memory
+                WHERE embedding IS NOT NULL
+                  AND array::len(embedding) = array::len($query_vector)
+                ORDER BY score DESC
+                LIMIT $limit
+            "#,
+            similarity_expression(metric, "embedding")
+        );
+
+        #[derive(serde::Deserialize)]
+        struct VectorHit {
+            id: RecordId,
+            score: f32,
+            snippet: String,
+        }
+
+        let knn = if metric != DistanceMetric::Cosine {
+            Ok(Vec::new())
+        } else {
+            match self
+                .client
+                .query(&knn_query)
+                .bind(("query_vector", query_vector.to_vec()))
+                .bind(("snippet_chars", SNIPPET_CHARS))
+                .await
+            {
+                Ok(mut result) => result.take::<Vec<VectorHit>>(0),
+                Err(e) => Err(e),
+            }
+        };
+        let hits = match knn {
+            Ok(hits) if !hits.is_empty() => hits,
+            knn => {
+                if let Err(e) = knn {
+                    tracing::debug!("KNN hit search failed, scanning instead: {}", e);
+                }
+                let mut result = self
+                    .client
+                    .query(&scan_query)
+                    .bind(("query_vector", query_vector.to_vec()))
+                    .bind(("snippet_chars", SNIPPET_CHARS))
+                    .bind(("limit", limit))
+                    .await
+                    .map_err(|e| {
+                        StorageError::Query(format!("Failed to perform vector search: {}", e))
+                    })?;
+                result.take(0).map_err(|e| {
+                    StorageError::Query(format!("Failed to extract vector search results: {}", e))
+                })?
+            }
+        };
+
+        Ok(hits
+            .into_iter()
+            .map(|hit| SearchHit {
+                id: record_key(&hit.id),
+                score: hit.score,
+                snippet: hit.snippet,
+            })
+            .collect())
+    }
+
+    /// Sparse vector search, scoring memories sharing a dimension with the query
+    async fn sparse_search_memories(
+        &self,
+        query: &SparseVector,
+        limit: Option<usize>,
+    ) -> Result<Vec<(Memory, f32)>, StorageError> {
+        let limit = limit.unwrap_or(10);
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Narrow to memories with a matching dimension, then score here
+        let sparse_query = format!(
+            "SELECT * FROM memory WHERE {}.indices CONTAINSANY $indices",
+            property_field(SPARSE_EMBEDDING_PROPERTY)
+        );
+        let mut result = self
+            .client
+            .query(&sparse_query)
+            .bind(("indices", query.indices.clone()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to perform sparse search: {}", e)))?;
+        let candidates: Vec<SurrealMemory> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract sparse search results: {}", e))
+        })?;
+
+        let mut scored: Vec<(Memory, f32)> = candidates
+            .into_iter()
+            .map(Memory::from)
+            .filter_map(|memory| {
+                let score = memory.sparse_embedding()?.dot(query);
+                Some((memory, score))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
     /// Search memories with configurable multi-factor scoring
     async fn search_memories_with_scoring(
         &self,
@@ -732,7 +1048,8 @@ where
             None
         };
 
-        // Calculate final scores
+        // Calculate final scores, with memory age measured by the storage clock
+        let now = self.config.clock.now();
         let mut scored_results: Vec<(Memory, f32)> = bm25_results
             .into_iter()
             .map(|(memory, bm25_score, _highlighted)| {
@@ -743,7 +1060,7 @@ where
                     .map(|(_, score)| *score);
 
                 let final_score =
-                    calculator.calculate_final_score(bm25_score, vector_score, &memory);
+                    calculator.calculate_final_score_at(bm25_score, vector_score, &memory, now);
                 (memory, final_score)
             })
             .collect();
@@ -822,7 +1139,7 @@ where
         }
 
         // Convert BM25SearchResult to SurrealMemory then to Memory
-        Ok(results
+        let mut matches: Vec<(Memory, f32, String)> = results
             .into_iter()
             .map(|r| {
                 let surreal_memory = SurrealMemory {
@@ -842,7 +1159,10 @@ where
                     r.highlighted_content,
                 )
             })
-            .collect())
+            .collect();
+
+        self.merge_alias_matches(query, limit, &mut matches).await?;
+        Ok(matches)
     }
 
     /// Fuzzy search for typo tolerance
@@ -978,6 +1298,12 @@ where
         limit: Option<usize>,
     ) -> Result<Vec<(Memory, f32, String)>, StorageError> {
         let limit = limit.unwrap_or(10);
+        let query_vector = &self.normalized_embedding(query_vector);
+
+        // The M-Tree index only ranks by cosine distance
+        if self.config.memory_space.metric != DistanceMetric::Cosine {
+            return self.brute_force_vector_search(query_vector, limit).await;
+        }
 
         // Search memories that have embeddings using SurrealDB KNN vector similarity
         // Note: Uses M-Tree index on embedding field (defined in schema) for exact nearest neighbor search
@@ -1081,8 +1407,9 @@ where
             .collect())
     }
 
-    /// Brute-force vector search using cosine similarity
-    /// This is a fallback when M-Tree index doesn't work (e.g., with optional fields)
+    /// Brute-force vector search using the memory vector space's metric
+    /// This is a fallback when M-Tree index doesn't work (e.g., with optional fields),
+    /// and the search for metrics other than cosine
     async fn brute_force_vector_search(
         &self,
         query_vector: &[f32],
@@ -1108,14 +1435,15 @@ where
             memories.len()
         );
 
-        // Calculate cosine similarity for each memory
+        // Score each memory by the configured metric
+        let metric = self.config.memory_space.metric;
         let mut scored_memories: Vec<(Memory, f32)> = memories
             .into_iter()
             .filter_map(|surreal_mem| {
                 let mem = Memory::from(surreal_mem.clone());
                 if let Some(embedding) = &mem.embedding {
                     if embedding.len() == query_vector.len() {
-                        let similarity = cosine_similarity(query_vector, embedding);
+                        let similarity = metric.similarity(query_vector, embedding);
                         Some((mem, similarity))
                     } else {
                         tracing::debug!(
@@ -1207,6 +1535,61 @@ where
         Ok(suggestions)
     }
 
+    /// Explain how `list_memories` executes `filter`
+    ///
+    /// Runs the same query with `EXPLAIN`, which plans it without reading
+    /// any records, and reports the indexes the plan uses.
+    pub async fn explain_memory_filter(
+        &self,
+        filter: &MemoryFilter,
+    ) -> Result<FilterExplanation, StorageError> {
+        let (query, bindings) = list_memories_query(Some(filter), None, None);
+
+        let mut result = bindings
+            .into_iter()
+            .fold(
+                self.client.query(format!("{} EXPLAIN", query)),
+                |query, binding| query.bind(binding),
+            )
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to explain filter: {}", e)))?;
+        let plan: Vec<Value> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract plan: {}", e)))?;
+
+        let mut indexes_used = Vec::new();
+        let mut full_scan = false;
+        for step in &plan {
+            let operation = step["operation"].as_str().unwrap_or_default();
+            if operation == "Iterate Table" {
+                full_scan = true;
+            } else if operation.starts_with("Iterate Index")
+                && let Some(index) = step["detail"]["plan"]["index"].as_str()
+                && !indexes_used.iter().any(|used| used == index)
+            {
+                indexes_used.push(index.to_string());
+            }
+        }
+
+        let mut unindexed_properties: Vec<String> = filter
+            .properties
+            .iter()
+            .flat_map(|properties| properties.keys())
+            .chain(filter.conditions.iter().flatten().map(|c| &c.property))
+            .filter(|key| !self.config.indexed_properties.contains(key))
+            .cloned()
+            .collect();
+        unindexed_properties.dedup();
+
+        Ok(FilterExplanation {
+            query,
+            indexes_used,
+            full_scan,
+            unindexed_properties,
+            plan: Value::Array(plan),
+        })
+    }
+
     /// Temporal search for memories within a time range
     pub async fn temporal_search_memories(
         &self,
@@ -1310,6 +1693,253 @@ where
     }
 }
 
+/// Memory writes shared by `MemoryStore` and `OutboxStore`
+///
+/// With no outbox messages these run the plain write; otherwise the write
+/// and the messages commit together (see [`super::outbox`]).
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// `memory` with its embedding as the memory vector space stores it
+    fn in_memory_space(&self, mut memory: Memory) -> Memory {
+        if let Some(embedding) = &memory.embedding {
+            memory.embedding = Some(self.normalized_embedding(embedding));
+        }
+        memory
+    }
+
+    /// Create a memory, recording `outbox` messages with it
+    pub(super) async fn create_memory_record(
+        &self,
+        memory: Memory,
+        outbox: &[OutboxMessage],
+    ) -> Result<Memory, StorageError> {
+        let memory = self.in_memory_space(memory);
+
+        // Ensure system user exists
+        self.ensure_system_user().await?;
+
+        // Build metadata object exactly like the working implementation
+        let metadata = memory_metadata(&memory);
+
+        // With an outbox the memory keeps its own ID, so messages can refer to it;
+        // a deterministic ID strategy keeps it too, so the ID is reproducible
+        let target = if outbox.is_empty() && !self.config.id_strategy.is_deterministic() {
+            "memory"
+        } else {
+            "$record"
+        };
+        let write = format!(
+            r#"
+            CREATE {} CONTENT {{
+                content: $content,
+                metadata: $metadata,
+                embedding: $embedding,
+                checksum: $checksum,
+                importance: $importance,
+                owner: $owner,
+                shared_with: $shared_with,
+                created_at: type::datetime($created_at),
+                version_count: 0
+            }}
+        "#,
+            target
+        );
+
+        let mut result = self
+            .client
+            .query(with_outbox(&write, outbox))
+            .bind(("record", RecordId::from(("memory", memory.id.as_str()))))
+            .bind(("outbox", outbox_binding(outbox)))
+            .bind(("content", memory.content.clone()))
+            .bind(("metadata", metadata))
+            .bind(("embedding", memory.embedding.clone()))
+            .bind((
+                "checksum",
+                memory_checksum(&memory.content, memory.embedding.as_deref()),
+            ))
+            .bind(("importance", None::<f32>))
+            .bind(("owner", RecordId::from(("user", "system"))))
+            .bind(("shared_with", None::<Vec<RecordId>>))
+            .bind(("created_at", memory.created_at.to_rfc3339()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to create memory: {}", e)))?;
+
+        let last = result.num_statements().saturating_sub(1);
+        let created: Vec<SurrealMemory> = result
+            .take(last)
+            .map_err(|e| StorageError::Query(format!("Failed to extract created memory: {}", e)))?;
+
+        let created_memory = created
+            .into_iter()
+            .next()
+            .map(Memory::from)
+            .ok_or_else(|| StorageError::Internal("No memory created".to_string()))?;
+
+        self.memory_created(&created_memory).await;
+
+        Ok(created_memory)
+    }
+
+    /// Version a newly created memory and run its creation hooks
+    async fn memory_created(&self, created_memory: &Memory) {
+        // Create initial version automatically
+        use crate::storage::traits::MemoryVersionStore;
+        if let Err(e) = self
+            .create_memory_version(&created_memory.id, &created_memory.content, None)
+            .await
+        {
+            tracing::warn!(
+                "Failed to create initial version for memory {}: {}",
+                created_memory.id,
+                e
+            );
+            // Don't fail memory creation if versioning fails
+        }
+
+        self.refresh_memory_aliases(&created_memory.id).await;
+
+        // Execute on_memory_created hooks (non-blocking, fire-and-forget)
+        let hooks = self.hook_registry.clone();
+        let memory_clone = created_memory.clone();
+        tokio::spawn(async move {
+            if let Err(e) = hooks.execute_on_created(&memory_clone).await {
+                tracing::warn!("Hook execution failed for on_memory_created: {}", e);
+            }
+        });
+    }
+
+    /// Update a memory, recording `outbox` messages with it
+    pub(super) async fn update_memory_record(
+        &self,
+        memory: Memory,
+        outbox: &[OutboxMessage],
+    ) -> Result<Memory, StorageError> {
+        let memory = self.in_memory_space(memory);
+        let record_id = RecordId::from(("memory", memory.id.as_str()));
+
+        // Get the old memory before updating (use internal to avoid hook recursion)
+        let old_memory = self.get_memory_internal(&memory.id).await?;
+
+        // Build metadata exactly like create_memory
+        let metadata = memory_metadata(&memory);
+
+        let write = r#"
+            UPDATE $id SET 
+                content = $content,
+                metadata = $metadata,
+                embedding = $embedding,
+                checksum = $checksum,
+                updated_at = time::now()
+        "#;
+
+        let mut result = self
+            .client
+            .query(with_outbox(write, outbox))
+            .bind(("id", record_id))
+            .bind(("outbox", outbox_binding(outbox)))
+            .bind(("content", memory.content.clone()))
+            .bind(("metadata", metadata))
+            .bind(("embedding", memory.embedding.clone()))
+            .bind((
+                "checksum",
+                memory_checksum(&memory.content, memory.embedding.as_deref()),
+            ))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to update memory: {}", e)))?;
+
+        let last = result.num_statements().saturating_sub(1);
+        let updated: Vec<SurrealMemory> = result
+            .take(last)
+            .map_err(|e| StorageError::Query(format!("Failed to extract updated memory: {}", e)))?;
+
+        let updated_memory = updated
+            .into_iter()
+            .next()
+            .map(Memory::from)
+            .ok_or_else(|| {
+                StorageError::NotFound(format!("Memory with id {} not found", memory.id))
+            })?;
+
+        self.refresh_memory_aliases(&updated_memory.id).await;
+
+        // Execute on_memory_updated hooks (non-blocking, fire-and-forget)
+        if let Some(old_mem) = old_memory {
+            let hooks = self.hook_registry.clone();
+            let updated_clone = updated_memory.clone();
+            tokio::spawn(async move {
+                if let Err(e) = hooks.execute_on_updated(&old_mem, &updated_clone).await {
+                    tracing::warn!("Hook execution failed for on_memory_updated: {}", e);
+                }
+            });
+        }
+
+        Ok(updated_memory)
+    }
+
+    /// Delete a memory, recording `outbox` messages with it
+    pub(super) async fn delete_memory_record(
+        &self,
+        id: &str,
+        outbox: &[OutboxMessage],
+    ) -> Result<bool, StorageError> {
+        // Get memory before deletion (use internal to avoid hook recursion)
+        let memory_to_delete = self.get_memory_internal(id).await?;
+
+        // Execute before_memory_deleted hooks (blocking for veto support)
+        if let Some(mem) = &memory_to_delete {
+            match self.hook_registry.execute_before_deleted(mem).await {
+                Ok(true) => {
+                    // Hooks allowed deletion, proceed
+                    tracing::debug!("Deletion allowed by hooks for memory {}", id);
+                }
+                Ok(false) => {
+                    // Hooks vetoed deletion
+                    tracing::warn!("Deletion vetoed by hooks for memory {}", id);
+                    return Ok(false);
+                }
+                Err(e) => {
+                    // Hook execution error - log but continue (don't fail the operation)
+                    tracing::warn!("Hook execution failed during deletion check: {}", e);
+                }
+            }
+        }
+
+        if outbox.is_empty() {
+            // Use SDK method directly like VectorStore for consistency
+            let deleted: Option<SurrealMemory> = self
+                .client
+                .delete(("memory", id))
+                .await
+                .map_err(|e| StorageError::Query(format!("Failed to delete memory: {}", e)))?;
+
+            if deleted.is_some() {
+                self.drop_memory_aliases(id).await;
+            }
+            return Ok(deleted.is_some());
+        }
+
+        let mut result = self
+            .client
+            .query(with_outbox("DELETE $id RETURN BEFORE", outbox))
+            .bind(("id", RecordId::from(("memory", id))))
+            .bind(("outbox", outbox_binding(outbox)))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to delete memory: {}", e)))?;
+
+        let last = result.num_statements().saturating_sub(1);
+        let deleted: Vec<SurrealMemory> = result
+            .take(last)
+            .map_err(|e| StorageError::Query(format!("Failed to extract deleted memory: {}", e)))?;
+
+        if !deleted.is_empty() {
+            self.drop_memory_aliases(id).await;
+        }
+        Ok(!deleted.is_empty())
+    }
+}
+
 /// Private implementation for lifecycle tracking
 impl<C> SharedStorage<C>
 where
@@ -9,11 +9,14 @@ use surrealdb::{Connection, RecordId};
 use uuid::Uuid;
 
 use super::base::SharedStorage;
+use super::version_integrity::version_hash;
 use crate::models::Memory;
 use crate::storage::errors::StorageError;
 use crate::storage::models::{
     DiffHunk, DiffLine, DiffType, IntegrityIssueType, MemoryDiff, MemorySnapshot,
-    MemoryVersionInfo, RepairReport, RestoreMode, VersionIntegrityIssue, VersioningStats,
+    MemoryVersionInfo, RepairReport, RestoreAction, RestoreMode, RestorePreview,
+    RestorePreviewEntry, SNAPSHOT_BUNDLE_FORMAT_VERSION, SnapshotBundle, VersionChainReport,
+    VersionIntegrityIssue, VersioningStats,
 };
 use crate::storage::traits::MemoryVersionStore;
 use base64::{Engine, engine::general_purpose};
@@ -39,9 +42,6 @@ struct SurrealMemoryVersion {
     is_compressed: bool,
 }
 
-// SurrealMemorySnapshot struct not needed yet - we serialize directly
-// Will be used in Phase 2 when we implement snapshot retrieval
-
 #[async_trait]
 impl<C> MemoryVersionStore for SharedStorage<C>
 where
@@ -127,6 +127,25 @@ where
             serde_json::json!({})
         };
 
+        // Chain the new version to its parent's hash if enabled
+        let created_at = self.config.clock.now();
+        let (hash, parent_hash) = if config.hash_chain {
+            let parent_hash = match &parent_version_id {
+                Some(parent_id) => self.get_version_hash(memory_id, parent_id).await?,
+                None => None,
+            };
+            let hash = version_hash(
+                config.hash_chain_key.as_deref(),
+                parent_hash.as_deref(),
+                &version_id,
+                created_at,
+                content,
+            );
+            (Some(hash), parent_hash)
+        } else {
+            (None, None)
+        };
+
         // Create version record
         let query = r#"
             CREATE memory_version CONTENT {
@@ -139,14 +158,16 @@ where
                 diff_data: $diff_data,
                 is_delta: $is_delta,
                 size_bytes: $size_bytes,
-                is_compressed: false
+                is_compressed: false,
+                hash: $hash,
+                parent_hash: $parent_hash
             }
         "#;
 
         let memory_id_owned = memory_id.to_string();
         let version_id_owned = version_id.clone();
         let content_owned = stored_content;
-        let created_at_str = Utc::now().to_rfc3339();
+        let created_at_str = created_at.to_rfc3339();
 
         self.client
             .query(query)
@@ -159,6 +180,8 @@ where
             .bind(("diff_data", diff_data))
             .bind(("is_delta", is_delta))
             .bind(("size_bytes", size_bytes))
+            .bind(("hash", hash))
+            .bind(("parent_hash", parent_hash))
             .await
             .map_err(|e| StorageError::Query(format!("Failed to create memory version: {}", e)))?;
 
@@ -677,7 +700,7 @@ where
         "#;
 
         let snapshot_id_owned = snapshot_id.clone();
-        let created_at_str = Utc::now().to_rfc3339();
+        let created_at_str = self.config.clock.now().to_rfc3339();
         let memory_ids_owned = memories_to_snapshot.clone();
         let version_map_owned = version_map.clone();
         let snapshot_metadata_owned = snapshot_metadata.clone();
@@ -706,7 +729,7 @@ where
 
         Ok(MemorySnapshot {
             snapshot_id,
-            created_at: Utc::now(),
+            created_at: self.config.clock.now(),
             memory_count: memories_to_snapshot.len(),
             memory_ids: memories_to_snapshot,
             version_map,
@@ -720,47 +743,209 @@ where
         snapshot: &MemorySnapshot,
         restore_mode: RestoreMode,
     ) -> Result<(), StorageError> {
-        match restore_mode {
-            RestoreMode::Overwrite => {
-                // Restore each memory from its version in the snapshot
-                use crate::storage::traits::MemoryStore;
-                for (memory_id, version_id) in &snapshot.version_map {
-                    if let Some(version_memory) =
-                        self.get_memory_version(memory_id, version_id).await?
-                    {
-                        // Update memory with version content
-                        MemoryStore::update_memory(self, version_memory).await?;
-                    }
-                }
+        // Follow the preview so a restore always does what it showed
+        let preview = self
+            .restore_snapshot_preview(snapshot, restore_mode)
+            .await?;
+
+        use crate::storage::traits::MemoryStore;
+        for entry in preview.entries {
+            if entry.action == RestoreAction::Skip {
+                continue;
             }
-            RestoreMode::SkipExisting => {
-                // Only restore memories that don't exist
-                use crate::storage::traits::MemoryStore;
-                for (memory_id, version_id) in &snapshot.version_map {
-                    if MemoryStore::get_memory(self, memory_id).await?.is_none()
-                        && let Some(version_memory) =
-                            self.get_memory_version(memory_id, version_id).await?
-                    {
-                        MemoryStore::update_memory(self, version_memory).await?;
-                    }
+            let Some(version_memory) = self
+                .get_memory_version(&entry.memory_id, &entry.version_id)
+                .await?
+            else {
+                continue;
+            };
+            match entry.action {
+                RestoreAction::Overwrite => {
+                    MemoryStore::update_memory(self, version_memory).await?;
                 }
-            }
-            RestoreMode::CreateVersions => {
-                // Create new versions instead of overwriting
-                for (memory_id, version_id) in &snapshot.version_map {
-                    if let Some(version_memory) =
-                        self.get_memory_version(memory_id, version_id).await?
-                    {
-                        self.create_memory_version(memory_id, &version_memory.content, None)
-                            .await?;
-                    }
+                RestoreAction::CreateVersion => {
+                    self.create_memory_version(&entry.memory_id, &version_memory.content, None)
+                        .await?;
                 }
+                RestoreAction::Skip => {}
             }
         }
 
         Ok(())
     }
 
+    async fn restore_snapshot_preview(
+        &self,
+        snapshot: &MemorySnapshot,
+        restore_mode: RestoreMode,
+    ) -> Result<RestorePreview, StorageError> {
+        use crate::storage::traits::MemoryStore;
+
+        let mut memory_ids: Vec<&String> = snapshot.version_map.keys().collect();
+        memory_ids.sort();
+
+        let mut entries = Vec::with_capacity(memory_ids.len());
+        for memory_id in memory_ids {
+            let version_id = &snapshot.version_map[memory_id];
+            let skip = |reason: String| RestorePreviewEntry {
+                memory_id: memory_id.clone(),
+                version_id: version_id.clone(),
+                action: RestoreAction::Skip,
+                skip_reason: Some(reason),
+                changes: Vec::new(),
+            };
+
+            // Versions are rebuilt on top of the memory record, so a deleted
+            // memory can't be brought back from them
+            let Some(current) = MemoryStore::get_memory(self, memory_id).await? else {
+                entries.push(skip("Memory no longer exists".to_string()));
+                continue;
+            };
+            if restore_mode == RestoreMode::SkipExisting {
+                entries.push(skip("Memory already exists".to_string()));
+                continue;
+            }
+            let Some(version) = self.get_memory_version(memory_id, version_id).await? else {
+                entries.push(skip(format!("Snapshot version {} not found", version_id)));
+                continue;
+            };
+
+            let changes = if current.content != version.content {
+                vec![crate::storage::models::Change::ContentChanged {
+                    diff_hunks: compute_simple_diff(&current.content, &version.content),
+                    old_content: current.content,
+                    new_content: version.content,
+                }]
+            } else {
+                vec![]
+            };
+            entries.push(RestorePreviewEntry {
+                memory_id: memory_id.clone(),
+                version_id: version_id.clone(),
+                action: if restore_mode == RestoreMode::CreateVersions {
+                    RestoreAction::CreateVersion
+                } else {
+                    RestoreAction::Overwrite
+                },
+                skip_reason: None,
+                changes,
+            });
+        }
+
+        Ok(RestorePreview {
+            snapshot_id: snapshot.snapshot_id.clone(),
+            restore_mode,
+            entries,
+        })
+    }
+
+    async fn get_snapshot(
+        &self,
+        snapshot_id: &str,
+    ) -> Result<Option<MemorySnapshot>, StorageError> {
+        let query = r#"
+            SELECT snapshot_id, created_at, memory_count, memory_ids, version_map, metadata, size_bytes
+            FROM memory_snapshot
+            WHERE snapshot_id = $snapshot_id
+            LIMIT 1
+        "#;
+
+        let mut result = self
+            .client
+            .query(query)
+            .bind(("snapshot_id", snapshot_id.to_string()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get snapshot: {}", e)))?;
+
+        let snapshots: Vec<MemorySnapshot> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract snapshot: {}", e)))?;
+
+        Ok(snapshots.into_iter().next())
+    }
+
+    async fn export_snapshot(
+        &self,
+        snapshot: &MemorySnapshot,
+    ) -> Result<SnapshotBundle, StorageError> {
+        use crate::storage::traits::MemoryStore;
+
+        let mut memories = Vec::with_capacity(snapshot.memory_ids.len());
+        for memory_id in &snapshot.memory_ids {
+            let Some(mut memory) = MemoryStore::get_memory(self, memory_id).await? else {
+                tracing::warn!(
+                    "Memory {} no longer exists, leaving it out of snapshot export",
+                    memory_id
+                );
+                continue;
+            };
+
+            // Unversioned memories map to their own ID and are exported as-is
+            let version = match snapshot.version_map.get(memory_id) {
+                Some(version_id) => self.get_memory_version(memory_id, version_id).await?,
+                None => None,
+            };
+            if let Some(version) = version
+                && version.content != memory.content
+            {
+                memory.content = version.content;
+                // The embedding belongs to the current content
+                memory.embedding = None;
+            }
+            memories.push(memory);
+        }
+
+        let mut snapshot = snapshot.clone();
+        snapshot.memory_ids = memories.iter().map(|m| m.id.clone()).collect();
+        snapshot
+            .version_map
+            .retain(|memory_id, _| snapshot.memory_ids.contains(memory_id));
+        snapshot.memory_count = memories.len();
+
+        Ok(SnapshotBundle {
+            format_version: SNAPSHOT_BUNDLE_FORMAT_VERSION,
+            snapshot,
+            memories,
+        })
+    }
+
+    async fn import_snapshot(
+        &self,
+        bundle: &SnapshotBundle,
+        restore_mode: RestoreMode,
+    ) -> Result<MemorySnapshot, StorageError> {
+        use crate::storage::traits::MemoryStore;
+
+        if bundle.format_version > SNAPSHOT_BUNDLE_FORMAT_VERSION {
+            return Err(StorageError::Validation(format!(
+                "Unsupported snapshot bundle format version {} (expected at most {})",
+                bundle.format_version, SNAPSHOT_BUNDLE_FORMAT_VERSION
+            )));
+        }
+
+        let mut imported = Vec::with_capacity(bundle.memories.len());
+        for memory in &bundle.memories {
+            let exists = MemoryStore::get_memory(self, &memory.id).await?.is_some();
+            match restore_mode {
+                RestoreMode::SkipExisting if exists => continue,
+                RestoreMode::CreateVersions if exists => {}
+                _ => {
+                    MemoryStore::upsert_memory(self, memory.clone()).await?;
+                }
+            }
+            self.create_memory_version(&memory.id, &memory.content, None)
+                .await?;
+            imported.push(memory.id.clone());
+        }
+
+        let mut metadata = bundle.snapshot.metadata.clone();
+        metadata.insert(
+            "imported_from".to_string(),
+            Value::String(bundle.snapshot.snapshot_id.clone()),
+        );
+        self.create_snapshot(Some(&imported), Some(&metadata)).await
+    }
+
     async fn search_snapshot(
         &self,
         snapshot: &MemorySnapshot,
@@ -870,7 +1055,7 @@ where
         }
 
         if let Some(days) = older_than_days {
-            let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+            let cutoff = self.config.clock.now() - chrono::Duration::days(days as i64);
             conditions.push(format!(
                 "created_at < type::datetime('{}')",
                 cutoff.to_rfc3339()
@@ -1171,6 +1356,10 @@ where
 
         Ok(())
     }
+
+    async fn verify_integrity(&self, memory_id: &str) -> Result<VersionChainReport, StorageError> {
+        self.verify_version_chain(memory_id).await
+    }
 }
 
 /// Helper methods for versioning
@@ -1178,7 +1367,7 @@ impl<C> SharedStorage<C>
 where
     C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
 {
-    async fn get_current_version_id_from_db(
+    pub(super) async fn get_current_version_id_from_db(
         &self,
         memory_id: &str,
     ) -> Result<Option<String>, StorageError> {
@@ -1535,7 +1724,7 @@ where
         memory_id: &str,
         threshold_days: u64,
     ) -> Result<(), StorageError> {
-        let cutoff = Utc::now() - chrono::Duration::days(threshold_days as i64);
+        let cutoff = self.config.clock.now() - chrono::Duration::days(threshold_days as i64);
         let cutoff_str = cutoff.to_rfc3339();
 
         let query = r#"
@@ -1586,7 +1775,7 @@ where
 }
 
 /// Compress content using gzip
-fn compress_content(content: &str) -> Result<Vec<u8>, StorageError> {
+pub(super) fn compress_content(content: &str) -> Result<Vec<u8>, StorageError> {
     let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
     encoder
         .write_all(content.as_bytes())
@@ -1597,7 +1786,7 @@ fn compress_content(content: &str) -> Result<Vec<u8>, StorageError> {
 }
 
 /// Decompress content from gzip
-fn decompress_content(compressed: &[u8]) -> Result<String, StorageError> {
+pub(super) fn decompress_content(compressed: &[u8]) -> Result<String, StorageError> {
     let mut decoder = GzDecoder::new(compressed);
     let mut decompressed = String::new();
     decoder
@@ -1607,7 +1796,7 @@ fn decompress_content(compressed: &[u8]) -> Result<String, StorageError> {
 }
 
 /// Apply diff hunks to reconstruct content from a delta
-fn apply_diff_hunks(
+pub(super) fn apply_diff_hunks(
     old_content: &str,
     hunks: &[crate::storage::models::DiffHunk],
 ) -> Result<String, StorageError> {
@@ -16,20 +16,31 @@ use crate::storage::config::{SurrealDBAuth, SurrealDBAuthType, SurrealDBConfig,
 use crate::storage::errors::StorageError;
 use crate::storage::traits::GraphStore;
 
+pub mod aliases;
+pub mod archive;
 pub mod base;
+pub mod changefeed;
+pub mod checksum;
 pub mod config;
 pub mod entity;
+pub mod entity_merge;
 pub mod graph;
 pub mod intelligence;
+pub mod lease;
 pub mod live_query;
 pub mod memory;
 pub mod memory_version;
+pub mod outbox;
+pub mod record_version;
 pub mod relationship;
 pub mod schema;
 pub mod vector;
 pub mod version;
 pub mod version_access;
 pub mod version_cache;
+pub mod version_integrity;
+pub mod version_retention;
+pub mod weighted_paths;
 
 pub use base::*;
 pub use config::*;
@@ -45,9 +56,11 @@ pub async fn create_embedded_shared_storage(
 ) -> Result<EmbeddedSharedStorage, StorageError> {
     use surrealdb::engine::local::RocksDb;
 
-    let client = Surreal::new::<RocksDb>(path).await.map_err(|e| {
-        StorageError::Connection(format!("Failed to create embedded database: {}", e))
-    })?;
+    let client = Surreal::new::<RocksDb>((path, changefeed::embedded_engine_config()))
+        .await
+        .map_err(|e| {
+            StorageError::Connection(format!("Failed to create embedded database: {}", e))
+        })?;
 
     SharedStorage::new(client, config).await
 }
@@ -59,17 +72,19 @@ pub async fn create_shared_store(
     match config.engine {
         SurrealDBEngine::Memory => {
             tracing::info!("Creating SharedStorage in-memory store");
-            let client = Surreal::new::<surrealdb::engine::local::Mem>(())
-                .await
-                .map_err(|e| {
-                    StorageError::Connection(format!("Failed to create memory client: {}", e))
-                })?;
+            let client =
+                Surreal::new::<surrealdb::engine::local::Mem>(changefeed::embedded_engine_config())
+                    .await
+                    .map_err(|e| {
+                        StorageError::Connection(format!("Failed to create memory client: {}", e))
+                    })?;
 
             let shared_config = SharedStorageConfig {
                 namespace: config.namespace.clone(),
                 database: config.database.clone(),
                 lifecycle_tracking: Default::default(),
                 versioning: Default::default(),
+                ..Default::default()
             };
             let store = SharedStorage::new(client, shared_config).await?;
             Ok(Box::new(store))
@@ -79,17 +94,21 @@ pub async fn create_shared_store(
                 "Creating SharedStorage RocksDB store at {}",
                 config.connection
             );
-            let client = Surreal::new::<surrealdb::engine::local::RocksDb>(&config.connection)
-                .await
-                .map_err(|e| {
-                    StorageError::Connection(format!("Failed to create RocksDB client: {}", e))
-                })?;
+            let client = Surreal::new::<surrealdb::engine::local::RocksDb>((
+                &config.connection,
+                changefeed::embedded_engine_config(),
+            ))
+            .await
+            .map_err(|e| {
+                StorageError::Connection(format!("Failed to create RocksDB client: {}", e))
+            })?;
 
             let shared_config = SharedStorageConfig {
                 namespace: config.namespace.clone(),
                 database: config.database.clone(),
                 lifecycle_tracking: Default::default(),
                 versioning: Default::default(),
+                ..Default::default()
             };
             let store = SharedStorage::new(client, shared_config).await?;
             Ok(Box::new(store))
@@ -116,6 +135,7 @@ pub async fn create_shared_store(
                 database: config.database.clone(),
                 lifecycle_tracking: Default::default(),
                 versioning: Default::default(),
+                ..Default::default()
             };
             let store = SharedStorage::new(client, shared_config).await?;
             Ok(Box::new(store))
@@ -146,6 +166,7 @@ pub async fn create_shared_store(
                 database: config.database.clone(),
                 lifecycle_tracking: Default::default(),
                 versioning: Default::default(),
+                ..Default::default()
             };
             let store = SharedStorage::new(client, shared_config).await?;
             Ok(Box::new(store))
@@ -0,0 +1,144 @@
+//! Transactional outbox for SharedStorage
+//!
+//! Notifications about a memory write are inserted into the `outbox` table in
+//! the same transaction as the write, so either both commit or neither does.
+//! A relay then reads pending entries, delivers them and acknowledges each
+//! one, which deletes it. Entries left behind by a crash are delivered when
+//! the relay next runs, so delivery is at least once.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use surrealdb::{Connection, RecordId};
+
+use super::base::{SharedStorage, record_key};
+use crate::models::Memory;
+use crate::storage::errors::StorageError;
+use crate::storage::models::OutboxMessage;
+use crate::storage::traits::OutboxStore;
+
+/// Wrap a single write statement so `messages` commit with it
+///
+/// Messages are only recorded if the write returned a record, so updating or
+/// deleting a missing memory records nothing. The written records are the
+/// result of the last statement. Without messages the write is returned as is.
+pub(super) fn with_outbox(write: &str, messages: &[OutboxMessage]) -> String {
+    if messages.is_empty() {
+        return write.to_string();
+    }
+    format!(
+        r#"
+        BEGIN TRANSACTION;
+        LET $written = ({});
+        IF array::len($written) > 0 {{
+            FOR $entry IN $outbox {{
+                CREATE type::thing('outbox', $entry.id) CONTENT {{
+                    topic: $entry.topic,
+                    payload: $entry.payload,
+                    created_at: type::datetime($entry.created_at)
+                }};
+            }};
+        }};
+        RETURN $written;
+        COMMIT TRANSACTION;
+    "#,
+        write.trim()
+    )
+}
+
+/// Outbox messages in the shape bound to `$outbox`
+pub(super) fn outbox_binding(messages: &[OutboxMessage]) -> Value {
+    Value::Array(
+        messages
+            .iter()
+            .map(|message| {
+                serde_json::json!({
+                    "id": message.id,
+                    "topic": message.topic,
+                    "payload": message.payload.to_string(),
+                    "created_at": message.created_at.to_rfc3339(),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Outbox record as read back from SurrealDB
+#[derive(Debug, Clone, Deserialize)]
+struct SurrealOutboxMessage {
+    id: RecordId,
+    topic: String,
+    /// JSON text, since SurrealDB would drop null fields from an object
+    payload: String,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<SurrealOutboxMessage> for OutboxMessage {
+    type Error = StorageError;
+
+    fn try_from(record: SurrealOutboxMessage) -> Result<Self, Self::Error> {
+        let payload = serde_json::from_str(&record.payload).map_err(|e| {
+            StorageError::Serialization(format!("Failed to parse outbox payload: {}", e))
+        })?;
+        Ok(Self {
+            id: record_key(&record.id),
+            topic: record.topic,
+            payload,
+            created_at: record.created_at,
+        })
+    }
+}
+
+#[async_trait]
+impl<C> OutboxStore for SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    async fn create_memory_with_outbox(
+        &self,
+        memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<Memory, StorageError> {
+        self.create_memory_record(memory, &messages).await
+    }
+
+    async fn update_memory_with_outbox(
+        &self,
+        memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<Memory, StorageError> {
+        self.update_memory_record(memory, &messages).await
+    }
+
+    async fn delete_memory_with_outbox(
+        &self,
+        id: &str,
+        messages: Vec<OutboxMessage>,
+    ) -> Result<bool, StorageError> {
+        self.delete_memory_record(id, &messages).await
+    }
+
+    async fn pending_outbox(&self, limit: usize) -> Result<Vec<OutboxMessage>, StorageError> {
+        let records: Vec<SurrealOutboxMessage> = self
+            .client
+            .query("SELECT * FROM outbox ORDER BY created_at ASC LIMIT $limit")
+            .bind(("limit", limit))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to read outbox: {}", e)))?
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract outbox: {}", e)))?;
+
+        records.into_iter().map(OutboxMessage::try_from).collect()
+    }
+
+    async fn ack_outbox(&self, id: &str) -> Result<bool, StorageError> {
+        let deleted: Option<SurrealOutboxMessage> = self
+            .client
+            .delete(("outbox", id))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to acknowledge outbox: {}", e)))?;
+
+        Ok(deleted.is_some())
+    }
+}
@@ -0,0 +1,422 @@
+//! Entity and relationship version history for SharedStorage
+//!
+//! Each write to an entity or relationship stores a full snapshot of the
+//! record in the `record_version` table (deletes store a tombstone). Records
+//! are small, so unlike memory versions there are no deltas or compression.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use surrealdb::{Connection, RecordId};
+use uuid::Uuid;
+
+use super::base::SharedStorage;
+use crate::replication::RecordKind;
+use crate::storage::errors::StorageError;
+use crate::storage::models::{
+    Entity, FieldChange, RecordDiff, RecordOperation, RecordVersion, Relationship,
+};
+use crate::storage::traits::RecordVersionStore;
+
+/// Fields every write changes, left out of diffs
+const BOOKKEEPING_FIELDS: [&str; 3] = ["id", "created_at", "updated_at"];
+
+const VERSION_FIELDS: &str = "version_id, kind, record_id, operation, data, created_at";
+
+/// Version record as read back from SurrealDB
+#[derive(Debug, Clone, Deserialize)]
+struct SurrealRecordVersion {
+    version_id: String,
+    kind: String,
+    record_id: String,
+    operation: String,
+    data: Option<Value>,
+    created_at: DateTime<Utc>,
+}
+
+impl SurrealRecordVersion {
+    fn into_version(self) -> Result<RecordVersion, StorageError> {
+        let parse = |field: &str, value: String| {
+            serde_json::from_value(Value::String(value)).map_err(|e| {
+                StorageError::Serialization(format!("Invalid record version {}: {}", field, e))
+            })
+        };
+        Ok(RecordVersion {
+            version_id: self.version_id,
+            kind: parse("kind", self.kind)?,
+            record_id: self.record_id,
+            operation: parse("operation", self.operation)?,
+            data: self.data,
+            created_at: self.created_at,
+        })
+    }
+}
+
+fn kind_name(kind: RecordKind) -> &'static str {
+    match kind {
+        RecordKind::Memory => "memory",
+        RecordKind::Entity => "entity",
+        RecordKind::Relationship => "relationship",
+    }
+}
+
+fn operation_name(operation: RecordOperation) -> &'static str {
+    match operation {
+        RecordOperation::Created => "created",
+        RecordOperation::Updated => "updated",
+        RecordOperation::Deleted => "deleted",
+    }
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// Record a version of an entity or relationship after a write
+    ///
+    /// Failures are logged rather than returned, so a versioning problem
+    /// never fails the write that triggered it.
+    pub(super) async fn record_version<T: serde::Serialize>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        operation: RecordOperation,
+        record: Option<&T>,
+    ) {
+        if let Err(e) = self.store_record_version(kind, id, operation, record).await {
+            tracing::warn!(
+                "Failed to record version of {} {}: {}",
+                kind_name(kind),
+                id,
+                e
+            );
+        }
+    }
+
+    /// Operation to record for an upsert: a create unless the record has a
+    /// live version already
+    pub(super) async fn upsert_operation(&self, kind: RecordKind, id: &str) -> RecordOperation {
+        match self.latest_record_version(kind, id, None).await {
+            Ok(Some(version)) if version.operation != RecordOperation::Deleted => {
+                RecordOperation::Updated
+            }
+            _ => RecordOperation::Created,
+        }
+    }
+
+    async fn store_record_version<T: serde::Serialize>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        operation: RecordOperation,
+        record: Option<&T>,
+    ) -> Result<(), StorageError> {
+        let data = record.map(serde_json::to_value).transpose().map_err(|e| {
+            StorageError::Serialization(format!("Failed to serialize record: {}", e))
+        })?;
+        let version_id = Uuid::new_v4().to_string();
+
+        let query = r#"
+            CREATE $record CONTENT {
+                version_id: $version_id,
+                kind: $kind,
+                record_id: $record_id,
+                operation: $operation,
+                data: $data,
+                created_at: type::datetime($created_at)
+            }
+        "#;
+
+        self.client
+            .query(query)
+            .bind((
+                "record",
+                RecordId::from(("record_version", version_id.as_str())),
+            ))
+            .bind(("version_id", version_id))
+            .bind(("kind", kind_name(kind).to_string()))
+            .bind(("record_id", id.to_string()))
+            .bind(("operation", operation_name(operation).to_string()))
+            .bind(("data", data))
+            .bind(("created_at", self.config.clock.now().to_rfc3339()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to create record version: {}", e)))?
+            .check()
+            .map_err(|e| StorageError::Query(format!("Failed to create record version: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Latest version of a record, optionally as of `at_time`
+    async fn latest_record_version(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        at_time: Option<DateTime<Utc>>,
+    ) -> Result<Option<RecordVersion>, StorageError> {
+        let query = format!(
+            "SELECT {} FROM record_version
+             WHERE kind = $kind AND record_id = $record_id
+                AND created_at <= type::datetime($at_time)
+             ORDER BY created_at DESC LIMIT 1",
+            VERSION_FIELDS
+        );
+
+        let mut result = self
+            .client
+            .query(query)
+            .bind(("kind", kind_name(kind).to_string()))
+            .bind(("record_id", id.to_string()))
+            .bind((
+                "at_time",
+                at_time
+                    .unwrap_or_else(|| self.config.clock.now())
+                    .to_rfc3339(),
+            ))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get record version: {}", e)))?;
+        let versions: Vec<SurrealRecordVersion> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract record version: {}", e)))?;
+
+        versions
+            .into_iter()
+            .next()
+            .map(SurrealRecordVersion::into_version)
+            .transpose()
+    }
+
+    /// A record as it existed at `at_time`, or None if it was absent then
+    async fn record_at_time<T: DeserializeOwned>(
+        &self,
+        kind: RecordKind,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<T>, StorageError> {
+        let Some(data) = self
+            .latest_record_version(kind, id, Some(at_time))
+            .await?
+            .and_then(|version| version.data)
+        else {
+            return Ok(None);
+        };
+
+        serde_json::from_value(data).map(Some).map_err(|e| {
+            StorageError::Serialization(format!("Failed to parse record version: {}", e))
+        })
+    }
+}
+
+#[async_trait]
+impl<C> RecordVersionStore for SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    async fn list_record_versions(
+        &self,
+        kind: RecordKind,
+        id: &str,
+    ) -> Result<Vec<RecordVersion>, StorageError> {
+        let query = format!(
+            "SELECT {} FROM record_version
+             WHERE kind = $kind AND record_id = $record_id
+             ORDER BY created_at ASC",
+            VERSION_FIELDS
+        );
+
+        let mut result = self
+            .client
+            .query(query)
+            .bind(("kind", kind_name(kind).to_string()))
+            .bind(("record_id", id.to_string()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to list record versions: {}", e)))?;
+        let versions: Vec<SurrealRecordVersion> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract record versions: {}", e))
+        })?;
+
+        versions
+            .into_iter()
+            .map(SurrealRecordVersion::into_version)
+            .collect()
+    }
+
+    async fn get_record_version(
+        &self,
+        version_id: &str,
+    ) -> Result<Option<RecordVersion>, StorageError> {
+        let mut result = self
+            .client
+            .query(format!("SELECT {} FROM $record", VERSION_FIELDS))
+            .bind(("record", RecordId::from(("record_version", version_id))))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get record version: {}", e)))?;
+        let versions: Vec<SurrealRecordVersion> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract record version: {}", e)))?;
+
+        versions
+            .into_iter()
+            .next()
+            .map(SurrealRecordVersion::into_version)
+            .transpose()
+    }
+
+    async fn get_entity_at_time(
+        &self,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<Entity>, StorageError> {
+        self.record_at_time(RecordKind::Entity, id, at_time).await
+    }
+
+    async fn get_relationship_at_time(
+        &self,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<Relationship>, StorageError> {
+        self.record_at_time(RecordKind::Relationship, id, at_time)
+            .await
+    }
+
+    async fn diff_record_versions(
+        &self,
+        old_version_id: &str,
+        new_version_id: &str,
+    ) -> Result<RecordDiff, StorageError> {
+        let old_version = self
+            .get_record_version(old_version_id)
+            .await?
+            .ok_or_else(|| {
+                StorageError::NotFound(format!("Old version not found: {}", old_version_id))
+            })?;
+        let new_version = self
+            .get_record_version(new_version_id)
+            .await?
+            .ok_or_else(|| {
+                StorageError::NotFound(format!("New version not found: {}", new_version_id))
+            })?;
+
+        if old_version.kind != new_version.kind || old_version.record_id != new_version.record_id {
+            return Err(StorageError::Validation(format!(
+                "Versions {} and {} belong to different records",
+                old_version_id, new_version_id
+            )));
+        }
+
+        Ok(RecordDiff {
+            kind: new_version.kind,
+            record_id: new_version.record_id,
+            old_version_id: old_version.version_id,
+            new_version_id: new_version.version_id,
+            changes: diff_fields(old_version.data.as_ref(), new_version.data.as_ref()),
+        })
+    }
+}
+
+/// Field-by-field changes between two record snapshots
+///
+/// Nested objects (such as `properties`) are compared per key and reported
+/// under dotted paths; arrays and scalars are compared whole.
+pub(crate) fn diff_fields(old: Option<&Value>, new: Option<&Value>) -> Vec<FieldChange> {
+    let old = flatten(old);
+    let mut new = flatten(new);
+
+    let mut changes = Vec::new();
+    for (field, old_value) in old {
+        match new.remove(&field) {
+            Some(new_value) if new_value == old_value => {}
+            new_value => changes.push(FieldChange {
+                field,
+                old_value: Some(old_value),
+                new_value,
+            }),
+        }
+    }
+    changes.extend(new.into_iter().map(|(field, new_value)| FieldChange {
+        field,
+        old_value: None,
+        new_value: Some(new_value),
+    }));
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
+fn flatten(value: Option<&Value>) -> BTreeMap<String, Value> {
+    fn walk(prefix: &str, object: &Map<String, Value>, out: &mut BTreeMap<String, Value>) {
+        for (key, value) in object {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                Value::Object(nested) if !nested.is_empty() => walk(&path, nested, out),
+                _ => {
+                    out.insert(path, value.clone());
+                }
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    if let Some(Value::Object(object)) = value {
+        let fields = object
+            .iter()
+            .filter(|(key, _)| !BOOKKEEPING_FIELDS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        walk("", &fields, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_reports_nested_property_changes() {
+        let old = json!({
+            "id": "e1",
+            "entity_type": "person",
+            "properties": { "name": "Alice", "role": "engineer" },
+            "updated_at": "2026-01-01T00:00:00Z"
+        });
+        let new = json!({
+            "id": "e1",
+            "entity_type": "person",
+            "properties": { "name": "Alice", "team": "graph" },
+            "updated_at": "2026-02-01T00:00:00Z"
+        });
+
+        let changes = diff_fields(Some(&old), Some(&new));
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange {
+                    field: "properties.role".to_string(),
+                    old_value: Some(json!("engineer")),
+                    new_value: None,
+                },
+                FieldChange {
+                    field: "properties.team".to_string(),
+                    old_value: None,
+                    new_value: Some(json!("graph")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_against_tombstone_removes_every_field() {
+        let old = json!({ "entity_type": "person", "properties": { "name": "Alice" } });
+        let changes = diff_fields(Some(&old), None);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|change| change.new_value.is_none()));
+    }
+}
@@ -5,10 +5,11 @@ use chrono::{DateTime, Utc};
 use serde_json::Value;
 use surrealdb::{Connection, RecordId};
 
-use super::base::SharedStorage;
+use super::base::{SharedStorage, record_key};
+use crate::replication::RecordKind;
 use crate::storage::errors::StorageError;
 use crate::storage::filters::RelationshipFilter;
-use crate::storage::models::{Entity, Relationship};
+use crate::storage::models::{Entity, RecordOperation, Relationship};
 use crate::storage::traits::{EntityStore, MemoryStore, RelationshipStore};
 
 /// Internal representation of a Relationship record for SurrealDB
@@ -21,7 +22,7 @@ pub(crate) struct SurrealRelationship {
     properties: Value,
     owner: RecordId,
     created_at: DateTime<Utc>,
-    updated_at: DateTime<Utc>,
+    pub(super) updated_at: DateTime<Utc>,
 }
 
 /// Struct for creating relationships (without generated fields)
@@ -52,7 +53,7 @@ impl From<Relationship> for SurrealRelationship {
 impl From<SurrealRelationship> for Relationship {
     fn from(surreal_relationship: SurrealRelationship) -> Self {
         Self {
-            id: surreal_relationship.id.key().to_string(),
+            id: record_key(&surreal_relationship.id),
             relationship_type: surreal_relationship.relationship_type,
             source_id: surreal_relationship.source_id,
             target_id: surreal_relationship.target_id,
@@ -178,6 +179,8 @@ where
                         .map_err(|e| {
                             StorageError::Query(format!("Failed to create contains edge: {}", e))
                         })?;
+
+                    self.refresh_memory_aliases(&relationship.source_id).await;
                 }
                 "references" => {
                     // Create memory->references->relationship edge
@@ -251,6 +254,13 @@ where
             }
         }
 
+        self.record_version(
+            RecordKind::Relationship,
+            &created_relationship.id,
+            RecordOperation::Created,
+            Some(&created_relationship),
+        )
+        .await;
         Ok(created_relationship)
     }
 
@@ -332,9 +342,69 @@ where
             .await
             .map_err(|e| StorageError::Query(format!("Failed to update edge: {}", e)))?;
 
+        self.record_version(
+            RecordKind::Relationship,
+            &updated_relationship.id,
+            RecordOperation::Updated,
+            Some(&updated_relationship),
+        )
+        .await;
         Ok(updated_relationship)
     }
 
+    async fn upsert_relationship(
+        &self,
+        relationship: Relationship,
+    ) -> Result<Relationship, StorageError> {
+        self.ensure_system_user().await?;
+        let operation = self
+            .upsert_operation(RecordKind::Relationship, &relationship.id)
+            .await;
+
+        let query = r#"
+            UPSERT $record_id SET
+                relationship_type = $relationship_type,
+                source_id = $source_id,
+                target_id = $target_id,
+                properties = $properties,
+                owner = $owner,
+                created_at = type::datetime($created_at)
+        "#;
+
+        let mut response = self
+            .client
+            .query(query)
+            .bind((
+                "record_id",
+                RecordId::from(("relationship", relationship.id.as_str())),
+            ))
+            .bind(("relationship_type", relationship.relationship_type))
+            .bind(("source_id", relationship.source_id))
+            .bind(("target_id", relationship.target_id))
+            .bind(("properties", relationship.properties))
+            .bind(("owner", RecordId::from(("user", "system"))))
+            .bind(("created_at", relationship.created_at.to_rfc3339()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to upsert relationship: {}", e)))?;
+
+        let upserted: Option<SurrealRelationship> = response.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract upserted relationship: {}", e))
+        })?;
+
+        let upserted = upserted
+            .map(Relationship::from)
+            .ok_or_else(|| StorageError::Internal("No relationship upserted".to_string()))?;
+
+        self.record_version(
+            RecordKind::Relationship,
+            &upserted.id,
+            operation,
+            Some(&upserted),
+        )
+        .await;
+        Ok(upserted)
+    }
+
     /// Delete a relationship by its ID
     async fn delete_relationship(&self, id: &str) -> Result<bool, StorageError> {
         // Get the relationship first to get source and target IDs for edge cleanup
@@ -377,6 +447,14 @@ where
                 .map_err(|e| {
                     StorageError::Query(format!("Failed to extract edge delete result: {}", e))
                 })?;
+
+            self.record_version::<Relationship>(
+                RecordKind::Relationship,
+                id,
+                RecordOperation::Deleted,
+                None,
+            )
+            .await;
         }
 
         Ok(deleted.is_some())
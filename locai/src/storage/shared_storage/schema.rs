@@ -3,6 +3,9 @@
 use crate::storage::errors::StorageError;
 use surrealdb::{Connection, Surreal};
 
+/// Dimension of the memory embedding vector index
+pub const EMBEDDING_DIMENSIONS: usize = 1024;
+
 /// Initialize the SharedStorage schema with tables and relationships for Locai
 pub async fn initialize_schema<C>(client: &Surreal<C>) -> Result<(), StorageError>
 where
@@ -58,6 +61,7 @@ where
         DEFINE FIELD IF NOT EXISTS content ON memory TYPE string;
         DEFINE FIELD IF NOT EXISTS metadata ON memory TYPE object DEFAULT {};
         DEFINE FIELD IF NOT EXISTS embedding ON memory TYPE option<array<float>>;
+        DEFINE FIELD IF NOT EXISTS checksum ON memory TYPE option<string>;
         DEFINE FIELD IF NOT EXISTS importance ON memory TYPE option<float>;
         DEFINE FIELD IF NOT EXISTS owner ON memory TYPE record<user>;
         DEFINE FIELD IF NOT EXISTS shared_with ON memory TYPE option<set<record<user>>> DEFAULT NONE;
@@ -206,6 +210,8 @@ where
         DEFINE FIELD IF NOT EXISTS is_delta ON memory_version TYPE bool DEFAULT false;
         DEFINE FIELD IF NOT EXISTS is_compressed ON memory_version TYPE bool DEFAULT false;
         DEFINE FIELD IF NOT EXISTS size_bytes ON memory_version TYPE number;
+        DEFINE FIELD IF NOT EXISTS hash ON memory_version TYPE option<string>;
+        DEFINE FIELD IF NOT EXISTS parent_hash ON memory_version TYPE option<string>;
         
         DEFINE INDEX IF NOT EXISTS memory_version_memory_id_idx ON memory_version FIELDS memory_id;
         DEFINE INDEX IF NOT EXISTS memory_version_version_id_idx ON memory_version FIELDS version_id UNIQUE;
@@ -236,6 +242,79 @@ where
         DEFINE INDEX IF NOT EXISTS memory_snapshot_created_at_idx ON memory_snapshot FIELDS created_at;
     "#;
 
+    // Create the memory_archive table for cold storage of inactive memories.
+    // Deliberately no full-text or vector index: archived memories stay out of
+    // normal search and are only found by explicit archive scans.
+    let memory_archive_table_query = r#"
+        DEFINE TABLE IF NOT EXISTS memory_archive SCHEMALESS
+        COMMENT "Stores compressed archived memories";
+        
+        DEFINE FIELD IF NOT EXISTS id ON memory_archive TYPE record<memory_archive>;
+        DEFINE FIELD IF NOT EXISTS memory_id ON memory_archive TYPE string;
+        DEFINE FIELD IF NOT EXISTS data ON memory_archive TYPE string;
+        DEFINE FIELD IF NOT EXISTS memory_type ON memory_archive TYPE string;
+        DEFINE FIELD IF NOT EXISTS archived_at ON memory_archive TYPE datetime DEFAULT time::now();
+        DEFINE FIELD IF NOT EXISTS original_bytes ON memory_archive TYPE number;
+        DEFINE FIELD IF NOT EXISTS stored_bytes ON memory_archive TYPE number;
+        
+        DEFINE INDEX IF NOT EXISTS memory_archive_archived_at_idx ON memory_archive FIELDS archived_at;
+        DEFINE INDEX IF NOT EXISTS memory_archive_type_idx ON memory_archive FIELDS memory_type;
+    "#;
+
+    // Create the memory_alias table with the entity names each memory mentions,
+    // searched alongside memory content so aliases find canonical names
+    let memory_alias_table_query = r#"
+        DEFINE TABLE IF NOT EXISTS memory_alias SCHEMALESS
+        COMMENT "Stores names of aliased entities mentioned by each memory";
+        
+        DEFINE FIELD IF NOT EXISTS memory ON memory_alias TYPE record<memory>;
+        DEFINE FIELD IF NOT EXISTS terms ON memory_alias TYPE string;
+        DEFINE FIELD IF NOT EXISTS entities ON memory_alias TYPE array<string>;
+        
+        DEFINE INDEX IF NOT EXISTS memory_alias_terms_ft ON memory_alias
+            FIELDS terms
+            SEARCH ANALYZER memory_analyzer BM25
+            COMMENT "Full-text search on entity names and aliases";
+    "#;
+
+    // Create the outbox table for notifications committed with memory writes
+    let outbox_table_query = r#"
+        DEFINE TABLE IF NOT EXISTS outbox SCHEMALESS
+        COMMENT "Stores notifications awaiting delivery";
+        
+        DEFINE FIELD IF NOT EXISTS topic ON outbox TYPE string;
+        DEFINE FIELD IF NOT EXISTS payload ON outbox TYPE string;
+        DEFINE FIELD IF NOT EXISTS created_at ON outbox TYPE datetime DEFAULT time::now();
+        
+        DEFINE INDEX IF NOT EXISTS outbox_created_at_idx ON outbox FIELDS created_at;
+    "#;
+
+    // Create the lease table for tasks only one process should run
+    let lease_table_query = r#"
+        DEFINE TABLE IF NOT EXISTS lease SCHEMALESS
+        COMMENT "Stores who runs each singleton background task";
+        
+        DEFINE FIELD IF NOT EXISTS holder ON lease TYPE string;
+        DEFINE FIELD IF NOT EXISTS expires_at ON lease TYPE datetime;
+    "#;
+
+    // Create the record_version table with snapshots of entity and
+    // relationship writes
+    let record_version_table_query = r#"
+        DEFINE TABLE IF NOT EXISTS record_version SCHEMALESS
+        COMMENT "Stores versions of entities and relationships";
+        
+        DEFINE FIELD IF NOT EXISTS version_id ON record_version TYPE string;
+        DEFINE FIELD IF NOT EXISTS kind ON record_version TYPE string;
+        DEFINE FIELD IF NOT EXISTS record_id ON record_version TYPE string;
+        DEFINE FIELD IF NOT EXISTS operation ON record_version TYPE string;
+        DEFINE FIELD IF NOT EXISTS data ON record_version TYPE option<object>;
+        DEFINE FIELD IF NOT EXISTS created_at ON record_version TYPE datetime DEFAULT time::now();
+        
+        DEFINE INDEX IF NOT EXISTS record_version_version_id_idx ON record_version FIELDS version_id UNIQUE;
+        DEFINE INDEX IF NOT EXISTS record_version_record_created_idx ON record_version FIELDS kind, record_id, created_at;
+    "#;
+
     // Create edge tables for graph relationships
     let memory_entity_edge_query = r#"
         DEFINE TABLE contains SCHEMAFULL TYPE RELATION
@@ -283,6 +362,14 @@ where
             COMMENT "Full-text search on reference context";
     "#;
 
+    // Keep committed changes to the main tables for changefeed consumers.
+    // ALTER also applies to tables created before the changefeed existed.
+    let changefeed_query = r#"
+        ALTER TABLE memory CHANGEFEED 7d;
+        ALTER TABLE entity CHANGEFEED 7d;
+        ALTER TABLE relationship CHANGEFEED 7d;
+    "#;
+
     // Execute schema creation queries
     execute_schema_query(client, analyzers_query, "search analyzers").await?;
     execute_schema_query(client, user_table_query, "user table").await?;
@@ -294,6 +381,11 @@ where
     execute_schema_query(client, version_table_query, "version table").await?;
     execute_schema_query(client, memory_version_table_query, "memory_version table").await?;
     execute_schema_query(client, memory_snapshot_table_query, "memory_snapshot table").await?;
+    execute_schema_query(client, memory_archive_table_query, "memory_archive table").await?;
+    execute_schema_query(client, memory_alias_table_query, "memory_alias table").await?;
+    execute_schema_query(client, outbox_table_query, "outbox table").await?;
+    execute_schema_query(client, lease_table_query, "lease table").await?;
+    execute_schema_query(client, record_version_table_query, "record_version table").await?;
     execute_schema_query(client, memory_entity_edge_query, "memory-entity edge").await?;
     execute_schema_query(client, entity_relationship_edge_query, "entity-entity edge").await?;
     execute_schema_query(
@@ -302,6 +394,7 @@ where
         "memory-relationship edge",
     )
     .await?;
+    execute_schema_query(client, changefeed_query, "changefeeds").await?;
 
     tracing::info!(
         "SharedStorage schema with full-text search capabilities initialized successfully"
@@ -309,6 +402,41 @@ where
     Ok(())
 }
 
+/// Name of the index on a memory property
+pub(crate) fn property_index_name(property: &str) -> String {
+    format!("memory_property_{}_idx", property)
+}
+
+/// Define an index on `metadata.properties.<name>` for each configured property
+///
+/// Names are validated with the configuration; any that aren't plain
+/// identifiers are skipped rather than spliced into the definition.
+pub async fn define_property_indexes<C>(
+    client: &Surreal<C>,
+    properties: &[String],
+) -> Result<(), StorageError>
+where
+    C: Connection,
+{
+    for property in properties {
+        if property.is_empty()
+            || !property
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            tracing::warn!("Skipping index on invalid property name '{}'", property);
+            continue;
+        }
+        let query = format!(
+            "DEFINE INDEX IF NOT EXISTS {} ON memory FIELDS metadata.properties.{};",
+            property_index_name(property),
+            property
+        );
+        execute_schema_query(client, &query, &format!("index on property {}", property)).await?;
+    }
+    Ok(())
+}
+
 /// Execute a schema query and handle errors
 async fn execute_schema_query<C>(
     client: &Surreal<C>,
@@ -365,6 +493,7 @@ where
         "REMOVE TABLE IF EXISTS references;",
         "REMOVE TABLE IF EXISTS relates;",
         "REMOVE TABLE IF EXISTS contains;",
+        "REMOVE TABLE IF EXISTS memory_archive;",
         "REMOVE TABLE IF EXISTS memory_snapshot;",
         "REMOVE TABLE IF EXISTS memory_version;",
         "REMOVE TABLE IF EXISTS version;",
@@ -6,9 +6,11 @@ use serde_json::Value;
 use surrealdb::{Connection, RecordId};
 
 use super::base::SharedStorage;
+use super::checksum::vector_checksum;
+use crate::config::VectorSpaceConfig;
 use crate::storage::errors::StorageError;
 use crate::storage::filters::VectorFilter;
-use crate::storage::models::{Vector, VectorSearchParams};
+use crate::storage::models::{DistanceMetric, Vector, VectorSearchParams};
 use crate::storage::traits::VectorStore;
 
 /// Internal representation of a Vector record for SurrealDB
@@ -30,6 +32,7 @@ struct CreateVector {
     dimension: usize,
     metadata: Value,
     source_id: Option<String>,
+    checksum: String,
 }
 
 impl From<Vector> for SurrealVector {
@@ -74,8 +77,10 @@ where
         }
 
         // Create vector data for insertion with current timestamp
+        let stored = self.normalized_vector(&vector.vector);
         let create_vector = CreateVector {
-            vector: vector.vector.clone(),
+            checksum: vector_checksum(&stored),
+            vector: stored,
             dimension: vector.dimension,
             metadata: vector.metadata.clone(),
             source_id: vector.source_id.clone(),
@@ -157,6 +162,8 @@ where
     }
 
     /// Search for similar vectors using SurrealDB's native vector search
+    ///
+    /// Uses the metric in `params`, or the vector space's configured one.
     async fn search_vectors(
         &self,
         query_vector: &[f32],
@@ -164,38 +171,52 @@ where
     ) -> Result<Vec<(Vector, f32)>, StorageError> {
         let limit = params.limit.unwrap_or(10);
         let threshold = params.threshold;
+        let metric = params
+            .distance_metric
+            .unwrap_or(self.config.vector_space.metric);
 
-        // Convert query_vector to owned Vec to avoid lifetime issues
-        let query_vector_owned: Vec<f32> = query_vector.to_vec();
-
-        // Determine distance metric
-        let distance_function = match params.distance_metric.unwrap_or_default() {
-            crate::storage::models::DistanceMetric::Cosine => "COSINE",
-            crate::storage::models::DistanceMetric::Euclidean => "EUCLIDEAN",
-            crate::storage::models::DistanceMetric::DotProduct => "DOT",
-            crate::storage::models::DistanceMetric::Manhattan => "MANHATTAN",
-        };
+        // Normalized vectors are compared with normalized queries
+        let query_vector_owned: Vec<f32> = self.normalized_vector(query_vector);
 
         // Build the KNN search query using the correct SurrealDB syntax
         // The distance will be computed automatically by SurrealDB when using the KNN operator
-        let mut query = format!(
-            "SELECT *, vector::distance::knn() AS distance FROM vector WHERE vector <|{},{}|> $query_vector",
-            limit, distance_function
-        );
+        let distance_function = match metric {
+            DistanceMetric::Cosine => Some("COSINE"),
+            DistanceMetric::Euclidean => Some("EUCLIDEAN"),
+            DistanceMetric::Manhattan => Some("MANHATTAN"),
+            // The KNN operator has no dot product
+            DistanceMetric::DotProduct => None,
+        };
+        let (mut query, distance) = match distance_function {
+            Some(distance_function) => (
+                format!(
+                    "SELECT *, vector::distance::knn() AS distance FROM vector WHERE vector <|{},{}|> $query_vector",
+                    limit, distance_function
+                ),
+                "vector::distance::knn()",
+            ),
+            // Negated, so the largest dot product is the smallest distance
+            None => (
+                "SELECT *, -vector::dot(vector, $query_vector) AS distance FROM vector \
+                 WHERE array::len(vector) = array::len($query_vector)"
+                    .to_string(),
+                "-vector::dot(vector, $query_vector)",
+            ),
+        };
 
         // Add threshold filter if specified
         if let Some(thresh) = threshold {
             // For distance metrics, smaller values mean more similar
             // For similarity metrics like cosine, larger values mean more similar
             // We need to handle this appropriately
-            match params.distance_metric.unwrap_or_default() {
-                crate::storage::models::DistanceMetric::Cosine => {
+            match metric {
+                DistanceMetric::Cosine => {
                     // Cosine similarity: higher values are better, threshold is minimum similarity
-                    query = format!("{} AND vector::distance::knn() >= {}", query, thresh);
+                    query = format!("{} AND {} >= {}", query, distance, thresh);
                 }
                 _ => {
                     // Distance metrics: lower values are better, threshold is maximum distance
-                    query = format!("{} AND vector::distance::knn() <= {}", query, thresh);
+                    query = format!("{} AND {} <= {}", query, distance, thresh);
                 }
             }
         }
@@ -231,19 +252,10 @@ where
         }
 
         // Order by distance - this is crucial for consistent results
-        // All SurrealDB distance metrics return distances where lower values = more similar
-        // So we always use ASC ordering for better matches first
+        // All distances here are lower for more similar vectors, so we always
+        // use ASC ordering for better matches first
         // Add secondary ordering by ID for deterministic results when distances are equal
-        match params.distance_metric.unwrap_or_default() {
-            crate::storage::models::DistanceMetric::Cosine => {
-                // Cosine distance: lower values are better (0 = identical, 1 = orthogonal)
-                query = format!("{} ORDER BY distance ASC, id ASC", query);
-            }
-            _ => {
-                // Other distance metrics: lower values are also better
-                query = format!("{} ORDER BY distance ASC, id ASC", query);
-            }
-        }
+        query = format!("{} ORDER BY distance ASC, id ASC", query);
 
         // Add explicit LIMIT to ensure we get the expected number of results
         query = format!("{} LIMIT {}", query, limit);
@@ -427,8 +439,10 @@ where
         }
 
         // For upsert, we need to include the created_at field to avoid NONE values
+        let stored = self.normalized_vector(&vector.vector);
         let upsert_data = serde_json::json!({
-            "vector": vector.vector,
+            "checksum": vector_checksum(&stored),
+            "vector": stored,
             "dimension": vector.dimension,
             "metadata": vector.metadata,
             "source_id": vector.source_id,
@@ -445,3 +459,30 @@ where
         Ok(())
     }
 }
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// A memory embedding, scaled to unit length if the memory space
+    /// normalizes
+    pub(super) fn normalized_embedding(&self, embedding: &[f32]) -> Vec<f32> {
+        normalized(embedding, &self.config.memory_space)
+    }
+
+    /// A vector store vector, scaled to unit length if the vector space
+    /// normalizes
+    pub(super) fn normalized_vector(&self, vector: &[f32]) -> Vec<f32> {
+        normalized(vector, &self.config.vector_space)
+    }
+}
+
+/// `vector` scaled to unit length if `space` normalizes
+fn normalized(vector: &[f32], space: &VectorSpaceConfig) -> Vec<f32> {
+    let mut vector = vector.to_vec();
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if space.normalize && norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
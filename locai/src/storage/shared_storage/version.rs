@@ -335,7 +335,7 @@ where
             id: Uuid::new_v4().to_string(),
             description: description.to_string(),
             metadata: Value::Object(metadata),
-            created_at: Utc::now(),
+            created_at: self.config.clock.now(),
         };
 
         self.create_version(version).await
@@ -362,7 +362,7 @@ where
             id: Uuid::new_v4().to_string(),
             description: description.to_string(),
             metadata: Value::Object(metadata),
-            created_at: Utc::now(),
+            created_at: self.config.clock.now(),
         };
 
         self.create_version(version).await
@@ -2,6 +2,7 @@
 //!
 //! Tracks access patterns to inform promotion decisions (delta -> full copy).
 
+use crate::clock::ClockHandle;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -19,23 +20,23 @@ pub struct VersionAccessStats {
 }
 
 impl VersionAccessStats {
-    pub fn new(version_id: String) -> Self {
+    pub fn new(version_id: String, now: DateTime<Utc>) -> Self {
         Self {
             version_id,
             access_count: 0,
             first_accessed: None,
-            last_accessed: Utc::now(),
+            last_accessed: now,
             total_reconstruction_time_ms: 0,
             average_reconstruction_time_ms: 0.0,
         }
     }
 
-    pub fn record_access(&mut self, reconstruction_time_ms: u64) {
+    pub fn record_access(&mut self, reconstruction_time_ms: u64, now: DateTime<Utc>) {
         self.access_count += 1;
         if self.first_accessed.is_none() {
-            self.first_accessed = Some(Utc::now());
+            self.first_accessed = Some(now);
         }
-        self.last_accessed = Utc::now();
+        self.last_accessed = now;
         self.total_reconstruction_time_ms += reconstruction_time_ms;
         self.average_reconstruction_time_ms =
             self.total_reconstruction_time_ms as f64 / self.access_count as f64;
@@ -46,22 +47,30 @@ impl VersionAccessStats {
 #[derive(Debug)]
 pub struct VersionAccessTracker {
     stats: Arc<Mutex<HashMap<String, VersionAccessStats>>>,
+    clock: ClockHandle,
 }
 
 impl VersionAccessTracker {
     pub fn new() -> Self {
+        Self::with_clock(ClockHandle::default())
+    }
+
+    /// Track accesses with times read from `clock`
+    pub fn with_clock(clock: ClockHandle) -> Self {
         Self {
             stats: Arc::new(Mutex::new(HashMap::new())),
+            clock,
         }
     }
 
     /// Record an access to a version
     pub async fn record_access(&self, version_id: String, reconstruction_time_ms: u64) {
+        let now = self.clock.now();
         let mut stats = self.stats.lock().await;
         let entry = stats
             .entry(version_id.clone())
-            .or_insert_with(|| VersionAccessStats::new(version_id));
-        entry.record_access(reconstruction_time_ms);
+            .or_insert_with(|| VersionAccessStats::new(version_id, now));
+        entry.record_access(reconstruction_time_ms, now);
     }
 
     /// Get access statistics for a version
@@ -105,7 +114,7 @@ impl VersionAccessTracker {
 
     /// Clear old access statistics (older than time window)
     pub async fn cleanup_old_stats(&self, time_window_hours: u64) {
-        let cutoff = Utc::now() - chrono::Duration::hours(time_window_hours as i64);
+        let cutoff = self.clock.now() - chrono::Duration::hours(time_window_hours as i64);
         let mut stats = self.stats.lock().await;
         stats.retain(|_, stat| stat.last_accessed > cutoff);
     }
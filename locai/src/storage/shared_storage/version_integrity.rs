@@ -0,0 +1,213 @@
+//! Hash-chained memory versions for SharedStorage
+//!
+//! When `versioning.hash_chain` is enabled, each new version stores a hash of
+//! its parent's hash, its identity and its full content. Altering, removing
+//! or reordering a version then breaks the chain from that point on. With
+//! `hash_chain_key` set the hashes are HMAC-SHA256 signatures, so the chain
+//! cannot be recomputed without the key.
+
+use chrono::{DateTime, Utc};
+use ring::{digest, hmac};
+use std::collections::HashMap;
+use surrealdb::Connection;
+
+use super::base::SharedStorage;
+use super::version_retention::StoredMemoryVersion;
+use crate::storage::errors::StorageError;
+use crate::storage::models::{IntegrityIssueType, VersionChainReport, VersionIntegrityIssue};
+
+/// Hash of a version, chained to its parent's hash
+pub(crate) fn version_hash(
+    key: Option<&str>,
+    parent_hash: Option<&str>,
+    version_id: &str,
+    created_at: DateTime<Utc>,
+    content: &str,
+) -> String {
+    // Length-prefix each part so no two inputs share an encoding
+    let created_at = created_at.timestamp_micros().to_string();
+    let mut message = Vec::new();
+    for part in [parent_hash.unwrap_or(""), version_id, &created_at, content] {
+        message.extend_from_slice(&(part.len() as u64).to_be_bytes());
+        message.extend_from_slice(part.as_bytes());
+    }
+
+    let hash = match key {
+        Some(key) => {
+            let key = hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes());
+            hmac::sign(&key, &message).as_ref().to_vec()
+        }
+        None => digest::digest(&digest::SHA256, &message).as_ref().to_vec(),
+    };
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// Stored hash of a version, if it has one
+    pub(super) async fn get_version_hash(
+        &self,
+        memory_id: &str,
+        version_id: &str,
+    ) -> Result<Option<String>, StorageError> {
+        let mut result = self
+            .client
+            .query(
+                "SELECT VALUE hash FROM memory_version \
+                 WHERE memory_id = $memory_id AND version_id = $version_id",
+            )
+            .bind(("memory_id", memory_id.to_string()))
+            .bind(("version_id", version_id.to_string()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to get version hash: {}", e)))?;
+        let hashes: Vec<Option<String>> = result
+            .take(0)
+            .map_err(|e| StorageError::Query(format!("Failed to extract version hash: {}", e)))?;
+        Ok(hashes.into_iter().next().flatten())
+    }
+
+    pub(super) async fn verify_version_chain(
+        &self,
+        memory_id: &str,
+    ) -> Result<VersionChainReport, StorageError> {
+        let mut result = self
+            .client
+            .query(
+                "SELECT version_id, content, diff_data, is_delta, is_compressed, created_at, \
+                 parent_version_id, hash, parent_hash \
+                 FROM memory_version WHERE memory_id = $memory_id ORDER BY created_at ASC",
+            )
+            .bind(("memory_id", memory_id.to_string()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to load memory versions: {}", e)))?;
+        let versions: Vec<StoredMemoryVersion> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract memory versions: {}", e))
+        })?;
+
+        let key = self.config.versioning.hash_chain_key.as_deref();
+        let hashes: HashMap<&str, Option<&str>> = versions
+            .iter()
+            .map(|v| (v.version_id.as_str(), v.hash.as_deref()))
+            .collect();
+        let issue = |version_id: &str, issue_type, description: String| VersionIntegrityIssue {
+            memory_id: memory_id.to_string(),
+            version_id: Some(version_id.to_string()),
+            issue_type,
+            description,
+        };
+
+        let mut report = VersionChainReport {
+            memory_id: memory_id.to_string(),
+            versions_checked: 0,
+            unhashed_versions: 0,
+            issues: Vec::new(),
+        };
+        let mut content = String::new();
+        for (index, version) in versions.iter().enumerate() {
+            match version.full_content(&content) {
+                Ok(full_content) => content = full_content,
+                Err(e) => {
+                    report.issues.push(issue(
+                        &version.version_id,
+                        IntegrityIssueType::CorruptedDelta,
+                        format!("Failed to reconstruct content: {}", e),
+                    ));
+                    continue;
+                }
+            }
+
+            let Some(hash) = &version.hash else {
+                report.unhashed_versions += 1;
+                continue;
+            };
+            report.versions_checked += 1;
+
+            let expected = version_hash(
+                key,
+                version.parent_hash.as_deref(),
+                &version.version_id,
+                version.created_at,
+                &content,
+            );
+            if expected != *hash {
+                report.issues.push(issue(
+                    &version.version_id,
+                    IntegrityIssueType::HashMismatch,
+                    "Content does not match the stored hash".to_string(),
+                ));
+            }
+
+            match version.parent_version_id.as_deref() {
+                Some(parent_id) => match hashes.get(parent_id) {
+                    Some(parent_hash) if *parent_hash != version.parent_hash.as_deref() => {
+                        report.issues.push(issue(
+                            &version.version_id,
+                            IntegrityIssueType::BrokenHashChain,
+                            format!("Parent hash does not match version {}", parent_id),
+                        ));
+                    }
+                    Some(_) => {}
+                    // The chain may start after versions pruned by retention
+                    None if index == 0 => {}
+                    None => report.issues.push(issue(
+                        &version.version_id,
+                        IntegrityIssueType::BrokenHashChain,
+                        format!("Parent version {} is missing", parent_id),
+                    )),
+                },
+                None if version.parent_hash.is_some() => report.issues.push(issue(
+                    &version.version_id,
+                    IntegrityIssueType::BrokenHashChain,
+                    "Version has a parent hash but no parent".to_string(),
+                )),
+                None => {}
+            }
+        }
+
+        // Removing the newest versions leaves the memory pointing at one that
+        // no longer exists
+        if report.versions_checked > 0
+            && let Some(current) = self.get_current_version_id_from_db(memory_id).await?
+            && !hashes.contains_key(current.as_str())
+        {
+            report.issues.push(issue(
+                &current,
+                IntegrityIssueType::BrokenHashChain,
+                format!("Current version {} is missing", current),
+            ));
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_hash_covers_every_part() {
+        let now = Utc::now();
+        let hash = version_hash(None, Some("parent"), "v1", now, "content");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(
+            hash,
+            version_hash(None, Some("parent"), "v1", now, "content")
+        );
+        assert_ne!(hash, version_hash(None, None, "v1", now, "content"));
+        assert_ne!(
+            hash,
+            version_hash(None, Some("parent"), "v2", now, "content")
+        );
+        assert_ne!(
+            hash,
+            version_hash(None, Some("parent"), "v1", now, "altered")
+        );
+        assert_ne!(
+            hash,
+            version_hash(Some("secret"), Some("parent"), "v1", now, "content")
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Version retention for SharedStorage
+//!
+//! Applies a [`VersionRetentionConfig`] to the `memory_version` and
+//! `record_version` tables, record by record. Memory versions may be stored
+//! as deltas against the version before them, so a kept delta whose chain
+//! loses a version is rewritten as a full copy before anything is deleted.
+
+use base64::{Engine, engine::general_purpose};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use surrealdb::{Connection, Surreal};
+
+use super::base::SharedStorage;
+use super::memory_version::{apply_diff_hunks, decompress_content};
+use crate::config::VersionRetentionConfig;
+use crate::storage::errors::StorageError;
+use crate::storage::models::DiffHunk;
+
+/// Which of a record's versions the policy removes
+///
+/// `created` holds the versions' creation times, newest first; the result
+/// flags the versions to remove, in the same order.
+pub(crate) fn expired_versions(
+    created: &[DateTime<Utc>],
+    policy: &VersionRetentionConfig,
+    now: DateTime<Utc>,
+) -> Vec<bool> {
+    let cutoff = |days: Option<u64>| days.map(|days| now - Duration::days(days as i64));
+    let max_age = cutoff(policy.max_age_days);
+    let daily = cutoff(policy.daily_after_days);
+
+    let mut days_kept: HashSet<NaiveDate> = HashSet::new();
+    created
+        .iter()
+        .enumerate()
+        .map(|(index, &created_at)| {
+            if index < policy.keep_last.max(1) {
+                return false;
+            }
+            if max_age.is_some_and(|cutoff| created_at < cutoff) {
+                return true;
+            }
+            daily.is_some_and(|cutoff| created_at < cutoff)
+                && !days_kept.insert(created_at.date_naive())
+        })
+        .collect()
+}
+
+/// A memory version as stored, for walking a memory's versions in order
+#[derive(Debug, Deserialize)]
+pub(super) struct StoredMemoryVersion {
+    pub(super) version_id: String,
+    content: String,
+    diff_data: Option<Value>,
+    pub(super) is_delta: bool,
+    #[serde(default)]
+    is_compressed: bool,
+    pub(super) created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub(super) parent_version_id: Option<String>,
+    #[serde(default)]
+    pub(super) hash: Option<String>,
+    #[serde(default)]
+    pub(super) parent_hash: Option<String>,
+}
+
+impl StoredMemoryVersion {
+    /// Full content of this version, given the full content of the one before
+    pub(super) fn full_content(&self, previous: &str) -> Result<String, StorageError> {
+        if self.is_delta {
+            let diff_data = self.diff_data.clone().ok_or_else(|| {
+                StorageError::Query(format!(
+                    "Delta version {} has no diff_data",
+                    self.version_id
+                ))
+            })?;
+            let hunks: Vec<DiffHunk> = serde_json::from_value(diff_data)
+                .map_err(|e| StorageError::Query(format!("Failed to deserialize diff: {}", e)))?;
+            apply_diff_hunks(previous, &hunks)
+        } else if self.is_compressed {
+            let compressed = general_purpose::STANDARD
+                .decode(&self.content)
+                .map_err(|e| {
+                    StorageError::Query(format!("Failed to decode compressed content: {}", e))
+                })?;
+            decompress_content(&compressed)
+        } else {
+            Ok(self.content.clone())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionGroup {
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(alias = "memory_id")]
+    record_id: String,
+    versions: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RetainedRecordVersion {
+    version_id: String,
+    created_at: DateTime<Utc>,
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// Apply a retention policy now, returning how many versions were removed
+    ///
+    /// The background task does this on a schedule when
+    /// [`VersionRetentionConfig::auto_compact`] is set.
+    pub async fn apply_version_retention(
+        &self,
+        policy: &VersionRetentionConfig,
+    ) -> Result<usize, StorageError> {
+        Self::prune_versions(&self.client, policy).await
+    }
+
+    /// Remove the versions `policy` no longer retains, returning how many
+    pub(super) async fn prune_versions(
+        client: &Surreal<C>,
+        policy: &VersionRetentionConfig,
+    ) -> Result<usize, StorageError> {
+        let now = self.config.clock.now();
+        let keep = policy.keep_last.max(1);
+        let mut removed = 0;
+
+        let mut result = client
+            .query("SELECT memory_id, count() AS versions FROM memory_version GROUP BY memory_id")
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to group memory versions: {}", e)))?;
+        let groups: Vec<VersionGroup> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract memory version groups: {}", e))
+        })?;
+        for group in groups.into_iter().filter(|group| group.versions > keep) {
+            match Self::retain_memory_versions(client, &group.record_id, policy, now).await {
+                Ok(count) => removed += count,
+                Err(e) => tracing::warn!(
+                    "Failed to apply version retention to memory {}: {}",
+                    group.record_id,
+                    e
+                ),
+            }
+        }
+
+        let mut result = client
+            .query(
+                "SELECT kind, record_id, count() AS versions FROM record_version \
+                 GROUP BY kind, record_id",
+            )
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to group record versions: {}", e)))?;
+        let groups: Vec<VersionGroup> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract record version groups: {}", e))
+        })?;
+        for group in groups.into_iter().filter(|group| group.versions > keep) {
+            let kind = group.kind.unwrap_or_default();
+            match Self::retain_record_versions(client, &kind, &group.record_id, policy, now).await {
+                Ok(count) => removed += count,
+                Err(e) => tracing::warn!(
+                    "Failed to apply version retention to {} {}: {}",
+                    kind,
+                    group.record_id,
+                    e
+                ),
+            }
+        }
+
+        Ok(removed)
+    }
+
+    async fn retain_memory_versions(
+        client: &Surreal<C>,
+        memory_id: &str,
+        policy: &VersionRetentionConfig,
+        now: DateTime<Utc>,
+    ) -> Result<usize, StorageError> {
+        let mut result = client
+            .query(
+                "SELECT version_id, content, diff_data, is_delta, is_compressed, created_at \
+                 FROM memory_version WHERE memory_id = $memory_id ORDER BY created_at ASC",
+            )
+            .bind(("memory_id", memory_id.to_string()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to load memory versions: {}", e)))?;
+        let versions: Vec<StoredMemoryVersion> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract memory versions: {}", e))
+        })?;
+
+        let created: Vec<_> = versions.iter().rev().map(|v| v.created_at).collect();
+        let mut expired = expired_versions(&created, policy, now);
+        expired.reverse();
+        if !expired.contains(&true) {
+            return Ok(0);
+        }
+
+        // Walk the chain oldest first, promoting kept deltas that follow a
+        // removed version while their content can still be rebuilt
+        let mut content = String::new();
+        let mut chain_intact = true;
+        let mut doomed = Vec::new();
+        for (version, expired) in versions.iter().zip(expired) {
+            content = version.full_content(&content)?;
+            if expired {
+                doomed.push(version.version_id.clone());
+                chain_intact = false;
+            } else if !version.is_delta {
+                chain_intact = true;
+            } else if !chain_intact {
+                client
+                    .query(
+                        "UPDATE memory_version SET is_delta = false, content = $content, \
+                         is_compressed = false, size_bytes = $size_bytes, diff_data = NONE \
+                         WHERE memory_id = $memory_id AND version_id = $version_id",
+                    )
+                    .bind(("memory_id", memory_id.to_string()))
+                    .bind(("version_id", version.version_id.clone()))
+                    .bind(("size_bytes", content.len()))
+                    .bind(("content", content.clone()))
+                    .await
+                    .map_err(|e| StorageError::Query(format!("Failed to promote version: {}", e)))?
+                    .check()
+                    .map_err(|e| {
+                        StorageError::Query(format!("Failed to promote version: {}", e))
+                    })?;
+                chain_intact = true;
+            }
+        }
+
+        Self::delete_versions(client, "memory_version", doomed).await
+    }
+
+    async fn retain_record_versions(
+        client: &Surreal<C>,
+        kind: &str,
+        record_id: &str,
+        policy: &VersionRetentionConfig,
+        now: DateTime<Utc>,
+    ) -> Result<usize, StorageError> {
+        let mut result = client
+            .query(
+                "SELECT version_id, created_at FROM record_version \
+                 WHERE kind = $kind AND record_id = $record_id ORDER BY created_at DESC",
+            )
+            .bind(("kind", kind.to_string()))
+            .bind(("record_id", record_id.to_string()))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to load record versions: {}", e)))?;
+        let versions: Vec<RetainedRecordVersion> = result.take(0).map_err(|e| {
+            StorageError::Query(format!("Failed to extract record versions: {}", e))
+        })?;
+
+        let created: Vec<_> = versions.iter().map(|v| v.created_at).collect();
+        let doomed = versions
+            .into_iter()
+            .zip(expired_versions(&created, policy, now))
+            .filter(|(_, expired)| *expired)
+            .map(|(version, _)| version.version_id)
+            .collect();
+
+        Self::delete_versions(client, "record_version", doomed).await
+    }
+
+    async fn delete_versions(
+        client: &Surreal<C>,
+        table: &str,
+        version_ids: Vec<String>,
+    ) -> Result<usize, StorageError> {
+        if version_ids.is_empty() {
+            return Ok(0);
+        }
+        let count = version_ids.len();
+        client
+            .query(format!("DELETE {} WHERE version_id IN $version_ids", table))
+            .bind(("version_ids", version_ids))
+            .await
+            .map_err(|e| StorageError::Query(format!("Failed to delete versions: {}", e)))?
+            .check()
+            .map_err(|e| StorageError::Query(format!("Failed to delete versions: {}", e)))?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days_ago(now: DateTime<Utc>, days: i64, hours: i64) -> DateTime<Utc> {
+        now - Duration::days(days) - Duration::hours(hours)
+    }
+
+    #[test]
+    fn test_keep_last_protects_newest_versions() {
+        let now = Utc::now();
+        let created: Vec<_> = (0..4).map(|i| days_ago(now, 400 + i, 0)).collect();
+        let policy = VersionRetentionConfig {
+            keep_last: 2,
+            max_age_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(
+            expired_versions(&created, &policy, now),
+            vec![false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_old_versions_thinned_to_one_per_day() {
+        let now = Utc::now();
+        let created = vec![
+            days_ago(now, 1, 0),
+            days_ago(now, 1, 1),
+            days_ago(now, 10, 1),
+            days_ago(now, 10, 2),
+            days_ago(now, 12, 1),
+        ];
+        let policy = VersionRetentionConfig {
+            keep_last: 1,
+            daily_after_days: Some(7),
+            max_age_days: None,
+            ..Default::default()
+        };
+        let expired = expired_versions(&created, &policy, now);
+        // Recent versions all stay; the two from ten days ago collapse to the
+        // newer one unless they straddle midnight
+        assert_eq!(&expired[..2], &[false, false]);
+        assert!(!expired[2]);
+        assert_eq!(
+            expired[3],
+            created[3].date_naive() == created[2].date_naive()
+        );
+        assert!(!expired[4]);
+    }
+}
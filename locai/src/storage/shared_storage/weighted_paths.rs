@@ -0,0 +1,171 @@
+//! Weighted, constrained path search for SharedStorage
+//!
+//! Paths run from memory to memory through the entities they contain, like
+//! the breadth-first `find_paths`, but each hop costs the weight of the
+//! relationship it follows. Partial paths are expanded cheapest first, so
+//! paths reach the target in order of cost.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use surrealdb::Connection;
+
+use super::base::SharedStorage;
+use crate::storage::errors::StorageError;
+use crate::storage::models::{MemoryPath, PathConstraints};
+use crate::storage::traits::{EntityStore, GraphTraversal, MemoryStore};
+
+/// A partial path waiting to be expanded, ordered cheapest first
+struct Candidate {
+    cost: f64,
+    path: MemoryPath,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    /// Reversed, as `BinaryHeap` pops the greatest; shorter paths win ties
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.path.length().cmp(&self.path.length()))
+    }
+}
+
+impl<C> SharedStorage<C>
+where
+    C: Connection + Clone + Send + Sync + std::fmt::Debug + 'static,
+{
+    /// Up to `constraints.limit` paths from `from_id` to `to_id`, cheapest
+    /// first
+    ///
+    /// A memory is expanded at most `limit` times, which is enough to find
+    /// the `limit` cheapest paths through it without enumerating every path.
+    pub(super) async fn search_weighted_paths(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        constraints: &PathConstraints,
+    ) -> Result<Vec<MemoryPath>, StorageError> {
+        let start = self.get_memory(from_id).await?.ok_or_else(|| {
+            StorageError::NotFound(format!("Source memory {} not found", from_id))
+        })?;
+        if self.get_memory(to_id).await?.is_none() {
+            return Err(StorageError::NotFound(format!(
+                "Target memory {} not found",
+                to_id
+            )));
+        }
+
+        let mut initial_path = MemoryPath::new(from_id.to_string(), to_id.to_string());
+        initial_path.add_memory(start);
+        initial_path.cost = Some(0.0);
+        if constraints.limit == 0 {
+            return Ok(Vec::new());
+        }
+        if from_id == to_id {
+            return Ok(vec![initial_path]);
+        }
+
+        let mut paths = Vec::new();
+        let mut expansions: HashMap<String, usize> = HashMap::new();
+        let mut entity_types: HashMap<String, Option<String>> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        queue.push(Candidate {
+            cost: 0.0,
+            path: initial_path,
+        });
+
+        while let Some(Candidate { cost, path }) = queue.pop() {
+            let Some(last_memory) = path.memories.last() else {
+                continue;
+            };
+            let last_id = last_memory.id.clone();
+
+            if last_id == to_id {
+                paths.push(path);
+                if paths.len() >= constraints.limit {
+                    break;
+                }
+                continue;
+            }
+
+            let expanded = expansions.entry(last_id.clone()).or_default();
+            *expanded += 1;
+            if *expanded > constraints.limit || path.length() >= constraints.max_depth as usize {
+                continue;
+            }
+
+            for entity in self.get_entities_from_memory(&last_id).await? {
+                if !constraints.allows_node_type(&entity.entity_type) {
+                    continue;
+                }
+
+                for relationship in self.get_entity_relationships(&entity.id).await? {
+                    if !constraints.allows_relationship_type(&relationship.relationship_type) {
+                        continue;
+                    }
+                    let other_id = if relationship.source_id == entity.id {
+                        &relationship.target_id
+                    } else {
+                        &relationship.source_id
+                    };
+
+                    if !constraints.node_types.is_empty() {
+                        if !entity_types.contains_key(other_id) {
+                            let entity_type = self
+                                .get_entity(other_id)
+                                .await?
+                                .map(|other| other.entity_type);
+                            entity_types.insert(other_id.clone(), entity_type);
+                        }
+                        let allowed = entity_types[other_id]
+                            .as_deref()
+                            .is_some_and(|entity_type| constraints.allows_node_type(entity_type));
+                        if !allowed {
+                            continue;
+                        }
+                    }
+
+                    let next_cost = cost + constraints.edge_cost(&relationship);
+                    if constraints
+                        .max_cost
+                        .is_some_and(|max_cost| next_cost > max_cost)
+                    {
+                        continue;
+                    }
+
+                    for memory in self.get_memories_containing_entity(other_id).await? {
+                        if path.memories.iter().any(|m| m.id == memory.id) {
+                            continue;
+                        }
+
+                        let mut next_path = path.clone();
+                        next_path.add_memory(memory);
+                        next_path.add_relationship(relationship.clone());
+                        next_path.cost = Some(next_cost);
+                        queue.push(Candidate {
+                            cost: next_cost,
+                            path: next_path,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(paths)
+    }
+}
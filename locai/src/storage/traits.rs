@@ -3,12 +3,14 @@
 use async_trait::async_trait;
 use std::fmt::Debug;
 
-use crate::models::Memory;
+use crate::models::{Memory, SparseVector};
+use crate::replication::RecordKind;
 use crate::storage::errors::StorageError;
 use crate::storage::filters::{EntityFilter, MemoryFilter, RelationshipFilter, VectorFilter};
 use crate::storage::models::{
-    Entity, MemoryDiff, MemoryGraph, MemoryPath, MemorySnapshot, MemoryVersionInfo, Relationship,
-    RestoreMode, Vector, VectorSearchParams, Version,
+    ArchiveStats, ChangeFeedPage, ChecksumReport, Entity, EntitySplit, MemoryDiff, MemoryGraph,
+    MemoryPath, MemorySnapshot, MemoryVersionInfo, OutboxMessage, PathConstraints, RecordDiff,
+    RecordVersion, Relationship, RestoreMode, SearchHit, Vector, VectorSearchParams, Version,
 };
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
@@ -44,6 +46,13 @@ pub trait MemoryStore: BaseStore {
     /// Delete a memory by its ID
     async fn delete_memory(&self, id: &str) -> std::result::Result<bool, StorageError>;
 
+    /// Write a memory under its own ID, creating or replacing it
+    ///
+    /// Unlike [`create_memory`](Self::create_memory) this keeps the given ID
+    /// and skips versioning and hooks; it is meant for copying records between
+    /// stores (replication, restores).
+    async fn upsert_memory(&self, memory: Memory) -> std::result::Result<Memory, StorageError>;
+
     /// List memories with optional filtering
     async fn list_memories(
         &self,
@@ -98,6 +107,29 @@ pub trait MemoryStore: BaseStore {
         limit: Option<usize>,
     ) -> std::result::Result<Vec<(Memory, f32, String)>, StorageError>;
 
+    /// BM25 search returning only memory IDs, scores and snippets
+    async fn bm25_search_memory_hits(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> std::result::Result<Vec<SearchHit>, StorageError>;
+
+    /// Vector similarity search returning only memory IDs, scores and snippets
+    async fn vector_search_memory_hits(
+        &self,
+        query_vector: &[f32],
+        limit: Option<usize>,
+    ) -> std::result::Result<Vec<SearchHit>, StorageError>;
+
+    /// Search memories' sparse vectors, scored by dot product with `query`
+    ///
+    /// Only memories sharing a non-zero dimension with the query match.
+    async fn sparse_search_memories(
+        &self,
+        query: &SparseVector,
+        limit: Option<usize>,
+    ) -> std::result::Result<Vec<(Memory, f32)>, StorageError>;
+
     /// Search memories with configurable multi-factor scoring
     ///
     /// Combines BM25 keyword matching, vector similarity (if available), and
@@ -134,6 +166,9 @@ pub trait EntityStore: BaseStore {
     /// Delete an entity by its ID
     async fn delete_entity(&self, id: &str) -> std::result::Result<bool, StorageError>;
 
+    /// Write an entity under its own ID, creating or replacing it
+    async fn upsert_entity(&self, entity: Entity) -> std::result::Result<Entity, StorageError>;
+
     /// List entities with optional filtering
     async fn list_entities(
         &self,
@@ -147,6 +182,38 @@ pub trait EntityStore: BaseStore {
         &self,
         filter: Option<EntityFilter>,
     ) -> std::result::Result<usize, StorageError>;
+
+    /// Merge `source_ids` into `target_id`
+    ///
+    /// Relationships and memory links of the sources move to the target,
+    /// which gains their names as aliases and any properties it lacks, and
+    /// keeps a copy of each source under `merged_from`. The sources are then
+    /// deleted. Returns the updated target.
+    async fn merge_entities(
+        &self,
+        _target_id: &str,
+        _source_ids: &[String],
+    ) -> std::result::Result<Entity, StorageError> {
+        Err(StorageError::Other(
+            "Entity merging is not supported by this store".to_string(),
+        ))
+    }
+
+    /// Split part of an entity off into a new one
+    ///
+    /// Creates `split.entity` and moves the listed relationships and memory
+    /// links to it. Its names stop being aliases of the original, and the two
+    /// are connected by `split_from` and `split_into` properties. Returns the
+    /// new entity.
+    async fn split_entity(
+        &self,
+        _entity_id: &str,
+        _split: EntitySplit,
+    ) -> std::result::Result<Entity, StorageError> {
+        Err(StorageError::Other(
+            "Entity splitting is not supported by this store".to_string(),
+        ))
+    }
 }
 
 /// Trait for relationship operations
@@ -173,6 +240,15 @@ pub trait RelationshipStore: BaseStore {
     /// Delete a relationship by its ID
     async fn delete_relationship(&self, id: &str) -> std::result::Result<bool, StorageError>;
 
+    /// Write a relationship under its own ID, creating or replacing it
+    ///
+    /// Endpoints are not validated, so a relationship may arrive before the
+    /// records it connects.
+    async fn upsert_relationship(
+        &self,
+        relationship: Relationship,
+    ) -> std::result::Result<Relationship, StorageError>;
+
     /// List relationships with optional filtering
     async fn list_relationships(
         &self,
@@ -240,7 +316,15 @@ pub trait VersionStore: BaseStore {
 /// Combined trait for all graph operations
 #[async_trait]
 pub trait GraphStore:
-    MemoryStore + EntityStore + RelationshipStore + VersionStore + VectorStore + GraphTraversal
+    MemoryStore
+    + EntityStore
+    + RelationshipStore
+    + VersionStore
+    + VectorStore
+    + GraphTraversal
+    + ArchiveStore
+    + OutboxStore
+    + RecordVersionStore
 {
     /// Clear all data from the storage
     async fn clear_storage(&self) -> std::result::Result<(), StorageError>;
@@ -264,6 +348,47 @@ pub trait GraphStore:
         Ok(None)
     }
 
+    /// Read committed changes to memories, entities and relationships
+    ///
+    /// Returns up to about `limit` changes made after `since`, oldest first;
+    /// pass 0 to start from the oldest retained change, and the returned
+    /// cursor to continue.
+    async fn read_changes(
+        &self,
+        _since: u64,
+        _limit: usize,
+    ) -> std::result::Result<ChangeFeedPage, StorageError> {
+        Err(StorageError::Other(
+            "Changefeed is not supported by this store".to_string(),
+        ))
+    }
+
+    /// Take or renew the lease `name` for `holder`, for `ttl`
+    ///
+    /// Granted when nobody holds the lease, it has expired, or `holder`
+    /// already holds it. Stores that can't coordinate between processes
+    /// grant every lease, so tasks guarded by one run wherever they are
+    /// started, as they would without it. See [`crate::runtime::Lease`].
+    async fn acquire_lease(
+        &self,
+        _name: &str,
+        _holder: &str,
+        _ttl: std::time::Duration,
+    ) -> std::result::Result<bool, StorageError> {
+        Ok(true)
+    }
+
+    /// Give up the lease `name` if `holder` holds it
+    ///
+    /// Returns false if it didn't.
+    async fn release_lease(
+        &self,
+        _name: &str,
+        _holder: &str,
+    ) -> std::result::Result<bool, StorageError> {
+        Ok(false)
+    }
+
     /// Get a reference to the underlying store as Any for downcasting
     fn as_any(&self) -> &dyn std::any::Any;
 }
@@ -351,6 +476,23 @@ pub trait GraphTraversal: Send + Sync + 'static {
         max_depth: u8,
     ) -> std::result::Result<Vec<MemoryPath>, StorageError>;
 
+    /// Find the cheapest paths between two memories
+    ///
+    /// Like [`find_paths`](Self::find_paths), but each hop costs the weight of
+    /// its relationship, hops are limited to the allowed relationship and
+    /// entity types, and paths over the maximum cost are dropped. Returns up
+    /// to `constraints.limit` paths, cheapest first, with their cost set.
+    async fn find_weighted_paths(
+        &self,
+        _from_id: &str,
+        _to_id: &str,
+        _constraints: &PathConstraints,
+    ) -> std::result::Result<Vec<MemoryPath>, StorageError> {
+        Err(StorageError::Other(
+            "Weighted path search is not supported by this store".to_string(),
+        ))
+    }
+
     /// Find memories connected to a given memory by a specific relationship type
     ///
     /// # Arguments
@@ -404,6 +546,177 @@ pub trait GraphTraversal: Send + Sync + 'static {
     ) -> std::result::Result<Vec<Relationship>, StorageError>;
 }
 
+/// Trait for moving inactive memories to and from cold storage
+///
+/// Archived memories are removed from the active memory table, and so from its
+/// BM25 and vector indexes. They are stored compressed and can still be read,
+/// listed and searched (by scanning) until unarchived.
+#[async_trait]
+pub trait ArchiveStore: BaseStore {
+    /// Move a memory to the archive
+    ///
+    /// Returns false if no active memory has this ID.
+    async fn archive_memory(&self, id: &str) -> std::result::Result<bool, StorageError>;
+
+    /// Restore an archived memory to the active table under its original ID
+    ///
+    /// Returns false if no archived memory has this ID.
+    async fn unarchive_memory(&self, id: &str) -> std::result::Result<bool, StorageError>;
+
+    /// Get an archived memory by ID
+    async fn get_archived_memory(
+        &self,
+        id: &str,
+    ) -> std::result::Result<Option<Memory>, StorageError>;
+
+    /// List archived memories, most recently archived first
+    async fn list_archived_memories(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> std::result::Result<Vec<Memory>, StorageError>;
+
+    /// Search archived memories
+    ///
+    /// Scores by the fraction of query terms a memory contains, or by cosine
+    /// similarity to `query_embedding` when that is higher.
+    async fn search_archived_memories(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        limit: Option<usize>,
+    ) -> std::result::Result<Vec<crate::storage::models::SearchResult>, StorageError>;
+
+    /// Archive memories last accessed (or, if never accessed, created) before `cutoff`
+    ///
+    /// # Returns
+    /// The IDs of the archived memories
+    async fn archive_inactive_memories(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: Option<usize>,
+    ) -> std::result::Result<Vec<String>, StorageError>;
+
+    /// Get archive statistics
+    async fn get_archive_stats(&self) -> std::result::Result<ArchiveStats, StorageError>;
+}
+
+/// Trait for verifying the content checksums stored with memories and vectors
+///
+/// Each memory is written with a checksum of its content and embedding, and
+/// each vector with a checksum of its values, so data changed after the
+/// write (by disk corruption or edits made outside Locai) can be detected.
+#[async_trait]
+pub trait ChecksumStore: BaseStore {
+    /// Recompute the checksum of every memory and vector and compare it to the stored one
+    ///
+    /// Records written before checksums were stored are counted but not checked.
+    async fn verify_checksums(&self) -> std::result::Result<ChecksumReport, StorageError>;
+
+    /// Store checksums for records written before checksums were stored
+    ///
+    /// # Returns
+    /// The number of records given a checksum
+    async fn backfill_checksums(&self) -> std::result::Result<usize, StorageError>;
+}
+
+/// Trait for recording notifications atomically with memory writes
+///
+/// Each write commits its outbox messages in the same transaction, so a
+/// crash can never leave a written memory without its notification or a
+/// notification for a write that did not happen. Messages stay pending until
+/// acknowledged, so a relay that fails mid-delivery sends them again.
+#[async_trait]
+pub trait OutboxStore: BaseStore {
+    /// Create a memory and record `messages` in one transaction
+    ///
+    /// The memory is stored under its own ID, so the messages can refer to it.
+    async fn create_memory_with_outbox(
+        &self,
+        memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> std::result::Result<Memory, StorageError>;
+
+    /// Update a memory and record `messages` in one transaction
+    ///
+    /// Nothing is recorded if the memory does not exist.
+    async fn update_memory_with_outbox(
+        &self,
+        memory: Memory,
+        messages: Vec<OutboxMessage>,
+    ) -> std::result::Result<Memory, StorageError>;
+
+    /// Delete a memory and record `messages` in one transaction
+    ///
+    /// Nothing is recorded if the memory does not exist or a hook vetoes the
+    /// deletion.
+    async fn delete_memory_with_outbox(
+        &self,
+        id: &str,
+        messages: Vec<OutboxMessage>,
+    ) -> std::result::Result<bool, StorageError>;
+
+    /// Get undelivered messages, oldest first
+    async fn pending_outbox(
+        &self,
+        limit: usize,
+    ) -> std::result::Result<Vec<OutboxMessage>, StorageError>;
+
+    /// Mark a message as delivered, removing it from the outbox
+    ///
+    /// Returns false if no pending message has this ID.
+    async fn ack_outbox(&self, id: &str) -> std::result::Result<bool, StorageError>;
+}
+
+/// Trait for the version history of entities and relationships
+///
+/// Every create, update, upsert and delete of an entity or relationship
+/// records a full snapshot, so changes to the graph can be audited and
+/// reconstructed the way memory content can through [`MemoryVersionStore`].
+#[async_trait]
+pub trait RecordVersionStore: BaseStore {
+    /// List the versions of an entity or relationship, oldest first
+    ///
+    /// Memories are versioned by [`MemoryVersionStore`], so this is empty
+    /// for [`RecordKind::Memory`].
+    async fn list_record_versions(
+        &self,
+        kind: RecordKind,
+        id: &str,
+    ) -> std::result::Result<Vec<RecordVersion>, StorageError>;
+
+    /// Get a single version by its ID
+    async fn get_record_version(
+        &self,
+        version_id: &str,
+    ) -> std::result::Result<Option<RecordVersion>, StorageError>;
+
+    /// Get an entity as it existed at a specific time
+    ///
+    /// Returns None if the entity did not exist yet or had been deleted.
+    async fn get_entity_at_time(
+        &self,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> std::result::Result<Option<Entity>, StorageError>;
+
+    /// Get a relationship as it existed at a specific time
+    ///
+    /// Returns None if the relationship did not exist yet or had been deleted.
+    async fn get_relationship_at_time(
+        &self,
+        id: &str,
+        at_time: DateTime<Utc>,
+    ) -> std::result::Result<Option<Relationship>, StorageError>;
+
+    /// Compute the field changes between two versions of the same record
+    async fn diff_record_versions(
+        &self,
+        old_version_id: &str,
+        new_version_id: &str,
+    ) -> std::result::Result<RecordDiff, StorageError>;
+}
+
 /// Trait for memory versioning operations
 #[async_trait]
 pub trait MemoryVersionStore: BaseStore {
@@ -533,6 +846,66 @@ pub trait MemoryVersionStore: BaseStore {
         restore_mode: RestoreMode,
     ) -> std::result::Result<(), StorageError>;
 
+    /// Preview a snapshot restore without changing anything
+    ///
+    /// Reports which memories `restore_snapshot` would overwrite, version
+    /// or skip with the same mode, along with the content diffs.
+    ///
+    /// # Arguments
+    /// * `snapshot` - The snapshot to restore
+    /// * `restore_mode` - How to handle existing memories
+    ///
+    /// # Returns
+    /// The planned action for each memory in the snapshot
+    async fn restore_snapshot_preview(
+        &self,
+        snapshot: &MemorySnapshot,
+        restore_mode: RestoreMode,
+    ) -> std::result::Result<crate::storage::models::RestorePreview, StorageError>;
+
+    /// Get a stored snapshot
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The snapshot ID
+    ///
+    /// # Returns
+    /// The snapshot if it exists
+    async fn get_snapshot(
+        &self,
+        snapshot_id: &str,
+    ) -> std::result::Result<Option<MemorySnapshot>, StorageError>;
+
+    /// Export a snapshot together with the memory state it refers to
+    ///
+    /// Memories deleted since the snapshot was taken are left out.
+    ///
+    /// # Arguments
+    /// * `snapshot` - The snapshot to export
+    ///
+    /// # Returns
+    /// A self-contained bundle that `import_snapshot` can load elsewhere
+    async fn export_snapshot(
+        &self,
+        snapshot: &MemorySnapshot,
+    ) -> std::result::Result<crate::storage::models::SnapshotBundle, StorageError>;
+
+    /// Import a snapshot bundle exported from another instance
+    ///
+    /// Memories missing here are created with their original IDs. Existing
+    /// ones are handled according to `restore_mode`.
+    ///
+    /// # Arguments
+    /// * `bundle` - The exported bundle
+    /// * `restore_mode` - How to handle memories that already exist
+    ///
+    /// # Returns
+    /// A new local snapshot of the imported memories
+    async fn import_snapshot(
+        &self,
+        bundle: &crate::storage::models::SnapshotBundle,
+        restore_mode: RestoreMode,
+    ) -> std::result::Result<MemorySnapshot, StorageError>;
+
     /// Search memories in a snapshot state
     ///
     /// # Arguments
@@ -628,4 +1001,19 @@ pub trait MemoryVersionStore: BaseStore {
         memory_id: &str,
         version_id: &str,
     ) -> std::result::Result<(), StorageError>;
+
+    /// Verify a memory's version hash chain
+    ///
+    /// Recomputes the hash of every hashed version from its reconstructed
+    /// content and checks that each links to its parent's hash.
+    ///
+    /// # Arguments
+    /// * `memory_id` - The memory ID
+    ///
+    /// # Returns
+    /// Report of the versions checked and any issues found
+    async fn verify_integrity(
+        &self,
+        memory_id: &str,
+    ) -> std::result::Result<crate::storage::models::VersionChainReport, StorageError>;
 }
@@ -0,0 +1,644 @@
+//! Synthetic data
+//!
+//! Generates fake but plausible memories, entities and relationships at
+//! scale, for load testing, benchmarks and demo environments. Everything is
+//! derived from a seed, so the same [`SyntheticConfig`] always produces the
+//! same data, memory and entity IDs included.
+//!
+//! Entities are people, places and organizations. Memories are short notes
+//! about them: meetings, decisions, visits and plans, spread over the last
+//! year. Each memory mentions one or two entities, and relationships link
+//! memories to the entities they mention and to earlier memories.
+//!
+//! ```rust,no_run
+//! use locai::synthetic::{SyntheticConfig, load};
+//!
+//! # async fn example(manager: &locai::core::MemoryManager) -> locai::Result<()> {
+//! let config = SyntheticConfig {
+//!     memories: 10_000,
+//!     entities: 500,
+//!     ..SyntheticConfig::default()
+//! };
+//! let report = load(manager, &config, |_| {}).await?;
+//! println!("{} memories, {} relationships", report.memories, report.relationships);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Memories have the source [`SYNTHETIC_SOURCE`] and entities a `source`
+//! property with the same value; [`remove`] deletes both.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::bench::{SplitMix64, embedding};
+use crate::core::MemoryManager;
+use crate::models::{Memory, MemoryBuilder, MemoryPriority, MemoryType};
+use crate::storage::filters::{EntityFilter, MemoryFilter};
+use crate::storage::models::{Entity, Relationship};
+use crate::{LocaiError, Result};
+
+/// Source of synthetic memories, and `source` property of synthetic entities
+pub const SYNTHETIC_SOURCE: &str = "synthetic";
+
+/// Relationship type from a memory to an entity it mentions
+pub const MENTIONS_RELATIONSHIP: &str = "mentions";
+
+/// Relationship type from a memory to an earlier one
+pub const RELATED_RELATIONSHIP: &str = "related_to";
+
+/// Memories stored per storage call
+const BATCH_SIZE: usize = 500;
+
+/// Memories created over this many days before now
+const SPAN_DAYS: i64 = 365;
+
+const FIRST_NAMES: &[&str] = &[
+    "Ada", "Bruno", "Chen", "Dana", "Elif", "Farah", "Gustavo", "Hana", "Ivan", "Jonas", "Keiko",
+    "Lena", "Mateo", "Nadia", "Omar", "Priya", "Quinn", "Rosa", "Samir", "Tomas", "Uma", "Viktor",
+    "Wen", "Yusuf", "Zoe",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Alvarez", "Brennan", "Costa", "Dubois", "Eriksen", "Fischer", "Garcia", "Haddad", "Ito",
+    "Jensen", "Kowalski", "Larsen", "Moreau", "Nakamura", "Okafor", "Petrov", "Rossi", "Schmidt",
+    "Tanaka", "Varga", "Weber", "Yilmaz",
+];
+
+const PLACES: &[&str] = &[
+    "Harbor Office",
+    "North Warehouse",
+    "Riverside Cafe",
+    "Lisbon Studio",
+    "Main Library",
+    "Oak Street Clinic",
+    "Central Station",
+    "Summit Lodge",
+    "Old Town Market",
+    "Lakeside Lab",
+    "Airport Hotel",
+    "Eastside Depot",
+];
+
+const ORGANIZATIONS: &[&str] = &[
+    "Acme Logistics",
+    "Blue Finch Labs",
+    "Cedar Health",
+    "Delta Freight",
+    "Evergreen Bank",
+    "Foxglove Media",
+    "Granite Works",
+    "Helix Robotics",
+    "Ironwood Legal",
+    "Juniper Foods",
+    "Kestrel Air",
+    "Lumen Energy",
+];
+
+const TOPICS: &[&str] = &[
+    "budget",
+    "hiring plan",
+    "product launch",
+    "security review",
+    "roadmap",
+    "supplier contract",
+    "customer feedback",
+    "quarterly report",
+    "office move",
+    "training program",
+    "data migration",
+    "marketing campaign",
+    "incident review",
+    "partnership",
+];
+
+/// Sentences memories are made from; `{person}`, `{other}`, `{place}`,
+/// `{org}` and `{topic}` are filled in
+const TEMPLATES: &[(&str, MemoryType)] = &[
+    (
+        "{person} met {other} at {place} to go over the {topic}.",
+        MemoryType::Episodic,
+    ),
+    (
+        "{person} decided to move the {topic} to next quarter after talking with {org}.",
+        MemoryType::Episodic,
+    ),
+    (
+        "{org} signed off on the {topic}; {person} is the contact.",
+        MemoryType::Fact,
+    ),
+    (
+        "{person} prefers to discuss the {topic} in person at {place}.",
+        MemoryType::Identity,
+    ),
+    (
+        "{place} is where {org} keeps the records for the {topic}.",
+        MemoryType::World,
+    ),
+    (
+        "Follow up with {person} about the {topic} before Friday.",
+        MemoryType::Task,
+    ),
+    (
+        "{person} asked {other} for the latest numbers on the {topic}.",
+        MemoryType::Conversation,
+    ),
+    (
+        "To prepare the {topic}, {person} collects figures from {org} and reviews them at {place}.",
+        MemoryType::Procedural,
+    ),
+    (
+        "{org} announced changes to the {topic}, and {person} flagged the risks.",
+        MemoryType::Event,
+    ),
+];
+
+/// What to generate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyntheticConfig {
+    /// Number of memories
+    pub memories: usize,
+    /// Number of entities: people, places and organizations
+    pub entities: usize,
+    /// Relationships per memory on average
+    pub graph_density: f64,
+    /// Seed all data is derived from
+    pub seed: u64,
+    /// Give every memory a random unit-length embedding, for vector search
+    pub embeddings: bool,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self {
+            memories: 1_000,
+            entities: 100,
+            graph_density: 2.0,
+            seed: 42,
+            embeddings: false,
+        }
+    }
+}
+
+impl SyntheticConfig {
+    /// Check the counts and density make sense
+    pub fn validate(&self) -> Result<()> {
+        if !(self.graph_density.is_finite() && self.graph_density >= 0.0) {
+            return Err(LocaiError::Configuration(format!(
+                "Graph density must be zero or more, got {}",
+                self.graph_density
+            )));
+        }
+        if self.graph_density > 0.0 && self.memories < 2 && self.entities == 0 {
+            return Err(LocaiError::Configuration(
+                "Relationships need at least two memories or one entity".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Data generated for a [`SyntheticConfig`]
+#[derive(Debug, Clone)]
+pub struct SyntheticDataset {
+    pub entities: Vec<Entity>,
+    pub memories: Vec<Memory>,
+    pub relationships: Vec<Relationship>,
+}
+
+/// How much was stored by [`load`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyntheticReport {
+    pub memories: usize,
+    pub entities: usize,
+    pub relationships: usize,
+    /// Records that failed to store
+    pub failed: usize,
+}
+
+/// Progress of [`load`], reported after each step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntheticProgress {
+    /// Records stored or failed so far
+    pub done: usize,
+    /// Records to store in total
+    pub total: usize,
+}
+
+/// Generate the data for `config` without storing it
+pub fn generate(config: &SyntheticConfig) -> Result<SyntheticDataset> {
+    config.validate()?;
+    let mut rng = SplitMix64::new(config.seed);
+    let now = Utc::now();
+
+    let entities: Vec<Entity> = (0..config.entities)
+        .map(|n| synthetic_entity(config.seed, n, &mut rng))
+        .collect();
+    let (people, places, organizations) = by_kind(&entities);
+
+    let mut memories = Vec::with_capacity(config.memories);
+    let mut mentions = Vec::with_capacity(config.memories);
+    for n in 0..config.memories {
+        let (template, memory_type) = TEMPLATES[rng.below(TEMPLATES.len())].clone();
+        let mut mentioned = Vec::new();
+        let mut pick = |pool: &[&Entity], fallback: String, rng: &mut SplitMix64| match pool {
+            [] => fallback,
+            pool => {
+                let entity = pool[rng.below(pool.len())];
+                if !mentioned.contains(&entity.id) {
+                    mentioned.push(entity.id.clone());
+                }
+                entity_name(entity)
+            }
+        };
+
+        let topic = TOPICS[rng.below(TOPICS.len())];
+        let mut content = template.to_string();
+        for (slot, pool) in [
+            ("{person}", &people),
+            ("{other}", &people),
+            ("{place}", &places),
+            ("{org}", &organizations),
+        ] {
+            if content.contains(slot) {
+                let fallback = match slot {
+                    "{place}" => PLACES[rng.below(PLACES.len())].to_string(),
+                    "{org}" => ORGANIZATIONS[rng.below(ORGANIZATIONS.len())].to_string(),
+                    _ => person_name(&mut rng),
+                };
+                let name = pick(pool, fallback, &mut rng);
+                content = content.replace(slot, &name);
+            }
+        }
+        let content = content.replace("{topic}", topic);
+
+        let priority = match rng.below(10) {
+            0 => MemoryPriority::Low,
+            1..=6 => MemoryPriority::Normal,
+            7 | 8 => MemoryPriority::High,
+            _ => MemoryPriority::Critical,
+        };
+        let mut builder = MemoryBuilder::new(format!("synthetic-{}-{}", config.seed, n), content)
+            .memory_type(memory_type)
+            .priority(priority)
+            .source(SYNTHETIC_SOURCE)
+            .tag(topic.replace(' ', "-"));
+        if config.embeddings {
+            builder = builder.embedding(embedding(rng.next_u64()));
+        }
+        let mut memory = builder.build();
+        memory.created_at =
+            now - Duration::minutes(rng.below((SPAN_DAYS * 24 * 60) as usize) as i64);
+
+        memories.push(memory);
+        mentions.push(mentioned);
+    }
+    // Oldest first, so "earlier" memories were created earlier
+    let mut order: Vec<usize> = (0..memories.len()).collect();
+    order.sort_by_key(|&n| memories[n].created_at);
+    let memories: Vec<Memory> = order.iter().map(|&n| memories[n].clone()).collect();
+    let mentions: Vec<Vec<String>> = order.iter().map(|&n| mentions[n].clone()).collect();
+
+    let relationships = link(config, &memories, &mentions, &mut rng);
+    Ok(SyntheticDataset {
+        entities,
+        memories,
+        relationships,
+    })
+}
+
+/// Generate the data for `config` and store it
+///
+/// Entities are stored first, then memories in batches, then relationships.
+/// `progress` is called after each step. Records that fail to store are
+/// counted in [`SyntheticReport::failed`] and skipped.
+pub async fn load(
+    manager: &MemoryManager,
+    config: &SyntheticConfig,
+    mut progress: impl FnMut(SyntheticProgress),
+) -> Result<SyntheticReport> {
+    let dataset = generate(config)?;
+    let total = dataset.entities.len() + dataset.memories.len() + dataset.relationships.len();
+    let mut report = SyntheticReport::default();
+    let mut done = 0;
+    // Storage may qualify IDs (`entity:...`), so relationships use the stored ones
+    let mut stored_ids = HashMap::new();
+
+    for entity in dataset.entities {
+        let id = entity.id.clone();
+        match manager.create_entity(entity).await {
+            Ok(stored) => {
+                stored_ids.insert(id, stored.id);
+                report.entities += 1;
+            }
+            Err(e) => {
+                tracing::debug!("Failed to store synthetic entity: {}", e);
+                report.failed += 1;
+            }
+        }
+        done += 1;
+        progress(SyntheticProgress { done, total });
+    }
+
+    let mut memories = dataset.memories;
+    while !memories.is_empty() {
+        let rest = memories.split_off(memories.len().min(BATCH_SIZE));
+        let batch = std::mem::replace(&mut memories, rest);
+        done += batch.len();
+        let ids: Vec<String> = batch.iter().map(|memory| memory.id.clone()).collect();
+        let results = manager.store_memory_batch(batch, 4).await;
+        for (id, result) in ids.into_iter().zip(results) {
+            match result {
+                Ok(stored) => {
+                    stored_ids.insert(id, stored);
+                    report.memories += 1;
+                }
+                Err(e) => {
+                    tracing::debug!("Failed to store synthetic memory: {}", e);
+                    report.failed += 1;
+                }
+            }
+        }
+        progress(SyntheticProgress { done, total });
+    }
+
+    for mut relationship in dataset.relationships {
+        let (Some(source), Some(target)) = (
+            stored_ids.get(&relationship.source_id),
+            stored_ids.get(&relationship.target_id),
+        ) else {
+            // An end failed to store
+            report.failed += 1;
+            done += 1;
+            continue;
+        };
+        relationship.source_id = source.clone();
+        relationship.target_id = target.clone();
+        match manager.create_relationship_entity(relationship).await {
+            Ok(_) => report.relationships += 1,
+            Err(e) => {
+                tracing::debug!("Failed to store synthetic relationship: {}", e);
+                report.failed += 1;
+            }
+        }
+        done += 1;
+        if done % 100 == 0 || done == total {
+            progress(SyntheticProgress { done, total });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Delete every synthetic memory and entity
+///
+/// Returns the number of memories and entities deleted.
+pub async fn remove(manager: &MemoryManager) -> Result<(usize, usize)> {
+    let memory_filter = MemoryFilter {
+        source: Some(SYNTHETIC_SOURCE.to_string()),
+        ..Default::default()
+    };
+    let mut memories = 0;
+    loop {
+        let page = manager
+            .filter_memories(memory_filter.clone(), None, None, Some(BATCH_SIZE))
+            .await?;
+        if page.is_empty() {
+            break;
+        }
+        let mut deleted = 0;
+        for memory in page {
+            if manager.delete_memory(&memory.id).await? {
+                deleted += 1;
+            }
+        }
+        if deleted == 0 {
+            break;
+        }
+        memories += deleted;
+    }
+
+    let entity_filter = EntityFilter {
+        properties: Some(HashMap::from([(
+            "source".to_string(),
+            json!(SYNTHETIC_SOURCE),
+        )])),
+        ..Default::default()
+    };
+    let mut entities = 0;
+    loop {
+        let page = manager
+            .list_entities(Some(entity_filter.clone()), Some(BATCH_SIZE), None)
+            .await?;
+        if page.is_empty() {
+            break;
+        }
+        let mut deleted = 0;
+        for entity in page {
+            if manager.delete_entity(&entity.id).await? {
+                deleted += 1;
+            }
+        }
+        if deleted == 0 {
+            break;
+        }
+        entities += deleted;
+    }
+
+    Ok((memories, entities))
+}
+
+fn person_name(rng: &mut SplitMix64) -> String {
+    format!(
+        "{} {}",
+        FIRST_NAMES[rng.below(FIRST_NAMES.len())],
+        LAST_NAMES[rng.below(LAST_NAMES.len())]
+    )
+}
+
+/// The `n`th entity: half people, the rest split between places and
+/// organizations. Names repeat once the lists run out, with a number added.
+fn synthetic_entity(seed: u64, n: usize, rng: &mut SplitMix64) -> Entity {
+    let (entity_type, name) = match n % 4 {
+        0 | 1 => ("Person", person_name(rng)),
+        2 => {
+            let round = n / 4 / PLACES.len();
+            let place = PLACES[(n / 4) % PLACES.len()];
+            ("Location", numbered(place, round))
+        }
+        _ => {
+            let round = n / 4 / ORGANIZATIONS.len();
+            let organization = ORGANIZATIONS[(n / 4) % ORGANIZATIONS.len()];
+            ("Organization", numbered(organization, round))
+        }
+    };
+    let now = Utc::now();
+    Entity {
+        id: format!("synthetic-{}-entity-{}", seed, n),
+        entity_type: entity_type.to_string(),
+        properties: json!({ "name": name, "source": SYNTHETIC_SOURCE }),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn numbered(name: &str, round: usize) -> String {
+    if round == 0 {
+        name.to_string()
+    } else {
+        format!("{} {}", name, round + 1)
+    }
+}
+
+fn entity_name(entity: &Entity) -> String {
+    entity.properties["name"]
+        .as_str()
+        .unwrap_or(&entity.id)
+        .to_string()
+}
+
+fn by_kind(entities: &[Entity]) -> (Vec<&Entity>, Vec<&Entity>, Vec<&Entity>) {
+    let of_type = |entity_type: &str| {
+        entities
+            .iter()
+            .filter(|entity| entity.entity_type == entity_type)
+            .collect::<Vec<_>>()
+    };
+    (
+        of_type("Person"),
+        of_type("Location"),
+        of_type("Organization"),
+    )
+}
+
+/// Relationships for the memories: each gets `graph_density` on average,
+/// first to the entities it mentions, then to earlier memories
+fn link(
+    config: &SyntheticConfig,
+    memories: &[Memory],
+    mentions: &[Vec<String>],
+    rng: &mut SplitMix64,
+) -> Vec<Relationship> {
+    let whole = config.graph_density.floor() as usize;
+    let fraction = config.graph_density - config.graph_density.floor();
+    let now = Utc::now();
+    let mut relationships = Vec::new();
+    let mut relate = |source: &Memory, target: &str, relationship_type: &str| {
+        relationships.push(Relationship {
+            id: format!("synthetic-{}-rel-{}", config.seed, relationships.len()),
+            relationship_type: relationship_type.to_string(),
+            source_id: source.id.clone(),
+            target_id: target.to_string(),
+            properties: json!({ "source": SYNTHETIC_SOURCE }),
+            created_at: now,
+            updated_at: now,
+        });
+    };
+
+    for (n, memory) in memories.iter().enumerate() {
+        let extra = ((rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < fraction;
+        let count = whole + usize::from(extra);
+        let mentioned = &mentions[n];
+        for target in mentioned.iter().take(count) {
+            relate(memory, target, MENTIONS_RELATIONSHIP);
+        }
+        for _ in mentioned.len().min(count)..count {
+            if n == 0 {
+                break;
+            }
+            let earlier = &memories[rng.below(n)];
+            relate(memory, &earlier.id, RELATED_RELATIONSHIP);
+        }
+    }
+    relationships
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SyntheticConfig {
+        SyntheticConfig {
+            memories: 200,
+            entities: 40,
+            graph_density: 1.5,
+            seed: 7,
+            embeddings: false,
+        }
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let first = generate(&config()).unwrap();
+        let second = generate(&config()).unwrap();
+        let contents = |dataset: &SyntheticDataset| {
+            dataset
+                .memories
+                .iter()
+                .map(|memory| (memory.id.clone(), memory.content.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(contents(&first), contents(&second));
+        assert_eq!(
+            first
+                .entities
+                .iter()
+                .map(|e| &e.properties)
+                .collect::<Vec<_>>(),
+            second
+                .entities
+                .iter()
+                .map(|e| &e.properties)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(first.relationships.len(), second.relationships.len());
+
+        let other = generate(&SyntheticConfig {
+            seed: 8,
+            ..config()
+        })
+        .unwrap();
+        assert_ne!(contents(&first), contents(&other));
+    }
+
+    #[test]
+    fn test_counts_and_density() {
+        let dataset = generate(&config()).unwrap();
+        assert_eq!(dataset.memories.len(), 200);
+        assert_eq!(dataset.entities.len(), 40);
+        // 1.5 per memory on average; the first memory can only mention entities
+        let per_memory = dataset.relationships.len() as f64 / 200.0;
+        assert!((1.2..=1.8).contains(&per_memory), "got {}", per_memory);
+
+        let ids: std::collections::HashSet<&str> = dataset
+            .memories
+            .iter()
+            .map(|memory| memory.id.as_str())
+            .chain(dataset.entities.iter().map(|entity| entity.id.as_str()))
+            .collect();
+        assert!(
+            dataset
+                .relationships
+                .iter()
+                .all(|r| ids.contains(r.source_id.as_str()) && ids.contains(r.target_id.as_str()))
+        );
+        assert!(
+            dataset
+                .memories
+                .iter()
+                .all(|memory| !memory.content.contains('{'))
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = SyntheticConfig {
+            graph_density: -1.0,
+            ..SyntheticConfig::default()
+        };
+        assert!(generate(&config).is_err());
+    }
+}
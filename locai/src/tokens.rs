@@ -0,0 +1,331 @@
+//! Token counting
+//!
+//! Everything sized in tokens, from context budgets to text chunks and
+//! session summary triggers, counts through one [`TokenCounter`], so they all
+//! agree on how big a piece of text is. The manager's counter comes from
+//! [`TokenizerConfig`](crate::config::TokenizerConfig): a tiktoken-compatible
+//! BPE file when one is configured, otherwise an estimate from the character
+//! count. Any other tokenizer can be plugged in with a
+//! [`CallbackTokenCounter`].
+//!
+//! ```rust
+//! use locai::tokens::{ApproxTokenCounter, TokenCounter, chunk_text};
+//!
+//! let counter = ApproxTokenCounter::default();
+//! assert_eq!(counter.count("twelve chars"), 3);
+//!
+//! let chunks = chunk_text(&counter, "one two three four five six", 4, 0);
+//! assert_eq!(chunks, vec!["one two three", "four five six"]);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use regex::Regex;
+
+use crate::config::TokenizerConfig;
+use crate::{LocaiError, Result};
+
+/// Splits text into the pieces BPE merges stay within, as tiktoken's
+/// `cl100k_base` does
+///
+/// tiktoken ends a run of whitespace before the last space when a word
+/// follows; the `regex` crate has no lookahead, so [`BpeTokenCounter`] does
+/// that step by hand.
+const PRETOKENIZE_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+
+/// Counts the tokens in text
+pub trait TokenCounter: fmt::Debug + Send + Sync {
+    /// Short human-readable name
+    fn name(&self) -> &str;
+
+    /// Number of tokens in `text`
+    fn count(&self, text: &str) -> usize;
+
+    /// The longest prefix of `text` that fits in `max_tokens`
+    fn truncate<'a>(&self, text: &'a str, max_tokens: usize) -> &'a str {
+        if self.count(text) <= max_tokens {
+            return text;
+        }
+        let ends: Vec<usize> = text.char_indices().map(|(index, _)| index).collect();
+        // The first `fits` characters fit, the first `fits + 1` don't
+        let (mut fits, mut over) = (0, ends.len());
+        while over - fits > 1 {
+            let middle = (fits + over) / 2;
+            if self.count(&text[..ends[middle]]) <= max_tokens {
+                fits = middle;
+            } else {
+                over = middle;
+            }
+        }
+        &text[..ends[fits]]
+    }
+}
+
+/// Estimates tokens from the number of characters
+#[derive(Debug, Clone)]
+pub struct ApproxTokenCounter {
+    chars_per_token: usize,
+}
+
+impl ApproxTokenCounter {
+    pub fn new(chars_per_token: usize) -> Self {
+        Self {
+            chars_per_token: chars_per_token.max(1),
+        }
+    }
+}
+
+impl Default for ApproxTokenCounter {
+    /// Four characters per token, about right for English with OpenAI's
+    /// tokenizers
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl TokenCounter for ApproxTokenCounter {
+    fn name(&self) -> &str {
+        "approx"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(self.chars_per_token)
+    }
+}
+
+/// Counts tokens with a byte-level BPE vocabulary in tiktoken's format
+///
+/// A tiktoken file has one token per line: the token's bytes in base64, a
+/// space and its rank. Merges are applied lowest rank first within the pieces
+/// `cl100k_base` splits text into, so counts match tiktoken's for the
+/// vocabularies that split text that way.
+pub struct BpeTokenCounter {
+    name: String,
+    ranks: HashMap<Vec<u8>, u32>,
+    pretokenizer: Regex,
+}
+
+impl BpeTokenCounter {
+    /// Load a vocabulary from a tiktoken file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path).map_err(|e| {
+            LocaiError::Configuration(format!("Failed to read BPE file {}: {}", path.display(), e))
+        })?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "bpe".to_string());
+        Self::parse(name, &data)
+    }
+
+    /// Read a vocabulary in tiktoken's format
+    pub fn parse(name: impl Into<String>, data: &str) -> Result<Self> {
+        let mut ranks = HashMap::new();
+        for (number, line) in data.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid =
+                || LocaiError::Configuration(format!("Invalid BPE vocabulary line {}", number + 1));
+            let (token, rank) = line.split_once(' ').ok_or_else(invalid)?;
+            let token = STANDARD.decode(token).map_err(|_| invalid())?;
+            let rank = rank.trim().parse::<u32>().map_err(|_| invalid())?;
+            ranks.insert(token, rank);
+        }
+        if ranks.is_empty() {
+            return Err(LocaiError::Configuration(
+                "The BPE vocabulary is empty".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            name: name.into(),
+            ranks,
+            pretokenizer: Regex::new(PRETOKENIZE_PATTERN)
+                .expect("pretokenize pattern is a valid regex"),
+        })
+    }
+
+    /// Split text into the pieces merges stay within
+    fn pieces<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while let Some(found) = self.pretokenizer.find_at(text, start) {
+            let mut end = found.end();
+            let piece = found.as_str();
+            // Leave the last space of a run to the word after it
+            if piece.chars().all(char::is_whitespace)
+                && !piece.ends_with(['\r', '\n'])
+                && text[end..].starts_with(|c: char| !c.is_whitespace())
+                && let Some((last, _)) = piece.char_indices().last()
+                && last > 0
+            {
+                end = found.start() + last;
+            }
+            pieces.push(&text[found.start()..end]);
+            start = end;
+        }
+        pieces
+    }
+
+    /// Number of tokens the merges leave `piece` in
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if piece.len() <= 1 || self.ranks.contains_key(piece) {
+            return 1;
+        }
+        // Start of each part, plus the end of the piece
+        let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let best = (0..bounds.len() - 2)
+                .filter_map(|i| {
+                    self.ranks
+                        .get(&piece[bounds[i]..bounds[i + 2]])
+                        .map(|rank| (*rank, i))
+                })
+                .min();
+            match best {
+                Some((_, i)) => {
+                    bounds.remove(i + 1);
+                }
+                None => return bounds.len() - 1,
+            }
+            if bounds.len() == 2 {
+                return 1;
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BpeTokenCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BpeTokenCounter")
+            .field("name", &self.name)
+            .field("tokens", &self.ranks.len())
+            .finish()
+    }
+}
+
+impl TokenCounter for BpeTokenCounter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        self.pieces(text)
+            .into_iter()
+            .map(|piece| self.count_piece(piece.as_bytes()))
+            .sum()
+    }
+}
+
+type CountFn = dyn Fn(&str) -> usize + Send + Sync;
+
+/// A [`TokenCounter`] backed by a user-provided function
+///
+/// ```rust
+/// use locai::tokens::{CallbackTokenCounter, TokenCounter};
+///
+/// // One token per word
+/// let counter = CallbackTokenCounter::new("words", |text| text.split_whitespace().count());
+/// assert_eq!(counter.count("three short words"), 3);
+/// ```
+#[derive(Clone)]
+pub struct CallbackTokenCounter {
+    name: String,
+    callback: Arc<CountFn>,
+}
+
+impl CallbackTokenCounter {
+    /// Wrap a count function
+    pub fn new<F>(name: impl Into<String>, callback: F) -> Self
+    where
+        F: Fn(&str) -> usize + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            callback: Arc::new(callback),
+        }
+    }
+}
+
+impl fmt::Debug for CallbackTokenCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackTokenCounter")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl TokenCounter for CallbackTokenCounter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        (self.callback)(text)
+    }
+}
+
+/// The token counter a configuration asks for
+pub fn counter_for(config: &TokenizerConfig) -> Result<Arc<dyn TokenCounter>> {
+    Ok(match &config.bpe_file {
+        Some(path) => Arc::new(BpeTokenCounter::from_file(path)?),
+        None => Arc::new(ApproxTokenCounter::new(config.chars_per_token)),
+    })
+}
+
+/// Split text into chunks of at most `max_tokens`, breaking between words
+///
+/// Each chunk after the first repeats up to `overlap` tokens from the end of
+/// the one before. A word too long for `max_tokens` on its own makes a chunk
+/// by itself.
+pub fn chunk_text(
+    counter: &dyn TokenCounter,
+    text: &str,
+    max_tokens: usize,
+    overlap: usize,
+) -> Vec<String> {
+    // Where each word starts, plus the end of the text
+    let mut bounds: Vec<usize> = text
+        .char_indices()
+        .zip(std::iter::once(' ').chain(text.chars()))
+        .filter(|((_, c), previous)| !c.is_whitespace() && previous.is_whitespace())
+        .map(|((index, _), _)| index)
+        .collect();
+    bounds.push(text.len());
+    let tokens = |from: usize, to: usize| counter.count(text[bounds[from]..bounds[to]].trim());
+
+    let last = bounds.len() - 1;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < last {
+        // The words from `start` up to `end` fit; up to `over` they don't
+        let (mut end, mut over) = (start + 1, last + 1);
+        while over - end > 1 {
+            let middle = (end + over) / 2;
+            if tokens(start, middle) <= max_tokens {
+                end = middle;
+            } else {
+                over = middle;
+            }
+        }
+        chunks.push(text[bounds[start]..bounds[end]].trim().to_string());
+        if end == last {
+            break;
+        }
+
+        // Back up over as many words as fit in the overlap
+        let mut next = end;
+        while next - 1 > start && tokens(next - 1, end) <= overlap {
+            next -= 1;
+        }
+        start = next;
+    }
+    chunks
+}
@@ -0,0 +1,1506 @@
+//! Agent memory tests: personas, preferences, procedures, reflections, reminders, tasks, sessions, tokens and templates
+
+mod common;
+
+mod persona {
+    //! Persona tests
+    //!
+    //! Each agent has one persona: setting it again rewrites it, earlier
+    //! revisions stay in the version history, and built contexts can open with it.
+
+    use locai::LocaiError;
+
+    use locai::memory::{ContextOptions, MemoryContext, Persona};
+    use locai::models::MemoryType;
+
+    use crate::common::create_manager;
+
+    fn release_manager() -> Persona {
+        Persona::new("agent-7", "A release manager for the platform team")
+            .with_trait("methodical")
+            .with_trait("calm under pressure")
+            .with_writing_style("short sentences, no jargon")
+            .with_constraint("Never promise a release date")
+    }
+
+    #[tokio::test]
+    async fn test_set_and_replace_persona() {
+        let (manager, _dir) = create_manager().await;
+        let first = manager.set_persona(release_manager()).await.unwrap();
+
+        let memory = manager.get_memory(&first.id).await.unwrap().unwrap();
+        assert_eq!(memory.memory_type, MemoryType::Identity);
+        assert_eq!(memory.content, release_manager().render());
+
+        // Setting the same profile changes nothing
+        let same = manager.set_persona(release_manager()).await.unwrap();
+        assert_eq!(same.updated_at, first.updated_at);
+
+        let second = manager
+            .set_persona(release_manager().with_constraint("Escalate outages at once"))
+            .await
+            .unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.constraints.len(), 2);
+
+        let persona = manager.get_persona("agent-7").await.unwrap().unwrap();
+        assert_eq!(persona, second);
+        assert_eq!(manager.list_personas().await.unwrap().len(), 1);
+
+        assert!(manager.remove_persona("agent-7").await.unwrap());
+        assert!(!manager.remove_persona("agent-7").await.unwrap());
+        assert!(manager.get_persona("agent-7").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_persona_history() {
+        let (manager, _dir) = create_manager().await;
+        manager.set_persona(release_manager()).await.unwrap();
+        manager
+            .set_persona(Persona::new("agent-7", "An incident commander").with_trait("decisive"))
+            .await
+            .unwrap();
+
+        let history = manager.persona_history("agent-7").await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0].identity,
+            "A release manager for the platform team"
+        );
+        assert_eq!(history[0].traits, vec!["methodical", "calm under pressure"]);
+        assert_eq!(
+            history[0].writing_style.as_deref(),
+            Some("short sentences, no jargon")
+        );
+        assert_eq!(history[0].constraints, vec!["Never promise a release date"]);
+        assert_eq!(history[1].identity, "An incident commander");
+        assert_eq!(history[1].writing_style, None);
+        assert!(history[0].updated_at <= history[1].updated_at);
+
+        assert!(manager.persona_history("agent-8").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_personas_are_refused() {
+        let (manager, _dir) = create_manager().await;
+        let error = manager
+            .set_persona(Persona::new("agent-7", " "))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Persona(_)), "{}", error);
+        let error = manager
+            .set_persona(Persona::new("", "A release manager"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Persona(_)), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_context_opens_with_persona() {
+        let (manager, _dir) = create_manager().await;
+        manager.set_persona(release_manager()).await.unwrap();
+        manager.set_preference("tone", "concise").await.unwrap();
+        manager
+            .add_fact("The release checklist lives in the wiki")
+            .await
+            .unwrap();
+
+        // The search matches the persona too, but it is only listed once
+        let options = ContextOptions::default().with_persona("agent-7");
+        let context = manager.build_context("release", &options).await.unwrap();
+        assert_eq!(context.persona.as_ref().unwrap().agent, "agent-7");
+        assert_eq!(context.memories.len(), 1);
+        assert!(context.render().starts_with(&format!(
+            "Persona:\n{}\n\nPreferences:\n- tone: concise\n\nMemories:",
+            release_manager().render()
+        )));
+
+        // The persona is the last thing dropped to fit a budget
+        let persona_only = MemoryContext {
+            persona: context.persona.clone(),
+            ..Default::default()
+        };
+        let fitted = context.fit_to_budget(persona_only.estimated_tokens());
+        assert!(fitted.memories.is_empty());
+        assert!(fitted.preferences.is_empty());
+        assert!(fitted.persona.is_some());
+
+        // Without a persona set for the agent, the context has none
+        let other = manager
+            .build_context(
+                "release",
+                &ContextOptions::default().with_persona("agent-8"),
+            )
+            .await
+            .unwrap();
+        assert!(other.persona.is_none());
+    }
+}
+
+mod preference {
+    //! Preference tests
+    //!
+    //! Preferences are key-value memories per scope: the last write wins, global
+    //! values apply unless a scope overrides them, earlier values stay in the
+    //! version history, and built contexts lead with them.
+
+    use locai::LocaiError;
+
+    use locai::memory::{ContextOptions, GLOBAL_PREFERENCE_SCOPE, MemoryContext};
+    use locai::models::MemoryType;
+
+    use crate::common::create_manager;
+
+    fn values(preferences: &[locai::memory::Preference]) -> Vec<(&str, &str)> {
+        preferences
+            .iter()
+            .map(|preference| (preference.key.as_str(), preference.value.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_last_write_wins() {
+        let (manager, _dir) = create_manager().await;
+        let first = manager.set_preference("tone", "concise").await.unwrap();
+        assert_eq!(first.scope, GLOBAL_PREFERENCE_SCOPE);
+
+        let memory = manager.get_memory(&first.id).await.unwrap().unwrap();
+        assert_eq!(memory.memory_type, MemoryType::Preference);
+        assert_eq!(memory.content, "tone: concise");
+
+        let second = manager.set_preference("tone", "detailed").await.unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.value, "detailed");
+        assert!(second.updated_at >= first.updated_at);
+
+        let tone = manager.get_preference("tone", None).await.unwrap().unwrap();
+        assert_eq!(tone.value, "detailed");
+        assert_eq!(manager.list_preferences().await.unwrap().len(), 1);
+
+        // Setting the same value again changes nothing
+        let same = manager.set_preference("tone", "detailed").await.unwrap();
+        assert_eq!(same.updated_at, second.updated_at);
+    }
+
+    #[tokio::test]
+    async fn test_scopes_override_global_preferences() {
+        let (manager, _dir) = create_manager().await;
+        manager.set_preference("tone", "concise").await.unwrap();
+        manager.set_preference("language", "en").await.unwrap();
+        manager
+            .set_scoped_preference("agent-7", "tone", "playful")
+            .await
+            .unwrap();
+
+        let global = manager.get_preferences(None).await.unwrap();
+        assert_eq!(
+            values(&global),
+            vec![("language", "en"), ("tone", "concise")]
+        );
+
+        let agent = manager.get_preferences(Some("agent-7")).await.unwrap();
+        assert_eq!(
+            values(&agent),
+            vec![("language", "en"), ("tone", "playful")]
+        );
+        assert_eq!(agent[1].scope, "agent-7");
+
+        let other = manager.get_preferences(Some("agent-8")).await.unwrap();
+        assert_eq!(values(&other), values(&global));
+
+        // Removing the override brings the global value back
+        assert!(manager.remove_preference("agent-7", "tone").await.unwrap());
+        assert!(!manager.remove_preference("agent-7", "tone").await.unwrap());
+        let tone = manager
+            .get_preference("tone", Some("agent-7"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tone.value, "concise");
+        assert!(
+            manager
+                .get_scoped_preference("agent-7", "tone")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preference_history() {
+        let (manager, _dir) = create_manager().await;
+        for value in ["concise", "detailed", "concise"] {
+            manager
+                .set_scoped_preference("ana", "tone", value)
+                .await
+                .unwrap();
+        }
+
+        let history = manager.preference_history("ana", "tone").await.unwrap();
+        let values: Vec<&str> = history.iter().map(|change| change.value.as_str()).collect();
+        assert_eq!(values, vec!["concise", "detailed", "concise"]);
+        assert!(
+            history
+                .windows(2)
+                .all(|pair| pair[0].changed_at <= pair[1].changed_at)
+        );
+
+        assert!(
+            manager
+                .preference_history("ana", "language")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_preferences_are_refused() {
+        let (manager, _dir) = create_manager().await;
+        let error = manager.set_preference("  ", "concise").await.unwrap_err();
+        assert!(matches!(error, LocaiError::Preference(_)), "{}", error);
+        let error = manager
+            .set_scoped_preference("", "tone", "concise")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Preference(_)), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_context_leads_with_preferences() {
+        let (manager, _dir) = create_manager().await;
+        manager.set_preference("tone", "concise").await.unwrap();
+        manager
+            .set_preference("lists", "release checklist items as bullets")
+            .await
+            .unwrap();
+        manager
+            .set_scoped_preference("agent-7", "tone", "playful")
+            .await
+            .unwrap();
+        manager
+            .add_fact("The release checklist lives in the wiki")
+            .await
+            .unwrap();
+
+        // The search matches the "lists" preference too, but it is only listed once
+        let options = ContextOptions::default().for_scope("agent-7");
+        let context = manager
+            .build_context("release checklist", &options)
+            .await
+            .unwrap();
+        assert_eq!(
+            values(&context.preferences),
+            vec![
+                ("lists", "release checklist items as bullets"),
+                ("tone", "playful")
+            ]
+        );
+        assert_eq!(context.memories.len(), 1);
+        assert_eq!(
+            context.render(),
+            "Preferences:\n- lists: release checklist items as bullets\n- tone: playful\n\n\
+         Memories:\n- The release checklist lives in the wiki"
+        );
+
+        let without = manager
+            .build_context(
+                "release checklist",
+                &ContextOptions::default().without_preferences(),
+            )
+            .await
+            .unwrap();
+        assert!(without.preferences.is_empty());
+        assert_eq!(without.memories.len(), 1);
+
+        // An empty query still gives the preferences
+        let empty = manager
+            .build_context("", &ContextOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(empty.preferences.len(), 2);
+        assert!(empty.memories.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_context_fits_token_budget() {
+        let (manager, _dir) = create_manager().await;
+        manager.set_preference("tone", "concise").await.unwrap();
+        for content in [
+            "Deploys happen on Tuesdays after the standup meeting",
+            "Deploys need a green build on the main branch first",
+        ] {
+            manager.add_fact(content).await.unwrap();
+        }
+
+        let full = manager
+            .build_context("deploys", &ContextOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(full.memories.len(), 2);
+
+        let budget = full.estimated_tokens() - 1;
+        let fitted = manager
+            .build_context("deploys", &ContextOptions::default().max_tokens(budget))
+            .await
+            .unwrap();
+        assert_eq!(fitted.memories.len(), 1);
+        assert_eq!(fitted.preferences.len(), 1);
+        assert!(fitted.estimated_tokens() <= budget);
+
+        assert!(full.fit_to_budget(0).is_empty());
+        assert_eq!(MemoryContext::default().render(), "");
+    }
+}
+
+mod procedure {
+    //! Procedure tests
+    //!
+    //! Procedures are procedural memories with structured steps, and are found
+    //! for a goal by text relevance plus the goal's entities they are linked to.
+
+    use locai::LocaiError;
+
+    use locai::memory::{Procedure, ProcedureStep};
+    use locai::models::MemoryType;
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    fn entity(id: &str, name: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "technology".to_string(),
+            properties: json!({ "name": name }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn contains(memory_id: &str, entity_id: &str) -> Relationship {
+        Relationship {
+            id: String::new(),
+            relationship_type: "contains".to_string(),
+            source_id: memory_id.to_string(),
+            target_id: entity_id.to_string(),
+            properties: json!({}),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn rotate_keys() -> Procedure {
+        Procedure::new("Rotate API keys", "Replace a leaked API key")
+            .precondition("Admin access to the key vault")
+            .step("Create a new key in the vault")
+            .step(ProcedureStep::new("Deploy the new key").expecting("Health checks pass"))
+            .step("Revoke the old key")
+            .success_metric("No requests signed with the old key")
+            .tag("security")
+    }
+
+    #[tokio::test]
+    async fn test_procedure_round_trip() {
+        let (manager, _dir) = create_manager().await;
+        let procedure = manager.create_procedure(rotate_keys()).await.unwrap();
+        assert_eq!(procedure.steps.len(), 3);
+        assert_eq!(
+            procedure.steps[1].expected.as_deref(),
+            Some("Health checks pass")
+        );
+
+        let memory = manager.get_memory(&procedure.id).await.unwrap().unwrap();
+        assert_eq!(memory.memory_type, MemoryType::Procedural);
+        assert!(memory.content.starts_with("Rotate API keys"));
+        assert!(
+            memory
+                .content
+                .contains("2. Deploy the new key (expect: Health checks pass)")
+        );
+        assert_eq!(memory.tags, vec!["security"]);
+
+        let fetched = manager.get_procedure(&procedure.id).await.unwrap().unwrap();
+        assert_eq!(fetched, procedure);
+
+        // Other memories aren't procedures
+        let fact = manager
+            .add_fact("The vault lives in us-east-1")
+            .await
+            .unwrap();
+        assert!(manager.get_procedure(&fact).await.unwrap().is_none());
+        assert_eq!(manager.list_procedures().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_procedures_are_refused() {
+        let (manager, _dir) = create_manager().await;
+        for procedure in [
+            Procedure::new("No steps", "Nothing"),
+            Procedure::new("  ", "Nameless").step("Do it"),
+            Procedure::new("Blank step", "Nothing")
+                .step("Begin")
+                .step(" "),
+        ] {
+            let error = manager.create_procedure(procedure).await.unwrap_err();
+            assert!(matches!(error, LocaiError::Procedure(_)), "{}", error);
+        }
+        assert!(manager.list_procedures().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_procedures_by_text() {
+        let (manager, _dir) = create_manager().await;
+        let keys = manager.create_procedure(rotate_keys()).await.unwrap();
+        manager
+            .create_procedure(
+                Procedure::new(
+                    "Restore a backup",
+                    "Bring the database back after data loss",
+                )
+                .step("Stop writes to the database")
+                .step("Load the latest snapshot"),
+            )
+            .await
+            .unwrap();
+        manager
+            .add_fact("Someone leaked an API key on the forum")
+            .await
+            .unwrap();
+
+        let matches = manager
+            .find_procedures_for("an API key leaked", None)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].procedure.id, keys.id);
+        assert_eq!(matches[0].text_score, 1.0);
+        assert!(matches[0].matched_entities.is_empty());
+
+        assert!(
+            manager
+                .find_procedures_for("bake sourdough", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_goal_entities_boost_procedures() {
+        let (manager, _dir) = create_manager().await;
+        manager
+            .create_entity(entity("postgres", "Postgres"))
+            .await
+            .unwrap();
+
+        let generic = manager
+            .create_procedure(
+                Procedure::new("Restore a backup", "Recover a database from a backup")
+                    .step("Stop writes")
+                    .step("Restore the backup"),
+            )
+            .await
+            .unwrap();
+        let specific = manager
+            .create_procedure(
+                Procedure::new("Point-in-time recovery", "Recover a database to a moment")
+                    .step("Pick the target time")
+                    .step("Replay the write-ahead log"),
+            )
+            .await
+            .unwrap();
+        manager
+            .storage()
+            .create_relationship(contains(&specific.id, "postgres"))
+            .await
+            .unwrap();
+
+        let matches = manager
+            .find_procedures_for("recover the Postgres database", Some(5))
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].procedure.id, specific.id);
+        assert_eq!(matches[0].matched_entities, vec!["Postgres"]);
+        assert_eq!(matches[0].entity_score, 1.0);
+        assert_eq!(matches[1].procedure.id, generic.id);
+        assert_eq!(matches[1].entity_score, 0.0);
+
+        let top = manager
+            .find_procedures_for("recover the Postgres database", Some(1))
+            .await
+            .unwrap();
+        assert_eq!(top.len(), 1);
+    }
+}
+
+mod reflection {
+    //! Reflection tests
+    //!
+    //! Reflection hands the memories from a time range to a reflector and stores
+    //! the insights it draws as wisdom memories linked to the memories they cite.
+
+    use std::sync::Arc;
+
+    use chrono::{Duration, Utc};
+    use locai::LocaiError;
+
+    use locai::memory::{CallbackReflector, DERIVED_FROM, Insight, TimeRange};
+    use locai::models::MemoryType;
+    use locai::storage::filters::RelationshipFilter;
+
+    use crate::common::create_manager;
+
+    /// Cites every memory given, plus one that wasn't
+    fn summarizer() -> Arc<CallbackReflector> {
+        Arc::new(CallbackReflector::new("summarizer", |topic, memories| {
+            let mut sources: Vec<String> =
+                memories.iter().map(|memory| memory.id.clone()).collect();
+            sources.push("made-up".to_string());
+            Ok(vec![
+                Insight::new(
+                    format!(
+                        "{} memories about {}",
+                        memories.len(),
+                        topic.unwrap_or("anything")
+                    ),
+                    sources,
+                ),
+                Insight::new("  ", Vec::new()),
+            ])
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_reflect_stores_linked_insights() {
+        let (manager, _dir) = create_manager().await;
+        let manager = manager.with_reflector(summarizer());
+        let deploy = manager.add_fact("Deploy day is Tuesday").await.unwrap();
+        let rollback = manager
+            .add_fact("The Tuesday deploy was rolled back")
+            .await
+            .unwrap();
+        manager.add_fact("Lunch is at noon").await.unwrap();
+
+        let insights = manager
+            .reflect(&TimeRange::last_hours(1), Some("deploy"))
+            .await
+            .unwrap();
+        assert_eq!(insights.len(), 1);
+        let insight = &insights[0];
+        assert_eq!(insight.memory_type, MemoryType::Wisdom);
+        assert_eq!(insight.content, "2 memories about deploy");
+        assert_eq!(insight.source, "reflection");
+        assert_eq!(insight.properties["reflection"]["reflector"], "summarizer");
+
+        let filter = RelationshipFilter {
+            relationship_type: Some(DERIVED_FROM.to_string()),
+            source_id: Some(insight.id.clone()),
+            ..Default::default()
+        };
+        let links = manager
+            .storage()
+            .list_relationships(Some(filter), None, None)
+            .await
+            .unwrap();
+        let mut targets: Vec<String> = links.into_iter().map(|link| link.target_id).collect();
+        targets.sort();
+        let mut expected = vec![deploy, rollback];
+        expected.sort();
+        assert_eq!(targets, expected);
+    }
+
+    #[tokio::test]
+    async fn test_reflect_without_topic_uses_the_time_range() {
+        let (manager, _dir) = create_manager().await;
+        let manager = manager.with_reflector(summarizer());
+        manager.add_fact("Deploy day is Tuesday").await.unwrap();
+        manager.add_fact("Lunch is at noon").await.unwrap();
+
+        let insights = manager
+            .reflect(&TimeRange::last_hours(1), None)
+            .await
+            .unwrap();
+        assert_eq!(insights[0].content, "2 memories about anything");
+
+        // The first insight is in range for the next reflection
+        let insights = manager
+            .reflect(&TimeRange::last_hours(1), None)
+            .await
+            .unwrap();
+        assert_eq!(insights[0].content, "3 memories about anything");
+
+        // Nothing to reflect on gives no insights
+        let past = TimeRange::new(
+            Utc::now() - Duration::days(2),
+            Utc::now() - Duration::days(1),
+        );
+        assert!(manager.reflect(&past, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reflect_needs_a_reflector() {
+        let (manager, _dir) = create_manager().await;
+        manager.add_fact("Deploy day is Tuesday").await.unwrap();
+        let error = manager
+            .reflect(&TimeRange::last_hours(1), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Reflection(_)), "{}", error);
+    }
+}
+
+mod reminder {
+    //! Memory reminder tests
+    //!
+    //! One-off reminders fire once; recurring ones move to their next occurrence,
+    //! and snoozing delays only the current occurrence.
+
+    use std::sync::Arc;
+    use std::time::Duration as StdDuration;
+
+    use chrono::{Duration, TimeZone, Utc};
+    use futures::StreamExt;
+    use locai::LocaiError;
+
+    use locai::memory::{Recurrence, Reminder, ReminderEvent};
+    use locai::messaging::LocaiMessaging;
+
+    use crate::common::create_manager;
+
+    #[tokio::test]
+    async fn test_one_off_reminder_fires_once() {
+        let (manager, _dir) = create_manager().await;
+        let due = manager.add_fact("Renew the passport").await.unwrap();
+        let later = manager.add_fact("Book the dentist").await.unwrap();
+
+        let remind_at = Utc::now() - Duration::seconds(1);
+        manager
+            .set_reminder(Reminder::new(&due, remind_at))
+            .await
+            .unwrap();
+        manager
+            .set_reminder(Reminder::new(&later, Utc::now() + Duration::days(1)))
+            .await
+            .unwrap();
+        assert_eq!(manager.list_reminders().await.unwrap().len(), 2);
+
+        let events = manager.fire_due_reminders().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].memory.id, due);
+        assert_eq!(events[0].due_at, remind_at);
+        assert!(events[0].next_at.is_none());
+
+        assert!(manager.get_reminder(&due).await.unwrap().is_none());
+        assert!(manager.get_reminder(&later).await.unwrap().is_some());
+        assert!(manager.fire_due_reminders().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recurring_reminder_moves_to_next_occurrence() {
+        let (manager, _dir) = create_manager().await;
+        let id = manager.add_fact("Water the plants").await.unwrap();
+
+        let remind_at = Utc::now() - Duration::hours(1);
+        manager
+            .set_reminder(Reminder::new(&id, remind_at).with_recurrence(Recurrence::Daily))
+            .await
+            .unwrap();
+
+        let events = manager.fire_due_reminders().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].next_at, Some(remind_at + Duration::days(1)));
+
+        let reminder = manager.get_reminder(&id).await.unwrap().unwrap();
+        assert_eq!(reminder.remind_at, remind_at + Duration::days(1));
+        assert_eq!(reminder.fired_count, 1);
+        assert!(reminder.last_fired_at.is_some());
+        assert!(manager.fire_due_reminders().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snoozed_reminder_waits() {
+        let (manager, _dir) = create_manager().await;
+        let id = manager.add_fact("Call back the landlord").await.unwrap();
+
+        let remind_at = Utc::now() - Duration::minutes(1);
+        manager
+            .set_reminder(Reminder::new(&id, remind_at).with_recurrence(Recurrence::Weekly))
+            .await
+            .unwrap();
+        let until = Utc::now() + Duration::hours(1);
+        let reminder = manager.snooze_reminder(&id, until).await.unwrap().unwrap();
+        assert_eq!(reminder.due_at(), until);
+        assert_eq!(reminder.remind_at, remind_at);
+
+        assert!(manager.fire_due_reminders().await.unwrap().is_empty());
+        assert!(
+            manager
+                .snooze_reminder("no-such-memory", until)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(manager.cancel_reminder(&id).await.unwrap());
+        assert!(!manager.cancel_reminder(&id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reminders_need_a_memory() {
+        let (manager, _dir) = create_manager().await;
+        let error = manager
+            .set_reminder(Reminder::new("no-such-memory", Utc::now()))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Memory(_)), "{}", error);
+
+        // A reminder on a memory deleted since is dropped without firing
+        let id = manager.add_fact("Short-lived").await.unwrap();
+        manager
+            .set_reminder(Reminder::new(&id, Utc::now() - Duration::seconds(1)))
+            .await
+            .unwrap();
+        manager.delete_memory(&id).await.unwrap();
+        assert!(manager.fire_due_reminders().await.unwrap().is_empty());
+        assert!(manager.list_reminders().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_recurrence_parsing() {
+        assert_eq!("weekly".parse::<Recurrence>().unwrap(), Recurrence::Weekly);
+        assert_eq!(
+            "every 90m".parse::<Recurrence>().unwrap(),
+            Recurrence::Every(StdDuration::from_secs(90 * 60))
+        );
+        assert_eq!(
+            "2h".parse::<Recurrence>().unwrap(),
+            Recurrence::Every(StdDuration::from_secs(2 * 60 * 60))
+        );
+        let every = Recurrence::Every(StdDuration::from_secs(90 * 60));
+        assert_eq!(every.to_string().parse::<Recurrence>().unwrap(), every);
+
+        for invalid in ["fortnightly", "0s", ""] {
+            let error = invalid.parse::<Recurrence>().unwrap_err();
+            assert!(matches!(error, LocaiError::Reminder(_)), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_missed_occurrences_are_skipped() {
+        let from = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 22, 12, 0, 0).unwrap();
+        assert_eq!(
+            Recurrence::Weekly.next_after(from, now),
+            Utc.with_ymd_and_hms(2025, 1, 27, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            Recurrence::Daily.next_after(from, now),
+            Utc.with_ymd_and_hms(2025, 1, 23, 9, 0, 0).unwrap()
+        );
+
+        // Month ends clamp to the shorter month
+        let month_end = Utc.with_ymd_and_hms(2025, 1, 31, 9, 0, 0).unwrap();
+        assert_eq!(
+            Recurrence::Monthly.next_after(month_end, month_end),
+            Utc.with_ymd_and_hms(2025, 2, 28, 9, 0, 0).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_due_reminders_are_published() {
+        let (manager, _dir) = create_manager().await;
+        let manager = Arc::new(manager);
+        let messaging = Arc::new(
+            LocaiMessaging::embedded(manager.clone(), "assistant".to_string())
+                .await
+                .unwrap(),
+        );
+        let mut reminders = messaging.subscribe_reminders().await.unwrap();
+
+        let id = manager.add_fact("Standup notes are due").await.unwrap();
+        manager
+            .set_reminder(Reminder::new(&id, Utc::now() - Duration::seconds(1)))
+            .await
+            .unwrap();
+        let _scheduler = messaging.start_reminders(StdDuration::from_millis(50));
+
+        let message = tokio::time::timeout(StdDuration::from_secs(5), reminders.next())
+            .await
+            .expect("no reminder published")
+            .unwrap()
+            .unwrap();
+        let event = ReminderEvent::from_message(&message).expect("not a reminder event");
+        assert_eq!(event.memory.id, id);
+        assert!(message.has_tag("reminder"));
+    }
+}
+
+mod task {
+    //! Task memory tests
+    //!
+    //! Tasks move through open → in_progress → done/cancelled, can be reopened,
+    //! and can't be started or completed while a dependency is unfinished.
+
+    use chrono::{Duration, Utc};
+    use locai::LocaiError;
+
+    use locai::memory::{Task, TaskQuery, TaskStatus};
+    use locai::models::MemoryType;
+
+    use crate::common::create_manager;
+
+    #[tokio::test]
+    async fn test_task_lifecycle() {
+        let (manager, _dir) = create_manager().await;
+        let task = manager
+            .create_task(Task::new("Write the migration guide").assigned_to("sam"))
+            .await
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Open);
+
+        let memory = manager.get_memory(&task.id).await.unwrap().unwrap();
+        assert_eq!(memory.memory_type, MemoryType::Task);
+        assert_eq!(memory.content, "Write the migration guide");
+
+        let task = manager
+            .set_task_status(&task.id, TaskStatus::InProgress)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::InProgress);
+        assert!(task.closed_at.is_none());
+
+        let task = manager
+            .set_task_status(&task.id, TaskStatus::Done)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(task.closed_at.is_some());
+
+        // A closed task can only be reopened
+        let error = manager
+            .set_task_status(&task.id, TaskStatus::InProgress)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Task(_)), "{}", error);
+
+        let task = manager
+            .set_task_status(&task.id, TaskStatus::Open)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Open);
+        assert!(task.closed_at.is_none());
+        assert_eq!(task.assignee.as_deref(), Some("sam"));
+
+        assert!(
+            manager
+                .set_task_status("no-such-task", TaskStatus::Done)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_tasks_by_assignee() {
+        let (manager, _dir) = create_manager().await;
+        let soon = manager
+            .create_task(
+                Task::new("Review the PR")
+                    .assigned_to("ana")
+                    .due(Utc::now() + Duration::hours(2)),
+            )
+            .await
+            .unwrap();
+        let later = manager
+            .create_task(
+                Task::new("Plan the offsite")
+                    .assigned_to("ana")
+                    .due(Utc::now() + Duration::days(10)),
+            )
+            .await
+            .unwrap();
+        let undated = manager
+            .create_task(Task::new("Tidy the backlog").assigned_to("ana"))
+            .await
+            .unwrap();
+        manager
+            .create_task(Task::new("Order a new laptop").assigned_to("ben"))
+            .await
+            .unwrap();
+        let finished = manager
+            .create_task(Task::new("Renew the certificate").assigned_to("ana"))
+            .await
+            .unwrap();
+        manager
+            .set_task_status(&finished.id, TaskStatus::Done)
+            .await
+            .unwrap();
+        manager.add_fact("Ana prefers mornings").await.unwrap();
+
+        let ids: Vec<String> = manager
+            .open_tasks(Some("ana"))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|task| task.id)
+            .collect();
+        assert_eq!(ids, vec![soon.id, later.id, undated.id]);
+        assert_eq!(manager.open_tasks(None).await.unwrap().len(), 4);
+
+        let done = manager
+            .list_tasks(&TaskQuery {
+                statuses: vec![TaskStatus::Done],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].id, finished.id);
+    }
+
+    #[tokio::test]
+    async fn test_overdue_tasks() {
+        let (manager, _dir) = create_manager().await;
+        let late = manager
+            .create_task(Task::new("File the expense report").due(Utc::now() - Duration::days(1)))
+            .await
+            .unwrap();
+        manager
+            .create_task(Task::new("Prepare the demo").due(Utc::now() + Duration::days(1)))
+            .await
+            .unwrap();
+
+        let overdue = manager.overdue_tasks().await.unwrap();
+        assert_eq!(overdue.len(), 1);
+        assert!(overdue[0].is_overdue(Utc::now()));
+
+        // Moving the due date takes it off the list
+        manager
+            .set_task_due(&late.id, Some(Utc::now() + Duration::days(2)))
+            .await
+            .unwrap();
+        assert!(manager.overdue_tasks().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_block_progress() {
+        let (manager, _dir) = create_manager().await;
+        let schema = manager
+            .create_task(Task::new("Design the schema"))
+            .await
+            .unwrap();
+        let api = manager
+            .create_task(Task::new("Build the API"))
+            .await
+            .unwrap();
+
+        assert!(
+            manager
+                .add_task_dependency(&api.id, &schema.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !manager
+                .add_task_dependency(&api.id, &schema.id)
+                .await
+                .unwrap()
+        );
+        assert_eq!(manager.task_blockers(&api.id).await.unwrap().len(), 1);
+
+        let error = manager
+            .set_task_status(&api.id, TaskStatus::InProgress)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Task(_)), "{}", error);
+
+        // Finishing the dependency unblocks the task
+        manager
+            .set_task_status(&schema.id, TaskStatus::Done)
+            .await
+            .unwrap();
+        assert!(manager.task_blockers(&api.id).await.unwrap().is_empty());
+        manager
+            .set_task_status(&api.id, TaskStatus::InProgress)
+            .await
+            .unwrap();
+
+        let dependencies = manager.task_dependencies(&api.id).await.unwrap();
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].id, schema.id);
+        assert!(
+            manager
+                .remove_task_dependency(&api.id, &schema.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !manager
+                .remove_task_dependency(&api.id, &schema.id)
+                .await
+                .unwrap()
+        );
+        assert!(manager.task_dependencies(&api.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dependency_cycles_are_refused() {
+        let (manager, _dir) = create_manager().await;
+        let a = manager.create_task(Task::new("A")).await.unwrap();
+        let b = manager.create_task(Task::new("B")).await.unwrap();
+        let c = manager.create_task(Task::new("C")).await.unwrap();
+        let fact = manager.add_fact("Not a task").await.unwrap();
+
+        manager.add_task_dependency(&a.id, &b.id).await.unwrap();
+        manager.add_task_dependency(&b.id, &c.id).await.unwrap();
+
+        for (id, depends_on) in [(&c.id, &a.id), (&a.id, &a.id), (&a.id, &fact)] {
+            let error = manager
+                .add_task_dependency(id, depends_on)
+                .await
+                .unwrap_err();
+            assert!(matches!(error, LocaiError::Task(_)), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        use TaskStatus::*;
+
+        assert!(Open.can_become(InProgress));
+        assert!(InProgress.can_become(Open));
+        assert!(InProgress.can_become(Done));
+        assert!(Open.can_become(Cancelled));
+        assert!(Done.can_become(Open));
+        assert!(!Done.can_become(Cancelled));
+        assert!(!Cancelled.can_become(InProgress));
+
+        assert_eq!("in-progress".parse::<TaskStatus>().unwrap(), InProgress);
+        assert_eq!(InProgress.to_string(), "in_progress");
+        assert!("paused".parse::<TaskStatus>().is_err());
+    }
+}
+
+mod session {
+    //! Session tests
+    //!
+    //! Turns pile up in a session until the older ones are folded into a rolling
+    //! summary; built contexts then give the summary and the recent turns.
+
+    use std::sync::Arc;
+
+    use crate::common::create_manager_with;
+    use locai::LocaiError;
+    use locai::config::SessionConfig;
+    use locai::core::MemoryManager;
+    use locai::memory::{CallbackSummarizer, ContextOptions};
+    use locai::models::MemoryPriority;
+    use tempfile::TempDir;
+
+    async fn create_manager(
+        summarize_every: usize,
+        keep_recent: usize,
+    ) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| {
+            config.with_sessions(SessionConfig {
+                summarize_every,
+                keep_recent,
+            })
+        })
+        .await
+    }
+
+    /// Summarizes by listing the turns' indexes after the previous summary
+    fn index_summarizer() -> Arc<CallbackSummarizer> {
+        Arc::new(CallbackSummarizer::new("indexes", |previous, turns| {
+            let mut summary: Vec<String> = previous.map(str::to_string).into_iter().collect();
+            summary.extend(turns.iter().map(|turn| turn.index.to_string()));
+            Ok(summary.join(" "))
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_turns_are_numbered_per_session() {
+        let (manager, _dir) = create_manager(0, 2).await;
+        manager.add_turn("s1", "user", "Hello").await.unwrap();
+        manager.add_turn("s2", "user", "Hi").await.unwrap();
+        let turn = manager
+            .add_turn("s1", "assistant", "Hello back")
+            .await
+            .unwrap();
+        assert_eq!(turn.index, 2);
+
+        let turns = manager.session_turns("s1").await.unwrap();
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].render(), "user: Hello");
+        assert_eq!(turns[1].render(), "assistant: Hello back");
+
+        let error = manager.add_turn("s1", "user", "  ").await.unwrap_err();
+        assert!(matches!(error, LocaiError::Session(_)), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_turns_roll_up_into_summaries() {
+        let (manager, _dir) = create_manager(3, 2).await;
+        let manager = manager.with_summarizer(index_summarizer());
+
+        for index in 1..=4 {
+            manager
+                .add_turn("s1", "user", &format!("Message {}", index))
+                .await
+                .unwrap();
+        }
+        assert!(manager.session_summary("s1").await.unwrap().is_none());
+
+        // The fifth turn makes keep_recent + summarize_every pending turns
+        manager.add_turn("s1", "user", "Message 5").await.unwrap();
+        let summary = manager.session_summary("s1").await.unwrap().unwrap();
+        assert_eq!(summary.content, "1 2 3");
+        assert_eq!(summary.through_turn, 3);
+
+        let turns = manager.session_turns("s1").await.unwrap();
+        let summarized: Vec<bool> = turns.iter().map(|turn| turn.summarized).collect();
+        assert_eq!(summarized, vec![true, true, true, false, false]);
+        let demoted = manager.get_memory(&turns[0].id).await.unwrap().unwrap();
+        assert_eq!(demoted.priority, MemoryPriority::Low);
+
+        // The next summary builds on the previous one
+        for index in 6..=8 {
+            manager
+                .add_turn("s1", "user", &format!("Message {}", index))
+                .await
+                .unwrap();
+        }
+        let summary = manager.session_summary("s1").await.unwrap().unwrap();
+        assert_eq!(summary.content, "1 2 3 4 5 6");
+    }
+
+    #[tokio::test]
+    async fn test_summarize_session_on_demand() {
+        let (manager, _dir) = create_manager(0, 1).await;
+        for content in ["One", "Two", "Three"] {
+            manager.add_turn("s1", "user", content).await.unwrap();
+        }
+
+        let error = manager.summarize_session("s1").await.unwrap_err();
+        assert!(matches!(error, LocaiError::Session(_)), "{}", error);
+
+        // Automatic summaries are off, so nothing was summarized along the way
+        let manager = manager.with_summarizer(index_summarizer());
+        let summary = manager.summarize_session("s1").await.unwrap().unwrap();
+        assert_eq!(summary.content, "1 2");
+        assert!(manager.summarize_session("s1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_context_uses_summary_and_recent_turns() {
+        let (manager, _dir) = create_manager(2, 1).await;
+        let manager = manager.with_summarizer(index_summarizer());
+        manager
+            .add_turn("s1", "user", "The export keeps timing out")
+            .await
+            .unwrap();
+        manager
+            .add_turn("s1", "assistant", "How large is the export?")
+            .await
+            .unwrap();
+        manager
+            .add_turn("s1", "user", "About two gigabytes of export data")
+            .await
+            .unwrap();
+        manager
+            .add_fact("An export over a gigabyte runs in the background")
+            .await
+            .unwrap();
+
+        // The search matches the turns too, but only the fact is a memory
+        let options = ContextOptions::default().for_session("s1");
+        let context = manager.build_context("export", &options).await.unwrap();
+        assert_eq!(context.summary.as_ref().unwrap().content, "1 2");
+        assert_eq!(context.turns.len(), 1);
+        assert_eq!(context.memories.len(), 1);
+        assert_eq!(
+            context.render(),
+            "Conversation so far:\n1 2\n\n\
+         Recent turns:\n- user: About two gigabytes of export data\n\n\
+         Memories:\n- An export over a gigabyte runs in the background"
+        );
+
+        // Without the session, demoted turns still stay out of the context
+        let context = manager
+            .build_context("export", &ContextOptions::default())
+            .await
+            .unwrap();
+        assert!(context.summary.is_none());
+        assert!(
+            context
+                .memories
+                .iter()
+                .all(|result| result.memory.content != "The export keeps timing out")
+        );
+    }
+}
+
+mod token {
+    //! Token counting tests
+    //!
+    //! Context budgets, chunking and session summary triggers all count tokens
+    //! with the manager's counter, whether estimated, loaded from a BPE file or
+    //! plugged in.
+
+    use std::sync::Arc;
+
+    use crate::common::create_manager_with;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use locai::LocaiError;
+    use locai::config::{ConfigBuilder, SessionConfig, TokenizerConfig};
+    use locai::core::MemoryManager;
+    use locai::memory::{CallbackSummarizer, ContextOptions};
+    use locai::tokens::{
+        ApproxTokenCounter, BpeTokenCounter, CallbackTokenCounter, TokenCounter, chunk_text,
+    };
+    use tempfile::TempDir;
+
+    async fn create_manager(sessions: SessionConfig) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| config.with_sessions(sessions)).await
+    }
+
+    fn word_counter() -> Arc<CallbackTokenCounter> {
+        Arc::new(CallbackTokenCounter::new("words", |text| {
+            text.split_whitespace().count()
+        }))
+    }
+
+    /// A vocabulary in tiktoken's format where "abc" and " abc" are one token
+    fn vocabulary() -> String {
+        ["a", "b", "c", " ", "ab", "abc", " abc"]
+            .iter()
+            .enumerate()
+            .map(|(rank, token)| format!("{} {}\n", STANDARD.encode(token), rank))
+            .collect()
+    }
+
+    #[test]
+    fn test_approx_counts_and_truncation() {
+        let counter = ApproxTokenCounter::new(2);
+        assert_eq!(counter.count(""), 0);
+        assert_eq!(counter.count("abcde"), 3);
+        assert_eq!(counter.truncate("abcdefgh", 2), "abcd");
+        assert_eq!(counter.truncate("héllo", 1), "hé");
+        assert_eq!(counter.truncate("short", 10), "short");
+    }
+
+    #[test]
+    fn test_bpe_counts() {
+        let counter = BpeTokenCounter::parse("test", &vocabulary()).unwrap();
+        assert_eq!(counter.count("abc"), 1);
+        // " ab" and " c" have no merge all the way, so they take two tokens each
+        assert_eq!(counter.count("abc abc ab c"), 6);
+        // The last space of a run goes with the word after it
+        assert_eq!(counter.count("abc  abc"), 3);
+
+        let error = BpeTokenCounter::parse("test", "YWJj not-a-rank").unwrap_err();
+        assert!(matches!(error, LocaiError::Configuration(_)), "{}", error);
+        assert!(BpeTokenCounter::parse("test", "").is_err());
+    }
+
+    #[test]
+    fn test_chunk_text() {
+        let counter = CallbackTokenCounter::new("words", |text| text.split_whitespace().count());
+        let text = "one two three four five six seven";
+        assert_eq!(
+            chunk_text(&counter, text, 3, 0),
+            vec!["one two three", "four five six", "seven"]
+        );
+        assert_eq!(
+            chunk_text(&counter, text, 3, 1),
+            vec!["one two three", "three four five", "five six seven"]
+        );
+        assert!(chunk_text(&counter, "  ", 3, 0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_manager_loads_bpe_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tiny.tiktoken");
+        std::fs::write(&path, vocabulary()).unwrap();
+
+        let config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .with_tokenizer(TokenizerConfig {
+                bpe_file: Some(path),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let manager = locai::init(config).await.unwrap();
+        assert_eq!(manager.token_counter().name(), "tiny");
+        assert_eq!(manager.token_counter().count("abc abc"), 2);
+        assert_eq!(
+            manager.chunk_text("abc abc abc", 2, 0),
+            vec!["abc abc", "abc"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_context_budget_uses_manager_counter() {
+        let (manager, _dir) = create_manager(SessionConfig::default()).await;
+        let manager = manager.with_token_counter(word_counter());
+        for step in ["one", "two", "three"] {
+            manager
+                .add_fact(&format!("release step {}", step))
+                .await
+                .unwrap();
+        }
+
+        // "Memories:" and two lines of four words each
+        let options = ContextOptions::default().max_tokens(9);
+        let context = manager.build_context("release", &options).await.unwrap();
+        assert_eq!(context.memories.len(), 2);
+        assert_eq!(context.count_tokens(manager.token_counter().as_ref()), 9);
+    }
+
+    #[tokio::test]
+    async fn test_long_turns_trigger_summaries() {
+        let (manager, _dir) = create_manager(SessionConfig {
+            summarize_every: 100,
+            keep_recent: 1,
+            max_pending_tokens: 10,
+        })
+        .await;
+        let manager = manager
+            .with_token_counter(word_counter())
+            .with_summarizer(Arc::new(CallbackSummarizer::new("count", |_, turns| {
+                Ok(format!("{} turns", turns.len()))
+            })));
+
+        // Each turn renders as five words
+        manager.add_turn("s1", "user", "a b c d").await.unwrap();
+        manager.add_turn("s1", "user", "e f g h").await.unwrap();
+        assert!(manager.session_summary("s1").await.unwrap().is_none());
+
+        manager.add_turn("s1", "user", "i j k l").await.unwrap();
+        let summary = manager.session_summary("s1").await.unwrap().unwrap();
+        assert_eq!(summary.content, "2 turns");
+    }
+}
+
+mod template {
+    //! Memory template tests
+    //!
+    //! Templates fill content, tags and typed properties from variables.
+
+    use locai::LocaiError;
+
+    use locai::memory::{MemoryTemplate, Placeholder, PlaceholderType};
+    use locai::models::MemoryType;
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    #[tokio::test]
+    async fn test_builtin_meeting_template() {
+        let (manager, _dir) = create_manager().await;
+
+        let id = manager
+            .remember_from_template(
+                "meeting",
+                [
+                    ("title", json!("Standup")),
+                    ("date", json!("2025-11-03")),
+                    ("attendees", json!("Ana, Raj")),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(
+            memory.content,
+            "Meeting: Standup on 2025-11-03 with Ana, Raj. Notes: "
+        );
+        assert_eq!(memory.memory_type, MemoryType::Episodic);
+        assert!(memory.tags.contains(&"meeting".to_string()));
+        assert_eq!(memory.properties["attendees"], json!(["Ana", "Raj"]));
+        assert_eq!(memory.properties["template"], json!("meeting"));
+    }
+
+    #[tokio::test]
+    async fn test_template_variable_errors() {
+        let (manager, _dir) = create_manager().await;
+
+        let missing = manager
+            .remember_from_template("meeting", [("date", json!("2025-11-03"))])
+            .await
+            .unwrap_err();
+        assert!(matches!(missing, LocaiError::Template(_)), "{}", missing);
+
+        let mistyped = manager
+            .remember_from_template(
+                "meeting",
+                [("title", json!("Standup")), ("date", json!("next week"))],
+            )
+            .await
+            .unwrap_err();
+        assert!(mistyped.to_string().contains("date"), "{}", mistyped);
+
+        let unknown = manager
+            .remember_from_template(
+                "meeting",
+                [
+                    ("title", json!("Standup")),
+                    ("date", json!("2025-11-03")),
+                    ("room", json!("B2")),
+                ],
+            )
+            .await
+            .unwrap_err();
+        assert!(unknown.to_string().contains("room"), "{}", unknown);
+
+        let no_template = manager
+            .remember_from_template("nope", Vec::<(String, String)>::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(no_template, LocaiError::Template(_)));
+    }
+
+    #[tokio::test]
+    async fn test_registered_templates() {
+        let (manager, _dir) = create_manager().await;
+
+        let undeclared = MemoryTemplate::new("workout", "Ran {{distance}} km");
+        assert!(manager.register_template(undeclared).await.is_err());
+
+        let template = MemoryTemplate::new("workout", "Ran {{distance}} km")
+            .with_tag("fitness")
+            .with_property("distance_km", json!("{{distance}}"))
+            .with_property("outdoor", json!("{{outdoor}}"))
+            .with_placeholder(Placeholder::new("distance", PlaceholderType::Number))
+            .with_placeholder(
+                Placeholder::optional("outdoor", PlaceholderType::Boolean)
+                    .with_default(json!(true)),
+            );
+        manager.register_template(template).await.unwrap();
+        assert!(manager.get_template("workout").await.unwrap().is_some());
+        assert!(manager.list_templates().await.unwrap().len() >= 3);
+
+        let id = manager
+            .remember_from_template("workout", [("distance", "5.5")])
+            .await
+            .unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(memory.content, "Ran 5.5 km");
+        assert_eq!(memory.properties["distance_km"], json!(5.5));
+        assert_eq!(memory.properties["outdoor"], json!(true));
+
+        assert!(manager.remove_template("workout").await.unwrap());
+        assert!(!manager.remove_template("meeting").await.unwrap());
+        assert!(manager.get_template("meeting").await.unwrap().is_some());
+    }
+}
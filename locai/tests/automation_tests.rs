@@ -0,0 +1,927 @@
+//! Automation tests: automations, rules, the store pipeline, plugins and bulk ingestion
+
+mod common;
+
+mod automation {
+    //! Automation tests
+    //!
+    //! Automations are rules written as configuration: a trigger on a memory
+    //! event and actions run by the hook registry when it matches.
+
+    use std::time::Duration;
+
+    use locai::config::{
+        AutomationAction, AutomationRule, AutomationTrigger, ConfigBuilder, ConfigLoader,
+        RulesConfig,
+    };
+    use locai::memory::RuleEvent;
+    use locai::models::{Memory, MemoryPriority, MemoryType};
+    use tempfile::TempDir;
+
+    fn escalate_urgent() -> AutomationRule {
+        AutomationRule {
+            name: "escalate-urgent".to_string(),
+            trigger: AutomationTrigger {
+                event: RuleEvent::Created,
+                tags: vec!["urgent".to_string()],
+                memory_type: None,
+                content_contains: None,
+                source: None,
+            },
+            actions: vec![
+                AutomationAction::SetPriority("high".to_string()),
+                AutomationAction::AddTag("escalated".to_string()),
+                AutomationAction::CreateRelationship {
+                    target: "incident-42".to_string(),
+                    relationship_type: "part_of".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_loader_reads_automations_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let automations = temp_dir.path().join("automations.yaml");
+        std::fs::write(
+            &automations,
+            r#"
+automations:
+  - name: escalate-urgent
+    trigger:
+      event: memory.created
+      tags: [urgent]
+    actions:
+      - set_priority: high
+      - add_tag: escalated
+      - webhook: { url: "https://hooks.example.com/urgent" }
+"#,
+        )
+        .unwrap();
+        let config_file = temp_dir.path().join("config.yaml");
+        std::fs::write(
+            &config_file,
+            format!(
+                r#"
+rules:
+  automations_file: "{}"
+  automations:
+    - name: tag-reminders
+      trigger:
+        event: reminder
+      actions:
+        - add_tag: surfaced
+"#,
+                automations.display()
+            ),
+        )
+        .unwrap();
+
+        let mut loader = ConfigLoader::new();
+        loader.load_file(&config_file).unwrap();
+        let config = loader.extract().unwrap();
+
+        let names: Vec<&str> = config
+            .rules
+            .automations
+            .iter()
+            .map(|automation| automation.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["tag-reminders", "escalate-urgent"]);
+
+        let escalate = &config.rules.automations[1];
+        assert_eq!(escalate.trigger.event, RuleEvent::Created);
+        assert_eq!(escalate.trigger.tags, vec!["urgent"]);
+        assert!(matches!(
+            &escalate.actions[2],
+            AutomationAction::Webhook { url, .. } if url == "https://hooks.example.com/urgent"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_automations_are_rejected() {
+        let mut automation = escalate_urgent();
+        automation.actions = vec![AutomationAction::SetPriority("soon".to_string())];
+        let result = ConfigBuilder::new()
+            .with_memory_storage()
+            .with_rules(RulesConfig {
+                automations: vec![automation],
+                ..RulesConfig::default()
+            })
+            .build();
+        assert!(result.is_err());
+
+        let mut automation = escalate_urgent();
+        automation.actions = Vec::new();
+        let result = ConfigBuilder::new()
+            .with_memory_storage()
+            .with_rules(RulesConfig {
+                automations: vec![automation],
+                ..RulesConfig::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_automations_run_on_matching_memories() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .with_rules(RulesConfig {
+                automations: vec![escalate_urgent()],
+                ..RulesConfig::default()
+            })
+            .build()
+            .expect("Failed to build config");
+        let manager = locai::init(config).await.expect("Failed to init");
+
+        manager
+            .store_memory(Memory::new(
+                "incident-42".to_string(),
+                "Deploys are failing across the fleet".to_string(),
+                MemoryType::Event,
+            ))
+            .await
+            .unwrap();
+        let urgent = manager
+            .add_memory_with_options("The deploy is failing".to_string(), |b| b.tag("urgent"))
+            .await
+            .unwrap();
+        let calm = manager.add_fact("The tide turns at noon").await.unwrap();
+
+        for _ in 0..50 {
+            let memory = manager.get_memory(&urgent).await.unwrap().unwrap();
+            if memory.priority == MemoryPriority::High {
+                assert!(memory.tags.contains(&"escalated".to_string()));
+                let related = manager
+                    .get_related_memories(&urgent, Some("part_of"), "outgoing")
+                    .await
+                    .unwrap();
+                assert!(related.iter().any(|memory| memory.id == "incident-42"));
+
+                let calm = manager.get_memory(&calm).await.unwrap().unwrap();
+                assert_eq!(calm.priority, MemoryPriority::Normal);
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Automation never escalated memory {}", urgent);
+    }
+}
+
+#[cfg(feature = "rhai-rules")]
+mod rule {
+    //! Automation rule tests
+    //!
+    //! Rules are Rhai scripts stored as memories and run on memory events once
+    //! `enable_rules` registers them with the storage's hooks.
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use locai::core::MemoryManager;
+    use locai::memory::{Rule, RuleEvent};
+    use locai::models::MemoryPriority;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const ESCALATE: &str = r#"
+if memory.tags.contains("urgent") {
+    memory.priority = "high";
+    memory.properties.escalated = true;
+    send("alerts", "Urgent: " + memory.content);
+}
+"#;
+
+    async fn create_manager() -> (Arc<MemoryManager>, TempDir) {
+        let (manager, temp_dir) = crate::common::create_manager().await;
+        (Arc::new(manager), temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_set_list_and_remove_rules() {
+        let (manager, _temp_dir) = create_manager().await;
+
+        let rule = manager
+            .set_rule(
+                Rule::new("escalate", RuleEvent::Created, ESCALATE)
+                    .with_description("Urgent first"),
+            )
+            .await
+            .unwrap();
+        manager
+            .set_rule(Rule::new(
+                "audit",
+                RuleEvent::Deleted,
+                "send(\"audit\", memory.id);",
+            ))
+            .await
+            .unwrap();
+
+        // Setting a rule again rewrites the same memory
+        let replaced = manager
+            .set_rule(Rule::new("escalate", RuleEvent::Accessed, ESCALATE))
+            .await
+            .unwrap();
+        assert_eq!(replaced.id, rule.id);
+        assert_eq!(replaced.event, RuleEvent::Accessed);
+
+        let names: Vec<String> = manager
+            .list_rules()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|rule| rule.name)
+            .collect();
+        assert_eq!(names, vec!["audit", "escalate"]);
+
+        assert!(manager.remove_rule("audit").await.unwrap());
+        assert!(!manager.remove_rule("audit").await.unwrap());
+        assert!(manager.get_rule("audit").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_rules_are_rejected() {
+        let (manager, _temp_dir) = create_manager().await;
+
+        let unparsable = manager
+            .set_rule(Rule::new("broken", RuleEvent::Created, "if memory {"))
+            .await;
+        assert!(matches!(unparsable, Err(locai::LocaiError::Rule(_))));
+
+        let unnamed = manager
+            .set_rule(Rule::new("two words", RuleEvent::Created, "()"))
+            .await;
+        assert!(matches!(unnamed, Err(locai::LocaiError::Rule(_))));
+
+        assert!("renamed".parse::<RuleEvent>().is_err());
+        assert!(manager.list_rules().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_reports_changes_without_saving() {
+        let (manager, _temp_dir) = create_manager().await;
+        let id = manager
+            .add_memory_with_options("The deploy is failing".to_string(), |b| b.tag("urgent"))
+            .await
+            .unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+
+        let rule = Rule::new("escalate", RuleEvent::Created, ESCALATE);
+        let outcome = manager
+            .test_rule(&rule, RuleEvent::Created, &memory)
+            .unwrap();
+        assert_eq!(outcome.fired, vec!["escalate"]);
+        let changed = outcome.memory.unwrap();
+        assert_eq!(changed.priority, MemoryPriority::High);
+        assert_eq!(changed.properties["escalated"], json!(true));
+        assert_eq!(outcome.messages.len(), 1);
+        assert_eq!(outcome.messages[0].topic, "alerts");
+        assert_eq!(
+            outcome.messages[0].content,
+            json!("Urgent: The deploy is failing")
+        );
+
+        // Changes on updates are discarded, messages are still sent
+        let outcome = manager
+            .test_rule(&rule, RuleEvent::Updated, &memory)
+            .unwrap();
+        assert!(outcome.memory.is_none());
+        assert_eq!(outcome.messages.len(), 1);
+
+        let stored = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(stored.priority, MemoryPriority::Normal);
+    }
+
+    #[tokio::test]
+    async fn test_failing_and_runaway_scripts_are_reported() {
+        let (manager, _temp_dir) = create_manager().await;
+        let id = manager.add_fact("The tide turns at noon").await.unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+
+        let bad_priority = Rule::new("bad", RuleEvent::Created, "memory.priority = \"soon\";");
+        let outcome = manager
+            .test_rule(&bad_priority, RuleEvent::Created, &memory)
+            .unwrap();
+        assert!(outcome.fired.is_empty());
+        assert_eq!(outcome.failures.len(), 1);
+
+        let runaway = Rule::new("loop", RuleEvent::Created, "loop {}");
+        let outcome = manager
+            .test_rule(&runaway, RuleEvent::Created, &memory)
+            .unwrap();
+        assert_eq!(outcome.failures.len(), 1);
+        assert!(outcome.memory.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_enabled_rules_run_on_memory_events() {
+        let (manager, _temp_dir) = create_manager().await;
+        manager.enable_rules().await.unwrap();
+        manager
+            .set_rule(Rule::new("escalate", RuleEvent::Created, ESCALATE))
+            .await
+            .unwrap();
+        manager
+            .set_rule(
+                Rule::new("quiet", RuleEvent::Created, "memory.priority = \"low\";")
+                    .with_enabled(false),
+            )
+            .await
+            .unwrap();
+
+        let urgent = manager
+            .add_memory_with_options("The deploy is failing".to_string(), |b| b.tag("urgent"))
+            .await
+            .unwrap();
+        let calm = manager.add_fact("The tide turns at noon").await.unwrap();
+
+        for _ in 0..50 {
+            let memory = manager.get_memory(&urgent).await.unwrap().unwrap();
+            if memory.priority == MemoryPriority::High {
+                assert_eq!(memory.properties["escalated"], json!(true));
+                let calm = manager.get_memory(&calm).await.unwrap().unwrap();
+                assert_eq!(calm.priority, MemoryPriority::Normal);
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Rule never escalated memory {}", urgent);
+    }
+}
+
+mod pipeline {
+    //! Store pipeline tests
+    //!
+    //! Memories pass through the configured stages in order before they are
+    //! stored, and applications can add stages of their own.
+
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use crate::common::create_manager_with;
+    use async_trait::async_trait;
+    use locai::LocaiError;
+    use locai::config::{ChunkingConfig, ConfigBuilder, PiiKind, PipelineConfig, RedactionConfig};
+    use locai::core::MemoryManager;
+    use locai::memory::pipeline::{
+        CHUNK_COUNT_PROPERTY, CHUNK_OF_PROPERTY, PendingMemory, StoreFlow, StoreMiddleware,
+    };
+    use locai::models::Memory;
+    use locai::storage::filters::MemoryFilter;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Records what it sees, tagging memories as they pass
+    #[derive(Debug, Default)]
+    struct Recorder {
+        name: String,
+        seen: Mutex<Vec<String>>,
+        stored: Mutex<Vec<String>>,
+    }
+
+    impl Recorder {
+        fn named(name: &str) -> Arc<Self> {
+            Arc::new(Self {
+                name: name.to_string(),
+                ..Self::default()
+            })
+        }
+    }
+
+    #[async_trait]
+    impl StoreMiddleware for Recorder {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn before_store(&self, pending: &mut PendingMemory) -> locai::Result<StoreFlow> {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(pending.memory.content.clone());
+            pending.memory.add_tag(&self.name);
+            Ok(StoreFlow::Continue)
+        }
+
+        async fn after_store(&self, memory: &Memory) {
+            self.stored.lock().unwrap().push(memory.id.clone());
+        }
+    }
+
+    /// Refuses every memory
+    #[derive(Debug)]
+    struct Refuse;
+
+    #[async_trait]
+    impl StoreMiddleware for Refuse {
+        fn name(&self) -> &str {
+            "refuse"
+        }
+
+        async fn before_store(&self, _pending: &mut PendingMemory) -> locai::Result<StoreFlow> {
+            Err(LocaiError::Memory("Refused".to_string()))
+        }
+    }
+
+    async fn create_manager(pipeline: PipelineConfig) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| config.with_pipeline(pipeline)).await
+    }
+
+    fn redacting(kinds: Vec<PiiKind>) -> PipelineConfig {
+        PipelineConfig {
+            redaction: RedactionConfig {
+                kinds,
+                ..RedactionConfig::default()
+            },
+            ..PipelineConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_pipeline_runs_builtin_stages_in_order() {
+        let (manager, _temp_dir) = create_manager(PipelineConfig::default()).await;
+        assert_eq!(
+            manager.store_pipeline().stage_names(),
+            vec!["dedup", "redact", "chunk", "extract", "embed"]
+        );
+
+        // Nothing is redacted or chunked until configured
+        let id = manager
+            .add_fact("Mail jane@example.com about the report")
+            .await
+            .unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(memory.content, "Mail jane@example.com about the report");
+    }
+
+    #[tokio::test]
+    async fn test_redact_stage_replaces_personal_data() {
+        let (manager, _temp_dir) = create_manager(redacting(vec![
+            PiiKind::Email,
+            PiiKind::Phone,
+            PiiKind::Ssn,
+        ]))
+        .await;
+
+        let id = manager
+            .add_fact("Reach jane@example.com or 555-123-4567; SSN 123-45-6789")
+            .await
+            .unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(
+            memory.content,
+            "Reach [REDACTED] or [REDACTED]; SSN [REDACTED]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redact_stage_applies_custom_patterns() {
+        let mut pipeline = PipelineConfig::default();
+        pipeline.redaction.patterns = vec![r"ACCT-\d+".to_string()];
+        pipeline.redaction.replacement = "<account>".to_string();
+        let (manager, _temp_dir) = create_manager(pipeline).await;
+
+        let id = manager.add_fact("Charge ACCT-99812 today").await.unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(memory.content, "Charge <account> today");
+    }
+
+    #[tokio::test]
+    async fn test_chunk_stage_stores_chunks_beside_long_memories() {
+        let pipeline = PipelineConfig {
+            chunking: ChunkingConfig {
+                max_tokens: Some(8),
+                overlap: 0,
+            },
+            ..PipelineConfig::default()
+        };
+        let (manager, _temp_dir) = create_manager(pipeline).await;
+
+        let content = "The lighthouse keeper logged every ship that passed the point. \
+                   Storms came from the west in autumn and from the north in winter.";
+        let id = manager.add_fact(content).await.unwrap();
+        let parent = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(parent.content, content);
+
+        let chunks = manager
+            .filter_memories(
+                MemoryFilter {
+                    properties: Some(HashMap::from([(CHUNK_OF_PROPERTY.to_string(), json!(id))])),
+                    ..MemoryFilter::default()
+                },
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(chunks.len() > 1);
+        assert_eq!(
+            parent.properties[CHUNK_COUNT_PROPERTY].as_u64(),
+            Some(chunks.len() as u64)
+        );
+        assert!(chunks.iter().all(|chunk| content.contains(&chunk.content)));
+
+        // Short memories aren't chunked
+        let short = manager.add_fact("Fog at dawn").await.unwrap();
+        let short = manager.get_memory(&short).await.unwrap().unwrap();
+        assert!(short.properties.get(CHUNK_COUNT_PROPERTY).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_added_middleware_runs_at_hooks() {
+        let (manager, _temp_dir) = create_manager(PipelineConfig::default()).await;
+        let recorder = Recorder::named("recorder");
+        let manager = manager.with_store_middleware(recorder.clone());
+        assert_eq!(
+            manager.store_pipeline().stage_names().last(),
+            Some(&"recorder")
+        );
+
+        let id = manager.add_fact("The ferry leaves at nine").await.unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert!(memory.tags.contains(&"recorder".to_string()));
+        assert_eq!(*recorder.stored.lock().unwrap(), vec![id]);
+    }
+
+    #[tokio::test]
+    async fn test_named_middleware_runs_where_listed() {
+        let mut pipeline = redacting(vec![PiiKind::Email]);
+        pipeline.stages = vec![
+            "before".to_string(),
+            "redact".to_string(),
+            "after".to_string(),
+        ];
+        let (manager, _temp_dir) = create_manager(pipeline).await;
+        let before = Recorder::named("before");
+        let after = Recorder::named("after");
+        let manager = manager
+            .with_store_middleware(after.clone())
+            .with_store_middleware(before.clone());
+        assert_eq!(
+            manager.store_pipeline().stage_names(),
+            vec!["before", "redact", "after"]
+        );
+
+        manager.add_fact("Ask bob@example.com").await.unwrap();
+        assert_eq!(*before.seen.lock().unwrap(), vec!["Ask bob@example.com"]);
+        assert_eq!(*after.seen.lock().unwrap(), vec!["Ask [REDACTED]"]);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_error_stops_the_store() {
+        let (manager, _temp_dir) = create_manager(PipelineConfig::default()).await;
+        let manager = manager.with_store_middleware(Arc::new(Refuse));
+
+        let result = manager.add_fact("Never stored").await;
+        assert!(result.is_err());
+        assert_eq!(manager.count_memories(None).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_redaction_pattern_is_rejected() {
+        let mut pipeline = PipelineConfig::default();
+        pipeline.redaction.patterns = vec!["(unclosed".to_string()];
+        let result = ConfigBuilder::new()
+            .with_memory_storage()
+            .with_pipeline(pipeline)
+            .build();
+        assert!(result.is_err());
+    }
+}
+
+mod plugin {
+    //! Plugin tests
+    //!
+    //! Extension crates register plugins by name, and configuration enables
+    //! them when Locai starts. Tests share the global registry, so each
+    //! registers under names of its own.
+
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::common::init_with;
+    use async_trait::async_trait;
+    use locai::config::{ConfigBuilder, PluginSpec, PluginsConfig};
+    use locai::core::MemoryManager;
+    use locai::hooks::{HookResult, MemoryHook};
+    use locai::memory::pipeline::{PendingMemory, StoreFlow, StoreMiddleware};
+    use locai::models::Memory;
+    use locai::plugins::{PluginKind, PluginRegistry};
+    use locai::search::rerank::{CallbackReranker, Reranker};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Tags memories with the tag it was configured with
+    #[derive(Debug)]
+    struct Tagger {
+        tag: String,
+    }
+
+    #[async_trait]
+    impl StoreMiddleware for Tagger {
+        fn name(&self) -> &str {
+            "tagger"
+        }
+
+        async fn before_store(&self, pending: &mut PendingMemory) -> locai::Result<StoreFlow> {
+            pending.memory.add_tag(&self.tag);
+            Ok(StoreFlow::Continue)
+        }
+    }
+
+    /// Records the memories created
+    #[derive(Debug, Default)]
+    struct CreatedHook {
+        created: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl MemoryHook for CreatedHook {
+        async fn on_memory_created(&self, memory: &Memory) -> HookResult {
+            self.created.lock().unwrap().push(memory.id.clone());
+            HookResult::Continue
+        }
+    }
+
+    async fn create_manager(plugins: PluginsConfig) -> (locai::Result<MemoryManager>, TempDir) {
+        init_with(|config| config.with_plugins(plugins)).await
+    }
+
+    #[test]
+    fn test_registry_rejects_empty_and_duplicate_names() {
+        let registry = PluginRegistry::new();
+        let reranker = |_: &serde_json::Value| -> locai::Result<Arc<dyn Reranker>> {
+            Ok(Arc::new(CallbackReranker::new("flat", |_, documents| {
+                Ok(vec![0.5; documents.len()])
+            })))
+        };
+
+        assert!(registry.register_reranker("", reranker).is_err());
+        registry.register_reranker("flat", reranker).unwrap();
+        assert!(registry.register_reranker("flat", reranker).is_err());
+        registry.register_reranker("another", reranker).unwrap();
+
+        // Names are per kind
+        assert_eq!(
+            registry.registered(PluginKind::Reranker),
+            vec!["another", "flat"]
+        );
+        assert!(registry.registered(PluginKind::Hook).is_empty());
+
+        assert!(registry.unregister(PluginKind::Reranker, "flat"));
+        assert!(!registry.unregister(PluginKind::Reranker, "flat"));
+        assert_eq!(registry.registered(PluginKind::Reranker), vec!["another"]);
+    }
+
+    #[tokio::test]
+    async fn test_configured_plugins_are_attached_with_their_options() {
+        let registry = PluginRegistry::global();
+        registry
+            .register_store_middleware("test-tagger", |options| {
+                let tag = options["tag"].as_str().unwrap_or("untagged").to_string();
+                Ok(Arc::new(Tagger { tag }))
+            })
+            .unwrap();
+        registry
+            .register_reranker("test-reranker", |_| {
+                Ok(Arc::new(CallbackReranker::new(
+                    "test-reranker",
+                    |_, documents| Ok(vec![1.0; documents.len()]),
+                )))
+            })
+            .unwrap();
+
+        let (manager, _temp_dir) = create_manager(PluginsConfig {
+            store_middlewares: vec![
+                PluginSpec::new("test-tagger").with_options(json!({ "tag": "from-plugin" })),
+            ],
+            reranker: Some(PluginSpec::new("test-reranker")),
+            ..PluginsConfig::default()
+        })
+        .await;
+        let manager = manager.unwrap();
+
+        assert_eq!(
+            manager.store_pipeline().stage_names().last(),
+            Some(&"tagger")
+        );
+        assert_eq!(
+            manager.reranker().map(|reranker| reranker.name()),
+            Some("test-reranker")
+        );
+
+        let id = manager.add_fact("The tide turns at noon").await.unwrap();
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert!(memory.tags.contains(&"from-plugin".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_hook_plugins_receive_memory_events() {
+        let created = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&created);
+        PluginRegistry::global()
+            .register_hook("test-created-hook", move |_| {
+                Ok(Arc::new(CreatedHook {
+                    created: Arc::clone(&seen),
+                }))
+            })
+            .unwrap();
+
+        let (manager, _temp_dir) = create_manager(PluginsConfig {
+            hooks: vec![PluginSpec::new("test-created-hook")],
+            ..PluginsConfig::default()
+        })
+        .await;
+        let manager = manager.unwrap();
+
+        let id = manager.add_fact("The bridge opens at dusk").await.unwrap();
+        for _ in 0..50 {
+            if created.lock().unwrap().contains(&id) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Hook plugin never saw memory {}", id);
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_plugin_fails_startup() {
+        let (manager, _temp_dir) = create_manager(PluginsConfig {
+            extractors: vec![PluginSpec::new("test-missing-extractor")],
+            ..PluginsConfig::default()
+        })
+        .await;
+
+        let error = manager.unwrap_err().to_string();
+        assert!(error.contains("test-missing-extractor"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_failing_factory_fails_startup() {
+        PluginRegistry::global()
+            .register_reranker("test-failing-reranker", |_| {
+                Err(locai::LocaiError::Configuration(
+                    "api_key is required".to_string(),
+                ))
+            })
+            .unwrap();
+
+        let (manager, _temp_dir) = create_manager(PluginsConfig {
+            reranker: Some(PluginSpec::new("test-failing-reranker")),
+            ..PluginsConfig::default()
+        })
+        .await;
+
+        let error = manager.unwrap_err().to_string();
+        assert!(error.contains("api_key is required"), "{}", error);
+    }
+
+    #[test]
+    fn test_empty_plugin_name_is_rejected() {
+        let result = ConfigBuilder::new()
+            .with_memory_storage()
+            .with_plugins(PluginsConfig {
+                hooks: vec![PluginSpec::new(" ")],
+                ..PluginsConfig::default()
+            })
+            .build();
+        assert!(result.is_err());
+    }
+}
+
+mod ingest {
+    //! Bulk ingestion pipeline tests
+
+    use std::sync::Arc;
+
+    use futures::stream;
+
+    use locai::core::MemoryManager;
+    use locai::ingest::{IngestConfig, IngestJob, IngestState};
+    use locai::models::{Memory, MemoryBuilder};
+    use tempfile::TempDir;
+
+    async fn create_manager() -> (Arc<MemoryManager>, TempDir) {
+        let (manager, temp_dir) = crate::common::create_manager().await;
+        (Arc::new(manager), temp_dir)
+    }
+
+    fn notes(count: usize) -> Vec<Memory> {
+        (0..count)
+            .map(|n| MemoryBuilder::new_with_content(format!("Imported note {}", n)).build())
+            .collect()
+    }
+
+    async fn stored_count(manager: &MemoryManager) -> usize {
+        manager.count_memories(None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_store_memory_batch_reports_each_memory() {
+        let (manager, _dir) = create_manager().await;
+
+        let mut memories = notes(3);
+        // Wrong embedding dimension, rejected before the write
+        memories[1].embedding = Some(vec![0.5; 3]);
+
+        let results = manager.store_memory_batch(memories, 2).await;
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+
+        let stored = manager
+            .get_memory(results[2].as_ref().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.content, "Imported note 2");
+        assert_eq!(stored_count(&manager).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_job_ingests_stream_in_batches() {
+        let (manager, _dir) = create_manager().await;
+
+        let mut memories = notes(25);
+        memories[7].embedding = Some(vec![0.5; 3]);
+        let config = IngestConfig {
+            batch_size: 10,
+            concurrency: 3,
+            ..IngestConfig::default()
+        };
+        let job = IngestJob::spawn(manager.clone(), stream::iter(memories), config);
+        let summary = job.wait().await.unwrap();
+
+        assert_eq!(summary.state, IngestState::Completed);
+        assert_eq!(summary.read, 25);
+        assert_eq!(summary.stored, 24);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.batches, 3);
+        assert_eq!(summary.failures.len(), 1);
+        assert_eq!(summary.failures[0].index, 7);
+        assert_eq!(stored_count(&manager).await, 24);
+    }
+
+    #[tokio::test]
+    async fn test_job_pauses_resumes_and_cancels() {
+        let (manager, _dir) = create_manager().await;
+
+        let config = IngestConfig {
+            batch_size: 5,
+            ..IngestConfig::default()
+        };
+        let (sender, job) = IngestJob::channel(manager.clone(), config);
+        let mut progress = job.subscribe();
+
+        for memory in notes(5) {
+            sender.send(memory).await.unwrap();
+        }
+        progress.wait_for(|p| p.stored == 5).await.unwrap();
+
+        // Nothing is taken from the source while paused
+        job.pause();
+        progress
+            .wait_for(|p| p.state == IngestState::Paused)
+            .await
+            .unwrap();
+        for memory in notes(5) {
+            sender.send(memory).await.unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(job.progress().read, 5);
+
+        job.resume();
+        progress.wait_for(|p| p.stored == 10).await.unwrap();
+
+        job.cancel();
+        // Cancelling can't be undone
+        job.resume();
+        let summary = job.wait().await.unwrap();
+        assert_eq!(summary.state, IngestState::Cancelled);
+        assert_eq!(summary.stored, 10);
+        assert!(sender.send(notes(1).remove(0)).await.is_err());
+        assert_eq!(stored_count(&manager).await, 10);
+    }
+
+    #[tokio::test]
+    async fn test_channel_job_completes_when_senders_drop() {
+        let (manager, _dir) = create_manager().await;
+
+        let (sender, job) = IngestJob::channel(manager.clone(), IngestConfig::default());
+        let producer = tokio::spawn(async move {
+            for memory in notes(12) {
+                sender.send(memory).await.unwrap();
+            }
+        });
+        producer.await.unwrap();
+
+        let summary = job.wait().await.unwrap();
+        assert_eq!(summary.state, IngestState::Completed);
+        assert_eq!(summary.stored, 12);
+    }
+}
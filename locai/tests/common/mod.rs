@@ -0,0 +1,60 @@
+//! Fixtures shared by the integration tests
+//!
+//! Every store is in-memory and lives in its own temp directory, which is
+//! removed when the returned `TempDir` is dropped.
+
+#![allow(dead_code)]
+
+use locai::config::ConfigBuilder;
+use locai::core::MemoryManager;
+use locai::prelude::{Locai, LocaiBuilder};
+use tempfile::TempDir;
+
+/// A memory manager with the default configuration
+pub async fn create_manager() -> (MemoryManager, TempDir) {
+    create_manager_with(|config| config).await
+}
+
+/// A memory manager with `configure` applied on top of the defaults
+pub async fn create_manager_with(
+    configure: impl FnOnce(ConfigBuilder) -> ConfigBuilder,
+) -> (MemoryManager, TempDir) {
+    let (manager, temp_dir) = init_with(configure).await;
+    (manager.expect("Failed to init Locai"), temp_dir)
+}
+
+/// Like [`create_manager_with`], for tests that expect startup to fail
+pub async fn init_with(
+    configure: impl FnOnce(ConfigBuilder) -> ConfigBuilder,
+) -> (locai::Result<MemoryManager>, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let config = configure(
+        ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage(),
+    )
+    .build()
+    .expect("Failed to build config");
+    (locai::init(config).await, temp_dir)
+}
+
+/// A [`Locai`] with the default configuration
+pub async fn create_test_locai() -> (Locai, TempDir) {
+    create_test_locai_with(|builder| builder).await
+}
+
+/// A [`Locai`] with `configure` applied to its builder
+pub async fn create_test_locai_with(
+    configure: impl FnOnce(LocaiBuilder) -> LocaiBuilder,
+) -> (Locai, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let locai = configure(
+        Locai::builder()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage(),
+    )
+    .build()
+    .await
+    .expect("Failed to create Locai");
+    (locai, temp_dir)
+}
@@ -0,0 +1,543 @@
+//! Entity tests: aliases, merging, profiles and version history
+
+mod common;
+
+mod entity_alias_search {
+    //! Entity alias search tests
+    //!
+    //! Searching any name of an entity finds memories that use another of its
+    //! names, and the expansion follows changes to the entity.
+
+    use locai::core::MemoryManager;
+    use locai::memory::SearchMode;
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    fn person(name: &str, aliases: &[&str]) -> Entity {
+        Entity {
+            id: "robert".to_string(),
+            entity_type: "person".to_string(),
+            properties: json!({ "name": name, "aliases": aliases }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    async fn search_ids(manager: &MemoryManager, query: &str) -> Vec<String> {
+        manager
+            .search(query, Some(10), None, SearchMode::Text)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.memory.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_alias_finds_canonical_name() {
+        let (manager, _dir) = create_manager().await;
+        let storage = manager.storage();
+        storage
+            .create_entity(person("Robert Smith", &["Bob"]))
+            .await
+            .unwrap();
+
+        let lunch = manager
+            .add_fact("Had lunch with Robert Smith downtown")
+            .await
+            .unwrap();
+        let build = manager
+            .add_fact("Bob fixed the nightly build")
+            .await
+            .unwrap();
+        let other = manager.add_fact("Alice reviewed the budget").await.unwrap();
+
+        let found = search_ids(&manager, "Bob").await;
+        assert!(found.contains(&lunch));
+        assert!(found.contains(&build));
+        assert!(!found.contains(&other));
+
+        // Works the other way round too
+        assert!(search_ids(&manager, "Smith").await.contains(&build));
+
+        // Linked memories count even without naming the entity
+        let review = manager
+            .add_fact("The lead approved the plan")
+            .await
+            .unwrap();
+        storage
+            .create_relationship(Relationship {
+                id: String::new(),
+                relationship_type: "mentions".to_string(),
+                source_id: review.clone(),
+                target_id: "robert".to_string(),
+                properties: json!({}),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        assert!(search_ids(&manager, "Bob").await.contains(&review));
+    }
+
+    #[tokio::test]
+    async fn test_alias_changes_update_search() {
+        let (manager, _dir) = create_manager().await;
+        let storage = manager.storage();
+        storage
+            .create_entity(person("Robert Smith", &["Bob"]))
+            .await
+            .unwrap();
+        let lunch = manager
+            .add_fact("Had lunch with Robert Smith downtown")
+            .await
+            .unwrap();
+        assert!(search_ids(&manager, "Bob").await.contains(&lunch));
+
+        storage
+            .update_entity(person("Robert Smith", &["Rob"]))
+            .await
+            .unwrap();
+        assert!(search_ids(&manager, "Rob").await.contains(&lunch));
+        assert!(!search_ids(&manager, "Bob").await.contains(&lunch));
+
+        // Memories written later pick up the current aliases
+        let memo = manager.add_fact("Memo from Robert Smith").await.unwrap();
+        assert!(search_ids(&manager, "Rob").await.contains(&memo));
+
+        assert!(storage.delete_entity("robert").await.unwrap());
+        assert!(search_ids(&manager, "Rob").await.is_empty());
+    }
+}
+
+mod entity_merge {
+    //! Entity merge and split tests
+    //!
+    //! Merging folds duplicate entities into one without losing their links, and
+    //! splitting moves a chosen part of an entity into a new one.
+
+    use locai::core::MemoryManager;
+    use locai::storage::models::{Entity, EntitySplit, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    fn person(id: &str, properties: serde_json::Value) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    async fn relate(manager: &MemoryManager, kind: &str, source: &str, target: &str) -> String {
+        manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: kind.to_string(),
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                properties: json!({}),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn memory_ids(manager: &MemoryManager, entity_id: &str) -> Vec<String> {
+        let mut ids: Vec<String> = manager
+            .storage()
+            .get_memories_containing_entity(entity_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|memory| memory.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_merge_entities() {
+        let (manager, _dir) = create_manager().await;
+        manager
+            .create_entity(person(
+                "robert",
+                json!({ "name": "Robert Smith", "role": "lead" }),
+            ))
+            .await
+            .unwrap();
+        manager
+            .create_entity(person(
+                "bob",
+                json!({ "name": "Bob", "email": "bob@example.com" }),
+            ))
+            .await
+            .unwrap();
+        manager
+            .create_entity(person("alice", json!({ "name": "Alice" })))
+            .await
+            .unwrap();
+
+        relate(&manager, "knows", "bob", "alice").await;
+        relate(&manager, "same_as", "robert", "bob").await;
+        let first = manager.add_fact("Robert Smith joined").await.unwrap();
+        let second = manager.add_fact("Bob fixed the build").await.unwrap();
+        relate(&manager, "contains", &first, "robert").await;
+        relate(&manager, "contains", &second, "bob").await;
+
+        let merged = manager
+            .merge_entities("robert", &["bob".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(merged.properties["name"], "Robert Smith");
+        assert_eq!(merged.properties["role"], "lead");
+        assert_eq!(merged.properties["email"], "bob@example.com");
+        assert_eq!(merged.properties["aliases"], json!(["Bob"]));
+        assert_eq!(merged.properties["merged_from"][0]["id"], "bob");
+
+        assert!(manager.get_entity("bob").await.unwrap().is_none());
+        let related = manager
+            .find_related_entities("robert", None, None)
+            .await
+            .unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].id, "alice");
+
+        // The relationship between the two became a self-loop and is gone
+        let relationships = manager
+            .storage()
+            .get_entity_relationships("robert")
+            .await
+            .unwrap();
+        assert!(
+            relationships
+                .iter()
+                .all(|r| r.relationship_type != "same_as")
+        );
+
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(memory_ids(&manager, "robert").await, expected);
+        assert!(memory_ids(&manager, "bob").await.is_empty());
+
+        // Merging into itself or from a missing entity fails
+        assert!(
+            manager
+                .merge_entities("robert", &["robert".to_string()])
+                .await
+                .is_err()
+        );
+        assert!(
+            manager
+                .merge_entities("robert", &["nobody".to_string()])
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_split_entity() {
+        let (manager, _dir) = create_manager().await;
+        manager
+            .create_entity(person(
+                "jordan",
+                json!({ "name": "Jordan", "aliases": ["Jordan Lee", "JB"] }),
+            ))
+            .await
+            .unwrap();
+        manager
+            .create_entity(person("team", json!({ "name": "Platform team" })))
+            .await
+            .unwrap();
+
+        let kept = relate(&manager, "member_of", "jordan", "team").await;
+        let moved = relate(&manager, "reports_to", "jordan", "team").await;
+        let work = manager.add_fact("Jordan shipped the API").await.unwrap();
+        let other = manager.add_fact("JB ran the offsite").await.unwrap();
+        relate(&manager, "contains", &work, "jordan").await;
+        relate(&manager, "contains", &other, "jordan").await;
+
+        let created = manager
+            .split_entity(
+                "jordan",
+                EntitySplit {
+                    entity: person("jb", json!({ "name": "JB" })),
+                    relationship_ids: vec![moved.clone()],
+                    memory_ids: vec![other.clone()],
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.id, "jb");
+        assert_eq!(created.properties["split_from"]["id"], "jordan");
+
+        let original = manager.get_entity("jordan").await.unwrap().unwrap();
+        assert_eq!(original.properties["aliases"], json!(["Jordan Lee"]));
+        assert_eq!(original.properties["split_into"][0]["id"], "jb");
+
+        assert_eq!(memory_ids(&manager, "jordan").await, vec![work]);
+        assert_eq!(memory_ids(&manager, "jb").await, vec![other]);
+
+        let relationship = manager.get_relationship(&moved).await.unwrap().unwrap();
+        assert_eq!(relationship.source_id, "jb");
+        let relationship = manager.get_relationship(&kept).await.unwrap().unwrap();
+        assert_eq!(relationship.source_id, "jordan");
+    }
+}
+
+mod entity_profile {
+    //! Entity profile tests
+    //!
+    //! A profile gathers the memories that mention an entity together with its
+    //! relationships, mention timeline, sentiment and co-occurring entities.
+
+    use locai::memory::TrendDirection;
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    fn entity(id: &str, name: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties: json!({ "name": name }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn relationship(kind: &str, source: &str, target: &str) -> Relationship {
+        Relationship {
+            id: String::new(),
+            relationship_type: kind.to_string(),
+            source_id: source.to_string(),
+            target_id: target.to_string(),
+            properties: json!({}),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entity_profile_aggregates_mentions() {
+        let (manager, _dir) = create_manager().await;
+        for (id, name) in [("ada", "Ada"), ("grace", "Grace"), ("alan", "Alan")] {
+            manager.create_entity(entity(id, name)).await.unwrap();
+        }
+        manager
+            .create_relationship_entity(relationship("knows", "ada", "alan"))
+            .await
+            .unwrap();
+
+        let mut memories = Vec::new();
+        for content in [
+            "Ada and Grace had a great planning session",
+            "Ada helped Grace fix the build",
+            "Ada was upset that the release failed",
+        ] {
+            let id = manager.add_fact(content).await.unwrap();
+            manager
+                .create_relationship_entity(relationship("contains", &id, "ada"))
+                .await
+                .unwrap();
+            memories.push(id);
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        for id in &memories[..2] {
+            manager
+                .create_relationship_entity(relationship("contains", id, "grace"))
+                .await
+                .unwrap();
+        }
+
+        let profile = manager.entity_profile("ada").await.unwrap().unwrap();
+        assert_eq!(profile.entity.id, "ada");
+        assert_eq!(profile.mention_count, 3);
+        assert_eq!(profile.top_memories.len(), 3);
+        assert!(profile.first_mentioned <= profile.last_mentioned);
+
+        let knows = profile
+            .relationships
+            .iter()
+            .find(|summary| summary.relationship_type == "knows")
+            .expect("knows relationships");
+        assert_eq!(knows.related_ids, vec!["alan".to_string()]);
+
+        assert_eq!(profile.sentiment.points.len(), 3);
+        assert_eq!(profile.sentiment.points[0].memory_id, memories[0]);
+        assert!(matches!(
+            profile.sentiment.direction,
+            TrendDirection::Decreasing
+        ));
+
+        assert_eq!(profile.co_occurring.len(), 1);
+        assert_eq!(profile.co_occurring[0].entity_id, "grace");
+        assert_eq!(profile.co_occurring[0].name.as_deref(), Some("Grace"));
+        assert_eq!(profile.co_occurring[0].count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_entity_profile_of_unknown_entity() {
+        let (manager, _dir) = create_manager().await;
+        assert!(manager.entity_profile("nobody").await.unwrap().is_none());
+
+        // An entity nobody mentions still has a profile
+        manager.create_entity(entity("ada", "Ada")).await.unwrap();
+        let profile = manager.entity_profile("ada").await.unwrap().unwrap();
+        assert_eq!(profile.mention_count, 0);
+        assert!(profile.first_mentioned.is_none());
+        assert!(matches!(
+            profile.sentiment.direction,
+            TrendDirection::Stable
+        ));
+    }
+}
+
+mod record_version {
+    //! Entity and relationship version history tests
+    //!
+    //! Every write to an entity or relationship is snapshotted, so its history
+    //! can be listed, diffed and read back as of any point in time.
+
+    use locai::replication::RecordKind;
+    use locai::storage::models::{Entity, RecordOperation, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    fn person(id: &str, properties: serde_json::Value) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entity_history() {
+        let (manager, _dir) = create_manager().await;
+        manager
+            .create_entity(person(
+                "alice",
+                json!({ "name": "Alice", "role": "engineer" }),
+            ))
+            .await
+            .unwrap();
+        let before_update = chrono::Utc::now();
+        manager
+            .update_entity(person(
+                "alice",
+                json!({ "name": "Alice", "role": "manager" }),
+            ))
+            .await
+            .unwrap();
+        manager.delete_entity("alice").await.unwrap();
+
+        let versions = manager
+            .list_record_versions(RecordKind::Entity, "alice")
+            .await
+            .unwrap();
+        let operations: Vec<_> = versions.iter().map(|v| v.operation).collect();
+        assert_eq!(
+            operations,
+            vec![
+                RecordOperation::Created,
+                RecordOperation::Updated,
+                RecordOperation::Deleted
+            ]
+        );
+        assert!(versions[2].data.is_none());
+
+        let diff = manager
+            .diff_record_versions(&versions[0].version_id, &versions[1].version_id)
+            .await
+            .unwrap();
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].field, "properties.role");
+        assert_eq!(diff.changes[0].old_value, Some(json!("engineer")));
+        assert_eq!(diff.changes[0].new_value, Some(json!("manager")));
+
+        // Deleted now, but still readable as it was before the update
+        assert!(manager.get_entity("alice").await.unwrap().is_none());
+        let past = manager
+            .get_entity_at_time("alice", before_update)
+            .await
+            .unwrap()
+            .expect("entity existed before the update");
+        assert_eq!(past.properties["role"], "engineer");
+        assert!(
+            manager
+                .get_entity_at_time("alice", chrono::Utc::now())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relationship_history() {
+        let (manager, _dir) = create_manager().await;
+        for id in ["alice", "bob"] {
+            manager
+                .create_entity(person(id, json!({ "name": id })))
+                .await
+                .unwrap();
+        }
+        let created = manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: "knows".to_string(),
+                source_id: "alice".to_string(),
+                target_id: "bob".to_string(),
+                properties: json!({ "since": 2020 }),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let before_update = chrono::Utc::now();
+        manager
+            .update_relationship(Relationship {
+                properties: json!({ "since": 2021 }),
+                ..created.clone()
+            })
+            .await
+            .unwrap();
+
+        let versions = manager
+            .list_record_versions(RecordKind::Relationship, &created.id)
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1].operation, RecordOperation::Updated);
+
+        let past = manager
+            .get_relationship_at_time(&created.id, before_update)
+            .await
+            .unwrap()
+            .expect("relationship existed before the update");
+        assert_eq!(past.properties["since"], 2020);
+
+        // Versions of different records cannot be diffed
+        let alice = manager
+            .list_record_versions(RecordKind::Entity, "alice")
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .diff_record_versions(&alice[0].version_id, &versions[1].version_id)
+                .await
+                .is_err()
+        );
+    }
+}
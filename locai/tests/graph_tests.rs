@@ -0,0 +1,628 @@
+//! Graph tests: diagrams, diffs, subgraphs, paths and RDF export
+
+mod common;
+
+mod diagram {
+    //! Graph diagram tests
+    //!
+    //! Diagrams select part of the graph around a root or by relationship type
+    //! and render it as Mermaid or PlantUML source.
+
+    use locai::core::MemoryManager;
+    use locai::memory::DiagramFilter;
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    async fn relate(manager: &MemoryManager, kind: &str, source: &str, target: &str) {
+        manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: kind.to_string(),
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                properties: json!({}),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_graph_diagram() {
+        let (manager, _dir) = create_manager().await;
+        for (id, entity_type, name) in [
+            ("alice", "person", "Alice"),
+            ("bob", "person", "Bob"),
+            ("acme", "organization", "Acme"),
+            ("beta", "organization", "Beta"),
+        ] {
+            manager
+                .create_entity(Entity {
+                    id: id.to_string(),
+                    entity_type: entity_type.to_string(),
+                    properties: json!({ "name": name }),
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        relate(&manager, "works_at", "alice", "acme").await;
+        relate(&manager, "knows", "alice", "bob").await;
+        relate(&manager, "acquired", "acme", "beta").await;
+
+        // One hop around Alice, following only works_at
+        let filter = DiagramFilter {
+            root_id: Some("alice".to_string()),
+            depth: 1,
+            relationship_types: vec!["works_at".to_string()],
+            ..DiagramFilter::default()
+        };
+        let mermaid = manager.graph_diagram(&filter).await.unwrap().to_mermaid();
+        assert_eq!(
+            mermaid,
+            "flowchart LR\n    n0(\"Alice<br/><i>person</i>\")\n    n1(\"Acme<br/><i>organization</i>\")\n    n0 -->|\"works_at\"| n1"
+        );
+
+        // The whole graph, organizations only
+        let filter = DiagramFilter {
+            entity_types: vec!["organization".to_string()],
+            ..DiagramFilter::default()
+        };
+        let diagram = manager.graph_diagram(&filter).await.unwrap();
+        assert_eq!(diagram.nodes.len(), 2);
+        assert_eq!(diagram.edges.len(), 1);
+        let plantuml = diagram.to_plantuml();
+        assert!(plantuml.starts_with("@startuml\n"));
+        assert!(plantuml.contains(" --> "));
+        assert!(plantuml.contains(" : acquired"));
+        assert!(!plantuml.contains("Alice"));
+
+        let filter = DiagramFilter {
+            root_id: Some("nobody".to_string()),
+            ..DiagramFilter::default()
+        };
+        let diagram = manager.graph_diagram(&filter).await.unwrap();
+        assert!(diagram.nodes.is_empty());
+    }
+}
+
+mod graph_diff {
+    //! Graph diff tests
+    //!
+    //! Diffs replay the storage changefeed to list the nodes and edges added,
+    //! removed and changed between two points in time.
+
+    use std::time::Duration;
+
+    use chrono::Utc;
+
+    use locai::memory::GraphChange;
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    fn person(id: &str, name: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties: json!({ "name": name }),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn ids(changes: &[GraphChange]) -> Vec<&str> {
+        changes.iter().map(|change| change.id.as_str()).collect()
+    }
+
+    /// Let the clock move on so writes fall clearly on one side of a timestamp
+    async fn tick() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn test_graph_diff() {
+        let (manager, _dir) = create_manager().await;
+        let start = Utc::now();
+        tick().await;
+
+        manager.create_entity(person("ada", "Ada")).await.unwrap();
+        let memory = manager
+            .add_fact("Ada wrote the first program")
+            .await
+            .unwrap();
+        tick().await;
+        let middle = Utc::now();
+        tick().await;
+
+        manager
+            .create_entity(person("charles", "Charles"))
+            .await
+            .unwrap();
+        manager
+            .update_entity(person("ada", "Ada Lovelace"))
+            .await
+            .unwrap();
+        let relationship = manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: "knows".to_string(),
+                source_id: "ada".to_string(),
+                target_id: "charles".to_string(),
+                properties: json!({}),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .await
+            .unwrap()
+            .id;
+        manager.delete_memory(&memory).await.unwrap();
+        tick().await;
+        let end = Utc::now();
+
+        // Everything written in the first half is new
+        let diff = manager.graph_diff(start, middle).await.unwrap();
+        assert!(ids(&diff.nodes.added).contains(&"ada"));
+        assert!(ids(&diff.nodes.added).contains(&memory.as_str()));
+        assert!(!ids(&diff.nodes.added).contains(&"charles"));
+        assert!(diff.nodes.removed.is_empty());
+        assert!(diff.edges.added.is_empty());
+
+        let diff = manager.graph_diff(middle, end).await.unwrap();
+        assert_eq!(ids(&diff.nodes.added), vec!["charles"]);
+        assert_eq!(ids(&diff.nodes.removed), vec![memory.as_str()]);
+        assert_eq!(ids(&diff.nodes.changed), vec!["ada"]);
+        let change = &diff.nodes.changed[0];
+        assert_eq!(change.before.as_ref().unwrap()["properties"]["name"], "Ada");
+        assert_eq!(
+            change.after.as_ref().unwrap()["properties"]["name"],
+            "Ada Lovelace"
+        );
+        assert_eq!(
+            diff.nodes.removed[0].before.as_ref().unwrap()["content"],
+            "Ada wrote the first program"
+        );
+        assert_eq!(ids(&diff.edges.added), vec![relationship.as_str()]);
+
+        // Nothing happens after the last write
+        let diff = manager.graph_diff(end, Utc::now()).await.unwrap();
+        assert!(diff.is_empty());
+
+        assert!(manager.graph_diff(end, start).await.is_err());
+    }
+}
+
+mod subgraph {
+    //! Subgraph extraction tests
+    //!
+    //! Subgraphs grow out from seed memories or entities, keep the best scored
+    //! nodes and can be rendered as triples within a token budget.
+
+    use locai::core::MemoryManager;
+    use locai::memory::{SubgraphFormat, SubgraphScoring};
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    async fn relate(manager: &MemoryManager, kind: &str, source: &str, target: &str) {
+        manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: kind.to_string(),
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                properties: json!({}),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_extract_subgraph() {
+        let (manager, _dir) = create_manager().await;
+        for (id, name) in [
+            ("alice", "Alice"),
+            ("acme", "Acme"),
+            ("beta", "Beta"),
+            ("gamma", "Gamma"),
+        ] {
+            manager
+                .create_entity(Entity {
+                    id: id.to_string(),
+                    entity_type: "organization".to_string(),
+                    properties: json!({ "name": name }),
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        relate(&manager, "works_at", "alice", "acme").await;
+        relate(&manager, "acquired", "acme", "beta").await;
+        relate(&manager, "sued", "beta", "gamma").await;
+        let memory = manager.add_fact("Alice gave a talk").await.unwrap();
+        relate(&manager, "contains", &memory, "alice").await;
+
+        let seeds = vec!["alice".to_string()];
+        let scoring = SubgraphScoring::default();
+
+        let subgraph = manager
+            .extract_subgraph(&seeds, 2, 10, &scoring)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = subgraph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids[0], "alice");
+        assert_eq!(ids.len(), 4);
+        assert!(ids.contains(&memory.as_str()));
+        assert!(ids.contains(&"beta"));
+        assert!(!ids.contains(&"gamma"));
+
+        let triples = subgraph.to_triples();
+        assert!(triples.contains("Alice works_at Acme"));
+        assert!(triples.contains("Acme acquired Beta"));
+
+        // Nodes one hop away outscore those two hops away
+        let subgraph = manager
+            .extract_subgraph(&seeds, 2, 3, &scoring)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = subgraph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert!(!ids.contains(&"beta"));
+
+        let fitted = subgraph.fit_to_budget(6, SubgraphFormat::Triples);
+        assert!(fitted.estimated_tokens(SubgraphFormat::Triples) <= 6);
+        assert_eq!(fitted.seed_ids, seeds);
+        assert_eq!(fitted.nodes[0].id, "alice");
+
+        let unknown = manager
+            .extract_subgraph(&["nobody".to_string()], 2, 10, &scoring)
+            .await
+            .unwrap();
+        assert!(unknown.nodes.is_empty());
+    }
+}
+
+mod path_narration {
+    //! Path narration tests
+    //!
+    //! Paths between memories are rendered as sentences naming the entities they
+    //! pass through.
+
+    use locai::core::MemoryManager;
+    use locai::memory::PathNarrator;
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    async fn relate(manager: &MemoryManager, kind: &str, source: &str, target: &str) {
+        manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: kind.to_string(),
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                properties: json!({}),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_explain_paths() {
+        let (manager, _dir) = create_manager().await;
+        for (id, entity_type, name) in [
+            ("alice", "person", "Alice"),
+            ("acme", "organization", "Acme"),
+            ("beta", "organization", "Beta"),
+        ] {
+            manager
+                .create_entity(Entity {
+                    id: id.to_string(),
+                    entity_type: entity_type.to_string(),
+                    properties: json!({ "name": name }),
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        relate(&manager, "works_at", "alice", "acme").await;
+        relate(&manager, "acquired", "acme", "beta").await;
+
+        let start = manager.add_fact("Alice started a new job").await.unwrap();
+        let middle = manager.add_fact("Acme had a record quarter").await.unwrap();
+        let end = manager.add_fact("Beta shut down its office").await.unwrap();
+        relate(&manager, "contains", &start, "alice").await;
+        relate(&manager, "contains", &middle, "acme").await;
+        relate(&manager, "contains", &end, "beta").await;
+
+        let narrations = manager
+            .explain_paths(&start, &end, 4, &PathNarrator::new())
+            .await
+            .unwrap();
+        assert!(narrations.contains(&"Alice WORKS_AT Acme, which ACQUIRED Beta.".to_string()));
+
+        let narrator = PathNarrator::new()
+            .with_template("works_at", "{source} works at {target}")
+            .with_template("acquired", "{source} bought {target}");
+        let narrations = manager
+            .explain_paths(&start, &end, 4, &narrator)
+            .await
+            .unwrap();
+        assert!(narrations.contains(&"Alice works at Acme, which bought Beta.".to_string()));
+    }
+}
+
+mod weighted_path {
+    //! Weighted path search tests
+    //!
+    //! Paths are ranked by the total weight of their relationships and limited
+    //! by relationship type, entity type and cost.
+
+    use locai::core::MemoryManager;
+    use locai::storage::models::{Entity, MemoryPath, PathConstraints, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    async fn relate(manager: &MemoryManager, kind: &str, source: &str, target: &str, weight: f64) {
+        manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: kind.to_string(),
+                source_id: source.to_string(),
+                target_id: target.to_string(),
+                properties: json!({ "weight": weight }),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn mention(manager: &MemoryManager, content: &str, entity_id: &str) -> String {
+        let memory_id = manager.add_fact(content).await.unwrap();
+        manager
+            .create_relationship_entity(Relationship {
+                id: String::new(),
+                relationship_type: "contains".to_string(),
+                source_id: memory_id.clone(),
+                target_id: entity_id.to_string(),
+                properties: json!({}),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        memory_id
+    }
+
+    fn hops(path: &MemoryPath) -> Vec<&str> {
+        path.relationships
+            .iter()
+            .map(|relationship| relationship.relationship_type.as_str())
+            .collect()
+    }
+
+    /// Three routes from Alice to Beta: through Bob (cost 2), through Acme
+    /// (cost 6) and directly (cost 10)
+    async fn create_graph(manager: &MemoryManager) -> (String, String) {
+        for (id, entity_type) in [
+            ("alice", "person"),
+            ("bob", "person"),
+            ("acme", "organization"),
+            ("beta", "company"),
+        ] {
+            manager
+                .create_entity(Entity {
+                    id: id.to_string(),
+                    entity_type: entity_type.to_string(),
+                    properties: json!({ "name": id }),
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        relate(manager, "knows", "alice", "bob", 1.0).await;
+        relate(manager, "founded", "bob", "beta", 1.0).await;
+        relate(manager, "works_at", "alice", "acme", 5.0).await;
+        relate(manager, "acquired", "acme", "beta", 1.0).await;
+        relate(manager, "invested_in", "alice", "beta", 10.0).await;
+
+        let start = mention(manager, "Alice moved to Berlin", "alice").await;
+        mention(manager, "Bob went hiking", "bob").await;
+        mention(manager, "Acme opened an office", "acme").await;
+        let end = mention(manager, "Beta launched its app", "beta").await;
+        (start, end)
+    }
+
+    #[tokio::test]
+    async fn test_weighted_paths() {
+        let (manager, _dir) = create_manager().await;
+        let (start, end) = create_graph(&manager).await;
+
+        let paths = manager
+            .find_weighted_paths(&start, &end, &PathConstraints::default())
+            .await
+            .unwrap();
+        let costs: Vec<f64> = paths.iter().filter_map(|path| path.cost).collect();
+        assert_eq!(costs, vec![2.0, 6.0, 10.0]);
+        assert_eq!(hops(&paths[0]), vec!["knows", "founded"]);
+        assert_eq!(hops(&paths[1]), vec!["works_at", "acquired"]);
+        assert_eq!(hops(&paths[2]), vec!["invested_in"]);
+
+        let paths = manager
+            .find_weighted_paths(
+                &start,
+                &end,
+                &PathConstraints {
+                    limit: 1,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].cost, Some(2.0));
+    }
+
+    #[tokio::test]
+    async fn test_constrained_paths() {
+        let (manager, _dir) = create_manager().await;
+        let (start, end) = create_graph(&manager).await;
+
+        let costs = |paths: Vec<MemoryPath>| -> Vec<f64> {
+            paths.iter().filter_map(|path| path.cost).collect()
+        };
+
+        let excluded = PathConstraints {
+            excluded_relationship_types: vec!["KNOWS".to_string()],
+            ..Default::default()
+        };
+        let paths = manager
+            .find_weighted_paths(&start, &end, &excluded)
+            .await
+            .unwrap();
+        assert_eq!(costs(paths), vec![6.0, 10.0]);
+
+        let allowed = PathConstraints {
+            relationship_types: vec!["works_at".to_string(), "acquired".to_string()],
+            ..Default::default()
+        };
+        let paths = manager
+            .find_weighted_paths(&start, &end, &allowed)
+            .await
+            .unwrap();
+        assert_eq!(costs(paths), vec![6.0]);
+
+        let people_and_companies = PathConstraints {
+            node_types: vec!["person".to_string(), "company".to_string()],
+            ..Default::default()
+        };
+        let paths = manager
+            .find_weighted_paths(&start, &end, &people_and_companies)
+            .await
+            .unwrap();
+        assert_eq!(costs(paths), vec![2.0, 10.0]);
+
+        let cheap = PathConstraints {
+            max_cost: Some(5.0),
+            ..Default::default()
+        };
+        let paths = manager
+            .find_weighted_paths(&start, &end, &cheap)
+            .await
+            .unwrap();
+        assert_eq!(costs(paths), vec![2.0]);
+
+        let short = PathConstraints {
+            max_depth: 1,
+            ..Default::default()
+        };
+        let paths = manager
+            .find_weighted_paths(&start, &end, &short)
+            .await
+            .unwrap();
+        assert_eq!(costs(paths), vec![10.0]);
+    }
+}
+
+mod rdf_export {
+    //! RDF export tests
+    //!
+    //! The entity graph, and optionally memories, is read into triples that can
+    //! be serialized or queried with triple patterns.
+
+    use locai::export::rdf::{RdfExportOptions, RdfFormat, Term, TripleQuery, export_triples};
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    #[tokio::test]
+    async fn test_export_and_query_triples() {
+        let (manager, _dir) = create_manager().await;
+        for (id, entity_type, name) in [
+            ("alice", "person", "Alice"),
+            ("bob", "person", "Bob"),
+            ("acme", "organization", "Acme"),
+        ] {
+            manager
+                .create_entity(Entity {
+                    id: id.to_string(),
+                    entity_type: entity_type.to_string(),
+                    properties: json!({ "name": name }),
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        for source in ["alice", "bob"] {
+            manager
+                .create_relationship_entity(Relationship {
+                    id: String::new(),
+                    relationship_type: "works_at".to_string(),
+                    source_id: source.to_string(),
+                    target_id: "acme".to_string(),
+                    properties: json!({}),
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+        let memory = manager.add_fact("Acme ships rockets").await.unwrap();
+
+        // A small batch size exercises paging
+        let options = RdfExportOptions {
+            batch_size: 2,
+            ..RdfExportOptions::default()
+        };
+        let graph = export_triples(&manager, &options).await.unwrap();
+        // A type and a name per entity, and one triple per relationship
+        assert_eq!(graph.triples.len(), 8);
+
+        let ntriples = graph.render(RdfFormat::NTriples, "urn:test:");
+        assert!(
+            ntriples
+                .contains("<urn:test:node/bob> <urn:test:vocab/works_at> <urn:test:node/acme> .")
+        );
+        assert!(!ntriples.contains(&memory));
+
+        let query = TripleQuery::parse(
+            "SELECT ?who WHERE { ?who works_at ?org . ?org name \"Acme\" . ?who a person }",
+        )
+        .unwrap();
+        let mut people: Vec<String> = graph
+            .query(&query)
+            .rows
+            .iter()
+            .map(|row| row["who"].value().to_string())
+            .collect();
+        people.sort();
+        assert_eq!(people, vec!["alice", "bob"]);
+
+        let options = RdfExportOptions {
+            include_memories: true,
+            ..RdfExportOptions::default()
+        };
+        let graph = export_triples(&manager, &options).await.unwrap();
+        let query = TripleQuery::parse("?m content \"Acme ships rockets\"").unwrap();
+        let rows = graph.query(&query).rows;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["m"], Term::Node(memory));
+    }
+}
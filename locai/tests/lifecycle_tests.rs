@@ -0,0 +1,357 @@
+//! Memory lifecycle tests: forgetting, pinning and review
+
+mod common;
+
+mod forgetting {
+    //! Forgetting curve tests
+    //!
+    //! With forgetting enabled, memories fade from the last time they were
+    //! accessed, fade more slowly the more often they were, rank lower in search
+    //! as they fade, and are archived once they fall below the threshold.
+
+    use std::collections::HashMap;
+
+    use crate::common::create_manager_with;
+    use chrono::{Duration, Utc};
+    use locai::config::ForgettingConfig;
+    use locai::core::MemoryManager;
+    use locai::memory::SearchMode;
+    use locai::memory::forgetting;
+    use locai::models::{Memory, MemoryType};
+    use tempfile::TempDir;
+
+    async fn create_manager(forgetting: ForgettingConfig) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| config.with_forgetting(forgetting)).await
+    }
+
+    fn enabled() -> ForgettingConfig {
+        ForgettingConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn memory(id: &str, content: &str, age_days: i64, access_count: u32) -> Memory {
+        let mut memory = Memory::new(id.to_string(), content.to_string(), MemoryType::Episodic);
+        memory.created_at = Utc::now() - Duration::days(age_days);
+        memory.access_count = access_count;
+        memory
+    }
+
+    #[test]
+    fn test_retrievability_follows_the_curve() {
+        let config = ForgettingConfig::default();
+        let now = Utc::now();
+
+        let fresh = memory("fresh", "", 0, 0);
+        assert!((forgetting::retrievability(&config, &fresh, now) - 1.0).abs() < 1e-3);
+
+        // One stability period gives 1/e
+        let week_old = memory("week-old", "", 7, 0);
+        let r = forgetting::retrievability(&config, &week_old, now);
+        assert!((r - (-1.0f32).exp()).abs() < 1e-3, "{}", r);
+
+        // Each access doubles stability
+        let reviewed = memory("reviewed", "", 7, 1);
+        assert_eq!(forgetting::stability_days(&config, &reviewed), Some(14.0));
+        assert!(forgetting::retrievability(&config, &reviewed, now) > r);
+
+        // Recall resets the clock
+        let mut recalled = memory("recalled", "", 30, 0);
+        recalled.last_accessed = Some(now);
+        assert!(!forgetting::is_forgotten(&config, &recalled, now));
+        assert!(forgetting::is_forgotten(
+            &config,
+            &memory("old", "", 30, 0),
+            now
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_faded_memories_rank_lower() {
+        let (manager, _dir) = create_manager(enabled()).await;
+        manager
+            .store_memory(memory("old", "The deploy was rolled back", 10, 0))
+            .await
+            .unwrap();
+        manager
+            .store_memory(memory("new", "The deploy was rolled back", 0, 0))
+            .await
+            .unwrap();
+
+        let results = manager
+            .search("deploy", Some(10), None, SearchMode::Text)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.memory.id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "old"]);
+        if let (Some(new), Some(old)) = (results[0].score, results[1].score) {
+            assert!(old < new * 0.5);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forgotten_memories_are_archived() {
+        let mut config = enabled();
+        config.type_stability_days = HashMap::from([("identity".to_string(), 0.0)]);
+        let (manager, _dir) = create_manager(config).await;
+
+        manager
+            .store_memory(memory("faded", "Lunch was soup", 30, 0))
+            .await
+            .unwrap();
+        manager
+            .store_memory(memory("reinforced", "Deploys are on Tuesday", 30, 5))
+            .await
+            .unwrap();
+        manager
+            .store_memory(memory("fresh", "Standup moved to ten", 1, 0))
+            .await
+            .unwrap();
+        let mut identity = memory("identity", "I am a release bot", 365, 0);
+        identity.memory_type = MemoryType::Identity;
+        manager.store_memory(identity).await.unwrap();
+
+        let archived = manager.archive_forgotten_memories(None).await.unwrap();
+        assert_eq!(archived, vec!["faded".to_string()]);
+        assert!(manager.get_memory("faded").await.unwrap().is_none());
+        assert!(
+            manager
+                .get_archived_memory("faded")
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(manager.get_memory("identity").await.unwrap().is_some());
+
+        assert!(
+            manager
+                .archive_forgotten_memories(None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forgetting_is_off_by_default() {
+        let (manager, _dir) = create_manager(ForgettingConfig::default()).await;
+        manager
+            .store_memory(memory("faded", "Lunch was soup", 30, 0))
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .archive_forgotten_memories(None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(manager.get_memory("faded").await.unwrap().is_some());
+    }
+}
+
+mod pin {
+    //! Pinned memory tests
+    //!
+    //! Pinned memories lead search results whatever their score, with global pins
+    //! applying to every scope.
+
+    use locai::LocaiError;
+
+    use locai::memory::SearchMode;
+
+    use crate::common::create_manager;
+
+    fn ids(results: &[locai::storage::models::SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.memory.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_pinned_memories_lead_search_results() {
+        let (manager, _dir) = create_manager().await;
+        let persona = manager.add_fact("You are a terse assistant").await.unwrap();
+        let matched = manager.add_fact("Rust borrow checker notes").await.unwrap();
+
+        assert!(manager.pin(&persona).await.unwrap());
+        assert!(!manager.pin(&persona).await.unwrap());
+
+        let results = manager
+            .search_with_pins("borrow", Some(1), None, SearchMode::Text, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&results), vec![persona.as_str(), matched.as_str()]);
+        assert!(results[0].score.is_none());
+
+        assert!(manager.unpin(&persona).await.unwrap());
+        let results = manager
+            .search_with_pins("borrow", Some(10), None, SearchMode::Text, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&results), vec![matched.as_str()]);
+    }
+
+    #[tokio::test]
+    async fn test_scoped_pins() {
+        let (manager, _dir) = create_manager().await;
+        let everywhere = manager.add_fact("Always cite sources").await.unwrap();
+        let planner = manager.add_fact("Plan in small steps").await.unwrap();
+        let writer = manager.add_fact("Write in plain English").await.unwrap();
+
+        manager.pin(&everywhere).await.unwrap();
+        manager.pin_in_scope("planner", &planner).await.unwrap();
+        manager.pin_in_scope("writer", &writer).await.unwrap();
+
+        let pinned = manager.pinned_memories(Some("planner")).await.unwrap();
+        let pinned: Vec<&str> = pinned.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(pinned, vec![everywhere.as_str(), planner.as_str()]);
+        assert_eq!(manager.pinned_memories(None).await.unwrap().len(), 1);
+        assert_eq!(manager.pin_lists().await.unwrap().len(), 3);
+
+        // A pin matching the query isn't repeated further down
+        let results = manager
+            .search_with_pins("steps", Some(10), None, SearchMode::Text, Some("planner"))
+            .await
+            .unwrap();
+        assert_eq!(ids(&results), vec![everywhere.as_str(), planner.as_str()]);
+
+        assert!(!manager.unpin_in_scope("writer", &planner).await.unwrap());
+        assert!(manager.unpin_in_scope("planner", &planner).await.unwrap());
+        assert_eq!(
+            manager
+                .pinned_memories(Some("planner"))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pinning_missing_memory_fails() {
+        let (manager, _dir) = create_manager().await;
+        let error = manager.pin("no-such-memory").await.unwrap_err();
+        assert!(matches!(error, LocaiError::Memory(_)), "{}", error);
+    }
+}
+
+mod review {
+    //! Review queue tests
+    //!
+    //! With review enabled, memories from the configured sources wait in the
+    //! queue until approved, unless an auto-approve rule matches them.
+
+    use crate::common::create_manager_with;
+    use locai::config::{AutoApproveRule, ReviewConfig};
+    use locai::core::MemoryManager;
+    use locai::models::{MemoryBuilder, MemoryType};
+    use locai::storage::OutboxMessage;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_manager() -> (MemoryManager, TempDir) {
+        create_manager_with(|config| {
+            config.with_review(ReviewConfig {
+                enabled: true,
+                sources: vec!["agent:*".to_string(), "importer".to_string()],
+                auto_approve: vec![AutoApproveRule {
+                    name: "short-facts".to_string(),
+                    tags: Vec::new(),
+                    memory_type: Some("fact".to_string()),
+                    source: None,
+                    max_length: Some(20),
+                }],
+            })
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_held_memories_are_stored_once_approved() {
+        let (manager, _temp_dir) = create_manager().await;
+
+        let held = manager
+            .add_memory_with_options("The office closes at noon on Fridays".to_string(), |b| {
+                b.source("agent:planner")
+            })
+            .await
+            .unwrap();
+        let short = manager
+            .add_memory_with_options("Sky is blue".to_string(), |b| {
+                b.source("agent:planner").memory_type(MemoryType::Fact)
+            })
+            .await
+            .unwrap();
+        let person = manager.add_fact("Written by a person").await.unwrap();
+
+        assert!(manager.get_memory(&held).await.unwrap().is_none());
+        assert!(manager.get_memory(&short).await.unwrap().is_some());
+        assert!(manager.get_memory(&person).await.unwrap().is_some());
+
+        let queue = manager.list_pending_reviews().await.unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].memory.id, held);
+
+        assert_eq!(manager.approve_memory(&held).await.unwrap(), held);
+        let stored = manager.get_memory(&held).await.unwrap().unwrap();
+        assert_eq!(stored.source, "agent:planner");
+        assert!(manager.list_pending_reviews().await.unwrap().is_empty());
+
+        let again = manager.approve_memory(&held).await;
+        assert!(matches!(again, Err(locai::LocaiError::Review(_))));
+    }
+
+    #[tokio::test]
+    async fn test_batches_hold_memories_and_rejection_drops_them() {
+        let (manager, _temp_dir) = create_manager().await;
+        let memories = vec![
+            MemoryBuilder::new_with_content("Imported from the wiki")
+                .source("importer")
+                .build(),
+            MemoryBuilder::new_with_content("Typed in by hand")
+                .source("user")
+                .build(),
+        ];
+
+        let ids: Vec<String> = manager
+            .store_memory_batch(memories, 2)
+            .await
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect();
+        assert!(manager.get_memory(&ids[0]).await.unwrap().is_none());
+        assert!(manager.get_memory(&ids[1]).await.unwrap().is_some());
+        assert!(manager.get_pending_review(&ids[0]).await.unwrap().is_some());
+
+        assert!(manager.reject_memory(&ids[0]).await.unwrap());
+        assert!(!manager.reject_memory(&ids[0]).await.unwrap());
+        assert!(manager.get_pending_review(&ids[0]).await.unwrap().is_none());
+        assert!(manager.get_memory(&ids[0]).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_outbox_messages_wait_for_approval() {
+        let (manager, _temp_dir) = create_manager().await;
+        let memory = MemoryBuilder::new_with_content("Imported from the wiki")
+            .source("importer")
+            .build();
+        let id = memory.id.clone();
+
+        manager
+            .store_memory_with_outbox(
+                memory,
+                vec![OutboxMessage::new("memory.created", json!({ "id": id }))],
+            )
+            .await
+            .unwrap();
+        assert!(manager.pending_outbox(10).await.unwrap().is_empty());
+        let item = manager.get_pending_review(&id).await.unwrap().unwrap();
+        assert_eq!(item.outbox.len(), 1);
+
+        manager.approve_memory(&id).await.unwrap();
+        let pending = manager.pending_outbox(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].topic, "memory.created");
+        assert_eq!(pending[0].payload, json!({ "id": id }));
+    }
+}
@@ -6,7 +6,7 @@
 use chrono::{Duration, Utc};
 use locai::models::{Memory, MemoryPriority, MemoryType};
 use locai::prelude::*;
-use locai::storage::models::{MemorySnapshot, RestoreMode};
+use locai::storage::models::{Change, MemorySnapshot, RestoreAction, RestoreMode};
 use locai::storage::shared_storage::{SharedStorage, SharedStorageConfig};
 use locai::storage::traits::MemoryVersionStore;
 use serde_json::json;
@@ -18,6 +18,7 @@ async fn create_test_storage() -> SharedStorage<surrealdb::engine::local::Db> {
         database: "test_versioning".to_string(),
         lifecycle_tracking: Default::default(),
         versioning: Default::default(),
+        ..Default::default()
     };
 
     let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
@@ -307,6 +308,167 @@ async fn test_restore_snapshot() {
     assert_eq!(restored.content, "Snapshot version");
 }
 
+#[tokio::test]
+async fn test_restore_snapshot_preview() {
+    let storage = create_test_storage().await;
+
+    use locai::storage::traits::MemoryStore;
+    let kept = MemoryStore::create_memory(&storage, create_test_memory("preview_kept", "Kept"))
+        .await
+        .unwrap();
+    storage
+        .create_memory_version(&kept.id, "Snapshot version", None)
+        .await
+        .expect("Failed to create version");
+    let deleted = MemoryStore::create_memory(&storage, create_test_memory("preview_gone", "Gone"))
+        .await
+        .unwrap();
+
+    let memory_ids = vec![kept.id.clone(), deleted.id.clone()];
+    let snapshot = storage
+        .create_snapshot(Some(&memory_ids), None)
+        .await
+        .expect("Failed to create snapshot");
+    let stored = storage
+        .get_snapshot(&snapshot.snapshot_id)
+        .await
+        .expect("Failed to get snapshot")
+        .expect("Snapshot should exist");
+    assert_eq!(stored.version_map, snapshot.version_map);
+
+    let mut updated_memory = kept.clone();
+    updated_memory.content = "Modified content".to_string();
+    MemoryStore::update_memory(&storage, updated_memory)
+        .await
+        .expect("Failed to update memory");
+    MemoryStore::delete_memory(&storage, &deleted.id)
+        .await
+        .expect("Failed to delete memory");
+
+    let preview = storage
+        .restore_snapshot_preview(&stored, RestoreMode::Overwrite)
+        .await
+        .expect("Failed to preview restore");
+    assert_eq!(preview.count(RestoreAction::Overwrite), 1);
+    assert_eq!(preview.count(RestoreAction::Skip), 1);
+    let entry = preview
+        .entries
+        .iter()
+        .find(|e| e.memory_id == kept.id)
+        .expect("Kept memory should be previewed");
+    match &entry.changes[..] {
+        [
+            Change::ContentChanged {
+                old_content,
+                new_content,
+                ..
+            },
+        ] => {
+            assert_eq!(old_content, "Modified content");
+            assert_eq!(new_content, "Snapshot version");
+        }
+        changes => panic!("Unexpected changes: {:?}", changes),
+    }
+
+    // Previewing writes nothing
+    let current = MemoryStore::get_memory(&storage, &kept.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(current.content, "Modified content");
+
+    let preview = storage
+        .restore_snapshot_preview(&stored, RestoreMode::SkipExisting)
+        .await
+        .expect("Failed to preview restore");
+    assert_eq!(preview.count(RestoreAction::Skip), 2);
+
+    storage
+        .restore_snapshot(&stored, RestoreMode::Overwrite)
+        .await
+        .expect("Failed to restore snapshot");
+    let restored = MemoryStore::get_memory(&storage, &kept.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored.content, "Snapshot version");
+}
+
+#[tokio::test]
+async fn test_snapshot_export_import_between_instances() {
+    let source = create_test_storage().await;
+    let target = create_test_storage().await;
+
+    use locai::storage::traits::MemoryStore;
+    let mut unchanged = create_test_memory("portable_unchanged", "Still current");
+    unchanged.embedding = Some(vec![0.1; 1024]);
+    let unchanged = MemoryStore::create_memory(&source, unchanged)
+        .await
+        .unwrap();
+    source
+        .create_memory_version(&unchanged.id, "Still current", None)
+        .await
+        .expect("Failed to create version");
+    let mut edited = create_test_memory("portable_edited", "Current text");
+    edited.embedding = Some(vec![0.2; 1024]);
+    let edited = MemoryStore::create_memory(&source, edited).await.unwrap();
+    source
+        .create_memory_version(&edited.id, "Snapshot text", None)
+        .await
+        .expect("Failed to create version");
+
+    let memory_ids = vec![unchanged.id.clone(), edited.id.clone()];
+    let snapshot = source
+        .create_snapshot(Some(&memory_ids), None)
+        .await
+        .expect("Failed to create snapshot");
+    let bundle = source
+        .export_snapshot(&snapshot)
+        .await
+        .expect("Failed to export snapshot");
+    let bundle: locai::storage::models::SnapshotBundle =
+        serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+    assert_eq!(bundle.memories.len(), 2);
+
+    // The target already has its own copy of one memory
+    MemoryStore::create_memory(&target, create_test_memory(&edited.id, "Local text"))
+        .await
+        .unwrap();
+
+    let imported = target
+        .import_snapshot(&bundle, RestoreMode::SkipExisting)
+        .await
+        .expect("Failed to import snapshot");
+    assert_eq!(imported.memory_ids, vec![unchanged.id.clone()]);
+    assert_eq!(
+        imported.metadata.get("imported_from"),
+        Some(&json!(snapshot.snapshot_id))
+    );
+    let copied = MemoryStore::get_memory(&target, &unchanged.id)
+        .await
+        .unwrap()
+        .expect("Memory should be imported");
+    assert_eq!(copied.content, "Still current");
+    assert_eq!(copied.embedding.map(|e| e.len()), Some(1024));
+    let local = MemoryStore::get_memory(&target, &edited.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(local.content, "Local text");
+
+    target
+        .import_snapshot(&bundle, RestoreMode::Overwrite)
+        .await
+        .expect("Failed to import snapshot");
+    let overwritten = MemoryStore::get_memory(&target, &edited.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(overwritten.content, "Snapshot text");
+    // The source embedding was for different content, so it isn't carried over
+    assert!(overwritten.embedding.is_none());
+}
+
 #[tokio::test]
 async fn test_auto_version_on_create() {
     let storage = create_test_storage().await;
@@ -648,3 +810,121 @@ async fn test_repair_versions() {
     assert!(report.versions_repaired >= 0);
     assert!(report.versions_failed >= 0);
 }
+
+#[tokio::test]
+async fn test_version_retention_keeps_deltas_readable() {
+    let storage = create_test_storage().await;
+
+    let memory = create_test_memory("test_memory_retention", "Initial");
+    use locai::storage::traits::MemoryStore;
+    let created = MemoryStore::create_memory(&storage, memory).await.unwrap();
+
+    // Enough versions that some are stored as deltas
+    for i in 1..=15 {
+        storage
+            .create_memory_version(&created.id, &format!("Version {}", i), None)
+            .await
+            .expect("Failed to create version");
+    }
+    let before = storage
+        .list_memory_versions(&created.id)
+        .await
+        .expect("Failed to list versions")
+        .len();
+
+    let policy = locai::config::VersionRetentionConfig {
+        keep_last: 3,
+        max_age_days: Some(0),
+        ..Default::default()
+    };
+    let pruned = storage
+        .apply_version_retention(&policy)
+        .await
+        .expect("Failed to apply retention");
+    assert_eq!(pruned, before - 3);
+
+    let versions = storage
+        .list_memory_versions(&created.id)
+        .await
+        .expect("Failed to list versions");
+    assert_eq!(versions.len(), 3);
+
+    let mut contents = Vec::new();
+    for version in &versions {
+        let version = storage
+            .get_memory_version(&created.id, &version.version_id)
+            .await
+            .expect("Failed to get version")
+            .expect("Version should exist");
+        contents.push(version.content);
+    }
+    contents.sort();
+    assert_eq!(contents, vec!["Version 13", "Version 14", "Version 15"]);
+
+    let issues = storage
+        .validate_versions(Some(&created.id))
+        .await
+        .expect("Failed to validate versions");
+    assert!(
+        issues.is_empty(),
+        "Retention broke a delta chain: {:?}",
+        issues
+    );
+}
+
+#[tokio::test]
+async fn test_verify_integrity_detects_tampering() {
+    let config = SharedStorageConfig {
+        namespace: "test_versioning".to_string(),
+        database: "test_versioning".to_string(),
+        lifecycle_tracking: Default::default(),
+        versioning: locai::config::VersioningConfig {
+            hash_chain: true,
+            hash_chain_key: Some("audit-key".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
+        .await
+        .unwrap();
+    let storage = SharedStorage::new(client, config).await.unwrap();
+
+    let memory = create_test_memory("test_memory_chain", "Initial");
+    use locai::storage::traits::MemoryStore;
+    let created = MemoryStore::create_memory(&storage, memory).await.unwrap();
+
+    let mut version_ids = Vec::new();
+    for i in 1..=3 {
+        let version_id = storage
+            .create_memory_version(&created.id, &format!("Version {}", i), None)
+            .await
+            .expect("Failed to create version");
+        version_ids.push(version_id);
+    }
+
+    let report = storage
+        .verify_integrity(&created.id)
+        .await
+        .expect("Failed to verify integrity");
+    assert!(report.is_intact(), "Unexpected issues: {:?}", report.issues);
+    assert!(report.versions_checked >= 3);
+
+    // Rewrite the middle version's content behind the API's back
+    storage
+        .client()
+        .query("UPDATE memory_version SET content = 'Rewritten' WHERE version_id = $version_id")
+        .bind(("version_id", version_ids[1].clone()))
+        .await
+        .unwrap();
+
+    let report = storage
+        .verify_integrity(&created.id)
+        .await
+        .expect("Failed to verify integrity");
+    assert!(!report.is_intact());
+    assert!(report.issues.iter().any(|issue| {
+        issue.issue_type == locai::storage::models::IntegrityIssueType::HashMismatch
+            && issue.version_id.as_deref() == Some(version_ids[1].as_str())
+    }));
+}
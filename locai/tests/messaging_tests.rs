@@ -0,0 +1,1300 @@
+//! Messaging tests: presence, delivery, encryption, retention, bridges, notifications and the outbox
+
+mod common;
+
+mod agent_presence {
+    //! Agent registry and presence tests
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use locai::config::ConfigBuilder;
+    use locai::messaging::presence::{PresenceReason, PresenceStatus};
+    use locai::messaging::{AgentRegistration, LocaiMessaging, MessageStream, PresenceEvent};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_agents(app_ids: &[&str]) -> (Vec<Arc<LocaiMessaging>>, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .expect("Failed to build config");
+        let manager = Arc::new(locai::init(config).await.expect("Failed to init Locai"));
+        let mut agents = Vec::new();
+        for app_id in app_ids {
+            let messaging = LocaiMessaging::embedded(manager.clone(), app_id.to_string())
+                .await
+                .expect("Failed to create messaging");
+            agents.push(Arc::new(messaging));
+        }
+        (agents, temp_dir)
+    }
+
+    async fn next_event(stream: &mut MessageStream) -> PresenceEvent {
+        let message = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("no presence event")
+            .unwrap()
+            .unwrap();
+        PresenceEvent::from_message(&message).expect("not a presence event")
+    }
+
+    #[tokio::test]
+    async fn test_registered_agents_are_listed() {
+        let (agents, _dir) = create_agents(&["planner", "worker"]).await;
+        let (planner, worker) = (&agents[0], &agents[1]);
+
+        planner
+            .register_agent(AgentRegistration::new(["plan"]).metadata(json!({ "model": "small" })))
+            .await
+            .unwrap();
+        worker
+            .register_agent(AgentRegistration::new(["summarize", "translate"]))
+            .await
+            .unwrap();
+
+        let online = planner.list_agents().await.unwrap();
+        let ids: Vec<&str> = online.iter().map(|a| a.app_id.as_str()).collect();
+        assert_eq!(ids, vec!["planner", "worker"]);
+        assert_eq!(online[0].metadata["model"], "small");
+
+        let summarizers = planner.find_agents("summarize").await.unwrap();
+        assert_eq!(summarizers.len(), 1);
+        assert_eq!(summarizers[0].app_id, "worker");
+
+        assert!(worker.deregister_agent().await.unwrap());
+        assert!(!worker.deregister_agent().await.unwrap());
+        assert_eq!(planner.list_agents().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_presence_changes_are_published() {
+        let (agents, _dir) = create_agents(&["planner", "worker"]).await;
+        let (planner, worker) = (&agents[0], &agents[1]);
+        let mut presence = planner.subscribe_presence().await.unwrap();
+
+        worker
+            .register_agent(
+                AgentRegistration::new(["summarize"]).heartbeat_ttl(Duration::from_secs(1)),
+            )
+            .await
+            .unwrap();
+        let event = next_event(&mut presence).await;
+        assert_eq!(event.app_id, "worker");
+        assert_eq!(event.status, PresenceStatus::Online);
+        assert_eq!(event.reason, PresenceReason::Registered);
+        assert_eq!(event.capabilities, vec!["summarize".to_string()]);
+
+        // Missed heartbeats are noticed by the next listing
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(planner.list_agents().await.unwrap().is_empty());
+        let event = next_event(&mut presence).await;
+        assert_eq!(event.status, PresenceStatus::Offline);
+        assert_eq!(event.reason, PresenceReason::HeartbeatTimeout);
+        assert!(!planner.get_agent("worker").await.unwrap().unwrap().online);
+
+        worker.heartbeat().await.unwrap();
+        let event = next_event(&mut presence).await;
+        assert_eq!(event.status, PresenceStatus::Online);
+        assert_eq!(event.reason, PresenceReason::HeartbeatResumed);
+        assert_eq!(planner.list_agents().await.unwrap().len(), 1);
+
+        worker.deregister_agent().await.unwrap();
+        let event = next_event(&mut presence).await;
+        assert_eq!(event.status, PresenceStatus::Offline);
+        assert_eq!(event.reason, PresenceReason::Deregistered);
+    }
+
+    #[tokio::test]
+    async fn test_background_heartbeat_keeps_agent_online() {
+        let (agents, _dir) = create_agents(&["worker"]).await;
+        let worker = &agents[0];
+        worker
+            .register_agent(
+                AgentRegistration::new(["summarize"]).heartbeat_ttl(Duration::from_secs(1)),
+            )
+            .await
+            .unwrap();
+
+        let heartbeat = worker.start_heartbeat(Duration::from_millis(200));
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert_eq!(worker.list_agents().await.unwrap().len(), 1);
+
+        heartbeat.stop();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(worker.list_agents().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_requires_registration() {
+        let (agents, _dir) = create_agents(&["worker"]).await;
+        assert!(agents[0].heartbeat().await.is_err());
+    }
+}
+
+mod dead_letter {
+    //! Dead letter queue tests
+
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use locai::config::ConfigBuilder;
+    use locai::messaging::dead_letter::list_dead_letters;
+    use locai::messaging::{DeadLetterPolicy, DeadLetterReason, LocaiMessaging, Message};
+    use locai::{LocaiError, Result};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_messaging(
+        app_id: &str,
+        policy: DeadLetterPolicy,
+    ) -> (Arc<LocaiMessaging>, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .expect("Failed to build config");
+        let manager = Arc::new(locai::init(config).await.expect("Failed to init Locai"));
+        let messaging = LocaiMessaging::embedded(manager, app_id.to_string())
+            .await
+            .expect("Failed to create messaging")
+            .with_dead_letter_policy(policy);
+        (Arc::new(messaging), temp_dir)
+    }
+
+    fn fast_policy() -> DeadLetterPolicy {
+        DeadLetterPolicy {
+            max_attempts: 3,
+            retry_delay: Duration::from_millis(5),
+            ..DeadLetterPolicy::default()
+        }
+    }
+
+    async fn wait_for<F: Fn() -> bool>(condition: F) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("condition not met in time");
+    }
+
+    #[tokio::test]
+    async fn test_failing_handler_dead_letters_after_retries() {
+        let (messaging, _dir) = create_messaging("game", fast_policy()).await;
+        let calls = Arc::new(AtomicU32::new(0));
+        let handler_calls = calls.clone();
+        let consumer = messaging
+            .consume("orders.*", move |message: Message| {
+                let calls = handler_calls.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    if message.content["ok"] == true {
+                        Ok(())
+                    } else {
+                        Err(LocaiError::Other("cannot process order".to_string()))
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        messaging
+            .send("orders.new", json!({ "ok": true }))
+            .await
+            .unwrap();
+        let poison = messaging
+            .send("orders.new", json!({ "ok": false }))
+            .await
+            .unwrap();
+
+        wait_for(|| consumer.stats().dead_lettered() == 1).await;
+        assert_eq!(consumer.stats().handled(), 1);
+        assert_eq!(consumer.stats().retried(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        let letters = messaging.get_dead_letters(None).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        let letter = &letters[0];
+        assert_eq!(letter.reason, DeadLetterReason::HandlerFailed);
+        assert_eq!(letter.original_topic, "app:game.orders.new");
+        assert_eq!(letter.original_id, poison.to_string());
+        assert_eq!(letter.attempts, 3);
+        assert!(letter.error.contains("cannot process order"));
+        assert_eq!(letter.message.content["ok"], false);
+        assert_eq!(letter.message.topic, "app:game.dlq");
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_are_not_redelivered_to_wildcard_consumers() {
+        let (messaging, _dir) = create_messaging("game", fast_policy()).await;
+        let consumer = messaging
+            .consume("*", |_message: Message| async {
+                Err::<(), _>(LocaiError::Other("always fails".to_string()))
+            })
+            .await
+            .unwrap();
+
+        messaging.send("jobs", json!(1)).await.unwrap();
+        wait_for(|| consumer.stats().dead_lettered() == 1).await;
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(consumer.stats().dead_lettered(), 1);
+        assert_eq!(messaging.get_dead_letters(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_is_rejected_and_dead_lettered() {
+        let policy = DeadLetterPolicy {
+            max_message_size: 64,
+            ..fast_policy()
+        };
+        let (messaging, _dir) = create_messaging("game", policy).await;
+
+        let result = messaging
+            .send("uploads", json!({ "blob": "x".repeat(256) }))
+            .await;
+        assert!(result.is_err());
+
+        let letters = messaging.get_dead_letters(None).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].reason, DeadLetterReason::TooLarge);
+        assert_eq!(letters[0].attempts, 0);
+        assert!(letters[0].message.content.is_null());
+        assert!(
+            messaging
+                .get_message_history(None, None)
+                .await
+                .unwrap()
+                .iter()
+                .all(|m| m.topic != "app:game.uploads")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_topic_is_dead_lettered_and_listed_from_store() -> Result<()> {
+        let (messaging, _dir) = create_messaging("game", fast_policy()).await;
+
+        assert!(messaging.send("bad topic", json!("hi")).await.is_err());
+
+        let manager = messaging.memory_manager().unwrap();
+        let letters = list_dead_letters(manager, Some("game"), Some(10)).await?;
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].reason, DeadLetterReason::InvalidFormat);
+        assert_eq!(letters[0].original_topic, "app:game.bad topic");
+
+        assert!(
+            list_dead_letters(manager, Some("other"), None)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+}
+
+mod encrypted_messaging {
+    //! End-to-end encrypted messaging tests
+
+    use std::sync::Arc;
+
+    use locai::config::ConfigBuilder;
+    use locai::messaging::encryption::{ENCRYPTION_HEADER, is_encrypted};
+    use locai::messaging::{AppKeyPair, LocaiMessaging, MessageFilter};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_apps(app_ids: &[&str]) -> (Vec<LocaiMessaging>, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .expect("Failed to build config");
+        let manager = Arc::new(locai::init(config).await.expect("Failed to init Locai"));
+        let mut apps = Vec::new();
+        for app_id in app_ids {
+            let messaging = LocaiMessaging::embedded(manager.clone(), app_id.to_string())
+                .await
+                .expect("Failed to create messaging")
+                .with_encryption(AppKeyPair::generate().unwrap());
+            messaging.publish_public_key().await.unwrap();
+            apps.push(messaging);
+        }
+        (apps, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_only_recipients_can_read_encrypted_messages() {
+        let (apps, _dir) = create_apps(&["planner", "worker", "observer"]).await;
+        let (planner, worker, observer) = (&apps[0], &apps[1], &apps[2]);
+
+        planner
+            .send_encrypted(
+                "tasks",
+                json!({ "task": "rotate the api keys" }),
+                &["worker"],
+            )
+            .await
+            .unwrap();
+
+        let filter = MessageFilter::new().topic_patterns(["app:planner.tasks"]);
+        let stored = worker
+            .get_message_history(Some(filter), None)
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        let message = &stored[0];
+
+        // What storage and the server see
+        assert!(is_encrypted(message));
+        assert!(!message.content.to_string().contains("api keys"));
+        assert_eq!(message.recipients, vec!["worker".to_string()]);
+
+        let decrypted = worker.decrypt(message).await.unwrap();
+        assert_eq!(decrypted.content["task"], "rotate the api keys");
+        assert!(decrypted.get_header(ENCRYPTION_HEADER).is_none());
+
+        // The sender can read its own message; others can't
+        assert!(planner.decrypt(message).await.is_ok());
+        assert!(observer.decrypt(message).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_key_rejects_impostor() {
+        let (apps, _dir) = create_apps(&["planner", "worker"]).await;
+        let (planner, worker) = (&apps[0], &apps[1]);
+
+        planner
+            .send_encrypted("tasks", json!("hello"), &["worker"])
+            .await
+            .unwrap();
+        let message = worker
+            .get_message_history(
+                Some(MessageFilter::new().topic_patterns(["app:planner.tasks"])),
+                None,
+            )
+            .await
+            .unwrap()
+            .remove(0);
+
+        // A key pinned out of band wins over the announced one
+        worker.trust_public_key("planner", AppKeyPair::generate().unwrap().public_key());
+        assert!(worker.decrypt(&message).await.is_err());
+
+        worker.trust_public_key("planner", planner.public_key().unwrap());
+        assert_eq!(worker.decrypt(&message).await.unwrap().content, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_encryption_requires_keys() {
+        let (apps, _dir) = create_apps(&["planner"]).await;
+        let plain = LocaiMessaging::embedded(
+            apps[0].memory_manager().unwrap().clone(),
+            "plain".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            plain
+                .send_encrypted("tasks", json!(1), &["planner"])
+                .await
+                .is_err()
+        );
+        // Unknown recipients have no announced key
+        assert!(
+            apps[0]
+                .send_encrypted("tasks", json!(1), &["nobody"])
+                .await
+                .is_err()
+        );
+    }
+}
+
+mod message_retention {
+    //! Message retention and compaction tests
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use locai::config::{ConfigBuilder, TopicRetentionPolicy};
+    use locai::messaging::{LocaiMessaging, Message, MessageFilter};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_messaging(
+        policies: Vec<TopicRetentionPolicy>,
+        interval_secs: u64,
+    ) -> (Arc<LocaiMessaging>, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let mut config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .expect("Failed to build config");
+        config.messaging.retention = policies;
+        config.messaging.retention_interval_secs = interval_secs;
+        let manager = Arc::new(locai::init(config).await.expect("Failed to init Locai"));
+        let messaging = LocaiMessaging::embedded(manager, "game".to_string())
+            .await
+            .expect("Failed to create messaging");
+        (Arc::new(messaging), temp_dir)
+    }
+
+    async fn send_aged(
+        messaging: &LocaiMessaging,
+        topic: &str,
+        age_secs: i64,
+        header: Option<&str>,
+    ) {
+        let mut message = Message::new(
+            format!("app:game.{}", topic),
+            "game".to_string(),
+            json!({ "age": age_secs }),
+        );
+        message.timestamp = chrono::Utc::now() - chrono::Duration::seconds(age_secs);
+        if let Some(value) = header {
+            message = message.add_header("entity", value);
+        }
+        messaging.send_with_options(message).await.unwrap();
+    }
+
+    async fn stored_ages(messaging: &LocaiMessaging, topic_pattern: &str) -> Vec<i64> {
+        let filter = MessageFilter::new().topic_patterns([format!("app:game.{}", topic_pattern)]);
+        let mut ages: Vec<i64> = messaging
+            .get_message_history(Some(filter), None)
+            .await
+            .unwrap()
+            .iter()
+            .map(|message| message.content["age"].as_i64().unwrap())
+            .collect();
+        ages.sort();
+        ages
+    }
+
+    #[tokio::test]
+    async fn test_age_and_count_limits() {
+        let (messaging, _dir) = create_messaging(
+            vec![TopicRetentionPolicy {
+                topic: "app:game.chat.*".to_string(),
+                max_age_secs: Some(3600),
+                max_count: Some(2),
+                ..Default::default()
+            }],
+            0,
+        )
+        .await;
+
+        for age in [10, 20, 30, 7200] {
+            send_aged(&messaging, "chat.say", age, None).await;
+        }
+        send_aged(&messaging, "weather.changed", 7200, None).await;
+
+        let report = messaging
+            .memory_manager()
+            .unwrap()
+            .enforce_message_retention()
+            .await
+            .unwrap();
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.trimmed, 1);
+        assert_eq!(report.compacted, 0);
+
+        assert_eq!(stored_ages(&messaging, "chat.*").await, vec![10, 20]);
+        // Topics without a policy are kept forever
+        assert_eq!(stored_ages(&messaging, "weather.*").await, vec![7200]);
+    }
+
+    #[tokio::test]
+    async fn test_compaction_by_header() {
+        let (messaging, _dir) = create_messaging(
+            vec![TopicRetentionPolicy {
+                topic: "app:game.state".to_string(),
+                compact_by_header: Some("entity".to_string()),
+                ..Default::default()
+            }],
+            0,
+        )
+        .await;
+
+        send_aged(&messaging, "state", 30, Some("door")).await;
+        send_aged(&messaging, "state", 20, Some("lamp")).await;
+        send_aged(&messaging, "state", 10, Some("door")).await;
+        send_aged(&messaging, "state", 40, None).await;
+
+        let report = messaging
+            .memory_manager()
+            .unwrap()
+            .enforce_message_retention()
+            .await
+            .unwrap();
+        assert_eq!(report.compacted, 1);
+        assert_eq!(stored_ages(&messaging, "state").await, vec![10, 20, 40]);
+    }
+
+    #[tokio::test]
+    async fn test_background_task_enforces_retention() {
+        let (messaging, _dir) = create_messaging(
+            vec![TopicRetentionPolicy {
+                topic: "app:game.*".to_string(),
+                max_count: Some(1),
+                ..Default::default()
+            }],
+            1,
+        )
+        .await;
+
+        for age in [1, 2, 3] {
+            send_aged(&messaging, "ticks", age, None).await;
+        }
+
+        for _ in 0..50 {
+            if stored_ages(&messaging, "ticks").await == vec![1] {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("retention task did not trim messages");
+    }
+}
+
+mod messaging_bridge {
+    //! Messaging bridge tests
+    //!
+    //! An in-process broker stands in for NATS or Kafka: every publish is
+    //! delivered to subscribers of the exact subject or of a `prefix.>` wildcard.
+
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use locai::Result;
+    use locai::config::ConfigBuilder;
+    use locai::messaging::bridge::{
+        BRIDGE_ORIGIN_HEADER, BridgeConfig, BridgeRecord, BridgeStream, BridgeTransport,
+        MessagingBridge,
+    };
+    use locai::messaging::{LocaiMessaging, Message};
+    use serde_json::json;
+    use tempfile::TempDir;
+    use tokio::sync::mpsc;
+
+    #[derive(Default)]
+    struct TestBroker {
+        published: Mutex<Vec<BridgeRecord>>,
+        subscribers: Mutex<Vec<(String, mpsc::UnboundedSender<Result<BridgeRecord>>)>>,
+    }
+
+    fn subject_matches(pattern: &str, subject: &str) -> bool {
+        match pattern.strip_suffix('>') {
+            Some(prefix) => subject.starts_with(prefix),
+            None => pattern == subject,
+        }
+    }
+
+    #[async_trait]
+    impl BridgeTransport for TestBroker {
+        fn name(&self) -> &str {
+            "test-broker"
+        }
+
+        async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<()> {
+            let record = BridgeRecord {
+                subject: subject.to_string(),
+                payload,
+            };
+            self.published.lock().unwrap().push(record.clone());
+            for (pattern, sender) in self.subscribers.lock().unwrap().iter() {
+                if subject_matches(pattern, subject) {
+                    let _ = sender.send(Ok(record.clone()));
+                }
+            }
+            Ok(())
+        }
+
+        async fn subscribe(&self, subject: &str) -> Result<BridgeStream> {
+            let (sender, mut receiver) = mpsc::unbounded_channel();
+            self.subscribers
+                .lock()
+                .unwrap()
+                .push((subject.to_string(), sender));
+            Ok(Box::pin(async_stream::stream! {
+                while let Some(record) = receiver.recv().await {
+                    yield record;
+                }
+            }))
+        }
+    }
+
+    impl TestBroker {
+        fn published(&self) -> Vec<BridgeRecord> {
+            self.published.lock().unwrap().clone()
+        }
+    }
+
+    async fn create_messaging(app_id: &str) -> (Arc<LocaiMessaging>, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .expect("Failed to build config");
+        let manager = Arc::new(locai::init(config).await.expect("Failed to init Locai"));
+        let messaging = LocaiMessaging::embedded(manager, app_id.to_string())
+            .await
+            .expect("Failed to create messaging");
+        (Arc::new(messaging), temp_dir)
+    }
+
+    async fn wait_for<F: Fn() -> bool>(condition: F) {
+        for _ in 0..100 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("condition not met in time");
+    }
+
+    #[tokio::test]
+    async fn test_outbound_messages_reach_broker() {
+        let (messaging, _dir) = create_messaging("game").await;
+        let broker = Arc::new(TestBroker::default());
+        let bridge = MessagingBridge::start(
+            messaging.clone(),
+            broker.clone(),
+            BridgeConfig {
+                outbound: vec!["character.*".to_string()],
+                ..BridgeConfig::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        messaging
+            .send("character.moved", json!({ "to": "tavern" }))
+            .await
+            .unwrap();
+        messaging
+            .send("weather.changed", json!({ "to": "rain" }))
+            .await
+            .unwrap();
+
+        wait_for(|| bridge.stats().published() == 1).await;
+        let published = broker.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].subject, "locai.character.moved");
+
+        let message: Message = serde_json::from_slice(&published[0].payload).unwrap();
+        assert_eq!(message.content["to"], "tavern");
+        assert_eq!(message.sender, "game");
+        assert_eq!(
+            message.get_header(BRIDGE_ORIGIN_HEADER).unwrap(),
+            bridge.id()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inbound_messages_are_republished() {
+        let (messaging, _dir) = create_messaging("game").await;
+        let broker = Arc::new(TestBroker::default());
+        let mut alerts = messaging.subscribe("alerts.*").await.unwrap();
+        let bridge = MessagingBridge::start(
+            messaging.clone(),
+            broker.clone(),
+            BridgeConfig {
+                outbound: vec!["alerts.*".to_string()],
+                inbound: vec!["locai.alerts.>".to_string()],
+                ..BridgeConfig::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        broker
+            .publish("locai.alerts.disk", br#"{"free_gb":2}"#.to_vec())
+            .await
+            .unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(5), alerts.next())
+            .await
+            .expect("no message republished")
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.topic, "app:game.alerts.disk");
+        assert_eq!(message.content["free_gb"], 2);
+        assert_eq!(message.sender, "test-broker");
+        wait_for(|| bridge.stats().received() == 1).await;
+
+        // The republished message matches the outbound pattern but is not sent
+        // back to the broker it came from
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(broker.published().len(), 1);
+        assert_eq!(bridge.stats().published(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_bridge_ignores_its_own_messages() {
+        let (messaging, _dir) = create_messaging("game").await;
+        let broker = Arc::new(TestBroker::default());
+        let bridge = MessagingBridge::start(
+            messaging.clone(),
+            broker.clone(),
+            BridgeConfig {
+                outbound: vec!["chat.*".to_string()],
+                inbound: vec!["locai.chat.>".to_string()],
+                ..BridgeConfig::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        messaging.send("chat.say", json!("hello")).await.unwrap();
+        wait_for(|| bridge.stats().published() == 1).await;
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(bridge.stats().received(), 0);
+        assert_eq!(broker.published().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bridge_requires_topics() {
+        let (messaging, _dir) = create_messaging("game").await;
+        let broker = Arc::new(TestBroker::default());
+        assert!(
+            MessagingBridge::start(messaging, broker, BridgeConfig::default())
+                .await
+                .is_err()
+        );
+    }
+}
+
+mod notification {
+    //! Notification tests
+    //!
+    //! Rules send notifications about review queue items and reminders to the
+    //! targets they name.
+
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use locai::config::{
+        ConfigBuilder, NotificationChannel, NotificationRule, NotificationTarget,
+        NotificationsConfig, ReviewConfig,
+    };
+    use locai::notifications::{Notification, NotificationKind, Notifier};
+    use tempfile::TempDir;
+
+    #[derive(Debug, Default)]
+    struct Recorder {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for Recorder {
+        async fn send(&self, notification: &Notification) -> locai::Result<()> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_held_memories_notify_their_rule_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .with_review(ReviewConfig {
+                enabled: true,
+                sources: vec!["agent:*".to_string()],
+                auto_approve: Vec::new(),
+            })
+            .build()
+            .expect("Failed to build config");
+        let manager = locai::init(config).await.expect("Failed to init");
+
+        let recorder = Arc::new(Recorder::default());
+        let notifications = manager.notifications();
+        notifications
+            .add_notifier("recorder", recorder.clone())
+            .unwrap();
+        notifications
+            .set_rule(NotificationRule {
+                name: "reviews".to_string(),
+                events: vec![NotificationKind::Review],
+                targets: vec!["recorder".to_string()],
+                tags: Vec::new(),
+            })
+            .unwrap();
+
+        let held = manager
+            .add_memory_with_options("The office closes at noon on Fridays".to_string(), |b| {
+                b.source("agent:planner")
+            })
+            .await
+            .unwrap();
+        manager.add_fact("Written by a person").await.unwrap();
+
+        let mut sent = Vec::new();
+        for _ in 0..50 {
+            sent = recorder.sent.lock().unwrap().clone();
+            if !sent.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].kind, NotificationKind::Review);
+        assert_eq!(sent[0].memory_id.as_deref(), Some(held.as_str()));
+    }
+
+    #[test]
+    fn test_rules_must_send_to_configured_targets() {
+        let target = NotificationTarget {
+            name: "ops".to_string(),
+            channel: NotificationChannel::Slack {
+                webhook_url: "https://hooks.slack.com/services/T0/B0/X".to_string(),
+            },
+        };
+        let rule = |target: &str| NotificationRule {
+            name: "anomalies".to_string(),
+            events: vec![NotificationKind::Anomaly],
+            targets: vec![target.to_string()],
+            tags: Vec::new(),
+        };
+
+        let valid = ConfigBuilder::new()
+            .with_memory_storage()
+            .with_notifications(NotificationsConfig {
+                targets: vec![target.clone()],
+                rules: vec![rule("ops")],
+                ..NotificationsConfig::default()
+            })
+            .build();
+        assert!(valid.is_ok());
+
+        let unknown = ConfigBuilder::new()
+            .with_memory_storage()
+            .with_notifications(NotificationsConfig {
+                targets: vec![target],
+                rules: vec![rule("email")],
+                ..NotificationsConfig::default()
+            })
+            .build();
+        assert!(unknown.is_err());
+    }
+}
+
+mod offline_queue {
+    //! Offline queue tests
+    //!
+    //! The store never really drops out here; `go_offline` stands in for a lost
+    //! connection, and edits made directly on the store play another writer.
+
+    use std::sync::Arc;
+
+    use locai::prelude::*;
+    use locai::replication::offline::{
+        ConflictResolution, OfflineConfig, OfflineConflictPolicy, OfflineQueue,
+    };
+    use locai::replication::{ChangeOp, RecordKind};
+    use locai::storage::models::Entity;
+    use tempfile::TempDir;
+
+    async fn create_locai() -> (Locai, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let locai = Locai::builder()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .await
+            .expect("Failed to create Locai");
+        (locai, temp_dir)
+    }
+
+    async fn open_queue(locai: &Locai, config: OfflineConfig) -> Arc<OfflineQueue> {
+        OfflineQueue::open(
+            locai.manager().storage().clone(),
+            OfflineConfig {
+                retry_interval: None,
+                ..config
+            },
+        )
+        .await
+        .expect("Failed to open offline queue")
+    }
+
+    fn entity(id: &str, name: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties: serde_json::json!({ "name": name }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_queue_while_offline_and_replay_in_order() {
+        let (locai, _dir) = create_locai().await;
+        let queue = open_queue(&locai, OfflineConfig::default()).await;
+        let storage = locai.manager().storage();
+
+        let memory = queue
+            .put_memory(MemoryBuilder::fact("Charger is in the hallway").build())
+            .await
+            .unwrap();
+        assert!(queue.is_online());
+        assert!(storage.get_memory(&memory.id).await.unwrap().is_some());
+
+        queue.go_offline();
+        let queued = queue
+            .put_memory(MemoryBuilder::fact("Charger moved to the office").build())
+            .await
+            .unwrap();
+        queue.put_entity(entity("dana", "Dana")).await.unwrap();
+        assert!(queue.delete_memory(&memory.id).await.unwrap());
+
+        let pending = queue.pending_writes();
+        assert_eq!(pending.len(), 3);
+        assert_eq!(pending[0].kind, RecordKind::Memory);
+        assert_eq!(pending[1].id, "dana");
+        assert_eq!(pending[2].op, ChangeOp::Delete);
+        assert!(pending.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+
+        // Reads through the queue see queued writes; the store has none of them
+        assert_eq!(
+            queue.get_memory(&queued.id).await.unwrap().unwrap().content,
+            "Charger moved to the office"
+        );
+        assert!(queue.get_memory(&memory.id).await.unwrap().is_none());
+        assert!(storage.get_memory(&queued.id).await.unwrap().is_none());
+        assert!(storage.get_memory(&memory.id).await.unwrap().is_some());
+
+        let report = queue.reconcile().await.unwrap();
+        assert_eq!(report.applied, 3);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(report.remaining, 0);
+        assert!(queue.is_online());
+        assert!(queue.pending_writes().is_empty());
+
+        assert_eq!(
+            storage
+                .get_memory(&queued.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .content,
+            "Charger moved to the office"
+        );
+        assert!(storage.get_entity("dana").await.unwrap().is_some());
+        assert!(storage.get_memory(&memory.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_writes_are_reported() {
+        let (locai, _dir) = create_locai().await;
+        let queue = open_queue(&locai, OfflineConfig::default()).await;
+        let storage = locai.manager().storage();
+
+        queue.put_entity(entity("erin", "Erin")).await.unwrap();
+        queue.go_offline();
+        queue
+            .put_entity(entity("erin", "Erin Offline"))
+            .await
+            .unwrap();
+        // A second queued write to the same record builds on the first
+        queue
+            .put_entity(entity("erin", "Erin Offline 2"))
+            .await
+            .unwrap();
+
+        // Another writer changes the record while this node is offline
+        storage
+            .update_entity(entity("erin", "Erin Remote"))
+            .await
+            .unwrap();
+
+        let report = queue.reconcile().await.unwrap();
+        assert_eq!(report.applied, 0);
+        assert_eq!(report.conflicts.len(), 2);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.resolution, ConflictResolution::KeptRemote);
+        assert_eq!(
+            conflict.remote.as_ref().unwrap()["properties"]["name"],
+            "Erin Remote"
+        );
+        assert_eq!(
+            conflict.write.record.as_ref().unwrap()["properties"]["name"],
+            "Erin Offline"
+        );
+        assert_eq!(
+            storage
+                .get_entity("erin")
+                .await
+                .unwrap()
+                .unwrap()
+                .properties["name"],
+            "Erin Remote"
+        );
+
+        assert_eq!(queue.conflicts().len(), 2);
+        assert_eq!(queue.take_conflicts().len(), 2);
+        assert!(queue.conflicts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keep_local_policy_overwrites_remote() {
+        let (locai, _dir) = create_locai().await;
+        let queue = open_queue(
+            &locai,
+            OfflineConfig {
+                conflict_policy: OfflineConflictPolicy::KeepLocal,
+                ..OfflineConfig::default()
+            },
+        )
+        .await;
+        let storage = locai.manager().storage();
+
+        storage.create_entity(entity("finn", "Finn")).await.unwrap();
+        // Reading through the queue records the copy later writes are based on
+        assert!(queue.get_entity("finn").await.unwrap().is_some());
+        queue.go_offline();
+        queue
+            .put_entity(entity("finn", "Finn Local"))
+            .await
+            .unwrap();
+        queue.put_entity(entity("gail", "Gail")).await.unwrap();
+        storage.delete_entity("finn").await.unwrap();
+
+        let report = queue.reconcile().await.unwrap();
+        assert_eq!(report.applied, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(
+            report.conflicts[0].resolution,
+            ConflictResolution::KeptLocal
+        );
+        assert!(report.conflicts[0].remote.is_none());
+        assert_eq!(
+            storage
+                .get_entity("finn")
+                .await
+                .unwrap()
+                .unwrap()
+                .properties["name"],
+            "Finn Local"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queue_survives_restart() {
+        let (locai, dir) = create_locai().await;
+        let config = OfflineConfig {
+            queue_path: Some(dir.path().join("queue.jsonl")),
+            ..OfflineConfig::default()
+        };
+
+        let memory_id = {
+            let queue = open_queue(&locai, config.clone()).await;
+            queue.go_offline();
+            queue
+                .put_memory(MemoryBuilder::fact("Left the keys with the neighbour").build())
+                .await
+                .unwrap()
+                .id
+        };
+
+        let queue = open_queue(&locai, config).await;
+        assert!(!queue.is_online());
+        let pending = queue.pending_writes();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, memory_id);
+
+        let report = queue.reconcile().await.unwrap();
+        assert_eq!(report.applied, 1);
+        assert!(
+            locai
+                .manager()
+                .get_memory(&memory_id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        // The log is emptied once the writes are replayed
+        let reopened = open_queue(
+            &locai,
+            OfflineConfig {
+                queue_path: Some(dir.path().join("queue.jsonl")),
+                ..OfflineConfig::default()
+            },
+        )
+        .await;
+        assert!(reopened.pending_writes().is_empty());
+        assert!(reopened.is_online());
+    }
+}
+
+mod outbox {
+    //! Transactional outbox tests
+
+    use locai::models::{Memory, MemoryBuilder, MemoryType};
+    use locai::storage::OutboxMessage;
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    fn note(content: &str) -> Memory {
+        MemoryBuilder::new_with_content(content).build()
+    }
+
+    #[tokio::test]
+    async fn test_writes_record_outbox_messages() {
+        let (manager, _dir) = create_manager().await;
+
+        let memory = note("The bridge is out");
+        let id = memory.id.clone();
+        let stored_id = manager
+            .store_memory_with_outbox(
+                memory,
+                vec![OutboxMessage::new(
+                    "memory.created",
+                    json!({ "id": id, "parent": null }),
+                )],
+            )
+            .await
+            .unwrap();
+        assert_eq!(stored_id, id);
+        assert!(manager.get_memory(&id).await.unwrap().is_some());
+
+        let mut updated = manager.get_memory(&id).await.unwrap().unwrap();
+        updated.content = "The bridge is repaired".to_string();
+        manager
+            .update_memory_with_outbox(
+                updated,
+                vec![OutboxMessage::new("memory.updated", json!({ "id": id }))],
+            )
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .delete_memory_with_outbox(
+                    &id,
+                    vec![OutboxMessage::new("memory.deleted", json!({ "id": id }))],
+                )
+                .await
+                .unwrap()
+        );
+
+        let pending = manager.pending_outbox(10).await.unwrap();
+        let topics: Vec<&str> = pending.iter().map(|m| m.topic.as_str()).collect();
+        assert_eq!(
+            topics,
+            vec!["memory.created", "memory.updated", "memory.deleted"]
+        );
+        assert!(pending.iter().all(|m| m.payload["id"] == id.as_str()));
+        // Payloads round-trip exactly, null fields included
+        assert_eq!(pending[0].payload, json!({ "id": id, "parent": null }));
+    }
+
+    #[tokio::test]
+    async fn test_failed_writes_record_nothing() {
+        let (manager, _dir) = create_manager().await;
+
+        let deleted = manager
+            .delete_memory_with_outbox(
+                "missing",
+                vec![OutboxMessage::new("memory.deleted", json!({}))],
+            )
+            .await
+            .unwrap();
+        assert!(!deleted);
+
+        let missing = Memory::new(
+            "missing".to_string(),
+            "Nowhere".to_string(),
+            MemoryType::Episodic,
+        );
+        assert!(
+            manager
+                .update_memory_with_outbox(
+                    missing,
+                    vec![OutboxMessage::new("memory.updated", json!({}))]
+                )
+                .await
+                .is_err()
+        );
+
+        // Creating the same ID twice rolls back the second write and its message
+        let memory = note("Only once");
+        let duplicate = memory.clone();
+        manager
+            .store_memory_with_outbox(memory, vec![OutboxMessage::new("memory.created", json!(1))])
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .store_memory_with_outbox(
+                    duplicate,
+                    vec![OutboxMessage::new("memory.created", json!(2))]
+                )
+                .await
+                .is_err()
+        );
+
+        let pending = manager.pending_outbox(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].payload, json!(1));
+    }
+
+    #[tokio::test]
+    async fn test_relay_delivers_in_order_and_retries_failures() {
+        let (manager, _dir) = create_manager().await;
+        for n in 0..3 {
+            manager
+                .store_memory_with_outbox(
+                    note(&format!("Memory {}", n)),
+                    vec![OutboxMessage::new("memory.created", json!(n))],
+                )
+                .await
+                .unwrap();
+        }
+
+        // Delivery stops at the first failure, leaving the rest pending
+        let mut seen = Vec::new();
+        let delivered = manager
+            .relay_outbox(10, |message| {
+                if message.payload == json!(1) {
+                    return Err(locai::LocaiError::Other(
+                        "subscriber unavailable".to_string(),
+                    ));
+                }
+                seen.push(message.payload.clone());
+                Ok(())
+            })
+            .await;
+        assert!(delivered.is_err());
+        assert_eq!(seen, vec![json!(0)]);
+        assert_eq!(manager.pending_outbox(10).await.unwrap().len(), 2);
+
+        let mut seen = Vec::new();
+        let delivered = manager
+            .relay_outbox(10, |message| {
+                seen.push(message.payload.clone());
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(delivered, 2);
+        assert_eq!(seen, vec![json!(1), json!(2)]);
+        assert!(manager.pending_outbox(10).await.unwrap().is_empty());
+    }
+}
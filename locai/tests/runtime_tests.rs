@@ -0,0 +1,270 @@
+//! Runtime tests: clocks, tuning, workload benchmarks and synthetic data
+
+mod common;
+
+mod clock {
+    //! Clock injection tests
+    //!
+    //! Versioning, decay scoring, retention and archiving read the time from the
+    //! configured clock, so moving the clock moves them.
+
+    use std::sync::{Arc, Mutex};
+
+    use crate::common::create_test_locai_with;
+    use chrono::{DateTime, Duration, Utc};
+    use locai::clock::{Clock, ClockHandle};
+    use locai::prelude::*;
+    use tempfile::TempDir;
+
+    /// A clock that only moves when told to
+    #[derive(Debug, Clone)]
+    struct ManualClock(Arc<Mutex<DateTime<Utc>>>);
+
+    impl ManualClock {
+        fn advance(&self, by: Duration) {
+            *self.0.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    async fn create_test_locai(clock: &ManualClock) -> (Locai, TempDir) {
+        create_test_locai_with(|builder| builder.with_clock(ClockHandle::new(clock.clone()))).await
+    }
+
+    #[tokio::test]
+    async fn test_versions_are_dated_by_the_clock() {
+        let start = Utc::now() - Duration::days(400);
+        let clock = ManualClock(Arc::new(Mutex::new(start)));
+        let (locai, _temp_dir) = create_test_locai(&clock).await;
+        assert_eq!(locai.manager().clock().now(), start);
+
+        let id = locai.remember("First draft").await.unwrap();
+        clock.advance(Duration::days(10));
+        locai
+            .remember_version(&id, "Second draft", None)
+            .await
+            .unwrap();
+
+        let versions = locai.list_memory_versions(&id).await.unwrap();
+        let latest = versions.iter().map(|v| v.created_at).max().unwrap();
+        assert_eq!(latest.timestamp(), (start + Duration::days(10)).timestamp());
+    }
+
+    #[tokio::test]
+    async fn test_archiving_follows_the_clock() {
+        let clock = ManualClock(Arc::new(Mutex::new(Utc::now())));
+        let (locai, _temp_dir) = create_test_locai(&clock).await;
+        locai
+            .remember("Inventory count for the north warehouse")
+            .await
+            .unwrap();
+
+        let archived = locai
+            .manager()
+            .archive_inactive_memories(30, None)
+            .await
+            .unwrap();
+        assert!(archived.is_empty());
+
+        clock.advance(Duration::days(31));
+        let archived = locai
+            .manager()
+            .archive_inactive_memories(30, None)
+            .await
+            .unwrap();
+        assert_eq!(archived.len(), 1);
+    }
+}
+
+mod runtime {
+    //! Runtime tuning tests
+    //!
+    //! Storage can run on a dedicated runtime, and Locai can be initialized on a
+    //! runtime other than the caller's.
+
+    use locai::config::{ConfigBuilder, RuntimeConfig};
+    use locai::memory::SearchMode;
+    use tempfile::TempDir;
+
+    fn config(temp_dir: &TempDir, runtime: RuntimeConfig) -> locai::config::LocaiConfig {
+        ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .with_runtime(runtime)
+            .build()
+            .expect("Failed to build config")
+    }
+
+    #[tokio::test]
+    async fn test_storage_runs_on_dedicated_runtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = locai::init(config(
+            &temp_dir,
+            RuntimeConfig {
+                storage_io_threads: Some(2),
+                ..RuntimeConfig::default()
+            },
+        ))
+        .await
+        .unwrap();
+        assert!(manager.storage_runtime().is_some());
+
+        let id = manager
+            .add_fact("Storage has its own threads")
+            .await
+            .unwrap();
+        let results = manager
+            .search("threads", Some(5), None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(results[0].memory.id, id);
+
+        // Shutting the storage runtime down must not block this task
+        drop(manager);
+    }
+
+    #[tokio::test]
+    async fn test_storage_shares_caller_runtime_by_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = locai::init(config(&temp_dir, RuntimeConfig::default()))
+            .await
+            .unwrap();
+        assert!(manager.storage_runtime().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_init_on_existing_runtime() {
+        let server_runtime = RuntimeConfig {
+            worker_threads: Some(1),
+            thread_name: "server".to_string(),
+            ..RuntimeConfig::default()
+        }
+        .build()
+        .unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let manager = locai::init_on_runtime(
+            config(&temp_dir, RuntimeConfig::default()),
+            server_runtime.handle(),
+        )
+        .await
+        .unwrap();
+
+        let id = manager.add_fact("Initialized elsewhere").await.unwrap();
+        assert!(manager.get_memory(&id).await.unwrap().is_some());
+
+        drop(manager);
+        server_runtime.shutdown_background();
+    }
+}
+
+mod bench {
+    //! Workload benchmark report tests
+
+    use locai::bench::{BenchConfig, run};
+
+    #[tokio::test]
+    async fn test_run_reports_every_corpus_size() {
+        let config = BenchConfig {
+            corpus_sizes: vec![10, 20],
+            queries: 2,
+            ..BenchConfig::default()
+        };
+        let report = run(&config).await.unwrap();
+
+        assert_eq!(report.locai_version, locai::VERSION);
+        assert_eq!(report.corpora.len(), 2);
+        for (corpus, size) in report.corpora.iter().zip([10, 20]) {
+            assert_eq!(corpus.corpus_size, size);
+            assert_eq!(corpus.store.operations, size);
+            assert!(corpus.link.operations >= size - 1);
+            assert_eq!(corpus.bm25_search.samples, 2);
+            assert_eq!(corpus.vector_search.samples, 2);
+            assert_eq!(corpus.graph_traversal.samples, 2);
+            assert_eq!(corpus.shortest_path.samples, 2);
+        }
+
+        // Reports round-trip, so saved runs can be compared later
+        let json = serde_json::to_string(&report).unwrap();
+        let parsed: locai::bench::BenchReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.corpora[1].corpus_size, 20);
+        let (before, after) = (
+            &report.corpora[1].bm25_search,
+            &parsed.corpora[1].bm25_search,
+        );
+        assert_eq!(after.samples, before.samples);
+        assert!((after.p95_ms - before.p95_ms).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_empty_corpus_is_rejected() {
+        let config = BenchConfig {
+            corpus_sizes: vec![0],
+            ..BenchConfig::default()
+        };
+        assert!(run(&config).await.is_err());
+    }
+}
+
+mod synthetic {
+    //! Synthetic data tests
+
+    use locai::prelude::*;
+    use locai::storage::filters::MemoryFilter;
+    use locai::synthetic::{SYNTHETIC_SOURCE, SyntheticConfig, load, remove};
+
+    use crate::common::create_test_locai;
+
+    fn synthetic_memories() -> MemoryFilter {
+        MemoryFilter {
+            source: Some(SYNTHETIC_SOURCE.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_and_remove() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+        let config = SyntheticConfig {
+            memories: 30,
+            entities: 8,
+            graph_density: 1.0,
+            seed: 3,
+            embeddings: true,
+        };
+
+        let mut last = None;
+        let report = load(manager, &config, |progress| last = Some(progress))
+            .await
+            .unwrap();
+        assert_eq!(report.memories, 30);
+        assert_eq!(report.entities, 8);
+        assert_eq!(report.failed, 0);
+        assert!(report.relationships > 0);
+        let last = last.unwrap();
+        assert_eq!(last.done, last.total);
+
+        let stored = manager
+            .filter_memories(synthetic_memories(), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 30);
+        assert!(stored.iter().all(|memory| memory.embedding.is_some()));
+
+        let (memories, entities) = remove(manager).await.unwrap();
+        assert_eq!((memories, entities), (30, 8));
+        assert!(
+            manager
+                .filter_memories(synthetic_memories(), None, None, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
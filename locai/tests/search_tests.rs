@@ -0,0 +1,993 @@
+//! Search tests: filters, indexes, scoring, caching and collections
+
+mod common;
+
+mod filter_expression {
+    //! Filter expression tests
+    //!
+    //! Expressions such as `amount > 100 and due_date within next 7d` become
+    //! typed property conditions that list and search results must satisfy.
+
+    use chrono::{Duration, Utc};
+
+    use locai::core::MemoryManager;
+    use locai::memory::SearchMode;
+    use locai::models::MemoryBuilder;
+    use locai::storage::filter_expr::parse_filter_expression;
+    use locai::storage::filters::{MemoryFilter, SemanticSearchFilter};
+    use serde_json::json;
+
+    use crate::common::create_manager;
+
+    async fn store_invoice(
+        manager: &MemoryManager,
+        content: &str,
+        amount: f64,
+        due_in_days: i64,
+    ) -> String {
+        let due_date = (Utc::now() + Duration::days(due_in_days)).to_rfc3339();
+        manager
+            .store_memory(
+                MemoryBuilder::fact(content)
+                    .property("amount", json!(amount))
+                    .property("due_date", json!(due_date))
+                    .build(),
+            )
+            .await
+            .unwrap()
+    }
+
+    fn expression_filter(expression: &str) -> MemoryFilter {
+        MemoryFilter {
+            conditions: Some(parse_filter_expression(expression, Utc::now()).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    async fn filtered_ids(manager: &MemoryManager, expression: &str) -> Vec<String> {
+        let mut ids: Vec<String> = manager
+            .filter_memories(expression_filter(expression), None, None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|memory| memory.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_number_and_date() {
+        let (manager, _dir) = create_manager().await;
+        let large_soon = store_invoice(&manager, "Invoice for the new servers", 450.0, 3).await;
+        let large_later = store_invoice(&manager, "Invoice for the office lease", 1200.0, 30).await;
+        let small_soon = store_invoice(&manager, "Invoice for coffee beans", 40.0, 2).await;
+        manager
+            .store_memory(MemoryBuilder::fact("A note without an amount").build())
+            .await
+            .unwrap();
+
+        let mut expected = vec![large_soon.clone(), large_later];
+        expected.sort();
+        assert_eq!(filtered_ids(&manager, "amount > 100").await, expected);
+
+        let mut expected = vec![large_soon.clone(), small_soon];
+        expected.sort();
+        assert_eq!(
+            filtered_ids(&manager, "due_date within next 7d").await,
+            expected
+        );
+
+        assert_eq!(
+            filtered_ids(&manager, "amount > 100 and due_date within next 7d").await,
+            vec![large_soon]
+        );
+        assert!(
+            filtered_ids(&manager, "amount >= 100, amount < 100")
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filter_expression_combines_with_search() {
+        let (manager, _dir) = create_manager().await;
+        let large = store_invoice(&manager, "Invoice for the new servers", 450.0, 3).await;
+        store_invoice(&manager, "Invoice for coffee beans", 40.0, 2).await;
+
+        let filter = SemanticSearchFilter {
+            memory_filter: Some(expression_filter("amount > 100 and due_date <= now+7d")),
+            ..Default::default()
+        };
+        let results = manager
+            .search("invoice", Some(10), Some(filter), SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.id, large);
+    }
+}
+
+mod geo {
+    //! Location search tests
+    //!
+    //! Memories and entities with a `location` property can be found by their
+    //! distance from a point.
+
+    use chrono::Utc;
+
+    use locai::core::MemoryManager;
+    use locai::memory::SearchMode;
+    use locai::models::{GeoPoint, GeoRadius, MemoryBuilder};
+    use locai::storage::filters::{MemoryFilter, SemanticSearchFilter};
+    use locai::storage::models::Entity;
+
+    use crate::common::create_manager;
+
+    /// Westminster, London
+    const CENTER: GeoPoint = GeoPoint {
+        latitude: 51.5007,
+        longitude: -0.1246,
+    };
+
+    async fn store_at(manager: &MemoryManager, content: &str, location: GeoPoint) -> String {
+        manager
+            .store_memory(MemoryBuilder::episodic(content).location(location).build())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_search_near_orders_by_distance() {
+        let (manager, _dir) = create_manager().await;
+        // About 900 m, 250 m and 340 km from the center
+        let trafalgar = store_at(
+            &manager,
+            "Coffee by the fountains",
+            GeoPoint::new(51.5080, -0.1281),
+        )
+        .await;
+        let abbey = store_at(
+            &manager,
+            "Choir practice at the abbey",
+            GeoPoint::new(51.4994, -0.1273),
+        )
+        .await;
+        store_at(
+            &manager,
+            "Croissants near the Louvre",
+            GeoPoint::new(48.8606, 2.3376),
+        )
+        .await;
+        manager
+            .store_memory(MemoryBuilder::episodic("Somewhere unknown").build())
+            .await
+            .unwrap();
+
+        let nearby = manager.search_near(CENTER, 1000.0, None).await.unwrap();
+        let ids: Vec<&str> = nearby.iter().map(|n| n.memory.id.as_str()).collect();
+        assert_eq!(ids, vec![abbey.as_str(), trafalgar.as_str()]);
+        assert!(nearby[0].distance_meters < 300.0);
+        assert!(nearby[1].distance_meters > 800.0 && nearby[1].distance_meters < 1000.0);
+
+        let nearest = manager.search_near(CENTER, 1000.0, Some(1)).await.unwrap();
+        assert_eq!(nearest.len(), 1);
+        assert_eq!(nearest[0].memory.id, abbey);
+
+        let everything = manager.search_near(CENTER, 500_000.0, None).await.unwrap();
+        assert_eq!(everything.len(), 3);
+
+        assert!(
+            manager
+                .search_near(GeoPoint::new(95.0, 0.0), 1000.0, None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_near_filter_combines_with_search() {
+        let (manager, _dir) = create_manager().await;
+        let nearby = store_at(
+            &manager,
+            "Coffee by the fountains",
+            GeoPoint::new(51.5080, -0.1281),
+        )
+        .await;
+        store_at(
+            &manager,
+            "Coffee near the Louvre",
+            GeoPoint::new(48.8606, 2.3376),
+        )
+        .await;
+
+        let filter = SemanticSearchFilter {
+            memory_filter: Some(MemoryFilter {
+                near: Some(GeoRadius::new(CENTER, 2000.0)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let results = manager
+            .search("coffee", Some(10), Some(filter), SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.id, nearby);
+    }
+
+    #[tokio::test]
+    async fn test_entities_near() {
+        let (manager, _dir) = create_manager().await;
+        let mut cafe = Entity {
+            id: "cafe".to_string(),
+            entity_type: "place".to_string(),
+            properties: serde_json::json!({ "name": "Fountain Cafe" }),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        cafe.set_location(GeoPoint::new(51.5080, -0.1281));
+        let mut louvre = cafe.clone();
+        louvre.id = "louvre".to_string();
+        louvre.set_location(GeoPoint::new(48.8606, 2.3376));
+        manager.create_entity(cafe).await.unwrap();
+        manager.create_entity(louvre).await.unwrap();
+
+        let nearby = manager.entities_near(CENTER, 2000.0, None).await.unwrap();
+        assert_eq!(nearby.len(), 1);
+        assert_eq!(nearby[0].entity.id, "cafe");
+        assert_eq!(
+            nearby[0].entity.location(),
+            Some(GeoPoint::new(51.5080, -0.1281))
+        );
+    }
+}
+
+mod property_index {
+    //! Property index tests
+    //!
+    //! Properties listed in `storage.indexed_properties` get SurrealDB indexes,
+    //! so filters on them are planned as index lookups instead of table scans.
+
+    use std::collections::HashMap;
+
+    use crate::common::create_manager_with;
+    use locai::core::MemoryManager;
+    use locai::models::MemoryBuilder;
+    use locai::storage::filters::MemoryFilter;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_manager() -> (MemoryManager, TempDir) {
+        create_manager_with(|config| config.with_indexed_properties(["properties.project_id"]))
+            .await
+    }
+
+    fn property_filter(key: &str, value: serde_json::Value) -> MemoryFilter {
+        MemoryFilter {
+            properties: Some(HashMap::from([(key.to_string(), value)])),
+            ..Default::default()
+        }
+    }
+
+    async fn store(
+        manager: &MemoryManager,
+        content: &str,
+        project_id: &str,
+        owner: &str,
+    ) -> String {
+        manager
+            .store_memory(
+                MemoryBuilder::fact(content)
+                    .property("project_id", json!(project_id))
+                    .property("owner", json!(owner))
+                    .build(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_property() {
+        let (manager, _dir) = create_manager().await;
+        let alpha = store(&manager, "Alpha ships on Friday", "alpha", "ops").await;
+        store(&manager, "Beta ships on Monday", "beta", "ops").await;
+
+        let memories = manager
+            .filter_memories(
+                property_filter("project_id", json!("alpha")),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0].id, alpha);
+
+        let memories = manager
+            .filter_memories(property_filter("owner", json!("ops")), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(memories.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_explain_reports_property_index() {
+        let (manager, _dir) = create_manager().await;
+        store(&manager, "Alpha ships on Friday", "alpha", "ops").await;
+
+        let explanation = manager
+            .explain_memory_filter(&property_filter("project_id", json!("alpha")))
+            .await
+            .unwrap();
+        assert_eq!(
+            explanation.indexes_used,
+            vec!["memory_property_project_id_idx".to_string()]
+        );
+        assert!(!explanation.full_scan);
+        assert!(explanation.unindexed_properties.is_empty());
+
+        let explanation = manager
+            .explain_memory_filter(&property_filter("owner", json!("ops")))
+            .await
+            .unwrap();
+        assert!(explanation.indexes_used.is_empty());
+        assert!(explanation.full_scan);
+        assert_eq!(explanation.unindexed_properties, vec!["owner".to_string()]);
+    }
+}
+
+mod query_expansion {
+    //! Graph-neighbor query expansion tests
+    //!
+    //! A query naming a role should also find memories that only name the person
+    //! holding it, when the graph links the two.
+
+    use chrono::Utc;
+    use locai::core::{SearchOptions, SearchStrategy, SearchTypeFilter};
+    use locai::memory::QueryExpansionConfig;
+    use locai::prelude::*;
+    use locai::storage::models::{Entity, Relationship};
+    use serde_json::json;
+
+    use crate::common::create_test_locai;
+
+    async fn link_lead_to_person(locai: &Locai, confidence: f64) {
+        let manager = locai.manager();
+        let role = manager
+            .create_entity(Entity {
+                id: "role_project_lead".to_string(),
+                entity_type: "role".to_string(),
+                properties: json!({"name": "project lead"}),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        let person = manager
+            .create_entity(Entity {
+                id: "person_marisol".to_string(),
+                entity_type: "person".to_string(),
+                properties: json!({"name": "Marisol"}),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        manager
+            .create_relationship_entity(Relationship {
+                id: "marisol_holds_lead".to_string(),
+                relationship_type: "holds_role".to_string(),
+                source_id: person.id.clone(),
+                target_id: role.id.clone(),
+                properties: json!({"confidence": confidence}),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    fn keyword_options(query_expansion: Option<QueryExpansionConfig>) -> SearchOptions {
+        SearchOptions {
+            limit: 10,
+            strategy: SearchStrategy::Keyword,
+            include_types: SearchTypeFilter::memories_only(),
+            query_expansion,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expansion_finds_memories_naming_related_entity() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        link_lead_to_person(&locai, 0.9).await;
+
+        let marisol_id = locai
+            .remember("Marisol said the launch moves to March")
+            .await
+            .unwrap();
+
+        let expanded = locai
+            .manager()
+            .expand_query(
+                "what did the project lead say",
+                &QueryExpansionConfig::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(expanded.added_terms, vec!["Marisol".to_string()]);
+
+        let plain = locai
+            .search_with_options("project lead", keyword_options(None))
+            .await
+            .unwrap();
+        assert!(plain.iter().all(|r| r.id != marisol_id));
+
+        let expanded_results = locai
+            .search_with_options(
+                "project lead",
+                keyword_options(Some(QueryExpansionConfig::default())),
+            )
+            .await
+            .unwrap();
+        assert!(expanded_results.iter().any(|r| r.id == marisol_id));
+    }
+
+    #[tokio::test]
+    async fn test_weak_relationships_are_not_followed() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        link_lead_to_person(&locai, 0.2).await;
+
+        let expanded = locai
+            .manager()
+            .expand_query(
+                "what did the project lead say",
+                &QueryExpansionConfig::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(expanded.seed_entities.len(), 1);
+        assert!(expanded.added_terms.is_empty());
+    }
+}
+
+mod scoring_profile {
+    //! Scoring profile tests
+    //!
+    //! Named scoring profiles come from configuration or are set at runtime, and
+    //! a search selects one with `SearchOptions::scoring_profile`.
+
+    use crate::common::create_test_locai_with;
+    use locai::core::{SearchOptions, SearchTypeFilter};
+    use locai::prelude::*;
+    use locai::search::{DecayFunction, ScoringConfig};
+    use tempfile::TempDir;
+
+    /// Ranks by priority far more than by keyword relevance
+    fn priority_heavy() -> ScoringConfig {
+        ScoringConfig {
+            bm25_weight: 0.1,
+            vector_weight: 0.0,
+            recency_boost: 0.0,
+            access_boost: 0.0,
+            priority_boost: 10.0,
+            decay_function: DecayFunction::None,
+            decay_rate: 0.1,
+        }
+    }
+
+    async fn create_test_locai() -> (Locai, TempDir) {
+        create_test_locai_with(|builder| {
+            builder.with_scoring_profile("priority-heavy", priority_heavy())
+        })
+        .await
+    }
+
+    fn profile_options(profile: &str) -> SearchOptions {
+        SearchOptions {
+            limit: 10,
+            include_types: SearchTypeFilter::memories_only(),
+            scoring_profile: Some(profile.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_with_configured_profile() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        locai
+            .remember_with("Quarterly report draft: report report report")
+            .with_priority(MemoryPriority::Low)
+            .with_tag("reports")
+            .save()
+            .await
+            .unwrap();
+        let critical = locai
+            .remember_with("Incident report for the outage")
+            .with_priority(MemoryPriority::Critical)
+            .with_tag("reports")
+            .save()
+            .await
+            .unwrap();
+
+        let results = locai
+            .search_with_options("report", profile_options("priority-heavy"))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, critical);
+
+        let results = locai
+            .search_with_options("report", profile_options("graph-heavy"))
+            .await;
+        assert!(matches!(results, Err(LocaiError::Scoring(_))));
+    }
+
+    #[tokio::test]
+    async fn test_profiles_change_at_runtime() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let profiles = locai.manager().scoring_profiles();
+
+        let names: Vec<String> = profiles.list().into_keys().collect();
+        assert!(names.contains(&"priority-heavy".to_string()));
+        assert!(names.contains(&"recency".to_string()));
+
+        let precision = ScoringConfig {
+            vector_weight: 0.0,
+            recency_boost: 0.0,
+            ..Default::default()
+        };
+        profiles.set("precision", precision.clone()).unwrap();
+        assert_eq!(profiles.get("precision"), Some(precision));
+
+        locai.remember("Release checklist").await.unwrap();
+        let results = locai
+            .search_with_options("checklist", profile_options("precision"))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        assert!(profiles.remove("precision"));
+        assert_eq!(profiles.get("precision"), None);
+    }
+}
+
+mod search_cache {
+    //! Search cache tests
+    //!
+    //! Repeated searches are answered from the cache until a memory change that
+    //! could affect them arrives through live queries.
+
+    use std::time::Duration;
+
+    use crate::common::create_manager_with;
+    use locai::config::SearchCacheConfig;
+    use locai::core::MemoryManager;
+    use locai::memory::{SearchCacheStats, SearchMode};
+    use locai::models::MemoryType;
+    use locai::storage::filters::{MemoryFilter, SemanticSearchFilter};
+    use tempfile::TempDir;
+
+    async fn create_manager(enabled: bool) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| {
+            config.with_search_cache(SearchCacheConfig {
+                enabled,
+                ..Default::default()
+            })
+        })
+        .await
+    }
+
+    fn stats(manager: &MemoryManager) -> SearchCacheStats {
+        manager.search_cache_stats().expect("search cache enabled")
+    }
+
+    /// Wait for live queries to deliver changes until `entries` searches are
+    /// left cached
+    async fn wait_for_entries(manager: &MemoryManager, entries: usize) {
+        for _ in 0..100 {
+            if stats(manager).entries == entries {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("Cached searches were not invalidated");
+    }
+
+    /// Let the change events from setting up pass before caching anything
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    fn of_type(memory_type: MemoryType) -> Option<SemanticSearchFilter> {
+        Some(SemanticSearchFilter {
+            memory_filter: Some(MemoryFilter {
+                memory_type: Some(memory_type.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_repeated_search_hits_cache() {
+        let (manager, _dir) = create_manager(true).await;
+        manager.add_fact("Deploys run every Tuesday").await.unwrap();
+        settle().await;
+
+        let first = manager
+            .search("deploys", Some(5), None, SearchMode::Text)
+            .await
+            .unwrap();
+        let second = manager
+            .search("deploys", Some(5), None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].memory.id, first[0].memory.id);
+
+        // A different limit is a different search
+        manager
+            .search("deploys", Some(10), None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(
+            stats(&manager),
+            SearchCacheStats {
+                hits: 1,
+                misses: 2,
+                invalidations: 0,
+                entries: 2,
+            }
+        );
+
+        manager.clear_caches().await;
+        assert_eq!(stats(&manager).entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_changes_invalidate_cached_searches() {
+        let (manager, _dir) = create_manager(true).await;
+        manager.add_fact("Deploys run every Tuesday").await.unwrap();
+        manager.add_identity("Deploys are my job").await.unwrap();
+        settle().await;
+
+        manager
+            .search("deploys", None, None, SearchMode::Text)
+            .await
+            .unwrap();
+        let identities = manager
+            .search(
+                "deploys",
+                None,
+                of_type(MemoryType::Identity),
+                SearchMode::Text,
+            )
+            .await
+            .unwrap();
+        assert_eq!(identities.len(), 1);
+
+        // A new fact can change the unfiltered search, not the identity one
+        manager
+            .add_fact("Deploys freeze in December")
+            .await
+            .unwrap();
+        wait_for_entries(&manager, 1).await;
+
+        let results = manager
+            .search("deploys", None, None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        let hits = stats(&manager).hits;
+        manager
+            .search(
+                "deploys",
+                None,
+                of_type(MemoryType::Identity),
+                SearchMode::Text,
+            )
+            .await
+            .unwrap();
+        assert_eq!(stats(&manager).hits, hits + 1);
+
+        // Deleting a memory drops every search it appeared in
+        manager
+            .delete_memory(&identities[0].memory.id)
+            .await
+            .unwrap();
+        wait_for_entries(&manager, 0).await;
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_off_by_default() {
+        let (manager, _dir) = create_manager(false).await;
+        manager
+            .search("deploys", None, None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert!(manager.search_cache_stats().is_none());
+    }
+}
+
+mod search_hits {
+    //! Lightweight search hit tests
+    //!
+    //! Hits carry only IDs, scores and snippets; full memories are loaded on
+    //! demand with `hydrate`.
+
+    use locai::memory::SearchMode;
+    use locai::prelude::*;
+
+    use crate::common::create_test_locai;
+
+    /// Unit vector pointing mostly along `axis`
+    fn embedding(axis: usize) -> Vec<f32> {
+        let mut embedding = vec![0.01; 1024];
+        embedding[axis] = 1.0;
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        embedding.iter().map(|x| x / norm).collect()
+    }
+
+    #[tokio::test]
+    async fn test_text_hits_match_full_search() -> Result<()> {
+        let (locai, _dir) = create_test_locai().await;
+        let manager = locai.manager();
+        manager
+            .add_memory_with_options("The deploy pipeline runs nightly", |b| b)
+            .await?;
+        manager
+            .add_memory_with_options("Deploy rollbacks need approval from the deploy lead", |b| b)
+            .await?;
+        manager
+            .add_memory_with_options("Lunch is at noon", |b| b)
+            .await?;
+
+        let hits = manager
+            .search_hits("deploy", None, Some(10), SearchMode::Text)
+            .await?;
+        let results = manager
+            .search("deploy", Some(10), None, SearchMode::Text)
+            .await?;
+
+        assert_eq!(hits.len(), 2);
+        let hit_ids: Vec<&str> = hits.iter().map(|h| h.id.as_str()).collect();
+        let result_ids: Vec<&str> = results.iter().map(|r| r.memory.id.as_str()).collect();
+        assert_eq!(hit_ids, result_ids);
+        assert!(hits.iter().all(|h| h.snippet.contains("<mark>")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snippet_is_trimmed_around_match() -> Result<()> {
+        let (locai, _dir) = create_test_locai().await;
+        let manager = locai.manager();
+        let content = format!(
+            "{} the tardigrade survived {}",
+            "filler ".repeat(60),
+            "padding ".repeat(60)
+        );
+        manager.add_memory_with_options(content, |b| b).await?;
+
+        let hits = manager
+            .search_hits("tardigrade", None, None, SearchMode::Text)
+            .await?;
+        assert_eq!(hits.len(), 1);
+        let snippet = &hits[0].snippet;
+        assert!(snippet.contains("<mark>tardigrade</mark>"));
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.chars().count() < 200);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hydrate_keeps_order_and_skips_deleted() -> Result<()> {
+        let (locai, _dir) = create_test_locai().await;
+        let manager = locai.manager();
+        let first = manager
+            .add_memory_with_options("Quarterly report draft is ready", |b| b)
+            .await?;
+        let second = manager
+            .add_memory_with_options("Quarterly report review moved to Friday", |b| b)
+            .await?;
+
+        let hits = manager
+            .search_hits("quarterly report", None, None, SearchMode::Text)
+            .await?;
+        assert_eq!(hits.len(), 2);
+
+        let memory = hits[0].hydrate(manager).await?.expect("memory exists");
+        assert_eq!(memory.id, hits[0].id);
+
+        let deleted = if hits[0].id == first { &second } else { &first };
+        manager.delete_memory(deleted).await?;
+        let results = manager.hydrate(&hits).await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.id, hits[0].id);
+        assert_eq!(results[0].score, Some(hits[0].score));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vector_and_hybrid_hits() -> Result<()> {
+        let (locai, _dir) = create_test_locai().await;
+        let manager = locai.manager();
+        let near = manager
+            .add_memory_with_options("Notes on the cache layer", |b| b.embedding(embedding(3)))
+            .await?;
+        manager
+            .add_memory_with_options("Notes on the billing service", |b| {
+                b.embedding(embedding(700))
+            })
+            .await?;
+
+        let query = embedding(3);
+        let hits = manager
+            .search_hits("cache", Some(&query), Some(1), SearchMode::Vector)
+            .await?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, near);
+        assert!(hits[0].snippet.starts_with("Notes on the cache"));
+
+        let hybrid = manager
+            .search_hits("cache", Some(&query), Some(2), SearchMode::Hybrid)
+            .await?;
+        assert_eq!(hybrid[0].id, near);
+        assert_eq!(hybrid[0].score, 1.0);
+        // The BM25 side supplies the highlighted snippet
+        assert!(hybrid[0].snippet.contains("<mark>"));
+
+        assert!(
+            manager
+                .search_hits("cache", None, None, SearchMode::Vector)
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+}
+
+mod collection {
+    //! Collection tests
+    //!
+    //! Collections group memories by hand-picked IDs, by a query, or both, and
+    //! scope searches to their members.
+
+    use locai::LocaiError;
+
+    use locai::core::MemoryManager;
+    use locai::memory::{Collection, CollectionQuery, SearchMode};
+
+    use crate::common::create_manager;
+
+    async fn add_tagged(manager: &MemoryManager, content: &str, tag: &str) -> String {
+        manager
+            .add_memory_with_options(content.to_string(), |b| b.tag(tag))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_collection_crud() {
+        let (manager, _dir) = create_manager().await;
+
+        let created = manager
+            .create_collection(Collection::new("reading").with_description("Books to read"))
+            .await
+            .unwrap();
+
+        let by_name = manager.get_collection("reading").await.unwrap().unwrap();
+        assert_eq!(by_name.id, created.id);
+        let by_id = manager.get_collection(&created.id).await.unwrap().unwrap();
+        assert_eq!(by_id.description.as_deref(), Some("Books to read"));
+
+        let error = manager
+            .create_collection(Collection::new("reading"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Collection(_)), "{}", error);
+
+        let mut renamed = by_id;
+        renamed.name = "library".to_string();
+        renamed.description = None;
+        manager.update_collection(renamed).await.unwrap();
+        let stored = manager.get_collection(&created.id).await.unwrap().unwrap();
+        assert_eq!(stored.name, "library");
+        assert_eq!(stored.description, None);
+        assert_eq!(manager.list_collections().await.unwrap().len(), 1);
+
+        assert!(manager.delete_collection("library").await.unwrap());
+        assert!(manager.get_collection(&created.id).await.unwrap().is_none());
+        assert!(!manager.delete_collection("library").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_members_by_id_and_query() {
+        let (manager, _dir) = create_manager().await;
+        let picked = add_tagged(&manager, "Dune is a novel about spice", "misc").await;
+        let queried = add_tagged(&manager, "Neuromancer is a cyberpunk novel", "books").await;
+        add_tagged(&manager, "Buy milk", "errands").await;
+
+        let collection = manager
+            .create_collection(
+                Collection::new("novels")
+                    .with_memories([picked.clone()])
+                    .with_query(CollectionQuery {
+                        tags: Some(vec!["books".to_string()]),
+                        ..Default::default()
+                    }),
+            )
+            .await
+            .unwrap();
+
+        let members = manager
+            .collection_memories(&collection, None)
+            .await
+            .unwrap();
+        let mut ids: Vec<String> = members.into_iter().map(|m| m.id).collect();
+        ids.sort();
+        let mut expected = vec![picked.clone(), queried.clone()];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        // Removing a hand-picked memory doesn't touch query matches
+        let collection = manager
+            .remove_from_collection("novels", &[picked.clone(), queried.clone()])
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(collection.memory_ids.is_empty());
+        let ids = manager.collection_member_ids(&collection).await.unwrap();
+        assert_eq!(ids.into_iter().collect::<Vec<_>>(), vec![queried]);
+    }
+
+    #[tokio::test]
+    async fn test_adding_missing_memory_fails() {
+        let (manager, _dir) = create_manager().await;
+        manager
+            .create_collection(Collection::new("empty"))
+            .await
+            .unwrap();
+
+        let error = manager
+            .add_to_collection("empty", &["no-such-memory".to_string()])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LocaiError::Collection(_)), "{}", error);
+        assert!(
+            manager
+                .add_to_collection("missing", &[])
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_scoped_to_collection() {
+        let (manager, _dir) = create_manager().await;
+        let inside = add_tagged(&manager, "Rust ownership rules", "work").await;
+        add_tagged(&manager, "Rust on the garden fence", "home").await;
+
+        let collection = manager
+            .create_collection(Collection::new("work").with_memories([inside.clone()]))
+            .await
+            .unwrap();
+
+        let everywhere = manager
+            .search("rust", Some(10), None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(everywhere.len(), 2);
+
+        let scoped = manager
+            .search_collection(&collection, "rust", Some(10), None, SearchMode::Text)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = scoped.iter().map(|r| r.memory.id.as_str()).collect();
+        assert_eq!(ids, vec![inside.as_str()]);
+    }
+}
@@ -10,11 +10,12 @@
 //! SharedStorage instance and executes hooks asynchronously without blocking operations.
 
 use chrono::Utc;
+use locai::models::MemoryBuilder;
 use locai::storage::{
     filters::{EntityFilter, RelationshipFilter, VectorFilter},
     models::{DistanceMetric, Entity, Relationship, Vector, VectorSearchParams},
     shared_storage::{SharedStorage, SharedStorageConfig},
-    traits::{BaseStore, EntityStore, RelationshipStore, VectorStore},
+    traits::{BaseStore, EntityStore, GraphTraversal, MemoryStore, RelationshipStore, VectorStore},
 };
 use serde_json::json;
 
@@ -26,6 +27,7 @@ async fn create_test_storage() -> Result<TestStorage, Box<dyn std::error::Error>
         database: "locai_test".to_string(),
         lifecycle_tracking: Default::default(),
         versioning: Default::default(),
+        ..Default::default()
     };
 
     let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(()).await?;
@@ -686,3 +688,58 @@ async fn test_complex_relationship_queries() {
     assert_eq!(related_topics.len(), 1);
     assert_eq!(related_topics[0].id, topic_entity.id);
 }
+
+#[tokio::test]
+async fn test_entity_relationships_skip_memory_links() {
+    let storage = create_test_storage()
+        .await
+        .expect("Failed to create test storage");
+
+    for id in ["lead", "project"] {
+        storage
+            .create_entity(Entity {
+                id: id.to_string(),
+                entity_type: "Thing".to_string(),
+                properties: json!({}),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .await
+            .expect("Failed to create entity");
+    }
+    let memory = MemoryStore::create_memory(
+        &storage,
+        MemoryBuilder::new_with_content("The lead runs the project").build(),
+    )
+    .await
+    .expect("Failed to create memory");
+
+    let mut created = Vec::new();
+    for (source_id, target_id, relationship_type) in [
+        ("lead", "project", "leads"),
+        (memory.id.as_str(), "lead", "mentions"),
+    ] {
+        let relationship = storage
+            .create_relationship(Relationship {
+                id: String::new(),
+                source_id: source_id.to_string(),
+                target_id: target_id.to_string(),
+                relationship_type: relationship_type.to_string(),
+                properties: json!({}),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .await
+            .expect("Failed to create relationship");
+        created.push(relationship);
+    }
+
+    // Only the entity-to-entity relationship comes back, with its own ID
+    let relationships = storage
+        .get_entity_relationships("lead")
+        .await
+        .expect("Failed to get entity relationships");
+    assert_eq!(relationships.len(), 1);
+    assert_eq!(relationships[0].id, created[0].id);
+    assert_eq!(relationships[0].target_id, "project");
+}
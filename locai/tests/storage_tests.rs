@@ -0,0 +1,1111 @@
+//! Storage tests: archiving, integrity, sharding, backends, quotas, leases and IDs
+
+mod common;
+
+mod archive {
+    //! Archive tier tests
+    //!
+    //! Archived memories leave the active indexes, stay reachable through
+    //! `include_archived`, and come back unchanged when unarchived.
+
+    use locai::core::{SearchOptions, SearchStrategy, SearchTypeFilter};
+    use locai::prelude::*;
+
+    use crate::common::create_test_locai;
+
+    fn keyword_options(include_archived: bool) -> SearchOptions {
+        SearchOptions {
+            limit: 10,
+            strategy: SearchStrategy::Keyword,
+            include_types: SearchTypeFilter::memories_only(),
+            include_archived,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_unarchive_round_trip() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+
+        let id = locai
+            .remember("The lighthouse keeper logged a storm in 1887")
+            .await
+            .unwrap();
+        let original = manager.get_memory(&id).await.unwrap().unwrap();
+
+        assert!(locai.archive(&id).await.unwrap());
+        assert!(manager.get_memory(&id).await.unwrap().is_none());
+        assert!(!locai.archive(&id).await.unwrap());
+
+        let archived = manager.get_archived_memory(&id).await.unwrap().unwrap();
+        assert_eq!(archived.content, original.content);
+
+        let stats = manager.archive_stats().await.unwrap();
+        assert_eq!(stats.archived_count, 1);
+        assert!(stats.stored_bytes > 0);
+
+        assert!(locai.unarchive(&id).await.unwrap());
+        let restored = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(restored.id, original.id);
+        assert_eq!(restored.content, original.content);
+        assert_eq!(restored.memory_type, original.memory_type);
+        assert!(manager.get_archived_memory(&id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_archived_memories_are_searchable_on_request() {
+        let (locai, _temp_dir) = create_test_locai().await;
+
+        let archived_id = locai
+            .remember("The lighthouse keeper logged a storm in 1887")
+            .await
+            .unwrap();
+        let active_id = locai
+            .remember("A new lighthouse lamp was installed last spring")
+            .await
+            .unwrap();
+        locai.archive(&archived_id).await.unwrap();
+
+        let default_results = locai
+            .search_with_options("lighthouse", keyword_options(false))
+            .await
+            .unwrap();
+        assert!(default_results.iter().any(|r| r.id == active_id));
+        assert!(default_results.iter().all(|r| r.id != archived_id));
+
+        let with_archived = locai
+            .search_with_options("lighthouse", keyword_options(true))
+            .await
+            .unwrap();
+        assert!(with_archived.iter().any(|r| r.id == active_id));
+        assert!(with_archived.iter().any(|r| r.id == archived_id));
+    }
+
+    #[tokio::test]
+    async fn test_archive_inactive_uses_cutoff() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+
+        locai.remember("first note").await.unwrap();
+        locai.remember("second note").await.unwrap();
+
+        // Nothing is a day old yet
+        assert!(locai.archive_inactive(1).await.unwrap().is_empty());
+
+        // A zero-day window archives everything created before now
+        let archived = locai.archive_inactive(0).await.unwrap();
+        assert_eq!(archived.len(), 2);
+        assert_eq!(manager.count_memories(None).await.unwrap(), 0);
+
+        let listed = manager.list_archived_memories(None, None).await.unwrap();
+        assert_eq!(listed.len(), 2);
+    }
+}
+
+mod consistency {
+    //! Index consistency check and repair tests
+
+    use chrono::Utc;
+    use locai::core::{check_consistency, repair_consistency};
+    use locai::prelude::*;
+    use locai::storage::models::{Relationship, Vector};
+    use serde_json::json;
+
+    use crate::common::create_test_locai;
+
+    fn vector(id: &str, source_id: &str) -> Vector {
+        Vector {
+            id: id.to_string(),
+            vector: vec![0.5; 1024],
+            dimension: 1024,
+            metadata: json!({}),
+            source_id: Some(source_id.to_string()),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_and_repair_consistency() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+        let storage = manager.storage();
+
+        let memory_id = manager
+            .store_memory(MemoryBuilder::fact("Still here").build())
+            .await
+            .expect("Failed to store memory");
+
+        storage
+            .add_vector(vector("kept_vector", &memory_id))
+            .await
+            .expect("Failed to add vector");
+        storage
+            .add_vector(vector("orphaned_vector", "deleted_memory"))
+            .await
+            .expect("Failed to add vector");
+
+        // Upserts skip endpoint validation, like a replicated write whose
+        // target was deleted in the meantime
+        let now = Utc::now();
+        storage
+            .upsert_relationship(Relationship {
+                id: "dangling_relationship".to_string(),
+                relationship_type: "mentions".to_string(),
+                source_id: memory_id.clone(),
+                target_id: "deleted_entity".to_string(),
+                properties: json!({}),
+                created_at: now,
+                updated_at: now,
+            })
+            .await
+            .expect("Failed to upsert relationship");
+
+        let report = check_consistency(manager)
+            .await
+            .expect("Failed to check consistency");
+        assert_eq!(report.orphaned_vectors, vec!["orphaned_vector".to_string()]);
+        assert_eq!(report.dangling_relationships.len(), 1);
+        assert_eq!(
+            report.dangling_relationships[0].relationship_id,
+            "dangling_relationship"
+        );
+        assert_eq!(
+            report.dangling_relationships[0].missing_endpoints,
+            vec!["deleted_entity".to_string()]
+        );
+        assert!(report.broken_delta_chains.is_empty());
+
+        let repair = repair_consistency(manager, &report)
+            .await
+            .expect("Failed to repair consistency");
+        assert_eq!(repair.vectors_deleted, 1);
+        assert_eq!(repair.relationships_deleted, 1);
+        assert_eq!(repair.failed, 0);
+
+        let report = check_consistency(manager)
+            .await
+            .expect("Failed to check consistency");
+        assert!(report.is_consistent());
+        assert!(
+            storage
+                .get_vector("kept_vector")
+                .await
+                .expect("Failed to get vector")
+                .is_some()
+        );
+    }
+}
+
+mod degraded_storage {
+    //! Degraded startup tests
+    //!
+    //! Opening a database another manager holds fails with a hint by default,
+    //! serves a read-only snapshot, or serves an in-memory overlay whose writes
+    //! are queued and later replayed into the database.
+
+    use std::path::Path;
+    use std::time::Duration;
+
+    use locai::LocaiError;
+    use locai::config::{ConfigBuilder, LocaiConfig, LockedStoragePolicy};
+    use locai::core::MemoryManager;
+    use locai::storage::degraded::{DegradedMode, pending_writes_path, replay_pending_writes};
+    use tempfile::TempDir;
+
+    fn config(dir: &Path, policy: LockedStoragePolicy) -> LocaiConfig {
+        ConfigBuilder::new()
+            .with_data_dir(dir)
+            .with_default_storage()
+            .with_locked_storage_policy(policy)
+            .build()
+            .expect("Failed to build config")
+    }
+
+    /// A manager holding the database, with one fact in it
+    async fn holder(dir: &Path) -> (MemoryManager, String) {
+        let manager = locai::init(config(dir, LockedStoragePolicy::Fail))
+            .await
+            .expect("Failed to init Locai");
+        let id = manager.add_fact("Deploys run every Tuesday").await.unwrap();
+        (manager, id)
+    }
+
+    /// Wait for the overlay to queue `count` writes
+    async fn wait_for_pending(manager: &MemoryManager, count: usize) {
+        for _ in 0..100 {
+            if manager.degraded_status().unwrap().pending_writes >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("Overlay writes were not queued");
+    }
+
+    #[tokio::test]
+    async fn test_locked_storage_fails_with_hint() {
+        let dir = TempDir::new().unwrap();
+        let (_holder, _) = holder(dir.path()).await;
+
+        match locai::init(config(dir.path(), LockedStoragePolicy::Fail)).await {
+            Err(LocaiError::StorageNotAccessible { hint, .. }) => {
+                assert!(hint.contains("on_locked"));
+            }
+            other => panic!("Expected StorageNotAccessible, got {:?}", other.err()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_serves_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let (holder, id) = holder(dir.path()).await;
+
+        let manager = locai::init(config(dir.path(), LockedStoragePolicy::ReadOnly))
+            .await
+            .expect("Read-only startup failed");
+        let status = manager.degraded_status().expect("storage is degraded");
+        assert_eq!(status.mode, DegradedMode::ReadOnly);
+        assert!(manager.get_memory(&id).await.unwrap().is_some());
+        assert!(
+            manager
+                .add_fact("Deploys freeze in December")
+                .await
+                .is_err()
+        );
+
+        // Writes made after the snapshot aren't seen
+        let later = holder.add_fact("Deploys need approval").await.unwrap();
+        assert!(manager.get_memory(&later).await.unwrap().is_none());
+        assert!(holder.degraded_status().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overlay_queues_writes_for_replay() {
+        let dir = TempDir::new().unwrap();
+        let (holder, id) = holder(dir.path()).await;
+        let locked = config(dir.path(), LockedStoragePolicy::Overlay);
+
+        let manager = locai::init(locked.clone())
+            .await
+            .expect("Overlay startup failed");
+        assert_eq!(
+            manager.degraded_status().unwrap().mode,
+            DegradedMode::Overlay
+        );
+        let added = manager
+            .add_fact("Deploys freeze in December")
+            .await
+            .unwrap();
+        manager.delete_memory(&id).await.unwrap();
+        wait_for_pending(&manager, 2).await;
+        drop(manager);
+
+        let log = pending_writes_path(&locked.storage.graph.surrealdb.connection);
+        assert!(log.exists());
+        let report = replay_pending_writes(holder.storage().clone(), &locked)
+            .await
+            .unwrap()
+            .expect("writes were queued");
+        assert!(report.conflicts.is_empty());
+        assert_eq!(report.remaining, 0);
+        assert!(!log.exists());
+
+        assert!(holder.get_memory(&added).await.unwrap().is_some());
+        assert!(holder.get_memory(&id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overlay_replay_keeps_conflicting_database_changes() {
+        let dir = TempDir::new().unwrap();
+        let (holder, id) = holder(dir.path()).await;
+        let locked = config(dir.path(), LockedStoragePolicy::Overlay);
+
+        let manager = locai::init(locked.clone()).await.unwrap();
+        manager.delete_memory(&id).await.unwrap();
+        wait_for_pending(&manager, 1).await;
+        drop(manager);
+
+        // The holder changed the same memory after the snapshot
+        let mut memory = holder.get_memory(&id).await.unwrap().unwrap();
+        memory.content = "Deploys run every Wednesday".to_string();
+        holder.update_memory(memory).await.unwrap();
+
+        let report = replay_pending_writes(holder.storage().clone(), &locked)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        let kept = holder.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(kept.content, "Deploys run every Wednesday");
+    }
+}
+
+mod integrity {
+    //! Storage checksum verification and repair tests
+
+    use chrono::Utc;
+    use locai::core::{repair_storage, verify_storage};
+    use locai::prelude::*;
+    use locai::storage::models::{ChecksumIssueType, ChecksummedRecord, Vector};
+    use locai::storage::shared_storage::SharedStorage;
+    use locai::storage::traits::MemoryVersionStore;
+    use serde_json::json;
+    use surrealdb::RecordId;
+
+    use crate::common::create_test_locai;
+
+    fn shared_storage(locai: &Locai) -> &SharedStorage<surrealdb::engine::local::Db> {
+        locai
+            .manager()
+            .storage()
+            .as_any()
+            .downcast_ref()
+            .expect("memory storage is SharedStorage")
+    }
+
+    /// Change a record's fields without going through the storage API
+    async fn tamper(locai: &Locai, table: &str, id: &str, field: &str, value: serde_json::Value) {
+        shared_storage(locai)
+            .client()
+            .query(format!("UPDATE $id SET {} = $value", field))
+            .bind(("id", RecordId::from((table, id))))
+            .bind(("value", value))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_repair_restores_memory_from_version() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+
+        let id = manager
+            .store_memory(MemoryBuilder::fact("Deploys run every Tuesday").build())
+            .await
+            .unwrap();
+        let report = verify_storage(manager).await.unwrap();
+        assert!(report.is_intact(), "Unexpected issues: {:?}", report.issues);
+        assert_eq!(report.memories_checked, 1);
+        assert_eq!(report.unchecksummed, 0);
+
+        tamper(&locai, "memory", &id, "content", json!("Deploys run never")).await;
+
+        let report = verify_storage(manager).await.unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].record, ChecksummedRecord::Memory);
+        assert_eq!(report.issues[0].issue_type, ChecksumIssueType::Mismatch);
+        assert_eq!(report.issues[0].id, id);
+
+        let repair = repair_storage(manager, &report, None, false).await.unwrap();
+        assert_eq!(repair.restored_from_versions, 1);
+        assert_eq!(repair.failed, 0);
+
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(memory.content, "Deploys run every Tuesday");
+        assert!(verify_storage(manager).await.unwrap().is_intact());
+    }
+
+    #[tokio::test]
+    async fn test_repair_falls_back_to_backup() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+        let versions = manager.memory_version_store().unwrap();
+
+        let id = manager
+            .store_memory(MemoryBuilder::fact("Deploys freeze in December").build())
+            .await
+            .unwrap();
+        let snapshot = versions.create_snapshot(None, None).await.unwrap();
+        let backup = versions.export_snapshot(&snapshot).await.unwrap();
+
+        // Lose the version history along with the content
+        tamper(
+            &locai,
+            "memory",
+            &id,
+            "content",
+            json!("Deploys never freeze"),
+        )
+        .await;
+        shared_storage(&locai)
+            .client()
+            .query("DELETE memory_version WHERE memory_id = $id")
+            .bind(("id", id.clone()))
+            .await
+            .unwrap();
+
+        let report = verify_storage(manager).await.unwrap();
+        let repair = repair_storage(manager, &report, None, false).await.unwrap();
+        assert_eq!(repair.failed, 1);
+
+        let repair = repair_storage(manager, &report, Some(&backup), false)
+            .await
+            .unwrap();
+        assert_eq!(repair.restored_from_backup, 1);
+        assert_eq!(repair.failed, 0);
+        let memory = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(memory.content, "Deploys freeze in December");
+    }
+
+    #[tokio::test]
+    async fn test_repair_rebuilds_vector_from_source_memory() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+        let embedding = vec![0.5; 1024];
+
+        let id = manager
+            .store_memory(
+                MemoryBuilder::fact("Deploys need approval")
+                    .embedding(embedding.clone())
+                    .build(),
+            )
+            .await
+            .unwrap();
+        manager
+            .storage()
+            .add_vector(Vector {
+                id: "deploy_vector".to_string(),
+                vector: embedding.clone(),
+                dimension: 1024,
+                metadata: json!({}),
+                source_id: Some(id),
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        assert!(verify_storage(manager).await.unwrap().is_intact());
+
+        tamper(
+            &locai,
+            "vector",
+            "deploy_vector",
+            "vector",
+            json!(vec![0.25; 1024]),
+        )
+        .await;
+
+        let report = verify_storage(manager).await.unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].record, ChecksummedRecord::Vector);
+
+        let repair = repair_storage(manager, &report, None, false).await.unwrap();
+        assert_eq!(repair.vectors_rebuilt, 1);
+        let vector = manager
+            .storage()
+            .get_vector("deploy_vector")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(vector.vector, embedding);
+        assert!(verify_storage(manager).await.unwrap().is_intact());
+    }
+
+    #[tokio::test]
+    async fn test_backfill_checksums_for_older_records() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+
+        let id = manager
+            .store_memory(MemoryBuilder::fact("Deploys run every Tuesday").build())
+            .await
+            .unwrap();
+        // As if written before checksums were stored
+        shared_storage(&locai)
+            .client()
+            .query("UPDATE $id SET checksum = NONE")
+            .bind(("id", RecordId::from(("memory", id.as_str()))))
+            .await
+            .unwrap();
+
+        let report = verify_storage(manager).await.unwrap();
+        assert!(report.is_intact());
+        assert_eq!(report.unchecksummed, 1);
+
+        let repair = repair_storage(manager, &report, None, true).await.unwrap();
+        assert_eq!(repair.checksums_backfilled, 1);
+        assert_eq!(verify_storage(manager).await.unwrap().unchecksummed, 0);
+    }
+}
+
+mod sharded_storage {
+    //! Sharded storage tests
+    //!
+    //! Memories spread over in-memory shards, with searches and listings merged
+    //! back together behind the usual GraphStore interface.
+
+    use crate::common::create_manager_with;
+    use locai::config::{ShardKey, ShardingConfig};
+    use locai::core::MemoryManager;
+    use locai::memory::SearchMode;
+    use locai::storage::models::{Entity, Relationship};
+    use locai::storage::sharded::ShardedStorage;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_manager(key: ShardKey) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| {
+            config.with_sharding(ShardingConfig {
+                shards: 3,
+                key,
+                ..ShardingConfig::default()
+            })
+        })
+        .await
+    }
+
+    fn sharded(manager: &MemoryManager) -> &ShardedStorage {
+        manager
+            .storage()
+            .as_any()
+            .downcast_ref::<ShardedStorage>()
+            .expect("storage is sharded")
+    }
+
+    fn relationship(source: &str, target: &str, kind: &str) -> Relationship {
+        Relationship {
+            id: String::new(),
+            relationship_type: kind.to_string(),
+            source_id: source.to_string(),
+            target_id: target.to_string(),
+            properties: json!({}),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hash_sharding_spreads_and_merges() {
+        let (manager, _dir) = create_manager(ShardKey::Hash).await;
+        let mut ids = Vec::new();
+        for n in 0..30 {
+            ids.push(
+                manager
+                    .add_fact(format!("Observation {} about the glacier", n))
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let health = sharded(&manager).shard_health().await;
+        assert_eq!(health.len(), 3);
+        assert!(health.iter().all(|shard| shard.healthy));
+        let counts: Vec<usize> = health.iter().map(|s| s.memories.unwrap()).collect();
+        assert_eq!(counts.iter().sum::<usize>(), 30);
+        assert!(counts.iter().filter(|&&count| count > 0).count() > 1);
+
+        assert_eq!(manager.count_memories(None).await.unwrap(), 30);
+        for id in &ids {
+            assert!(manager.get_memory(id).await.unwrap().is_some());
+        }
+
+        let results = manager
+            .search("glacier", Some(50), None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 30);
+
+        let storage = manager.storage();
+        let page = storage
+            .list_memories(None, Some(5), Some(10))
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 5);
+        let all = storage.list_memories(None, None, None).await.unwrap();
+        let expected: Vec<&str> = all[10..15].iter().map(|m| m.id.as_str()).collect();
+        let actual: Vec<&str> = page.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(actual, expected);
+
+        assert!(manager.delete_memory(&ids[0]).await.unwrap());
+        assert_eq!(manager.count_memories(None).await.unwrap(), 29);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_sharding_keeps_tenants_together() {
+        let (manager, _dir) = create_manager(ShardKey::Tenant).await;
+        let mut acme = Vec::new();
+        for n in 0..5 {
+            acme.push(
+                manager
+                    .add_memory_with_options(format!("Acme roadmap item {}", n), |b| {
+                        b.property("tenant", json!("acme"))
+                    })
+                    .await
+                    .unwrap(),
+            );
+        }
+        let globex = manager
+            .add_memory_with_options("Globex roadmap item", |b| {
+                b.property("tenant", json!("globex"))
+            })
+            .await
+            .unwrap();
+
+        let health = sharded(&manager).shard_health().await;
+        assert!(
+            health
+                .iter()
+                .any(|shard| shard.memories == Some(5) || shard.memories == Some(6))
+        );
+
+        // Memories of one tenant link freely
+        manager
+            .storage()
+            .create_relationship(relationship(&acme[0], &acme[1], "follows"))
+            .await
+            .unwrap();
+        let connected = manager
+            .find_connected_memories(&acme[0], None, 2)
+            .await
+            .unwrap();
+        assert!(connected.iter().any(|m| m.id == acme[1]));
+
+        let results = manager
+            .search("roadmap", Some(10), None, SearchMode::Text)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 6);
+        assert!(manager.get_memory(&globex).await.unwrap().is_some());
+        assert!(manager.delete_memory(&globex).await.unwrap());
+        assert!(manager.get_memory(&globex).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_entities_are_copied_to_linking_shards() {
+        let (manager, _dir) = create_manager(ShardKey::Hash).await;
+        let storage = manager.storage();
+        storage
+            .create_entity(Entity {
+                id: "lisbon".to_string(),
+                entity_type: "place".to_string(),
+                properties: json!({ "name": "Lisbon" }),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        // Enough memories that some live away from the entity's shard
+        for n in 0..6 {
+            let id = manager
+                .add_fact(format!("Trip {} went through the capital", n))
+                .await
+                .unwrap();
+            storage
+                .create_relationship(relationship(&id, "lisbon", "mentions"))
+                .await
+                .unwrap();
+        }
+
+        // Copies are listed once
+        let entities = storage.list_entities(None, None, None).await.unwrap();
+        assert_eq!(entities.iter().filter(|e| e.id == "lisbon").count(), 1);
+        let memories = storage
+            .get_memories_containing_entity("lisbon")
+            .await
+            .unwrap();
+        assert_eq!(memories.len(), 6);
+
+        let mut entity = storage.get_entity("lisbon").await.unwrap().unwrap();
+        entity.properties = json!({ "name": "Lisboa" });
+        storage.update_entity(entity).await.unwrap();
+        for shard in sharded(&manager).shards() {
+            if let Some(copy) = shard.get_entity("lisbon").await.unwrap() {
+                assert_eq!(copy.properties["name"], "Lisboa");
+            }
+        }
+
+        assert!(storage.delete_entity("lisbon").await.unwrap());
+        assert!(storage.get_entity("lisbon").await.unwrap().is_none());
+    }
+}
+
+mod storage_backend {
+    //! Registered storage backend tests
+    //!
+    //! Backends are registered process-wide, so each test uses its own name.
+
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use locai::config::{ConfigBuilder, LocaiConfig};
+    use locai::storage::backend::{register_backend, registered_backends, unregister_backend};
+    use locai::storage::errors::StorageError;
+    use locai::storage::traits::GraphStore;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Open the built-in in-memory store, as a third-party backend would its own
+    async fn in_memory_store(mut config: LocaiConfig) -> Result<Box<dyn GraphStore>, StorageError> {
+        config.storage.graph.backend = None;
+        locai::storage::create_storage_service(&config).await
+    }
+
+    fn config_for(temp_dir: &TempDir, backend: &str) -> LocaiConfig {
+        ConfigBuilder::new()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .with_storage_backend(backend, json!({ "label": backend }))
+            .build()
+            .expect("Failed to build config")
+    }
+
+    #[tokio::test]
+    async fn test_registered_backend_is_selected_by_config() {
+        let opened = Arc::new(AtomicUsize::new(0));
+        let counter = opened.clone();
+        register_backend("counting", move |config: LocaiConfig| {
+            let counter = counter.clone();
+            async move {
+                assert_eq!(config.storage.graph.backend_options["label"], "counting");
+                counter.fetch_add(1, Ordering::SeqCst);
+                in_memory_store(config).await
+            }
+        })
+        .unwrap();
+        assert!(registered_backends().contains(&"counting".to_string()));
+
+        let temp_dir = TempDir::new().unwrap();
+        let manager = locai::init(config_for(&temp_dir, "counting"))
+            .await
+            .expect("Failed to init Locai");
+        assert_eq!(opened.load(Ordering::SeqCst), 1);
+
+        let id = manager.add_fact("Stored through a plugin").await.unwrap();
+        assert!(manager.get_memory(&id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_and_builtin_names_are_rejected() {
+        register_backend("duplicate", in_memory_store).unwrap();
+        assert!(matches!(
+            register_backend("duplicate", in_memory_store),
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            register_backend("surrealdb", in_memory_store),
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert!(register_backend(" ", in_memory_store).is_err());
+
+        assert!(unregister_backend("duplicate"));
+        assert!(!unregister_backend("duplicate"));
+        register_backend("duplicate", in_memory_store).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_backend_fails_to_start() {
+        let temp_dir = TempDir::new().unwrap();
+        let error = locai::init(config_for(&temp_dir, "missing"))
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("No storage backend registered as 'missing'"),
+            "{}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_builtin_backend_name_uses_surrealdb() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = locai::init(config_for(&temp_dir, "surrealdb"))
+            .await
+            .expect("Failed to init Locai");
+        manager.add_fact("Stored in SurrealDB").await.unwrap();
+    }
+}
+
+mod quota {
+    //! Storage quota tests
+    //!
+    //! Limits are enforced per owner, named by the `tenant` property or by the
+    //! memory source, and usage follows stores, updates and deletes.
+
+    use crate::common::create_manager_with;
+    use locai::LocaiError;
+    use locai::config::{QuotaConfig, QuotaLimits};
+    use locai::core::MemoryManager;
+    use locai::models::MemoryBuilder;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn create_manager(quotas: QuotaConfig) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| config.with_quotas(quotas)).await
+    }
+
+    async fn add_for(
+        manager: &MemoryManager,
+        tenant: &str,
+        content: &str,
+    ) -> locai::Result<String> {
+        manager
+            .add_memory_with_options(content.to_string(), |b| b.property("tenant", json!(tenant)))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit_per_tenant() {
+        let mut quotas = QuotaConfig::default();
+        quotas.default_limits.max_memories = Some(2);
+        quotas.owners.insert(
+            "big-tenant".to_string(),
+            QuotaLimits {
+                max_memories: Some(3),
+                ..Default::default()
+            },
+        );
+        let (manager, _dir) = create_manager(quotas).await;
+
+        add_for(&manager, "acme", "First").await.unwrap();
+        let second = add_for(&manager, "acme", "Second").await.unwrap();
+        let error = add_for(&manager, "acme", "Third").await.unwrap_err();
+        match error {
+            LocaiError::QuotaExceeded {
+                owner,
+                resource,
+                used,
+                limit,
+            } => {
+                assert_eq!(owner, "acme");
+                assert_eq!(resource, "memories");
+                assert_eq!((used, limit), (3, 2));
+            }
+            other => panic!("expected a quota error, got {:?}", other),
+        }
+
+        // Other owners have their own limits
+        for n in 0..3 {
+            add_for(&manager, "big-tenant", &format!("Note {}", n))
+                .await
+                .unwrap();
+        }
+        assert!(add_for(&manager, "big-tenant", "One more").await.is_err());
+
+        // Deleting frees room
+        assert!(manager.delete_memory(&second).await.unwrap());
+        add_for(&manager, "acme", "Third").await.unwrap();
+
+        let usage = manager.quota_usage("acme").await.unwrap();
+        assert_eq!(usage.memories, 2);
+        assert_eq!(usage.limits.max_memories, Some(2));
+        let all = manager.list_quota_usage().await.unwrap();
+        assert_eq!(all.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_byte_limit_covers_updates_and_batches() {
+        let mut quotas = QuotaConfig::default();
+        quotas.default_limits.max_bytes = Some(100);
+        let (manager, _dir) = create_manager(quotas).await;
+
+        // Without a tenant property the source is the owner
+        let id = manager
+            .add_memory_with_options("Short note".to_string(), |b| b.source("agent-7"))
+            .await
+            .unwrap();
+
+        let mut memory = manager.get_memory(&id).await.unwrap().unwrap();
+        memory.content = "x".repeat(200);
+        let error = manager.update_memory(memory).await.unwrap_err();
+        assert!(matches!(error, LocaiError::QuotaExceeded { .. }));
+        assert_eq!(
+            manager.get_memory(&id).await.unwrap().unwrap().content,
+            "Short note"
+        );
+
+        let memories = vec![
+            MemoryBuilder::new_with_content("y".repeat(40))
+                .source("agent-7")
+                .build(),
+            MemoryBuilder::new_with_content("z".repeat(80))
+                .source("agent-7")
+                .build(),
+        ];
+        let results = manager.store_memory_batch(memories, 2).await;
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(LocaiError::QuotaExceeded { .. })));
+
+        let usage = manager.quota_usage("agent-7").await.unwrap();
+        assert_eq!(usage.memories, 2);
+        assert!((50..=100).contains(&usage.bytes));
+    }
+
+    #[tokio::test]
+    async fn test_usage_counts_existing_memories() {
+        let (manager, _dir) = create_manager(QuotaConfig::default()).await;
+        add_for(&manager, "acme", "Stored before anyone asked")
+            .await
+            .unwrap();
+
+        let usage = manager.quota_usage("acme").await.unwrap();
+        assert_eq!(usage.memories, 1);
+        assert!(usage.limits.is_unlimited());
+
+        // Once counted, writes keep the totals current
+        add_for(&manager, "acme", "Stored after").await.unwrap();
+        assert_eq!(manager.quota_usage("acme").await.unwrap().memories, 2);
+    }
+}
+
+mod lease {
+    //! Lease tests
+    //!
+    //! Processes sharing a database take turns running a background task by
+    //! holding a lease stored with the data.
+
+    use std::time::Duration;
+
+    use locai::runtime::Lease;
+
+    use crate::common::create_manager;
+
+    #[tokio::test]
+    async fn test_lease_has_one_holder_until_released() {
+        let (manager, _temp_dir) = create_manager().await;
+        let ttl = Duration::from_secs(60);
+        let first = Lease::new(manager.storage().clone(), "compaction", ttl).with_holder("first");
+        let second = Lease::new(manager.storage().clone(), "compaction", ttl).with_holder("second");
+
+        assert!(first.try_acquire().await);
+        assert!(!second.try_acquire().await);
+
+        // The holder renews its own lease
+        assert!(first.try_acquire().await);
+
+        // Only the holder can release it
+        assert!(!second.release().await);
+        assert!(first.release().await);
+        assert!(second.try_acquire().await);
+        assert!(!first.try_acquire().await);
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_can_be_taken_over() {
+        let (manager, _temp_dir) = create_manager().await;
+        let ttl = Duration::from_millis(200);
+        let first = Lease::new(manager.storage().clone(), "retention", ttl).with_holder("first");
+        let second = Lease::new(manager.storage().clone(), "retention", ttl).with_holder("second");
+
+        assert!(first.try_acquire().await);
+        assert!(!second.try_acquire().await);
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert!(second.try_acquire().await);
+        assert!(!first.try_acquire().await);
+    }
+
+    #[tokio::test]
+    async fn test_leases_are_independent() {
+        let (manager, _temp_dir) = create_manager().await;
+        let ttl = Duration::from_secs(60);
+        let reminders =
+            Lease::new(manager.storage().clone(), "reminders", ttl).with_holder("first");
+        let retention =
+            Lease::new(manager.storage().clone(), "retention", ttl).with_holder("second");
+
+        assert!(reminders.try_acquire().await);
+        assert!(retention.try_acquire().await);
+    }
+}
+
+mod id_strategy {
+    //! ID strategy tests
+    //!
+    //! Content-hashed IDs make storing the same content again a no-op, and
+    //! seeded ULIDs repeat from run to run.
+
+    use crate::common::create_test_locai_with;
+    use chrono::{DateTime, TimeZone, Utc};
+    use locai::clock::{Clock, ClockHandle};
+    use locai::ids::{IdStrategy, content_hash_id};
+    use locai::prelude::*;
+    use tempfile::TempDir;
+
+    #[derive(Debug)]
+    struct FixedClock;
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()
+        }
+    }
+
+    async fn create_test_locai(strategy: IdStrategy) -> (Locai, TempDir) {
+        create_test_locai_with(|builder| {
+            builder
+                .with_clock(ClockHandle::new(FixedClock))
+                .with_id_strategy(strategy)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_content_hash_ids_match_across_stores() {
+        let (first, _first_dir) = create_test_locai(IdStrategy::ContentHash).await;
+        let (second, _second_dir) = create_test_locai(IdStrategy::ContentHash).await;
+
+        let a = first.remember("The bridge opens at dawn").await.unwrap();
+        let b = second.remember("The bridge opens at dawn").await.unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, content_hash_id("The bridge opens at dawn"));
+    }
+
+    #[tokio::test]
+    async fn test_content_hash_import_is_idempotent() {
+        let (locai, _temp_dir) = create_test_locai(IdStrategy::ContentHash).await;
+        let manager = locai.manager();
+
+        let first = manager.add_fact("Water boils at 100C").await.unwrap();
+        let again = manager.add_fact("Water boils at 100C").await.unwrap();
+        assert_eq!(first, again);
+
+        let batch: Vec<_> = ["Water boils at 100C", "Ice melts at 0C"]
+            .into_iter()
+            .map(|content| manager.memory_builder(content).build())
+            .collect();
+        let ids: Vec<String> = manager
+            .store_memory_batch(batch, 2)
+            .await
+            .into_iter()
+            .map(|id| id.unwrap())
+            .collect();
+        assert_eq!(ids[0], first);
+        assert_eq!(ids[1], content_hash_id("Ice melts at 0C"));
+
+        assert_eq!(manager.count_memories(None).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_seeded_ulids_repeat_across_stores() {
+        let strategy = IdStrategy::Ulid { seed: Some(42) };
+        let (first, _first_dir) = create_test_locai(strategy).await;
+        let (second, _second_dir) = create_test_locai(strategy).await;
+
+        let mut ids = Vec::new();
+        for locai in [&first, &second] {
+            let mut run = Vec::new();
+            for content in ["One", "Two", "Three"] {
+                run.push(locai.remember(content).await.unwrap());
+            }
+            ids.push(run);
+        }
+        assert_eq!(ids[0], ids[1]);
+        assert!(ids[0].iter().all(|id| id.len() == 26));
+
+        let stored = first.manager().get_memory(&ids[0][1]).await.unwrap();
+        assert_eq!(stored.unwrap().content, "Two");
+    }
+
+    #[tokio::test]
+    async fn test_random_ids_by_default() {
+        let (locai, _temp_dir) = create_test_locai(IdStrategy::default()).await;
+
+        let a = locai.remember("Same words").await.unwrap();
+        let b = locai.remember("Same words").await.unwrap();
+        assert_ne!(a, b);
+    }
+}
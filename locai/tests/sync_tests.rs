@@ -0,0 +1,585 @@
+//! Sync tests: changefeed, replication and idempotency keys
+
+mod common;
+
+mod changefeed {
+    //! Changefeed tests
+
+    use locai::prelude::*;
+    use locai::replication::{ChangeOp, RecordKind};
+    use locai::storage::models::Entity;
+    use tempfile::TempDir;
+
+    async fn create_locai() -> (Locai, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let locai = Locai::builder()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .await
+            .expect("Failed to create Locai");
+        (locai, temp_dir)
+    }
+
+    fn entity(id: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties: serde_json::json!({ "name": id }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes_merge_tables_in_commit_order() {
+        let (locai, _dir) = create_locai().await;
+        let storage = locai.manager().storage();
+        let start = storage.read_changes(0, 1000).await.unwrap().cursor;
+
+        storage.create_entity(entity("hana")).await.unwrap();
+        let memory_id = locai
+            .manager()
+            .store_memory(MemoryBuilder::fact("Hana likes green tea").build())
+            .await
+            .unwrap();
+        storage.delete_entity("hana").await.unwrap();
+
+        let page = storage.read_changes(start, 1000).await.unwrap();
+        let changes: Vec<_> = page
+            .changes
+            .iter()
+            .map(|change| (change.kind, change.id.as_str(), change.op))
+            .collect();
+        let first_memory = changes
+            .iter()
+            .position(|change| {
+                *change == (RecordKind::Memory, memory_id.as_str(), ChangeOp::Upsert)
+            })
+            .unwrap();
+        assert_eq!(changes[0], (RecordKind::Entity, "hana", ChangeOp::Upsert));
+        assert_eq!(
+            changes.last().unwrap(),
+            &(RecordKind::Entity, "hana", ChangeOp::Delete)
+        );
+        assert!(first_memory > 0);
+        assert!(
+            page.changes
+                .windows(2)
+                .all(|pair| pair[0].cursor <= pair[1].cursor)
+        );
+        assert_eq!(page.cursor, page.changes.last().unwrap().cursor);
+
+        let record = page.changes[0].record.as_ref().unwrap();
+        assert_eq!(record["properties"]["name"], "hana");
+    }
+
+    #[tokio::test]
+    async fn test_changes_page_with_cursor() {
+        let (locai, _dir) = create_locai().await;
+        let storage = locai.manager().storage();
+        let start = storage.read_changes(0, 1000).await.unwrap().cursor;
+
+        for name in ["ivy", "jun", "kai", "lea", "mo"] {
+            storage.create_entity(entity(name)).await.unwrap();
+        }
+
+        let mut cursor = start;
+        let mut seen = Vec::new();
+        loop {
+            let page = storage.read_changes(cursor, 2).await.unwrap();
+            if page.changes.is_empty() {
+                assert_eq!(page.cursor, cursor);
+                break;
+            }
+            assert!(page.changes.len() <= 2);
+            assert!(page.cursor > cursor);
+            cursor = page.cursor;
+            seen.extend(page.changes.into_iter().map(|change| change.id));
+        }
+        assert_eq!(seen, ["ivy", "jun", "kai", "lea", "mo"]);
+    }
+}
+
+mod replication {
+    //! Replication tests
+    //!
+    //! Two in-memory instances stand in for an edge agent and a home server;
+    //! changes are carried between them directly or over an in-process session.
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::{SinkExt, StreamExt};
+    use locai::prelude::*;
+    use locai::replication::transport::{ReplicationMessage, Role, run_session};
+    use locai::replication::{
+        ApplyOutcome, ChangeEvent, ChangeOp, ConflictPolicy, RecordKind, ReplicationConfig,
+        Replicator,
+    };
+    use locai::storage::models::{Entity, Relationship};
+    use tempfile::TempDir;
+    use tokio::sync::broadcast;
+
+    async fn create_node(
+        node_id: &str,
+        policy: ConflictPolicy,
+    ) -> (Locai, Arc<Replicator>, TempDir) {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let locai = Locai::builder()
+            .with_data_dir(temp_dir.path())
+            .with_memory_storage()
+            .build()
+            .await
+            .expect("Failed to create Locai");
+        let config = ReplicationConfig {
+            conflict_policy: policy,
+            ..ReplicationConfig::with_node_id(node_id)
+        };
+        let replicator = Replicator::start(locai.manager().storage().clone(), config)
+            .await
+            .expect("Failed to start replication");
+        (locai, replicator, temp_dir)
+    }
+
+    /// Next change for a record of `kind`, skipping changes to other records
+    async fn next_change(
+        changes: &mut broadcast::Receiver<ChangeEvent>,
+        kind: RecordKind,
+        id: &str,
+    ) -> ChangeEvent {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let change = changes.recv().await.expect("change stream closed");
+                if change.kind == kind && change.id == id {
+                    return change;
+                }
+            }
+        })
+        .await
+        .expect("timed out waiting for change")
+    }
+
+    fn entity(id: &str, name: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            entity_type: "person".to_string(),
+            properties: serde_json::json!({ "name": name }),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes_replicate_with_original_ids() {
+        let (edge, edge_replicator, _edge_dir) =
+            create_node("edge", ConflictPolicy::VectorClock).await;
+        let (home, home_replicator, _home_dir) =
+            create_node("home", ConflictPolicy::VectorClock).await;
+        let mut edge_changes = edge_replicator.subscribe();
+        let mut home_changes = home_replicator.subscribe();
+
+        let memory_id = edge
+            .manager()
+            .store_memory(MemoryBuilder::fact("The kettle is in the left cupboard").build())
+            .await
+            .unwrap();
+        let change = next_change(&mut edge_changes, RecordKind::Memory, &memory_id).await;
+        assert_eq!(change.origin, "edge");
+        assert_eq!(change.op, ChangeOp::Upsert);
+        assert_eq!(change.clock.get("edge"), 1);
+        assert_eq!(
+            home_replicator.apply(change).await.unwrap(),
+            ApplyOutcome::Applied
+        );
+
+        let storage = edge.manager().storage();
+        storage
+            .create_entity(entity("alice", "Alice"))
+            .await
+            .unwrap();
+        let change = next_change(&mut edge_changes, RecordKind::Entity, "alice").await;
+        home_replicator.apply(change).await.unwrap();
+
+        let relationship = storage
+            .create_relationship(Relationship {
+                id: String::new(),
+                relationship_type: "mentions".to_string(),
+                source_id: memory_id.clone(),
+                target_id: "alice".to_string(),
+                properties: serde_json::json!({}),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let change = next_change(
+            &mut edge_changes,
+            RecordKind::Relationship,
+            &relationship.id,
+        )
+        .await;
+        home_replicator.apply(change).await.unwrap();
+
+        // Applied changes are not republished by the receiving node
+        assert!(
+            tokio::time::timeout(Duration::from_millis(300), home_changes.recv())
+                .await
+                .is_err()
+        );
+
+        let home_storage = home.manager().storage();
+        let replicated = home_storage.get_memory(&memory_id).await.unwrap().unwrap();
+        assert_eq!(replicated.content, "The kettle is in the left cupboard");
+        assert_eq!(
+            home_storage
+                .get_entity("alice")
+                .await
+                .unwrap()
+                .unwrap()
+                .properties["name"],
+            "Alice"
+        );
+        let replicated = home_storage
+            .get_relationship(&relationship.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(replicated.source_id, memory_id);
+
+        edge.manager().delete_memory(&memory_id).await.unwrap();
+        let change = next_change(&mut edge_changes, RecordKind::Memory, &memory_id).await;
+        assert_eq!(change.op, ChangeOp::Delete);
+        assert!(change.clock.get("edge") > 1);
+        home_replicator.apply(change).await.unwrap();
+        assert!(
+            home.manager()
+                .get_memory(&memory_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vector_clock_detects_stale_and_concurrent_changes() {
+        let (edge, edge_replicator, _edge_dir) =
+            create_node("edge", ConflictPolicy::VectorClock).await;
+        let (home, home_replicator, _home_dir) =
+            create_node("home", ConflictPolicy::VectorClock).await;
+        let mut edge_changes = edge_replicator.subscribe();
+        let mut home_changes = home_replicator.subscribe();
+
+        let edge_storage = edge.manager().storage();
+        let home_storage = home.manager().storage();
+        edge_storage
+            .create_entity(entity("bob", "Bob"))
+            .await
+            .unwrap();
+        let first = next_change(&mut edge_changes, RecordKind::Entity, "bob").await;
+        assert_eq!(
+            home_replicator.apply(first.clone()).await.unwrap(),
+            ApplyOutcome::Applied
+        );
+
+        // Replaying a change the node already has is a no-op
+        assert_eq!(
+            home_replicator.apply(first.clone()).await.unwrap(),
+            ApplyOutcome::Stale
+        );
+        // A node ignores its own changes
+        assert_eq!(
+            edge_replicator.apply(first).await.unwrap(),
+            ApplyOutcome::Ignored
+        );
+
+        // Both sides edit the record without seeing each other's change
+        edge_storage
+            .update_entity(entity("bob", "Robert"))
+            .await
+            .unwrap();
+        let from_edge = next_change(&mut edge_changes, RecordKind::Entity, "bob").await;
+        home_storage
+            .update_entity(entity("bob", "Bobby"))
+            .await
+            .unwrap();
+        let from_home = next_change(&mut home_changes, RecordKind::Entity, "bob").await;
+        assert_eq!(from_home.clock.get("edge"), 1);
+        assert_eq!(from_home.clock.get("home"), 1);
+
+        // The home edit was captured later, so it wins on both sides
+        assert_eq!(
+            home_replicator.apply(from_edge).await.unwrap(),
+            ApplyOutcome::Conflict { applied: false }
+        );
+        assert_eq!(
+            home_storage
+                .get_entity("bob")
+                .await
+                .unwrap()
+                .unwrap()
+                .properties["name"],
+            "Bobby"
+        );
+
+        // The winner is republished with a clock that dominates both edits
+        let republished = next_change(&mut home_changes, RecordKind::Entity, "bob").await;
+        assert_eq!(republished.clock.get("edge"), 2);
+        assert_eq!(republished.clock.get("home"), 2);
+        assert_eq!(
+            edge_replicator.apply(republished).await.unwrap(),
+            ApplyOutcome::Applied
+        );
+        assert_eq!(
+            edge_storage
+                .get_entity("bob")
+                .await
+                .unwrap()
+                .unwrap()
+                .properties["name"],
+            "Bobby"
+        );
+
+        // The edge's own concurrent edit is now stale there too
+        assert_eq!(
+            edge_replicator.apply(from_home).await.unwrap(),
+            ApplyOutcome::Stale
+        );
+    }
+
+    #[tokio::test]
+    async fn test_last_write_wins_uses_timestamps() {
+        let (home, home_replicator, _home_dir) =
+            create_node("home", ConflictPolicy::LastWriteWins).await;
+        let mut home_changes = home_replicator.subscribe();
+
+        let storage = home.manager().storage();
+        storage
+            .create_entity(entity("carol", "Carol"))
+            .await
+            .unwrap();
+        let local = next_change(&mut home_changes, RecordKind::Entity, "carol").await;
+
+        let remote = |name: &str, offset: chrono::Duration| ChangeEvent {
+            origin: "edge".to_string(),
+            kind: RecordKind::Entity,
+            id: "carol".to_string(),
+            op: ChangeOp::Upsert,
+            record: Some(serde_json::to_value(entity("carol", name)).unwrap()),
+            timestamp: local.timestamp + offset,
+            // Clocks are ignored under last-write-wins
+            clock: Default::default(),
+        };
+
+        assert_eq!(
+            home_replicator
+                .apply(remote("Old Carol", chrono::Duration::seconds(-10)))
+                .await
+                .unwrap(),
+            ApplyOutcome::Stale
+        );
+        assert_eq!(
+            home_replicator
+                .apply(remote("New Carol", chrono::Duration::seconds(10)))
+                .await
+                .unwrap(),
+            ApplyOutcome::Applied
+        );
+        assert_eq!(
+            storage
+                .get_entity("carol")
+                .await
+                .unwrap()
+                .unwrap()
+                .properties["name"],
+            "New Carol"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_carries_changes_between_nodes() {
+        let (edge, edge_replicator, _edge_dir) =
+            create_node("edge", ConflictPolicy::VectorClock).await;
+        let (home, home_replicator, _home_dir) =
+            create_node("home", ConflictPolicy::VectorClock).await;
+
+        // Edge -> home frames; the home -> edge direction stays idle
+        let (to_home, from_edge) = futures::channel::mpsc::unbounded::<ReplicationMessage>();
+        let (to_edge, from_home) = futures::channel::mpsc::unbounded::<ReplicationMessage>();
+
+        let sender = tokio::spawn(async move {
+            run_session(
+                &edge_replicator,
+                Role::Send,
+                from_home.map(Ok),
+                to_home.sink_map_err(|e| LocaiError::Connection(e.to_string())),
+            )
+            .await
+        });
+        let receiver = tokio::spawn({
+            let home_replicator = home_replicator.clone();
+            async move {
+                run_session(
+                    &home_replicator,
+                    Role::Receive,
+                    from_edge.map(Ok),
+                    to_edge.sink_map_err(|e| LocaiError::Connection(e.to_string())),
+                )
+                .await
+            }
+        });
+
+        // Give the sending side time to subscribe
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let memory_id = edge
+            .manager()
+            .store_memory(MemoryBuilder::fact("Home wifi password is on the fridge").build())
+            .await
+            .unwrap();
+
+        let replicated = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(memory) = home.manager().get_memory(&memory_id).await.unwrap() {
+                    return memory;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("memory was not replicated");
+        assert_eq!(replicated.content, "Home wifi password is on the fridge");
+
+        sender.abort();
+        let _ = receiver.await;
+    }
+}
+
+mod idempotency {
+    //! Idempotency key tests
+    //!
+    //! Writes repeated under an idempotency key return the first result instead
+    //! of storing duplicates, until the key's retention window passes.
+
+    use std::sync::{Arc, Mutex};
+
+    use crate::common::create_test_locai_with;
+    use chrono::{DateTime, Duration, Utc};
+    use locai::LocaiError;
+    use locai::clock::{Clock, ClockHandle};
+    use locai::models::MemoryBuilder;
+    use locai::prelude::*;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Clone)]
+    struct ManualClock(Arc<Mutex<DateTime<Utc>>>);
+
+    impl ManualClock {
+        fn advance(&self, by: Duration) {
+            *self.0.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    async fn create_test_locai(clock: &ManualClock) -> (Locai, TempDir) {
+        create_test_locai_with(|builder| builder.with_clock(ClockHandle::new(clock.clone()))).await
+    }
+
+    fn fact(content: &str) -> locai::models::Memory {
+        MemoryBuilder::fact(content).build()
+    }
+
+    #[tokio::test]
+    async fn test_repeated_key_returns_first_memory() {
+        let clock = ManualClock(Arc::new(Mutex::new(Utc::now())));
+        let (locai, _temp_dir) = create_test_locai(&clock).await;
+        let manager = locai.manager();
+
+        let first = manager
+            .store_memory_idempotent(fact("The vault code is 4312"), "call-1")
+            .await
+            .unwrap();
+        let retried = manager
+            .store_memory_idempotent(fact("The vault code is 4312"), "call-1")
+            .await
+            .unwrap();
+        assert_eq!(first, retried);
+        assert_eq!(manager.count_memories(None).await.unwrap(), 1);
+
+        // Another key is another write
+        let other = manager
+            .store_memory_idempotent(fact("The vault code is 4312"), "call-2")
+            .await
+            .unwrap();
+        assert_ne!(first, other);
+    }
+
+    #[tokio::test]
+    async fn test_key_reused_for_other_content_fails() {
+        let clock = ManualClock(Arc::new(Mutex::new(Utc::now())));
+        let (locai, _temp_dir) = create_test_locai(&clock).await;
+        let manager = locai.manager();
+
+        manager
+            .store_memory_idempotent(fact("First"), "call-1")
+            .await
+            .unwrap();
+        let result = manager
+            .store_memory_idempotent(fact("Second"), "call-1")
+            .await;
+        assert!(matches!(result, Err(LocaiError::Idempotency(_))));
+        assert_eq!(manager.count_memories(None).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_expires_after_retention() {
+        let clock = ManualClock(Arc::new(Mutex::new(Utc::now())));
+        let (locai, _temp_dir) = create_test_locai(&clock).await;
+        let manager = locai.manager();
+
+        let first = manager
+            .store_memory_idempotent(fact("Daily standup notes"), "standup")
+            .await
+            .unwrap();
+        clock.advance(Duration::hours(25));
+        let later = manager
+            .store_memory_idempotent(fact("Daily standup notes"), "standup")
+            .await
+            .unwrap();
+        assert_ne!(first, later);
+
+        // The replaced record is current, so nothing is left to purge
+        assert_eq!(manager.purge_idempotency_keys().await.unwrap(), 0);
+        clock.advance(Duration::hours(25));
+        assert_eq!(manager.purge_idempotency_keys().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_batch_returns_first_results() {
+        let clock = ManualClock(Arc::new(Mutex::new(Utc::now())));
+        let (locai, _temp_dir) = create_test_locai(&clock).await;
+        let manager = locai.manager();
+
+        let batch = || vec![fact("Alpha"), fact("Beta")];
+        let first: Vec<String> = manager
+            .store_memory_batch_idempotent(batch(), 2, "import-1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|id| id.unwrap())
+            .collect();
+        let retried: Vec<String> = manager
+            .store_memory_batch_idempotent(batch(), 2, "import-1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|id| id.unwrap())
+            .collect();
+        assert_eq!(first, retried);
+        assert_eq!(manager.count_memories(None).await.unwrap(), 2);
+    }
+}
@@ -0,0 +1,743 @@
+//! Vector tests: embedding providers and validation, sparse vectors, vector spaces, export and warmup
+
+mod common;
+
+mod embedding_provider {
+    //! Embedding provider integration tests
+    //!
+    //! Verifies that a configured `EmbeddingProvider` embeds memories on store and
+    //! queries on vector/hybrid search, while explicit embeddings still win. Deferred
+    //! stores embed once their background indexing finishes.
+
+    use crate::common::create_test_locai_with;
+    use async_trait::async_trait;
+    use locai::memory::search_extensions::SearchMode;
+    use locai::ml::EmbeddingProvider;
+    use locai::prelude::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    const DIMENSIONS: usize = 1024;
+
+    /// Deterministic bag-of-words provider: each token sets one hashed bucket
+    #[derive(Debug, Default)]
+    struct BagOfWordsProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for BagOfWordsProvider {
+        fn name(&self) -> &str {
+            "bag-of-words"
+        }
+
+        fn dimensions(&self) -> Option<usize> {
+            Some(DIMENSIONS)
+        }
+
+        async fn embed(&self, text: &str) -> locai::ml::Result<Vec<f32>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+
+            let mut embedding = vec![0.0f32; DIMENSIONS];
+            for token in text
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|t| !t.is_empty())
+            {
+                let bucket = token
+                    .to_lowercase()
+                    .bytes()
+                    .fold(7usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+                embedding[bucket % DIMENSIONS] += 1.0;
+            }
+
+            let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                embedding.iter_mut().for_each(|x| *x /= norm);
+            }
+            Ok(embedding)
+        }
+    }
+
+    async fn create_test_locai(provider: Arc<BagOfWordsProvider>) -> (Locai, TempDir) {
+        create_test_locai_with(|builder| builder.with_embedding_provider(provider)).await
+    }
+
+    #[tokio::test]
+    async fn test_provider_embeds_memories_and_queries() {
+        let provider = Arc::new(BagOfWordsProvider::default());
+        let (locai, _temp_dir) = create_test_locai(Arc::clone(&provider)).await;
+
+        assert!(locai.has_semantic_search());
+
+        let dragon_id = locai
+            .remember("The dragon sleeps beneath the mountain")
+            .await
+            .unwrap();
+        locai
+            .remember("Elena is a skilled blacksmith in the village")
+            .await
+            .unwrap();
+
+        let stored = locai
+            .manager()
+            .get_memory(&dragon_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.embedding.map(|e| e.len()), Some(DIMENSIONS));
+
+        // No query embedding supplied: the provider embeds the query
+        let results = locai
+            .search_for("dragon mountain")
+            .mode(SearchMode::Vector)
+            .limit(1)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, dragon_id);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+
+        // Text search never calls the provider
+        locai.search_for("dragon").execute().await.unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_explicit_embedding_takes_precedence() {
+        let provider = Arc::new(BagOfWordsProvider::default());
+        let (locai, _temp_dir) = create_test_locai(Arc::clone(&provider)).await;
+
+        let mut explicit = vec![0.0f32; DIMENSIONS];
+        explicit[0] = 1.0;
+        let memory = MemoryBuilder::new_with_content("Pre-embedded content")
+            .embedding(explicit.clone())
+            .build();
+        let id = locai.manager().store_memory(memory).await.unwrap();
+
+        let stored = locai.manager().get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(stored.embedding, Some(explicit));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_deferred_store_embeds_in_background() {
+        let provider = Arc::new(BagOfWordsProvider::default());
+        let (locai, _temp_dir) = create_test_locai(Arc::clone(&provider)).await;
+
+        let memory = MemoryBuilder::new_with_content("Captured between meetings").build();
+        let (id, indexing) = locai.manager().store_memory_deferred(memory).await.unwrap();
+
+        // Stored right away, embedded once indexing finishes
+        assert!(locai.manager().get_memory(&id).await.unwrap().is_some());
+        indexing.await.unwrap();
+
+        let stored = locai.manager().get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(stored.embedding.map(|e| e.len()), Some(DIMENSIONS));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+}
+
+mod embedding_validation {
+    //! Embedding validation tests
+    //!
+    //! Embeddings the vector index couldn't search are refused on every write
+    //! path instead of being stored where search would silently skip them.
+
+    use crate::common::create_test_locai_with;
+    use locai::LocaiError;
+    use locai::models::MemoryBuilder;
+    use locai::prelude::*;
+    use tempfile::TempDir;
+
+    const DIMENSIONS: usize = 1024;
+
+    async fn create_test_locai(normalize: bool) -> (Locai, TempDir) {
+        create_test_locai_with(|builder| {
+            if normalize {
+                builder.with_normalized_embeddings()
+            } else {
+                builder
+            }
+        })
+        .await
+    }
+
+    fn with_embedding(content: &str, embedding: Vec<f32>) -> locai::models::Memory {
+        MemoryBuilder::new_with_content(content)
+            .embedding(embedding)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_unsearchable_embeddings_are_rejected() {
+        let (locai, _temp_dir) = create_test_locai(false).await;
+        let manager = locai.manager();
+
+        let mut not_finite = vec![0.5; DIMENSIONS];
+        not_finite[10] = f32::NAN;
+        let embeddings = [vec![0.5; 3], not_finite, vec![0.0; DIMENSIONS]];
+        for embedding in embeddings {
+            let result = manager
+                .store_memory(with_embedding("Unsearchable", embedding))
+                .await;
+            assert!(
+                matches!(result, Err(LocaiError::InvalidEmbedding(_))),
+                "{:?}",
+                result
+            );
+        }
+        assert_eq!(manager.count_memories(None).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_rejects_wrong_dimensions() {
+        let (locai, _temp_dir) = create_test_locai(false).await;
+        let manager = locai.manager();
+
+        let id = manager
+            .store_memory(with_embedding("Searchable", vec![0.5; DIMENSIONS]))
+            .await
+            .unwrap();
+        let mut memory = manager.get_memory(&id).await.unwrap().unwrap();
+        memory.embedding = Some(vec![0.5; 1536]);
+
+        let result = manager.update_memory(memory).await;
+        assert!(matches!(result, Err(LocaiError::InvalidEmbedding(_))));
+        let stored = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(stored.embedding.map(|e| e.len()), Some(DIMENSIONS));
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_kept_as_given_by_default() {
+        let (locai, _temp_dir) = create_test_locai(false).await;
+        let manager = locai.manager();
+
+        let id = manager
+            .store_memory(with_embedding("As given", vec![0.5; DIMENSIONS]))
+            .await
+            .unwrap();
+        let stored = manager.get_memory(&id).await.unwrap().unwrap();
+        assert_eq!(stored.embedding.unwrap()[0], 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_normalized_when_configured() {
+        let (locai, _temp_dir) = create_test_locai(true).await;
+        let manager = locai.manager();
+
+        let id = manager
+            .store_memory(with_embedding("Scaled", vec![0.5; DIMENSIONS]))
+            .await
+            .unwrap();
+        let stored = manager.get_memory(&id).await.unwrap().unwrap();
+        let norm: f32 = stored
+            .embedding
+            .unwrap()
+            .iter()
+            .map(|x| x * x)
+            .sum::<f32>()
+            .sqrt();
+        assert!((norm - 1.0).abs() < 1e-4, "norm {}", norm);
+    }
+}
+
+mod sparse_vector {
+    //! Sparse vector tests
+    //!
+    //! Memories carry sparse vectors alongside dense embeddings, sparse search
+    //! ranks them by dot product, and hybrid search fuses sparse matches in.
+
+    use locai::LocaiError;
+    use locai::memory::SearchMode;
+    use locai::prelude::*;
+
+    use crate::common::create_test_locai;
+
+    async fn store_sparse(locai: &Locai, content: &str, pairs: &[(u32, f32)]) -> String {
+        let memory = MemoryBuilder::new_with_content(content)
+            .sparse_embedding(SparseVector::from_pairs(pairs.iter().copied()))
+            .build();
+        locai.manager().store_memory(memory).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sparse_search_ranks_by_dot_product() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let weak = store_sparse(&locai, "Harbor cranes", &[(3, 0.2), (9, 1.0)]).await;
+        let strong = store_sparse(&locai, "Harbor tides", &[(3, 2.0)]).await;
+        store_sparse(&locai, "Mountain passes", &[(40, 3.0)]).await;
+
+        let results = locai
+            .search_for("harbor")
+            .mode(SearchMode::Vector)
+            .with_sparse_query(SparseVector::from_pairs([(3, 1.0)]))
+            .execute()
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec![strong.as_str(), weak.as_str()]);
+
+        let stored = locai.manager().get_memory(&strong).await.unwrap().unwrap();
+        assert_eq!(
+            stored.sparse_embedding(),
+            Some(SparseVector::from_pairs([(3, 2.0)]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_fuses_sparse_matches() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let text_match = locai
+            .remember("The lighthouse keeper logs storms")
+            .await
+            .unwrap();
+        let sparse_match = store_sparse(&locai, "Beacon maintenance schedule", &[(7, 1.5)]).await;
+
+        let results = locai
+            .search_for("lighthouse")
+            .mode(SearchMode::Hybrid)
+            .with_sparse_query(SparseVector::from_pairs([(7, 1.0)]))
+            .execute()
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|m| m.id.as_str()).collect();
+        assert!(ids.contains(&text_match.as_str()), "{:?}", ids);
+        assert!(ids.contains(&sparse_match.as_str()), "{:?}", ids);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_sparse_vector_rejected() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let memory = MemoryBuilder::new_with_content("Broken weights")
+            .property(
+                locai::models::SPARSE_EMBEDDING_PROPERTY,
+                serde_json::json!({ "indices": [1, 2], "values": [0.5] }),
+            )
+            .build();
+
+        let result = locai.manager().store_memory(memory).await;
+        assert!(matches!(result, Err(LocaiError::InvalidEmbedding(_))));
+    }
+}
+
+mod vector_space {
+    //! Vector space tests
+    //!
+    //! Each vector space ranks by its configured metric, and a space with
+    //! normalization on keeps and compares unit-length vectors.
+
+    use chrono::Utc;
+    use locai::config::VectorSpaceConfig;
+    use locai::models::MemoryBuilder;
+    use locai::storage::{
+        models::{DistanceMetric, Vector, VectorSearchParams},
+        shared_storage::{SharedStorage, SharedStorageConfig},
+        traits::{MemoryStore, VectorStore},
+    };
+    use serde_json::json;
+
+    type TestStorage = SharedStorage<surrealdb::engine::local::Db>;
+
+    const DIMENSIONS: usize = 1024;
+
+    async fn create_test_storage(
+        memory_space: VectorSpaceConfig,
+        vector_space: VectorSpaceConfig,
+    ) -> TestStorage {
+        let config = SharedStorageConfig {
+            memory_space,
+            vector_space,
+            ..Default::default()
+        };
+        let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())
+            .await
+            .expect("Failed to create client");
+        SharedStorage::new(client, config)
+            .await
+            .expect("Failed to create storage")
+    }
+
+    /// A dot product space, normalized or not
+    fn dot(normalize: bool) -> VectorSpaceConfig {
+        VectorSpaceConfig {
+            metric: DistanceMetric::DotProduct,
+            normalize,
+        }
+    }
+
+    fn norm(vector: &[f32]) -> f32 {
+        vector.iter().map(|x| x * x).sum::<f32>().sqrt()
+    }
+
+    /// Long but only half aligned with the query
+    fn long_vector() -> Vec<f32> {
+        let mut vector = vec![5.0; DIMENSIONS / 2];
+        vector.resize(DIMENSIONS, 0.0);
+        vector
+    }
+
+    /// Short and aligned with the query
+    fn aligned_vector() -> Vec<f32> {
+        vec![1.0; DIMENSIONS]
+    }
+
+    fn query() -> Vec<f32> {
+        vec![1.0; DIMENSIONS]
+    }
+
+    async fn add_vectors(storage: &TestStorage) {
+        for (id, vector) in [("long", long_vector()), ("aligned", aligned_vector())] {
+            storage
+                .add_vector(Vector {
+                    id: id.to_string(),
+                    vector,
+                    dimension: DIMENSIONS,
+                    metadata: json!({}),
+                    source_id: None,
+                    created_at: Utc::now(),
+                })
+                .await
+                .expect("Failed to add vector");
+        }
+    }
+
+    async fn best_vector(storage: &TestStorage, metric: Option<DistanceMetric>) -> String {
+        let params = VectorSearchParams {
+            distance_metric: metric,
+            ..Default::default()
+        };
+        let results = storage
+            .search_vectors(&query(), params)
+            .await
+            .expect("Failed to search vectors");
+        assert_eq!(results.len(), 2);
+        // Distances, nearest first
+        assert!(results[0].1 <= results[1].1);
+        results[0].0.id.clone()
+    }
+
+    #[tokio::test]
+    async fn test_vector_search_ranks_by_configured_metric() {
+        let storage = create_test_storage(dot(false), dot(false)).await;
+        add_vectors(&storage).await;
+
+        // Unnormalized, the longer vector wins on dot product
+        assert_eq!(best_vector(&storage, None).await, "long");
+        // A metric in the search params overrides the space's
+        assert_eq!(
+            best_vector(&storage, Some(DistanceMetric::Cosine)).await,
+            "aligned"
+        );
+        assert_eq!(
+            best_vector(&storage, Some(DistanceMetric::Euclidean)).await,
+            "aligned"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_normalized_vectors_rank_dot_product_like_cosine() {
+        let storage = create_test_storage(dot(false), dot(true)).await;
+        add_vectors(&storage).await;
+
+        assert_eq!(best_vector(&storage, None).await, "aligned");
+
+        let stored = storage.get_vector("long").await.unwrap().unwrap();
+        let norm = norm(&stored.vector);
+        assert!((norm - 1.0).abs() < 1e-4, "norm {}", norm);
+    }
+
+    #[tokio::test]
+    async fn test_memory_space_ranks_normalized_embeddings() {
+        let storage = create_test_storage(dot(true), dot(false)).await;
+
+        let long = storage
+            .create_memory(
+                MemoryBuilder::new_with_content("Long")
+                    .embedding(long_vector())
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let aligned = storage
+            .create_memory(
+                MemoryBuilder::new_with_content("Aligned")
+                    .embedding(aligned_vector())
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        let stored = storage.get_memory(&long.id).await.unwrap().unwrap();
+        let norm = norm(&stored.embedding.unwrap());
+        assert!((norm - 1.0).abs() < 1e-4, "norm {}", norm);
+
+        let results = storage
+            .vector_search_memories(&query(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(results[0].0.id, aligned.id);
+        let hits = storage
+            .vector_search_memory_hits(&query(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(hits[0].id, aligned.id);
+    }
+
+    #[tokio::test]
+    async fn test_spaces_normalize_independently() {
+        let storage = create_test_storage(dot(true), dot(false)).await;
+        add_vectors(&storage).await;
+        let memory = storage
+            .create_memory(
+                MemoryBuilder::new_with_content("Long")
+                    .embedding(long_vector())
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        // The vector store keeps vectors as given while memories are scaled
+        let stored = storage.get_vector("long").await.unwrap().unwrap();
+        assert!((norm(&stored.vector) - norm(&long_vector())).abs() < 1e-2);
+        assert_eq!(best_vector(&storage, None).await, "long");
+        let stored = storage.get_memory(&memory.id).await.unwrap().unwrap();
+        assert!((norm(&stored.embedding.unwrap()) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_metric_names_in_config() {
+        let space: VectorSpaceConfig = serde_json::from_value(json!({ "metric": "dot" })).unwrap();
+        assert_eq!(space.metric, DistanceMetric::DotProduct);
+        assert!(!space.normalize);
+
+        let space: VectorSpaceConfig =
+            serde_json::from_value(json!({ "metric": "dot", "normalize": true })).unwrap();
+        assert!(space.normalize);
+    }
+}
+
+mod vector_export {
+    //! Vector export tests
+    //!
+    //! Uses an in-memory sink; the Qdrant and pgvector sinks have unit tests of
+    //! their own behind their features.
+
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use locai::export::{VectorExportOptions, VectorRecord, VectorSink, export_vectors};
+    use locai::prelude::*;
+    use locai::storage::filters::MemoryFilter;
+
+    use crate::common::create_test_locai;
+
+    #[derive(Default)]
+    struct CollectingSink {
+        prepared: Mutex<Vec<usize>>,
+        batches: Mutex<Vec<Vec<VectorRecord>>>,
+    }
+
+    #[async_trait]
+    impl VectorSink for CollectingSink {
+        fn name(&self) -> &str {
+            "collecting"
+        }
+
+        async fn prepare(&self, dimensions: usize) -> Result<()> {
+            self.prepared.lock().unwrap().push(dimensions);
+            Ok(())
+        }
+
+        async fn upsert(&self, records: &[VectorRecord]) -> Result<()> {
+            self.batches.lock().unwrap().push(records.to_vec());
+            Ok(())
+        }
+    }
+
+    fn embedding(seed: usize) -> Vec<f32> {
+        (0..1024).map(|i| ((i + seed) % 10) as f32 / 10.0).collect()
+    }
+
+    #[tokio::test]
+    async fn test_export_vectors_in_batches() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+
+        for i in 0..5 {
+            let memory = MemoryBuilder::fact(format!("Fact number {}", i))
+                .tag("exported")
+                .embedding(embedding(i))
+                .build();
+            manager.store_memory(memory).await.unwrap();
+        }
+        manager
+            .store_memory(MemoryBuilder::fact("No embedding here").build())
+            .await
+            .unwrap();
+
+        let sink = CollectingSink::default();
+        let report = export_vectors(
+            manager,
+            &sink,
+            VectorExportOptions {
+                batch_size: 2,
+                filter: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.exported, 5);
+        assert_eq!(report.skipped_without_embedding, 1);
+        assert_eq!(report.dimensions, Some(1024));
+        assert_eq!(*sink.prepared.lock().unwrap(), vec![1024]);
+
+        let batches = sink.batches.lock().unwrap();
+        assert!(batches.iter().all(|batch| batch.len() <= 2));
+        let mut ids: Vec<&str> = batches.iter().flatten().map(|r| r.id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 5);
+        assert!(
+            batches
+                .iter()
+                .flatten()
+                .all(|r| r.payload["tags"][0] == "exported")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_vectors_honors_filter() {
+        let (locai, _temp_dir) = create_test_locai().await;
+        let manager = locai.manager();
+
+        for (i, tag) in ["keep", "drop", "keep"].iter().enumerate() {
+            let memory = MemoryBuilder::fact(format!("Tagged {}", i))
+                .tag(*tag)
+                .embedding(embedding(i))
+                .build();
+            manager.store_memory(memory).await.unwrap();
+        }
+
+        let sink = CollectingSink::default();
+        let report = export_vectors(
+            manager,
+            &sink,
+            VectorExportOptions {
+                filter: Some(MemoryFilter {
+                    tags: Some(vec!["keep".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.exported, 2);
+
+        let sink = CollectingSink::default();
+        let result = export_vectors(
+            manager,
+            &sink,
+            VectorExportOptions {
+                batch_size: 0,
+                filter: None,
+            },
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
+
+mod warmup {
+    //! Index warmup tests
+    //!
+    //! Warmup runs text searches for configured queries and common terms, and
+    //! vector searches with recent memories' embeddings, without failing.
+
+    use crate::common::create_manager_with;
+    use locai::config::WarmupConfig;
+    use locai::core::MemoryManager;
+    use locai::memory::warmup::common_terms;
+    use locai::models::{Memory, MemoryType};
+    use tempfile::TempDir;
+
+    async fn create_manager(warmup: WarmupConfig) -> (MemoryManager, TempDir) {
+        create_manager_with(|config| config.with_warmup(warmup)).await
+    }
+
+    /// Unit vector pointing mostly along `axis`
+    fn embedding(axis: usize) -> Vec<f32> {
+        let mut embedding = vec![0.01; 1024];
+        embedding[axis] = 1.0;
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        embedding.iter().map(|x| x / norm).collect()
+    }
+
+    #[test]
+    fn test_common_terms() {
+        let memories: Vec<Memory> = [
+            "The deploy pipeline runs nightly",
+            "Deploy rollbacks need approval, deploy lead decides",
+            "Lunch at noon in 2024",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, content)| Memory::new(i.to_string(), content.to_string(), MemoryType::Fact))
+        .collect();
+
+        // "deploy" is in two memories; ties go alphabetically, short words and
+        // numbers are skipped
+        assert_eq!(
+            common_terms(&memories, 3),
+            vec!["deploy", "approval", "decides"]
+        );
+        assert!(common_terms(&[], 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_runs_text_and_vector_searches() {
+        let (manager, _dir) = create_manager(WarmupConfig {
+            queries: vec!["deploy".to_string(), " ".to_string()],
+            max_queries: 3,
+            vector_probes: 1,
+            ..Default::default()
+        })
+        .await;
+        manager
+            .add_memory_with_options("The deploy pipeline runs nightly", |b| {
+                b.embedding(embedding(3))
+            })
+            .await
+            .unwrap();
+        manager
+            .add_memory_with_options("Deploy rollbacks need approval", |b| {
+                b.embedding(embedding(700))
+            })
+            .await
+            .unwrap();
+        manager
+            .add_memory_with_options("Lunch is at noon", |b| b)
+            .await
+            .unwrap();
+
+        let report = manager.warm_up().await;
+        assert_eq!(report.memories_sampled, 3);
+        // "deploy" once, then the two most common other terms
+        assert_eq!(report.text_queries, 3);
+        assert_eq!(report.vector_queries, 1);
+        assert_eq!(report.errors, 0);
+        assert!(!report.timed_out);
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_empty_store() {
+        let (manager, _dir) = create_manager(WarmupConfig::default()).await;
+        let report = manager.warm_up().await;
+        assert_eq!(report.memories_sampled, 0);
+        assert_eq!(report.text_queries, 0);
+        assert_eq!(report.vector_queries, 0);
+    }
+}
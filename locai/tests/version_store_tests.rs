@@ -18,6 +18,7 @@ async fn create_test_store() -> SharedStorage<surrealdb::engine::local::Db> {
         database: "test_version".to_string(),
         lifecycle_tracking: Default::default(),
         versioning: Default::default(),
+        ..Default::default()
     };
 
     let client = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(())